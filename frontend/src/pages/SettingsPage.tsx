@@ -63,12 +63,16 @@ interface Settings {
   autoDetectLanguageCombination: boolean;
   concurrentOcrJobs: number;
   ocrTimeoutSeconds: number;
+  ocrTextLayerMinChars: number;
   maxFileSizeMb: number;
   allowedFileTypes: string[];
   autoRotateImages: boolean;
   enableImagePreprocessing: boolean;
   searchResultsPerPage: number;
   searchSnippetLength: number;
+  searchSort: 'relevance' | 'date' | 'name';
+  searchCleanSnippets: boolean;
+  syncNotificationMode: 'summary' | 'per_file';
   fuzzySearchThreshold: number;
   retentionDays: number | null;
   enableAutoCleanup: boolean;
@@ -201,12 +205,16 @@ const SettingsPage: React.FC = () => {
     autoDetectLanguageCombination: false,
     concurrentOcrJobs: 4,
     ocrTimeoutSeconds: 300,
+    ocrTextLayerMinChars: 100,
     maxFileSizeMb: 50,
     allowedFileTypes: ['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt'],
     autoRotateImages: true,
     enableImagePreprocessing: false,
     searchResultsPerPage: 25,
     searchSnippetLength: 200,
+    searchSort: 'relevance',
+    searchCleanSnippets: true,
+    syncNotificationMode: 'summary',
     fuzzySearchThreshold: 0.8,
     retentionDays: null,
     enableAutoCleanup: false,
@@ -306,12 +314,16 @@ const SettingsPage: React.FC = () => {
         autoDetectLanguageCombination: response.data.auto_detect_language_combination || false,
         concurrentOcrJobs: response.data.concurrent_ocr_jobs || 4,
         ocrTimeoutSeconds: response.data.ocr_timeout_seconds || 300,
+        ocrTextLayerMinChars: response.data.ocr_text_layer_min_chars ?? 100,
         maxFileSizeMb: response.data.max_file_size_mb || 50,
         allowedFileTypes: response.data.allowed_file_types || ['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt'],
         autoRotateImages: response.data.auto_rotate_images !== undefined ? response.data.auto_rotate_images : true,
         enableImagePreprocessing: response.data.enable_image_preprocessing !== undefined ? response.data.enable_image_preprocessing : false,
         searchResultsPerPage: response.data.search_results_per_page || 25,
         searchSnippetLength: response.data.search_snippet_length || 200,
+        searchSort: response.data.search_sort || 'relevance',
+        searchCleanSnippets: response.data.search_clean_snippets !== undefined ? response.data.search_clean_snippets : true,
+        syncNotificationMode: response.data.sync_notification_mode || 'summary',
         fuzzySearchThreshold: response.data.fuzzy_search_threshold || 0.8,
         retentionDays: response.data.retention_days,
         enableAutoCleanup: response.data.enable_auto_cleanup || false,
@@ -916,6 +928,18 @@ const SettingsPage: React.FC = () => {
                         helperText="Maximum time for OCR processing per file"
                       />
                     </Grid>
+                    <Grid item xs={12} md={6}>
+                      <TextField
+                        fullWidth
+                        type="number"
+                        label="PDF Text Layer Threshold (characters)"
+                        value={settings.ocrTextLayerMinChars}
+                        onChange={(e) => handleSettingsChange('ocrTextLayerMinChars', parseInt(e.target.value))}
+                        disabled={loading}
+                        inputProps={{ min: 0 }}
+                        helperText="PDFs with at least this much embedded text skip OCR. Set to 0 to always run OCR"
+                      />
+                    </Grid>
                     <Grid item xs={12} md={6}>
                       <FormControl fullWidth>
                         <InputLabel>CPU Priority</InputLabel>
@@ -1110,6 +1134,21 @@ const SettingsPage: React.FC = () => {
                         </Select>
                       </FormControl>
                     </Grid>
+                    <Grid item xs={12} md={6}>
+                      <FormControl fullWidth>
+                        <InputLabel>Default Sort</InputLabel>
+                        <Select
+                          value={settings.searchSort}
+                          label="Default Sort"
+                          onChange={(e) => handleSettingsChange('searchSort', e.target.value)}
+                          disabled={loading}
+                        >
+                          <MenuItem value="relevance">Relevance</MenuItem>
+                          <MenuItem value="date">Newest first</MenuItem>
+                          <MenuItem value="name">Name</MenuItem>
+                        </Select>
+                      </FormControl>
+                    </Grid>
                     <Grid item xs={12} md={6}>
                       <TextField
                         fullWidth
@@ -1134,6 +1173,21 @@ const SettingsPage: React.FC = () => {
                         helperText="Tolerance for spelling mistakes (0.0-1.0)"
                       />
                     </Grid>
+                    <Grid item xs={12}>
+                      <FormControlLabel
+                        control={
+                          <Switch
+                            checked={settings.searchCleanSnippets}
+                            onChange={(e) => handleSettingsChange('searchCleanSnippets', e.target.checked)}
+                            disabled={loading}
+                          />
+                        }
+                        label="Clean OCR snippets"
+                      />
+                      <Typography variant="caption" color="text.secondary" sx={{ display: 'block', mt: 0.5 }}>
+                        Remove stray symbols and broken letters from previews of OCR text. Turn off to see the raw OCR output.
+                      </Typography>
+                    </Grid>
                   </Grid>
                 </CardContent>
               </Card>
@@ -1194,6 +1248,34 @@ const SettingsPage: React.FC = () => {
                   </Grid>
                 </CardContent>
               </Card>
+
+              <Card sx={{ mb: 3 }}>
+                <CardContent>
+                  <Typography variant="subtitle1" sx={{ mb: 2 }}>
+                    Notifications
+                  </Typography>
+                  <Divider sx={{ mb: 2 }} />
+                  <Grid container spacing={2}>
+                    <Grid item xs={12} md={6}>
+                      <FormControl fullWidth>
+                        <InputLabel>Source Sync Notifications</InputLabel>
+                        <Select
+                          value={settings.syncNotificationMode}
+                          label="Source Sync Notifications"
+                          onChange={(e) => handleSettingsChange('syncNotificationMode', e.target.value)}
+                          disabled={loading}
+                        >
+                          <MenuItem value="summary">One summary per sync</MenuItem>
+                          <MenuItem value="per_file">One per new file</MenuItem>
+                        </Select>
+                      </FormControl>
+                      <Typography variant="caption" color="text.secondary" sx={{ display: 'block', mt: 0.5 }}>
+                        Failed files are always reported in one notification per sync
+                      </Typography>
+                    </Grid>
+                  </Grid>
+                </CardContent>
+              </Card>
             </Box>
           )}
 
@@ -76,7 +76,7 @@ import {
   Error as CriticalIcon,
 } from '@mui/icons-material';
 import { useNavigate } from 'react-router-dom';
-import api, { queueService, sourcesService, ErrorHelper, ErrorCodes } from '../services/api';
+import api, { queueService, sourcesService, ErrorHelper, ErrorCodes, type SourceConnectionTestResult } from '../services/api';
 import { formatDistanceToNow } from 'date-fns';
 import { useAuth } from '../contexts/AuthContext';
 import SyncProgressDisplay from '../components/SyncProgress';
@@ -139,7 +139,7 @@ const SourcesPage: React.FC = () => {
     server_url: '',
     username: '',
     password: '',
-    server_type: 'generic' as 'nextcloud' | 'owncloud' | 'generic',
+    server_type: 'generic' as 'nextcloud' | 'owncloud' | 'seafile' | 'apache' | 'generic',
     // Local Folder fields
     recursive: true,
     follow_symlinks: false,
@@ -150,6 +150,8 @@ const SourcesPage: React.FC = () => {
     secret_access_key: '',
     endpoint_url: '',
     prefix: '',
+    force_path_style: false,
+    ca_certificate: '',
     // Common fields
     watch_folders: ['/Documents'],
     file_extensions: ['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt'],
@@ -298,6 +300,8 @@ const SourcesPage: React.FC = () => {
       secret_access_key: '',
       endpoint_url: '',
       prefix: '',
+      force_path_style: false,
+      ca_certificate: '',
       // Common fields
       watch_folders: ['/Documents'],
       file_extensions: ['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt'],
@@ -332,6 +336,8 @@ const SourcesPage: React.FC = () => {
       secret_access_key: config.secret_access_key || '',
       endpoint_url: config.endpoint_url || '',
       prefix: config.prefix || '',
+      force_path_style: config.force_path_style || false,
+      ca_certificate: config.ca_certificate || '',
       // Common fields
       watch_folders: config.watch_folders || ['/Documents'],
       file_extensions: config.file_extensions || ['pdf', 'png', 'jpg', 'jpeg', 'tiff', 'bmp', 'txt'],
@@ -344,45 +350,53 @@ const SourcesPage: React.FC = () => {
     setDialogOpen(true);
   };
 
+  // The source config as it is saved, also used to test the settings before saving
+  const buildSourceConfig = () => {
+    let config = {};
+
+    if (formData.source_type === 'webdav') {
+      config = {
+        server_url: formData.server_url,
+        username: formData.username,
+        password: formData.password,
+        watch_folders: formData.watch_folders,
+        file_extensions: formData.file_extensions,
+        auto_sync: formData.auto_sync,
+        sync_interval_minutes: formData.sync_interval_minutes,
+        server_type: formData.server_type,
+      };
+    } else if (formData.source_type === 'local_folder') {
+      config = {
+        watch_folders: formData.watch_folders,
+        file_extensions: formData.file_extensions,
+        auto_sync: formData.auto_sync,
+        sync_interval_minutes: formData.sync_interval_minutes,
+        recursive: formData.recursive,
+        follow_symlinks: formData.follow_symlinks,
+      };
+    } else if (formData.source_type === 's3') {
+      config = {
+        bucket_name: formData.bucket_name,
+        region: formData.region,
+        access_key_id: formData.access_key_id,
+        secret_access_key: formData.secret_access_key,
+        endpoint_url: formData.endpoint_url,
+        prefix: formData.prefix,
+        force_path_style: formData.force_path_style,
+        ca_certificate: formData.ca_certificate || null,
+        watch_folders: formData.watch_folders,
+        file_extensions: formData.file_extensions,
+        auto_sync: formData.auto_sync,
+        sync_interval_minutes: formData.sync_interval_minutes,
+      };
+    }
+
+    return config;
+  };
+
   const handleSaveSource = async () => {
     try {
-      let config = {};
-      
-      // Build config based on source type
-      if (formData.source_type === 'webdav') {
-        config = {
-          server_url: formData.server_url,
-          username: formData.username,
-          password: formData.password,
-          watch_folders: formData.watch_folders,
-          file_extensions: formData.file_extensions,
-          auto_sync: formData.auto_sync,
-          sync_interval_minutes: formData.sync_interval_minutes,
-          server_type: formData.server_type,
-        };
-      } else if (formData.source_type === 'local_folder') {
-        config = {
-          watch_folders: formData.watch_folders,
-          file_extensions: formData.file_extensions,
-          auto_sync: formData.auto_sync,
-          sync_interval_minutes: formData.sync_interval_minutes,
-          recursive: formData.recursive,
-          follow_symlinks: formData.follow_symlinks,
-        };
-      } else if (formData.source_type === 's3') {
-        config = {
-          bucket_name: formData.bucket_name,
-          region: formData.region,
-          access_key_id: formData.access_key_id,
-          secret_access_key: formData.secret_access_key,
-          endpoint_url: formData.endpoint_url,
-          prefix: formData.prefix,
-          watch_folders: formData.watch_folders,
-          file_extensions: formData.file_extensions,
-          auto_sync: formData.auto_sync,
-          sync_interval_minutes: formData.sync_interval_minutes,
-        };
-      }
+      const config = buildSourceConfig();
 
       if (editingSource) {
         await api.put(`/sources/${editingSource.id}`, {
@@ -467,47 +481,25 @@ const SourcesPage: React.FC = () => {
   const handleTestConnection = async () => {
     setTestingConnection(true);
     try {
-      let response;
-      if (formData.source_type === 'webdav') {
-        response = await api.post('/sources/test-connection', {
-          source_type: 'webdav',
-          config: {
-            server_url: formData.server_url,
-            username: formData.username,
-            password: formData.password,
-            server_type: formData.server_type,
-            watch_folders: formData.watch_folders,
-            file_extensions: formData.file_extensions,
-          }
-        });
-      } else if (formData.source_type === 'local_folder') {
-        response = await api.post('/sources/test-connection', {
-          source_type: 'local_folder',
-          config: {
-            watch_folders: formData.watch_folders,
-            file_extensions: formData.file_extensions,
-            recursive: formData.recursive,
-            follow_symlinks: formData.follow_symlinks,
-          }
-        });
-      } else if (formData.source_type === 's3') {
-        response = await api.post('/sources/test-connection', {
-          source_type: 's3',
-          config: {
-            bucket_name: formData.bucket_name,
-            region: formData.region,
-            access_key_id: formData.access_key_id,
-            secret_access_key: formData.secret_access_key,
-            endpoint_url: formData.endpoint_url,
-            prefix: formData.prefix,
-          }
-        });
+      const response = await api.post<SourceConnectionTestResult>('/sources/test-connection', {
+        source_type: formData.source_type,
+        config: buildSourceConfig(),
+      });
+
+      const detectedServerType = response.data.server_info?.detected_server_type;
+      if (
+        formData.source_type === 'webdav' &&
+        formData.server_type === 'generic' &&
+        (detectedServerType === 'nextcloud' || detectedServerType === 'owncloud' || detectedServerType === 'seafile')
+      ) {
+        // Pre-select the detected type unless the user picked a specific one
+        setFormData(prev => ({ ...prev, server_type: detectedServerType }));
       }
 
-      if (response && response.data.success) {
+      if (response.data.success) {
         showSnackbar(response.data.message || 'Connection successful!', 'success');
       } else {
-        showSnackbar(response?.data.message || 'Connection failed', 'error');
+        showSnackbar(response.data.message || 'Connection failed', 'error');
       }
     } catch (error: any) {
       console.error('Failed to test connection:', error);
@@ -1511,6 +1503,8 @@ const SourcesPage: React.FC = () => {
                         ? "https://nextcloud.example.com/"
                         : formData.server_type === 'owncloud'
                         ? "https://owncloud.example.com/remote.php/dav/files/username/"
+                        : formData.server_type === 'seafile'
+                        ? "https://seafile.example.com/"
                         : "https://webdav.example.com/dav/"
                     }
                     sx={{ '& .MuiOutlinedInput-root': { borderRadius: 2 } }}
@@ -1568,6 +1562,28 @@ const SourcesPage: React.FC = () => {
                           </Box>
                         </Stack>
                       </MenuItem>
+                      <MenuItem value="seafile">
+                        <Stack direction="row" alignItems="center" spacing={2}>
+                          <ServerIcon />
+                          <Box>
+                            <Typography variant="body1">Seafile</Typography>
+                            <Typography variant="caption" color="text.secondary">
+                              SeafDAV, watch folders start with the library name
+                            </Typography>
+                          </Box>
+                        </Stack>
+                      </MenuItem>
+                      <MenuItem value="apache">
+                        <Stack direction="row" alignItems="center" spacing={2}>
+                          <ServerIcon />
+                          <Box>
+                            <Typography variant="body1">Apache mod_dav</Typography>
+                            <Typography variant="caption" color="text.secondary">
+                              Server URL is the full WebDAV root path
+                            </Typography>
+                          </Box>
+                        </Stack>
+                      </MenuItem>
                       <MenuItem value="generic">
                         <Stack direction="row" alignItems="center" spacing={2}>
                           <CloudIcon />
@@ -2184,6 +2200,37 @@ const SourcesPage: React.FC = () => {
                     sx={{ '& .MuiOutlinedInput-root': { borderRadius: 2 } }}
                   />
 
+                  <FormControlLabel
+                    control={
+                      <Switch
+                        checked={formData.force_path_style}
+                        onChange={(e) => setFormData({ ...formData, force_path_style: e.target.checked })}
+                      />
+                    }
+                    label={
+                      <Box>
+                        <Typography variant="body2" fontWeight="medium">
+                          Path-Style Addressing
+                        </Typography>
+                        <Typography variant="caption" color="text.secondary">
+                          Use endpoint/bucket/key URLs instead of bucket subdomains (required for MinIO)
+                        </Typography>
+                      </Box>
+                    }
+                  />
+
+                  <TextField
+                    fullWidth
+                    multiline
+                    minRows={3}
+                    label="Custom CA Certificate (Optional)"
+                    value={formData.ca_certificate}
+                    onChange={(e) => setFormData({ ...formData, ca_certificate: e.target.value })}
+                    placeholder="-----BEGIN CERTIFICATE-----"
+                    helperText="PEM certificate to trust for endpoints with self-signed certificates"
+                    sx={{ '& .MuiOutlinedInput-root': { borderRadius: 2 } }}
+                  />
+
                   <FormControlLabel
                     control={
                       <Switch
@@ -66,12 +66,17 @@ export interface Document {
 export interface SearchRequest {
   query: string
   tags?: string[]
+  labels?: string[]
+  filter_match?: 'any' | 'all'
   mime_types?: string[]
   limit?: number
   offset?: number
   include_snippets?: boolean
   snippet_length?: number
   search_mode?: 'simple' | 'phrase' | 'fuzzy' | 'boolean'
+  sort?: 'relevance' | 'date' | 'name'
+  captured_after?: string
+  captured_before?: string
 }
 
 export interface HighlightRange {
@@ -174,6 +179,22 @@ export interface BulkOcrRetryResponse {
   estimated_total_time_minutes: number
 }
 
+export interface ReprocessOcrRequest {
+  language?: string
+  document_ids?: string[]
+  mime_types?: string[]
+  source_id?: string
+  created_after?: string
+  created_before?: string
+  force?: boolean
+}
+
+export interface ReprocessOcrResponse {
+  job_id: string
+  queued: number
+  skipped: number
+}
+
 export interface OcrRetryStatsResponse {
   failure_reasons: Array<{
     reason: string
@@ -251,8 +272,72 @@ export interface OcrResponse {
   ocr_status?: string
   ocr_error?: string
   ocr_completed_at?: string
+  detected_script?: string | null
+  script_confidence?: number | null
+  manually_edited: boolean
+  text_source?: 'embedded' | 'ocr' | null
+}
+
+export interface OcrDebugImages {
+  document_id: string
+  original_url: string
+  original_mime_type: string
+  processed_url: string
+  processed_width: number
+  processed_height: number
+  processed_file_size: number
+  processing_steps: string[]
+  parameters: Record<string, any> | null
+  processed_at: string
+}
+
+export interface SkippedArchiveEntry {
+  path: string
+  reason: string
 }
 
+// Returned by upload instead of a single document when a ZIP archive was extracted
+export interface ArchiveUploadResponse {
+  archive_filename: string
+  archive_document_id?: string
+  document_ids: string[]
+  duplicate_document_ids: string[]
+  skipped: SkippedArchiveEntry[]
+  status: 'extracted'
+  message: string
+}
+
+// A link that gives access to one document without an account
+export interface DocumentShare {
+  id: string
+  document_id: string
+  token: string
+  created_by: string
+  expires_at: string
+  allow_download: boolean
+  created_at: string
+  access_count: number
+  last_accessed_at?: string | null
+  url: string
+}
+
+// Result of testing source settings; reachable and authenticated tell how far a failed test got
+export interface SourceConnectionTestResult {
+  success: boolean
+  reachable: boolean
+  authenticated: boolean
+  message: string
+  server_info?: Record<string, any> | null
+}
+
+// The search API takes lists as comma-separated query parameters
+const searchParams = (searchRequest: SearchRequest) => ({
+  ...searchRequest,
+  tags: searchRequest.tags?.join(','),
+  labels: searchRequest.labels?.join(','),
+  mime_types: searchRequest.mime_types?.join(','),
+})
+
 export const documentService = {
   upload: (file: File, languages?: string[]) => {
     const formData = new FormData()
@@ -320,10 +405,20 @@ export const documentService = {
     }
   },
 
+  downloadSearchablePdf: (id: string) => {
+    return api.get(`/documents/${id}/searchable-pdf`, {
+      responseType: 'blob',
+    })
+  },
+
   getOcrText: (id: string) => {
     return api.get<OcrResponse>(`/documents/${id}/ocr`)
   },
 
+  correctOcrText: (id: string, ocrText: string) => {
+    return api.put(`/documents/${id}/ocr`, { ocr_text: ocrText })
+  },
+
   view: (id: string) => {
     return api.get(`/documents/${id}/view`, {
       responseType: 'blob',
@@ -342,8 +437,18 @@ export const documentService = {
     })
   },
 
-  retryOcr: (id: string) => {
-    return api.post(`/documents/${id}/ocr/retry`)
+  getOcrDebugImages: (id: string) => {
+    return api.get<OcrDebugImages>(`/documents/${id}/ocr/debug-images`)
+  },
+
+  getOcrDebugProcessedImage: (id: string) => {
+    return api.get(`/documents/${id}/ocr/debug-images/processed`, {
+      responseType: 'blob',
+    })
+  },
+
+  retryOcr: (id: string, overwriteManualEdits = false) => {
+    return api.post(`/documents/${id}/ocr/retry`, { overwrite_manual_edits: overwriteManualEdits })
   },
 
   // Advanced OCR retry functionality
@@ -351,6 +456,10 @@ export const documentService = {
     return api.post<BulkOcrRetryResponse>('/documents/ocr/bulk-retry', request)
   },
 
+  reprocessOcr: (request: ReprocessOcrRequest) => {
+    return api.post<ReprocessOcrResponse>('/documents/ocr/reprocess', request)
+  },
+
   getRetryStats: () => {
     return api.get<OcrRetryStatsResponse>('/documents/ocr/retry-stats')
   },
@@ -377,14 +486,14 @@ export const documentService = {
 
   search: (searchRequest: SearchRequest) => {
     return api.get<SearchResponse>('/search', {
-      params: searchRequest,
+      params: searchParams(searchRequest),
     })
   },
 
   enhancedSearch: (searchRequest: SearchRequest) => {
     return api.get<SearchResponse>('/search/enhanced', {
       params: {
-        ...searchRequest,
+        ...searchParams(searchRequest),
         include_snippets: searchRequest.include_snippets ?? true,
         snippet_length: searchRequest.snippet_length ?? 200,
         search_mode: searchRequest.search_mode ?? 'simple',
@@ -400,6 +509,18 @@ export const documentService = {
     return api.delete(`/documents/${id}`)
   },
 
+  share: (id: string, options: { expires_in_hours?: number; allow_download?: boolean } = {}) => {
+    return api.post<DocumentShare>(`/documents/${id}/share`, options)
+  },
+
+  listShares: (id: string) => {
+    return api.get<DocumentShare[]>(`/documents/${id}/shares`)
+  },
+
+  revokeShare: (id: string, token: string) => {
+    return api.delete(`/documents/${id}/shares/${token}`)
+  },
+
   bulkDelete: (documentIds: string[]) => {
     return api.post('/documents/bulk/delete', {
       document_ids: documentIds
@@ -447,9 +568,64 @@ export interface AvailableLanguagesResponse {
   current_user_language: string
 }
 
+export interface OcrToolStatus {
+  installed: boolean
+  version?: string
+  usable: boolean
+}
+
+export interface OcrLanguageStatus {
+  code: string
+  name: string
+  usable: boolean
+  error?: string
+}
+
+export interface OcrDiagnosticsResponse {
+  ocr_enabled: boolean
+  tesseract: OcrToolStatus
+  ocrmypdf: OcrToolStatus
+  languages: OcrLanguageStatus[]
+  issues: string[]
+}
+
 export interface RetryOcrRequest {
   language?: string
   languages?: string[]
+  overwrite_manual_edits?: boolean
+}
+
+export interface AdminQueueItem {
+  id: string
+  document_id: string
+  filename: string
+  user_id: string
+  username: string
+  status: 'pending' | 'processing' | 'completed' | 'failed' | 'cancelled' | 'dead_letter'
+  priority: number
+  attempts: number
+  max_attempts: number
+  worker_id?: string
+  error_message?: string
+  file_size?: number
+  created_at: string
+  started_at?: string
+  dead_lettered_at?: string
+  age_seconds: number
+}
+
+export interface AdminQueueResponse {
+  items: AdminQueueItem[]
+  total: number
+  limit: number
+  offset: number
+}
+
+export interface AdminQueueParams {
+  status?: AdminQueueItem['status']
+  user_id?: string
+  limit?: number
+  offset?: number
 }
 
 export const queueService = {
@@ -472,6 +648,30 @@ export const queueService = {
   resumeOcr: () => {
     return api.post<OcrActionResponse>('/queue/resume')
   },
+
+  getAdminQueue: (params: AdminQueueParams = {}) => {
+    return api.get<AdminQueueResponse>('/queue/admin', { params })
+  },
+
+  setQueueItemPriority: (id: string, priority: number) => {
+    return api.post(`/queue/admin/${id}/priority`, { priority })
+  },
+
+  cancelQueueItem: (id: string) => {
+    return api.post(`/queue/admin/${id}/cancel`)
+  },
+
+  requeueStale: () => {
+    return api.post<{ requeued_count: number; stale_after_minutes: number }>('/queue/admin/requeue-stale')
+  },
+
+  getDeadLetterQueue: (params: Omit<AdminQueueParams, 'status'> = {}) => {
+    return api.get<AdminQueueResponse>('/queue/dead-letter', { params })
+  },
+
+  requeueDeadLetterItem: (id: string) => {
+    return api.post<{ id: string; status: 'pending' }>(`/queue/dead-letter/${id}/requeue`)
+  },
 }
 
 export const ocrService = {
@@ -483,6 +683,10 @@ export const ocrService = {
     return api.get('/ocr/health')
   },
 
+  getDiagnostics: () => {
+    return api.get<OcrDiagnosticsResponse>('/ocr/diagnostics')
+  },
+
   retryWithLanguage: (documentId: string, language?: string, languages?: string[]) => {
     const data: RetryOcrRequest = {}
     if (languages && languages.length > 0) {
@@ -691,9 +895,42 @@ export const userWatchService = {
   },
 }
 
+export interface SourceSyncAbortResult {
+  sync_stopped: boolean
+  sync_run_id: string | null
+  ocr_jobs_cancelled: number
+  documents_deleted: number
+}
+
+export type SourceSyncMode = 'smart' | 'new-only'
+
+export interface SourceSyncStarted {
+  mode: SourceSyncMode
+  message: string
+  limitations: string[]
+}
+
+export interface MissingSourceDocument {
+  id: string
+  filename: string
+  source_path: string
+  missing_since: string | null
+}
+
+export type MissingDocumentAction = 'delete' | 'detach'
+
+export interface ResolveMissingDocumentsResponse {
+  action: MissingDocumentAction
+  resolved_count: number
+  resolved_documents: string[]
+  failed_documents: string[]
+}
+
 export const sourcesService = {
-  triggerSync: (sourceId: string) => {
-    return api.post(`/sources/${sourceId}/sync`)
+  triggerSync: (sourceId: string, mode: SourceSyncMode = 'smart') => {
+    return api.post<SourceSyncStarted>(`/sources/${sourceId}/sync`, null, {
+      params: { mode },
+    })
   },
 
   triggerDeepScan: (sourceId: string) => {
@@ -704,10 +941,27 @@ export const sourcesService = {
     return api.post(`/sources/${sourceId}/sync/stop`)
   },
 
+  abortSync: (sourceId: string, deleteDocuments = false) => {
+    return api.post<SourceSyncAbortResult>(`/sources/${sourceId}/sync/abort`, null, {
+      params: { delete_documents: deleteDocuments },
+    })
+  },
+
   getSyncStatus: (sourceId: string) => {
     return api.get(`/sources/${sourceId}/sync/status`)
   },
 
+  getMissingDocuments: (sourceId: string) => {
+    return api.get<MissingSourceDocument[]>(`/sources/${sourceId}/missing`)
+  },
+
+  resolveMissingDocuments: (sourceId: string, action: MissingDocumentAction, documentIds?: string[]) => {
+    return api.post<ResolveMissingDocumentsResponse>(`/sources/${sourceId}/missing/resolve`, {
+      action,
+      document_ids: documentIds,
+    })
+  },
+
   createSyncProgressWebSocket: (sourceId: string) => {
     return new SyncProgressWebSocket(sourceId);
   },
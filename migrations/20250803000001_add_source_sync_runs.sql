@@ -0,0 +1,17 @@
+-- One row per source sync run, used for sync history and throughput metrics
+CREATE TABLE IF NOT EXISTS source_sync_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    files_discovered BIGINT NOT NULL DEFAULT 0,
+    files_downloaded BIGINT NOT NULL DEFAULT 0,
+    files_failed BIGINT NOT NULL DEFAULT 0,
+    bytes_transferred BIGINT NOT NULL DEFAULT 0,
+    outcome VARCHAR(20) NOT NULL DEFAULT 'running',
+    error_message TEXT,
+    CONSTRAINT check_source_sync_run_outcome CHECK (outcome IN ('running', 'success', 'failed', 'cancelled'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_source_sync_runs_source_started ON source_sync_runs(source_id, started_at DESC);
+CREATE INDEX IF NOT EXISTS idx_source_sync_runs_running ON source_sync_runs(source_id) WHERE outcome = 'running';
@@ -0,0 +1,15 @@
+-- Soft-delete support: deleted documents stay in the trash until purged
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMPTZ;
+
+CREATE INDEX IF NOT EXISTS idx_documents_deleted_at
+ON documents(deleted_at)
+WHERE deleted_at IS NOT NULL;
+
+-- Trashed documents must not block re-uploading the same content
+DROP INDEX IF EXISTS idx_documents_user_file_hash;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_documents_user_file_hash
+ON documents(user_id, file_hash)
+WHERE file_hash IS NOT NULL AND deleted_at IS NULL;
+
+COMMENT ON COLUMN documents.deleted_at IS 'When the document was moved to the trash; NULL for live documents';
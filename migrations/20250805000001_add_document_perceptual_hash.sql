@@ -0,0 +1,9 @@
+-- Perceptual (difference) hash for image documents, used to find near-duplicate scans
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS perceptual_hash BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_documents_user_perceptual_hash
+ON documents(user_id)
+WHERE perceptual_hash IS NOT NULL AND deleted_at IS NULL;
+
+COMMENT ON COLUMN documents.perceptual_hash IS '64-bit dHash of image documents; NULL for non-images or when not yet computed';
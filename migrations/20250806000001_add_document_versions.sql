@@ -0,0 +1,33 @@
+-- Prior versions of documents whose source file changed. The documents row always
+-- holds the current version; superseded content is archived here.
+CREATE TABLE IF NOT EXISTS document_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    version_number INTEGER NOT NULL,
+    filename VARCHAR(255) NOT NULL,
+    file_path TEXT NOT NULL,
+    file_size BIGINT NOT NULL,
+    mime_type VARCHAR(100) NOT NULL,
+    file_hash VARCHAR(64),
+    content TEXT,
+    ocr_text TEXT,
+    ocr_confidence REAL,
+    ocr_word_count INTEGER,
+    original_modified_at TIMESTAMPTZ,
+    -- When this version was first ingested and when it was replaced
+    created_at TIMESTAMPTZ NOT NULL,
+    superseded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (document_id, version_number)
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_versions_document_id
+ON document_versions(document_id, version_number DESC);
+
+-- Versions are matched by the source they were synced from and their path within it
+CREATE INDEX IF NOT EXISTS idx_documents_source_path
+ON documents(user_id, source_id, source_path)
+WHERE source_id IS NOT NULL AND source_path IS NOT NULL AND deleted_at IS NULL;
+
+-- The current version's number; 1 until the source file first changes
+ALTER TABLE documents
+ADD COLUMN IF NOT EXISTS version_number INTEGER NOT NULL DEFAULT 1;
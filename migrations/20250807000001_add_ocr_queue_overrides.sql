@@ -0,0 +1,3 @@
+-- Per-job Tesseract settings (whitelist/blacklist, PSM, OEM) requested when retrying OCR.
+-- NULL means the job uses the document owner's saved settings.
+ALTER TABLE ocr_queue ADD COLUMN IF NOT EXISTS ocr_overrides JSONB;
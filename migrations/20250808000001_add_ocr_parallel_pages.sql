@@ -0,0 +1,3 @@
+-- Opt-in per-page parallel OCR for multi-page PDFs. Off by default so
+-- low-memory deployments keep OCRing one page at a time.
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_parallel_pages BOOLEAN NOT NULL DEFAULT false;
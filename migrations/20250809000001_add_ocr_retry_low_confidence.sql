@@ -0,0 +1,3 @@
+-- Low-confidence OCR results get one retry with escalated preprocessing before
+-- they are failed. On by default; users can turn it off to fail them right away.
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_retry_low_confidence BOOLEAN NOT NULL DEFAULT true;
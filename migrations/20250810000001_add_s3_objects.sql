@@ -0,0 +1,13 @@
+-- Objects synced from S3 sources, so later syncs only download new or changed objects
+-- and can tell when an object has been removed from the bucket
+CREATE TABLE IF NOT EXISTS s3_objects (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    source_id UUID NOT NULL REFERENCES sources(id) ON DELETE CASCADE,
+    object_key TEXT NOT NULL,
+    etag TEXT NOT NULL,
+    last_modified TIMESTAMPTZ,
+    size BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (source_id, object_key)
+);
@@ -0,0 +1,11 @@
+-- Word-level OCR results (text, confidence, bounding box) for image and PDF documents,
+-- one row per page so viewers can fetch them a few pages at a time
+CREATE TABLE IF NOT EXISTS document_ocr_words (
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    page INT NOT NULL,
+    width INT NOT NULL,
+    height INT NOT NULL,
+    words JSONB NOT NULL DEFAULT '[]'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (document_id, page)
+);
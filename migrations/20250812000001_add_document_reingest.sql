@@ -0,0 +1,31 @@
+-- One row per bulk re-hash of document files against the database
+CREATE TABLE IF NOT EXISTS document_reingest_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    reenqueue_ocr BOOLEAN NOT NULL DEFAULT false,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    total_documents BIGINT NOT NULL DEFAULT 0,
+    checked BIGINT NOT NULL DEFAULT 0,
+    mismatched BIGINT NOT NULL DEFAULT 0,
+    missing BIGINT NOT NULL DEFAULT 0,
+    reenqueued BIGINT NOT NULL DEFAULT 0,
+    outcome VARCHAR(20) NOT NULL DEFAULT 'running',
+    error_message TEXT,
+    CONSTRAINT check_document_reingest_run_outcome CHECK (outcome IN ('running', 'success', 'failed', 'cancelled'))
+);
+
+-- Only one reingest may run at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_document_reingest_runs_single_running
+    ON document_reingest_runs ((true)) WHERE outcome = 'running';
+
+-- Documents whose file on disk is missing or no longer matches the stored hash.
+-- A document's row is removed once a later check finds its file intact.
+CREATE TABLE IF NOT EXISTS document_integrity_issues (
+    document_id UUID PRIMARY KEY REFERENCES documents(id) ON DELETE CASCADE,
+    issue VARCHAR(20) NOT NULL,
+    expected_hash TEXT,
+    actual_hash TEXT,
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_document_integrity_issue CHECK (issue IN ('missing', 'hash_mismatch'))
+);
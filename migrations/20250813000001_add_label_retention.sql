@@ -0,0 +1,5 @@
+-- Per-label retention: documents are kept for the longest period among their labels,
+-- and never removed while they carry a keep-forever label
+ALTER TABLE labels
+ADD COLUMN IF NOT EXISTS retention_days INT CHECK (retention_days > 0),
+ADD COLUMN IF NOT EXISTS retain_forever BOOLEAN NOT NULL DEFAULT false;
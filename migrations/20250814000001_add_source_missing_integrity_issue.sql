@@ -0,0 +1,4 @@
+-- Documents whose folder was removed from the source they were synced from
+ALTER TABLE document_integrity_issues DROP CONSTRAINT IF EXISTS check_document_integrity_issue;
+ALTER TABLE document_integrity_issues ADD CONSTRAINT check_document_integrity_issue
+    CHECK (issue IN ('missing', 'hash_mismatch', 'source_missing'));
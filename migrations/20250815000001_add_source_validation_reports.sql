@@ -0,0 +1,10 @@
+-- Latest scheduled ETag tracking validation of each WebDAV source, and whether it asked for a deep scan
+CREATE TABLE IF NOT EXISTS source_validation_reports (
+    source_id UUID PRIMARY KEY REFERENCES sources(id) ON DELETE CASCADE,
+    health_score INT NOT NULL,
+    report JSONB NOT NULL,
+    deep_scan_pending BOOLEAN NOT NULL DEFAULT false,
+    validated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_source_validation_reports_pending ON source_validation_reports(source_id) WHERE deep_scan_pending;
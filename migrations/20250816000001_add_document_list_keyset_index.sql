@@ -0,0 +1,3 @@
+-- Keyset pagination of the document list orders by (created_at, id)
+CREATE INDEX IF NOT EXISTS idx_documents_user_created_id ON documents(user_id, created_at DESC, id DESC) WHERE deleted_at IS NULL;
+CREATE INDEX IF NOT EXISTS idx_documents_created_id ON documents(created_at DESC, id DESC) WHERE deleted_at IS NULL;
@@ -0,0 +1,2 @@
+-- The OCR worker claims pending jobs by priority, then smallest file first
+CREATE INDEX IF NOT EXISTS idx_ocr_queue_pending_dispatch ON ocr_queue(priority DESC, file_size ASC NULLS LAST, created_at ASC) WHERE status = 'pending';
@@ -0,0 +1,3 @@
+-- Opt-in extraction of uploaded ZIP archives into one document per contained file.
+-- Off by default so archives keep being stored as they are.
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS extract_archives BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,3 @@
+-- Default order of search results when a search doesn't ask for one
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS search_sort TEXT NOT NULL DEFAULT 'relevance'
+    CHECK (search_sort IN ('relevance', 'date', 'name'));
@@ -0,0 +1,23 @@
+-- Links that give anyone holding the token access to a single document, without an account
+CREATE TABLE IF NOT EXISTS document_shares (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    token VARCHAR(64) NOT NULL UNIQUE,
+    created_by UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    allow_download BOOLEAN NOT NULL DEFAULT false,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_shares_document_id ON document_shares(document_id);
+
+-- Every use of a share link, for the document owner to review
+CREATE TABLE IF NOT EXISTS document_share_accesses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    share_id UUID NOT NULL REFERENCES document_shares(id) ON DELETE CASCADE,
+    client_ip TEXT NOT NULL,
+    downloaded BOOLEAN NOT NULL DEFAULT false,
+    accessed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_share_accesses_share_id ON document_share_accesses(share_id, accessed_at);
@@ -0,0 +1,7 @@
+-- Opt-in detection of the script a document is written in, using Tesseract's
+-- orientation and script detection. Off by default since it costs an extra pass.
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_detect_script BOOLEAN NOT NULL DEFAULT false;
+
+-- Script detected on the last OCR run, if detection was enabled
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS detected_script TEXT;
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS script_confidence REAL;
@@ -0,0 +1,22 @@
+-- One row per backfill of derived document fields (file hash, OCR word count)
+-- for documents ingested before those columns were filled in
+CREATE TABLE IF NOT EXISTS document_backfill_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- NULL when the run was started at server startup
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    total_documents BIGINT NOT NULL DEFAULT 0,
+    checked BIGINT NOT NULL DEFAULT 0,
+    hashes_filled BIGINT NOT NULL DEFAULT 0,
+    word_counts_filled BIGINT NOT NULL DEFAULT 0,
+    missing BIGINT NOT NULL DEFAULT 0,
+    hash_conflicts BIGINT NOT NULL DEFAULT 0,
+    outcome VARCHAR(20) NOT NULL DEFAULT 'running',
+    error_message TEXT,
+    CONSTRAINT check_document_backfill_run_outcome CHECK (outcome IN ('running', 'success', 'failed', 'cancelled'))
+);
+
+-- Only one backfill may run at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_document_backfill_runs_single_running
+    ON document_backfill_runs ((true)) WHERE outcome = 'running';
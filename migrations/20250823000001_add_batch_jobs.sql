@@ -0,0 +1,42 @@
+-- Long-running bulk OCR operations. The matching documents are recorded when the job is
+-- created and fed into the OCR queue a window at a time, so a restart resumes the job
+-- instead of losing what was not yet queued.
+CREATE TABLE IF NOT EXISTS batch_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(30) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'running',
+    -- Priority and per-job OCR overrides the documents are queued with
+    priority INTEGER NOT NULL,
+    ocr_overrides JSONB,
+    total INTEGER NOT NULL DEFAULT 0,
+    enqueued INTEGER NOT NULL DEFAULT 0,
+    done INTEGER NOT NULL DEFAULT 0,
+    failed INTEGER NOT NULL DEFAULT 0,
+    -- Documents left alone because OCR was already pending or running for them
+    skipped INTEGER NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    CONSTRAINT check_batch_job_kind CHECK (kind IN ('reocr', 'reprocess_language')),
+    CONSTRAINT check_batch_job_status CHECK (status IN ('running', 'completed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_batch_jobs_running ON batch_jobs(created_at) WHERE status = 'running';
+CREATE INDEX IF NOT EXISTS idx_batch_jobs_user_id ON batch_jobs(user_id);
+
+-- The documents a batch job covers and how far each has got
+CREATE TABLE IF NOT EXISTS batch_job_documents (
+    batch_job_id UUID NOT NULL REFERENCES batch_jobs(id) ON DELETE CASCADE,
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    state VARCHAR(20) NOT NULL DEFAULT 'waiting',
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (batch_job_id, document_id),
+    CONSTRAINT check_batch_job_document_state CHECK (state IN ('waiting', 'queued', 'completed', 'failed', 'skipped'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_batch_job_documents_state ON batch_job_documents(batch_job_id, state);
+
+-- Queue entries created by a batch job report their outcome back to it
+ALTER TABLE ocr_queue ADD COLUMN IF NOT EXISTS batch_job_id UUID REFERENCES batch_jobs(id) ON DELETE SET NULL;
+CREATE INDEX IF NOT EXISTS idx_ocr_queue_batch_job_id ON ocr_queue(batch_job_id) WHERE batch_job_id IS NOT NULL;
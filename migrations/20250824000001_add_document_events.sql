@@ -0,0 +1,18 @@
+-- Processing history of each document: one row per lifecycle transition in ingestion
+-- and the OCR queue, pruned after DOCUMENT_EVENT_RETENTION_DAYS
+CREATE TABLE IF NOT EXISTS document_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    event_type VARCHAR(30) NOT NULL,
+    detail TEXT,
+    -- OCR worker that made the transition, for events from the queue
+    worker_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT check_document_event_type CHECK (event_type IN (
+        'uploaded', 'new_version', 'queued', 'ocr_started', 'ocr_retried',
+        'ocr_failed', 'ocr_completed', 'ocr_cancelled'
+    ))
+);
+
+CREATE INDEX IF NOT EXISTS idx_document_events_document_id ON document_events(document_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_document_events_created_at ON document_events(created_at);
@@ -0,0 +1,8 @@
+-- OCR text corrected by hand is kept when OCR runs again, unless the run says otherwise
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_manually_edited BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Set on jobs that may replace manually corrected text: a confirmed single retry, or a
+-- forced bulk reprocess
+ALTER TABLE ocr_queue ADD COLUMN IF NOT EXISTS overwrite_manual_edits BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE batch_jobs ADD COLUMN IF NOT EXISTS force BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,19 @@
+-- Whether a synced document's file still exists on its source: NULL while it does,
+-- 'missing' once a sync that listed its directory no longer found it
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS source_status TEXT;
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS source_missing_at TIMESTAMPTZ;
+
+ALTER TABLE documents DROP CONSTRAINT IF EXISTS check_document_source_status;
+ALTER TABLE documents ADD CONSTRAINT check_document_source_status
+    CHECK (source_status IS NULL OR source_status = 'missing');
+
+-- Removed S3 objects used to be flagged in the source metadata
+UPDATE documents
+SET source_status = 'missing',
+    source_missing_at = COALESCE((source_metadata->>'source_deleted_at')::timestamptz, NOW()),
+    source_metadata = source_metadata - 'source_deleted_at'
+WHERE source_metadata ? 'source_deleted_at';
+
+CREATE INDEX IF NOT EXISTS idx_documents_source_missing
+    ON documents (source_id)
+    WHERE source_status = 'missing' AND deleted_at IS NULL;
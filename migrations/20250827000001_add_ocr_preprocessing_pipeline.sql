@@ -0,0 +1,3 @@
+-- Image preprocessing pipeline run before OCR: a preset name or a list of steps.
+-- NULL keeps the enhancements chosen from the image quality and the OCR toggles.
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_preprocessing JSONB;
@@ -0,0 +1,19 @@
+-- OCR jobs that used up all their attempts move to 'dead_letter' instead of 'failed', so
+-- they stay out of the retry sweeps until an operator requeues them. Both status checks
+-- are replaced by one that also allows 'cancelled'.
+ALTER TABLE ocr_queue DROP CONSTRAINT IF EXISTS check_status;
+ALTER TABLE ocr_queue DROP CONSTRAINT IF EXISTS check_queue_status;
+ALTER TABLE ocr_queue ADD CONSTRAINT check_queue_status
+    CHECK (status IN ('pending', 'processing', 'completed', 'failed', 'cancelled', 'dead_letter'));
+
+ALTER TABLE ocr_queue ADD COLUMN IF NOT EXISTS dead_lettered_at TIMESTAMPTZ;
+
+UPDATE ocr_queue
+SET status = 'dead_letter',
+    dead_lettered_at = NOW()
+WHERE status = 'failed'
+  AND attempts >= max_attempts;
+
+CREATE INDEX IF NOT EXISTS idx_ocr_queue_dead_letter
+    ON ocr_queue (dead_lettered_at DESC)
+    WHERE status = 'dead_letter';
@@ -0,0 +1,2 @@
+-- Whether search snippets taken from OCR text are cleaned of OCR noise before they are returned
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS search_clean_snippets BOOLEAN NOT NULL DEFAULT TRUE;
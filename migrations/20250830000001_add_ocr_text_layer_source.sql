@@ -0,0 +1,7 @@
+-- PDFs whose embedded text layer has at least this many characters are stored without OCR
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS ocr_text_layer_min_chars INTEGER NOT NULL DEFAULT 100
+    CHECK (ocr_text_layer_min_chars >= 0);
+
+-- Where a document's extracted text came from: the file's embedded text or an OCR run
+ALTER TABLE documents ADD COLUMN IF NOT EXISTS ocr_text_source TEXT
+    CHECK (ocr_text_source IN ('embedded', 'ocr'));
@@ -0,0 +1,3 @@
+-- Whether finished source syncs are reported with one summary notification or one per file
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS sync_notification_mode TEXT NOT NULL DEFAULT 'summary'
+    CHECK (sync_notification_mode IN ('summary', 'per_file'));
@@ -0,0 +1,15 @@
+-- Index of derived files (thumbnails, first-page renders, searchable PDFs) kept on disk, so
+-- the cache can be held under DERIVED_ASSET_CACHE_MAX_MB by evicting the least recently
+-- used entries. A document has at most one entry of each kind.
+CREATE TABLE IF NOT EXISTS derived_asset_cache (
+    kind TEXT NOT NULL CHECK (kind IN ('thumbnail', 'pdf_thumbnail', 'searchable_pdf')),
+    document_id UUID NOT NULL REFERENCES documents(id) ON DELETE CASCADE,
+    file_path TEXT NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes >= 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_accessed TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (kind, document_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_derived_asset_cache_last_accessed
+    ON derived_asset_cache (last_accessed);
@@ -0,0 +1,12 @@
+-- Orientation of image documents. rotation_degrees is the clockwise rotation that shows the
+-- document upright, applied automatically or, when rotation_by_user is set, chosen by the user;
+-- detection never overrides a user's choice. The detected_ columns record what orientation
+-- detection found, and orientation_needs_review marks detections too unsure to apply.
+ALTER TABLE documents
+    ADD COLUMN IF NOT EXISTS rotation_degrees INTEGER NOT NULL DEFAULT 0
+        CHECK (rotation_degrees IN (0, 90, 180, 270)),
+    ADD COLUMN IF NOT EXISTS detected_rotation_degrees INTEGER
+        CHECK (detected_rotation_degrees IN (0, 90, 180, 270)),
+    ADD COLUMN IF NOT EXISTS orientation_confidence REAL,
+    ADD COLUMN IF NOT EXISTS orientation_needs_review BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS rotation_by_user BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,19 @@
+-- One row per database maintenance run (search index rebuild or vacuum) started by an admin
+CREATE TABLE IF NOT EXISTS maintenance_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    operation VARCHAR(20) NOT NULL,
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    -- The statements of the run in order, with the timing of those that finished
+    steps JSONB NOT NULL DEFAULT '[]',
+    completed_steps INTEGER NOT NULL DEFAULT 0,
+    outcome VARCHAR(20) NOT NULL DEFAULT 'running',
+    error_message TEXT,
+    CONSTRAINT check_maintenance_run_operation CHECK (operation IN ('reindex', 'vacuum')),
+    CONSTRAINT check_maintenance_run_outcome CHECK (outcome IN ('running', 'success', 'failed', 'cancelled'))
+);
+
+-- Only one maintenance run may work on the tables at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_maintenance_runs_single_running
+    ON maintenance_runs ((true)) WHERE outcome = 'running';
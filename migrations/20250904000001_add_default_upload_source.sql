@@ -0,0 +1,3 @@
+-- Source that a user's uploads are filed under, and whether they are written back to it
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS default_source_id UUID REFERENCES sources(id) ON DELETE SET NULL;
+ALTER TABLE settings ADD COLUMN IF NOT EXISTS default_source_write_back BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+-- TOTP time step of the last code a user signed in with; codes at or before it are refused
+ALTER TABLE users ADD COLUMN IF NOT EXISTS totp_last_used_step BIGINT;
@@ -0,0 +1,48 @@
+-- One row per long-running admin operation over the documents. Runs of every kind share
+-- the lifecycle columns; what a run counts depends on its kind and is kept in `details`.
+CREATE TABLE IF NOT EXISTS document_runs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    kind VARCHAR(20) NOT NULL,
+    -- NULL when the run was started at server startup
+    started_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    started_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMPTZ,
+    details JSONB NOT NULL DEFAULT '{}',
+    outcome VARCHAR(20) NOT NULL DEFAULT 'running',
+    error_message TEXT,
+    CONSTRAINT check_document_run_kind CHECK (kind IN ('reingest', 'backfill')),
+    CONSTRAINT check_document_run_outcome CHECK (outcome IN ('running', 'success', 'failed', 'cancelled'))
+);
+
+-- Only one run of each kind may run at a time
+CREATE UNIQUE INDEX IF NOT EXISTS idx_document_runs_single_running
+    ON document_runs (kind) WHERE outcome = 'running';
+
+INSERT INTO document_runs (id, kind, started_by, started_at, finished_at, details, outcome, error_message)
+SELECT id, 'reingest', started_by, started_at, finished_at,
+       jsonb_build_object(
+           'reenqueue_ocr', reenqueue_ocr,
+           'total_documents', total_documents,
+           'checked', checked,
+           'mismatched', mismatched,
+           'missing', missing,
+           'reenqueued', reenqueued
+       ),
+       outcome, error_message
+FROM document_reingest_runs;
+
+INSERT INTO document_runs (id, kind, started_by, started_at, finished_at, details, outcome, error_message)
+SELECT id, 'backfill', started_by, started_at, finished_at,
+       jsonb_build_object(
+           'total_documents', total_documents,
+           'checked', checked,
+           'hashes_filled', hashes_filled,
+           'word_counts_filled', word_counts_filled,
+           'missing', missing,
+           'hash_conflicts', hash_conflicts
+       ),
+       outcome, error_message
+FROM document_backfill_runs;
+
+DROP TABLE IF EXISTS document_reingest_runs;
+DROP TABLE IF EXISTS document_backfill_runs;
@@ -0,0 +1,17 @@
+-- Database maintenance runs are tracked with the other document runs; the operation
+-- (reindex or vacuum) is part of their details so only one of either runs at a time.
+ALTER TABLE document_runs DROP CONSTRAINT IF EXISTS check_document_run_kind;
+ALTER TABLE document_runs
+    ADD CONSTRAINT check_document_run_kind CHECK (kind IN ('reingest', 'backfill', 'maintenance'));
+
+INSERT INTO document_runs (id, kind, started_by, started_at, finished_at, details, outcome, error_message)
+SELECT id, 'maintenance', started_by, started_at, finished_at,
+       jsonb_build_object(
+           'operation', operation,
+           'steps', steps,
+           'completed_steps', completed_steps
+       ),
+       outcome, error_message
+FROM maintenance_runs;
+
+DROP TABLE IF EXISTS maintenance_runs;
@@ -1,26 +1,35 @@
 use anyhow::Result;
 use axum::{
-    extract::FromRequestParts,
-    http::{request::Parts, HeaderMap, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, OriginalUri},
+    http::{request::Parts, HeaderMap, Method, StatusCode},
     response::{IntoResponse, Response},
 };
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use uuid::Uuid;
 
 use crate::{models::User, AppState};
 
+/// How long an impersonation token is valid
+pub const IMPERSONATION_TOKEN_MINUTES: i64 = 30;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: Uuid,
     pub username: String,
     pub exp: usize,
+    /// The admin acting as `sub`, on impersonation tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonated_by: Option<Uuid>,
 }
 
 pub struct AuthUser {
     pub user: User,
+    /// The admin acting as `user`, when the request carries an impersonation token
+    pub impersonated_by: Option<Uuid>,
 }
 
 impl FromRequestParts<Arc<AppState>> for AuthUser {
@@ -44,7 +53,31 @@ impl FromRequestParts<Arc<AppState>> for AuthUser {
             .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response())?
             .ok_or_else(|| (StatusCode::UNAUTHORIZED, "User not found").into_response())?;
 
-        Ok(AuthUser { user })
+        let span = tracing::Span::current();
+        span.record("user_id", tracing::field::display(user.id));
+
+        if let Some(admin_id) = claims.impersonated_by {
+            span.record("impersonated_by", tracing::field::display(admin_id));
+            let path = parts
+                .extensions
+                .get::<OriginalUri>()
+                .map(|OriginalUri(uri)| uri.path().to_string())
+                .unwrap_or_else(|| parts.uri.path().to_string());
+            tracing::info!(
+                admin_id = %admin_id,
+                user_id = %user.id,
+                "Impersonated request: {} {}",
+                parts.method,
+                path
+            );
+
+            if state.config.impersonation_read_only && !is_read_only_method(&parts.method) {
+                tracing::warn!("Blocked {} {} by admin {} impersonating user {}", parts.method, path, admin_id, user.id);
+                return Err((StatusCode::FORBIDDEN, "Changes are disabled while impersonating a user").into_response());
+            }
+        }
+
+        Ok(AuthUser { user, impersonated_by: claims.impersonated_by })
     }
 }
 
@@ -58,6 +91,7 @@ pub fn create_jwt(user: &User, secret: &str) -> Result<String> {
         sub: user.id,
         username: user.username.clone(),
         exp: expiration as usize,
+        impersonated_by: None,
     };
 
     let token = encode(
@@ -69,6 +103,27 @@ pub fn create_jwt(user: &User, secret: &str) -> Result<String> {
     Ok(token)
 }
 
+/// Issues a short-lived token that lets `admin_id` act as `user`. Returns the token and
+/// when it expires.
+pub fn create_impersonation_jwt(user: &User, admin_id: Uuid, secret: &str) -> Result<(String, DateTime<Utc>)> {
+    let expires_at = Utc::now() + Duration::minutes(IMPERSONATION_TOKEN_MINUTES);
+
+    let claims = Claims {
+        sub: user.id,
+        username: user.username.clone(),
+        exp: expires_at.timestamp() as usize,
+        impersonated_by: Some(admin_id),
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(secret.as_bytes()),
+    )?;
+
+    Ok((token, expires_at))
+}
+
 pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims> {
     let token_data = decode::<Claims>(
         token,
@@ -79,7 +134,11 @@ pub fn verify_jwt(token: &str, secret: &str) -> Result<Claims> {
     Ok(token_data.claims)
 }
 
-fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
+fn is_read_only_method(method: &Method) -> bool {
+    matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+}
+
+pub fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
     let auth_header = headers.get("authorization")?;
     let auth_str = auth_header.to_str().ok()?;
     
@@ -88,4 +147,129 @@ fn extract_token_from_headers(headers: &HeaderMap) -> Option<String> {
     } else {
         None
     }
-}
\ No newline at end of file
+}
+
+/// Trusted proxies used when `TRUSTED_PROXIES` is not set: only a proxy on the same host
+pub const DEFAULT_TRUSTED_PROXIES: &str = "127.0.0.1/32,::1/128";
+
+/// A network whose peers are reverse proxies allowed to report the client address in
+/// forwarding headers. Written as a CIDR such as `10.0.0.0/8`; a bare address is a single host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrustedProxy {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl TrustedProxy {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 peers of a dual-stack listener arrive as IPv4-mapped IPv6 addresses
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => prefix_matches(u32::from(network).into(), u32::from(ip).into(), self.prefix_len, 32),
+            (IpAddr::V6(network), IpAddr::V6(ip)) => prefix_matches(u128::from(network), u128::from(ip), self.prefix_len, 128),
+            _ => false,
+        }
+    }
+
+    /// Parses a comma-separated list such as `TRUSTED_PROXIES`; an empty list trusts no proxy
+    pub fn parse_list(value: &str) -> Result<Vec<TrustedProxy>, String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+fn prefix_matches(network: u128, ip: u128, prefix_len: u8, bits: u8) -> bool {
+    let shift = u32::from(bits - prefix_len);
+    network.checked_shr(shift).unwrap_or(0) == ip.checked_shr(shift).unwrap_or(0)
+}
+
+impl std::str::FromStr for TrustedProxy {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = match value.trim().split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (value.trim(), None),
+        };
+        let network: IpAddr = address
+            .parse()
+            .map_err(|_| format!("'{}' is not an IP address", address))?;
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => match prefix_len.parse::<u8>() {
+                Ok(prefix_len) if prefix_len <= max_prefix_len => prefix_len,
+                _ => return Err(format!("Prefix length of '{}' must be between 0 and {}", value, max_prefix_len)),
+            },
+            None => max_prefix_len,
+        };
+        Ok(TrustedProxy { network, prefix_len })
+    }
+}
+
+impl std::fmt::Display for TrustedProxy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix_len)
+    }
+}
+
+/// Best-effort client IP for rate limiting. Forwarding headers are only read when the direct
+/// peer is one of the configured `TRUSTED_PROXIES`.
+pub struct ClientIp(pub String);
+
+impl FromRequestParts<Arc<AppState>> for ClientIp {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        let peer_ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+
+        Ok(ClientIp(match peer_ip {
+            Some(peer_ip) => resolve_client_ip(peer_ip, &parts.headers, &state.config.trusted_proxies).to_string(),
+            None => "unknown".to_string(),
+        }))
+    }
+}
+
+/// The address a request came from. A trusted proxy appends the address it received the
+/// request from to `X-Forwarded-For`, so the header is read from the right, skipping the
+/// trusted proxies of a chain: everything left of that is whatever the client sent.
+/// `X-Real-IP` is used when there is no `X-Forwarded-For`.
+pub fn resolve_client_ip(peer_ip: IpAddr, headers: &HeaderMap, trusted_proxies: &[TrustedProxy]) -> IpAddr {
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|proxy| proxy.contains(ip));
+    if !is_trusted(peer_ip) {
+        return peer_ip;
+    }
+
+    let forwarded_for: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .collect();
+
+    if !forwarded_for.is_empty() {
+        let mut client_ip = peer_ip;
+        for entry in forwarded_for.iter().rev() {
+            // An entry that isn't an address can't be attributed; keep the last one that was
+            let Ok(ip) = entry.parse::<IpAddr>() else { break };
+            client_ip = ip;
+            if !is_trusted(ip) {
+                break;
+            }
+        }
+        return client_ip;
+    }
+
+    headers
+        .get("x-real-ip")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(peer_ip)
+}
@@ -64,7 +64,7 @@ async fn main() -> Result<()> {
     
     let config = Config::from_env()?;
     let db = Database::new(&config.database_url).await?;
-    let file_service = FileService::new(config.upload_path.clone());
+    let file_service = FileService::new(config.upload_path.clone()).with_storage_layout(config.storage_layout);
     let queue_service = OcrQueueService::new(db.clone(), db.get_pool().clone(), 1);
     
     let ingester = BatchIngester::new(db, queue_service, file_service, config);
@@ -0,0 +1,53 @@
+/*!
+ * CLI tool to move stored document files to the layout set by STORAGE_LAYOUT
+ *
+ * The server does the same migration in the background at startup. Run this to preview
+ * the moves with --dry-run, or to migrate while the server is stopped.
+ *
+ * Usage: cargo run --bin migrate_storage_layout -- [--dry-run]
+ */
+
+use anyhow::Result;
+use clap::{Arg, Command};
+
+use readur::{
+    config::Config,
+    db::Database,
+    services::file_service::FileService,
+};
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let matches = Command::new("migrate_storage_layout")
+        .about("Move stored document files to the layout set by STORAGE_LAYOUT")
+        .arg(
+            Arg::new("dry-run")
+                .help("Log the planned moves without touching files or the database")
+                .long("dry-run")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .get_matches();
+    let dry_run = matches.get_flag("dry-run");
+
+    let config = Config::from_env()?;
+    let db = Database::new(&config.database_url).await?;
+    let file_service = FileService::new(config.upload_path.clone()).with_storage_layout(config.storage_layout);
+
+    let report = file_service.migrate_existing_files(&db, dry_run).await?;
+
+    println!("\nStorage layout: {}{}", config.storage_layout, if dry_run { " (dry run)" } else { "" });
+    println!("Files checked:              {}", report.checked);
+    println!("{:<28}{}", if dry_run { "Files to move:" } else { "Files moved:" }, report.moved);
+    println!("Already in place:           {}", report.already_in_place);
+    println!("Outside documents dir:      {}", report.outside_documents_dir);
+    println!("Missing:                    {}", report.missing);
+    println!("Failed:                     {}", report.failed);
+    println!("Legacy root files:          {}", report.legacy_files_moved);
+
+    if report.failed > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -1,12 +1,17 @@
 use anyhow::Result;
 use std::env;
 
+use crate::auth::{TrustedProxy, DEFAULT_TRUSTED_PROXIES};
+use crate::services::file_service::StorageLayout;
+
 #[derive(Clone, Debug)]
 pub struct Config {
     pub database_url: String,
     pub server_address: String,
     pub jwt_secret: String,
     pub upload_path: String,
+    // How document files are arranged under UPLOAD_PATH/documents
+    pub storage_layout: StorageLayout,
     pub watch_folder: String,
     pub user_watch_base_dir: String,
     pub enable_per_user_watch: bool,
@@ -18,6 +23,10 @@ pub struct Config {
     // OCR Configuration
     pub ocr_language: String,
     pub concurrent_ocr_jobs: usize,
+    /// Share of the background database pool OCR workers may hold (0.0-1.0]
+    pub ocr_db_pool_fraction: f64,
+    /// Most OCR jobs a worker claims per queue query
+    pub ocr_claim_batch_size: usize,
     pub ocr_timeout_seconds: u64,
     pub max_file_size_mb: u64,
     
@@ -31,6 +40,97 @@ pub struct Config {
     pub oidc_client_secret: Option<String>,
     pub oidc_issuer_url: Option<String>,
     pub oidc_redirect_uri: Option<String>,
+    
+    // Email (SMTP) Configuration
+    pub smtp_host: Option<String>,
+    pub smtp_port: u16,
+    pub smtp_username: Option<String>,
+    pub smtp_password: Option<String>,
+    pub smtp_from: String,
+    pub smtp_starttls: bool,
+    pub public_base_url: Option<String>,
+    
+    // Login Rate Limiting
+    pub login_max_failed_attempts: u32,
+    pub login_attempt_window_seconds: u64,
+    pub login_lockout_seconds: u64,
+    // Reverse proxies whose X-Forwarded-For and X-Real-IP headers are believed
+    pub trusted_proxies: Vec<TrustedProxy>,
+    
+    // Trash
+    pub trash_retention_days: u64,
+
+    // Versioning
+    pub document_version_retention: usize,
+
+    // WebDAV ETag tracking validation; 0 disables the scheduled runs
+    pub webdav_validation_interval_hours: u64,
+
+    // Metadata extraction
+    pub strip_gps_metadata: bool,
+
+    // Admin impersonation: reject requests that change data
+    pub impersonation_read_only: bool,
+
+    // Maintenance: backfill missing file hashes and word counts once at startup
+    pub backfill_on_startup: bool,
+
+    // Document history: days of processing events kept; 0 keeps them forever
+    pub document_event_retention_days: u64,
+
+    // Source sync: concurrent file operations shared fairly by all running syncs
+    pub source_sync_max_concurrency: usize,
+
+    // Source downloads: transfers running at once across all sources
+    pub source_download_max_concurrency: usize,
+
+    // Uploads to Nextcloud and ownCloud larger than this are sent in chunks of this size
+    pub webdav_upload_chunk_size_mb: u64,
+
+    // Worker threads of the dedicated OCR, background and database runtimes
+    pub ocr_worker_threads: usize,
+    pub background_worker_threads: usize,
+    pub db_worker_threads: usize,
+
+    // Thumbnails, first-page renders and searchable PDFs kept on disk; 0 disables eviction
+    pub derived_asset_cache_max_mb: u64,
+}
+
+pub const DEFAULT_OCR_WORKER_THREADS: usize = 3;
+pub const DEFAULT_BACKGROUND_WORKER_THREADS: usize = 2;
+pub const DEFAULT_DB_WORKER_THREADS: usize = 2;
+
+/// Reads the worker thread count of a dedicated runtime from `name`. The count is at least 1
+/// and at most the available parallelism, which also caps the default on small machines.
+fn worker_threads_from_env(name: &str, default_value: usize) -> usize {
+    let available = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1);
+    let requested = match env::var(name) {
+        Ok(val) => match val.parse::<usize>() {
+            Ok(parsed) if parsed > 0 => {
+                println!("✅ {}: {} (loaded from env)", name, parsed);
+                parsed
+            }
+            Ok(_) => {
+                println!("❌ {}: must be at least 1, using default {}", name, default_value);
+                default_value
+            }
+            Err(e) => {
+                println!("❌ {}: Invalid value '{}' - {}, using default {}", name, val, e, default_value);
+                default_value
+            }
+        },
+        Err(_) => {
+            println!("⚠️  {}: {} (using default - env var not set)", name, default_value);
+            default_value
+        }
+    };
+
+    if requested > available {
+        println!("⚠️  {}: capped at {} (available parallelism)", name, available);
+        available
+    } else {
+        requested
+    }
 }
 
 impl Config {
@@ -153,6 +253,22 @@ impl Config {
                     default_path
                 }
             },
+            storage_layout: match env::var("STORAGE_LAYOUT") {
+                Ok(val) => match val.parse::<StorageLayout>() {
+                    Ok(layout) => {
+                        println!("✅ STORAGE_LAYOUT: {} (loaded from env)", layout);
+                        layout
+                    }
+                    Err(e) => {
+                        println!("❌ STORAGE_LAYOUT: Invalid value '{}' - {}, using default {}", val, e, StorageLayout::default());
+                        StorageLayout::default()
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  STORAGE_LAYOUT: {} (using default - env var not set)", StorageLayout::default());
+                    StorageLayout::default()
+                }
+            },
             watch_folder: match env::var("WATCH_FOLDER") {
                 Ok(folder) => {
                     println!("✅ WATCH_FOLDER: {} (loaded from env)", folder);
@@ -300,6 +416,52 @@ impl Config {
                     }
                 }
             },
+            ocr_db_pool_fraction: {
+                let default_fraction = crate::ocr::queue::DEFAULT_OCR_DB_POOL_FRACTION;
+                match env::var("OCR_DB_POOL_FRACTION") {
+                    Ok(val) => match val.parse::<f64>() {
+                        Ok(parsed) if parsed > 0.0 && parsed <= 1.0 => {
+                            println!("✅ OCR_DB_POOL_FRACTION: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Ok(parsed) => {
+                            println!("❌ OCR_DB_POOL_FRACTION: {} is outside (0, 1], using default {}", parsed, default_fraction);
+                            default_fraction
+                        }
+                        Err(e) => {
+                            println!("❌ OCR_DB_POOL_FRACTION: Invalid value '{}' - {}, using default {}", val, e, default_fraction);
+                            default_fraction
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  OCR_DB_POOL_FRACTION: {} (using default - env var not set)", default_fraction);
+                        default_fraction
+                    }
+                }
+            },
+            ocr_claim_batch_size: {
+                let default_batch_size = crate::ocr::queue::DEFAULT_OCR_CLAIM_BATCH_SIZE;
+                match env::var("OCR_CLAIM_BATCH_SIZE") {
+                    Ok(val) => match val.parse::<usize>() {
+                        Ok(parsed) if parsed > 0 => {
+                            println!("✅ OCR_CLAIM_BATCH_SIZE: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Ok(_) => {
+                            println!("❌ OCR_CLAIM_BATCH_SIZE: must be at least 1, using default {}", default_batch_size);
+                            default_batch_size
+                        }
+                        Err(e) => {
+                            println!("❌ OCR_CLAIM_BATCH_SIZE: Invalid value '{}' - {}, using default {}", val, e, default_batch_size);
+                            default_batch_size
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  OCR_CLAIM_BATCH_SIZE: {} (using default - env var not set)", default_batch_size);
+                        default_batch_size
+                    }
+                }
+            },
             ocr_timeout_seconds: {
                 match env::var("OCR_TIMEOUT_SECONDS") {
                     Ok(val) => match val.parse::<u64>() {
@@ -431,6 +593,396 @@ impl Config {
                     None
                 }
             },
+            
+            // Email (SMTP) Configuration
+            smtp_host: match env::var("SMTP_HOST") {
+                Ok(host) => {
+                    println!("✅ SMTP_HOST: {} (loaded from env)", host);
+                    Some(host)
+                }
+                Err(_) => {
+                    println!("⚠️  SMTP_HOST: Not set, outgoing email is disabled");
+                    None
+                }
+            },
+            smtp_port: {
+                match env::var("SMTP_PORT") {
+                    Ok(val) => match val.parse::<u16>() {
+                        Ok(parsed) => {
+                            println!("✅ SMTP_PORT: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_port = 587;
+                            println!("❌ SMTP_PORT: Invalid value '{}' - {}, using default {}", val, e, default_port);
+                            default_port
+                        }
+                    },
+                    Err(_) => {
+                        let default_port = 587;
+                        println!("⚠️  SMTP_PORT: {} (using default - env var not set)", default_port);
+                        default_port
+                    }
+                }
+            },
+            smtp_username: match env::var("SMTP_USERNAME") {
+                Ok(username) => {
+                    println!("✅ SMTP_USERNAME: {} (loaded from env)", username);
+                    Some(username)
+                }
+                Err(_) => {
+                    println!("⚠️  SMTP_USERNAME: Not set");
+                    None
+                }
+            },
+            smtp_password: match env::var("SMTP_PASSWORD") {
+                Ok(password) => {
+                    println!("✅ SMTP_PASSWORD: ***hidden*** (loaded from env, {} chars)", password.len());
+                    Some(password)
+                }
+                Err(_) => {
+                    println!("⚠️  SMTP_PASSWORD: Not set");
+                    None
+                }
+            },
+            smtp_from: match env::var("SMTP_FROM") {
+                Ok(from) => {
+                    println!("✅ SMTP_FROM: {} (loaded from env)", from);
+                    from
+                }
+                Err(_) => {
+                    let default_from = "Readur <noreply@readur.local>".to_string();
+                    println!("⚠️  SMTP_FROM: {} (using default - env var not set)", default_from);
+                    default_from
+                }
+            },
+            smtp_starttls: match env::var("SMTP_STARTTLS") {
+                Ok(val) => match val.to_lowercase().as_str() {
+                    "false" | "0" | "no" | "off" => {
+                        println!("✅ SMTP_STARTTLS: false (loaded from env)");
+                        false
+                    }
+                    _ => {
+                        println!("✅ SMTP_STARTTLS: true (loaded from env)");
+                        true
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  SMTP_STARTTLS: true (using default - env var not set)");
+                    true
+                }
+            },
+            public_base_url: match env::var("PUBLIC_BASE_URL") {
+                Ok(url) => {
+                    println!("✅ PUBLIC_BASE_URL: {} (loaded from env)", url);
+                    Some(url.trim_end_matches('/').to_string())
+                }
+                Err(_) => {
+                    println!("⚠️  PUBLIC_BASE_URL: Not set");
+                    None
+                }
+            },
+            
+            // Login Rate Limiting
+            login_max_failed_attempts: {
+                match env::var("LOGIN_MAX_FAILED_ATTEMPTS") {
+                    Ok(val) => match val.parse::<u32>() {
+                        Ok(parsed) => {
+                            println!("✅ LOGIN_MAX_FAILED_ATTEMPTS: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 5;
+                            println!("❌ LOGIN_MAX_FAILED_ATTEMPTS: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 5;
+                        println!("⚠️  LOGIN_MAX_FAILED_ATTEMPTS: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            login_attempt_window_seconds: {
+                match env::var("LOGIN_ATTEMPT_WINDOW_SECONDS") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) => {
+                            println!("✅ LOGIN_ATTEMPT_WINDOW_SECONDS: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 900;
+                            println!("❌ LOGIN_ATTEMPT_WINDOW_SECONDS: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 900;
+                        println!("⚠️  LOGIN_ATTEMPT_WINDOW_SECONDS: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            login_lockout_seconds: {
+                match env::var("LOGIN_LOCKOUT_SECONDS") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) => {
+                            println!("✅ LOGIN_LOCKOUT_SECONDS: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 900;
+                            println!("❌ LOGIN_LOCKOUT_SECONDS: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 900;
+                        println!("⚠️  LOGIN_LOCKOUT_SECONDS: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            trusted_proxies: {
+                let default_proxies = TrustedProxy::parse_list(DEFAULT_TRUSTED_PROXIES).expect("valid default trusted proxies");
+                match env::var("TRUSTED_PROXIES") {
+                    Ok(val) => match TrustedProxy::parse_list(&val) {
+                        Ok(parsed) => {
+                            println!("✅ TRUSTED_PROXIES: {:?} (loaded from env)", parsed.iter().map(ToString::to_string).collect::<Vec<_>>());
+                            parsed
+                        }
+                        Err(e) => {
+                            println!("❌ TRUSTED_PROXIES: Invalid value '{}' - {}, using default {}", val, e, DEFAULT_TRUSTED_PROXIES);
+                            default_proxies
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  TRUSTED_PROXIES: {} (using default - env var not set)", DEFAULT_TRUSTED_PROXIES);
+                        default_proxies
+                    }
+                }
+            },
+            trash_retention_days: {
+                match env::var("TRASH_RETENTION_DAYS") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) => {
+                            println!("✅ TRASH_RETENTION_DAYS: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 30;
+                            println!("❌ TRASH_RETENTION_DAYS: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 30;
+                        println!("⚠️  TRASH_RETENTION_DAYS: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            document_version_retention: {
+                match env::var("DOCUMENT_VERSION_RETENTION") {
+                    Ok(val) => match val.parse::<usize>() {
+                        Ok(parsed) => {
+                            println!("✅ DOCUMENT_VERSION_RETENTION: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 10;
+                            println!("❌ DOCUMENT_VERSION_RETENTION: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 10;
+                        println!("⚠️  DOCUMENT_VERSION_RETENTION: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            webdav_validation_interval_hours: {
+                match env::var("WEBDAV_VALIDATION_INTERVAL_HOURS") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) => {
+                            println!("✅ WEBDAV_VALIDATION_INTERVAL_HOURS: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 24;
+                            println!("❌ WEBDAV_VALIDATION_INTERVAL_HOURS: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 24;
+                        println!("⚠️  WEBDAV_VALIDATION_INTERVAL_HOURS: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            strip_gps_metadata: match env::var("STRIP_GPS_METADATA") {
+                Ok(val) => match val.to_lowercase().as_str() {
+                    "true" | "1" | "yes" | "on" => {
+                        println!("✅ STRIP_GPS_METADATA: true (loaded from env)");
+                        true
+                    }
+                    _ => {
+                        println!("✅ STRIP_GPS_METADATA: false (loaded from env)");
+                        false
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  STRIP_GPS_METADATA: false (using default - env var not set)");
+                    false
+                }
+            },
+            impersonation_read_only: match env::var("IMPERSONATION_READ_ONLY") {
+                Ok(val) => match val.to_lowercase().as_str() {
+                    "false" | "0" | "no" | "off" => {
+                        println!("✅ IMPERSONATION_READ_ONLY: false (loaded from env)");
+                        false
+                    }
+                    _ => {
+                        println!("✅ IMPERSONATION_READ_ONLY: true (loaded from env)");
+                        true
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  IMPERSONATION_READ_ONLY: true (using default - env var not set)");
+                    true
+                }
+            },
+            backfill_on_startup: match env::var("BACKFILL_ON_STARTUP") {
+                Ok(val) => match val.to_lowercase().as_str() {
+                    "true" | "1" | "yes" | "on" => {
+                        println!("✅ BACKFILL_ON_STARTUP: true (loaded from env)");
+                        true
+                    }
+                    _ => {
+                        println!("✅ BACKFILL_ON_STARTUP: false (loaded from env)");
+                        false
+                    }
+                },
+                Err(_) => {
+                    println!("⚠️  BACKFILL_ON_STARTUP: false (using default - env var not set)");
+                    false
+                }
+            },
+            document_event_retention_days: {
+                match env::var("DOCUMENT_EVENT_RETENTION_DAYS") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) => {
+                            println!("✅ DOCUMENT_EVENT_RETENTION_DAYS: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 90;
+                            println!("❌ DOCUMENT_EVENT_RETENTION_DAYS: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 90;
+                        println!("⚠️  DOCUMENT_EVENT_RETENTION_DAYS: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            source_sync_max_concurrency: {
+                let default_value = crate::scheduling::sync_budget::DEFAULT_SYNC_MAX_CONCURRENCY;
+                match env::var("SOURCE_SYNC_MAX_CONCURRENCY") {
+                    Ok(val) => match val.parse::<usize>() {
+                        Ok(parsed) if parsed > 0 => {
+                            println!("✅ SOURCE_SYNC_MAX_CONCURRENCY: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Ok(_) => {
+                            println!("❌ SOURCE_SYNC_MAX_CONCURRENCY: must be at least 1, using default {}", default_value);
+                            default_value
+                        }
+                        Err(e) => {
+                            println!("❌ SOURCE_SYNC_MAX_CONCURRENCY: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  SOURCE_SYNC_MAX_CONCURRENCY: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            source_download_max_concurrency: {
+                let default_value = crate::scheduling::download_limiter::DEFAULT_DOWNLOAD_MAX_CONCURRENCY;
+                match env::var("SOURCE_DOWNLOAD_MAX_CONCURRENCY") {
+                    Ok(val) => match val.parse::<usize>() {
+                        Ok(parsed) if parsed > 0 => {
+                            println!("✅ SOURCE_DOWNLOAD_MAX_CONCURRENCY: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Ok(_) => {
+                            println!("❌ SOURCE_DOWNLOAD_MAX_CONCURRENCY: must be at least 1, using default {}", default_value);
+                            default_value
+                        }
+                        Err(e) => {
+                            println!("❌ SOURCE_DOWNLOAD_MAX_CONCURRENCY: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  SOURCE_DOWNLOAD_MAX_CONCURRENCY: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            webdav_upload_chunk_size_mb: {
+                let default_value = crate::services::webdav::DEFAULT_UPLOAD_CHUNK_SIZE_MB;
+                match env::var("WEBDAV_UPLOAD_CHUNK_SIZE_MB") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) if parsed > 0 => {
+                            println!("✅ WEBDAV_UPLOAD_CHUNK_SIZE_MB: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Ok(_) => {
+                            println!("❌ WEBDAV_UPLOAD_CHUNK_SIZE_MB: must be at least 1, using default {}", default_value);
+                            default_value
+                        }
+                        Err(e) => {
+                            println!("❌ WEBDAV_UPLOAD_CHUNK_SIZE_MB: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        println!("⚠️  WEBDAV_UPLOAD_CHUNK_SIZE_MB: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
+            ocr_worker_threads: worker_threads_from_env("OCR_WORKER_THREADS", DEFAULT_OCR_WORKER_THREADS),
+            background_worker_threads: worker_threads_from_env("BACKGROUND_WORKER_THREADS", DEFAULT_BACKGROUND_WORKER_THREADS),
+            db_worker_threads: worker_threads_from_env("DB_WORKER_THREADS", DEFAULT_DB_WORKER_THREADS),
+            derived_asset_cache_max_mb: {
+                match env::var("DERIVED_ASSET_CACHE_MAX_MB") {
+                    Ok(val) => match val.parse::<u64>() {
+                        Ok(parsed) => {
+                            println!("✅ DERIVED_ASSET_CACHE_MAX_MB: {} (loaded from env)", parsed);
+                            parsed
+                        }
+                        Err(e) => {
+                            let default_value = 2048;
+                            println!("❌ DERIVED_ASSET_CACHE_MAX_MB: Invalid value '{}' - {}, using default {}", val, e, default_value);
+                            default_value
+                        }
+                    },
+                    Err(_) => {
+                        let default_value = 2048;
+                        println!("⚠️  DERIVED_ASSET_CACHE_MAX_MB: {} (using default - env var not set)", default_value);
+                        default_value
+                    }
+                }
+            },
         };
         
         println!("\n🔍 CONFIGURATION VALIDATION:");
@@ -461,6 +1013,7 @@ impl Config {
         println!("{}", "=".repeat(50));
         println!("🌐 Server will bind to: {}", config.server_address);
         println!("📁 Upload directory: {}", config.upload_path);
+        println!("🗂️  Storage layout: {}", config.storage_layout);
         println!("👁️  Watch directory: {}", config.watch_folder);
         println!("👥 Per-user watch enabled: {}", config.enable_per_user_watch);
         if config.enable_per_user_watch {
@@ -469,6 +1022,12 @@ impl Config {
         println!("📄 Allowed file types: {:?}", config.allowed_file_types);
         println!("🧠 OCR language: {}", config.ocr_language);
         println!("⚙️  Concurrent OCR jobs: {}", config.concurrent_ocr_jobs);
+        println!("⚙️  OCR database pool share: {:.0}%", config.ocr_db_pool_fraction * 100.0);
+        println!("⚙️  OCR jobs claimed per query: {}", config.ocr_claim_batch_size);
+        println!(
+            "🧵 Runtime worker threads: OCR {}, background {}, database {}",
+            config.ocr_worker_threads, config.background_worker_threads, config.db_worker_threads
+        );
         println!("⏱️  OCR timeout: {}s", config.ocr_timeout_seconds);
         println!("📏 Max file size: {}MB", config.max_file_size_mb);
         println!("💾 Memory limit: {}MB", config.memory_limit_mb);
@@ -513,6 +1072,32 @@ impl Config {
         Ok(config)
     }
     
+    /// The database URL with the password replaced and the username reduced to its
+    /// first and last character, for logging
+    pub fn masked_database_url(&self) -> String {
+        let Some(at_pos) = self.database_url.find('@') else {
+            return "Invalid database URL format".to_string();
+        };
+        let host_part = &self.database_url[at_pos + 1..];
+        let protocol = if self.database_url.starts_with("postgresql://") { "postgresql" } else { "postgres" };
+
+        // Extract just username from credentials part (before @)
+        let creds_part = &self.database_url[..at_pos];
+        let username = match creds_part.find("://") {
+            Some(proto_end) => {
+                let after_proto = &creds_part[proto_end + 3..];
+                after_proto.split(':').next().unwrap_or(after_proto)
+            }
+            None => "unknown",
+        };
+        let masked_username = match (username.chars().next(), username.chars().last()) {
+            (Some(first), Some(last)) => format!("{}{}", first, last),
+            _ => String::new(),
+        };
+
+        format!("{}://{}:***@{}", protocol, masked_username, host_part)
+    }
+
     fn validate_paths(&self) -> Result<()> {
         use std::path::Path;
         
@@ -0,0 +1,76 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::BatchJob;
+
+impl Database {
+    pub async fn get_batch_job(&self, batch_job_id: Uuid) -> Result<Option<BatchJob>> {
+        let job = sqlx::query_as::<_, BatchJob>("SELECT * FROM batch_jobs WHERE id = $1")
+            .bind(batch_job_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(job)
+    }
+
+    /// Running batch jobs, oldest first
+    pub async fn get_running_batch_job_ids(&self) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar("SELECT id FROM batch_jobs WHERE status = 'running' ORDER BY created_at")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(ids)
+    }
+
+    /// Counts a queued document of a batch job as done or failed. Documents that were not
+    /// waiting on the queue are left alone, so reporting the same result twice counts it once.
+    pub async fn record_batch_job_result(&self, batch_job_id: Uuid, document_id: Uuid, succeeded: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            WITH document AS (
+                UPDATE batch_job_documents
+                SET state = CASE WHEN $3 THEN 'completed' ELSE 'failed' END,
+                    updated_at = NOW()
+                WHERE batch_job_id = $1 AND document_id = $2 AND state = 'queued'
+                RETURNING batch_job_id
+            )
+            UPDATE batch_jobs j
+            SET done = done + CASE WHEN $3 THEN 1 ELSE 0 END,
+                failed = failed + CASE WHEN $3 THEN 0 ELSE 1 END,
+                updated_at = NOW()
+            FROM document
+            WHERE j.id = document.batch_job_id
+            "#
+        )
+        .bind(batch_job_id)
+        .bind(document_id)
+        .bind(succeeded)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks a running batch job as completed once none of its documents is waiting or
+    /// queued. Returns whether the job was completed by this call.
+    pub async fn complete_batch_job_if_done(&self, batch_job_id: Uuid) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE batch_jobs
+            SET status = 'completed', finished_at = NOW(), updated_at = NOW()
+            WHERE id = $1
+              AND status = 'running'
+              AND NOT EXISTS (
+                  SELECT 1 FROM batch_job_documents
+                  WHERE batch_job_id = $1 AND state IN ('waiting', 'queued')
+              )
+            "#
+        )
+        .bind(batch_job_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
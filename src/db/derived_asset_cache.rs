@@ -0,0 +1,89 @@
+use anyhow::Result;
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::Database;
+use crate::services::derived_asset_cache::DerivedAssetKind;
+
+/// A derived file tracked by the on-disk cache
+#[derive(Debug, Clone)]
+pub struct DerivedAssetEntry {
+    pub kind: String,
+    pub document_id: Uuid,
+    pub file_path: String,
+    pub size_bytes: i64,
+}
+
+impl Database {
+    /// Records that a derived file was served or written, creating its entry if needed
+    pub async fn touch_derived_asset(
+        &self,
+        kind: DerivedAssetKind,
+        document_id: Uuid,
+        file_path: &str,
+        size_bytes: i64,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO derived_asset_cache (kind, document_id, file_path, size_bytes)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (kind, document_id) DO UPDATE
+            SET file_path = EXCLUDED.file_path, size_bytes = EXCLUDED.size_bytes, last_accessed = NOW()
+            "#
+        )
+        .bind(kind.as_str())
+        .bind(document_id)
+        .bind(file_path)
+        .bind(size_bytes)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Number of tracked entries and their total size in bytes
+    pub async fn get_derived_asset_cache_usage(&self) -> Result<(i64, i64)> {
+        let row = sqlx::query(
+            "SELECT COUNT(*) AS entries, COALESCE(SUM(size_bytes), 0)::BIGINT AS total_bytes FROM derived_asset_cache"
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((row.get("entries"), row.get("total_bytes")))
+    }
+
+    /// Entries ordered from least to most recently accessed
+    pub async fn get_least_recently_used_derived_assets(&self, limit: i64) -> Result<Vec<DerivedAssetEntry>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT kind, document_id, file_path, size_bytes
+            FROM derived_asset_cache
+            ORDER BY last_accessed ASC
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| DerivedAssetEntry {
+                kind: row.get("kind"),
+                document_id: row.get("document_id"),
+                file_path: row.get("file_path"),
+                size_bytes: row.get("size_bytes"),
+            })
+            .collect())
+    }
+
+    pub async fn delete_derived_asset(&self, kind: &str, document_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM derived_asset_cache WHERE kind = $1 AND document_id = $2")
+            .bind(kind)
+            .bind(document_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
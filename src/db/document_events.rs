@@ -0,0 +1,55 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::{DocumentEvent, DocumentEventType};
+
+impl Database {
+    /// Appends an event to a document's processing history
+    pub async fn record_document_event(
+        &self,
+        document_id: Uuid,
+        event_type: DocumentEventType,
+        detail: Option<&str>,
+        worker_id: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO document_events (document_id, event_type, detail, worker_id) VALUES ($1, $2, $3, $4)"
+        )
+        .bind(document_id)
+        .bind(event_type.to_string())
+        .bind(detail)
+        .bind(worker_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// A document's processing history, oldest first
+    pub async fn get_document_events(&self, document_id: Uuid) -> Result<Vec<DocumentEvent>> {
+        let events = sqlx::query_as::<_, DocumentEvent>(
+            r#"
+            SELECT id, document_id, event_type, detail, worker_id, created_at
+            FROM document_events
+            WHERE document_id = $1
+            ORDER BY created_at, id
+            "#
+        )
+        .bind(document_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Deletes events older than `days`. Returns how many were deleted.
+    pub async fn prune_document_events(&self, days: u64) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM document_events WHERE created_at < NOW() - INTERVAL '1 day' * $1")
+            .bind(days as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,123 @@
+use anyhow::Result;
+use sqlx::{postgres::PgRow, types::Json, FromRow, Row};
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::{DocumentRun, DocumentRunDetails, DocumentRunOutcome};
+
+const DOCUMENT_RUN_FIELDS: &str = "id, started_by, started_at, finished_at, details, outcome, error_message";
+
+impl<'r, D: DocumentRunDetails> FromRow<'r, PgRow> for DocumentRun<D> {
+    fn from_row(row: &'r PgRow) -> sqlx::Result<Self> {
+        let outcome: String = row.try_get("outcome")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            started_by: row.try_get("started_by")?,
+            started_at: row.try_get("started_at")?,
+            finished_at: row.try_get("finished_at")?,
+            details: row.try_get::<Json<D>, _>("details")?.0,
+            outcome: DocumentRunOutcome::try_from(outcome).map_err(|e| sqlx::Error::ColumnDecode {
+                index: "outcome".to_string(),
+                source: e.into(),
+            })?,
+            error_message: row.try_get("error_message")?,
+        })
+    }
+}
+
+/// Runs are read and written by kind: every query is limited to the kind of `D`, so a run
+/// id of one kind is not found as another.
+impl Database {
+    /// Fails with a unique violation if a run of the same kind is still running
+    pub async fn create_document_run<D: DocumentRunDetails>(&self, started_by: Option<Uuid>, details: &D) -> Result<DocumentRun<D>> {
+        let query_str = format!(
+            "INSERT INTO document_runs (kind, started_by, details) VALUES ($1, $2, $3) RETURNING {}",
+            DOCUMENT_RUN_FIELDS
+        );
+
+        let run = sqlx::query_as::<_, DocumentRun<D>>(&query_str)
+            .bind(D::KIND)
+            .bind(started_by)
+            .bind(Json(details))
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(run)
+    }
+
+    pub async fn get_document_run<D: DocumentRunDetails>(&self, run_id: Uuid) -> Result<Option<DocumentRun<D>>> {
+        let query_str = format!("SELECT {} FROM document_runs WHERE id = $1 AND kind = $2", DOCUMENT_RUN_FIELDS);
+        let run = sqlx::query_as::<_, DocumentRun<D>>(&query_str)
+            .bind(run_id)
+            .bind(D::KIND)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(run)
+    }
+
+    pub async fn get_running_document_run<D: DocumentRunDetails>(&self) -> Result<Option<DocumentRun<D>>> {
+        let query_str = format!("SELECT {} FROM document_runs WHERE kind = $1 AND outcome = 'running'", DOCUMENT_RUN_FIELDS);
+        let run = sqlx::query_as::<_, DocumentRun<D>>(&query_str)
+            .bind(D::KIND)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(run)
+    }
+
+    /// Stores the details of a run that is still in progress
+    pub async fn update_document_run<D: DocumentRunDetails>(&self, run_id: Uuid, details: &D) -> Result<()> {
+        sqlx::query("UPDATE document_runs SET details = $3 WHERE id = $1 AND kind = $2 AND outcome = 'running'")
+            .bind(run_id)
+            .bind(D::KIND)
+            .bind(Json(details))
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Closes out a run. Runs that were already finished are left untouched.
+    pub async fn finish_document_run<D: DocumentRunDetails>(
+        &self,
+        run_id: Uuid,
+        details: &D,
+        outcome: DocumentRunOutcome,
+        error_message: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE document_runs
+            SET finished_at = NOW(), details = $3, outcome = $4, error_message = $5
+            WHERE id = $1 AND kind = $2 AND outcome = 'running'
+            "#
+        )
+        .bind(run_id)
+        .bind(D::KIND)
+        .bind(Json(details))
+        .bind(outcome.to_string())
+        .bind(error_message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks runs that never finished, e.g. because the server restarted, as failed
+    pub async fn fail_unfinished_document_runs<D: DocumentRunDetails>(&self, error_message: &str) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE document_runs
+            SET finished_at = NOW(), outcome = 'failed', error_message = $2
+            WHERE kind = $1 AND outcome = 'running'
+            "#
+        )
+        .bind(D::KIND)
+        .bind(error_message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// Documents that still lack a derived field: a file hash, or a word count for existing OCR text
+const NEEDS_BACKFILL: &str =
+    "deleted_at IS NULL AND (file_hash IS NULL OR (ocr_word_count IS NULL AND ocr_text IS NOT NULL))";
+
+/// A document with at least one derived field to backfill
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DocumentBackfillRecord {
+    pub id: Uuid,
+    pub file_path: String,
+    pub file_size: i64,
+    pub file_hash: Option<String>,
+    pub ocr_text: Option<String>,
+    pub ocr_word_count: Option<i32>,
+}
+
+impl Database {
+    /// Counts the live documents that need a backfill
+    pub async fn count_document_backfill_records(&self) -> Result<i64> {
+        let query_str = format!("SELECT COUNT(*) FROM documents WHERE {}", NEEDS_BACKFILL);
+        let count = sqlx::query_scalar::<_, i64>(&query_str)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Returns up to `limit` live documents that need a backfill, ordered by id, starting after `after_id`
+    pub async fn get_document_backfill_records(&self, after_id: Option<Uuid>, limit: i64) -> Result<Vec<DocumentBackfillRecord>> {
+        let query_str = format!(
+            r#"
+            SELECT id, file_path, file_size, file_hash, ocr_text, ocr_word_count
+            FROM documents
+            WHERE {} AND ($1::uuid IS NULL OR id > $1)
+            ORDER BY id
+            LIMIT $2
+            "#,
+            NEEDS_BACKFILL
+        );
+
+        let records = sqlx::query_as::<_, DocumentBackfillRecord>(&query_str)
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    /// Stores the hash of a document that has none. Returns false if it got one in the
+    /// meantime. Fails with a unique violation if another of the owner's documents has
+    /// the same content.
+    pub async fn fill_document_file_hash(&self, document_id: Uuid, file_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE documents SET file_hash = $2, updated_at = NOW() WHERE id = $1 AND file_hash IS NULL"
+        )
+        .bind(document_id)
+        .bind(file_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stores the word count of a document's existing OCR text if it has none.
+    /// Returns false if it got one in the meantime.
+    pub async fn fill_document_word_count(&self, document_id: Uuid, word_count: i32) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE documents SET ocr_word_count = $2 WHERE id = $1 AND ocr_word_count IS NULL"
+        )
+        .bind(document_id)
+        .bind(word_count)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -1,11 +1,16 @@
 use anyhow::Result;
-use sqlx::{QueryBuilder, Postgres};
+use sqlx::{QueryBuilder, Postgres, Row};
+use std::collections::HashSet;
 use uuid::Uuid;
 
 use crate::models::{Document, UserRole};
 use super::helpers::{map_row_to_document, apply_role_based_filter, apply_pagination, DOCUMENT_FIELDS};
 use crate::db::Database;
 
+/// Rows per multi-row INSERT. Each row binds 30 parameters, so this stays well below
+/// Postgres' 65535 bind parameter limit.
+const BULK_INSERT_CHUNK_SIZE: usize = 500;
+
 impl Database {
     /// Creates a new document in the database
     pub async fn create_document(&self, document: Document) -> Result<Document> {
@@ -55,12 +60,68 @@ impl Database {
         Ok(map_row_to_document(&row))
     }
 
+    /// Inserts many documents using one multi-row INSERT per chunk.
+    ///
+    /// Rows that collide with an existing `(user_id, file_hash)` are skipped rather than
+    /// failing the batch. Returns the ids of the documents that were actually inserted.
+    pub async fn bulk_create_documents(&self, documents: &[Document]) -> Result<HashSet<Uuid>> {
+        let mut inserted = HashSet::with_capacity(documents.len());
+
+        for chunk in documents.chunks(BULK_INSERT_CHUNK_SIZE) {
+            let mut query = QueryBuilder::<Postgres>::new(
+                "INSERT INTO documents (id, filename, original_filename, file_path, file_size, mime_type, content, ocr_text, ocr_confidence, ocr_word_count, ocr_processing_time_ms, ocr_status, ocr_error, ocr_completed_at, ocr_retry_count, ocr_failure_reason, tags, created_at, updated_at, user_id, file_hash, original_created_at, original_modified_at, source_path, source_type, source_id, file_permissions, file_owner, file_group, source_metadata) "
+            );
+
+            query.push_values(chunk, |mut row, document| {
+                row.push_bind(document.id)
+                    .push_bind(&document.filename)
+                    .push_bind(&document.original_filename)
+                    .push_bind(&document.file_path)
+                    .push_bind(document.file_size)
+                    .push_bind(&document.mime_type)
+                    .push_bind(&document.content)
+                    .push_bind(&document.ocr_text)
+                    .push_bind(document.ocr_confidence)
+                    .push_bind(document.ocr_word_count)
+                    .push_bind(document.ocr_processing_time_ms)
+                    .push_bind(&document.ocr_status)
+                    .push_bind(&document.ocr_error)
+                    .push_bind(document.ocr_completed_at)
+                    .push_bind(document.ocr_retry_count)
+                    .push_bind(&document.ocr_failure_reason)
+                    .push_bind(&document.tags)
+                    .push_bind(document.created_at)
+                    .push_bind(document.updated_at)
+                    .push_bind(document.user_id)
+                    .push_bind(&document.file_hash)
+                    .push_bind(document.original_created_at)
+                    .push_bind(document.original_modified_at)
+                    .push_bind(&document.source_path)
+                    .push_bind(&document.source_type)
+                    .push_bind(document.source_id)
+                    .push_bind(document.file_permissions)
+                    .push_bind(&document.file_owner)
+                    .push_bind(&document.file_group)
+                    .push_bind(&document.source_metadata);
+            });
+
+            // Matches the partial unique index idx_documents_user_file_hash
+            query.push(" ON CONFLICT (user_id, file_hash) WHERE file_hash IS NOT NULL AND deleted_at IS NULL DO NOTHING RETURNING id");
+
+            let rows = query.build().fetch_all(&self.pool).await?;
+            inserted.extend(rows.iter().map(|row| row.get::<Uuid, _>("id")));
+        }
+
+        Ok(inserted)
+    }
+
     /// Retrieves a document by ID with role-based access control
     pub async fn get_document_by_id(&self, document_id: Uuid, user_id: Uuid, user_role: UserRole) -> Result<Option<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
         query.push(" FROM documents WHERE id = ");
         query.push_bind(document_id);
+        query.push(" AND deleted_at IS NULL");
         
         apply_role_based_filter(&mut query, user_id, user_role);
 
@@ -78,7 +139,7 @@ impl Database {
             r#"
             SELECT {}
             FROM documents 
-            WHERE user_id = $1 
+            WHERE user_id = $1 AND deleted_at IS NULL
             ORDER BY created_at DESC 
             LIMIT $2 OFFSET $3
             "#,
@@ -99,7 +160,7 @@ impl Database {
     pub async fn get_documents_by_user_with_role(&self, user_id: Uuid, user_role: UserRole, limit: i64, offset: i64) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE 1=1");
+        query.push(" FROM documents WHERE deleted_at IS NULL");
         
         apply_role_based_filter(&mut query, user_id, user_role);
         query.push(" ORDER BY created_at DESC");
@@ -119,7 +180,7 @@ impl Database {
             r#"
             SELECT {}
             FROM documents 
-            WHERE user_id = $1 AND file_hash = $2
+            WHERE user_id = $1 AND file_hash = $2 AND deleted_at IS NULL
             "#,
             DOCUMENT_FIELDS
         );
@@ -139,7 +200,7 @@ impl Database {
             r#"
             SELECT {}
             FROM documents 
-            WHERE user_id = $1 AND (filename ILIKE $2 OR original_filename ILIKE $2)
+            WHERE user_id = $1 AND (filename ILIKE $2 OR original_filename ILIKE $2) AND deleted_at IS NULL
             ORDER BY created_at DESC
             LIMIT $3 OFFSET $4
             "#,
@@ -158,6 +219,20 @@ impl Database {
         Ok(rows.iter().map(map_row_to_document).collect())
     }
 
+    /// Whether the user has a document with exactly this filename. Trashed documents count,
+    /// so restoring one never produces a duplicate name.
+    pub async fn document_filename_exists(&self, user_id: Uuid, filename: &str) -> Result<bool> {
+        let exists = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM documents WHERE user_id = $1 AND filename = $2)"
+        )
+        .bind(user_id)
+        .bind(filename)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
     /// Updates the OCR text for a document
     pub async fn update_document_ocr(&self, document_id: Uuid, ocr_text: Option<String>, ocr_confidence: Option<f32>, ocr_word_count: Option<i32>, ocr_processing_time_ms: Option<i32>, ocr_status: Option<String>) -> Result<()> {
         sqlx::query(
@@ -179,13 +254,24 @@ impl Database {
         Ok(())
     }
 
+    /// Replaces the tags on a document
+    pub async fn update_document_tags(&self, document_id: Uuid, tags: &[String]) -> Result<()> {
+        sqlx::query("UPDATE documents SET tags = $2, updated_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .bind(tags)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Gets recent documents for a specific source
     pub async fn get_recent_documents_for_source(&self, user_id: Uuid, source_id: Uuid, limit: i64) -> Result<Vec<Document>> {
         let query_str = format!(
             r#"
             SELECT {}
             FROM documents 
-            WHERE user_id = $1 AND source_id = $2
+            WHERE user_id = $1 AND source_id = $2 AND deleted_at IS NULL
             ORDER BY created_at DESC
             LIMIT $3
             "#,
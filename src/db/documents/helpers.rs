@@ -13,6 +13,19 @@ pub const DOCUMENT_FIELDS: &str = r#"
     file_owner, file_group, source_metadata
 "#;
 
+/// `DOCUMENT_FIELDS` for document lists, without the potentially large `content` and
+/// `ocr_text`. `ocr_text` is an empty string when the document has text, so
+/// `DocumentResponse::has_ocr_text` stays accurate.
+pub const DOCUMENT_LIST_FIELDS: &str = r#"
+    id, filename, original_filename, file_path, file_size, mime_type, 
+    NULL::TEXT AS content, CASE WHEN ocr_text IS NOT NULL THEN '' END AS ocr_text, 
+    ocr_confidence, ocr_word_count, ocr_processing_time_ms, 
+    ocr_status, ocr_error, ocr_completed_at, ocr_retry_count, ocr_failure_reason, 
+    tags, created_at, updated_at, user_id, file_hash, original_created_at, 
+    original_modified_at, source_path, source_type, source_id, file_permissions, 
+    file_owner, file_group, source_metadata
+"#;
+
 /// Maps a database row to a Document struct
 /// This eliminates the ~15+ instances of duplicate row mapping code
 pub fn map_row_to_document(row: &sqlx::postgres::PgRow) -> Document {
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// The stored file details of a document that a reingest checks
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DocumentFileRecord {
+    pub id: Uuid,
+    pub file_path: String,
+    pub file_hash: Option<String>,
+    pub file_size: i64,
+}
+
+impl Database {
+    /// Counts the live documents a reingest checks
+    pub async fn count_document_file_records(&self) -> Result<i64> {
+        let count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM documents WHERE deleted_at IS NULL")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(count)
+    }
+
+    /// Returns up to `limit` live documents ordered by id, starting after `after_id`
+    pub async fn get_document_file_records(&self, after_id: Option<Uuid>, limit: i64) -> Result<Vec<DocumentFileRecord>> {
+        let records = sqlx::query_as::<_, DocumentFileRecord>(
+            r#"
+            SELECT id, file_path, file_hash, file_size
+            FROM documents
+            WHERE deleted_at IS NULL AND ($1::uuid IS NULL OR id > $1)
+            ORDER BY id
+            LIMIT $2
+            "#
+        )
+        .bind(after_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Records that a document's file is `missing` or a `hash_mismatch`
+    pub async fn set_document_integrity_issue(
+        &self,
+        document_id: Uuid,
+        issue: &str,
+        expected_hash: Option<&str>,
+        actual_hash: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO document_integrity_issues (document_id, issue, expected_hash, actual_hash)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (document_id) DO UPDATE SET
+                issue = EXCLUDED.issue,
+                expected_hash = EXCLUDED.expected_hash,
+                actual_hash = EXCLUDED.actual_hash,
+                detected_at = NOW()
+            "#
+        )
+        .bind(document_id)
+        .bind(issue)
+        .bind(expected_hash)
+        .bind(actual_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Clears a `missing` or `hash_mismatch` issue once the file is intact again
+    pub async fn clear_document_integrity_issue(&self, document_id: Uuid) -> Result<()> {
+        sqlx::query("DELETE FROM document_integrity_issues WHERE document_id = $1 AND issue IN ('missing', 'hash_mismatch')")
+            .bind(document_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Flags the live documents a source synced from below `directory_path` as `source_missing`.
+    /// Returns the number of documents flagged.
+    pub async fn mark_source_directory_documents_missing(&self, source_id: Uuid, directory_path: &str) -> Result<i64> {
+        let prefix = format!("{}/", directory_path.trim_end_matches('/'));
+        let result = sqlx::query(
+            r#"
+            INSERT INTO document_integrity_issues (document_id, issue)
+            SELECT id, 'source_missing'
+            FROM documents
+            WHERE source_id = $1 AND deleted_at IS NULL AND starts_with(source_path, $2)
+            ON CONFLICT (document_id) DO UPDATE SET
+                issue = EXCLUDED.issue,
+                expected_hash = NULL,
+                actual_hash = NULL,
+                detected_at = NOW()
+            "#
+        )
+        .bind(source_id)
+        .bind(prefix)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() as i64)
+    }
+
+    /// Adopts the file currently on disk: stores its hash and size and resets OCR to pending
+    pub async fn replace_document_file_hash(&self, document_id: Uuid, file_hash: &str, file_size: i64) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET file_hash = $2, file_size = $3, ocr_status = 'pending',
+                ocr_error = NULL, ocr_failure_reason = NULL, updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .bind(file_hash)
+        .bind(file_size)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -2,10 +2,52 @@ use anyhow::Result;
 use sqlx::{QueryBuilder, Postgres, Row};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole, FacetItem};
+use crate::models::{Document, DocumentCursor, DocumentListSort, DocumentOrientation, UserRole, FacetItem};
 use crate::routes::labels::Label;
-use super::helpers::{map_row_to_document, apply_role_based_filter, DOCUMENT_FIELDS};
+use super::helpers::{map_row_to_document, apply_role_based_filter, DOCUMENT_FIELDS, DOCUMENT_LIST_FIELDS};
 use crate::db::Database;
+use crate::ocr::enhanced::OcrTextSource;
+
+/// Selection, order and position of one page of the document list
+#[derive(Debug, Clone, Default)]
+pub struct DocumentListOptions<'a> {
+    pub ocr_status: Option<&'a str>,
+    /// Only documents synced from this source
+    pub source_id: Option<Uuid>,
+    pub sort: DocumentListSort,
+    /// Start after this document (keyset pagination); `offset` is ignored when set
+    pub after: Option<DocumentCursor>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+fn apply_ocr_status_filter(query: &mut QueryBuilder<Postgres>, ocr_status: Option<&str>) {
+    if let Some(status) = ocr_status {
+        match status {
+            "pending" => {
+                query.push(" AND (ocr_status IS NULL OR ocr_status = 'pending')");
+            }
+            "completed" => {
+                query.push(" AND ocr_status = 'completed'");
+            }
+            "failed" => {
+                query.push(" AND ocr_status = 'failed'");
+            }
+            _ => {
+                query.push(" AND ocr_status = ");
+                query.push_bind(status.to_string());
+            }
+        }
+    }
+}
+
+fn apply_list_filters(query: &mut QueryBuilder<Postgres>, options: &DocumentListOptions<'_>) {
+    apply_ocr_status_filter(query, options.ocr_status);
+    if let Some(source_id) = options.source_id {
+        query.push(" AND source_id = ");
+        query.push_bind(source_id);
+    }
+}
 
 impl Database {
     /// Gets labels for a specific document
@@ -60,6 +102,8 @@ impl Database {
                 background_color: None,
                 icon: None,
                 is_system: false,
+                retention_days: None,
+                retain_forever: false,
                 created_at: row.get("created_at"),
                 updated_at: row.get("updated_at"),
                 document_count: 0,
@@ -90,7 +134,7 @@ impl Database {
             WITH duplicate_hashes AS (
                 SELECT file_hash, COUNT(*) as count
                 FROM documents 
-                WHERE file_hash IS NOT NULL
+                WHERE file_hash IS NOT NULL AND deleted_at IS NULL
             "#
         );
 
@@ -107,7 +151,7 @@ impl Database {
             SELECT d.*
             FROM documents d
             JOIN duplicate_hashes dh ON d.file_hash = dh.file_hash
-            WHERE d.file_hash IS NOT NULL
+            WHERE d.file_hash IS NOT NULL AND d.deleted_at IS NULL
             "#
         );
 
@@ -146,10 +190,221 @@ impl Database {
         Ok(duplicate_groups.into_iter().skip(start).take(end - start).collect())
     }
 
+    /// Finds groups of image documents whose perceptual hashes are within `threshold` bits
+    pub async fn get_user_perceptual_duplicates(&self, user_id: Uuid, user_role: UserRole, threshold: u32, limit: i64, offset: i64) -> Result<Vec<Vec<Document>>> {
+        let mut query = QueryBuilder::<Postgres>::new(
+            "SELECT d.*, d.perceptual_hash AS phash FROM documents d WHERE d.perceptual_hash IS NOT NULL AND d.deleted_at IS NULL"
+        );
+
+        if user_role != UserRole::Admin {
+            query.push(" AND d.user_id = ");
+            query.push_bind(user_id);
+        }
+
+        query.push(" ORDER BY d.created_at");
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        let hashes: Vec<i64> = rows.iter().map(|row| row.get("phash")).collect();
+        let mut documents: Vec<Option<Document>> = rows.iter().map(|row| Some(map_row_to_document(row))).collect();
+
+        let duplicate_groups: Vec<Vec<Document>> = crate::services::perceptual_hash::group_similar_hashes(&hashes, threshold)
+            .into_iter()
+            .map(|group| group.into_iter().filter_map(|i| documents[i].take()).collect())
+            .collect();
+
+        // Apply pagination to groups
+        Ok(duplicate_groups.into_iter().skip(offset as usize).take(limit as usize).collect())
+    }
+
+    /// Records the perceptual hash computed for an image document
+    pub async fn update_document_perceptual_hash(&self, document_id: Uuid, perceptual_hash: i64) -> Result<()> {
+        sqlx::query("UPDATE documents SET perceptual_hash = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(perceptual_hash)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Records the script detected on a document's last OCR run
+    pub async fn update_document_script_detection(&self, document_id: Uuid, script: &str, confidence: f32) -> Result<()> {
+        sqlx::query("UPDATE documents SET detected_script = $2, script_confidence = $3 WHERE id = $1")
+            .bind(document_id)
+            .bind(script)
+            .bind(confidence)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// The detected script and its confidence, if script detection ran on the document
+    pub async fn get_document_script_detection(&self, document_id: Uuid) -> Result<Option<(String, f32)>> {
+        let row = sqlx::query("SELECT detected_script, script_confidence FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.and_then(|row| {
+            let script: Option<String> = row.get("detected_script");
+            let confidence: Option<f32> = row.get("script_confidence");
+            script.map(|script| (script, confidence.unwrap_or_default()))
+        }))
+    }
+
+    /// Replaces a document's OCR text with a correction made by hand. The text is kept
+    /// when OCR runs again, unless that run is told to overwrite it.
+    pub async fn correct_document_ocr_text(&self, document_id: Uuid, ocr_text: &str) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_text = $2,
+                ocr_word_count = $3,
+                ocr_status = 'completed',
+                ocr_error = NULL,
+                ocr_failure_reason = NULL,
+                ocr_completed_at = COALESCE(ocr_completed_at, NOW()),
+                ocr_manually_edited = TRUE,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .bind(ocr_text)
+        .bind(ocr_text.split_whitespace().count() as i32)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Lets OCR write to a document again that currently counts as finished, so a confirmed
+    /// retry can replace manually corrected text
+    pub async fn mark_document_ocr_pending(&self, document_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_status = 'pending', updated_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Whether a document's OCR text was corrected by hand since it was last read by OCR
+    pub async fn is_document_ocr_manually_edited(&self, document_id: Uuid) -> Result<bool> {
+        let edited: Option<bool> = sqlx::query_scalar("SELECT ocr_manually_edited FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(edited.unwrap_or(false))
+    }
+
+    /// Records whether the OCR text of a document came from its embedded text or an OCR run
+    pub async fn set_document_ocr_text_source(&self, document_id: Uuid, source: OcrTextSource) -> Result<()> {
+        sqlx::query("UPDATE documents SET ocr_text_source = $2 WHERE id = $1")
+            .bind(document_id)
+            .bind(source.as_str())
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Files a document under a source. `source_path` is where the source has a copy of it;
+    /// without one the source can't match the document to a file, nor delete one for it.
+    pub async fn file_document_under_source(&self, document_id: Uuid, source_id: Uuid, source_path: Option<&str>) -> Result<()> {
+        sqlx::query("UPDATE documents SET source_id = $2, source_path = $3, updated_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .bind(source_id)
+            .bind(source_path)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Where a document's OCR text came from; `None` until text was first extracted
+    pub async fn get_document_ocr_text_source(&self, document_id: Uuid) -> Result<Option<String>> {
+        let source: Option<Option<String>> = sqlx::query_scalar("SELECT ocr_text_source FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(source.flatten())
+    }
+
+    /// Records the outcome of orientation detection: the rotation applied, if any, what was
+    /// detected, and whether the detection was too unsure to apply. A rotation the user chose
+    /// is kept. Returns whether the rotation the document is shown with changed.
+    pub async fn set_document_detected_orientation(
+        &self,
+        document_id: Uuid,
+        rotation_degrees: i32,
+        detected_rotation_degrees: i32,
+        confidence: f32,
+        needs_review: bool,
+    ) -> Result<bool> {
+        let rotations: Option<(i32, i32)> = sqlx::query_as(
+            r#"
+            UPDATE documents d
+            SET rotation_degrees = CASE WHEN previous.rotation_by_user THEN previous.rotation_degrees ELSE $2 END,
+                detected_rotation_degrees = $3,
+                orientation_confidence = $4,
+                orientation_needs_review = $5 AND NOT previous.rotation_by_user
+            FROM (SELECT id, rotation_degrees, rotation_by_user FROM documents WHERE id = $1 FOR UPDATE) previous
+            WHERE d.id = previous.id
+            RETURNING previous.rotation_degrees, d.rotation_degrees
+            "#
+        )
+        .bind(document_id)
+        .bind(rotation_degrees)
+        .bind(detected_rotation_degrees)
+        .bind(confidence)
+        .bind(needs_review)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(rotations.is_some_and(|(previous, current)| previous != current))
+    }
+
+    /// Sets the rotation a user chose for a document, which settles any doubt about its orientation
+    pub async fn set_document_rotation(&self, document_id: Uuid, rotation_degrees: i32) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET rotation_degrees = $2, rotation_by_user = TRUE, orientation_needs_review = FALSE
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .bind(rotation_degrees)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// How a document is turned to show it upright
+    pub async fn get_document_orientation(&self, document_id: Uuid) -> Result<Option<DocumentOrientation>> {
+        let orientation = sqlx::query_as::<_, DocumentOrientation>(
+            r#"
+            SELECT rotation_degrees, detected_rotation_degrees, orientation_confidence,
+                   orientation_needs_review AS needs_review, rotation_by_user
+            FROM documents WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(orientation)
+    }
+
     /// Gets MIME type facets (aggregated counts by MIME type)
     pub async fn get_mime_type_facets(&self, user_id: Uuid, user_role: UserRole) -> Result<Vec<FacetItem>> {
         let mut query = QueryBuilder::<Postgres>::new(
-            "SELECT mime_type as value, COUNT(*) as count FROM documents WHERE 1=1"
+            "SELECT mime_type as value, COUNT(*) as count FROM documents WHERE deleted_at IS NULL"
         );
 
         apply_role_based_filter(&mut query, user_id, user_role);
@@ -166,7 +421,7 @@ impl Database {
     /// Gets tag facets (aggregated counts by tag)
     pub async fn get_tag_facets(&self, user_id: Uuid, user_role: UserRole) -> Result<Vec<FacetItem>> {
         let mut query = QueryBuilder::<Postgres>::new(
-            "SELECT unnest(tags) as value, COUNT(*) as count FROM documents WHERE 1=1"
+            "SELECT unnest(tags) as value, COUNT(*) as count FROM documents WHERE deleted_at IS NULL"
         );
 
         apply_role_based_filter(&mut query, user_id, user_role);
@@ -188,7 +443,7 @@ impl Database {
                 COUNT(*) as total_documents,
                 COUNT(CASE WHEN ocr_text IS NOT NULL THEN 1 END) as total_documents_ocr
             FROM documents 
-            WHERE user_id = $1 AND source_id = $2
+            WHERE user_id = $1 AND source_id = $2 AND deleted_at IS NULL
             "#
         )
         .bind(user_id)
@@ -213,7 +468,7 @@ impl Database {
                 COUNT(*) as total_documents,
                 COUNT(CASE WHEN ocr_text IS NOT NULL THEN 1 END) as total_documents_ocr
             FROM documents 
-            WHERE user_id = $1 AND source_id = ANY($2)
+            WHERE user_id = $1 AND source_id = ANY($2) AND deleted_at IS NULL
             GROUP BY source_id
             "#
         )
@@ -241,27 +496,10 @@ impl Database {
     ) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE 1=1");
+        query.push(" FROM documents WHERE deleted_at IS NULL");
 
         apply_role_based_filter(&mut query, user_id, user_role);
-
-        if let Some(status) = ocr_status {
-            match status {
-                "pending" => {
-                    query.push(" AND (ocr_status IS NULL OR ocr_status = 'pending')");
-                }
-                "completed" => {
-                    query.push(" AND ocr_status = 'completed'");
-                }
-                "failed" => {
-                    query.push(" AND ocr_status = 'failed'");
-                }
-                _ => {
-                    query.push(" AND ocr_status = ");
-                    query.push_bind(status);
-                }
-            }
-        }
+        apply_ocr_status_filter(&mut query, ocr_status);
 
         query.push(" ORDER BY created_at DESC");
         query.push(" LIMIT ");
@@ -273,6 +511,50 @@ impl Database {
         Ok(rows.iter().map(map_row_to_document).collect())
     }
 
+    /// Gets one page of the document list. Ties on `created_at` are broken by `id`, so the
+    /// order is total and a cursor page continues exactly after the previous one even while
+    /// documents are being added. `content` and `ocr_text` are not loaded.
+    pub async fn list_documents_page(&self, user_id: Uuid, user_role: UserRole, options: &DocumentListOptions<'_>) -> Result<Vec<Document>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        query.push(DOCUMENT_LIST_FIELDS);
+        query.push(" FROM documents WHERE deleted_at IS NULL");
+
+        apply_role_based_filter(&mut query, user_id, user_role);
+        apply_list_filters(&mut query, options);
+
+        let (comparison, direction) = match options.sort {
+            DocumentListSort::Newest => ("<", "DESC"),
+            DocumentListSort::Oldest => (">", "ASC"),
+        };
+        if let Some(after) = options.after {
+            query.push(format!(" AND (created_at, id) {} (", comparison));
+            query.push_bind(after.created_at);
+            query.push(", ");
+            query.push_bind(after.id);
+            query.push(")");
+        }
+
+        query.push(format!(" ORDER BY created_at {0}, id {0} LIMIT ", direction));
+        query.push_bind(options.limit);
+        if options.after.is_none() {
+            query.push(" OFFSET ");
+            query.push_bind(options.offset);
+        }
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(map_row_to_document).collect())
+    }
+
+    /// Counts the documents `list_documents_page` pages through with the same filters
+    pub async fn count_listed_documents(&self, user_id: Uuid, user_role: UserRole, options: &DocumentListOptions<'_>) -> Result<i64> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM documents WHERE deleted_at IS NULL");
+        apply_role_based_filter(&mut query, user_id, user_role);
+        apply_list_filters(&mut query, options);
+
+        let row = query.build().fetch_one(&self.pool).await?;
+        Ok(row.get(0))
+    }
+
     /// Counts documents with role-based access and OCR status filtering
     pub async fn get_documents_count_with_role_and_filter(
         &self, 
@@ -280,7 +562,7 @@ impl Database {
         user_role: UserRole, 
         ocr_status: Option<&str>
     ) -> Result<i64> {
-        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM documents WHERE 1=1");
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM documents WHERE deleted_at IS NULL");
 
         apply_role_based_filter(&mut query, user_id, user_role);
 
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::db::Database;
+
+impl Database {
+    /// The user's own documents matching an imported metadata row, with their current tags.
+    /// Both keys must match when both are given.
+    pub async fn find_documents_for_metadata_import(
+        &self,
+        user_id: Uuid,
+        original_filename: Option<&str>,
+        file_hash: Option<&str>,
+    ) -> Result<Vec<(Uuid, Vec<String>)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, tags
+            FROM documents
+            WHERE user_id = $1
+              AND deleted_at IS NULL
+              AND ($2::text IS NULL OR original_filename = $2)
+              AND ($3::text IS NULL OR file_hash = $3)
+            ORDER BY created_at
+            LIMIT 100
+            "#
+        )
+        .bind(user_id)
+        .bind(original_filename)
+        .bind(file_hash)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.get("id"), row.get("tags"))).collect())
+    }
+
+    /// Names and ids of the labels the user may assign: their own, then the system labels
+    pub async fn get_assignable_labels(&self, user_id: Uuid) -> Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query("SELECT id, name FROM labels WHERE user_id = $1 OR is_system = TRUE ORDER BY is_system, name")
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.into_iter().map(|row| (row.get("id"), row.get("name"))).collect())
+    }
+
+    /// Applies one imported metadata row to a document in a single transaction. Labels
+    /// already on the document are left in place.
+    pub async fn apply_imported_document_metadata(
+        &self,
+        document_id: Uuid,
+        user_id: Uuid,
+        tags: Option<&[String]>,
+        original_created_at: Option<DateTime<Utc>>,
+        label_ids: &[Uuid],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET tags = COALESCE($3, tags),
+                original_created_at = COALESCE($4, original_created_at),
+                updated_at = NOW()
+            WHERE id = $1 AND user_id = $2
+            "#
+        )
+        .bind(document_id)
+        .bind(user_id)
+        .bind(tags)
+        .bind(original_created_at)
+        .execute(&mut *tx)
+        .await?;
+
+        for label_id in label_ids {
+            sqlx::query(
+                r#"
+                INSERT INTO document_labels (document_id, label_id, assigned_by)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (document_id, label_id) DO NOTHING
+                "#
+            )
+            .bind(document_id)
+            .bind(label_id)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
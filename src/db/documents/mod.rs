@@ -5,6 +5,19 @@ mod crud;
 mod search;
 mod management;
 mod operations;
+mod versions;
+mod shares;
+mod integrity;
+mod retention;
+mod backfill;
+mod metadata_import;
+mod storage;
+mod processing_times;
 
 // Re-export helper functions for use by other modules if needed
-pub use helpers::*;
\ No newline at end of file
+pub use helpers::*;
+pub use integrity::DocumentFileRecord;
+pub use backfill::DocumentBackfillRecord;
+pub use storage::{StoredFileRecord, StoredFileTable};
+pub use processing_times::OcrProcessingTimeStats;
+pub use management::DocumentListOptions;
\ No newline at end of file
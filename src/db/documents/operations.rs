@@ -1,24 +1,29 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::{QueryBuilder, Postgres, Row};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole, FailedDocument};
-use super::helpers::{map_row_to_document, apply_role_based_filter, DOCUMENT_FIELDS};
+use crate::models::{Document, UserRole, FailedDocument, MissingSourceDocument, Source, SourceDocumentStats};
+use super::helpers::{map_row_to_document, apply_role_based_filter, apply_pagination, DOCUMENT_FIELDS};
 use crate::db::Database;
 
 impl Database {
-    /// Deletes a single document with role-based access control
+    /// Moves a single document to the trash with role-based access control
     pub async fn delete_document(&self, document_id: Uuid, user_id: Uuid, user_role: UserRole) -> Result<bool> {
-        let mut query = QueryBuilder::<Postgres>::new("DELETE FROM documents WHERE id = ");
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE documents SET deleted_at = NOW(), updated_at = NOW() WHERE id = ");
         query.push_bind(document_id);
+        query.push(" AND deleted_at IS NULL");
         
         apply_role_based_filter(&mut query, user_id, user_role);
 
         let result = query.build().execute(&self.pool).await?;
+        if result.rows_affected() > 0 {
+            self.remove_pending_ocr_jobs(&[document_id]).await?;
+        }
         Ok(result.rows_affected() > 0)
     }
 
-    /// Bulk deletes multiple documents with role-based access control
+    /// Bulk moves multiple documents to the trash with role-based access control
     pub async fn bulk_delete_documents(&self, document_ids: &[Uuid], user_id: Uuid, user_role: UserRole) -> Result<(Vec<Uuid>, Vec<Uuid>)> {
         if document_ids.is_empty() {
             return Ok((Vec::new(), Vec::new()));
@@ -29,8 +34,9 @@ impl Database {
         let mut failed_ids = Vec::new();
 
         for &doc_id in document_ids {
-            let mut query = QueryBuilder::<Postgres>::new("DELETE FROM documents WHERE id = ");
+            let mut query = QueryBuilder::<Postgres>::new("UPDATE documents SET deleted_at = NOW(), updated_at = NOW() WHERE id = ");
             query.push_bind(doc_id);
+            query.push(" AND deleted_at IS NULL");
             
             apply_role_based_filter(&mut query, user_id, user_role);
             query.push(" RETURNING id");
@@ -50,14 +56,154 @@ impl Database {
         }
 
         tx.commit().await?;
+        self.remove_pending_ocr_jobs(&deleted_ids).await?;
         Ok((deleted_ids, failed_ids))
     }
 
+    /// Associates documents with `source`, or detaches them from their source when it is `None`.
+    /// Only live documents the user may access are changed, and with a source only those of
+    /// the source's owner. Returns the updated and the rejected document ids.
+    pub async fn bulk_assign_document_source(
+        &self,
+        document_ids: &[Uuid],
+        source: Option<&Source>,
+        user_id: Uuid,
+        user_role: UserRole,
+    ) -> Result<(Vec<Uuid>, Vec<Uuid>)> {
+        if document_ids.is_empty() {
+            return Ok((Vec::new(), Vec::new()));
+        }
+
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE documents SET source_id = ");
+        query.push_bind(source.map(|source| source.id));
+        query.push(", source_type = ");
+        query.push_bind(source.map(|source| source.source_type.to_string()));
+        // Whether the file is missing was only known for the previous source
+        query.push(", source_status = NULL, source_missing_at = NULL");
+        query.push(", updated_at = NOW() WHERE id = ANY(");
+        query.push_bind(document_ids);
+        query.push(") AND deleted_at IS NULL");
+
+        apply_role_based_filter(&mut query, user_id, user_role);
+        if let Some(source) = source {
+            query.push(" AND user_id = ");
+            query.push_bind(source.user_id);
+        }
+        query.push(" RETURNING id");
+
+        let updated_ids: Vec<Uuid> = query
+            .build()
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| row.get("id"))
+            .collect();
+        let failed_ids = document_ids
+            .iter()
+            .filter(|id| !updated_ids.contains(id))
+            .copied()
+            .collect();
+
+        Ok((updated_ids, failed_ids))
+    }
+
+    /// Drops queued OCR work for documents that were moved to the trash
+    async fn remove_pending_ocr_jobs(&self, document_ids: &[Uuid]) -> Result<()> {
+        if document_ids.is_empty() {
+            return Ok(());
+        }
+
+        sqlx::query("DELETE FROM ocr_queue WHERE document_id = ANY($1) AND status = 'pending'")
+            .bind(document_ids)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Gets a trashed document by ID with role-based access control
+    pub async fn get_deleted_document_by_id(&self, document_id: Uuid, user_id: Uuid, user_role: UserRole) -> Result<Option<Document>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        query.push(DOCUMENT_FIELDS);
+        query.push(" FROM documents WHERE id = ");
+        query.push_bind(document_id);
+        query.push(" AND deleted_at IS NOT NULL");
+
+        apply_role_based_filter(&mut query, user_id, user_role);
+
+        let row = query.build().fetch_optional(&self.pool).await?;
+        Ok(row.map(|r| map_row_to_document(&r)))
+    }
+
+    /// Lists trashed documents, most recently deleted first, along with when they were deleted
+    pub async fn get_deleted_documents(&self, user_id: Uuid, user_role: UserRole, limit: i64, offset: i64) -> Result<Vec<(Document, DateTime<Utc>)>> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        query.push(DOCUMENT_FIELDS);
+        query.push(", deleted_at FROM documents WHERE deleted_at IS NOT NULL");
+
+        apply_role_based_filter(&mut query, user_id, user_role);
+        query.push(" ORDER BY deleted_at DESC");
+        apply_pagination(&mut query, limit, offset);
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        Ok(rows.iter().map(|row| (map_row_to_document(row), row.get("deleted_at"))).collect())
+    }
+
+    /// Counts trashed documents with role-based access control
+    pub async fn count_deleted_documents(&self, user_id: Uuid, user_role: UserRole) -> Result<i64> {
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as total FROM documents WHERE deleted_at IS NOT NULL");
+        apply_role_based_filter(&mut query, user_id, user_role);
+        let row = query.build().fetch_one(&self.pool).await?;
+        Ok(row.get("total"))
+    }
+
+    /// Takes a document out of the trash and points it at its restored file location.
+    /// Fails with a unique violation if the user re-uploaded the same content in the meantime.
+    pub async fn restore_document(&self, document_id: Uuid, file_path: &str, user_id: Uuid, user_role: UserRole) -> Result<bool> {
+        let mut query = QueryBuilder::<Postgres>::new("UPDATE documents SET deleted_at = NULL, updated_at = NOW(), file_path = ");
+        query.push_bind(file_path);
+        query.push(" WHERE id = ");
+        query.push_bind(document_id);
+        query.push(" AND deleted_at IS NOT NULL");
+
+        apply_role_based_filter(&mut query, user_id, user_role);
+
+        let result = query.build().execute(&self.pool).await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Updates where a document's file is stored
+    pub async fn update_document_file_path(&self, document_id: Uuid, file_path: &str) -> Result<()> {
+        sqlx::query("UPDATE documents SET file_path = $2, updated_at = NOW() WHERE id = $1")
+            .bind(document_id)
+            .bind(file_path)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Permanently removes trashed documents deleted before `deleted_before`, returning them
+    /// so their files can be cleaned up
+    pub async fn purge_deleted_documents(&self, deleted_before: DateTime<Utc>) -> Result<Vec<Document>> {
+        let query_str = format!(
+            "DELETE FROM documents WHERE deleted_at IS NOT NULL AND deleted_at < $1 RETURNING {}",
+            DOCUMENT_FIELDS
+        );
+
+        let rows = sqlx::query(&query_str)
+            .bind(deleted_before)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows.iter().map(map_row_to_document).collect())
+    }
+
     /// Finds documents with OCR confidence below threshold
     pub async fn find_documents_by_confidence_threshold(&self, user_id: Uuid, user_role: UserRole, max_confidence: f32, limit: i64, offset: i64) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE ocr_confidence IS NOT NULL AND ocr_confidence <= ");
+        query.push(" FROM documents WHERE deleted_at IS NULL AND ocr_confidence IS NOT NULL AND ocr_confidence <= ");
         query.push_bind(max_confidence);
 
         apply_role_based_filter(&mut query, user_id, user_role);
@@ -75,7 +221,7 @@ impl Database {
     pub async fn find_failed_ocr_documents(&self, user_id: Uuid, user_role: UserRole, limit: i64, offset: i64) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE ocr_status = 'failed'");
+        query.push(" FROM documents WHERE deleted_at IS NULL AND ocr_status = 'failed'");
 
         apply_role_based_filter(&mut query, user_id, user_role);
         query.push(" ORDER BY created_at DESC");
@@ -92,7 +238,7 @@ impl Database {
     pub async fn find_low_confidence_and_failed_documents(&self, user_id: Uuid, user_role: UserRole, max_confidence: f32, limit: i64, offset: i64) -> Result<Vec<Document>> {
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE (ocr_status = 'failed' OR (ocr_confidence IS NOT NULL AND ocr_confidence <= ");
+        query.push(" FROM documents WHERE deleted_at IS NULL AND (ocr_status = 'failed' OR (ocr_confidence IS NOT NULL AND ocr_confidence <= ");
         query.push_bind(max_confidence);
         query.push("))");
 
@@ -256,7 +402,7 @@ impl Database {
                 COUNT(CASE WHEN ocr_status IS NULL OR ocr_status = 'pending' THEN 1 END) as pending,
                 COUNT(CASE WHEN ocr_status = 'completed' THEN 1 END) as completed,
                 COUNT(CASE WHEN ocr_status = 'failed' THEN 1 END) as failed
-            FROM documents WHERE 1=1
+            FROM documents WHERE deleted_at IS NULL
             "#
         );
 
@@ -274,7 +420,7 @@ impl Database {
 
     /// Counts total documents for a user with role-based access control
     pub async fn count_documents_by_user_with_role(&self, user_id: Uuid, user_role: UserRole) -> Result<i64> {
-        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as total FROM documents WHERE 1=1");
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as total FROM documents WHERE deleted_at IS NULL");
         apply_role_based_filter(&mut query, user_id, user_role);
         let row = query.build().fetch_one(&self.pool).await?;
         Ok(row.get("total"))
@@ -287,7 +433,7 @@ impl Database {
         user_role: UserRole, 
         ocr_status: Option<&str>
     ) -> Result<i64> {
-        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as total FROM documents WHERE 1=1");
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) as total FROM documents WHERE deleted_at IS NULL");
         apply_role_based_filter(&mut query, user_id, user_role);
         
         if let Some(status) = ocr_status {
@@ -298,4 +444,125 @@ impl Database {
         let row = query.build().fetch_one(&self.pool).await?;
         Ok(row.get("total"))
     }
-}
\ No newline at end of file
+
+    /// Flags the documents synced from `source_paths` of a source as missing on the source
+    /// (`source_status = 'missing'`), or clears the flag when they reappear.
+    /// The documents themselves are kept.
+    pub async fn set_source_documents_missing(&self, source_id: Uuid, source_paths: &[String], missing: bool) -> Result<u64> {
+        if source_paths.is_empty() {
+            return Ok(0);
+        }
+
+        let query = if missing {
+            r#"
+            UPDATE documents
+            SET source_status = 'missing', source_missing_at = NOW(), updated_at = NOW()
+            WHERE source_id = $1 AND source_path = ANY($2) AND deleted_at IS NULL
+              AND source_status IS NULL
+            "#
+        } else {
+            r#"
+            UPDATE documents
+            SET source_status = NULL, source_missing_at = NULL, updated_at = NOW()
+            WHERE source_id = $1 AND source_path = ANY($2)
+              AND source_status = 'missing'
+            "#
+        };
+
+        let result = sqlx::query(query)
+            .bind(source_id)
+            .bind(source_paths)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Source paths of the live documents synced from a source, with whether each is
+    /// currently flagged as missing on the source
+    pub async fn get_source_document_paths(&self, source_id: Uuid) -> Result<Vec<(String, bool)>> {
+        let paths = sqlx::query_as(
+            r#"
+            SELECT DISTINCT source_path, source_status IS NOT NULL
+            FROM documents
+            WHERE source_id = $1 AND source_path IS NOT NULL AND deleted_at IS NULL
+            "#
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(paths)
+    }
+
+    /// Live documents of a source whose file is gone from the source, longest missing first
+    pub async fn get_missing_source_documents(&self, source_id: Uuid) -> Result<Vec<MissingSourceDocument>> {
+        let documents = sqlx::query_as::<_, MissingSourceDocument>(
+            r#"
+            SELECT id, filename, source_path, source_missing_at AS missing_since
+            FROM documents
+            WHERE source_id = $1 AND source_status = 'missing' AND deleted_at IS NULL
+            ORDER BY source_missing_at, source_path
+            "#
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    /// Number, total size and OCR status breakdown of the live documents of a source
+    pub async fn get_source_document_stats(&self, source_id: Uuid) -> Result<SourceDocumentStats> {
+        let stats = sqlx::query_as::<_, SourceDocumentStats>(
+            r#"
+            SELECT $1 AS source_id,
+                   COUNT(*) AS total_documents,
+                   COALESCE(SUM(file_size), 0)::BIGINT AS total_size_bytes,
+                   COUNT(*) FILTER (WHERE ocr_status IS NULL OR ocr_status = 'pending') AS ocr_pending,
+                   COUNT(*) FILTER (WHERE ocr_status = 'processing') AS ocr_processing,
+                   COUNT(*) FILTER (WHERE ocr_status = 'completed') AS ocr_completed,
+                   COUNT(*) FILTER (WHERE ocr_status = 'failed') AS ocr_failed,
+                   COUNT(*) FILTER (WHERE source_status = 'missing') AS missing_on_source
+            FROM documents
+            WHERE source_id = $1 AND deleted_at IS NULL
+            "#
+        )
+        .bind(source_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+
+    /// Cancels the OCR jobs still waiting in the queue for documents of a source.
+    /// Jobs already being processed are left to finish.
+    pub async fn cancel_pending_source_ocr_jobs(&self, source_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE ocr_queue
+            SET status = 'cancelled', completed_at = NOW(), error_message = 'Source sync aborted'
+            WHERE status = 'pending'
+              AND document_id IN (SELECT id FROM documents WHERE source_id = $1)
+            "#
+        )
+        .bind(source_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Live documents of a source created at or after `since`, e.g. the ones ingested by a sync run
+    pub async fn get_source_document_ids_created_since(&self, source_id: Uuid, since: DateTime<Utc>) -> Result<Vec<Uuid>> {
+        let ids = sqlx::query_scalar(
+            "SELECT id FROM documents WHERE source_id = $1 AND created_at >= $2 AND deleted_at IS NULL"
+        )
+        .bind(source_id)
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(ids)
+    }
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+
+use crate::db::Database;
+
+/// Completed OCR runs of one MIME type within one file-size bucket
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OcrProcessingTimeStats {
+    pub mime_type: String,
+    /// Index into the bucket boundaries the stats were grouped by
+    pub size_bucket: i32,
+    pub samples: i64,
+    pub average_ms: f64,
+}
+
+impl Database {
+    /// OCR processing times of documents completed within the last `days` days, grouped by
+    /// MIME type and by the size bucket delimited by the ascending `size_boundaries`.
+    /// Bucket `i` holds files smaller than `size_boundaries[i]` and at least as large as
+    /// the boundary before it; the last bucket holds everything larger.
+    pub async fn get_ocr_processing_time_stats(&self, size_boundaries: &[i64], days: i32) -> Result<Vec<OcrProcessingTimeStats>> {
+        let stats = sqlx::query_as::<_, OcrProcessingTimeStats>(
+            r#"
+            SELECT mime_type,
+                   width_bucket(file_size, $1::int8[]) AS size_bucket,
+                   COUNT(*) AS samples,
+                   AVG(ocr_processing_time_ms)::float8 AS average_ms
+            FROM documents
+            WHERE ocr_status = 'completed'
+              AND ocr_processing_time_ms > 0
+              AND COALESCE(ocr_completed_at, updated_at) > NOW() - make_interval(days => $2)
+            GROUP BY 1, 2
+            "#
+        )
+        .bind(size_boundaries)
+        .bind(days)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(stats)
+    }
+}
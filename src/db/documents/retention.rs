@@ -0,0 +1,44 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::db::Database;
+use crate::models::RetentionCandidate;
+
+impl Database {
+    /// Finds live documents past their retention period. A document is kept for the longest
+    /// `retention_days` among its labels, or the owner's default `retention_days` when none of
+    /// its labels sets one; a keep-forever label exempts it entirely.
+    ///
+    /// With a `user_id` only that user's documents are considered. Without one, every user who
+    /// enabled automatic cleanup is.
+    pub async fn get_retention_expired_documents(&self, user_id: Option<Uuid>) -> Result<Vec<RetentionCandidate>> {
+        let candidates = sqlx::query_as::<_, RetentionCandidate>(
+            r#"
+            WITH policies AS (
+                SELECT d.id, d.user_id, d.original_filename, d.created_at,
+                       COALESCE(BOOL_OR(l.retain_forever), false) AS retain_forever,
+                       COALESCE(MAX(l.retention_days), s.retention_days) AS retention_days
+                FROM documents d
+                LEFT JOIN settings s ON s.user_id = d.user_id
+                LEFT JOIN document_labels dl ON dl.document_id = d.id
+                LEFT JOIN labels l ON l.id = dl.label_id
+                WHERE d.deleted_at IS NULL
+                  AND ($1::uuid IS NULL AND s.enable_auto_cleanup OR d.user_id = $1)
+                GROUP BY d.id, s.retention_days
+            )
+            SELECT id, user_id, original_filename, created_at, retention_days,
+                   created_at + make_interval(days => retention_days) AS expired_at
+            FROM policies
+            WHERE NOT retain_forever
+              AND retention_days IS NOT NULL
+              AND created_at + make_interval(days => retention_days) < NOW()
+            ORDER BY created_at
+            "#
+        )
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(candidates)
+    }
+}
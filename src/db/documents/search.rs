@@ -2,52 +2,258 @@ use anyhow::Result;
 use sqlx::{QueryBuilder, Postgres, Row};
 use uuid::Uuid;
 
-use crate::models::{Document, UserRole, SearchRequest, SearchMode, SearchSnippet, HighlightRange, EnhancedDocumentResponse};
+use crate::models::{Document, UserRole, SearchRequest, SearchMode, SearchSort, SearchSnippet, HighlightRange, EnhancedDocumentResponse, FilterMatch};
 use super::helpers::{map_row_to_document, apply_role_based_filter, apply_pagination, find_word_boundary, DOCUMENT_FIELDS};
 use crate::db::Database;
 
-impl Database {
-    /// Performs basic document search with PostgreSQL full-text search
-    pub async fn search_documents(&self, user_id: Uuid, search_request: &SearchRequest) -> Result<Vec<Document>> {
-        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
-        query.push(DOCUMENT_FIELDS);
-        query.push(" FROM documents WHERE user_id = ");
-        query.push_bind(user_id);
+/// Ranked text: filename matches weigh more than matches in the body
+const SEARCH_VECTOR: &str = "setweight(to_tsvector('english', COALESCE(original_filename, '')), 'A') || to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''))";
 
-        // Add search conditions
-        if !search_request.query.trim().is_empty() {
-            query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ plainto_tsquery('english', ");
-            query.push_bind(&search_request.query);
-            query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ plainto_tsquery('english', ");
-            query.push_bind(&search_request.query);
-            query.push("))");
+/// Single-letter tokens in a row from this many on are read as a word OCR broke apart
+const BROKEN_RUN_MIN_TOKENS: usize = 3;
+
+/// Whether an OCR token is a fragment: a lone letter or symbol, or a token without any
+/// letters or digits (`|`, `~~`, `.,`)
+fn is_ocr_fragment(token: &str) -> bool {
+    let mut chars = token.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => !c.is_ascii_digit(),
+        _ => !token.chars().any(char::is_alphanumeric),
+    }
+}
+
+/// Cleans a snippet taken from OCR text for display: collapses whitespace, drops tokens
+/// without letters or digits and runs of fragments left by broken glyphs, and moves the
+/// highlight ranges onto the cleaned text. Tokens a highlight touches are always kept; the
+/// offsets into the stored text are left as they are.
+pub fn clean_ocr_snippet(snippet: &mut SearchSnippet) {
+    let text = &snippet.text;
+    let mut tokens: Vec<(usize, usize)> = Vec::new();
+    let mut token_start = None;
+    for (index, c) in text.char_indices() {
+        match (c.is_whitespace(), token_start) {
+            (true, Some(start)) => {
+                tokens.push((start, index));
+                token_start = None;
+            }
+            (false, None) => token_start = Some(index),
+            _ => {}
         }
+    }
+    if let Some(start) = token_start {
+        tokens.push((start, text.len()));
+    }
 
-        // Add tag filtering
-        if let Some(ref tags) = search_request.tags {
-            if !tags.is_empty() {
-                query.push(" AND tags && ");
-                query.push_bind(tags);
+    let highlighted = |(start, end): (usize, usize)| {
+        snippet.highlight_ranges.iter().any(|range| (range.start as usize) < end && (range.end as usize) > start)
+    };
+    let mut keep: Vec<bool> = tokens
+        .iter()
+        .map(|&(start, end)| text[start..end].chars().any(char::is_alphanumeric) || highlighted((start, end)))
+        .collect();
+
+    let mut run_start = 0;
+    for index in 0..=tokens.len() {
+        let in_run = index < tokens.len() && is_ocr_fragment(&text[tokens[index].0..tokens[index].1]);
+        if in_run {
+            continue;
+        }
+        if index - run_start >= BROKEN_RUN_MIN_TOKENS {
+            for i in run_start..index {
+                keep[i] = highlighted(tokens[i]);
             }
         }
+        run_start = index + 1;
+    }
+
+    let mut cleaned = String::with_capacity(text.len());
+    let mut new_starts = vec![None; tokens.len()];
+    for (i, &(start, end)) in tokens.iter().enumerate() {
+        if !keep[i] {
+            continue;
+        }
+        if !cleaned.is_empty() {
+            cleaned.push(' ');
+        }
+        new_starts[i] = Some(cleaned.len());
+        cleaned.push_str(&text[start..end]);
+    }
+
+    // Highlights never span whitespace, so each maps to an offset within its kept token
+    let remap = |position: usize, is_end: bool| {
+        tokens.iter().zip(&new_starts).find_map(|(&(start, end), new_start)| {
+            let inside = if is_end { position > start && position <= end } else { position >= start && position < end };
+            if inside { new_start.map(|new_start| new_start + position - start) } else { None }
+        })
+    };
+    snippet.highlight_ranges = snippet
+        .highlight_ranges
+        .iter()
+        .filter_map(|range| {
+            let start = remap(range.start as usize, false)?;
+            let end = remap(range.end as usize, true)?;
+            Some(HighlightRange { start: start as i32, end: end as i32 })
+        })
+        .collect();
+    snippet.text = cleaned;
+}
+
+/// Pushes the tsquery for `mode`; fuzzy search does not use one
+fn push_tsquery<'a>(query: &mut QueryBuilder<'a, Postgres>, mode: &SearchMode, search_query: &'a str) {
+    let function = match mode {
+        SearchMode::Phrase => "phraseto_tsquery",
+        SearchMode::Boolean => "to_tsquery",
+        SearchMode::Simple | SearchMode::Fuzzy => "plainto_tsquery",
+    };
+    query.push(format!("{}('english', ", function));
+    query.push_bind(search_query);
+    query.push(")");
+}
+
+/// Pushes `, <score> as search_rank`. Full-text modes use cover density ranking
+/// normalised to 0-1; fuzzy mode uses trigram similarity.
+fn push_search_rank<'a>(query: &mut QueryBuilder<'a, Postgres>, mode: &SearchMode, search_query: &'a str) {
+    if let SearchMode::Fuzzy = mode {
+        query.push(", similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
+        query.push_bind(search_query);
+        query.push(") as search_rank");
+        return;
+    }
+
+    query.push(", ts_rank_cd(");
+    query.push(SEARCH_VECTOR);
+    query.push(", ");
+    push_tsquery(query, mode, search_query);
+    query.push(", 32) as search_rank");
+}
+
+/// Pushes the condition matching documents whose filename, content or OCR text hit the query
+fn push_search_condition<'a>(query: &mut QueryBuilder<'a, Postgres>, mode: &SearchMode, search_query: &'a str) {
+    if let SearchMode::Fuzzy = mode {
+        query.push(" AND similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
+        query.push_bind(search_query);
+        query.push(") > 0.3");
+        return;
+    }
+
+    query.push(" AND (");
+    for (index, column) in ["content", "ocr_text", "original_filename"].iter().enumerate() {
+        if index > 0 {
+            query.push(" OR ");
+        }
+        query.push(format!("to_tsvector('english', COALESCE({}, '')) @@ ", column));
+        push_tsquery(query, mode, search_query);
+    }
+    query.push(")");
+}
+
+/// Pushes the tag and label filters. `any` needs one of the tags and one of the
+/// labels, `all` needs every tag and every label.
+fn push_tag_and_label_filter<'a>(query: &mut QueryBuilder<'a, Postgres>, search_request: &'a SearchRequest) {
+    let filter_match = search_request.filter_match.unwrap_or_default();
+
+    if let Some(ref tags) = search_request.tags {
+        if !tags.is_empty() {
+            query.push(match filter_match {
+                FilterMatch::Any => " AND tags && ",
+                FilterMatch::All => " AND tags @> ",
+            });
+            query.push_bind(tags);
+        }
+    }
 
-        // Add MIME type filtering
-        if let Some(ref mime_types) = search_request.mime_types {
-            if !mime_types.is_empty() {
-                query.push(" AND mime_type = ANY(");
-                query.push_bind(mime_types);
-                query.push(")");
+    if let Some(ref labels) = search_request.labels {
+        if !labels.is_empty() {
+            match filter_match {
+                FilterMatch::Any => {
+                    query.push(" AND EXISTS (SELECT 1 FROM document_labels dl WHERE dl.document_id = documents.id AND dl.label_id = ANY(");
+                    query.push_bind(labels);
+                    query.push("))");
+                }
+                FilterMatch::All => {
+                    let mut distinct = labels.clone();
+                    distinct.sort();
+                    distinct.dedup();
+                    query.push(" AND (SELECT COUNT(DISTINCT dl.label_id) FROM document_labels dl WHERE dl.document_id = documents.id AND dl.label_id = ANY(");
+                    query.push_bind(labels);
+                    query.push(")) = ");
+                    query.push_bind(distinct.len() as i64);
+                }
             }
         }
+    }
+}
+
+/// Pushes the text match and every filter of `search_request`, shared by the
+/// result and count queries so totals agree with the pages
+fn push_search_filters<'a>(query: &mut QueryBuilder<'a, Postgres>, mode: &SearchMode, search_query: &'a str, search_request: &'a SearchRequest) {
+    if !search_query.is_empty() {
+        push_search_condition(query, mode, search_query);
+    }
+
+    push_tag_and_label_filter(query, search_request);
+
+    if let Some(ref mime_types) = search_request.mime_types {
+        if !mime_types.is_empty() {
+            query.push(" AND mime_type = ANY(");
+            query.push_bind(mime_types);
+            query.push(")");
+        }
+    }
+
+    push_capture_date_filter(query, search_request);
+}
+
+/// Pushes bounds on the original creation date (EXIF capture or PDF creation date when known)
+fn push_capture_date_filter<'a>(query: &mut QueryBuilder<'a, Postgres>, search_request: &'a SearchRequest) {
+    if let Some(captured_after) = search_request.captured_after {
+        query.push(" AND original_created_at >= ");
+        query.push_bind(captured_after);
+    }
+    if let Some(captured_before) = search_request.captured_before {
+        query.push(" AND original_created_at < ");
+        query.push_bind(captured_before);
+    }
+}
+
+/// Pushes the ORDER BY clause; every ordering ends with newest first so results are stable
+fn push_search_order(query: &mut QueryBuilder<'_, Postgres>, sort: SearchSort) {
+    query.push(match sort {
+        SearchSort::Relevance => " ORDER BY search_rank DESC, created_at DESC",
+        SearchSort::Date => " ORDER BY created_at DESC",
+        SearchSort::Name => " ORDER BY LOWER(original_filename) ASC, created_at DESC",
+    });
+}
+
+impl Database {
+    /// Performs basic document search with PostgreSQL full-text search, returning each
+    /// document with its relevance score
+    pub async fn search_documents(&self, user_id: Uuid, search_request: &SearchRequest) -> Result<Vec<(Document, f32)>> {
+        let search_query = search_request.query.trim();
+
+        let mut query = QueryBuilder::<Postgres>::new("SELECT ");
+        query.push(DOCUMENT_FIELDS);
+        if search_query.is_empty() {
+            query.push(", 0.0::real as search_rank");
+        } else {
+            push_search_rank(&mut query, &SearchMode::Simple, search_query);
+        }
+        query.push(" FROM documents WHERE deleted_at IS NULL AND user_id = ");
+        query.push_bind(user_id);
+
+        push_search_filters(&mut query, &SearchMode::Simple, search_query, search_request);
 
-        query.push(" ORDER BY created_at DESC");
+        push_search_order(&mut query, search_request.sort.unwrap_or_default());
         
         let limit = search_request.limit.unwrap_or(25);
         let offset = search_request.offset.unwrap_or(0);
         apply_pagination(&mut query, limit, offset);
 
         let rows = query.build().fetch_all(&self.pool).await?;
-        Ok(rows.iter().map(map_row_to_document).collect())
+        Ok(rows
+            .iter()
+            .map(|row| (map_row_to_document(row), row.try_get("search_rank").unwrap_or(0.0)))
+            .collect())
     }
 
     /// Enhanced search with snippets and ranking
@@ -60,91 +266,26 @@ impl Database {
         let search_query = search_request.query.trim();
         let include_snippets = search_request.include_snippets.unwrap_or(true);
         let snippet_length = search_request.snippet_length.unwrap_or(200) as usize;
+        let clean_snippets = search_request.clean_snippets.unwrap_or(true);
+        let search_mode = search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple);
 
         let mut query = QueryBuilder::<Postgres>::new("SELECT ");
         query.push(DOCUMENT_FIELDS);
         
         // Add search ranking if there's a query
         if !search_query.is_empty() {
-            match search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple) {
-                SearchMode::Simple => {
-                    query.push(", ts_rank(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')), plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(")) as search_rank");
-                }
-                SearchMode::Phrase => {
-                    query.push(", ts_rank(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')), phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(")) as search_rank");
-                }
-                SearchMode::Boolean => {
-                    query.push(", ts_rank(to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')), to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(")) as search_rank");
-                }
-                SearchMode::Fuzzy => {
-                    query.push(", similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
-                    query.push_bind(search_query);
-                    query.push(") as search_rank");
-                }
-            }
+            push_search_rank(&mut query, search_mode, search_query);
         } else {
-            query.push(", 0.0 as search_rank");
+            query.push(", 0.0::real as search_rank");
         }
 
-        query.push(" FROM documents WHERE 1=1");
+        query.push(" FROM documents WHERE deleted_at IS NULL");
 
         apply_role_based_filter(&mut query, user_id, user_role);
 
-        // Add search conditions
-        if !search_query.is_empty() {
-            match search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple) {
-                SearchMode::Simple => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ plainto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Phrase => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ phraseto_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Boolean => {
-                    query.push(" AND (to_tsvector('english', COALESCE(content, '')) @@ to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push(") OR to_tsvector('english', COALESCE(ocr_text, '')) @@ to_tsquery('english', ");
-                    query.push_bind(search_query);
-                    query.push("))");
-                }
-                SearchMode::Fuzzy => {
-                    query.push(" AND similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), ");
-                    query.push_bind(search_query);
-                    query.push(") > 0.3");
-                }
-            }
-        }
-
-        // Add filtering
-        if let Some(ref tags) = search_request.tags {
-            if !tags.is_empty() {
-                query.push(" AND tags && ");
-                query.push_bind(tags);
-            }
-        }
-
-        if let Some(ref mime_types) = search_request.mime_types {
-            if !mime_types.is_empty() {
-                query.push(" AND mime_type = ANY(");
-                query.push_bind(mime_types);
-                query.push(")");
-            }
-        }
+        push_search_filters(&mut query, search_mode, search_query, search_request);
 
-        query.push(" ORDER BY search_rank DESC, created_at DESC");
+        push_search_order(&mut query, search_request.sort.unwrap_or_default());
         
         let limit = search_request.limit.unwrap_or(25);
         let offset = search_request.offset.unwrap_or(0);
@@ -158,7 +299,7 @@ impl Database {
             let search_rank: f32 = row.try_get("search_rank").unwrap_or(0.0);
 
             let snippets = if include_snippets && !search_query.is_empty() {
-                self.generate_snippets(&document, search_query, snippet_length).await
+                self.generate_snippets(&document, search_query, snippet_length, clean_snippets).await
             } else {
                 Vec::new()
             };
@@ -184,8 +325,75 @@ impl Database {
         Ok(results)
     }
 
-    /// Generates search snippets with highlighted matches
-    pub async fn generate_snippets(&self, document: &Document, search_query: &str, snippet_length: usize) -> Vec<SearchSnippet> {
+    /// Counts every document matching a search in `search_mode`, ignoring `limit` and `offset`
+    pub async fn count_search_results(&self, user_id: Uuid, user_role: UserRole, search_mode: &SearchMode, search_request: &SearchRequest) -> Result<i64> {
+        let search_query = search_request.query.trim();
+
+        let mut query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM documents WHERE deleted_at IS NULL");
+        apply_role_based_filter(&mut query, user_id, user_role);
+        push_search_filters(&mut query, search_mode, search_query, search_request);
+
+        Ok(query.build_query_scalar::<i64>().fetch_one(&self.pool).await?)
+    }
+
+    /// Ids of `user_id`'s own documents matching a search in `search_mode`, oldest first and
+    /// at most `limit` of them, ignoring the request's `limit` and `offset`
+    pub async fn search_document_ids(&self, user_id: Uuid, search_mode: &SearchMode, search_request: &SearchRequest, limit: i64) -> Result<Vec<Uuid>> {
+        let search_query = search_request.query.trim();
+
+        let mut query = QueryBuilder::<Postgres>::new("SELECT id FROM documents WHERE deleted_at IS NULL AND user_id = ");
+        query.push_bind(user_id);
+        push_search_filters(&mut query, search_mode, search_query, search_request);
+        query.push(" ORDER BY created_at ASC, id ASC LIMIT ");
+        query.push_bind(limit);
+
+        Ok(query.build_query_scalar::<Uuid>().fetch_all(&self.pool).await?)
+    }
+
+    /// Other documents of `document`'s owner whose text is similar to its text, most similar
+    /// first, with the trigram similarity of the texts (0-1). Candidates have to contain one
+    /// of the document's `term_count` most frequent terms, which the full-text index finds
+    /// without comparing every document.
+    pub async fn find_related_documents(&self, document: &Document, term_count: i64, min_similarity: f32, limit: i64) -> Result<Vec<(Document, f32)>> {
+        let body = format!("{} {}", document.content.as_deref().unwrap_or(""), document.ocr_text.as_deref().unwrap_or(""));
+        if body.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = QueryBuilder::<Postgres>::new("WITH origin AS (SELECT ");
+        query.push_bind(body);
+        query.push(
+            "::text AS body), origin_terms AS (
+                SELECT string_agg(lexeme, ' | ')::tsquery AS terms FROM (
+                    SELECT lexeme FROM unnest(to_tsvector('english', (SELECT body FROM origin)))
+                    WHERE length(lexeme) > 2 AND lexeme ~ '^[[:alnum:]]+$'
+                    ORDER BY array_length(positions, 1) DESC, lexeme
+                    LIMIT ",
+        );
+        query.push_bind(term_count);
+        query.push(") top_terms) SELECT ");
+        query.push(DOCUMENT_FIELDS);
+        query.push(", similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), origin.body) AS related_score");
+        query.push(" FROM documents, origin, origin_terms WHERE deleted_at IS NULL AND id <> ");
+        query.push_bind(document.id);
+        query.push(" AND user_id = ");
+        query.push_bind(document.user_id);
+        query.push(" AND to_tsvector('english', COALESCE(content, '') || ' ' || COALESCE(ocr_text, '')) @@ origin_terms.terms");
+        query.push(" AND similarity(COALESCE(content, '') || ' ' || COALESCE(ocr_text, ''), origin.body) >= ");
+        query.push_bind(min_similarity);
+        query.push(" ORDER BY related_score DESC, created_at DESC LIMIT ");
+        query.push_bind(limit);
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        Ok(rows
+            .iter()
+            .map(|row| (map_row_to_document(row), row.try_get("related_score").unwrap_or(0.0)))
+            .collect())
+    }
+
+    /// Generates search snippets with highlighted matches. With `clean_ocr`, snippets from the
+    /// OCR text are cleaned of OCR noise; the stored text is never changed.
+    pub async fn generate_snippets(&self, document: &Document, search_query: &str, snippet_length: usize, clean_ocr: bool) -> Vec<SearchSnippet> {
         let mut snippets = Vec::new();
         let search_terms: Vec<&str> = search_query.split_whitespace().collect();
 
@@ -235,12 +443,16 @@ impl Database {
                         end: highlight_end as i32,
                     }];
 
-                    snippets.push(SearchSnippet {
+                    let mut snippet = SearchSnippet {
                         text: snippet_text.to_string(),
                         start_offset: snippet_start as i32,
                         end_offset: snippet_end as i32,
                         highlight_ranges,
-                    });
+                    };
+                    if clean_ocr && source == "ocr_text" {
+                        clean_ocr_snippet(&mut snippet);
+                    }
+                    snippets.push(snippet);
 
                     start_pos = absolute_match_pos + term.len();
                     
@@ -256,4 +468,46 @@ impl Database {
         snippets.truncate(5);
         snippets
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(text: &str, term: &str) -> SearchSnippet {
+        let start = text.find(term).unwrap();
+        SearchSnippet {
+            text: text.to_string(),
+            start_offset: 120,
+            end_offset: 120 + text.len() as i32,
+            highlight_ranges: vec![HighlightRange { start: start as i32, end: (start + term.len()) as i32 }],
+        }
+    }
+
+    fn highlighted(snippet: &SearchSnippet) -> Vec<&str> {
+        snippet.highlight_ranges.iter().map(|range| &snippet.text[range.start as usize..range.end as usize]).collect()
+    }
+
+    #[test]
+    fn test_clean_ocr_snippet_strips_noise_and_keeps_highlight() {
+        let mut noisy = snippet("Total  due | ~ on the\n\n  invoice ;  i l | r n  dated 12 May . 2024", "invoice");
+        let offsets = (noisy.start_offset, noisy.end_offset);
+        clean_ocr_snippet(&mut noisy);
+        assert_eq!(noisy.text, "Total due on the invoice dated 12 May 2024");
+        assert_eq!(highlighted(&noisy), vec!["invoice"]);
+        assert_eq!((noisy.start_offset, noisy.end_offset), offsets, "offsets into the stored text are kept");
+    }
+
+    #[test]
+    fn test_clean_ocr_snippet_keeps_highlighted_fragments() {
+        let mut noisy = snippet("see ~~ section B | -- of the contract", "B");
+        clean_ocr_snippet(&mut noisy);
+        assert_eq!(noisy.text, "see section B of the contract");
+        assert_eq!(highlighted(&noisy), vec!["B"]);
+
+        let mut clean = snippet("A plain sentence about a contract", "contract");
+        clean_ocr_snippet(&mut clean);
+        assert_eq!(clean.text, "A plain sentence about a contract");
+        assert_eq!(highlighted(&clean), vec!["contract"]);
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{Document, DocumentShare};
+use super::helpers::{map_row_to_document, DOCUMENT_FIELDS};
+use crate::db::Database;
+
+const SHARE_FIELDS: &str = r#"
+    s.id, s.document_id, s.token, s.created_by, s.expires_at, s.allow_download, s.created_at,
+    (SELECT COUNT(*) FROM document_share_accesses a WHERE a.share_id = s.id) AS access_count,
+    (SELECT MAX(a.accessed_at) FROM document_share_accesses a WHERE a.share_id = s.id) AS last_accessed_at
+"#;
+
+impl Database {
+    pub async fn create_document_share(
+        &self,
+        document_id: Uuid,
+        created_by: Uuid,
+        token: &str,
+        expires_at: DateTime<Utc>,
+        allow_download: bool,
+    ) -> Result<DocumentShare> {
+        let query_str = format!(
+            r#"
+            WITH s AS (
+                INSERT INTO document_shares (document_id, created_by, token, expires_at, allow_download)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING *
+            )
+            SELECT {} FROM s
+            "#,
+            SHARE_FIELDS
+        );
+
+        let share = sqlx::query_as::<_, DocumentShare>(&query_str)
+            .bind(document_id)
+            .bind(created_by)
+            .bind(token)
+            .bind(expires_at)
+            .bind(allow_download)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(share)
+    }
+
+    /// Lists the shares of a document, expired ones included, newest first
+    pub async fn get_document_shares(&self, document_id: Uuid) -> Result<Vec<DocumentShare>> {
+        let query_str = format!(
+            "SELECT {} FROM document_shares s WHERE s.document_id = $1 ORDER BY s.created_at DESC",
+            SHARE_FIELDS
+        );
+
+        let shares = sqlx::query_as::<_, DocumentShare>(&query_str)
+            .bind(document_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(shares)
+    }
+
+    /// Revokes a share of a document. Returns false if the document has no share with this token.
+    pub async fn delete_document_share(&self, document_id: Uuid, token: &str) -> Result<bool> {
+        let result = sqlx::query("DELETE FROM document_shares WHERE document_id = $1 AND token = $2")
+            .bind(document_id)
+            .bind(token)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Finds an unexpired share by its token, together with the document it shares.
+    /// Shares of documents in the trash are not found.
+    pub async fn get_shared_document(&self, token: &str) -> Result<Option<(DocumentShare, Document)>> {
+        let share_query = format!(
+            "SELECT {} FROM document_shares s WHERE s.token = $1 AND s.expires_at > NOW()",
+            SHARE_FIELDS
+        );
+        let Some(share) = sqlx::query_as::<_, DocumentShare>(&share_query)
+            .bind(token)
+            .fetch_optional(&self.pool)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let document_query = format!(
+            "SELECT {} FROM documents WHERE id = $1 AND deleted_at IS NULL",
+            DOCUMENT_FIELDS
+        );
+        let row = sqlx::query(&document_query)
+            .bind(share.document_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| (share, map_row_to_document(&row))))
+    }
+
+    pub async fn record_document_share_access(&self, share_id: Uuid, client_ip: &str, downloaded: bool) -> Result<()> {
+        sqlx::query("INSERT INTO document_share_accesses (share_id, client_ip, downloaded) VALUES ($1, $2, $3)")
+            .bind(share_id)
+            .bind(client_ip)
+            .bind(downloaded)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
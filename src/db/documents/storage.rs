@@ -0,0 +1,96 @@
+use anyhow::Result;
+use sqlx::{Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// A table whose rows point at files in the documents directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoredFileTable {
+    /// Current versions of live documents. Trashed documents stay in the trash directory.
+    Documents,
+    /// Superseded versions of documents
+    DocumentVersions,
+}
+
+impl StoredFileTable {
+    fn select_records(&self) -> &'static str {
+        match self {
+            StoredFileTable::Documents =>
+                "SELECT id, file_path, file_hash FROM documents \
+                 WHERE deleted_at IS NULL AND ($1::uuid IS NULL OR id > $1) ORDER BY id LIMIT $2",
+            StoredFileTable::DocumentVersions =>
+                "SELECT id, file_path, file_hash FROM document_versions \
+                 WHERE ($1::uuid IS NULL OR id > $1) ORDER BY id LIMIT $2",
+        }
+    }
+
+    fn update_path(&self) -> &'static str {
+        match self {
+            StoredFileTable::Documents =>
+                "UPDATE documents SET file_path = $3 WHERE id = $1 AND file_path = $2 AND deleted_at IS NULL",
+            StoredFileTable::DocumentVersions =>
+                "UPDATE document_versions SET file_path = $3 WHERE id = $1 AND file_path = $2",
+        }
+    }
+}
+
+impl std::fmt::Display for StoredFileTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StoredFileTable::Documents => write!(f, "documents"),
+            StoredFileTable::DocumentVersions => write!(f, "document_versions"),
+        }
+    }
+}
+
+/// Where a document or document version's file is stored
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StoredFileRecord {
+    pub id: Uuid,
+    pub file_path: String,
+    pub file_hash: Option<String>,
+}
+
+impl Database {
+    /// Returns up to `limit` rows of `table` ordered by id, starting after `after_id`
+    pub async fn get_stored_file_records(
+        &self,
+        table: StoredFileTable,
+        after_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<StoredFileRecord>> {
+        let records = sqlx::query_as::<_, StoredFileRecord>(table.select_records())
+            .bind(after_id)
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(records)
+    }
+
+    /// Points a row at the new location of its file inside a transaction that the caller
+    /// commits once the file is moved. The row stays locked until then. Returns `None`,
+    /// changing nothing, if the row no longer points at `old_path`.
+    pub async fn begin_stored_file_relocation(
+        &self,
+        table: StoredFileTable,
+        id: Uuid,
+        old_path: &str,
+        new_path: &str,
+    ) -> Result<Option<Transaction<'static, Postgres>>> {
+        let mut tx = self.pool.begin().await?;
+        let result = sqlx::query(table.update_path())
+            .bind(id)
+            .bind(old_path)
+            .bind(new_path)
+            .execute(&mut *tx)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(None);
+        }
+        Ok(Some(tx))
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::models::{Document, DocumentVersion};
+use super::helpers::{map_row_to_document, DOCUMENT_FIELDS};
+use crate::db::Database;
+
+const VERSION_FIELDS: &str = r#"
+    id, document_id, version_number, filename, file_path, file_size, mime_type,
+    file_hash, content, ocr_text, ocr_confidence, ocr_word_count,
+    original_modified_at, created_at, superseded_at
+"#;
+
+impl Database {
+    /// Finds the live document that was synced from `source_path` of a source
+    pub async fn find_document_by_source_path(&self, user_id: Uuid, source_id: Uuid, source_path: &str) -> Result<Option<Document>> {
+        let query_str = format!(
+            r#"
+            SELECT {} FROM documents
+            WHERE user_id = $1 AND source_id = $2 AND source_path = $3 AND deleted_at IS NULL
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+            DOCUMENT_FIELDS
+        );
+
+        let row = sqlx::query(&query_str)
+            .bind(user_id)
+            .bind(source_id)
+            .bind(source_path)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(map_row_to_document))
+    }
+
+    /// Archives the current content of a document as a version and makes `new_version`'s
+    /// file the current one. OCR results are cleared so the new content gets reprocessed.
+    pub async fn create_document_version(&self, document_id: Uuid, new_version: &Document) -> Result<Document> {
+        let mut tx = self.pool.begin().await?;
+
+        // Lock the row so concurrent syncs of the same path get consecutive version numbers
+        sqlx::query("SELECT id FROM documents WHERE id = $1 AND deleted_at IS NULL FOR UPDATE")
+            .bind(document_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO document_versions (
+                document_id, version_number, filename, file_path, file_size, mime_type,
+                file_hash, content, ocr_text, ocr_confidence, ocr_word_count,
+                original_modified_at, created_at
+            )
+            SELECT d.id, d.version_number, d.filename, d.file_path, d.file_size, d.mime_type,
+                   d.file_hash, d.content, d.ocr_text, d.ocr_confidence, d.ocr_word_count,
+                   d.original_modified_at,
+                   COALESCE((SELECT MAX(v.superseded_at) FROM document_versions v WHERE v.document_id = d.id), d.created_at)
+            FROM documents d
+            WHERE d.id = $1
+            "#
+        )
+        .bind(document_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let query_str = format!(
+            r#"
+            UPDATE documents
+            SET version_number = version_number + 1,
+                filename = $2,
+                file_path = $3,
+                file_size = $4,
+                mime_type = $5,
+                file_hash = $6,
+                original_modified_at = $7,
+                source_metadata = $8,
+                content = NULL,
+                ocr_text = NULL,
+                ocr_confidence = NULL,
+                ocr_word_count = NULL,
+                ocr_processing_time_ms = NULL,
+                ocr_status = 'pending',
+                ocr_error = NULL,
+                ocr_completed_at = NULL,
+                ocr_retry_count = NULL,
+                ocr_failure_reason = NULL,
+                ocr_manually_edited = FALSE,
+                perceptual_hash = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING {}
+            "#,
+            DOCUMENT_FIELDS
+        );
+
+        let row = sqlx::query(&query_str)
+            .bind(document_id)
+            .bind(&new_version.filename)
+            .bind(&new_version.file_path)
+            .bind(new_version.file_size)
+            .bind(&new_version.mime_type)
+            .bind(&new_version.file_hash)
+            .bind(new_version.original_modified_at)
+            .bind(&new_version.source_metadata)
+            .fetch_one(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok(map_row_to_document(&row))
+    }
+
+    /// Puts the text and OCR outcome of `previous`, the document as it was before its latest
+    /// version, back on it. For new versions that only change the layout, like rotated pages.
+    pub async fn restore_document_text(&self, document_id: Uuid, previous: &Document, manually_edited: bool) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET content = $2,
+                ocr_text = $3,
+                ocr_confidence = $4,
+                ocr_word_count = $5,
+                ocr_processing_time_ms = $6,
+                ocr_status = $7,
+                ocr_error = $8,
+                ocr_completed_at = $9,
+                ocr_failure_reason = $10,
+                ocr_manually_edited = $11,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .bind(&previous.content)
+        .bind(&previous.ocr_text)
+        .bind(previous.ocr_confidence)
+        .bind(previous.ocr_word_count)
+        .bind(previous.ocr_processing_time_ms)
+        .bind(&previous.ocr_status)
+        .bind(&previous.ocr_error)
+        .bind(previous.ocr_completed_at)
+        .bind(&previous.ocr_failure_reason)
+        .bind(manually_edited)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns the version number of a document's current content
+    pub async fn get_document_version_number(&self, document_id: Uuid) -> Result<i32> {
+        let version_number = sqlx::query_scalar("SELECT version_number FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(version_number)
+    }
+
+    /// Lists the archived versions of a document, newest first
+    pub async fn get_document_versions(&self, document_id: Uuid) -> Result<Vec<DocumentVersion>> {
+        let query_str = format!(
+            "SELECT {} FROM document_versions WHERE document_id = $1 ORDER BY version_number DESC",
+            VERSION_FIELDS
+        );
+
+        let versions = sqlx::query_as::<_, DocumentVersion>(&query_str)
+            .bind(document_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(versions)
+    }
+
+    /// Gets one archived version of a document
+    pub async fn get_document_version(&self, document_id: Uuid, version_number: i32) -> Result<Option<DocumentVersion>> {
+        let query_str = format!(
+            "SELECT {} FROM document_versions WHERE document_id = $1 AND version_number = $2",
+            VERSION_FIELDS
+        );
+
+        let version = sqlx::query_as::<_, DocumentVersion>(&query_str)
+            .bind(document_id)
+            .bind(version_number)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(version)
+    }
+
+    /// Deletes all but the `keep` newest archived versions of a document.
+    /// Returns the removed versions so their files can be deleted.
+    pub async fn prune_document_versions(&self, document_id: Uuid, keep: i64) -> Result<Vec<DocumentVersion>> {
+        let query_str = format!(
+            r#"
+            DELETE FROM document_versions
+            WHERE document_id = $1
+              AND version_number NOT IN (
+                  SELECT version_number FROM document_versions
+                  WHERE document_id = $1
+                  ORDER BY version_number DESC
+                  LIMIT $2
+              )
+            RETURNING {}
+            "#,
+            VERSION_FIELDS
+        );
+
+        let versions = sqlx::query_as::<_, DocumentVersion>(&query_str)
+            .bind(document_id)
+            .bind(keep)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(versions)
+    }
+
+    /// File paths of archived versions belonging to trashed documents that
+    /// `purge_deleted_documents` would remove for the same cutoff
+    pub async fn get_purgeable_version_file_paths(&self, deleted_before: DateTime<Utc>) -> Result<Vec<String>> {
+        let paths = sqlx::query_scalar(
+            r#"
+            SELECT v.file_path
+            FROM document_versions v
+            JOIN documents d ON d.id = v.document_id
+            WHERE d.deleted_at IS NOT NULL AND d.deleted_at < $1
+            "#
+        )
+        .bind(deleted_before)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(paths)
+    }
+}
@@ -0,0 +1,29 @@
+use anyhow::Result;
+
+use super::Database;
+
+impl Database {
+    /// The indexes of `table` among `index_names` that exist in the current schema, in the given order
+    pub async fn get_existing_indexes(&self, table: &str, index_names: &[&str]) -> Result<Vec<String>> {
+        let existing: Vec<String> = sqlx::query_scalar(
+            "SELECT indexname::text FROM pg_indexes WHERE schemaname = current_schema() AND tablename = $1 AND indexname = ANY($2)"
+        )
+        .bind(table)
+        .bind(index_names)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(index_names.iter()
+            .filter(|name| existing.iter().any(|e| e == *name))
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    /// Runs a maintenance statement such as `VACUUM` or `REINDEX ... CONCURRENTLY` on its own.
+    /// These refuse to run inside a transaction block, so the statement is sent as a plain
+    /// query in autocommit mode rather than prepared.
+    pub async fn execute_maintenance_statement(&self, statement: &str) -> Result<()> {
+        sqlx::raw_sql(statement).execute(&self.pool).await?;
+        Ok(())
+    }
+}
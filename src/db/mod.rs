@@ -14,6 +14,17 @@ pub mod images;
 pub mod ignored_files;
 pub mod constraint_validation;
 pub mod ocr_retry;
+pub mod two_factor;
+pub mod password_reset;
+pub mod source_sync_runs;
+pub mod source_validation_reports;
+pub mod s3_objects;
+pub mod ocr_words;
+pub mod batch_jobs;
+pub mod document_events;
+pub mod derived_asset_cache;
+pub mod maintenance;
+pub mod document_runs;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DatabasePoolHealth {
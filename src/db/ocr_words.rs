@@ -0,0 +1,68 @@
+use anyhow::Result;
+use sqlx::types::Json;
+use uuid::Uuid;
+
+use super::Database;
+use crate::ocr::word_boxes::{OcrPageWords, OcrWord};
+
+impl Database {
+    /// Replaces all stored word boxes of a document with `pages`
+    pub async fn replace_document_ocr_words(&self, document_id: Uuid, pages: &[OcrPageWords]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM document_ocr_words WHERE document_id = $1")
+            .bind(document_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for page in pages {
+            sqlx::query(
+                r#"
+                INSERT INTO document_ocr_words (document_id, page, width, height, words)
+                VALUES ($1, $2, $3, $4, $5)
+                "#
+            )
+            .bind(document_id)
+            .bind(page.page)
+            .bind(page.width)
+            .bind(page.height)
+            .bind(Json(&page.words))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` pages of word boxes starting at page `first_page`,
+    /// together with the number of pages stored for the document
+    pub async fn get_document_ocr_words(&self, document_id: Uuid, first_page: i32, limit: i64) -> Result<(Vec<OcrPageWords>, i64)> {
+        let rows: Vec<(i32, i32, i32, Json<Vec<OcrWord>>)> = sqlx::query_as(
+            r#"
+            SELECT page, width, height, words
+            FROM document_ocr_words
+            WHERE document_id = $1 AND page >= $2
+            ORDER BY page
+            LIMIT $3
+            "#
+        )
+        .bind(document_id)
+        .bind(first_page)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total_pages: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM document_ocr_words WHERE document_id = $1")
+            .bind(document_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let pages = rows
+            .into_iter()
+            .map(|(page, width, height, Json(words))| OcrPageWords { page, width, height, words })
+            .collect();
+
+        Ok((pages, total_pages))
+    }
+}
@@ -0,0 +1,89 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::Database;
+
+impl Database {
+    /// Stores a new reset token hash. Any outstanding tokens for the user are invalidated
+    /// so only the most recent email link works.
+    pub async fn create_password_reset_token(
+        &self,
+        user_id: Uuid,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, $3)"
+        )
+        .bind(user_id)
+        .bind(token_hash)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Sets a new password with a valid, unexpired token of a local account and returns the
+    /// account's user id. The token and every other unused token of the user are used up in
+    /// the same transaction, so no reset link outlives the reset.
+    pub async fn reset_password_with_token(&self, token_hash: &str, password: &str) -> Result<Option<Uuid>> {
+        let password_hash = bcrypt::hash(password, 12)?;
+        let mut tx = self.pool.begin().await?;
+
+        let row = sqlx::query(
+            r#"
+            UPDATE password_reset_tokens t SET used_at = NOW()
+            FROM users u
+            WHERE t.token_hash = $1 AND t.used_at IS NULL AND t.expires_at > NOW()
+              AND u.id = t.user_id AND u.auth_provider = 'local'
+            RETURNING t.user_id
+            "#
+        )
+        .bind(token_hash)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(user_id) = row.map(|row| row.get::<Uuid, _>("user_id")) else {
+            return Ok(None);
+        };
+
+        sqlx::query("UPDATE users SET password_hash = $1, updated_at = NOW() WHERE id = $2")
+            .bind(&password_hash)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(Some(user_id))
+    }
+
+    pub async fn invalidate_password_reset_tokens(&self, user_id: Uuid) -> Result<u64> {
+        let result = sqlx::query(
+            "UPDATE password_reset_tokens SET used_at = NOW() WHERE user_id = $1 AND used_at IS NULL"
+        )
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+}
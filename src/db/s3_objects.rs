@@ -0,0 +1,59 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::{CreateS3Object, S3Object};
+
+impl Database {
+    pub async fn list_s3_objects(&self, source_id: Uuid) -> Result<Vec<S3Object>> {
+        let objects = sqlx::query_as::<_, S3Object>(
+            r#"
+            SELECT id, source_id, object_key, etag, last_modified, size, created_at, updated_at
+            FROM s3_objects
+            WHERE source_id = $1
+            "#
+        )
+        .bind(source_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(objects)
+    }
+
+    pub async fn upsert_s3_object(&self, object: &CreateS3Object) -> Result<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO s3_objects (source_id, object_key, etag, last_modified, size)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (source_id, object_key) DO UPDATE SET
+                etag = EXCLUDED.etag,
+                last_modified = EXCLUDED.last_modified,
+                size = EXCLUDED.size,
+                updated_at = NOW()
+            "#
+        )
+        .bind(object.source_id)
+        .bind(&object.object_key)
+        .bind(&object.etag)
+        .bind(object.last_modified)
+        .bind(object.size)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_s3_objects(&self, source_id: Uuid, object_keys: &[String]) -> Result<u64> {
+        if object_keys.is_empty() {
+            return Ok(0);
+        }
+
+        let result = sqlx::query("DELETE FROM s3_objects WHERE source_id = $1 AND object_key = ANY($2)")
+            .bind(source_id)
+            .bind(object_keys)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+}
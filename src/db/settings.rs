@@ -15,6 +15,15 @@ fn parse_jsonb_string_array(value: Value) -> Vec<String> {
     }
 }
 
+// Helper function to parse the stored preprocessing pipeline; one that no longer validates
+// falls back to the default enhancements
+fn parse_preprocessing(value: Option<Value>) -> Option<crate::ocr::preprocessing::PreprocessingConfig> {
+    let value = value?;
+    crate::ocr::preprocessing::PreprocessingConfig::from_value(&value)
+        .map_err(|e| tracing::warn!("Ignoring invalid stored OCR preprocessing pipeline: {}", e))
+        .ok()
+}
+
 // Helper function to create Settings from database row
 fn settings_from_row(row: &sqlx::postgres::PgRow) -> crate::models::Settings {
     let preferred_languages_json: Value = row.get("preferred_languages");
@@ -35,6 +44,7 @@ fn settings_from_row(row: &sqlx::postgres::PgRow) -> crate::models::Settings {
         enable_image_preprocessing: row.get("enable_image_preprocessing"),
         search_results_per_page: row.get("search_results_per_page"),
         search_snippet_length: row.get("search_snippet_length"),
+        search_sort: crate::models::SearchSort::try_from(row.get::<String, _>("search_sort")).unwrap_or_default(),
         fuzzy_search_threshold: row.get("fuzzy_search_threshold"),
         retention_days: row.get("retention_days"),
         enable_auto_cleanup: row.get("enable_auto_cleanup"),
@@ -67,6 +77,16 @@ fn settings_from_row(row: &sqlx::postgres::PgRow) -> crate::models::Settings {
         ocr_quality_threshold_noise: row.get("ocr_quality_threshold_noise"),
         ocr_quality_threshold_sharpness: row.get("ocr_quality_threshold_sharpness"),
         ocr_skip_enhancement: row.get("ocr_skip_enhancement"),
+        ocr_parallel_pages: row.get("ocr_parallel_pages"),
+        ocr_retry_low_confidence: row.get("ocr_retry_low_confidence"),
+        ocr_detect_script: row.get("ocr_detect_script"),
+        ocr_preprocessing: parse_preprocessing(row.get("ocr_preprocessing")),
+        search_clean_snippets: row.get("search_clean_snippets"),
+        ocr_text_layer_min_chars: row.get("ocr_text_layer_min_chars"),
+        sync_notification_mode: crate::models::SyncNotificationMode::try_from(row.get::<String, _>("sync_notification_mode")).unwrap_or_default(),
+        default_source_id: row.get("default_source_id"),
+        default_source_write_back: row.get("default_source_write_back"),
+        extract_archives: row.get("extract_archives"),
         webdav_enabled: row.get("webdav_enabled"),
         webdav_server_url: row.get("webdav_server_url"),
         webdav_username: row.get("webdav_username"),
@@ -99,7 +119,7 @@ impl Database {
                    ocr_morphological_operations, ocr_adaptive_threshold_window_size, ocr_histogram_equalization,
                    ocr_upscale_factor, ocr_max_image_width, ocr_max_image_height, save_processed_images,
                    ocr_quality_threshold_brightness, ocr_quality_threshold_contrast, ocr_quality_threshold_noise,
-                   ocr_quality_threshold_sharpness, ocr_skip_enhancement,
+                   ocr_quality_threshold_sharpness, ocr_skip_enhancement, ocr_parallel_pages, ocr_retry_low_confidence, extract_archives, search_sort, ocr_detect_script, ocr_preprocessing, search_clean_snippets, ocr_text_layer_min_chars, sync_notification_mode, default_source_id, default_source_write_back,
                    webdav_enabled, webdav_server_url, webdav_username, webdav_password,
                    webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
                    created_at, updated_at
@@ -134,7 +154,7 @@ impl Database {
                ocr_morphological_operations, ocr_adaptive_threshold_window_size, ocr_histogram_equalization,
                ocr_upscale_factor, ocr_max_image_width, ocr_max_image_height, save_processed_images,
                ocr_quality_threshold_brightness, ocr_quality_threshold_contrast, ocr_quality_threshold_noise,
-               ocr_quality_threshold_sharpness, ocr_skip_enhancement,
+               ocr_quality_threshold_sharpness, ocr_skip_enhancement, ocr_parallel_pages, ocr_retry_low_confidence, extract_archives, search_sort, ocr_detect_script, ocr_preprocessing, search_clean_snippets, ocr_text_layer_min_chars, sync_notification_mode, default_source_id, default_source_write_back,
                webdav_enabled, webdav_server_url, webdav_username, webdav_password,
                webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
                created_at, updated_at
@@ -179,9 +199,10 @@ impl Database {
                 ocr_quality_threshold_brightness, ocr_quality_threshold_contrast, ocr_quality_threshold_noise,
                 ocr_quality_threshold_sharpness, ocr_skip_enhancement,
                 webdav_enabled, webdav_server_url, webdav_username, webdav_password,
-                webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes
+                webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
+                ocr_parallel_pages, ocr_retry_low_confidence, extract_archives, search_sort, ocr_detect_script, ocr_preprocessing, search_clean_snippets, ocr_text_layer_min_chars, sync_notification_mode, default_source_id, default_source_write_back
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, $37, $38, $39, $40, $41, $42, $43, $44, $45, $46, $47, $48, $49, $50, $51, $52, $53, $54, $55, $56, $57, $58, $59, $60, $61, $62, $63, $64)
             ON CONFLICT (user_id) DO UPDATE SET
                 ocr_language = $2,
                 preferred_languages = $3,
@@ -227,6 +248,17 @@ impl Database {
                 ocr_quality_threshold_noise = $43,
                 ocr_quality_threshold_sharpness = $44,
                 ocr_skip_enhancement = $45,
+                ocr_parallel_pages = $54,
+                ocr_retry_low_confidence = $55,
+                extract_archives = $56,
+                search_sort = $57,
+                ocr_detect_script = $58,
+                ocr_preprocessing = $59,
+                search_clean_snippets = $60,
+                ocr_text_layer_min_chars = $61,
+                sync_notification_mode = $62,
+                default_source_id = $63,
+                default_source_write_back = $64,
                 webdav_enabled = $46,
                 webdav_server_url = $47,
                 webdav_username = $48,
@@ -251,7 +283,7 @@ impl Database {
                       ocr_morphological_operations, ocr_adaptive_threshold_window_size, ocr_histogram_equalization,
                       ocr_upscale_factor, ocr_max_image_width, ocr_max_image_height, save_processed_images,
                       ocr_quality_threshold_brightness, ocr_quality_threshold_contrast, ocr_quality_threshold_noise,
-                      ocr_quality_threshold_sharpness, ocr_skip_enhancement,
+                      ocr_quality_threshold_sharpness, ocr_skip_enhancement, ocr_parallel_pages, ocr_retry_low_confidence, extract_archives, search_sort, ocr_detect_script, ocr_preprocessing, search_clean_snippets, ocr_text_layer_min_chars, sync_notification_mode, default_source_id, default_source_write_back,
                       webdav_enabled, webdav_server_url, webdav_username, webdav_password,
                       webdav_watch_folders, webdav_file_extensions, webdav_auto_sync, webdav_sync_interval_minutes,
                       created_at, updated_at
@@ -310,6 +342,17 @@ impl Database {
         .bind(settings.webdav_file_extensions.as_ref().unwrap_or(&current.webdav_file_extensions))
         .bind(settings.webdav_auto_sync.unwrap_or(current.webdav_auto_sync))
         .bind(settings.webdav_sync_interval_minutes.unwrap_or(current.webdav_sync_interval_minutes))
+        .bind(settings.ocr_parallel_pages.unwrap_or(current.ocr_parallel_pages))
+        .bind(settings.ocr_retry_low_confidence.unwrap_or(current.ocr_retry_low_confidence))
+        .bind(settings.extract_archives.unwrap_or(current.extract_archives))
+        .bind(settings.search_sort.unwrap_or(current.search_sort).to_string())
+        .bind(settings.ocr_detect_script.unwrap_or(current.ocr_detect_script))
+        .bind(settings.ocr_preprocessing.as_ref().unwrap_or(&current.ocr_preprocessing).as_ref().map(sqlx::types::Json))
+        .bind(settings.search_clean_snippets.unwrap_or(current.search_clean_snippets))
+        .bind(settings.ocr_text_layer_min_chars.unwrap_or(current.ocr_text_layer_min_chars))
+        .bind(settings.sync_notification_mode.unwrap_or(current.sync_notification_mode).to_string())
+        .bind(settings.default_source_id.unwrap_or(current.default_source_id))
+        .bind(settings.default_source_write_back.unwrap_or(current.default_source_write_back))
         .fetch_one(&self.pool)
         .await?;
 
@@ -0,0 +1,166 @@
+use anyhow::Result;
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::{SourceSyncRun, SyncRunOutcome};
+
+/// Counters collected over the course of a single sync run
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncRunCounts {
+    pub files_discovered: i64,
+    pub files_downloaded: i64,
+    pub files_failed: i64,
+    pub bytes_transferred: i64,
+}
+
+/// Lifetime totals for a source, used by the Prometheus exporter
+#[derive(Debug, Clone)]
+pub struct SourceSyncTotals {
+    pub source_id: Uuid,
+    pub source_name: String,
+    pub files_synced_total: i64,
+    pub bytes_transferred_total: i64,
+    pub last_run_duration_seconds: Option<f64>,
+}
+
+impl Database {
+    pub async fn create_source_sync_run(&self, source_id: Uuid) -> Result<Uuid> {
+        let row = sqlx::query(
+            "INSERT INTO source_sync_runs (source_id, outcome) VALUES ($1, 'running') RETURNING id"
+        )
+        .bind(source_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.get("id"))
+    }
+
+    /// Closes out a run. Runs that were already finished are left untouched.
+    pub async fn finish_source_sync_run(
+        &self,
+        run_id: Uuid,
+        counts: SyncRunCounts,
+        outcome: SyncRunOutcome,
+        error_message: Option<&str>,
+    ) -> Result<()> {
+        sqlx::query(
+            r#"
+            UPDATE source_sync_runs
+            SET finished_at = NOW(), files_discovered = $2, files_downloaded = $3,
+                files_failed = $4, bytes_transferred = $5, outcome = $6, error_message = $7
+            WHERE id = $1 AND outcome = 'running'
+            "#
+        )
+        .bind(run_id)
+        .bind(counts.files_discovered)
+        .bind(counts.files_downloaded)
+        .bind(counts.files_failed)
+        .bind(counts.bytes_transferred)
+        .bind(outcome.to_string())
+        .bind(error_message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Marks runs that never finished (timed out, or interrupted by a restart) as failed.
+    /// When `source_id` is `None` every unfinished run is closed.
+    pub async fn fail_unfinished_source_sync_runs(&self, source_id: Option<Uuid>, error_message: &str) -> Result<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE source_sync_runs
+            SET finished_at = NOW(), outcome = 'failed', error_message = $2
+            WHERE outcome = 'running' AND ($1::uuid IS NULL OR source_id = $1)
+            "#
+        )
+        .bind(source_id)
+        .bind(error_message)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn get_source_sync_runs(&self, source_id: Uuid, limit: i64) -> Result<Vec<SourceSyncRun>> {
+        let runs = sqlx::query_as::<_, SourceSyncRun>(
+            r#"
+            SELECT id, source_id, started_at, finished_at, files_discovered, files_downloaded,
+                   files_failed, bytes_transferred, outcome, error_message
+            FROM source_sync_runs
+            WHERE source_id = $1
+            ORDER BY started_at DESC
+            LIMIT $2
+            "#
+        )
+        .bind(source_id)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(runs)
+    }
+
+    pub async fn get_latest_source_sync_run(&self, source_id: Uuid) -> Result<Option<SourceSyncRun>> {
+        Ok(self.get_source_sync_runs(source_id, 1).await?.into_iter().next())
+    }
+
+    /// Documents a run added, oldest first, as `(id, original filename)`
+    pub async fn get_sync_run_documents(&self, run: &SourceSyncRun, limit: i64) -> Result<Vec<(Uuid, String)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, original_filename
+            FROM documents
+            WHERE source_id = $1 AND deleted_at IS NULL
+              AND created_at >= $2 AND created_at <= COALESCE($3, NOW())
+            ORDER BY created_at
+            LIMIT $4
+            "#
+        )
+        .bind(run.source_id)
+        .bind(run.started_at)
+        .bind(run.finished_at)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|row| (row.get("id"), row.get("original_filename"))).collect())
+    }
+
+    pub async fn get_source_sync_totals(&self) -> Result<Vec<SourceSyncTotals>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                s.id AS source_id,
+                s.name AS source_name,
+                COALESCE(SUM(r.files_downloaded), 0)::BIGINT AS files_synced_total,
+                COALESCE(SUM(r.bytes_transferred), 0)::BIGINT AS bytes_transferred_total,
+                (
+                    SELECT EXTRACT(EPOCH FROM (last.finished_at - last.started_at))::DOUBLE PRECISION
+                    FROM source_sync_runs last
+                    WHERE last.source_id = s.id AND last.finished_at IS NOT NULL
+                    ORDER BY last.finished_at DESC
+                    LIMIT 1
+                ) AS last_run_duration_seconds
+            FROM sources s
+            LEFT JOIN source_sync_runs r ON r.source_id = s.id
+            GROUP BY s.id, s.name
+            ORDER BY s.name
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| SourceSyncTotals {
+                source_id: row.get("source_id"),
+                source_name: row.get("source_name"),
+                files_synced_total: row.get("files_synced_total"),
+                bytes_transferred_total: row.get("bytes_transferred_total"),
+                last_run_duration_seconds: row.get("last_run_duration_seconds"),
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use super::Database;
+use crate::models::SourceValidationReport;
+
+impl Database {
+    /// Replaces the stored validation of a source with a new one
+    pub async fn save_source_validation_report(
+        &self,
+        source_id: Uuid,
+        health_score: i32,
+        report: &serde_json::Value,
+        deep_scan_pending: bool,
+    ) -> Result<SourceValidationReport> {
+        let saved = sqlx::query_as::<_, SourceValidationReport>(
+            r#"
+            INSERT INTO source_validation_reports (source_id, health_score, report, deep_scan_pending, validated_at)
+            VALUES ($1, $2, $3, $4, NOW())
+            ON CONFLICT (source_id) DO UPDATE
+            SET health_score = EXCLUDED.health_score,
+                report = EXCLUDED.report,
+                -- A deep scan requested earlier stays queued until a sync has run it
+                deep_scan_pending = source_validation_reports.deep_scan_pending OR EXCLUDED.deep_scan_pending,
+                validated_at = EXCLUDED.validated_at
+            RETURNING source_id, health_score, report, deep_scan_pending, validated_at
+            "#
+        )
+        .bind(source_id)
+        .bind(health_score)
+        .bind(report)
+        .bind(deep_scan_pending)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(saved)
+    }
+
+    pub async fn get_source_validation_report(&self, source_id: Uuid) -> Result<Option<SourceValidationReport>> {
+        let report = sqlx::query_as::<_, SourceValidationReport>(
+            r#"
+            SELECT source_id, health_score, report, deep_scan_pending, validated_at
+            FROM source_validation_reports
+            WHERE source_id = $1
+            "#
+        )
+        .bind(source_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(report)
+    }
+
+    /// Called once a sync has run the deep scan the last validation asked for
+    pub async fn clear_source_deep_scan_pending(&self, source_id: Uuid) -> Result<()> {
+        sqlx::query("UPDATE source_validation_reports SET deep_scan_pending = false WHERE source_id = $1")
+            .bind(source_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
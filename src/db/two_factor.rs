@@ -0,0 +1,109 @@
+use anyhow::Result;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::models::UserTotpState;
+use super::Database;
+
+impl Database {
+    pub async fn get_user_totp_state(&self, user_id: Uuid) -> Result<Option<UserTotpState>> {
+        let row = sqlx::query("SELECT totp_secret, totp_enabled FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| UserTotpState {
+            secret: row.get("totp_secret"),
+            enabled: row.get("totp_enabled"),
+        }))
+    }
+
+    /// Stores a pending TOTP secret. 2FA stays disabled until a code is verified.
+    pub async fn set_user_totp_secret(&self, user_id: Uuid, secret: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE users SET totp_secret = $1, totp_enabled = FALSE, updated_at = NOW() WHERE id = $2"
+        )
+        .bind(secret)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Enables 2FA and replaces any existing recovery codes with the given hashes
+    pub async fn enable_user_totp(&self, user_id: Uuid, recovery_code_hashes: &[String]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE users SET totp_enabled = TRUE, updated_at = NOW() WHERE id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for code_hash in recovery_code_hashes {
+            sqlx::query("INSERT INTO user_recovery_codes (user_id, code_hash) VALUES ($1, $2)")
+                .bind(user_id)
+                .bind(code_hash)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn disable_user_totp(&self, user_id: Uuid) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE users SET totp_secret = NULL, totp_enabled = FALSE, updated_at = NOW() WHERE id = $1"
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("DELETE FROM user_recovery_codes WHERE user_id = $1")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Records that a TOTP code of `step` was accepted. Returns false if a code of this or a
+    /// later step was accepted before, so each code can only be used once.
+    pub async fn record_totp_step(&self, user_id: Uuid, step: i64) -> Result<bool> {
+        let result = sqlx::query(
+            "UPDATE users SET totp_last_used_step = $1 \
+             WHERE id = $2 AND (totp_last_used_step IS NULL OR totp_last_used_step < $1)"
+        )
+        .bind(step)
+        .bind(user_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Marks a recovery code as used. Returns false if the code is unknown or already used.
+    pub async fn consume_recovery_code(&self, user_id: Uuid, code_hash: &str) -> Result<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE user_recovery_codes SET used_at = NOW()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+            "#
+        )
+        .bind(user_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
@@ -71,6 +71,33 @@ impl Database {
         }
     }
 
+    pub async fn get_user_by_email(&self, email: &str) -> Result<Option<User>> {
+        let row = sqlx::query(
+            "SELECT id, username, email, password_hash, role, created_at, updated_at,
+             oidc_subject, oidc_issuer, oidc_email, auth_provider FROM users WHERE LOWER(email) = LOWER($1)"
+        )
+        .bind(email)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match row {
+            Some(row) => Ok(Some(User {
+                id: row.get("id"),
+                username: row.get("username"),
+                email: row.get("email"),
+                password_hash: row.get("password_hash"),
+                role: row.get::<String, _>("role").try_into().unwrap_or(crate::models::UserRole::User),
+                created_at: row.get("created_at"),
+                updated_at: row.get("updated_at"),
+                oidc_subject: row.get("oidc_subject"),
+                oidc_issuer: row.get("oidc_issuer"),
+                oidc_email: row.get("oidc_email"),
+                auth_provider: row.get::<String, _>("auth_provider").try_into().unwrap_or(AuthProvider::Local),
+            })),
+            None => Ok(None),
+        }
+    }
+
     pub async fn get_user_by_id(&self, id: Uuid) -> Result<Option<User>> {
         let row = sqlx::query(
             "SELECT id, username, email, password_hash, role, created_at, updated_at,
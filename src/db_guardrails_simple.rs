@@ -92,6 +92,7 @@ impl DocumentTransactionManager {
                 ocr_confidence = $3,
                 ocr_word_count = $4,
                 ocr_processing_time_ms = $5,
+                ocr_manually_edited = FALSE,
                 updated_at = NOW()
             WHERE id = $1 
               AND ocr_status != 'completed'  -- Extra safety check
@@ -111,12 +112,13 @@ impl DocumentTransactionManager {
             return Ok(false);
         }
 
-        // 6. Remove from OCR queue atomically
+        // 6. Remove from OCR queue atomically; a success also clears the document out of
+        // the dead-letter queue
         let _queue_removed = sqlx::query(
             r#"
             DELETE FROM ocr_queue 
             WHERE document_id = $1 
-              AND status = 'processing'
+              AND status IN ('processing', 'dead_letter')
             "#
         )
         .bind(document_id)
@@ -51,6 +51,15 @@ pub enum LabelError {
     
     #[error("Label '{name}' is reserved and cannot be created")]
     ReservedName { name: String },
+    
+    #[error("Invalid search: {reason}")]
+    InvalidSearch { reason: String },
+    
+    #[error("Search matches more than {max_documents} documents")]
+    TooManyDocuments { max_documents: i64 },
+    
+    #[error("Internal error: {message}")]
+    InternalError { message: String },
 }
 
 impl AppError for LabelError {
@@ -70,6 +79,9 @@ impl AppError for LabelError {
             LabelError::DeleteRestricted { .. } => StatusCode::CONFLICT,
             LabelError::InvalidAssignment { .. } => StatusCode::BAD_REQUEST,
             LabelError::ReservedName { .. } => StatusCode::CONFLICT,
+            LabelError::InvalidSearch { .. } => StatusCode::BAD_REQUEST,
+            LabelError::TooManyDocuments { .. } => StatusCode::BAD_REQUEST,
+            LabelError::InternalError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
     
@@ -89,6 +101,9 @@ impl AppError for LabelError {
             LabelError::DeleteRestricted { reason } => format!("Cannot delete label: {}", reason),
             LabelError::InvalidAssignment { reason, .. } => format!("Invalid label assignment: {}", reason),
             LabelError::ReservedName { .. } => "Label name is reserved and cannot be used".to_string(),
+            LabelError::InvalidSearch { reason } => format!("Invalid search: {}", reason),
+            LabelError::TooManyDocuments { max_documents } => format!("The search matches more than {} documents", max_documents),
+            LabelError::InternalError { .. } => "An internal error occurred".to_string(),
         }
     }
     
@@ -109,6 +124,9 @@ impl AppError for LabelError {
             LabelError::DeleteRestricted { .. } => "LABEL_DELETE_RESTRICTED",
             LabelError::InvalidAssignment { .. } => "LABEL_INVALID_ASSIGNMENT",
             LabelError::ReservedName { .. } => "LABEL_RESERVED_NAME",
+            LabelError::InvalidSearch { .. } => "LABEL_INVALID_SEARCH",
+            LabelError::TooManyDocuments { .. } => "LABEL_TOO_MANY_DOCUMENTS",
+            LabelError::InternalError { .. } => "LABEL_INTERNAL_ERROR",
         }
     }
     
@@ -127,6 +145,7 @@ impl AppError for LabelError {
             LabelError::NotFound 
             | LabelError::DuplicateName { .. } 
             | LabelError::LabelInUse { .. } => ErrorSeverity::Expected,
+            LabelError::InternalError { .. } => ErrorSeverity::Critical,
             _ => ErrorSeverity::Minor,
         }
     }
@@ -148,6 +167,7 @@ impl AppError for LabelError {
             LabelError::ColorConflict { .. } => Some("Choose colors with better contrast for readability".to_string()),
             LabelError::DescriptionTooLong { max_length, .. } => Some(format!("Shorten description to {} characters or less", max_length)),
             LabelError::ReservedName { .. } => Some("Choose a different name that is not reserved by the system".to_string()),
+            LabelError::TooManyDocuments { .. } => Some("Narrow the search with more terms or filters and label the parts one at a time".to_string()),
             LabelError::InvalidIcon { supported_icons, .. } => Some(format!("Use one of the supported icons: {}", supported_icons)),
             _ => None,
         }
@@ -225,4 +245,16 @@ impl LabelError {
     pub fn reserved_name<S: Into<String>>(name: S) -> Self {
         Self::ReservedName { name: name.into() }
     }
+    
+    pub fn invalid_search<S: Into<String>>(reason: S) -> Self {
+        Self::InvalidSearch { reason: reason.into() }
+    }
+    
+    pub fn too_many_documents(max_documents: i64) -> Self {
+        Self::TooManyDocuments { max_documents }
+    }
+    
+    pub fn internal_error<S: Into<String>>(message: S) -> Self {
+        Self::InternalError { message: message.into() }
+    }
 }
\ No newline at end of file
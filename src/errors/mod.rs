@@ -47,6 +47,7 @@ pub trait AppError: std::error::Error + Send + Sync + 'static {
             technical_details: self.to_string(),
             suggested_action: self.suggested_action(),
             suppression_key: self.suppression_key(),
+            request_id: crate::monitoring::request_id::current_request_id(),
         }
     }
 }
@@ -65,7 +66,8 @@ macro_rules! impl_into_response {
                 // Send error to management system
                 let error_manager = get_error_manager();
                 let managed_error = self.to_managed_error();
-                tokio::spawn(async move {
+                let request_id = managed_error.request_id.clone();
+                crate::monitoring::request_id::spawn_with_request_id(async move {
                     error_manager.handle_error(managed_error).await;
                 });
                 
@@ -74,7 +76,8 @@ macro_rules! impl_into_response {
                 let body = Json(json!({
                     "error": self.user_message(),
                     "code": self.error_code(),
-                    "status": status.as_u16()
+                    "status": status.as_u16(),
+                    "request_id": request_id
                 }));
                 
                 (status, body).into_response()
@@ -66,6 +66,9 @@ pub enum SettingsError {
     
     #[error("Invalid search configuration: {details}")]
     InvalidSearchConfiguration { details: String },
+    
+    #[error("Internal server error: {message}")]
+    InternalServerError { message: String },
 }
 
 impl AppError for SettingsError {
@@ -90,13 +93,16 @@ impl AppError for SettingsError {
             SettingsError::PermissionDenied { .. } => StatusCode::FORBIDDEN,
             SettingsError::SystemSettingsReset => StatusCode::FORBIDDEN,
             SettingsError::InvalidSearchConfiguration { .. } => StatusCode::BAD_REQUEST,
+            SettingsError::InternalServerError { .. } => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
     
     fn user_message(&self) -> String {
         match self {
             SettingsError::NotFound | SettingsError::NotFoundForUser { .. } => "Settings not found".to_string(),
-            SettingsError::InvalidLanguage { .. } => "Invalid language specified".to_string(),
+            SettingsError::InvalidLanguage { language, available_languages } => {
+                format!("Language '{}' is not installed. Installed languages: {}", language, available_languages)
+            }
             SettingsError::InvalidValue { setting_name, .. } => format!("Invalid value for {}", setting_name),
             SettingsError::ReadOnlySetting { setting_name } => format!("Setting '{}' cannot be modified", setting_name),
             SettingsError::ValidationFailed { setting_name, reason } => format!("Validation failed for {}: {}", setting_name, reason),
@@ -114,6 +120,7 @@ impl AppError for SettingsError {
             SettingsError::PermissionDenied { reason } => format!("Permission denied: {}", reason),
             SettingsError::SystemSettingsReset => "System settings cannot be reset".to_string(),
             SettingsError::InvalidSearchConfiguration { .. } => "Invalid search configuration".to_string(),
+            SettingsError::InternalServerError { .. } => "An internal error occurred".to_string(),
         }
     }
     
@@ -139,6 +146,7 @@ impl AppError for SettingsError {
             SettingsError::PermissionDenied { .. } => "SETTINGS_PERMISSION_DENIED",
             SettingsError::SystemSettingsReset => "SETTINGS_SYSTEM_RESET_DENIED",
             SettingsError::InvalidSearchConfiguration { .. } => "SETTINGS_INVALID_SEARCH_CONFIG",
+            SettingsError::InternalServerError { .. } => "SETTINGS_INTERNAL_ERROR",
         }
     }
     
@@ -146,12 +154,14 @@ impl AppError for SettingsError {
         match self {
             SettingsError::PermissionDenied { .. } | SettingsError::SystemSettingsReset => ErrorCategory::Auth,
             SettingsError::InvalidOcrConfiguration { .. } => ErrorCategory::OcrProcessing,
+            SettingsError::InternalServerError { .. } => ErrorCategory::Database,
             _ => ErrorCategory::Config,
         }
     }
     
     fn error_severity(&self) -> ErrorSeverity {
         match self {
+            SettingsError::InternalServerError { .. } => ErrorSeverity::Critical,
             SettingsError::ReadOnlySetting { .. } 
             | SettingsError::PermissionDenied { .. } 
             | SettingsError::SystemSettingsReset => ErrorSeverity::Important,
@@ -287,4 +297,8 @@ impl SettingsError {
     pub fn invalid_search_configuration<S: Into<String>>(details: S) -> Self {
         Self::InvalidSearchConfiguration { details: details.into() }
     }
+    
+    pub fn internal_server_error<S: Into<String>>(message: S) -> Self {
+        Self::InternalServerError { message: message.into() }
+    }
 }
\ No newline at end of file
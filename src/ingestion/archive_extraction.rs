@@ -0,0 +1,278 @@
+/*!
+ * Archive Extraction
+ *
+ * Unpacks uploaded ZIP archives so that every file inside can be ingested as a
+ * document of its own. Archives are untrusted input: the number of entries and
+ * the total extracted size are capped to defuse zip bombs, sizes are counted
+ * while entries are inflated instead of being taken from the archive headers,
+ * and an entry whose path would escape the archive rejects the whole upload.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use utoipa::ToSchema;
+
+use crate::config::Config;
+
+/// Most entries (files and directories) an archive may contain
+pub const MAX_ARCHIVE_ENTRIES: usize = 1000;
+
+/// An archive may expand to at most this many times the maximum upload size
+pub const MAX_ARCHIVE_EXPANSION: u64 = 10;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    /// Entries larger than this are skipped, like an upload of that size would be refused
+    pub max_entry_bytes: u64,
+    /// Extraction stops and the archive is rejected once this many bytes were written
+    pub max_total_bytes: u64,
+}
+
+impl ArchiveLimits {
+    pub fn from_config(config: &Config) -> Self {
+        let max_entry_bytes = config.max_file_size_mb * 1024 * 1024;
+        Self {
+            max_entries: MAX_ARCHIVE_ENTRIES,
+            max_entry_bytes,
+            max_total_bytes: max_entry_bytes.saturating_mul(MAX_ARCHIVE_EXPANSION),
+        }
+    }
+}
+
+/// Why an archive was rejected as a whole
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Archive could not be read: {0}")]
+    Invalid(String),
+
+    #[error("Archive contains {count} entries, more than the {max} allowed")]
+    TooManyEntries { count: usize, max: usize },
+
+    #[error("Archive expands to more than the {max_bytes} bytes allowed")]
+    TooLarge { max_bytes: u64 },
+
+    #[error("Archive entry '{path}' has an unsafe path")]
+    UnsafePath { path: String },
+
+    #[error("Failed to extract archive: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A file written out of the archive
+#[derive(Debug, Clone)]
+pub struct ExtractedEntry {
+    /// Path of the entry inside the archive, e.g. `2024/invoices/march.pdf`
+    pub archive_path: String,
+    pub file_name: String,
+    /// Where the content was written. Named by position, never by the entry's own path.
+    pub extracted_path: PathBuf,
+    pub size: u64,
+}
+
+/// An entry that was left out, with the reason
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct SkippedArchiveEntry {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Default)]
+pub struct ArchiveExtraction {
+    pub entries: Vec<ExtractedEntry>,
+    pub skipped: Vec<SkippedArchiveEntry>,
+}
+
+pub fn is_zip_archive(mime_type: &str) -> bool {
+    mime_type == "application/zip"
+}
+
+/// Extracts the files of a ZIP archive into `output_dir`, which must exist. Directories,
+/// symbolic links and macOS resource forks are not extracted; entries that are too large,
+/// encrypted or corrupt are reported as skipped. This is blocking and should run on a
+/// blocking thread.
+pub fn extract_zip(archive_path: &Path, output_dir: &Path, limits: &ArchiveLimits) -> Result<ArchiveExtraction, ArchiveError> {
+    let invalid = |e: zip::result::ZipError| ArchiveError::Invalid(e.to_string());
+    let mut archive = zip::ZipArchive::new(File::open(archive_path)?).map_err(invalid)?;
+
+    if archive.len() > limits.max_entries {
+        return Err(ArchiveError::TooManyEntries { count: archive.len(), max: limits.max_entries });
+    }
+
+    // Check every path and the declared sizes before anything is written
+    let mut declared_bytes: u64 = 0;
+    let mut names = Vec::with_capacity(archive.len());
+    for index in 0..archive.len() {
+        let entry = archive.by_index_raw(index).map_err(invalid)?;
+        if entry.enclosed_name().is_none() {
+            return Err(ArchiveError::UnsafePath { path: entry.name().to_string() });
+        }
+        declared_bytes = declared_bytes.saturating_add(entry.size());
+        names.push(entry.name().to_string());
+    }
+    if declared_bytes > limits.max_total_bytes {
+        return Err(ArchiveError::TooLarge { max_bytes: limits.max_total_bytes });
+    }
+
+    let mut extraction = ArchiveExtraction::default();
+    let mut extracted_bytes: u64 = 0;
+    for (index, name) in names.into_iter().enumerate() {
+        let skip = |reason: &str| SkippedArchiveEntry { path: name.clone(), reason: reason.to_string() };
+
+        let mut entry = match archive.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                extraction.skipped.push(skip(&format!("could not be opened: {}", e)));
+                continue;
+            }
+        };
+        if entry.is_dir() || is_resource_fork(&name) {
+            continue;
+        }
+        if entry.is_symlink() {
+            extraction.skipped.push(skip("symbolic links are not extracted"));
+            continue;
+        }
+        if entry.size() > limits.max_entry_bytes {
+            extraction.skipped.push(skip("larger than the maximum upload size"));
+            continue;
+        }
+
+        let extracted_path = output_dir.join(format!("entry-{}", index));
+        let mut output = File::create(&extracted_path)?;
+        // Read one byte past the limit so an entry that understates its size is noticed
+        let remaining = limits.max_total_bytes - extracted_bytes;
+        let written = match std::io::copy(&mut (&mut entry).take(limits.max_entry_bytes.min(remaining) + 1), &mut output) {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = std::fs::remove_file(&extracted_path);
+                extraction.skipped.push(skip(&format!("could not be decompressed: {}", e)));
+                continue;
+            }
+        };
+
+        if written > remaining {
+            return Err(ArchiveError::TooLarge { max_bytes: limits.max_total_bytes });
+        }
+        if written > limits.max_entry_bytes {
+            let _ = std::fs::remove_file(&extracted_path);
+            extraction.skipped.push(skip("larger than the maximum upload size"));
+            continue;
+        }
+        extracted_bytes += written;
+
+        let file_name = Path::new(&name)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().to_string())
+            .unwrap_or_else(|| name.clone());
+        extraction.entries.push(ExtractedEntry {
+            archive_path: name,
+            file_name,
+            extracted_path,
+            size: written,
+        });
+    }
+
+    Ok(extraction)
+}
+
+/// Metadata macOS adds to archives it creates
+fn is_resource_fork(path: &str) -> bool {
+    path.starts_with("__MACOSX/") || Path::new(path).file_name().is_some_and(|name| name == ".DS_Store")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    const LIMITS: ArchiveLimits = ArchiveLimits { max_entries: 10, max_entry_bytes: 1024, max_total_bytes: 4096 };
+
+    fn write_zip(path: &Path, entries: &[(&str, Vec<u8>)]) {
+        let mut writer = zip::ZipWriter::new(File::create(path).unwrap());
+        for (name, content) in entries {
+            if name.ends_with('/') {
+                writer.add_directory(*name, SimpleFileOptions::default()).unwrap();
+            } else {
+                writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+                writer.write_all(content).unwrap();
+            }
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extracts_files_and_skips_oversized_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("scans.zip");
+        write_zip(&archive, &[
+            ("invoice.pdf", b"%PDF-1.4 invoice".to_vec()),
+            ("2024/", Vec::new()),
+            ("2024/notes.txt", b"notes".to_vec()),
+            ("__MACOSX/2024/._notes.txt", b"fork".to_vec()),
+            ("huge.txt", vec![b'a'; 2048]),
+        ]);
+        let output = dir.path().join("out");
+        std::fs::create_dir(&output).unwrap();
+
+        let extraction = extract_zip(&archive, &output, &LIMITS).unwrap();
+
+        let paths: Vec<&str> = extraction.entries.iter().map(|entry| entry.archive_path.as_str()).collect();
+        assert_eq!(paths, vec!["invoice.pdf", "2024/notes.txt"]);
+        assert_eq!(extraction.entries[1].file_name, "notes.txt");
+        assert_eq!(std::fs::read(&extraction.entries[1].extracted_path).unwrap(), b"notes");
+        assert!(extraction.entries.iter().all(|entry| entry.extracted_path.starts_with(&output)));
+        assert_eq!(extraction.skipped, vec![SkippedArchiveEntry {
+            path: "huge.txt".to_string(),
+            reason: "larger than the maximum upload size".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn test_rejects_path_traversal() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("evil.zip");
+        write_zip(&archive, &[("ok.txt", b"fine".to_vec()), ("../../etc/cron.d/evil", b"* * * * *".to_vec())]);
+
+        let error = extract_zip(&archive, dir.path(), &LIMITS).unwrap_err();
+        assert!(matches!(error, ArchiveError::UnsafePath { ref path } if path == "../../etc/cron.d/evil"));
+        assert!(!dir.path().join("entry-0").exists(), "nothing is extracted from a rejected archive");
+    }
+
+    #[test]
+    fn test_rejects_too_many_entries_and_too_much_content() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let crowded = dir.path().join("crowded.zip");
+        let entries: Vec<(String, Vec<u8>)> = (0..11).map(|i| (format!("{}.txt", i), b"x".to_vec())).collect();
+        let entries: Vec<(&str, Vec<u8>)> = entries.iter().map(|(name, content)| (name.as_str(), content.clone())).collect();
+        write_zip(&crowded, &entries);
+        assert!(matches!(
+            extract_zip(&crowded, dir.path(), &LIMITS).unwrap_err(),
+            ArchiveError::TooManyEntries { count: 11, max: 10 }
+        ));
+
+        // Highly compressible content: a small archive that expands past the total limit
+        let bomb = dir.path().join("bomb.zip");
+        let entries: Vec<(String, Vec<u8>)> = (0..5).map(|i| (format!("{}.txt", i), vec![0u8; 1000])).collect();
+        let entries: Vec<(&str, Vec<u8>)> = entries.iter().map(|(name, content)| (name.as_str(), content.clone())).collect();
+        write_zip(&bomb, &entries);
+        assert!(std::fs::metadata(&bomb).unwrap().len() < 4096);
+        assert!(matches!(
+            extract_zip(&bomb, dir.path(), &LIMITS).unwrap_err(),
+            ArchiveError::TooLarge { max_bytes: 4096 }
+        ));
+    }
+
+    #[test]
+    fn test_rejects_files_that_are_not_zip_archives() {
+        let dir = tempfile::tempdir().unwrap();
+        let not_zip = dir.path().join("fake.zip");
+        std::fs::write(&not_zip, b"plain text").unwrap();
+        assert!(matches!(extract_zip(&not_zip, dir.path(), &LIMITS).unwrap_err(), ArchiveError::Invalid(_)));
+    }
+}
@@ -15,6 +15,7 @@ use crate::{
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult, DeduplicationPolicy},
     ocr::queue::OcrQueueService,
     models::FileIngestionInfo,
+    metadata_extraction::MetadataExtractionOptions,
 };
 
 pub struct BatchIngester {
@@ -82,10 +83,11 @@ impl BatchIngester {
             
             // Process file asynchronously
             let db_clone = self.db.clone();
+            let metadata_options = MetadataExtractionOptions::from_config(&self.config);
             let handle = tokio::spawn(async move {
                 let permit = semaphore_clone.acquire().await.unwrap();
                 let _permit = permit;
-                process_single_file(path_clone, file_service, user_id_clone, db_clone).await
+                process_single_file(path_clone, file_service, user_id_clone, db_clone, metadata_options).await
             });
             
             batch.push(handle);
@@ -232,6 +234,7 @@ async fn process_single_file(
     file_service: FileService,
     user_id: Uuid,
     db: Database,
+    metadata_options: MetadataExtractionOptions,
 ) -> Result<Option<(Uuid, i64)>> {
     // Extract basic file info first
     let mut file_info = extract_file_info_from_path(&path).await?;
@@ -246,9 +249,7 @@ async fn process_single_file(
     let file_data = fs::read(&path).await?;
     
     // Extract content-based metadata
-    if let Ok(Some(content_metadata)) = crate::metadata_extraction::extract_content_metadata(&file_data, &file_info.mime_type, &file_info.name).await {
-        file_info.metadata = Some(content_metadata);
-    }
+    crate::metadata_extraction::enrich_file_info(&mut file_info, &file_data, metadata_options).await;
     
     // Use the unified ingestion service with full metadata support
     let ingestion_service = DocumentIngestionService::new(db, file_service);
@@ -259,7 +260,7 @@ async fn process_single_file(
         .map_err(|e| anyhow::anyhow!(e))?;
 
     match result {
-        IngestionResult::Created(doc) => {
+        IngestionResult::Created(doc) | IngestionResult::NewVersion(doc) => {
             info!("Created new document for batch file {}: {}", file_info.name, doc.id);
             Ok(Some((doc.id, file_info.size)))
         }
@@ -6,15 +6,19 @@
  * source sync, batch ingest, folder watcher).
  */
 
+use std::collections::HashSet;
+use std::sync::Mutex;
 use uuid::Uuid;
 use sha2::{Digest, Sha256};
 use tracing::{debug, info, warn};
 use serde_json;
 use chrono::Utc;
 
-use crate::models::{Document, FileIngestionInfo};
+use crate::models::{Document, DocumentEventType, FileIngestionInfo};
 use crate::db::Database;
-use crate::services::file_service::FileService;
+use crate::ingestion::filename_template::{FilenameTemplate, FilenameTokens};
+use crate::mime_detection::{detect_mime_from_content, detect_mime_from_file, MimeDetectionResult};
+use crate::services::file_service::{FileService, StoredFile};
 
 #[derive(Debug, Clone)]
 pub enum DeduplicationPolicy {
@@ -38,6 +42,18 @@ pub enum IngestionResult {
     Skipped { existing_document_id: Uuid, reason: String },
     /// Document was tracked as duplicate (for WebDAV)
     TrackedAsDuplicate { existing_document_id: Uuid },
+    /// Changed content for a source path that was synced before; the existing
+    /// document now holds it and its previous content was archived as a version
+    NewVersion(Document),
+}
+
+/// Outcome of preparing a file for a batched insert
+#[derive(Debug)]
+pub enum PreparedIngestion {
+    /// Content is stored and the record is ready for `ingest_prepared_batch`
+    Pending(Document),
+    /// The deduplication policy resolved the file without creating a record
+    Resolved(IngestionResult),
 }
 
 #[derive(Debug)]
@@ -66,19 +82,42 @@ pub struct DocumentIngestionRequest {
     pub source_metadata: Option<serde_json::Value>,
 }
 
+/// Archived versions kept per document unless overridden with `with_version_retention`
+pub const DEFAULT_VERSION_RETENTION: usize = 10;
+
+/// Candidates tried for a templated filename before falling back to the remote name
+const MAX_FILENAME_CANDIDATES: u32 = 10_000;
+
 pub struct DocumentIngestionService {
     db: Database,
     file_service: FileService,
+    version_retention: usize,
+    /// Templated filenames handed out but possibly not inserted yet, e.g. in a prepared batch
+    reserved_filenames: Mutex<HashSet<String>>,
 }
 
 impl DocumentIngestionService {
     pub fn new(db: Database, file_service: FileService) -> Self {
-        Self { db, file_service }
+        Self {
+            db,
+            file_service,
+            version_retention: DEFAULT_VERSION_RETENTION,
+            reserved_filenames: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Sets how many archived versions to keep when a synced file changes
+    pub fn with_version_retention(mut self, version_retention: usize) -> Self {
+        self.version_retention = version_retention;
+        self
     }
 
     /// Extract metadata from FileIngestionInfo for storage in document
     fn extract_metadata_from_file_info(file_info: &FileIngestionInfo) -> (Option<chrono::DateTime<chrono::Utc>>, Option<chrono::DateTime<chrono::Utc>>, Option<serde_json::Value>) {
-        let original_created_at = file_info.created_at;
+        // A capture/creation date embedded in the file beats the filesystem timestamp
+        let original_created_at = file_info.metadata.as_ref()
+            .and_then(crate::metadata_extraction::embedded_creation_date)
+            .or(file_info.created_at);
         let original_modified_at = file_info.last_modified;
         
         // Build comprehensive metadata object
@@ -117,62 +156,60 @@ impl DocumentIngestionService {
         (original_created_at, original_modified_at, final_metadata)
     }
 
+    /// The file content, not its extension or the client's claim, decides the stored MIME type
+    fn apply_detected_mime_type(request: &mut DocumentIngestionRequest, detection: MimeDetectionResult) {
+        if detection.mime_type != request.mime_type {
+            info!(
+                "Using detected MIME type {} instead of declared {} for {} ({:?})",
+                detection.mime_type, request.mime_type, request.filename, detection.detection_method
+            );
+            request.mime_type = detection.mime_type;
+        }
+    }
+
+    fn sniff_mime_type(request: &mut DocumentIngestionRequest) {
+        let detection = detect_mime_from_content(&request.file_data, &request.filename, Some(&request.mime_type));
+        Self::apply_detected_mime_type(request, detection);
+    }
+
     /// Unified document ingestion with configurable deduplication policy
-    pub async fn ingest_document(&self, request: DocumentIngestionRequest) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+    pub async fn ingest_document(&self, mut request: DocumentIngestionRequest) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        Self::sniff_mime_type(&mut request);
         let file_hash = self.calculate_file_hash(&request.file_data);
         let file_size = request.file_data.len() as i64;
-        
-        // Clone source_type early for error handling
-        let source_type_for_error = request.source_type.clone();
 
         debug!(
             "Ingesting document: {} for user {} (hash: {}, size: {} bytes, policy: {:?})",
             request.filename, request.user_id, &file_hash[..8], file_size, request.deduplication_policy
         );
 
-        // Check for existing document with same content
-        match self.db.get_document_by_user_and_hash(request.user_id, &file_hash).await {
-            Ok(Some(existing_doc)) => {
-                debug!(
-                    "Found existing document with same content: {} (ID: {}) matches new file: {}",
-                    existing_doc.original_filename, existing_doc.id, request.filename
-                );
+        if let Some(existing) = self.find_previous_version(&request, &file_hash).await {
+            let file_path = self.store_file(&request, &file_hash, file_size).await?;
+            return self.create_version(existing, &request, file_path, file_hash, file_size).await;
+        }
 
-                match request.deduplication_policy {
-                    DeduplicationPolicy::Skip => {
-                        return Ok(IngestionResult::Skipped {
-                            existing_document_id: existing_doc.id,
-                            reason: format!("Content already exists as '{}'", existing_doc.original_filename),
-                        });
-                    }
-                    DeduplicationPolicy::ReturnExisting => {
-                        return Ok(IngestionResult::ExistingDocument(existing_doc));
-                    }
-                    DeduplicationPolicy::TrackAsDuplicate => {
-                        return Ok(IngestionResult::TrackedAsDuplicate {
-                            existing_document_id: existing_doc.id,
-                        });
-                    }
-                    DeduplicationPolicy::AllowDuplicateContent => {
-                        // Continue with creating new document record
-                        debug!("Creating new document record despite duplicate content (policy: AllowDuplicateContent)");
-                    }
-                }
-            }
-            Ok(None) => {
-                debug!("No duplicate content found, proceeding with new document creation");
-            }
-            Err(e) => {
-                warn!("Error checking for duplicate content (hash: {}): {}", &file_hash[..8], e);
-                // Continue with ingestion even if duplicate check fails
-            }
+        if let Some(result) = self.check_existing_content(&request, &file_hash).await {
+            return Ok(result);
         }
 
+        self.apply_filename_template(&mut request).await;
+        let file_path = self.store_file(&request, &file_hash, file_size).await?;
+
+        self.create_document_record(request, file_path, file_hash, file_size).await
+    }
+
+    /// Saves the request's file data to storage, recording a failed document if that fails
+    async fn store_file(
+        &self,
+        request: &DocumentIngestionRequest,
+        file_hash: &str,
+        file_size: i64,
+    ) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
         // Save file to storage
-        let file_path = match self.file_service
+        match self.file_service
             .save_file(&request.filename, &request.file_data)
             .await {
-                Ok(path) => path,
+                Ok(path) => Ok(path),
                 Err(e) => {
                     warn!("Failed to save file {}: {}", request.filename, e);
                     
@@ -185,7 +222,7 @@ impl DocumentIngestionService {
                         original_path: None,
                         file_path: None, // couldn't save
                         file_size: Some(file_size),
-                        file_hash: Some(file_hash.clone()),
+                        file_hash: Some(file_hash.to_string()),
                         mime_type: Some(request.mime_type.clone()),
                         content: None,
                         tags: Vec::new(),
@@ -196,7 +233,7 @@ impl DocumentIngestionService {
                         failure_reason: "storage_error".to_string(),
                         failure_stage: "storage".to_string(),
                         existing_document_id: None,
-                        ingestion_source: source_type_for_error.clone().unwrap_or_else(|| "upload".to_string()),
+                        ingestion_source: request.source_type.clone().unwrap_or_else(|| "upload".to_string()),
                         error_message: Some(e.to_string()),
                         retry_count: Some(0),
                         last_retry_at: None,
@@ -208,29 +245,208 @@ impl DocumentIngestionService {
                         warn!("Failed to create failed document record for storage error: {}", failed_err);
                     }
                     
-                    return Err(e.into());
+                    Err(e.into())
                 }
-            };
+            }
+    }
 
-        // Create document record
-        let document = self.file_service.create_document(
+    fn build_document(&self, request: &DocumentIngestionRequest, file_path: &str, file_hash: &str, file_size: i64) -> Document {
+        self.file_service.create_document(
             &request.filename,
             &request.original_filename,
-            &file_path,
+            file_path,
             file_size,
             &request.mime_type,
             request.user_id,
-            Some(file_hash.clone()),
+            Some(file_hash.to_string()),
             request.original_created_at,
             request.original_modified_at,
-            request.source_path,
-            request.source_type,
+            request.source_path.clone(),
+            request.source_type.clone(),
             request.source_id,
             request.file_permissions,
-            request.file_owner,
-            request.file_group,
-            request.source_metadata,
+            request.file_owner.clone(),
+            request.file_group.clone(),
+            request.source_metadata.clone(),
+        )
+    }
+
+    /// For files synced from a source, finds the document previously ingested from the same
+    /// path when its content has changed, so the new content becomes a version of it.
+    async fn find_previous_version(&self, request: &DocumentIngestionRequest, file_hash: &str) -> Option<Document> {
+        let source_id = request.source_id?;
+        let source_path = request.source_path.as_deref()?;
+
+        let existing = match self.db.find_document_by_source_path(request.user_id, source_id, source_path).await {
+            Ok(existing) => existing?,
+            Err(e) => {
+                warn!("Error looking up previous version of {}: {}", source_path, e);
+                return None;
+            }
+        };
+
+        // Unchanged content is handled by the regular deduplication policy
+        if existing.file_hash.as_deref() == Some(file_hash) {
+            return None;
+        }
+
+        // Content that already exists as another document can't be stored twice
+        match self.db.get_document_by_user_and_hash(request.user_id, file_hash).await {
+            Ok(None) => Some(existing),
+            Ok(Some(other)) => {
+                debug!(
+                    "Changed content of {} matches existing document {}, not versioning",
+                    source_path, other.id
+                );
+                None
+            }
+            Err(e) => {
+                warn!("Error checking for duplicate content (hash: {}): {}", &file_hash[..8], e);
+                None
+            }
+        }
+    }
+
+    /// Makes stored content the current version of `existing`, archiving its previous
+    /// content and pruning versions beyond the retention limit
+    async fn create_version(
+        &self,
+        existing: Document,
+        request: &DocumentIngestionRequest,
+        file_path: String,
+        file_hash: String,
+        file_size: i64,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        let mut new_version = self.build_document(request, &file_path, &file_hash, file_size);
+        // A templated name was chosen when the document was created and stays with it
+        if self.source_filename_template(request).await.is_some() {
+            new_version.filename = existing.filename.clone();
+        }
+
+        let document = match self.db.create_document_version(existing.id, &new_version).await {
+            Ok(document) => document,
+            Err(e) => {
+                warn!("Failed to create new version of document {}: {}", existing.id, e);
+                if let Err(remove_err) = tokio::fs::remove_file(&file_path).await {
+                    warn!("Failed to remove stored file {}: {}", file_path, remove_err);
+                }
+                return Err(e.into());
+            }
+        };
+
+        info!(
+            "Stored new version of {} (ID: {}) for user {}",
+            document.original_filename, document.id, request.user_id
         );
+        self.record_history(document.id, DocumentEventType::NewVersion, request.source_type.as_deref()).await;
+        self.prune_versions(document.id).await;
+
+        Ok(IngestionResult::NewVersion(document))
+    }
+
+    /// Replaces a document's file with edited content, e.g. rotated pages, archiving the
+    /// previous content as a version. `detail` is recorded in the processing history.
+    pub async fn replace_document_content(
+        &self,
+        existing: &Document,
+        file_data: &[u8],
+        detail: &str,
+    ) -> Result<Document, Box<dyn std::error::Error + Send + Sync>> {
+        let file_hash = self.calculate_file_hash(file_data);
+        let file_path = self.file_service.save_file(&existing.filename, file_data).await?;
+        let new_version = Document {
+            file_path: file_path.clone(),
+            file_size: file_data.len() as i64,
+            file_hash: Some(file_hash),
+            ..existing.clone()
+        };
+
+        let document = match self.db.create_document_version(existing.id, &new_version).await {
+            Ok(document) => document,
+            Err(e) => {
+                if let Err(remove_err) = tokio::fs::remove_file(&file_path).await {
+                    warn!("Failed to remove stored file {}: {}", file_path, remove_err);
+                }
+                return Err(e.into());
+            }
+        };
+
+        info!("Stored new version of {} (ID: {}): {}", document.original_filename, document.id, detail);
+        self.record_history(document.id, DocumentEventType::NewVersion, Some(detail)).await;
+        self.prune_versions(document.id).await;
+
+        Ok(document)
+    }
+
+    /// Drops archived versions of a document beyond the retention limit, with their files
+    async fn prune_versions(&self, document_id: Uuid) {
+        match self.db.prune_document_versions(document_id, self.version_retention as i64).await {
+            Ok(pruned) => {
+                for version in pruned {
+                    if let Err(e) = tokio::fs::remove_file(&version.file_path).await {
+                        warn!("Failed to remove file of pruned version {} of document {}: {}", version.version_number, document_id, e);
+                    }
+                }
+            }
+            Err(e) => warn!("Failed to prune old versions of document {}: {}", document_id, e),
+        }
+    }
+
+    /// Applies the deduplication policy. Returns the final result if ingestion should stop here.
+    async fn check_existing_content(&self, request: &DocumentIngestionRequest, file_hash: &str) -> Option<IngestionResult> {
+        // Check for existing document with same content
+        match self.db.get_document_by_user_and_hash(request.user_id, file_hash).await {
+            Ok(Some(existing_doc)) => {
+                debug!(
+                    "Found existing document with same content: {} (ID: {}) matches new file: {}",
+                    existing_doc.original_filename, existing_doc.id, request.filename
+                );
+
+                match request.deduplication_policy {
+                    DeduplicationPolicy::Skip => {
+                        return Some(IngestionResult::Skipped {
+                            existing_document_id: existing_doc.id,
+                            reason: format!("Content already exists as '{}'", existing_doc.original_filename),
+                        });
+                    }
+                    DeduplicationPolicy::ReturnExisting => {
+                        return Some(IngestionResult::ExistingDocument(existing_doc));
+                    }
+                    DeduplicationPolicy::TrackAsDuplicate => {
+                        return Some(IngestionResult::TrackedAsDuplicate {
+                            existing_document_id: existing_doc.id,
+                        });
+                    }
+                    DeduplicationPolicy::AllowDuplicateContent => {
+                        // Continue with creating new document record
+                        debug!("Creating new document record despite duplicate content (policy: AllowDuplicateContent)");
+                    }
+                }
+            }
+            Ok(None) => {
+                debug!("No duplicate content found, proceeding with new document creation");
+            }
+            Err(e) => {
+                warn!("Error checking for duplicate content (hash: {}): {}", &file_hash[..8], e);
+                // Continue with ingestion even if duplicate check fails
+            }
+        }
+
+        None
+    }
+
+    /// Creates the document record for content that has already been written to storage
+    async fn create_document_record(
+        &self,
+        request: DocumentIngestionRequest,
+        file_path: String,
+        file_hash: String,
+        file_size: i64,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        let source_type_for_error = request.source_type.clone();
+
+        // Create document record
+        let document = self.build_document(&request, &file_path, &file_hash, file_size);
 
         let saved_document = match self.db.create_document(document).await {
             Ok(doc) => doc,
@@ -303,10 +519,72 @@ impl DocumentIngestionService {
             "Successfully ingested document: {} (ID: {}) for user {}",
             saved_document.original_filename, saved_document.id, request.user_id
         );
+        self.record_history(saved_document.id, DocumentEventType::Uploaded, request.source_type.as_deref()).await;
 
         Ok(IngestionResult::Created(saved_document))
     }
 
+    /// Appends to the document's processing history. A failed write is only logged, so
+    /// the history never fails an ingestion.
+    async fn record_history(&self, document_id: Uuid, event_type: DocumentEventType, detail: Option<&str>) {
+        if let Err(e) = self.db.record_document_event(document_id, event_type, detail, None).await {
+            warn!("Failed to record {} event of document {}: {}", event_type, document_id, e);
+        }
+    }
+
+    /// The `filename_template` of the source a request comes from, if it has one
+    async fn source_filename_template(&self, request: &DocumentIngestionRequest) -> Option<(FilenameTemplate, String)> {
+        let source = match self.db.get_source_by_id(request.source_id?).await {
+            Ok(source) => source?,
+            Err(e) => {
+                warn!("Failed to load source of {} for its filename template: {}", request.original_filename, e);
+                return None;
+            }
+        };
+        match FilenameTemplate::from_source_config(&source.config) {
+            Ok(template) => Some((template?, source.name)),
+            Err(e) => {
+                warn!("Ignoring invalid filename template of source {}: {}", source.id, e);
+                None
+            }
+        }
+    }
+
+    /// Names a new document from its source's filename template. The first candidate
+    /// the user has no document for wins; `original_filename` keeps the remote name.
+    async fn apply_filename_template(&self, request: &mut DocumentIngestionRequest) {
+        let Some((template, source_name)) = self.source_filename_template(request).await else {
+            return;
+        };
+        let tokens = FilenameTokens {
+            date: request.original_created_at.or(request.original_modified_at).map(|date| date.date_naive()),
+            source_name: &source_name,
+            original_filename: &request.original_filename,
+        };
+
+        for counter in 1..=MAX_FILENAME_CANDIDATES {
+            let candidate = template.render(&tokens, counter);
+            if self.reserved_filenames.lock().unwrap().contains(&candidate) {
+                continue;
+            }
+            match self.db.document_filename_exists(request.user_id, &candidate).await {
+                Ok(false) => {}
+                Ok(true) => continue,
+                Err(e) => {
+                    warn!("Failed to check filename {} for collisions, keeping {}: {}", candidate, request.filename, e);
+                    return;
+                }
+            }
+            if self.reserved_filenames.lock().unwrap().insert(candidate.clone()) {
+                debug!("Naming {} as {} from its source's filename template", request.original_filename, candidate);
+                request.filename = candidate;
+                return;
+            }
+        }
+
+        warn!("No free templated filename for {} after {} candidates, keeping it", request.original_filename, MAX_FILENAME_CANDIDATES);
+    }
+
     /// Calculate SHA256 hash of file content
     fn calculate_file_hash(&self, data: &[u8]) -> String {
         let mut hasher = Sha256::new();
@@ -325,10 +603,131 @@ impl DocumentIngestionService {
         source_type: &str,
         source_id: Option<Uuid>,
     ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        let request = Self::request_from_file_info(file_info, file_data, user_id, deduplication_policy, source_type, source_id);
+        self.ingest_document(request).await
+    }
+
+    /// Ingest a file that was already streamed to storage (see `FileService::begin_streaming_save`).
+    /// The stored file is removed again if the deduplication policy stops ingestion.
+    pub async fn ingest_stored_file(
+        &self,
+        file_info: &FileIngestionInfo,
+        stored_file: StoredFile,
+        user_id: Uuid,
+        deduplication_policy: DeduplicationPolicy,
+        source_type: &str,
+        source_id: Option<Uuid>,
+    ) -> Result<IngestionResult, Box<dyn std::error::Error + Send + Sync>> {
+        // Content is already on disk, so the request carries no data
+        let mut request = Self::request_from_file_info(file_info, Vec::new(), user_id, deduplication_policy, source_type, source_id);
+        let detection = detect_mime_from_file(&stored_file.file_path, &request.mime_type).await;
+        Self::apply_detected_mime_type(&mut request, detection);
+
+        debug!(
+            "Ingesting stored document: {} for user {} (hash: {}, size: {} bytes, policy: {:?})",
+            request.filename, request.user_id, &stored_file.file_hash[..8], stored_file.file_size, request.deduplication_policy
+        );
+
+        if let Some(existing) = self.find_previous_version(&request, &stored_file.file_hash).await {
+            return self.create_version(existing, &request, stored_file.file_path, stored_file.file_hash, stored_file.file_size).await;
+        }
+
+        if let Some(result) = self.check_existing_content(&request, &stored_file.file_hash).await {
+            if let Err(e) = tokio::fs::remove_file(&stored_file.file_path).await {
+                warn!("Failed to remove stored duplicate {}: {}", stored_file.file_path, e);
+            }
+            return Ok(result);
+        }
+
+        self.apply_filename_template(&mut request).await;
+        self.create_document_record(request, stored_file.file_path, stored_file.file_hash, stored_file.file_size).await
+    }
+
+    /// Like `ingest_from_file_info`, but stops short of inserting the document record so
+    /// callers can insert many records at once with `ingest_prepared_batch`.
+    pub async fn prepare_from_file_info(
+        &self,
+        file_info: &FileIngestionInfo,
+        file_data: Vec<u8>,
+        user_id: Uuid,
+        deduplication_policy: DeduplicationPolicy,
+        source_type: &str,
+        source_id: Option<Uuid>,
+    ) -> Result<PreparedIngestion, Box<dyn std::error::Error + Send + Sync>> {
+        let mut request = Self::request_from_file_info(file_info, file_data, user_id, deduplication_policy, source_type, source_id);
+        Self::sniff_mime_type(&mut request);
+        let file_hash = self.calculate_file_hash(&request.file_data);
+        let file_size = request.file_data.len() as i64;
+
+        if let Some(existing) = self.find_previous_version(&request, &file_hash).await {
+            let file_path = self.store_file(&request, &file_hash, file_size).await?;
+            let result = self.create_version(existing, &request, file_path, file_hash, file_size).await?;
+            return Ok(PreparedIngestion::Resolved(result));
+        }
+
+        if let Some(result) = self.check_existing_content(&request, &file_hash).await {
+            return Ok(PreparedIngestion::Resolved(result));
+        }
+
+        self.apply_filename_template(&mut request).await;
+        let file_path = self.store_file(&request, &file_hash, file_size).await?;
+        Ok(PreparedIngestion::Pending(self.build_document(&request, &file_path, &file_hash, file_size)))
+    }
+
+    /// Inserts prepared documents in bulk. Results are returned in input order.
+    ///
+    /// Documents whose content was inserted concurrently by someone else (skipped by the
+    /// conflict clause) resolve to the existing document, and their stored file is removed.
+    pub async fn ingest_prepared_batch(
+        &self,
+        documents: Vec<Document>,
+    ) -> Result<Vec<Result<IngestionResult, String>>, Box<dyn std::error::Error + Send + Sync>> {
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let inserted = self.db.bulk_create_documents(&documents).await?;
+        debug!("Bulk inserted {} of {} prepared documents", inserted.len(), documents.len());
+
+        let mut results = Vec::with_capacity(documents.len());
+        for document in documents {
+            if inserted.contains(&document.id) {
+                self.record_history(document.id, DocumentEventType::Uploaded, document.source_type.as_deref()).await;
+                results.push(Ok(IngestionResult::Created(document)));
+                continue;
+            }
+
+            if let Err(e) = tokio::fs::remove_file(&document.file_path).await {
+                warn!("Failed to remove stored duplicate {}: {}", document.file_path, e);
+            }
+
+            let file_hash = document.file_hash.clone().unwrap_or_default();
+            let result = match self.db.get_document_by_user_and_hash(document.user_id, &file_hash).await {
+                Ok(Some(existing_doc)) => {
+                    debug!("Prepared document {} conflicted with existing document {}", document.original_filename, existing_doc.id);
+                    Ok(IngestionResult::ExistingDocument(existing_doc))
+                }
+                Ok(None) => Err(format!("Document {} was not inserted and no existing document matches its content", document.original_filename)),
+                Err(e) => Err(format!("Failed to fetch existing document for {}: {}", document.original_filename, e)),
+            };
+            results.push(result);
+        }
+
+        Ok(results)
+    }
+
+    fn request_from_file_info(
+        file_info: &FileIngestionInfo,
+        file_data: Vec<u8>,
+        user_id: Uuid,
+        deduplication_policy: DeduplicationPolicy,
+        source_type: &str,
+        source_id: Option<Uuid>,
+    ) -> DocumentIngestionRequest {
         let (original_created_at, original_modified_at, source_metadata) = 
             Self::extract_metadata_from_file_info(file_info);
             
-        let request = DocumentIngestionRequest {
+        DocumentIngestionRequest {
             filename: file_info.name.clone(),
             original_filename: file_info.name.clone(),
             file_data,
@@ -344,9 +743,7 @@ impl DocumentIngestionService {
             file_owner: file_info.owner.clone(),
             file_group: file_info.group.clone(),
             source_metadata,
-        };
-
-        self.ingest_document(request).await
+        }
     }
 
     /// Convenience method for direct uploads (maintains backward compatibility)
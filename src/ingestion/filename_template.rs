@@ -0,0 +1,205 @@
+/*!
+ * Filename Templates
+ *
+ * A source can name the documents it ingests from a template such as
+ * `{date}_{source}_{original}` (`filename_template` in the source config) instead
+ * of keeping cryptic remote names like `IMG_0423.pdf`. Only the stored `filename`
+ * is templated; `original_filename` keeps the remote name. The original extension
+ * is always kept.
+ */
+
+use chrono::NaiveDate;
+
+/// Tokens a template may use
+pub const TEMPLATE_TOKENS: &[&str] = &["date", "source", "original", "counter"];
+
+/// Rendered for `{date}` when a file has no capture or modification date
+const UNDATED: &str = "undated";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Date,
+    Source,
+    Original,
+    Counter,
+}
+
+/// What a template is rendered from
+#[derive(Debug, Clone)]
+pub struct FilenameTokens<'a> {
+    /// Capture date from the file's metadata, or its modification date
+    pub date: Option<NaiveDate>,
+    pub source_name: &'a str,
+    /// The file's name in the source, extension included
+    pub original_filename: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilenameTemplate {
+    segments: Vec<Segment>,
+}
+
+impl FilenameTemplate {
+    pub const MAX_LEN: usize = 200;
+
+    /// Parses a template. Tokens are `{date}`, `{source}`, `{original}` and `{counter}`;
+    /// everything else is literal text, which may not contain path separators.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        if template.trim().is_empty() {
+            return Err("filename_template must not be empty".to_string());
+        }
+        if template.len() > Self::MAX_LEN {
+            return Err(format!("filename_template must be at most {} characters", Self::MAX_LEN));
+        }
+        if template.contains(['/', '\\']) {
+            return Err("filename_template must not contain path separators".to_string());
+        }
+
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        let mut rest = template;
+        while let Some(c) = rest.chars().next() {
+            match c {
+                '{' => {
+                    let Some(end) = rest.find('}') else {
+                        return Err(format!("Unclosed '{{' in filename_template: {}", template));
+                    };
+                    let segment = match &rest[1..end] {
+                        "date" => Segment::Date,
+                        "source" => Segment::Source,
+                        "original" => Segment::Original,
+                        "counter" => Segment::Counter,
+                        token => {
+                            return Err(format!(
+                                "Unknown token {{{}}} in filename_template, expected one of: {}",
+                                token,
+                                TEMPLATE_TOKENS.iter().map(|t| format!("{{{}}}", t)).collect::<Vec<_>>().join(", ")
+                            ));
+                        }
+                    };
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(segment);
+                    rest = &rest[end + 1..];
+                }
+                '}' => return Err(format!("Unmatched '}}' in filename_template: {}", template)),
+                _ => {
+                    literal.push(c);
+                    rest = &rest[c.len_utf8()..];
+                }
+            }
+        }
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(Self { segments })
+    }
+
+    /// Reads the `filename_template` of a source config. Absent or null means the source
+    /// keeps remote names; anything else must be a string that parses.
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Option<Self>, String> {
+        match config.get("filename_template") {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(template)) => Self::parse(template).map(Some),
+            Some(_) => Err("filename_template must be a string".to_string()),
+        }
+    }
+
+    pub fn has_counter(&self) -> bool {
+        self.segments.contains(&Segment::Counter)
+    }
+
+    /// Renders the filename for the `counter`th candidate, starting at 1. Templates without
+    /// `{counter}` get `_<counter>` appended from the second candidate on, so collisions are
+    /// always resolved the same way.
+    pub fn render(&self, tokens: &FilenameTokens, counter: u32) -> String {
+        let (original_stem, extension) = split_extension(tokens.original_filename);
+
+        let mut stem = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => stem.push_str(text),
+                Segment::Date => match tokens.date {
+                    Some(date) => stem.push_str(&date.format("%Y-%m-%d").to_string()),
+                    None => stem.push_str(UNDATED),
+                },
+                Segment::Source => stem.push_str(&sanitize(tokens.source_name)),
+                Segment::Original => stem.push_str(&sanitize(original_stem)),
+                Segment::Counter => stem.push_str(&counter.to_string()),
+            }
+        }
+        if !self.has_counter() && counter > 1 {
+            stem.push_str(&format!("_{}", counter));
+        }
+
+        let stem = stem.trim();
+        let stem = if stem.is_empty() { sanitize(original_stem) } else { stem.to_string() };
+        match extension {
+            Some(extension) => format!("{}.{}", stem, extension),
+            None => stem,
+        }
+    }
+}
+
+/// Splits `name.ext` into `("name", Some("ext"))`. A leading dot is part of the name.
+fn split_extension(filename: &str) -> (&str, Option<&str>) {
+    match filename.rfind('.') {
+        Some(dot) if dot > 0 && dot + 1 < filename.len() => (&filename[..dot], Some(&filename[dot + 1..])),
+        _ => (filename, None),
+    }
+}
+
+/// Replaces characters that can't appear in a filename
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| if c == '/' || c == '\\' || c.is_control() { '_' } else { c })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(date: Option<NaiveDate>) -> FilenameTokens<'static> {
+        FilenameTokens { date, source_name: "Phone/Camera", original_filename: "IMG_0423.pdf" }
+    }
+
+    #[test]
+    fn test_render() {
+        let template = FilenameTemplate::parse("{date}_{source}_{original}").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9);
+        assert_eq!(template.render(&tokens(date), 1), "2024-03-09_Phone_Camera_IMG_0423.pdf");
+        assert_eq!(template.render(&tokens(None), 1), "undated_Phone_Camera_IMG_0423.pdf");
+
+        let template = FilenameTemplate::parse("Scan {counter}").unwrap();
+        assert_eq!(template.render(&tokens(date), 7), "Scan 7.pdf");
+    }
+
+    #[test]
+    fn test_collisions_append_counter() {
+        let template = FilenameTemplate::parse("{date}_{original}").unwrap();
+        let date = NaiveDate::from_ymd_opt(2024, 3, 9);
+        assert_eq!(template.render(&tokens(date), 1), "2024-03-09_IMG_0423.pdf");
+        assert_eq!(template.render(&tokens(date), 2), "2024-03-09_IMG_0423_2.pdf");
+        assert!(!template.has_counter());
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(FilenameTemplate::parse("").is_err());
+        assert!(FilenameTemplate::parse("{date}/{original}").is_err());
+        assert!(FilenameTemplate::parse("{author}_{original}").is_err());
+        assert!(FilenameTemplate::parse("{date_{original}").is_err());
+        assert!(FilenameTemplate::parse("date}_{original}").is_err());
+        assert!(FilenameTemplate::parse(&"x".repeat(FilenameTemplate::MAX_LEN + 1)).is_err());
+
+        assert_eq!(FilenameTemplate::from_source_config(&serde_json::json!({})), Ok(None));
+        assert!(FilenameTemplate::from_source_config(&serde_json::json!({ "filename_template": 5 })).is_err());
+    }
+}
@@ -1,2 +1,3 @@
+pub mod archive_extraction;
 pub mod batch_ingest;
-pub mod document_ingestion;
\ No newline at end of file
+pub mod document_ingestion;pub mod filename_template;
@@ -40,6 +40,10 @@ pub struct AppState {
     pub oidc_client: Option<std::sync::Arc<OidcClient>>,
     pub sync_progress_tracker: std::sync::Arc<services::sync_progress_tracker::SyncProgressTracker>,
     pub user_watch_service: Option<std::sync::Arc<services::user_watch_service::UserWatchService>>,
+    pub login_rate_limiter: std::sync::Arc<monitoring::login_rate_limiter::LoginRateLimiter>,
+    pub derived_asset_cache: std::sync::Arc<services::derived_asset_cache::DerivedAssetCache>,
+    pub download_limiter: std::sync::Arc<scheduling::download_limiter::DownloadLimiter>,
+    pub source_secrets: std::sync::Arc<services::source_secrets::SourceSecrets>,
 }
 
 /// Health check endpoint for monitoring
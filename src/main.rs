@@ -51,6 +51,129 @@ fn determine_static_files_path() -> std::path::PathBuf {
     PathBuf::from("frontend/dist")
 }
 
+/// Loads and validates the configuration, then checks that the database is reachable and
+/// the OCR tools are installed. Never runs migrations or starts any background work, so it
+/// is safe to run in CI or a pre-start hook. Returns an error (nonzero exit) on any problem.
+async fn check_config() -> anyhow::Result<()> {
+    println!("\n🔎 READUR CONFIGURATION CHECK");
+    println!("{}", "=".repeat(60));
+
+    let config = match Config::from_env() {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            println!("❌ Configuration is invalid: {}", e);
+            return Err(e);
+        }
+    };
+
+    let mut problems: Vec<String> = Vec::new();
+    let mut warnings: Vec<String> = Vec::new();
+    let set_or_missing = |value: &Option<String>| if value.is_some() { "***" } else { "(not set)" };
+
+    println!("\n🧾 EFFECTIVE CONFIGURATION:");
+    println!("{}", "=".repeat(50));
+    println!("🌐 Server address: {}", config.server_address);
+    println!("🗄️  Database: {}", config.masked_database_url());
+    println!("🔑 JWT secret: ***");
+    println!("📁 Upload directory: {}", config.upload_path);
+    println!("👁️  Watch directory: {}", config.watch_folder);
+    println!("🧠 OCR language: {}", config.ocr_language);
+    println!("⚙️  Concurrent OCR jobs: {}", config.concurrent_ocr_jobs);
+    println!("🔐 OIDC enabled: {}", config.oidc_enabled);
+    if config.oidc_enabled {
+        println!("   Issuer: {}", config.oidc_issuer_url.as_deref().unwrap_or("(not set)"));
+        println!("   Client ID: {}", config.oidc_client_id.as_deref().unwrap_or("(not set)"));
+        println!("   Client secret: {}", set_or_missing(&config.oidc_client_secret));
+    }
+    println!("✉️  SMTP host: {}", config.smtp_host.as_deref().unwrap_or("(not set)"));
+    if config.smtp_host.is_some() {
+        println!("   Username: {}", config.smtp_username.as_deref().unwrap_or("(not set)"));
+        println!("   Password: {}", set_or_missing(&config.smtp_password));
+    }
+
+    if config.jwt_secret == "your-secret-key" {
+        warnings.push("JWT_SECRET is the default value; set a unique secret in production (sources can't store client certificates until then)".to_string());
+    }
+    if config.oidc_enabled {
+        for (name, value) in [
+            ("OIDC_CLIENT_ID", &config.oidc_client_id),
+            ("OIDC_CLIENT_SECRET", &config.oidc_client_secret),
+            ("OIDC_ISSUER_URL", &config.oidc_issuer_url),
+            ("OIDC_REDIRECT_URI", &config.oidc_redirect_uri),
+        ] {
+            if value.is_none() {
+                problems.push(format!("{} is required when OIDC is enabled", name));
+            }
+        }
+    }
+
+    println!("\n🗄️  DATABASE CONNECTIVITY:");
+    println!("{}", "=".repeat(50));
+    match Database::new_with_pool_config(&config.database_url, 1, 0).await {
+        Ok(db) => {
+            match sqlx::query("SELECT 1").execute(db.get_pool()).await {
+                Ok(_) => println!("✅ Connected to {}", config.masked_database_url()),
+                Err(e) => problems.push(format!("Database query failed: {}", e)),
+            }
+            db.close().await;
+        }
+        Err(e) => problems.push(format!("Could not connect to the database: {}", e)),
+    }
+
+    println!("\n🧠 OCR TOOLS:");
+    println!("{}", "=".repeat(50));
+    if cfg!(feature = "ocr") {
+        let health_checker = readur::ocr::health::OcrHealthChecker::new();
+        match health_checker.check_tesseract_installation() {
+            Ok(version) => {
+                println!("✅ {}", version);
+                if let Err(e) = health_checker.validate_language_combination(&config.ocr_language) {
+                    problems.push(format!("OCR language '{}' is not usable: {}", config.ocr_language, e));
+                }
+            }
+            Err(e) => problems.push(format!("Tesseract is not available: {}", e)),
+        }
+        for (tool, version_flag, purpose) in [
+            ("ocrmypdf", "--version", "OCR of scanned PDFs"),
+            ("pdftotext", "-v", "PDF text extraction"),
+        ] {
+            let available = tokio::process::Command::new(tool)
+                .arg(version_flag)
+                .output()
+                .await
+                .map(|output| output.status.success())
+                .unwrap_or(false);
+            if available {
+                println!("✅ {} is installed", tool);
+            } else {
+                warnings.push(format!("{} is not installed; {} will be unavailable", tool, purpose));
+            }
+        }
+    } else {
+        println!("ℹ️  OCR support is not compiled into this build");
+    }
+
+    if !warnings.is_empty() {
+        println!("\n⚠️  WARNINGS:");
+        println!("{}", "=".repeat(50));
+        for warning in &warnings {
+            println!("⚠️  {}", warning);
+        }
+    }
+
+    if problems.is_empty() {
+        println!("\n✅ Configuration check passed");
+        Ok(())
+    } else {
+        println!("\n❌ PROBLEMS:");
+        println!("{}", "=".repeat(50));
+        for problem in &problems {
+            println!("❌ {}", problem);
+        }
+        Err(anyhow::anyhow!("Configuration check failed with {} problem(s)", problems.len()))
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initialize logging with custom filters to reduce spam from noisy crates
@@ -58,18 +181,14 @@ async fn main() -> anyhow::Result<()> {
     // RUST_LOG=debug cargo run                                          (enable debug for all)
     // RUST_LOG=readur=debug,pdf_extract=error,sqlx::postgres::notice=off (debug for readur, suppress spam)
     // RUST_LOG=sqlx::postgres::notice=debug                             (show PostgreSQL notices for debugging)
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| {
-            // Default filter when RUST_LOG is not set
-            tracing_subscriber::EnvFilter::new("info")
-                .add_directive("pdf_extract=error".parse().unwrap())           // Suppress pdf_extract WARN spam
-                .add_directive("sqlx::postgres::notice=warn".parse().unwrap()) // Suppress PostgreSQL NOTICE spam  
-                .add_directive("readur=info".parse().unwrap())                 // Keep our app logs at info
-        });
+    // Set LOG_FORMAT=json for structured output (e.g. when shipping logs from Kubernetes)
+    let log_format = readur::monitoring::logging::LogFormat::from_env();
+    readur::monitoring::logging::init_tracing(log_format);
     
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .init();
+    // `--check-config` validates the environment and exits without starting the server
+    if std::env::args().skip(1).any(|arg| arg == "--check-config") {
+        return check_config().await;
+    }
     
     println!("\n🚀 READUR APPLICATION STARTUP");
     println!("{}", "=".repeat(60));
@@ -92,30 +211,7 @@ async fn main() -> anyhow::Result<()> {
     println!("\n🔗 STARTUP CONFIGURATION:");
     println!("{}", "=".repeat(50));
     println!("🌐 Server will start on: {}", config.server_address);
-    // Parse database URL safely without exposing credentials
-    let db_info = if let Some(at_pos) = config.database_url.find('@') {
-        let host_part = &config.database_url[at_pos + 1..];
-        let protocol = if config.database_url.starts_with("postgresql://") { "postgresql" } else { "postgres" };
-        
-        // Extract just username from credentials part (before @)
-        let creds_part = &config.database_url[..at_pos];
-        let username = if let Some(proto_end) = creds_part.find("://") {
-            let after_proto = &creds_part[proto_end + 3..];
-            if let Some(colon_pos) = after_proto.find(':') {
-                &after_proto[..colon_pos]
-            } else {
-                after_proto
-            }
-        } else {
-            "unknown"
-        };
-        // if we get the username, let's now mask it to get just the first and last character
-        let masked_username = format!("{}{}", &username[..1], &username[username.len() - 1..]);
-        
-        format!("{}://{}:***@{}", protocol, masked_username, host_part)
-    } else {
-        "Invalid database URL format".to_string()
-    };
+    let db_info = config.masked_database_url();
     
     println!("🗄️  Database connection: {}", db_info);
     println!("📁 Upload directory: {}", config.upload_path);
@@ -130,13 +226,6 @@ async fn main() -> anyhow::Result<()> {
     }
     info!("✅ Upload directory structure initialized");
     
-    // Migrate existing files to new structure (one-time operation)
-    info!("Migrating existing files to structured directories...");
-    if let Err(e) = file_service.migrate_existing_files().await {
-        warn!("Failed to migrate some existing files: {}", e);
-        // Don't fail startup for migration issues
-    }
-    
     // Create separate database pools for different workloads
     println!("\n🗄️  DATABASE CONNECTION:");
     println!("{}", "=".repeat(50));
@@ -310,12 +399,14 @@ async fn main() -> anyhow::Result<()> {
     }
     
     // Create shared OCR queue service for both web and background operations
-    let concurrent_jobs = 15; // Limit concurrent OCR jobs to prevent DB pool exhaustion
+    // The worker additionally stays within OCR_DB_POOL_FRACTION of the background pool
     let shared_queue_service = Arc::new(readur::ocr::queue::OcrQueueService::new(
         background_db.clone(), 
         background_db.get_pool().clone(), 
-        concurrent_jobs
-    ));
+        config.concurrent_ocr_jobs
+    )
+    .with_db_pool_fraction(config.ocr_db_pool_fraction)
+    .with_claim_batch_size(config.ocr_claim_batch_size));
     
     // Initialize OIDC client if enabled
     let oidc_client = if config.oidc_enabled {
@@ -348,6 +439,18 @@ async fn main() -> anyhow::Result<()> {
         None
     };
     
+    // Source configs are encrypted with a key derived from the JWT secret
+    let source_secrets = Arc::new(readur::services::source_secrets::SourceSecrets::new(&config.jwt_secret));
+    
+    // Login rate limiter is shared so lockouts apply regardless of which state handles the request
+    let login_rate_limiter = Arc::new(readur::monitoring::login_rate_limiter::LoginRateLimiter::from_config(&config));
+    
+    // Derived file cache is shared so hit counts and eviction cover every request
+    let derived_asset_cache = Arc::new(readur::services::derived_asset_cache::DerivedAssetCache::from_config(web_db.clone(), &config));
+    
+    // Download limiter is shared so the limit covers syncs started from either state
+    let download_limiter = Arc::new(readur::scheduling::download_limiter::DownloadLimiter::new(config.source_download_max_concurrency));
+    
     // Create web-facing state with shared queue service
     let web_state = AppState { 
         db: web_db, 
@@ -358,6 +461,10 @@ async fn main() -> anyhow::Result<()> {
         oidc_client: oidc_client.clone(),
         sync_progress_tracker: sync_progress_tracker.clone(),
         user_watch_service: user_watch_service.clone(),
+        login_rate_limiter: login_rate_limiter.clone(),
+        derived_asset_cache: derived_asset_cache.clone(),
+        download_limiter: download_limiter.clone(),
+        source_secrets: source_secrets.clone(),
     };
     let web_state = Arc::new(web_state);
     
@@ -371,6 +478,10 @@ async fn main() -> anyhow::Result<()> {
         oidc_client: oidc_client.clone(),
         sync_progress_tracker: sync_progress_tracker.clone(),
         user_watch_service: user_watch_service.clone(),
+        login_rate_limiter: login_rate_limiter.clone(),
+        derived_asset_cache: derived_asset_cache.clone(),
+        download_limiter: download_limiter.clone(),
+        source_secrets: source_secrets.clone(),
     };
     let background_state = Arc::new(background_state);
     
@@ -384,24 +495,28 @@ async fn main() -> anyhow::Result<()> {
     
     // Create dedicated runtime for OCR processing to prevent interference with WebDAV
     let ocr_runtime = tokio::runtime::Builder::new_multi_thread()
-        .worker_threads(3)  // Dedicated threads for OCR work
+        .worker_threads(config.ocr_worker_threads)  // Dedicated threads for OCR work
         .thread_name("readur-ocr")
         .enable_all()
         .build()?;
     
     // Create separate runtime for other background tasks (WebDAV, maintenance)
     let background_runtime = tokio::runtime::Builder::new_multi_thread()
-        .worker_threads(2)  // Dedicated threads for WebDAV and maintenance
+        .worker_threads(config.background_worker_threads)  // Dedicated threads for WebDAV and maintenance
         .thread_name("readur-background")
         .enable_all()
         .build()?;
         
     // Create dedicated runtime for database-heavy operations
     let db_runtime = tokio::runtime::Builder::new_multi_thread()
-        .worker_threads(2)  // Dedicated threads for intensive DB operations
+        .worker_threads(config.db_worker_threads)  // Dedicated threads for intensive DB operations
         .thread_name("readur-db")
         .enable_all()
         .build()?;
+    info!(
+        "Runtime worker threads: OCR {}, background {}, database {}",
+        config.ocr_worker_threads, config.background_worker_threads, config.db_worker_threads
+    );
     
     // Start OCR queue worker on dedicated OCR runtime using shared queue service
     let queue_worker = shared_queue_service.clone();
@@ -416,13 +531,15 @@ async fn main() -> anyhow::Result<()> {
     
     // Start OCR maintenance tasks on dedicated OCR runtime
     let queue_maintenance = shared_queue_service.clone();
+    let event_retention_db = background_state.db.clone();
+    let document_event_retention_days = config.document_event_retention_days;
     ocr_runtime.spawn(async move {
         let mut interval = tokio::time::interval(std::time::Duration::from_secs(300)); // Every 5 minutes
         loop {
             interval.tick().await;
             
-            // Recover stale items (older than 10 minutes)
-            if let Err(e) = queue_maintenance.recover_stale_items(10).await {
+            // Recover items whose worker stopped reporting
+            if let Err(e) = queue_maintenance.recover_stale_items(readur::ocr::queue::STALE_PROCESSING_MINUTES).await {
                 error!("Error recovering stale items: {}", e);
             }
             
@@ -430,9 +547,109 @@ async fn main() -> anyhow::Result<()> {
             if let Err(e) = queue_maintenance.cleanup_completed(7).await {
                 error!("Error cleaning up completed items: {}", e);
             }
+
+            // Prune document processing history past its retention horizon
+            if document_event_retention_days > 0 {
+                match event_retention_db.prune_document_events(document_event_retention_days).await {
+                    Ok(pruned) if pruned > 0 => info!("Pruned {} document events older than {} days", pruned, document_event_retention_days),
+                    Ok(_) => {}
+                    Err(e) => error!("Error pruning document events: {}", e),
+                }
+            }
         }
     });
     
+    // Keep batch jobs topped up; the first tick resumes jobs left running before a restart
+    let batch_job_feeder = shared_queue_service.clone();
+    ocr_runtime.spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+
+            if let Err(e) = batch_job_feeder.feed_running_batch_jobs().await {
+                error!("Error feeding batch jobs: {}", e);
+            }
+        }
+    });
+
+    // Permanently purge documents that have sat in the trash past the retention window
+    let trash_db = background_state.db.clone();
+    let trash_upload_path = config.upload_path.clone();
+    let trash_retention_days = config.trash_retention_days;
+    background_runtime.spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600)); // Every hour
+        loop {
+            interval.tick().await;
+            
+            if let Err(e) = readur::routes::documents::purge_expired_documents(&trash_db, &trash_upload_path, trash_retention_days).await {
+                error!("Error purging expired trash: {}", e);
+            }
+        }
+    });
+    
+    // Evict least recently used thumbnails and searchable PDFs once the cache is over its cap
+    let cache_evictor = derived_asset_cache.clone();
+    background_runtime.spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(600)); // Every 10 minutes
+        loop {
+            interval.tick().await;
+            
+            if let Err(e) = cache_evictor.evict_to_limit().await {
+                error!("Error evicting derived file cache: {}", e);
+            }
+        }
+    });
+    
+    // Move documents past their label or default retention period to the trash
+    let retention_db = background_state.db.clone();
+    let retention_upload_path = config.upload_path.clone();
+    background_runtime.spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(3600)); // Every hour
+        loop {
+            interval.tick().await;
+            
+            if let Err(e) = readur::scheduling::retention_cleanup::apply_retention_policies(&retention_db, &retention_upload_path, None, false).await {
+                error!("Error applying retention policies: {}", e);
+            }
+        }
+    });
+    
+    // Move stored files to where STORAGE_LAYOUT puts them. Files already in place are
+    // skipped, so this only does work after the layout changed or an interrupted run.
+    let layout_db = background_state.db.clone();
+    let layout_file_service = file_service.clone().with_storage_layout(config.storage_layout);
+    background_runtime.spawn(async move {
+        if let Err(e) = layout_file_service.migrate_existing_files(&layout_db, false).await {
+            // Don't fail startup for migration issues
+            warn!("Failed to migrate some existing files: {}", e);
+        }
+    });
+    
+    // Fill in file hashes and word counts missing from documents ingested by older versions
+    if config.backfill_on_startup {
+        let backfill_state = background_state.clone();
+        background_runtime.spawn(async move {
+            let total_documents = match backfill_state.db.count_document_backfill_records().await {
+                Ok(count) => count,
+                Err(e) => {
+                    error!("Failed to count documents to backfill: {}", e);
+                    return;
+                }
+            };
+            // A run still marked as running was cut short by the previous shutdown and is closed first
+            let details = readur::models::BackfillDetails { total_documents, ..Default::default() };
+            match readur::services::document_runs::start_document_run(&backfill_state, None, details).await {
+                Ok(run) => {
+                    info!("Starting document backfill run {} at startup ({} documents)", run.id, run.details.total_documents);
+                    readur::services::document_backfill_service::DocumentBackfillService::new(backfill_state.clone())
+                        .run(run)
+                        .await;
+                }
+                Err(e) => error!("Failed to start document backfill at startup: {}", e),
+            }
+        });
+    }
+
     // Create universal source scheduler with background state (handles WebDAV, Local, S3)
     println!("\n📅 SCHEDULER INITIALIZATION:");
     println!("{}", "=".repeat(50));
@@ -454,6 +671,10 @@ async fn main() -> anyhow::Result<()> {
         oidc_client: oidc_client.clone(),
         sync_progress_tracker: sync_progress_tracker.clone(),
         user_watch_service: user_watch_service.clone(),
+        login_rate_limiter: login_rate_limiter.clone(),
+        derived_asset_cache: derived_asset_cache.clone(),
+        download_limiter: download_limiter.clone(),
+        source_secrets: source_secrets.clone(),
     };
     let web_state = Arc::new(updated_web_state);
     
@@ -478,9 +699,12 @@ async fn main() -> anyhow::Result<()> {
     // Create the router with the updated state
     let app = Router::new()
         .route("/api/health", get(readur::health_check))
+        .route("/api/health/ready", get(readur::routes::health::readiness_check))
+        .nest("/api/admin", readur::routes::admin::router())
         .nest("/api/auth", readur::routes::auth::router())
         .nest("/api/documents", readur::routes::documents::router())
         .nest("/api/ignored-files", readur::routes::ignored_files::ignored_files_routes())
+        .nest("/api/jobs", readur::routes::jobs::router())
         .nest("/api/labels", readur::routes::labels::router())
         .nest("/api/metrics", readur::routes::metrics::router())
         .nest("/metrics", readur::routes::prometheus_metrics::router())
@@ -489,6 +713,7 @@ async fn main() -> anyhow::Result<()> {
         .nest("/api/queue", readur::routes::queue::router())
         .nest("/api/search", readur::routes::search::router())
         .nest("/api/settings", readur::routes::settings::router())
+        .nest("/api/shared", readur::routes::shared::router())
         .nest("/api/sources", readur::routes::sources::router())
         .nest("/api/users", readur::routes::users::router())
         .nest("/api/webdav", readur::routes::webdav::router())
@@ -501,6 +726,7 @@ async fn main() -> anyhow::Result<()> {
         )
         .layer(DefaultBodyLimit::max(config.max_file_size_mb as usize * 1024 * 1024))
         .layer(CorsLayer::permissive())
+        .layer(axum::middleware::from_fn(readur::monitoring::request_id::request_id_middleware))
         .with_state(web_state.clone());
 
     println!("\n🌐 STARTING HTTP SERVER:");
@@ -533,7 +759,11 @@ async fn main() -> anyhow::Result<()> {
     
     info!("🚀 Readur server is now running and accepting connections");
     
-    axum::serve(listener, app).await?;
+    // Connect info is needed so login rate limiting can key on the client address
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    ).await?;
     
     Ok(())
 }
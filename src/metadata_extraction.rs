@@ -1,16 +1,53 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::config::Config;
+use crate::models::FileIngestionInfo;
+
+mod exif;
+mod pdf_info;
+
+pub use exif::{parse_exif_datetime, read_exif, ExifData};
+pub use pdf_info::{parse_pdf_date, read_pdf_properties, PdfProperties};
+
+/// Options controlling which extracted fields are kept
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MetadataExtractionOptions {
+    /// Drop GPS coordinates read from image EXIF data
+    pub strip_gps: bool,
+}
+
+impl MetadataExtractionOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            strip_gps: config.strip_gps_metadata,
+        }
+    }
+}
 
 /// Extract metadata from file content based on file type
 pub async fn extract_content_metadata(file_data: &[u8], mime_type: &str, filename: &str) -> Result<Option<Value>> {
+    extract_content_metadata_with_options(file_data, mime_type, filename, MetadataExtractionOptions::default()).await
+}
+
+/// Extract metadata from file content, applying the given privacy options
+pub async fn extract_content_metadata_with_options(
+    file_data: &[u8],
+    mime_type: &str,
+    filename: &str,
+    options: MetadataExtractionOptions,
+) -> Result<Option<Value>> {
     let mut metadata = Map::new();
     
     match mime_type {
-        // Image files - extract basic image info
+        // Image files - extract basic image info plus EXIF capture details
         mime if mime.starts_with("image/") => {
             if let Ok(img_metadata) = extract_image_metadata(file_data).await {
                 metadata.extend(img_metadata);
             }
+            metadata.extend(extract_exif_metadata(file_data, options));
         }
         
         // PDF files - extract basic PDF info
@@ -48,6 +85,59 @@ pub async fn extract_content_metadata(file_data: &[u8], mime_type: &str, filenam
     }
 }
 
+/// Extract content metadata into `file_info.metadata`, keeping any fields the
+/// source already provided
+pub async fn enrich_file_info(file_info: &mut FileIngestionInfo, file_data: &[u8], options: MetadataExtractionOptions) {
+    // Pick the extractor by what the content is, not by what the extension claims
+    let mime_type = crate::mime_detection::detect_mime_from_content(file_data, &file_info.name, Some(&file_info.mime_type)).mime_type;
+    let Ok(Some(Value::Object(content_metadata))) =
+        extract_content_metadata_with_options(file_data, &mime_type, &file_info.name, options).await
+    else {
+        return;
+    };
+
+    match file_info.metadata.as_mut() {
+        Some(Value::Object(existing)) => {
+            for (key, value) in content_metadata {
+                existing.entry(key).or_insert(value);
+            }
+        }
+        _ => file_info.metadata = Some(Value::Object(content_metadata)),
+    }
+}
+
+/// Extract content metadata of a file on disk into `file_info.metadata`, like
+/// `enrich_file_info`. The file is memory-mapped rather than read, so it is never
+/// copied into memory however large it is.
+pub async fn enrich_file_info_from_path(
+    file_info: &mut FileIngestionInfo,
+    path: &Path,
+    options: MetadataExtractionOptions,
+) -> Result<()> {
+    let file = std::fs::File::open(path)?;
+    // Empty files can't be mapped
+    if file.metadata()?.len() == 0 {
+        enrich_file_info(file_info, &[], options).await;
+        return Ok(());
+    }
+
+    // SAFETY: stored files are written once and never modified in place, so the mapping
+    // can't change underneath the extractors
+    let mapped = unsafe { memmap2::Mmap::map(&file)? };
+    enrich_file_info(file_info, &mapped, options).await;
+    Ok(())
+}
+
+/// The date a document was originally created according to its embedded
+/// metadata: the EXIF capture date for images, the creation date for PDFs
+pub fn embedded_creation_date(metadata: &Value) -> Option<DateTime<Utc>> {
+    ["capture_date", "pdf_creation_date"]
+        .iter()
+        .filter_map(|key| metadata.get(key).and_then(Value::as_str))
+        .find_map(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map(|date| date.with_timezone(&Utc))
+}
+
 /// Extract metadata from image files
 async fn extract_image_metadata(file_data: &[u8]) -> Result<Map<String, Value>> {
     let mut metadata = Map::new();
@@ -80,6 +170,32 @@ async fn extract_image_metadata(file_data: &[u8]) -> Result<Map<String, Value>>
     Ok(metadata)
 }
 
+/// Extract capture date, camera and location from an image's EXIF block
+fn extract_exif_metadata(file_data: &[u8], options: MetadataExtractionOptions) -> Map<String, Value> {
+    let mut metadata = Map::new();
+    let Some(exif) = read_exif(file_data) else {
+        return metadata;
+    };
+
+    if let Some(capture_date) = exif.capture_date {
+        metadata.insert("capture_date".to_string(), Value::String(capture_date.to_rfc3339()));
+    }
+    if let Some(make) = exif.camera_make {
+        metadata.insert("camera_make".to_string(), Value::String(make));
+    }
+    if let Some(model) = exif.camera_model {
+        metadata.insert("camera_model".to_string(), Value::String(model));
+    }
+    if !options.strip_gps {
+        if let (Some(latitude), Some(longitude)) = (exif.gps_latitude, exif.gps_longitude) {
+            metadata.insert("gps_latitude".to_string(), serde_json::json!(latitude));
+            metadata.insert("gps_longitude".to_string(), serde_json::json!(longitude));
+        }
+    }
+    
+    metadata
+}
+
 /// Extract metadata from PDF files
 async fn extract_pdf_metadata(file_data: &[u8]) -> Result<Map<String, Value>> {
     let mut metadata = Map::new();
@@ -95,12 +211,23 @@ async fn extract_pdf_metadata(file_data: &[u8]) -> Result<Map<String, Value>> {
             }
         }
         
-        // Try to count pages by counting "Type /Page" entries
-        let content = String::from_utf8_lossy(file_data);
-        let page_count = content.matches("/Type /Page").count();
-        if page_count > 0 {
+        // Document properties from the Info dictionary or XMP packet
+        let properties = read_pdf_properties(file_data);
+        if let Some(page_count) = properties.page_count {
             metadata.insert("page_count".to_string(), Value::Number(page_count.into()));
         }
+        if let Some(title) = properties.title {
+            metadata.insert("pdf_title".to_string(), Value::String(title));
+        }
+        if let Some(author) = properties.author {
+            metadata.insert("pdf_author".to_string(), Value::String(author));
+        }
+        // Normalise the creation date to RFC 3339, keeping the raw value if it can't be parsed
+        if let Some(creation_date) = properties.creation_date.map(|date| date.to_rfc3339()).or(properties.raw_creation_date) {
+            metadata.insert("pdf_creation_date".to_string(), Value::String(creation_date));
+        }
+        
+        let content = String::from_utf8_lossy(file_data);
         
         // Look for basic PDF info
         if content.contains("/Linearized") {
@@ -112,16 +239,6 @@ async fn extract_pdf_metadata(file_data: &[u8]) -> Result<Map<String, Value>> {
             metadata.insert("encrypted".to_string(), Value::Bool(true));
         }
         
-        // Try to find creation/modification dates in metadata
-        if let Some(creation_start) = content.find("/CreationDate") {
-            if let Some(date_start) = content[creation_start..].find('(') {
-                if let Some(date_end) = content[creation_start + date_start..].find(')') {
-                    let date_str = &content[creation_start + date_start + 1..creation_start + date_start + date_end];
-                    metadata.insert("pdf_creation_date".to_string(), Value::String(date_str.to_string()));
-                }
-            }
-        }
-        
         // Basic content analysis
         if content.contains("/Font") {
             metadata.insert("contains_fonts".to_string(), Value::Bool(true));
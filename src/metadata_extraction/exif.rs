@@ -0,0 +1,242 @@
+//! Minimal EXIF reader for capture date, camera and GPS tags.
+//!
+//! Only the handful of tags readur surfaces are decoded. Every read is bounds
+//! checked so truncated or malformed EXIF blocks simply yield fewer fields.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+
+const TAG_MAKE: u16 = 0x010F;
+const TAG_MODEL: u16 = 0x0110;
+const TAG_DATE_TIME: u16 = 0x0132;
+const TAG_EXIF_IFD: u16 = 0x8769;
+const TAG_GPS_IFD: u16 = 0x8825;
+const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+const TAG_DATE_TIME_DIGITIZED: u16 = 0x9004;
+const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+const TAG_GPS_LATITUDE_REF: u16 = 0x0001;
+const TAG_GPS_LATITUDE: u16 = 0x0002;
+const TAG_GPS_LONGITUDE_REF: u16 = 0x0003;
+const TAG_GPS_LONGITUDE: u16 = 0x0004;
+
+const TYPE_ASCII: u16 = 2;
+const TYPE_SHORT: u16 = 3;
+const TYPE_LONG: u16 = 4;
+const TYPE_RATIONAL: u16 = 5;
+
+/// Fields decoded from an image's EXIF block
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExifData {
+    pub capture_date: Option<DateTime<Utc>>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    pub gps_latitude: Option<f64>,
+    pub gps_longitude: Option<f64>,
+}
+
+impl ExifData {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Read EXIF data from a JPEG, PNG or TIFF file. Returns `None` when the file
+/// carries no EXIF block.
+pub fn read_exif(file_data: &[u8]) -> Option<ExifData> {
+    let tiff = find_tiff_block(file_data)?;
+    let reader = TiffReader::new(tiff)?;
+    let ifd0 = reader.read_ifd(reader.u32_at(4)? as usize)?;
+
+    let mut exif = ExifData {
+        camera_make: ifd0.get(&TAG_MAKE).and_then(|e| reader.ascii(e)),
+        camera_model: ifd0.get(&TAG_MODEL).and_then(|e| reader.ascii(e)),
+        ..Default::default()
+    };
+
+    let exif_ifd = ifd0.get(&TAG_EXIF_IFD)
+        .and_then(|e| reader.offset(e))
+        .and_then(|offset| reader.read_ifd(offset));
+    let local_date = exif_ifd.as_ref()
+        .and_then(|ifd| ifd.get(&TAG_DATE_TIME_ORIGINAL).or_else(|| ifd.get(&TAG_DATE_TIME_DIGITIZED)))
+        .or_else(|| ifd0.get(&TAG_DATE_TIME))
+        .and_then(|e| reader.ascii(e));
+    let offset = exif_ifd.as_ref()
+        .and_then(|ifd| ifd.get(&TAG_OFFSET_TIME_ORIGINAL))
+        .and_then(|e| reader.ascii(e));
+    exif.capture_date = local_date.and_then(|date| parse_exif_datetime(&date, offset.as_deref()));
+
+    if let Some(gps) = ifd0.get(&TAG_GPS_IFD)
+        .and_then(|e| reader.offset(e))
+        .and_then(|offset| reader.read_ifd(offset))
+    {
+        exif.gps_latitude = gps_coordinate(&reader, &gps, TAG_GPS_LATITUDE, TAG_GPS_LATITUDE_REF, 'S');
+        exif.gps_longitude = gps_coordinate(&reader, &gps, TAG_GPS_LONGITUDE, TAG_GPS_LONGITUDE_REF, 'W');
+    }
+
+    Some(exif)
+}
+
+/// Parse an EXIF `YYYY:MM:DD HH:MM:SS` timestamp. EXIF dates carry no zone, so
+/// they are taken as UTC unless an `OffsetTimeOriginal` value is present.
+pub fn parse_exif_datetime(value: &str, offset: Option<&str>) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(value.trim(), "%Y:%m:%d %H:%M:%S").ok()?;
+    match offset.and_then(|o| DateTime::parse_from_rfc3339(&format!("2000-01-01T00:00:00{}", o.trim())).ok()) {
+        Some(with_offset) => FixedOffset::east_opt(with_offset.offset().local_minus_utc())?
+            .from_local_datetime(&naive)
+            .single()
+            .map(|date| date.with_timezone(&Utc)),
+        None => Some(Utc.from_utc_datetime(&naive)),
+    }
+}
+
+/// Locate the TIFF header that holds the EXIF IFDs
+fn find_tiff_block(data: &[u8]) -> Option<&[u8]> {
+    if data.starts_with(b"II*\0") || data.starts_with(b"MM\0*") {
+        return Some(data);
+    }
+
+    if data.starts_with(&[0xFF, 0xD8]) {
+        // Walk JPEG segments until the APP1 "Exif" segment or the start of scan
+        let mut pos = 2;
+        while pos + 4 <= data.len() && data[pos] == 0xFF {
+            let marker = data[pos + 1];
+            if marker == 0xDA || marker == 0xD9 {
+                break;
+            }
+            let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+            let segment = data.get(pos + 4..pos + 2 + length)?;
+            if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+                return Some(&segment[6..]);
+            }
+            pos += 2 + length;
+        }
+        return None;
+    }
+
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let mut pos = 8;
+        while pos + 8 <= data.len() {
+            let length = u32::from_be_bytes(data[pos..pos + 4].try_into().ok()?) as usize;
+            let chunk_type = &data[pos + 4..pos + 8];
+            if chunk_type == b"eXIf" {
+                return data.get(pos + 8..pos + 8 + length);
+            }
+            if chunk_type == b"IDAT" || chunk_type == b"IEND" {
+                break;
+            }
+            pos += 12 + length;
+        }
+    }
+
+    None
+}
+
+fn gps_coordinate(
+    reader: &TiffReader,
+    gps: &HashMap<u16, IfdEntry>,
+    value_tag: u16,
+    ref_tag: u16,
+    negative_ref: char,
+) -> Option<f64> {
+    let parts = reader.rationals(gps.get(&value_tag)?)?;
+    if parts.len() < 3 {
+        return None;
+    }
+    let degrees = parts[0] + parts[1] / 60.0 + parts[2] / 3600.0;
+    let negative = gps.get(&ref_tag)
+        .and_then(|e| reader.ascii(e))
+        .is_some_and(|r| r.starts_with(negative_ref));
+    let value = if negative { -degrees } else { degrees };
+    value.is_finite().then_some(value)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct IfdEntry {
+    field_type: u16,
+    count: u32,
+    /// Raw 4-byte value/offset field position within the TIFF block
+    value_pos: usize,
+}
+
+struct TiffReader<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(0..2)? {
+            b"II" => true,
+            b"MM" => false,
+            _ => return None,
+        };
+        Some(Self { data, little_endian })
+    }
+
+    fn u16_at(&self, pos: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(pos..pos + 2)?.try_into().ok()?;
+        Some(if self.little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    }
+
+    fn u32_at(&self, pos: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(pos..pos + 4)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    }
+
+    fn read_ifd(&self, offset: usize) -> Option<HashMap<u16, IfdEntry>> {
+        let count = self.u16_at(offset)? as usize;
+        let mut entries = HashMap::with_capacity(count);
+        for i in 0..count {
+            let pos = offset + 2 + i * 12;
+            let (Some(tag), Some(field_type), Some(value_count)) =
+                (self.u16_at(pos), self.u16_at(pos + 2), self.u32_at(pos + 4))
+            else {
+                break;
+            };
+            entries.insert(tag, IfdEntry { field_type, count: value_count, value_pos: pos + 8 });
+        }
+        Some(entries)
+    }
+
+    /// Position of an entry's payload, following the offset when it does not
+    /// fit inline
+    fn payload(&self, entry: &IfdEntry, unit_size: usize) -> Option<&'a [u8]> {
+        let len = (entry.count as usize).checked_mul(unit_size)?;
+        let start = if len <= 4 { entry.value_pos } else { self.u32_at(entry.value_pos)? as usize };
+        self.data.get(start..start.checked_add(len)?)
+    }
+
+    fn offset(&self, entry: &IfdEntry) -> Option<usize> {
+        match entry.field_type {
+            TYPE_LONG => self.u32_at(entry.value_pos).map(|v| v as usize),
+            TYPE_SHORT => self.u16_at(entry.value_pos).map(|v| v as usize),
+            _ => None,
+        }
+    }
+
+    fn ascii(&self, entry: &IfdEntry) -> Option<String> {
+        if entry.field_type != TYPE_ASCII {
+            return None;
+        }
+        let bytes = self.payload(entry, 1)?;
+        let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+        let value = String::from_utf8_lossy(&bytes[..end]).trim().to_string();
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn rationals(&self, entry: &IfdEntry) -> Option<Vec<f64>> {
+        if entry.field_type != TYPE_RATIONAL {
+            return None;
+        }
+        let start = self.u32_at(entry.value_pos)? as usize;
+        self.payload(entry, 8)?;
+        (0..entry.count as usize)
+            .map(|i| {
+                let numerator = self.u32_at(start + i * 8)?;
+                let denominator = self.u32_at(start + i * 8 + 4)?;
+                (denominator != 0).then(|| numerator as f64 / denominator as f64)
+            })
+            .collect()
+    }
+}
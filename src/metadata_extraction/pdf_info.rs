@@ -0,0 +1,293 @@
+//! Reads document properties from a PDF's Info dictionary, falling back to the
+//! XMP metadata packet when the Info dictionary is missing or compressed.
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use regex::bytes::Regex;
+use std::sync::LazyLock;
+
+static INFO_REF: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/Info\s+(\d+)\s+(\d+)\s+R").unwrap());
+static INFO_KEY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/(Title|Author|CreationDate)\s*([(<])").unwrap());
+static PAGE_OBJECT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"/Type\s*/Page(?-u:\b)").unwrap());
+static XMP_TITLE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?s)<dc:title>\s*<rdf:Alt>\s*<rdf:li[^>]*>([^<]*)</rdf:li>").unwrap()
+});
+static XMP_CREATOR: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r"(?s)<dc:creator>\s*<rdf:(?:Seq|Bag)>\s*<rdf:li[^>]*>([^<]*)</rdf:li>").unwrap()
+});
+static XMP_CREATE_DATE: LazyLock<regex::Regex> = LazyLock::new(|| {
+    regex::Regex::new(r#"xmp:CreateDate(?:>([^<]+)</xmp:CreateDate>|\s*=\s*"([^"]+)")"#).unwrap()
+});
+
+/// Document properties read from a PDF
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PdfProperties {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    /// Creation date as written in the file
+    pub raw_creation_date: Option<String>,
+    pub creation_date: Option<DateTime<Utc>>,
+    pub page_count: Option<usize>,
+}
+
+pub fn read_pdf_properties(file_data: &[u8]) -> PdfProperties {
+    let mut properties = PdfProperties::default();
+
+    if let Some(info) = find_info_dictionary(file_data) {
+        for captures in INFO_KEY.captures_iter(info) {
+            let start = captures.get(2).map(|m| m.start()).unwrap_or_default();
+            let Some(bytes) = read_string(&info[start..]) else {
+                continue;
+            };
+            let value = decode_text_string(&bytes).trim().to_string();
+            if value.is_empty() {
+                continue;
+            }
+            match &captures[1] {
+                b"Title" => properties.title = Some(value),
+                b"Author" => properties.author = Some(value),
+                _ => {
+                    properties.creation_date = parse_pdf_date(&value);
+                    properties.raw_creation_date = Some(value);
+                }
+            }
+        }
+    }
+
+    if properties.title.is_none() || properties.author.is_none() || properties.creation_date.is_none() {
+        apply_xmp(file_data, &mut properties);
+    }
+
+    let page_count = PAGE_OBJECT.find_iter(file_data).count();
+    if page_count > 0 {
+        properties.page_count = Some(page_count);
+    }
+
+    properties
+}
+
+/// Parse a PDF date string (`D:YYYYMMDDHHmmSSOHH'mm'`). Every component after
+/// the year is optional; a missing zone is taken as UTC.
+pub fn parse_pdf_date(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    let value = value.strip_prefix("D:").unwrap_or(value);
+    let digits_end = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (digits, zone) = value.split_at(digits_end);
+    if digits.len() < 4 {
+        return None;
+    }
+
+    let component = |range: std::ops::Range<usize>, default: u32| -> Option<u32> {
+        match digits.get(range) {
+            Some(part) if !part.is_empty() => part.parse().ok(),
+            _ => Some(default),
+        }
+    };
+    let year: i32 = digits[0..4].parse().ok()?;
+    let date = NaiveDate::from_ymd_opt(year, component(4..6, 1)?, component(6..8, 1)?)?;
+    let naive = date.and_hms_opt(component(8..10, 0)?, component(10..12, 0)?, component(12..14, 0)?)?;
+
+    let offset = match zone.chars().next() {
+        Some(sign @ ('+' | '-')) => {
+            let zone_digits: String = zone[1..].chars().filter(char::is_ascii_digit).collect();
+            let hours: i32 = zone_digits.get(0..2).and_then(|h| h.parse().ok()).unwrap_or(0);
+            let minutes: i32 = zone_digits.get(2..4).and_then(|m| m.parse().ok()).unwrap_or(0);
+            let seconds = hours * 3600 + minutes * 60;
+            FixedOffset::east_opt(if sign == '-' { -seconds } else { seconds })?
+        }
+        _ => FixedOffset::east_opt(0)?,
+    };
+
+    offset.from_local_datetime(&naive).single().map(|date| date.with_timezone(&Utc))
+}
+
+/// Locate the body of the Info dictionary referenced from the trailer
+fn find_info_dictionary(file_data: &[u8]) -> Option<&[u8]> {
+    // Incremental updates append a new trailer, so the last reference wins
+    let captures = INFO_REF.captures_iter(file_data).last()?;
+    let object = Regex::new(&format!(r"(?:^|\s){}\s+{}\s+obj\s*<<", &captures_str(&captures, 1)?, &captures_str(&captures, 2)?)).ok()?;
+    let start = object.find_iter(file_data).last()?.end();
+    let length = dictionary_length(&file_data[start..])?;
+    Some(&file_data[start..start + length])
+}
+
+fn captures_str(captures: &regex::bytes::Captures, index: usize) -> Option<String> {
+    std::str::from_utf8(captures.get(index)?.as_bytes()).ok().map(str::to_string)
+}
+
+/// Length of a dictionary body up to its closing `>>`, skipping over strings
+/// and nested dictionaries
+fn dictionary_length(data: &[u8]) -> Option<usize> {
+    let mut depth = 1;
+    let mut pos = 0;
+    while pos < data.len() {
+        match data[pos] {
+            b'(' => {
+                pos += literal_string_length(&data[pos..])?;
+                continue;
+            }
+            b'<' if data.get(pos + 1) == Some(&b'<') => {
+                depth += 1;
+                pos += 2;
+                continue;
+            }
+            b'>' if data.get(pos + 1) == Some(&b'>') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos);
+                }
+                pos += 2;
+                continue;
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Byte length of a literal string starting at an opening parenthesis,
+/// including both delimiters
+fn literal_string_length(data: &[u8]) -> Option<usize> {
+    let mut depth = 0;
+    let mut pos = 0;
+    while pos < data.len() {
+        match data[pos] {
+            b'\\' => pos += 1,
+            b'(' => depth += 1,
+            b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(pos + 1);
+                }
+            }
+            _ => {}
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Read a literal `(...)` or hex `<...>` string, returning its raw bytes
+fn read_string(data: &[u8]) -> Option<Vec<u8>> {
+    match data.first()? {
+        b'(' => {
+            let body = &data[1..literal_string_length(data)? - 1];
+            let mut bytes = Vec::with_capacity(body.len());
+            let mut pos = 0;
+            while pos < body.len() {
+                let byte = body[pos];
+                pos += 1;
+                if byte != b'\\' {
+                    bytes.push(byte);
+                    continue;
+                }
+                let Some(&escaped) = body.get(pos) else {
+                    break;
+                };
+                pos += 1;
+                match escaped {
+                    b'n' => bytes.push(b'\n'),
+                    b'r' => bytes.push(b'\r'),
+                    b't' => bytes.push(b'\t'),
+                    b'b' => bytes.push(0x08),
+                    b'f' => bytes.push(0x0C),
+                    b'0'..=b'7' => {
+                        let mut value = (escaped - b'0') as u32;
+                        for _ in 0..2 {
+                            match body.get(pos) {
+                                Some(&digit @ b'0'..=b'7') => {
+                                    value = value * 8 + (digit - b'0') as u32;
+                                    pos += 1;
+                                }
+                                _ => break,
+                            }
+                        }
+                        bytes.push(value as u8);
+                    }
+                    // Escaped line breaks continue the string on the next line
+                    b'\r' => {
+                        if body.get(pos) == Some(&b'\n') {
+                            pos += 1;
+                        }
+                    }
+                    b'\n' => {}
+                    other => bytes.push(other),
+                }
+            }
+            Some(bytes)
+        }
+        b'<' => {
+            let end = data.iter().position(|&b| b == b'>')?;
+            let mut hex: Vec<u8> = data[1..end].iter().copied().filter(u8::is_ascii_hexdigit).collect();
+            if hex.len() % 2 == 1 {
+                hex.push(b'0');
+            }
+            hex.chunks(2)
+                .map(|pair| u8::from_str_radix(std::str::from_utf8(pair).ok()?, 16).ok())
+                .collect()
+        }
+        _ => None,
+    }
+}
+
+/// Decode a PDF text string: UTF-16BE when it carries a byte order mark,
+/// otherwise PDFDocEncoding, approximated here as Latin-1
+fn decode_text_string(bytes: &[u8]) -> String {
+    if let Some(utf16) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units: Vec<u16> = utf16.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+        return String::from_utf16_lossy(&units);
+    }
+    if let Some(utf8) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(utf8).into_owned();
+    }
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+fn apply_xmp(file_data: &[u8], properties: &mut PdfProperties) {
+    let Some(start) = find_bytes(file_data, b"<x:xmpmeta") else {
+        return;
+    };
+    let end = find_bytes(&file_data[start..], b"</x:xmpmeta>").map_or(file_data.len(), |end| start + end);
+    let xmp = String::from_utf8_lossy(&file_data[start..end]);
+
+    if properties.title.is_none() {
+        properties.title = XMP_TITLE.captures(&xmp).map(|c| decode_xml_entities(c[1].trim())).filter(|t| !t.is_empty());
+    }
+    if properties.author.is_none() {
+        properties.author = XMP_CREATOR.captures(&xmp).map(|c| decode_xml_entities(c[1].trim())).filter(|a| !a.is_empty());
+    }
+    if properties.creation_date.is_none() {
+        if let Some(raw) = XMP_CREATE_DATE.captures(&xmp).and_then(|c| c.get(1).or_else(|| c.get(2))) {
+            let raw = raw.as_str().trim();
+            properties.creation_date = parse_xmp_date(raw);
+            if properties.raw_creation_date.is_none() {
+                properties.raw_creation_date = Some(raw.to_string());
+            }
+        }
+    }
+}
+
+/// XMP dates are ISO 8601, with the seconds and zone both optional
+fn parse_xmp_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(date) = DateTime::parse_from_rfc3339(value) {
+        return Some(date.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .or_else(|| NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))
+        .map(|naive| Utc.from_utc_datetime(&naive))
+}
+
+fn decode_xml_entities(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
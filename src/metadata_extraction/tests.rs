@@ -1,8 +1,80 @@
 #[cfg(test)]
 mod tests {
-    use crate::metadata_extraction::extract_content_metadata;
+    use crate::metadata_extraction::{
+        embedded_creation_date, extract_content_metadata, extract_content_metadata_with_options, parse_pdf_date,
+        MetadataExtractionOptions,
+    };
     use std::fs;
-    use serde_json::Value;
+    use serde_json::{json, Value};
+
+    enum ExifValue {
+        Ascii(&'static str),
+        Long(u32),
+        Rationals(Vec<(u32, u32)>),
+    }
+
+    /// Appends a little-endian IFD with its out-of-line values and returns its offset
+    fn push_ifd(tiff: &mut Vec<u8>, entries: &[(u16, ExifValue)]) -> u32 {
+        let start = tiff.len();
+        let data_start = start + 2 + entries.len() * 12 + 4;
+        let mut data = Vec::new();
+        tiff.extend((entries.len() as u16).to_le_bytes());
+        for (tag, value) in entries {
+            let (field_type, count, bytes): (u16, u32, Vec<u8>) = match value {
+                ExifValue::Ascii(text) => (2, text.len() as u32 + 1, [text.as_bytes(), &[0]].concat()),
+                ExifValue::Long(value) => (4, 1, value.to_le_bytes().to_vec()),
+                ExifValue::Rationals(parts) => (
+                    5,
+                    parts.len() as u32,
+                    parts.iter().flat_map(|(n, d)| [n.to_le_bytes(), d.to_le_bytes()].concat()).collect(),
+                ),
+            };
+            tiff.extend(tag.to_le_bytes());
+            tiff.extend(field_type.to_le_bytes());
+            tiff.extend(count.to_le_bytes());
+            if bytes.len() <= 4 {
+                let mut inline = bytes;
+                inline.resize(4, 0);
+                tiff.extend(inline);
+            } else {
+                tiff.extend(((data_start + data.len()) as u32).to_le_bytes());
+                data.extend(bytes);
+            }
+        }
+        tiff.extend(0u32.to_le_bytes());
+        tiff.extend(data);
+        start as u32
+    }
+
+    /// A JPEG whose only content is an EXIF segment: Canon EOS R5, taken
+    /// 2023-06-15 14:30 at UTC+2 near Zurich (47°22'N 8°32'E)
+    fn jpeg_with_exif() -> Vec<u8> {
+        let mut tiff = b"II*\0\0\0\0\0".to_vec();
+        let gps = push_ifd(&mut tiff, &[
+            (0x0001, ExifValue::Ascii("N")),
+            (0x0002, ExifValue::Rationals(vec![(47, 1), (22, 1), (0, 1)])),
+            (0x0003, ExifValue::Ascii("E")),
+            (0x0004, ExifValue::Rationals(vec![(8, 1), (32, 1), (0, 1)])),
+        ]);
+        let exif = push_ifd(&mut tiff, &[
+            (0x9003, ExifValue::Ascii("2023:06:15 14:30:00")),
+            (0x9011, ExifValue::Ascii("+02:00")),
+        ]);
+        let ifd0 = push_ifd(&mut tiff, &[
+            (0x010F, ExifValue::Ascii("Canon")),
+            (0x0110, ExifValue::Ascii("EOS R5")),
+            (0x8769, ExifValue::Long(exif)),
+            (0x8825, ExifValue::Long(gps)),
+        ]);
+        tiff[4..8].copy_from_slice(&ifd0.to_le_bytes());
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend(((tiff.len() + 8) as u16).to_be_bytes());
+        jpeg.extend(b"Exif\0\0");
+        jpeg.extend(tiff);
+        jpeg.extend([0xFF, 0xD9]);
+        jpeg
+    }
 
     #[tokio::test]
     async fn test_image_metadata_extraction_portrait() {
@@ -336,4 +408,118 @@ mod tests {
         // Should not have file_extension field
         assert!(!obj.contains_key("file_extension"));
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_exif_capture_date_camera_and_gps() {
+        let metadata = extract_content_metadata(&jpeg_with_exif(), "image/jpeg", "IMG_0001.jpg")
+            .await
+            .expect("Failed to extract metadata")
+            .expect("EXIF metadata should be present");
+
+        assert_eq!(metadata["capture_date"], "2023-06-15T12:30:00+00:00");
+        assert_eq!(metadata["camera_make"], "Canon");
+        assert_eq!(metadata["camera_model"], "EOS R5");
+        let latitude = metadata["gps_latitude"].as_f64().unwrap();
+        let longitude = metadata["gps_longitude"].as_f64().unwrap();
+        assert!((latitude - 47.3667).abs() < 0.001, "latitude was {}", latitude);
+        assert!((longitude - 8.5333).abs() < 0.001, "longitude was {}", longitude);
+    }
+
+    #[tokio::test]
+    async fn test_exif_gps_stripped_when_requested() {
+        let options = MetadataExtractionOptions { strip_gps: true };
+        let metadata = extract_content_metadata_with_options(&jpeg_with_exif(), "image/jpeg", "IMG_0001.jpg", options)
+            .await
+            .expect("Failed to extract metadata")
+            .expect("EXIF metadata should be present");
+
+        assert_eq!(metadata["camera_model"], "EOS R5");
+        assert!(metadata.get("gps_latitude").is_none());
+        assert!(metadata.get("gps_longitude").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_image_without_exif() {
+        let image_data = fs::read("test_files/portrait_100x200.png").expect("Failed to read portrait test image");
+
+        let metadata = extract_content_metadata(&image_data, "image/png", "portrait_100x200.png")
+            .await
+            .expect("Failed to extract metadata")
+            .unwrap();
+
+        assert!(metadata.get("capture_date").is_none());
+        assert!(metadata.get("camera_make").is_none());
+        assert!(embedded_creation_date(&metadata).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_exif_is_ignored() {
+        let mut jpeg = jpeg_with_exif();
+        jpeg.truncate(40);
+
+        let metadata = extract_content_metadata(&jpeg, "image/jpeg", "broken.jpg")
+            .await
+            .expect("Failed to extract metadata")
+            .unwrap();
+
+        assert!(metadata.get("capture_date").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_pdf_info_dictionary_properties() {
+        let pdf = b"%PDF-1.4\n\
+1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+2 0 obj << /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >> endobj\n\
+3 0 obj << /Type /Page /Parent 2 0 R >> endobj\n\
+4 0 obj << /Type /Page /Parent 2 0 R >> endobj\n\
+5 0 obj << /Title (Quarterly \\(Q3\\) Report) /Author <FEFF004A006F00EB006C> /CreationDate (D:20210304050607+01'00') >> endobj\n\
+trailer << /Root 1 0 R /Info 5 0 R >>\n%%EOF";
+
+        let metadata = extract_content_metadata(pdf, "application/pdf", "report.pdf")
+            .await
+            .expect("Failed to extract metadata")
+            .unwrap();
+
+        assert_eq!(metadata["pdf_title"], "Quarterly (Q3) Report");
+        assert_eq!(metadata["pdf_author"], "Jo\u{eb}l");
+        assert_eq!(metadata["pdf_creation_date"], "2021-03-04T04:06:07+00:00");
+        assert_eq!(metadata["page_count"], 2, "the /Pages node is not a page");
+    }
+
+    #[tokio::test]
+    async fn test_pdf_xmp_fallback() {
+        let pdf = b"%PDF-1.7\n\
+1 0 obj << /Type /Metadata /Subtype /XML >> stream\n\
+<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"><rdf:RDF><rdf:Description xmp:CreateDate=\"2019-11-02T08:15:00Z\">\
+<dc:title><rdf:Alt><rdf:li xml:lang=\"x-default\">Site &amp; Survey</rdf:li></rdf:Alt></dc:title>\
+<dc:creator><rdf:Seq><rdf:li>Ada Lovelace</rdf:li></rdf:Seq></dc:creator>\
+</rdf:Description></rdf:RDF></x:xmpmeta>\nendstream endobj\n%%EOF";
+
+        let metadata = extract_content_metadata(pdf, "application/pdf", "survey.pdf")
+            .await
+            .expect("Failed to extract metadata")
+            .unwrap();
+
+        assert_eq!(metadata["pdf_title"], "Site & Survey");
+        assert_eq!(metadata["pdf_author"], "Ada Lovelace");
+        assert_eq!(metadata["pdf_creation_date"], "2019-11-02T08:15:00+00:00");
+    }
+
+    #[test]
+    fn test_parse_pdf_date() {
+        assert_eq!(parse_pdf_date("D:20210304050607Z").unwrap().to_rfc3339(), "2021-03-04T05:06:07+00:00");
+        assert_eq!(parse_pdf_date("D:20210304050607-05'30'").unwrap().to_rfc3339(), "2021-03-04T10:36:07+00:00");
+        assert_eq!(parse_pdf_date("D:2021").unwrap().to_rfc3339(), "2021-01-01T00:00:00+00:00");
+        assert!(parse_pdf_date("D:20211340").is_none());
+        assert!(parse_pdf_date("yesterday").is_none());
+    }
+
+    #[test]
+    fn test_embedded_creation_date_prefers_capture_date() {
+        let photo = json!({"capture_date": "2023-06-15T12:30:00+00:00", "pdf_creation_date": "2001-01-01T00:00:00+00:00"});
+        assert_eq!(embedded_creation_date(&photo).unwrap().to_rfc3339(), "2023-06-15T12:30:00+00:00");
+
+        let unparsed = json!({"pdf_creation_date": "sometime in 2001"});
+        assert!(embedded_creation_date(&unparsed).is_none());
+    }
+}
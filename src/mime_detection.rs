@@ -9,8 +9,13 @@
 /// for OCR processing where incorrectly classified image files can cause issues.
 
 use std::path::Path;
+use tokio::io::AsyncReadExt;
 use tracing::{debug, warn};
 
+/// Bytes inspected when sniffing content. Every signature Readur relies on sits
+/// well inside this window, so files never need to be read in full.
+pub const SNIFF_LENGTH: usize = 8 * 1024;
+
 /// Strategy for MIME type detection
 #[derive(Debug, Clone, PartialEq)]
 pub enum DetectionStrategy {
@@ -201,12 +206,22 @@ pub fn detect_mime_from_content(
 ) -> MimeDetectionResult {
     debug!("Detecting MIME type from content: filename={}, server_type={:?}, content_len={}", 
            filename, server_mime_type, content.len());
+    let content = &content[..content.len().min(SNIFF_LENGTH)];
 
     // First, try magic byte detection
-    if let Some(detected_type) = infer::get(content) {
-        let mime_type = detected_type.mime_type().to_string();
+    if let Some(mime_type) = sniff_signature(content) {
         debug!("Magic bytes detected MIME type: {}", mime_type);
 
+        // ZIP and OLE containers carry office documents; a matching declared type is more specific
+        if let Some(declared) = declared_mime_type(filename, server_mime_type) {
+            if is_container_of(&mime_type, &declared) {
+                let mut result = MimeDetectionResult::from_content(declared, server_mime_type.map(str::to_string));
+                result.confidence = MimeConfidence::VeryHigh;
+                result.detection_method = DetectionMethod::Hybrid;
+                return result;
+            }
+        }
+
         // If server provided a type, check for consistency
         if let Some(server_type) = server_mime_type {
             if are_mime_types_compatible(&mime_type, server_type) {
@@ -227,9 +242,13 @@ pub fn detect_mime_from_content(
         }
     }
 
-    // Magic bytes detection failed, fall back to server type if trusted
+    // Magic bytes detection failed. Declared types that always carry a signature
+    // (PDFs, raster images) are wrong if it is missing, so only trust the others.
+    let verifiable = !content.is_empty();
+
+    // Fall back to server type if trusted
     if let Some(server_type) = server_mime_type {
-        if is_trusted_server_mime_type(server_type) {
+        if is_trusted_server_mime_type(server_type) && !(verifiable && has_signature(server_type)) {
             debug!("Using trusted server MIME type: {}", server_type);
             return MimeDetectionResult::from_server(server_type.to_string());
         }
@@ -237,7 +256,173 @@ pub fn detect_mime_from_content(
 
     // Fall back to extension-based detection
     debug!("Content detection failed, falling back to extension detection");
-    detect_from_extension(filename, server_mime_type)
+    let from_extension = detect_from_extension(filename, server_mime_type);
+    if !verifiable || (from_extension.detection_method == DetectionMethod::Extension && !has_signature(&from_extension.mime_type)) {
+        return from_extension;
+    }
+
+    if let Some(declared) = declared_mime_type(filename, server_mime_type) {
+        warn!("MIME type mismatch: {} declared as {} but has no matching signature", filename, declared);
+    }
+
+    if looks_like_text(content) {
+        let mut result = MimeDetectionResult::from_content("text/plain".to_string(), server_mime_type.map(str::to_string));
+        result.confidence = MimeConfidence::Medium;
+        return result;
+    }
+
+    let mut result = MimeDetectionResult::fallback();
+    result.original_server_type = server_mime_type.map(str::to_string);
+    result
+}
+
+/// Detect a stored file's MIME type from its first `SNIFF_LENGTH` bytes, using
+/// the declared type as a hint. Falls back to the declared type if the file
+/// can't be read.
+pub async fn detect_mime_from_file(file_path: &str, declared_mime_type: &str) -> MimeDetectionResult {
+    detect_mime_from_stored_file(file_path, file_path, declared_mime_type).await
+}
+
+/// Like `detect_mime_from_file`, for files stored under a different name than
+/// the one they were declared with, e.g. uploads
+pub async fn detect_mime_from_stored_file(file_path: &str, filename: &str, declared_mime_type: &str) -> MimeDetectionResult {
+    match read_file_head(file_path).await {
+        Ok(head) => detect_mime_from_content(&head, filename, Some(declared_mime_type)),
+        Err(e) => {
+            warn!("Failed to read {} for MIME sniffing: {}", file_path, e);
+            MimeDetectionResult::from_server(declared_mime_type.to_string())
+        }
+    }
+}
+
+/// Why a file was refused by an allow-list of extensions
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileTypeRejection {
+    /// The filename's extension is not on the list
+    ExtensionNotAllowed { filename: String },
+    /// The extension is allowed but the content is of a type that is not
+    ContentNotAllowed { filename: String, declared: String, detected: String },
+}
+
+impl std::fmt::Display for FileTypeRejection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ExtensionNotAllowed { filename } => write!(f, "File type of '{}' is not allowed", filename),
+            Self::ContentNotAllowed { filename, declared, detected } => write!(
+                f,
+                "'{}' is declared as {} but its content was detected as {}, which is not an allowed file type",
+                filename, declared, detected
+            ),
+        }
+    }
+}
+
+/// Checks a file against `allowed_extensions` by both its extension and its detected
+/// content, so renaming a file does not get it past the list. Content detected as plain
+/// text is accepted under any allowed extension that does not promise a binary format,
+/// as text comes in too many variants to tell apart.
+pub fn check_allowed_file_type(
+    detection: &MimeDetectionResult,
+    filename: &str,
+    allowed_extensions: &[String],
+) -> Result<(), FileTypeRejection> {
+    let is_allowed = |ext: &str| allowed_extensions.iter().any(|allowed| allowed.eq_ignore_ascii_case(ext));
+
+    let extension = Path::new(filename).extension().and_then(|ext| ext.to_str()).unwrap_or("");
+    if !is_allowed(extension) {
+        return Err(FileTypeRejection::ExtensionNotAllowed { filename: filename.to_string() });
+    }
+
+    let declared = mime_guess::from_path(filename).first_or_octet_stream().to_string();
+    let allowed = if detection.mime_type == declared {
+        true
+    } else if detection.mime_type == "text/plain" {
+        // mime_guess maps dozens of source and config extensions to text/plain; only
+        // the declared type and "txt" say whether text was meant to be accepted
+        !has_signature(&declared) || is_allowed("txt")
+    } else {
+        mime_guess::get_mime_extensions_str(&detection.mime_type)
+            .unwrap_or(&[])
+            .iter()
+            .any(|ext| is_allowed(ext))
+    };
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(FileTypeRejection::ContentNotAllowed {
+            filename: filename.to_string(),
+            declared,
+            detected: detection.mime_type.clone(),
+        })
+    }
+}
+
+/// Match the signature of a file on disk, reading at most `SNIFF_LENGTH` bytes.
+/// `Ok(None)` means the file is readable but empty or of no known format.
+pub fn sniff_file_signature(file_path: &str) -> std::io::Result<Option<String>> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(SNIFF_LENGTH);
+    std::fs::File::open(file_path)?.take(SNIFF_LENGTH as u64).read_to_end(&mut head)?;
+    Ok(sniff_signature(&head))
+}
+
+async fn read_file_head(file_path: &str) -> std::io::Result<Vec<u8>> {
+    let file = tokio::fs::File::open(file_path).await?;
+    let mut head = Vec::with_capacity(SNIFF_LENGTH);
+    file.take(SNIFF_LENGTH as u64).read_to_end(&mut head).await?;
+    Ok(head)
+}
+
+/// Match the file signature. PDFs may carry junk before their header, which
+/// readers accept within the first kilobyte.
+fn sniff_signature(content: &[u8]) -> Option<String> {
+    if let Some(detected_type) = infer::get(content) {
+        return Some(detected_type.mime_type().to_string());
+    }
+    let window = &content[..content.len().min(1024)];
+    window.windows(5).any(|w| w == b"%PDF-").then(|| "application/pdf".to_string())
+}
+
+/// The type the caller claims: a trusted server type, else the extension's
+fn declared_mime_type(filename: &str, server_mime_type: Option<&str>) -> Option<String> {
+    if let Some(server_type) = server_mime_type.filter(|t| is_trusted_server_mime_type(t)) {
+        return Some(server_type.to_string());
+    }
+    mime_guess::from_path(filename).first().map(|m| m.to_string())
+}
+
+/// Formats whose content always starts with a signature we can sniff
+fn has_signature(mime_type: &str) -> bool {
+    mime_type == "application/pdf" || (mime_type.starts_with("image/") && mime_type != "image/svg+xml")
+}
+
+/// Whether a sniffed generic container (ZIP or OLE compound file) can hold the declared format
+fn is_container_of(sniffed: &str, declared: &str) -> bool {
+    match sniffed {
+        "application/zip" => {
+            declared.starts_with("application/vnd.openxmlformats-officedocument.")
+                || declared.starts_with("application/vnd.oasis.opendocument.")
+                || declared == "application/epub+zip"
+        }
+        "application/x-ole-storage" => matches!(declared,
+            "application/msword" | "application/vnd.ms-excel" | "application/vnd.ms-powerpoint"
+        ),
+        _ => false,
+    }
+}
+
+/// Heuristic for plain text: valid UTF-8 (allowing a character cut off at the
+/// sniff boundary) without NUL or other binary control bytes
+fn looks_like_text(content: &[u8]) -> bool {
+    if content.is_empty() || content.iter().any(|&b| b == 0 || (b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0C))) {
+        return false;
+    }
+    match std::str::from_utf8(content) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none() && content.len() == SNIFF_LENGTH,
+    }
 }
 
 /// Update an existing MIME type with content-based detection if available
@@ -417,6 +602,143 @@ mod tests {
         assert_eq!(get_mime_type_from_extension("png"), "image/png");
     }
 
+    #[test]
+    fn test_content_overrides_wrong_extension() {
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+        let result = detect_mime_from_content(&jpeg, "scan.pdf", Some("application/pdf"));
+        assert_eq!(result.mime_type, "image/jpeg");
+        assert_eq!(result.detection_method, DetectionMethod::MagicBytes);
+        assert_eq!(result.original_server_type.as_deref(), Some("application/pdf"));
+    }
+
+    #[test]
+    fn test_pdf_header_after_leading_junk() {
+        let mut content = vec![b' '; 200];
+        content.extend_from_slice(b"%PDF-1.7\n");
+        let result = detect_mime_from_content(&content, "upload", None);
+        assert_eq!(result.mime_type, "application/pdf");
+    }
+
+    #[test]
+    fn test_missing_signature_is_not_trusted() {
+        // Declared as a PDF, but it's plain text
+        let result = detect_mime_from_content(b"Meeting notes\n", "notes.pdf", Some("application/pdf"));
+        assert_eq!(result.mime_type, "text/plain");
+
+        // Declared as an image, but it's unrecognisable binary
+        let result = detect_mime_from_content(&[0x00, 0x13, 0x37, 0x00, 0xFE], "photo.png", None);
+        assert_eq!(result.mime_type, "application/octet-stream");
+        assert_eq!(result.detection_method, DetectionMethod::Fallback);
+    }
+
+    #[test]
+    fn test_extensionless_files() {
+        let result = detect_mime_from_content(b"plain words, nothing else", "README", None);
+        assert_eq!(result.mime_type, "text/plain");
+
+        let result = detect_mime_from_content(&[0x00, 0x01, 0x02, 0x03], "blob", None);
+        assert_eq!(result.mime_type, "application/octet-stream");
+
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        let result = detect_mime_from_content(png, "scan", Some("application/octet-stream"));
+        assert_eq!(result.mime_type, "image/png");
+    }
+
+    #[test]
+    fn test_text_formats_keep_declared_type() {
+        let result = detect_mime_from_content(b"name,amount\nrent,900\n", "ledger.csv", None);
+        assert_eq!(result.mime_type, "text/csv");
+        assert_eq!(result.detection_method, DetectionMethod::Extension);
+    }
+
+    #[test]
+    fn test_containers_keep_specific_declared_type() {
+        // A bare ZIP header is all infer can see of an Office document here
+        let zip = b"PK\x03\x04\x14\x00\x00\x00\x08\x00";
+        let docx = "application/vnd.openxmlformats-officedocument.wordprocessingml.document";
+        let result = detect_mime_from_content(zip, "report.docx", None);
+        assert_eq!(result.mime_type, docx);
+        assert_eq!(result.detection_method, DetectionMethod::Hybrid);
+
+        let result = detect_mime_from_content(zip, "bundle.zip", Some("application/zip"));
+        assert_eq!(result.mime_type, "application/zip");
+
+        let result = detect_mime_from_content(zip, "scan.pdf", None);
+        assert_eq!(result.mime_type, "application/zip");
+    }
+
+    #[test]
+    fn test_only_the_sniff_window_is_inspected() {
+        let mut content = vec![b'a'; SNIFF_LENGTH];
+        content.extend_from_slice(&[0x00; 64]);
+        let result = detect_mime_from_content(&content, "log", None);
+        assert_eq!(result.mime_type, "text/plain");
+    }
+
+    #[test]
+    fn test_empty_content_keeps_declared_type() {
+        let result = detect_mime_from_content(&[], "empty.pdf", Some("application/pdf"));
+        assert_eq!(result.mime_type, "application/pdf");
+        assert_eq!(result.detection_method, DetectionMethod::ServerProvided);
+    }
+
+    #[tokio::test]
+    async fn test_detect_mime_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invoice.pdf");
+        std::fs::write(&path, [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]).unwrap();
+        let path = path.to_str().unwrap();
+
+        assert_eq!(detect_mime_from_file(path, "application/pdf").await.mime_type, "image/png");
+        assert_eq!(sniff_file_signature(path).unwrap().as_deref(), Some("image/png"));
+
+        let missing = dir.path().join("missing.pdf");
+        let result = detect_mime_from_file(missing.to_str().unwrap(), "application/pdf").await;
+        assert_eq!(result.mime_type, "application/pdf");
+        assert!(sniff_file_signature(missing.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_allowed_file_type_checks_content() {
+        let allowed: Vec<String> = ["pdf", "txt", "png"].iter().map(|ext| ext.to_string()).collect();
+        let check = |content: &[u8], filename: &str| {
+            check_allowed_file_type(&detect_mime_from_content(content, filename, None), filename, &allowed)
+        };
+
+        assert_eq!(check(b"%PDF-1.7\n", "invoice.pdf"), Ok(()));
+        assert_eq!(check(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", "scan.PNG"), Ok(()));
+        assert_eq!(check(b"Meeting notes\n", "notes.txt"), Ok(()));
+
+        // A renamed executable
+        let exe = b"MZ\x90\x00\x03\x00\x00\x00\x04\x00\x00\x00\xff\xff\x00\x00";
+        match check(exe, "invoice.pdf") {
+            Err(FileTypeRejection::ContentNotAllowed { declared, detected, .. }) => {
+                assert_eq!(declared, "application/pdf");
+                assert_eq!(detected, "application/vnd.microsoft.portable-executable");
+            }
+            other => panic!("renamed executable was not rejected: {:?}", other),
+        }
+
+        // A JPEG is fine content, but not on this list
+        let jpeg = [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F'];
+        assert!(matches!(check(&jpeg, "photo.png"), Err(FileTypeRejection::ContentNotAllowed { .. })));
+        assert_eq!(check(&jpeg, "photo.jpg"), Err(FileTypeRejection::ExtensionNotAllowed { filename: "photo.jpg".to_string() }));
+        assert!(matches!(check(exe, "setup"), Err(FileTypeRejection::ExtensionNotAllowed { .. })));
+    }
+
+    #[test]
+    fn test_allowed_file_type_is_permissive_with_text() {
+        let allowed: Vec<String> = vec!["log".to_string(), "md".to_string(), "pdf".to_string()];
+        let check = |content: &[u8], filename: &str| {
+            check_allowed_file_type(&detect_mime_from_content(content, filename, None), filename, &allowed)
+        };
+
+        assert_eq!(check(b"2025-08-01 started\n", "server.log"), Ok(()));
+        assert_eq!(check(b"# Title\n", "README.md"), Ok(()));
+        // Text is no PDF, and "txt" is not allowed here
+        assert!(matches!(check(b"Meeting notes\n", "notes.pdf"), Err(FileTypeRejection::ContentNotAllowed { .. })));
+    }
+
     #[test]
     fn test_ocr_suitability() {
         let pdf_result = MimeDetectionResult::from_content("application/pdf".to_string(), None);
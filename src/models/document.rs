@@ -191,6 +191,45 @@ pub struct FailedDocument {
     pub updated_at: DateTime<Utc>,
 }
 
+/// A superseded version of a document, archived when its source file changed
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentVersion {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub version_number: i32,
+    pub filename: String,
+    pub file_path: String,
+    pub file_size: i64,
+    pub mime_type: String,
+    pub file_hash: Option<String>,
+    pub content: Option<String>,
+    pub ocr_text: Option<String>,
+    pub ocr_confidence: Option<f32>,
+    pub ocr_word_count: Option<i32>,
+    /// Modification time reported by the source for this version
+    pub original_modified_at: Option<DateTime<Utc>>,
+    /// When this version was first ingested
+    pub created_at: DateTime<Utc>,
+    /// When a newer version replaced it
+    pub superseded_at: DateTime<Utc>,
+}
+
+/// A link that gives anyone holding its token access to one document
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentShare {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub token: String,
+    pub created_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    /// Whether the link also serves the file, not just its metadata
+    pub allow_download: bool,
+    pub created_at: DateTime<Utc>,
+    /// Number of times the link was used
+    pub access_count: i64,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct ProcessedImage {
     pub id: Uuid,
@@ -277,4 +316,302 @@ pub struct FileIngestionInfo {
     pub group: Option<String>,
     /// Additional metadata from source (EXIF, PDF metadata, custom attributes, etc.)
     pub metadata: Option<serde_json::Value>,
-}
\ No newline at end of file
+}
+
+/// How a document run ended, or `running` while it is in progress
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentRunOutcome {
+    Running,
+    Success,
+    Failed,
+    Cancelled,
+}
+
+impl std::fmt::Display for DocumentRunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentRunOutcome::Running => write!(f, "running"),
+            DocumentRunOutcome::Success => write!(f, "success"),
+            DocumentRunOutcome::Failed => write!(f, "failed"),
+            DocumentRunOutcome::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl TryFrom<String> for DocumentRunOutcome {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "running" => Ok(DocumentRunOutcome::Running),
+            "success" => Ok(DocumentRunOutcome::Success),
+            "failed" => Ok(DocumentRunOutcome::Failed),
+            "cancelled" => Ok(DocumentRunOutcome::Cancelled),
+            _ => Err(format!("Invalid document run outcome: {}", value)),
+        }
+    }
+}
+
+/// The part of a document run that depends on its kind, stored as JSON in `document_runs.details`
+pub trait DocumentRunDetails: Clone + Serialize + serde::de::DeserializeOwned + Send + Sync + Unpin + 'static {
+    /// The `kind` of the runs with these details. Only one run of a kind may be running.
+    const KIND: &'static str;
+}
+
+/// A long-running admin operation over the documents, started from the API or at startup
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DocumentRun<D> {
+    pub id: Uuid,
+    /// The admin who started the run; `None` for runs started at server startup
+    pub started_by: Option<Uuid>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    #[serde(flatten)]
+    pub details: D,
+    pub outcome: DocumentRunOutcome,
+    pub error_message: Option<String>,
+}
+
+/// A bulk re-hash of every document file against the database
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+pub struct ReingestDetails {
+    /// Whether documents whose file changed are re-enqueued for OCR
+    pub reenqueue_ocr: bool,
+    pub total_documents: i64,
+    pub checked: i64,
+    /// Files whose hash differs from the stored `file_hash`
+    pub mismatched: i64,
+    pub missing: i64,
+    pub reenqueued: i64,
+}
+
+impl DocumentRunDetails for ReingestDetails {
+    const KIND: &'static str = "reingest";
+}
+
+/// A backfill of file hashes and OCR word counts missing from older documents
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+pub struct BackfillDetails {
+    /// Documents missing a file hash or a word count when the run started
+    pub total_documents: i64,
+    pub checked: i64,
+    pub hashes_filled: i64,
+    pub word_counts_filled: i64,
+    /// Documents whose file could not be read; they are flagged as `missing`
+    pub missing: i64,
+    /// Files whose hash matches another of the owner's documents, left without a hash
+    pub hash_conflicts: i64,
+}
+
+impl DocumentRunDetails for BackfillDetails {
+    const KIND: &'static str = "backfill";
+}
+
+/// What a database maintenance run does to the document tables
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MaintenanceOperation {
+    /// Rebuilds the document search indexes and refreshes the planner statistics
+    Reindex,
+    /// Vacuums and analyzes the documents and OCR queue tables
+    Vacuum,
+}
+
+impl std::fmt::Display for MaintenanceOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaintenanceOperation::Reindex => write!(f, "reindex"),
+            MaintenanceOperation::Vacuum => write!(f, "vacuum"),
+        }
+    }
+}
+
+impl TryFrom<String> for MaintenanceOperation {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "reindex" => Ok(MaintenanceOperation::Reindex),
+            "vacuum" => Ok(MaintenanceOperation::Vacuum),
+            _ => Err(format!("Invalid maintenance operation: {}", value)),
+        }
+    }
+}
+
+/// One statement of a maintenance run
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceStep {
+    /// The statement, e.g. `REINDEX INDEX CONCURRENTLY idx_documents_content_trgm`
+    pub statement: String,
+    /// How long the statement took; `None` until it finished
+    pub duration_ms: Option<i64>,
+}
+
+/// A rebuild of the search indexes or a vacuum of the document tables
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceDetails {
+    pub operation: MaintenanceOperation,
+    pub steps: Vec<MaintenanceStep>,
+    pub completed_steps: i32,
+}
+
+impl DocumentRunDetails for MaintenanceDetails {
+    const KIND: &'static str = "maintenance";
+}
+
+/// A lifecycle transition in a document's processing history
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DocumentEventType {
+    Uploaded,
+    NewVersion,
+    Queued,
+    OcrStarted,
+    /// A failed or low-confidence OCR attempt that goes back in the queue
+    OcrRetried,
+    OcrFailed,
+    OcrCompleted,
+    OcrCancelled,
+}
+
+impl std::fmt::Display for DocumentEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DocumentEventType::Uploaded => write!(f, "uploaded"),
+            DocumentEventType::NewVersion => write!(f, "new_version"),
+            DocumentEventType::Queued => write!(f, "queued"),
+            DocumentEventType::OcrStarted => write!(f, "ocr_started"),
+            DocumentEventType::OcrRetried => write!(f, "ocr_retried"),
+            DocumentEventType::OcrFailed => write!(f, "ocr_failed"),
+            DocumentEventType::OcrCompleted => write!(f, "ocr_completed"),
+            DocumentEventType::OcrCancelled => write!(f, "ocr_cancelled"),
+        }
+    }
+}
+
+impl TryFrom<String> for DocumentEventType {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, <DocumentEventType as TryFrom<String>>::Error> {
+        match value.as_str() {
+            "uploaded" => Ok(DocumentEventType::Uploaded),
+            "new_version" => Ok(DocumentEventType::NewVersion),
+            "queued" => Ok(DocumentEventType::Queued),
+            "ocr_started" => Ok(DocumentEventType::OcrStarted),
+            "ocr_retried" => Ok(DocumentEventType::OcrRetried),
+            "ocr_failed" => Ok(DocumentEventType::OcrFailed),
+            "ocr_completed" => Ok(DocumentEventType::OcrCompleted),
+            "ocr_cancelled" => Ok(DocumentEventType::OcrCancelled),
+            _ => Err(format!("Invalid document event type: {}", value)),
+        }
+    }
+}
+
+/// An entry in a document's processing history
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentEvent {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    #[sqlx(try_from = "String")]
+    pub event_type: DocumentEventType,
+    /// Source of an upload, priority of a queued job, or the error of a failed attempt
+    pub detail: Option<String>,
+    /// OCR worker that made the transition
+    pub worker_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A bulk OCR operation that feeds its documents into the OCR queue a window at a time
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct BatchJob {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// `reocr` or `reprocess_language`
+    pub kind: String,
+    /// `running` until every document has completed, failed or been skipped, then `completed`
+    pub status: String,
+    pub priority: i32,
+    /// OCR overrides the documents are queued with
+    pub ocr_overrides: Option<serde_json::Value>,
+    /// Whether the job replaces OCR text that was corrected by hand
+    pub force: bool,
+    /// Documents the job covers
+    pub total: i32,
+    /// Documents handed to the OCR queue so far
+    pub enqueued: i32,
+    pub done: i32,
+    pub failed: i32,
+    /// Documents left alone because OCR was already pending or running for them
+    pub skipped: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// A document whose retention period has run out
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct RetentionCandidate {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub original_filename: String,
+    pub created_at: DateTime<Utc>,
+    /// The retention that applies: the longest among the document's labels, else the user's default
+    pub retention_days: i32,
+    pub expired_at: DateTime<Utc>,
+}
+
+/// How an image document is turned to show it upright
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DocumentOrientation {
+    /// Clockwise rotation applied to thumbnails and processed images: 0, 90, 180 or 270
+    pub rotation_degrees: i32,
+    /// Clockwise rotation orientation detection found the document needs, if it ran
+    pub detected_rotation_degrees: Option<i32>,
+    /// Tesseract's orientation confidence, a relative score; below 14 a detection is not applied
+    pub orientation_confidence: Option<f32>,
+    /// Detection was too unsure to rotate the document, which may need rotating by hand
+    pub needs_review: bool,
+    /// The rotation was chosen by the user and is kept when OCR runs again
+    pub rotation_by_user: bool,
+}
+
+/// Order of the document list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+pub enum DocumentListSort {
+    /// Newest documents first
+    #[default]
+    #[serde(rename = "newest")]
+    Newest,
+    /// Oldest documents first
+    #[serde(rename = "oldest")]
+    Oldest,
+}
+
+/// Position in the document list after which the next page starts. Encoded for clients as
+/// an opaque string of the last document's `created_at` and `id`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocumentCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl DocumentCursor {
+    pub fn encode(&self) -> String {
+        format!("{}_{}", self.created_at.timestamp_micros(), self.id.simple())
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let (micros, id) = cursor.split_once('_')?;
+        Some(Self {
+            created_at: DateTime::from_timestamp_micros(micros.parse().ok()?)?,
+            id: Uuid::try_parse(id).ok()?,
+        })
+    }
+}
+
+impl From<&Document> for DocumentCursor {
+    fn from(document: &Document) -> Self {
+        Self { created_at: document.created_at, id: document.id }
+    }
+}
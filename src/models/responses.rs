@@ -180,8 +180,18 @@ pub struct DocumentOcrResponse {
     pub ocr_processing_time_ms: Option<i32>,
     /// Language detected in the document
     pub detected_language: Option<String>,
+    /// Script Tesseract detected on the last OCR run, e.g. `Latin` or `Cyrillic`; only
+    /// set when the `ocr_detect_script` setting was on
+    pub detected_script: Option<String>,
+    /// Tesseract's relative confidence in `detected_script`
+    pub script_confidence: Option<f32>,
     /// Number of pages processed (for multi-page documents)
     pub pages_processed: Option<i32>,
+    /// Whether `ocr_text` was corrected by hand; OCR runs keep it unless told to overwrite it
+    pub manually_edited: bool,
+    /// Where `ocr_text` came from: `embedded` for text stored in the file, like the text layer
+    /// of a born-digital PDF, or `ocr` for text Tesseract recognized
+    pub text_source: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
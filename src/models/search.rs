@@ -1,26 +1,99 @@
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::str::FromStr;
 use utoipa::{ToSchema, IntoParams};
+use uuid::Uuid;
 
 use super::responses::EnhancedDocumentResponse;
+use super::settings::Settings;
 
 #[derive(Debug, Serialize, Deserialize, ToSchema, IntoParams)]
 pub struct SearchRequest {
     /// Search query text (searches both document content and OCR-extracted text)
     pub query: String,
-    /// Filter by specific tags
+    /// Filter by specific tags (comma-separated in query strings)
+    #[serde(default, deserialize_with = "deserialize_list")]
     pub tags: Option<Vec<String>>,
+    /// Filter by label IDs (comma-separated in query strings)
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub labels: Option<Vec<Uuid>>,
+    /// Whether documents need any (default) or all of the given tags and labels
+    pub filter_match: Option<FilterMatch>,
     /// Filter by MIME types (e.g., "application/pdf", "image/png")
+    #[serde(default, deserialize_with = "deserialize_list")]
     pub mime_types: Option<Vec<String>>,
-    /// Maximum number of results to return (default: 25)
+    /// Maximum number of results to return (default: your `search_results_per_page` setting)
     pub limit: Option<i64>,
     /// Number of results to skip for pagination (default: 0)
     pub offset: Option<i64>,
     /// Whether to include text snippets with search matches (default: true)
     pub include_snippets: Option<bool>,
-    /// Length of text snippets in characters (default: 200)
+    /// Length of text snippets in characters (default: your `search_snippet_length` setting)
     pub snippet_length: Option<i32>,
+    /// Whether snippets from OCR text are cleaned of OCR noise (default: your `search_clean_snippets` setting)
+    pub clean_snippets: Option<bool>,
     /// Search algorithm to use (default: simple)
     pub search_mode: Option<SearchMode>,
+    /// Result ordering (default: your `search_sort` setting)
+    pub sort: Option<SearchSort>,
+    /// Only documents originally created (photo capture or PDF creation date) at or after this time
+    pub captured_after: Option<DateTime<Utc>>,
+    /// Only documents originally created before this time
+    pub captured_before: Option<DateTime<Utc>>,
+}
+
+/// Most results one search page may hold
+pub const MAX_SEARCH_LIMIT: i64 = 1000;
+
+impl SearchRequest {
+    /// Fills in the page size, snippet length and cleaning, and order the request leaves open
+    /// from the user's saved search settings
+    pub fn apply_user_defaults(&mut self, settings: &Settings) {
+        self.limit.get_or_insert(i64::from(settings.search_results_per_page).clamp(1, MAX_SEARCH_LIMIT));
+        self.snippet_length.get_or_insert(settings.search_snippet_length.max(0));
+        self.clean_snippets.get_or_insert(settings.search_clean_snippets);
+        self.sort.get_or_insert(settings.search_sort);
+    }
+}
+
+/// How the `tags` and `labels` filters of a search combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterMatch {
+    /// Documents with at least one of the tags and at least one of the labels
+    #[default]
+    Any,
+    /// Documents with every one of the tags and every one of the labels
+    All,
+}
+
+/// Accepts a list either as a sequence (JSON) or as a comma-separated string
+/// (query strings, which can't repeat a field)
+fn deserialize_list<'de, D, T>(deserializer: D) -> Result<Option<Vec<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: std::fmt::Display,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum List {
+        Items(Vec<String>),
+        CommaSeparated(String),
+    }
+
+    let items = match Option::<List>::deserialize(deserializer)? {
+        None => return Ok(None),
+        Some(List::Items(items)) => items,
+        Some(List::CommaSeparated(value)) => value.split(',').map(str::to_string).collect(),
+    };
+    items
+        .iter()
+        .map(|item| item.trim())
+        .filter(|item| !item.is_empty())
+        .map(|item| item.parse::<T>().map_err(serde::de::Error::custom))
+        .collect::<Result<Vec<T>, _>>()
+        .map(Some)
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -45,6 +118,42 @@ impl Default for SearchMode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+pub enum SearchSort {
+    /// Highest relevance score first, newest first among equal scores
+    #[default]
+    #[serde(rename = "relevance")]
+    Relevance,
+    /// Newest documents first
+    #[serde(rename = "date")]
+    Date,
+    /// Alphabetical by original filename
+    #[serde(rename = "name")]
+    Name,
+}
+
+impl std::fmt::Display for SearchSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchSort::Relevance => write!(f, "relevance"),
+            SearchSort::Date => write!(f, "date"),
+            SearchSort::Name => write!(f, "name"),
+        }
+    }
+}
+
+impl TryFrom<String> for SearchSort {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, <SearchSort as TryFrom<String>>::Error> {
+        match value.as_str() {
+            "relevance" => Ok(SearchSort::Relevance),
+            "date" => Ok(SearchSort::Date),
+            "name" => Ok(SearchSort::Name),
+            _ => Err(format!("Invalid search sort: {}", value)),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct SearchResponse {
@@ -4,6 +4,10 @@ use sqlx::FromRow;
 use uuid::Uuid;
 use utoipa::ToSchema;
 
+use super::search::SearchSort;
+use super::source::SyncNotificationMode;
+use crate::ocr::preprocessing::PreprocessingConfig;
+
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Settings {
     pub id: Uuid,
@@ -20,6 +24,8 @@ pub struct Settings {
     pub enable_image_preprocessing: bool,
     pub search_results_per_page: i32,
     pub search_snippet_length: i32,
+    #[sqlx(try_from = "String")]
+    pub search_sort: SearchSort,
     pub fuzzy_search_threshold: f32,
     pub retention_days: Option<i32>,
     pub enable_auto_cleanup: bool,
@@ -52,6 +58,24 @@ pub struct Settings {
     pub ocr_quality_threshold_noise: f32,
     pub ocr_quality_threshold_sharpness: f32,
     pub ocr_skip_enhancement: bool,
+    pub ocr_parallel_pages: bool,
+    pub ocr_retry_low_confidence: bool,
+    pub ocr_detect_script: bool,
+    /// Preprocessing pipeline run on images instead of the quality-driven enhancements
+    #[sqlx(json(nullable))]
+    pub ocr_preprocessing: Option<PreprocessingConfig>,
+    /// Strip OCR noise from search snippets taken from OCR text
+    pub search_clean_snippets: bool,
+    /// PDFs whose text layer has at least this many characters skip OCR; 0 always runs OCR
+    pub ocr_text_layer_min_chars: i32,
+    /// Whether finished source syncs are reported with one summary or per file
+    #[sqlx(try_from = "String")]
+    pub sync_notification_mode: SyncNotificationMode,
+    /// Source that documents uploaded through the API are filed under
+    pub default_source_id: Option<Uuid>,
+    /// Whether uploads filed under the default source are also written to it
+    pub default_source_write_back: bool,
+    pub extract_archives: bool,
     pub webdav_enabled: bool,
     pub webdav_server_url: Option<String>,
     pub webdav_username: Option<String>,
@@ -78,6 +102,7 @@ pub struct SettingsResponse {
     pub enable_image_preprocessing: bool,
     pub search_results_per_page: i32,
     pub search_snippet_length: i32,
+    pub search_sort: SearchSort,
     pub fuzzy_search_threshold: f32,
     pub retention_days: Option<i32>,
     pub enable_auto_cleanup: bool,
@@ -110,6 +135,16 @@ pub struct SettingsResponse {
     pub ocr_quality_threshold_noise: f32,
     pub ocr_quality_threshold_sharpness: f32,
     pub ocr_skip_enhancement: bool,
+    pub ocr_parallel_pages: bool,
+    pub ocr_retry_low_confidence: bool,
+    pub ocr_detect_script: bool,
+    pub ocr_preprocessing: Option<PreprocessingConfig>,
+    pub search_clean_snippets: bool,
+    pub ocr_text_layer_min_chars: i32,
+    pub sync_notification_mode: SyncNotificationMode,
+    pub default_source_id: Option<Uuid>,
+    pub default_source_write_back: bool,
+    pub extract_archives: bool,
     pub webdav_enabled: bool,
     pub webdav_server_url: Option<String>,
     pub webdav_username: Option<String>,
@@ -134,6 +169,7 @@ pub struct UpdateSettings {
     pub enable_image_preprocessing: Option<bool>,
     pub search_results_per_page: Option<i32>,
     pub search_snippet_length: Option<i32>,
+    pub search_sort: Option<SearchSort>,
     pub fuzzy_search_threshold: Option<f32>,
     pub retention_days: Option<Option<i32>>,
     pub enable_auto_cleanup: Option<bool>,
@@ -166,6 +202,18 @@ pub struct UpdateSettings {
     pub ocr_quality_threshold_noise: Option<f32>,
     pub ocr_quality_threshold_sharpness: Option<f32>,
     pub ocr_skip_enhancement: Option<bool>,
+    pub ocr_parallel_pages: Option<bool>,
+    pub ocr_retry_low_confidence: Option<bool>,
+    pub ocr_detect_script: Option<bool>,
+    pub ocr_preprocessing: Option<Option<PreprocessingConfig>>,
+    pub search_clean_snippets: Option<bool>,
+    pub ocr_text_layer_min_chars: Option<i32>,
+    pub sync_notification_mode: Option<SyncNotificationMode>,
+    /// `null` clears the default source; leaving the field out keeps it
+    #[serde(default, deserialize_with = "deserialize_nullable")]
+    pub default_source_id: Option<Option<Uuid>>,
+    pub default_source_write_back: Option<bool>,
+    pub extract_archives: Option<bool>,
     pub webdav_enabled: Option<bool>,
     pub webdav_server_url: Option<Option<String>>,
     pub webdav_username: Option<Option<String>>,
@@ -176,6 +224,15 @@ pub struct UpdateSettings {
     pub webdav_sync_interval_minutes: Option<i32>,
 }
 
+/// Tells an explicit `null` (`Some(None)`) apart from an absent field (`None`)
+fn deserialize_nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
 impl From<Settings> for SettingsResponse {
     fn from(settings: Settings) -> Self {
         Self {
@@ -191,6 +248,7 @@ impl From<Settings> for SettingsResponse {
             enable_image_preprocessing: settings.enable_image_preprocessing,
             search_results_per_page: settings.search_results_per_page,
             search_snippet_length: settings.search_snippet_length,
+            search_sort: settings.search_sort,
             fuzzy_search_threshold: settings.fuzzy_search_threshold,
             retention_days: settings.retention_days,
             enable_auto_cleanup: settings.enable_auto_cleanup,
@@ -223,6 +281,16 @@ impl From<Settings> for SettingsResponse {
             ocr_quality_threshold_noise: settings.ocr_quality_threshold_noise,
             ocr_quality_threshold_sharpness: settings.ocr_quality_threshold_sharpness,
             ocr_skip_enhancement: settings.ocr_skip_enhancement,
+            ocr_parallel_pages: settings.ocr_parallel_pages,
+            ocr_retry_low_confidence: settings.ocr_retry_low_confidence,
+            ocr_detect_script: settings.ocr_detect_script,
+            ocr_preprocessing: settings.ocr_preprocessing,
+            search_clean_snippets: settings.search_clean_snippets,
+            ocr_text_layer_min_chars: settings.ocr_text_layer_min_chars,
+            sync_notification_mode: settings.sync_notification_mode,
+            default_source_id: settings.default_source_id,
+            default_source_write_back: settings.default_source_write_back,
+            extract_archives: settings.extract_archives,
             webdav_enabled: settings.webdav_enabled,
             webdav_server_url: settings.webdav_server_url,
             webdav_username: settings.webdav_username,
@@ -255,6 +323,7 @@ impl UpdateSettings {
             enable_image_preprocessing: None,
             search_results_per_page: None,
             search_snippet_length: None,
+            search_sort: None,
             fuzzy_search_threshold: None,
             retention_days: None,
             enable_auto_cleanup: None,
@@ -287,6 +356,16 @@ impl UpdateSettings {
             ocr_quality_threshold_noise: None,
             ocr_quality_threshold_sharpness: None,
             ocr_skip_enhancement: None,
+            ocr_parallel_pages: None,
+            ocr_retry_low_confidence: None,
+            ocr_detect_script: None,
+            ocr_preprocessing: None,
+            search_clean_snippets: None,
+            ocr_text_layer_min_chars: None,
+            sync_notification_mode: None,
+            default_source_id: None,
+            default_source_write_back: None,
+            extract_archives: None,
             webdav_enabled: None,
             webdav_server_url: None,
             webdav_username: None,
@@ -324,6 +403,7 @@ impl Default for Settings {
             enable_image_preprocessing: false,
             search_results_per_page: 25,
             search_snippet_length: 200,
+            search_sort: SearchSort::Relevance,
             fuzzy_search_threshold: 0.8,
             retention_days: None,
             enable_auto_cleanup: false,
@@ -356,6 +436,16 @@ impl Default for Settings {
             ocr_quality_threshold_noise: 0.7, // Conservative threshold
             ocr_quality_threshold_sharpness: 0.3, // Conservative threshold
             ocr_skip_enhancement: false, // Allow enhancement by default
+            ocr_parallel_pages: false, // Serial PDF OCR keeps memory use predictable
+            ocr_retry_low_confidence: true, // One escalated retry before failing low-confidence results
+            ocr_detect_script: false, // Script detection is an extra Tesseract pass per document
+            ocr_preprocessing: None, // Enhancements follow the toggles above
+            search_clean_snippets: true, // Raw OCR noise makes snippets hard to read
+            ocr_text_layer_min_chars: 100, // About a line of text; less is usually a stray header or page number
+            sync_notification_mode: SyncNotificationMode::Summary,
+            default_source_id: None, // Uploads belong to no source until the user picks one
+            default_source_write_back: false,
+            extract_archives: false, // Uploaded ZIP files are stored as they are unless the user opts in
             webdav_enabled: false,
             webdav_server_url: None,
             webdav_username: None,
@@ -376,4 +466,156 @@ impl Default for Settings {
             updated_at: chrono::Utc::now(),
         }
     }
-}
\ No newline at end of file
+}
+/// Tesseract settings applied to a single OCR run, e.g. when retrying a misread document,
+/// or to every document synced from a source (`ocr_overrides` in the source config).
+/// They take precedence over the user's saved settings without changing them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OcrOverrides {
+    /// Only recognize these characters (`tessedit_char_whitelist`)
+    pub whitelist_chars: Option<String>,
+    /// Never recognize these characters (`tessedit_char_blacklist`)
+    pub blacklist_chars: Option<String>,
+    /// Page segmentation mode, 0-13
+    pub psm: Option<i32>,
+    /// OCR engine mode, 0-3
+    pub oem: Option<i32>,
+    /// Run with escalated preprocessing: higher DPI, contrast enhancement and orientation detection
+    pub escalate: Option<bool>,
+    /// Target DPI images are scaled to before recognition
+    pub dpi: Option<i32>,
+    pub enhance_contrast: Option<bool>,
+    pub remove_noise: Option<bool>,
+    pub detect_orientation: Option<bool>,
+    /// Rotate images whose detected orientation is off
+    pub auto_rotate: Option<bool>,
+    /// Tesseract language or `+`-joined combination, e.g. `deu` or `deu+eng`; the first is primary
+    pub language: Option<String>,
+    /// Image preprocessing pipeline: a preset name or a list of steps
+    pub preprocessing: Option<PreprocessingConfig>,
+}
+
+impl OcrOverrides {
+    pub const MAX_PSM: i32 = 13;
+    pub const MAX_OEM: i32 = 3;
+    /// Longest whitelist/blacklist accepted; Tesseract character sets are small
+    pub const MAX_CHARS_LEN: usize = 1024;
+    /// Lowest DPI used by an escalated run
+    pub const ESCALATED_MIN_DPI: i32 = 400;
+    pub const MIN_DPI: i32 = 72;
+    pub const MAX_DPI: i32 = 1200;
+    /// Most languages in a `language` combination, as for the user's preferred languages
+    pub const MAX_LANGUAGES: usize = 4;
+
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(psm) = self.psm {
+            if !(0..=Self::MAX_PSM).contains(&psm) {
+                return Err(format!("psm must be between 0 and {}, got {}", Self::MAX_PSM, psm));
+            }
+            // Mode 0 only detects orientation and script and never produces text
+            if psm == 0 {
+                return Err("psm 0 (orientation and script detection only) does not produce text".to_string());
+            }
+        }
+        if let Some(oem) = self.oem {
+            if !(0..=Self::MAX_OEM).contains(&oem) {
+                return Err(format!("oem must be between 0 and {}, got {}", Self::MAX_OEM, oem));
+            }
+        }
+        if let Some(dpi) = self.dpi {
+            if !(Self::MIN_DPI..=Self::MAX_DPI).contains(&dpi) {
+                return Err(format!("dpi must be between {} and {}, got {}", Self::MIN_DPI, Self::MAX_DPI, dpi));
+            }
+        }
+        for (name, chars) in [("whitelist_chars", &self.whitelist_chars), ("blacklist_chars", &self.blacklist_chars)] {
+            if let Some(chars) = chars {
+                if chars.len() > Self::MAX_CHARS_LEN {
+                    return Err(format!("{} must be at most {} bytes", name, Self::MAX_CHARS_LEN));
+                }
+                if chars.chars().any(char::is_control) {
+                    return Err(format!("{} must not contain control characters", name));
+                }
+            }
+        }
+        if let Some(language) = &self.language {
+            let languages: Vec<&str> = language.split('+').collect();
+            if languages.iter().any(|lang| lang.is_empty() || !lang.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')) {
+                return Err(format!("language must be Tesseract language codes joined by '+', got '{}'", language));
+            }
+            if languages.len() > Self::MAX_LANGUAGES {
+                return Err(format!("language combines at most {} languages, got {}", Self::MAX_LANGUAGES, languages.len()));
+            }
+        }
+        if let Some(preprocessing) = &self.preprocessing {
+            preprocessing.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Reads the `ocr_overrides` object of a source config. Absent or null means the
+    /// source has none; anything else must parse and pass `validate`.
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Option<Self>, String> {
+        match config.get("ocr_overrides") {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(value) => {
+                let overrides: Self = serde_json::from_value(value.clone())
+                    .map_err(|e| format!("Invalid ocr_overrides: {}", e))?;
+                overrides.validate()?;
+                Ok(Some(overrides).filter(|o| !o.is_empty()))
+            }
+        }
+    }
+
+    /// Returns a copy of `settings` with these overrides applied
+    pub fn apply_to(&self, settings: &Settings) -> Settings {
+        let mut settings = settings.clone();
+        if let Some(chars) = &self.whitelist_chars {
+            settings.ocr_whitelist_chars = Some(chars.clone());
+        }
+        if let Some(chars) = &self.blacklist_chars {
+            settings.ocr_blacklist_chars = Some(chars.clone());
+        }
+        if let Some(psm) = self.psm {
+            settings.ocr_page_segmentation_mode = psm;
+        }
+        if let Some(oem) = self.oem {
+            settings.ocr_engine_mode = oem;
+        }
+        if let Some(dpi) = self.dpi {
+            settings.ocr_dpi = dpi;
+        }
+        if let Some(enhance_contrast) = self.enhance_contrast {
+            settings.ocr_enhance_contrast = enhance_contrast;
+        }
+        if let Some(remove_noise) = self.remove_noise {
+            settings.ocr_remove_noise = remove_noise;
+        }
+        if let Some(detect_orientation) = self.detect_orientation {
+            settings.ocr_detect_orientation = detect_orientation;
+        }
+        if let Some(auto_rotate) = self.auto_rotate {
+            settings.auto_rotate_images = auto_rotate;
+        }
+        if let Some(language) = &self.language {
+            let languages: Vec<String> = language.split('+').map(str::to_string).collect();
+            settings.primary_language = languages[0].clone();
+            settings.ocr_language = languages[0].clone();
+            settings.preferred_languages = languages;
+        }
+        if let Some(preprocessing) = &self.preprocessing {
+            settings.ocr_preprocessing = Some(preprocessing.clone());
+        }
+        if self.escalate == Some(true) {
+            settings.ocr_dpi = (settings.ocr_dpi * 3 / 2).max(Self::ESCALATED_MIN_DPI);
+            settings.ocr_enhance_contrast = true;
+            settings.ocr_detect_orientation = true;
+            settings.enable_image_preprocessing = true;
+            settings.ocr_skip_enhancement = false;
+        }
+        settings
+    }
+}
@@ -15,6 +15,10 @@ pub enum SourceType {
     LocalFolder,
     #[serde(rename = "s3")]
     S3,
+    #[serde(rename = "smb")]
+    Smb,
+    #[serde(rename = "imap")]
+    Imap,
 }
 
 impl std::fmt::Display for SourceType {
@@ -23,6 +27,8 @@ impl std::fmt::Display for SourceType {
             SourceType::WebDAV => write!(f, "webdav"),
             SourceType::LocalFolder => write!(f, "local_folder"),
             SourceType::S3 => write!(f, "s3"),
+            SourceType::Smb => write!(f, "smb"),
+            SourceType::Imap => write!(f, "imap"),
         }
     }
 }
@@ -35,6 +41,8 @@ impl TryFrom<String> for SourceType {
             "webdav" => Ok(SourceType::WebDAV),
             "local_folder" => Ok(SourceType::LocalFolder),
             "s3" => Ok(SourceType::S3),
+            "smb" => Ok(SourceType::Smb),
+            "imap" => Ok(SourceType::Imap),
             _ => Err(format!("Invalid source type: {}", value)),
         }
     }
@@ -158,6 +166,230 @@ pub struct SourceWithStats {
     pub sync_progress: Option<f32>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum SyncRunOutcome {
+    #[serde(rename = "running")]
+    Running,
+    #[serde(rename = "success")]
+    Success,
+    #[serde(rename = "failed")]
+    Failed,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+impl std::fmt::Display for SyncRunOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncRunOutcome::Running => write!(f, "running"),
+            SyncRunOutcome::Success => write!(f, "success"),
+            SyncRunOutcome::Failed => write!(f, "failed"),
+            SyncRunOutcome::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl TryFrom<String> for SyncRunOutcome {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, <SyncRunOutcome as TryFrom<String>>::Error> {
+        match value.as_str() {
+            "running" => Ok(SyncRunOutcome::Running),
+            "success" => Ok(SyncRunOutcome::Success),
+            "failed" => Ok(SyncRunOutcome::Failed),
+            "cancelled" => Ok(SyncRunOutcome::Cancelled),
+            _ => Err(format!("Invalid sync run outcome: {}", value)),
+        }
+    }
+}
+
+/// How a user is notified about finished source syncs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+pub enum SyncNotificationMode {
+    /// One notification per sync run with its totals
+    #[default]
+    #[serde(rename = "summary")]
+    Summary,
+    /// One notification per new or failed file
+    #[serde(rename = "per_file")]
+    PerFile,
+}
+
+impl std::fmt::Display for SyncNotificationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncNotificationMode::Summary => write!(f, "summary"),
+            SyncNotificationMode::PerFile => write!(f, "per_file"),
+        }
+    }
+}
+
+impl TryFrom<String> for SyncNotificationMode {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, <SyncNotificationMode as TryFrom<String>>::Error> {
+        match value.as_str() {
+            "summary" => Ok(SyncNotificationMode::Summary),
+            "per_file" => Ok(SyncNotificationMode::PerFile),
+            _ => Err(format!("Invalid sync notification mode: {}", value)),
+        }
+    }
+}
+
+/// A single recorded sync of a source
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SourceSyncRun {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub files_discovered: i64,
+    pub files_downloaded: i64,
+    pub files_failed: i64,
+    pub bytes_transferred: i64,
+    #[sqlx(try_from = "String")]
+    pub outcome: SyncRunOutcome,
+    pub error_message: Option<String>,
+}
+
+/// Latest scheduled ETag tracking validation of a WebDAV source
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SourceValidationReport {
+    pub source_id: Uuid,
+    /// 0-100, from the report's `overall_health_score`
+    pub health_score: i32,
+    /// The full `ValidationReport` produced by the WebDAV service
+    pub report: serde_json::Value,
+    /// Whether the next scheduled sync of the source runs as a full deep scan
+    pub deep_scan_pending: bool,
+    pub validated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SyncHistoryQuery {
+    /// Maximum number of runs to return (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SourceCleanupQuery {
+    /// Remove the orphaned directories; without it the cleanup is a dry run
+    #[serde(default)]
+    pub apply: bool,
+    /// Also flag documents synced from the orphaned directories as `source_missing`
+    #[serde(default)]
+    pub mark_documents: bool,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SourceSyncAbortQuery {
+    /// Also move the documents ingested during the aborted sync run to the trash
+    #[serde(default)]
+    pub delete_documents: bool,
+}
+
+/// How much of a source a manually triggered sync looks at
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceSyncMode {
+    /// The regular sync: directory ETags decide what is rescanned, or a queued deep scan runs
+    #[default]
+    Smart,
+    /// WebDAV only: scan just the directories directly under each watch folder that are
+    /// not tracked yet, without checking known directories for changes
+    NewOnly,
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema, utoipa::IntoParams)]
+pub struct SourceSyncQuery {
+    /// `smart` (default) or `new-only`
+    #[serde(default)]
+    pub mode: SourceSyncMode,
+}
+
+/// A sync that was started in the background
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceSyncStarted {
+    pub mode: SourceSyncMode,
+    pub message: String,
+    /// Changes this mode does not pick up; empty for a smart sync
+    pub limitations: Vec<String>,
+}
+
+/// What an aborted sync stopped, cancelled and deleted
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceSyncAbortResult {
+    /// Whether a running sync was stopped
+    pub sync_stopped: bool,
+    /// The sync run whose documents were considered for deletion
+    pub sync_run_id: Option<Uuid>,
+    /// Pending OCR jobs of the source's documents that were cancelled
+    pub ocr_jobs_cancelled: i64,
+    /// Documents moved to the trash; always 0 unless `delete_documents` was set
+    pub documents_deleted: i64,
+}
+
+/// Tracked WebDAV directories of a source that no longer exist on the server
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OrphanedDirectoryCleanup {
+    /// Whether the directories were removed, or only reported
+    pub applied: bool,
+    pub directories_checked: usize,
+    pub orphaned_paths: Vec<String>,
+    pub documents_marked: i64,
+}
+
+/// A document whose file is gone from the source it was synced from
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct MissingSourceDocument {
+    pub id: Uuid,
+    pub filename: String,
+    pub source_path: String,
+    /// When a sync first found the file gone
+    pub missing_since: Option<DateTime<Utc>>,
+}
+
+/// What a source has ingested: its live documents, their total size and OCR status
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct SourceDocumentStats {
+    pub source_id: Uuid,
+    pub total_documents: i64,
+    pub total_size_bytes: i64,
+    /// Documents waiting for OCR, including ones never queued
+    pub ocr_pending: i64,
+    pub ocr_processing: i64,
+    pub ocr_completed: i64,
+    pub ocr_failed: i64,
+    /// Documents whose file is gone from the source
+    pub missing_on_source: i64,
+}
+
+/// What to do with documents whose file is gone from their source
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MissingDocumentAction {
+    /// Move the documents to the trash
+    Delete,
+    /// Keep the documents but unlink them from the source
+    Detach,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResolveMissingDocumentsRequest {
+    pub action: MissingDocumentAction,
+    /// Missing documents to act on; all of the source's missing documents when omitted
+    pub document_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ResolveMissingDocumentsResponse {
+    pub action: MissingDocumentAction,
+    pub resolved_count: i64,
+    pub resolved_documents: Vec<Uuid>,
+    /// Requested documents that are not missing documents of the source, or could not be changed
+    pub failed_documents: Vec<Uuid>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct WebDAVSourceConfig {
     pub server_url: String,
@@ -168,6 +400,63 @@ pub struct WebDAVSourceConfig {
     pub auto_sync: bool,
     pub sync_interval_minutes: i32,
     pub server_type: Option<String>,
+    #[serde(flatten)]
+    pub tls: WebDAVTlsConfig,
+}
+
+/// TLS settings for a WebDAV server with a self-signed certificate or one from a private CA.
+/// Certificates are verified against the system's trusted roots unless these say otherwise.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct WebDAVTlsConfig {
+    /// Accept any certificate, including self-signed and expired ones. Insecure: anyone
+    /// between Readur and the server can read the credentials. Prefer `ca_certificate`.
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// PEM-encoded CA certificate to trust in addition to the system's roots. May contain
+    /// several certificates.
+    #[serde(default)]
+    pub ca_certificate: Option<String>,
+    #[serde(flatten)]
+    pub client_certificate: ClientCertificateConfig,
+}
+
+/// Client certificate for servers that require mutual TLS: a PEM certificate and key, or a
+/// PKCS#12 bundle. The values are stored encrypted and are never returned by the API.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ClientCertificateConfig {
+    /// PEM-encoded client certificate, optionally followed by its intermediate certificates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<String>,
+    /// PEM-encoded private key of `client_certificate`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key: Option<String>,
+    /// Base64-encoded PKCS#12 (.p12/.pfx) bundle with the certificate and key, instead of
+    /// `client_certificate` and `client_key`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_pkcs12: Option<String>,
+    /// Password of `client_pkcs12`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_pkcs12_password: Option<String>,
+}
+
+impl ClientCertificateConfig {
+    /// Config keys that hold client certificate material
+    pub const FIELDS: [&'static str; 4] = ["client_certificate", "client_key", "client_pkcs12", "client_pkcs12_password"];
+
+    pub fn is_configured(&self) -> bool {
+        [&self.client_certificate, &self.client_key, &self.client_pkcs12]
+            .iter()
+            .any(|value| value.as_deref().is_some_and(|value| !value.trim().is_empty()))
+    }
+}
+
+// Configs are logged with `{:?}`, so the certificate material is left out
+impl std::fmt::Debug for ClientCertificateConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClientCertificateConfig")
+            .field("configured", &self.is_configured())
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
@@ -182,18 +471,88 @@ pub struct LocalFolderSourceConfig {
 
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct S3SourceConfig {
+    #[serde(alias = "bucket")]
     pub bucket_name: String,
     pub region: String,
+    #[serde(alias = "access_key")]
     pub access_key_id: String,
+    #[serde(alias = "secret_key")]
     pub secret_access_key: String,
-    pub endpoint_url: Option<String>, // For S3-compatible services
-    pub prefix: Option<String>,       // Optional path prefix
+    pub endpoint_url: Option<String>, // For S3-compatible services such as MinIO or Backblaze B2
+    pub prefix: Option<String>,       // Key prefix that watch folders are relative to
     pub watch_folders: Vec<String>,   // S3 prefixes to monitor
     pub file_extensions: Vec<String>,
+    #[serde(default)]
+    pub include_patterns: Vec<String>, // Globs an object key must match (all objects if empty)
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>, // Globs that skip an object
+    #[serde(default)]
+    pub force_path_style: bool,       // `endpoint/bucket/key` addressing, required by MinIO
+    #[serde(default)]
+    pub ca_certificate: Option<String>, // PEM CA to trust in addition to the system roots, for self-signed endpoints
+    #[serde(flatten)]
+    pub client_certificate: ClientCertificateConfig, // For endpoints that require mutual TLS
+    pub auto_sync: bool,
+    pub sync_interval_minutes: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SmbSourceConfig {
+    pub host: String,                 // Hostname or IP, optionally with :port
+    pub share: String,
+    pub username: String,
+    pub password: String,
+    pub domain: Option<String>,       // Windows domain / workgroup
+    pub subpath: Option<String>,      // Directory within the share that watch folders are relative to
+    pub watch_folders: Vec<String>,
+    pub file_extensions: Vec<String>,
+    #[serde(default)]
+    pub include_patterns: Vec<String>, // Globs a file must match (all files if empty)
+    #[serde(default)]
+    pub exclude_patterns: Vec<String>, // Globs that skip a file or directory
+    pub auto_sync: bool,
+    pub sync_interval_minutes: i32,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+pub enum ImapTlsMode {
+    #[default]
+    #[serde(rename = "tls")]
+    Tls,      // Implicit TLS, usually port 993
+    #[serde(rename = "starttls")]
+    StartTls, // Plain connection upgraded with STARTTLS, usually port 143
+    #[serde(rename = "none")]
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImapSourceConfig {
+    pub host: String,
+    pub port: Option<u16>,            // Defaults to 993 for TLS, 143 otherwise
+    pub username: String,
+    pub password: String,
+    #[serde(default = "default_imap_folder")]
+    pub folder: String,
+    #[serde(default)]
+    pub tls_mode: ImapTlsMode,
+    pub file_extensions: Vec<String>, // Attachment extensions to ingest
     pub auto_sync: bool,
     pub sync_interval_minutes: i32,
 }
 
+fn default_imap_folder() -> String {
+    "INBOX".to_string()
+}
+
+impl ImapSourceConfig {
+    pub fn effective_port(&self) -> u16 {
+        self.port.unwrap_or(match self.tls_mode {
+            ImapTlsMode::Tls => 993,
+            ImapTlsMode::StartTls | ImapTlsMode::None => 143,
+        })
+    }
+}
+
 // WebDAV-related structs
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct WebDAVFolderInfo {
@@ -211,6 +570,9 @@ pub struct WebDAVCrawlEstimate {
     pub total_supported_files: i64,
     pub total_estimated_time_hours: f32,
     pub total_size_mb: f64,
+    /// How the OCR time of each file was projected
+    #[serde(default)]
+    pub processing_model: crate::services::processing_time_model::ProcessingTimeModel,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -218,7 +580,9 @@ pub struct WebDAVTestConnection {
     pub server_url: String,
     pub username: String,
     pub password: String,
-    pub server_type: Option<String>, // "nextcloud", "owncloud", "generic"
+    pub server_type: Option<String>, // "nextcloud", "owncloud", "seafile", "apache", "generic"
+    #[serde(flatten)]
+    pub tls: WebDAVTlsConfig,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -227,6 +591,43 @@ pub struct WebDAVConnectionResult {
     pub message: String,
     pub server_version: Option<String>,
     pub server_type: Option<String>,
+    /// Server type found by probing the server, for pre-selecting it when creating a source
+    #[serde(default)]
+    pub detected_server_type: Option<String>,
+    /// The server answered
+    #[serde(default)]
+    pub reachable: bool,
+    /// The server accepted the credentials
+    #[serde(default)]
+    pub authenticated: bool,
+    /// The server's TLS certificate was not trusted
+    #[serde(default)]
+    pub certificate_error: bool,
+    /// The server required a client certificate and did not accept the configured one
+    #[serde(default)]
+    pub client_certificate_error: bool,
+}
+
+/// Outcome of testing a source's settings, reported the same way for every source type
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceConnectionTestResult {
+    pub success: bool,
+    /// The server could be reached; for local folders, the folder exists
+    pub reachable: bool,
+    /// The credentials were accepted. A test can authenticate and still fail, e.g. when
+    /// the bucket, share, folder or mailbox can't be read.
+    pub authenticated: bool,
+    /// The server was reached but its TLS certificate was not trusted, e.g. because it is
+    /// self-signed
+    #[serde(default)]
+    pub certificate_error: bool,
+    /// The server asked for a client certificate and rejected the configured one, or none
+    /// was configured
+    #[serde(default)]
+    pub client_certificate_error: bool,
+    pub message: String,
+    /// Details the server reported, such as the detected WebDAV server type
+    pub server_info: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
@@ -324,6 +725,28 @@ pub struct UpdateWebDAVDirectory {
     pub total_size_bytes: i64,
 }
 
+/// An object an S3 source has synced, as it looked when it was last downloaded
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct S3Object {
+    pub id: Uuid,
+    pub source_id: Uuid,
+    pub object_key: String,
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateS3Object {
+    pub source_id: Uuid,
+    pub object_key: String,
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub size: i64,
+}
+
 // Notification-related structs
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct Notification {
@@ -354,7 +777,8 @@ pub struct NotificationSummary {
 }
 
 impl From<Source> for SourceResponse {
-    fn from(source: Source) -> Self {
+    fn from(mut source: Source) -> Self {
+        crate::services::source_secrets::redact_config(&mut source.config);
         Self {
             id: source.id,
             name: source.name,
@@ -104,6 +104,19 @@ pub struct LoginResponse {
     pub user: UserResponse,
 }
 
+/// A token that lets an admin see Readur as another user
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImpersonationResponse {
+    pub token: String,
+    /// The user the token acts as
+    pub user: UserResponse,
+    /// The admin who started the impersonation
+    pub impersonated_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    /// Whether requests that change data are rejected under this token
+    pub read_only: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct UserResponse {
     pub id: Uuid,
@@ -128,4 +141,56 @@ impl From<User> for UserResponse {
             role: user.role,
         }
     }
-}
\ No newline at end of file
+}
+/// TOTP state for a user. Kept out of `User` so the secret is never serialized.
+#[derive(Debug, Clone)]
+pub struct UserTotpState {
+    pub secret: Option<String>,
+    pub enabled: bool,
+}
+
+/// Returned by the password step of login when the account has 2FA enabled
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorChallengeResponse {
+    pub requires_2fa: bool,
+    pub challenge_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorSetupResponse {
+    /// Base32-encoded secret for manual entry
+    pub secret: String,
+    /// otpauth:// URI suitable for rendering as a QR code
+    pub otpauth_url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorCodeRequest {
+    /// 6-digit TOTP code, or a recovery code where accepted
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorVerifyResponse {
+    pub enabled: bool,
+    /// One-time recovery codes. They are only shown once.
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TwoFactorLoginRequest {
+    pub challenge_token: String,
+    /// 6-digit TOTP code or an unused recovery code
+    pub code: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
@@ -44,6 +44,7 @@ pub struct ManagedError {
     pub technical_details: String,
     pub suggested_action: Option<String>,
     pub suppression_key: Option<String>, // For suppressing repeated errors
+    pub request_id: Option<String>, // Correlation id of the request that raised the error
 }
 
 /// Error management service with intelligent logging and user experience
@@ -67,12 +68,15 @@ impl ErrorManager {
 
     /// Handle an error with intelligent logging and suppression
     pub async fn handle_error(&self, error: ManagedError) {
+        let request_id = error.request_id.as_deref().unwrap_or("-");
+
         // Check if this error should be suppressed
         if let Some(suppression_key) = &error.suppression_key {
             if self.should_suppress_error(suppression_key).await {
                 debug!(
                     category = ?error.category,
                     code = error.code,
+                    request_id,
                     "Suppressed repeated error: {}", error.technical_details
                 );
                 return;
@@ -86,6 +90,7 @@ impl ErrorManager {
                 error!(
                     category = ?error.category,
                     code = error.code,
+                    request_id,
                     user_message = error.user_message,
                     "Critical error: {}",
                     error.technical_details
@@ -95,6 +100,7 @@ impl ErrorManager {
                 warn!(
                     category = ?error.category,
                     code = error.code,
+                    request_id,
                     "Important error: {} | User: {}",
                     error.technical_details,
                     error.user_message
@@ -104,6 +110,7 @@ impl ErrorManager {
                 info!(
                     category = ?error.category,
                     code = error.code,
+                    request_id,
                     "Minor issue: {}",
                     error.technical_details
                 );
@@ -112,6 +119,7 @@ impl ErrorManager {
                 debug!(
                     category = ?error.category,
                     code = error.code,
+                    request_id,
                     "Expected error: {}",
                     error.technical_details
                 );
@@ -171,6 +179,7 @@ impl PdfErrorHandler {
             ),
             suggested_action: Some("File will be processed using image-based OCR instead".to_string()),
             suppression_key: Some(format!("pdf_font_encoding_{}", filename)),
+            request_id: None,
         }
     }
 
@@ -187,6 +196,7 @@ impl PdfErrorHandler {
             ),
             suggested_action: Some("Consider re-uploading the PDF if OCR results are poor".to_string()),
             suppression_key: Some(format!("pdf_corruption_{}", filename)),
+            request_id: None,
         }
     }
 }
@@ -208,6 +218,7 @@ impl OcrErrorHandler {
             ),
             suggested_action: Some("Contact support if this persists".to_string()),
             suppression_key: None, // Don't suppress database issues
+            request_id: None,
         }
     }
 
@@ -224,6 +235,7 @@ impl OcrErrorHandler {
             ),
             suggested_action: Some("Large or complex files may take additional time".to_string()),
             suppression_key: Some(format!("ocr_timeout_{}", filename)),
+            request_id: None,
         }
     }
 }
@@ -0,0 +1,77 @@
+/*!
+ * Logging Setup
+ *
+ * Installs the global tracing subscriber. `LOG_FORMAT=json` switches to
+ * newline-delimited JSON (timestamp, level, target, fields and the active
+ * span context) for log pipelines; anything else keeps the human-readable
+ * formatter used in local development. Logging starts before `Config` is
+ * loaded, so the format is read straight from the environment.
+ */
+
+use tracing_subscriber::EnvFilter;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    Pretty,
+    Json,
+}
+
+impl LogFormat {
+    pub fn from_env() -> Self {
+        std::env::var("LOG_FORMAT")
+            .ok()
+            .and_then(|value| Self::parse(&value))
+            .unwrap_or(LogFormat::Pretty)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "json" => Some(LogFormat::Json),
+            "pretty" | "text" | "plain" => Some(LogFormat::Pretty),
+            _ => None,
+        }
+    }
+}
+
+/// Default filter when RUST_LOG is not set, suppressing spam from noisy crates
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| {
+            EnvFilter::new("info")
+                .add_directive("pdf_extract=error".parse().unwrap())           // Suppress pdf_extract WARN spam
+                .add_directive("sqlx::postgres::notice=warn".parse().unwrap()) // Suppress PostgreSQL NOTICE spam
+                .add_directive("readur=info".parse().unwrap())                 // Keep our app logs at info
+        })
+}
+
+pub fn init_tracing(format: LogFormat) {
+    match format {
+        LogFormat::Pretty => {
+            tracing_subscriber::fmt()
+                .with_env_filter(env_filter())
+                .init();
+        }
+        LogFormat::Json => {
+            tracing_subscriber::fmt()
+                .json()
+                .flatten_event(true)
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_env_filter(env_filter())
+                .init();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!(LogFormat::parse("json"), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse(" JSON "), Some(LogFormat::Json));
+        assert_eq!(LogFormat::parse("pretty"), Some(LogFormat::Pretty));
+        assert_eq!(LogFormat::parse("xml"), None);
+    }
+}
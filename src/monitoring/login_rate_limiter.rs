@@ -0,0 +1,220 @@
+/*!
+ * Login Rate Limiting
+ *
+ * Tracks failed login attempts per username and per client IP. Once a key
+ * exceeds the allowed number of failures within the window it is locked out
+ * for a fixed period. State is kept in memory and swept periodically.
+ */
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+/// How often stale entries are swept from the in-memory map
+const SWEEP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy)]
+pub struct LoginRateLimitConfig {
+    pub max_failed_attempts: u32,
+    pub window: Duration,
+    pub lockout: Duration,
+}
+
+#[derive(Debug, Default)]
+struct AttemptState {
+    failures: u32,
+    window_start: Option<Instant>,
+    locked_until: Option<Instant>,
+}
+
+struct LimiterState {
+    entries: HashMap<String, AttemptState>,
+    last_sweep: Instant,
+}
+
+pub struct LoginRateLimiter {
+    config: LoginRateLimitConfig,
+    state: Mutex<LimiterState>,
+}
+
+impl LoginRateLimiter {
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(LoginRateLimitConfig {
+            max_failed_attempts: config.login_max_failed_attempts.max(1),
+            window: Duration::from_secs(config.login_attempt_window_seconds),
+            lockout: Duration::from_secs(config.login_lockout_seconds),
+        })
+    }
+
+    pub fn new(config: LoginRateLimitConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(LimiterState {
+                entries: HashMap::new(),
+                last_sweep: Instant::now(),
+            }),
+        }
+    }
+
+    fn keys(username: &str, ip: &str) -> [String; 2] {
+        [
+            format!("user:{}", username.trim().to_lowercase()),
+            format!("ip:{}", ip),
+        ]
+    }
+
+    /// Returns `Err(retry_after)` if either the username or the IP is currently locked out
+    pub fn check(&self, username: &str, ip: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        self.sweep_if_due(&mut state, now);
+
+        let retry_after = Self::keys(username, ip)
+            .iter()
+            .filter_map(|key| state.entries.get(key))
+            .filter_map(|entry| entry.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+            .max();
+
+        match retry_after {
+            Some(remaining) => Err(remaining),
+            None => Ok(()),
+        }
+    }
+
+    /// Records a failed attempt. Returns the lockout duration if this failure triggered one.
+    pub fn record_failure(&self, username: &str, ip: &str) -> Option<Duration> {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        let mut triggered = None;
+
+        for key in Self::keys(username, ip) {
+            let entry = state.entries.entry(key.clone()).or_default();
+
+            let window_expired = entry
+                .window_start
+                .map(|start| now.duration_since(start) > self.config.window)
+                .unwrap_or(true);
+            if window_expired {
+                entry.failures = 0;
+                entry.window_start = Some(now);
+            }
+
+            entry.failures += 1;
+
+            if entry.failures >= self.config.max_failed_attempts {
+                entry.locked_until = Some(now + self.config.lockout);
+                entry.failures = 0;
+                entry.window_start = None;
+                warn!("Login lockout triggered for {} ({}s)", key, self.config.lockout.as_secs());
+                triggered = Some(self.config.lockout);
+            }
+        }
+
+        triggered
+    }
+
+    /// Clears the username's counter after a complete login. The IP keeps its count, so one
+    /// valid account doesn't reset the throttling of an address trying passwords on many.
+    pub fn record_success(&self, username: &str) {
+        let [user_key, _] = Self::keys(username, "");
+        self.state.lock().unwrap().entries.remove(&user_key);
+    }
+
+    fn sweep_if_due(&self, state: &mut LimiterState, now: Instant) {
+        if now.duration_since(state.last_sweep) < SWEEP_INTERVAL {
+            return;
+        }
+
+        let before = state.entries.len();
+        let window = self.config.window;
+        state.entries.retain(|_, entry| {
+            let locked = entry.locked_until.map(|until| until > now).unwrap_or(false);
+            let in_window = entry
+                .window_start
+                .map(|start| now.duration_since(start) <= window)
+                .unwrap_or(false);
+            locked || in_window
+        });
+        state.last_sweep = now;
+
+        let removed = before - state.entries.len();
+        if removed > 0 {
+            info!("Login rate limiter swept {} stale entries", removed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(max_failed_attempts: u32) -> LoginRateLimiter {
+        LoginRateLimiter::new(LoginRateLimitConfig {
+            max_failed_attempts,
+            window: Duration::from_secs(60),
+            lockout: Duration::from_secs(120),
+        })
+    }
+
+    #[test]
+    fn test_lockout_after_max_failures() {
+        let limiter = limiter(3);
+
+        assert!(limiter.record_failure("alice", "10.0.0.1").is_none());
+        assert!(limiter.record_failure("alice", "10.0.0.1").is_none());
+        assert!(limiter.check("alice", "10.0.0.1").is_ok());
+
+        assert!(limiter.record_failure("alice", "10.0.0.1").is_some());
+        let retry_after = limiter.check("alice", "10.0.0.1").unwrap_err();
+        assert!(retry_after <= Duration::from_secs(120));
+    }
+
+    #[test]
+    fn test_username_lockout_applies_from_any_ip() {
+        let limiter = limiter(2);
+
+        limiter.record_failure("Alice", "10.0.0.1");
+        limiter.record_failure("alice", "10.0.0.2");
+
+        assert!(limiter.check("ALICE", "10.0.0.3").is_err());
+        assert!(limiter.check("bob", "10.0.0.3").is_ok());
+    }
+
+    #[test]
+    fn test_ip_lockout_applies_to_any_username() {
+        let limiter = limiter(2);
+
+        limiter.record_failure("alice", "10.0.0.1");
+        limiter.record_failure("bob", "10.0.0.1");
+
+        assert!(limiter.check("carol", "10.0.0.1").is_err());
+        assert!(limiter.check("carol", "10.0.0.2").is_ok());
+    }
+
+    #[test]
+    fn test_success_resets_username_counter() {
+        let limiter = limiter(2);
+
+        limiter.record_failure("alice", "10.0.0.1");
+        limiter.record_success("alice");
+        limiter.record_failure("alice", "10.0.0.2");
+
+        assert!(limiter.check("alice", "10.0.0.3").is_ok());
+    }
+
+    #[test]
+    fn test_success_keeps_ip_counter() {
+        let limiter = limiter(2);
+
+        limiter.record_failure("alice", "10.0.0.1");
+        limiter.record_success("bob");
+        limiter.record_failure("carol", "10.0.0.1");
+
+        assert!(limiter.check("dave", "10.0.0.1").is_err());
+    }
+}
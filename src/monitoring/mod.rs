@@ -1,3 +1,6 @@
 pub mod db_monitoring;
 pub mod error_management;
+pub mod logging;
+pub mod login_rate_limiter;
+pub mod request_id;
 pub mod request_throttler;
\ No newline at end of file
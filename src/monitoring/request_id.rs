@@ -0,0 +1,152 @@
+/*!
+ * Request Correlation IDs
+ *
+ * Every request gets a correlation id: the incoming `X-Request-Id` header is
+ * honored when it looks sane, otherwise a UUID is generated. The id is
+ * recorded on a tracing span wrapping the request, stored in a task-local so
+ * error responses can include it, and echoed back in the `X-Request-Id`
+ * response header. Once the request is authenticated the span also records
+ * the user, and the admin behind it for impersonated requests.
+ */
+
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::future::Future;
+use tracing::Instrument;
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied id we will echo back
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// The correlation id of the request being handled, if any
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Spawns a background task that keeps the caller's request id and tracing span,
+/// so its log lines correlate with the request that triggered it
+pub fn spawn_with_request_id<F>(future: F) -> tokio::task::JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    let request_id = current_request_id();
+    let span = tracing::Span::current();
+    tokio::spawn(
+        async move {
+            match request_id {
+                Some(request_id) => REQUEST_ID.scope(request_id, future).await,
+                None => future.await,
+            }
+        }
+        .instrument(span),
+    )
+}
+
+fn is_valid_request_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_REQUEST_ID_LEN
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
+pub async fn request_id_middleware(request: Request, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| is_valid_request_id(value))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %request.method(),
+        path = %request.uri().path(),
+        user_id = tracing::field::Empty,
+        impersonated_by = tracing::field::Empty,
+    );
+
+    let mut response = REQUEST_ID
+        .scope(request_id.clone(), next.run(request))
+        .instrument(span)
+        .await;
+
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::{Request as HttpRequest, StatusCode}, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/", get(|| async { current_request_id().unwrap_or_default() }))
+            .layer(axum::middleware::from_fn(request_id_middleware))
+    }
+
+    #[tokio::test]
+    async fn test_generates_request_id_when_missing() {
+        let response = app()
+            .oneshot(HttpRequest::builder().uri("/").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let header = response.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap().to_string();
+        assert!(Uuid::parse_str(&header).is_ok());
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, header.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_honors_incoming_request_id() {
+        let response = app()
+            .oneshot(HttpRequest::builder().uri("/").header(REQUEST_ID_HEADER, "client-abc.123").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers().get(REQUEST_ID_HEADER).unwrap(), "client-abc.123");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_malformed_request_id() {
+        let response = app()
+            .oneshot(HttpRequest::builder().uri("/").header(REQUEST_ID_HEADER, "bad id\twith spaces").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        let header = response.headers().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap();
+        assert!(Uuid::parse_str(header).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_tasks_inherit_request_id() {
+        let id = REQUEST_ID
+            .scope("req-1".to_string(), async {
+                spawn_with_request_id(async { current_request_id() }).await.unwrap()
+            })
+            .await;
+
+        assert_eq!(id.as_deref(), Some("req-1"));
+        assert_eq!(current_request_id(), None);
+    }
+}
@@ -112,39 +112,42 @@ pub async fn perform_ocr(
             "text": text,
             "status": "success"
         }))),
-        Err(e) => {
-            if let Some(ocr_error) = e.downcast_ref::<OcrError>() {
-                let (status_code, details) = match ocr_error {
-                    OcrError::TesseractNotInstalled => (StatusCode::SERVICE_UNAVAILABLE, "Please install Tesseract OCR"),
-                    OcrError::LanguageDataNotFound { .. } => (StatusCode::BAD_REQUEST, "Language pack not installed"),
-                    OcrError::InsufficientMemory { .. } => (StatusCode::INSUFFICIENT_STORAGE, "Not enough memory"),
-                    OcrError::ImageTooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, "Image exceeds size limits"),
-                    OcrError::OcrTimeout { .. } => (StatusCode::REQUEST_TIMEOUT, "OCR operation timed out"),
-                    OcrError::PermissionDenied { .. } => (StatusCode::FORBIDDEN, "Cannot access file"),
-                    OcrError::InvalidImageFormat { .. } => (StatusCode::UNPROCESSABLE_ENTITY, "Invalid image format"),
-                    _ => (StatusCode::INTERNAL_SERVER_ERROR, "OCR processing failed"),
-                };
-                
-                Err((
-                    status_code,
-                    Json(OcrErrorResponse {
-                        error: ocr_error.to_string(),
-                        error_code: ocr_error.error_code().to_string(),
-                        details: Some(details.to_string()),
-                        is_recoverable: ocr_error.is_recoverable(),
-                    }),
-                ))
-            } else {
-                Err((
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(OcrErrorResponse {
-                        error: e.to_string(),
-                        error_code: "OCR_UNKNOWN_ERROR".to_string(),
-                        details: None,
-                        is_recoverable: false,
-                    }),
-                ))
-            }
-        }
+        Err(e) => Err(error_response(&e)),
     }
-}
\ No newline at end of file
+}
+
+/// Map an OCR failure to an HTTP status and error body
+pub(crate) fn error_response(e: &anyhow::Error) -> (StatusCode, Json<OcrErrorResponse>) {
+    if let Some(ocr_error) = e.downcast_ref::<OcrError>() {
+        let (status_code, details) = match ocr_error {
+            OcrError::TesseractNotInstalled => (StatusCode::SERVICE_UNAVAILABLE, "Please install Tesseract OCR"),
+            OcrError::LanguageDataNotFound { .. } => (StatusCode::BAD_REQUEST, "Language pack not installed"),
+            OcrError::InsufficientMemory { .. } => (StatusCode::INSUFFICIENT_STORAGE, "Not enough memory"),
+            OcrError::ImageTooLarge { .. } => (StatusCode::PAYLOAD_TOO_LARGE, "Image exceeds size limits"),
+            OcrError::OcrTimeout { .. } => (StatusCode::REQUEST_TIMEOUT, "OCR operation timed out"),
+            OcrError::PermissionDenied { .. } => (StatusCode::FORBIDDEN, "Cannot access file"),
+            OcrError::InvalidImageFormat { .. } => (StatusCode::UNPROCESSABLE_ENTITY, "Invalid image format"),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, "OCR processing failed"),
+        };
+        
+        (
+            status_code,
+            Json(OcrErrorResponse {
+                error: ocr_error.to_string(),
+                error_code: ocr_error.error_code().to_string(),
+                details: Some(details.to_string()),
+                is_recoverable: ocr_error.is_recoverable(),
+            }),
+        )
+    } else {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(OcrErrorResponse {
+                error: e.to_string(),
+                error_code: "OCR_UNKNOWN_ERROR".to_string(),
+                details: None,
+                is_recoverable: false,
+            }),
+        )
+    }
+}
@@ -0,0 +1,309 @@
+/*!
+ * OCR Benchmark
+ *
+ * Measures the OCR throughput of this instance for capacity planning. Samples are OCRed
+ * from a scratch directory with the same engine and settings as queued documents, but
+ * nothing touches the OCR queue, the database or the upload directory. At most one
+ * benchmark runs at a time and its concurrency is capped, so it only competes with the
+ * queue worker for CPU.
+ */
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::{metadata_extraction::read_pdf_properties, models::Settings, ocr::enhanced::EnhancedOcrService};
+
+/// Times each sample is OCRed when the request doesn't say
+pub const DEFAULT_BENCHMARK_ITERATIONS: usize = 3;
+pub const MAX_BENCHMARK_ITERATIONS: usize = 10;
+/// Most samples one benchmark may upload
+pub const MAX_BENCHMARK_SAMPLES: usize = 10;
+
+static BENCHMARK_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// A file to benchmark OCR with
+#[derive(Debug, Clone)]
+pub struct BenchmarkSample {
+    pub filename: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Samples shipped with the server: a scanned page, a phone photo of a document and a
+/// multi-page PDF
+pub fn bundled_samples() -> Vec<BenchmarkSample> {
+    let sample = |filename: &str, mime_type: &str, data: &[u8]| BenchmarkSample {
+        filename: filename.to_string(),
+        mime_type: mime_type.to_string(),
+        data: data.to_vec(),
+    };
+    vec![
+        sample("scanned_page.png", "image/png", include_bytes!("benchmark_samples/scanned_page.png")),
+        sample("photo.jpg", "image/jpeg", include_bytes!("benchmark_samples/photo.jpg")),
+        sample("multipage.pdf", "application/pdf", include_bytes!("benchmark_samples/multipage.pdf")),
+    ]
+}
+
+/// Throughput of a benchmark as a whole
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OcrBenchmarkReport {
+    /// False when the samples were uploaded with the request
+    pub bundled_samples: bool,
+    pub iterations: usize,
+    /// OCR runs that were in flight at once
+    pub concurrency: usize,
+    /// The OCR language(s) of the settings the benchmark ran with, e.g. `eng+deu`
+    pub language: String,
+    pub runs: usize,
+    pub failed_runs: usize,
+    /// Pages OCRed by the successful runs
+    pub pages: u64,
+    pub wall_time_ms: u64,
+    pub pages_per_second: f64,
+    /// Files of the sample mix OCRed per hour at this concurrency; divide a backlog by it
+    /// to estimate how long its OCR takes
+    pub files_per_hour: f64,
+    pub average_latency_ms: f64,
+    pub p95_latency_ms: u64,
+    pub samples: Vec<OcrBenchmarkSampleResult>,
+}
+
+/// Timing of one sample over all iterations
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OcrBenchmarkSampleResult {
+    pub filename: String,
+    pub mime_type: String,
+    pub file_size: u64,
+    pub pages: u64,
+    pub runs: usize,
+    pub failed_runs: usize,
+    /// `None` when every run failed
+    pub average_latency_ms: Option<f64>,
+    pub min_latency_ms: Option<u64>,
+    pub max_latency_ms: Option<u64>,
+    pub average_confidence: Option<f32>,
+    /// The error of the last failed run
+    pub error: Option<String>,
+}
+
+/// Held while a benchmark runs; released on drop
+pub struct BenchmarkGuard(());
+
+impl BenchmarkGuard {
+    /// `None` when another benchmark is running
+    pub fn acquire() -> Option<Self> {
+        BENCHMARK_RUNNING
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| Self(()))
+    }
+}
+
+impl Drop for BenchmarkGuard {
+    fn drop(&mut self) {
+        BENCHMARK_RUNNING.store(false, Ordering::Release);
+    }
+}
+
+pub struct OcrBenchmark {
+    settings: Settings,
+    iterations: usize,
+    concurrency: usize,
+    timeout: Duration,
+}
+
+impl OcrBenchmark {
+    /// Benchmarks with `settings`, running each sample `iterations` times with up to
+    /// `concurrency` runs at once. A run taking longer than `timeout` counts as failed.
+    pub fn new(settings: Settings, iterations: usize, concurrency: usize, timeout: Duration) -> Self {
+        let mut settings = settings;
+        // Nothing from a benchmark is kept
+        settings.save_processed_images = false;
+        Self {
+            settings,
+            iterations: iterations.clamp(1, MAX_BENCHMARK_ITERATIONS),
+            concurrency: concurrency.max(1),
+            timeout,
+        }
+    }
+
+    /// OCRs every sample `iterations` times in `scratch_dir` and reports the timings
+    pub async fn run(&self, samples: Vec<BenchmarkSample>, bundled: bool, scratch_dir: &Path) -> Result<OcrBenchmarkReport> {
+        let mut paths = Vec::with_capacity(samples.len());
+        for (index, sample) in samples.iter().enumerate() {
+            let extension = Path::new(&sample.filename).extension().and_then(|e| e.to_str()).unwrap_or("bin");
+            let path = scratch_dir.join(format!("sample_{}.{}", index, extension));
+            tokio::fs::write(&path, &sample.data).await?;
+            paths.push(path);
+        }
+
+        info!(
+            "Starting OCR benchmark: {} samples x {} iterations, concurrency {}",
+            samples.len(), self.iterations, self.concurrency
+        );
+
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let scratch = scratch_dir.to_string_lossy().to_string();
+        let mut runs = JoinSet::new();
+        let started = Instant::now();
+
+        for _ in 0..self.iterations {
+            for (index, sample) in samples.iter().enumerate() {
+                let permit = semaphore.clone().acquire_owned().await?;
+                let path: PathBuf = paths[index].clone();
+                let (filename, mime_type, size) = (sample.filename.clone(), sample.mime_type.clone(), sample.data.len() as i64);
+                let settings = self.settings.clone();
+                let scratch = scratch.clone();
+                let timeout = self.timeout;
+
+                runs.spawn(async move {
+                    let _permit = permit;
+                    let ocr_service = EnhancedOcrService::new(scratch);
+                    let run_started = Instant::now();
+                    let result = tokio::time::timeout(
+                        timeout,
+                        ocr_service.extract_text_with_context(&path.to_string_lossy(), &mime_type, &filename, size, &settings),
+                    )
+                    .await;
+                    let latency_ms = run_started.elapsed().as_millis() as u64;
+                    let outcome = match result {
+                        Ok(Ok(result)) => Ok(result.confidence),
+                        Ok(Err(e)) => Err(e.to_string()),
+                        Err(_) => Err(format!("Timed out after {} seconds", timeout.as_secs())),
+                    };
+                    (index, latency_ms, outcome)
+                });
+            }
+        }
+
+        let mut latencies: Vec<Vec<u64>> = vec![Vec::new(); samples.len()];
+        let mut confidences: Vec<Vec<f32>> = vec![Vec::new(); samples.len()];
+        let mut failures: Vec<(usize, Option<String>)> = vec![(0, None); samples.len()];
+        while let Some(joined) = runs.join_next().await {
+            let (index, latency_ms, outcome) = match joined {
+                Ok(run) => run,
+                Err(e) => {
+                    warn!("OCR benchmark run panicked: {}", e);
+                    continue;
+                }
+            };
+            match outcome {
+                Ok(confidence) => {
+                    latencies[index].push(latency_ms);
+                    confidences[index].push(confidence);
+                }
+                Err(error) => {
+                    failures[index].0 += 1;
+                    failures[index].1 = Some(error);
+                }
+            }
+        }
+        let wall_time = started.elapsed();
+
+        let mut results = Vec::with_capacity(samples.len());
+        for (index, sample) in samples.iter().enumerate() {
+            let sample_latencies = &latencies[index];
+            let (failed_runs, error) = failures[index].clone();
+            results.push(OcrBenchmarkSampleResult {
+                filename: sample.filename.clone(),
+                mime_type: sample.mime_type.clone(),
+                file_size: sample.data.len() as u64,
+                pages: count_pages(sample, &paths[index]),
+                runs: sample_latencies.len() + failed_runs,
+                failed_runs,
+                average_latency_ms: average(sample_latencies.iter().map(|&l| l as f64)),
+                min_latency_ms: sample_latencies.iter().min().copied(),
+                max_latency_ms: sample_latencies.iter().max().copied(),
+                average_confidence: average(confidences[index].iter().map(|&c| c as f64)).map(|c| c as f32),
+                error,
+            });
+        }
+
+        let mut all_latencies: Vec<u64> = latencies.iter().flatten().copied().collect();
+        all_latencies.sort_unstable();
+        let successful_runs = all_latencies.len();
+        let pages: u64 = results.iter().map(|r| r.pages * (r.runs - r.failed_runs) as u64).sum();
+        let seconds = wall_time.as_secs_f64().max(f64::EPSILON);
+
+        let report = OcrBenchmarkReport {
+            bundled_samples: bundled,
+            iterations: self.iterations,
+            concurrency: self.concurrency,
+            language: if self.settings.preferred_languages.is_empty() {
+                self.settings.ocr_language.clone()
+            } else {
+                self.settings.preferred_languages.join("+")
+            },
+            runs: self.iterations * samples.len(),
+            failed_runs: results.iter().map(|r| r.failed_runs).sum(),
+            pages,
+            wall_time_ms: wall_time.as_millis() as u64,
+            pages_per_second: pages as f64 / seconds,
+            files_per_hour: successful_runs as f64 / seconds * 3600.0,
+            average_latency_ms: average(all_latencies.iter().map(|&l| l as f64)).unwrap_or(0.0),
+            p95_latency_ms: percentile(&all_latencies, 0.95),
+            samples: results,
+        };
+
+        info!(
+            "OCR benchmark finished: {} runs ({} failed), {:.2} pages/s, {:.0} ms average latency",
+            report.runs, report.failed_runs, report.pages_per_second, report.average_latency_ms
+        );
+        Ok(report)
+    }
+}
+
+/// Pages of a sample: the page count of a PDF, the frames of a TIFF, 1 for anything else
+#[cfg_attr(not(feature = "ocr"), allow(unused_variables))]
+fn count_pages(sample: &BenchmarkSample, path: &Path) -> u64 {
+    match sample.mime_type.as_str() {
+        "application/pdf" => read_pdf_properties(&sample.data).page_count.unwrap_or(1) as u64,
+        #[cfg(feature = "ocr")]
+        "image/tiff" => crate::ocr::tiff_pages::count_frames(path).unwrap_or(1) as u64,
+        _ => 1,
+    }
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// The nearest-rank percentile of sorted values; 0 when there are none
+fn percentile(sorted: &[u64], fraction: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (fraction * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_uses_nearest_rank() {
+        assert_eq!(percentile(&[], 0.95), 0);
+        assert_eq!(percentile(&[40], 0.95), 40);
+        let latencies: Vec<u64> = (1..=20).map(|i| i * 10).collect();
+        assert_eq!(percentile(&latencies, 0.95), 190);
+        assert_eq!(percentile(&latencies, 0.5), 100);
+    }
+
+    #[test]
+    fn test_only_one_benchmark_runs_at_a_time() {
+        let guard = BenchmarkGuard::acquire().expect("no benchmark is running");
+        assert!(BenchmarkGuard::acquire().is_none());
+        drop(guard);
+        assert!(BenchmarkGuard::acquire().is_some());
+    }
+}
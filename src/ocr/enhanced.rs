@@ -1,4 +1,5 @@
 use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 use std::panic::{catch_unwind, AssertUnwindSafe};
 
@@ -10,14 +11,27 @@ use imageproc::{
     morphology::{close, open},
     filter::{median_filter, gaussian_blur_f32},
     distance_transform::Norm,
+    geometric_transformations::{rotate_about_center, Interpolation},
 };
 #[cfg(feature = "ocr")]
 use tesseract::{Tesseract, PageSegMode, OcrEngineMode};
+#[cfg(feature = "ocr")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "ocr")]
+use tokio::sync::Semaphore;
 
 use crate::models::Settings;
 use crate::services::file_service::FileService;
+use crate::ocr::preprocessing::PreprocessingStep;
+use crate::ocr::work_dir::OcrWorkDir;
+#[cfg(feature = "ocr")]
+use crate::ocr::preprocessing::estimate_skew_angle;
+#[cfg(feature = "ocr")]
+use crate::services::pdf_service::PdfService;
+#[cfg(feature = "ocr")]
+use crate::ocr::process::{is_timeout, output_before, OcrDeadline};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ImageQualityStats {
     pub average_brightness: f32,
     pub contrast_ratio: f32,
@@ -33,6 +47,70 @@ pub struct OcrResult {
     pub word_count: usize,
     pub preprocessing_applied: Vec<String>,
     pub processed_image_path: Option<String>,
+    pub preprocessing_parameters: Option<PreprocessingParameters>,
+    pub text_source: OcrTextSource,
+}
+
+/// Where the text of an `OcrResult` came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OcrTextSource {
+    /// Text stored in the file itself, like the text layer of a born-digital PDF
+    Embedded,
+    /// Text recognized by Tesseract
+    Ocr,
+}
+
+impl OcrTextSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OcrTextSource::Embedded => "embedded",
+            OcrTextSource::Ocr => "ocr",
+        }
+    }
+}
+
+/// Characters of a PDF text layer counted against `ocr_text_layer_min_chars`. Only letters
+/// and digits count, so layout whitespace and extraction debris do not make a layer look
+/// substantial.
+pub fn text_layer_char_count(text: &str) -> usize {
+    text.chars().filter(|c| c.is_alphanumeric()).count()
+}
+
+/// Parameters chosen while preprocessing an image for OCR. Stored with the
+/// saved processed image so a poor OCR result can be traced back to the
+/// enhancements that produced it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PreprocessingParameters {
+    pub original_width: u32,
+    pub original_height: u32,
+    pub processed_width: u32,
+    pub processed_height: u32,
+    /// Clockwise rotation applied by orientation correction, in degrees
+    pub rotation_degrees: u32,
+    /// Clockwise rotation orientation detection found the image needs, if detection ran
+    pub detected_rotation_degrees: Option<u32>,
+    /// Tesseract's orientation confidence for `detected_rotation_degrees`
+    pub orientation_confidence: Option<f32>,
+    /// A rotation was detected but too unsure to apply
+    pub orientation_needs_review: bool,
+    pub quality: Option<ImageQualityStats>,
+    /// False when the image met the quality thresholds and was only resized
+    /// and converted to grayscale
+    pub enhancement_applied: bool,
+    pub brightness_boost: Option<f32>,
+    pub contrast_multiplier: Option<f32>,
+    pub noise_reduction_level: Option<i32>,
+    /// Window size of the adaptive binarization threshold
+    pub binarization_window: Option<u32>,
+    /// Histogram equalization was used instead of adaptive binarization
+    pub histogram_equalization: bool,
+    pub sharpened: bool,
+    pub morphological_operations: bool,
+    /// Clockwise tilt of the text lines straightened by deskewing, in degrees
+    pub deskew_degrees: Option<f32>,
+    /// Steps run when a configured pipeline replaced the quality-driven enhancements
+    pub pipeline: Option<Vec<PreprocessingStep>>,
 }
 
 pub struct EnhancedOcrService {
@@ -47,6 +125,14 @@ impl EnhancedOcrService {
         Self { temp_dir, file_service }
     }
 
+    /// A copy of this service that writes its intermediate files into `work_dir`
+    pub fn in_work_dir(&self, work_dir: &OcrWorkDir) -> Self {
+        Self {
+            temp_dir: work_dir.path().to_string_lossy().to_string(),
+            file_service: self.file_service.clone(),
+        }
+    }
+
     /// Extract text from image with high-quality OCR settings
     #[cfg(feature = "ocr")]
     pub async fn extract_text_from_image(&self, file_path: &str, settings: &Settings) -> Result<OcrResult> {
@@ -55,12 +141,13 @@ impl EnhancedOcrService {
         
         let mut preprocessing_applied = Vec::new();
         
-        // Load and preprocess the image
-        let (processed_image_path, mut preprocess_steps) = if settings.enable_image_preprocessing {
-            let (processed_path, steps) = self.preprocess_image(file_path, settings).await?;
-            (processed_path, steps)
+        // Load and preprocess the image; a configured pipeline always runs
+        let has_pipeline = settings.ocr_preprocessing.as_ref().is_some_and(|config| config.steps().is_some());
+        let (processed_image_path, mut preprocess_steps, preprocessing_parameters) = if settings.enable_image_preprocessing || has_pipeline {
+            let (processed_path, steps, parameters) = self.preprocess_image(file_path, settings).await?;
+            (processed_path, steps, Some(parameters))
         } else {
-            (file_path.to_string(), Vec::new())
+            (file_path.to_string(), Vec::new(), None)
         };
         
         preprocessing_applied.extend(preprocess_steps);
@@ -70,7 +157,7 @@ impl EnhancedOcrService {
         let settings_clone = settings.clone();
         let temp_dir = self.temp_dir.clone();
         
-        let ocr_result = tokio::task::spawn_blocking(move || -> Result<(String, f32)> {
+        let recognition = tokio::task::spawn_blocking(move || -> Result<(String, f32)> {
             // Configure Tesseract with optimal settings
             let ocr_service = EnhancedOcrService::new(temp_dir);
             let mut tesseract = ocr_service.configure_tesseract(&processed_image_path_clone, &settings_clone)?;
@@ -80,7 +167,23 @@ impl EnhancedOcrService {
             let confidence = ocr_service.calculate_overall_confidence(&mut tesseract)?;
             
             Ok((text, confidence))
-        }).await??;
+        });
+
+        // Tesseract runs in-process here, so a timed out recognition cannot be killed; the job
+        // gives up on it and frees its worker slot, and the blocking thread ends with tesseract
+        let deadline = OcrDeadline::from_settings(settings);
+        let ocr_result = match deadline.remaining() {
+            Some(remaining) => match tokio::time::timeout(remaining, recognition).await {
+                Ok(joined) => joined??,
+                Err(_) => {
+                    if processed_image_path != file_path {
+                        let _ = tokio::fs::remove_file(&processed_image_path).await;
+                    }
+                    return Err(deadline.timeout_error().into());
+                }
+            },
+            None => recognition.await??,
+        };
         
         let (text, confidence) = ocr_result;
         
@@ -106,6 +209,8 @@ impl EnhancedOcrService {
             word_count,
             preprocessing_applied,
             processed_image_path: result_processed_image_path,
+            preprocessing_parameters,
+            text_source: OcrTextSource::Ocr,
         };
         
         // Clean up temporary files if not saved for review
@@ -118,20 +223,164 @@ impl EnhancedOcrService {
         Ok(result)
     }
 
+    /// OCR a TIFF frame by frame. Each frame is written out as a page image and run
+    /// through the regular image OCR; a page that fails is left blank rather than failing
+    /// the document. Single-frame TIFFs, and ones whose frames cannot be read, use the
+    /// plain image path. The OCR timeout is checked between pages.
+    #[cfg(feature = "ocr")]
+    pub async fn extract_text_from_tiff(&self, file_path: &str, settings: &Settings) -> Result<OcrResult> {
+        let start_time = std::time::Instant::now();
+
+        let tiff_path = PathBuf::from(file_path);
+        let page_count = match tokio::task::spawn_blocking(move || crate::ocr::tiff_pages::count_frames(&tiff_path)).await? {
+            Ok(count) => count,
+            Err(e) => {
+                warn!("Could not count frames of TIFF '{}', reading it as a single image: {}", file_path, e);
+                1
+            }
+        };
+        if page_count <= 1 {
+            return self.extract_text_from_image(file_path, settings).await;
+        }
+
+        info!("Starting per-page OCR for multi-page TIFF '{}': {} pages", file_path, page_count);
+
+        let scratch_dir = Path::new(&self.temp_dir).join(format!("tiff_pages_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&scratch_dir).await?;
+        let pages = self.ocr_tiff_pages(file_path, page_count, &scratch_dir, settings, start_time).await;
+        if let Err(e) = tokio::fs::remove_dir_all(&scratch_dir).await {
+            warn!("Failed to remove TIFF page scratch directory {}: {}", scratch_dir.display(), e);
+        }
+        let pages = pages?;
+
+        let failed_pages = pages.iter().filter(|page| page.is_none()).count();
+        if failed_pages == pages.len() {
+            return Err(anyhow!("OCR failed for all {} pages of '{}'", pages.len(), file_path));
+        }
+
+        let page_texts: Vec<Option<String>> = pages.iter().map(|page| page.as_ref().map(|result| result.text.clone())).collect();
+        let text = join_page_texts(&page_texts);
+        let confidence = weighted_confidence(pages.iter().flatten().map(|result| (result.confidence, result.word_count)));
+        let processing_time = start_time.elapsed().as_millis() as u64;
+        let word_count = self.count_words_safely(&text);
+
+        // The first page that was OCRed stands in for the document's processed image
+        let first_page = pages.iter().flatten().next();
+        let mut preprocessing_applied = vec![format!("Per-page OCR of multi-page TIFF ({} pages)", page_count)];
+        preprocessing_applied.extend(first_page.map(|result| result.preprocessing_applied.clone()).unwrap_or_default());
+        if failed_pages > 0 {
+            warn!("{} of {} pages of '{}' failed OCR and were left blank", failed_pages, page_count, file_path);
+            preprocessing_applied.push(format!("{} pages failed OCR and were left blank", failed_pages));
+        }
+
+        info!(
+            "Multi-page TIFF OCR completed for '{}': {} words, {:.1}% confidence, {}ms",
+            file_path, word_count, confidence, processing_time
+        );
+
+        Ok(OcrResult {
+            text,
+            confidence,
+            processing_time_ms: processing_time,
+            word_count,
+            preprocessing_applied,
+            processed_image_path: first_page.and_then(|result| result.processed_image_path.clone()),
+            preprocessing_parameters: first_page.and_then(|result| result.preprocessing_parameters.clone()),
+            text_source: OcrTextSource::Ocr,
+        })
+    }
+
+    /// OCRs the frames of a TIFF one after another. Results are in page order, with `None`
+    /// for pages whose OCR failed. Fails once the OCR timeout has passed before the next page.
+    #[cfg(feature = "ocr")]
+    async fn ocr_tiff_pages(&self, file_path: &str, page_count: usize, scratch_dir: &Path, settings: &Settings, start_time: std::time::Instant) -> Result<Vec<Option<OcrResult>>> {
+        let timeout = std::time::Duration::from_secs(settings.ocr_timeout_seconds.max(0) as u64);
+        let mut pages: Vec<Option<OcrResult>> = Vec::with_capacity(page_count);
+
+        for index in 0..page_count {
+            if !timeout.is_zero() && start_time.elapsed() >= timeout {
+                for page in pages.iter().flatten() {
+                    if let Some(ref processed) = page.processed_image_path {
+                        let _ = tokio::fs::remove_file(processed).await;
+                    }
+                }
+                return Err(anyhow!(
+                    "OCR timed out after {}s for '{}': {} of {} pages done",
+                    settings.ocr_timeout_seconds, file_path, index, page_count
+                ));
+            }
+
+            let page_path = scratch_dir.join(format!("page-{}.png", index + 1));
+            let (tiff_path, frame_path) = (PathBuf::from(file_path), page_path.clone());
+            let written = tokio::task::spawn_blocking(move || crate::ocr::tiff_pages::write_frame_png(&tiff_path, index, &frame_path)).await?;
+
+            let page = match written {
+                Ok(()) => self.extract_text_from_image(&page_path.to_string_lossy(), settings).await,
+                Err(e) => Err(e),
+            };
+            match page {
+                Ok(mut result) => {
+                    // Only the first page's processed image is kept for review
+                    if pages.iter().flatten().next().is_some() {
+                        if let Some(processed) = result.processed_image_path.take() {
+                            let _ = tokio::fs::remove_file(processed).await;
+                        }
+                    }
+                    pages.push(Some(result));
+                }
+                Err(e) => {
+                    warn!("OCR failed for page {} of '{}': {}", index + 1, file_path, e);
+                    pages.push(None);
+                }
+            }
+            let _ = tokio::fs::remove_file(&page_path).await;
+        }
+
+        Ok(pages)
+    }
+
     /// Preprocess image for optimal OCR quality, especially for challenging conditions
     #[cfg(feature = "ocr")]
-    async fn preprocess_image(&self, input_path: &str, settings: &Settings) -> Result<(String, Vec<String>)> {
+    async fn preprocess_image(&self, input_path: &str, settings: &Settings) -> Result<(String, Vec<String>, PreprocessingParameters)> {
         // Resolve the file path first
         let resolved_path = self.resolve_file_path(input_path).await?;
+        let pipeline = settings.ocr_preprocessing.as_ref().and_then(|config| config.steps());
+        let wants_orientation = match &pipeline {
+            Some(steps) => steps.contains(&PreprocessingStep::Orientation),
+            None => settings.ocr_detect_orientation,
+        };
+        let orientation = if wants_orientation {
+            match crate::ocr::orientation::detect_orientation(Path::new(&resolved_path)).await {
+                Ok(detection) => detection,
+                Err(e) => {
+                    warn!("Orientation detection failed for '{}': {}", input_path, e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         let img = image::open(&resolved_path)?;
         let mut processed_img = img;
         let mut preprocessing_applied = Vec::new();
+        let mut parameters = PreprocessingParameters {
+            original_width: processed_img.width(),
+            original_height: processed_img.height(),
+            ..Default::default()
+        };
         
         info!("Original image dimensions: {}x{}", processed_img.width(), processed_img.height());
         
+        // A configured pipeline replaces the enhancements chosen below
+        if let Some(steps) = pipeline {
+            let processed_gray = self.run_preprocessing_pipeline(processed_img, &steps, settings, orientation.as_ref(), &mut parameters, &mut preprocessing_applied)?;
+            return self.save_processed_image(processed_gray, preprocessing_applied, parameters);
+        }
+        
         // Apply orientation detection and correction
-        if settings.ocr_detect_orientation {
-            processed_img = self.detect_and_correct_orientation(processed_img)?;
+        if let Some(ref detection) = orientation {
+            processed_img = self.correct_orientation(processed_img, detection, settings.auto_rotate_images, &mut parameters);
         }
         
         // Aggressively upscale low-resolution images for better OCR
@@ -145,9 +394,11 @@ impl EnhancedOcrService {
         let quality_stats = self.analyze_image_quality(&processed_gray);
         info!("Image quality analysis: brightness={:.1}, contrast={:.1}, noise_level={:.1}, sharpness={:.1}", 
                quality_stats.average_brightness, quality_stats.contrast_ratio, quality_stats.noise_level, quality_stats.sharpness);
+        parameters.quality = Some(quality_stats.clone());
         
         // Determine if image needs enhancement based on quality thresholds
         let needs_enhancement = self.needs_enhancement(&quality_stats, settings);
+        parameters.enhancement_applied = needs_enhancement;
         
         if !needs_enhancement {
             info!("Image quality is good, skipping enhancement steps");
@@ -157,12 +408,15 @@ impl EnhancedOcrService {
             // Apply brightness correction only for very dim images
             if quality_stats.average_brightness < 50.0 || settings.ocr_brightness_boost > 0.0 {
                 processed_gray = self.enhance_brightness_and_contrast(processed_gray, &quality_stats, settings)?;
+                parameters.brightness_boost = Some(brightness_boost(&quality_stats, settings));
+                parameters.contrast_multiplier = Some(contrast_multiplier(&quality_stats, settings));
                 preprocessing_applied.push("Brightness/contrast correction".to_string());
             }
             
             // Apply noise removal only for very noisy images
             if quality_stats.noise_level > 0.25 || (settings.ocr_remove_noise && settings.ocr_noise_reduction_level > 1) {
                 processed_gray = self.adaptive_noise_removal(processed_gray, &quality_stats, settings)?;
+                parameters.noise_reduction_level = Some(noise_reduction_level(&quality_stats, settings));
                 preprocessing_applied.push("Noise reduction".to_string());
             }
             
@@ -170,8 +424,10 @@ impl EnhancedOcrService {
             if quality_stats.contrast_ratio < 0.2 || (settings.ocr_enhance_contrast && settings.ocr_adaptive_threshold_window_size > 0) {
                 let original_gray = processed_gray.clone();
                 match self.adaptive_contrast_enhancement(processed_gray, &quality_stats, settings) {
-                    Ok(enhanced) => {
+                    Ok((enhanced, window)) => {
                         processed_gray = enhanced;
+                        parameters.binarization_window = window;
+                        parameters.histogram_equalization = window.is_none();
                         preprocessing_applied.push("Contrast enhancement".to_string());
                     }
                     Err(e) => {
@@ -182,6 +438,7 @@ impl EnhancedOcrService {
                                 warn!("Alternative contrast enhancement also failed, using original image");
                                 original_gray
                             });
+                        parameters.histogram_equalization = true;
                         preprocessing_applied.push("Basic contrast enhancement".to_string());
                     }
                 }
@@ -190,28 +447,106 @@ impl EnhancedOcrService {
             // Apply sharpening only for very blurry images
             if quality_stats.sharpness < 0.2 || settings.ocr_sharpening_strength > 0.5 {
                 processed_gray = self.sharpen_image(processed_gray, settings)?;
+                parameters.sharpened = true;
                 preprocessing_applied.push("Image sharpening".to_string());
             }
             
             // Apply morphological operations only if explicitly enabled and image needs it
             if settings.ocr_morphological_operations && quality_stats.noise_level > 0.15 {
                 processed_gray = self.apply_morphological_operations(processed_gray)?;
+                parameters.morphological_operations = true;
                 preprocessing_applied.push("Morphological operations".to_string());
             }
         }
         
+        self.save_processed_image(processed_gray, preprocessing_applied, parameters)
+    }
+
+    /// Run the steps of a configured preprocessing pipeline, exactly as given and in order
+    #[cfg(feature = "ocr")]
+    fn run_preprocessing_pipeline(
+        &self,
+        img: DynamicImage,
+        steps: &[PreprocessingStep],
+        settings: &Settings,
+        orientation: Option<&crate::ocr::orientation::OrientationDetection>,
+        parameters: &mut PreprocessingParameters,
+        preprocessing_applied: &mut Vec<String>,
+    ) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>> {
+        info!("Running configured preprocessing pipeline: {}",
+              steps.iter().map(|step| step.name()).collect::<Vec<_>>().join(", "));
+        parameters.enhancement_applied = true;
+        parameters.pipeline = Some(steps.to_vec());
+        
+        let mut processed_gray = img.to_luma8();
+        for step in steps {
+            processed_gray = match *step {
+                PreprocessingStep::Orientation => match orientation {
+                    Some(detection) => self.correct_orientation(DynamicImage::ImageLuma8(processed_gray), detection, true, parameters).into_luma8(),
+                    None => processed_gray,
+                },
+                PreprocessingStep::Resize => {
+                    self.smart_resize_for_ocr(DynamicImage::ImageLuma8(processed_gray), settings.ocr_dpi)?.into_luma8()
+                }
+                PreprocessingStep::Deskew { max_angle } => {
+                    let (deskewed, angle) = self.deskew(processed_gray, max_angle);
+                    parameters.deskew_degrees = Some(angle);
+                    deskewed
+                }
+                PreprocessingStep::Brightness { boost, contrast } => {
+                    parameters.brightness_boost = Some(boost);
+                    parameters.contrast_multiplier = Some(contrast);
+                    self.apply_brightness_and_contrast(processed_gray, boost, contrast)
+                }
+                PreprocessingStep::Denoise { level } => {
+                    parameters.noise_reduction_level = Some(level);
+                    self.apply_noise_reduction(processed_gray, level)
+                }
+                PreprocessingStep::Binarize { window } => {
+                    parameters.binarization_window = Some(window);
+                    self.binarize_adaptive(&processed_gray, window)
+                }
+                PreprocessingStep::Threshold { threshold } => {
+                    self.binarize_global(&processed_gray, threshold)
+                }
+                PreprocessingStep::Equalize => {
+                    parameters.histogram_equalization = true;
+                    self.equalize_histogram(&processed_gray)
+                }
+                PreprocessingStep::Sharpen => {
+                    parameters.sharpened = true;
+                    self.sharpen_image(processed_gray, settings)?
+                }
+                PreprocessingStep::Morphology => {
+                    parameters.morphological_operations = true;
+                    self.apply_morphological_operations(processed_gray)?
+                }
+            };
+            preprocessing_applied.push(format!("Pipeline: {}", step.name()));
+        }
+        
+        Ok(processed_gray)
+    }
+
+    /// Save a preprocessed image to the temp directory for Tesseract to read
+    #[cfg(feature = "ocr")]
+    fn save_processed_image(
+        &self,
+        processed_gray: ImageBuffer<Luma<u8>, Vec<u8>>,
+        preprocessing_applied: Vec<String>,
+        mut parameters: PreprocessingParameters,
+    ) -> Result<(String, Vec<String>, PreprocessingParameters)> {
+        (parameters.processed_width, parameters.processed_height) = processed_gray.dimensions();
+        
         // Save processed image to temporary file
-        let temp_filename = format!("processed_{}_{}.png", 
-            std::process::id(), 
-            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
-        );
+        let temp_filename = format!("processed_{}.png", uuid::Uuid::new_v4());
         let temp_path = format!("{}/{}", self.temp_dir, temp_filename);
         
         let dynamic_processed = DynamicImage::ImageLuma8(processed_gray);
         dynamic_processed.save(&temp_path)?;
         
         info!("Processed image saved to: {}", temp_path);
-        Ok((temp_path, preprocessing_applied))
+        Ok((temp_path, preprocessing_applied, parameters))
     }
 
     /// Determine if image needs enhancement based on quality thresholds
@@ -250,7 +585,7 @@ impl EnhancedOcrService {
     }
     
     /// Build language combination string for Tesseract (e.g., "eng+spa")
-    fn build_language_combination(&self, settings: &Settings) -> String {
+    pub(crate) fn build_language_combination(&self, settings: &Settings) -> String {
         if settings.preferred_languages.len() > 1 {
             // Use preferred_languages with primary_language first
             let mut languages = settings.preferred_languages.clone();
@@ -274,7 +609,16 @@ impl EnhancedOcrService {
     #[cfg(feature = "ocr")]
     fn configure_tesseract(&self, image_path: &str, settings: &Settings) -> Result<Tesseract> {
         let language_combination = self.build_language_combination(settings);
-        let mut tesseract = Tesseract::new(None, Some(&language_combination))?;
+
+        // Configure OCR Engine Mode (OEM); it can only be chosen when Tesseract is initialized
+        let oem = match settings.ocr_engine_mode {
+            0 => OcrEngineMode::TesseractOnly,
+            1 => OcrEngineMode::LstmOnly,
+            2 => OcrEngineMode::TesseractLstmCombined,
+            3 => OcrEngineMode::Default,
+            _ => OcrEngineMode::Default, // Default fallback
+        };
+        let mut tesseract = Tesseract::new_with_oem(None, Some(&language_combination), oem)?;
         
         // Set the image
         tesseract = tesseract.set_image(image_path)?;
@@ -299,20 +643,13 @@ impl EnhancedOcrService {
         };
         tesseract.set_page_seg_mode(psm);
         
-        // Configure OCR Engine Mode (OEM)
-        let _oem = match settings.ocr_engine_mode {
-            0 => OcrEngineMode::TesseractOnly,
-            1 => OcrEngineMode::LstmOnly,
-            2 => OcrEngineMode::TesseractLstmCombined,
-            3 => OcrEngineMode::Default,
-            _ => OcrEngineMode::Default, // Default fallback
-        };
-        
-        // Note: set_engine_mode may not be available in the current tesseract crate version
-        // We'll configure this differently if needed
-        
-        // Basic configuration - skip advanced settings that might cause issues
-        // Only set essential variables that are widely supported
+        // Restrict the recognized character set
+        if let Some(whitelist) = settings.ocr_whitelist_chars.as_deref().filter(|c| !c.is_empty()) {
+            tesseract = tesseract.set_variable("tessedit_char_whitelist", whitelist)?;
+        }
+        if let Some(blacklist) = settings.ocr_blacklist_chars.as_deref().filter(|c| !c.is_empty()) {
+            tesseract = tesseract.set_variable("tessedit_char_blacklist", blacklist)?;
+        }
         
         Ok(tesseract)
     }
@@ -334,19 +671,35 @@ impl EnhancedOcrService {
         Ok(clamped_confidence)
     }
     
-    /// Detect and correct image orientation
+    /// Turns an image upright as orientation detection found, if the detection is confident
+    /// and `auto_rotate` is on, and records the detection in `parameters`. An unsure detection
+    /// of a turned page is flagged for review instead of applied.
     #[cfg(feature = "ocr")]
-    fn detect_and_correct_orientation(&self, img: DynamicImage) -> Result<DynamicImage> {
-        // For now, we'll implement basic rotation detection
-        // In a production system, you might want to use Tesseract's OSD or advanced algorithms
-        let (width, height) = img.dimensions();
-        
-        // If image is wider than tall by significant margin, it might need rotation
-        if width as f32 / height as f32 > 2.0 {
-            Ok(img.rotate90())
-        } else {
-            Ok(img)
+    fn correct_orientation(
+        &self,
+        img: DynamicImage,
+        detection: &crate::ocr::orientation::OrientationDetection,
+        auto_rotate: bool,
+        parameters: &mut PreprocessingParameters,
+    ) -> DynamicImage {
+        parameters.detected_rotation_degrees = Some(detection.rotate_degrees);
+        parameters.orientation_confidence = Some(detection.confidence);
+        if detection.rotate_degrees == 0 {
+            return img;
+        }
+        if !detection.is_confident() {
+            info!("Orientation detection suggests rotating by {}° but is unsure ({:.2}), leaving the image as is",
+                  detection.rotate_degrees, detection.confidence);
+            parameters.orientation_needs_review = true;
+            return img;
+        }
+        if !auto_rotate {
+            return img;
         }
+
+        info!("Rotating image by {}° (orientation confidence {:.2})", detection.rotate_degrees, detection.confidence);
+        parameters.rotation_degrees = (parameters.rotation_degrees + detection.rotate_degrees) % 360;
+        crate::ocr::orientation::rotate_image(img, detection.rotate_degrees)
     }
     
     /// Smart resize for OCR - optimize image size for best OCR performance
@@ -552,29 +905,18 @@ impl EnhancedOcrService {
     /// Enhanced brightness and contrast correction for dim images
     #[cfg(feature = "ocr")]
     fn enhance_brightness_and_contrast(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, stats: &ImageQualityStats, settings: &Settings) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>> {
-        let (width, height) = img.dimensions();
-        let mut enhanced = ImageBuffer::new(width, height);
-        
         // Calculate enhancement parameters based on image statistics and user settings
-        let brightness_boost = if settings.ocr_brightness_boost > 0.0 {
-            settings.ocr_brightness_boost  // Use user-configured value
-        } else if stats.average_brightness < 50.0 {
-            60.0 - stats.average_brightness  // Aggressive boost for very dim images
-        } else if stats.average_brightness < 80.0 {
-            30.0 - (stats.average_brightness - 50.0) * 0.5  // Moderate boost
-        } else {
-            0.0  // No boost needed
-        };
+        let brightness_boost = brightness_boost(stats, settings);
+        let contrast_multiplier = contrast_multiplier(stats, settings);
         
-        let contrast_multiplier = if settings.ocr_contrast_multiplier > 0.0 {
-            settings.ocr_contrast_multiplier  // Use user-configured value
-        } else if stats.contrast_ratio < 0.2 {
-            2.5  // Aggressive contrast boost for flat images
-        } else if stats.contrast_ratio < 0.4 {
-            1.8  // Moderate contrast boost
-        } else {
-            1.2  // Slight boost
-        };
+        Ok(self.apply_brightness_and_contrast(img, brightness_boost, contrast_multiplier))
+    }
+    
+    /// Add `brightness_boost` to every pixel, then multiply it by `contrast_multiplier`
+    #[cfg(feature = "ocr")]
+    fn apply_brightness_and_contrast(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, brightness_boost: f32, contrast_multiplier: f32) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let (width, height) = img.dimensions();
+        let mut enhanced = ImageBuffer::new(width, height);
         
         info!("Applying brightness boost: {:.1}, contrast multiplier: {:.1}", brightness_boost, contrast_multiplier);
         
@@ -588,25 +930,21 @@ impl EnhancedOcrService {
             enhanced.put_pixel(x, y, Luma([clamped_value]));
         }
         
-        Ok(enhanced)
+        enhanced
     }
     
     /// Adaptive noise removal based on detected noise level
     #[cfg(feature = "ocr")]
     fn adaptive_noise_removal(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, stats: &ImageQualityStats, settings: &Settings) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>> {
+        let noise_level = noise_reduction_level(stats, settings);
+        Ok(self.apply_noise_reduction(img, noise_level))
+    }
+    
+    /// Noise reduction from level 1 (light) to 3 (heavy)
+    #[cfg(feature = "ocr")]
+    fn apply_noise_reduction(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, noise_level: i32) -> ImageBuffer<Luma<u8>, Vec<u8>> {
         let mut processed = img;
         
-        // Use user-configured noise reduction level if specified
-        let noise_level = if settings.ocr_noise_reduction_level > 0 {
-            settings.ocr_noise_reduction_level
-        } else if stats.noise_level > 0.2 {
-            3  // Heavy noise
-        } else if stats.noise_level > 0.1 {
-            2  // Moderate noise
-        } else {
-            1  // Light noise
-        };
-        
         match noise_level {
             3 => {
                 // Heavy noise - apply multiple filters
@@ -627,36 +965,25 @@ impl EnhancedOcrService {
             }
         }
         
-        Ok(processed)
+        processed
     }
     
-    /// Adaptive contrast enhancement based on image quality
+    /// Adaptive contrast enhancement based on image quality. Returns the
+    /// binarization window used, or `None` when histogram equalization was
+    /// applied instead.
     #[cfg(feature = "ocr")]
-    fn adaptive_contrast_enhancement(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, stats: &ImageQualityStats, settings: &Settings) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>> {
+    fn adaptive_contrast_enhancement(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, stats: &ImageQualityStats, settings: &Settings) -> Result<(ImageBuffer<Luma<u8>, Vec<u8>>, Option<u32>)> {
         // Choose threshold size based on image dimensions and quality
         let (width, height) = img.dimensions();
-        let min_dimension = width.min(height);
         
         // Check if image is too large for safe adaptive threshold processing
         // The integral image calculation can overflow with large images
         if width as u64 * height as u64 > 1_500_000 {
             info!("Image too large for adaptive threshold ({}x{}), using alternative contrast enhancement", width, height);
-            return self.apply_alternative_contrast_enhancement(img, stats, settings);
+            return Ok((self.apply_alternative_contrast_enhancement(img, stats, settings)?, None));
         }
         
-        let threshold_size = if settings.ocr_adaptive_threshold_window_size > 0 {
-            // Use user-configured window size
-            settings.ocr_adaptive_threshold_window_size as u32
-        } else if stats.contrast_ratio < 0.2 {
-            // Low contrast - use smaller windows for more aggressive local adaptation
-            (min_dimension / 20).max(11).min(31)
-        } else {
-            // Good contrast - use larger windows
-            (min_dimension / 15).max(15).min(41)
-        };
-        
-        // Ensure odd number for threshold size
-        let threshold_size = if threshold_size % 2 == 0 { threshold_size + 1 } else { threshold_size };
+        let threshold_size = binarization_window(width.min(height), stats, settings);
         
         info!("Applying adaptive threshold with window size: {}", threshold_size);
         
@@ -666,10 +993,10 @@ impl EnhancedOcrService {
         }));
         
         match enhanced {
-            Ok(result) => Ok(result),
+            Ok(result) => Ok((result, Some(threshold_size))),
             Err(_) => {
                 warn!("Adaptive threshold panicked (likely overflow), using alternative method");
-                self.apply_alternative_contrast_enhancement(img, stats, settings)
+                Ok((self.apply_alternative_contrast_enhancement(img, stats, settings)?, None))
             }
         }
     }
@@ -677,9 +1004,6 @@ impl EnhancedOcrService {
     /// Alternative contrast enhancement for large images to avoid overflow
     #[cfg(feature = "ocr")]
     fn apply_alternative_contrast_enhancement(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, stats: &ImageQualityStats, settings: &Settings) -> Result<ImageBuffer<Luma<u8>, Vec<u8>>> {
-        let (width, height) = img.dimensions();
-        let mut enhanced = ImageBuffer::new(width, height);
-        
         // Use histogram equalization instead of adaptive threshold for large images
         if settings.ocr_histogram_equalization {
             info!("Applying histogram equalization for contrast enhancement (user enabled)");
@@ -687,6 +1011,22 @@ impl EnhancedOcrService {
             info!("Applying histogram equalization for contrast enhancement (fallback)");
         }
         
+        let mut enhanced = self.equalize_histogram(&img);
+        
+        // Apply additional contrast stretching if needed
+        if stats.contrast_ratio < 0.3 {
+            enhanced = self.apply_contrast_stretching(enhanced)?;
+        }
+        
+        Ok(enhanced)
+    }
+    
+    /// Spread grey levels evenly over the full range
+    #[cfg(feature = "ocr")]
+    fn equalize_histogram(&self, img: &ImageBuffer<Luma<u8>, Vec<u8>>) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let (width, height) = img.dimensions();
+        let mut enhanced = ImageBuffer::new(width, height);
+        
         // Calculate histogram using u64 to prevent overflow
         let mut histogram = [0u64; 256];
         for pixel in img.pixels() {
@@ -716,12 +1056,95 @@ impl EnhancedOcrService {
             enhanced.put_pixel(x, y, Luma([new_value]));
         }
         
-        // Apply additional contrast stretching if needed
-        if stats.contrast_ratio < 0.3 {
-            enhanced = self.apply_contrast_stretching(enhanced)?;
+        enhanced
+    }
+    
+    /// Adaptive threshold: pixels darker than the mean of the `window`-pixel square around
+    /// them become black. Sums are kept in u64 so large images cannot overflow.
+    #[cfg(feature = "ocr")]
+    fn binarize_adaptive(&self, img: &ImageBuffer<Luma<u8>, Vec<u8>>, window: u32) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        let (width, height) = img.dimensions();
+        let radius = window / 2;
+        info!("Applying adaptive binarization with window size: {}", window);
+        
+        // Integral image with a leading row and column of zeros
+        let stride = width as usize + 1;
+        let mut integral = vec![0u64; stride * (height as usize + 1)];
+        for y in 0..height as usize {
+            let mut row_sum = 0u64;
+            for x in 0..width as usize {
+                row_sum += img.get_pixel(x as u32, y as u32)[0] as u64;
+                integral[(y + 1) * stride + x + 1] = integral[y * stride + x + 1] + row_sum;
+            }
         }
         
-        Ok(enhanced)
+        ImageBuffer::from_fn(width, height, |x, y| {
+            let (x0, y0) = (x.saturating_sub(radius) as usize, y.saturating_sub(radius) as usize);
+            let (x1, y1) = ((x + radius + 1).min(width) as usize, (y + radius + 1).min(height) as usize);
+            let sum = integral[y1 * stride + x1] + integral[y0 * stride + x0]
+                - integral[y0 * stride + x1] - integral[y1 * stride + x0];
+            let count = ((x1 - x0) * (y1 - y0)) as u64;
+            
+            let value = img.get_pixel(x, y)[0] as u64;
+            if value * count < sum { Luma([0]) } else { Luma([255]) }
+        })
+    }
+    
+    /// Global threshold: pixels darker than `threshold` become black, all others white
+    #[cfg(feature = "ocr")]
+    fn binarize_global(&self, img: &ImageBuffer<Luma<u8>, Vec<u8>>, threshold: u8) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+        info!("Applying global threshold: {}", threshold);
+        ImageBuffer::from_fn(img.width(), img.height(), |x, y| {
+            if img.get_pixel(x, y)[0] < threshold { Luma([0]) } else { Luma([255]) }
+        })
+    }
+    
+    /// Straighten text lines tilted by up to `max_angle` degrees either way. Returns the
+    /// image and the clockwise tilt that was corrected, in degrees.
+    #[cfg(feature = "ocr")]
+    fn deskew(&self, img: ImageBuffer<Luma<u8>, Vec<u8>>, max_angle: f32) -> (ImageBuffer<Luma<u8>, Vec<u8>>, f32) {
+        // The tilt is estimated on a reduced copy, full resolution does not change it
+        const SAMPLE_DIMENSION: f32 = 1000.0;
+        const MAX_SAMPLE_POINTS: usize = 200_000;
+        
+        let (width, height) = img.dimensions();
+        let scale = (SAMPLE_DIMENSION / width.max(height) as f32).min(1.0);
+        let sample = if scale < 1.0 {
+            image::imageops::resize(
+                &img,
+                ((width as f32 * scale) as u32).max(1),
+                ((height as f32 * scale) as u32).max(1),
+                image::imageops::FilterType::Triangle,
+            )
+        } else {
+            img.clone()
+        };
+        
+        // Text is whatever is clearly darker than the page on average
+        let pixel_count = sample.width() as u64 * sample.height() as u64;
+        let mean = sample.pixels().map(|p| p[0] as u64).sum::<u64>() / pixel_count.max(1);
+        let darkest = sample.pixels().map(|p| p[0]).min().unwrap_or(0) as u64;
+        let threshold = ((darkest + mean) / 2) as u8;
+        
+        let mut points: Vec<(f32, f32)> = sample
+            .enumerate_pixels()
+            .filter(|(_, _, p)| p[0] < threshold)
+            .map(|(x, y, _)| (x as f32, y as f32))
+            .collect();
+        if points.len() > MAX_SAMPLE_POINTS {
+            let stride = points.len().div_ceil(MAX_SAMPLE_POINTS);
+            points = points.into_iter().step_by(stride).collect();
+        }
+        
+        let angle = estimate_skew_angle(&points, max_angle);
+        if angle.abs() < 0.1 {
+            info!("Deskew: no tilt detected");
+            return (img, 0.0);
+        }
+        
+        info!("Deskew: straightening text tilted by {:.2} degrees", angle);
+        let deskewed = rotate_about_center(&img, -angle.to_radians(), Interpolation::Bilinear, Luma([255]));
+        (deskewed, angle)
     }
     
     /// Apply contrast stretching to improve dynamic range
@@ -848,6 +1271,35 @@ impl EnhancedOcrService {
             ));
         }
         
+        // A born-digital PDF already carries its text; it is stored as is and Tesseract never
+        // runs. Only PDFs whose text layer is sparse or missing go through OCR.
+        let min_chars = settings.ocr_text_layer_min_chars.max(0) as usize;
+        if min_chars > 0 {
+            match self.extract_pdf_text_layer(file_path, &OcrDeadline::from_settings(settings)).await {
+                Ok((text, extraction_time)) => {
+                    let char_count = text_layer_char_count(&text);
+                    if char_count >= min_chars {
+                        info!("Using the text layer of '{}' ({} characters), skipping OCR", file_path, char_count);
+                        let word_count = self.count_words_safely(&text);
+                        return Ok(OcrResult {
+                            text,
+                            confidence: 95.0,
+                            processing_time_ms: extraction_time,
+                            word_count,
+                            preprocessing_applied: vec!["PDF text extraction (text layer)".to_string()],
+                            processed_image_path: None,
+                            preprocessing_parameters: None,
+                            text_source: OcrTextSource::Embedded,
+                        });
+                    }
+                    info!("Text layer of '{}' is sparse ({} of {} characters), using full OCR", file_path, char_count, min_chars);
+                }
+                Err(e) => {
+                    warn!("Reading the text layer of '{}' failed: {}, using full OCR", file_path, e);
+                }
+            }
+        }
+
         // Check if ocrmypdf is available
         if !self.is_ocrmypdf_available().await {
             return Err(anyhow!(
@@ -856,39 +1308,17 @@ impl EnhancedOcrService {
                 On macOS: 'brew install ocrmypdf'."
             ));
         }
+
+        // The text layer was sparse or missing, so use full OCR
+        let full_ocr_result = if settings.ocr_parallel_pages {
+            self.extract_text_from_pdf_pages_parallel(file_path, settings, start_time).await
+        } else {
+            self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await
+        };
         
-        // First try to extract text without OCR for performance (using --skip-text)
-        let quick_extraction_result = self.extract_pdf_text_quick(file_path).await;
-        
-        match quick_extraction_result {
-            Ok((text, extraction_time)) => {
-                let word_count = self.count_words_safely(&text);
-                
-                // Check if quick extraction got good results
-                if self.is_text_extraction_quality_sufficient(&text, word_count, file_size) {
-                    info!("PDF text extraction successful for '{}' using quick method", file_path);
-                    return Ok(OcrResult {
-                        text,
-                        confidence: 95.0,
-                        processing_time_ms: extraction_time,
-                        word_count,
-                        preprocessing_applied: vec!["PDF text extraction (pdftotext)".to_string()],
-                        processed_image_path: None,
-                    });
-                } else {
-                    info!("Quick PDF extraction insufficient for '{}' ({} words), using full OCR", file_path, word_count);
-                }
-            }
-            Err(e) => {
-                warn!("Quick PDF extraction failed for '{}': {}, using full OCR", file_path, e);
-            }
-        }
-        
-        // If quick extraction failed or was insufficient, use full OCR
-        let full_ocr_result = self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await;
-        
-        // If OCR also fails, try direct text extraction as last resort
-        if full_ocr_result.is_err() {
+        // If OCR also fails, try direct text extraction as last resort. A timed out document
+        // is failed as is, rather than passed off with whatever strings its bytes contain.
+        if full_ocr_result.as_ref().is_err_and(|e| !is_timeout(e)) {
             warn!("Full OCR failed, trying direct text extraction as last resort for: {}", file_path);
             
             match self.extract_text_from_pdf_bytes(file_path).await {
@@ -904,6 +1334,8 @@ impl EnhancedOcrService {
                         word_count,
                         preprocessing_applied: vec!["Direct PDF text extraction (last resort)".to_string()],
                         processed_image_path: None,
+                        preprocessing_parameters: None,
+                        text_source: OcrTextSource::Embedded,
                     });
                 }
                 Ok(_) => {
@@ -918,63 +1350,6 @@ impl EnhancedOcrService {
         full_ocr_result
     }
     
-    /// Assess if text extraction quality is sufficient or if OCR fallback is needed
-    #[cfg(feature = "ocr")]
-    fn is_text_extraction_quality_sufficient(&self, text: &str, word_count: usize, file_size: u64) -> bool {
-        // If we got no words at all, definitely need OCR
-        if word_count == 0 {
-            return false;
-        }
-        
-        // For very small files, low word count might be normal
-        if file_size < 50_000 && word_count >= 1 {
-            return true;
-        }
-        
-        // Calculate word density (words per KB)
-        let file_size_kb = (file_size as f64) / 1024.0;
-        let word_density = (word_count as f64) / file_size_kb;
-        
-        // Reasonable thresholds based on typical PDF content:
-        // - Text-based PDFs typically have 50-200 words per KB
-        // - Below 5 words per KB suggests mostly images/scanned content
-        // - But if we have a substantial number of words (>50), accept it regardless of density
-        const MIN_WORD_DENSITY: f64 = 5.0;
-        const MIN_WORDS_FOR_LARGE_FILES: usize = 10;
-        const SUBSTANTIAL_WORD_COUNT: usize = 50;
-        
-        // If we have substantial text, accept it regardless of density
-        if word_count >= SUBSTANTIAL_WORD_COUNT {
-            debug!("PDF has substantial text content: {} words, accepting regardless of density", word_count);
-            return true;
-        }
-        
-        if word_density < MIN_WORD_DENSITY && word_count < MIN_WORDS_FOR_LARGE_FILES {
-            debug!("PDF appears to be image-based: {} words in {:.1} KB (density: {:.2} words/KB)", 
-                   word_count, file_size_kb, word_density);
-            return false;
-        }
-        
-        // Additional check: if text is mostly non-alphanumeric, might be extraction artifacts
-        let alphanumeric_chars = text.chars().filter(|c| c.is_alphanumeric()).count();
-        let alphanumeric_ratio = if text.len() > 0 {
-            (alphanumeric_chars as f64) / (text.len() as f64)
-        } else {
-            0.0
-        };
-        
-        // If less than 30% alphanumeric content, likely poor extraction
-        if alphanumeric_ratio < 0.3 {
-            debug!("PDF text has low alphanumeric content: {:.1}% ({} of {} chars)", 
-                   alphanumeric_ratio * 100.0, alphanumeric_chars, text.len());
-            return false;
-        }
-        
-        debug!("PDF text extraction quality sufficient: {} words, {:.2} words/KB, {:.1}% alphanumeric", 
-               word_count, word_density, alphanumeric_ratio * 100.0);
-        true
-    }
-    
     /// Extract text from PDF using OCR (ocrmypdf) for image-based or poor-quality PDFs
     #[cfg(feature = "ocr")]
     async fn extract_text_from_pdf_with_ocr(&self, file_path: &str, settings: &Settings, start_time: std::time::Instant) -> Result<OcrResult> {
@@ -991,116 +1366,8 @@ impl EnhancedOcrService {
             ));
         }
         
-        // Generate temporary file path for OCR'd PDF
-        let temp_ocr_filename = format!("ocr_{}_{}.pdf", 
-            std::process::id(), 
-            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
-        );
-        let temp_ocr_path = format!("{}/{}", self.temp_dir, temp_ocr_filename);
-        
-        // Run ocrmypdf with progressive fallback strategies
-        let ocrmypdf_result = tokio::time::timeout(
-            std::time::Duration::from_secs(300), // 5 minute timeout for OCR
-            tokio::task::spawn_blocking({
-                let file_path = file_path.to_string();
-                let temp_ocr_path = temp_ocr_path.clone();
-                move || {
-                    // Strategy 1: Standard OCR with cleaning
-                    let mut result = std::process::Command::new("ocrmypdf")
-                        .arg("--force-ocr")  // OCR even if text is detected
-                        .arg("-O2")          // Optimize level 2 (balanced quality/speed)
-                        .arg("--deskew")     // Correct skewed pages
-                        .arg("--clean")      // Clean up artifacts
-                        .arg("--language")
-                        .arg("eng")          // English language
-                        .arg(&file_path)
-                        .arg(&temp_ocr_path)
-                        .output();
-                    
-                    if result.is_ok() && result.as_ref().unwrap().status.success() {
-                        return result;
-                    }
-                    
-                    // Strategy 2: If standard OCR fails, try with error recovery
-                    eprintln!("Standard OCR failed, trying recovery mode...");
-                    result = std::process::Command::new("ocrmypdf")
-                        .arg("--force-ocr")
-                        .arg("--fix-metadata")  // Fix metadata issues
-                        .arg("--remove-background")  // Remove background noise
-                        .arg("-O1")          // Lower optimization for problematic PDFs
-                        .arg("--language")
-                        .arg("eng")
-                        .arg(&file_path)
-                        .arg(&temp_ocr_path)
-                        .output();
-                    
-                    if result.is_ok() && result.as_ref().unwrap().status.success() {
-                        return result;
-                    }
-                    
-                    // Strategy 3: Last resort - minimal processing (skips very large pages)
-                    eprintln!("Recovery mode failed, trying minimal processing...");
-                    std::process::Command::new("ocrmypdf")
-                        .arg("--force-ocr")
-                        .arg("--skip-big")  // Skip very large pages that might cause memory issues
-                        .arg("--language")
-                        .arg("eng")
-                        .arg(&file_path)
-                        .arg(&temp_ocr_path)
-                        .output()
-                }
-            })
-        ).await;
-        
-        let ocrmypdf_output = match ocrmypdf_result {
-            Ok(Ok(output)) => output?,
-            Ok(Err(e)) => return Err(anyhow!("Failed to join ocrmypdf task: {}", e)),
-            Err(_) => return Err(anyhow!("ocrmypdf timed out after 5 minutes for file '{}'", file_path)),
-        };
-        
-        if !ocrmypdf_output.status.success() {
-            let stderr = String::from_utf8_lossy(&ocrmypdf_output.stderr);
-            let stdout = String::from_utf8_lossy(&ocrmypdf_output.stdout);
-            return Err(anyhow!(
-                "ocrmypdf failed for '{}': Exit code {}\nStderr: {}\nStdout: {}",
-                file_path, ocrmypdf_output.status.code().unwrap_or(-1), stderr, stdout
-            ));
-        }
-        
-        // Extract text from the OCR'd PDF
-        let ocr_text_result = tokio::task::spawn_blocking({
-            let temp_ocr_path = temp_ocr_path.clone();
-            move || -> Result<String> {
-                let bytes = std::fs::read(&temp_ocr_path)?;
-                // Catch panics from pdf-extract library (same pattern as used elsewhere)
-                // Extract text from the OCR'd PDF using ocrmypdf's sidecar option
-                let temp_text_path = format!("{}.txt", temp_ocr_path);
-                let extract_result = std::process::Command::new("ocrmypdf")
-                    .arg("--sidecar")  // Extract text to a sidecar file
-                    .arg(&temp_text_path)
-                    .arg(&temp_ocr_path)
-                    .arg("-")  // Output to stdout (dummy, required by ocrmypdf)
-                    .output()?;
-                
-                if !extract_result.status.success() {
-                    let stderr = String::from_utf8_lossy(&extract_result.stderr);
-                    return Err(anyhow!(
-                        "ocrmypdf text extraction failed: {}",
-                        stderr
-                    ));
-                }
-                
-                // Read the extracted text from the sidecar file
-                let text = std::fs::read_to_string(&temp_text_path)?;
-                
-                // Clean up the text file
-                let _ = std::fs::remove_file(&temp_text_path);
-                Ok(text.trim().to_string())
-            }
-        }).await??;
-        
-        // Clean up temporary file
-        let _ = tokio::fs::remove_file(&temp_ocr_path).await;
+        let deadline = OcrDeadline::from_settings(settings);
+        let ocr_text_result = self.run_ocrmypdf(file_path, &self.temp_dir, &deadline).await?;
         
         let processing_time = start_time.elapsed().as_millis() as u64;
         let word_count = self.count_words_safely(&ocr_text_result);
@@ -1115,148 +1382,227 @@ impl EnhancedOcrService {
             word_count,
             preprocessing_applied: vec!["OCR via ocrmypdf".to_string()],
             processed_image_path: None,
+            preprocessing_parameters: None,
+            text_source: OcrTextSource::Ocr,
         })
     }
     
-    /// Progressive PDF text extraction with fallback strategies
+    /// OCR a multi-page PDF page by page on a worker pool bounded by `concurrent_ocr_jobs`.
+    /// A page that fails OCR is left blank rather than failing the document; PDFs that
+    /// cannot be split, or have a single page, use the serial path instead.
     #[cfg(feature = "ocr")]
-    async fn extract_pdf_text_quick(&self, file_path: &str) -> Result<(String, u64)> {
-        let start_time = std::time::Instant::now();
-        
-        // Generate temporary file path for text extraction
-        let temp_text_filename = format!("quick_text_{}_{}.txt", 
-            std::process::id(), 
-            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH)?.as_millis()
-        );
-        let temp_text_path = format!("{}/{}", self.temp_dir, temp_text_filename);
-        
-        // Strategy 1: Fast text extraction using pdftotext (for existing text)
-        debug!("Trying pdftotext for existing text extraction: {}", file_path);
-        debug!("Using temp file path: {}", temp_text_path);
-        let pdftotext_result = tokio::process::Command::new("pdftotext")
-            .arg("-layout")  // Preserve layout
-            .arg(file_path)
-            .arg(&temp_text_path)
-            .output()
-            .await;
-        
-        if let Ok(output) = pdftotext_result {
-            debug!("pdftotext exit status: {}", output.status);
-            if !output.stderr.is_empty() {
-                debug!("pdftotext stderr: {}", String::from_utf8_lossy(&output.stderr));
-            }
-            if output.status.success() {
-                if let Ok(text) = tokio::fs::read_to_string(&temp_text_path).await {
-                    let _ = tokio::fs::remove_file(&temp_text_path).await;
-                    let word_count = text.split_whitespace().count();
-                    debug!("pdftotext extracted {} words from temp file", word_count);
-                    
-                    // If we got substantial text (more than a few words), use it
-                    if word_count > 5 {
-                        let processing_time = start_time.elapsed().as_millis() as u64;
-                        info!("pdftotext extracted {} words from: {}", word_count, file_path);
-                        return Ok((text.trim().to_string(), processing_time));
-                    } else {
-                        debug!("pdftotext only extracted {} words, will try direct extraction before OCR", word_count);
-                    }
-                } else {
-                    debug!("Failed to read pdftotext output file: {}", temp_text_path);
-                }
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                debug!("pdftotext failed with status {}: {}", output.status, stderr);
-            }
-        } else {
-            debug!("Failed to execute pdftotext command");
+    async fn extract_text_from_pdf_pages_parallel(&self, file_path: &str, settings: &Settings, start_time: std::time::Instant) -> Result<OcrResult> {
+        if !self.is_ocrmypdf_available().await {
+            return self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await;
         }
-        
-        info!("pdftotext extraction insufficient for '{}', trying direct extraction before OCR", file_path);
-        
-        // Strategy 2: Try direct text extraction (often works when pdftotext fails)
-        match self.extract_text_from_pdf_bytes(file_path).await {
-            Ok(text) if !text.trim().is_empty() => {
-                let word_count = text.split_whitespace().count();
-                if word_count > 5 {
-                    let processing_time = start_time.elapsed().as_millis() as u64;
-                    info!("Direct text extraction succeeded for '{}': {} words", file_path, word_count);
-                    return Ok((text, processing_time));
-                } else {
-                    debug!("Direct extraction only got {} words, trying OCR", word_count);
-                }
-            }
-            Ok(_) => {
-                debug!("Direct text extraction returned empty text");
+
+        let pdf_service = PdfService::new(PathBuf::from(&self.temp_dir));
+        let page_count = match pdf_service.page_count(Path::new(file_path)).await {
+            Ok(count) => count,
+            Err(e) => {
+                warn!("Could not count pages of '{}', using serial OCR: {}", file_path, e);
+                return self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await;
             }
+        };
+        if page_count <= 1 {
+            return self.extract_text_from_pdf_with_ocr(file_path, settings, start_time).await;
+        }
+
+        let workers = settings.concurrent_ocr_jobs.max(1) as usize;
+        info!("Starting parallel OCR for PDF '{}': {} pages, {} workers", file_path, page_count, workers);
+
+        let scratch_dir = Path::new(&self.temp_dir).join(format!("pdf_pages_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&scratch_dir).await?;
+        let deadline = OcrDeadline::from_settings(settings);
+        let page_texts = self.ocr_pdf_pages(&pdf_service, file_path, &scratch_dir, workers, &deadline).await;
+        if let Err(e) = tokio::fs::remove_dir_all(&scratch_dir).await {
+            warn!("Failed to remove PDF page scratch directory {}: {}", scratch_dir.display(), e);
+        }
+        let page_texts = page_texts?;
+
+        let failed_pages = page_texts.iter().filter(|text| text.is_none()).count();
+        if failed_pages == page_texts.len() {
+            return Err(anyhow!("OCR failed for all {} pages of '{}'", page_texts.len(), file_path));
+        }
+
+        let text = join_page_texts(&page_texts);
+        let processing_time = start_time.elapsed().as_millis() as u64;
+        let word_count = self.count_words_safely(&text);
+
+        let mut preprocessing_applied = vec![format!("Parallel per-page OCR via ocrmypdf ({} pages)", page_texts.len())];
+        if failed_pages > 0 {
+            warn!("{} of {} pages of '{}' failed OCR and were left blank", failed_pages, page_texts.len(), file_path);
+            preprocessing_applied.push(format!("{} pages failed OCR and were left blank", failed_pages));
+        }
+
+        info!("Parallel OCR completed for '{}': {} words in {}ms", file_path, word_count, processing_time);
+
+        Ok(OcrResult {
+            text,
+            confidence: 85.0,
+            processing_time_ms: processing_time,
+            word_count,
+            preprocessing_applied,
+            processed_image_path: None,
+            preprocessing_parameters: None,
+            text_source: OcrTextSource::Ocr,
+        })
+    }
+
+    /// Splits a PDF into `scratch_dir` and OCRs the pages with at most `workers` running at once.
+    /// Results are in page order, with `None` for pages whose OCR failed. Fails if the pages
+    /// are not all done by `deadline`.
+    #[cfg(feature = "ocr")]
+    async fn ocr_pdf_pages(&self, pdf_service: &PdfService, file_path: &str, scratch_dir: &Path, workers: usize, deadline: &OcrDeadline) -> Result<Vec<Option<String>>> {
+        let pages = pdf_service.split_pages(Path::new(file_path), scratch_dir).await?;
+        let work_dir = scratch_dir.to_string_lossy().to_string();
+        let semaphore = Semaphore::new(workers);
+
+        // join_all yields results in page order regardless of completion order
+        let page_texts = futures::future::join_all(
+            pages.iter().enumerate().map(|(index, page_path)| {
+                self.ocr_pdf_page(&semaphore, file_path, index + 1, page_path, &work_dir, deadline)
+            })
+        ).await;
+
+        page_texts.into_iter().collect()
+    }
+
+    /// OCRs one split-out page once a worker slot is free; `None` if OCR failed. A timeout
+    /// is an error, as it fails the whole document.
+    #[cfg(feature = "ocr")]
+    async fn ocr_pdf_page(&self, semaphore: &Semaphore, file_path: &str, page: usize, page_path: &Path, work_dir: &str, deadline: &OcrDeadline) -> Result<Option<String>> {
+        let Ok(_permit) = semaphore.acquire().await else {
+            return Ok(None);
+        };
+        match self.run_ocrmypdf(&page_path.to_string_lossy(), work_dir, deadline).await {
+            Ok(text) => Ok(Some(text)),
+            Err(e) if is_timeout(&e) => Err(e),
             Err(e) => {
-                debug!("Direct text extraction failed: {}", e);
+                warn!("OCR failed for page {} of '{}': {}", page, file_path, e);
+                Ok(None)
             }
         }
-        
-        info!("Direct extraction insufficient for '{}', using OCR extraction", file_path);
-        
-        // Strategy 3: Use ocrmypdf --sidecar to extract existing OCR text
-        let ocrmypdf_result = tokio::process::Command::new("ocrmypdf")
-            .arg("--sidecar")
-            .arg(&temp_text_path)
-            .arg(file_path)
-            .arg("-")  // Dummy output (we only want sidecar)
-            .output()
-            .await;
-        
-        if let Ok(output) = &ocrmypdf_result {
-            if output.status.success() {
-                if let Ok(text) = tokio::fs::read_to_string(&temp_text_path).await {
-                    let _ = tokio::fs::remove_file(&temp_text_path).await;
-                    let word_count = text.split_whitespace().count();
-                    if word_count > 0 {
-                        let processing_time = start_time.elapsed().as_millis() as u64;
-                        info!("ocrmypdf --sidecar extracted {} words from: {}", word_count, file_path);
-                        return Ok((text.trim().to_string(), processing_time));
-                    }
+    }
+    
+    /// Runs ocrmypdf on a PDF and returns its sidecar text. Intermediate files are
+    /// written to `work_dir` and removed whether or not OCR succeeds. ocrmypdf is killed
+    /// if it is still running at `deadline`.
+    #[cfg(feature = "ocr")]
+    async fn run_ocrmypdf(&self, file_path: &str, work_dir: &str, deadline: &OcrDeadline) -> Result<String> {
+        let temp_ocr_path = format!("{}/ocr_{}.pdf", work_dir, uuid::Uuid::new_v4());
+        let temp_text_path = format!("{}.txt", temp_ocr_path);
+
+        let result: Result<String> = async {
+            // Run ocrmypdf with progressive fallback strategies
+            let strategies: [&[&str]; 3] = [
+                // Strategy 1: Standard OCR with cleaning
+                &["--force-ocr", "-O2", "--deskew", "--clean", "--language", "eng"],
+                // Strategy 2: If standard OCR fails, try with error recovery
+                &["--force-ocr", "--fix-metadata", "--remove-background", "-O1", "--language", "eng"],
+                // Strategy 3: Last resort - minimal processing (skips very large pages)
+                &["--force-ocr", "--skip-big", "--language", "eng"],
+            ];
+
+            let mut ocrmypdf_output = None;
+            for (index, args) in strategies.iter().enumerate() {
+                if index > 0 {
+                    debug!("ocrmypdf strategy {} failed for '{}', trying strategy {}", index, file_path, index + 1);
                 }
-            } else {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                debug!("ocrmypdf --sidecar failed: {}", stderr);
-                
-                // Check if the error indicates the page already has text
-                if stderr.contains("page already has text") {
-                    // This is good - it means there's already text, we should use pdftotext
-                    warn!("ocrmypdf detected existing text in PDF, this should have been caught by pdftotext");
+                let attempt = output_before(
+                    tokio::process::Command::new("ocrmypdf").args(*args).arg(file_path).arg(&temp_ocr_path),
+                    deadline,
+                ).await;
+                match attempt {
+                    // A timed out run has used up the budget of the whole document
+                    Err(e) if is_timeout(&e) => return Err(e),
+                    Ok(output) if output.status.success() => {
+                        ocrmypdf_output = Some(output);
+                        break;
+                    }
+                    attempt => ocrmypdf_output = attempt.ok(),
                 }
             }
-        }
-        
-        // Strategy 3: Last resort - direct byte-level text extraction
-        warn!("Standard extraction methods failed, trying direct text extraction from: {}", file_path);
-        
-        match self.extract_text_from_pdf_bytes(file_path).await {
-            Ok(text) if !text.trim().is_empty() => {
-                let processing_time = start_time.elapsed().as_millis() as u64;
-                let word_count = text.split_whitespace().count();
-                info!("Direct text extraction succeeded for '{}': {} words", file_path, word_count);
-                Ok((text, processing_time))
+
+            let ocrmypdf_output = ocrmypdf_output
+                .ok_or_else(|| anyhow!("Failed to run ocrmypdf for '{}'", file_path))?;
+            if !ocrmypdf_output.status.success() {
+                let stderr = String::from_utf8_lossy(&ocrmypdf_output.stderr);
+                let stdout = String::from_utf8_lossy(&ocrmypdf_output.stdout);
+                return Err(anyhow!(
+                    "ocrmypdf failed for '{}': Exit code {}\nStderr: {}\nStdout: {}",
+                    file_path, ocrmypdf_output.status.code().unwrap_or(-1), stderr, stdout
+                ));
             }
-            Ok(_) => {
-                warn!("Direct text extraction returned empty text for: {}", file_path);
-                // If all strategies fail, return the last error
-                if let Ok(ref output) = ocrmypdf_result {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    Err(anyhow!("All PDF extraction strategies failed. Last error: {}", stderr))
-                } else {
-                    Err(anyhow!("All PDF extraction strategies failed"))
-                }
+
+            // Extract text from the OCR'd PDF using ocrmypdf's sidecar option
+            let extract_result = output_before(
+                tokio::process::Command::new("ocrmypdf")
+                    .arg("--sidecar")  // Extract text to a sidecar file
+                    .arg(&temp_text_path)
+                    .arg(&temp_ocr_path)
+                    .arg("-"),  // Output to stdout (dummy, required by ocrmypdf)
+                deadline,
+            ).await?;
+
+            if !extract_result.status.success() {
+                let stderr = String::from_utf8_lossy(&extract_result.stderr);
+                return Err(anyhow!(
+                    "ocrmypdf text extraction failed: {}",
+                    stderr
+                ));
             }
-            Err(e) => {
-                warn!("Direct text extraction also failed for {}: {}", file_path, e);
-                // If all strategies fail, return the last error
-                if let Ok(ref output) = ocrmypdf_result {
-                    let stderr = String::from_utf8_lossy(&output.stderr);
-                    Err(anyhow!("All PDF extraction strategies failed. Last error: {}", stderr))
-                } else {
-                    Err(anyhow!("All PDF extraction strategies failed: {}", e))
+
+            // Read the extracted text from the sidecar file
+            let text = tokio::fs::read_to_string(&temp_text_path).await?;
+            Ok(text.trim().to_string())
+        }.await;
+
+        let _ = tokio::fs::remove_file(&temp_ocr_path).await;
+        let _ = tokio::fs::remove_file(&temp_text_path).await;
+        result
+    }
+    
+    /// Reads the text layer of a PDF without OCR: pdftotext first, then the strings stored
+    /// in the PDF bytes. An empty result means the PDF has no text layer.
+    #[cfg(feature = "ocr")]
+    async fn extract_pdf_text_layer(&self, file_path: &str, deadline: &OcrDeadline) -> Result<(String, u64)> {
+        let start_time = std::time::Instant::now();
+        let temp_text_path = format!("{}/text_layer_{}.txt", self.temp_dir, uuid::Uuid::new_v4());
+
+        debug!("Trying pdftotext for existing text extraction: {}", file_path);
+        let pdftotext_result = output_before(
+            tokio::process::Command::new("pdftotext")
+                .arg("-layout")  // Preserve layout
+                .arg(file_path)
+                .arg(&temp_text_path),
+            deadline,
+        ).await;
+
+        let mut text = String::new();
+        match pdftotext_result {
+            Ok(output) if output.status.success() => {
+                match tokio::fs::read_to_string(&temp_text_path).await {
+                    Ok(extracted) => text = extracted.trim().to_string(),
+                    Err(e) => debug!("Failed to read pdftotext output file {}: {}", temp_text_path, e),
                 }
             }
+            Ok(output) => {
+                debug!("pdftotext failed with status {}: {}", output.status, String::from_utf8_lossy(&output.stderr));
+            }
+            Err(e) => debug!("Failed to execute pdftotext command: {}", e),
+        }
+        let _ = tokio::fs::remove_file(&temp_text_path).await;
+
+        // Direct extraction often works when pdftotext fails on a damaged PDF
+        if text.is_empty() {
+            match self.extract_text_from_pdf_bytes(file_path).await {
+                Ok(extracted) => text = extracted.trim().to_string(),
+                Err(e) => debug!("Direct text extraction failed for '{}': {}", file_path, e),
+            }
         }
+
+        Ok((text, start_time.elapsed().as_millis() as u64))
     }
     
     /// Last resort: extract readable text directly from PDF bytes
@@ -1378,12 +1724,6 @@ impl EnhancedOcrService {
         }
     }
     
-    #[cfg(not(feature = "ocr"))]
-    fn is_text_extraction_quality_sufficient(&self, _text: &str, _word_count: usize, _file_size: u64) -> bool {
-        // When OCR is disabled, always accept text extraction results
-        true
-    }
-    
     #[cfg(not(feature = "ocr"))]
     async fn is_ocrmypdf_available(&self) -> bool {
         false // OCR feature not enabled
@@ -1417,7 +1757,9 @@ impl EnhancedOcrService {
     pub async fn extract_text(&self, file_path: &str, mime_type: &str, settings: &Settings) -> Result<OcrResult> {
         // Resolve the actual file path
         let resolved_path = self.resolve_file_path(file_path).await?;
-        match mime_type {
+        // Route on what the file actually contains; the stored type may predate content sniffing
+        let detected = crate::mime_detection::detect_mime_from_file(&resolved_path, mime_type).await;
+        match detected.mime_type.as_str() {
             "application/pdf" => {
                 #[cfg(feature = "ocr")]
                 {
@@ -1428,6 +1770,16 @@ impl EnhancedOcrService {
                     Err(anyhow::anyhow!("OCR feature not enabled"))
                 }
             }
+            "image/tiff" => {
+                #[cfg(feature = "ocr")]
+                {
+                    self.extract_text_from_tiff(&resolved_path, settings).await
+                }
+                #[cfg(not(feature = "ocr"))]
+                {
+                    Err(anyhow::anyhow!("OCR feature not enabled"))
+                }
+            }
             mime if mime.starts_with("image/") => {
                 #[cfg(feature = "ocr")]
                 {
@@ -1476,9 +1828,11 @@ impl EnhancedOcrService {
                     word_count,
                     preprocessing_applied: vec!["Plain text read".to_string()],
                     processed_image_path: None, // No image processing for plain text
+                    preprocessing_parameters: None,
+                    text_source: OcrTextSource::Embedded,
                 })
             }
-            _ => Err(anyhow::anyhow!("Unsupported file type: {}", mime_type)),
+            _ => Err(anyhow::anyhow!("Unsupported file type: {}", detected.mime_type)),
         }
     }
     
@@ -1613,6 +1967,92 @@ impl EnhancedOcrService {
     }
 }
 
+/// Joins per-page OCR text in page order. Pages that failed OCR (`None`) or came back
+/// empty contribute nothing.
+#[cfg(feature = "ocr")]
+fn join_page_texts(pages: &[Option<String>]) -> String {
+    pages
+        .iter()
+        .filter_map(|page| page.as_deref().map(str::trim))
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Average of per-page confidences weighted by each page's word count, so a nearly
+/// blank page does not drag down a document of otherwise clean pages. Falls back to
+/// the plain average when no page has words.
+#[cfg(feature = "ocr")]
+fn weighted_confidence(pages: impl Iterator<Item = (f32, usize)> + Clone) -> f32 {
+    let total_words: usize = pages.clone().map(|(_, words)| words).sum();
+    if total_words > 0 {
+        return pages.map(|(confidence, words)| confidence * words as f32).sum::<f32>() / total_words as f32;
+    }
+    let (sum, count) = pages.fold((0.0, 0usize), |(sum, count), (confidence, _)| (sum + confidence, count + 1));
+    if count == 0 { 0.0 } else { sum / count as f32 }
+}
+
+/// Brightness offset added before the contrast multiplier is applied
+#[cfg(feature = "ocr")]
+fn brightness_boost(stats: &ImageQualityStats, settings: &Settings) -> f32 {
+    if settings.ocr_brightness_boost > 0.0 {
+        settings.ocr_brightness_boost  // Use user-configured value
+    } else if stats.average_brightness < 50.0 {
+        60.0 - stats.average_brightness  // Aggressive boost for very dim images
+    } else if stats.average_brightness < 80.0 {
+        30.0 - (stats.average_brightness - 50.0) * 0.5  // Moderate boost
+    } else {
+        0.0  // No boost needed
+    }
+}
+
+/// Factor pixel values are multiplied by during brightness/contrast correction
+#[cfg(feature = "ocr")]
+fn contrast_multiplier(stats: &ImageQualityStats, settings: &Settings) -> f32 {
+    if settings.ocr_contrast_multiplier > 0.0 {
+        settings.ocr_contrast_multiplier  // Use user-configured value
+    } else if stats.contrast_ratio < 0.2 {
+        2.5  // Aggressive contrast boost for flat images
+    } else if stats.contrast_ratio < 0.4 {
+        1.8  // Moderate contrast boost
+    } else {
+        1.2  // Slight boost
+    }
+}
+
+/// Noise reduction level from 1 (light) to 3 (heavy)
+#[cfg(feature = "ocr")]
+fn noise_reduction_level(stats: &ImageQualityStats, settings: &Settings) -> i32 {
+    // Use user-configured noise reduction level if specified
+    if settings.ocr_noise_reduction_level > 0 {
+        settings.ocr_noise_reduction_level
+    } else if stats.noise_level > 0.2 {
+        3  // Heavy noise
+    } else if stats.noise_level > 0.1 {
+        2  // Moderate noise
+    } else {
+        1  // Light noise
+    }
+}
+
+/// Odd window size for adaptive threshold binarization
+#[cfg(feature = "ocr")]
+fn binarization_window(min_dimension: u32, stats: &ImageQualityStats, settings: &Settings) -> u32 {
+    let threshold_size = if settings.ocr_adaptive_threshold_window_size > 0 {
+        // Use user-configured window size
+        settings.ocr_adaptive_threshold_window_size as u32
+    } else if stats.contrast_ratio < 0.2 {
+        // Low contrast - use smaller windows for more aggressive local adaptation
+        (min_dimension / 20).clamp(11, 31)
+    } else {
+        // Good contrast - use larger windows
+        (min_dimension / 15).clamp(15, 41)
+    };
+    
+    // Ensure odd number for threshold size
+    if threshold_size % 2 == 0 { threshold_size + 1 } else { threshold_size }
+}
+
 /// Check if the given bytes represent a valid PDF file
 /// Handles PDFs with leading null bytes or whitespace
 fn is_valid_pdf(data: &[u8]) -> bool {
@@ -1632,4 +2072,49 @@ fn is_valid_pdf(data: &[u8]) -> bool {
     }
     
     false
-}
\ No newline at end of file
+}
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join_page_texts_preserves_order_and_blanks_failures() {
+        let pages = vec![
+            Some("Page one\n".to_string()),
+            None,
+            Some("   ".to_string()),
+            Some("Page four".to_string()),
+        ];
+        assert_eq!(join_page_texts(&pages), "Page one\n\nPage four");
+        assert_eq!(join_page_texts(&[None, None]), "");
+    }
+
+    #[test]
+    fn test_weighted_confidence_favours_pages_with_more_words() {
+        let pages = [(90.0, 300), (30.0, 3)];
+        assert!((weighted_confidence(pages.into_iter()) - 89.41).abs() < 0.01);
+        assert_eq!(weighted_confidence([(80.0, 0), (40.0, 0)].into_iter()), 60.0);
+        assert_eq!(weighted_confidence(std::iter::empty()), 0.0);
+    }
+
+    #[test]
+    fn test_text_layer_char_count_ignores_layout_and_debris() {
+        assert_eq!(text_layer_char_count("  Invoice  No. 42\n\n\t-- | --"), 11);
+        assert_eq!(text_layer_char_count("\n \u{c} \n"), 0);
+        assert_eq!(text_layer_char_count("Straße 7"), 7);
+    }
+
+    #[test]
+    fn test_binarization_window_is_odd_and_honours_user_setting() {
+        let flat = ImageQualityStats { average_brightness: 120.0, contrast_ratio: 0.1, noise_level: 0.0, sharpness: 0.5 };
+        let mut settings = Settings::default();
+
+        settings.ocr_adaptive_threshold_window_size = 20;
+        assert_eq!(binarization_window(1000, &flat, &settings), 21);
+
+        settings.ocr_adaptive_threshold_window_size = 0;
+        assert_eq!(binarization_window(100, &flat, &settings), 11);
+        assert_eq!(binarization_window(4000, &flat, &settings), 31);
+    }
+}
@@ -30,6 +30,36 @@ impl OcrHealthChecker {
         Ok(version)
     }
     
+    /// First line of `ocrmypdf --version`, or `None` when ocrmypdf cannot be run
+    pub fn check_ocrmypdf_installation(&self) -> Option<String> {
+        let output = Command::new("ocrmypdf").arg("--version").output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+
+        let version_info = String::from_utf8_lossy(&output.stdout);
+        let version = version_info.lines().next().map(|s| s.trim().to_string()).unwrap_or_default();
+        Some(if version.is_empty() { "Unknown".to_string() } else { version })
+    }
+
+    /// Whether Tesseract can actually be initialized with a language's data. A pack can
+    /// be listed by `--list-langs` and still fail to load, e.g. when it is truncated or
+    /// was trained for another Tesseract version.
+    pub fn check_language_usable(&self, lang: &str) -> Result<(), OcrError> {
+        #[cfg(feature = "ocr")]
+        {
+            tesseract::Tesseract::new(None, Some(lang))
+                .map(|_| ())
+                .map_err(|e| OcrError::InitializationFailed { details: e.to_string() })
+        }
+
+        #[cfg(not(feature = "ocr"))]
+        {
+            let _ = lang;
+            Err(OcrError::InitializationFailed { details: "OCR support is not compiled into this build".to_string() })
+        }
+    }
+    
     pub fn check_language_data(&self, lang: &str) -> Result<(), OcrError> {
         // Use Tesseract's built-in language validation by attempting to list languages
         let output = Command::new("tesseract")
@@ -0,0 +1,152 @@
+//! OCR language picked per document from where it sits on its source.
+//!
+//! A source's `ocr_language_routes` maps folders or labels to Tesseract languages, so a
+//! library sorted into `/Docs/Deutsch` and `/Docs/English` is read with `deu` and `eng`.
+//! The first route that matches a document wins; documents no route matches keep the
+//! language of the user's settings.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::models::OcrOverrides;
+
+/// Most routes one source may have
+pub const MAX_ROUTES: usize = 100;
+
+/// Documents one OCR language applies to: those at or below `path_prefix` on the source,
+/// or those with the label `label`. A route has exactly one of the two.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(deny_unknown_fields)]
+pub struct OcrLanguageRoute {
+    /// Folder on the source, e.g. `/Docs/Deutsch`; matches the folder and everything below it
+    #[serde(default)]
+    pub path_prefix: Option<String>,
+    /// Name of a label, matched exactly
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Tesseract language or `+`-joined combination, e.g. `deu` or `deu+eng`
+    pub language: String,
+}
+
+impl OcrLanguageRoute {
+    fn matches(&self, source_path: Option<&str>, labels: &[String]) -> bool {
+        if let Some(prefix) = &self.path_prefix {
+            let prefix = prefix.trim_end_matches('/');
+            return source_path.is_some_and(|path| {
+                path.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            });
+        }
+        self.label.as_ref().is_some_and(|label| labels.iter().any(|l| l == label))
+    }
+}
+
+/// The `ocr_language_routes` of a source, in the order they are tried
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct OcrLanguageRoutes(pub Vec<OcrLanguageRoute>);
+
+impl OcrLanguageRoutes {
+    /// Reads the `ocr_language_routes` list of a source config. Absent, null or empty means
+    /// the source has none; anything else must parse and pass `validate`.
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Option<Self>, String> {
+        match config.get("ocr_language_routes") {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(value) => {
+                let routes: Self = serde_json::from_value(value.clone())
+                    .map_err(|e| format!("Invalid ocr_language_routes: {}", e))?;
+                routes.validate()?;
+                Ok(Some(routes).filter(|r| !r.0.is_empty()))
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.0.len() > MAX_ROUTES {
+            return Err(format!("ocr_language_routes has at most {} routes, got {}", MAX_ROUTES, self.0.len()));
+        }
+        for route in &self.0 {
+            match (&route.path_prefix, &route.label) {
+                (Some(_), Some(_)) | (None, None) => {
+                    return Err("each OCR language route needs either a path_prefix or a label".to_string());
+                }
+                (Some(prefix), None) if prefix.trim_end_matches('/').is_empty() => {
+                    return Err("path_prefix must name a folder; leave the route out to use one language for the whole source".to_string());
+                }
+                (None, Some(label)) if label.trim().is_empty() => {
+                    return Err("label must not be empty".to_string());
+                }
+                _ => {}
+            }
+            // Same format as the language of OCR overrides
+            OcrOverrides { language: Some(route.language.clone()), ..Default::default() }.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Rejects routes to languages that have no installed language pack
+    pub fn validate_installed(&self, installed: &[String]) -> Result<(), String> {
+        for route in &self.0 {
+            if let Some(missing) = route.language.split('+').find(|lang| !installed.iter().any(|code| code == lang)) {
+                return Err(format!(
+                    "OCR language '{}' is not installed. Available languages: {}",
+                    missing,
+                    installed.join(", ")
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Language of the first route matching a document at `source_path` with `labels`
+    pub fn language_for(&self, source_path: Option<&str>, labels: &[String]) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|route| route.matches(source_path, labels))
+            .map(|route| route.language.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routes(value: serde_json::Value) -> Result<Option<OcrLanguageRoutes>, String> {
+        OcrLanguageRoutes::from_source_config(&serde_json::json!({ "ocr_language_routes": value }))
+    }
+
+    #[test]
+    fn test_first_matching_route_picks_the_language() {
+        let routes = routes(serde_json::json!([
+            { "path_prefix": "/Docs/Deutsch/", "language": "deu" },
+            { "path_prefix": "/Docs", "language": "eng" },
+            { "label": "Français", "language": "fra" }
+        ])).unwrap().unwrap();
+        let labels = vec!["Français".to_string()];
+
+        assert_eq!(routes.language_for(Some("/Docs/Deutsch/brief.pdf"), &[]), Some("deu"));
+        assert_eq!(routes.language_for(Some("/Docs/English/letter.pdf"), &labels), Some("eng"));
+        // A prefix only matches whole folder names
+        assert_eq!(routes.language_for(Some("/Docsets/notes.pdf"), &[]), None);
+        assert_eq!(routes.language_for(Some("/Other/lettre.pdf"), &labels), Some("fra"));
+        assert_eq!(routes.language_for(None, &[]), None);
+    }
+
+    #[test]
+    fn test_invalid_routes_are_rejected() {
+        assert_eq!(routes(serde_json::Value::Null), Ok(None));
+        assert_eq!(routes(serde_json::json!([])), Ok(None));
+        assert!(routes(serde_json::json!([{ "language": "deu" }])).is_err());
+        assert!(routes(serde_json::json!([{ "path_prefix": "/a", "label": "b", "language": "deu" }])).is_err());
+        assert!(routes(serde_json::json!([{ "path_prefix": "/", "language": "deu" }])).is_err());
+        assert!(routes(serde_json::json!([{ "path_prefix": "/a", "language": "deu; rm" }])).is_err());
+        assert!(routes(serde_json::json!({ "/a": "deu" })).is_err());
+    }
+
+    #[test]
+    fn test_languages_must_be_installed() {
+        let routes = routes(serde_json::json!([{ "path_prefix": "/a", "language": "deu+eng" }])).unwrap().unwrap();
+        assert!(routes.validate_installed(&["deu".to_string(), "eng".to_string()]).is_ok());
+        let error = routes.validate_installed(&["eng".to_string()]).unwrap_err();
+        assert!(error.contains("'deu'"), "{}", error);
+    }
+}
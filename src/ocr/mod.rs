@@ -1,19 +1,41 @@
 pub mod api;
+pub mod benchmark;
 pub mod enhanced;
 pub mod enhanced_processing;
 pub mod error;
 pub mod health;
+pub mod language_routing;
+pub mod orientation;
+pub mod preprocessing;
+pub mod process;
 pub mod queue;
+pub mod script_detection;
 pub mod tests;
+pub mod tiff_pages;
+pub mod word_boxes;
+pub mod work_dir;
 
 use anyhow::{anyhow, Result};
 use std::path::Path;
 use crate::ocr::error::OcrError;
 use crate::ocr::health::OcrHealthChecker;
+use crate::ocr::script_detection::ScriptDetection;
+use crate::ocr::word_boxes::OcrPageWords;
+use crate::ocr::work_dir::OcrWorkDir;
 
 #[cfg(feature = "ocr")]
 use tesseract::Tesseract;
 
+/// Resolution PDF pages are rendered at for word boxes
+#[cfg(feature = "ocr")]
+const WORD_BOX_PDF_DPI: u32 = 300;
+
+/// Resolution the first page of a PDF is rendered at for script detection
+const SCRIPT_DETECTION_PDF_DPI: u32 = 200;
+
+/// How long one script detection pass may take
+const SCRIPT_DETECTION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
 pub struct OcrService {
     health_checker: OcrHealthChecker,
 }
@@ -75,9 +97,9 @@ impl OcrService {
                 ));
             }
             
-            // Create temporary file for text extraction
-            let temp_dir = std::env::var("TEMP_DIR").unwrap_or_else(|_| "/tmp".to_string());
-            let temp_text_path = format!("{}/pdf_text_{}.txt", temp_dir, std::process::id());
+            // Intermediate files live in a directory of their own, removed when this returns
+            let work_dir = OcrWorkDir::in_temp_dir()?;
+            let temp_text_path = work_dir.file("pdf_text.txt");
             
             // Progressive extraction with fallback strategies
             // Strategy 1: pdftotext for existing text (fastest)
@@ -93,7 +115,6 @@ impl OcrService {
                 if let Ok(text) = tokio::fs::read_to_string(&temp_text_path).await {
                     let word_count = text.split_whitespace().count();
                     if word_count > 5 {
-                        let _ = tokio::fs::remove_file(&temp_text_path).await;
                         return Ok(text.trim().to_string());
                     }
                 }
@@ -122,8 +143,6 @@ impl OcrService {
                         
                     if !output.status.success() {
                         let stderr = String::from_utf8_lossy(&output.stderr);
-                        // Clean up temp file on error
-                        let _ = tokio::fs::remove_file(&temp_text_path).await;
                         
                         // Last resort: try direct text extraction
                         match self.extract_text_from_pdf_bytes(file_path).await {
@@ -146,9 +165,6 @@ impl OcrService {
             // Read the extracted text
             let text = tokio::fs::read_to_string(&temp_text_path).await?;
             
-            // Clean up temporary file
-            let _ = tokio::fs::remove_file(&temp_text_path).await;
-            
             Ok(text.trim().to_string())
         }
         
@@ -158,12 +174,136 @@ impl OcrService {
         }
     }
 
+    /// Recognizes an image or PDF and returns every word with its bounding box and confidence,
+    /// grouped by page. PDF pages are rendered at `WORD_BOX_PDF_DPI`, which the boxes refer to;
+    /// each frame of a multi-page TIFF is a page.
+    pub async fn extract_text_with_boxes(&self, file_path: &str, mime_type: &str, lang: &str) -> Result<Vec<OcrPageWords>> {
+        #[cfg(feature = "ocr")]
+        {
+            self.health_checker.validate_language_combination(lang)
+                .map_err(|e: OcrError| anyhow!(e))?;
+
+            let detected = crate::mime_detection::detect_mime_from_file(file_path, mime_type).await;
+            let mime_type = detected.mime_type.as_str();
+            let tiff_frames = if mime_type == "image/tiff" { Self::tiff_frame_count(file_path).await } else { 1 };
+            if mime_type == "application/pdf" {
+                let work_dir = OcrWorkDir::in_temp_dir()?;
+                let pdf_service = crate::services::pdf_service::PdfService::new(work_dir.path().to_path_buf());
+                let page_images = pdf_service.render_pages(Path::new(file_path), work_dir.path(), WORD_BOX_PDF_DPI).await?;
+                let mut pages = Vec::with_capacity(page_images.len());
+                for (index, page_image) in page_images.iter().enumerate() {
+                    let page_image = page_image.to_string_lossy().to_string();
+                    pages.push(Self::recognize_word_boxes(page_image, lang.to_string(), index as i32 + 1).await?);
+                }
+                Ok(pages)
+            } else if tiff_frames > 1 {
+                let work_dir = OcrWorkDir::in_temp_dir()?;
+                let mut pages = Vec::with_capacity(tiff_frames);
+                for index in 0..tiff_frames {
+                    let page_image = work_dir.path().join(format!("page-{}.png", index + 1));
+                    let (tiff_path, frame_path) = (Path::new(file_path).to_path_buf(), page_image.clone());
+                    tokio::task::spawn_blocking(move || crate::ocr::tiff_pages::write_frame_png(&tiff_path, index, &frame_path)).await??;
+                    let page_image = page_image.to_string_lossy().to_string();
+                    pages.push(Self::recognize_word_boxes(page_image, lang.to_string(), index as i32 + 1).await?);
+                }
+                Ok(pages)
+            } else if mime_type.starts_with("image/") || self.is_image_file(file_path) {
+                Ok(vec![Self::recognize_word_boxes(file_path.to_string(), lang.to_string(), 1).await?])
+            } else {
+                Err(anyhow!(OcrError::InvalidImageFormat {
+                    details: format!("Word boxes are not available for MIME type: {}", mime_type)
+                }))
+            }
+        }
+
+        #[cfg(not(feature = "ocr"))]
+        {
+            Err(anyhow!(OcrError::TesseractNotInstalled))
+        }
+    }
+
+    /// Number of frames in a TIFF; 1 when the frames cannot be read, so the file is
+    /// treated as a single image
+    #[cfg(feature = "ocr")]
+    async fn tiff_frame_count(file_path: &str) -> usize {
+        let tiff_path = Path::new(file_path).to_path_buf();
+        match tokio::task::spawn_blocking(move || crate::ocr::tiff_pages::count_frames(&tiff_path)).await {
+            Ok(Ok(count)) => count,
+            _ => 1,
+        }
+    }
+
+    /// Runs Tesseract on one page image and parses its TSV output
+    #[cfg(feature = "ocr")]
+    async fn recognize_word_boxes(image_path: String, lang: String, page: i32) -> Result<OcrPageWords> {
+        tokio::task::spawn_blocking(move || {
+            let mut tesseract = Tesseract::new(None, Some(&lang))
+                .map_err(|e| anyhow!(OcrError::InitializationFailed {
+                    details: e.to_string()
+                }))?
+                .set_image(&image_path)?
+                .recognize()?;
+            let tsv = tesseract.get_tsv_text(0)?;
+            Ok(crate::ocr::word_boxes::parse_tsv(&tsv, page))
+        })
+        .await?
+    }
+
+    /// Runs Tesseract's orientation and script detection on an image, or on the first
+    /// page of a PDF. Returns `None` when Tesseract finds too little text to name a script.
+    /// Needs the `osd` language data.
+    pub async fn detect_script(&self, file_path: &str, mime_type: &str) -> Result<Option<ScriptDetection>> {
+        let detected = crate::mime_detection::detect_mime_from_file(file_path, mime_type).await;
+        if detected.mime_type == "application/pdf" {
+            let work_dir = OcrWorkDir::in_temp_dir()?;
+            let pdf_service = crate::services::pdf_service::PdfService::new(work_dir.path().to_path_buf());
+            let page_image = pdf_service.render_first_page(Path::new(file_path), work_dir.path(), SCRIPT_DETECTION_PDF_DPI).await?;
+            Self::run_osd(&page_image).await
+        } else if detected.mime_type.starts_with("image/") || self.is_image_file(file_path) {
+            Self::run_osd(Path::new(file_path)).await
+        } else {
+            Err(anyhow!(OcrError::InvalidImageFormat {
+                details: format!("Script detection is not available for MIME type: {}", detected.mime_type)
+            }))
+        }
+    }
+
+    async fn run_osd(image_path: &Path) -> Result<Option<ScriptDetection>> {
+        let output = tokio::time::timeout(
+            SCRIPT_DETECTION_TIMEOUT,
+            tokio::process::Command::new("tesseract")
+                .arg(image_path)
+                .arg("stdout")
+                .args(["--psm", "0", "-l", "osd"])
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await
+        .map_err(|_| anyhow!("Script detection timed out after {}s", SCRIPT_DETECTION_TIMEOUT.as_secs()))?
+        .map_err(|_| anyhow!(OcrError::TesseractNotInstalled))?;
+
+        // Tesseract writes OSD results to stdout, and the reason it found none to stderr
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if let Some(detection) = crate::ocr::script_detection::parse_osd_output(&stdout) {
+            return Ok(Some(detection));
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if output.status.success() || stderr.contains("Too few characters") {
+            Ok(None)
+        } else {
+            Err(anyhow!("Script detection failed: {}", stderr.trim()))
+        }
+    }
+
     pub async fn extract_text(&self, file_path: &str, mime_type: &str) -> Result<String> {
         self.extract_text_with_lang(file_path, mime_type, "eng").await
     }
 
     pub async fn extract_text_with_lang(&self, file_path: &str, mime_type: &str, lang: &str) -> Result<String> {
-        match mime_type {
+        // Route on what the file actually contains rather than the declared type
+        let detected = crate::mime_detection::detect_mime_from_file(file_path, mime_type).await;
+        match detected.mime_type.as_str() {
             "application/pdf" => self.extract_text_from_pdf(file_path).await,
             "image/png" | "image/jpeg" | "image/jpg" | "image/tiff" | "image/bmp" => {
                 self.extract_text_from_image_with_lang(file_path, lang).await
@@ -177,7 +317,7 @@ impl OcrService {
                     self.extract_text_from_image_with_lang(file_path, lang).await
                 } else {
                     Err(anyhow!(OcrError::InvalidImageFormat { 
-                        details: format!("Unsupported MIME type: {}", mime_type) 
+                        details: format!("Unsupported MIME type: {}", detected.mime_type) 
                     }))
                 }
             }
@@ -223,7 +363,13 @@ impl OcrService {
         }
     }
 
+    /// Whether the file is an image, judged by its content when it can be read
+    /// and by its extension otherwise
     pub fn is_image_file(&self, file_path: &str) -> bool {
+        if let Ok(signature) = crate::mime_detection::sniff_file_signature(file_path) {
+            return signature.is_some_and(|mime_type| mime_type.starts_with("image/"));
+        }
+
         if let Some(extension) = Path::new(file_path)
             .extension()
             .and_then(|ext| ext.to_str())
@@ -0,0 +1,173 @@
+/*!
+ * Orientation Detection
+ *
+ * Tesseract's orientation and script detection (`--psm 0`, the `osd` model) reports how
+ * far a scanned page is turned and how sure it is. Confident detections are corrected
+ * before OCR; unsure ones are left alone and flagged so the user can rotate the document
+ * by hand.
+ */
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::ocr::process::{output_before, OcrDeadline};
+
+/// Orientation confidence from which a detected rotation is applied, the default of
+/// ocrmypdf's `--rotate-pages-threshold`. Like the script confidence, it is a relative
+/// score rather than a percentage.
+pub const MIN_ORIENTATION_CONFIDENCE: f32 = 14.0;
+
+/// How long Tesseract may spend detecting the orientation of one image
+const ORIENTATION_DETECTION_TIMEOUT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OrientationDetection {
+    /// Clockwise rotation that turns the page upright: 0, 90, 180 or 270
+    pub rotate_degrees: u32,
+    pub confidence: f32,
+}
+
+impl OrientationDetection {
+    pub fn is_confident(&self) -> bool {
+        self.confidence >= MIN_ORIENTATION_CONFIDENCE
+    }
+}
+
+/// Parses the output of `tesseract <image> stdout --psm 0`. Returns `None` when it reports
+/// no orientation, e.g. because the page had too little text.
+pub fn parse_osd_orientation(output: &str) -> Option<OrientationDetection> {
+    let mut rotate_degrees = None;
+    let mut confidence = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "Rotate" => rotate_degrees = value.parse::<u32>().ok().filter(|degrees| degrees % 90 == 0),
+            "Orientation confidence" => confidence = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(OrientationDetection {
+        rotate_degrees: rotate_degrees? % 360,
+        confidence: confidence.unwrap_or(0.0),
+    })
+}
+
+/// Runs Tesseract's orientation detection on an image. Needs the `osd` language data.
+pub async fn detect_orientation(image_path: &Path) -> Result<Option<OrientationDetection>> {
+    let output = output_before(
+        tokio::process::Command::new("tesseract")
+            .arg(image_path)
+            .arg("stdout")
+            .args(["--psm", "0", "-l", "osd"]),
+        &OcrDeadline::after(ORIENTATION_DETECTION_TIMEOUT),
+    ).await?;
+
+    if let Some(detection) = parse_osd_orientation(&String::from_utf8_lossy(&output.stdout)) {
+        return Ok(Some(detection));
+    }
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if output.status.success() || stderr.contains("Too few characters") {
+        Ok(None)
+    } else {
+        Err(anyhow!("Orientation detection failed: {}", stderr.trim()))
+    }
+}
+
+/// Normalizes a clockwise rotation in degrees to 0, 90, 180 or 270; `None` unless it is a
+/// multiple of 90
+pub fn normalize_rotation(degrees: i32) -> Option<i32> {
+    (degrees % 90 == 0).then(|| degrees.rem_euclid(360))
+}
+
+/// Turns an image clockwise by a multiple of 90 degrees
+#[cfg(feature = "ocr")]
+pub fn rotate_image(image: image::DynamicImage, degrees: u32) -> image::DynamicImage {
+    match degrees % 360 {
+        90 => image.rotate90(),
+        180 => image.rotate180(),
+        270 => image.rotate270(),
+        _ => image,
+    }
+}
+
+/// Whether stored images of `mime_type` can be rotated and written back in their format
+#[cfg(feature = "ocr")]
+pub fn can_rotate_image_data(mime_type: &str) -> bool {
+    image::ImageFormat::from_mime_type(mime_type).is_some_and(|format| format.reading_enabled() && format.writing_enabled())
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn can_rotate_image_data(_mime_type: &str) -> bool {
+    false
+}
+
+/// Turns an encoded image clockwise by a multiple of 90 degrees and encodes it again in
+/// the same format
+#[cfg(feature = "ocr")]
+pub fn rotate_image_data(data: &[u8], mime_type: &str, degrees: u32) -> Result<Vec<u8>> {
+    let format = image::ImageFormat::from_mime_type(mime_type)
+        .ok_or_else(|| anyhow!("Unsupported image type {}", mime_type))?;
+    let rotated = rotate_image(image::load_from_memory_with_format(data, format)?, degrees);
+
+    let mut output = std::io::Cursor::new(Vec::new());
+    rotated.write_to(&mut output, format)?;
+    Ok(output.into_inner())
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn rotate_image_data(_data: &[u8], _mime_type: &str, _degrees: u32) -> Result<Vec<u8>> {
+    anyhow::bail!("Image rotation requires OCR feature")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_osd_orientation() {
+        let output = "Page number: 0\n\
+                      Orientation in degrees: 270\n\
+                      Rotate: 90\n\
+                      Orientation confidence: 21.27\n\
+                      Script: Latin\n\
+                      Script confidence: 4.14\n";
+        let detection = parse_osd_orientation(output).unwrap();
+        assert_eq!(detection.rotate_degrees, 90);
+        assert!(detection.is_confident());
+
+        let unsure = parse_osd_orientation("Rotate: 180\nOrientation confidence: 0.74\n").unwrap();
+        assert_eq!(unsure.rotate_degrees, 180);
+        assert!(!unsure.is_confident());
+
+        assert_eq!(parse_osd_orientation("Too few characters. Skipping this page\n"), None);
+    }
+
+    #[test]
+    fn test_normalize_rotation() {
+        assert_eq!(normalize_rotation(90), Some(90));
+        assert_eq!(normalize_rotation(-90), Some(270));
+        assert_eq!(normalize_rotation(450), Some(90));
+        assert_eq!(normalize_rotation(0), Some(0));
+        assert_eq!(normalize_rotation(45), None);
+    }
+
+    #[cfg(feature = "ocr")]
+    #[test]
+    fn test_rotate_image_data() {
+        let mut encoded = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::new_luma8(40, 10).write_to(&mut encoded, image::ImageFormat::Png).unwrap();
+
+        let rotated = rotate_image_data(encoded.get_ref(), "image/png", 90).unwrap();
+        let rotated = image::load_from_memory(&rotated).unwrap();
+        assert_eq!((rotated.width(), rotated.height()), (10, 40));
+
+        assert!(can_rotate_image_data("image/jpeg"));
+        assert!(!can_rotate_image_data("application/pdf"));
+    }
+}
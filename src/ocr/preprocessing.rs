@@ -0,0 +1,376 @@
+//! Configurable image preprocessing: an ordered list of steps the enhanced OCR service runs
+//! on an image before recognition, instead of choosing enhancements from the image quality.
+//!
+//! A pipeline is written either as the name of a preset (`"photo"`) or as a list of steps,
+//! e.g. `[{"step": "deskew", "max_angle": 5}, {"step": "threshold", "threshold": 140}]`.
+
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+/// One step of a preprocessing pipeline. Steps run in the order given.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(tag = "step", rename_all = "snake_case", deny_unknown_fields)]
+pub enum PreprocessingStep {
+    /// Rotate pages that are more than twice as wide as they are tall by 90 degrees
+    Orientation,
+    /// Scale large images down and small images up to a size Tesseract reads well
+    Resize,
+    /// Straighten text lines tilted by up to `max_angle` degrees
+    Deskew {
+        #[serde(default = "default_deskew_max_angle")]
+        max_angle: f32,
+    },
+    /// Add `boost` to every pixel, then multiply it by `contrast`
+    Brightness {
+        #[serde(default)]
+        boost: f32,
+        #[serde(default = "default_contrast")]
+        contrast: f32,
+    },
+    /// Median filter, plus a Gaussian blur from level 2; 1 is light, 3 is heavy
+    Denoise {
+        #[serde(default = "default_denoise_level")]
+        level: i32,
+    },
+    /// Adaptive threshold comparing each pixel to the mean of a `window`-pixel square around it
+    Binarize {
+        #[serde(default = "default_binarize_window")]
+        window: u32,
+    },
+    /// Global threshold: pixels darker than `threshold` become black, all others white
+    Threshold { threshold: u8 },
+    /// Spread the grey levels evenly over the full range (histogram equalization)
+    Equalize,
+    /// Sharpen edges with an unsharp-mask kernel
+    Sharpen,
+    /// Morphological opening then closing, removing specks and filling small gaps in strokes
+    Morphology,
+}
+
+fn default_deskew_max_angle() -> f32 {
+    5.0
+}
+
+fn default_contrast() -> f32 {
+    1.0
+}
+
+fn default_denoise_level() -> i32 {
+    2
+}
+
+fn default_binarize_window() -> u32 {
+    25
+}
+
+impl PreprocessingStep {
+    pub const MAX_DESKEW_ANGLE: f32 = 45.0;
+    pub const MAX_BRIGHTNESS_BOOST: f32 = 255.0;
+    pub const MAX_CONTRAST: f32 = 5.0;
+    pub const MIN_BINARIZE_WINDOW: u32 = 3;
+    pub const MAX_BINARIZE_WINDOW: u32 = 255;
+
+    /// Snake-case name of the step, as written in a pipeline
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Orientation => "orientation",
+            Self::Resize => "resize",
+            Self::Deskew { .. } => "deskew",
+            Self::Brightness { .. } => "brightness",
+            Self::Denoise { .. } => "denoise",
+            Self::Binarize { .. } => "binarize",
+            Self::Threshold { .. } => "threshold",
+            Self::Equalize => "equalize",
+            Self::Sharpen => "sharpen",
+            Self::Morphology => "morphology",
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Self::Deskew { max_angle } => {
+                if !(max_angle > 0.0 && max_angle <= Self::MAX_DESKEW_ANGLE) {
+                    return Err(format!("deskew max_angle must be greater than 0 and at most {}, got {}", Self::MAX_DESKEW_ANGLE, max_angle));
+                }
+            }
+            Self::Brightness { boost, contrast } => {
+                if !(-Self::MAX_BRIGHTNESS_BOOST..=Self::MAX_BRIGHTNESS_BOOST).contains(&boost) {
+                    return Err(format!("brightness boost must be between -{0} and {0}, got {1}", Self::MAX_BRIGHTNESS_BOOST, boost));
+                }
+                if !(contrast > 0.0 && contrast <= Self::MAX_CONTRAST) {
+                    return Err(format!("brightness contrast must be greater than 0 and at most {}, got {}", Self::MAX_CONTRAST, contrast));
+                }
+            }
+            Self::Denoise { level } => {
+                if !(1..=3).contains(&level) {
+                    return Err(format!("denoise level must be between 1 and 3, got {}", level));
+                }
+            }
+            Self::Binarize { window } => {
+                if !(Self::MIN_BINARIZE_WINDOW..=Self::MAX_BINARIZE_WINDOW).contains(&window) || window % 2 == 0 {
+                    return Err(format!(
+                        "binarize window must be an odd number between {} and {}, got {}",
+                        Self::MIN_BINARIZE_WINDOW, Self::MAX_BINARIZE_WINDOW, window
+                    ));
+                }
+            }
+            Self::Threshold { threshold } => {
+                if threshold == 0 || threshold == u8::MAX {
+                    return Err(format!("threshold must be between 1 and 254, got {}", threshold));
+                }
+            }
+            Self::Orientation | Self::Resize | Self::Equalize | Self::Sharpen | Self::Morphology => {}
+        }
+        Ok(())
+    }
+}
+
+/// Named pipelines for common kinds of input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PreprocessingPreset {
+    /// Enhancements chosen from the image quality and the OCR settings toggles
+    /// (contrast, noise removal, orientation); what runs when no pipeline is set
+    Default,
+    /// Phone camera pictures: uneven light, tilt and blur
+    Photo,
+    /// Flatbed scans of printed pages that only need straightening
+    CleanScan,
+    /// Low-contrast pages with faint or washed-out text
+    FadedDocument,
+}
+
+impl PreprocessingPreset {
+    pub const ALL: [Self; 4] = [Self::Default, Self::Photo, Self::CleanScan, Self::FadedDocument];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::Photo => "photo",
+            Self::CleanScan => "clean_scan",
+            Self::FadedDocument => "faded_document",
+        }
+    }
+
+    /// The steps of the preset, or `None` for the quality-driven default
+    pub fn steps(&self) -> Option<Vec<PreprocessingStep>> {
+        use PreprocessingStep::*;
+        match self {
+            Self::Default => None,
+            Self::Photo => Some(vec![
+                Orientation,
+                Resize,
+                Deskew { max_angle: 10.0 },
+                Denoise { level: 2 },
+                Equalize,
+                Sharpen,
+                Binarize { window: 31 },
+            ]),
+            Self::CleanScan => Some(vec![
+                Resize,
+                Deskew { max_angle: 3.0 },
+            ]),
+            Self::FadedDocument => Some(vec![
+                Resize,
+                Deskew { max_angle: 5.0 },
+                Denoise { level: 1 },
+                Equalize,
+                Brightness { boost: 0.0, contrast: 1.5 },
+                Binarize { window: 25 },
+            ]),
+        }
+    }
+}
+
+/// Preprocessing pipeline of the user's settings or of an OCR override: a preset name or
+/// a list of steps
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum PreprocessingConfig {
+    Preset(PreprocessingPreset),
+    Steps(Vec<PreprocessingStep>),
+}
+
+impl PreprocessingConfig {
+    /// Most steps in a pipeline
+    pub const MAX_STEPS: usize = 20;
+
+    /// Parses a preset name or a list of steps, rejecting unknown presets and steps
+    pub fn from_value(value: &serde_json::Value) -> Result<Self, String> {
+        let config = match value {
+            serde_json::Value::String(name) => {
+                let preset = PreprocessingPreset::ALL
+                    .into_iter()
+                    .find(|preset| preset.name() == name)
+                    .ok_or_else(|| format!(
+                        "unknown preprocessing preset '{}', expected one of: {}",
+                        name,
+                        PreprocessingPreset::ALL.map(|preset| preset.name()).join(", ")
+                    ))?;
+                Self::Preset(preset)
+            }
+            serde_json::Value::Array(steps) => {
+                let steps = steps
+                    .iter()
+                    .enumerate()
+                    .map(|(i, step)| parse_step(step).map_err(|e| format!("preprocessing step {}: {}", i + 1, e)))
+                    .collect::<Result<Vec<PreprocessingStep>, String>>()?;
+                Self::Steps(steps)
+            }
+            _ => return Err("preprocessing must be a preset name or a list of steps".to_string()),
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Self::Steps(steps) = self {
+            if steps.is_empty() {
+                return Err("preprocessing needs at least one step; use the \"default\" preset for the standard enhancements".to_string());
+            }
+            if steps.len() > Self::MAX_STEPS {
+                return Err(format!("preprocessing has at most {} steps, got {}", Self::MAX_STEPS, steps.len()));
+            }
+            for (i, step) in steps.iter().enumerate() {
+                step.validate().map_err(|e| format!("preprocessing step {}: {}", i + 1, e))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The steps to run, or `None` for the quality-driven default enhancements
+    pub fn steps(&self) -> Option<Vec<PreprocessingStep>> {
+        match self {
+            Self::Preset(preset) => preset.steps(),
+            Self::Steps(steps) => Some(steps.clone()),
+        }
+    }
+}
+
+fn parse_step(value: &serde_json::Value) -> Result<PreprocessingStep, String> {
+    let step: PreprocessingStep = serde_json::from_value(value.clone()).map_err(|e| e.to_string())?;
+
+    // serde lets steps without parameters through with any fields, so check those against
+    // the fields the step has
+    let known = serde_json::to_value(&step).map_err(|e| e.to_string())?;
+    if let (Some(given), Some(known)) = (value.as_object(), known.as_object()) {
+        if let Some(field) = given.keys().find(|field| !known.contains_key(*field)) {
+            return Err(format!("unknown field `{}` for step `{}`", field, step.name()));
+        }
+    }
+    Ok(step)
+}
+
+impl<'de> Deserialize<'de> for PreprocessingConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Self::from_value(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Angle in degrees, within `max_angle` either way, by which the text lines formed by the
+/// dark pixels at `points` are tilted clockwise. Lines are found where projecting the points
+/// onto rows turned by the angle gives the sharpest peaks.
+pub fn estimate_skew_angle(points: &[(f32, f32)], max_angle: f32) -> f32 {
+    const STEP_DEGREES: f32 = 0.25;
+
+    if points.is_empty() {
+        return 0.0;
+    }
+
+    let steps = (max_angle / STEP_DEGREES).floor() as i32;
+    let mut best_angle = 0.0;
+    let mut best_score = f64::MIN;
+    let mut rows = std::collections::HashMap::new();
+
+    // Try the angles closest to level first so ties keep the image as it is
+    let angles = std::iter::once(0).chain((1..=steps).flat_map(|i| [i, -i]));
+    for step in angles {
+        let angle = step as f32 * STEP_DEGREES;
+        let (sin, cos) = angle.to_radians().sin_cos();
+
+        rows.clear();
+        for &(x, y) in points {
+            *rows.entry((y * cos - x * sin).round() as i64).or_insert(0u64) += 1;
+        }
+        let score: f64 = rows.values().map(|&count| (count * count) as f64).sum();
+
+        if score > best_score {
+            best_score = score;
+            best_angle = angle;
+        }
+    }
+
+    best_angle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parses_presets_and_steps() {
+        let preset = PreprocessingConfig::from_value(&json!("clean_scan")).unwrap();
+        assert_eq!(preset, PreprocessingConfig::Preset(PreprocessingPreset::CleanScan));
+        assert!(PreprocessingConfig::Preset(PreprocessingPreset::Default).steps().is_none());
+
+        let steps = PreprocessingConfig::from_value(&json!([
+            {"step": "deskew"},
+            {"step": "binarize", "window": 15},
+            {"step": "threshold", "threshold": 140}
+        ])).unwrap();
+        assert_eq!(steps.steps().unwrap(), vec![
+            PreprocessingStep::Deskew { max_angle: 5.0 },
+            PreprocessingStep::Binarize { window: 15 },
+            PreprocessingStep::Threshold { threshold: 140 },
+        ]);
+
+        // Round-trips through the settings column
+        let stored = serde_json::to_value(&steps).unwrap();
+        assert_eq!(serde_json::from_value::<PreprocessingConfig>(stored).unwrap(), steps);
+        assert_eq!(serde_json::to_value(&preset).unwrap(), json!("clean_scan"));
+    }
+
+    #[test]
+    fn test_rejects_unknown_names_and_invalid_parameters() {
+        for invalid in [
+            json!("scan"),
+            json!([{"step": "blur"}]),
+            json!([{"step": "sharpen", "amount": 2}]),
+            json!([{"step": "binarize", "window": 16}]),
+            json!([{"step": "denoise", "level": 4}]),
+            json!([{"step": "threshold"}]),
+            json!([{"step": "deskew", "max_angle": 90}]),
+            json!([]),
+            json!({"step": "resize"}),
+        ] {
+            assert!(PreprocessingConfig::from_value(&invalid).is_err(), "accepted {}", invalid);
+        }
+
+        let error = PreprocessingConfig::from_value(&json!([{"step": "resize"}, {"step": "blur"}])).unwrap_err();
+        assert!(error.starts_with("preprocessing step 2:"), "{}", error);
+    }
+
+    #[test]
+    fn test_presets_are_valid() {
+        for preset in PreprocessingPreset::ALL {
+            PreprocessingConfig::Preset(preset).validate().unwrap();
+            if let Some(steps) = preset.steps() {
+                PreprocessingConfig::Steps(steps).validate().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_estimate_skew_angle_finds_tilted_lines() {
+        // Three text lines sloping down to the right by 2 degrees
+        let slope = 2.0f32.to_radians().tan();
+        let points: Vec<(f32, f32)> = (0..3)
+            .flat_map(|line| (0..400).map(move |x| (x as f32, 50.0 + line as f32 * 40.0 + x as f32 * slope)))
+            .collect();
+
+        assert!((estimate_skew_angle(&points, 5.0) - 2.0).abs() < 0.3);
+        assert_eq!(estimate_skew_angle(&[], 5.0), 0.0);
+    }
+}
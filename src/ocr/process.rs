@@ -0,0 +1,161 @@
+//! Running the external OCR tools (`ocrmypdf`, `pdftotext`) under the OCR timeout.
+//!
+//! A tool that outlives its deadline is killed together with everything it started:
+//! `ocrmypdf` hands the actual work to `tesseract` and `ghostscript` subprocesses, so
+//! each tool runs in its own process group and the whole group is killed on timeout.
+
+use std::process::{Output, Stdio};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use tracing::warn;
+
+use crate::models::Settings;
+use crate::ocr::error::OcrError;
+
+/// The time left for the tools run on one document. Every invocation gets what remains of
+/// the budget, so a document falling back through several strategies still gives up once
+/// `ocr_timeout_seconds` have passed in total.
+#[derive(Debug, Clone, Copy)]
+pub struct OcrDeadline {
+    start: Instant,
+    limit: Option<Duration>,
+}
+
+impl OcrDeadline {
+    /// A deadline `limit` from now
+    pub fn after(limit: Duration) -> Self {
+        Self { start: Instant::now(), limit: Some(limit) }
+    }
+
+    /// A deadline that never passes
+    pub fn none() -> Self {
+        Self { start: Instant::now(), limit: None }
+    }
+
+    /// The effective `ocr_timeout_seconds`, counted from now; 0 or less means no limit
+    pub fn from_settings(settings: &Settings) -> Self {
+        match settings.ocr_timeout_seconds {
+            seconds if seconds > 0 => Self::after(Duration::from_secs(seconds as u64)),
+            _ => Self::none(),
+        }
+    }
+
+    /// Time left before the deadline, or `None` without a limit
+    pub fn remaining(&self) -> Option<Duration> {
+        self.limit.map(|limit| limit.saturating_sub(self.start.elapsed()))
+    }
+
+    pub fn timeout_error(&self) -> OcrError {
+        OcrError::OcrTimeout { seconds: self.limit.unwrap_or_default().as_secs() }
+    }
+}
+
+/// Whether `error` is an OCR timeout, as opposed to the tool failing on the file
+pub fn is_timeout(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<OcrError>(), Some(OcrError::OcrTimeout { .. }))
+}
+
+/// Runs `command` and collects its output. If it is still running at the deadline, the
+/// process and its children are killed and reaped, and `OcrError::OcrTimeout` is returned.
+pub async fn output_before(command: &mut Command, deadline: &OcrDeadline) -> anyhow::Result<Output> {
+    let program = command.as_std().get_program().to_string_lossy().to_string();
+    if deadline.remaining().is_some_and(|remaining| remaining.is_zero()) {
+        return Err(deadline.timeout_error().into());
+    }
+
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(unix)]
+    command.process_group(0);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", program, e))?;
+    let pid = child.id();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let run = async {
+        let (status, stdout, stderr) = tokio::join!(child.wait(), read_all(stdout), read_all(stderr));
+        Ok::<_, std::io::Error>(Output { status: status?, stdout: stdout?, stderr: stderr? })
+    };
+    let result = match deadline.remaining() {
+        Some(remaining) => tokio::time::timeout(remaining, run).await.ok(),
+        None => Some(run.await),
+    };
+
+    match result {
+        Some(output) => output.map_err(|e| anyhow::anyhow!("Failed to run {}: {}", program, e)),
+        None => {
+            warn!("{} timed out after {}s, killing it", program, deadline.start.elapsed().as_secs());
+            #[cfg(unix)]
+            if let Some(pid) = pid {
+                // SAFETY: `killpg` only sends a signal; the group was created for this child
+                unsafe { libc::killpg(pid as libc::pid_t, libc::SIGKILL) };
+            }
+            #[cfg(not(unix))]
+            let _ = pid;
+            if let Err(e) = child.kill().await {
+                warn!("Failed to kill {} after its timeout: {}", program, e);
+            }
+            Err(deadline.timeout_error().into())
+        }
+    }
+}
+
+async fn read_all(pipe: Option<impl AsyncRead + Unpin>) -> std::io::Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    if let Some(mut pipe) = pipe {
+        pipe.read_to_end(&mut buffer).await?;
+    }
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_output_before_returns_finished_output() {
+        let output = output_before(Command::new("echo").arg("hello"), &OcrDeadline::after(Duration::from_secs(10)))
+            .await
+            .unwrap();
+        assert!(output.status.success());
+        assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "hello");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_output_before_kills_a_hung_tool_and_its_children() {
+        let dir = tempfile::tempdir().unwrap();
+        let pid_file = dir.path().join("child.pid");
+        // The shell starts a long sleep in the background, like ocrmypdf starting tesseract
+        let script = format!("sleep 30 & echo $! > {}; wait", pid_file.display());
+
+        let started = Instant::now();
+        let error = output_before(Command::new("sh").arg("-c").arg(&script), &OcrDeadline::after(Duration::from_secs(1)))
+            .await
+            .unwrap_err();
+        assert!(is_timeout(&error), "unexpected error: {}", error);
+        assert!(error.to_string().contains("timeout"));
+        assert!(started.elapsed() < Duration::from_secs(5), "the hung tool was waited for");
+
+        let child_pid = std::fs::read_to_string(&pid_file).unwrap().trim().to_string();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        // A killed process may linger as a zombie until it is reaped, which is fine
+        let state = std::fs::read_to_string(format!("/proc/{}/stat", child_pid)).ok()
+            .and_then(|stat| stat.rsplit(") ").next().and_then(|rest| rest.chars().next()));
+        assert!(matches!(state, None | Some('Z')), "the tool's child process outlived the timeout");
+    }
+
+    #[tokio::test]
+    async fn test_passed_deadline_does_not_start_the_tool() {
+        let deadline = OcrDeadline::after(Duration::ZERO);
+        let error = output_before(&mut Command::new("true"), &deadline).await.unwrap_err();
+        assert!(is_timeout(&error));
+    }
+}
@@ -2,14 +2,135 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, Row, Column};
-use std::sync::Arc;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use tokio::sync::Semaphore;
 use tokio::time::{sleep, Duration};
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
-use crate::{db::Database, ocr::enhanced::EnhancedOcrService, db_guardrails_simple::DocumentTransactionManager, monitoring::request_throttler::RequestThrottler};
+use crate::{db::{Database, DatabasePoolHealth}, ocr::{enhanced::EnhancedOcrService, work_dir::{self, OcrWorkDir}}, db_guardrails_simple::DocumentTransactionManager, monitoring::request_throttler::RequestThrottler};
+use crate::models::DocumentEventType;
+use crate::services::ocr_progress_tracker::{OcrEventKind, OcrProgressTracker};
+
+/// Escalated retries a document gets when OCR completes below the user's confidence threshold
+const MAX_LOW_CONFIDENCE_RETRIES: i32 = 1;
+
+/// Minutes a job may sit in `processing` before its worker is presumed dead and it is requeued
+pub const STALE_PROCESSING_MINUTES: i32 = 10;
+
+/// Share of the database pool OCR workers may hold when `OCR_DB_POOL_FRACTION` is not set
+pub const DEFAULT_OCR_DB_POOL_FRACTION: f64 = 0.5;
+
+/// Jobs the worker claims per query when `OCR_CLAIM_BATCH_SIZE` is not set
+pub const DEFAULT_OCR_CLAIM_BATCH_SIZE: usize = 1;
+
+/// First and longest wait before claiming another job while the pool is saturated
+const POOL_SATURATED_MIN_BACKOFF: Duration = Duration::from_millis(500);
+const POOL_SATURATED_MAX_BACKOFF: Duration = Duration::from_secs(8);
+
+/// Number of OCR jobs that may run at once: `max_concurrent_jobs`, lowered so
+/// the workers never hold more than `pool_fraction` of the pool's connections.
+/// Always at least one so the queue keeps moving on tiny pools.
+pub fn ocr_worker_limit(max_concurrent_jobs: usize, pool_max_connections: u32, pool_fraction: f64) -> usize {
+    let pool_share = (pool_max_connections as f64 * pool_fraction.clamp(0.0, 1.0)).floor() as usize;
+    max_concurrent_jobs.min(pool_share).max(1)
+}
+
+/// Whether the pool has opened every connection it may and none is idle
+pub fn pool_is_saturated(health: &DatabasePoolHealth, pool_max_connections: u32) -> bool {
+    health.size >= pool_max_connections && health.num_idle == 0
+}
+
+/// Upper bounds (inclusive) of the OCR processing time histogram buckets
+pub const PROCESSING_TIME_BUCKETS_MS: [u64; 10] = [250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 120_000, 300_000];
+
+/// Processing times of the OCR jobs this worker completed. Completed queue rows are deleted,
+/// so the times are counted as jobs finish rather than read back from the queue; the counts
+/// start over when the process restarts, which Prometheus treats as a counter reset.
+#[derive(Debug, Default)]
+pub struct ProcessingTimeHistogram {
+    /// Cumulative counts, one per entry in `PROCESSING_TIME_BUCKETS_MS`
+    bucket_counts: [AtomicU64; PROCESSING_TIME_BUCKETS_MS.len()],
+    sum_ms: AtomicU64,
+    count: AtomicU64,
+}
+
+/// Point-in-time copy of a `ProcessingTimeHistogram`
+#[derive(Debug, Clone, Default)]
+pub struct ProcessingTimeSnapshot {
+    /// Cumulative counts, one per entry in `PROCESSING_TIME_BUCKETS_MS`
+    pub bucket_counts: Vec<u64>,
+    pub sum_ms: u64,
+    pub count: u64,
+}
+
+impl ProcessingTimeHistogram {
+    pub fn record(&self, processing_time_ms: u64) {
+        for (bound, bucket) in PROCESSING_TIME_BUCKETS_MS.iter().zip(&self.bucket_counts) {
+            if processing_time_ms <= *bound {
+                bucket.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.sum_ms.fetch_add(processing_time_ms, Ordering::Relaxed);
+        self.count.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> ProcessingTimeSnapshot {
+        ProcessingTimeSnapshot {
+            bucket_counts: self.bucket_counts.iter().map(|bucket| bucket.load(Ordering::Relaxed)).collect(),
+            sum_ms: self.sum_ms.load(Ordering::Relaxed),
+            count: self.count.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Counts a running OCR job for as long as it is alive, including when the job panics
+struct ActiveWorkerGuard(Arc<AtomicUsize>);
+
+impl ActiveWorkerGuard {
+    fn new(active_workers: &Arc<AtomicUsize>) -> Self {
+        active_workers.fetch_add(1, Ordering::SeqCst);
+        Self(active_workers.clone())
+    }
+}
+
+impl Drop for ActiveWorkerGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Documents this process is running OCR on. Across processes, the unique index on
+/// pending and processing jobs plus `SKIP LOCKED` claiming keep a document to one job;
+/// this set also keeps two tasks of the same process off one document, e.g. when a
+/// job still running here was reset by stale-job recovery and claimed again.
+#[derive(Clone, Default)]
+struct InFlightDocuments(Arc<Mutex<HashSet<Uuid>>>);
+
+impl InFlightDocuments {
+    /// Marks the document as in flight until the guard is dropped; `None` if it already is
+    fn claim(&self, document_id: Uuid) -> Option<InFlightGuard> {
+        let mut documents = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        documents.insert(document_id).then(|| InFlightGuard { documents: self.clone(), document_id })
+    }
+
+    fn snapshot(&self) -> Vec<Uuid> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).iter().copied().collect()
+    }
+}
+
+struct InFlightGuard {
+    documents: InFlightDocuments,
+    document_id: Uuid,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.documents.0.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.document_id);
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct OcrQueueItem {
@@ -26,6 +147,25 @@ pub struct OcrQueueItem {
     pub worker_id: Option<String>,
     pub processing_time_ms: Option<i32>,
     pub file_size: Option<i64>,
+    pub ocr_overrides: Option<serde_json::Value>,
+    /// `ocr_overrides` of the source the document was synced from, if any
+    #[sqlx(default)]
+    pub source_ocr_overrides: Option<serde_json::Value>,
+    /// `ocr_language_routes` of the source the document was synced from, if any
+    #[sqlx(default)]
+    pub source_language_routes: Option<serde_json::Value>,
+    /// Path of the document on its source, which language routes match against
+    #[sqlx(default)]
+    pub source_path: Option<String>,
+    /// Names of the document's labels, which language routes match against
+    #[sqlx(default)]
+    pub labels: Vec<String>,
+    /// The batch job that queued this item, which is told how it ended
+    #[sqlx(default)]
+    pub batch_job_id: Option<Uuid>,
+    /// Whether the job may replace OCR text that was corrected by hand
+    #[sqlx(default)]
+    pub overwrite_manual_edits: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,15 +178,119 @@ pub struct QueueStats {
     pub oldest_pending_minutes: Option<f64>,
 }
 
+/// Queue depth and estimated wait, as sent in OCR progress heartbeats
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OcrQueueDepth {
+    /// Jobs of all users that are pending or processing
+    pub queue_depth: i64,
+    pub user_pending: i64,
+    pub user_processing: i64,
+    /// Time to work through the whole queue at the recent average job duration
+    pub estimated_seconds_remaining: Option<u64>,
+}
+
+/// A queue entry with the document and owner it belongs to, for operators viewing the whole queue
+#[derive(Debug, Clone, Serialize, FromRow, utoipa::ToSchema)]
+pub struct AdminQueueItem {
+    pub id: Uuid,
+    pub document_id: Uuid,
+    pub filename: String,
+    pub user_id: Uuid,
+    pub username: String,
+    pub status: String,
+    pub priority: i32,
+    pub attempts: i32,
+    pub max_attempts: i32,
+    pub worker_id: Option<String>,
+    pub error_message: Option<String>,
+    pub file_size: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the job used up its attempts, for jobs in the dead-letter queue
+    pub dead_lettered_at: Option<DateTime<Utc>>,
+    /// Seconds since the job was enqueued
+    pub age_seconds: i64,
+}
+
+/// Outcome of requeueing a job from the dead-letter queue
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeadLetterRequeue {
+    Requeued,
+    /// The job is not in the dead-letter queue; carries its status
+    NotDeadLettered(String),
+    /// The document already has another job pending or processing
+    AlreadyQueued,
+}
+
+/// Which queue entries an admin listing shows; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct AdminQueueFilter {
+    pub status: Option<String>,
+    pub user_id: Option<Uuid>,
+}
+
+/// Which failed documents a bulk reprocess picks up
+#[derive(Debug, Clone, Default)]
+pub struct FailedOcrSelection {
+    /// Only this user's documents; `None` selects every user's
+    pub user_id: Option<Uuid>,
+    pub failure_reason: Option<String>,
+    /// Documents created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Documents created before this time
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+/// Which of a user's documents a bulk OCR reprocess picks up; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct OcrReprocessSelection {
+    pub user_id: Uuid,
+    pub document_ids: Option<Vec<Uuid>>,
+    pub mime_types: Option<Vec<String>>,
+    pub source_id: Option<Uuid>,
+    /// Documents created at or after this time
+    pub created_after: Option<DateTime<Utc>>,
+    /// Documents created before this time
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+/// Most OCR jobs a batch job keeps pending or processing at once. The rest of its documents
+/// wait in the batch job, so uploads and syncs are not stuck behind a whole library.
+pub const BATCH_JOB_WINDOW: i64 = 100;
+
+/// Turns enqueueing a document that already has a pending or processing job into a
+/// priority bump of that job, so a document is never queued twice at once
+const ON_ACTIVE_JOB_CONFLICT: &str = r#"
+    ON CONFLICT (document_id) WHERE status IN ('pending', 'processing')
+    DO UPDATE SET priority = GREATEST(ocr_queue.priority, EXCLUDED.priority)
+"#;
+
+/// Matches the documents of an `OcrReprocessSelection` bound as $1-$6
+const REPROCESS_SELECTION_FILTER: &str = r#"
+    d.user_id = $1
+    AND d.deleted_at IS NULL
+    AND ($2::uuid[] IS NULL OR d.id = ANY($2))
+    AND ($3::text[] IS NULL OR d.mime_type = ANY($3))
+    AND ($4::uuid IS NULL OR d.source_id = $4)
+    AND ($5::timestamptz IS NULL OR d.created_at >= $5)
+    AND ($6::timestamptz IS NULL OR d.created_at < $6)
+"#;
+
 #[derive(Clone)]
 pub struct OcrQueueService {
     db: Database,
     pool: PgPool,
     max_concurrent_jobs: usize,
+    db_pool_fraction: f64,
+    claim_batch_size: usize,
+    active_workers: Arc<AtomicUsize>,
     worker_id: String,
     transaction_manager: DocumentTransactionManager,
     processing_throttler: Arc<RequestThrottler>,
     is_paused: Arc<AtomicBool>,
+    progress_tracker: OcrProgressTracker,
+    in_flight: InFlightDocuments,
+    processing_times: ProcessingTimeHistogram,
 }
 
 impl OcrQueueService {
@@ -57,7 +301,7 @@ impl OcrQueueService {
         // Create a processing throttler to limit concurrent OCR operations
         // This prevents overwhelming the database connection pool
         let processing_throttler = Arc::new(RequestThrottler::new(
-            max_concurrent_jobs.min(15), // Don't exceed 15 concurrent OCR processes
+            max_concurrent_jobs.max(1),
             60, // 60 second max wait time for OCR processing
             format!("ocr-processing-{}", worker_id),
         ));
@@ -66,14 +310,74 @@ impl OcrQueueService {
             db,
             pool,
             max_concurrent_jobs,
+            db_pool_fraction: DEFAULT_OCR_DB_POOL_FRACTION,
+            claim_batch_size: DEFAULT_OCR_CLAIM_BATCH_SIZE,
+            active_workers: Arc::new(AtomicUsize::new(0)),
             worker_id,
             transaction_manager,
             processing_throttler,
             is_paused: Arc::new(AtomicBool::new(false)),
+            progress_tracker: OcrProgressTracker::new(),
+            in_flight: InFlightDocuments::default(),
+            processing_times: ProcessingTimeHistogram::default(),
         }
     }
 
-    /// Add a document to the OCR queue
+    /// Limit the worker to this share of the database pool's connections
+    pub fn with_db_pool_fraction(mut self, fraction: f64) -> Self {
+        self.db_pool_fraction = fraction;
+        self
+    }
+
+    /// Claim up to this many jobs per query. The worker never claims more jobs than it has
+    /// free slots, so claimed jobs start right away.
+    pub fn with_claim_batch_size(mut self, batch_size: usize) -> Self {
+        self.claim_batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Configured maximum number of concurrent OCR jobs
+    pub fn max_concurrent_jobs(&self) -> usize {
+        self.max_concurrent_jobs
+    }
+
+    /// Number of OCR jobs the worker runs at once after applying the pool share
+    pub fn worker_limit(&self) -> usize {
+        ocr_worker_limit(self.max_concurrent_jobs, self.pool.options().get_max_connections(), self.db_pool_fraction)
+    }
+
+    /// Number of OCR jobs currently being processed by this service's worker
+    pub fn active_workers(&self) -> usize {
+        self.active_workers.load(Ordering::SeqCst)
+    }
+
+    /// Live events of jobs enqueued and processed through this service
+    pub fn progress_tracker(&self) -> &OcrProgressTracker {
+        &self.progress_tracker
+    }
+
+    /// Processing times of the jobs this worker completed
+    pub fn processing_times(&self) -> &ProcessingTimeHistogram {
+        &self.processing_times
+    }
+
+    fn publish_event(&self, user_id: Option<Uuid>, document_id: Uuid, job_id: Uuid, kind: OcrEventKind) {
+        if let Some(user_id) = user_id {
+            self.progress_tracker.publish(user_id, document_id, job_id, kind);
+        }
+    }
+
+    /// Appends to the document's processing history. A failed write is only logged, so
+    /// the history never holds up the queue.
+    async fn record_history(&self, document_id: Uuid, event_type: DocumentEventType, detail: Option<&str>, by_worker: bool) {
+        let worker_id = by_worker.then_some(self.worker_id.as_str());
+        if let Err(e) = self.db.record_document_event(document_id, event_type, detail, worker_id).await {
+            warn!("Failed to record {} event of document {}: {}", event_type, document_id, e);
+        }
+    }
+
+    /// Add a document to the OCR queue. A document already waiting or being processed is
+    /// not queued again; its job keeps the higher of the two priorities and its id is returned.
     pub async fn enqueue_document(&self, document_id: Uuid, priority: i32, file_size: i64) -> Result<Uuid> {
         crate::debug_log!("OCR_QUEUE",
             "document_id" => document_id,
@@ -82,13 +386,15 @@ impl OcrQueueService {
             "message" => "Enqueueing document"
         );
         
-        let row = sqlx::query(
+        let row = sqlx::query(&format!(
             r#"
             INSERT INTO ocr_queue (document_id, priority, file_size)
             VALUES ($1, $2, $3)
-            RETURNING id
-            "#
-        )
+            {}
+            RETURNING id, (xmax = 0) AS inserted, (SELECT user_id FROM documents WHERE id = $1) AS user_id
+            "#,
+            ON_ACTIVE_JOB_CONFLICT
+        ))
         .bind(document_id)
         .bind(priority)
         .bind(file_size)
@@ -100,6 +406,12 @@ impl OcrQueueService {
         })?;
         
         let id: Uuid = row.get("id");
+        if !row.get::<bool, _>("inserted") {
+            info!("Document {} is already queued for OCR as job {}", document_id, id);
+            return Ok(id);
+        }
+        self.publish_event(row.get("user_id"), document_id, id, OcrEventKind::Enqueued { priority });
+        self.record_history(document_id, DocumentEventType::Queued, Some(&format!("priority {}", priority)), false).await;
 
         crate::debug_log!("OCR_QUEUE",
             "document_id" => document_id,
@@ -113,21 +425,69 @@ impl OcrQueueService {
         Ok(id)
     }
 
+    /// Add a document to the queue with Tesseract settings that apply to this job only.
+    /// With `overwrite_manual_edits` the job replaces OCR text that was corrected by hand.
+    pub async fn enqueue_document_with_overrides(
+        &self,
+        document_id: Uuid,
+        priority: i32,
+        file_size: i64,
+        overrides: &crate::models::OcrOverrides,
+        overwrite_manual_edits: bool,
+    ) -> Result<Uuid> {
+        // A job that hasn't started yet takes the new options; a running one keeps its own
+        let row = sqlx::query(
+            r#"
+            INSERT INTO ocr_queue (document_id, priority, file_size, ocr_overrides, overwrite_manual_edits)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (document_id) WHERE status IN ('pending', 'processing')
+            DO UPDATE SET priority = GREATEST(ocr_queue.priority, EXCLUDED.priority),
+                          ocr_overrides = CASE WHEN ocr_queue.status = 'pending'
+                                               THEN EXCLUDED.ocr_overrides
+                                               ELSE ocr_queue.ocr_overrides END,
+                          overwrite_manual_edits = CASE WHEN ocr_queue.status = 'pending'
+                                                        THEN EXCLUDED.overwrite_manual_edits
+                                                        ELSE ocr_queue.overwrite_manual_edits END
+            RETURNING id, (xmax = 0) AS inserted, (SELECT user_id FROM documents WHERE id = $1) AS user_id
+            "#
+        )
+        .bind(document_id)
+        .bind(priority)
+        .bind(file_size)
+        .bind(serde_json::to_value(overrides)?)
+        .bind(overwrite_manual_edits)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let id: Uuid = row.get("id");
+        if !row.get::<bool, _>("inserted") {
+            info!("Document {} is already queued for OCR as job {}", document_id, id);
+            return Ok(id);
+        }
+        self.publish_event(row.get("user_id"), document_id, id, OcrEventKind::Enqueued { priority });
+        self.record_history(document_id, DocumentEventType::Queued, Some(&format!("priority {} with OCR overrides", priority)), false).await;
+        info!("Enqueued document {} with priority {} and OCR overrides {:?}", document_id, priority, overrides);
+        Ok(id)
+    }
+
     /// Batch enqueue multiple documents
     pub async fn enqueue_documents_batch(&self, documents: Vec<(Uuid, i32, i64)>) -> Result<Vec<Uuid>> {
         let mut ids = Vec::new();
+        let mut events = Vec::new();
         
         // Use a transaction for batch insert
         let mut tx = self.pool.begin().await?;
         
         for (document_id, priority, file_size) in documents {
-            let row = sqlx::query(
+            let row = sqlx::query(&format!(
                 r#"
                 INSERT INTO ocr_queue (document_id, priority, file_size)
                 VALUES ($1, $2, $3)
-                RETURNING id
-                "#
-            )
+                {}
+                RETURNING id, (xmax = 0) AS inserted, (SELECT user_id FROM documents WHERE id = $1) AS user_id
+                "#,
+                ON_ACTIVE_JOB_CONFLICT
+            ))
             .bind(document_id)
             .bind(priority)
             .bind(file_size)
@@ -136,156 +496,420 @@ impl OcrQueueService {
             
             let id: Uuid = row.get("id");
             ids.push(id);
+            if row.get::<bool, _>("inserted") {
+                events.push((row.get::<Option<Uuid>, _>("user_id"), document_id, id, priority));
+            }
         }
         
         tx.commit().await?;
+
+        for (user_id, document_id, id, priority) in events {
+            self.publish_event(user_id, document_id, id, OcrEventKind::Enqueued { priority });
+            self.record_history(document_id, DocumentEventType::Queued, Some(&format!("priority {}", priority)), false).await;
+        }
         
         info!("Batch enqueued {} documents for OCR processing", ids.len());
         Ok(ids)
     }
 
-    /// Get the next item from the queue with atomic job claiming and retry logic
-    pub async fn dequeue(&self) -> Result<Option<OcrQueueItem>> {
-        crate::debug_log!("OCR_QUEUE", 
-            "worker_id" => &self.worker_id,
-            "message" => "Starting dequeue operation"
-        );
-        
-        // Retry up to 3 times for race condition scenarios
-        for attempt in 1..=3 {
-            crate::debug_log!("OCR_QUEUE", 
-                "worker_id" => &self.worker_id,
-                "attempt" => attempt,
-                "message" => "Attempting to dequeue job"
-            );
-            // Use a transaction to ensure atomic job claiming
-            let mut tx = self.pool.begin().await?;
-        
-        // Step 1: Find and lock the next available job atomically
-        let job_row = sqlx::query(
+    /// Resets up to `limit` failed documents matching `selection` to pending, oldest first,
+    /// and enqueues them with `priority`. Each reset is recorded in the retry history under
+    /// `requested_by`. Returns how many were enqueued and how many matching failures remain.
+    /// Documents in the dead-letter queue are left for `requeue_dead_letter_item`.
+    pub async fn requeue_failed_documents(
+        &self,
+        selection: &FailedOcrSelection,
+        limit: i64,
+        priority: i32,
+        requested_by: Uuid,
+    ) -> Result<(i64, i64)> {
+        let mut tx = self.pool.begin().await?;
+
+        let enqueued = sqlx::query(
             r#"
-            SELECT id, document_id, priority, status, attempts, max_attempts, 
-                   created_at, started_at, completed_at, error_message, 
-                   worker_id, processing_time_ms, file_size
-            FROM ocr_queue
-            WHERE status = 'pending'
-              AND attempts < max_attempts
-            ORDER BY priority DESC, created_at ASC
-            FOR UPDATE SKIP LOCKED
-            LIMIT 1
+            WITH selected AS (
+                SELECT d.id, d.file_size, d.ocr_status, d.ocr_failure_reason, d.ocr_error
+                FROM documents d
+                WHERE d.ocr_status = 'failed'
+                  AND d.deleted_at IS NULL
+                  AND ($1::uuid IS NULL OR d.user_id = $1)
+                  AND ($2::text IS NULL OR d.ocr_failure_reason = $2)
+                  AND ($3::timestamptz IS NULL OR d.created_at >= $3)
+                  AND ($4::timestamptz IS NULL OR d.created_at < $4)
+                  AND NOT EXISTS (
+                      SELECT 1 FROM ocr_queue q
+                      WHERE q.document_id = d.id AND q.status IN ('pending', 'processing', 'dead_letter')
+                  )
+                ORDER BY d.created_at ASC
+                LIMIT $5
+                FOR UPDATE OF d SKIP LOCKED
+            ),
+            history AS (
+                INSERT INTO ocr_retry_history (
+                    document_id, user_id, retry_reason, previous_status,
+                    previous_failure_reason, previous_error, priority
+                )
+                SELECT id, $7, 'bulk_retry_failed', ocr_status, ocr_failure_reason, ocr_error, $6
+                FROM selected
+            ),
+            events AS (
+                INSERT INTO document_events (document_id, event_type, detail)
+                SELECT id, 'queued', 'retry of failed OCR, priority ' || $6 FROM selected
+            ),
+            reset AS (
+                UPDATE documents d
+                SET ocr_status = 'pending',
+                    ocr_error = NULL,
+                    ocr_failure_reason = NULL,
+                    updated_at = NOW()
+                FROM selected
+                WHERE d.id = selected.id
+            )
+            INSERT INTO ocr_queue (document_id, priority, file_size)
+            SELECT id, $6, file_size FROM selected
             "#
         )
-        .fetch_optional(&mut *tx)
+        .bind(selection.user_id)
+        .bind(&selection.failure_reason)
+        .bind(selection.created_after)
+        .bind(selection.created_before)
+        .bind(limit)
+        .bind(priority)
+        .bind(requested_by)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected() as i64;
+
+        let remaining: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM documents d
+            WHERE d.ocr_status = 'failed'
+              AND d.deleted_at IS NULL
+              AND ($1::uuid IS NULL OR d.user_id = $1)
+              AND ($2::text IS NULL OR d.ocr_failure_reason = $2)
+              AND ($3::timestamptz IS NULL OR d.created_at >= $3)
+              AND ($4::timestamptz IS NULL OR d.created_at < $4)
+              AND NOT EXISTS (
+                  SELECT 1 FROM ocr_queue q
+                  WHERE q.document_id = d.id AND q.status = 'dead_letter'
+              )
+            "#
+        )
+        .bind(selection.user_id)
+        .bind(&selection.failure_reason)
+        .bind(selection.created_after)
+        .bind(selection.created_before)
+        .fetch_one(&mut *tx)
         .await?;
 
-        let job_id = match job_row {
-            Some(ref row) => {
-                let job_id = row.get::<Uuid, _>("id");
-                let document_id = row.get::<Uuid, _>("document_id");
-                crate::debug_log!("OCR_QUEUE", 
-                    "worker_id" => &self.worker_id,
-                    "job_id" => job_id,
-                    "document_id" => document_id,
-                    "attempt" => attempt,
-                    "message" => "Found pending job in queue"
-                );
-                job_id
-            },
-            None => {
-                crate::debug_log!("OCR_QUEUE", 
-                    "worker_id" => &self.worker_id,
-                    "attempt" => attempt,
-                    "message" => "No pending jobs found in queue"
-                );
-                // No jobs available
-                tx.rollback().await?;
-                return Ok(None);
-            }
-        };
+        tx.commit().await?;
+
+        info!("Requeued {} failed documents for OCR with priority {} ({} still failed)", enqueued, priority, remaining);
+        Ok((enqueued, remaining))
+    }
+
+    /// Records the documents matching `selection` as a batch job that reprocesses them with
+    /// `priority` and `overrides`. Documents with a job already pending or running are marked
+    /// as skipped, as are documents with manually corrected OCR text unless `force` is set.
+    /// Nothing is queued yet; `feed_batch_job` hands the documents to the queue.
+    pub async fn create_reprocess_batch_job(
+        &self,
+        selection: &OcrReprocessSelection,
+        overrides: &crate::models::OcrOverrides,
+        priority: i32,
+        force: bool,
+    ) -> Result<crate::models::BatchJob> {
+        let kind = if overrides.language.is_some() { "reprocess_language" } else { "reocr" };
+        let mut tx = self.pool.begin().await?;
 
-        // Step 2: Atomically update the job to processing state
-        let updated_rows = sqlx::query(
+        let job_id: Uuid = sqlx::query_scalar(
+            "INSERT INTO batch_jobs (user_id, kind, priority, ocr_overrides, force) VALUES ($1, $2, $3, $4, $5) RETURNING id"
+        )
+        .bind(selection.user_id)
+        .bind(kind)
+        .bind(priority)
+        .bind(serde_json::to_value(overrides)?)
+        .bind(force)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(&format!(
             r#"
-            UPDATE ocr_queue
-            SET status = 'processing',
-                started_at = NOW(),
-                worker_id = $1,
-                attempts = attempts + 1
-            WHERE id = $2
-              AND status = 'pending'  -- Extra safety check
+            INSERT INTO batch_job_documents (batch_job_id, document_id, state)
+            SELECT $7, d.id,
+                   CASE WHEN (d.ocr_manually_edited AND NOT $8) OR EXISTS (
+                       SELECT 1 FROM ocr_queue q
+                       WHERE q.document_id = d.id AND q.status IN ('pending', 'processing')
+                   ) THEN 'skipped' ELSE 'waiting' END
+            FROM documents d
+            WHERE {}
+            "#,
+            REPROCESS_SELECTION_FILTER
+        ))
+        .bind(selection.user_id)
+        .bind(&selection.document_ids)
+        .bind(&selection.mime_types)
+        .bind(selection.source_id)
+        .bind(selection.created_after)
+        .bind(selection.created_before)
+        .bind(job_id)
+        .bind(force)
+        .execute(&mut *tx)
+        .await?;
+
+        let job = sqlx::query_as::<_, crate::models::BatchJob>(
+            r#"
+            WITH counts AS (
+                SELECT COUNT(*) AS total,
+                       COUNT(*) FILTER (WHERE state = 'skipped') AS skipped
+                FROM batch_job_documents
+                WHERE batch_job_id = $1
+            )
+            UPDATE batch_jobs j
+            SET total = counts.total,
+                skipped = counts.skipped,
+                status = CASE WHEN counts.total = counts.skipped THEN 'completed' ELSE 'running' END,
+                finished_at = CASE WHEN counts.total = counts.skipped THEN NOW() END
+            FROM counts
+            WHERE j.id = $1
+            RETURNING j.*
             "#
         )
-        .bind(&self.worker_id)
         .bind(job_id)
-        .execute(&mut *tx)
+        .fetch_one(&mut *tx)
         .await?;
 
-        if updated_rows.rows_affected() != 1 {
-            // Job was claimed by another worker between SELECT and UPDATE
-            crate::debug_log!("OCR_QUEUE", 
-                "worker_id" => &self.worker_id,
-                "job_id" => job_id,
-                "attempt" => attempt,
-                "rows_affected" => updated_rows.rows_affected(),
-                "message" => "Job was claimed by another worker, retrying"
-            );
-            tx.rollback().await?;
-            warn!("Job {} was claimed by another worker, retrying", job_id);
-            continue; // Continue to next attempt instead of returning
-        }
-        
-        crate::debug_log!("OCR_QUEUE", 
-            "worker_id" => &self.worker_id,
-            "job_id" => job_id,
-            "attempt" => attempt,
-            "message" => "Successfully claimed job, updating to processing state"
+        tx.commit().await?;
+
+        info!(
+            "Created {} batch job {} for user {} with {} documents ({} skipped), priority {} and OCR overrides {:?}",
+            kind, job.id, selection.user_id, job.total, job.skipped, priority, overrides
         );
+        Ok(job)
+    }
 
-        // Step 3: Get the updated job details
-        let row = sqlx::query(
+    /// Hands waiting documents of a running batch job to the queue until the job has
+    /// `BATCH_JOB_WINDOW` jobs pending or processing. Each document's OCR results are cleared
+    /// and the reset recorded in the retry history as it is queued; documents that were
+    /// deleted, got a job of their own or, unless the job is forced, were corrected by hand
+    /// in the meantime are skipped. Returns how many documents were queued.
+    pub async fn feed_batch_job(&self, batch_job_id: Uuid) -> Result<i64> {
+        let mut tx = self.pool.begin().await?;
+
+        // Locking the job row keeps concurrent feeds of the same job from overfilling the window
+        let job: Option<(Uuid, String, i32, Option<serde_json::Value>, bool)> = sqlx::query_as(
+            "SELECT user_id, kind, priority, ocr_overrides, force FROM batch_jobs WHERE id = $1 AND status = 'running' FOR UPDATE"
+        )
+        .bind(batch_job_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some((user_id, kind, priority, overrides, force)) = job else {
+            return Ok(0);
+        };
+
+        let in_flight: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM batch_job_documents WHERE batch_job_id = $1 AND state = 'queued'"
+        )
+        .bind(batch_job_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        let slots = BATCH_JOB_WINDOW - in_flight;
+        if slots <= 0 {
+            return Ok(0);
+        }
+
+        let retry_reason = if kind == "reprocess_language" { "language_reprocess" } else { "reocr" };
+        let queued: i64 = sqlx::query_scalar(
             r#"
-            SELECT id, document_id, priority, status, attempts, max_attempts, 
-                   created_at, started_at, completed_at, error_message, 
-                   worker_id, processing_time_ms, file_size
-            FROM ocr_queue
-            WHERE id = $1
+            WITH candidates AS (
+                SELECT d.id, d.file_size, d.ocr_status, d.ocr_failure_reason, d.ocr_error,
+                       (d.deleted_at IS NOT NULL OR (d.ocr_manually_edited AND NOT $7) OR EXISTS (
+                           SELECT 1 FROM ocr_queue q
+                           WHERE q.document_id = d.id AND q.status IN ('pending', 'processing')
+                       )) AS busy
+                FROM batch_job_documents bd
+                JOIN documents d ON d.id = bd.document_id
+                WHERE bd.batch_job_id = $1 AND bd.state = 'waiting'
+                ORDER BY bd.document_id
+                LIMIT $2
+                FOR UPDATE OF d SKIP LOCKED
+            ),
+            selected AS (
+                SELECT * FROM candidates WHERE NOT busy
+            ),
+            marked AS (
+                UPDATE batch_job_documents bd
+                SET state = CASE WHEN candidates.busy THEN 'skipped' ELSE 'queued' END,
+                    updated_at = NOW()
+                FROM candidates
+                WHERE bd.batch_job_id = $1 AND bd.document_id = candidates.id
+            ),
+            history AS (
+                INSERT INTO ocr_retry_history (
+                    document_id, user_id, retry_reason, previous_status,
+                    previous_failure_reason, previous_error, priority
+                )
+                SELECT id, $3, $4, ocr_status, ocr_failure_reason, ocr_error, $5
+                FROM selected
+            ),
+            events AS (
+                INSERT INTO document_events (document_id, event_type, detail)
+                SELECT id, 'queued', 'batch job ' || $1 || ', priority ' || $5 FROM selected
+            ),
+            words AS (
+                DELETE FROM document_ocr_words w
+                USING selected
+                WHERE w.document_id = selected.id
+            ),
+            reset AS (
+                UPDATE documents d
+                SET ocr_status = 'pending',
+                    ocr_text = NULL,
+                    ocr_error = NULL,
+                    ocr_failure_reason = NULL,
+                    ocr_confidence = NULL,
+                    ocr_word_count = NULL,
+                    ocr_processing_time_ms = NULL,
+                    ocr_completed_at = NULL,
+                    updated_at = NOW()
+                FROM selected
+                WHERE d.id = selected.id
+            ),
+            queued AS (
+                INSERT INTO ocr_queue (document_id, priority, file_size, ocr_overrides, batch_job_id, overwrite_manual_edits)
+                SELECT id, $5, file_size, $6, $1, $7 FROM selected
+                RETURNING id
+            ),
+            counted AS (
+                UPDATE batch_jobs
+                SET enqueued = enqueued + (SELECT COUNT(*) FROM queued),
+                    skipped = skipped + (SELECT COUNT(*) FROM candidates WHERE busy),
+                    updated_at = NOW()
+                WHERE id = $1
+            )
+            SELECT COUNT(*) FROM queued
             "#
         )
-        .bind(job_id)
+        .bind(batch_job_id)
+        .bind(slots)
+        .bind(user_id)
+        .bind(retry_reason)
+        .bind(priority)
+        .bind(overrides)
+        .bind(force)
         .fetch_one(&mut *tx)
         .await?;
 
         tx.commit().await?;
 
-        // Return the successfully claimed job
-        let item = OcrQueueItem {
-            id: row.get("id"),
-            document_id: row.get("document_id"),
-            status: row.get("status"),
-            priority: row.get("priority"),
-            attempts: row.get("attempts"),
-            max_attempts: row.get("max_attempts"),
-            created_at: row.get("created_at"),
-            started_at: row.get("started_at"),
-            completed_at: row.get("completed_at"),
-            error_message: row.get("error_message"),
-            worker_id: row.get("worker_id"),
-            processing_time_ms: row.get("processing_time_ms"),
-            file_size: row.get("file_size"),
-        };
+        if queued > 0 {
+            info!("Batch job {} queued {} more documents for OCR", batch_job_id, queued);
+        }
+        if self.db.complete_batch_job_if_done(batch_job_id).await? {
+            info!("Batch job {} completed", batch_job_id);
+        }
+        Ok(queued)
+    }
 
-        info!("✅ Worker {} successfully claimed job {} for document {}", 
-              self.worker_id, item.id, item.document_id);
-        
-        return Ok(Some(item));
+    /// Tops up every running batch job. Run periodically, this also resumes the jobs a
+    /// previous server process left running.
+    pub async fn feed_running_batch_jobs(&self) -> Result<i64> {
+        let mut queued = 0;
+        for batch_job_id in self.db.get_running_batch_job_ids().await? {
+            match self.feed_batch_job(batch_job_id).await {
+                Ok(count) => queued += count,
+                Err(e) => warn!("Failed to feed batch job {}: {}", batch_job_id, e),
+            }
         }
-        
-        // If all retry attempts failed, return None
-        Ok(None)
+        Ok(queued)
+    }
+
+    /// Tells a batch job how one of its documents ended and refills its window. Errors are
+    /// logged; the next periodic feed catches the job up.
+    async fn report_to_batch_job(&self, batch_job_id: Uuid, document_id: Uuid, succeeded: bool) {
+        if let Err(e) = self.db.record_batch_job_result(batch_job_id, document_id, succeeded).await {
+            warn!("Failed to record OCR result of document {} in batch job {}: {}", document_id, batch_job_id, e);
+            return;
+        }
+        if let Err(e) = self.feed_batch_job(batch_job_id).await {
+            warn!("Failed to feed batch job {}: {}", batch_job_id, e);
+        }
+    }
+
+    /// Get the next item from the queue with atomic job claiming
+    pub async fn dequeue(&self) -> Result<Option<OcrQueueItem>> {
+        Ok(self.dequeue_batch(1).await?.into_iter().next())
     }
 
-    /// Mark an item as completed
-    async fn mark_completed(&self, item_id: Uuid, processing_time_ms: i32) -> Result<()> {
+    /// Claims up to `limit` of the most urgent pending jobs in one statement. `SKIP LOCKED`
+    /// passes over rows another worker is claiming, so no job is ever claimed twice, and
+    /// documents this process is already working on are left for later.
+    pub async fn dequeue_batch(&self, limit: usize) -> Result<Vec<OcrQueueItem>> {
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        crate::debug_log!("OCR_QUEUE",
+            "worker_id" => &self.worker_id,
+            "limit" => limit,
+            "message" => "Claiming jobs"
+        );
+
+        let items = sqlx::query_as::<_, OcrQueueItem>(
+            r#"
+            WITH claimed AS (
+                UPDATE ocr_queue
+                SET status = 'processing',
+                    started_at = NOW(),
+                    worker_id = $1,
+                    attempts = attempts + 1
+                WHERE id IN (
+                    SELECT id
+                    FROM ocr_queue
+                    WHERE status = 'pending'
+                      AND attempts < max_attempts
+                      AND NOT (document_id = ANY($2))
+                    ORDER BY priority DESC, file_size ASC NULLS LAST, created_at ASC
+                    FOR UPDATE SKIP LOCKED
+                    LIMIT $3
+                )
+                RETURNING *
+            )
+            SELECT c.id, c.document_id, c.priority, c.status, c.attempts, c.max_attempts,
+                   c.created_at, c.started_at, c.completed_at, c.error_message,
+                   c.worker_id, c.processing_time_ms, c.file_size, c.ocr_overrides, c.batch_job_id, c.overwrite_manual_edits,
+                   s.config->'ocr_overrides' AS source_ocr_overrides,
+                   s.config->'ocr_language_routes' AS source_language_routes,
+                   d.source_path,
+                   ARRAY(
+                       SELECT l.name::text FROM document_labels dl JOIN labels l ON l.id = dl.label_id
+                       WHERE dl.document_id = c.document_id
+                   ) AS labels
+            FROM claimed c
+            LEFT JOIN documents d ON d.id = c.document_id
+            LEFT JOIN sources s ON s.id = d.source_id
+            ORDER BY c.priority DESC, c.file_size ASC NULLS LAST, c.created_at ASC
+            "#
+        )
+        .bind(&self.worker_id)
+        .bind(self.in_flight.snapshot())
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        for item in &items {
+            info!("✅ Worker {} successfully claimed job {} for document {}",
+                  self.worker_id, item.id, item.document_id);
+            self.record_history(item.document_id, DocumentEventType::OcrStarted, Some(&format!("attempt {}", item.attempts)), true).await;
+        }
+
+        Ok(items)
+    }
+
+    /// Mark an item as completed. A successful OCR update already removes the queue row, so
+    /// the history and the batch job are told from the item rather than from the row.
+    async fn mark_completed(&self, item: &OcrQueueItem, processing_time_ms: i32) -> Result<()> {
         sqlx::query(
             r#"
             UPDATE ocr_queue
@@ -295,28 +919,69 @@ impl OcrQueueService {
             WHERE id = $1
             "#
         )
-        .bind(item_id)
+        .bind(item.id)
         .bind(processing_time_ms)
         .execute(&self.pool)
         .await?;
 
+        self.processing_times.record(processing_time_ms.max(0) as u64);
+        self.record_history(item.document_id, DocumentEventType::OcrCompleted, Some(&format!("{} ms", processing_time_ms)), true).await;
+        if let Some(batch_job_id) = item.batch_job_id {
+            self.report_to_batch_job(batch_job_id, item.document_id, true).await;
+        }
+
         Ok(())
     }
 
+    /// Completes a job without running OCR because the document's text was corrected by
+    /// hand and the job may not replace it. Returns the confidence and word count the
+    /// document keeps.
+    async fn keep_manual_edits(&self, item: &OcrQueueItem) -> Result<(f32, i32)> {
+        let (confidence, word_count): (Option<f32>, Option<i32>) = sqlx::query_as(
+            r#"
+            UPDATE documents
+            SET ocr_status = 'completed',
+                ocr_error = NULL,
+                ocr_failure_reason = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            RETURNING ocr_confidence, ocr_word_count
+            "#
+        )
+        .bind(item.document_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        sqlx::query(
+            "UPDATE ocr_queue SET status = 'completed', completed_at = NOW(), processing_time_ms = 0 WHERE id = $1"
+        )
+        .bind(item.id)
+        .execute(&self.pool)
+        .await?;
+
+        self.record_history(item.document_id, DocumentEventType::OcrCompleted, Some("kept manually corrected text"), true).await;
+        if let Some(batch_job_id) = item.batch_job_id {
+            self.report_to_batch_job(batch_job_id, item.document_id, true).await;
+        }
+
+        Ok((confidence.unwrap_or_default(), word_count.unwrap_or_default()))
+    }
+
     /// Mark an item as failed
     async fn mark_failed(&self, item_id: Uuid, error: &str) -> Result<()> {
         let result = sqlx::query(
             r#"
             UPDATE ocr_queue
             SET status = CASE 
-                    WHEN attempts >= max_attempts THEN 'failed'
+                    WHEN attempts >= max_attempts THEN 'dead_letter'
                     ELSE 'pending'
                 END,
+                dead_lettered_at = CASE WHEN attempts >= max_attempts THEN NOW() END,
                 error_message = $2,
                 started_at = NULL,
                 worker_id = NULL
             WHERE id = $1
-            RETURNING status
+            RETURNING status, document_id, batch_job_id, (SELECT user_id FROM documents WHERE id = ocr_queue.document_id) AS user_id
             "#
         )
         .bind(item_id)
@@ -325,21 +990,251 @@ impl OcrQueueService {
         .await?;
 
         let status: Option<String> = result.get("status");
-        if status == Some("failed".to_string()) {
-            error!("OCR job {} permanently failed after max attempts: {}", item_id, error);
+        let event_type = if status.as_deref() == Some("pending") { DocumentEventType::OcrRetried } else { DocumentEventType::OcrFailed };
+        self.record_history(result.get("document_id"), event_type, Some(error), true).await;
+        if status.as_deref() == Some("dead_letter") {
+            error!("OCR job {} permanently failed after max attempts, moved to the dead-letter queue: {}", item_id, error);
+            if let Some(batch_job_id) = result.get::<Option<Uuid>, _>("batch_job_id") {
+                self.report_to_batch_job(batch_job_id, result.get("document_id"), false).await;
+            }
         }
 
+        self.publish_event(result.get("user_id"), result.get("document_id"), item_id, OcrEventKind::Failed {
+            error: error.to_string(),
+            will_retry: status.as_deref() == Some("pending"),
+        });
+
         Ok(())
     }
 
+    /// Puts a job back in the queue to run again with escalated preprocessing after a
+    /// low-confidence result. The retry counts against the document's `ocr_retry_count`.
+    async fn requeue_escalated(&self, item: &OcrQueueItem, overrides: &crate::models::OcrOverrides, reason: &str) -> Result<()> {
+        let overrides = crate::models::OcrOverrides {
+            escalate: Some(true),
+            ..overrides.clone()
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_retry_count = COALESCE(ocr_retry_count, 0) + 1,
+                ocr_status = 'pending',
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(item.document_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // The escalated run must not be lost to the attempt limit
+        sqlx::query(
+            r#"
+            UPDATE ocr_queue
+            SET status = 'pending',
+                ocr_overrides = $2,
+                error_message = $3,
+                max_attempts = GREATEST(max_attempts, attempts + 1),
+                started_at = NULL,
+                worker_id = NULL
+            WHERE id = $1
+            "#
+        )
+        .bind(item.id)
+        .bind(serde_json::to_value(&overrides)?)
+        .bind(reason)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        self.record_history(item.document_id, DocumentEventType::OcrRetried, Some(&format!("escalated: {}", reason)), true).await;
+        Ok(())
+    }
+
+    /// Computes and stores the perceptual hash of an image document. Failures are
+    /// logged and never fail the OCR job; the document just won't match near-duplicates.
+    async fn store_perceptual_hash(&self, document_id: Uuid, file_path: &str) {
+        let path = std::path::PathBuf::from(file_path);
+        let hash = tokio::task::spawn_blocking(move || {
+            crate::services::perceptual_hash::compute_perceptual_hash(&path)
+        })
+        .await;
+
+        match hash {
+            Ok(Ok(hash)) => {
+                if let Err(e) = self.db.update_document_perceptual_hash(document_id, hash).await {
+                    warn!("Failed to store perceptual hash for document {}: {}", document_id, e);
+                }
+            }
+            Ok(Err(e)) => warn!("Could not compute perceptual hash for document {}: {}", document_id, e),
+            Err(e) => warn!("Perceptual hash task for document {} panicked: {}", document_id, e),
+        }
+    }
+
+    /// Extracts and stores the word boxes of an image or PDF document. Failures are
+    /// logged and never fail the OCR job; the document just has no text overlay.
+    async fn store_word_boxes(&self, document_id: Uuid, file_path: &str, mime_type: &str, lang: &str) {
+        let ocr_service = crate::ocr::OcrService::new();
+        match ocr_service.extract_text_with_boxes(file_path, mime_type, lang).await {
+            Ok(pages) => {
+                if let Err(e) = self.db.replace_document_ocr_words(document_id, &pages).await {
+                    warn!("Failed to store word boxes for document {}: {}", document_id, e);
+                }
+            }
+            Err(e) => warn!("Could not extract word boxes for document {}: {}", document_id, e),
+        }
+    }
+
+    /// Stores the orientation detected while preprocessing an image. When that changes the
+    /// rotation the document is shown with, its cached thumbnail is dropped so the next
+    /// request renders it turned. Failures are only logged.
+    async fn store_orientation(&self, document_id: Uuid, file_path: &str, parameters: &crate::ocr::enhanced::PreprocessingParameters, ocr_service: &EnhancedOcrService) {
+        let Some(detected) = parameters.detected_rotation_degrees else {
+            return;
+        };
+
+        let rotation_changed = match self.db.set_document_detected_orientation(
+            document_id,
+            parameters.rotation_degrees as i32,
+            detected as i32,
+            parameters.orientation_confidence.unwrap_or(0.0),
+            parameters.orientation_needs_review,
+        ).await {
+            Ok(changed) => changed,
+            Err(e) => {
+                warn!("Failed to store orientation of document {}: {}", document_id, e);
+                return;
+            }
+        };
+
+        if parameters.orientation_needs_review {
+            info!(
+                "Document {} may need turning {}° (orientation confidence {:.2}), left as is for review",
+                document_id, detected, parameters.orientation_confidence.unwrap_or(0.0)
+            );
+        }
+
+        if rotation_changed {
+            ocr_service.file_service.remove_thumbnail(file_path).await;
+        }
+    }
+
+    /// Detects and stores the script of an image or PDF document. When the script
+    /// confidently doesn't fit the OCR languages, the owner is notified so they can
+    /// reprocess the document with the right language. Failures are only logged.
+    async fn store_script_detection(&self, document_id: Uuid, user_id: Option<Uuid>, filename: &str, file_path: &str, mime_type: &str, lang: &str) {
+        let ocr_service = crate::ocr::OcrService::new();
+        let detection = match ocr_service.detect_script(file_path, mime_type).await {
+            Ok(Some(detection)) => detection,
+            Ok(None) => {
+                info!("No script detected for document {}", document_id);
+                return;
+            }
+            Err(e) => {
+                warn!("Could not detect script for document {}: {}", document_id, e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.db.update_document_script_detection(document_id, &detection.script, detection.script_confidence).await {
+            warn!("Failed to store detected script for document {}: {}", document_id, e);
+        }
+
+        let (Some(user_id), Some(expected)) = (user_id, crate::ocr::script_detection::script_mismatch(lang, &detection)) else {
+            return;
+        };
+        info!(
+            "Document {} looks like {} script (confidence {:.2}) but was OCRed as {}",
+            document_id, detection.script, detection.script_confidence, lang
+        );
+
+        let notification = crate::models::CreateNotification {
+            notification_type: "warning".to_string(),
+            title: "OCR Language May Not Match".to_string(),
+            message: format!(
+                "{} appears to be written in {} script, but was processed as {}. Reprocess it with a matching language for better text.",
+                filename, detection.script, lang
+            ),
+            action_url: Some(format!("/documents/{}", document_id)),
+            metadata: Some(serde_json::json!({
+                "document_id": document_id,
+                "detected_script": detection.script,
+                "script_confidence": detection.script_confidence,
+                "ocr_language": lang,
+                "expected_scripts": expected,
+            })),
+        };
+        if let Err(e) = self.db.create_notification(user_id, &notification).await {
+            warn!("Failed to notify about script mismatch of document {}: {}", document_id, e);
+        }
+    }
+
     /// Process a single queue item
+    /// Settings an OCR job runs with: the user's settings, then the overrides of the source
+    /// the document came from, then the language the source routes the document to, then
+    /// the overrides requested for this job. Also returns the
+    /// job's own overrides, which are what an escalated retry carries forward.
+    pub fn resolve_job_settings(
+        item: &OcrQueueItem,
+        user_settings: &crate::models::Settings,
+    ) -> (crate::models::Settings, crate::models::OcrOverrides) {
+        let mut settings = user_settings.clone();
+
+        if let Some(overrides) = item.source_ocr_overrides.clone().filter(|v| !v.is_null()) {
+            match serde_json::from_value::<crate::models::OcrOverrides>(overrides) {
+                Ok(overrides) => {
+                    info!("Applying source OCR overrides to job {}: {:?}", item.id, overrides);
+                    settings = overrides.apply_to(&settings);
+                }
+                Err(e) => warn!("Ignoring invalid source OCR overrides on job {}: {}", item.id, e),
+            }
+        }
+
+        if let Some(routes) = item.source_language_routes.clone().filter(|v| !v.is_null()) {
+            match serde_json::from_value::<crate::ocr::language_routing::OcrLanguageRoutes>(routes) {
+                Ok(routes) => {
+                    if let Some(language) = routes.language_for(item.source_path.as_deref(), &item.labels) {
+                        info!("Routing job {} to OCR language {}", item.id, language);
+                        let route = crate::models::OcrOverrides { language: Some(language.to_string()), ..Default::default() };
+                        settings = route.apply_to(&settings);
+                    }
+                }
+                Err(e) => warn!("Ignoring invalid source OCR language routes on job {}: {}", item.id, e),
+            }
+        }
+
+        // Overrides requested for this job only; the saved settings are left untouched
+        let mut job_overrides = crate::models::OcrOverrides::default();
+        if let Some(overrides) = item.ocr_overrides.clone() {
+            match serde_json::from_value::<crate::models::OcrOverrides>(overrides) {
+                Ok(overrides) => {
+                    info!("Applying OCR overrides to job {}: {:?}", item.id, overrides);
+                    settings = overrides.apply_to(&settings);
+                    job_overrides = overrides;
+                }
+                Err(e) => warn!("Ignoring invalid OCR overrides on job {}: {}", item.id, e),
+            }
+        }
+
+        (settings, job_overrides)
+    }
+
     pub async fn process_item(&self, item: OcrQueueItem, ocr_service: &EnhancedOcrService) -> Result<()> {
+        // The task already working on the document owns this job row and finishes it
+        let Some(_in_flight) = self.in_flight.claim(item.document_id) else {
+            warn!("Skipping job {}: document {} is already being processed by this worker", item.id, item.document_id);
+            return Ok(());
+        };
         let start_time = std::time::Instant::now();
         
         // Get document details including filename for validation
         let document = sqlx::query(
             r#"
-            SELECT file_path, mime_type, user_id, filename, file_size
+            SELECT file_path, mime_type, user_id, filename, file_size,
+                   COALESCE(ocr_retry_count, 0) AS ocr_retry_count, ocr_manually_edited
             FROM documents
             WHERE id = $1
             "#
@@ -355,6 +1250,19 @@ impl OcrQueueService {
                 let user_id: Option<Uuid> = row.get("user_id");
                 let filename: String = row.get("filename");
                 let file_size: i64 = row.get("file_size");
+                let ocr_retry_count: i32 = row.get("ocr_retry_count");
+                let ocr_manually_edited: bool = row.get("ocr_manually_edited");
+
+                if ocr_manually_edited && !item.overwrite_manual_edits {
+                    info!("Keeping manually corrected OCR text of '{}' | Job: {} | Document: {}", filename, item.id, item.document_id);
+                    let (confidence, word_count) = self.keep_manual_edits(&item).await?;
+                    self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Completed {
+                        confidence,
+                        word_count,
+                        processing_time_ms: start_time.elapsed().as_millis() as i32,
+                    });
+                    return Ok(());
+                }
                 
                 // Format file size for better readability
                 let file_size_mb = file_size as f64 / (1024.0 * 1024.0);
@@ -363,21 +1271,52 @@ impl OcrQueueService {
                     "Processing OCR job {} for document {} | File: '{}' | Type: {} | Size: {:.2} MB", 
                     item.id, item.document_id, filename, mime_type, file_size_mb
                 );
+                self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Started { filename: filename.clone() });
                 // Get user's OCR settings or use defaults
-                let settings = if let Some(user_id) = user_id {
+                let user_settings = if let Some(user_id) = user_id {
                     self.db.get_user_settings(user_id).await.ok().flatten()
                         .unwrap_or_else(|| crate::models::Settings::default())
                 } else {
                     crate::models::Settings::default()
                 };
+                let (settings, job_overrides) = Self::resolve_job_settings(&item, &user_settings);
+
+                if crate::services::perceptual_hash::supports_perceptual_hash(&mime_type) {
+                    self.store_perceptual_hash(item.document_id, &file_path).await;
+                }
 
                 // Perform enhanced OCR
-                match ocr_service.extract_text_with_context(&file_path, &mime_type, &filename, file_size, &settings).await {
+                self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Progress { stage: "recognizing_text".to_string() });
+                // Every intermediate file of this job goes in its own directory, which is
+                // removed once the job is done, however it ends
+                let work_dir = OcrWorkDir::create(&ocr_service.temp_dir);
+                let extraction = match &work_dir {
+                    Ok(work_dir) => {
+                        ocr_service.in_work_dir(work_dir)
+                            .extract_text_with_context(&file_path, &mime_type, &filename, file_size, &settings).await
+                    }
+                    Err(e) => Err(anyhow::anyhow!("Failed to create OCR work directory: {}", e)),
+                };
+                match extraction {
                     Ok(ocr_result) => {
                         // Validate OCR quality
                         if !ocr_service.validate_ocr_quality(&ocr_result, &settings) {
-                            let error_msg = format!("OCR quality below threshold: {:.1}% confidence, {} words", 
-                                                   ocr_result.confidence, ocr_result.word_count);
+                            let mut error_msg = format!("OCR quality below threshold: {:.1}% confidence, {} words", 
+                                                       ocr_result.confidence, ocr_result.word_count);
+
+                            if settings.ocr_retry_low_confidence && ocr_result.confidence < settings.ocr_min_confidence {
+                                let already_escalated = job_overrides.escalate == Some(true);
+                                if !already_escalated && ocr_retry_count < MAX_LOW_CONFIDENCE_RETRIES {
+                                    info!("🔁 Low OCR confidence for '{}' ({:.1}% < {:.1}%), retrying job {} with escalated settings",
+                                          filename, ocr_result.confidence, settings.ocr_min_confidence, item.id);
+                                    self.requeue_escalated(&item, &job_overrides, &error_msg).await?;
+                                    self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Enqueued { priority: item.priority });
+                                    return Ok(());
+                                }
+                                error_msg = format!("low confidence after retry: {:.1}% confidence, {} words",
+                                                    ocr_result.confidence, ocr_result.word_count);
+                            }
+
                             warn!("⚠️  OCR quality issues for '{}' | Job: {} | Document: {} | {:.1}% confidence | {} words", 
                                   filename, item.id, item.document_id, ocr_result.confidence, ocr_result.word_count);
                             
@@ -410,6 +1349,8 @@ impl OcrQueueService {
                         }
                         
                         if !ocr_result.text.is_empty() {
+                            self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Progress { stage: "saving_results".to_string() });
+
                             // Use transaction-safe OCR update to prevent corruption
                             let processing_time_ms = start_time.elapsed().as_millis() as i64;
                             
@@ -423,6 +1364,9 @@ impl OcrQueueService {
                             ).await {
                                 Ok(true) => {
                                     info!("✅ Transaction-safe OCR update successful for document {}", item.document_id);
+                                    if let Err(e) = self.db.set_document_ocr_text_source(item.document_id, ocr_result.text_source).await {
+                                        warn!("Failed to record text source of document {}: {}", item.document_id, e);
+                                    }
                                 }
                                 Ok(false) => {
                                     let error_msg = "OCR update failed validation (document may have been modified)";
@@ -498,6 +1442,22 @@ impl OcrQueueService {
                             return Ok(());
                         }
 
+                        if crate::ocr::word_boxes::supports_word_boxes(&mime_type) {
+                            self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Progress { stage: "extracting_word_boxes".to_string() });
+                            let lang = ocr_service.build_language_combination(&settings);
+                            self.store_word_boxes(item.document_id, &file_path, &mime_type, &lang).await;
+                        }
+
+                        if settings.ocr_detect_script && crate::ocr::word_boxes::supports_word_boxes(&mime_type) {
+                            self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Progress { stage: "detecting_script".to_string() });
+                            let lang = ocr_service.build_language_combination(&settings);
+                            self.store_script_detection(item.document_id, user_id, &filename, &file_path, &mime_type, &lang).await;
+                        }
+
+                        if let Some(ref parameters) = ocr_result.preprocessing_parameters {
+                            self.store_orientation(item.document_id, &file_path, parameters, ocr_service).await;
+                        }
+
                         // Save processed image if setting is enabled and image was processed
                         if settings.save_processed_images {
                             if let Some(ref processed_image_path) = ocr_result.processed_image_path {
@@ -507,6 +1467,7 @@ impl OcrQueueService {
                                     &file_path,
                                     processed_image_path,
                                     &ocr_result.preprocessing_applied,
+                                    ocr_result.preprocessing_parameters.as_ref(),
                                 ).await {
                                     Ok(_) => {
                                         info!("✅ Saved processed image for document {} for review", item.document_id);
@@ -524,7 +1485,12 @@ impl OcrQueueService {
                         }
 
                         let processing_time_ms = start_time.elapsed().as_millis() as i32;
-                        self.mark_completed(item.id, processing_time_ms).await?;
+                        self.mark_completed(&item, processing_time_ms).await?;
+                        self.publish_event(user_id, item.document_id, item.id, OcrEventKind::Completed {
+                            confidence: ocr_result.confidence,
+                            word_count: ocr_result.word_count as i32,
+                            processing_time_ms,
+                        });
                         
                         info!(
                             "✅ OCR completed for '{}' | Job: {} | Document: {} | {:.1}% confidence | {} words | {}ms | Preprocessing: {:?}",
@@ -608,17 +1574,22 @@ impl OcrQueueService {
 
     /// Start the worker loop
     pub async fn start_worker(self: Arc<Self>) -> Result<()> {
-        let semaphore = Arc::new(Semaphore::new(self.max_concurrent_jobs));
-        let ocr_service = Arc::new(EnhancedOcrService::new("/tmp".to_string()));
+        let worker_limit = self.worker_limit();
+        let pool_max_connections = self.pool.options().get_max_connections();
+        let semaphore = Arc::new(Semaphore::new(worker_limit));
+        let ocr_service = Arc::new(EnhancedOcrService::new(work_dir::base_temp_dir()));
+        let mut saturated_backoff = POOL_SATURATED_MIN_BACKOFF;
         
         info!(
-            "Starting OCR worker {} with {} concurrent jobs",
-            self.worker_id, self.max_concurrent_jobs
+            "Starting OCR worker {} with {} concurrent jobs (configured {}, {:.0}% of {} database connections), claiming up to {} at a time",
+            self.worker_id, worker_limit, self.max_concurrent_jobs, self.db_pool_fraction * 100.0, pool_max_connections,
+            self.claim_batch_size.min(worker_limit)
         );
         
         crate::debug_log!("OCR_WORKER", 
             "worker_id" => &self.worker_id,
             "max_concurrent_jobs" => self.max_concurrent_jobs,
+            "worker_limit" => worker_limit,
             "message" => "OCR worker loop starting"
         );
 
@@ -634,49 +1605,84 @@ impl OcrQueueService {
                 continue;
             }
             
+            // Wait for a free slot before claiming a job, so the job claimed is
+            // the most urgent one at the moment the slot frees up
+            let permit = semaphore.clone().acquire_owned().await?;
+            
+            // Leave the remaining connections to the rest of the background work
+            let pool_health = self.db.get_pool_health();
+            if pool_is_saturated(&pool_health, pool_max_connections) {
+                debug!(
+                    "Database pool saturated ({} connections, none idle), delaying OCR for {:?}",
+                    pool_health.size, saturated_backoff
+                );
+                drop(permit);
+                sleep(saturated_backoff).await;
+                saturated_backoff = (saturated_backoff * 2).min(POOL_SATURATED_MAX_BACKOFF);
+                continue;
+            }
+            saturated_backoff = POOL_SATURATED_MIN_BACKOFF;
+
+            // Claim a job for every slot that is free right now, up to the batch size
+            let mut permits = vec![permit];
+            while permits.len() < self.claim_batch_size {
+                match semaphore.clone().try_acquire_owned() {
+                    Ok(permit) => permits.push(permit),
+                    Err(_) => break,
+                }
+            }
+            
             crate::debug_log!("OCR_WORKER", 
                 "worker_id" => &self.worker_id,
+                "free_slots" => permits.len(),
                 "message" => "Worker loop iteration - checking for items to process"
             );
 
             // Check for items to process
-            match self.dequeue().await {
-                Ok(Some(item)) => {
-                    crate::debug_log!("OCR_WORKER", 
-                        "worker_id" => &self.worker_id,
-                        "job_id" => item.id,
-                        "document_id" => item.document_id,
-                        "priority" => item.priority,
-                        "message" => "Dequeued job, spawning processing task"
-                    );
-                    
-                    let permit = semaphore.clone().acquire_owned().await?;
-                    let self_clone = self.clone();
-                    let ocr_service_clone = ocr_service.clone();
-                    
-                    // Spawn task to process item with throttling
-                    tokio::spawn(async move {
-                        // Acquire throttling permit to prevent overwhelming the database
-                        match self_clone.processing_throttler.acquire_permit().await {
-                            Ok(_throttle_permit) => {
-                                // Process the item with both semaphore and throttle permits held
-                                if let Err(e) = self_clone.process_item(item, &ocr_service_clone).await {
-                                    error!("Error processing OCR item: {}", e);
+            match self.dequeue_batch(permits.len()).await {
+                Ok(items) if !items.is_empty() => {
+                    // Slots no job was claimed for go back to the semaphore
+                    permits.truncate(items.len());
+
+                    for (item, permit) in items.into_iter().zip(permits) {
+                        crate::debug_log!("OCR_WORKER", 
+                            "worker_id" => &self.worker_id,
+                            "job_id" => item.id,
+                            "document_id" => item.document_id,
+                            "priority" => item.priority,
+                            "message" => "Dequeued job, spawning processing task"
+                        );
+                        
+                        let active_worker = ActiveWorkerGuard::new(&self.active_workers);
+                        let self_clone = self.clone();
+                        let ocr_service_clone = ocr_service.clone();
+                        
+                        // Spawn task to process item with throttling
+                        tokio::spawn(async move {
+                            // Acquire throttling permit to prevent overwhelming the database
+                            match self_clone.processing_throttler.acquire_permit().await {
+                                Ok(_throttle_permit) => {
+                                    // Process the item with both semaphore and throttle permits held
+                                    if let Err(e) = self_clone.process_item(item, &ocr_service_clone).await {
+                                        error!("Error processing OCR item: {}", e);
+                                    }
+                                    // Permits are automatically released when dropped
                                 }
-                                // Permits are automatically released when dropped
-                            }
-                            Err(e) => {
-                                error!("Failed to acquire throttling permit for OCR processing: {}", e);
-                                // Mark the item as failed due to throttling
-                                if let Err(mark_err) = self_clone.mark_failed(item.id, &format!("Throttling error: {}", e)).await {
-                                    error!("Failed to mark item as failed after throttling error: {}", mark_err);
+                                Err(e) => {
+                                    error!("Failed to acquire throttling permit for OCR processing: {}", e);
+                                    // Mark the item as failed due to throttling
+                                    if let Err(mark_err) = self_clone.mark_failed(item.id, &format!("Throttling error: {}", e)).await {
+                                        error!("Failed to mark item as failed after throttling error: {}", mark_err);
+                                    }
                                 }
                             }
-                        }
-                        drop(permit);
-                    });
+                            drop(active_worker);
+                            drop(permit);
+                        });
+                    }
                 }
-                Ok(None) => {
+                Ok(_) => {
+                    drop(permits);
                     crate::debug_log!("OCR_WORKER", 
                         "worker_id" => &self.worker_id,
                         "message" => "No items in queue, sleeping for 5 seconds"
@@ -686,6 +1692,7 @@ impl OcrQueueService {
                     sleep(Duration::from_secs(5)).await;
                 }
                 Err(e) => {
+                    drop(permits);
                     error!("Error dequeuing item: {}", e);
                     sleep(Duration::from_secs(5)).await;
                 }
@@ -701,6 +1708,7 @@ impl OcrQueueService {
         original_image_path: &str,
         processed_image_path: &str,
         processing_steps: &[String],
+        preprocessing_parameters: Option<&crate::ocr::enhanced::PreprocessingParameters>,
     ) -> Result<()> {
         use std::path::Path;
         
@@ -765,6 +1773,7 @@ impl OcrQueueService {
             "steps": processing_steps,
             "timestamp": chrono::Utc::now(),
             "original_path": original_image_path,
+            "preprocessing": preprocessing_parameters,
         });
         
         // Save metadata to database with error handling
@@ -800,6 +1809,38 @@ impl OcrQueueService {
         Ok(())
     }
 
+    /// Overall queue depth, a user's share of it and the estimated time to drain it
+    pub async fn get_queue_depth(&self, user_id: Uuid) -> Result<OcrQueueDepth> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                COUNT(*) AS queue_depth,
+                COUNT(*) FILTER (WHERE q.status = 'pending' AND d.user_id = $1) AS user_pending,
+                COUNT(*) FILTER (WHERE q.status = 'processing' AND d.user_id = $1) AS user_processing,
+                (SELECT AVG(processing_time_ms)::float8 FROM ocr_queue
+                 WHERE status = 'completed' AND completed_at > NOW() - INTERVAL '1 hour') AS avg_processing_ms
+            FROM ocr_queue q
+            JOIN documents d ON d.id = q.document_id
+            WHERE q.status IN ('pending', 'processing')
+            "#
+        )
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let queue_depth: i64 = row.get("queue_depth");
+        let avg_processing_ms: Option<f64> = row.get("avg_processing_ms");
+        let workers = self.worker_limit() as f64;
+
+        Ok(OcrQueueDepth {
+            queue_depth,
+            user_pending: row.get("user_pending"),
+            user_processing: row.get("user_processing"),
+            estimated_seconds_remaining: avg_processing_ms
+                .map(|avg_ms| (queue_depth as f64 * avg_ms / 1000.0 / workers).ceil() as u64),
+        })
+    }
+
     /// Get queue statistics
     pub async fn get_stats(&self) -> Result<QueueStats> {
         tracing::debug!("OCR Queue: Starting get_stats() call");
@@ -1097,6 +2138,189 @@ impl OcrQueueService {
         Ok(result.rows_affected() as i64)
     }
 
+    /// Lists queue entries of every user matching `filter`, highest priority and oldest first,
+    /// with the total number of matches
+    pub async fn list_queue_items(&self, filter: &AdminQueueFilter, limit: i64, offset: i64) -> Result<(Vec<AdminQueueItem>, i64)> {
+        let items = sqlx::query_as::<_, AdminQueueItem>(
+            r#"
+            SELECT q.id, q.document_id, d.original_filename AS filename, d.user_id, u.username,
+                   q.status, q.priority, q.attempts, q.max_attempts, q.worker_id, q.error_message,
+                   q.file_size, q.created_at, q.started_at, q.dead_lettered_at,
+                   EXTRACT(EPOCH FROM (NOW() - q.created_at))::bigint AS age_seconds
+            FROM ocr_queue q
+            JOIN documents d ON d.id = q.document_id
+            JOIN users u ON u.id = d.user_id
+            WHERE ($1::text IS NULL OR q.status = $1)
+              AND ($2::uuid IS NULL OR d.user_id = $2)
+            ORDER BY q.priority DESC, q.created_at ASC, q.id
+            LIMIT $3 OFFSET $4
+            "#
+        )
+        .bind(&filter.status)
+        .bind(filter.user_id)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM ocr_queue q
+            JOIN documents d ON d.id = q.document_id
+            WHERE ($1::text IS NULL OR q.status = $1)
+              AND ($2::uuid IS NULL OR d.user_id = $2)
+            "#
+        )
+        .bind(&filter.status)
+        .bind(filter.user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok((items, total))
+    }
+
+    /// Sets the priority of a pending job. Returns the job's status, `None` if there is no
+    /// such job; jobs that are not pending are left unchanged.
+    pub async fn set_item_priority(&self, item_id: Uuid, priority: i32) -> Result<Option<String>> {
+        let status = sqlx::query_scalar(
+            r#"
+            WITH item AS (
+                SELECT id, status FROM ocr_queue WHERE id = $1 FOR UPDATE
+            ),
+            updated AS (
+                UPDATE ocr_queue q
+                SET priority = $2
+                FROM item
+                WHERE q.id = item.id AND item.status = 'pending'
+            )
+            SELECT status FROM item
+            "#
+        )
+        .bind(item_id)
+        .bind(priority)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if status.as_deref() == Some("pending") {
+            info!("Set priority of OCR job {} to {}", item_id, priority);
+        }
+        Ok(status)
+    }
+
+    /// Cancels a pending job. Returns the job's status before the call, `None` if there is
+    /// no such job; jobs that are not pending are left unchanged.
+    pub async fn cancel_item(&self, item_id: Uuid, reason: &str) -> Result<Option<String>> {
+        let item: Option<(String, Uuid, Option<Uuid>)> = sqlx::query_as(
+            r#"
+            WITH item AS (
+                SELECT id, status, document_id, batch_job_id FROM ocr_queue WHERE id = $1 FOR UPDATE
+            ),
+            updated AS (
+                UPDATE ocr_queue q
+                SET status = 'cancelled', completed_at = NOW(), error_message = $2
+                FROM item
+                WHERE q.id = item.id AND item.status = 'pending'
+            )
+            SELECT status, document_id, batch_job_id FROM item
+            "#
+        )
+        .bind(item_id)
+        .bind(reason)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some((status, document_id, batch_job_id)) = item else {
+            return Ok(None);
+        };
+        if status == "pending" {
+            info!("Cancelled OCR job {}: {}", item_id, reason);
+            self.record_history(document_id, DocumentEventType::OcrCancelled, Some(reason), false).await;
+            // A cancelled document counts as failed so its batch job can still finish
+            if let Some(batch_job_id) = batch_job_id {
+                self.report_to_batch_job(batch_job_id, document_id, false).await;
+            }
+        }
+        Ok(Some(status))
+    }
+
+    /// Number of jobs in the dead-letter queue
+    pub async fn count_dead_letter_items(&self) -> Result<i64> {
+        let count = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue WHERE status = 'dead_letter'")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(count)
+    }
+
+    /// Puts a job from the dead-letter queue back in the queue with its attempts reset, and
+    /// sets its document back to pending. Returns `None` if there is no such job.
+    pub async fn requeue_dead_letter_item(&self, item_id: Uuid) -> Result<Option<DeadLetterRequeue>> {
+        let mut tx = self.pool.begin().await?;
+
+        let item: Option<(String, Uuid)> = sqlx::query_as(
+            "SELECT status, document_id FROM ocr_queue WHERE id = $1 FOR UPDATE"
+        )
+        .bind(item_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some((status, document_id)) = item else {
+            return Ok(None);
+        };
+        if status != "dead_letter" {
+            return Ok(Some(DeadLetterRequeue::NotDeadLettered(status)));
+        }
+
+        let already_queued: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM ocr_queue WHERE document_id = $1 AND status IN ('pending', 'processing'))"
+        )
+        .bind(document_id)
+        .fetch_one(&mut *tx)
+        .await?;
+        if already_queued {
+            return Ok(Some(DeadLetterRequeue::AlreadyQueued));
+        }
+
+        // The batch job that queued it was already told it failed
+        sqlx::query(
+            r#"
+            UPDATE ocr_queue
+            SET status = 'pending',
+                attempts = 0,
+                error_message = NULL,
+                dead_lettered_at = NULL,
+                started_at = NULL,
+                completed_at = NULL,
+                worker_id = NULL,
+                batch_job_id = NULL
+            WHERE id = $1
+            "#
+        )
+        .bind(item_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE documents
+            SET ocr_status = 'pending',
+                ocr_error = NULL,
+                ocr_failure_reason = NULL,
+                updated_at = NOW()
+            WHERE id = $1
+            "#
+        )
+        .bind(document_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        info!("Requeued OCR job {} from the dead-letter queue", item_id);
+        self.record_history(document_id, DocumentEventType::Queued, Some("requeued from the dead-letter queue"), false).await;
+        Ok(Some(DeadLetterRequeue::Requeued))
+    }
+
     /// Handle stale processing items (worker crashed)
     pub async fn recover_stale_items(&self, stale_minutes: i32) -> Result<i64> {
         let result = sqlx::query(
@@ -1213,4 +2437,30 @@ impl OcrQueueService {
             ("other", false)  // Fallback for any unrecognized errors
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worker_limit_respects_pool_share() {
+        // 15 configured jobs on a 30 connection pool limited to half of it
+        assert_eq!(ocr_worker_limit(15, 30, 0.5), 15);
+        assert_eq!(ocr_worker_limit(20, 30, 0.5), 15);
+        assert_eq!(ocr_worker_limit(4, 30, 0.5), 4);
+        assert_eq!(ocr_worker_limit(8, 10, 0.25), 2);
+        // Never stalls the queue entirely
+        assert_eq!(ocr_worker_limit(8, 1, 0.5), 1);
+        assert_eq!(ocr_worker_limit(0, 30, 0.5), 1);
+    }
+
+    #[test]
+    fn test_pool_saturation() {
+        let health = |size, num_idle| DatabasePoolHealth { size, num_idle, is_closed: false };
+        assert!(pool_is_saturated(&health(30, 0), 30));
+        assert!(!pool_is_saturated(&health(30, 2), 30));
+        // Still able to open new connections
+        assert!(!pool_is_saturated(&health(12, 0), 30));
+    }
+}
@@ -0,0 +1,136 @@
+/*!
+ * Script Detection
+ *
+ * Tesseract's orientation and script detection (`--psm 0`, the `osd` model) reports
+ * which writing system a page is in. Comparing it with the scripts of the configured
+ * OCR languages catches documents recognized with the wrong language, e.g. a Russian
+ * letter OCRed as English, which otherwise only shows up as garbled text.
+ */
+
+use serde::{Deserialize, Serialize};
+
+/// Script confidence below which a detection is not trusted enough to report a
+/// mismatch. Tesseract's script confidence is a relative score, not a percentage;
+/// clean pages usually score well above this, pages with little text below it.
+pub const MIN_SCRIPT_CONFIDENCE: f32 = 5.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScriptDetection {
+    /// Script name as Tesseract reports it, e.g. `Latin` or `Cyrillic`
+    pub script: String,
+    pub script_confidence: f32,
+    /// Clockwise rotation of the page in degrees, if reported
+    pub orientation_degrees: Option<i32>,
+}
+
+impl ScriptDetection {
+    pub fn is_confident(&self) -> bool {
+        self.script_confidence >= MIN_SCRIPT_CONFIDENCE
+    }
+}
+
+/// Parses the output of `tesseract <image> stdout --psm 0`. Returns `None` when it
+/// names no script, e.g. because the page had too little text.
+pub fn parse_osd_output(output: &str) -> Option<ScriptDetection> {
+    let mut script = None;
+    let mut script_confidence = None;
+    let mut orientation_degrees = None;
+
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else { continue };
+        let value = value.trim();
+        match key.trim() {
+            "Script" => script = Some(value.to_string()).filter(|script| !script.is_empty()),
+            "Script confidence" => script_confidence = value.parse().ok(),
+            "Orientation in degrees" => orientation_degrees = value.parse().ok(),
+            _ => {}
+        }
+    }
+
+    Some(ScriptDetection {
+        script: script?,
+        script_confidence: script_confidence.unwrap_or(0.0),
+        orientation_degrees,
+    })
+}
+
+/// Scripts Tesseract's OSD may report for text in a Tesseract language, or `None`
+/// for languages not covered here
+pub fn scripts_for_language(lang: &str) -> Option<&'static [&'static str]> {
+    let scripts: &'static [&'static str] = match lang {
+        "eng" | "deu" | "fra" | "spa" | "ita" | "por" | "nld" | "swe" | "nor" | "dan" | "fin" | "pol"
+        | "ces" | "slk" | "slv" | "hrv" | "hun" | "ron" | "tur" | "est" | "lav" | "lit" | "cat" | "isl"
+        | "gle" | "vie" | "ind" | "msa" | "lat" | "frk" => &["Latin", "Fraktur"],
+        "rus" | "ukr" | "bel" | "bul" | "srp" | "mkd" | "kaz" | "kir" | "mon" => &["Cyrillic"],
+        "ell" | "grc" => &["Greek"],
+        "ara" | "fas" | "urd" | "pus" => &["Arabic"],
+        "heb" | "yid" => &["Hebrew"],
+        "chi_sim" | "chi_tra" => &["Han"],
+        "jpn" => &["Japanese", "Han", "Hiragana", "Katakana"],
+        "kor" => &["Hangul", "Han"],
+        "hin" | "mar" | "nep" | "san" => &["Devanagari"],
+        "tha" => &["Thai"],
+        _ => return None,
+    };
+    Some(scripts)
+}
+
+/// Checks a confident detection against an OCR language setting such as `eng` or
+/// `deu+rus`. Returns the scripts the languages expect when the detected script is
+/// none of them. Unknown languages and uncertain detections never count as a mismatch.
+pub fn script_mismatch(languages: &str, detection: &ScriptDetection) -> Option<Vec<&'static str>> {
+    if !detection.is_confident() {
+        return None;
+    }
+
+    let mut expected: Vec<&'static str> = Vec::new();
+    for lang in languages.split('+').map(str::trim).filter(|lang| !lang.is_empty()) {
+        for script in scripts_for_language(lang)? {
+            if !expected.contains(script) {
+                expected.push(script);
+            }
+        }
+    }
+
+    if expected.is_empty() || expected.iter().any(|script| script.eq_ignore_ascii_case(&detection.script)) {
+        None
+    } else {
+        Some(expected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detection(script: &str, script_confidence: f32) -> ScriptDetection {
+        ScriptDetection { script: script.to_string(), script_confidence, orientation_degrees: Some(0) }
+    }
+
+    #[test]
+    fn test_parse_osd_output() {
+        let output = "Page number: 0\nOrientation in degrees: 90\nRotate: 270\n\
+                      Orientation confidence: 12.43\nScript: Cyrillic\nScript confidence: 8.17\n";
+        assert_eq!(parse_osd_output(output), Some(ScriptDetection {
+            script: "Cyrillic".to_string(),
+            script_confidence: 8.17,
+            orientation_degrees: Some(90),
+        }));
+
+        assert_eq!(parse_osd_output("Too few characters. Skipping this page\n"), None);
+        assert_eq!(parse_osd_output("Script: \nScript confidence: 0.00\n"), None);
+    }
+
+    #[test]
+    fn test_script_mismatch() {
+        assert_eq!(script_mismatch("eng", &detection("Cyrillic", 9.0)), Some(vec!["Latin", "Fraktur"]));
+        assert_eq!(script_mismatch("eng", &detection("Latin", 9.0)), None);
+        assert_eq!(script_mismatch("eng+rus", &detection("Cyrillic", 9.0)), None);
+        assert_eq!(script_mismatch("rus", &detection("Latin", 12.0)), Some(vec!["Cyrillic"]));
+        assert_eq!(script_mismatch("jpn", &detection("Katakana", 9.0)), None);
+
+        // Weak detections and languages without a known script are not judged
+        assert_eq!(script_mismatch("eng", &detection("Cyrillic", 1.5)), None);
+        assert_eq!(script_mismatch("eng+xyz", &detection("Cyrillic", 9.0)), None);
+    }
+}
@@ -0,0 +1,124 @@
+/*!
+ * Multi-Page TIFF Frames
+ *
+ * Fax and scanner workflows often deliver every page of a document as a frame
+ * of a single TIFF. The `image` crate only decodes the first frame, so frames
+ * are enumerated with the `tiff` decoder and written out one at a time as PNG
+ * page images that the regular image OCR can read.
+ */
+
+#[cfg(feature = "ocr")]
+use anyhow::{anyhow, Result};
+#[cfg(feature = "ocr")]
+use std::path::Path;
+
+/// Number of frames (pages) in a TIFF file. Only the directory of each frame is
+/// read, not its image data.
+#[cfg(feature = "ocr")]
+pub fn count_frames(tiff_path: &Path) -> Result<usize> {
+    let mut decoder = open_decoder(tiff_path)?;
+    let mut frames = 1;
+    while decoder.more_images() {
+        decoder.next_image()?;
+        frames += 1;
+    }
+    Ok(frames)
+}
+
+/// Decodes frame `index` (0-based) of a TIFF file and saves it as a PNG at `output_path`
+#[cfg(feature = "ocr")]
+pub fn write_frame_png(tiff_path: &Path, index: usize, output_path: &Path) -> Result<()> {
+    let mut decoder = open_decoder(tiff_path)?;
+    decoder.seek_to_image(index)?;
+    decode_frame(&mut decoder)?.save_with_format(output_path, image::ImageFormat::Png)?;
+    Ok(())
+}
+
+#[cfg(feature = "ocr")]
+fn open_decoder(tiff_path: &Path) -> Result<tiff::decoder::Decoder<std::io::BufReader<std::fs::File>>> {
+    let file = std::io::BufReader::new(std::fs::File::open(tiff_path)?);
+    Ok(tiff::decoder::Decoder::new(file)?)
+}
+
+/// Converts the current frame to an image, supporting the same color types as the
+/// `image` crate's own TIFF decoder
+#[cfg(feature = "ocr")]
+fn decode_frame<R: std::io::Read + std::io::Seek>(decoder: &mut tiff::decoder::Decoder<R>) -> Result<image::DynamicImage> {
+    use image::{DynamicImage, ImageBuffer};
+    use tiff::{decoder::DecodingResult, ColorType};
+
+    let (width, height) = decoder.dimensions()?;
+    let color_type = decoder.colortype()?;
+    let too_small = || anyhow!("TIFF frame data is smaller than its {}x{} dimensions", width, height);
+
+    let image = match (color_type, decoder.read_image()?) {
+        (ColorType::Gray(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageLuma8(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (ColorType::Gray(16), DecodingResult::U16(data)) => {
+            DynamicImage::ImageLuma16(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (ColorType::GrayA(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageLumaA8(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (ColorType::RGB(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (ColorType::RGB(16), DecodingResult::U16(data)) => {
+            DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (ColorType::RGBA(8), DecodingResult::U8(data)) => {
+            DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (ColorType::RGBA(16), DecodingResult::U16(data)) => {
+            DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, data).ok_or_else(too_small)?)
+        }
+        (color_type, _) => return Err(anyhow!("Unsupported TIFF frame color type: {:?}", color_type)),
+    };
+    Ok(image)
+}
+
+#[cfg(all(test, feature = "ocr"))]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma, RgbImage, Rgb};
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    fn write_tiff(path: &Path, frames: usize) {
+        let mut encoder = TiffEncoder::new(std::fs::File::create(path).unwrap()).unwrap();
+        for frame in 0..frames {
+            let shade = (frame * 40) as u8;
+            let page = GrayImage::from_pixel(8 + frame as u32, 6, Luma([shade]));
+            encoder.write_image::<colortype::Gray8>(page.width(), page.height(), page.as_raw()).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_frames_are_counted_and_written_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let tiff_path = dir.path().join("fax.tiff");
+        write_tiff(&tiff_path, 3);
+
+        assert_eq!(count_frames(&tiff_path).unwrap(), 3);
+
+        let page_path = dir.path().join("page-3.png");
+        write_frame_png(&tiff_path, 2, &page_path).unwrap();
+        let page = image::open(&page_path).unwrap().to_luma8();
+        assert_eq!(page.dimensions(), (10, 6));
+        assert_eq!(page.get_pixel(0, 0), &Luma([80]));
+
+        assert!(write_frame_png(&tiff_path, 3, &dir.path().join("missing.png")).is_err());
+    }
+
+    #[test]
+    fn test_single_frame_tiff() {
+        let dir = tempfile::tempdir().unwrap();
+        let tiff_path = dir.path().join("scan.tiff");
+        RgbImage::from_pixel(4, 4, Rgb([200, 10, 10])).save(&tiff_path).unwrap();
+
+        assert_eq!(count_frames(&tiff_path).unwrap(), 1);
+        let page_path = dir.path().join("page-1.png");
+        write_frame_png(&tiff_path, 0, &page_path).unwrap();
+        assert_eq!(image::open(&page_path).unwrap().to_rgb8().get_pixel(3, 3), &Rgb([200, 10, 10]));
+    }
+}
@@ -0,0 +1,115 @@
+/*!
+ * Word-Level OCR Results
+ *
+ * Tesseract's TSV output lists every recognized word with its bounding box and
+ * confidence. These are kept per page so a viewer can lay a selectable text
+ * layer over the rendered document.
+ */
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Tesseract TSV levels of a page row and a word row
+const TSV_LEVEL_PAGE: i32 = 1;
+const TSV_LEVEL_WORD: i32 = 5;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OcrWord {
+    pub text: String,
+    /// Tesseract confidence, 0-100
+    pub confidence: f32,
+    /// `[x, y, width, height]` in pixels of the page image
+    pub bbox: [i32; 4],
+    /// 1-based page number
+    pub page: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct OcrPageWords {
+    /// 1-based page number
+    pub page: i32,
+    /// Size in pixels of the page image the boxes refer to
+    pub width: i32,
+    pub height: i32,
+    pub words: Vec<OcrWord>,
+}
+
+/// Whether word boxes are extracted for documents of this MIME type
+pub fn supports_word_boxes(mime_type: &str) -> bool {
+    mime_type == "application/pdf" || mime_type.starts_with("image/")
+}
+
+/// Parses Tesseract TSV output for one page image. The header row, if present, and
+/// rows without recognized text (blocks, lines, empty words) are skipped.
+pub fn parse_tsv(tsv: &str, page: i32) -> OcrPageWords {
+    let mut page_words = OcrPageWords { page, width: 0, height: 0, words: Vec::new() };
+
+    for line in tsv.lines() {
+        let fields: Vec<&str> = line.splitn(12, '\t').collect();
+        if fields.len() < 11 {
+            continue;
+        }
+        let Ok(level) = fields[0].parse::<i32>() else { continue };
+        let numbers: Option<Vec<i32>> = fields[6..10].iter().map(|field| field.parse().ok()).collect();
+        let Some(&[left, top, width, height]) = numbers.as_deref() else { continue };
+
+        match level {
+            TSV_LEVEL_PAGE => {
+                page_words.width = width;
+                page_words.height = height;
+            }
+            TSV_LEVEL_WORD => {
+                let confidence: f32 = fields[10].parse().unwrap_or(-1.0);
+                let text = fields.get(11).map(|text| text.trim()).unwrap_or_default();
+                if confidence < 0.0 || text.is_empty() {
+                    continue;
+                }
+                page_words.words.push(OcrWord {
+                    text: text.to_string(),
+                    confidence,
+                    bbox: [left, top, width, height],
+                    page,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    page_words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tsv_keeps_recognized_words() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+                   1\t1\t0\t0\t0\t0\t0\t0\t2480\t3508\t-1\t\n\
+                   2\t1\t1\t0\t0\t0\t120\t200\t900\t60\t-1\t\n\
+                   5\t1\t1\t1\t1\t1\t120\t200\t310\t58\t96.4\tInvoice\n\
+                   5\t1\t1\t1\t1\t2\t450\t201\t180\t57\t91.02\t4711\n\
+                   5\t1\t1\t1\t1\t3\t650\t201\t10\t57\t95\t \n";
+
+        let page = parse_tsv(tsv, 3);
+        assert_eq!((page.page, page.width, page.height), (3, 2480, 3508));
+        assert_eq!(page.words, vec![
+            OcrWord { text: "Invoice".to_string(), confidence: 96.4, bbox: [120, 200, 310, 58], page: 3 },
+            OcrWord { text: "4711".to_string(), confidence: 91.02, bbox: [450, 201, 180, 57], page: 3 },
+        ]);
+    }
+
+    #[test]
+    fn test_parse_tsv_ignores_malformed_rows() {
+        let page = parse_tsv("garbage\n5\t1\t1\t1\t1\t1\tx\t0\t1\t1\t90\tword\n", 1);
+        assert!(page.words.is_empty());
+        assert_eq!((page.width, page.height), (0, 0));
+    }
+
+    #[test]
+    fn test_supports_word_boxes() {
+        assert!(supports_word_boxes("application/pdf"));
+        assert!(supports_word_boxes("image/tiff"));
+        assert!(!supports_word_boxes("text/plain"));
+    }
+}
@@ -0,0 +1,91 @@
+/*!
+ * OCR Working Directories
+ *
+ * Every OCR job gets its own scratch directory, named by a UUID rather than the
+ * process id, so concurrent jobs in one process never share intermediate files
+ * such as ocrmypdf sidecars. The directory is removed when its guard is dropped,
+ * which also happens when the job fails, is cancelled or panics.
+ */
+
+use std::path::{Path, PathBuf};
+use tracing::warn;
+use uuid::Uuid;
+
+/// Base directory for OCR scratch files: `TEMP_DIR`, or `/tmp` when unset
+pub fn base_temp_dir() -> String {
+    std::env::var("TEMP_DIR").unwrap_or_else(|_| "/tmp".to_string())
+}
+
+/// A unique directory for one OCR job's intermediate files, removed on drop
+#[derive(Debug)]
+pub struct OcrWorkDir {
+    path: PathBuf,
+}
+
+impl OcrWorkDir {
+    /// Creates `<base>/ocr_job_<uuid>`, and `base` itself if it is missing
+    pub fn create(base: impl AsRef<Path>) -> std::io::Result<Self> {
+        let path = base.as_ref().join(format!("ocr_job_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self { path })
+    }
+
+    /// Creates a work directory under `TEMP_DIR`
+    pub fn in_temp_dir() -> std::io::Result<Self> {
+        Self::create(base_temp_dir())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Path of a file called `name` inside the directory, as the string the OCR tools take
+    pub fn file(&self, name: &str) -> String {
+        self.path.join(name).to_string_lossy().to_string()
+    }
+}
+
+impl Drop for OcrWorkDir {
+    // Synchronous on purpose: drop also runs while a panic unwinds, where nothing can be awaited
+    fn drop(&mut self) {
+        match std::fs::remove_dir_all(&self.path) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to remove OCR work directory {}: {}", self.path.display(), e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_work_dirs_are_unique_and_removed_on_drop() {
+        let base = tempfile::tempdir().unwrap();
+        let first = OcrWorkDir::create(base.path()).unwrap();
+        let second = OcrWorkDir::create(base.path()).unwrap();
+        assert_ne!(first.path(), second.path());
+
+        std::fs::write(first.file("sidecar.txt"), "first").unwrap();
+        let first_path = first.path().to_path_buf();
+        drop(first);
+        assert!(!first_path.exists());
+        assert!(second.path().exists());
+    }
+
+    #[test]
+    fn test_work_dir_removed_when_job_panics() {
+        let base = tempfile::tempdir().unwrap();
+        let base_path = base.path().to_path_buf();
+
+        let outcome = std::panic::catch_unwind(move || {
+            let work_dir = OcrWorkDir::create(&base_path).unwrap();
+            std::fs::write(work_dir.file("page-1.png"), b"not really a png").unwrap();
+            panic!("OCR failed");
+        });
+
+        assert!(outcome.is_err());
+        assert_eq!(std::fs::read_dir(base.path()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,150 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    models::{DocumentRun, MaintenanceDetails, MaintenanceOperation},
+    routes::queue::require_admin,
+    services::{
+        database_maintenance_service::DatabaseMaintenanceService,
+        document_runs::{start_document_run, StartRunError},
+    },
+    AppState,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/maintenance/reindex", post(start_reindex))
+        .route("/maintenance/vacuum", post(start_vacuum))
+        .route("/maintenance/{id}", get(get_maintenance_run))
+}
+
+/// Start a rebuild of the document search indexes
+///
+/// Rebuilds the full-text and trigram search indexes one at a time with
+/// `REINDEX INDEX CONCURRENTLY`, then runs `ANALYZE documents`. Documents can still be
+/// read and written meanwhile. Poll the returned run for progress and per-step timing.
+#[utoipa::path(
+    post,
+    path = "/api/admin/maintenance/reindex",
+    tag = "admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 202, description = "Reindex started", body = DocumentRun<MaintenanceDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 409, description = "A maintenance run is already running"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn start_reindex(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<(StatusCode, Json<DocumentRun<MaintenanceDetails>>), StatusCode> {
+    start_maintenance(state, auth_user, MaintenanceOperation::Reindex).await
+}
+
+/// Start a vacuum of the documents and OCR queue tables
+///
+/// Runs `VACUUM (ANALYZE)` on `documents` and `ocr_queue` to reclaim space left by
+/// updated and deleted rows. `VACUUM FULL` is never used, so the tables stay writable.
+/// Poll the returned run for progress and per-step timing.
+#[utoipa::path(
+    post,
+    path = "/api/admin/maintenance/vacuum",
+    tag = "admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 202, description = "Vacuum started", body = DocumentRun<MaintenanceDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 409, description = "A maintenance run is already running"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn start_vacuum(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<(StatusCode, Json<DocumentRun<MaintenanceDetails>>), StatusCode> {
+    start_maintenance(state, auth_user, MaintenanceOperation::Vacuum).await
+}
+
+/// Creates a maintenance run and executes it in the background. Only one run, of either
+/// operation, may work on the tables at a time.
+async fn start_maintenance(
+    state: Arc<AppState>,
+    auth_user: AuthUser,
+    operation: MaintenanceOperation,
+) -> Result<(StatusCode, Json<DocumentRun<MaintenanceDetails>>), StatusCode> {
+    require_admin(&auth_user)?;
+
+    let service = DatabaseMaintenanceService::new(state.clone());
+    let steps = service.plan(operation).await.map_err(|e| {
+        error!("Failed to plan {} run: {}", operation, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let details = MaintenanceDetails { operation, steps, completed_steps: 0 };
+    let run = start_document_run(&state, Some(auth_user.user.id), details).await.map_err(|e| match e {
+        StartRunError::AlreadyRunning => StatusCode::CONFLICT,
+        StartRunError::Failed(e) => {
+            error!("Failed to start {} run: {}", operation, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    })?;
+
+    info!("User {} started {} run {}", auth_user.user.id, operation, run.id);
+    tokio::spawn({
+        let run = run.clone();
+        async move { service.run(run).await }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// Get the progress or report of a maintenance run
+#[utoipa::path(
+    get,
+    path = "/api/admin/maintenance/{id}",
+    tag = "admin",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Maintenance run ID")
+    ),
+    responses(
+        (status = 200, description = "Maintenance run", body = DocumentRun<MaintenanceDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Maintenance run not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_maintenance_run(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(run_id): Path<Uuid>,
+) -> Result<Json<DocumentRun<MaintenanceDetails>>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let run = state.db.get_document_run::<MaintenanceDetails>(run_id).await
+        .map_err(|e| {
+            error!("Failed to get maintenance run {}: {}", run_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(run))
+}
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json, Response, Redirect},
     routing::{get, post},
     Router,
@@ -9,8 +9,14 @@ use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::{
-    auth::{create_jwt, AuthUser},
-    models::{CreateUser, LoginRequest, LoginResponse, UserResponse, UserRole},
+    auth::{create_jwt, extract_token_from_headers, verify_jwt, AuthUser, ClientIp},
+    models::{
+        AuthProvider, CreateUser, ForgotPasswordRequest, LoginRequest, LoginResponse,
+        ResetPasswordRequest, TwoFactorChallengeResponse,
+        TwoFactorCodeRequest, TwoFactorLoginRequest, TwoFactorSetupResponse,
+        TwoFactorVerifyResponse, User, UserResponse, UserRole,
+    },
+    services::{email_service::EmailService, password_reset_service, two_factor_service},
     AppState,
 };
 
@@ -19,6 +25,13 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/register", post(register))
         .route("/login", post(login))
         .route("/me", get(me))
+        .route("/impersonate/stop", post(stop_impersonation))
+        .route("/2fa/setup", post(setup_two_factor))
+        .route("/2fa/verify", post(verify_two_factor))
+        .route("/2fa/disable", post(disable_two_factor))
+        .route("/2fa/login", post(two_factor_login))
+        .route("/forgot-password", post(forgot_password))
+        .route("/reset-password", post(reset_password))
         .route("/oidc/login", get(oidc_login))
         .route("/oidc/callback", get(oidc_callback))
 }
@@ -75,42 +88,363 @@ async fn register(
     tag = "auth",
     request_body = LoginRequest,
     responses(
-        (status = 200, description = "Login successful", body = LoginResponse),
+        (status = 200, description = "Login successful, or a 2FA challenge when the account has 2FA enabled", body = LoginResponse),
         (status = 401, description = "Unauthorized - invalid credentials"),
+        (status = 429, description = "Too many failed attempts - see the Retry-After header"),
         (status = 500, description = "Internal server error")
     )
 )]
 async fn login(
     State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
     Json(login_data): Json<LoginRequest>,
-) -> Result<Json<LoginResponse>, StatusCode> {
+) -> Result<Response, Response> {
+    if let Err(retry_after) = state.login_rate_limiter.check(&login_data.username, &client_ip) {
+        tracing::warn!("Rejecting login for '{}' from {}: locked out", login_data.username, client_ip);
+        return Err(too_many_attempts(retry_after));
+    }
+
+    let record_failure = || {
+        match state.login_rate_limiter.record_failure(&login_data.username, &client_ip) {
+            Some(lockout) => too_many_attempts(lockout),
+            None => StatusCode::UNAUTHORIZED.into_response(),
+        }
+    };
+
     let user = state
         .db
         .get_user_by_username(&login_data.username)
         .await
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .ok_or_else(record_failure)?;
 
     let password_hash = user.password_hash
         .as_ref()
-        .ok_or(StatusCode::UNAUTHORIZED)?; // OIDC users don't have passwords
+        .ok_or_else(record_failure)?; // OIDC users don't have passwords
         
     let is_valid = bcrypt::verify(&login_data.password, password_hash)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
 
     if !is_valid {
-        return Err(StatusCode::UNAUTHORIZED);
+        return Err(record_failure());
     }
 
+    let totp_state = state
+        .db
+        .get_user_totp_state(user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    if totp_state.map(|s| s.enabled).unwrap_or(false) {
+        let challenge_token = two_factor_service::create_challenge_token(user.id, &state.config.jwt_secret)
+            .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+        // The counter is only cleared once the second factor is accepted too
+        return Ok(Json(TwoFactorChallengeResponse {
+            requires_2fa: true,
+            challenge_token,
+        }).into_response());
+    }
+
+    state.login_rate_limiter.record_success(&login_data.username);
+
     let token = create_jwt(&user, &state.config.jwt_secret)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    invalidate_reset_tokens_after_login(&state, &user).await;
 
     Ok(Json(LoginResponse {
         token,
         user: user.into(),
+    }).into_response())
+}
+
+fn too_many_attempts(retry_after: std::time::Duration) -> Response {
+    // Round up so clients never retry a moment too early
+    let retry_after_secs = retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0);
+
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after_secs.to_string())],
+        Json(serde_json::json!({
+            "error": "Too many failed login attempts. Please try again later.",
+            "retry_after_seconds": retry_after_secs
+        })),
+    ).into_response()
+}
+
+/// A successful login proves the user knows their password, so any pending reset links are revoked
+async fn invalidate_reset_tokens_after_login(state: &AppState, user: &User) {
+    if let Err(e) = state.db.invalidate_password_reset_tokens(user.id).await {
+        tracing::warn!("Failed to invalidate password reset tokens for user {}: {}", user.username, e);
+    }
+}
+
+/// Checks a TOTP code and uses it up. A code of a time step at or before the last one
+/// accepted for the user is refused, so a code seen by someone else can't be replayed.
+async fn accept_totp_code(
+    state: &AppState,
+    user: &User,
+    secret: &str,
+    code: &str,
+) -> Result<bool, StatusCode> {
+    let step = two_factor_service::matching_step(secret, &user.username, code)
+        .map_err(|e| {
+            tracing::error!("Failed to verify TOTP code: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let Some(step) = step else {
+        return Ok(false);
+    };
+
+    let fresh = state
+        .db
+        .record_totp_step(user.id, step as i64)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    if !fresh {
+        tracing::warn!("Refusing reused TOTP code for user {}", user.username);
+    }
+    Ok(fresh)
+}
+
+/// Checks a TOTP code, falling back to consuming a recovery code
+async fn verify_second_factor(
+    state: &AppState,
+    user: &User,
+    secret: &str,
+    code: &str,
+) -> Result<bool, StatusCode> {
+    if accept_totp_code(state, user, secret, code).await? {
+        return Ok(true);
+    }
+
+    let code_hash = two_factor_service::hash_recovery_code(code);
+    state
+        .db
+        .consume_recovery_code(user.id, &code_hash)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/setup",
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "New TOTP secret generated; 2FA is enabled once a code is verified", body = TwoFactorSetupResponse),
+        (status = 400, description = "2FA is only available for local accounts"),
+        (status = 401, description = "Unauthorized - invalid or missing token"),
+        (status = 409, description = "2FA is already enabled"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn setup_two_factor(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<Json<TwoFactorSetupResponse>, StatusCode> {
+    if auth_user.user.auth_provider != AuthProvider::Local {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let totp_state = state
+        .db
+        .get_user_totp_state(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    if totp_state.map(|s| s.enabled).unwrap_or(false) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let secret = two_factor_service::generate_secret();
+    let otpauth_url = two_factor_service::otpauth_url(&secret, &auth_user.user.username)
+        .map_err(|e| {
+            tracing::error!("Failed to build otpauth URL: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    state
+        .db
+        .set_user_totp_secret(auth_user.user.id, &secret)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    Ok(Json(TwoFactorSetupResponse { secret, otpauth_url }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/verify",
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "Code confirmed and 2FA enabled", body = TwoFactorVerifyResponse),
+        (status = 400, description = "2FA setup has not been started"),
+        (status = 401, description = "Invalid code or token"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn verify_two_factor(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<Json<TwoFactorVerifyResponse>, StatusCode> {
+    let secret = state
+        .db
+        .get_user_totp_state(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .and_then(|s| s.secret)
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    if !accept_totp_code(&state, &auth_user.user, &secret, &request.code).await? {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    let recovery_codes = two_factor_service::generate_recovery_codes();
+    let hashes: Vec<String> = recovery_codes
+        .iter()
+        .map(|code| two_factor_service::hash_recovery_code(code))
+        .collect();
+
+    state
+        .db
+        .enable_user_totp(auth_user.user.id, &hashes)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    tracing::info!("Two-factor authentication enabled for user {}", auth_user.user.username);
+
+    Ok(Json(TwoFactorVerifyResponse {
+        enabled: true,
+        recovery_codes,
     }))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/disable",
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = TwoFactorCodeRequest,
+    responses(
+        (status = 200, description = "2FA disabled"),
+        (status = 400, description = "2FA is not enabled"),
+        (status = 401, description = "Invalid code or token"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn disable_two_factor(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<TwoFactorCodeRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let totp_state = state
+        .db
+        .get_user_totp_state(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let secret = match (totp_state.enabled, totp_state.secret) {
+        (true, Some(secret)) => secret,
+        _ => return Err(StatusCode::BAD_REQUEST),
+    };
+
+    if !verify_second_factor(&state, &auth_user.user, &secret, &request.code).await? {
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    state
+        .db
+        .disable_user_totp(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+
+    tracing::info!("Two-factor authentication disabled for user {}", auth_user.user.username);
+
+    Ok(Json(serde_json::json!({ "enabled": false })))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/2fa/login",
+    tag = "auth",
+    request_body = TwoFactorLoginRequest,
+    responses(
+        (status = 200, description = "Second factor accepted", body = LoginResponse),
+        (status = 401, description = "Invalid or expired challenge token, or invalid code"),
+        (status = 429, description = "Too many failed attempts - see the Retry-After header"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn two_factor_login(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    Json(request): Json<TwoFactorLoginRequest>,
+) -> Result<Response, Response> {
+    let claims = two_factor_service::verify_challenge_token(&request.challenge_token, &state.config.jwt_secret)
+        .map_err(|_| StatusCode::UNAUTHORIZED.into_response())?;
+
+    let user = state
+        .db
+        .get_user_by_id(claims.sub)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())?;
+
+    // Wrong codes count against the same limits as wrong passwords, so a fresh challenge
+    // token doesn't buy more guesses
+    if let Err(retry_after) = state.login_rate_limiter.check(&user.username, &client_ip) {
+        tracing::warn!("Rejecting 2FA login for '{}' from {}: locked out", user.username, client_ip);
+        return Err(too_many_attempts(retry_after));
+    }
+
+    let totp_state = state
+        .db
+        .get_user_totp_state(user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?
+        .ok_or_else(|| StatusCode::UNAUTHORIZED.into_response())?;
+
+    let secret = match (totp_state.enabled, totp_state.secret) {
+        (true, Some(secret)) => secret,
+        _ => return Err(StatusCode::UNAUTHORIZED.into_response()),
+    };
+
+    let accepted = verify_second_factor(&state, &user, &secret, &request.code)
+        .await
+        .map_err(IntoResponse::into_response)?;
+    if !accepted {
+        return Err(match state.login_rate_limiter.record_failure(&user.username, &client_ip) {
+            Some(lockout) => too_many_attempts(lockout),
+            None => StatusCode::UNAUTHORIZED.into_response(),
+        });
+    }
+
+    state.login_rate_limiter.record_success(&user.username);
+
+    let token = create_jwt(&user, &state.config.jwt_secret)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())?;
+
+    invalidate_reset_tokens_after_login(&state, &user).await;
+
+    Ok(Json(LoginResponse {
+        token,
+        user: user.into(),
+    }).into_response())
+}
+
 #[utoipa::path(
     get,
     path = "/api/auth/me",
@@ -128,6 +462,175 @@ async fn me(auth_user: AuthUser) -> Json<UserResponse> {
     Json(auth_user.user.into())
 }
 
+/// End an impersonation
+///
+/// Takes the impersonation token and returns a regular session for the admin who started it.
+#[utoipa::path(
+    post,
+    path = "/api/auth/impersonate/stop",
+    tag = "auth",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Session of the impersonating admin", body = LoginResponse),
+        (status = 400, description = "Not an impersonation token"),
+        (status = 401, description = "Invalid or expired token"),
+        (status = 403, description = "The impersonating user is no longer an admin")
+    )
+)]
+async fn stop_impersonation(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> Result<Json<LoginResponse>, StatusCode> {
+    let token = extract_token_from_headers(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let claims = verify_jwt(&token, &state.config.jwt_secret).map_err(|_| StatusCode::UNAUTHORIZED)?;
+    let admin_id = claims.impersonated_by.ok_or(StatusCode::BAD_REQUEST)?;
+
+    let admin = state
+        .db
+        .get_user_by_id(admin_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+    if admin.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let token = create_jwt(&admin, &state.config.jwt_secret).map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    tracing::warn!(
+        admin_id = %admin.id,
+        user_id = %claims.sub,
+        "Admin {} stopped impersonating user {}",
+        admin.username,
+        claims.username
+    );
+
+    Ok(Json(LoginResponse {
+        token,
+        user: admin.into(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/forgot-password",
+    tag = "auth",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 200, description = "If a local account exists for the email, a reset link has been sent")
+    )
+)]
+async fn forgot_password(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ForgotPasswordRequest>,
+) -> Json<serde_json::Value> {
+    // Always answer the same way so the endpoint can't be used to discover accounts
+    let response = Json(serde_json::json!({
+        "message": "If an account with that email exists, a password reset link has been sent"
+    }));
+
+    let user = match state.db.get_user_by_email(request.email.trim()).await {
+        Ok(Some(user)) if user.auth_provider == AuthProvider::Local => user,
+        Ok(_) => return response,
+        Err(e) => {
+            tracing::error!("Failed to look up user for password reset: {}", e);
+            return response;
+        }
+    };
+
+    let token = password_reset_service::generate_reset_token();
+    let expires_at = chrono::Utc::now()
+        + chrono::Duration::minutes(password_reset_service::RESET_TOKEN_EXPIRY_MINUTES);
+
+    if let Err(e) = state
+        .db
+        .create_password_reset_token(user.id, &password_reset_service::hash_reset_token(&token), expires_at)
+        .await
+    {
+        tracing::error!("Failed to store password reset token for user {}: {}", user.username, e);
+        return response;
+    }
+
+    let reset_link = password_reset_service::build_reset_link(state.config.public_base_url.as_deref(), &token);
+    let config = state.config.clone();
+
+    // Send in the background so response timing doesn't reveal whether the account exists
+    tokio::spawn(async move {
+        let email_service = match EmailService::new(&config) {
+            Ok(service) => service,
+            Err(e) => {
+                tracing::error!("Failed to initialize email service: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = email_service
+            .send_password_reset(
+                &user.email,
+                &user.username,
+                &reset_link,
+                password_reset_service::RESET_TOKEN_EXPIRY_MINUTES,
+            )
+            .await
+        {
+            tracing::error!("Failed to send password reset email to user {}: {}", user.username, e);
+        }
+    });
+
+    response
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/reset-password",
+    tag = "auth",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password updated"),
+        (status = 400, description = "Invalid or expired token, or password does not meet requirements"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn reset_password(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ResetPasswordRequest>,
+) -> Result<Json<serde_json::Value>, Response> {
+    if request.new_password.len() < password_reset_service::MIN_PASSWORD_LENGTH {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({
+                "error": format!(
+                    "Password must be at least {} characters",
+                    password_reset_service::MIN_PASSWORD_LENGTH
+                )
+            })),
+        ).into_response());
+    }
+
+    let invalid_token = || {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(serde_json::json!({ "error": "Invalid or expired reset token" })),
+        ).into_response()
+    };
+
+    let token_hash = password_reset_service::hash_reset_token(&request.token);
+    let user_id = state
+        .db
+        .reset_password_with_token(&token_hash, &request.new_password)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to reset password: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?
+        .ok_or_else(invalid_token)?;
+
+    tracing::info!("Password reset completed for user {}", user_id);
+
+    Ok(Json(serde_json::json!({ "message": "Password has been reset" })))
+}
+
 #[derive(Deserialize)]
 struct OidcCallbackQuery {
     code: Option<String>,
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header::CONTENT_TYPE, HeaderMap, StatusCode},
     response::Json,
 };
 use std::sync::Arc;
@@ -8,10 +8,20 @@ use tracing::{debug, error, info, warn};
 
 use crate::{
     auth::AuthUser,
-    services::file_service::FileService,
+    models::{Document, UserRole},
+    scheduling::retention_cleanup::apply_retention_policies,
+    services::{
+        file_service::FileService,
+        metadata_import_service::{self, MetadataImportError, MetadataImportFormat, MetadataImportRowStatus},
+        source_file_deletion_service::{delete_source_files, plan_source_deletions},
+    },
     AppState,
 };
-use super::types::{BulkDeleteRequest, DeleteLowConfidenceRequest, BulkDeleteResponse};
+use super::crud::DocumentError;
+use super::types::{
+    BulkDeleteRequest, DeleteLowConfidenceRequest, BulkDeleteResponse, RetentionCleanupRequest,
+    RetentionCleanupResponse, BulkAssignSourceRequest, BulkAssignSourceResponse, MetadataImportResponse,
+};
 
 /// Bulk delete multiple documents
 #[utoipa::path(
@@ -23,8 +33,8 @@ use super::types::{BulkDeleteRequest, DeleteLowConfidenceRequest, BulkDeleteResp
     ),
     request_body = BulkDeleteRequest,
     responses(
-        (status = 200, description = "Bulk delete results", body = BulkDeleteResponse),
-        (status = 400, description = "Bad request"),
+        (status = 200, description = "Bulk delete results, including the outcome of deleting files on their sources", body = BulkDeleteResponse),
+        (status = 400, description = "Bad request, or files would be deleted on their sources without `confirm`"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -33,31 +43,64 @@ pub async fn bulk_delete_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
     Json(request): Json<BulkDeleteRequest>,
-) -> Result<Json<BulkDeleteResponse>, StatusCode> {
+) -> Result<Json<BulkDeleteResponse>, DocumentError> {
     if request.document_ids.is_empty() {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(DocumentError::BadRequest("No documents to delete".to_string()));
     }
 
     if request.document_ids.len() > 1000 {
-        return Err(StatusCode::BAD_REQUEST);
+        return Err(DocumentError::BadRequest("At most 1000 documents can be deleted at once".to_string()));
     }
 
     info!("Bulk deleting {} documents", request.document_ids.len());
 
-    // Get documents first to check access and collect file paths
-    let mut documents_to_delete = Vec::new();
-    let mut accessible_ids = Vec::new();
+    let documents = accessible_documents(&state, &auth_user, &request.document_ids).await;
+    let pending = plan_source_deletions(&state.db, &documents, request.source_files)
+        .await
+        .map_err(|e| DocumentError::InternalServerError(format!("Failed to load document sources: {}", e)))?;
+    if !pending.is_empty() && !request.confirm {
+        return Err(DocumentError::BadRequest(format!(
+            "{} of the documents' files would also be deleted on their sources; repeat the request with \"confirm\": true",
+            pending.len()
+        )));
+    }
+
+    let mut response = trash_documents(&state, &auth_user, documents)
+        .await
+        .map_err(|_| DocumentError::InternalServerError("Failed to delete documents".to_string()))?;
+
+    // The local deletion stands whatever happens on the sources
+    let pending = pending.into_iter().filter(|p| response.deleted_documents.contains(&p.document_id)).collect();
+    response.source_files = delete_source_files(pending, &state.source_secrets).await;
 
-    for document_id in &request.document_ids {
+    info!("Bulk delete completed: {} deleted, {} failed", 
+        response.deleted_count, response.failed_count);
+
+    Ok(Json(response))
+}
+
+/// Moves the documents the user may access to the trash, together with their files.
+/// Files on the sources the documents were synced from are kept.
+pub(crate) async fn delete_documents_with_files(
+    state: &AppState,
+    auth_user: &AuthUser,
+    document_ids: &[uuid::Uuid],
+) -> Result<BulkDeleteResponse, StatusCode> {
+    let documents = accessible_documents(state, auth_user, document_ids).await;
+    trash_documents(state, auth_user, documents).await
+}
+
+/// The documents among `document_ids` the user may access
+async fn accessible_documents(state: &AppState, auth_user: &AuthUser, document_ids: &[uuid::Uuid]) -> Vec<Document> {
+    let mut documents = Vec::new();
+
+    for document_id in document_ids {
         match state
             .db
             .get_document_by_id(*document_id, auth_user.user.id, auth_user.user.role)
             .await
         {
-            Ok(Some(document)) => {
-                documents_to_delete.push(document);
-                accessible_ids.push(*document_id);
-            }
+            Ok(Some(document)) => documents.push(document),
             Ok(None) => {
                 debug!("Document {} not found or access denied", document_id);
             }
@@ -67,6 +110,17 @@ pub async fn bulk_delete_documents(
         }
     }
 
+    documents
+}
+
+/// Moves the documents to the trash together with their files
+async fn trash_documents(
+    state: &AppState,
+    auth_user: &AuthUser,
+    documents_to_delete: Vec<Document>,
+) -> Result<BulkDeleteResponse, StatusCode> {
+    let accessible_ids: Vec<uuid::Uuid> = documents_to_delete.iter().map(|document| document.id).collect();
+
     // Perform bulk delete from database
     let (deleted_ids, failed_ids) = state
         .db
@@ -77,38 +131,187 @@ pub async fn bulk_delete_documents(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    // Delete associated files
+    // Move associated files to the trash
     let file_service = FileService::new(state.config.upload_path.clone());
     let mut files_deleted = 0;
     let mut files_failed = 0;
 
     for document in documents_to_delete {
         if deleted_ids.contains(&document.id) {
-            match file_service.delete_document_files(&document).await {
-                Ok(_) => files_deleted += 1,
+            match file_service.move_to_trash(&document).await {
+                Ok(trash_path) => {
+                    if trash_path != document.file_path {
+                        if let Err(e) = state.db.update_document_file_path(document.id, &trash_path).await {
+                            error!("Failed to record trash path for document {}: {}", document.id, e);
+                        }
+                    }
+                    files_deleted += 1;
+                }
                 Err(e) => {
-                    warn!("Failed to delete files for document {}: {}", document.id, e);
+                    warn!("Failed to move files for document {} to trash: {}", document.id, e);
                     files_failed += 1;
                 }
             }
         }
     }
 
-    let response = BulkDeleteResponse {
+    Ok(BulkDeleteResponse {
         deleted_count: deleted_ids.len() as i64,
         failed_count: failed_ids.len() as i64,
         deleted_documents: deleted_ids,
         failed_documents: failed_ids,
         total_files_deleted: files_deleted,
         total_files_failed: files_failed,
+        source_files: Vec::new(),
+    })
+}
+
+/// Bulk assign documents to a source
+///
+/// Associates documents with a source, or detaches them from their source when `source_id`
+/// is null. Only the document metadata changes; files are not moved. A document can only be
+/// assigned to a source of its own owner.
+#[utoipa::path(
+    post,
+    path = "/api/documents/bulk/assign-source",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = BulkAssignSourceRequest,
+    responses(
+        (status = 200, description = "Bulk assign results", body = BulkAssignSourceResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn bulk_assign_source(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<BulkAssignSourceRequest>,
+) -> Result<Json<BulkAssignSourceResponse>, StatusCode> {
+    if request.document_ids.is_empty() || request.document_ids.len() > 1000 {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let source = match request.source_id {
+        Some(source_id) => {
+            // Admins may assign to any user's source; the documents must still belong to its owner
+            let source = if auth_user.user.role == UserRole::Admin {
+                state.db.get_source_by_id(source_id).await
+            } else {
+                state.db.get_source(auth_user.user.id, source_id).await
+            };
+            let source = source.map_err(|e| {
+                error!("Database error getting source {}: {}", source_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            Some(source.ok_or(StatusCode::NOT_FOUND)?)
+        }
+        None => None,
     };
 
-    info!("Bulk delete completed: {} deleted, {} failed", 
-        response.deleted_count, response.failed_count);
+    let (updated_ids, failed_ids) = state
+        .db
+        .bulk_assign_document_source(&request.document_ids, source.as_ref(), auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error during bulk source assignment: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    match &source {
+        Some(source) => info!("Assigned {} documents to source {} ({} failed)", updated_ids.len(), source.id, failed_ids.len()),
+        None => info!("Detached {} documents from their source ({} failed)", updated_ids.len(), failed_ids.len()),
+    }
+
+    Ok(Json(BulkAssignSourceResponse {
+        updated_count: updated_ids.len() as i64,
+        failed_count: failed_ids.len() as i64,
+        updated_documents: updated_ids,
+        failed_documents: failed_ids,
+    }))
+}
+
+/// Apply tags, labels and original creation dates from a CSV or JSON export to existing documents
+///
+/// Rows are matched to the caller's own documents by `original_filename` and/or `file_hash`.
+/// Each row is validated and applied on its own, and reported as applied, unmatched,
+/// invalid or failed. CSV uploads need a header row; separate several tags or labels in one
+/// field with `;`. Labels are matched by name and must already exist.
+#[utoipa::path(
+    post,
+    path = "/api/documents/import-metadata",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content = String, description = "CSV with the columns original_filename, file_hash, tags, labels and original_created_at, or a JSON array of objects with those fields", content_type = "text/csv"),
+    responses(
+        (status = 200, description = "Per-row import results", body = MetadataImportResponse),
+        (status = 400, description = "Malformed CSV or JSON, an unknown column, no rows, or too many rows"),
+        (status = 401, description = "Unauthorized"),
+        (status = 415, description = "Content-Type is neither text/csv nor application/json"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn import_document_metadata(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    headers: HeaderMap,
+    body: String,
+) -> Result<Json<MetadataImportResponse>, DocumentError> {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .unwrap_or_default();
+    let format = match content_type.as_str() {
+        "text/csv" | "application/csv" => MetadataImportFormat::Csv,
+        "application/json" => MetadataImportFormat::Json,
+        _ => {
+            return Err(DocumentError::UnsupportedMediaType(
+                "Send the metadata as text/csv or application/json".to_string(),
+            ))
+        }
+    };
+
+    let rows = metadata_import_service::parse_rows(format, &body).map_err(import_error)?;
+    let rows = metadata_import_service::import_document_metadata(&state.db, auth_user.user.id, rows)
+        .await
+        .map_err(import_error)?;
+
+    let count = |status: MetadataImportRowStatus| rows.iter().filter(|row| row.status == status).count();
+    let response = MetadataImportResponse {
+        total_rows: rows.len(),
+        applied_rows: count(MetadataImportRowStatus::Applied),
+        unmatched_rows: count(MetadataImportRowStatus::Unmatched),
+        invalid_rows: count(MetadataImportRowStatus::Invalid),
+        failed_rows: count(MetadataImportRowStatus::Failed),
+        rows,
+    };
+    info!(
+        "Metadata import by user {}: {} rows, {} applied, {} unmatched, {} invalid, {} failed",
+        auth_user.user.id, response.total_rows, response.applied_rows, response.unmatched_rows,
+        response.invalid_rows, response.failed_rows
+    );
 
     Ok(Json(response))
 }
 
+fn import_error(e: MetadataImportError) -> DocumentError {
+    match e {
+        MetadataImportError::Internal(e) => {
+            error!("Metadata import failed: {}", e);
+            DocumentError::InternalServerError("Failed to import metadata".to_string())
+        }
+        e => DocumentError::BadRequest(e.to_string()),
+    }
+}
+
 /// Delete documents with low OCR confidence
 #[utoipa::path(
     post,
@@ -195,17 +398,24 @@ pub async fn delete_low_confidence_documents(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    // Delete associated files
+    // Move associated files to the trash
     let file_service = FileService::new(state.config.upload_path.clone());
     let mut files_deleted = 0;
     let mut files_failed = 0;
 
     for document in low_confidence_docs {
         if deleted_ids.contains(&document.id) {
-            match file_service.delete_document_files(&document).await {
-                Ok(_) => files_deleted += 1,
+            match file_service.move_to_trash(&document).await {
+                Ok(trash_path) => {
+                    if trash_path != document.file_path {
+                        if let Err(e) = state.db.update_document_file_path(document.id, &trash_path).await {
+                            error!("Failed to record trash path for document {}: {}", document.id, e);
+                        }
+                    }
+                    files_deleted += 1;
+                }
                 Err(e) => {
-                    warn!("Failed to delete files for document {}: {}", document.id, e);
+                    warn!("Failed to move files for document {} to trash: {}", document.id, e);
                     files_failed += 1;
                 }
             }
@@ -281,17 +491,24 @@ pub async fn delete_failed_ocr_documents(
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    // Delete associated files
+    // Move associated files to the trash
     let file_service = FileService::new(state.config.upload_path.clone());
     let mut files_deleted = 0;
     let mut files_failed = 0;
 
     for document in failed_ocr_docs {
         if deleted_ids.contains(&document.id) {
-            match file_service.delete_document_files(&document).await {
-                Ok(_) => files_deleted += 1,
+            match file_service.move_to_trash(&document).await {
+                Ok(trash_path) => {
+                    if trash_path != document.file_path {
+                        if let Err(e) = state.db.update_document_file_path(document.id, &trash_path).await {
+                            error!("Failed to record trash path for document {}: {}", document.id, e);
+                        }
+                    }
+                    files_deleted += 1;
+                }
                 Err(e) => {
-                    warn!("Failed to delete files for document {}: {}", document.id, e);
+                    warn!("Failed to move files for document {} to trash: {}", document.id, e);
                     files_failed += 1;
                 }
             }
@@ -312,6 +529,50 @@ pub async fn delete_failed_ocr_documents(
     })))
 }
 
+/// Apply retention policies
+///
+/// Moves documents past their retention period to the trash. Each document is kept for the
+/// longest retention among its labels, or the user's default `retention_days` without one;
+/// documents with a keep-forever label are never removed. A dry run only reports them.
+#[utoipa::path(
+    post,
+    path = "/api/documents/cleanup/retention",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = RetentionCleanupRequest,
+    responses(
+        (status = 200, description = "Documents past their retention period", body = RetentionCleanupResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required for all_users"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn apply_retention_cleanup(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<RetentionCleanupRequest>,
+) -> Result<Json<RetentionCleanupResponse>, StatusCode> {
+    if request.all_users && auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let user_id = (!request.all_users).then_some(auth_user.user.id);
+    let documents = apply_retention_policies(&state.db, &state.config.upload_path, user_id, request.dry_run)
+        .await
+        .map_err(|e| {
+            error!("Failed to apply retention policies: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(RetentionCleanupResponse {
+        dry_run: request.dry_run,
+        count: documents.len(),
+        documents,
+    }))
+}
+
 /// Get documents marked for deletion (cleanup preview)
 pub async fn get_cleanup_preview(
     State(state): State<Arc<AppState>>,
@@ -1,6 +1,6 @@
 use axum::{
     extract::{Multipart, Path, Query, State},
-    http::{StatusCode, header::CONTENT_TYPE},
+    http::{HeaderMap, StatusCode, header::{ACCEPT_RANGES, CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_RANGE, CONTENT_TYPE, RANGE}},
     response::{Json, Response, IntoResponse},
     body::Body,
 };
@@ -10,12 +10,30 @@ use tracing::{debug, error, info, warn};
 
 use crate::{
     auth::AuthUser,
+    ingestion::archive_extraction::{extract_zip, is_zip_archive, ArchiveError, ArchiveLimits, SkippedArchiveEntry},
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
-    services::file_service::FileService,
-    models::DocumentResponse,
+    services::byte_range::{parse_range_header, RangeRequest},
+    services::file_service::{FileService, StoredFile},
+    services::perceptual_hash::{DEFAULT_PERCEPTUAL_THRESHOLD, MAX_PERCEPTUAL_THRESHOLD},
+    services::source_file_deletion_service::{delete_source_files, plan_source_deletions},
+    services::source_upload_service::UploadTarget,
+    db::documents::DocumentListOptions,
+    models::{DocumentCursor, DocumentResponse, FileIngestionInfo},
     AppState,
 };
-use super::types::{PaginationQuery, DocumentUploadResponse, PaginatedDocumentsResponse, DocumentPaginationInfo};
+use super::types::{
+    PaginationQuery, DuplicatesQuery, DuplicateMode, ArchiveUploadResponse, DocumentUploadResponse, PaginatedDocumentsResponse, DocumentPaginationInfo,
+    ContentFormat, DocumentContentQuery, DocumentContentOcr, DocumentContentResponse,
+    RelatedDocumentsQuery, RelatedDocumentResponse, RelatedDocumentsResponse,
+    DeleteDocumentQuery, DeleteDocumentResponse,
+};
+
+const DEFAULT_RELATED_LIMIT: i64 = 10;
+const MAX_RELATED_LIMIT: i64 = 50;
+const DEFAULT_RELATED_MIN_SIMILARITY: f32 = 0.2;
+/// How many of a document's most frequent terms candidates for related documents are
+/// looked up by
+const RELATED_QUERY_TERMS: i64 = 20;
 
 /// Custom error type for document operations
 #[derive(Debug)]
@@ -24,6 +42,7 @@ pub enum DocumentError {
     NotFound,
     Conflict(String),
     PayloadTooLarge(String),
+    UnsupportedMediaType(String),
     InternalServerError(String),
     UploadTimeout(String),
     DatabaseConstraintViolation(String),
@@ -39,6 +58,7 @@ impl IntoResponse for DocumentError {
             DocumentError::NotFound => (StatusCode::NOT_FOUND, "Document not found".to_string(), "UPLOAD_NOT_FOUND"),
             DocumentError::Conflict(msg) => (StatusCode::CONFLICT, msg, "UPLOAD_CONFLICT"),
             DocumentError::PayloadTooLarge(msg) => (StatusCode::PAYLOAD_TOO_LARGE, msg, "UPLOAD_TOO_LARGE"),
+            DocumentError::UnsupportedMediaType(msg) => (StatusCode::UNSUPPORTED_MEDIA_TYPE, msg, "UPLOAD_UNSUPPORTED_TYPE"),
             DocumentError::InternalServerError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg, "UPLOAD_INTERNAL_ERROR"),
             DocumentError::UploadTimeout(msg) => (StatusCode::REQUEST_TIMEOUT, msg, "UPLOAD_TIMEOUT"),
             DocumentError::DatabaseConstraintViolation(msg) => (StatusCode::CONFLICT, msg, "UPLOAD_DB_CONSTRAINT"),
@@ -52,7 +72,8 @@ impl IntoResponse for DocumentError {
             "status": status.as_u16(),
             "error_code": error_code,
             "timestamp": chrono::Utc::now().to_rfc3339(),
-            "request_id": uuid::Uuid::new_v4()
+            "request_id": crate::monitoring::request_id::current_request_id()
+                .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
         }))).into_response()
     }
 }
@@ -67,10 +88,11 @@ impl IntoResponse for DocumentError {
     ),
     request_body(content = String, description = "Document file", content_type = "multipart/form-data"),
     responses(
-        (status = 200, description = "Document uploaded successfully", body = DocumentUploadResponse),
-        (status = 400, description = "Bad request"),
+        (status = 200, description = "Document uploaded successfully, or an `ArchiveUploadResponse` when a ZIP archive was extracted", body = DocumentUploadResponse),
+        (status = 400, description = "Bad request, or an archive with unsafe paths or too many entries"),
         (status = 401, description = "Unauthorized"),
-        (status = 413, description = "File too large"),
+        (status = 413, description = "File too large, or an archive that expands too far"),
+        (status = 415, description = "File type not allowed, by extension or by detected content"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -78,131 +100,187 @@ pub async fn upload_document(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
     mut multipart: Multipart,
-) -> Result<Json<DocumentUploadResponse>, DocumentError> {
-    let mut uploaded_file = None;
+) -> Result<Response, DocumentError> {
+    let mut uploaded_file: Option<(String, String, StoredFile)> = None;
     let mut ocr_language: Option<String> = None;
     let mut ocr_languages: Vec<String> = Vec::new();
+    let mut keep_archive = false;
     
-    // First pass: collect all multipart fields
-    while let Some(field) = multipart.next_field().await.map_err(|e| {
-        let error_msg = format!("Failed to get multipart field: {}", e);
-        error!("{}", error_msg);
-        DocumentError::BadRequest(error_msg)
-    })? {
-        let name = field.name().unwrap_or("").to_string();
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+    let max_file_size_bytes = state.config.max_file_size_mb as u64 * 1024 * 1024;
+    
+    // First pass: collect all multipart fields. The file is streamed straight to storage.
+    let collect_result: Result<(), DocumentError> = async {
+        while let Some(mut field) = multipart.next_field().await.map_err(|e| {
+            let error_msg = format!("Failed to get multipart field: {}", e);
+            error!("{}", error_msg);
+            DocumentError::BadRequest(error_msg)
+        })? {
+            let name = field.name().unwrap_or("").to_string();
         
-        if name == "ocr_language" {
-            let language = field.text().await.map_err(|_| DocumentError::BadRequest("Failed to read language field".to_string()))?;
-            if !language.trim().is_empty() {
-                // Validate that the language is available
-                let health_checker = crate::ocr::health::OcrHealthChecker::new();
-                match health_checker.validate_language(language.trim()) {
-                    Ok(_) => {
-                        ocr_language = Some(language.trim().to_string());
-                        info!("OCR language specified and validated: {}", language);
-                    }
-                    Err(e) => {
-                        let available_languages = health_checker.get_available_languages().unwrap_or_default();
-                        let error_msg = format!(
-                            "Invalid OCR language '{}': {}. Available languages: {}",
-                            language, e, available_languages.join(", ")
-                        );
-                        warn!("{}", error_msg);
-                        return Err(DocumentError::BadRequest(error_msg));
+            if name == "ocr_language" {
+                let language = field.text().await.map_err(|_| DocumentError::BadRequest("Failed to read language field".to_string()))?;
+                if !language.trim().is_empty() {
+                    // Validate that the language is available
+                    let health_checker = crate::ocr::health::OcrHealthChecker::new();
+                    match health_checker.validate_language(language.trim()) {
+                        Ok(_) => {
+                            ocr_language = Some(language.trim().to_string());
+                            info!("OCR language specified and validated: {}", language);
+                        }
+                        Err(e) => {
+                            let available_languages = health_checker.get_available_languages().unwrap_or_default();
+                            let error_msg = format!(
+                                "Invalid OCR language '{}': {}. Available languages: {}",
+                                language, e, available_languages.join(", ")
+                            );
+                            warn!("{}", error_msg);
+                            return Err(DocumentError::BadRequest(error_msg));
+                        }
                     }
                 }
-            }
-        } else if name == "ocr_languages" || name.starts_with("ocr_languages[") {
-            let language = field.text().await.map_err(|_| DocumentError::BadRequest("Failed to read language field".to_string()))?;
-            if !language.trim().is_empty() {
-                // Validate that the language is available
-                let health_checker = crate::ocr::health::OcrHealthChecker::new();
-                debug!("Validating OCR language: '{}'", language.trim());
-                match health_checker.validate_language(language.trim()) {
-                    Ok(_) => {
-                        ocr_languages.push(language.trim().to_string());
-                        info!("OCR language added to list: {}", language);
-                    }
-                    Err(e) => {
-                        let available_languages = health_checker.get_available_languages().unwrap_or_default();
-                        let error_msg = format!(
-                            "Invalid OCR language '{}': {}. Available languages: {}",
-                            language, e, available_languages.join(", ")
-                        );
-                        warn!("{}", error_msg);
-                        return Err(DocumentError::BadRequest(error_msg));
+            } else if name == "ocr_languages" || name.starts_with("ocr_languages[") {
+                let language = field.text().await.map_err(|_| DocumentError::BadRequest("Failed to read language field".to_string()))?;
+                if !language.trim().is_empty() {
+                    // Validate that the language is available
+                    let health_checker = crate::ocr::health::OcrHealthChecker::new();
+                    debug!("Validating OCR language: '{}'", language.trim());
+                    match health_checker.validate_language(language.trim()) {
+                        Ok(_) => {
+                            ocr_languages.push(language.trim().to_string());
+                            info!("OCR language added to list: {}", language);
+                        }
+                        Err(e) => {
+                            let available_languages = health_checker.get_available_languages().unwrap_or_default();
+                            let error_msg = format!(
+                                "Invalid OCR language '{}': {}. Available languages: {}",
+                                language, e, available_languages.join(", ")
+                            );
+                            warn!("{}", error_msg);
+                            return Err(DocumentError::BadRequest(error_msg));
+                        }
                     }
                 }
-            }
-        } else if name == "file" {
-            let filename = field.file_name()
-                .ok_or_else(|| {
-                    let error_msg = "No filename provided in upload".to_string();
+            } else if name == "keep_archive" {
+                let value = field.text().await.map_err(|_| DocumentError::BadRequest("Failed to read keep_archive field".to_string()))?;
+                keep_archive = value.trim().eq_ignore_ascii_case("true");
+            } else if name == "file" {
+                let filename = field.file_name()
+                    .ok_or_else(|| {
+                        let error_msg = "No filename provided in upload".to_string();
+                        error!("{}", error_msg);
+                        DocumentError::BadRequest(error_msg)
+                    })?
+                    .to_string();
+            
+                let content_type = field.content_type()
+                    .unwrap_or("application/octet-stream")
+                    .to_string();
+            
+                let mut writer = file_service.begin_streaming_save(&filename).await.map_err(|e| {
+                    let error_msg = format!("Failed to prepare storage for upload: {}", e);
                     error!("{}", error_msg);
-                    DocumentError::BadRequest(error_msg)
-                })?
-                .to_string();
+                    DocumentError::InternalServerError(error_msg)
+                })?;
             
-            let content_type = field.content_type()
-                .unwrap_or("application/octet-stream")
-                .to_string();
+                loop {
+                    let chunk = match field.chunk().await {
+                        Ok(Some(chunk)) => chunk,
+                        Ok(None) => break,
+                        Err(e) => {
+                            writer.abort().await;
+                            let error_msg = format!("Failed to read file data: {}", e);
+                            error!("{}", error_msg);
+                            return Err(DocumentError::BadRequest(error_msg));
+                        }
+                    };
+                
+                    // Enforce the size limit while streaming rather than after the fact
+                    if writer.bytes_written() + chunk.len() as u64 > max_file_size_bytes {
+                        writer.abort().await;
+                        let error_msg = format!("File '{}' exceeds maximum allowed size ({} bytes / {}MB)", 
+                               filename, max_file_size_bytes, state.config.max_file_size_mb);
+                        error!("{}", error_msg);
+                        return Err(DocumentError::PayloadTooLarge(error_msg));
+                    }
+                
+                    if let Err(e) = writer.write_chunk(&chunk).await {
+                        writer.abort().await;
+                        let error_msg = format!("Failed to write file data: {}", e);
+                        error!("{}", error_msg);
+                        return Err(DocumentError::FileProcessingError(error_msg));
+                    }
+                }
             
-            let data = field.bytes().await.map_err(|e| {
-                let error_msg = format!("Failed to read file data: {}", e);
-                error!("{}", error_msg);
-                DocumentError::BadRequest(error_msg)
-            })?;
+                let stored_file = writer.finish().await.map_err(|e| {
+                    let error_msg = format!("Failed to store uploaded file: {}", e);
+                    error!("{}", error_msg);
+                    DocumentError::FileProcessingError(error_msg)
+                })?;
             
-            uploaded_file = Some((filename, content_type, data.to_vec()));
+                // Only the last file field is kept
+                if let Some((_, _, previous)) = uploaded_file.replace((filename, content_type, stored_file)) {
+                    discard_stored_upload(&previous).await;
+                }
+            }
+        }
+        Ok(())
+    }.await;
+    
+    if let Err(e) = collect_result {
+        if let Some((_, _, stored_file)) = &uploaded_file {
+            discard_stored_upload(stored_file).await;
         }
+        return Err(e);
     }
     
-    let (filename, content_type, data) = uploaded_file.ok_or_else(|| {
+    let (filename, content_type, stored_file) = uploaded_file.ok_or_else(|| {
         let error_msg = "No file found in upload".to_string();
         error!("{}", error_msg);
         DocumentError::BadRequest(error_msg)
     })?;
     
-    // Validate file size against configured limit
-    let max_file_size_bytes = state.config.max_file_size_mb as usize * 1024 * 1024;
-    if data.len() > max_file_size_bytes {
-        let error_msg = format!("File '{}' size ({} bytes) exceeds maximum allowed size ({} bytes / {}MB)", 
-               filename, data.len(), max_file_size_bytes, state.config.max_file_size_mb);
-        error!("{}", error_msg);
-        return Err(DocumentError::PayloadTooLarge(error_msg));
-    }
+    let settings = match state.db.get_user_settings(auth_user.user.id).await {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("Failed to load settings of user {}, uploading with defaults: {}", auth_user.user.id, e);
+            None
+        }
+    };
+    let upload_target = UploadTarget::for_user(&state.db, auth_user.user.id, settings.as_ref()).await;
+    let source_id = upload_target.as_ref().map(|target| target.source.id);
     
-    info!("Uploading document: {} ({} bytes)", filename, data.len());
+    // Check what the file actually is, not just what its name claims
+    let detection = crate::mime_detection::detect_mime_from_stored_file(&stored_file.file_path, &filename, &content_type).await;
+    if is_zip_archive(&detection.mime_type) {
+        if settings.as_ref().is_some_and(|settings| settings.extract_archives) {
+            let response = upload_archive(&state, &auth_user, &filename, stored_file, keep_archive, source_id).await?;
+            return Ok(Json(response).into_response());
+        }
+    }
+    if let Err(rejection) = crate::mime_detection::check_allowed_file_type(&detection, &filename, &state.config.allowed_file_types) {
+        discard_stored_upload(&stored_file).await;
+        let error_msg = format!("{}. Allowed types: {}", rejection, state.config.allowed_file_types.join(", "));
+        warn!("Rejected upload from user {}: {}", auth_user.user.id, error_msg);
+        return Err(DocumentError::UnsupportedMediaType(error_msg));
+    }
     
-    // Create FileIngestionInfo from uploaded data
-    use crate::models::FileIngestionInfo;
-    use chrono::Utc;
+    info!("Uploading document: {} ({} bytes, {})", filename, stored_file.file_size, detection.mime_type);
     
-    let mut file_info = FileIngestionInfo {
-        relative_path: format!("upload/{}", filename), // Virtual path for web uploads
-        full_path: format!("upload/{}", filename), // For web uploads, relative and full are the same
-        #[allow(deprecated)]
-        path: format!("upload/{}", filename), // Virtual path for web uploads
-        name: filename.clone(),
-        size: data.len() as i64,
-        mime_type: content_type.clone(),
-        last_modified: Some(Utc::now()), // Upload time as last modified
-        etag: format!("{}-{}", data.len(), Utc::now().timestamp()),
-        is_directory: false,
-        created_at: Some(Utc::now()), // Upload time as creation time
-        permissions: None, // Web uploads don't have filesystem permissions
-        owner: Some(auth_user.user.username.clone()), // Uploader as owner
-        group: None, // Web uploads don't have filesystem groups
-        metadata: None, // Will be populated with extracted metadata below
-    };
+    // Create FileIngestionInfo from uploaded data, under a virtual path for web uploads. It is
+    // ingested under no source; a default source is only set once the path on it is known.
+    let mut file_info = upload_file_info(
+        format!("upload/{}", filename),
+        filename.clone(),
+        &stored_file,
+        detection.mime_type.clone(),
+        &auth_user.user.username,
+    );
     
     // Extract content-based metadata from uploaded file
-    if let Ok(Some(content_metadata)) = crate::metadata_extraction::extract_content_metadata(&data, &content_type, &filename).await {
-        file_info.metadata = Some(content_metadata);
-    }
+    extract_upload_metadata(&state, &mut file_info, &stored_file).await;
     
     // Create ingestion service
-    let file_service = FileService::new(state.config.upload_path.clone());
     let ingestion_service = DocumentIngestionService::new(
         state.db.clone(),
         file_service,
@@ -211,17 +289,43 @@ pub async fn upload_document(
     debug!("[UPLOAD_DEBUG] Calling ingestion service for file: {}", filename);
     let ingestion_start = std::time::Instant::now();
     
-    match ingestion_service.ingest_from_file_info(
+    match ingestion_service.ingest_stored_file(
         &file_info, 
-        data, 
+        stored_file, 
         auth_user.user.id, 
         crate::ingestion::document_ingestion::DeduplicationPolicy::Skip, 
         "web_upload", 
         None
     ).await {
-        Ok(IngestionResult::Created(document)) => {
+        Ok(IngestionResult::Created(mut document)) | Ok(IngestionResult::NewVersion(mut document)) => {
             info!("Document uploaded successfully: {}", document.id);
             
+            let mut write_back_error = None;
+            if let Some(target) = &upload_target {
+                let chunk_size = state.config.webdav_upload_chunk_size_mb * 1024 * 1024;
+                let source_path = match target.write_back(&state.source_secrets, &filename, &document.file_path, chunk_size).await {
+                    Ok(source_path) => {
+                        if let Some(source_path) = &source_path {
+                            info!("Wrote document {} back to {} on source {}", document.id, source_path, target.source.id);
+                        }
+                        source_path
+                    }
+                    Err(e) => {
+                        warn!("Failed to write document {} back to source {}: {}", document.id, target.source.id, e);
+                        write_back_error = Some(e.to_string());
+                        None
+                    }
+                };
+                // Only a copy actually written to the source gets a path on it
+                match state.db.file_document_under_source(document.id, target.source.id, source_path.as_deref()).await {
+                    Ok(()) => {
+                        document.source_id = Some(target.source.id);
+                        document.source_path = source_path;
+                    }
+                    Err(e) => warn!("Failed to file document {} under source {}: {}", document.id, target.source.id, e),
+                }
+            }
+            
             // Update user's OCR language settings based on what was provided
             if !ocr_languages.is_empty() {
                 // Multi-language support: update preferred languages
@@ -269,7 +373,9 @@ pub async fn upload_document(
                 mime_type: document.mime_type,
                 status: "success".to_string(),
                 message: "Document uploaded successfully".to_string(),
-            }))
+                source_id: document.source_id,
+                write_back_error,
+            }).into_response())
         }
         Ok(IngestionResult::ExistingDocument(existing_doc)) => {
             warn!("Duplicate document upload attempted: {}", existing_doc.id);
@@ -280,7 +386,9 @@ pub async fn upload_document(
                 mime_type: existing_doc.mime_type,
                 status: "duplicate".to_string(),
                 message: "Document already exists".to_string(),
-            }))
+                source_id: existing_doc.source_id,
+                write_back_error: None,
+            }).into_response())
         }
         Ok(IngestionResult::Skipped { existing_document_id, reason }) => {
             let error_msg = format!("Document upload skipped - {}: {}", reason, existing_document_id);
@@ -313,6 +421,230 @@ pub async fn upload_document(
     }
 }
 
+/// Removes an upload that was streamed to storage but will not be ingested
+async fn discard_stored_upload(stored_file: &StoredFile) {
+    if let Err(e) = tokio::fs::remove_file(&stored_file.file_path).await {
+        warn!("Failed to remove discarded upload {}: {}", stored_file.file_path, e);
+    }
+}
+
+/// Adds content-based metadata (EXIF, PDF properties) of a stored upload to `file_info`
+async fn extract_upload_metadata(state: &AppState, file_info: &mut FileIngestionInfo, stored_file: &StoredFile) {
+    let options = crate::metadata_extraction::MetadataExtractionOptions::from_config(&state.config);
+    let path = std::path::Path::new(&stored_file.file_path);
+    if let Err(e) = crate::metadata_extraction::enrich_file_info_from_path(file_info, path, options).await {
+        warn!("Failed to read stored upload for metadata extraction: {}", e);
+    }
+}
+
+/// `FileIngestionInfo` for a file received through the upload endpoint
+fn upload_file_info(relative_path: String, name: String, stored_file: &StoredFile, mime_type: String, owner: &str) -> FileIngestionInfo {
+    let now = chrono::Utc::now();
+    FileIngestionInfo {
+        full_path: relative_path.clone(), // For web uploads, relative and full are the same
+        #[allow(deprecated)]
+        path: relative_path.clone(),
+        relative_path,
+        name,
+        size: stored_file.file_size,
+        mime_type,
+        last_modified: Some(now), // Upload time as last modified
+        etag: format!("{}-{}", stored_file.file_size, now.timestamp()),
+        is_directory: false,
+        created_at: Some(now), // Upload time as creation time
+        permissions: None, // Web uploads don't have filesystem permissions
+        owner: Some(owner.to_string()), // Uploader as owner
+        group: None, // Web uploads don't have filesystem groups
+        metadata: None,
+    }
+}
+
+/// Ingests every file in an uploaded ZIP archive as a document of its own, tagged with the
+/// archive's name. Entries are held to the same allow-list and size limit as uploads.
+/// The archive itself is discarded unless `keep_archive` is set, in which case it is stored
+/// as a document too, but not OCRed.
+async fn upload_archive(
+    state: &Arc<AppState>,
+    auth_user: &AuthUser,
+    archive_filename: &str,
+    stored_archive: StoredFile,
+    keep_archive: bool,
+    source_id: Option<uuid::Uuid>,
+) -> Result<ArchiveUploadResponse, DocumentError> {
+    let limits = ArchiveLimits::from_config(&state.config);
+    let scratch_dir = std::env::temp_dir().join(format!("readur_archive_{}", uuid::Uuid::new_v4()));
+    let extraction = {
+        let archive_path = std::path::PathBuf::from(&stored_archive.file_path);
+        let scratch_dir = scratch_dir.clone();
+        tokio::task::spawn_blocking(move || {
+            std::fs::create_dir_all(&scratch_dir)?;
+            extract_zip(&archive_path, &scratch_dir, &limits)
+        }).await
+    };
+
+    let extraction = match extraction {
+        Ok(Ok(extraction)) => extraction,
+        Ok(Err(e)) => {
+            remove_scratch_dir(&scratch_dir).await;
+            discard_stored_upload(&stored_archive).await;
+            warn!("Rejected archive '{}' from user {}: {}", archive_filename, auth_user.user.id, e);
+            return Err(match e {
+                ArchiveError::TooLarge { .. } => DocumentError::PayloadTooLarge(e.to_string()),
+                ArchiveError::Io(_) => DocumentError::InternalServerError(e.to_string()),
+                _ => DocumentError::BadRequest(e.to_string()),
+            });
+        }
+        Err(e) => {
+            remove_scratch_dir(&scratch_dir).await;
+            discard_stored_upload(&stored_archive).await;
+            return Err(DocumentError::InternalServerError(format!("Archive extraction failed: {}", e)));
+        }
+    };
+
+    info!("Extracted {} files from archive '{}' ({} skipped)", extraction.entries.len(), archive_filename, extraction.skipped.len());
+
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+    let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service.clone());
+    let archive_tag = archive_filename.to_string();
+    let mut document_ids = Vec::new();
+    let mut duplicate_document_ids = Vec::new();
+    let mut skipped = extraction.skipped;
+
+    for entry in extraction.entries {
+        let extracted_path = entry.extracted_path.to_string_lossy().to_string();
+        let declared_mime_type = mime_guess::from_path(&entry.file_name).first_or_octet_stream().to_string();
+        let detection = crate::mime_detection::detect_mime_from_stored_file(&extracted_path, &entry.file_name, &declared_mime_type).await;
+        if let Err(rejection) = crate::mime_detection::check_allowed_file_type(&detection, &entry.file_name, &state.config.allowed_file_types) {
+            skipped.push(SkippedArchiveEntry { path: entry.archive_path, reason: rejection.to_string() });
+            continue;
+        }
+
+        let stored_file = match file_service.store_file_from_path(&entry.file_name, &entry.extracted_path).await {
+            Ok(stored_file) => stored_file,
+            Err(e) => {
+                error!("Failed to store '{}' from archive '{}': {}", entry.archive_path, archive_filename, e);
+                skipped.push(SkippedArchiveEntry { path: entry.archive_path, reason: format!("could not be stored: {}", e) });
+                continue;
+            }
+        };
+
+        let mut file_info = upload_file_info(
+            format!("upload/{}/{}", archive_filename, entry.archive_path),
+            entry.file_name.clone(),
+            &stored_file,
+            detection.mime_type.clone(),
+            &auth_user.user.username,
+        );
+        extract_upload_metadata(state, &mut file_info, &stored_file).await;
+
+        match ingestion_service.ingest_stored_file(
+            &file_info,
+            stored_file,
+            auth_user.user.id,
+            crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
+            "web_upload",
+            None,
+        ).await {
+            Ok(IngestionResult::Created(document)) | Ok(IngestionResult::NewVersion(document)) => {
+                tag_document(state, &document, &archive_tag).await;
+                file_under_source(state, &document, source_id).await;
+                if let Err(e) = state.queue_service.enqueue_document(document.id, 5, document.file_size).await {
+                    error!("Failed to enqueue document {} for OCR: {}", document.id, e);
+                }
+                document_ids.push(document.id);
+            }
+            Ok(IngestionResult::ExistingDocument(existing)) => duplicate_document_ids.push(existing.id),
+            Ok(IngestionResult::Skipped { existing_document_id, .. })
+            | Ok(IngestionResult::TrackedAsDuplicate { existing_document_id }) => duplicate_document_ids.push(existing_document_id),
+            Err(e) => {
+                error!("Failed to ingest '{}' from archive '{}': {}", entry.archive_path, archive_filename, e);
+                skipped.push(SkippedArchiveEntry { path: entry.archive_path, reason: format!("could not be ingested: {}", e) });
+            }
+        }
+    }
+    remove_scratch_dir(&scratch_dir).await;
+
+    let archive_document_id = if keep_archive {
+        let file_info = upload_file_info(
+            format!("upload/{}", archive_filename),
+            archive_filename.to_string(),
+            &stored_archive,
+            "application/zip".to_string(),
+            &auth_user.user.username,
+        );
+        match ingestion_service.ingest_stored_file(
+            &file_info,
+            stored_archive,
+            auth_user.user.id,
+            crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
+            "web_upload",
+            None,
+        ).await {
+            Ok(IngestionResult::Created(document)) | Ok(IngestionResult::NewVersion(document)) => {
+                tag_document(state, &document, &archive_tag).await;
+                file_under_source(state, &document, source_id).await;
+                Some(document.id)
+            }
+            Ok(IngestionResult::ExistingDocument(document)) => {
+                tag_document(state, &document, &archive_tag).await;
+                Some(document.id)
+            }
+            Ok(IngestionResult::Skipped { existing_document_id, .. })
+            | Ok(IngestionResult::TrackedAsDuplicate { existing_document_id }) => Some(existing_document_id),
+            Err(e) => {
+                error!("Failed to keep archive '{}': {}", archive_filename, e);
+                None
+            }
+        }
+    } else {
+        discard_stored_upload(&stored_archive).await;
+        None
+    };
+
+    Ok(ArchiveUploadResponse {
+        archive_filename: archive_filename.to_string(),
+        archive_document_id,
+        message: format!(
+            "Created {} documents from archive, {} files already existed, {} skipped",
+            document_ids.len(), duplicate_document_ids.len(), skipped.len()
+        ),
+        document_ids,
+        duplicate_document_ids,
+        skipped,
+        status: "extracted".to_string(),
+    })
+}
+
+/// Files a document from an archive under the default source. Archive contents are never
+/// written back, so they get no path on the source.
+async fn file_under_source(state: &AppState, document: &crate::models::Document, source_id: Option<uuid::Uuid>) {
+    let Some(source_id) = source_id else {
+        return;
+    };
+    if let Err(e) = state.db.file_document_under_source(document.id, source_id, None).await {
+        warn!("Failed to file document {} under source {}: {}", document.id, source_id, e);
+    }
+}
+
+async fn tag_document(state: &AppState, document: &crate::models::Document, tag: &str) {
+    if document.tags.iter().any(|existing| existing == tag) {
+        return;
+    }
+    let mut tags = document.tags.clone();
+    tags.push(tag.to_string());
+    if let Err(e) = state.db.update_document_tags(document.id, &tags).await {
+        warn!("Failed to tag document {} with '{}': {}", document.id, tag, e);
+    }
+}
+
+async fn remove_scratch_dir(scratch_dir: &std::path::Path) {
+    if let Err(e) = tokio::fs::remove_dir_all(scratch_dir).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to remove archive extraction directory {:?}: {}", scratch_dir, e);
+        }
+    }
+}
+
 /// Get a specific document by ID
 #[utoipa::path(
     get,
@@ -374,6 +706,10 @@ pub async fn get_document_by_id(
 }
 
 /// List documents with pagination and filtering
+///
+/// Pages either by `offset` or, for large libraries, by `cursor`: each page returns a
+/// `next_cursor` that continues right after its last document, unaffected by documents
+/// added in the meantime.
 #[utoipa::path(
     get,
     path = "/api/documents",
@@ -384,6 +720,7 @@ pub async fn get_document_by_id(
     params(PaginationQuery),
     responses(
         (status = 200, description = "Paginated list of documents", body = PaginatedDocumentsResponse),
+        (status = 400, description = "Invalid cursor"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -392,12 +729,60 @@ pub async fn list_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
     Query(query): Query<PaginationQuery>,
-) -> Result<Json<PaginatedDocumentsResponse>, StatusCode> {
-    let limit = query.limit.unwrap_or(25);
-    let offset = query.offset.unwrap_or(0);
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    list_documents_response(&state, &auth_user, &query, None).await
+}
+
+/// One page of the document list, optionally only the documents synced from `source_id`.
+/// Shared by the document list and a source's document list.
+pub(crate) async fn list_documents_response(
+    state: &AppState,
+    auth_user: &AuthUser,
+    query: &PaginationQuery,
+    source_id: Option<uuid::Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let limit = match query.limit {
+        Some(limit) => limit,
+        None => {
+            let settings = state
+                .db
+                .get_user_settings(auth_user.user.id)
+                .await
+                .map_err(|e| {
+                    error!("Database error loading settings: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+                .unwrap_or_default();
+            i64::from(settings.search_results_per_page.max(1))
+        }
+    };
+    let after = match query.cursor.as_deref() {
+        Some(cursor) => Some(DocumentCursor::decode(cursor).ok_or(StatusCode::BAD_REQUEST)?),
+        None => None,
+    };
+    // A cursor replaces the offset
+    let offset = if after.is_some() { 0 } else { query.offset.unwrap_or(0) };
+    let fields: Option<Vec<&str>> = query.fields.as_deref().map(|fields| {
+        fields.split(',').map(str::trim).filter(|field| !field.is_empty()).collect()
+    });
+
+    // One extra row tells whether another page follows
+    let options = DocumentListOptions {
+        ocr_status: query.ocr_status.as_deref(),
+        source_id,
+        sort: query.sort.unwrap_or_default(),
+        after,
+        limit: limit + 1,
+        offset,
+    };
 
     // Get total count for pagination
-    let total_count = if let Some(ocr_status) = query.ocr_status.as_deref() {
+    let total_count = if source_id.is_some() {
+        state
+            .db
+            .count_listed_documents(auth_user.user.id, auth_user.user.role, &options)
+            .await
+    } else if let Some(ocr_status) = query.ocr_status.as_deref() {
         state
             .db
             .count_documents_by_user_with_role_and_filter(
@@ -420,38 +805,27 @@ pub async fn list_documents(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
-    let documents = if let Some(ocr_status) = query.ocr_status.as_deref() {
-        state
-            .db
-            .get_documents_by_user_with_role_and_filter(
-                auth_user.user.id,
-                auth_user.user.role,
-                Some(ocr_status),
-                limit,
-                offset,
-            )
-            .await
-    } else {
-        state
-            .db
-            .get_documents_by_user_with_role(
-                auth_user.user.id,
-                auth_user.user.role,
-                limit,
-                offset,
-            )
-            .await
-    }
-    .map_err(|e| {
-        error!("Database error listing documents: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    let mut documents = state
+        .db
+        .list_documents_page(auth_user.user.id, auth_user.user.role, &options)
+        .await
+        .map_err(|e| {
+            error!("Database error listing documents: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let has_more = documents.len() as i64 > limit;
+    documents.truncate(limit.max(0) as usize);
+    let next_cursor = documents
+        .last()
+        .filter(|_| has_more)
+        .map(|document| DocumentCursor::from(document).encode());
 
     // Get document IDs for batch label fetching
     let document_ids: Vec<uuid::Uuid> = documents.iter().map(|d| d.id).collect();
+    let wants_labels = fields.as_ref().is_none_or(|fields| fields.contains(&"labels"));
     
     // Get labels for all documents in batch
-    let labels_map = if !document_ids.is_empty() {
+    let labels_map = if !document_ids.is_empty() && wants_labels {
         let labels = state
             .db
             .get_labels_for_documents(&document_ids)
@@ -483,16 +857,48 @@ pub async fn list_documents(
         total: total_count,
         limit,
         offset,
-        has_more: offset + limit < total_count,
+        has_more,
+        next_cursor,
     };
 
-    Ok(Json(PaginatedDocumentsResponse {
+    let mut body = serde_json::to_value(PaginatedDocumentsResponse {
         documents: responses,
         pagination,
-    }))
+    })
+    .map_err(|e| {
+        error!("Failed to serialize document list: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    if let (Some(fields), Some(serde_json::Value::Array(documents))) = (&fields, body.get_mut("documents")) {
+        for document in documents.iter_mut() {
+            project_document_fields(document, fields);
+        }
+    }
+
+    Ok(Json(body))
 }
 
-/// Delete a specific document
+/// Keeps only the requested fields of a serialized document. `thumbnail` adds the
+/// document's thumbnail URL.
+fn project_document_fields(document: &mut serde_json::Value, fields: &[&str]) {
+    let Some(object) = document.as_object_mut() else {
+        return;
+    };
+    let thumbnail_url = object
+        .get("id")
+        .and_then(|id| id.as_str())
+        .map(|id| format!("/api/documents/{}/thumbnail", id));
+
+    object.retain(|key, _| fields.contains(&key.as_str()));
+    if let (true, Some(url)) = (fields.contains(&"thumbnail"), thumbnail_url) {
+        object.insert("thumbnail_url".to_string(), json!(url));
+    }
+}
+
+/// Move a specific document to the trash
+///
+/// The file on the source the document was synced from is kept unless `source_files` or
+/// the source's `deletion_behavior` says `delete_source`, which also needs `confirm=true`.
 #[utoipa::path(
     delete,
     path = "/api/documents/{id}",
@@ -501,10 +907,13 @@ pub async fn list_documents(
         ("bearer_auth" = [])
     ),
     params(
-        ("id" = uuid::Uuid, Path, description = "Document ID")
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        DeleteDocumentQuery
     ),
     responses(
-        (status = 204, description = "Document deleted successfully"),
+        (status = 204, description = "Document moved to trash"),
+        (status = 200, description = "Document moved to trash and its file deleted on the source, or the reason that failed", body = DeleteDocumentResponse),
+        (status = 400, description = "The file would be deleted on the source without `confirm=true`"),
         (status = 404, description = "Document not found"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
@@ -514,7 +923,8 @@ pub async fn delete_document(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
     Path(document_id): Path<uuid::Uuid>,
-) -> Result<StatusCode, StatusCode> {
+    Query(query): Query<DeleteDocumentQuery>,
+) -> Result<Response, DocumentError> {
     // Get document first to check if it exists and user has access
     let document = state
         .db
@@ -522,9 +932,18 @@ pub async fn delete_document(
         .await
         .map_err(|e| {
             error!("Database error getting document {}: {}", document_id, e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            DocumentError::InternalServerError("Failed to get document".to_string())
         })?
-        .ok_or(StatusCode::NOT_FOUND)?;
+        .ok_or(DocumentError::NotFound)?;
+
+    let pending = plan_source_deletions(&state.db, std::slice::from_ref(&document), query.source_files)
+        .await
+        .map_err(|e| DocumentError::InternalServerError(format!("Failed to load the document's source: {}", e)))?;
+    if !pending.is_empty() && !query.confirm {
+        return Err(DocumentError::BadRequest(
+            "This also deletes the file on the source the document was synced from; repeat the request with confirm=true".to_string(),
+        ));
+    }
 
     // Delete from database
     let deleted = state
@@ -533,22 +952,35 @@ pub async fn delete_document(
         .await
         .map_err(|e| {
             error!("Database error deleting document {}: {}", document_id, e);
-            StatusCode::INTERNAL_SERVER_ERROR
+            DocumentError::InternalServerError("Failed to delete document".to_string())
         })?;
 
     if !deleted {
-        return Err(StatusCode::NOT_FOUND);
+        return Err(DocumentError::NotFound);
     }
 
-    // Delete associated files
+    // Move the file into the trash so the document can still be restored
     let file_service = FileService::new(state.config.upload_path.clone());
-    if let Err(e) = file_service.delete_document_files(&document).await {
-        warn!("Failed to delete files for document {}: {}", document_id, e);
-        // Continue anyway - database deletion succeeded
+    match file_service.move_to_trash(&document).await {
+        Ok(trash_path) if trash_path != document.file_path => {
+            if let Err(e) = state.db.update_document_file_path(document_id, &trash_path).await {
+                error!("Failed to record trash path for document {}: {}", document_id, e);
+            }
+        }
+        Ok(_) => {}
+        Err(e) => {
+            warn!("Failed to move file for document {} to trash: {}", document_id, e);
+            // Continue anyway - the document is already marked as deleted
+        }
     }
 
-    info!("Document deleted successfully: {}", document_id);
-    Ok(StatusCode::NO_CONTENT)
+    info!("Document moved to trash: {}", document_id);
+
+    // The local deletion stands whatever happens on the source
+    match delete_source_files(pending, &state.source_secrets).await.pop() {
+        Some(source_file) => Ok(Json(DeleteDocumentResponse { document_id, source_file }).into_response()),
+        None => Ok(StatusCode::NO_CONTENT.into_response()),
+    }
 }
 
 /// Download a document file
@@ -560,10 +992,13 @@ pub async fn delete_document(
         ("bearer_auth" = [])
     ),
     params(
-        ("id" = uuid::Uuid, Path, description = "Document ID")
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("Range" = Option<String>, Header, description = "Single byte range, e.g. `bytes=0-1023`")
     ),
     responses(
         (status = 200, description = "Document file", content_type = "application/octet-stream"),
+        (status = 206, description = "The requested byte range of the file", content_type = "application/octet-stream"),
+        (status = 416, description = "Range outside the file"),
         (status = 404, description = "Document not found"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
@@ -573,6 +1008,7 @@ pub async fn download_document(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
     Path(document_id): Path<uuid::Uuid>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, StatusCode> {
     let document = state
         .db
@@ -584,25 +1020,8 @@ pub async fn download_document(
         })?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    let file_service = FileService::new(state.config.upload_path.clone());
-    let file_data = file_service
-        .read_file(&document.file_path)
-        .await
-        .map_err(|e| {
-            error!("Failed to read document file {}: {}", document_id, e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
-
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .header(CONTENT_TYPE, document.mime_type)
-        .header("Content-Disposition", format!("attachment; filename=\"{}\"", document.original_filename))
-        .header("Content-Length", file_data.len().to_string())
-        .body(Body::from(file_data))
-        .map_err(|e| {
-            error!("Failed to build response: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    let disposition = format!("attachment; filename=\"{}\"", document.original_filename);
+    let response = serve_document_file(&state, &document, &headers, Some(disposition)).await?;
 
     debug!("Document downloaded: {}", document_id);
     Ok(response)
@@ -617,10 +1036,13 @@ pub async fn download_document(
         ("bearer_auth" = [])
     ),
     params(
-        ("id" = uuid::Uuid, Path, description = "Document ID")
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("Range" = Option<String>, Header, description = "Single byte range, e.g. `bytes=0-1023`")
     ),
     responses(
         (status = 200, description = "Document file for viewing", content_type = "application/octet-stream"),
+        (status = 206, description = "The requested byte range of the file", content_type = "application/octet-stream"),
+        (status = 416, description = "Range outside the file"),
         (status = 404, description = "Document not found"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
@@ -630,6 +1052,7 @@ pub async fn view_document(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
     Path(document_id): Path<uuid::Uuid>,
+    headers: HeaderMap,
 ) -> Result<Response<Body>, StatusCode> {
     let document = state
         .db
@@ -641,27 +1064,154 @@ pub async fn view_document(
         })?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    let response = serve_document_file(&state, &document, &headers, None).await?;
+
+    debug!("Document viewed: {}", document_id);
+    Ok(response)
+}
+
+/// Responds with a document's file, or with the byte range asked for by a single-range
+/// `Range` header (206, or 416 when the range lies outside the file). Multi-range and
+/// malformed `Range` headers get the whole file.
+async fn serve_document_file(
+    state: &AppState,
+    document: &crate::models::Document,
+    headers: &HeaderMap,
+    content_disposition: Option<String>,
+) -> Result<Response<Body>, StatusCode> {
     let file_service = FileService::new(state.config.upload_path.clone());
-    let file_data = file_service
-        .read_file(&document.file_path)
+    let file_size = file_service
+        .file_size(&document.file_path)
         .await
         .map_err(|e| {
-            error!("Failed to read document file {}: {}", document_id, e);
+            error!("Failed to read document file {}: {}", document.id, e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
-    let response = Response::builder()
-        .status(StatusCode::OK)
-        .header(CONTENT_TYPE, document.mime_type)
-        .header("Content-Length", file_data.len().to_string())
+    let range_header = headers.get(RANGE).and_then(|value| value.to_str().ok());
+    let range = parse_range_header(range_header, file_size);
+
+    let mut response = Response::builder()
+        .header(CONTENT_TYPE, document.mime_type.as_str())
+        .header(ACCEPT_RANGES, "bytes");
+    if let Some(content_range) = range.content_range(file_size) {
+        response = response.header(CONTENT_RANGE, content_range);
+    }
+    if let Some(content_disposition) = content_disposition {
+        response = response.header(CONTENT_DISPOSITION, content_disposition);
+    }
+
+    let (status, file_data) = match range {
+        RangeRequest::Full => (StatusCode::OK, file_service.read_file(&document.file_path).await),
+        RangeRequest::Partial { start, end } => (
+            StatusCode::PARTIAL_CONTENT,
+            file_service.read_file_range(&document.file_path, start, end - start + 1).await,
+        ),
+        RangeRequest::Unsatisfiable => (StatusCode::RANGE_NOT_SATISFIABLE, Ok(Vec::new())),
+    };
+    let file_data = file_data.map_err(|e| {
+        error!("Failed to read document file {}: {}", document.id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    response
+        .status(status)
+        .header(CONTENT_LENGTH, file_data.len().to_string())
         .body(Body::from(file_data))
         .map_err(|e| {
             error!("Failed to build response: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        })
+}
 
-    debug!("Document viewed: {}", document_id);
-    Ok(response)
+/// Export the extracted content of a document
+///
+/// Returns the document's extracted `content` followed by its OCR text, either as plain text
+/// or as JSON together with the document's tags, labels and OCR metadata.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/content",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        DocumentContentQuery
+    ),
+    responses(
+        (status = 200, description = "Extracted text (format=txt) or text with metadata (format=json)", content(
+            (String = "text/plain"),
+            (DocumentContentResponse = "application/json")
+        )),
+        (status = 400, description = "Unknown format"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn export_document_content(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Query(query): Query<DocumentContentQuery>,
+) -> Result<Response, StatusCode> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // OCR of a text document repeats its content; only keep it once
+    let text = [document.content.as_deref(), document.ocr_text.as_deref()]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .fold(Vec::<&str>::new(), |mut parts, part| {
+            if !parts.contains(&part) {
+                parts.push(part);
+            }
+            parts
+        })
+        .join("\n\n");
+
+    if query.format.unwrap_or_default() == ContentFormat::Txt {
+        return Ok(([(CONTENT_TYPE, "text/plain; charset=utf-8")], text).into_response());
+    }
+
+    let labels = state
+        .db
+        .get_labels_for_documents(&[document_id])
+        .await
+        .map_err(|e| {
+            error!("Failed to get labels for document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .into_iter()
+        .flat_map(|(_, labels)| labels)
+        .map(|label| label.name)
+        .collect();
+
+    Ok(Json(DocumentContentResponse {
+        id: document.id,
+        filename: document.original_filename,
+        mime_type: document.mime_type,
+        tags: document.tags,
+        labels,
+        ocr: DocumentContentOcr {
+            status: document.ocr_status,
+            confidence: document.ocr_confidence,
+            word_count: document.ocr_word_count,
+            completed_at: document.ocr_completed_at,
+        },
+        text,
+    })
+    .into_response())
 }
 
 /// Get user's duplicate documents
@@ -674,33 +1224,52 @@ pub async fn view_document(
     ),
     params(
         ("limit" = Option<i64>, Query, description = "Number of duplicate groups to return per page"),
-        ("offset" = Option<i64>, Query, description = "Number of duplicate groups to skip")
+        ("offset" = Option<i64>, Query, description = "Number of duplicate groups to skip"),
+        ("mode" = Option<DuplicateMode>, Query, description = "exact (identical file hash, default) or perceptual (visually similar images)"),
+        ("threshold" = Option<u32>, Query, description = "Maximum Hamming distance between perceptual hashes, 0-64 (default: 10)")
     ),
     responses(
         (status = 200, description = "User's duplicate documents grouped by hash", body = serde_json::Value),
+        (status = 400, description = "Invalid threshold"),
         (status = 401, description = "Unauthorized")
     )
 )]
 pub async fn get_user_duplicates(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(query): Query<PaginationQuery>,
+    Query(query): Query<DuplicatesQuery>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     let limit = query.limit.unwrap_or(25);
     let offset = query.offset.unwrap_or(0);
+    let mode = query.mode.unwrap_or_default();
+    let threshold = query.threshold.unwrap_or(DEFAULT_PERCEPTUAL_THRESHOLD);
 
-    let duplicates = state
-        .db
-        .get_user_duplicates(auth_user.user.id, auth_user.user.role, limit, offset)
-        .await
-        .map_err(|e| {
-            error!("Failed to get user duplicates: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+    if threshold > MAX_PERCEPTUAL_THRESHOLD {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let duplicates = match mode {
+        DuplicateMode::Exact => {
+            state
+                .db
+                .get_user_duplicates(auth_user.user.id, auth_user.user.role, limit, offset)
+                .await
+        }
+        DuplicateMode::Perceptual => {
+            state
+                .db
+                .get_user_perceptual_duplicates(auth_user.user.id, auth_user.user.role, threshold, limit, offset)
+                .await
+        }
+    }
+    .map_err(|e| {
+        error!("Failed to get user duplicates: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
     
     let total_count = duplicates.len() as i64;
 
-    let response = serde_json::json!({
+    let mut response = serde_json::json!({
         "duplicates": duplicates,
         "pagination": {
             "total": total_count,
@@ -710,8 +1279,70 @@ pub async fn get_user_duplicates(
         },
         "statistics": {
             "total_duplicate_groups": total_count
-        }
+        },
+        "mode": mode
     });
 
+    if mode == DuplicateMode::Perceptual {
+        response["threshold"] = serde_json::json!(threshold);
+    }
+
     Ok(Json(response))
-}
\ No newline at end of file
+}
+
+/// Finds the documents whose text is most similar to a document's, e.g. other letters
+/// from the same correspondence. Only documents of the same owner are considered.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/related",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        RelatedDocumentsQuery
+    ),
+    responses(
+        (status = 200, description = "Related documents, most similar first", body = RelatedDocumentsResponse),
+        (status = 400, description = "Invalid limit or min_similarity"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Document not found")
+    )
+)]
+pub async fn get_related_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Query(query): Query<RelatedDocumentsQuery>,
+) -> Result<Json<RelatedDocumentsResponse>, DocumentError> {
+    let limit = query.limit.unwrap_or(DEFAULT_RELATED_LIMIT);
+    if !(1..=MAX_RELATED_LIMIT).contains(&limit) {
+        return Err(DocumentError::BadRequest(format!("limit must be between 1 and {}", MAX_RELATED_LIMIT)));
+    }
+    let min_similarity = query.min_similarity.unwrap_or(DEFAULT_RELATED_MIN_SIMILARITY);
+    if !(0.0..=1.0).contains(&min_similarity) {
+        return Err(DocumentError::BadRequest("min_similarity must be between 0 and 1".to_string()));
+    }
+
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| DocumentError::InternalServerError(format!("Failed to get document: {}", e)))?
+        .ok_or(DocumentError::NotFound)?;
+
+    let related = state
+        .db
+        .find_related_documents(&document, RELATED_QUERY_TERMS, min_similarity, limit)
+        .await
+        .map_err(|e| DocumentError::InternalServerError(format!("Failed to find related documents: {}", e)))?;
+
+    Ok(Json(RelatedDocumentsResponse {
+        document_id,
+        related: related
+            .into_iter()
+            .map(|(document, similarity)| RelatedDocumentResponse { document: document.into(), similarity })
+            .collect(),
+    }))
+}
@@ -8,10 +8,11 @@ use tracing::{debug, error, info};
 
 use crate::{
     auth::AuthUser,
+    models::DocumentEvent,
     services::file_service::FileService,
     AppState,
 };
-use super::types::DocumentDebugInfo;
+use super::types::{DocumentDebugInfo, OcrDebugImages};
 
 /// Get comprehensive debug information for a document
 #[utoipa::path(
@@ -93,6 +94,24 @@ pub async fn get_document_debug_info(
         processing_steps.push("ocr_text_available".to_string());
     }
 
+    let history = state
+        .db
+        .get_document_events(document.id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting history of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let orientation = state
+        .db
+        .get_document_orientation(document.id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting orientation of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
     let debug_info = DocumentDebugInfo {
         document_id: document.id,
         filename: document.original_filename,
@@ -108,12 +127,63 @@ pub async fn get_document_debug_info(
         readable,
         permissions,
         user_settings,
+        history,
+        orientation,
     };
 
     debug!("Debug info generated for document: {}", document_id);
     Ok(Json(debug_info))
 }
 
+/// Get the processing history of a document
+///
+/// Lists the recorded lifecycle transitions, such as the upload, each time the document
+/// was queued, and the start and outcome of every OCR attempt, oldest first. Events older
+/// than `DOCUMENT_EVENT_RETENTION_DAYS` are pruned.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/history",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Document processing history", body = Vec<DocumentEvent>),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_document_history(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<Vec<DocumentEvent>>, StatusCode> {
+    state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let events = state
+        .db
+        .get_document_events(document_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting history of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(events))
+}
+
 /// Get thumbnail for a document (if available)
 #[utoipa::path(
     get,
@@ -126,7 +196,7 @@ pub async fn get_document_debug_info(
         ("id" = uuid::Uuid, Path, description = "Document ID")
     ),
     responses(
-        (status = 200, description = "Document thumbnail", content_type = "image/jpeg"),
+        (status = 200, description = "Document thumbnail (JPEG; PNG first-page render for PDFs)", content_type = "image/jpeg"),
         (status = 404, description = "Document or thumbnail not found"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
@@ -147,11 +217,39 @@ pub async fn get_document_thumbnail(
         })?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    let file_service = FileService::new(state.config.upload_path.clone());
+    let file_service = FileService::new(state.config.upload_path.clone()).with_asset_cache(state.derived_asset_cache.clone());
+
+    if document.mime_type == "application/pdf" {
+        let thumbnail = file_service.get_or_generate_pdf_thumbnail(&document).await.map_err(|e| {
+            error!("Failed to get or generate PDF thumbnail for document {}: {}", document_id, e);
+            StatusCode::NOT_FOUND
+        })?;
+
+        return axum::response::Response::builder()
+            .status(StatusCode::OK)
+            .header("Content-Type", thumbnail.content_type)
+            .header("Content-Length", thumbnail.data.len().to_string())
+            .header("Cache-Control", "public, max-age=3600")
+            .body(axum::body::Body::from(thumbnail.data))
+            .map_err(|e| {
+                error!("Failed to build thumbnail response: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            });
+    }
     
-    // Use the FileService to get or generate thumbnail
+    // Use the FileService to get or generate thumbnail, turned upright
     #[cfg(feature = "ocr")]
-    match file_service.get_or_generate_thumbnail(&document.file_path, &document.original_filename).await {
+    let rotation_degrees = state
+        .db
+        .get_document_orientation(document_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting orientation of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .map_or(0, |orientation| orientation.rotation_degrees as u32);
+    #[cfg(feature = "ocr")]
+    match file_service.get_or_generate_thumbnail(&document, rotation_degrees).await {
         Ok(data) => {
             let response = axum::response::Response::builder()
                 .status(StatusCode::OK)
@@ -244,6 +342,124 @@ pub async fn get_processed_image(
     }
 }
 
+/// Get the original and preprocessed image of a document side by side with the
+/// preprocessing parameters used for OCR. Requires `save_processed_images`.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/ocr/debug-images",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Image URLs and preprocessing parameters", body = OcrDebugImages),
+        (status = 404, description = "Document not found or no processed image was saved"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_ocr_debug_images(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<OcrDebugImages>, StatusCode> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let processed = state
+        .db
+        .get_processed_image_by_document_id(document.id, document.user_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting processed image for document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(OcrDebugImages {
+        document_id: document.id,
+        original_url: format!("/api/documents/{}/view", document.id),
+        original_mime_type: document.mime_type,
+        processed_url: format!("/api/documents/{}/ocr/debug-images/processed", document.id),
+        processed_width: processed.image_width,
+        processed_height: processed.image_height,
+        processed_file_size: processed.file_size,
+        processing_steps: processed.processing_steps,
+        parameters: processed.processing_parameters.get("preprocessing").cloned().unwrap_or_default(),
+        processed_at: processed.created_at,
+    }))
+}
+
+/// Get the preprocessed image referenced by the OCR debug images of a document
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/ocr/debug-images/processed",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Preprocessed image", content_type = "image/png"),
+        (status = 404, description = "Document not found or no processed image was saved"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_ocr_debug_processed_image(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<axum::response::Response, StatusCode> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let processed = state
+        .db
+        .get_processed_image_by_document_id(document.id, document.user_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting processed image for document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let image_data = tokio::fs::read(&processed.processed_image_path).await.map_err(|e| {
+        debug!("Processed image {} for document {} is not readable: {}", processed.processed_image_path, document_id, e);
+        StatusCode::NOT_FOUND
+    })?;
+    let content_type = mime_guess::from_path(&processed.processed_image_path).first_or(mime_guess::mime::IMAGE_PNG);
+
+    axum::response::Response::builder()
+        .status(StatusCode::OK)
+        .header("Content-Type", content_type.as_ref())
+        .header("Content-Length", image_data.len().to_string())
+        .body(axum::body::Body::from(image_data))
+        .map_err(|e| {
+            error!("Failed to build processed image response: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
 /// Get system-wide document statistics
 pub async fn get_document_statistics(
     State(state): State<Arc<AppState>>,
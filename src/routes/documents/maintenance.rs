@@ -0,0 +1,340 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    auth::AuthUser,
+    models::{BackfillDetails, DocumentRun, ReingestDetails},
+    routes::queue::require_admin,
+    services::{
+        document_backfill_service::DocumentBackfillService,
+        document_redownload_service::{DocumentRedownloadService, RedownloadError},
+        document_reingest_service::DocumentReingestService,
+        document_runs::{start_document_run, StartRunError},
+    },
+    AppState,
+};
+use super::types::{BulkRedownloadRequest, BulkRedownloadResponse, RedownloadRequest, RedownloadResult, ReingestRequest};
+
+fn start_run_error_status(kind: &str, error: StartRunError) -> StatusCode {
+    match error {
+        StartRunError::AlreadyRunning => StatusCode::CONFLICT,
+        StartRunError::Failed(e) => {
+            error!("Failed to start {}: {}", kind, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// Start a reingest of all document files
+///
+/// Re-hashes every document file on disk in the background and compares it with the
+/// database, flagging missing files and hash mismatches. Poll the returned run for progress.
+#[utoipa::path(
+    post,
+    path = "/api/documents/maintenance/reingest",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = ReingestRequest,
+    responses(
+        (status = 202, description = "Reingest started", body = DocumentRun<ReingestDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 409, description = "A reingest is already running"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn start_reingest(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<ReingestRequest>,
+) -> Result<(StatusCode, Json<DocumentRun<ReingestDetails>>), StatusCode> {
+    require_admin(&auth_user)?;
+
+    let total_documents = state.db.count_document_file_records().await.map_err(|e| {
+        error!("Failed to count documents for reingest: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let details = ReingestDetails { reenqueue_ocr: request.reenqueue_ocr, total_documents, ..Default::default() };
+    let run = start_document_run(&state, Some(auth_user.user.id), details)
+        .await
+        .map_err(|e| start_run_error_status("reingest", e))?;
+
+    info!("User {} started document reingest run {}", auth_user.user.id, run.id);
+    let service = DocumentReingestService::new(state.clone());
+    tokio::spawn({
+        let run = run.clone();
+        async move { service.run(run).await }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// Get the progress or report of a reingest run
+#[utoipa::path(
+    get,
+    path = "/api/documents/maintenance/reingest/{id}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Reingest run ID")
+    ),
+    responses(
+        (status = 200, description = "Reingest run", body = DocumentRun<ReingestDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Reingest run not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_reingest_run(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(run_id): Path<uuid::Uuid>,
+) -> Result<Json<DocumentRun<ReingestDetails>>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let run = state.db.get_document_run::<ReingestDetails>(run_id).await
+        .map_err(|e| {
+            error!("Failed to get reingest run {}: {}", run_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(run))
+}
+
+/// Start a backfill of missing file hashes and word counts
+///
+/// Computes the file hash of documents that have none from the file on disk, and the
+/// word count of documents that have OCR text but no count, in the background. OCR is
+/// not re-run. Documents whose file is missing are flagged. Poll the returned run for progress.
+#[utoipa::path(
+    post,
+    path = "/api/documents/maintenance/backfill",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 202, description = "Backfill started", body = DocumentRun<BackfillDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 409, description = "A backfill is already running"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn start_backfill(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<(StatusCode, Json<DocumentRun<BackfillDetails>>), StatusCode> {
+    require_admin(&auth_user)?;
+
+    let total_documents = state.db.count_document_backfill_records().await.map_err(|e| {
+        error!("Failed to count documents for backfill: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let details = BackfillDetails { total_documents, ..Default::default() };
+    let run = start_document_run(&state, Some(auth_user.user.id), details)
+        .await
+        .map_err(|e| start_run_error_status("backfill", e))?;
+
+    info!("User {} started document backfill run {}", auth_user.user.id, run.id);
+    let service = DocumentBackfillService::new(state.clone());
+    tokio::spawn({
+        let run = run.clone();
+        async move { service.run(run).await }
+    });
+
+    Ok((StatusCode::ACCEPTED, Json(run)))
+}
+
+/// Get the progress or report of a backfill run
+#[utoipa::path(
+    get,
+    path = "/api/documents/maintenance/backfill/{id}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Backfill run ID")
+    ),
+    responses(
+        (status = 200, description = "Backfill run", body = DocumentRun<BackfillDetails>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Backfill run not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_backfill_run(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(run_id): Path<uuid::Uuid>,
+) -> Result<Json<DocumentRun<BackfillDetails>>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let run = state.db.get_document_run::<BackfillDetails>(run_id).await
+        .map_err(|e| {
+            error!("Failed to get backfill run {}: {}", run_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(run))
+}
+
+/// Most documents one bulk re-download may name; each is downloaded in turn
+const MAX_BULK_REDOWNLOAD: usize = 100;
+
+fn redownload_error_status(error: &RedownloadError) -> StatusCode {
+    match error {
+        RedownloadError::NoSource | RedownloadError::UnsupportedSource(_) | RedownloadError::NoStoredHash => StatusCode::BAD_REQUEST,
+        RedownloadError::SourceNotFound | RedownloadError::HashMismatch { .. } => StatusCode::CONFLICT,
+        RedownloadError::DownloadFailed(_) => StatusCode::BAD_GATEWAY,
+        RedownloadError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// Re-downloads one document; failures are logged and reported in the result
+async fn redownload_one(
+    state: &Arc<AppState>,
+    auth_user: &AuthUser,
+    document_id: uuid::Uuid,
+    reenqueue_ocr: bool,
+) -> Result<RedownloadResult, (StatusCode, String)> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error".to_string())
+        })?
+        .ok_or((StatusCode::NOT_FOUND, "Document not found".to_string()))?;
+
+    match DocumentRedownloadService::new(state.clone()).redownload(&document, reenqueue_ocr).await {
+        Ok(outcome) => Ok(RedownloadResult {
+            document_id,
+            success: true,
+            file_size: Some(outcome.file_size),
+            ocr_enqueued: outcome.ocr_enqueued,
+            error: None,
+        }),
+        Err(e) => {
+            let status = redownload_error_status(&e);
+            if status.is_server_error() {
+                error!("Failed to re-download document {}: {}", document_id, e);
+            } else {
+                warn!("Not re-downloading document {}: {}", document_id, e);
+            }
+            Err((status, e.to_string()))
+        }
+    }
+}
+
+/// Re-download a document from its source
+///
+/// Fetches the file again from the source path it was synced from and replaces the stored
+/// file with it, repairing a corrupted or truncated copy without a full resync. The download
+/// must match the document's stored hash; otherwise the stored file is left untouched.
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/redownload",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body(content = RedownloadRequest, description = "Optional; OCR is not re-run by default"),
+    responses(
+        (status = 200, description = "File restored from the source", body = RedownloadResult),
+        (status = 400, description = "The document was not synced from a source that can download it again"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Document not found"),
+        (status = 409, description = "The source no longer exists, or its file no longer matches the document"),
+        (status = 502, description = "Download from the source failed"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn redownload_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    request: Option<Json<RedownloadRequest>>,
+) -> Response {
+    let Json(request) = request.unwrap_or_default();
+    match redownload_one(&state, &auth_user, document_id, request.reenqueue_ocr).await {
+        Ok(result) => Json(result).into_response(),
+        Err((status, message)) => (status, Json(serde_json::json!({
+            "document_id": document_id,
+            "error": message,
+        }))).into_response(),
+    }
+}
+
+/// Re-download several documents from their sources
+///
+/// Repairs each document like the single-document re-download and reports the outcome per
+/// document. At most 100 documents per request.
+#[utoipa::path(
+    post,
+    path = "/api/documents/bulk/redownload",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = BulkRedownloadRequest,
+    responses(
+        (status = 200, description = "Outcome per document", body = BulkRedownloadResponse),
+        (status = 400, description = "No documents, or more than 100"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn bulk_redownload_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<BulkRedownloadRequest>,
+) -> Result<Json<BulkRedownloadResponse>, StatusCode> {
+    if request.document_ids.is_empty() || request.document_ids.len() > MAX_BULK_REDOWNLOAD {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let mut results = Vec::with_capacity(request.document_ids.len());
+    for &document_id in &request.document_ids {
+        let result = redownload_one(&state, &auth_user, document_id, request.reenqueue_ocr)
+            .await
+            .unwrap_or_else(|(_, message)| RedownloadResult {
+                document_id,
+                success: false,
+                file_size: None,
+                ocr_enqueued: false,
+                error: Some(message),
+            });
+        results.push(result);
+    }
+
+    let redownloaded_count = results.iter().filter(|result| result.success).count() as i64;
+    info!(
+        "User {} re-downloaded {} of {} documents from their sources",
+        auth_user.user.id, redownloaded_count, results.len()
+    );
+
+    Ok(Json(BulkRedownloadResponse {
+        redownloaded_count,
+        failed_count: results.len() as i64 - redownloaded_count,
+        results,
+    }))
+}
@@ -8,6 +8,11 @@ pub mod ocr;
 pub mod bulk;
 pub mod debug;
 pub mod failed;
+pub mod trash;
+pub mod pdf;
+pub mod versions;
+pub mod shares;
+pub mod maintenance;
 
 // Re-export commonly used types and functions for backward compatibility
 pub use types::*;
@@ -16,6 +21,11 @@ pub use ocr::*;
 pub use bulk::*;
 pub use debug::*;
 pub use failed::*;
+pub use trash::*;
+pub use pdf::*;
+pub use versions::*;
+pub use shares::*;
+pub use maintenance::*;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -26,11 +36,30 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/{id}", delete(delete_document))
         .route("/{id}/download", get(download_document))
         .route("/{id}/view", get(view_document))
+        .route("/{id}/content", get(export_document_content))
+        .route("/{id}/related", get(get_related_documents))
+        .route("/{id}/restore", post(restore_document))
+        .route("/{id}/split", post(split_document))
+        .route("/{id}/rotate", post(rotate_document))
+        .route("/{id}/searchable-pdf", get(download_searchable_pdf))
+        
+        // Versions
+        .route("/{id}/versions", get(list_document_versions))
+        .route("/{id}/versions/{version}", get(get_document_version))
+        .route("/{id}/versions/{version}/download", get(download_document_version))
+
+        // Sharing
+        .route("/{id}/share", post(create_document_share))
+        .route("/{id}/shares", get(list_document_shares))
+        .route("/{id}/shares/{token}", delete(delete_document_share))
         
         // OCR operations
-        .route("/{id}/ocr", get(get_document_ocr))
+        .route("/{id}/ocr", get(get_document_ocr).put(correct_document_ocr))
+        .route("/{id}/ocr/boxes", get(get_document_ocr_boxes))
         .route("/{id}/ocr/retry", post(retry_ocr))
         .route("/ocr/stats", get(get_ocr_stats))
+        .route("/ocr/retry-failed", post(retry_failed_ocr))
+        .route("/ocr/reprocess", post(reprocess_ocr))
         .route("/{id}/ocr/stop", post(cancel_ocr))
         
         // OCR retry operations
@@ -41,16 +70,35 @@ pub fn router() -> Router<Arc<AppState>> {
         
         // Bulk operations
         .route("/bulk/delete", post(bulk_delete_documents))
+        .route("/bulk/assign-source", post(bulk_assign_source))
+        .route("/bulk/redownload", post(bulk_redownload_documents))
+        .route("/import-metadata", post(import_document_metadata))
+        .route("/merge", post(merge_documents))
         .route("/cleanup/low-confidence", delete(delete_low_confidence_documents))
         .route("/cleanup/failed-ocr", delete(delete_failed_ocr_documents))
+        .route("/cleanup/retention", post(apply_retention_cleanup))
+        
+        // Maintenance
+        .route("/maintenance/reingest", post(start_reingest))
+        .route("/maintenance/reingest/{id}", get(get_reingest_run))
+        .route("/maintenance/backfill", post(start_backfill))
+        .route("/maintenance/backfill/{id}", get(get_backfill_run))
+        .route("/{id}/redownload", post(redownload_document))
         
         // Debug operations
         .route("/{id}/debug", get(get_document_debug_info))
+        .route("/{id}/history", get(get_document_history))
         .route("/{id}/thumbnail", get(get_document_thumbnail))
         .route("/{id}/processed", get(get_processed_image))
+        .route("/{id}/ocr/debug-images", get(get_ocr_debug_images))
+        .route("/{id}/ocr/debug-images/processed", get(get_ocr_debug_processed_image))
         .route("/{id}/validate", get(validate_document_integrity))
         .route("/duplicates", get(get_user_duplicates))
         
+        // Trash
+        .route("/trash", get(list_trash))
+        .route("/trash", delete(purge_trash))
+        
         // Failed documents
         .route("/failed", get(get_failed_documents))
         .route("/failed/{id}", get(view_failed_document))
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{Json, Path, State},
+    extract::{Json, Path, Query, State},
     http::StatusCode,
     response::Json as ResponseJson,
 };
@@ -8,10 +8,22 @@ use tracing::{debug, error, info, warn};
 
 use crate::{
     auth::AuthUser,
-    models::DocumentOcrResponse,
+    models::{DocumentOcrResponse, UserRole},
+    ocr::queue::{FailedOcrSelection, OcrReprocessSelection},
     AppState,
 };
 
+/// Most failed documents a single reprocess call enqueues
+const RETRY_FAILED_BATCH_LIMIT: i64 = 500;
+/// Below the priority of uploads and source syncs so they are not held up by a reprocess
+const RETRY_FAILED_PRIORITY: i32 = 1;
+/// Language reprocessing can cover a whole library, so it also yields to new documents
+const REPROCESS_PRIORITY: i32 = 1;
+/// Pages of word boxes returned when the request does not say
+const DEFAULT_OCR_BOX_PAGES: i64 = 10;
+/// Most pages of word boxes returned by one request
+const MAX_OCR_BOX_PAGES: i64 = 50;
+
 /// Get OCR text for a document
 #[utoipa::path(
     get,
@@ -45,6 +57,32 @@ pub async fn get_document_ocr(
         })?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    let script_detection = state
+        .db
+        .get_document_script_detection(document_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting detected script of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let (detected_script, script_confidence) = script_detection.unzip();
+    let manually_edited = state
+        .db
+        .is_document_ocr_manually_edited(document_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting OCR edit state of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let text_source = state
+        .db
+        .get_document_ocr_text_source(document_id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting text source of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
     let response = DocumentOcrResponse {
         id: document.id,
         filename: document.original_filename,
@@ -54,12 +92,130 @@ pub async fn get_document_ocr(
         ocr_status: document.ocr_status,
         ocr_processing_time_ms: document.ocr_processing_time_ms,
         detected_language: None, // This would need to be stored separately if needed
+        detected_script,
+        script_confidence,
         pages_processed: None,   // This would need to be stored separately if needed
+        manually_edited,
+        text_source,
     };
 
     Ok(ResponseJson(response))
 }
 
+/// Correct the OCR text of a document
+///
+/// Replaces the OCR text with text corrected by hand. Later OCR runs keep the correction:
+/// reprocessing skips the document unless forced, and a retry asks for confirmation.
+#[utoipa::path(
+    put,
+    path = "/api/documents/{id}/ocr",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = super::types::CorrectOcrTextRequest,
+    responses(
+        (status = 204, description = "OCR text corrected"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 409, description = "OCR is running for the document"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn correct_document_ocr(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<super::types::CorrectOcrTextRequest>,
+) -> Result<StatusCode, StatusCode> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // The running job would overwrite the correction when it finishes
+    if document.ocr_status.as_deref() == Some("processing") {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    state
+        .db
+        .correct_document_ocr_text(document.id, &request.ocr_text)
+        .await
+        .map_err(|e| {
+            error!("Failed to save corrected OCR text of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("User {} corrected the OCR text of document {}", auth_user.user.id, document_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get word-level OCR boxes for a document
+///
+/// Returns the recognized words of image and PDF documents with their confidence and
+/// bounding box, a range of pages at a time.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/ocr/boxes",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        super::types::OcrBoxesQuery
+    ),
+    responses(
+        (status = 200, description = "Word boxes for the requested pages", body = super::types::OcrBoxesResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_document_ocr_boxes(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Query(query): Query<super::types::OcrBoxesQuery>,
+) -> Result<ResponseJson<super::types::OcrBoxesResponse>, StatusCode> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let first_page = query.page.unwrap_or(1).max(1);
+    let limit = query.limit.unwrap_or(DEFAULT_OCR_BOX_PAGES).clamp(1, MAX_OCR_BOX_PAGES);
+
+    let (pages, total_pages) = state
+        .db
+        .get_document_ocr_words(document.id, first_page, limit)
+        .await
+        .map_err(|e| {
+            error!("Database error getting word boxes for document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(ResponseJson(super::types::OcrBoxesResponse {
+        document_id: document.id,
+        total_pages,
+        pages,
+    }))
+}
+
 /// Retry OCR processing for a document
 #[utoipa::path(
     post,
@@ -74,9 +230,10 @@ pub async fn get_document_ocr(
     request_body(content = super::types::RetryOcrRequest, description = "OCR retry options"),
     responses(
         (status = 200, description = "OCR retry initiated"),
+        (status = 400, description = "Invalid language or OCR override values"),
         (status = 404, description = "Document not found"),
         (status = 401, description = "Unauthorized"),
-        (status = 409, description = "OCR already in progress"),
+        (status = 409, description = "The OCR text was corrected by hand and `overwrite_manual_edits` is not set"),
         (status = 500, description = "Internal server error")
     )
 )]
@@ -87,7 +244,13 @@ pub async fn retry_ocr(
     Json(request): Json<super::types::RetryOcrRequest>,
 ) -> Result<ResponseJson<serde_json::Value>, StatusCode> {
     debug!("OCR retry request for document {} by user {}", document_id, auth_user.user.id);
-    debug!("Request data: language={:?}, languages={:?}", request.language, request.languages);
+    debug!("Request data: language={:?}, languages={:?}, overrides={:?}", request.language, request.languages, request.overrides);
+
+    if let Err(e) = request.overrides.validate() {
+        warn!("Invalid OCR overrides for document {}: {}", document_id, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     // Get document first to check if it exists and user has access
     let document = state
         .db
@@ -109,6 +272,19 @@ pub async fn retry_ocr(
         }
     }
 
+    // Corrected text is only replaced once the user confirms it
+    let manually_edited = state
+        .db
+        .is_document_ocr_manually_edited(document.id)
+        .await
+        .map_err(|e| {
+            error!("Database error getting OCR edit state of document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    if manually_edited && !request.overwrite_manual_edits {
+        return Err(StatusCode::CONFLICT);
+    }
+
     // Update user's OCR language settings based on what was provided
     if let Some(languages) = &request.languages {
         // Multi-language support: validate and update preferred languages
@@ -152,8 +328,23 @@ pub async fn retry_ocr(
         }
     }
 
-    // Add to OCR queue
-    match state.queue_service.enqueue_document(document.id, 5, document.file_size).await {
+    if manually_edited {
+        if let Err(e) = state.db.mark_document_ocr_pending(document.id).await {
+            error!("Failed to reset OCR status of document {}: {}", document_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+
+    // Add to OCR queue, carrying any overrides with the job rather than saving them
+    let enqueue_result = if request.overrides.is_empty() && !manually_edited {
+        state.queue_service.enqueue_document(document.id, 5, document.file_size).await
+    } else {
+        state.queue_service.enqueue_document_with_overrides(
+            document.id, 5, document.file_size, &request.overrides, manually_edited,
+        ).await
+    };
+
+    match enqueue_result {
         Ok(_) => {
             info!("Document {} queued for OCR retry", document_id);
             Ok(ResponseJson(serde_json::json!({
@@ -168,6 +359,142 @@ pub async fn retry_ocr(
     }
 }
 
+/// Reprocess failed OCR documents
+///
+/// Resets matching documents with a failed OCR status to pending and enqueues them at low
+/// priority, at most 500 per call. Call again while `remaining` is above zero.
+#[utoipa::path(
+    post,
+    path = "/api/documents/ocr/retry-failed",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = super::types::RetryFailedOcrRequest,
+    responses(
+        (status = 200, description = "Failed documents enqueued for OCR", body = super::types::RetryFailedOcrResponse),
+        (status = 400, description = "Invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "all_users requires admin access"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn retry_failed_ocr(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<super::types::RetryFailedOcrRequest>,
+) -> Result<ResponseJson<super::types::RetryFailedOcrResponse>, StatusCode> {
+    if request.all_users && auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+    if let (Some(after), Some(before)) = (request.after, request.before) {
+        if after >= before {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let selection = FailedOcrSelection {
+        user_id: if request.all_users { None } else { Some(auth_user.user.id) },
+        failure_reason: request.failure_reason,
+        created_after: request.after,
+        created_before: request.before,
+    };
+
+    let (enqueued, remaining) = state
+        .queue_service
+        .requeue_failed_documents(&selection, RETRY_FAILED_BATCH_LIMIT, RETRY_FAILED_PRIORITY, auth_user.user.id)
+        .await
+        .map_err(|e| {
+            error!("Failed to requeue failed OCR documents: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("User {} requeued {} failed documents for OCR, {} remaining", auth_user.user.id, enqueued, remaining);
+    Ok(ResponseJson(super::types::RetryFailedOcrResponse { enqueued, remaining }))
+}
+
+/// Reprocess documents with a different OCR language
+///
+/// Clears the OCR text of the matching documents and reads them again at low priority, with
+/// `language` if given. Without any filter every document of the user matches. The language
+/// applies to these jobs only; the user's saved settings are not changed. Documents whose OCR
+/// text was corrected by hand are skipped unless `force` is set. The documents are recorded
+/// in a batch job that queues them a window at a time and survives restarts.
+#[utoipa::path(
+    post,
+    path = "/api/documents/ocr/reprocess",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = super::types::ReprocessOcrRequest,
+    responses(
+        (status = 200, description = "Batch job created for the matching documents", body = super::types::ReprocessOcrResponse),
+        (status = 400, description = "Unavailable language or invalid date range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn reprocess_ocr(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<super::types::ReprocessOcrRequest>,
+) -> Result<ResponseJson<super::types::ReprocessOcrResponse>, StatusCode> {
+    if let (Some(after), Some(before)) = (request.created_after, request.created_before) {
+        if after >= before {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let overrides = crate::models::OcrOverrides {
+        language: request.language.clone(),
+        ..Default::default()
+    };
+    if let Err(e) = overrides.validate() {
+        warn!("Invalid OCR reprocess language {:?}: {}", request.language, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if let Some(language) = &request.language {
+        if let Err(e) = crate::ocr::health::OcrHealthChecker::new().validate_language_combination(language) {
+            warn!("OCR reprocess language '{}' is not available: {}", language, e);
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let selection = OcrReprocessSelection {
+        user_id: auth_user.user.id,
+        document_ids: request.document_ids,
+        mime_types: request.mime_types,
+        source_id: request.source_id,
+        created_after: request.created_after,
+        created_before: request.created_before,
+    };
+
+    let job = state
+        .queue_service
+        .create_reprocess_batch_job(&selection, &overrides, REPROCESS_PRIORITY, request.force)
+        .await
+        .map_err(|e| {
+            error!("Failed to create OCR reprocess batch job: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    // The first window is queued right away; the rest follows as jobs finish
+    if let Err(e) = state.queue_service.feed_batch_job(job.id).await {
+        warn!("Failed to queue the first documents of batch job {}: {}", job.id, e);
+    }
+
+    info!(
+        "User {} started batch job {} reprocessing {} documents with language {:?}",
+        auth_user.user.id, job.id, job.total, request.language
+    );
+    Ok(ResponseJson(super::types::ReprocessOcrResponse {
+        job_id: job.id,
+        queued: i64::from(job.total - job.skipped),
+        skipped: i64::from(job.skipped),
+    }))
+}
+
 /// Get OCR processing status for multiple documents
 pub async fn get_ocr_status_batch(
     State(state): State<Arc<AppState>>,
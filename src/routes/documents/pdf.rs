@@ -0,0 +1,623 @@
+use axum::{
+    body::Body,
+    extract::{Path, Query, State},
+    http::{header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE}, StatusCode},
+    response::{Json, Response},
+};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    auth::AuthUser,
+    ingestion::document_ingestion::{
+        DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+    },
+    models::DocumentResponse,
+    ocr::orientation::{can_rotate_image_data, normalize_rotation, rotate_image_data},
+    services::{
+        file_service::FileService,
+        pdf_service::{validate_page_ranges, validate_pages, PdfService},
+    },
+    AppState,
+};
+use super::crud::DocumentError;
+use super::types::{
+    MergeDocumentsRequest, RotateDocumentRequest, RotateDocumentResponse, SplitDocumentQuery,
+    SplitDocumentRequest, SplitDocumentResponse,
+};
+
+/// Most documents a single merge may combine
+const MAX_MERGE_DOCUMENTS: usize = 100;
+
+/// Split a PDF into one new document per page range
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/split",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        SplitDocumentQuery
+    ),
+    request_body = SplitDocumentRequest,
+    responses(
+        (status = 200, description = "Documents created from the requested page ranges", body = SplitDocumentResponse),
+        (status = 400, description = "Document is not a PDF or the page ranges are invalid"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn split_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Query(query): Query<SplitDocumentQuery>,
+    Json(request): Json<SplitDocumentRequest>,
+) -> Result<Json<SplitDocumentResponse>, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    if document.mime_type != "application/pdf" {
+        return Err(DocumentError::BadRequest("Only PDF documents can be split".to_string()));
+    }
+
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+    let pdf_service = PdfService::new(file_service.get_temp_path());
+
+    let source_path = file_service.resolve_file_path(&document.file_path).await.map_err(|e| {
+        error!("File for document {} is missing: {}", document_id, e);
+        DocumentError::InternalServerError("Document file not found".to_string())
+    })?;
+    let source_path = std::path::PathBuf::from(source_path);
+
+    let page_count = pdf_service.page_count(&source_path).await.map_err(|e| {
+        error!("Failed to read page count of document {}: {}", document_id, e);
+        DocumentError::FileProcessingError(format!("Could not read PDF: {}", e))
+    })?;
+    validate_page_ranges(&request.ranges, page_count).map_err(DocumentError::BadRequest)?;
+
+    let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service.clone());
+    let stem = std::path::Path::new(&document.original_filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("document");
+
+    let mut document_ids = Vec::with_capacity(request.ranges.len());
+    for &[first, last] in &request.ranges {
+        let file_data = pdf_service.extract_pages(&source_path, first, last).await.map_err(|e| {
+            error!("Failed to extract pages {}-{} of document {}: {}", first, last, document_id, e);
+            DocumentError::FileProcessingError(format!("Failed to extract pages {}-{}: {}", first, last, e))
+        })?;
+
+        let filename = if first == last {
+            format!("{}_page_{}.pdf", stem, first)
+        } else {
+            format!("{}_pages_{}-{}.pdf", stem, first, last)
+        };
+
+        let result = ingestion_service
+            .ingest_document(DocumentIngestionRequest {
+                filename: filename.clone(),
+                original_filename: filename.clone(),
+                file_data,
+                mime_type: "application/pdf".to_string(),
+                user_id: document.user_id,
+                deduplication_policy: DeduplicationPolicy::ReturnExisting,
+                source_type: Some("split".to_string()),
+                source_id: None,
+                original_created_at: document.original_created_at,
+                original_modified_at: document.original_modified_at,
+                source_path: None,
+                file_permissions: None,
+                file_owner: None,
+                file_group: None,
+                source_metadata: Some(serde_json::json!({
+                    "split_from": document.id,
+                    "pages": [first, last],
+                })),
+            })
+            .await
+            .map_err(|e| {
+                error!("Failed to ingest split document {}: {}", filename, e);
+                DocumentError::InternalServerError(format!("Failed to store {}", filename))
+            })?;
+
+        match result {
+            IngestionResult::Created(split) | IngestionResult::NewVersion(split) => {
+                if !document.tags.is_empty() {
+                    if let Err(e) = state.db.update_document_tags(split.id, &document.tags).await {
+                        warn!("Failed to copy tags to split document {}: {}", split.id, e);
+                    }
+                }
+
+                let priority = 5; // Same priority as direct uploads
+                if let Err(e) = state.queue_service.enqueue_document(split.id, priority, split.file_size).await {
+                    error!("Failed to enqueue split document {} for OCR: {}", split.id, e);
+                }
+
+                document_ids.push(split.id);
+            }
+            IngestionResult::ExistingDocument(existing) => {
+                info!("Pages {}-{} of document {} match existing document {}", first, last, document_id, existing.id);
+                document_ids.push(existing.id);
+            }
+            IngestionResult::Skipped { existing_document_id, .. }
+            | IngestionResult::TrackedAsDuplicate { existing_document_id } => {
+                document_ids.push(existing_document_id);
+            }
+        }
+    }
+
+    let mut original_deleted = false;
+    if query.delete_original.unwrap_or(false) {
+        original_deleted = state
+            .db
+            .delete_document(document_id, auth_user.user.id, auth_user.user.role)
+            .await
+            .map_err(|e| {
+                error!("Failed to delete original document {} after split: {}", document_id, e);
+                DocumentError::InternalServerError("Split succeeded but the original could not be deleted".to_string())
+            })?;
+
+        if original_deleted {
+            match file_service.move_to_trash(&document).await {
+                Ok(trash_path) if trash_path != document.file_path => {
+                    if let Err(e) = state.db.update_document_file_path(document_id, &trash_path).await {
+                        error!("Failed to record trash path for document {}: {}", document_id, e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed to move file for document {} to trash: {}", document_id, e),
+            }
+        }
+    }
+
+    info!("Split document {} into {} documents", document_id, document_ids.len());
+    Ok(Json(SplitDocumentResponse {
+        document_ids,
+        original_deleted,
+    }))
+}
+
+/// Rotate a document's pages
+///
+/// Turns an image, or all or some pages of a PDF, clockwise and stores the result as a new
+/// version of the document; the previous file stays in its version history. OCR runs again
+/// on the rotated file unless `reprocess_ocr` is false.
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/rotate",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = RotateDocumentRequest,
+    responses(
+        (status = 200, description = "Document rotated", body = RotateDocumentResponse),
+        (status = 400, description = "Invalid degrees or pages"),
+        (status = 404, description = "Document not found"),
+        (status = 409, description = "OCR is running for the document"),
+        (status = 415, description = "Document is neither a PDF nor an image that can be rotated"),
+        (status = 422, description = "The file could not be rotated"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn rotate_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<RotateDocumentRequest>,
+) -> Result<Json<RotateDocumentResponse>, DocumentError> {
+    let degrees = normalize_rotation(request.degrees)
+        .filter(|&degrees| degrees != 0)
+        .ok_or_else(|| DocumentError::BadRequest("degrees must be 90, 180 or 270".to_string()))? as u32;
+
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    let is_pdf = document.mime_type == "application/pdf";
+    if !is_pdf && !can_rotate_image_data(&document.mime_type) {
+        return Err(DocumentError::UnsupportedMediaType(format!(
+            "Documents of type {} can't be rotated",
+            document.mime_type
+        )));
+    }
+    if !is_pdf && request.pages.is_some() {
+        return Err(DocumentError::BadRequest("Pages can only be selected for PDFs".to_string()));
+    }
+    if document.ocr_status.as_deref() == Some("processing") {
+        return Err(DocumentError::Conflict(
+            "OCR is running for this document; rotate it once OCR has finished".to_string(),
+        ));
+    }
+
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+    let source_path = file_service.resolve_file_path(&document.file_path).await.map_err(|e| {
+        error!("File for document {} is missing: {}", document_id, e);
+        DocumentError::InternalServerError("Document file not found".to_string())
+    })?;
+    let source_path = std::path::PathBuf::from(source_path);
+
+    let (file_data, detail) = if is_pdf {
+        let pdf_service = PdfService::new(file_service.get_temp_path());
+        if let Some(pages) = &request.pages {
+            let page_count = pdf_service.page_count(&source_path).await.map_err(|e| {
+                error!("Failed to read page count of document {}: {}", document_id, e);
+                DocumentError::FileProcessingError(format!("Could not read PDF: {}", e))
+            })?;
+            validate_pages(pages, page_count).map_err(DocumentError::BadRequest)?;
+        }
+
+        let file_data = pdf_service
+            .rotate_pages(&source_path, degrees, request.pages.as_deref())
+            .await
+            .map_err(|e| {
+                error!("Failed to rotate pages of document {}: {}", document_id, e);
+                DocumentError::FileProcessingError(format!("Failed to rotate PDF: {}", e))
+            })?;
+        let detail = match &request.pages {
+            Some(pages) => {
+                let noun = if pages.len() == 1 { "page" } else { "pages" };
+                let pages: Vec<String> = pages.iter().map(u32::to_string).collect();
+                format!("rotated {} {} by {}°", noun, pages.join(", "), degrees)
+            }
+            None => format!("rotated by {}°", degrees),
+        };
+        (file_data, detail)
+    } else {
+        // The image is shown turned by its recorded rotation, which the rotated file takes over
+        let shown_rotation = state
+            .db
+            .get_document_orientation(document_id)
+            .await
+            .map_err(|e| {
+                error!("Database error getting orientation of document {}: {}", document_id, e);
+                DocumentError::InternalServerError("Failed to load document".to_string())
+            })?
+            .map_or(0, |orientation| orientation.rotation_degrees as u32);
+        let total_degrees = (shown_rotation + degrees) % 360;
+
+        if total_degrees == 0 {
+            // Turning it back to how the file is stored only drops the shown rotation
+            reset_shown_rotation(&state, &file_service, &document).await?;
+            let version_number = state.db.get_document_version_number(document_id).await.map_err(|e| {
+                error!("Database error getting version of document {}: {}", document_id, e);
+                DocumentError::InternalServerError("Failed to load document".to_string())
+            })?;
+            return Ok(Json(RotateDocumentResponse {
+                document_id,
+                version_number,
+                pages: None,
+                ocr_queued: false,
+            }));
+        }
+
+        let original = tokio::fs::read(&source_path).await.map_err(|e| {
+            error!("Failed to read file of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to read document file".to_string())
+        })?;
+        let mime_type = document.mime_type.clone();
+        let file_data = tokio::task::spawn_blocking(move || rotate_image_data(&original, &mime_type, total_degrees))
+            .await
+            .map_err(|e| DocumentError::InternalServerError(format!("Image rotation failed: {}", e)))?
+            .map_err(|e| {
+                error!("Failed to rotate image of document {}: {}", document_id, e);
+                DocumentError::FileProcessingError(format!("Failed to rotate image: {}", e))
+            })?;
+        (file_data, format!("rotated by {}°", degrees))
+    };
+
+    let reprocess_ocr = request.reprocess_ocr.unwrap_or(true);
+    let manually_edited = !reprocess_ocr
+        && state.db.is_document_ocr_manually_edited(document_id).await.unwrap_or(false);
+
+    let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service.clone())
+        .with_version_retention(state.config.document_version_retention);
+    let rotated = ingestion_service
+        .replace_document_content(&document, &file_data, &detail)
+        .await
+        .map_err(|e| {
+            error!("Failed to store rotated file of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to store rotated document".to_string())
+        })?;
+
+    if !is_pdf {
+        reset_shown_rotation(&state, &file_service, &document).await?;
+    }
+
+    let mut ocr_queued = false;
+    if reprocess_ocr {
+        let priority = 5; // Same priority as direct uploads
+        match state.queue_service.enqueue_document(document_id, priority, rotated.file_size).await {
+            Ok(_) => ocr_queued = true,
+            Err(e) => error!("Failed to enqueue rotated document {} for OCR: {}", document_id, e),
+        }
+    } else {
+        // The text still applies, but word positions refer to the unrotated pages
+        if let Err(e) = state.db.restore_document_text(document_id, &document, manually_edited).await {
+            error!("Failed to keep the text of rotated document {}: {}", document_id, e);
+        }
+        if let Err(e) = state.db.replace_document_ocr_words(document_id, &[]).await {
+            warn!("Failed to clear word positions of rotated document {}: {}", document_id, e);
+        }
+    }
+
+    let version_number = state.db.get_document_version_number(document_id).await.map_err(|e| {
+        error!("Database error getting version of document {}: {}", document_id, e);
+        DocumentError::InternalServerError("Failed to load document".to_string())
+    })?;
+
+    info!("Document {} {}, now version {}", document_id, detail, version_number);
+    Ok(Json(RotateDocumentResponse {
+        document_id,
+        version_number,
+        pages: request.pages,
+        ocr_queued,
+    }))
+}
+
+/// Shows an image as its file is stored, with any cached thumbnail of the old rotation dropped
+async fn reset_shown_rotation(state: &AppState, file_service: &FileService, document: &crate::models::Document) -> Result<(), DocumentError> {
+    state.db.set_document_rotation(document.id, 0).await.map_err(|e| {
+        error!("Failed to reset rotation of document {}: {}", document.id, e);
+        DocumentError::InternalServerError("Failed to rotate document".to_string())
+    })?;
+    file_service.remove_thumbnail(&document.file_path).await;
+    Ok(())
+}
+
+/// Merge several PDFs into a single new document
+#[utoipa::path(
+    post,
+    path = "/api/documents/merge",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body = MergeDocumentsRequest,
+    responses(
+        (status = 200, description = "Merged document", body = DocumentResponse),
+        (status = 400, description = "An input is not a PDF, not owned by the caller, or the list is invalid"),
+        (status = 409, description = "A document with the merged content already exists"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn merge_documents(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(request): Json<MergeDocumentsRequest>,
+) -> Result<Json<DocumentResponse>, DocumentError> {
+    if request.document_ids.len() < 2 {
+        return Err(DocumentError::BadRequest("At least two documents are required to merge".to_string()));
+    }
+    if request.document_ids.len() > MAX_MERGE_DOCUMENTS {
+        return Err(DocumentError::BadRequest(format!("At most {} documents can be merged at once", MAX_MERGE_DOCUMENTS)));
+    }
+
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+
+    let mut sources = Vec::with_capacity(request.document_ids.len());
+    let mut source_paths = Vec::with_capacity(request.document_ids.len());
+    for &document_id in &request.document_ids {
+        let document = state
+            .db
+            .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+            .await
+            .map_err(|e| {
+                error!("Database error getting document {}: {}", document_id, e);
+                DocumentError::InternalServerError("Failed to load documents".to_string())
+            })?
+            .filter(|document| document.user_id == auth_user.user.id)
+            .ok_or_else(|| DocumentError::BadRequest(format!("Document {} was not found or is not owned by you", document_id)))?;
+
+        if document.mime_type != "application/pdf" {
+            return Err(DocumentError::BadRequest(format!(
+                "Document {} ({}) is not a PDF",
+                document_id, document.original_filename
+            )));
+        }
+
+        let path = file_service.resolve_file_path(&document.file_path).await.map_err(|e| {
+            error!("File for document {} is missing: {}", document_id, e);
+            DocumentError::InternalServerError(format!("File for document {} not found", document_id))
+        })?;
+        source_paths.push(std::path::PathBuf::from(path));
+        sources.push(document);
+    }
+
+    let pdf_service = PdfService::new(file_service.get_temp_path());
+    let file_data = pdf_service.merge(&source_paths).await.map_err(|e| {
+        error!("Failed to merge documents {:?}: {}", request.document_ids, e);
+        DocumentError::FileProcessingError(format!("Failed to merge PDFs: {}", e))
+    })?;
+
+    let file_hash = format!("{:x}", Sha256::digest(&file_data));
+    if let Ok(Some(existing)) = state.db.get_document_by_user_and_hash(auth_user.user.id, &file_hash).await {
+        return Err(DocumentError::Conflict(format!(
+            "These documents were already merged as {} ({})",
+            existing.original_filename, existing.id
+        )));
+    }
+
+    let filename = match request.filename.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() && name.to_lowercase().ends_with(".pdf") => name.to_string(),
+        Some(name) if !name.is_empty() => format!("{}.pdf", name),
+        _ => {
+            let stem = std::path::Path::new(&sources[0].original_filename)
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("document");
+            format!("{}_merged.pdf", stem)
+        }
+    };
+
+    let file_path = file_service.save_file(&filename, &file_data).await.map_err(|e| {
+        error!("Failed to store merged document {}: {}", filename, e);
+        DocumentError::InternalServerError("Failed to store merged document".to_string())
+    })?;
+
+    let mut merged = file_service.create_document(
+        &filename,
+        &filename,
+        &file_path,
+        file_data.len() as i64,
+        "application/pdf",
+        auth_user.user.id,
+        Some(file_hash),
+        None,
+        None,
+        None,
+        Some("merge".to_string()),
+        None,
+        None,
+        None,
+        None,
+        Some(serde_json::json!({ "merged_from": request.document_ids })),
+    );
+
+    // Carry the existing text over so the merged document is searchable before OCR re-runs
+    let texts: Vec<&str> = sources
+        .iter()
+        .filter_map(|d| d.ocr_text.as_deref().or(d.content.as_deref()))
+        .filter(|text| !text.trim().is_empty())
+        .collect();
+    if !texts.is_empty() {
+        merged.ocr_text = Some(texts.join("\n\n"));
+        merged.ocr_word_count = Some(sources.iter().filter_map(|d| d.ocr_word_count).sum());
+    }
+
+    for tag in sources.iter().flat_map(|d| d.tags.iter()) {
+        if !merged.tags.contains(tag) {
+            merged.tags.push(tag.clone());
+        }
+    }
+
+    // Record and file go together: never leave the merged file behind without its row
+    let merged = match state.db.create_document(merged).await {
+        Ok(document) => document,
+        Err(e) => {
+            if let Err(remove_err) = tokio::fs::remove_file(&file_path).await {
+                warn!("Failed to remove orphaned merged file {}: {}", file_path, remove_err);
+            }
+            error!("Failed to create merged document record: {}", e);
+            return Err(DocumentError::InternalServerError("Failed to create merged document".to_string()));
+        }
+    };
+
+    // Regenerate OCR for the combined file in the background
+    let priority = 5; // Same priority as direct uploads
+    if let Err(e) = state.queue_service.enqueue_document(merged.id, priority, merged.file_size).await {
+        error!("Failed to enqueue merged document {} for OCR: {}", merged.id, e);
+    }
+
+    info!("Merged {} documents into {}", sources.len(), merged.id);
+    Ok(Json(DocumentResponse::from(merged)))
+}
+
+/// Download a searchable PDF of a document
+///
+/// Returns the original PDF or image with the OCR text layered over it, so the text can be
+/// selected and searched in any PDF viewer. The PDF is generated with ocrmypdf on the first
+/// request and cached until the document's file changes.
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/searchable-pdf",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Searchable PDF", content_type = "application/pdf"),
+        (status = 400, description = "Document is neither a PDF nor an image"),
+        (status = 404, description = "Document not found"),
+        (status = 409, description = "OCR has not completed for the document yet"),
+        (status = 422, description = "ocrmypdf could not process the file"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn download_searchable_pdf(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Response, DocumentError> {
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)?;
+
+    if document.mime_type != "application/pdf" && !document.mime_type.starts_with("image/") {
+        return Err(DocumentError::BadRequest("Only PDF and image documents have a searchable PDF".to_string()));
+    }
+    if document.ocr_status.as_deref() != Some("completed") {
+        return Err(DocumentError::Conflict(
+            "OCR has not completed for this document; run OCR first".to_string(),
+        ));
+    }
+
+    // Read with the owner's languages, as the document's OCR was
+    let settings = state
+        .db
+        .get_user_settings(document.user_id)
+        .await
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let language = if settings.preferred_languages.is_empty() {
+        settings.ocr_language.clone()
+    } else {
+        settings.preferred_languages.join("+")
+    };
+
+    let file_service = FileService::new(state.config.upload_path.clone()).with_asset_cache(state.derived_asset_cache.clone());
+    let data = file_service.get_or_generate_searchable_pdf(&document, &language).await.map_err(|e| {
+        error!("Failed to generate searchable PDF for document {}: {}", document_id, e);
+        DocumentError::FileProcessingError(format!("Failed to generate searchable PDF: {}", e))
+    })?;
+
+    let stem = std::path::Path::new(&document.original_filename)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("document");
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/pdf")
+        .header(CONTENT_LENGTH, data.len().to_string())
+        .header(CONTENT_DISPOSITION, format!("attachment; filename=\"{}_searchable.pdf\"", stem))
+        .body(Body::from(data))
+        .map_err(|e| {
+            error!("Failed to build searchable PDF response: {}", e);
+            DocumentError::InternalServerError("Failed to build response".to_string())
+        })
+}
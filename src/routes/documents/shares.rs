@@ -0,0 +1,167 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    auth::AuthUser,
+    models::{Document, DocumentShare},
+    services::document_share_service::{
+        build_share_link, generate_share_token, DEFAULT_SHARE_EXPIRY_HOURS, MAX_SHARE_EXPIRY_HOURS,
+    },
+    AppState,
+};
+use super::crud::DocumentError;
+use super::types::{CreateDocumentShareRequest, DocumentShareResponse};
+
+/// Share a document through a link that works without an account
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/share",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    request_body = CreateDocumentShareRequest,
+    responses(
+        (status = 201, description = "Share created", body = DocumentShareResponse),
+        (status = 400, description = "Invalid expiry"),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn create_document_share(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+    Json(request): Json<CreateDocumentShareRequest>,
+) -> Result<(StatusCode, Json<DocumentShareResponse>), DocumentError> {
+    let expires_in_hours = request.expires_in_hours.unwrap_or(DEFAULT_SHARE_EXPIRY_HOURS);
+    if !(1..=MAX_SHARE_EXPIRY_HOURS).contains(&expires_in_hours) {
+        return Err(DocumentError::BadRequest(format!(
+            "expires_in_hours must be between 1 and {}",
+            MAX_SHARE_EXPIRY_HOURS
+        )));
+    }
+
+    get_document(&state, &auth_user, document_id).await?;
+
+    let share = state
+        .db
+        .create_document_share(
+            document_id,
+            auth_user.user.id,
+            &generate_share_token(),
+            Utc::now() + Duration::hours(expires_in_hours),
+            request.allow_download,
+        )
+        .await
+        .map_err(|e| {
+            error!("Failed to create share of document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to create share".to_string())
+        })?;
+
+    info!(
+        "User {} shared document {} until {} (download allowed: {})",
+        auth_user.user.username, document_id, share.expires_at, share.allow_download
+    );
+    Ok((StatusCode::CREATED, Json(share_response(&state, share))))
+}
+
+/// List the shares of a document, expired ones included
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/shares",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Shares of the document, newest first", body = Vec<DocumentShareResponse>),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_document_shares(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<Vec<DocumentShareResponse>>, DocumentError> {
+    get_document(&state, &auth_user, document_id).await?;
+
+    let shares = state.db.get_document_shares(document_id).await.map_err(|e| {
+        error!("Failed to list shares of document {}: {}", document_id, e);
+        DocumentError::InternalServerError("Failed to list shares".to_string())
+    })?;
+
+    Ok(Json(shares.into_iter().map(|share| share_response(&state, share)).collect()))
+}
+
+/// Revoke a share of a document
+#[utoipa::path(
+    delete,
+    path = "/api/documents/{id}/shares/{token}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("token" = String, Path, description = "Share token")
+    ),
+    responses(
+        (status = 204, description = "Share revoked"),
+        (status = 404, description = "Document or share not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn delete_document_share(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path((document_id, token)): Path<(uuid::Uuid, String)>,
+) -> Result<StatusCode, DocumentError> {
+    get_document(&state, &auth_user, document_id).await?;
+
+    let deleted = state.db.delete_document_share(document_id, &token).await.map_err(|e| {
+        error!("Failed to revoke share of document {}: {}", document_id, e);
+        DocumentError::InternalServerError("Failed to revoke share".to_string())
+    })?;
+    if !deleted {
+        return Err(DocumentError::NotFound);
+    }
+
+    info!("User {} revoked a share of document {}", auth_user.user.username, document_id);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn get_document(state: &AppState, auth_user: &AuthUser, document_id: uuid::Uuid) -> Result<Document, DocumentError> {
+    state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            DocumentError::InternalServerError("Failed to load document".to_string())
+        })?
+        .ok_or(DocumentError::NotFound)
+}
+
+fn share_response(state: &AppState, share: DocumentShare) -> DocumentShareResponse {
+    DocumentShareResponse {
+        url: build_share_link(state.config.public_base_url.as_deref(), &share.token),
+        share,
+    }
+}
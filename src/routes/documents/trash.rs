@@ -0,0 +1,231 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+
+use crate::{
+    auth::AuthUser,
+    db::Database,
+    models::{DocumentResponse, UserRole},
+    services::file_service::FileService,
+    AppState,
+};
+use super::types::{
+    PaginationQuery, DocumentPaginationInfo, PaginatedTrashResponse, TrashedDocumentResponse,
+    PurgeTrashQuery, PurgeTrashResponse,
+};
+
+/// List documents in the trash
+#[utoipa::path(
+    get,
+    path = "/api/documents/trash",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(PaginationQuery),
+    responses(
+        (status = 200, description = "Paginated list of trashed documents", body = PaginatedTrashResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_trash(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<PaginationQuery>,
+) -> Result<Json<PaginatedTrashResponse>, StatusCode> {
+    let limit = query.limit.unwrap_or(25);
+    let offset = query.offset.unwrap_or(0);
+
+    let total = state
+        .db
+        .count_deleted_documents(auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error counting trashed documents: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let documents = state
+        .db
+        .get_deleted_documents(auth_user.user.id, auth_user.user.role, limit, offset)
+        .await
+        .map_err(|e| {
+            error!("Database error listing trashed documents: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let retention = chrono::Duration::days(state.config.trash_retention_days as i64);
+    let documents = documents
+        .into_iter()
+        .map(|(document, deleted_at)| TrashedDocumentResponse {
+            document: DocumentResponse::from(document),
+            deleted_at,
+            purge_after: deleted_at + retention,
+        })
+        .collect();
+
+    Ok(Json(PaginatedTrashResponse {
+        documents,
+        pagination: DocumentPaginationInfo {
+            total,
+            limit,
+            offset,
+            has_more: offset + limit < total,
+            next_cursor: None,
+        },
+    }))
+}
+
+/// Restore a document from the trash
+#[utoipa::path(
+    post,
+    path = "/api/documents/{id}/restore",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Document restored", body = DocumentResponse),
+        (status = 404, description = "Document not found in trash"),
+        (status = 409, description = "A document with the same content already exists"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn restore_document(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<DocumentResponse>, StatusCode> {
+    let document = state
+        .db
+        .get_deleted_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting trashed document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+    let restored_path = file_service.restore_from_trash(&document).await.map_err(|e| {
+        error!("Failed to move file for document {} out of trash: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let restored = match state
+        .db
+        .restore_document(document_id, &restored_path, auth_user.user.id, auth_user.user.role)
+        .await
+    {
+        Ok(restored) => restored,
+        Err(e) => {
+            // Put the file back so the trashed row still points at it
+            let mut moved = document.clone();
+            moved.file_path = restored_path.clone();
+            if let Err(move_err) = file_service.move_to_trash(&moved).await {
+                warn!("Failed to return file for document {} to trash: {}", document_id, move_err);
+            }
+            // A live document of the owner has the same content
+            if let Some(db_error) = e.downcast_ref::<sqlx::Error>().and_then(|e| e.as_database_error()) {
+                if db_error.is_unique_violation() && db_error.constraint() == Some("idx_documents_user_file_hash") {
+                    return Err(StatusCode::CONFLICT);
+                }
+            }
+            error!("Database error restoring document {}: {}", document_id, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    if !restored {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    let document = state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting restored document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    info!("Document restored from trash: {}", document_id);
+    Ok(Json(DocumentResponse::from(document)))
+}
+
+/// Permanently remove trashed documents past the retention window (admin only)
+#[utoipa::path(
+    delete,
+    path = "/api/documents/trash",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(PurgeTrashQuery),
+    responses(
+        (status = 200, description = "Purge results", body = PurgeTrashResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn purge_trash(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<PurgeTrashQuery>,
+) -> Result<Json<PurgeTrashResponse>, StatusCode> {
+    if auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let older_than_days = query.older_than_days.unwrap_or(state.config.trash_retention_days);
+    let purged = purge_expired_documents(&state.db, &state.config.upload_path, older_than_days)
+        .await
+        .map_err(|e| {
+            error!("Failed to purge trash: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(PurgeTrashResponse {
+        purged_count: purged.len() as i64,
+        purged_documents: purged,
+    }))
+}
+
+/// Permanently deletes documents that have been in the trash for longer than
+/// `older_than_days`, along with their files. Used by the purge endpoint and the
+/// periodic trash cleanup job.
+pub async fn purge_expired_documents(db: &Database, upload_path: &str, older_than_days: u64) -> anyhow::Result<Vec<uuid::Uuid>> {
+    let cutoff = chrono::Utc::now() - chrono::Duration::days(older_than_days as i64);
+    // Archived versions go with their document; collect their files before the rows cascade away
+    let version_paths = db.get_purgeable_version_file_paths(cutoff).await?;
+    let documents = db.purge_deleted_documents(cutoff).await?;
+
+    let file_service = FileService::new(upload_path.to_string());
+    for document in &documents {
+        if let Err(e) = file_service.delete_document_files(document).await {
+            warn!("Failed to delete files for purged document {}: {}", document.id, e);
+        }
+    }
+    for path in &version_paths {
+        if let Err(e) = tokio::fs::remove_file(path).await {
+            warn!("Failed to delete file of purged document version {}: {}", path, e);
+        }
+    }
+
+    if !documents.is_empty() {
+        info!("Purged {} documents from trash (deleted more than {} days ago)", documents.len(), older_than_days);
+    }
+
+    Ok(documents.into_iter().map(|d| d.id).collect())
+}
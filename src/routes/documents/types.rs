@@ -1,11 +1,81 @@
 use serde::{Deserialize, Serialize};
 use utoipa::{ToSchema, IntoParams};
 
+use crate::services::source_file_deletion_service::{SourceDeletionBehavior, SourceFileDeletion};
+
 #[derive(Deserialize, ToSchema, IntoParams)]
 pub struct PaginationQuery {
+    /// Page size. The document list defaults to your `search_results_per_page` setting, other lists to 25.
     pub limit: Option<i64>,
     pub offset: Option<i64>,
     pub ocr_status: Option<String>,
+    /// `next_cursor` of the previous page. Pages by position instead of `offset`, so pages
+    /// don't shift while documents are being added.
+    pub cursor: Option<String>,
+    /// Document order (default: newest)
+    pub sort: Option<crate::models::DocumentListSort>,
+    /// Comma-separated document fields to return, e.g. `id,filename,thumbnail`.
+    /// `thumbnail` adds a `thumbnail_url`. Returns every field when omitted.
+    pub fields: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct DuplicatesQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    /// How duplicates are matched (default: exact)
+    pub mode: Option<DuplicateMode>,
+    /// Maximum Hamming distance between perceptual hashes (perceptual mode only, default: 10)
+    pub threshold: Option<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, ToSchema, Default)]
+pub enum DuplicateMode {
+    /// Identical file content (SHA-256)
+    #[serde(rename = "exact")]
+    #[default]
+    Exact,
+    /// Visually similar images (dHash)
+    #[serde(rename = "perceptual")]
+    Perceptual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, ToSchema, Default)]
+pub enum ContentFormat {
+    /// The extracted text as `text/plain`
+    #[serde(rename = "txt")]
+    #[default]
+    Txt,
+    /// The extracted text with document and OCR metadata
+    #[serde(rename = "json")]
+    Json,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct DocumentContentQuery {
+    /// Export format (default: txt)
+    pub format: Option<ContentFormat>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentContentOcr {
+    pub status: Option<String>,
+    pub confidence: Option<f32>,
+    pub word_count: Option<i32>,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentContentResponse {
+    pub id: uuid::Uuid,
+    pub filename: String,
+    pub mime_type: String,
+    pub tags: Vec<String>,
+    /// Names of the labels assigned to the document
+    pub labels: Vec<String>,
+    pub ocr: DocumentContentOcr,
+    /// Extracted content followed by the OCR text, as returned by `format=txt`
+    pub text: String,
 }
 
 #[derive(Deserialize, ToSchema, IntoParams)]
@@ -19,6 +89,57 @@ pub struct FailedDocumentsQuery {
 #[derive(Deserialize, Serialize, ToSchema)]
 pub struct BulkDeleteRequest {
     pub document_ids: Vec<uuid::Uuid>,
+    /// What happens to the files on the sources the documents were synced from. Defaults
+    /// to each source's `deletion_behavior`, which keeps them unless set otherwise.
+    #[serde(default)]
+    pub source_files: Option<SourceDeletionBehavior>,
+    /// Must be true when any file is deleted on its source
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct DeleteDocumentQuery {
+    /// What happens to the file on the source the document was synced from. Defaults to
+    /// the source's `deletion_behavior`, which keeps it unless set otherwise.
+    pub source_files: Option<SourceDeletionBehavior>,
+    /// Must be true when the file is deleted on its source
+    #[serde(default)]
+    pub confirm: bool,
+}
+
+/// Returned instead of an empty response when the document's file was deleted on its source
+#[derive(Serialize, ToSchema)]
+pub struct DeleteDocumentResponse {
+    pub document_id: uuid::Uuid,
+    pub source_file: SourceFileDeletion,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BulkAssignSourceRequest {
+    pub document_ids: Vec<uuid::Uuid>,
+    /// Source to associate the documents with; `null` detaches them from their source
+    pub source_id: Option<uuid::Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkAssignSourceResponse {
+    pub updated_count: i64,
+    pub failed_count: i64,
+    pub updated_documents: Vec<uuid::Uuid>,
+    /// Documents that were not found, not accessible, or not owned by the source's owner
+    pub failed_documents: Vec<uuid::Uuid>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MetadataImportResponse {
+    pub total_rows: usize,
+    pub applied_rows: usize,
+    pub unmatched_rows: usize,
+    pub invalid_rows: usize,
+    pub failed_rows: usize,
+    /// Every row of the import in order, with its errors
+    pub rows: Vec<crate::services::metadata_import_service::MetadataImportRowResult>,
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
@@ -31,6 +152,143 @@ pub struct DeleteLowConfidenceRequest {
 pub struct RetryOcrRequest {
     pub language: Option<String>,
     pub languages: Option<Vec<String>>,
+    /// Tesseract settings for this retry only; the user's saved settings are not changed
+    #[serde(flatten)]
+    pub overrides: crate::models::OcrOverrides,
+    /// Confirms that manually corrected OCR text may be replaced by the retry
+    #[serde(default)]
+    pub overwrite_manual_edits: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CorrectOcrTextRequest {
+    /// The corrected text, replacing the document's OCR text
+    pub ocr_text: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RetryFailedOcrRequest {
+    /// Only documents that failed for this reason, e.g. `low_ocr_confidence`
+    pub failure_reason: Option<String>,
+    /// Only documents created before this time
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only documents created at or after this time
+    pub after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Reprocess failed documents of every user (admins only)
+    #[serde(default)]
+    pub all_users: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ReprocessOcrRequest {
+    /// Tesseract language or `+`-joined combination to read the documents with, e.g. `deu`.
+    /// Without it the documents are read again with the user's OCR settings.
+    pub language: Option<String>,
+    /// Only these documents
+    pub document_ids: Option<Vec<uuid::Uuid>>,
+    /// Only documents of these MIME types
+    pub mime_types: Option<Vec<String>>,
+    /// Only documents synced from this source
+    pub source_id: Option<uuid::Uuid>,
+    /// Only documents created at or after this time
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only documents created before this time
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Also reprocess documents whose OCR text was corrected by hand, replacing the corrections
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ReprocessOcrResponse {
+    /// Batch job that queues the documents; follow it at `/api/jobs/{job_id}`
+    pub job_id: uuid::Uuid,
+    /// Matching documents the batch job reprocesses
+    pub queued: i64,
+    /// Matching documents left alone because OCR is already pending or running for them,
+    /// or, without `force`, because their OCR text was corrected by hand
+    pub skipped: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RetryFailedOcrResponse {
+    pub enqueued: i64,
+    /// Matching failed documents left for a later call because of the batch cap
+    pub remaining: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ReingestRequest {
+    /// Adopt changed files and queue them for OCR again
+    #[serde(default)]
+    pub reenqueue_ocr: bool,
+}
+
+#[derive(Deserialize, Default, ToSchema)]
+pub struct RedownloadRequest {
+    /// Queue the document for OCR again once its file is repaired
+    #[serde(default)]
+    pub reenqueue_ocr: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct BulkRedownloadRequest {
+    pub document_ids: Vec<uuid::Uuid>,
+    /// Queue the repaired documents for OCR again
+    #[serde(default)]
+    pub reenqueue_ocr: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RedownloadResult {
+    pub document_id: uuid::Uuid,
+    pub success: bool,
+    /// Size of the restored file
+    pub file_size: Option<i64>,
+    pub ocr_enqueued: bool,
+    /// Why the document was not repaired; its local file is left as it was
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BulkRedownloadResponse {
+    pub redownloaded_count: i64,
+    pub failed_count: i64,
+    pub results: Vec<RedownloadResult>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RetentionCleanupRequest {
+    /// Only report the documents that would be moved to the trash
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Apply the policies of every user who enabled automatic cleanup (admins only)
+    #[serde(default)]
+    pub all_users: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RetentionCleanupResponse {
+    pub dry_run: bool,
+    /// Documents moved to the trash, or that would be on a dry run
+    pub count: usize,
+    pub documents: Vec<crate::models::RetentionCandidate>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct OcrBoxesQuery {
+    /// First page to return, 1-based (default 1)
+    pub page: Option<i32>,
+    /// Number of pages to return (default 10, at most 50)
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OcrBoxesResponse {
+    pub document_id: uuid::Uuid,
+    /// Pages with stored word boxes; zero for documents that are not images or PDFs
+    pub total_pages: i64,
+    pub pages: Vec<crate::ocr::word_boxes::OcrPageWords>,
 }
 
 #[derive(Deserialize, Serialize, ToSchema)]
@@ -41,6 +299,28 @@ pub struct DocumentUploadResponse {
     pub mime_type: String,
     pub status: String,
     pub message: String,
+    /// Source the document is filed under: the uploader's default source, if they set one
+    #[serde(default)]
+    pub source_id: Option<uuid::Uuid>,
+    /// Why writing the upload back to the default source failed; the upload itself succeeded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub write_back_error: Option<String>,
+}
+
+/// Returned by the upload endpoint instead of a `DocumentUploadResponse` when an uploaded
+/// ZIP archive was extracted
+#[derive(Deserialize, Serialize, ToSchema)]
+pub struct ArchiveUploadResponse {
+    pub archive_filename: String,
+    /// The archive itself, if it was kept with `keep_archive`
+    pub archive_document_id: Option<uuid::Uuid>,
+    /// Documents created from the files in the archive
+    pub document_ids: Vec<uuid::Uuid>,
+    /// Existing documents that files in the archive duplicate
+    pub duplicate_document_ids: Vec<uuid::Uuid>,
+    pub skipped: Vec<crate::ingestion::archive_extraction::SkippedArchiveEntry>,
+    pub status: String,
+    pub message: String,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -51,6 +331,8 @@ pub struct BulkDeleteResponse {
     pub failed_documents: Vec<uuid::Uuid>,
     pub total_files_deleted: i64,
     pub total_files_failed: i64,
+    /// Files deleted on the sources the documents were synced from, if any were to be
+    pub source_files: Vec<SourceFileDeletion>,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -69,6 +351,52 @@ pub struct DocumentDebugInfo {
     pub readable: bool,
     pub permissions: Option<String>,
     pub user_settings: Option<crate::models::SettingsResponse>,
+    /// Recorded lifecycle events, oldest first; also at `/api/documents/{id}/history`
+    pub history: Vec<crate::models::DocumentEvent>,
+    /// Detected and applied rotation of an image document
+    pub orientation: Option<crate::models::DocumentOrientation>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RotateDocumentRequest {
+    /// Clockwise degrees to turn the document by: 90, 180 or 270. Negative values turn it
+    /// counterclockwise.
+    pub degrees: i32,
+    /// 1-based pages of a PDF to turn; all pages if omitted
+    pub pages: Option<Vec<u32>>,
+    /// Run OCR again on the rotated file (default: true). Otherwise the existing text is kept.
+    pub reprocess_ocr: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct RotateDocumentResponse {
+    pub document_id: uuid::Uuid,
+    /// Version number of the document's content after rotating. The previous file is kept
+    /// as an earlier version.
+    pub version_number: i32,
+    /// Pages that were turned, or `None` for the whole document
+    pub pages: Option<Vec<u32>>,
+    /// Whether OCR was queued for the rotated file
+    pub ocr_queued: bool,
+}
+
+/// Original and preprocessed image of a document with the preprocessing
+/// parameters the OCR pipeline chose
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct OcrDebugImages {
+    pub document_id: uuid::Uuid,
+    pub original_url: String,
+    pub original_mime_type: String,
+    pub processed_url: String,
+    pub processed_width: i32,
+    pub processed_height: i32,
+    pub processed_file_size: i64,
+    pub processing_steps: Vec<String>,
+    /// Rotation, quality analysis, brightness boost, contrast multiplier, noise
+    /// reduction level and binarization window. `null` for images processed
+    /// before these were recorded.
+    pub parameters: serde_json::Value,
+    pub processed_at: chrono::DateTime<chrono::Utc>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -77,6 +405,9 @@ pub struct DocumentPaginationInfo {
     pub limit: i64,
     pub offset: i64,
     pub has_more: bool,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, ToSchema)]
@@ -85,12 +416,141 @@ pub struct PaginatedDocumentsResponse {
     pub pagination: DocumentPaginationInfo,
 }
 
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct TrashedDocumentResponse {
+    #[serde(flatten)]
+    pub document: crate::models::DocumentResponse,
+    /// When the document was moved to the trash
+    pub deleted_at: chrono::DateTime<chrono::Utc>,
+    /// When the document becomes eligible for permanent removal
+    pub purge_after: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct RelatedDocumentsQuery {
+    /// Number of related documents to return (default: 10, at most 50)
+    pub limit: Option<i64>,
+    /// Minimum similarity of the texts, 0-1 (default: 0.2)
+    pub min_similarity: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RelatedDocumentResponse {
+    #[serde(flatten)]
+    pub document: crate::models::DocumentResponse,
+    /// Trigram similarity of the two documents' text, 0-1. Near-duplicates score close to 1.
+    pub similarity: f32,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct RelatedDocumentsResponse {
+    pub document_id: uuid::Uuid,
+    pub related: Vec<RelatedDocumentResponse>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct PaginatedTrashResponse {
+    pub documents: Vec<TrashedDocumentResponse>,
+    pub pagination: DocumentPaginationInfo,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct PurgeTrashQuery {
+    /// Only purge documents trashed more than this many days ago (defaults to TRASH_RETENTION_DAYS)
+    pub older_than_days: Option<u64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PurgeTrashResponse {
+    pub purged_count: i64,
+    pub purged_documents: Vec<uuid::Uuid>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SplitDocumentRequest {
+    /// Inclusive, 1-based `[first, last]` page ranges; each range becomes a new document
+    #[schema(example = json!([[1, 3], [4, 4], [5, 10]]))]
+    pub ranges: Vec<[u32; 2]>,
+}
+
+#[derive(Deserialize, ToSchema, IntoParams)]
+pub struct SplitDocumentQuery {
+    /// Move the original document to the trash once every range was split out
+    pub delete_original: Option<bool>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SplitDocumentResponse {
+    /// IDs of the documents produced, in the same order as the requested ranges
+    pub document_ids: Vec<uuid::Uuid>,
+    pub original_deleted: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct MergeDocumentsRequest {
+    /// PDFs to combine, in the order their pages should appear
+    pub document_ids: Vec<uuid::Uuid>,
+    /// Name for the merged document; defaults to the first document's name with a `_merged` suffix
+    pub filename: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentVersionInfo {
+    pub version_number: i32,
+    pub filename: String,
+    pub file_size: i64,
+    pub mime_type: String,
+    pub file_hash: Option<String>,
+    pub ocr_confidence: Option<f32>,
+    pub ocr_word_count: Option<i32>,
+    /// When this version was ingested
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When a newer version replaced it; unset for the current version
+    pub superseded_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub is_current: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentVersionsResponse {
+    pub document_id: uuid::Uuid,
+    pub current_version: i32,
+    /// All retained versions, newest first
+    pub versions: Vec<DocumentVersionInfo>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentVersionDetailResponse {
+    #[serde(flatten)]
+    pub version: DocumentVersionInfo,
+    pub ocr_text: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateDocumentShareRequest {
+    /// Hours until the link stops working (default: 168, at most 8760)
+    pub expires_in_hours: Option<i64>,
+    /// Whether the link also serves the file, not just its metadata (default: false)
+    #[serde(default)]
+    pub allow_download: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DocumentShareResponse {
+    #[serde(flatten)]
+    pub share: crate::models::DocumentShare,
+    /// Link to hand to the reviewer
+    pub url: String,
+}
+
 impl Default for PaginationQuery {
     fn default() -> Self {
         Self {
             limit: Some(25),
             offset: Some(0),
             ocr_status: None,
+            cursor: None,
+            sort: None,
+            fields: None,
         }
     }
 }
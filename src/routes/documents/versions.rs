@@ -0,0 +1,223 @@
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{Json, Response},
+};
+use std::sync::Arc;
+use tracing::{debug, error};
+
+use crate::{
+    auth::AuthUser,
+    models::{Document, DocumentVersion},
+    services::file_service::FileService,
+    AppState,
+};
+use super::types::{DocumentVersionDetailResponse, DocumentVersionInfo, DocumentVersionsResponse};
+
+/// List the versions of a document
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/versions",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID")
+    ),
+    responses(
+        (status = 200, description = "Current and retained prior versions, newest first", body = DocumentVersionsResponse),
+        (status = 404, description = "Document not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_document_versions(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(document_id): Path<uuid::Uuid>,
+) -> Result<Json<DocumentVersionsResponse>, StatusCode> {
+    let document = get_document(&state, &auth_user, document_id).await?;
+    let (current_version, archived) = get_versions(&state, document_id).await?;
+
+    let mut versions = Vec::with_capacity(archived.len() + 1);
+    versions.push(current_version_info(&document, current_version, &archived));
+    versions.extend(archived.iter().map(archived_version_info));
+
+    Ok(Json(DocumentVersionsResponse {
+        document_id,
+        current_version,
+        versions,
+    }))
+}
+
+/// Get one version of a document, including its OCR text
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/versions/{version}",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("version" = i32, Path, description = "Version number")
+    ),
+    responses(
+        (status = 200, description = "Document version", body = DocumentVersionDetailResponse),
+        (status = 404, description = "Document or version not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_document_version(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path((document_id, version_number)): Path<(uuid::Uuid, i32)>,
+) -> Result<Json<DocumentVersionDetailResponse>, StatusCode> {
+    let document = get_document(&state, &auth_user, document_id).await?;
+    let (current_version, archived) = get_versions(&state, document_id).await?;
+
+    if version_number == current_version {
+        return Ok(Json(DocumentVersionDetailResponse {
+            version: current_version_info(&document, current_version, &archived),
+            ocr_text: document.ocr_text,
+        }));
+    }
+
+    let version = archived
+        .into_iter()
+        .find(|v| v.version_number == version_number)
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(DocumentVersionDetailResponse {
+        version: archived_version_info(&version),
+        ocr_text: version.ocr_text,
+    }))
+}
+
+/// Download the file of a specific document version
+#[utoipa::path(
+    get,
+    path = "/api/documents/{id}/versions/{version}/download",
+    tag = "documents",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = uuid::Uuid, Path, description = "Document ID"),
+        ("version" = i32, Path, description = "Version number")
+    ),
+    responses(
+        (status = 200, description = "Document version file", content_type = "application/octet-stream"),
+        (status = 404, description = "Document or version not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn download_document_version(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path((document_id, version_number)): Path<(uuid::Uuid, i32)>,
+) -> Result<Response<Body>, StatusCode> {
+    let document = get_document(&state, &auth_user, document_id).await?;
+    let current_version = state.db.get_document_version_number(document_id).await.map_err(|e| {
+        error!("Failed to get version number of document {}: {}", document_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let (file_path, filename, mime_type) = if version_number == current_version {
+        (document.file_path, document.original_filename, document.mime_type)
+    } else {
+        let version = state
+            .db
+            .get_document_version(document_id, version_number)
+            .await
+            .map_err(|e| {
+                error!("Failed to get version {} of document {}: {}", version_number, document_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or(StatusCode::NOT_FOUND)?;
+        (version.file_path, version.filename, version.mime_type)
+    };
+
+    let file_service = FileService::new(state.config.upload_path.clone());
+    let file_data = file_service
+        .read_file(&file_path)
+        .await
+        .map_err(|e| {
+            error!("Failed to read version {} of document {}: {}", version_number, document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let response = Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, mime_type)
+        .header("Content-Disposition", format!("attachment; filename=\"{}\"", filename))
+        .header("Content-Length", file_data.len().to_string())
+        .body(Body::from(file_data))
+        .map_err(|e| {
+            error!("Failed to build response: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    debug!("Document {} version {} downloaded", document_id, version_number);
+    Ok(response)
+}
+
+async fn get_document(state: &AppState, auth_user: &AuthUser, document_id: uuid::Uuid) -> Result<Document, StatusCode> {
+    state
+        .db
+        .get_document_by_id(document_id, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Database error getting document {}: {}", document_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn get_versions(state: &AppState, document_id: uuid::Uuid) -> Result<(i32, Vec<DocumentVersion>), StatusCode> {
+    let current_version = state.db.get_document_version_number(document_id).await;
+    let archived = state.db.get_document_versions(document_id).await;
+
+    match (current_version, archived) {
+        (Ok(current_version), Ok(archived)) => Ok((current_version, archived)),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Failed to get versions of document {}: {}", document_id, e);
+            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
+
+fn current_version_info(document: &Document, current_version: i32, archived: &[DocumentVersion]) -> DocumentVersionInfo {
+    DocumentVersionInfo {
+        version_number: current_version,
+        filename: document.original_filename.clone(),
+        file_size: document.file_size,
+        mime_type: document.mime_type.clone(),
+        file_hash: document.file_hash.clone(),
+        ocr_confidence: document.ocr_confidence,
+        ocr_word_count: document.ocr_word_count,
+        // The current content arrived when the newest archived version was replaced
+        created_at: archived.first().map_or(document.created_at, |v| v.superseded_at),
+        superseded_at: None,
+        is_current: true,
+    }
+}
+
+fn archived_version_info(version: &DocumentVersion) -> DocumentVersionInfo {
+    DocumentVersionInfo {
+        version_number: version.version_number,
+        filename: version.filename.clone(),
+        file_size: version.file_size,
+        mime_type: version.mime_type.clone(),
+        file_hash: version.file_hash.clone(),
+        ocr_confidence: version.ocr_confidence,
+        ocr_word_count: version.ocr_word_count,
+        created_at: version.created_at,
+        superseded_at: Some(version.superseded_at),
+        is_current: false,
+    }
+}
@@ -678,17 +678,18 @@ async fn get_filtered_documents(
     Ok(documents)
 }
 
+/// Manually corrected text is kept; the queue leaves such documents alone
 async fn reset_document_ocr_status(state: &Arc<AppState>, document_id: Uuid) -> Result<(), anyhow::Error> {
     sqlx::query(
         r#"
         UPDATE documents
         SET ocr_status = 'pending',
-            ocr_text = NULL,
+            ocr_text = CASE WHEN ocr_manually_edited THEN ocr_text END,
             ocr_error = NULL,
             ocr_failure_reason = NULL,
             ocr_retry_count = NULL,
-            ocr_confidence = NULL,
-            ocr_word_count = NULL,
+            ocr_confidence = CASE WHEN ocr_manually_edited THEN ocr_confidence END,
+            ocr_word_count = CASE WHEN ocr_manually_edited THEN ocr_word_count END,
             ocr_processing_time_ms = NULL,
             ocr_completed_at = NULL,
             updated_at = NOW()
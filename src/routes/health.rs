@@ -0,0 +1,125 @@
+use axum::{extract::State, http::StatusCode, response::Json};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use utoipa::ToSchema;
+
+use crate::{ocr::health::OcrHealthChecker, AppState};
+
+/// Upper bound for each dependency check so the probe itself can never hang
+const READINESS_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+#[derive(Serialize, ToSchema)]
+pub struct ReadinessResponse {
+    /// `ready` when every check passed, otherwise `not_ready`
+    pub status: String,
+    /// Per-dependency results keyed by dependency name
+    pub checks: BTreeMap<String, DependencyCheck>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DependencyCheck {
+    /// `ok` or `error`
+    pub status: String,
+    pub duration_ms: u64,
+    /// Version, counts or other context for a passing check
+    pub details: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Readiness probe that checks the database, OCR tooling and migrations
+#[utoipa::path(
+    get,
+    path = "/api/health/ready",
+    tag = "health",
+    responses(
+        (status = 200, description = "All dependencies are healthy", body = ReadinessResponse),
+        (status = 503, description = "At least one dependency is unhealthy", body = ReadinessResponse),
+    )
+)]
+pub async fn readiness_check(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let (database, ocr, migrations) = tokio::join!(
+        run_check(check_database(&state)),
+        run_check(check_ocr()),
+        run_check(check_migrations(&state)),
+    );
+
+    let checks = BTreeMap::from([
+        ("database".to_string(), database),
+        ("ocr".to_string(), ocr),
+        ("migrations".to_string(), migrations),
+    ]);
+    let ready = checks.values().all(|check| check.status == "ok");
+
+    let status = if ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(ReadinessResponse {
+        status: if ready { "ready" } else { "not_ready" }.to_string(),
+        checks,
+    }))
+}
+
+async fn run_check<F>(check: F) -> DependencyCheck
+where
+    F: Future<Output = Result<Option<String>, String>>,
+{
+    let start = Instant::now();
+    let result = tokio::time::timeout(READINESS_CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {}s", READINESS_CHECK_TIMEOUT.as_secs())));
+    let duration_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(details) => DependencyCheck { status: "ok".to_string(), duration_ms, details, error: None },
+        Err(error) => DependencyCheck { status: "error".to_string(), duration_ms, details: None, error: Some(error) },
+    }
+}
+
+async fn check_database(state: &AppState) -> Result<Option<String>, String> {
+    match state.db.check_pool_health().await {
+        Ok(true) => {
+            let health = state.db.get_pool_health();
+            Ok(Some(format!("{} connections open, {} idle", health.size, health.num_idle)))
+        }
+        Ok(false) => Err("could not acquire a database connection".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+async fn check_ocr() -> Result<Option<String>, String> {
+    if !cfg!(feature = "ocr") {
+        return Ok(Some("OCR support is not compiled into this build".to_string()));
+    }
+
+    // The checker shells out synchronously
+    tokio::task::spawn_blocking(|| OcrHealthChecker::new().check_tesseract_installation())
+        .await
+        .map_err(|e| e.to_string())?
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
+
+async fn check_migrations(state: &AppState) -> Result<Option<String>, String> {
+    let applied: HashSet<i64> = sqlx::query_scalar("SELECT version FROM _sqlx_migrations WHERE success = true")
+        .fetch_all(state.db.get_pool())
+        .await
+        .map_err(|e| format!("could not read migration state: {}", e))?
+        .into_iter()
+        .collect();
+
+    let migrator = sqlx::migrate!("./migrations");
+    let pending: Vec<i64> = migrator
+        .iter()
+        .map(|migration| migration.version)
+        .filter(|version| !applied.contains(version))
+        .collect();
+
+    if pending.is_empty() {
+        Ok(Some(format!("{} migrations applied", applied.len())))
+    } else {
+        Err(format!("{} migrations pending, first: {}", pending.len(), pending[0]))
+    }
+}
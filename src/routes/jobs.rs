@@ -0,0 +1,64 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    auth::AuthUser,
+    models::{BatchJob, UserRole},
+    AppState,
+};
+
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{id}", get(get_batch_job))
+}
+
+/// Get the progress of a batch job
+///
+/// Batch jobs are started by bulk operations such as OCR reprocessing. `done` and `failed`
+/// grow as the OCR queue finishes the job's documents.
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    tag = "jobs",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Batch job ID")
+    ),
+    responses(
+        (status = 200, description = "Batch job", body = BatchJob),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Batch job not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_batch_job(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BatchJob>, StatusCode> {
+    let job = state
+        .db
+        .get_batch_job(id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get batch job {}: {}", id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    // Other users' jobs are reported as missing rather than forbidden
+    if job.user_id != auth_user.user.id && auth_user.user.role != UserRole::Admin {
+        return Err(StatusCode::NOT_FOUND);
+    }
+
+    Ok(Json(job))
+}
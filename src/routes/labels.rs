@@ -12,7 +12,12 @@ use uuid::Uuid;
 use chrono::{DateTime, Utc};
 use sqlx::{FromRow, Row};
 
-use crate::{auth::AuthUser, errors::label::LabelError, AppState};
+use crate::{
+    auth::AuthUser,
+    errors::label::LabelError,
+    models::{SearchMode, SearchRequest, UserRole},
+    AppState,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Label {
@@ -24,6 +29,14 @@ pub struct Label {
     pub background_color: Option<String>,
     pub icon: Option<String>,
     pub is_system: bool,
+    /// Documents with this label are kept at least this many days
+    #[sqlx(default)]
+    #[serde(default)]
+    pub retention_days: Option<i32>,
+    /// Documents with this label are never removed by retention cleanup
+    #[sqlx(default)]
+    #[serde(default)]
+    pub retain_forever: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     #[serde(default)]
@@ -40,6 +53,9 @@ pub struct CreateLabel {
     pub color: String,
     pub background_color: Option<String>,
     pub icon: Option<String>,
+    pub retention_days: Option<i32>,
+    #[serde(default)]
+    pub retain_forever: bool,
 }
 
 fn default_color() -> String {
@@ -53,6 +69,8 @@ pub struct UpdateLabel {
     pub color: Option<String>,
     pub background_color: Option<String>,
     pub icon: Option<String>,
+    pub retention_days: Option<Option<i32>>,
+    pub retain_forever: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -78,6 +96,30 @@ fn default_bulk_mode() -> String {
     "replace".to_string()
 }
 
+/// Most documents one label can be applied to by a search
+pub const MAX_APPLY_TO_SEARCH_DOCUMENTS: i64 = 10_000;
+
+/// A search in the shape `/api/search` takes; every matching document gets the label,
+/// not just one page of them
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ApplyLabelToSearchRequest {
+    #[serde(flatten)]
+    pub search: SearchRequest,
+    /// Only count the matching documents
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApplyLabelToSearchResponse {
+    pub label_id: Uuid,
+    /// Documents of the user the search matches
+    pub matched: i64,
+    /// Documents that got the label; those that already had it are not counted
+    pub labeled: i64,
+    pub dry_run: bool,
+}
+
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_labels))
@@ -90,6 +132,7 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/documents/{document_id}/labels/{label_id}", post(add_document_label))
         .route("/documents/{document_id}/labels/{label_id}", delete(remove_document_label))
         .route("/bulk/documents", post(bulk_update_document_labels))
+        .route("/{id}/apply-to-search", post(apply_label_to_search))
 }
 
 #[utoipa::path(
@@ -114,7 +157,8 @@ pub async fn get_labels(
             r#"
             SELECT 
                 l.id, l.user_id, l.name, l.description, l.color, 
-                l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+                l.background_color, l.icon, l.is_system, l.retention_days, l.retain_forever,
+                l.created_at, l.updated_at,
                 COUNT(DISTINCT dl.document_id) as document_count,
                 COUNT(DISTINCT sl.source_id) as source_count
             FROM labels l
@@ -132,7 +176,8 @@ pub async fn get_labels(
             r#"
             SELECT 
                 id, user_id, name, description, color, 
-                background_color, icon, is_system, created_at, updated_at,
+                background_color, icon, is_system, retention_days, retain_forever,
+                created_at, updated_at,
                 0::bigint as document_count, 0::bigint as source_count
             FROM labels
             WHERE (user_id = $1 OR is_system = TRUE)
@@ -185,13 +230,17 @@ pub async fn create_label(
         }
     }
 
+    if payload.retention_days.is_some_and(|days| days <= 0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     let label = sqlx::query_as::<_, Label>(
         r#"
-        INSERT INTO labels (user_id, name, description, color, background_color, icon)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO labels (user_id, name, description, color, background_color, icon, retention_days, retain_forever)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
         RETURNING 
             id, user_id, name, description, color, background_color, icon, 
-            is_system, created_at, updated_at,
+            is_system, retention_days, retain_forever, created_at, updated_at,
             0::bigint as document_count, 0::bigint as source_count
         "#
     )
@@ -201,6 +250,8 @@ pub async fn create_label(
     .bind(payload.color)
     .bind(payload.background_color)
     .bind(payload.icon)
+    .bind(payload.retention_days)
+    .bind(payload.retain_forever)
     .fetch_one(state.db.get_pool())
     .await
     .map_err(|e| {
@@ -239,7 +290,8 @@ pub async fn get_label(
         r#"
         SELECT 
             l.id, l.user_id, l.name, l.description, l.color, 
-            l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+            l.background_color, l.icon, l.is_system, l.retention_days, l.retain_forever,
+            l.created_at, l.updated_at,
             COUNT(DISTINCT dl.document_id) as document_count,
             COUNT(DISTINCT sl.source_id) as source_count
         FROM labels l
@@ -301,6 +353,10 @@ pub async fn update_label(
         }
     }
 
+    if payload.retention_days.flatten().is_some_and(|days| days <= 0) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     // Check if label exists and user has permission
     let existing = sqlx::query(
         "SELECT id FROM labels WHERE id = $1 AND user_id = $2 AND is_system = FALSE"
@@ -328,11 +384,13 @@ pub async fn update_label(
             color = COALESCE($4, color),
             background_color = COALESCE($5, background_color),
             icon = COALESCE($6, icon),
+            retention_days = CASE WHEN $7 THEN $8 ELSE retention_days END,
+            retain_forever = COALESCE($9, retain_forever),
             updated_at = CURRENT_TIMESTAMP
         WHERE id = $1
         RETURNING 
             id, user_id, name, description, color, background_color, icon, 
-            is_system, created_at, updated_at,
+            is_system, retention_days, retain_forever, created_at, updated_at,
             0::bigint as document_count, 0::bigint as source_count
         "#
     )
@@ -342,6 +400,9 @@ pub async fn update_label(
     .bind(payload.color)
     .bind(payload.background_color)
     .bind(payload.icon)
+    .bind(payload.retention_days.is_some())
+    .bind(payload.retention_days.flatten())
+    .bind(payload.retain_forever)
     .fetch_one(state.db.get_pool())
     .await
     .map_err(|e| {
@@ -436,7 +497,8 @@ pub async fn get_document_labels(
         r#"
         SELECT 
             l.id, l.user_id, l.name, l.description, l.color, 
-            l.background_color, l.icon, l.is_system, l.created_at, l.updated_at,
+            l.background_color, l.icon, l.is_system, l.retention_days, l.retain_forever,
+            l.created_at, l.updated_at,
             0::bigint as document_count, 0::bigint as source_count
         FROM labels l
         INNER JOIN document_labels dl ON l.id = dl.label_id
@@ -838,4 +900,113 @@ pub async fn bulk_update_document_labels(
         "message": format!("Labels {}d successfully", payload.mode),
         "documents_updated": payload.document_ids.len()
     })))
-}
\ No newline at end of file
+}
+#[utoipa::path(
+    post,
+    path = "/api/labels/{id}/apply-to-search",
+    tag = "labels",
+    security(("bearer_auth" = [])),
+    params(
+        ("id" = Uuid, Path, description = "Label ID")
+    ),
+    request_body = ApplyLabelToSearchRequest,
+    responses(
+        (status = 200, description = "Label applied to every document the search matches, or the matches counted on a dry run", body = ApplyLabelToSearchResponse),
+        (status = 400, description = "Invalid search, or a search matching more than 10000 documents"),
+        (status = 404, description = "Label not found"),
+    )
+)]
+pub async fn apply_label_to_search(
+    Path(label_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Json(payload): Json<ApplyLabelToSearchRequest>,
+) -> Result<Json<ApplyLabelToSearchResponse>, LabelError> {
+    let user_id = auth_user.user.id;
+    let search = &payload.search;
+
+    // Same limits as /api/search
+    let query_length = search.query.len();
+    if !(2..=1000).contains(&query_length) {
+        return Err(LabelError::invalid_search(format!("query must be 2 to 1000 characters long, got {}", query_length)));
+    }
+
+    let label_exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS(SELECT 1 FROM labels WHERE id = $1 AND (user_id = $2 OR is_system = TRUE))"
+    )
+    .bind(label_id)
+    .bind(user_id)
+    .fetch_one(state.db.get_pool())
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to verify label: {}", e);
+        LabelError::internal_error(e.to_string())
+    })?;
+    if !label_exists {
+        return Err(LabelError::not_found_by_id(label_id));
+    }
+
+    let search_mode = search.search_mode.as_ref().unwrap_or(&SearchMode::Simple);
+
+    if payload.dry_run {
+        // Only the user's own documents are labeled, also for admins
+        let matched = state
+            .db
+            .count_search_results(user_id, UserRole::User, search_mode, search)
+            .await
+            .map_err(|e| {
+                tracing::error!("Failed to count search results: {}", e);
+                LabelError::internal_error(e.to_string())
+            })?;
+        return Ok(Json(ApplyLabelToSearchResponse { label_id, matched, labeled: 0, dry_run: true }));
+    }
+
+    let document_ids = state
+        .db
+        .search_document_ids(user_id, search_mode, search, MAX_APPLY_TO_SEARCH_DOCUMENTS + 1)
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to resolve search results: {}", e);
+            LabelError::internal_error(e.to_string())
+        })?;
+    if document_ids.len() as i64 > MAX_APPLY_TO_SEARCH_DOCUMENTS {
+        return Err(LabelError::too_many_documents(MAX_APPLY_TO_SEARCH_DOCUMENTS));
+    }
+
+    let mut tx = state.db.get_pool().begin().await.map_err(|e| {
+        tracing::error!("Failed to begin transaction: {}", e);
+        LabelError::internal_error(e.to_string())
+    })?;
+
+    // Ownership is checked again here, so documents deleted since the search are skipped
+    let result = sqlx::query(
+        r#"
+        INSERT INTO document_labels (document_id, label_id, assigned_by)
+        SELECT d.id, $2, $3 FROM documents d
+        WHERE d.id = ANY($1) AND d.user_id = $3 AND d.deleted_at IS NULL
+        ON CONFLICT DO NOTHING
+        "#
+    )
+    .bind(&document_ids)
+    .bind(label_id)
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to apply label to search results: {}", e);
+        LabelError::internal_error(e.to_string())
+    })?;
+
+    tx.commit().await.map_err(|e| {
+        tracing::error!("Failed to commit transaction: {}", e);
+        LabelError::internal_error(e.to_string())
+    })?;
+
+    tracing::info!("Applied label {} to {} of {} documents matching a search of user {}", label_id, result.rows_affected(), document_ids.len(), user_id);
+    Ok(Json(ApplyLabelToSearchResponse {
+        label_id,
+        matched: document_ids.len() as i64,
+        labeled: result.rows_affected() as i64,
+        dry_run: false,
+    }))
+}
@@ -26,6 +26,7 @@ pub struct SystemMetrics {
     pub documents: DocumentMetrics,
     pub users: UserMetrics,
     pub system: GeneralSystemMetrics,
+    pub derived_asset_cache: DerivedAssetCacheMetrics,
     pub timestamp: i64,
 }
 
@@ -45,6 +46,10 @@ pub struct OcrMetrics {
     pub avg_processing_time_minutes: Option<f64>,
     pub queue_depth: i64,
     pub oldest_pending_minutes: Option<f64>,
+    /// OCR jobs currently being processed by this instance
+    pub active_workers: usize,
+    /// OCR jobs allowed at once after limiting to the database pool share
+    pub max_workers: usize,
 }
 
 #[derive(Serialize, ToSchema)]
@@ -57,6 +62,21 @@ pub struct DocumentMetrics {
     pub documents_without_ocr: i64,
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct DerivedAssetCacheMetrics {
+    /// Cached thumbnails, first-page renders and searchable PDFs
+    pub entries: i64,
+    pub size_bytes: i64,
+    /// Size cap from DERIVED_ASSET_CACHE_MAX_MB; 0 when eviction is disabled
+    pub max_bytes: u64,
+    /// Requests served from the cache since startup
+    pub hits: u64,
+    /// Requests that generated the file since startup
+    pub misses: u64,
+    /// `hits / (hits + misses)`, absent before the first request
+    pub hit_rate: Option<f64>,
+}
+
 #[derive(Serialize, ToSchema)]
 pub struct UserMetrics {
     pub total_users: i64,
@@ -98,12 +118,13 @@ pub async fn get_system_metrics(
     let timestamp = chrono::Utc::now().timestamp();
     
     // Collect all metrics concurrently for better performance
-    let (database_metrics, ocr_metrics, document_metrics, user_metrics, system_metrics) = tokio::try_join!(
+    let (database_metrics, ocr_metrics, document_metrics, user_metrics, system_metrics, derived_asset_cache_metrics) = tokio::try_join!(
         collect_database_metrics(&state),
         collect_ocr_metrics(&state),
         collect_document_metrics(&state),
         collect_user_metrics(&state),
-        collect_system_metrics()
+        collect_system_metrics(),
+        collect_derived_asset_cache_metrics(&state)
     )?;
     
     let metrics = SystemMetrics {
@@ -112,6 +133,7 @@ pub async fn get_system_metrics(
         documents: document_metrics,
         users: user_metrics,
         system: system_metrics,
+        derived_asset_cache: derived_asset_cache_metrics,
         timestamp,
     };
     
@@ -158,6 +180,8 @@ async fn collect_ocr_metrics(state: &Arc<AppState>) -> Result<OcrMetrics, Status
         avg_processing_time_minutes: stats.avg_wait_time_minutes,
         queue_depth: stats.pending_count + stats.processing_count,
         oldest_pending_minutes: stats.oldest_pending_minutes,
+        active_workers: state.queue_service.active_workers(),
+        max_workers: state.queue_service.worker_limit(),
     })
 }
 
@@ -228,6 +252,22 @@ async fn collect_document_metrics(state: &Arc<AppState>) -> Result<DocumentMetri
     })
 }
 
+async fn collect_derived_asset_cache_metrics(state: &Arc<AppState>) -> Result<DerivedAssetCacheMetrics, StatusCode> {
+    let stats = state.derived_asset_cache.stats().await.map_err(|e| {
+        tracing::error!("Failed to get derived file cache metrics: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    Ok(DerivedAssetCacheMetrics {
+        entries: stats.entries,
+        size_bytes: stats.size_bytes,
+        max_bytes: stats.max_bytes,
+        hits: stats.hits,
+        misses: stats.misses,
+        hit_rate: stats.hit_rate(),
+    })
+}
+
 async fn collect_user_metrics(state: &Arc<AppState>) -> Result<UserMetrics, StatusCode> {
     // Get total user count
     let total_users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
@@ -1,7 +1,10 @@
+pub mod admin;
 pub mod auth;
 pub mod documents;
 pub mod documents_ocr_retry;
+pub mod health;
 pub mod ignored_files;
+pub mod jobs;
 pub mod labels;
 pub mod metrics;
 pub mod notifications;
@@ -10,6 +13,7 @@ pub mod prometheus_metrics;
 pub mod queue;
 pub mod search;
 pub mod settings;
+pub mod shared;
 pub mod sources;
 pub mod users;
 pub mod webdav;
\ No newline at end of file
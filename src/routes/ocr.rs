@@ -1,17 +1,32 @@
 use axum::{
-    extract::State,
+    extract::{Multipart, State},
     http::StatusCode,
     response::Json,
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use serde::Serialize;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
 use utoipa::ToSchema;
 
 use crate::{
     auth::AuthUser,
-    ocr::health::OcrHealthChecker,
+    models::OcrOverrides,
+    ocr::{
+        api::{error_response, OcrErrorResponse},
+        benchmark::{
+            bundled_samples, BenchmarkGuard, BenchmarkSample, OcrBenchmark, OcrBenchmarkReport,
+            DEFAULT_BENCHMARK_ITERATIONS, MAX_BENCHMARK_ITERATIONS, MAX_BENCHMARK_SAMPLES,
+        },
+        enhanced::EnhancedOcrService,
+        health::OcrHealthChecker,
+        preprocessing::PreprocessingConfig,
+    },
+    routes::queue::require_admin,
+    services::file_service::FileService,
     AppState,
 };
 
@@ -33,6 +48,56 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/health", get(crate::ocr::api::health_check))
         .route("/perform", axum::routing::post(crate::ocr::api::perform_ocr))
         .route("/languages", get(get_available_languages))
+        .route("/preview", post(preview_ocr))
+        .route("/diagnostics", get(get_ocr_diagnostics))
+        .route("/benchmark", post(run_ocr_benchmark))
+}
+
+/// Installed OCR tools and language packs, for telling deployments apart
+#[derive(Serialize, ToSchema)]
+pub struct OcrDiagnosticsResponse {
+    /// False when this build was compiled without OCR support; nothing is OCRed then,
+    /// whatever is installed
+    pub ocr_enabled: bool,
+    pub tesseract: OcrToolStatus,
+    /// Used for PDFs
+    pub ocrmypdf: OcrToolStatus,
+    /// Language packs listed by `tesseract --list-langs`, sorted by code
+    pub languages: Vec<OcrLanguageStatus>,
+    /// Problems found, in plain words; empty when everything is usable
+    pub issues: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OcrToolStatus {
+    pub installed: bool,
+    /// First line of the tool's `--version` output
+    pub version: Option<String>,
+    /// Installed and usable by this build
+    pub usable: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OcrLanguageStatus {
+    pub code: String,
+    pub name: String,
+    /// Tesseract could be initialized with this language
+    pub usable: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct OcrPreviewResponse {
+    pub text: String,
+    pub confidence: f32,
+    pub word_count: usize,
+    pub processing_time_ms: u64,
+    /// Preprocessing steps that were applied to the image
+    pub preprocessing_applied: Vec<String>,
+    /// Tesseract language combination used, e.g. `eng+deu`
+    pub language: String,
+    pub psm: i32,
+    pub oem: i32,
 }
 
 #[utoipa::path(
@@ -89,8 +154,498 @@ async fn get_available_languages(
     }))
 }
 
+/// Report installed OCR tool versions and language packs
+///
+/// Runs `tesseract --version`, `ocrmypdf --version` and `tesseract --list-langs`, and
+/// tries to initialize Tesseract with each listed language. Admin only.
+#[utoipa::path(
+    get,
+    path = "/api/ocr/diagnostics",
+    tag = "ocr",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "OCR tool versions and language packs", body = OcrDiagnosticsResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_ocr_diagnostics(auth_user: AuthUser) -> Result<Json<OcrDiagnosticsResponse>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    // Every check starts a process or loads language data
+    let diagnostics = tokio::task::spawn_blocking(collect_ocr_diagnostics)
+        .await
+        .map_err(|e| {
+            tracing::error!("OCR diagnostics task failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(diagnostics))
+}
+
+fn collect_ocr_diagnostics() -> OcrDiagnosticsResponse {
+    let ocr_enabled = cfg!(feature = "ocr");
+    let health_checker = OcrHealthChecker::new();
+    let mut issues = Vec::new();
+    if !ocr_enabled {
+        issues.push("OCR support is not compiled into this build; documents are not OCRed".to_string());
+    }
+
+    let tesseract_version = match health_checker.check_tesseract_installation() {
+        Ok(version) => Some(version),
+        Err(e) => {
+            issues.push(e.to_string());
+            None
+        }
+    };
+
+    let languages: Vec<OcrLanguageStatus> = match health_checker.get_available_languages() {
+        Ok(codes) => codes
+            .into_iter()
+            .map(|code| {
+                let error = match health_checker.check_language_usable(&code) {
+                    Ok(()) => None,
+                    Err(e) => Some(e.to_string()),
+                };
+                OcrLanguageStatus {
+                    name: get_language_display_name(&code),
+                    usable: error.is_none(),
+                    error,
+                    code,
+                }
+            })
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    if ocr_enabled {
+        if tesseract_version.is_some() && !languages.iter().any(|language| language.usable) {
+            issues.push("No usable Tesseract language pack is installed".to_string());
+        }
+        for language in languages.iter().filter(|language| !language.usable) {
+            issues.push(format!("Language pack '{}' is installed but cannot be loaded", language.code));
+        }
+    }
+
+    let ocrmypdf_version = health_checker.check_ocrmypdf_installation();
+    if ocrmypdf_version.is_none() {
+        issues.push("ocrmypdf is not installed; scanned PDFs cannot be OCRed".to_string());
+    }
+
+    OcrDiagnosticsResponse {
+        ocr_enabled,
+        tesseract: OcrToolStatus {
+            installed: tesseract_version.is_some(),
+            usable: ocr_enabled && tesseract_version.is_some() && languages.iter().any(|language| language.usable),
+            version: tesseract_version,
+        },
+        ocrmypdf: OcrToolStatus {
+            installed: ocrmypdf_version.is_some(),
+            usable: ocr_enabled && ocrmypdf_version.is_some(),
+            version: ocrmypdf_version,
+        },
+        languages,
+        issues,
+    }
+}
+
+/// Run OCR on an uploaded sample without storing it
+///
+/// Multipart fields: `file` (required), `language` (e.g. `eng` or `eng+deu`), `psm`, `oem`,
+/// `whitelist_chars`, `blacklist_chars`, and the booleans `enable_image_preprocessing`,
+/// `enhance_contrast`, `remove_noise` and `detect_orientation`, and `preprocessing`, a preset
+/// name or a JSON list of preprocessing steps. Parameters that are not given fall back to the
+/// user's saved settings. No document or queue entry is created.
+#[utoipa::path(
+    post,
+    path = "/api/ocr/preview",
+    tag = "ocr",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content_type = "multipart/form-data", description = "Sample file and OCR parameters"),
+    responses(
+        (status = 200, description = "OCR result for the sample", body = OcrPreviewResponse),
+        (status = 400, description = "Missing file, unsupported file type or invalid parameters", body = OcrErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 413, description = "File exceeds the upload size limit", body = OcrErrorResponse),
+        (status = 500, description = "OCR processing failed", body = OcrErrorResponse)
+    )
+)]
+pub async fn preview_ocr(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    mut multipart: Multipart,
+) -> Result<Json<OcrPreviewResponse>, (StatusCode, Json<OcrErrorResponse>)> {
+    let mut settings = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .map_err(|e| preview_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load settings: {}", e)))?
+        .unwrap_or_default();
+    // Nothing from a preview is kept
+    settings.save_processed_images = false;
+
+    // Removed on drop, so the sample is cleaned up on errors and panics alike
+    let scratch = PreviewScratchDir::create()
+        .await
+        .map_err(|e| preview_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create scratch directory: {}", e)))?;
+
+    let file_service = FileService::new(state.config.upload_path.clone());
+    let max_file_size_bytes = state.config.max_file_size_mb as u64 * 1024 * 1024;
+    let mut overrides = OcrOverrides::default();
+    let mut language: Option<String> = None;
+    let mut sample: Option<(PathBuf, String, String, u64)> = None;
+
+    while let Some(mut field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| preview_error(StatusCode::BAD_REQUEST, format!("Failed to read multipart field: {}", e)))?
+    {
+        let name = field.name().unwrap_or("").to_string();
+
+        if name == "file" {
+            let filename = field
+                .file_name()
+                .ok_or_else(|| preview_error(StatusCode::BAD_REQUEST, "No filename provided in upload".to_string()))?
+                .to_string();
+            if !file_service.is_allowed_file_type(&filename, &state.config.allowed_file_types) {
+                return Err(preview_error(
+                    StatusCode::BAD_REQUEST,
+                    format!("File type of '{}' is not allowed. Allowed types: {}", filename, state.config.allowed_file_types.join(", ")),
+                ));
+            }
+            let mime_type = match field.content_type() {
+                Some(content_type) if content_type != "application/octet-stream" => content_type.to_string(),
+                _ => mime_guess::from_path(&filename).first_or_octet_stream().to_string(),
+            };
+
+            let extension = Path::new(&filename).extension().and_then(|e| e.to_str()).unwrap_or("bin");
+            let path = scratch.path().join(format!("sample.{}", extension));
+            let mut file = tokio::fs::File::create(&path)
+                .await
+                .map_err(|e| preview_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create temp file: {}", e)))?;
+
+            let mut size = 0u64;
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|e| preview_error(StatusCode::BAD_REQUEST, format!("Failed to read file data: {}", e)))?
+            {
+                size += chunk.len() as u64;
+                if size > max_file_size_bytes {
+                    return Err(preview_error(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        format!("File '{}' exceeds maximum allowed size ({}MB)", filename, state.config.max_file_size_mb),
+                    ));
+                }
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| preview_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+            }
+            file.flush()
+                .await
+                .map_err(|e| preview_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to write temp file: {}", e)))?;
+
+            sample = Some((path, filename, mime_type, size));
+            continue;
+        }
+
+        let value = field
+            .text()
+            .await
+            .map_err(|e| preview_error(StatusCode::BAD_REQUEST, format!("Failed to read field '{}': {}", name, e)))?;
+        let value = value.trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        match name.as_str() {
+            "language" => language = Some(value.to_string()),
+            "psm" => overrides.psm = Some(parse_preview_field(&name, value)?),
+            "oem" => overrides.oem = Some(parse_preview_field(&name, value)?),
+            "whitelist_chars" => overrides.whitelist_chars = Some(value.to_string()),
+            "blacklist_chars" => overrides.blacklist_chars = Some(value.to_string()),
+            "enable_image_preprocessing" => settings.enable_image_preprocessing = parse_preview_field(&name, value)?,
+            "enhance_contrast" => settings.ocr_enhance_contrast = parse_preview_field(&name, value)?,
+            "remove_noise" => settings.ocr_remove_noise = parse_preview_field(&name, value)?,
+            "detect_orientation" => settings.ocr_detect_orientation = parse_preview_field(&name, value)?,
+            "preprocessing" => {
+                // A preset name, or a JSON list of steps
+                let config = serde_json::from_str(value).unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                overrides.preprocessing = Some(
+                    PreprocessingConfig::from_value(&config).map_err(|e| preview_error(StatusCode::BAD_REQUEST, e))?,
+                );
+            }
+            other => warn!("Ignoring unknown OCR preview field '{}'", other),
+        }
+    }
+
+    overrides
+        .validate()
+        .map_err(|e| preview_error(StatusCode::BAD_REQUEST, e))?;
+    let mut settings = overrides.apply_to(&settings);
+
+    if let Some(language) = language {
+        let languages: Vec<String> = language.split('+').map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).collect();
+        let health_checker = OcrHealthChecker::new();
+        for lang in &languages {
+            health_checker
+                .validate_language(lang)
+                .map_err(|e| preview_error(StatusCode::BAD_REQUEST, format!("Invalid OCR language '{}': {}", lang, e)))?;
+        }
+        if let Some(primary) = languages.first() {
+            settings.primary_language = primary.clone();
+            settings.ocr_language = primary.clone();
+            settings.preferred_languages = languages;
+        }
+    }
+
+    let (sample_path, filename, mime_type, size) = sample
+        .ok_or_else(|| preview_error(StatusCode::BAD_REQUEST, "No file found in upload".to_string()))?;
+    info!("OCR preview for user {}: '{}' ({}, {} bytes)", auth_user.user.id, filename, mime_type, size);
+
+    // Run on its own task so a panic inside the OCR engine becomes an error response
+    let ocr_service = EnhancedOcrService::new(scratch.path().to_string_lossy().to_string());
+    let timeout = std::time::Duration::from_secs(state.config.ocr_timeout_seconds.max(1));
+    let task_settings = settings.clone();
+    let task = tokio::spawn(async move {
+        ocr_service
+            .extract_text_with_context(&sample_path.to_string_lossy(), &mime_type, &filename, size as i64, &task_settings)
+            .await
+    });
+
+    let result = match tokio::time::timeout(timeout, task).await {
+        Ok(Ok(Ok(result))) => result,
+        Ok(Ok(Err(e))) => return Err(error_response(&e)),
+        Ok(Err(e)) => {
+            return Err(preview_error(StatusCode::INTERNAL_SERVER_ERROR, format!("OCR preview task failed: {}", e)));
+        }
+        Err(_) => {
+            return Err(preview_error(
+                StatusCode::REQUEST_TIMEOUT,
+                format!("OCR preview timed out after {} seconds", timeout.as_secs()),
+            ));
+        }
+    };
+
+    let language = if settings.preferred_languages.is_empty() {
+        settings.ocr_language.clone()
+    } else {
+        settings.preferred_languages.join("+")
+    };
+
+    Ok(Json(OcrPreviewResponse {
+        text: result.text,
+        confidence: result.confidence,
+        word_count: result.word_count,
+        processing_time_ms: result.processing_time_ms,
+        preprocessing_applied: result.preprocessing_applied,
+        language,
+        psm: settings.ocr_page_segmentation_mode,
+        oem: settings.ocr_engine_mode,
+    }))
+}
+
+/// Benchmark OCR throughput for capacity planning (admin only)
+///
+/// OCRs sample files `iterations` times with the caller's OCR settings and reports pages
+/// per second and latency. Multipart fields, all optional: `iterations` (default 3, at
+/// most 10), `concurrency` (default and maximum `CONCURRENT_OCR_JOBS`) and up to 10
+/// `file` samples. Without uploaded samples, the bundled samples are used: a scanned page,
+/// a photo of a document and a multi-page PDF. Samples are processed in a scratch
+/// directory; the OCR queue and stored documents are not touched. Only one benchmark
+/// runs at a time.
+#[utoipa::path(
+    post,
+    path = "/api/ocr/benchmark",
+    tag = "ocr",
+    security(
+        ("bearer_auth" = [])
+    ),
+    request_body(content_type = "multipart/form-data", description = "Optional samples and benchmark parameters"),
+    responses(
+        (status = 200, description = "Benchmark report", body = OcrBenchmarkReport),
+        (status = 400, description = "Invalid parameters or unsupported sample", body = OcrErrorResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 409, description = "A benchmark is already running", body = OcrErrorResponse),
+        (status = 413, description = "A sample exceeds the upload size limit", body = OcrErrorResponse),
+        (status = 500, description = "Internal server error", body = OcrErrorResponse)
+    )
+)]
+pub async fn run_ocr_benchmark(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    mut multipart: Multipart,
+) -> Result<Json<OcrBenchmarkReport>, (StatusCode, Json<OcrErrorResponse>)> {
+    require_admin(&auth_user).map_err(|status| benchmark_error(status, "Admin access required".to_string()))?;
+
+    let max_concurrency = state.config.concurrent_ocr_jobs.max(1);
+    let max_file_size_bytes = state.config.max_file_size_mb as usize * 1024 * 1024;
+    let file_service = FileService::new(state.config.upload_path.clone());
+    let mut iterations = DEFAULT_BENCHMARK_ITERATIONS;
+    let mut concurrency = max_concurrency;
+    let mut samples = Vec::new();
+
+    while let Some(field) = multipart
+        .next_field()
+        .await
+        .map_err(|e| benchmark_error(StatusCode::BAD_REQUEST, format!("Failed to read multipart field: {}", e)))?
+    {
+        let name = field.name().unwrap_or("").to_string();
+        match name.as_str() {
+            "file" => {
+                let filename = field
+                    .file_name()
+                    .ok_or_else(|| benchmark_error(StatusCode::BAD_REQUEST, "No filename provided in upload".to_string()))?
+                    .to_string();
+                if samples.len() >= MAX_BENCHMARK_SAMPLES {
+                    return Err(benchmark_error(
+                        StatusCode::BAD_REQUEST,
+                        format!("At most {} samples can be benchmarked at once", MAX_BENCHMARK_SAMPLES),
+                    ));
+                }
+                if !file_service.is_allowed_file_type(&filename, &state.config.allowed_file_types) {
+                    return Err(benchmark_error(
+                        StatusCode::BAD_REQUEST,
+                        format!("File type of '{}' is not allowed. Allowed types: {}", filename, state.config.allowed_file_types.join(", ")),
+                    ));
+                }
+                let mime_type = match field.content_type() {
+                    Some(content_type) if content_type != "application/octet-stream" => content_type.to_string(),
+                    _ => mime_guess::from_path(&filename).first_or_octet_stream().to_string(),
+                };
+                let data = field
+                    .bytes()
+                    .await
+                    .map_err(|e| benchmark_error(StatusCode::BAD_REQUEST, format!("Failed to read file data: {}", e)))?;
+                if data.len() > max_file_size_bytes {
+                    return Err(benchmark_error(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        format!("File '{}' exceeds maximum allowed size ({}MB)", filename, state.config.max_file_size_mb),
+                    ));
+                }
+                samples.push(BenchmarkSample { filename, mime_type, data: data.to_vec() });
+            }
+            "iterations" | "concurrency" => {
+                let value = field
+                    .text()
+                    .await
+                    .map_err(|e| benchmark_error(StatusCode::BAD_REQUEST, format!("Failed to read field '{}': {}", name, e)))?;
+                let (parsed, max) = match name.as_str() {
+                    "iterations" => (&mut iterations, MAX_BENCHMARK_ITERATIONS),
+                    _ => (&mut concurrency, max_concurrency),
+                };
+                *parsed = match value.trim().parse::<usize>() {
+                    Ok(n) if (1..=max).contains(&n) => n,
+                    _ => {
+                        return Err(benchmark_error(
+                            StatusCode::BAD_REQUEST,
+                            format!("'{}' must be a number from 1 to {}, got '{}'", name, max, value.trim()),
+                        ))
+                    }
+                };
+            }
+            other => warn!("Ignoring unknown OCR benchmark field '{}'", other),
+        }
+    }
+
+    let _guard = BenchmarkGuard::acquire()
+        .ok_or_else(|| benchmark_error(StatusCode::CONFLICT, "An OCR benchmark is already running".to_string()))?;
+
+    let settings = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .map_err(|e| benchmark_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to load settings: {}", e)))?
+        .unwrap_or_default();
+
+    let bundled = samples.is_empty();
+    if bundled {
+        samples = bundled_samples();
+    }
+
+    // Removed on drop, so the samples are cleaned up on errors and panics alike
+    let scratch = PreviewScratchDir::create()
+        .await
+        .map_err(|e| benchmark_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to create scratch directory: {}", e)))?;
+
+    info!(
+        "User {} started an OCR benchmark: {} {} samples, {} iterations, concurrency {}",
+        auth_user.user.id, samples.len(), if bundled { "bundled" } else { "uploaded" }, iterations, concurrency
+    );
+    let timeout = std::time::Duration::from_secs(state.config.ocr_timeout_seconds.max(1));
+    OcrBenchmark::new(settings, iterations, concurrency, timeout)
+        .run(samples, bundled, scratch.path())
+        .await
+        .map(Json)
+        .map_err(|e| benchmark_error(StatusCode::INTERNAL_SERVER_ERROR, format!("OCR benchmark failed: {}", e)))
+}
+
+fn benchmark_error(status: StatusCode, message: String) -> (StatusCode, Json<OcrErrorResponse>) {
+    if status.is_server_error() {
+        tracing::error!("OCR benchmark failed: {}", message);
+    }
+    (
+        status,
+        Json(OcrErrorResponse {
+            error: message,
+            error_code: "OCR_BENCHMARK_ERROR".to_string(),
+            details: None,
+            is_recoverable: status.is_client_error(),
+        }),
+    )
+}
+
+fn parse_preview_field<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, (StatusCode, Json<OcrErrorResponse>)> {
+    value
+        .parse()
+        .map_err(|_| preview_error(StatusCode::BAD_REQUEST, format!("Invalid value '{}' for '{}'", value, name)))
+}
+
+fn preview_error(status: StatusCode, message: String) -> (StatusCode, Json<OcrErrorResponse>) {
+    if status.is_server_error() {
+        tracing::error!("OCR preview failed: {}", message);
+    }
+    (
+        status,
+        Json(OcrErrorResponse {
+            error: message,
+            error_code: "OCR_PREVIEW_ERROR".to_string(),
+            details: None,
+            is_recoverable: status.is_client_error(),
+        }),
+    )
+}
+
+/// A per-request scratch directory holding the samples and any processed images
+struct PreviewScratchDir(PathBuf);
+
+impl PreviewScratchDir {
+    async fn create() -> std::io::Result<Self> {
+        let path = std::env::temp_dir().join(format!("readur_ocr_preview_{}", uuid::Uuid::new_v4()));
+        tokio::fs::create_dir_all(&path).await?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for PreviewScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_dir_all(&self.0) {
+            warn!("Failed to remove OCR preview directory {}: {}", self.0.display(), e);
+        }
+    }
+}
+
 /// Convert language codes to human-readable names
-fn get_language_display_name(code: &str) -> String {
+pub(crate) fn get_language_display_name(code: &str) -> String {
     match code {
         "eng" => "English",
         "spa" => "Spanish",
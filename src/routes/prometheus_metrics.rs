@@ -9,7 +9,12 @@ use std::sync::Arc;
 use std::fmt::Write;
 use std::time::Instant;
 
-use crate::AppState;
+use crate::{
+    AppState,
+    db::source_sync_runs::SourceSyncTotals,
+    ocr::queue::PROCESSING_TIME_BUCKETS_MS,
+    services::derived_asset_cache::DerivedAssetCacheStats,
+};
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -39,14 +44,17 @@ pub async fn get_prometheus_metrics(
     tracing::debug!("Prometheus: Starting to collect all metrics");
     
     // Collect all metrics
-    let (document_metrics, ocr_metrics, user_metrics, database_metrics, system_metrics, storage_metrics, security_metrics) = tokio::try_join!(
+    let (document_metrics, ocr_metrics, ocr_queue_breakdown, user_metrics, database_metrics, system_metrics, storage_metrics, security_metrics, source_sync_totals, asset_cache_stats) = tokio::try_join!(
         collect_document_metrics(&state),
         collect_ocr_metrics(&state),
+        collect_ocr_queue_breakdown(&state),
         collect_user_metrics(&state),
         collect_database_metrics(&state),
         collect_system_metrics(&state),
         collect_storage_metrics(&state),
-        collect_security_metrics(&state)
+        collect_security_metrics(&state),
+        collect_source_sync_metrics(&state),
+        collect_derived_asset_cache_metrics(&state)
     ).map_err(|e| {
         tracing::error!("Prometheus: Failed to collect metrics: {:?}", e);
         e
@@ -90,6 +98,10 @@ pub async fn get_prometheus_metrics(
     writeln!(&mut output, "# TYPE readur_ocr_queue_failed gauge").unwrap();
     writeln!(&mut output, "readur_ocr_queue_failed {} {}", ocr_metrics.failed_jobs, timestamp).unwrap();
     
+    writeln!(&mut output, "# HELP readur_ocr_queue_dead_letter OCR jobs in the dead-letter queue after exhausting their attempts").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_queue_dead_letter gauge").unwrap();
+    writeln!(&mut output, "readur_ocr_queue_dead_letter {} {}", ocr_metrics.dead_letter_jobs, timestamp).unwrap();
+    
     writeln!(&mut output, "# HELP readur_ocr_completed_today OCR jobs completed today").unwrap();
     writeln!(&mut output, "# TYPE readur_ocr_completed_today gauge").unwrap();
     writeln!(&mut output, "readur_ocr_completed_today {} {}", ocr_metrics.completed_today, timestamp).unwrap();
@@ -151,10 +163,113 @@ pub async fn get_prometheus_metrics(
     writeln!(&mut output, "# TYPE readur_ocr_stuck_jobs gauge").unwrap();
     writeln!(&mut output, "readur_ocr_stuck_jobs {} {}", ocr_metrics.stuck_jobs, timestamp).unwrap();
     
+    writeln!(&mut output, "# HELP readur_ocr_workers_active OCR jobs currently being processed by this instance").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_workers_active gauge").unwrap();
+    writeln!(&mut output, "readur_ocr_workers_active {} {}", state.queue_service.active_workers(), timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_ocr_workers_max OCR jobs allowed at once after limiting to the database pool share").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_workers_max gauge").unwrap();
+    writeln!(&mut output, "readur_ocr_workers_max {} {}", state.queue_service.worker_limit(), timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_ocr_workers_configured_max OCR jobs allowed at once by CONCURRENT_OCR_JOBS").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_workers_configured_max gauge").unwrap();
+    writeln!(&mut output, "readur_ocr_workers_configured_max {} {}", state.queue_service.max_concurrent_jobs(), timestamp).unwrap();
+    
     writeln!(&mut output, "# HELP readur_ocr_queue_depth Total OCR queue depth (pending + processing)").unwrap();
     writeln!(&mut output, "# TYPE readur_ocr_queue_depth gauge").unwrap();
     writeln!(&mut output, "readur_ocr_queue_depth {} {}", ocr_metrics.queue_depth, timestamp).unwrap();
     
+    // OCR queue breakdown by status and priority bucket
+    writeln!(&mut output, "# HELP readur_ocr_queue_items OCR queue items by status and priority bucket").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_queue_items gauge").unwrap();
+    for group in &ocr_queue_breakdown.groups {
+        writeln!(&mut output, "readur_ocr_queue_items{{status=\"{}\",priority=\"{}\"}} {} {}",
+                 escape_label_value(&group.status), group.priority_bucket, group.count, timestamp).unwrap();
+    }
+    
+    writeln!(&mut output, "# HELP readur_ocr_queue_oldest_item_age_seconds Age of the oldest OCR queue item by status and priority bucket").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_queue_oldest_item_age_seconds gauge").unwrap();
+    for group in &ocr_queue_breakdown.groups {
+        writeln!(&mut output, "readur_ocr_queue_oldest_item_age_seconds{{status=\"{}\",priority=\"{}\"}} {} {}",
+                 escape_label_value(&group.status), group.priority_bucket, group.oldest_age_seconds, timestamp).unwrap();
+    }
+    
+    let histogram = state.queue_service.processing_times().snapshot();
+    writeln!(&mut output, "# HELP readur_ocr_processing_time_ms Processing time of OCR jobs completed since the server started, in milliseconds").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_processing_time_ms histogram").unwrap();
+    for (bound, count) in PROCESSING_TIME_BUCKETS_MS.iter().zip(&histogram.bucket_counts) {
+        writeln!(&mut output, "readur_ocr_processing_time_ms_bucket{{le=\"{}\"}} {} {}", bound, count, timestamp).unwrap();
+    }
+    writeln!(&mut output, "readur_ocr_processing_time_ms_bucket{{le=\"+Inf\"}} {} {}", histogram.count, timestamp).unwrap();
+    writeln!(&mut output, "readur_ocr_processing_time_ms_sum {} {}", histogram.sum_ms, timestamp).unwrap();
+    writeln!(&mut output, "readur_ocr_processing_time_ms_count {} {}", histogram.count, timestamp).unwrap();
+    
+    // Documents leave this count when their OCR is retried, so it is a gauge rather than a counter
+    writeln!(&mut output, "# HELP readur_ocr_failed_documents Documents whose OCR currently stands failed, by failure reason").unwrap();
+    writeln!(&mut output, "# TYPE readur_ocr_failed_documents gauge").unwrap();
+    for (reason, count) in &ocr_queue_breakdown.failures_by_reason {
+        writeln!(&mut output, "readur_ocr_failed_documents{{reason=\"{}\"}} {} {}", escape_label_value(reason), count, timestamp).unwrap();
+    }
+    
+    // Per-source sync metrics
+    writeln!(&mut output, "# HELP readur_source_files_synced_total Files downloaded by source syncs").unwrap();
+    writeln!(&mut output, "# TYPE readur_source_files_synced_total counter").unwrap();
+    for totals in &source_sync_totals {
+        writeln!(&mut output, "readur_source_files_synced_total{{source_id=\"{}\",source_name=\"{}\"}} {} {}",
+            totals.source_id, escape_label_value(&totals.source_name), totals.files_synced_total, timestamp).unwrap();
+    }
+    
+    writeln!(&mut output, "# HELP readur_source_bytes_transferred_total Bytes downloaded by source syncs").unwrap();
+    writeln!(&mut output, "# TYPE readur_source_bytes_transferred_total counter").unwrap();
+    for totals in &source_sync_totals {
+        writeln!(&mut output, "readur_source_bytes_transferred_total{{source_id=\"{}\",source_name=\"{}\"}} {} {}",
+            totals.source_id, escape_label_value(&totals.source_name), totals.bytes_transferred_total, timestamp).unwrap();
+    }
+    
+    writeln!(&mut output, "# HELP readur_source_last_run_duration_seconds Duration of the most recent finished sync run").unwrap();
+    writeln!(&mut output, "# TYPE readur_source_last_run_duration_seconds gauge").unwrap();
+    for totals in &source_sync_totals {
+        if let Some(duration) = totals.last_run_duration_seconds {
+            writeln!(&mut output, "readur_source_last_run_duration_seconds{{source_id=\"{}\",source_name=\"{}\"}} {} {}",
+                totals.source_id, escape_label_value(&totals.source_name), duration, timestamp).unwrap();
+        }
+    }
+    
+    writeln!(&mut output, "# HELP readur_source_downloads_active Downloads from sources running right now across all syncs").unwrap();
+    writeln!(&mut output, "# TYPE readur_source_downloads_active gauge").unwrap();
+    writeln!(&mut output, "readur_source_downloads_active {} {}", state.download_limiter.in_use(), timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_source_downloads_max Downloads from sources allowed at once by SOURCE_DOWNLOAD_MAX_CONCURRENCY").unwrap();
+    writeln!(&mut output, "# TYPE readur_source_downloads_max gauge").unwrap();
+    writeln!(&mut output, "readur_source_downloads_max {} {}", state.download_limiter.capacity(), timestamp).unwrap();
+    
+    // Derived file cache metrics
+    writeln!(&mut output, "# HELP readur_derived_asset_cache_bytes Size of cached thumbnails and searchable PDFs in bytes").unwrap();
+    writeln!(&mut output, "# TYPE readur_derived_asset_cache_bytes gauge").unwrap();
+    writeln!(&mut output, "readur_derived_asset_cache_bytes {} {}", asset_cache_stats.size_bytes, timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_derived_asset_cache_max_bytes Size cap of the derived file cache in bytes (0 is unbounded)").unwrap();
+    writeln!(&mut output, "# TYPE readur_derived_asset_cache_max_bytes gauge").unwrap();
+    writeln!(&mut output, "readur_derived_asset_cache_max_bytes {} {}", asset_cache_stats.max_bytes, timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_derived_asset_cache_entries Files in the derived file cache").unwrap();
+    writeln!(&mut output, "# TYPE readur_derived_asset_cache_entries gauge").unwrap();
+    writeln!(&mut output, "readur_derived_asset_cache_entries {} {}", asset_cache_stats.entries, timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_derived_asset_cache_hits_total Requests served from the derived file cache since startup").unwrap();
+    writeln!(&mut output, "# TYPE readur_derived_asset_cache_hits_total counter").unwrap();
+    writeln!(&mut output, "readur_derived_asset_cache_hits_total {} {}", asset_cache_stats.hits, timestamp).unwrap();
+    
+    writeln!(&mut output, "# HELP readur_derived_asset_cache_misses_total Requests that generated a derived file since startup").unwrap();
+    writeln!(&mut output, "# TYPE readur_derived_asset_cache_misses_total counter").unwrap();
+    writeln!(&mut output, "readur_derived_asset_cache_misses_total {} {}", asset_cache_stats.misses, timestamp).unwrap();
+    
+    if let Some(hit_rate) = asset_cache_stats.hit_rate() {
+        writeln!(&mut output, "# HELP readur_derived_asset_cache_hit_rate Share of derived file requests served from the cache since startup").unwrap();
+        writeln!(&mut output, "# TYPE readur_derived_asset_cache_hit_rate gauge").unwrap();
+        writeln!(&mut output, "readur_derived_asset_cache_hit_rate {} {}", hit_rate, timestamp).unwrap();
+    }
+    
     // Storage metrics
     writeln!(&mut output, "# HELP readur_storage_usage_percent Storage utilization percentage").unwrap();
     writeln!(&mut output, "# TYPE readur_storage_usage_percent gauge").unwrap();
@@ -213,6 +328,7 @@ struct OcrMetrics {
     pending_jobs: i64,
     processing_jobs: i64,
     failed_jobs: i64,
+    dead_letter_jobs: i64,
     completed_today: i64,
     avg_processing_time_minutes: Option<f64>,
     avg_confidence: Option<f64>,
@@ -221,6 +337,18 @@ struct OcrMetrics {
     queue_depth: i64,
 }
 
+struct OcrQueueGroup {
+    status: String,
+    priority_bucket: String,
+    count: i64,
+    oldest_age_seconds: f64,
+}
+
+struct OcrQueueBreakdown {
+    groups: Vec<OcrQueueGroup>,
+    failures_by_reason: Vec<(String, i64)>,
+}
+
 struct UserMetrics {
     total_users: i64,
     active_users_today: i64,
@@ -360,10 +488,19 @@ async fn collect_ocr_metrics(state: &Arc<AppState>) -> Result<OcrMetrics, Status
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    let dead_letter_jobs = queue_service
+        .count_dead_letter_items()
+        .await
+        .map_err(|e| {
+            tracing::error!("Failed to get OCR dead-letter count: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
     Ok(OcrMetrics {
         pending_jobs: stats.pending_count,
         processing_jobs: stats.processing_count,
         failed_jobs: stats.failed_count,
+        dead_letter_jobs,
         completed_today: stats.completed_today,
         avg_processing_time_minutes: stats.avg_wait_time_minutes,
         avg_confidence,
@@ -373,6 +510,85 @@ async fn collect_ocr_metrics(state: &Arc<AppState>) -> Result<OcrMetrics, Status
     })
 }
 
+/// Collects queue depth by status and priority bucket and failure reasons. Everything comes
+/// from two aggregate queries to keep the scrape cheap.
+async fn collect_ocr_queue_breakdown(state: &Arc<AppState>) -> Result<OcrQueueBreakdown, StatusCode> {
+    use sqlx::Row;
+    
+    // Priorities follow the enqueue conventions: WebDAV uses 2-10 by file size, uploads use 5
+    let rows = sqlx::query(
+        r#"
+        SELECT status,
+               CASE WHEN priority >= 8 THEN 'high' WHEN priority >= 4 THEN 'normal' ELSE 'low' END AS priority_bucket,
+               COUNT(*) AS item_count,
+               CAST(COALESCE(EXTRACT(EPOCH FROM (NOW() - MIN(created_at))), 0) AS DOUBLE PRECISION) AS oldest_age_seconds
+        FROM ocr_queue
+        WHERE status IN ('pending', 'processing', 'failed', 'dead_letter')
+        GROUP BY status, priority_bucket
+        "#
+    )
+    .fetch_all(&state.db.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to get OCR queue breakdown: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    
+    let groups = rows
+        .iter()
+        .map(|row| OcrQueueGroup {
+            status: row.get("status"),
+            priority_bucket: row.get("priority_bucket"),
+            count: row.get("item_count"),
+            oldest_age_seconds: row.get("oldest_age_seconds"),
+        })
+        .collect();
+    
+    let failure_rows = sqlx::query(
+        r#"
+        SELECT COALESCE(ocr_failure_reason, 'unknown') AS reason, COUNT(*) AS failure_count
+        FROM documents
+        WHERE ocr_status = 'failed'
+        GROUP BY 1
+        "#
+    )
+    .fetch_all(&state.db.pool)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to get OCR failures by reason: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    
+    let failures_by_reason = failure_rows
+        .iter()
+        .map(|row| (row.get("reason"), row.get("failure_count")))
+        .collect();
+    
+    Ok(OcrQueueBreakdown {
+        groups,
+        failures_by_reason,
+    })
+}
+
+/// Escapes a Prometheus label value (backslash, double quote and newline)
+fn escape_label_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+async fn collect_source_sync_metrics(state: &Arc<AppState>) -> Result<Vec<SourceSyncTotals>, StatusCode> {
+    state.db.get_source_sync_totals().await.map_err(|e| {
+        tracing::error!("Failed to get source sync metrics: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
+async fn collect_derived_asset_cache_metrics(state: &Arc<AppState>) -> Result<DerivedAssetCacheStats, StatusCode> {
+    state.derived_asset_cache.stats().await.map_err(|e| {
+        tracing::error!("Failed to get derived file cache metrics: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })
+}
+
 async fn collect_user_metrics(state: &Arc<AppState>) -> Result<UserMetrics, StatusCode> {
     // Get total user count
     let total_users = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM users")
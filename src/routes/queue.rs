@@ -1,14 +1,70 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::Json,
+    extract::{Path, Query, State, WebSocketUpgrade},
+    extract::ws::{Message, WebSocket},
+    http::{HeaderMap, StatusCode},
+    response::{Json, Response},
     routing::{get, post},
     Router,
 };
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
 use sqlx::Row;
-use std::{sync::Arc, error::Error};
+use std::{sync::Arc, error::Error, time::Duration};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 
-use crate::{auth::AuthUser, ocr::queue::OcrQueueService, AppState, models::UserRole};
+use crate::{
+    auth::AuthUser,
+    ocr::queue::{AdminQueueFilter, AdminQueueItem, DeadLetterRequeue, OcrQueueService, STALE_PROCESSING_MINUTES},
+    AppState,
+    models::UserRole,
+};
+
+/// How often the OCR progress websocket reports queue depth and ETA
+const OCR_PROGRESS_HEARTBEAT: Duration = Duration::from_secs(5);
+/// Queue entries returned by the admin listing when the request does not say
+const DEFAULT_ADMIN_QUEUE_LIMIT: i64 = 50;
+/// Most queue entries returned by one admin listing request
+const MAX_ADMIN_QUEUE_LIMIT: i64 = 500;
+/// Statuses an `ocr_queue` entry can have
+const QUEUE_STATUSES: [&str; 6] = ["pending", "processing", "completed", "failed", "cancelled", "dead_letter"];
+/// Highest priority the `ocr_queue` table accepts
+const MAX_QUEUE_PRIORITY: i32 = 1000;
+
+#[derive(Deserialize, IntoParams)]
+pub struct AdminQueueQuery {
+    /// Only entries with this status: pending, processing, completed, failed, cancelled or dead_letter
+    pub status: Option<String>,
+    /// Only entries for documents of this user
+    pub user_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct DeadLetterQuery {
+    /// Only entries for documents of this user
+    pub user_id: Option<Uuid>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AdminQueueResponse {
+    pub items: Vec<AdminQueueItem>,
+    /// Entries matching the filters, across all pages
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct QueuePriorityRequest {
+    /// New priority, 0-1000; higher runs sooner
+    pub priority: i32,
+}
 
 pub fn require_admin(auth_user: &AuthUser) -> Result<(), StatusCode> {
     if auth_user.user.role != UserRole::Admin {
@@ -26,6 +82,13 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/pause", post(pause_ocr_processing))
         .route("/resume", post(resume_ocr_processing))
         .route("/status", get(get_ocr_status))
+        .route("/progress/ws", get(ocr_progress_websocket))
+        .route("/admin", get(list_admin_queue))
+        .route("/admin/{id}/priority", post(set_queue_item_priority))
+        .route("/admin/{id}/cancel", post(cancel_queue_item))
+        .route("/admin/requeue-stale", post(requeue_stale_items))
+        .route("/dead-letter", get(list_dead_letter_queue))
+        .route("/dead-letter/{id}/requeue", post(requeue_dead_letter_item))
 }
 
 #[utoipa::path(
@@ -53,11 +116,16 @@ async fn get_queue_stats(
         .get_stats()
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let dead_letter = queue_service
+        .count_dead_letter_items()
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     
     Ok(Json(serde_json::json!({
         "pending": stats.pending_count,
         "processing": stats.processing_count,
         "failed": stats.failed_count,
+        "dead_letter": dead_letter,
         "completed_today": stats.completed_today,
         "avg_wait_time_minutes": stats.avg_wait_time_minutes,
         "oldest_pending_minutes": stats.oldest_pending_minutes,
@@ -269,4 +337,400 @@ async fn enqueue_pending_documents(
         "message": format!("Successfully queued {} pending documents for OCR processing", queue_ids.len()),
         "queue_ids": queue_ids
     })))
-}
\ No newline at end of file
+}
+/// List the OCR queue of every user
+#[utoipa::path(
+    get,
+    path = "/api/queue/admin",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(AdminQueueQuery),
+    responses(
+        (status = 200, description = "Queue entries with their owner, highest priority first", body = AdminQueueResponse),
+        (status = 400, description = "Unknown status"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn list_admin_queue(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<AdminQueueQuery>,
+) -> Result<Json<AdminQueueResponse>, StatusCode> {
+    require_admin(&auth_user)?;
+    if let Some(status) = &query.status {
+        if !QUEUE_STATUSES.contains(&status.as_str()) {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let limit = query.limit.unwrap_or(DEFAULT_ADMIN_QUEUE_LIMIT).clamp(1, MAX_ADMIN_QUEUE_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let filter = AdminQueueFilter {
+        status: query.status,
+        user_id: query.user_id,
+    };
+
+    let (items, total) = state
+        .queue_service
+        .list_queue_items(&filter, limit, offset)
+        .await
+        .map_err(|e| {
+            error!("Failed to list OCR queue: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(AdminQueueResponse { items, total, limit, offset }))
+}
+
+/// Change the priority of a pending OCR job
+#[utoipa::path(
+    post,
+    path = "/api/queue/admin/{id}/priority",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Queue entry ID")
+    ),
+    request_body = QueuePriorityRequest,
+    responses(
+        (status = 200, description = "Priority updated"),
+        (status = 400, description = "Priority out of range"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Queue entry not found"),
+        (status = 409, description = "The job is no longer pending"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn set_queue_item_priority(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(item_id): Path<Uuid>,
+    Json(request): Json<QueuePriorityRequest>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    require_admin(&auth_user)?;
+    if !(0..=MAX_QUEUE_PRIORITY).contains(&request.priority) {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let status = state
+        .queue_service
+        .set_item_priority(item_id, request.priority)
+        .await
+        .map_err(|e| {
+            error!("Failed to set priority of OCR job {}: {}", item_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    match status.as_deref() {
+        None => Err(StatusCode::NOT_FOUND),
+        Some("pending") => {
+            info!("Admin {} set priority of OCR job {} to {}", auth_user.user.id, item_id, request.priority);
+            Ok(Json(serde_json::json!({
+                "id": item_id,
+                "priority": request.priority,
+            })))
+        }
+        Some(_) => Err(StatusCode::CONFLICT),
+    }
+}
+
+/// Cancel a pending OCR job
+#[utoipa::path(
+    post,
+    path = "/api/queue/admin/{id}/cancel",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Queue entry ID")
+    ),
+    responses(
+        (status = 200, description = "Job cancelled"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Queue entry not found"),
+        (status = 409, description = "The job is no longer pending"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn cancel_queue_item(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(item_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let status = state
+        .queue_service
+        .cancel_item(item_id, "Cancelled by an administrator")
+        .await
+        .map_err(|e| {
+            error!("Failed to cancel OCR job {}: {}", item_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    match status.as_deref() {
+        None => Err(StatusCode::NOT_FOUND),
+        Some("pending") => {
+            info!("Admin {} cancelled OCR job {}", auth_user.user.id, item_id);
+            Ok(Json(serde_json::json!({
+                "id": item_id,
+                "status": "cancelled",
+            })))
+        }
+        Some(_) => Err(StatusCode::CONFLICT),
+    }
+}
+
+/// Requeue stale OCR jobs now
+///
+/// Puts jobs that have been processing for longer than 10 minutes back in the queue, as the
+/// maintenance task does every 5 minutes.
+#[utoipa::path(
+    post,
+    path = "/api/queue/admin/requeue-stale",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Stale jobs requeued"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn requeue_stale_items(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let count = state
+        .queue_service
+        .recover_stale_items(STALE_PROCESSING_MINUTES)
+        .await
+        .map_err(|e| {
+            error!("Failed to requeue stale OCR jobs: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    info!("Admin {} requeued {} stale OCR jobs", auth_user.user.id, count);
+    Ok(Json(serde_json::json!({
+        "requeued_count": count,
+        "stale_after_minutes": STALE_PROCESSING_MINUTES,
+    })))
+}
+
+/// List the dead-letter queue
+///
+/// OCR jobs that failed on every attempt, with the error of the last one, highest priority
+/// and oldest first. They are not retried automatically; a later successful OCR run of the
+/// document removes them.
+#[utoipa::path(
+    get,
+    path = "/api/queue/dead-letter",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(DeadLetterQuery),
+    responses(
+        (status = 200, description = "Jobs in the dead-letter queue with their owner", body = AdminQueueResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn list_dead_letter_queue(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Query(query): Query<DeadLetterQuery>,
+) -> Result<Json<AdminQueueResponse>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let limit = query.limit.unwrap_or(DEFAULT_ADMIN_QUEUE_LIMIT).clamp(1, MAX_ADMIN_QUEUE_LIMIT);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let filter = AdminQueueFilter {
+        status: Some("dead_letter".to_string()),
+        user_id: query.user_id,
+    };
+
+    let (items, total) = state
+        .queue_service
+        .list_queue_items(&filter, limit, offset)
+        .await
+        .map_err(|e| {
+            error!("Failed to list OCR dead-letter queue: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(AdminQueueResponse { items, total, limit, offset }))
+}
+
+/// Requeue a job from the dead-letter queue
+///
+/// Resets the job's attempts and puts it back in the queue, and sets its document back to
+/// pending OCR.
+#[utoipa::path(
+    post,
+    path = "/api/queue/dead-letter/{id}/requeue",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Queue entry ID")
+    ),
+    responses(
+        (status = 200, description = "Job requeued"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required"),
+        (status = 404, description = "Queue entry not found"),
+        (status = 409, description = "The job is not in the dead-letter queue, or its document is already queued"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn requeue_dead_letter_item(
+    State(state): State<Arc<AppState>>,
+    auth_user: AuthUser,
+    Path(item_id): Path<Uuid>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    require_admin(&auth_user)?;
+
+    let outcome = state
+        .queue_service
+        .requeue_dead_letter_item(item_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to requeue OCR job {} from the dead-letter queue: {}", item_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    match outcome {
+        None => Err(StatusCode::NOT_FOUND),
+        Some(DeadLetterRequeue::Requeued) => {
+            info!("Admin {} requeued OCR job {} from the dead-letter queue", auth_user.user.id, item_id);
+            Ok(Json(serde_json::json!({
+                "id": item_id,
+                "status": "pending",
+            })))
+        }
+        Some(DeadLetterRequeue::NotDeadLettered(_) | DeadLetterRequeue::AlreadyQueued) => Err(StatusCode::CONFLICT),
+    }
+}
+
+/// WebSocket endpoint for live OCR progress of the user's documents
+///
+/// Streams an event whenever one of the user's documents is enqueued, starts processing,
+/// moves to another processing stage, completes or fails. A `heartbeat` with the queue depth
+/// and the estimated time to drain the queue is sent every few seconds.
+///
+/// # Message Types
+/// - `connection_confirmed`: The connection is established
+/// - `enqueued`, `started`, `progress`, `completed`, `failed`: Job events, with the document and job id
+/// - `heartbeat`: Queue depth and ETA
+/// - `lagged`: The client fell behind and `skipped` events were dropped
+///
+/// # Security
+/// Authentication is handled via JWT token in the `Sec-WebSocket-Protocol` header, as for the
+/// source sync progress websocket.
+#[utoipa::path(
+    get,
+    path = "/api/queue/progress/ws",
+    tag = "queue",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 101, description = "WebSocket connection established - will stream OCR progress events"),
+        (status = 401, description = "Unauthorized - invalid or missing authentication token"),
+        (status = 500, description = "Internal server error during WebSocket upgrade")
+    )
+)]
+pub async fn ocr_progress_websocket(
+    ws: WebSocketUpgrade,
+    headers: HeaderMap,
+    State(state): State<Arc<AppState>>,
+) -> Result<Response, StatusCode> {
+    let token = crate::routes::sources::sync::extract_websocket_token(&headers).ok_or(StatusCode::UNAUTHORIZED)?;
+
+    let claims = crate::auth::verify_jwt(&token, &state.config.jwt_secret)
+        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+    let user = state.db.get_user_by_id(claims.sub).await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::UNAUTHORIZED)?;
+
+    Ok(ws.on_upgrade(move |socket| handle_ocr_progress_socket(socket, user.id, state)))
+}
+
+async fn handle_ocr_progress_socket(socket: WebSocket, user_id: Uuid, state: Arc<AppState>) {
+    info!("OCR progress WebSocket connection established for user {}", user_id);
+
+    let (mut sender, mut receiver) = socket.split();
+    // Dropped when this function returns, which ends the subscription
+    let mut events = state.queue_service.progress_tracker().subscribe();
+    let mut heartbeat = tokio::time::interval(OCR_PROGRESS_HEARTBEAT);
+
+    let confirmation = serde_json::json!({
+        "type": "connection_confirmed",
+        "data": { "timestamp": chrono::Utc::now().timestamp() }
+    });
+    if sender.send(Message::Text(confirmation.to_string().into())).await.is_err() {
+        return;
+    }
+
+    loop {
+        let message = tokio::select! {
+            incoming = receiver.next() => match incoming {
+                // Pings are answered by axum; other client messages are ignored
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => continue,
+            },
+            event = events.recv() => match event {
+                Ok(event) if event.user_id == user_id => serde_json::json!({
+                    "type": event.kind.name(),
+                    "data": event
+                }),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => serde_json::json!({
+                    "type": "lagged",
+                    "data": { "skipped": skipped }
+                }),
+                Err(RecvError::Closed) => break,
+            },
+            _ = heartbeat.tick() => match state.queue_service.get_queue_depth(user_id).await {
+                Ok(depth) => serde_json::json!({
+                    "type": "heartbeat",
+                    "data": {
+                        "queue_depth": depth.queue_depth,
+                        "user_pending": depth.user_pending,
+                        "user_processing": depth.user_processing,
+                        "estimated_seconds_remaining": depth.estimated_seconds_remaining,
+                        "timestamp": chrono::Utc::now().timestamp()
+                    }
+                }),
+                Err(e) => {
+                    warn!("Failed to get OCR queue depth for progress heartbeat: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        if sender.send(Message::Text(message.to_string().into())).await.is_err() {
+            break;
+        }
+    }
+
+    let _ = sender.send(Message::Close(None)).await;
+    info!("OCR progress WebSocket connection closed for user {}", user_id);
+}
@@ -10,7 +10,7 @@ use std::sync::Arc;
 use crate::{
     auth::AuthUser,
     errors::search::SearchError,
-    models::{SearchRequest, SearchResponse, EnhancedDocumentResponse, SearchFacetsResponse},
+    models::{SearchRequest, SearchResponse, EnhancedDocumentResponse, SearchFacetsResponse, SearchMode, UserRole, MAX_SEARCH_LIMIT},
     AppState,
 };
 
@@ -41,7 +41,7 @@ pub fn router() -> Router<Arc<AppState>> {
 async fn search_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(search_request): Query<SearchRequest>,
+    Query(mut search_request): Query<SearchRequest>,
 ) -> Result<Json<SearchResponse>, SearchError> {
     // Validate query length
     if search_request.query.len() < 2 {
@@ -51,10 +51,18 @@ async fn search_documents(
         return Err(SearchError::query_too_long(search_request.query.len(), 1000));
     }
     
+    let settings = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .map_err(|e| SearchError::index_unavailable(format!("Failed to load search settings: {}", e)))?
+        .unwrap_or_default();
+    search_request.apply_user_defaults(&settings);
+
     // Validate pagination
-    let limit = search_request.limit.unwrap_or(25);
+    let limit = search_request.limit.unwrap_or_default();
     let offset = search_request.offset.unwrap_or(0);
-    if limit > 1000 || offset < 0 || limit <= 0 {
+    if limit > MAX_SEARCH_LIMIT || offset < 0 || limit <= 0 {
         return Err(SearchError::invalid_pagination(offset, limit));
     }
     
@@ -64,15 +72,19 @@ async fn search_documents(
         .await
         .map_err(|e| SearchError::index_unavailable(format!("Search failed: {}", e)))?;
     
-    let total = documents.len() as i64;
-    
     // Check if too many results
-    if total > 10000 {
-        return Err(SearchError::too_many_results(total, 10000));
+    if documents.len() > 10000 {
+        return Err(SearchError::too_many_results(documents.len() as i64, 10000));
     }
 
+    let total = state
+        .db
+        .count_search_results(auth_user.user.id, UserRole::User, &SearchMode::Simple, &search_request)
+        .await
+        .map_err(|e| SearchError::index_unavailable(format!("Search failed: {}", e)))?;
+
     let response = SearchResponse {
-        documents: documents.into_iter().map(|doc| EnhancedDocumentResponse {
+        documents: documents.into_iter().map(|(doc, search_rank)| EnhancedDocumentResponse {
             id: doc.id,
             filename: doc.filename,
             original_filename: doc.original_filename,
@@ -85,7 +97,7 @@ async fn search_documents(
             ocr_word_count: doc.ocr_word_count,
             ocr_processing_time_ms: doc.ocr_processing_time_ms,
             ocr_status: doc.ocr_status,
-            search_rank: None,
+            search_rank: Some(search_rank),
             snippets: Vec::new(),
         }).collect(),
         total,
@@ -116,8 +128,16 @@ async fn search_documents(
 async fn enhanced_search_documents(
     State(state): State<Arc<AppState>>,
     auth_user: AuthUser,
-    Query(search_request): Query<SearchRequest>,
+    Query(mut search_request): Query<SearchRequest>,
 ) -> Result<Json<SearchResponse>, StatusCode> {
+    let settings = state
+        .db
+        .get_user_settings(auth_user.user.id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .unwrap_or_default();
+    search_request.apply_user_defaults(&settings);
+
     // Generate suggestions before moving search_request
     let suggestions = generate_search_suggestions(&search_request.query);
     
@@ -127,9 +147,14 @@ async fn enhanced_search_documents(
         .enhanced_search_documents_with_role(auth_user.user.id, auth_user.user.role, &search_request)
         .await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let search_mode = search_request.search_mode.as_ref().unwrap_or(&SearchMode::Simple);
+    let total = state
+        .db
+        .count_search_results(auth_user.user.id, auth_user.user.role, search_mode, &search_request)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     
     let query_time = start_time.elapsed().as_millis() as u64;
-    let total = documents.len() as i64;
 
     let response = SearchResponse {
         documents,
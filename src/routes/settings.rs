@@ -6,11 +6,14 @@ use axum::{
     Router,
 };
 use std::sync::Arc;
+use uuid::Uuid;
 
 use crate::{
     auth::AuthUser,
     errors::settings::SettingsError,
-    models::{SettingsResponse, UpdateSettings, UserRole},
+    models::{SettingsResponse, SourceType, UpdateSettings, UserRole},
+    ocr::health::OcrHealthChecker,
+    routes::ocr::{get_language_display_name, LanguageInfo},
     AppState,
 };
 use serde::Serialize;
@@ -19,6 +22,121 @@ pub fn router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/", get(get_settings).put(update_settings))
         .route("/config", get(get_server_configuration))
+        .route("/ocr/languages", get(get_ocr_languages))
+}
+
+/// OCR languages a user may choose from in their settings
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct OcrLanguagesResponse {
+    /// Installed Tesseract language packs, sorted by code
+    pub languages: Vec<LanguageInfo>,
+    /// False when `tesseract --list-langs` could not be run; languages are then not
+    /// checked when settings are saved
+    pub detected: bool,
+}
+
+/// Installed Tesseract languages, or `None` when Tesseract can't be asked
+fn installed_ocr_languages() -> Option<Vec<String>> {
+    match OcrHealthChecker::new().get_available_languages() {
+        Ok(languages) => Some(languages),
+        Err(e) => {
+            tracing::warn!("Could not list installed OCR languages: {}", e);
+            None
+        }
+    }
+}
+
+/// Rejects OCR languages in the update that have no installed language pack, so a
+/// language is never saved that would fail every OCR job
+fn validate_ocr_languages(update: &UpdateSettings, installed: &[String]) -> Result<(), SettingsError> {
+    let requested = update
+        .ocr_language
+        .iter()
+        .flat_map(|combination| combination.split('+'))
+        .chain(update.preferred_languages.iter().flatten().map(String::as_str))
+        .chain(update.primary_language.as_deref())
+        .map(str::trim);
+
+    for language in requested {
+        if !installed.iter().any(|code| code == language) {
+            return Err(SettingsError::invalid_language(language.to_string(), installed.join(", ")));
+        }
+    }
+    Ok(())
+}
+
+/// Rejects a default source the user doesn't own. Write-back needs a WebDAV default
+/// source, the only kind uploads can be written to.
+async fn validate_default_source(state: &AppState, user_id: Uuid, update: &UpdateSettings) -> Result<(), SettingsError> {
+    if update.default_source_id.is_none() && update.default_source_write_back.is_none() {
+        return Ok(());
+    }
+
+    let current = state
+        .db
+        .get_user_settings(user_id)
+        .await
+        .map_err(|e| SettingsError::internal_server_error(format!("Failed to fetch settings: {}", e)))?;
+    let source_id = update
+        .default_source_id
+        .unwrap_or_else(|| current.as_ref().and_then(|settings| settings.default_source_id));
+    let write_back = update
+        .default_source_write_back
+        .unwrap_or_else(|| current.as_ref().is_some_and(|settings| settings.default_source_write_back));
+
+    let Some(source_id) = source_id else {
+        if update.default_source_write_back == Some(true) {
+            return Err(SettingsError::validation_failed("default_source_write_back", "Write-back needs a default source"));
+        }
+        return Ok(());
+    };
+
+    let source = state
+        .db
+        .get_source(user_id, source_id)
+        .await
+        .map_err(|e| SettingsError::internal_server_error(format!("Failed to fetch source: {}", e)))?
+        .ok_or_else(|| SettingsError::validation_failed("default_source_id".to_string(), format!("Source {} does not exist or belongs to another user", source_id)))?;
+
+    if write_back && source.source_type != SourceType::WebDAV {
+        return Err(SettingsError::validation_failed(
+            "default_source_write_back".to_string(),
+            format!("Uploads can only be written back to WebDAV sources, not {}", source.source_type),
+        ));
+    }
+    Ok(())
+}
+
+/// List the OCR languages installed on this server
+///
+/// Runs `tesseract --list-langs`, so the list matches the language packs of the
+/// deployment rather than a fixed set.
+#[utoipa::path(
+    get,
+    path = "/api/settings/ocr/languages",
+    tag = "settings",
+    security(
+        ("bearer_auth" = [])
+    ),
+    responses(
+        (status = 200, description = "Installed OCR languages", body = OcrLanguagesResponse),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+async fn get_ocr_languages(_auth_user: AuthUser) -> Json<OcrLanguagesResponse> {
+    let installed = installed_ocr_languages();
+    let detected = installed.is_some();
+    let languages = installed
+        .unwrap_or_default()
+        .into_iter()
+        .map(|code| LanguageInfo {
+            name: get_language_display_name(&code),
+            installed: true,
+            code,
+        })
+        .collect();
+
+    Json(OcrLanguagesResponse { languages, detected })
 }
 
 #[utoipa::path(
@@ -42,7 +160,7 @@ async fn get_settings(
         .db
         .get_user_settings(auth_user.user.id)
         .await
-        .map_err(|e| SettingsError::invalid_value("database", &format!("Failed to fetch settings: {}", e), "Settings must be accessible"))?;
+        .map_err(|e| SettingsError::internal_server_error(format!("Failed to fetch settings: {}", e)))?;
 
     let response = match settings {
         Some(s) => s.into(),
@@ -93,6 +211,17 @@ async fn get_settings(
                 ocr_quality_threshold_noise: default.ocr_quality_threshold_noise,
                 ocr_quality_threshold_sharpness: default.ocr_quality_threshold_sharpness,
                 ocr_skip_enhancement: default.ocr_skip_enhancement,
+                ocr_parallel_pages: default.ocr_parallel_pages,
+                ocr_retry_low_confidence: default.ocr_retry_low_confidence,
+                ocr_detect_script: default.ocr_detect_script,
+                ocr_preprocessing: default.ocr_preprocessing,
+                search_clean_snippets: default.search_clean_snippets,
+                ocr_text_layer_min_chars: default.ocr_text_layer_min_chars,
+                sync_notification_mode: default.sync_notification_mode,
+                default_source_id: default.default_source_id,
+                default_source_write_back: default.default_source_write_back,
+                extract_archives: default.extract_archives,
+                search_sort: default.search_sort,
                 webdav_enabled: default.webdav_enabled,
                 webdav_server_url: default.webdav_server_url,
                 webdav_username: default.webdav_username,
@@ -118,7 +247,7 @@ async fn get_settings(
     request_body = UpdateSettings,
     responses(
         (status = 200, description = "Settings updated successfully", body = SettingsResponse),
-        (status = 400, description = "Bad request - invalid settings data"),
+        (status = 400, description = "Bad request - invalid settings data, an OCR language that is not installed, or a default source the user doesn't own"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
     )
@@ -127,12 +256,22 @@ async fn update_settings(
     auth_user: AuthUser,
     State(state): State<Arc<AppState>>,
     Json(update_data): Json<UpdateSettings>,
-) -> Result<Json<SettingsResponse>, StatusCode> {
+) -> Result<Json<SettingsResponse>, SettingsError> {
+    let changes_language = update_data.ocr_language.is_some()
+        || update_data.preferred_languages.is_some()
+        || update_data.primary_language.is_some();
+    if changes_language {
+        if let Some(installed) = installed_ocr_languages() {
+            validate_ocr_languages(&update_data, &installed)?;
+        }
+    }
+    validate_default_source(&state, auth_user.user.id, &update_data).await?;
+
     let settings = state
         .db
         .create_or_update_settings(auth_user.user.id, &update_data)
         .await
-        .map_err(|_| StatusCode::BAD_REQUEST)?;
+        .map_err(|e| SettingsError::invalid_value("settings", &e.to_string(), "Settings must be valid"))?;
 
     Ok(Json(settings.into()))
 }
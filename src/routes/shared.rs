@@ -0,0 +1,149 @@
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{Json, Response},
+    routing::get,
+    Router,
+};
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+
+use crate::{
+    auth::ClientIp,
+    models::{Document, DocumentShare},
+    services::{document_share_service::build_share_link, file_service::FileService},
+    AppState,
+};
+
+/// What a share link reveals about the shared document
+#[derive(Serialize, ToSchema)]
+pub struct SharedDocumentResponse {
+    pub filename: String,
+    pub mime_type: String,
+    pub file_size: i64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When the link stops working
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+    pub allow_download: bool,
+    /// Link to the file; unset unless the share allows downloads
+    pub download_url: Option<String>,
+}
+
+/// Routes for share links. They take no authentication: the token is the credential.
+pub fn router() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/{token}", get(get_shared_document))
+        .route("/{token}/download", get(download_shared_document))
+}
+
+/// Get the metadata of a document shared through a link
+#[utoipa::path(
+    get,
+    path = "/api/shared/{token}",
+    tag = "shared",
+    params(
+        ("token" = String, Path, description = "Share token")
+    ),
+    responses(
+        (status = 200, description = "Shared document", body = SharedDocumentResponse),
+        (status = 404, description = "Unknown, expired or revoked share"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_shared_document(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    Path(token): Path<String>,
+) -> Result<Json<SharedDocumentResponse>, StatusCode> {
+    let (share, document) = find_share(&state, &token).await?;
+    record_access(&state, &share, &client_ip, false).await;
+
+    Ok(Json(SharedDocumentResponse {
+        filename: document.original_filename,
+        mime_type: document.mime_type,
+        file_size: document.file_size,
+        created_at: document.created_at,
+        expires_at: share.expires_at,
+        allow_download: share.allow_download,
+        download_url: share.allow_download.then(|| {
+            format!("{}/download", build_share_link(state.config.public_base_url.as_deref(), &token))
+        }),
+    }))
+}
+
+/// Download the file of a document shared through a link that allows downloads
+#[utoipa::path(
+    get,
+    path = "/api/shared/{token}/download",
+    tag = "shared",
+    params(
+        ("token" = String, Path, description = "Share token")
+    ),
+    responses(
+        (status = 200, description = "Document file", content_type = "application/octet-stream"),
+        (status = 403, description = "The share does not allow downloads"),
+        (status = 404, description = "Unknown, expired or revoked share"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn download_shared_document(
+    State(state): State<Arc<AppState>>,
+    ClientIp(client_ip): ClientIp,
+    Path(token): Path<String>,
+) -> Result<Response<Body>, StatusCode> {
+    let (share, document) = find_share(&state, &token).await?;
+    if !share.allow_download {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let file_service = FileService::new(state.config.upload_path.clone());
+    let file_data = file_service
+        .read_file(&document.file_path)
+        .await
+        .map_err(|e| {
+            error!("Failed to read shared document file {}: {}", document.id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    record_access(&state, &share, &client_ip, true).await;
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, document.mime_type)
+        .header("Content-Disposition", format!("attachment; filename=\"{}\"", document.original_filename))
+        .header("Content-Length", file_data.len().to_string())
+        .body(Body::from(file_data))
+        .map_err(|e| {
+            error!("Failed to build response: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+/// Unknown, expired and revoked tokens, and shares of trashed documents, all look the same
+async fn find_share(state: &AppState, token: &str) -> Result<(DocumentShare, Document), StatusCode> {
+    state
+        .db
+        .get_shared_document(token)
+        .await
+        .map_err(|e| {
+            error!("Database error looking up share: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn record_access(state: &AppState, share: &DocumentShare, client_ip: &str, downloaded: bool) {
+    info!(
+        "Share {} of document {} {} from {}",
+        share.id,
+        share.document_id,
+        if downloaded { "downloaded" } else { "opened" },
+        client_ip
+    );
+
+    if let Err(e) = state.db.record_document_share_access(share.id, client_ip, downloaded).await {
+        warn!("Failed to record access to share {}: {}", share.id, e);
+    }
+}
@@ -11,6 +11,8 @@ use crate::{
     auth::AuthUser,
     errors::source::SourceError,
     models::{CreateSource, SourceResponse, SourceWithStats, UpdateSource, SourceType},
+    ocr::{error::OcrError, health::OcrHealthChecker, language_routing::OcrLanguageRoutes},
+    services::{client_certificate::load_client_identity, source_secrets},
     AppState,
 };
 
@@ -87,14 +89,21 @@ pub async fn list_sources(
 pub async fn create_source(
     auth_user: AuthUser,
     State(state): State<Arc<AppState>>,
-    Json(source_data): Json<CreateSource>,
+    Json(mut source_data): Json<CreateSource>,
 ) -> Result<Json<SourceResponse>, SourceError> {
     // Validate source configuration based on type
     if let Err(validation_error) = validate_source_config(&source_data) {
         error!("Source validation failed: {}", validation_error);
-        error!("Invalid source data received: {:?}", source_data);
+        error!("Invalid source data received for {} source '{}'", source_data.source_type, source_data.name);
         return Err(SourceError::configuration_invalid(validation_error));
     }
+    if let Err(validation_error) = validate_language_routes_installed(&source_data.config).await {
+        return Err(SourceError::configuration_invalid(validation_error));
+    }
+    state
+        .source_secrets
+        .encrypt_config(&mut source_data.config)
+        .map_err(|e| SourceError::connection_failed(format!("Failed to encrypt source secrets: {}", e)))?;
 
     let source = state
         .db
@@ -210,9 +219,10 @@ pub async fn update_source(
     auth_user: AuthUser,
     Path(source_id): Path<Uuid>,
     State(state): State<Arc<AppState>>,
-    Json(update_data): Json<UpdateSource>,
+    Json(mut update_data): Json<UpdateSource>,
 ) -> Result<Json<SourceResponse>, StatusCode> {
-    info!("Updating source {} with data: {:?}", source_id, update_data);
+    // The config may hold a private key, so it isn't logged
+    info!("Updating source {} (name: {:?}, enabled: {:?})", source_id, update_data.name, update_data.enabled);
     
     // Check if source exists
     let existing = state
@@ -223,12 +233,24 @@ pub async fn update_source(
         .ok_or(StatusCode::NOT_FOUND)?;
 
     // Validate config if provided
-    if let Some(config) = &update_data.config {
-        if let Err(validation_error) = validate_config_for_type(&existing.source_type, config) {
+    if let Some(config) = &mut update_data.config {
+        // Responses leave out the client certificate, so it's kept unless the update replaces it
+        source_secrets::merge_stored_secrets(config, &existing.config);
+        let decrypted = state.source_secrets.decrypt_config(config).map_err(|e| {
+            error!("Failed to decrypt the stored secrets of source {}: {}", source_id, e);
+            StatusCode::BAD_REQUEST
+        })?;
+        if let Err(validation_error) = validate_config_for_type(&existing.source_type, &decrypted) {
             error!("Config validation failed for source {}: {}", source_id, validation_error);
-            error!("Invalid config received: {:?}", config);
             return Err(StatusCode::BAD_REQUEST);
         }
+        if validate_language_routes_installed(config).await.is_err() {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+        state.source_secrets.encrypt_config(config).map_err(|e| {
+            error!("Failed to encrypt secrets of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
     }
 
     let source = state
@@ -291,6 +313,34 @@ pub async fn delete_source(
     }
 }
 
+/// Rejects OCR language routes to a language without an installed language pack. Routes
+/// are also rejected when Tesseract can't list its languages, as they can't be checked.
+pub async fn validate_language_routes_installed(config: &serde_json::Value) -> Result<(), &'static str> {
+    let Ok(Some(routes)) = OcrLanguageRoutes::from_source_config(config) else {
+        return Ok(());
+    };
+    // `tesseract --list-langs` runs a process, which must not block the executor
+    let installed = tokio::task::spawn_blocking(|| OcrHealthChecker::new().get_available_languages())
+        .await
+        .unwrap_or(Err(OcrError::TesseractNotInstalled));
+    check_language_routes_installed(&routes, installed)
+}
+
+/// Checks routes against the languages Tesseract listed, or the error listing them failed with
+pub fn check_language_routes_installed(
+    routes: &OcrLanguageRoutes,
+    installed: Result<Vec<String>, OcrError>,
+) -> Result<(), &'static str> {
+    let installed = installed.map_err(|e| {
+        error!("Could not list installed OCR languages to check source language routes: {}", e);
+        "OCR language routes can't be checked because the installed OCR languages could not be listed"
+    })?;
+    routes.validate_installed(&installed).map_err(|e| {
+        error!("Invalid source OCR language routes: {}", e);
+        "OCR language routes use a language that is not installed"
+    })
+}
+
 /// Validate source configuration based on type
 fn validate_source_config(source: &CreateSource) -> Result<(), &'static str> {
     validate_config_for_type(&source.source_type, &source.config)
@@ -301,10 +351,48 @@ pub fn validate_config_for_type(
     source_type: &SourceType,
     config: &serde_json::Value,
 ) -> Result<(), &'static str> {
+    // Any source type may carry OCR overrides for the documents it ingests
+    if let Err(e) = crate::models::OcrOverrides::from_source_config(config) {
+        error!("Invalid source OCR overrides: {}", e);
+        return Err("Invalid OCR overrides configuration");
+    }
+    if let Err(e) = OcrLanguageRoutes::from_source_config(config) {
+        error!("Invalid source OCR language routes: {}", e);
+        return Err("Invalid OCR language routes");
+    }
+    if let Err(e) = crate::ingestion::filename_template::FilenameTemplate::from_source_config(config) {
+        error!("Invalid source filename template: {}", e);
+        return Err("Invalid filename template");
+    }
+    if let Err(e) = crate::scheduling::sync_budget::SourceConcurrency::from_source_config(config) {
+        error!("Invalid source concurrency settings: {}", e);
+        return Err("Invalid concurrency configuration");
+    }
+    if let Err(e) = crate::services::source_file_deletion_service::SourceDeletionBehavior::from_source_config(config) {
+        error!("Invalid source deletion behavior: {}", e);
+        return Err("Invalid deletion behavior");
+    }
+
     match source_type {
         SourceType::WebDAV => {
-            let _: crate::models::WebDAVSourceConfig =
+            let webdav: crate::models::WebDAVSourceConfig =
                 serde_json::from_value(config.clone()).map_err(|_| "Invalid WebDAV configuration")?;
+            let mut webdav_config = crate::services::webdav::WebDAVConfig::new(
+                webdav.server_url,
+                webdav.username,
+                webdav.password,
+                webdav.watch_folders,
+                webdav.file_extensions,
+            );
+            if let Err(e) = load_client_identity(&webdav.tls.client_certificate) {
+                error!("Invalid WebDAV client certificate: {}", e);
+                return Err("Invalid WebDAV client certificate");
+            }
+            webdav_config.tls = webdav.tls;
+            if let Err(e) = webdav_config.ca_certificates() {
+                error!("Invalid WebDAV CA certificate: {}", e);
+                return Err("Invalid WebDAV CA certificate");
+            }
             Ok(())
         }
         SourceType::LocalFolder => {
@@ -313,8 +401,22 @@ pub fn validate_config_for_type(
             Ok(())
         }
         SourceType::S3 => {
-            let _: crate::models::S3SourceConfig =
+            let s3: crate::models::S3SourceConfig =
                 serde_json::from_value(config.clone()).map_err(|_| "Invalid S3 configuration")?;
+            if let Err(e) = load_client_identity(&s3.client_certificate) {
+                error!("Invalid S3 client certificate: {}", e);
+                return Err("Invalid S3 client certificate");
+            }
+            Ok(())
+        }
+        SourceType::Smb => {
+            let _: crate::models::SmbSourceConfig =
+                serde_json::from_value(config.clone()).map_err(|_| "Invalid SMB configuration")?;
+            Ok(())
+        }
+        SourceType::Imap => {
+            let _: crate::models::ImapSourceConfig =
+                serde_json::from_value(config.clone()).map_err(|_| "Invalid IMAP configuration")?;
             Ok(())
         }
     }
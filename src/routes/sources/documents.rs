@@ -0,0 +1,99 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use tracing::error;
+
+use crate::{
+    auth::AuthUser,
+    models::SourceDocumentStats,
+    routes::documents::{list_documents_response, PaginatedDocumentsResponse, PaginationQuery},
+    AppState,
+};
+
+/// List the documents synced from a source
+///
+/// Takes the same paging, sorting, OCR status filter and field selection as the document
+/// list. Uploaded documents and documents of other sources are not included.
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/documents",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        PaginationQuery
+    ),
+    responses(
+        (status = 200, description = "Paginated list of the source's documents", body = PaginatedDocumentsResponse),
+        (status = 400, description = "Invalid cursor"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_source_documents(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<PaginationQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let _source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    list_documents_response(&state, &auth_user, &query, Some(source_id)).await
+}
+
+/// Get totals for the documents synced from a source
+///
+/// Number of documents, their total size and how many are in each OCR status, to audit
+/// what a source has ingested.
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/documents/stats",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Totals for the source's documents", body = SourceDocumentStats),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_source_document_stats(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceDocumentStats>, StatusCode> {
+    let _source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let stats = state
+        .db
+        .get_source_document_stats(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load document totals of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(stats))
+}
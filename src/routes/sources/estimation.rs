@@ -43,10 +43,14 @@ pub async fn estimate_crawl(
 
     match source.source_type {
         crate::models::SourceType::WebDAV => {
-            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(source.config)
+            let config = state
+                .source_secrets
+                .decrypt_config(&source.config)
+                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(config)
                 .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
 
-            estimate_webdav_crawl_internal(&config).await
+            estimate_webdav_crawl_internal(&state, &config).await
         }
         _ => Ok(Json(serde_json::json!({
             "error": "Source type not supported for estimation"
@@ -72,35 +76,33 @@ pub async fn estimate_crawl(
 )]
 pub async fn estimate_crawl_with_config(
     _auth_user: AuthUser,
-    State(_state): State<Arc<AppState>>,
+    State(state): State<Arc<AppState>>,
     Json(config_data): Json<serde_json::Value>,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Parse the WebDAV config from the request
     let config: crate::models::WebDAVSourceConfig = serde_json::from_value(config_data)
         .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-    estimate_webdav_crawl_internal(&config).await
+    estimate_webdav_crawl_internal(&state, &config).await
 }
 
 /// Internal helper function to estimate WebDAV crawl
 async fn estimate_webdav_crawl_internal(
+    state: &AppState,
     config: &crate::models::WebDAVSourceConfig,
 ) -> Result<Json<serde_json::Value>, StatusCode> {
     // Create WebDAV service config
     let webdav_config = crate::services::webdav::WebDAVConfig {
-        server_url: config.server_url.clone(),
-        username: config.username.clone(),
-        password: config.password.clone(),
-        watch_folders: config.watch_folders.clone(),
-        file_extensions: config.file_extensions.clone(),
         timeout_seconds: 300,
-        server_type: config.server_type.clone(),
+        ..config.clone().into()
     };
 
+    let model = crate::services::processing_time_model::ProcessingTimeModel::load_or_default(&state.db).await;
+
     // Create WebDAV service and estimate crawl
     match crate::services::webdav::WebDAVService::new(webdav_config) {
         Ok(webdav_service) => {
-            match webdav_service.estimate_crawl().await {
+            match webdav_service.estimate_crawl(&model).await {
                 Ok(estimate) => Ok(Json(serde_json::to_value(estimate).unwrap())),
                 Err(e) => Ok(Json(serde_json::json!({
                     "error": format!("Crawl estimation failed: {}", e),
@@ -0,0 +1,150 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use tracing::{error, info};
+
+use crate::{
+    auth::AuthUser,
+    models::{MissingDocumentAction, MissingSourceDocument, ResolveMissingDocumentsRequest, ResolveMissingDocumentsResponse},
+    routes::documents::delete_documents_with_files,
+    AppState,
+};
+
+/// List the documents of a source whose file is gone from the source
+///
+/// A sync flags a document once a directory it lists in full no longer contains the file.
+/// Documents in directories a sync did not list are not judged.
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/missing",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Documents missing on the source, longest missing first", body = Vec<MissingSourceDocument>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn list_missing_documents(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<MissingSourceDocument>>, StatusCode> {
+    let _source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let documents = state
+        .db
+        .get_missing_source_documents(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load missing documents of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(documents))
+}
+
+/// Move documents that are missing on their source to the trash, or detach them from it
+///
+/// Acts on the given `document_ids`, or on all of the source's missing documents when
+/// omitted. Requested documents that are not flagged as missing on this source are left
+/// alone and reported as failed.
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/missing/resolve",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    request_body = ResolveMissingDocumentsRequest,
+    responses(
+        (status = 200, description = "Documents deleted or detached", body = ResolveMissingDocumentsResponse),
+        (status = 400, description = "Empty or too many document ids"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn resolve_missing_documents(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<ResolveMissingDocumentsRequest>,
+) -> Result<Json<ResolveMissingDocumentsResponse>, StatusCode> {
+    if let Some(document_ids) = &request.document_ids {
+        if document_ids.is_empty() || document_ids.len() > 1000 {
+            return Err(StatusCode::BAD_REQUEST);
+        }
+    }
+
+    let _source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let missing_ids: Vec<Uuid> = state
+        .db
+        .get_missing_source_documents(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load missing documents of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .into_iter()
+        .map(|document| document.id)
+        .collect();
+
+    let (target_ids, mut failed_ids): (Vec<Uuid>, Vec<Uuid>) = match request.document_ids {
+        Some(document_ids) => document_ids.into_iter().partition(|id| missing_ids.contains(id)),
+        None => (missing_ids, Vec::new()),
+    };
+
+    let (resolved_ids, rejected_ids) = match request.action {
+        MissingDocumentAction::Delete => {
+            let deleted = delete_documents_with_files(&state, &auth_user, &target_ids).await?;
+            let rejected = target_ids.iter().filter(|id| !deleted.deleted_documents.contains(id)).copied().collect();
+            (deleted.deleted_documents, rejected)
+        }
+        MissingDocumentAction::Detach => state
+            .db
+            .bulk_assign_document_source(&target_ids, None, auth_user.user.id, auth_user.user.role)
+            .await
+            .map_err(|e| {
+                error!("Failed to detach missing documents of source {}: {}", source_id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?,
+    };
+    failed_ids.extend(rejected_ids);
+
+    info!(
+        "Resolved {} missing documents of source {} ({:?}, {} failed)",
+        resolved_ids.len(), source_id, request.action, failed_ids.len()
+    );
+
+    Ok(Json(ResolveMissingDocumentsResponse {
+        action: request.action,
+        resolved_count: resolved_ids.len() as i64,
+        resolved_documents: resolved_ids,
+        failed_documents: failed_ids,
+    }))
+}
@@ -6,12 +6,16 @@ pub mod crud;
 pub mod sync;
 pub mod validation;
 pub mod estimation;
+pub mod missing;
+pub mod documents;
 
 // Re-export commonly used functions and types for backward compatibility
 pub use crud::*;
 pub use sync::*;
 pub use validation::*;
 pub use estimation::*;
+pub use missing::*;
+pub use documents::*;
 
 pub fn router() -> Router<Arc<AppState>> {
     Router::new()
@@ -25,13 +29,22 @@ pub fn router() -> Router<Arc<AppState>> {
         // Sync operations
         .route("/{id}/sync", post(trigger_sync))
         .route("/{id}/sync/stop", post(stop_sync))
+        .route("/{id}/sync/abort", post(abort_sync))
         .route("/{id}/sync/progress/ws", get(sync_progress_websocket))
         .route("/{id}/sync/status", get(get_sync_status))
+        .route("/{id}/sync/history", get(get_sync_history))
         .route("/{id}/deep-scan", post(trigger_deep_scan))
+        .route("/{id}/cleanup", post(cleanup_source_directories))
+        .route("/{id}/missing", get(list_missing_documents))
+        .route("/{id}/missing/resolve", post(resolve_missing_documents))
+        .route("/{id}/documents", get(list_source_documents))
+        .route("/{id}/documents/stats", get(get_source_document_stats))
         
         // Validation operations
-        .route("/{id}/validate", post(validate_source))
+        .route("/{id}/validate", get(get_source_validation_report).post(validate_source))
         .route("/test", post(test_connection_with_config))
+        .route("/test-connection", post(test_connection_with_config))
+        .route("/{id}/test", post(test_connection))
         
         // Estimation operations
         .route("/{id}/estimate", get(estimate_crawl))
@@ -1,17 +1,17 @@
 use axum::{
-    extract::{Path, State, WebSocketUpgrade},
+    extract::{Path, Query, State, WebSocketUpgrade},
     extract::ws::{WebSocket, Message},
     http::{StatusCode, HeaderMap},
     response::{Json, Response},
 };
 use std::sync::Arc;
 use uuid::Uuid;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use std::time::Duration;
 
 use crate::{
     auth::AuthUser,
-    models::SourceStatus,
+    models::{OrphanedDirectoryCleanup, SourceCleanupQuery, SourceStatus, SourceSyncAbortQuery, SourceSyncAbortResult, SourceSyncMode, SourceSyncQuery, SourceSyncRun, SourceSyncStarted, SyncHistoryQuery},
     services::webdav::{SyncProgress, SyncPhase},
     AppState,
 };
@@ -19,6 +19,10 @@ use crate::{
 // Removed WebSocketAuthQuery - using secure header-based authentication instead
 
 /// Trigger a sync for a source
+///
+/// `mode=new-only` (WebDAV only) lists each watch folder one level deep and scans just the
+/// directories that are not tracked yet. It is the cheapest way to pick up a newly added
+/// folder, but misses changes inside known directories.
 #[utoipa::path(
     post,
     path = "/api/sources/{id}/sync",
@@ -27,10 +31,12 @@ use crate::{
         ("bearer_auth" = [])
     ),
     params(
-        ("id" = Uuid, Path, description = "Source ID")
+        ("id" = Uuid, Path, description = "Source ID"),
+        SourceSyncQuery
     ),
     responses(
-        (status = 200, description = "Sync triggered successfully"),
+        (status = 200, description = "Sync triggered successfully", body = SourceSyncStarted),
+        (status = 400, description = "Sync mode not supported for this source type"),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Source not found"),
         (status = 409, description = "Source is already syncing"),
@@ -41,8 +47,9 @@ use crate::{
 pub async fn trigger_sync(
     auth_user: AuthUser,
     Path(source_id): Path<Uuid>,
+    Query(query): Query<SourceSyncQuery>,
     State(state): State<Arc<AppState>>,
-) -> Result<StatusCode, StatusCode> {
+) -> Result<Json<SourceSyncStarted>, StatusCode> {
     let source = state
         .db
         .get_source(auth_user.user.id, source_id)
@@ -50,10 +57,14 @@ pub async fn trigger_sync(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    if query.mode == SourceSyncMode::NewOnly && source.source_type != crate::models::SourceType::WebDAV {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
     // Trigger sync using the universal source scheduler
     // The scheduler will handle all status checks and atomic operations
     if let Some(scheduler) = &state.source_scheduler {
-        match scheduler.trigger_sync(source_id).await {
+        match scheduler.trigger_sync_with_mode(source_id, query.mode).await {
             Ok(()) => {
                 // Sync started successfully
             }
@@ -71,6 +82,9 @@ pub async fn trigger_sync(
                 }
             }
         }
+    } else if query.mode == SourceSyncMode::NewOnly {
+        // The WebDAV scheduler only runs regular syncs
+        return Err(StatusCode::NOT_IMPLEMENTED);
     } else {
         // Fallback to WebDAV scheduler for backward compatibility
         match source.source_type {
@@ -105,7 +119,29 @@ pub async fn trigger_sync(
         }
     }
 
-    Ok(StatusCode::OK)
+    Ok(Json(sync_started(query.mode, &source.name)))
+}
+
+fn sync_started(mode: SourceSyncMode, source_name: &str) -> SourceSyncStarted {
+    match mode {
+        SourceSyncMode::Smart => SourceSyncStarted {
+            mode,
+            message: format!("Sync started for source '{}'", source_name),
+            limitations: Vec::new(),
+        },
+        SourceSyncMode::NewOnly => SourceSyncStarted {
+            mode,
+            message: format!(
+                "New-only sync started for source '{}': only directories directly under the watch folders that are not tracked yet are scanned",
+                source_name
+            ),
+            limitations: vec![
+                "New, changed or deleted files in directories that are already tracked are not picked up".to_string(),
+                "New folders inside directories that are already tracked are not picked up".to_string(),
+                "Run a regular sync or a deep scan to catch these".to_string(),
+            ],
+        },
+    }
 }
 
 /// Stop sync for a source
@@ -139,6 +175,15 @@ pub async fn stop_sync(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
+    stop_running_sync(&state, &source).await?;
+
+    Ok(StatusCode::OK)
+}
+
+/// Stops a source's sync and leaves the source idle. Returns whether a sync was running.
+async fn stop_running_sync(state: &Arc<AppState>, source: &crate::models::Source) -> Result<bool, StatusCode> {
+    let source_id = source.id;
+
     // Allow stopping sync regardless of current status to handle edge cases
     // where the database status might be out of sync with actual running tasks
 
@@ -159,11 +204,13 @@ pub async fn stop_sync(
                         Some("No sync was running")
                     )
                     .await;
+                return Ok(false);
             } else {
                 error!("Failed to stop sync for source {}: {}", source_id, e);
                 return Err(StatusCode::INTERNAL_SERVER_ERROR);
             }
         }
+        Ok(true)
     } else {
         // Update status directly if no scheduler available (fallback)
         state
@@ -175,9 +222,125 @@ pub async fn stop_sync(
             )
             .await
             .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        Ok(matches!(source.status, SourceStatus::Syncing))
     }
+}
 
-    Ok(StatusCode::OK)
+/// Abort a source's sync along with the OCR it queued
+///
+/// Stops the running sync and cancels every still-pending OCR job of the source's documents.
+/// With `delete_documents=true` the documents ingested since the latest sync run started are
+/// also moved to the trash.
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/sync/abort",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        SourceSyncAbortQuery
+    ),
+    responses(
+        (status = 200, description = "Sync aborted", body = SourceSyncAbortResult),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn abort_sync(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    Query(query): Query<SourceSyncAbortQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceSyncAbortResult>, StatusCode> {
+    let source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let sync_run = state
+        .db
+        .get_latest_source_sync_run(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load the latest sync run of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let sync_stopped = stop_running_sync(&state, &source).await?;
+
+    let ocr_jobs_cancelled = state
+        .db
+        .cancel_pending_source_ocr_jobs(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to cancel pending OCR jobs of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })? as i64;
+
+    let mut documents_deleted = 0;
+    if let (true, Some(run)) = (query.delete_documents, &sync_run) {
+        let document_ids = state
+            .db
+            .get_source_document_ids_created_since(source_id, run.started_at)
+            .await
+            .map_err(|e| {
+                error!("Failed to list documents of sync run {}: {}", run.id, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        documents_deleted = trash_documents(&state, &auth_user, &document_ids).await? as i64;
+    }
+
+    info!(
+        "Aborted sync of source {} by {}: stopped {}, {} OCR jobs cancelled, {} documents deleted",
+        source_id, auth_user.user.username, sync_stopped, ocr_jobs_cancelled, documents_deleted
+    );
+
+    Ok(Json(SourceSyncAbortResult {
+        sync_stopped,
+        sync_run_id: sync_run.map(|run| run.id),
+        ocr_jobs_cancelled,
+        documents_deleted,
+    }))
+}
+
+/// Moves documents and their files to the trash, returning how many were deleted
+async fn trash_documents(state: &Arc<AppState>, auth_user: &AuthUser, document_ids: &[Uuid]) -> Result<usize, StatusCode> {
+    let (deleted_ids, _) = state
+        .db
+        .bulk_delete_documents(document_ids, auth_user.user.id, auth_user.user.role)
+        .await
+        .map_err(|e| {
+            error!("Failed to delete documents of an aborted sync: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let file_service = crate::services::file_service::FileService::new(state.config.upload_path.clone());
+    for document_id in &deleted_ids {
+        let document = match state.db.get_deleted_document_by_id(*document_id, auth_user.user.id, auth_user.user.role).await {
+            Ok(Some(document)) => document,
+            Ok(None) => continue,
+            Err(e) => {
+                warn!("Failed to load deleted document {}: {}", document_id, e);
+                continue;
+            }
+        };
+        match file_service.move_to_trash(&document).await {
+            Ok(trash_path) if trash_path != document.file_path => {
+                if let Err(e) = state.db.update_document_file_path(document.id, &trash_path).await {
+                    error!("Failed to record trash path for document {}: {}", document.id, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to move files for document {} to trash: {}", document.id, e),
+        }
+    }
+
+    Ok(deleted_ids.len())
 }
 
 /// Trigger a deep scan for a source
@@ -225,7 +388,12 @@ pub async fn trigger_deep_scan(
     match source.source_type {
         crate::models::SourceType::WebDAV => {
             // Handle WebDAV deep scan
-            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(source.config)
+            let config = state.source_secrets.decrypt_config(&source.config)
+                .map_err(|e| {
+                    error!("Failed to decrypt the stored secrets of source {}: {}", source_id, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(config)
                 .map_err(|e| {
                     error!("Failed to parse WebDAV config for source {}: {}", source_id, e);
                     StatusCode::INTERNAL_SERVER_ERROR
@@ -233,13 +401,8 @@ pub async fn trigger_deep_scan(
 
             // Create WebDAV service
             let webdav_config = crate::services::webdav::WebDAVConfig {
-                server_url: config.server_url.clone(),
-                username: config.username.clone(),
-                password: config.password.clone(),
-                watch_folders: config.watch_folders.clone(),
-                file_extensions: config.file_extensions.clone(),
                 timeout_seconds: 600, // 10 minutes for deep scan
-                server_type: config.server_type.clone(),
+                ..config.clone().into()
             };
 
             let webdav_service = crate::services::webdav::WebDAVService::new(webdav_config.clone())
@@ -654,7 +817,7 @@ async fn handle_websocket(mut socket: WebSocket, source_id: Uuid, state: Arc<App
 
 /// Extract JWT token from WebSocket headers securely
 /// Uses Sec-WebSocket-Protocol header to avoid token exposure in logs/URLs
-fn extract_websocket_token(headers: &HeaderMap) -> Option<String> {
+pub(crate) fn extract_websocket_token(headers: &HeaderMap) -> Option<String> {
     // Check for token in Sec-WebSocket-Protocol header (most secure)
     if let Some(protocol_header) = headers.get("sec-websocket-protocol") {
         if let Ok(protocols) = protocol_header.to_str() {
@@ -718,4 +881,124 @@ pub async fn get_sync_status(
     let progress_info = state.sync_progress_tracker.get_progress(source_id);
     
     Ok(Json(progress_info))
-}
\ No newline at end of file
+}
+/// Get recent sync runs for a source, newest first
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/sync/history",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        SyncHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Recent sync runs", body = Vec<SourceSyncRun>),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_sync_history(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    Query(query): Query<SyncHistoryQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<Vec<SourceSyncRun>>, StatusCode> {
+    let _source = state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let runs = state
+        .db
+        .get_source_sync_runs(source_id, limit)
+        .await
+        .map_err(|e| {
+            error!("Failed to load sync history for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    Ok(Json(runs))
+}
+
+/// Remove tracked WebDAV directories of a source that no longer exist on the server (admin only).
+/// Dry run unless `apply=true`.
+#[utoipa::path(
+    post,
+    path = "/api/sources/{id}/cleanup",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID"),
+        SourceCleanupQuery
+    ),
+    responses(
+        (status = 200, description = "Orphaned directories found, and removed if applied", body = OrphanedDirectoryCleanup),
+        (status = 400, description = "Source is not a WebDAV source"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Source not found"),
+        (status = 409, description = "Source is syncing"),
+        (status = 502, description = "WebDAV server could not be checked"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn cleanup_source_directories(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    Query(query): Query<SourceCleanupQuery>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<OrphanedDirectoryCleanup>, StatusCode> {
+    crate::routes::queue::require_admin(&auth_user)?;
+
+    let source = state
+        .db
+        .get_source_by_id(source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    if source.source_type != crate::models::SourceType::WebDAV {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    // A running sync rewrites the directory tracking this cleanup compares against
+    if matches!(source.status, SourceStatus::Syncing) {
+        return Err(StatusCode::CONFLICT);
+    }
+
+    let config = state.source_secrets.decrypt_config(&source.config)
+        .map_err(|e| {
+            error!("Failed to decrypt the stored secrets of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let config: crate::models::WebDAVSourceConfig = serde_json::from_value(config)
+        .map_err(|e| {
+            error!("Failed to parse WebDAV config for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    let webdav_service = crate::services::webdav::WebDAVService::new(config.into())
+        .map_err(|e| {
+            error!("Failed to create WebDAV service for cleanup of source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+    webdav_service.ensure_server_type().await;
+
+    info!("Orphaned directory cleanup for source {} by {} (apply: {})", source_id, auth_user.user.username, query.apply);
+    let cleanup = webdav_service
+        .cleanup_orphaned_directories(source.user_id, source_id, &state, &query)
+        .await
+        .map_err(|e| {
+            error!("Orphaned directory cleanup failed for source {}: {}", source_id, e);
+            StatusCode::BAD_GATEWAY
+        })?;
+
+    Ok(Json(cleanup))
+}
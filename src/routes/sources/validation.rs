@@ -11,7 +11,8 @@ use utoipa::ToSchema;
 
 use crate::{
     auth::AuthUser,
-    models::SourceType,
+    models::{SourceConnectionTestResult, SourceType, SourceValidationReport},
+    services::source_connection_test::test_source_connection,
     AppState,
 };
 
@@ -33,7 +34,7 @@ pub struct TestConnectionRequest {
         ("id" = Uuid, Path, description = "Source ID")
     ),
     responses(
-        (status = 200, description = "Connection test result", body = serde_json::Value),
+        (status = 200, description = "Connection test result", body = SourceConnectionTestResult),
         (status = 401, description = "Unauthorized"),
         (status = 404, description = "Source not found"),
         (status = 500, description = "Internal server error")
@@ -43,7 +44,7 @@ pub async fn test_connection(
     auth_user: AuthUser,
     Path(source_id): Path<Uuid>,
     State(state): State<Arc<AppState>>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
+) -> Result<Json<SourceConnectionTestResult>, StatusCode> {
     let source = state
         .db
         .get_source(auth_user.user.id, source_id)
@@ -51,82 +52,23 @@ pub async fn test_connection(
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
         .ok_or(StatusCode::NOT_FOUND)?;
 
-    match source.source_type {
-        SourceType::WebDAV => {
-            // Test WebDAV connection
-            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(source.config)
-                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-            let test_config = crate::models::WebDAVTestConnection {
-                server_url: config.server_url,
-                username: config.username,
-                password: config.password,
-                server_type: config.server_type,
-            };
-            
-            match crate::services::webdav::test_webdav_connection(&test_config).await {
-                Ok(result) => Ok(Json(serde_json::json!({
-                    "success": result.success,
-                    "message": result.message
-                }))),
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("Connection failed: {}", e)
-                }))),
-            }
-        }
-        SourceType::LocalFolder => {
-            // Test Local Folder access
-            let config: crate::models::LocalFolderSourceConfig = serde_json::from_value(source.config)
-                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
-
-            match crate::services::local_folder_service::LocalFolderService::new(config) {
-                Ok(service) => {
-                    match service.test_connection().await {
-                        Ok(message) => Ok(Json(serde_json::json!({
-                            "success": true,
-                            "message": message
-                        }))),
-                        Err(e) => Ok(Json(serde_json::json!({
-                            "success": false,
-                            "message": format!("Local folder test failed: {}", e)
-                        }))),
-                    }
-                }
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("Local folder configuration error: {}", e)
-                }))),
-            }
-        }
-        SourceType::S3 => {
-            // Test S3 connection
-            let config: crate::models::S3SourceConfig = serde_json::from_value(source.config)
-                .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let config = state.source_secrets.decrypt_config(&source.config).map_err(|e| {
+        error!("Failed to decrypt the stored secrets of source {}: {}", source_id, e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+    let result = test_source_connection(source.source_type, config)
+        .await
+        .map_err(|e| {
+            error!("Stored config of source {} is invalid: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
 
-            match crate::services::s3_service::S3Service::new(config).await {
-                Ok(service) => {
-                    match service.test_connection().await {
-                        Ok(message) => Ok(Json(serde_json::json!({
-                            "success": true,
-                            "message": message
-                        }))),
-                        Err(e) => Ok(Json(serde_json::json!({
-                            "success": false,
-                            "message": format!("S3 test failed: {}", e)
-                        }))),
-                    }
-                }
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("S3 configuration error: {}", e)
-                }))),
-            }
-        }
-    }
+    Ok(Json(result))
 }
 
 /// Test connection with a configuration (before creating source)
+///
+/// Also served at `/api/sources/test`.
 #[utoipa::path(
     post,
     path = "/api/sources/test-connection",
@@ -136,7 +78,7 @@ pub async fn test_connection(
     ),
     request_body = TestConnectionRequest,
     responses(
-        (status = 200, description = "Connection test result", body = serde_json::Value),
+        (status = 200, description = "Connection test result", body = SourceConnectionTestResult),
         (status = 400, description = "Bad request - invalid configuration"),
         (status = 401, description = "Unauthorized"),
         (status = 500, description = "Internal server error")
@@ -146,80 +88,12 @@ pub async fn test_connection_with_config(
     _auth_user: AuthUser,
     State(_state): State<Arc<AppState>>,
     Json(request): Json<TestConnectionRequest>,
-) -> Result<Json<serde_json::Value>, StatusCode> {
-    match request.source_type {
-        SourceType::WebDAV => {
-            // Test WebDAV connection
-            let config: crate::models::WebDAVSourceConfig = serde_json::from_value(request.config)
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-            let test_config = crate::models::WebDAVTestConnection {
-                server_url: config.server_url,
-                username: config.username,
-                password: config.password,
-                server_type: config.server_type,
-            };
-            
-            match crate::services::webdav::test_webdav_connection(&test_config).await {
-                Ok(result) => Ok(Json(serde_json::json!({
-                    "success": result.success,
-                    "message": result.message
-                }))),
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("WebDAV connection failed: {}", e)
-                }))),
-            }
-        }
-        SourceType::LocalFolder => {
-            // Test Local Folder access
-            let config: crate::models::LocalFolderSourceConfig = serde_json::from_value(request.config)
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
-
-            match crate::services::local_folder_service::LocalFolderService::new(config) {
-                Ok(service) => {
-                    match service.test_connection().await {
-                        Ok(message) => Ok(Json(serde_json::json!({
-                            "success": true,
-                            "message": message
-                        }))),
-                        Err(e) => Ok(Json(serde_json::json!({
-                            "success": false,
-                            "message": format!("Local folder test failed: {}", e)
-                        }))),
-                    }
-                }
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("Local folder configuration error: {}", e)
-                }))),
-            }
-        }
-        SourceType::S3 => {
-            // Test S3 connection
-            let config: crate::models::S3SourceConfig = serde_json::from_value(request.config)
-                .map_err(|_| StatusCode::BAD_REQUEST)?;
+) -> Result<Json<SourceConnectionTestResult>, StatusCode> {
+    let result = test_source_connection(request.source_type, request.config)
+        .await
+        .map_err(|_| StatusCode::BAD_REQUEST)?;
 
-            match crate::services::s3_service::S3Service::new(config).await {
-                Ok(service) => {
-                    match service.test_connection().await {
-                        Ok(message) => Ok(Json(serde_json::json!({
-                            "success": true,
-                            "message": message
-                        }))),
-                        Err(e) => Ok(Json(serde_json::json!({
-                            "success": false,
-                            "message": format!("S3 test failed: {}", e)
-                        }))),
-                    }
-                }
-                Err(e) => Ok(Json(serde_json::json!({
-                    "success": false,
-                    "message": format!("S3 configuration error: {}", e)
-                }))),
-            }
-        }
-    }
+    Ok(Json(result))
 }
 
 /// Validate source health and configuration
@@ -261,10 +135,58 @@ pub async fn validate_source(
         if let Err(e) = crate::scheduling::source_scheduler::SourceScheduler::validate_source_health(&source_clone, &state_clone).await {
             error!("Manual validation check failed for source {}: {}", source_clone.name, e);
         }
+        if source_clone.source_type == SourceType::WebDAV {
+            if let Err(e) = crate::scheduling::source_scheduler::SourceScheduler::validate_etag_tracking(&source_clone, &state_clone).await {
+                error!("Manual ETag tracking validation failed for source {}: {}", source_clone.name, e);
+            }
+        }
     });
 
     Ok(Json(serde_json::json!({
         "success": true,
         "message": format!("Validation check started for source '{}'", source.name)
     })))
-}
\ No newline at end of file
+}
+
+/// Get the latest ETag tracking validation of a WebDAV source
+#[utoipa::path(
+    get,
+    path = "/api/sources/{id}/validate",
+    tag = "sources",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "Source ID")
+    ),
+    responses(
+        (status = 200, description = "Latest stored validation report", body = SourceValidationReport),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Source not found or not validated yet"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+pub async fn get_source_validation_report(
+    auth_user: AuthUser,
+    Path(source_id): Path<Uuid>,
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<SourceValidationReport>, StatusCode> {
+    state
+        .db
+        .get_source(auth_user.user.id, source_id)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    let report = state
+        .db
+        .get_source_validation_report(source_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to load validation report for source {}: {}", source_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?
+        .ok_or(StatusCode::NOT_FOUND)?;
+
+    Ok(Json(report))
+}
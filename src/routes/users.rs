@@ -11,9 +11,9 @@ use uuid::Uuid;
 use utoipa::ToSchema;
 
 use crate::{
-    auth::AuthUser,
+    auth::{create_impersonation_jwt, AuthUser},
     errors::user::UserError,
-    models::{CreateUser, UpdateUser, UserResponse, UserRole},
+    models::{CreateUser, ImpersonationResponse, UpdateUser, UserResponse, UserRole},
     AppState,
 };
 
@@ -60,6 +60,7 @@ pub fn router() -> Router<Arc<AppState>> {
         .route("/", get(list_users).post(create_user))
         .route("/{id}", get(get_user).put(update_user).delete(delete_user))
         .route("/{id}/watch-directory", get(get_user_watch_directory).post(create_user_watch_directory).delete(delete_user_watch_directory))
+        .route("/{id}/impersonate", post(impersonate_user))
 }
 
 #[utoipa::path(
@@ -474,4 +475,69 @@ async fn delete_user_watch_directory(
             Ok(Json(response))
         }
     }
-}
\ No newline at end of file
+}
+
+/// Act as another user for support
+///
+/// Issues a short-lived token that authenticates as the target user and names the admin
+/// in its `impersonated_by` claim. Every request made with it is logged under both ids.
+/// Unless `IMPERSONATION_READ_ONLY` is turned off, requests that change data are
+/// rejected. End the impersonation with `POST /api/auth/impersonate/stop`.
+#[utoipa::path(
+    post,
+    path = "/api/users/{id}/impersonate",
+    tag = "users",
+    security(
+        ("bearer_auth" = [])
+    ),
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Impersonation token", body = ImpersonationResponse),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "Forbidden - Admin access required, or the target is an admin or yourself"),
+        (status = 404, description = "User not found"),
+        (status = 500, description = "Internal server error")
+    )
+)]
+async fn impersonate_user(
+    auth_user: AuthUser,
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ImpersonationResponse>, UserError> {
+    require_admin(&auth_user)?;
+    if auth_user.user.id == id {
+        return Err(UserError::permission_denied("Cannot impersonate yourself"));
+    }
+
+    let user = state
+        .db
+        .get_user_by_id(id)
+        .await
+        .map_err(|e| UserError::internal_server_error(format!("Failed to fetch user: {}", e)))?
+        .ok_or_else(|| UserError::not_found_by_id(id))?;
+    if user.role == UserRole::Admin {
+        return Err(UserError::permission_denied("Admins cannot be impersonated"));
+    }
+
+    let (token, expires_at) = create_impersonation_jwt(&user, auth_user.user.id, &state.config.jwt_secret)
+        .map_err(|e| UserError::internal_server_error(format!("Failed to create token: {}", e)))?;
+
+    tracing::warn!(
+        admin_id = %auth_user.user.id,
+        user_id = %user.id,
+        "Admin {} started impersonating user {} until {}",
+        auth_user.user.username,
+        user.username,
+        expires_at
+    );
+
+    Ok(Json(ImpersonationResponse {
+        token,
+        user: user.into(),
+        impersonated_by: auth_user.user.id,
+        expires_at,
+        read_only: state.config.impersonation_read_only,
+    }))
+}
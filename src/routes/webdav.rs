@@ -17,6 +17,7 @@ use crate::{
     },
     AppState,
 };
+use crate::services::processing_time_model::ProcessingTimeModel;
 use crate::services::webdav::WebDAVConfig;
 use crate::services::webdav::WebDAVService;
 
@@ -66,6 +67,7 @@ async fn get_user_webdav_config(state: &Arc<AppState>, user_id: uuid::Uuid) -> R
         file_extensions: settings.webdav_file_extensions,
         timeout_seconds: 300, // 5 minutes timeout for crawl estimation
         server_type: Some("nextcloud".to_string()), // Default to Nextcloud
+        tls: Default::default(),
     })
 }
 
@@ -101,6 +103,7 @@ async fn test_webdav_connection(
         file_extensions: Vec::new(),
         timeout_seconds: 300, // 5 minutes timeout for crawl estimation
         server_type: test_config.server_type.clone(),
+        tls: test_config.tls.clone(),
     };
 
     // Create WebDAV service and test connection
@@ -118,6 +121,11 @@ async fn test_webdav_connection(
                         message: format!("Connection test failed: {}", e),
                         server_version: None,
                         server_type: None,
+                        detected_server_type: None,
+                        reachable: false,
+                        authenticated: false,
+                        certificate_error: false,
+                        client_certificate_error: false,
                     }))
                 }
             }
@@ -129,6 +137,11 @@ async fn test_webdav_connection(
                 message: format!("Service creation failed: {}", e),
                 server_version: None,
                 server_type: None,
+                detected_server_type: None,
+                reachable: false,
+                authenticated: false,
+                certificate_error: false,
+                client_certificate_error: false,
             }))
         }
     }
@@ -175,14 +188,17 @@ async fn estimate_webdav_crawl(
                 total_supported_files: 0,
                 total_estimated_time_hours: 0.0,
                 total_size_mb: 0.0,
+                processing_model: Default::default(),
             }));
         }
     };
 
+    let model = ProcessingTimeModel::load_or_default(&state.db).await;
+
     // Create WebDAV service and estimate crawl
     match WebDAVService::new(webdav_config) {
         Ok(webdav_service) => {
-            match webdav_service.estimate_crawl().await {
+            match webdav_service.estimate_crawl(&model).await {
                 Ok(estimate) => {
                     info!("Crawl estimation completed: {} total files, {} supported files", 
                         estimate.total_files, estimate.total_supported_files);
@@ -196,6 +212,7 @@ async fn estimate_webdav_crawl(
                         total_supported_files: 0,
                         total_estimated_time_hours: 0.0,
                         total_size_mb: 0.0,
+                        processing_model: Default::default(),
                     }))
                 }
             }
@@ -208,6 +225,7 @@ async fn estimate_webdav_crawl(
                 total_supported_files: 0,
                 total_estimated_time_hours: 0.0,
                 total_size_mb: 0.0,
+                processing_model: Default::default(),
             }))
         }
     }
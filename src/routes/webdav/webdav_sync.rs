@@ -7,12 +7,101 @@ use futures::stream::{FuturesUnordered, StreamExt};
 
 use crate::{
     AppState,
-    models::{CreateWebDAVFile, UpdateWebDAVSyncState},
+    models::{CreateWebDAVFile, Document, FileIngestionInfo, UpdateWebDAVSyncState},
     services::file_service::FileService,
-    ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
+    ingestion::document_ingestion::{DocumentIngestionService, IngestionResult, PreparedIngestion},
     services::webdav::{WebDAVConfig, WebDAVService, SmartSyncService, SyncProgress, SyncPhase},
 };
 
+/// Number of new documents accumulated before they are inserted in a single round trip
+const INSERT_BATCH_SIZE: usize = 100;
+
+/// Result of processing a single synced file
+enum FileOutcome {
+    /// File is unchanged since the last sync
+    Unchanged,
+    /// File was fully handled without creating a new document (e.g. duplicate content)
+    Completed,
+    /// File was stored and its document is waiting for the next batch insert
+    Pending(PendingSyncDocument),
+}
+
+struct PendingSyncDocument {
+    document: Document,
+    file_info: FileIngestionInfo,
+}
+
+/// Accumulates new documents during a sync and inserts them in bulk
+struct SyncInsertBatch {
+    pending: Vec<PendingSyncDocument>,
+}
+
+impl SyncInsertBatch {
+    fn new() -> Self {
+        Self { pending: Vec::with_capacity(INSERT_BATCH_SIZE) }
+    }
+
+    fn push(&mut self, document: PendingSyncDocument) {
+        self.pending.push(document);
+    }
+
+    fn is_full(&self) -> bool {
+        self.pending.len() >= INSERT_BATCH_SIZE
+    }
+
+    /// Inserts all pending documents, then records tracking rows and enqueues OCR for the ones
+    /// that were actually inserted. Returns the number of files completed and any errors.
+    async fn flush(
+        &mut self,
+        state: &Arc<AppState>,
+        user_id: uuid::Uuid,
+        enable_background_ocr: bool,
+    ) -> (usize, Vec<String>) {
+        if self.pending.is_empty() {
+            return (0, Vec::new());
+        }
+
+        let pending = std::mem::take(&mut self.pending);
+        let documents = pending.iter().map(|p| p.document.clone()).collect();
+
+        let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+        let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service);
+
+        let results = match ingestion_service.ingest_prepared_batch(documents).await {
+            Ok(results) => results,
+            Err(e) => {
+                let errors = pending.iter()
+                    .map(|p| format!("Batch insert failed for {}: {}", p.file_info.name, e))
+                    .collect();
+                return (0, errors);
+            }
+        };
+
+        let mut completed = 0;
+        let mut errors = Vec::new();
+        for (entry, result) in pending.into_iter().zip(results) {
+            match result {
+                // Only documents that were actually inserted get queued for OCR
+                Ok(IngestionResult::Created(doc)) => {
+                    finalize_synced_file(state, user_id, &entry.file_info, &doc, enable_background_ocr, true, "synced").await;
+                    completed += 1;
+                }
+                Ok(IngestionResult::ExistingDocument(doc)) => {
+                    finalize_synced_file(state, user_id, &entry.file_info, &doc, enable_background_ocr, false, "duplicate_content").await;
+                    completed += 1;
+                }
+                Ok(other) => {
+                    errors.push(format!("Unexpected batch ingestion result for {}: {:?}", entry.file_info.name, other));
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        debug!("Flushed sync batch: {} files completed, {} errors", completed, errors.len());
+        (completed, errors)
+    }
+}
+
 pub async fn perform_webdav_sync_with_tracking(
     state: Arc<AppState>,
     user_id: uuid::Uuid,
@@ -163,6 +252,7 @@ async fn perform_sync_internal(
                 let concurrent_limit = 5; // Max 5 concurrent downloads
                 let semaphore = Arc::new(Semaphore::new(concurrent_limit));
                 let mut folder_files_processed = 0;
+                let mut batch = SyncInsertBatch::new();
                 
                 // Create futures for processing each file concurrently
                 let mut file_futures = FuturesUnordered::new();
@@ -203,12 +293,14 @@ async fn perform_sync_internal(
                     }
                     
                     match result {
-                        Ok(processed) => {
-                            if processed {
-                                folder_files_processed += 1;
-                                progress.add_files_processed(1, 0); // We don't track bytes here yet
-                                debug!("Successfully processed file ({} completed in this folder)", folder_files_processed);
-                            }
+                        Ok(FileOutcome::Unchanged) => {}
+                        Ok(FileOutcome::Completed) => {
+                            folder_files_processed += 1;
+                            progress.add_files_processed(1, 0); // We don't track bytes here yet
+                            debug!("Successfully processed file ({} completed in this folder)", folder_files_processed);
+                        }
+                        Ok(FileOutcome::Pending(pending)) => {
+                            batch.push(pending);
                         }
                         Err(error) => {
                             error!("File processing error: {}", error);
@@ -217,6 +309,17 @@ async fn perform_sync_internal(
                         }
                     }
                     
+                    if batch.is_full() {
+                        let (flushed, errors) = batch.flush(&state, user_id, enable_background_ocr).await;
+                        folder_files_processed += flushed;
+                        progress.add_files_processed(flushed, 0);
+                        for error in errors {
+                            error!("File processing error: {}", error);
+                            progress.add_error(&error);
+                            sync_errors.push(error);
+                        }
+                    }
+                    
                     // Update progress periodically
                     let progress_update = UpdateWebDAVSyncState {
                         last_sync_at: Some(Utc::now()),
@@ -233,6 +336,16 @@ async fn perform_sync_internal(
                     }
                 }
                 
+                // Insert whatever is left, including files stored before a cancellation
+                let (flushed, errors) = batch.flush(&state, user_id, enable_background_ocr).await;
+                folder_files_processed += flushed;
+                progress.add_files_processed(flushed, 0);
+                for error in errors {
+                    error!("File processing error: {}", error);
+                    progress.add_error(&error);
+                    sync_errors.push(error);
+                }
+                
                 total_files_processed += folder_files_processed;
             }
             Ok(None) => {
@@ -271,7 +384,7 @@ async fn process_single_file(
     enable_background_ocr: bool,
     semaphore: Arc<Semaphore>,
     webdav_source_id: Option<uuid::Uuid>,
-) -> Result<bool, String> {
+) -> Result<FileOutcome, String> {
     // Acquire semaphore permit to limit concurrent downloads
     let _permit = semaphore.acquire().await.map_err(|e| format!("Semaphore error: {}", e))?;
     
@@ -295,7 +408,7 @@ async fn process_single_file(
             // Check if file has changed (compare ETags)
             if existing_file.etag == file_info.etag {
                 debug!("Skipping unchanged WebDAV file: {} (ETag: {})", file_info.path, file_info.etag);
-                return Ok(false); // Not processed (no change)
+                return Ok(FileOutcome::Unchanged);
             }
             debug!("WebDAV file has changed: {} (old ETag: {}, new ETag: {})", 
                 file_info.path, existing_file.etag, file_info.etag);
@@ -308,19 +421,30 @@ async fn process_single_file(
         }
     }
     
-    // Download the file
+    // Download the file, within the instance-wide download limit
+    let download_permit = state.download_limiter.acquire().await;
     let file_data = webdav_service.download_file(&file_info.path).await
         .map_err(|e| format!("Failed to download {}: {}", file_info.path, e))?;
+    drop(download_permit);
     
     debug!("Downloaded file: {} ({} bytes)", file_info.name, file_data.len());
     
+    // Extract EXIF/PDF properties alongside the WebDAV metadata
+    let mut file_info = file_info.clone();
+    crate::metadata_extraction::enrich_file_info(
+        &mut file_info,
+        &file_data,
+        crate::metadata_extraction::MetadataExtractionOptions::from_config(&state.config),
+    ).await;
+    
     // Use the unified ingestion service for consistent deduplication
-    let file_service = FileService::new(state.config.upload_path.clone());
-    let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service);
+    let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+    let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service)
+        .with_version_retention(state.config.document_version_retention);
     
     let result = if let Some(source_id) = webdav_source_id {
         ingestion_service
-            .ingest_from_file_info(
+            .prepare_from_file_info(
                 &file_info,
                 file_data,
                 user_id,
@@ -332,7 +456,7 @@ async fn process_single_file(
     } else {
         // Fallback for backward compatibility - treat as generic WebDAV sync
         ingestion_service
-            .ingest_from_file_info(
+            .prepare_from_file_info(
                 &file_info,
                 file_data,
                 user_id,
@@ -346,15 +470,27 @@ async fn process_single_file(
     let result = result.map_err(|e| format!("Document ingestion failed for {}: {}", file_info.name, e))?;
 
     let (document, should_queue_ocr, webdav_sync_status) = match result {
-        IngestionResult::Created(doc) => {
+        PreparedIngestion::Pending(doc) => {
+            // New documents are inserted in bulk by the caller
+            debug!("Prepared new document for {}: {}", file_info.name, doc.id);
+            return Ok(FileOutcome::Pending(PendingSyncDocument {
+                document: doc,
+                file_info: file_info.clone(),
+            }));
+        }
+        PreparedIngestion::Resolved(IngestionResult::Created(doc)) => {
             debug!("Created new document for {}: {}", file_info.name, doc.id);
             (doc, true, "synced") // New document - queue for OCR
         }
-        IngestionResult::ExistingDocument(doc) => {
+        PreparedIngestion::Resolved(IngestionResult::NewVersion(doc)) => {
+            info!("Stored new version of {} as document {}", file_info.name, doc.id);
+            (doc, true, "synced") // Changed content - queue the new version for OCR
+        }
+        PreparedIngestion::Resolved(IngestionResult::ExistingDocument(doc)) => {
             debug!("Found existing document for {}: {}", file_info.name, doc.id);
             (doc, false, "duplicate_content") // Existing document - don't re-queue OCR
         }
-        IngestionResult::TrackedAsDuplicate { existing_document_id } => {
+        PreparedIngestion::Resolved(IngestionResult::TrackedAsDuplicate { existing_document_id }) => {
             debug!("Tracked {} as duplicate of existing document: {}", file_info.name, existing_document_id);
             
             // For duplicates, we still need to get the document info for WebDAV tracking
@@ -364,7 +500,7 @@ async fn process_single_file(
             
             (existing_doc, false, "duplicate_content") // Track as duplicate
         }
-        IngestionResult::Skipped { existing_document_id, reason: _ } => {
+        PreparedIngestion::Resolved(IngestionResult::Skipped { existing_document_id, reason: _ }) => {
             debug!("Skipped duplicate file {}: existing document {}", file_info.name, existing_document_id);
             
             // For skipped files, we still need to get the document info for WebDAV tracking
@@ -376,6 +512,21 @@ async fn process_single_file(
         }
     };
 
+    finalize_synced_file(&state, user_id, &file_info, &document, enable_background_ocr, should_queue_ocr, webdav_sync_status).await;
+    
+    Ok(FileOutcome::Completed)
+}
+
+/// Records the WebDAV tracking row for a synced file and queues OCR for new documents
+async fn finalize_synced_file(
+    state: &Arc<AppState>,
+    user_id: uuid::Uuid,
+    file_info: &FileIngestionInfo,
+    document: &Document,
+    enable_background_ocr: bool,
+    should_queue_ocr: bool,
+    webdav_sync_status: &str,
+) {
     // Record WebDAV file in tracking table
     let webdav_file = CreateWebDAVFile {
         user_id,
@@ -412,8 +563,6 @@ async fn process_single_file(
     } else {
         debug!("Background OCR is disabled or document already processed, skipping OCR queue for document {}", document.id);
     }
-    
-    Ok(true) // Successfully processed
 }
 
 /// Process files for deep scan - similar to regular sync but forces processing
@@ -431,6 +580,7 @@ pub async fn process_files_for_deep_scan(
     let semaphore = Arc::new(Semaphore::new(concurrent_limit));
     let mut files_processed = 0;
     let mut sync_errors = Vec::new();
+    let mut batch = SyncInsertBatch::new();
     
     // Create futures for processing each file concurrently
     let mut file_futures = FuturesUnordered::new();
@@ -460,19 +610,31 @@ pub async fn process_files_for_deep_scan(
     // Process files concurrently and collect results
     while let Some(result) = file_futures.next().await {
         match result {
-            Ok(processed) => {
-                if processed {
-                    files_processed += 1;
-                    debug!("Deep scan: Successfully processed file ({} completed)", files_processed);
-                }
+            Ok(FileOutcome::Unchanged) => {}
+            Ok(FileOutcome::Completed) => {
+                files_processed += 1;
+                debug!("Deep scan: Successfully processed file ({} completed)", files_processed);
+            }
+            Ok(FileOutcome::Pending(pending)) => {
+                batch.push(pending);
             }
             Err(error) => {
                 error!("Deep scan file processing error: {}", error);
                 sync_errors.push(error);
             }
         }
+        
+        if batch.is_full() {
+            let (flushed, errors) = batch.flush(&state, user_id, enable_background_ocr).await;
+            files_processed += flushed;
+            sync_errors.extend(errors);
+        }
     }
     
+    let (flushed, errors) = batch.flush(&state, user_id, enable_background_ocr).await;
+    files_processed += flushed;
+    sync_errors.extend(errors);
+    
     if !sync_errors.is_empty() {
         warn!("Deep scan completed with {} errors: {:?}", sync_errors.len(), sync_errors);
     }
@@ -0,0 +1,77 @@
+//! Instance-wide limit on concurrent source downloads.
+//!
+//! Each sync already limits its own transfers, but several sources syncing at once
+//! still add up. Every download from a source also takes a permit here, so the number
+//! of open transfers stays bounded however many syncs run.
+
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Default number of downloads from sources running at once
+pub const DEFAULT_DOWNLOAD_MAX_CONCURRENCY: usize = 8;
+
+/// Concurrent downloads shared by all sources
+#[derive(Debug)]
+pub struct DownloadLimiter {
+    semaphore: Arc<Semaphore>,
+    capacity: usize,
+}
+
+impl DownloadLimiter {
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self { semaphore: Arc::new(Semaphore::new(capacity)), capacity }
+    }
+
+    /// Waits for room for another download; it counts against the limit until the permit is dropped
+    pub async fn acquire(&self) -> DownloadPermit {
+        let permit = self.semaphore.clone().acquire_owned().await.expect("download semaphore is never closed");
+        DownloadPermit { _permit: permit }
+    }
+
+    /// Downloads running right now
+    pub fn in_use(&self) -> usize {
+        self.capacity - self.semaphore.available_permits()
+    }
+
+    /// Downloads allowed at once
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+}
+
+/// One running download; returned to the limiter when dropped
+#[derive(Debug)]
+pub struct DownloadPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_downloads_wait_for_a_free_permit() {
+        let limiter = Arc::new(DownloadLimiter::new(2));
+        let first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+        assert_eq!(limiter.in_use(), 2);
+
+        let waiting = tokio::spawn({
+            let limiter = limiter.clone();
+            async move { limiter.acquire().await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        let _third = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert_eq!(limiter.in_use(), 2);
+    }
+
+    #[test]
+    fn test_capacity_is_at_least_one() {
+        assert_eq!(DownloadLimiter::new(0).capacity(), 1);
+    }
+}
@@ -1,5 +1,11 @@
+pub mod download_limiter;
+pub mod retention_cleanup;
+pub mod source_listing;
 pub mod source_scheduler;
 pub mod source_sync;
+pub mod sync_budget;
+pub mod sync_notifications;
 pub mod user_watch_manager;
 pub mod webdav_scheduler;
+pub mod watch_debounce;
 pub mod watcher;
\ No newline at end of file
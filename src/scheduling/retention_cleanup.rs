@@ -0,0 +1,52 @@
+use anyhow::Result;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::Database,
+    models::{RetentionCandidate, UserRole},
+    services::file_service::FileService,
+};
+
+/// Moves documents past their retention period to the trash, from where the trash purge
+/// removes them for good. With `dry_run` nothing is changed and the documents that would be
+/// removed are returned. See [`Database::get_retention_expired_documents`] for which
+/// documents are selected.
+pub async fn apply_retention_policies(
+    db: &Database,
+    upload_path: &str,
+    user_id: Option<Uuid>,
+    dry_run: bool,
+) -> Result<Vec<RetentionCandidate>> {
+    let candidates = db.get_retention_expired_documents(user_id).await?;
+    if dry_run || candidates.is_empty() {
+        return Ok(candidates);
+    }
+
+    let file_service = FileService::new(upload_path.to_string());
+    let mut removed = Vec::with_capacity(candidates.len());
+
+    for candidate in candidates {
+        let Some(document) = db.get_document_by_id(candidate.id, candidate.user_id, UserRole::User).await? else {
+            continue;
+        };
+        if !db.delete_document(candidate.id, candidate.user_id, UserRole::User).await? {
+            continue;
+        }
+
+        match file_service.move_to_trash(&document).await {
+            Ok(trash_path) if trash_path != document.file_path => {
+                if let Err(e) = db.update_document_file_path(document.id, &trash_path).await {
+                    warn!("Failed to record trash path for document {}: {}", document.id, e);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to move file for expired document {} to trash: {}", document.id, e),
+        }
+
+        removed.push(candidate);
+    }
+
+    info!("Retention cleanup moved {} expired documents to the trash", removed.len());
+    Ok(removed)
+}
@@ -0,0 +1,101 @@
+//! What a sync run saw on its source, for finding documents whose file is gone.
+//!
+//! Incremental syncs only list the directories that changed, so a document can only be
+//! judged from the directories that were actually listed in full this run. A document is
+//! missing when a listed directory above it no longer contains the next step of its path:
+//! the file itself, or the folder it was in.
+
+use std::collections::HashSet;
+
+#[derive(Debug, Default)]
+pub struct SourceListing {
+    /// Directories whose listing succeeded
+    scanned_directories: HashSet<String>,
+    /// Files and directories found in them
+    entries: HashSet<String>,
+}
+
+impl SourceListing {
+    /// Records the result of listing directories. `files` and `directories` are the entries
+    /// found; only the directories in `scanned_directories` count as listed completely.
+    pub fn record<'a>(
+        &mut self,
+        scanned_directories: impl IntoIterator<Item = &'a str>,
+        files: impl IntoIterator<Item = &'a str>,
+        directories: impl IntoIterator<Item = &'a str>,
+    ) {
+        self.scanned_directories.extend(scanned_directories.into_iter().map(normalize));
+        self.entries.extend(files.into_iter().chain(directories).map(normalize));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scanned_directories.is_empty()
+    }
+
+    /// Whether the file at `source_path` is gone from the source: `Some(true)` if a listed
+    /// directory above it no longer leads to it, `Some(false)` if it was found, and `None`
+    /// if nothing listed this run tells either way.
+    pub fn is_missing(&self, source_path: &str) -> Option<bool> {
+        let path = normalize(source_path);
+
+        for (separator, _) in path.match_indices('/') {
+            let directory = &path[..separator];
+            if !self.scanned_directories.contains(directory) {
+                continue;
+            }
+
+            let next_separator = path[separator + 1..].find('/').map(|i| separator + 1 + i);
+            let child = &path[..next_separator.unwrap_or(path.len())];
+            if !self.entries.contains(child) {
+                return Some(true);
+            }
+        }
+
+        if self.entries.contains(&path) { Some(false) } else { None }
+    }
+}
+
+/// Paths are compared without trailing slashes; the root directory is the empty string
+fn normalize(path: &str) -> String {
+    path.trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn listing() -> SourceListing {
+        let mut listing = SourceListing::default();
+        listing.record(
+            ["/Documents/", "/Documents/2024"],
+            ["/Documents/a.pdf", "/Documents/2024/b.pdf"],
+            ["/Documents/2024/", "/Documents/2025/"],
+        );
+        listing
+    }
+
+    #[test]
+    fn test_files_in_listed_directories() {
+        let listing = listing();
+        assert_eq!(listing.is_missing("/Documents/a.pdf"), Some(false));
+        assert_eq!(listing.is_missing("/Documents/2024/b.pdf"), Some(false));
+        assert_eq!(listing.is_missing("/Documents/gone.pdf"), Some(true));
+        assert_eq!(listing.is_missing("/Documents/2024/gone.pdf"), Some(true));
+    }
+
+    #[test]
+    fn test_removed_folder_makes_its_files_missing() {
+        let listing = listing();
+        assert_eq!(listing.is_missing("/Documents/Old/c.pdf"), Some(true));
+        assert_eq!(listing.is_missing("/Documents/Old/2023/d.pdf"), Some(true));
+    }
+
+    #[test]
+    fn test_directories_not_listed_are_not_judged() {
+        let listing = listing();
+        // 2025 exists but was not listed itself, e.g. because it did not change
+        assert_eq!(listing.is_missing("/Documents/2025/e.pdf"), None);
+        assert_eq!(listing.is_missing("/Archive/f.pdf"), None);
+        assert!(SourceListing::default().is_empty());
+    }
+}
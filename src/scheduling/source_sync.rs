@@ -1,30 +1,76 @@
-use std::sync::Arc;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
 use std::path::Path;
 use anyhow::{anyhow, Result};
 use tokio::sync::Semaphore;
 use tokio_util::sync::CancellationToken;
 use futures::stream::{FuturesUnordered, StreamExt};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 use uuid::Uuid;
 
 use crate::{
     AppState,
-    models::{FileIngestionInfo, Source, SourceType, SourceStatus, LocalFolderSourceConfig, S3SourceConfig, WebDAVSourceConfig},
+    db::source_sync_runs::SyncRunCounts,
+    models::{CreateS3Object, FileIngestionInfo, Source, SourceType, SourceStatus, SourceSyncMode, SyncRunOutcome, ImapSourceConfig, LocalFolderSourceConfig, S3SourceConfig, SmbSourceConfig, WebDAVSourceConfig},
     services::file_service::FileService,
+    services::imap_service::ImapService,
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
     services::local_folder_service::LocalFolderService,
     services::s3_service::S3Service,
+    services::smb_service::SmbService,
     services::webdav::{WebDAVService, WebDAVConfig, SyncProgress, SyncPhase},
+    scheduling::source_listing::SourceListing,
+    scheduling::sync_budget::{SourceConcurrency, SyncBudget},
 };
 
+/// Counters for the sync run currently in progress, shared with the concurrent file tasks
+#[derive(Debug, Default)]
+pub struct SyncRunStats {
+    files_discovered: AtomicI64,
+    files_downloaded: AtomicI64,
+    files_failed: AtomicI64,
+    bytes_transferred: AtomicI64,
+    /// Source paths of the files that failed, so sources can retry them next run
+    failed_paths: Mutex<Vec<String>>,
+}
+
+impl SyncRunStats {
+    pub fn snapshot(&self) -> SyncRunCounts {
+        SyncRunCounts {
+            files_discovered: self.files_discovered.load(Ordering::Relaxed),
+            files_downloaded: self.files_downloaded.load(Ordering::Relaxed),
+            files_failed: self.files_failed.load(Ordering::Relaxed),
+            bytes_transferred: self.bytes_transferred.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_download(&self, bytes: usize) {
+        self.files_downloaded.fetch_add(1, Ordering::Relaxed);
+        self.bytes_transferred.fetch_add(bytes as i64, Ordering::Relaxed);
+    }
+
+    fn record_failure(&self, source_path: &str) {
+        self.files_failed.fetch_add(1, Ordering::Relaxed);
+        self.failed_paths.lock().unwrap().push(source_path.to_string());
+    }
+
+    fn failed_paths(&self) -> HashSet<String> {
+        self.failed_paths.lock().unwrap().iter().cloned().collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct SourceSyncService {
     state: Arc<AppState>,
+    /// File operations shared by every sync started through this service
+    budget: Arc<SyncBudget>,
 }
 
 impl SourceSyncService {
     pub fn new(state: Arc<AppState>) -> Self {
-        Self { state }
+        let budget = Arc::new(SyncBudget::new(state.config.source_sync_max_concurrency));
+        Self { state, budget }
     }
 
     /// Perform sync for any source type
@@ -35,7 +81,17 @@ impl SourceSyncService {
 
     /// Perform sync for any source type with cancellation support
     pub async fn sync_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
-        info!("Starting sync for source {} ({})", source.name, source.source_type);
+        self.sync_source_with_mode(source, enable_background_ocr, cancellation_token, SourceSyncMode::Smart).await
+    }
+
+    /// Perform sync for any source type in the given mode, with cancellation support.
+    /// `NewOnly` is only supported for WebDAV sources.
+    pub async fn sync_source_with_mode(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken, mode: SourceSyncMode) -> Result<usize> {
+        info!("Starting sync for source {} ({}, mode {:?})", source.name, source.source_type, mode);
+
+        if mode == SourceSyncMode::NewOnly && source.source_type != SourceType::WebDAV {
+            return Err(anyhow!("New-only sync is not supported for {} sources", source.source_type));
+        }
 
         // Check for cancellation before starting
         if cancellation_token.is_cancelled() {
@@ -48,12 +104,43 @@ impl SourceSyncService {
             error!("Failed to update source status: {}", e);
         }
 
+        let run_id = match self.state.db.create_source_sync_run(source.id).await {
+            Ok(run_id) => Some(run_id),
+            Err(e) => {
+                error!("Failed to record sync run for source {}: {}", source.name, e);
+                None
+            }
+        };
+        let run_stats = Arc::new(SyncRunStats::default());
+
+        let concurrency = SourceConcurrency::from_source_config(&source.config).unwrap_or_else(|e| {
+            warn!("Ignoring concurrency settings of source {}: {}", source.name, e);
+            SourceConcurrency::default()
+        });
+        let _budget_registration = self.budget.register(source.id, concurrency);
+
         let sync_result = match source.source_type {
-            SourceType::WebDAV => self.sync_webdav_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone()).await,
-            SourceType::LocalFolder => self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone()).await,
-            SourceType::S3 => self.sync_s3_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone()).await,
+            SourceType::WebDAV => self.sync_webdav_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), run_stats.clone(), mode).await,
+            SourceType::LocalFolder => self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), run_stats.clone()).await,
+            SourceType::S3 => self.sync_s3_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), run_stats.clone()).await,
+            SourceType::Smb => self.sync_smb_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), run_stats.clone()).await,
+            SourceType::Imap => self.sync_imap_source_with_cancellation(source, enable_background_ocr, cancellation_token.clone(), run_stats.clone()).await,
         };
 
+        if let Some(run_id) = run_id {
+            let (outcome, run_error) = if cancellation_token.is_cancelled() {
+                (SyncRunOutcome::Cancelled, None)
+            } else {
+                match &sync_result {
+                    Ok(_) => (SyncRunOutcome::Success, None),
+                    Err(e) => (SyncRunOutcome::Failed, Some(e.to_string())),
+                }
+            };
+            if let Err(e) = self.state.db.finish_source_sync_run(run_id, run_stats.snapshot(), outcome, run_error.as_deref()).await {
+                error!("Failed to finish sync run {} for source {}: {}", run_id, source.name, e);
+            }
+        }
+
         match &sync_result {
             Ok(files_processed) => {
                 if cancellation_token.is_cancelled() {
@@ -90,39 +177,50 @@ impl SourceSyncService {
     }
 
     async fn sync_webdav_source(&self, source: &Source, enable_background_ocr: bool) -> Result<usize> {
-        self.sync_webdav_source_with_cancellation(source, enable_background_ocr, CancellationToken::new()).await
+        self.sync_webdav_source_with_cancellation(source, enable_background_ocr, CancellationToken::new(), Arc::new(SyncRunStats::default()), SourceSyncMode::Smart).await
     }
 
-    async fn sync_webdav_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
-        let config: WebDAVSourceConfig = serde_json::from_value(source.config.clone())
+    async fn sync_webdav_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken, run_stats: Arc<SyncRunStats>, mode: SourceSyncMode) -> Result<usize> {
+        let config: WebDAVSourceConfig = serde_json::from_value(self.state.source_secrets.decrypt_config(&source.config)?)
             .map_err(|e| anyhow!("Invalid WebDAV config: {}", e))?;
 
         info!("WebDAV source sync config: server_url={}, username={}, watch_folders={:?}, file_extensions={:?}, server_type={:?}", 
             config.server_url, config.username, config.watch_folders, config.file_extensions, config.server_type);
 
-        // Requests to list files in a Nextcloud folder might take > 2 minutes
-        // Set timeout to 3 minutes to accommodate large folder structures
-        let webdav_config = WebDAVConfig {
-            server_url: config.server_url,
-            username: config.username,
-            password: config.password,
-            watch_folders: config.watch_folders,
-            file_extensions: config.file_extensions,
-            timeout_seconds: 180, // 3 minutes for discover_files_in_folder operations
-            server_type: config.server_type,
-        };
+        let webdav_config = WebDAVConfig::from(config);
 
         let webdav_service = WebDAVService::new(webdav_config.clone())
             .map_err(|e| anyhow!("Failed to create WebDAV service: {}", e))?;
 
+        // Sources saved without a server type get one detected so URLs use the right endpoint
+        if webdav_config.server_type.is_none() {
+            webdav_service.ensure_server_type().await;
+        }
+
         info!("WebDAV service created successfully, starting sync with {} folders", webdav_config.watch_folders.len());
 
+        // A scheduled ETag validation found tracking drift: rescan everything this time.
+        // A new-only sync leaves the queued deep scan for the next regular sync.
+        let deep_scan_pending = match self.state.db.get_source_validation_report(source.id).await {
+            Ok(report) => mode == SourceSyncMode::Smart && report.map(|r| r.deep_scan_pending).unwrap_or(false),
+            Err(e) => {
+                warn!("Failed to load validation report for source {}: {}", source.name, e);
+                false
+            }
+        };
+        if deep_scan_pending {
+            info!("🔍 Running queued deep scan instead of incremental sync for source '{}'", source.name);
+        }
+
         // Create progress tracker for scheduled sync and register it globally
         let progress = Arc::new(SyncProgress::new());
         progress.set_phase(SyncPhase::Initializing);
         self.state.sync_progress_tracker.register_sync(source.id, progress.clone());
         info!("🚀 Starting scheduled WebDAV sync with progress tracking for source '{}'", source.name);
 
+        // The directories this run lists in full, for finding documents whose file is gone
+        let listing: Arc<Mutex<SourceListing>> = Arc::default();
+
         let sync_result = self.perform_sync_internal_with_cancellation(
             source.user_id,
             source.id,
@@ -130,11 +228,13 @@ impl SourceSyncService {
             &webdav_config.file_extensions,
             enable_background_ocr,
             cancellation_token,
+            run_stats,
             |folder_path| {
                 let service = webdav_service.clone();
                 let state_clone = self.state.clone();
                 let user_id = source.user_id;  // Capture user_id from source
                 let progress = progress.clone(); // Clone progress for the async closure
+                let listing = listing.clone();
                 async move { 
                     info!("🧠 Using smart sync for scheduled sync: {}", folder_path);
                     progress.set_current_directory(&folder_path);
@@ -142,10 +242,29 @@ impl SourceSyncService {
                     // Use smart sync service for intelligent discovery
                     let smart_sync_service = crate::services::webdav::SmartSyncService::new(state_clone);
                     
-                    match smart_sync_service.evaluate_and_sync(user_id, &service, &folder_path, Some(&progress)).await {
+                    let sync_outcome = if mode == SourceSyncMode::NewOnly {
+                        smart_sync_service.sync_new_directories_only(user_id, &service, &folder_path, Some(&progress)).await
+                    } else if deep_scan_pending {
+                        smart_sync_service.perform_smart_sync(
+                            user_id,
+                            &service,
+                            &folder_path,
+                            crate::services::webdav::SmartSyncStrategy::FullDeepScan,
+                            Some(&progress),
+                        ).await.map(Some)
+                    } else {
+                        smart_sync_service.evaluate_and_sync(user_id, &service, &folder_path, Some(&progress)).await
+                    };
+
+                    match sync_outcome {
                         Ok(Some(sync_result)) => {
                             info!("✅ Smart sync completed for {}: {} files found using {:?}", 
                                   folder_path, sync_result.files.len(), sync_result.strategy_used);
+                            listing.lock().unwrap().record(
+                                sync_result.scanned_directories.iter().map(String::as_str),
+                                sync_result.files.iter().map(|file| file.relative_path.as_str()),
+                                sync_result.directories.iter().map(|directory| directory.relative_path.as_str()),
+                            );
                             Ok(sync_result.files)
                         },
                         Ok(None) => {
@@ -189,14 +308,61 @@ impl SourceSyncService {
         // Always unregister the progress tracker to prevent memory leaks
         self.state.sync_progress_tracker.unregister_sync(source.id);
 
+        if deep_scan_pending && sync_result.is_ok() {
+            if let Err(e) = self.state.db.clear_source_deep_scan_pending(source.id).await {
+                error!("Failed to clear queued deep scan for source {}: {}", source.name, e);
+            }
+        }
+
+        if sync_result.is_ok() {
+            let listing = std::mem::take(&mut *listing.lock().unwrap());
+            self.update_missing_documents(source.id, &listing).await;
+        }
+
         sync_result
     }
 
+    /// Flags the source's documents whose file a listed directory no longer contains, and
+    /// clears the flag on those that were found again. Documents in directories this run did
+    /// not list keep their current state.
+    async fn update_missing_documents(&self, source_id: Uuid, listing: &SourceListing) {
+        if listing.is_empty() {
+            return;
+        }
+
+        let paths = match self.state.db.get_source_document_paths(source_id).await {
+            Ok(paths) => paths,
+            Err(e) => {
+                error!("Failed to load document paths of source {}: {}", source_id, e);
+                return;
+            }
+        };
+
+        let mut now_missing = Vec::new();
+        let mut found_again = Vec::new();
+        for (source_path, flagged) in paths {
+            match listing.is_missing(&source_path) {
+                Some(true) if !flagged => now_missing.push(source_path),
+                Some(false) if flagged => found_again.push(source_path),
+                _ => {}
+            }
+        }
+
+        match self.state.db.set_source_documents_missing(source_id, &now_missing, true).await {
+            Ok(0) => {}
+            Ok(flagged) => info!("Flagged {} documents of source {} as missing on the source", flagged, source_id),
+            Err(e) => error!("Failed to flag missing documents of source {}: {}", source_id, e),
+        }
+        if let Err(e) = self.state.db.set_source_documents_missing(source_id, &found_again, false).await {
+            error!("Failed to clear missing flag on documents of source {}: {}", source_id, e);
+        }
+    }
+
     async fn sync_local_folder_source(&self, source: &Source, enable_background_ocr: bool) -> Result<usize> {
-        self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, CancellationToken::new()).await
+        self.sync_local_folder_source_with_cancellation(source, enable_background_ocr, CancellationToken::new(), Arc::new(SyncRunStats::default())).await
     }
 
-    async fn sync_local_folder_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
+    async fn sync_local_folder_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken, run_stats: Arc<SyncRunStats>) -> Result<usize> {
         let config: LocalFolderSourceConfig = serde_json::from_value(source.config.clone())
             .map_err(|e| anyhow!("Invalid LocalFolder config: {}", e))?;
 
@@ -216,6 +382,7 @@ impl SourceSyncService {
             &config.file_extensions,
             enable_background_ocr,
             cancellation_token,
+            run_stats,
             |folder_path| {
                 let service = local_service.clone();
                 async move { service.discover_files_in_folder(&folder_path).await }
@@ -239,16 +406,28 @@ impl SourceSyncService {
     }
 
     async fn sync_s3_source(&self, source: &Source, enable_background_ocr: bool) -> Result<usize> {
-        self.sync_s3_source_with_cancellation(source, enable_background_ocr, CancellationToken::new()).await
+        self.sync_s3_source_with_cancellation(source, enable_background_ocr, CancellationToken::new(), Arc::new(SyncRunStats::default())).await
     }
 
-    async fn sync_s3_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken) -> Result<usize> {
-        let config: S3SourceConfig = serde_json::from_value(source.config.clone())
+    async fn sync_s3_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken, run_stats: Arc<SyncRunStats>) -> Result<usize> {
+        let config: S3SourceConfig = serde_json::from_value(self.state.source_secrets.decrypt_config(&source.config)?)
             .map_err(|e| anyhow!("Invalid S3 config: {}", e))?;
 
         let s3_service = S3Service::new(config.clone()).await
             .map_err(|e| anyhow!("Failed to create S3 service: {}", e))?;
 
+        // ETags of the objects earlier runs synced; unchanged objects are not downloaded again
+        let known_etags: Arc<HashMap<String, String>> = Arc::new(
+            self.state.db.list_s3_objects(source.id).await?
+                .into_iter()
+                .map(|object| (object.object_key, object.etag))
+                .collect()
+        );
+        // Both discovery passes of a run share one listing per watch folder
+        let listings: Arc<Mutex<HashMap<String, Vec<FileIngestionInfo>>>> = Arc::default();
+        let listing_failed = Arc::new(AtomicBool::new(false));
+        let downloaded_keys: Arc<Mutex<HashSet<String>>> = Arc::default();
+
         // Create progress tracker for S3 sync and register it globally
         let progress = Arc::new(SyncProgress::new());
         progress.set_phase(SyncPhase::Initializing);
@@ -262,12 +441,154 @@ impl SourceSyncService {
             &config.file_extensions,
             enable_background_ocr,
             cancellation_token,
+            run_stats.clone(),
             |folder_path| {
                 let service = s3_service.clone();
-                async move { service.discover_files_in_folder(&folder_path).await }
+                let known_etags = known_etags.clone();
+                let listings = listings.clone();
+                let listing_failed = listing_failed.clone();
+                async move {
+                    let cached = listings.lock().unwrap().get(&folder_path).cloned();
+                    let files = match cached {
+                        Some(files) => files,
+                        None => {
+                            let files = service.discover_files_in_folder(&folder_path).await
+                                .inspect_err(|_| listing_failed.store(true, Ordering::Relaxed))?;
+                            listings.lock().unwrap().insert(folder_path, files.clone());
+                            files
+                        }
+                    };
+
+                    Ok(files
+                        .into_iter()
+                        .filter(|file| known_etags.get(&file.relative_path) != Some(&file.etag))
+                        .collect())
+                }
             },
             |file_path| {
                 let service = s3_service.clone();
+                let downloaded_keys = downloaded_keys.clone();
+                async move {
+                    let data = service.download_file(&file_path).await?;
+                    downloaded_keys.lock().unwrap().insert(file_path);
+                    Ok(data)
+                }
+            }
+        ).await;
+
+        let listed: HashMap<String, FileIngestionInfo> = listings.lock().unwrap()
+            .values()
+            .flatten()
+            .map(|file| (file.relative_path.clone(), file.clone()))
+            .collect();
+        let downloaded_keys = downloaded_keys.lock().unwrap().clone();
+        self.update_s3_object_tracking(source.id, &listed, &downloaded_keys, &run_stats.failed_paths()).await;
+
+        // Deletions are only trustworthy when every watch folder was listed completely
+        if sync_result.is_ok() && !listing_failed.load(Ordering::Relaxed) {
+            let watched_prefixes: Vec<String> = config.watch_folders.iter()
+                .map(|folder| s3_service.object_prefix(folder))
+                .collect();
+            let removed_keys: Vec<String> = known_etags.keys()
+                .filter(|key| !listed.contains_key(*key))
+                .filter(|key| watched_prefixes.iter().any(|prefix| key.starts_with(prefix.as_str())))
+                .cloned()
+                .collect();
+            self.handle_removed_s3_objects(source.id, &removed_keys).await;
+        }
+        
+        // Always mark sync phase and unregister progress tracker, regardless of result
+        match &sync_result {
+            Ok(_) => progress.set_phase(SyncPhase::Completed),
+            Err(e) => progress.set_phase(SyncPhase::Failed(e.to_string())),
+        }
+        
+        // Always unregister the progress tracker to prevent memory leaks
+        self.state.sync_progress_tracker.unregister_sync(source.id);
+        
+        sync_result
+    }
+
+    /// Records the objects this run downloaded and ingested so the next run can skip them while
+    /// their ETag stays the same. Objects that failed are left out so they are retried.
+    async fn update_s3_object_tracking(
+        &self,
+        source_id: Uuid,
+        listed: &HashMap<String, FileIngestionInfo>,
+        downloaded_keys: &HashSet<String>,
+        failed_keys: &HashSet<String>,
+    ) {
+        let synced_keys: Vec<String> = downloaded_keys.difference(failed_keys).cloned().collect();
+
+        for key in &synced_keys {
+            let Some(file) = listed.get(key) else { continue };
+            let object = CreateS3Object {
+                source_id,
+                object_key: key.clone(),
+                etag: file.etag.clone(),
+                last_modified: file.last_modified,
+                size: file.size,
+            };
+            if let Err(e) = self.state.db.upsert_s3_object(&object).await {
+                error!("Failed to record synced S3 object {}: {}", key, e);
+            }
+        }
+
+        // Objects that were removed and have come back are no longer flagged as missing
+        if let Err(e) = self.state.db.set_source_documents_missing(source_id, &synced_keys, false).await {
+            error!("Failed to clear missing flag on S3 documents: {}", e);
+        }
+    }
+
+    /// Flags the documents of objects that are gone from the bucket and stops tracking them
+    async fn handle_removed_s3_objects(&self, source_id: Uuid, removed_keys: &[String]) {
+        if removed_keys.is_empty() {
+            return;
+        }
+
+        match self.state.db.set_source_documents_missing(source_id, removed_keys, true).await {
+            Ok(flagged) => info!(
+                "{} S3 objects were removed from source {}; flagged {} documents",
+                removed_keys.len(), source_id, flagged
+            ),
+            Err(e) => {
+                error!("Failed to flag documents of removed S3 objects: {}", e);
+                return;
+            }
+        }
+
+        if let Err(e) = self.state.db.delete_s3_objects(source_id, removed_keys).await {
+            error!("Failed to stop tracking removed S3 objects: {}", e);
+        }
+    }
+
+    async fn sync_smb_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken, run_stats: Arc<SyncRunStats>) -> Result<usize> {
+        let config: SmbSourceConfig = serde_json::from_value(source.config.clone())
+            .map_err(|e| anyhow!("Invalid SMB config: {}", e))?;
+
+        let smb_service = SmbService::new(config.clone())
+            .map_err(|e| anyhow!("Failed to create SMB service: {}", e))?;
+
+        // Create progress tracker for SMB sync and register it globally
+        let progress = Arc::new(SyncProgress::new());
+        progress.set_phase(SyncPhase::Initializing);
+        self.state.sync_progress_tracker.register_sync(source.id, progress.clone());
+        info!("🚀 Starting SMB sync with progress tracking for source '{}'", source.name);
+
+        let sync_result = self.perform_sync_internal_with_cancellation(
+            source.user_id,
+            source.id,
+            &config.watch_folders,
+            &config.file_extensions,
+            enable_background_ocr,
+            cancellation_token,
+            run_stats,
+            |folder_path| {
+                let service = smb_service.clone();
+                async move { service.discover_files_in_folder(&folder_path).await }
+            },
+            |file_path| {
+                let service = smb_service.clone();
                 async move { service.download_file(&file_path).await }
             }
         ).await;
@@ -284,6 +605,138 @@ impl SourceSyncService {
         sync_result
     }
 
+    async fn sync_imap_source_with_cancellation(&self, source: &Source, enable_background_ocr: bool, cancellation_token: CancellationToken, run_stats: Arc<SyncRunStats>) -> Result<usize> {
+        let config: ImapSourceConfig = serde_json::from_value(source.config.clone())
+            .map_err(|e| anyhow!("Invalid IMAP config: {}", e))?;
+
+        let imap_service = ImapService::new(config)
+            .map_err(|e| anyhow!("Failed to create IMAP service: {}", e))?;
+
+        // Create progress tracker for IMAP sync and register it globally
+        let progress = Arc::new(SyncProgress::new());
+        progress.set_phase(SyncPhase::Initializing);
+        self.state.sync_progress_tracker.register_sync(source.id, progress.clone());
+        info!("🚀 Starting IMAP sync with progress tracking for source '{}'", source.name);
+
+        let sync_result = self.perform_imap_sync_with_cancellation(
+            source.user_id,
+            source.id,
+            &imap_service,
+            enable_background_ocr,
+            cancellation_token,
+            run_stats,
+        ).await;
+
+        // Always mark sync phase and unregister progress tracker, regardless of result
+        match &sync_result {
+            Ok(_) => progress.set_phase(SyncPhase::Completed),
+            Err(e) => progress.set_phase(SyncPhase::Failed(e.to_string())),
+        }
+
+        // Always unregister the progress tracker to prevent memory leaks
+        self.state.sync_progress_tracker.unregister_sync(source.id);
+
+        sync_result
+    }
+
+    /// Ingest the attachments of unseen messages, then mark those messages as seen.
+    /// A message with a failed attachment stays unseen so the next poll retries it;
+    /// attachments that did succeed are then skipped by content deduplication.
+    async fn perform_imap_sync_with_cancellation(
+        &self,
+        user_id: Uuid,
+        source_id: Uuid,
+        imap_service: &ImapService,
+        enable_background_ocr: bool,
+        cancellation_token: CancellationToken,
+        run_stats: Arc<SyncRunStats>,
+    ) -> Result<usize> {
+        let messages = imap_service.fetch_unseen_messages().await?;
+
+        let total_files_discovered: usize = messages.iter().map(|m| m.attachments.len()).sum();
+        let total_size_bytes: i64 = messages.iter()
+            .flat_map(|m| m.attachments.iter())
+            .map(|a| a.file_info.size)
+            .sum();
+        run_stats.files_discovered.store(total_files_discovered as i64, Ordering::Relaxed);
+
+        if let Err(e) = self.state.db.update_source_sync_stats(
+            source_id,
+            0, // files_synced starts at 0
+            total_files_discovered as i64,
+            total_size_bytes,
+        ).await {
+            error!("Failed to update initial sync stats: {}", e);
+        }
+
+        let mut total_files_processed = 0;
+        let mut completed_uids = Vec::with_capacity(messages.len());
+
+        for message in messages {
+            if cancellation_token.is_cancelled() {
+                info!("Sync cancelled during message processing");
+                break;
+            }
+
+            let mut message_complete = true;
+            for attachment in message.attachments {
+                let data = attachment.data;
+                let result = Self::process_single_file_with_cancellation(
+                    self.state.clone(),
+                    user_id,
+                    source_id,
+                    &attachment.file_info,
+                    enable_background_ocr,
+                    self.budget.clone(),
+                    move |_| {
+                        let data = data.clone();
+                        async move { Ok(data) }
+                    },
+                    cancellation_token.clone(),
+                    run_stats.clone(),
+                ).await;
+
+                match result {
+                    Ok(processed) => {
+                        if processed {
+                            total_files_processed += 1;
+                        }
+                    }
+                    Err(error) => {
+                        run_stats.record_failure(&attachment.file_info.relative_path);
+                        error!("Attachment processing error in message {}: {}", message.uid, error);
+                        message_complete = false;
+                    }
+                }
+            }
+
+            if message_complete {
+                completed_uids.push(message.uid);
+            }
+        }
+
+        // Messages without matching attachments are marked too, so they aren't downloaded again
+        if let Err(e) = imap_service.mark_seen(&completed_uids).await {
+            error!("Failed to mark {} IMAP messages as seen: {}", completed_uids.len(), e);
+        }
+
+        if cancellation_token.is_cancelled() {
+            return Err(anyhow!("Sync cancelled"));
+        }
+
+        if let Err(e) = self.state.db.update_source_sync_stats(
+            source_id,
+            total_files_processed as i64,
+            0, // All attachments are now processed
+            total_size_bytes,
+        ).await {
+            error!("Failed to update final sync stats: {}", e);
+        }
+
+        info!("IMAP sync completed: {} attachments processed", total_files_processed);
+        Ok(total_files_processed)
+    }
+
     async fn perform_sync_internal<F, D, Fut1, Fut2>(
         &self,
         user_id: Uuid,
@@ -392,6 +845,7 @@ impl SourceSyncService {
         file_extensions: &[String],
         enable_background_ocr: bool,
         cancellation_token: CancellationToken,
+        run_stats: Arc<SyncRunStats>,
         discover_files: F,
         download_file: D,
     ) -> Result<usize>
@@ -439,6 +893,8 @@ impl SourceSyncService {
             }
         }
 
+        run_stats.files_discovered.store(total_files_discovered as i64, Ordering::Relaxed);
+
         // Update initial statistics with discovered files
         if let Err(e) = self.state.db.update_source_sync_stats(
             source_id,
@@ -488,9 +944,7 @@ impl SourceSyncService {
 
                     info!("Processing {} files from folder {}", files_to_process.len(), folder_path);
 
-                    // Process files concurrently with a limit
-                    let concurrent_limit = 5;
-                    let semaphore = Arc::new(Semaphore::new(concurrent_limit));
+                    // Process files concurrently, as far as this source's share of the budget allows
                     let mut folder_files_processed = 0;
 
                     let mut file_futures = FuturesUnordered::new();
@@ -504,28 +958,31 @@ impl SourceSyncService {
 
                         let state_clone = self.state.clone();
                         let file_info_clone = file_info.clone();
-                        let semaphore_clone = semaphore.clone();
+                        let budget_clone = self.budget.clone();
                         let download_file_clone = download_file.clone();
                         let cancellation_token_clone = cancellation_token.clone();
+                        let run_stats_clone = run_stats.clone();
 
                         let future = async move {
-                            Self::process_single_file_with_cancellation(
+                            let result = Self::process_single_file_with_cancellation(
                                 state_clone,
                                 user_id,
                                 source_id,
                                 &file_info_clone,
                                 enable_background_ocr,
-                                semaphore_clone,
+                                budget_clone,
                                 download_file_clone,
                                 cancellation_token_clone,
-                            ).await
+                                run_stats_clone,
+                            ).await;
+                            (file_info_clone.relative_path, result)
                         };
 
                         file_futures.push(future);
                     }
 
                     // Process files concurrently and update stats periodically
-                    while let Some(result) = file_futures.next().await {
+                    while let Some((source_path, result)) = file_futures.next().await {
                         // Check for cancellation during processing
                         if cancellation_token.is_cancelled() {
                             info!("Sync cancelled during concurrent file processing");
@@ -555,6 +1012,7 @@ impl SourceSyncService {
                                 }
                             }
                             Err(error) => {
+                                run_stats.record_failure(&source_path);
                                 error!("File processing error: {}", error);
                             }
                         }
@@ -598,19 +1056,30 @@ impl SourceSyncService {
 
         debug!("Processing file: {}", file_info.relative_path);
         
-        // Download the file
+        // Download the file, within the instance-wide download limit
+        let download_permit = state.download_limiter.acquire().await;
         let file_data = download_file(file_info.relative_path.clone()).await
             .map_err(|e| anyhow!("Failed to download {}: {}", file_info.relative_path, e))?;
+        drop(download_permit);
 
         debug!("Downloaded file: {} ({} bytes)", file_info.name, file_data.len());
 
+        // Extract EXIF/PDF properties alongside the metadata reported by the source
+        let mut file_info = file_info.clone();
+        crate::metadata_extraction::enrich_file_info(
+            &mut file_info,
+            &file_data,
+            crate::metadata_extraction::MetadataExtractionOptions::from_config(&state.config),
+        ).await;
+
         // Use the unified ingestion service for consistent deduplication
-        let file_service = FileService::new(state.config.upload_path.clone());
-        let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service);
+        let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+        let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service)
+            .with_version_retention(state.config.document_version_retention);
         
         let result = ingestion_service
             .ingest_from_file_info(
-                file_info,
+                &file_info,
                 file_data,
                 user_id,
                 crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
@@ -625,6 +1094,10 @@ impl SourceSyncService {
                 debug!("Created new document for {}: {}", file_info.name, doc.id);
                 (doc, true) // New document - queue for OCR
             }
+            IngestionResult::NewVersion(doc) => {
+                info!("Stored new version of {} as document {}", file_info.name, doc.id);
+                (doc, true) // Changed content - queue the new version for OCR
+            }
             IngestionResult::Skipped { existing_document_id, reason } => {
                 info!("Skipped duplicate file {}: {} (existing: {})", file_info.name, reason, existing_document_id);
                 return Ok(false); // File was skipped due to deduplication
@@ -665,9 +1138,10 @@ impl SourceSyncService {
         source_id: Uuid,
         file_info: &FileIngestionInfo,
         enable_background_ocr: bool,
-        semaphore: Arc<Semaphore>,
+        budget: Arc<SyncBudget>,
         download_file: D,
         cancellation_token: CancellationToken,
+        run_stats: Arc<SyncRunStats>,
     ) -> Result<bool>
     where
         D: Fn(String) -> Fut,
@@ -679,20 +1153,34 @@ impl SourceSyncService {
             return Err(anyhow!("Processing cancelled"));
         }
 
-        let _permit = semaphore.acquire().await
-            .map_err(|e| anyhow!("Semaphore error: {}", e))?;
+        let _permit = tokio::select! {
+            permit = budget.acquire(source_id) => permit,
+            _ = cancellation_token.cancelled() => {
+                info!("File processing cancelled while waiting for the sync budget: {}", file_info.relative_path);
+                return Err(anyhow!("Processing cancelled"));
+            }
+        };
 
         debug!("Processing file: {}", file_info.relative_path);
 
-        // Check for cancellation again after acquiring semaphore
+        // Check for cancellation again after acquiring a permit
         if cancellation_token.is_cancelled() {
-            info!("File processing cancelled after acquiring semaphore: {}", file_info.relative_path);
+            info!("File processing cancelled after acquiring a permit: {}", file_info.relative_path);
             return Err(anyhow!("Processing cancelled"));
         }
 
-        // Download the file
+        // Download the file, within the instance-wide download limit
+        let download_permit = tokio::select! {
+            permit = state.download_limiter.acquire() => permit,
+            _ = cancellation_token.cancelled() => {
+                info!("File processing cancelled while waiting for a download slot: {}", file_info.relative_path);
+                return Err(anyhow!("Processing cancelled"));
+            }
+        };
         let file_data = download_file(file_info.relative_path.clone()).await
             .map_err(|e| anyhow!("Failed to download {}: {}", file_info.relative_path, e))?;
+        drop(download_permit);
+        run_stats.record_download(file_data.len());
 
         // Check for cancellation after download
         if cancellation_token.is_cancelled() {
@@ -708,13 +1196,22 @@ impl SourceSyncService {
             return Err(anyhow!("Processing cancelled"));
         }
 
+        // Extract EXIF/PDF properties alongside the metadata reported by the source
+        let mut file_info = file_info.clone();
+        crate::metadata_extraction::enrich_file_info(
+            &mut file_info,
+            &file_data,
+            crate::metadata_extraction::MetadataExtractionOptions::from_config(&state.config),
+        ).await;
+
         // Use the unified ingestion service for consistent deduplication
-        let file_service = FileService::new(state.config.upload_path.clone());
-        let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service);
+        let file_service = FileService::new(state.config.upload_path.clone()).with_storage_layout(state.config.storage_layout);
+        let ingestion_service = DocumentIngestionService::new(state.db.clone(), file_service)
+            .with_version_retention(state.config.document_version_retention);
         
         let result = ingestion_service
             .ingest_from_file_info(
-                file_info,
+                &file_info,
                 file_data,
                 user_id,
                 crate::ingestion::document_ingestion::DeduplicationPolicy::Skip,
@@ -729,6 +1226,10 @@ impl SourceSyncService {
                 debug!("Created new document for {}: {}", file_info.name, doc.id);
                 (doc, true) // New document - queue for OCR
             }
+            IngestionResult::NewVersion(doc) => {
+                info!("Stored new version of {} as document {}", file_info.name, doc.id);
+                (doc, true) // Changed content - queue the new version for OCR
+            }
             IngestionResult::Skipped { existing_document_id, reason } => {
                 info!("Skipped duplicate file {}: {} (existing: {})", file_info.name, reason, existing_document_id);
                 return Ok(false); // File was skipped due to deduplication
@@ -0,0 +1,276 @@
+//! Fair sharing of file transfers between source syncs.
+//!
+//! Every running sync downloads and ingests several files at once. Without a shared limit
+//! a source with a huge backlog keeps the server busy for hours while a small source's
+//! sync crawls along next to it. Running syncs instead share one budget of concurrent
+//! file operations: each gets a share weighted by its priority, never more than its own
+//! `max_concurrency`, and capacity a source cannot use is handed to the others.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+/// Concurrent file operations of a source whose config does not set `max_concurrency`
+pub const DEFAULT_SOURCE_MAX_CONCURRENCY: usize = 5;
+/// Highest `max_concurrency` a source may ask for
+pub const MAX_SOURCE_MAX_CONCURRENCY: usize = 64;
+/// Default size of the budget shared by all running syncs
+pub const DEFAULT_SYNC_MAX_CONCURRENCY: usize = 16;
+
+/// High-priority sources get this many times the share of a normal source
+const HIGH_PRIORITY_WEIGHT: usize = 2;
+
+/// How much of the sync budget a source may use, from `max_concurrency` and
+/// `high_priority` in its config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SourceConcurrency {
+    pub max_concurrency: usize,
+    pub high_priority: bool,
+}
+
+impl Default for SourceConcurrency {
+    fn default() -> Self {
+        Self { max_concurrency: DEFAULT_SOURCE_MAX_CONCURRENCY, high_priority: false }
+    }
+}
+
+impl SourceConcurrency {
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Self, String> {
+        let max_concurrency = match config.get("max_concurrency") {
+            None | Some(serde_json::Value::Null) => DEFAULT_SOURCE_MAX_CONCURRENCY,
+            Some(value) => match value.as_u64() {
+                Some(limit) if (1..=MAX_SOURCE_MAX_CONCURRENCY as u64).contains(&limit) => limit as usize,
+                _ => {
+                    return Err(format!(
+                        "max_concurrency must be a whole number from 1 to {}",
+                        MAX_SOURCE_MAX_CONCURRENCY
+                    ))
+                }
+            },
+        };
+        let high_priority = match config.get("high_priority") {
+            None | Some(serde_json::Value::Null) => false,
+            Some(serde_json::Value::Bool(high_priority)) => *high_priority,
+            Some(_) => return Err("high_priority must be true or false".to_string()),
+        };
+
+        Ok(Self { max_concurrency, high_priority })
+    }
+
+    fn weight(&self) -> usize {
+        if self.high_priority { HIGH_PRIORITY_WEIGHT } else { 1 }
+    }
+}
+
+#[derive(Debug, Default)]
+struct BudgetState {
+    /// Sources with a sync running
+    active: HashMap<Uuid, SourceConcurrency>,
+    /// Permits held per source
+    held: HashMap<Uuid, usize>,
+    /// Permits held by all sources together
+    total_held: usize,
+}
+
+impl BudgetState {
+    /// The most permits `source_id` may hold while the current set of sources is running.
+    /// Capacity is split by weight; a source capped below its split gives the rest back to
+    /// the others. A source without a registered sync is counted as a default one.
+    fn share(&self, capacity: usize, source_id: Uuid) -> usize {
+        let mut unresolved: Vec<(Uuid, SourceConcurrency)> = self.active.iter().map(|(id, c)| (*id, *c)).collect();
+        if !self.active.contains_key(&source_id) {
+            unresolved.push((source_id, SourceConcurrency::default()));
+        }
+
+        let mut remaining = capacity;
+        loop {
+            let total_weight: usize = unresolved.iter().map(|(_, c)| c.weight()).sum();
+            let fair = |concurrency: &SourceConcurrency| remaining * concurrency.weight() / total_weight;
+
+            let (capped, uncapped): (Vec<(Uuid, SourceConcurrency)>, Vec<_>) = unresolved
+                .iter()
+                .partition(|(_, concurrency)| concurrency.max_concurrency <= fair(concurrency));
+            if let Some((_, concurrency)) = capped.iter().find(|(id, _)| *id == source_id) {
+                return concurrency.max_concurrency;
+            }
+            if capped.is_empty() {
+                let (_, concurrency) = uncapped.iter().find(|(id, _)| *id == source_id).expect("source is in the share");
+                return fair(concurrency).max(1);
+            }
+
+            remaining -= capped.iter().map(|(_, c)| c.max_concurrency).sum::<usize>();
+            unresolved = uncapped;
+        }
+    }
+}
+
+/// Concurrent file operations shared by all running source syncs
+#[derive(Debug)]
+pub struct SyncBudget {
+    capacity: usize,
+    state: Mutex<BudgetState>,
+    changed: Notify,
+}
+
+impl SyncBudget {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity: capacity.max(1), state: Mutex::new(BudgetState::default()), changed: Notify::new() }
+    }
+
+    /// Records that a sync of `source_id` is running until the returned guard is dropped
+    pub fn register(self: &Arc<Self>, source_id: Uuid, concurrency: SourceConcurrency) -> SyncBudgetRegistration {
+        self.state.lock().unwrap().active.insert(source_id, concurrency);
+        self.changed.notify_waiters();
+        SyncBudgetRegistration { budget: self.clone(), source_id }
+    }
+
+    /// Waits until `source_id` may start another file operation. The operation counts
+    /// against the budget until the permit is dropped.
+    pub async fn acquire(self: &Arc<Self>, source_id: Uuid) -> SyncBudgetPermit {
+        loop {
+            // Subscribe before checking so a release in between is not missed
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            if let Some(permit) = self.try_acquire(source_id) {
+                return permit;
+            }
+            changed.await;
+        }
+    }
+
+    /// Takes a permit for `source_id` if both the budget and the source's share have room
+    pub fn try_acquire(self: &Arc<Self>, source_id: Uuid) -> Option<SyncBudgetPermit> {
+        let mut state = self.state.lock().unwrap();
+        let held = state.held.get(&source_id).copied().unwrap_or(0);
+        if state.total_held >= self.capacity || held >= state.share(self.capacity, source_id) {
+            return None;
+        }
+
+        *state.held.entry(source_id).or_insert(0) += 1;
+        state.total_held += 1;
+        Some(SyncBudgetPermit { budget: self.clone(), source_id })
+    }
+
+    fn release(&self, source_id: Uuid) {
+        let mut state = self.state.lock().unwrap();
+        if let Some(held) = state.held.get_mut(&source_id) {
+            *held -= 1;
+            if *held == 0 {
+                state.held.remove(&source_id);
+            }
+        }
+        state.total_held -= 1;
+        drop(state);
+        self.changed.notify_waiters();
+    }
+}
+
+/// A running sync's claim on the budget; dropping it gives the sync's share to the others
+#[derive(Debug)]
+pub struct SyncBudgetRegistration {
+    budget: Arc<SyncBudget>,
+    source_id: Uuid,
+}
+
+impl Drop for SyncBudgetRegistration {
+    fn drop(&mut self) {
+        self.budget.state.lock().unwrap().active.remove(&self.source_id);
+        self.budget.changed.notify_waiters();
+    }
+}
+
+/// One file operation of a source; returned to the budget when dropped
+#[derive(Debug)]
+pub struct SyncBudgetPermit {
+    budget: Arc<SyncBudget>,
+    source_id: Uuid,
+}
+
+impl Drop for SyncBudgetPermit {
+    fn drop(&mut self) {
+        self.budget.release(self.source_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normal(max_concurrency: usize) -> SourceConcurrency {
+        SourceConcurrency { max_concurrency, high_priority: false }
+    }
+
+    fn hold(budget: &Arc<SyncBudget>, source_id: Uuid) -> Vec<SyncBudgetPermit> {
+        std::iter::from_fn(|| budget.try_acquire(source_id)).collect()
+    }
+
+    #[test]
+    fn test_source_concurrency_from_config() {
+        assert_eq!(SourceConcurrency::from_source_config(&serde_json::json!({})), Ok(SourceConcurrency::default()));
+        assert_eq!(
+            SourceConcurrency::from_source_config(&serde_json::json!({ "max_concurrency": 12, "high_priority": true })),
+            Ok(SourceConcurrency { max_concurrency: 12, high_priority: true })
+        );
+        assert!(SourceConcurrency::from_source_config(&serde_json::json!({ "max_concurrency": 0 })).is_err());
+        assert!(SourceConcurrency::from_source_config(&serde_json::json!({ "max_concurrency": 65 })).is_err());
+        assert!(SourceConcurrency::from_source_config(&serde_json::json!({ "max_concurrency": "4" })).is_err());
+        assert!(SourceConcurrency::from_source_config(&serde_json::json!({ "high_priority": "yes" })).is_err());
+    }
+
+    #[test]
+    fn test_large_source_leaves_room_for_others() {
+        let budget = Arc::new(SyncBudget::new(8));
+        let (large, small) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let _large_sync = budget.register(large, normal(64));
+        let mut large_permits = hold(&budget, large);
+        assert_eq!(large_permits.len(), 8, "a lone source may use the whole budget");
+
+        let _small_sync = budget.register(small, normal(64));
+        assert!(budget.try_acquire(small).is_none(), "the budget is still in use");
+
+        // Permits the large source gives back go to the small one until both have half
+        large_permits.truncate(4);
+        let small_permits = hold(&budget, small);
+        assert_eq!(small_permits.len(), 4);
+        assert!(budget.try_acquire(large).is_none());
+    }
+
+    #[test]
+    fn test_shares_follow_priority_and_limits() {
+        let budget = Arc::new(SyncBudget::new(11));
+        let (high, normal_source, capped) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        let _high_sync = budget.register(high, SourceConcurrency { max_concurrency: 64, high_priority: true });
+        let _normal_sync = budget.register(normal_source, normal(64));
+        let _capped_sync = budget.register(capped, normal(2));
+
+        // The capped source takes 2; the other 9 are split 2:1
+        assert_eq!(hold(&budget, capped).len(), 2);
+        assert_eq!(hold(&budget, high).len(), 6);
+        assert_eq!(hold(&budget, normal_source).len(), 3);
+        assert!(budget.try_acquire(high).is_none(), "the budget is exhausted");
+    }
+
+    #[tokio::test]
+    async fn test_waiting_source_gets_released_permit() {
+        let budget = Arc::new(SyncBudget::new(1));
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let _first_sync = budget.register(first, normal(5));
+        let _second_sync = budget.register(second, normal(5));
+
+        let permit = budget.acquire(first).await;
+        let waiting = tokio::spawn({
+            let budget = budget.clone();
+            async move { budget.acquire(second).await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!waiting.is_finished());
+
+        drop(permit);
+        let _second_permit = tokio::time::timeout(std::time::Duration::from_secs(1), waiting).await.unwrap().unwrap();
+    }
+}
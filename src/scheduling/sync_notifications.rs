@@ -0,0 +1,249 @@
+//! Notifications sent when a source sync run finishes.
+//!
+//! A run is reported with one summary built from its sync-run record, e.g.
+//! "Scans: 1,240 new files, 3 failed, 2.1 GB", rather than a notification per file. Users
+//! who want to hear about every file set `sync_notification_mode` to `per_file`. Runs that
+//! found nothing new and had no failures are not reported at all.
+
+use std::sync::Arc;
+use tracing::{error, warn};
+
+use crate::{
+    models::{CreateNotification, Source, SourceSyncRun, SyncNotificationMode, SyncRunOutcome},
+    AppState,
+};
+
+/// Most files notified one by one for a run; larger runs get the summary instead
+pub const MAX_PER_FILE_NOTIFICATIONS: i64 = 50;
+
+/// Notifies the owner of `source` about its latest sync run. `health_score` is the score of
+/// the validation that ran after the sync, if one did.
+pub async fn notify_sync_finished(state: &Arc<AppState>, source: &Source, health_score: Option<i32>) {
+    let run = match state.db.get_latest_source_sync_run(source.id).await {
+        Ok(Some(run)) => run,
+        Ok(None) => return,
+        Err(e) => {
+            error!("Failed to load the sync run of source {} for its notification: {}", source.name, e);
+            return;
+        }
+    };
+
+    let mode = match state.db.get_user_settings(source.user_id).await {
+        Ok(settings) => settings.map(|settings| settings.sync_notification_mode).unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to load notification settings of user {}: {}", source.user_id, e);
+            SyncNotificationMode::default()
+        }
+    };
+
+    let notifications = match mode {
+        SyncNotificationMode::Summary => summary_notification(source, &run, health_score).into_iter().collect(),
+        SyncNotificationMode::PerFile => per_file_notifications(state, source, &run, health_score).await,
+    };
+
+    for notification in notifications {
+        if let Err(e) = state.db.create_notification(source.user_id, &notification).await {
+            error!("Failed to create sync notification for source {}: {}", source.name, e);
+        }
+    }
+}
+
+/// One notification per new file. Failures are still reported together, and a run with
+/// more files than `MAX_PER_FILE_NOTIFICATIONS` falls back to the summary.
+async fn per_file_notifications(
+    state: &Arc<AppState>,
+    source: &Source,
+    run: &SourceSyncRun,
+    health_score: Option<i32>,
+) -> Vec<CreateNotification> {
+    let documents = match state.db.get_sync_run_documents(run, MAX_PER_FILE_NOTIFICATIONS + 1).await {
+        Ok(documents) => documents,
+        Err(e) => {
+            warn!("Failed to load the documents of sync run {}: {}", run.id, e);
+            return summary_notification(source, run, health_score).into_iter().collect();
+        }
+    };
+    if documents.len() as i64 > MAX_PER_FILE_NOTIFICATIONS {
+        return summary_notification(source, run, health_score).into_iter().collect();
+    }
+
+    let mut notifications: Vec<CreateNotification> = documents
+        .into_iter()
+        .map(|(document_id, filename)| CreateNotification {
+            notification_type: "success".to_string(),
+            title: "New File Synced".to_string(),
+            message: format!("{} was synced from {}", filename, source.name),
+            action_url: Some(format!("/documents/{}", document_id)),
+            metadata: Some(serde_json::json!({
+                "source_type": source.source_type.to_string(),
+                "source_id": source.id,
+                "sync_run_id": run.id,
+                "document_id": document_id,
+            })),
+        })
+        .collect();
+
+    if run.files_failed > 0 || run.outcome == SyncRunOutcome::Failed {
+        notifications.extend(summary_notification(source, run, health_score));
+    }
+    notifications
+}
+
+/// The summary of a finished run, or `None` for runs not worth a notification: cancelled
+/// runs and successful runs that neither added nor failed any file.
+pub fn summary_notification(source: &Source, run: &SourceSyncRun, health_score: Option<i32>) -> Option<CreateNotification> {
+    let failed = run.outcome == SyncRunOutcome::Failed;
+    match run.outcome {
+        SyncRunOutcome::Running | SyncRunOutcome::Cancelled => return None,
+        SyncRunOutcome::Success if run.files_downloaded == 0 && run.files_failed == 0 => return None,
+        _ => {}
+    }
+
+    let mut parts = vec![
+        count_phrase(run.files_downloaded, "new file", "new files"),
+        format!("{} failed", format_count(run.files_failed)),
+        format_bytes(run.bytes_transferred),
+    ];
+    if let Some(score) = health_score {
+        parts.push(format!("health score {}/100", score));
+    }
+    let mut message = format!("{}: {}", source.name, parts.join(", "));
+    if let Some(error) = run.error_message.as_deref().filter(|_| failed) {
+        message.push_str(&format!(". Sync failed: {}", error));
+    }
+
+    let (notification_type, title) = if failed {
+        ("error", "Source Sync Failed")
+    } else if run.files_failed > 0 {
+        ("warning", "Source Sync Completed With Errors")
+    } else {
+        ("success", "Source Sync Completed")
+    };
+
+    Some(CreateNotification {
+        notification_type: notification_type.to_string(),
+        title: title.to_string(),
+        message,
+        action_url: Some(if failed || run.files_failed > 0 { "/sources" } else { "/documents" }.to_string()),
+        metadata: Some(serde_json::json!({
+            "source_type": source.source_type.to_string(),
+            "source_id": source.id,
+            "sync_run_id": run.id,
+            "outcome": run.outcome,
+            "files_discovered": run.files_discovered,
+            "files_downloaded": run.files_downloaded,
+            "files_failed": run.files_failed,
+            "bytes_transferred": run.bytes_transferred,
+            "health_score": health_score,
+        })),
+    })
+}
+
+fn count_phrase(count: i64, singular: &str, plural: &str) -> String {
+    format!("{} {}", format_count(count), if count == 1 { singular } else { plural })
+}
+
+/// `1240` as `1,240`
+fn format_count(count: i64) -> String {
+    let digits = count.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    if count < 0 {
+        grouped.insert(0, '-');
+    }
+    grouped
+}
+
+/// Byte counts in the largest unit that keeps the number at least 1, e.g. `2.1 GB`
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+    let mut value = bytes.max(0) as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes.max(0))
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{SourceStatus, SourceType};
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn source() -> Source {
+        Source {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            name: "Scans".to_string(),
+            source_type: SourceType::WebDAV,
+            enabled: true,
+            config: serde_json::json!({}),
+            status: SourceStatus::Idle,
+            last_sync_at: None,
+            last_error: None,
+            last_error_at: None,
+            total_files_synced: 0,
+            total_files_pending: 0,
+            total_size_bytes: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            validation_status: None,
+            last_validation_at: None,
+            validation_score: None,
+            validation_issues: None,
+        }
+    }
+
+    fn run(outcome: SyncRunOutcome, downloaded: i64, failed: i64, bytes: i64) -> SourceSyncRun {
+        SourceSyncRun {
+            id: Uuid::new_v4(),
+            source_id: Uuid::new_v4(),
+            started_at: Utc::now(),
+            finished_at: Some(Utc::now()),
+            files_discovered: downloaded + failed,
+            files_downloaded: downloaded,
+            files_failed: failed,
+            bytes_transferred: bytes,
+            outcome,
+            error_message: None,
+        }
+    }
+
+    #[test]
+    fn test_summary_reports_totals_and_escalates_on_failures() {
+        let source = source();
+
+        let clean = summary_notification(&source, &run(SyncRunOutcome::Success, 1240, 0, 2_254_857_830), Some(92)).unwrap();
+        assert_eq!(clean.notification_type, "success");
+        assert_eq!(clean.message, "Scans: 1,240 new files, 0 failed, 2.1 GB, health score 92/100");
+
+        let partial = summary_notification(&source, &run(SyncRunOutcome::Success, 1, 3, 512), None).unwrap();
+        assert_eq!(partial.notification_type, "warning");
+        assert_eq!(partial.message, "Scans: 1 new file, 3 failed, 512 B");
+
+        let mut broken = run(SyncRunOutcome::Failed, 0, 0, 0);
+        broken.error_message = Some("connection refused".to_string());
+        let broken = summary_notification(&source, &broken, None).unwrap();
+        assert_eq!(broken.notification_type, "error");
+        assert!(broken.message.ends_with("Sync failed: connection refused"), "{}", broken.message);
+    }
+
+    #[test]
+    fn test_quiet_and_cancelled_runs_are_not_reported() {
+        let source = source();
+        assert!(summary_notification(&source, &run(SyncRunOutcome::Success, 0, 0, 0), Some(100)).is_none());
+        assert!(summary_notification(&source, &run(SyncRunOutcome::Cancelled, 12, 0, 4096), None).is_none());
+    }
+}
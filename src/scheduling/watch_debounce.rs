@@ -0,0 +1,209 @@
+//! Debouncing for watch folder events.
+//!
+//! Applications that write large files in chunks, or write to a temporary name
+//! and rename, produce a burst of events before the file is complete. Paths are
+//! buffered here and only released once their size and modification time have
+//! stayed unchanged for the configured quiet period, so a create followed by
+//! any number of modifies results in a single ingestion.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Default quiet period when `FILE_STABILITY_CHECK_MS` is not set
+pub const DEFAULT_QUIET_PERIOD_MS: u64 = 500;
+
+/// Suffixes written by browsers, download managers and editors while a file
+/// is still in progress
+const TEMPORARY_SUFFIXES: &[&str] = &[".part", ".partial", ".crdownload", ".download", ".tmp", ".temp", ".swp"];
+
+/// Size and modification time used to decide whether a file is still changing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSnapshot {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl FileSnapshot {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        Some(Self { size: metadata.len(), modified: metadata.modified().ok() })
+    }
+}
+
+#[derive(Debug)]
+struct PendingFile {
+    snapshot: Option<FileSnapshot>,
+    stable_since: Instant,
+}
+
+/// Buffers changed paths until they have been stable for the quiet period
+#[derive(Debug)]
+pub struct WatchDebouncer {
+    quiet_period: Duration,
+    pending: HashMap<PathBuf, PendingFile>,
+}
+
+impl WatchDebouncer {
+    pub fn new(quiet_period: Duration) -> Self {
+        Self { quiet_period, pending: HashMap::new() }
+    }
+
+    pub fn quiet_period(&self) -> Duration {
+        self.quiet_period
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Record a change to `path`. Repeated events for the same path restart
+    /// its quiet period instead of queueing another ingestion.
+    pub fn record(&mut self, path: PathBuf, now: Instant) {
+        let snapshot = FileSnapshot::read(&path);
+        self.pending.insert(path, PendingFile { snapshot, stable_since: now });
+    }
+
+    /// Re-check every pending path and return the ones whose size and
+    /// modification time have not changed for the quiet period. Paths that
+    /// no longer exist (deleted, or renamed away from a temporary name) are
+    /// dropped.
+    pub fn take_ready(&mut self, now: Instant) -> Vec<PathBuf> {
+        let mut ready = Vec::new();
+        self.pending.retain(|path, pending| {
+            let Some(current) = FileSnapshot::read(path) else {
+                return false;
+            };
+            if pending.snapshot != Some(current) {
+                pending.snapshot = Some(current);
+                pending.stable_since = now;
+                return true;
+            }
+            if now.saturating_duration_since(pending.stable_since) >= self.quiet_period {
+                ready.push(path.clone());
+                return false;
+            }
+            true
+        });
+        ready.sort();
+        ready
+    }
+}
+
+/// Whether a file name matches a well-known temporary or in-progress pattern:
+/// dotfiles, Office lock files (`~$report.docx`), editor backups and partial
+/// downloads.
+pub fn is_temporary_file(filename: &str) -> bool {
+    if filename.is_empty() || filename.starts_with('.') || filename.starts_with('~') || filename.ends_with('~') {
+        return true;
+    }
+    let lower = filename.to_lowercase();
+    TEMPORARY_SUFFIXES.iter().any(|suffix| lower.ends_with(suffix))
+}
+
+/// Whether the file at `path` matches a temporary pattern
+pub fn is_temporary_path(path: &Path) -> bool {
+    path.file_name().and_then(|n| n.to_str()).is_none_or(is_temporary_file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    const QUIET: Duration = Duration::from_millis(200);
+
+    #[test]
+    fn test_file_is_released_after_quiet_period() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("scan.pdf");
+        fs::write(&path, b"%PDF-1.4").unwrap();
+
+        let start = Instant::now();
+        let mut debouncer = WatchDebouncer::new(QUIET);
+        debouncer.record(path.clone(), start);
+
+        assert!(debouncer.take_ready(start + QUIET / 2).is_empty());
+        assert_eq!(debouncer.take_ready(start + QUIET), vec![path]);
+        assert!(debouncer.is_empty());
+    }
+
+    #[test]
+    fn test_growing_file_restarts_quiet_period() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("large.pdf");
+        fs::write(&path, b"%PDF-1.4\n").unwrap();
+
+        let start = Instant::now();
+        let mut debouncer = WatchDebouncer::new(QUIET);
+        debouncer.record(path.clone(), start);
+
+        // Another chunk lands before the quiet period is up
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"more data").unwrap();
+        let grew_at = start + QUIET;
+        assert!(debouncer.take_ready(grew_at).is_empty());
+
+        assert!(debouncer.take_ready(grew_at + QUIET / 2).is_empty());
+        assert_eq!(debouncer.take_ready(grew_at + QUIET), vec![path]);
+    }
+
+    #[test]
+    fn test_create_and_modify_events_are_coalesced() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, b"hello").unwrap();
+
+        let start = Instant::now();
+        let mut debouncer = WatchDebouncer::new(QUIET);
+        for offset in 0..5 {
+            debouncer.record(path.clone(), start + Duration::from_millis(offset * 10));
+        }
+        assert_eq!(debouncer.len(), 1);
+
+        let ready = debouncer.take_ready(start + Duration::from_millis(40) + QUIET);
+        assert_eq!(ready, vec![path]);
+        assert!(debouncer.take_ready(start + QUIET * 10).is_empty());
+    }
+
+    #[test]
+    fn test_vanished_files_are_dropped() {
+        let dir = TempDir::new().unwrap();
+        let temp = dir.path().join("report.pdf.part");
+        fs::write(&temp, b"%PDF").unwrap();
+
+        let start = Instant::now();
+        let mut debouncer = WatchDebouncer::new(QUIET);
+        debouncer.record(temp.clone(), start);
+        fs::rename(&temp, dir.path().join("report.pdf")).unwrap();
+
+        assert!(debouncer.take_ready(start + QUIET).is_empty());
+        assert!(debouncer.is_empty());
+    }
+
+    #[test]
+    fn test_temporary_file_patterns() {
+        for name in ["report.pdf.part", "~$budget.xlsx", "upload.TMP", "scan.temp", ".DS_Store",
+                     "video.crdownload", "notes.txt~", ".notes.txt.swp", ""] {
+            assert!(is_temporary_file(name), "{} should be treated as temporary", name);
+        }
+        for name in ["report.pdf", "budget.xlsx", "partial-results.txt", "template.docx"] {
+            assert!(!is_temporary_file(name), "{} should be ingested", name);
+        }
+    }
+
+    #[test]
+    fn test_temporary_paths() {
+        assert!(is_temporary_path(Path::new("/watch/inbox/~$report.docx")));
+        assert!(is_temporary_path(Path::new("/watch/inbox/.report.pdf")));
+        assert!(!is_temporary_path(Path::new("/home/user/.local/watch/report.pdf")));
+    }
+}
@@ -1,10 +1,10 @@
 use anyhow::Result;
-use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tokio::sync::mpsc;
-use tokio::time::{interval, sleep};
+use tokio::time::{interval, sleep, MissedTickBehavior};
 use tracing::{debug, error, info, warn};
 use walkdir::WalkDir;
 use chrono::{DateTime, Utc};
@@ -13,7 +13,10 @@ use crate::{
     config::Config, 
     db::Database, 
     services::{file_service::FileService, user_watch_service::UserWatchService}, 
-    scheduling::user_watch_manager::UserWatchManager,
+    scheduling::{
+        user_watch_manager::UserWatchManager,
+        watch_debounce::{is_temporary_file, is_temporary_path, WatchDebouncer, DEFAULT_QUIET_PERIOD_MS},
+    },
     ingestion::document_ingestion::{DocumentIngestionService, IngestionResult, DeduplicationPolicy},
     ocr::queue::OcrQueueService,
     models::FileIngestionInfo,
@@ -37,7 +40,7 @@ pub async fn start_folder_watcher(config: Config, db: Database) -> Result<()> {
     info!("Upload folder canonical path: {:?}", upload_canonical);
     
     // Initialize services with shared database
-    let file_service = FileService::new(config.upload_path.clone());
+    let file_service = FileService::new(config.upload_path.clone()).with_storage_layout(config.storage_layout);
     let queue_service = OcrQueueService::new(db.clone(), db.get_pool().clone(), 1);
     
     // Initialize user watch components if enabled
@@ -157,22 +160,46 @@ async fn start_notify_watcher(
         }
     }
     
-    while let Some(res) = rx.recv().await {
-        match res {
-            Ok(event) => {
-                for path in event.paths {
+    let mut debouncer = WatchDebouncer::new(quiet_period(&config));
+    let mut ticker = interval((debouncer.quiet_period() / 4).clamp(Duration::from_millis(50), Duration::from_secs(1)));
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    
+    loop {
+        tokio::select! {
+            res = rx.recv() => match res {
+                Some(Ok(event)) => {
+                    // Reads and metadata-only access don't change the file contents
+                    if matches!(event.kind, EventKind::Access(_)) {
+                        continue;
+                    }
+                    for path in event.paths {
+                        if is_temporary_path(&path) {
+                            continue;
+                        }
+                        debouncer.record(path, Instant::now());
+                    }
+                }
+                Some(Err(e)) => error!("Watch error: {:?}", e),
+                None => break,
+            },
+            _ = ticker.tick(), if !debouncer.is_empty() => {
+                for path in debouncer.take_ready(Instant::now()) {
                     if let Err(e) = process_file(&path, &db, &file_service, &queue_service, &config, &user_watch_manager).await {
                         error!("Failed to process file {:?}: {}", path, e);
                     }
                 }
             }
-            Err(e) => error!("Watch error: {:?}", e),
         }
     }
     
     Ok(())
 }
 
+/// How long a file's size and modification time must stay unchanged before it is ingested
+fn quiet_period(config: &Config) -> Duration {
+    Duration::from_millis(config.file_stability_check_ms.unwrap_or(DEFAULT_QUIET_PERIOD_MS))
+}
+
 async fn start_polling_watcher(
     config: Config,
     db: Database,
@@ -183,17 +210,19 @@ async fn start_polling_watcher(
     info!("Started polling-based watcher on: {}", config.watch_folder);
     
     let mut known_files: HashSet<(PathBuf, SystemTime)> = HashSet::new();
+    let mut debouncer = WatchDebouncer::new(quiet_period(&config));
     let mut interval = interval(Duration::from_secs(config.watch_interval_seconds.unwrap_or(30)));
     
     // Initial scan of global watch directory
     info!("Starting initial scan of global watch directory: {}", config.watch_folder);
-    scan_directory(&config.watch_folder, &mut known_files, &db, &file_service, &queue_service, &config, &user_watch_manager).await?;
+    scan_directory(&config.watch_folder, &mut known_files, &mut debouncer).await?;
     
     // Initial scan of user watch directories if enabled
     if config.enable_per_user_watch {
         info!("Starting initial scan of user watch directories: {}", config.user_watch_base_dir);
-        scan_directory(&config.user_watch_base_dir, &mut known_files, &db, &file_service, &queue_service, &config, &user_watch_manager).await?;
+        scan_directory(&config.user_watch_base_dir, &mut known_files, &mut debouncer).await?;
     }
+    process_stable_files(&mut debouncer, &db, &file_service, &queue_service, &config, &user_watch_manager).await;
     
     info!("Initial scan completed. Found {} files to track", known_files.len());
     
@@ -201,29 +230,27 @@ async fn start_polling_watcher(
         interval.tick().await;
         
         // Scan global watch directory
-        if let Err(e) = scan_directory(&config.watch_folder, &mut known_files, &db, &file_service, &queue_service, &config, &user_watch_manager).await {
+        if let Err(e) = scan_directory(&config.watch_folder, &mut known_files, &mut debouncer).await {
             error!("Error during global watch directory scan: {}", e);
             // Continue polling even if one scan fails
         }
         
         // Scan user watch directories if enabled
         if config.enable_per_user_watch {
-            if let Err(e) = scan_directory(&config.user_watch_base_dir, &mut known_files, &db, &file_service, &queue_service, &config, &user_watch_manager).await {
+            if let Err(e) = scan_directory(&config.user_watch_base_dir, &mut known_files, &mut debouncer).await {
                 error!("Error during user watch directory scan: {}", e);
                 // Continue polling even if one scan fails
             }
         }
+        
+        process_stable_files(&mut debouncer, &db, &file_service, &queue_service, &config, &user_watch_manager).await;
     }
 }
 
 async fn scan_directory(
     watch_folder: &str,
     known_files: &mut HashSet<(PathBuf, SystemTime)>,
-    db: &Database,
-    file_service: &FileService,
-    queue_service: &OcrQueueService,
-    config: &Config,
-    user_watch_manager: &Option<UserWatchManager>,
+    debouncer: &mut WatchDebouncer,
 ) -> Result<()> {
     let mut current_files: HashSet<(PathBuf, SystemTime)> = HashSet::new();
     
@@ -242,15 +269,10 @@ async fn scan_directory(
                     let file_info = (path.clone(), modified);
                     current_files.insert(file_info.clone());
                     
-                    // Check if this is a new file or modified file
-                    if !known_files.contains(&file_info) {
-                        // Wait a bit to ensure file is fully written
-                        if is_file_stable(&path).await {
-                            debug!("Found new/modified file: {:?}", path);
-                            if let Err(e) = process_file(&path, db, file_service, queue_service, config, user_watch_manager).await {
-                                error!("Failed to process file {:?}: {}", path, e);
-                            }
-                        }
+                    // New or modified files wait in the debouncer until they stop changing
+                    if !known_files.contains(&file_info) && !is_temporary_path(&path) {
+                        debug!("Found new/modified file: {:?}", path);
+                        debouncer.record(path, Instant::now());
                     }
                 }
             }
@@ -263,22 +285,26 @@ async fn scan_directory(
     Ok(())
 }
 
-async fn is_file_stable(path: &Path) -> bool {
-    // Check if file size is stable (not currently being written)
-    if let Ok(metadata1) = tokio::fs::metadata(path).await {
-        let size1 = metadata1.len();
-        
-        // Wait a short time
-        sleep(Duration::from_millis(500)).await;
-        
-        if let Ok(metadata2) = tokio::fs::metadata(path).await {
-            let size2 = metadata2.len();
-            return size1 == size2;
-        }
+/// Ingest the files found by a scan once they have been stable for the quiet
+/// period. Files still being written stay pending until the next scan.
+async fn process_stable_files(
+    debouncer: &mut WatchDebouncer,
+    db: &Database,
+    file_service: &FileService,
+    queue_service: &OcrQueueService,
+    config: &Config,
+    user_watch_manager: &Option<UserWatchManager>,
+) {
+    if debouncer.is_empty() {
+        return;
     }
+    sleep(debouncer.quiet_period()).await;
     
-    // If we can't read metadata, assume it's not stable
-    false
+    for path in debouncer.take_ready(Instant::now()) {
+        if let Err(e) = process_file(&path, db, file_service, queue_service, config, user_watch_manager).await {
+            error!("Failed to process file {:?}: {}", path, e);
+        }
+    }
 }
 
 async fn process_file(
@@ -300,10 +326,7 @@ async fn process_file(
         .to_string();
     
     // Skip hidden files, temporary files, and system files
-    if filename.starts_with('.') || 
-       filename.starts_with('~') || 
-       filename.ends_with(".tmp") ||
-       filename.ends_with(".temp") ||
+    if is_temporary_file(&filename) ||
        filename.contains("$RECYCLE.BIN") ||
        filename.contains("System Volume Information") {
         debug!("Skipping system/temporary file: {}", filename);
@@ -446,9 +469,11 @@ async fn process_file(
     let mut file_info = extract_file_info_from_path(path).await?;
     
     // Extract content-based metadata
-    if let Ok(Some(content_metadata)) = crate::metadata_extraction::extract_content_metadata(&file_data, &file_info.mime_type, &file_info.name).await {
-        file_info.metadata = Some(content_metadata);
-    }
+    crate::metadata_extraction::enrich_file_info(
+        &mut file_info,
+        &file_data,
+        crate::metadata_extraction::MetadataExtractionOptions::from_config(config),
+    ).await;
     
     // Use the unified ingestion service for consistent deduplication
     let ingestion_service = DocumentIngestionService::new(db.clone(), file_service.clone());
@@ -459,7 +484,7 @@ async fn process_file(
         .map_err(|e| anyhow::anyhow!(e))?;
 
     match result {
-        IngestionResult::Created(doc) => {
+        IngestionResult::Created(doc) | IngestionResult::NewVersion(doc) => {
             info!("Created new document for watch folder file {}: {}", file_info.name, doc.id);
             
             // Enqueue for OCR processing with priority based on file size and type
@@ -274,6 +274,7 @@ impl WebDAVScheduler {
             file_extensions: settings.webdav_file_extensions.clone(),
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         })
     }
 
@@ -0,0 +1,96 @@
+/// What a `Range` request header asks of a file of a known size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeRequest {
+    /// No usable range: serve the whole file. Also used for multi-range requests,
+    /// which are answered with the full body instead of a multipart response.
+    Full,
+    /// Serve bytes `start..=end`
+    Partial { start: u64, end: u64 },
+    /// The range lies outside the file; answer 416
+    Unsatisfiable,
+}
+
+impl RangeRequest {
+    /// Value of the `Content-Range` header for this answer, if it has one
+    pub fn content_range(&self, file_size: u64) -> Option<String> {
+        match self {
+            RangeRequest::Full => None,
+            RangeRequest::Partial { start, end } => Some(format!("bytes {}-{}/{}", start, end, file_size)),
+            RangeRequest::Unsatisfiable => Some(format!("bytes */{}", file_size)),
+        }
+    }
+}
+
+/// Interprets a `Range` header against a file of `file_size` bytes. Malformed headers
+/// and units other than `bytes` are ignored, as RFC 9110 allows, so the whole file is
+/// served; an end past the file is clamped to its last byte.
+pub fn parse_range_header(header: Option<&str>, file_size: u64) -> RangeRequest {
+    let Some(spec) = header.and_then(|header| header.trim().strip_prefix("bytes=")) else {
+        return RangeRequest::Full;
+    };
+    if spec.contains(',') {
+        return RangeRequest::Full;
+    }
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return RangeRequest::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // Suffix range: the last `end` bytes
+        return match end.parse::<u64>() {
+            Ok(0) => RangeRequest::Unsatisfiable,
+            Ok(_) if file_size == 0 => RangeRequest::Unsatisfiable,
+            Ok(length) => RangeRequest::Partial { start: file_size.saturating_sub(length), end: file_size - 1 },
+            Err(_) => RangeRequest::Full,
+        };
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return RangeRequest::Full;
+    };
+    let end = if end.is_empty() {
+        None
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => Some(end),
+            _ => return RangeRequest::Full,
+        }
+    };
+
+    if start >= file_size {
+        return RangeRequest::Unsatisfiable;
+    }
+    RangeRequest::Partial { start, end: end.map_or(file_size - 1, |end| end.min(file_size - 1)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_single_ranges() {
+        assert_eq!(parse_range_header(Some("bytes=0-99"), 1000), RangeRequest::Partial { start: 0, end: 99 });
+        assert_eq!(parse_range_header(Some("bytes=500-"), 1000), RangeRequest::Partial { start: 500, end: 999 });
+        assert_eq!(parse_range_header(Some("bytes=-100"), 1000), RangeRequest::Partial { start: 900, end: 999 });
+        assert_eq!(parse_range_header(Some("bytes=-5000"), 1000), RangeRequest::Partial { start: 0, end: 999 });
+        assert_eq!(parse_range_header(Some("bytes=900-5000"), 1000), RangeRequest::Partial { start: 900, end: 999 });
+    }
+
+    #[test]
+    fn test_unsatisfiable_ranges() {
+        assert_eq!(parse_range_header(Some("bytes=1000-"), 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range_header(Some("bytes=-0"), 1000), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range_header(Some("bytes=0-"), 0), RangeRequest::Unsatisfiable);
+        assert_eq!(RangeRequest::Unsatisfiable.content_range(1000).as_deref(), Some("bytes */1000"));
+    }
+
+    #[test]
+    fn test_ranges_served_as_full_body() {
+        assert_eq!(parse_range_header(None, 1000), RangeRequest::Full);
+        assert_eq!(parse_range_header(Some("bytes=0-1,5-9"), 1000), RangeRequest::Full);
+        assert_eq!(parse_range_header(Some("items=0-1"), 1000), RangeRequest::Full);
+        assert_eq!(parse_range_header(Some("bytes=9-2"), 1000), RangeRequest::Full);
+        assert_eq!(parse_range_header(Some("bytes=abc"), 1000), RangeRequest::Full);
+    }
+}
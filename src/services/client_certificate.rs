@@ -0,0 +1,195 @@
+use anyhow::{anyhow, bail, Context, Result};
+use base64ct::{Base64, Encoding};
+use p12_keystore::KeyStore;
+use rustls::{
+    crypto::ring::default_provider,
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer},
+    sign::CertifiedKey,
+    InconsistentKeys,
+};
+
+use crate::models::ClientCertificateConfig;
+use crate::services::source_secrets;
+
+/// A client certificate, its intermediates and its private key, DER-encoded as rustls takes them
+pub struct ClientIdentity {
+    pub certificate_chain: Vec<CertificateDer<'static>>,
+    pub private_key: PrivateKeyDer<'static>,
+}
+
+impl ClientIdentity {
+    /// The chain followed by the key as PEM blocks, the form reqwest's `Identity::from_pem` reads
+    pub fn to_pem(&self) -> Vec<u8> {
+        let mut pem = String::new();
+        for certificate in &self.certificate_chain {
+            pem.push_str(&pem_block("CERTIFICATE", certificate));
+        }
+        let label = match &self.private_key {
+            PrivateKeyDer::Pkcs1(_) => "RSA PRIVATE KEY",
+            PrivateKeyDer::Sec1(_) => "EC PRIVATE KEY",
+            _ => "PRIVATE KEY",
+        };
+        pem.push_str(&pem_block(label, self.private_key.secret_der()));
+        pem.into_bytes()
+    }
+}
+
+fn pem_block(label: &str, der: &[u8]) -> String {
+    let encoded = Base64::encode_string(der);
+    let mut block = format!("-----BEGIN {}-----\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        block.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+        block.push('\n');
+    }
+    block.push_str(&format!("-----END {}-----\n", label));
+    block
+}
+
+/// Loads the configured client certificate. Returns `None` if no client certificate is
+/// configured. Stored configs must be decrypted with `SourceSecrets::decrypt_config` first.
+pub fn load_client_identity(config: &ClientCertificateConfig) -> Result<Option<ClientIdentity>> {
+    if !config.is_configured() {
+        return Ok(None);
+    }
+
+    let value = |field: &Option<String>| -> Result<Option<String>> {
+        match field.as_deref().filter(|value| !value.trim().is_empty()) {
+            Some(value) if source_secrets::is_encrypted(value) => {
+                bail!("The client certificate is still encrypted; the stored source config was not decrypted")
+            }
+            value => Ok(value.map(str::to_string)),
+        }
+    };
+
+    let (chain, key) = if let Some(bundle) = value(&config.client_pkcs12)? {
+        let der = Base64::decode_vec(&bundle.split_whitespace().collect::<String>())
+            .map_err(|_| anyhow!("client_pkcs12 must be a base64-encoded PKCS#12 file"))?;
+        let password = value(&config.client_pkcs12_password)?.unwrap_or_default();
+        let store = KeyStore::from_pkcs12(&der, &password)
+            .context("Could not open client_pkcs12; check client_pkcs12_password")?;
+
+        let (_, entry) = store
+            .private_key_chain()
+            .ok_or_else(|| anyhow!("client_pkcs12 contains no certificate with a private key"))?;
+        let chain: Vec<_> = entry
+            .chain()
+            .iter()
+            .map(|certificate| CertificateDer::from(certificate.as_der().to_vec()))
+            .collect();
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(entry.key().to_vec()));
+        (chain, key)
+    } else {
+        let certificate = value(&config.client_certificate)?
+            .ok_or_else(|| anyhow!("client_key is set but client_certificate is missing"))?;
+        let key = value(&config.client_key)?
+            .ok_or_else(|| anyhow!("client_certificate is set but client_key is missing"))?;
+
+        let chain = CertificateDer::pem_slice_iter(certificate.as_bytes())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| anyhow!("client_certificate is not a PEM certificate: {}", e))?;
+        let key = PrivateKeyDer::from_pem_slice(key.as_bytes())
+            .map_err(|e| anyhow!("client_key is not an unencrypted PEM private key: {}", e))?;
+        (chain, key)
+    };
+
+    if chain.is_empty() {
+        bail!("The client certificate chain contains no certificate");
+    }
+
+    let signing_key = default_provider()
+        .key_provider
+        .load_private_key(key.clone_key())
+        .map_err(|e| anyhow!("Unsupported client private key: {}", e))?;
+    match CertifiedKey::new(chain.clone(), signing_key).keys_match() {
+        Ok(()) | Err(rustls::Error::InconsistentKeys(InconsistentKeys::Unknown)) => {}
+        Err(rustls::Error::InconsistentKeys(InconsistentKeys::KeyMismatch)) => {
+            bail!("The client private key does not belong to the client certificate")
+        }
+        Err(e) => bail!("Invalid client certificate: {}", e),
+    }
+
+    Ok(Some(ClientIdentity { certificate_chain: chain, private_key: key }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::source_secrets::SourceSecrets;
+    use p12_keystore::{Certificate, KeyStoreEntry, PrivateKeyChain};
+
+    fn self_signed(common_name: &str) -> rcgen::CertifiedKey {
+        rcgen::generate_simple_self_signed(vec![common_name.to_string()]).unwrap()
+    }
+
+    fn pem_config(certified: &rcgen::CertifiedKey, key: &rcgen::KeyPair) -> ClientCertificateConfig {
+        ClientCertificateConfig {
+            client_certificate: Some(certified.cert.pem()),
+            client_key: Some(key.serialize_pem()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_nothing_configured() {
+        assert!(load_client_identity(&ClientCertificateConfig::default()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_pem_pair() {
+        let certified = self_signed("readur-client");
+        let identity = load_client_identity(&pem_config(&certified, &certified.key_pair)).unwrap().unwrap();
+        assert_eq!(identity.certificate_chain, vec![certified.cert.der().clone()]);
+        assert_eq!(identity.private_key.secret_der(), certified.key_pair.serialize_der().as_slice());
+
+        // The PEM written for the HTTP client reads back as the same identity
+        let pem = identity.to_pem();
+        assert_eq!(CertificateDer::pem_slice_iter(&pem).count(), 1);
+        assert_eq!(PrivateKeyDer::from_pem_slice(&pem).unwrap().secret_der(), identity.private_key.secret_der());
+
+        // Stored configs hold the values encrypted and are only read once decrypted
+        let mut stored = pem_config(&certified, &certified.key_pair);
+        stored.client_key = Some(SourceSecrets::new("test-secret").encrypt(stored.client_key.as_deref().unwrap()).unwrap());
+        let error = load_client_identity(&stored).err().unwrap();
+        assert!(error.to_string().contains("still encrypted"), "{}", error);
+    }
+
+    #[test]
+    fn test_pkcs12_bundle() {
+        let certified = self_signed("readur-client");
+        let chain = PrivateKeyChain::new(
+            certified.key_pair.serialize_der(),
+            [1u8; 20],
+            vec![Certificate::from_der(certified.cert.der()).unwrap()],
+        );
+        let mut store = KeyStore::new();
+        store.add_entry("readur", KeyStoreEntry::PrivateKeyChain(chain));
+        let bundle = store.writer("s3cret").write().unwrap();
+
+        let config = ClientCertificateConfig {
+            client_pkcs12: Some(Base64::encode_string(&bundle)),
+            client_pkcs12_password: Some("s3cret".to_string()),
+            ..Default::default()
+        };
+        let identity = load_client_identity(&config).unwrap().unwrap();
+        assert_eq!(identity.certificate_chain, vec![certified.cert.der().clone()]);
+
+        let wrong_password = ClientCertificateConfig { client_pkcs12_password: Some("guess".to_string()), ..config };
+        let error = load_client_identity(&wrong_password).err().unwrap();
+        assert!(error.to_string().contains("client_pkcs12_password"), "{}", error);
+    }
+
+    #[test]
+    fn test_invalid_configurations() {
+        let certified = self_signed("readur-client");
+        let other = self_signed("someone-else");
+
+        let missing_key = ClientCertificateConfig { client_key: None, ..pem_config(&certified, &certified.key_pair) };
+        assert!(load_client_identity(&missing_key).is_err());
+
+        let error = load_client_identity(&pem_config(&certified, &other.key_pair)).err().unwrap();
+        assert!(error.to_string().contains("does not belong"), "{}", error);
+
+        let garbage = ClientCertificateConfig { client_pkcs12: Some("not base64!".to_string()), ..Default::default() };
+        assert!(load_client_identity(&garbage).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use anyhow::Result;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::info;
+
+use crate::{
+    models::{DocumentRun, MaintenanceDetails, MaintenanceOperation, MaintenanceStep},
+    services::document_runs::DocumentRunTracker,
+    AppState,
+};
+
+/// The GIN indexes behind full-text and fuzzy document search. Frequent OCR updates
+/// bloat them over time, which slows every search.
+const SEARCH_INDEXES: &[&str] = &[
+    "idx_documents_content_search",
+    "idx_documents_content_trgm",
+    "idx_documents_filename_trgm",
+    "idx_documents_tags",
+];
+
+/// The tables that see the most updates and deletes
+const VACUUM_TABLES: &[&str] = &["documents", "ocr_queue"];
+
+/// Rebuilds the document search indexes and vacuums the busiest tables. Only the
+/// non-blocking variants are used: indexes are rebuilt with `REINDEX ... CONCURRENTLY`
+/// and tables get a plain `VACUUM (ANALYZE)`, never `VACUUM FULL`, so reads and writes
+/// carry on while a run is in progress.
+#[derive(Clone)]
+pub struct DatabaseMaintenanceService {
+    state: Arc<AppState>,
+}
+
+impl DatabaseMaintenanceService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// The statements a run of `operation` executes, in order. Search indexes that don't
+    /// exist in this database are left out.
+    pub async fn plan(&self, operation: MaintenanceOperation) -> Result<Vec<MaintenanceStep>> {
+        let statements = match operation {
+            MaintenanceOperation::Reindex => {
+                let indexes = self.state.db.get_existing_indexes("documents", SEARCH_INDEXES).await?;
+                indexes.iter()
+                    .map(|index| format!("REINDEX INDEX CONCURRENTLY {}", index))
+                    // The planner needs fresh statistics to pick the rebuilt indexes
+                    .chain(std::iter::once("ANALYZE documents".to_string()))
+                    .collect::<Vec<_>>()
+            }
+            MaintenanceOperation::Vacuum => VACUUM_TABLES.iter()
+                .map(|table| format!("VACUUM (ANALYZE) {}", table))
+                .collect(),
+        };
+
+        Ok(statements.into_iter()
+            .map(|statement| MaintenanceStep { statement, duration_ms: None })
+            .collect())
+    }
+
+    /// Executes the steps of a run that was already created, then closes the run.
+    /// Progress is registered with the sync progress tracker under the run id.
+    pub async fn run(&self, run: DocumentRun<MaintenanceDetails>) {
+        let mut tracker = DocumentRunTracker::start(self.state.clone(), &run);
+        tracker.progress.add_files_found(run.details.steps.len());
+        info!("Starting maintenance run {} ({} steps)", run.id, run.details.steps.len());
+
+        let result = self.run_steps(&mut tracker).await;
+        let details = tracker.finish(&result).await;
+
+        let total_ms: i64 = details.steps.iter().filter_map(|step| step.duration_ms).sum();
+        info!(
+            "Maintenance run {} finished: {} of {} steps in {} ms",
+            run.id, details.completed_steps, details.steps.len(), total_ms
+        );
+    }
+
+    async fn run_steps(&self, tracker: &mut DocumentRunTracker<MaintenanceDetails>) -> Result<()> {
+        for index in 0..tracker.details.steps.len() {
+            let statement = tracker.details.steps[index].statement.clone();
+            tracker.progress.set_current_file(Some(&statement));
+
+            let started = Instant::now();
+            self.state.db.execute_maintenance_statement(&statement).await
+                .map_err(|e| anyhow::anyhow!("{} failed: {}", statement, e))?;
+            let duration_ms = started.elapsed().as_millis().min(i64::MAX as u128) as i64;
+            info!("Maintenance run {}: {} took {} ms", tracker.run_id(), statement, duration_ms);
+
+            tracker.details.steps[index].duration_ms = Some(duration_ms);
+            tracker.details.completed_steps += 1;
+            tracker.progress.add_files_processed(1, 0);
+            tracker.save_progress().await?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+//! Bookkeeping for files derived from documents: thumbnails, first-page renders and
+//! searchable PDFs.
+//!
+//! The files themselves are written by `FileService`; this tracks each one in the
+//! `derived_asset_cache` table with its size and last access, counts cache hits and misses,
+//! and evicts the least recently used files once the cache grows past its size cap. An
+//! evicted file is simply regenerated on its next request.
+
+use anyhow::Result;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::fs;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{config::Config, db::Database};
+
+/// Entries fetched per round while evicting
+const EVICTION_BATCH_SIZE: i64 = 100;
+
+/// The kinds of derived files that are cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivedAssetKind {
+    /// JPEG thumbnail of an image or text document
+    Thumbnail,
+    /// PNG render of a PDF's first page
+    PdfThumbnail,
+    /// PDF with an OCR text layer
+    SearchablePdf,
+}
+
+impl DerivedAssetKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DerivedAssetKind::Thumbnail => "thumbnail",
+            DerivedAssetKind::PdfThumbnail => "pdf_thumbnail",
+            DerivedAssetKind::SearchablePdf => "searchable_pdf",
+        }
+    }
+}
+
+/// Size and effectiveness of the cache, as reported by the metrics endpoints
+#[derive(Debug, Clone, Copy)]
+pub struct DerivedAssetCacheStats {
+    pub entries: i64,
+    pub size_bytes: i64,
+    /// Size cap in bytes; 0 when the cache is unbounded
+    pub max_bytes: u64,
+    /// Requests served from the cache since startup
+    pub hits: u64,
+    /// Requests that had to generate the file since startup
+    pub misses: u64,
+}
+
+impl DerivedAssetCacheStats {
+    /// Share of requests served from the cache, or `None` before the first request
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// Outcome of one eviction pass
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EvictionSummary {
+    pub evicted: u64,
+    pub freed_bytes: i64,
+}
+
+pub struct DerivedAssetCache {
+    db: Database,
+    max_bytes: u64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl DerivedAssetCache {
+    /// A cache capped at `max_bytes`; 0 tracks usage without ever evicting
+    pub fn new(db: Database, max_bytes: u64) -> Self {
+        Self {
+            db,
+            max_bytes,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// A cache capped at `DERIVED_ASSET_CACHE_MAX_MB`
+    pub fn from_config(db: Database, config: &Config) -> Self {
+        Self::new(db, config.derived_asset_cache_max_mb.saturating_mul(1024 * 1024))
+    }
+
+    /// Records that a cached file was served, marking it as recently used
+    pub async fn record_hit(&self, kind: DerivedAssetKind, document_id: Uuid, file_path: &str, size_bytes: u64) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.touch(kind, document_id, file_path, size_bytes).await;
+    }
+
+    /// Records that a file had to be generated and was written to the cache
+    pub async fn record_miss(&self, kind: DerivedAssetKind, document_id: Uuid, file_path: &str, size_bytes: u64) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.touch(kind, document_id, file_path, size_bytes).await;
+    }
+
+    async fn touch(&self, kind: DerivedAssetKind, document_id: Uuid, file_path: &str, size_bytes: u64) {
+        if let Err(e) = self.db.touch_derived_asset(kind, document_id, file_path, size_bytes as i64).await {
+            warn!("Failed to track cached {} of document {}: {}", kind.as_str(), document_id, e);
+        }
+    }
+
+    pub async fn stats(&self) -> Result<DerivedAssetCacheStats> {
+        let (entries, size_bytes) = self.db.get_derived_asset_cache_usage().await?;
+        Ok(DerivedAssetCacheStats {
+            entries,
+            size_bytes,
+            max_bytes: self.max_bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        })
+    }
+
+    /// Deletes least recently used files until the cache fits under its size cap.
+    ///
+    /// An entry is removed from the index before its file, so a request racing the eviction
+    /// at worst finds the file gone and generates it again.
+    pub async fn evict_to_limit(&self) -> Result<EvictionSummary> {
+        let mut summary = EvictionSummary::default();
+        if self.max_bytes == 0 {
+            return Ok(summary);
+        }
+
+        let (_, mut size_bytes) = self.db.get_derived_asset_cache_usage().await?;
+        while size_bytes as u64 > self.max_bytes {
+            let batch = self.db.get_least_recently_used_derived_assets(EVICTION_BATCH_SIZE).await?;
+            if batch.is_empty() {
+                break;
+            }
+
+            for entry in batch {
+                if size_bytes as u64 <= self.max_bytes {
+                    break;
+                }
+                self.db.delete_derived_asset(&entry.kind, entry.document_id).await?;
+                if let Err(e) = fs::remove_file(&entry.file_path).await {
+                    if e.kind() != std::io::ErrorKind::NotFound {
+                        warn!("Failed to remove evicted {} {}: {}", entry.kind, entry.file_path, e);
+                    }
+                }
+                size_bytes -= entry.size_bytes;
+                summary.evicted += 1;
+                summary.freed_bytes += entry.size_bytes;
+            }
+        }
+
+        if summary.evicted > 0 {
+            info!(
+                "Evicted {} derived files ({} bytes) to keep the cache under {} bytes",
+                summary.evicted, summary.freed_bytes, self.max_bytes
+            );
+        }
+        Ok(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hit_rate_is_unknown_until_first_request() {
+        let mut stats = DerivedAssetCacheStats { entries: 0, size_bytes: 0, max_bytes: 0, hits: 0, misses: 0 };
+        assert_eq!(stats.hit_rate(), None);
+
+        stats.hits = 3;
+        stats.misses = 1;
+        assert_eq!(stats.hit_rate(), Some(0.75));
+    }
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{
+    db::documents::DocumentBackfillRecord,
+    models::{BackfillDetails, DocumentRun},
+    services::{document_runs::DocumentRunTracker, file_service::FileService},
+    AppState,
+};
+
+/// Documents loaded and backfilled per batch; progress is saved after each batch
+const BACKFILL_BATCH_SIZE: i64 = 200;
+
+/// Fills in the file hash and OCR word count of documents ingested before those
+/// fields were recorded. Only derived fields are written: OCR is never re-run and
+/// values that are already set are left alone, so a run can be repeated safely.
+#[derive(Clone)]
+pub struct DocumentBackfillService {
+    state: Arc<AppState>,
+}
+
+impl DocumentBackfillService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Backfills every document of a run that was already created, then closes the run.
+    /// Progress is registered with the sync progress tracker under the run id.
+    pub async fn run(&self, run: DocumentRun<BackfillDetails>) {
+        let mut tracker = DocumentRunTracker::start(self.state.clone(), &run);
+        info!("Starting document backfill run {}", run.id);
+
+        let result = self.backfill_all_documents(&mut tracker).await;
+        let details = tracker.finish(&result).await;
+
+        info!(
+            "Document backfill run {} finished: {} checked, {} hashes and {} word counts filled, {} missing, {} hash conflicts",
+            run.id, details.checked, details.hashes_filled, details.word_counts_filled, details.missing, details.hash_conflicts
+        );
+    }
+
+    async fn backfill_all_documents(&self, tracker: &mut DocumentRunTracker<BackfillDetails>) -> Result<()> {
+        let file_service = FileService::new(self.state.config.upload_path.clone());
+        let mut after_id = None;
+
+        // Documents that can't be filled in still match, so the cursor keeps moving past them
+        loop {
+            let batch = self.state.db.get_document_backfill_records(after_id, BACKFILL_BATCH_SIZE).await?;
+            let Some(last) = batch.last() else { break };
+            after_id = Some(last.id);
+            tracker.progress.add_files_found(batch.len());
+
+            for document in &batch {
+                tracker.progress.set_current_file(Some(&document.file_path));
+                self.backfill_document(&file_service, document, &mut tracker.details).await?;
+                tracker.progress.add_files_processed(1, document.file_size.max(0) as u64);
+            }
+
+            tracker.save_progress().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn backfill_document(
+        &self,
+        file_service: &FileService,
+        document: &DocumentBackfillRecord,
+        counts: &mut BackfillDetails,
+    ) -> Result<()> {
+        counts.checked += 1;
+        let db = &self.state.db;
+
+        // The word count comes from the stored OCR text, so it doesn't need the file
+        if let (None, Some(text)) = (document.ocr_word_count, &document.ocr_text) {
+            let word_count = text.split_whitespace().count().min(i32::MAX as usize) as i32;
+            if db.fill_document_word_count(document.id, word_count).await? {
+                counts.word_counts_filled += 1;
+            }
+        }
+
+        if document.file_hash.is_some() {
+            return Ok(());
+        }
+
+        let (file_hash, _) = match file_service.hash_file(&document.file_path).await {
+            Ok(hashed) => hashed,
+            Err(e) => {
+                warn!("File of document {} is missing or unreadable, leaving it without a hash: {}", document.id, e);
+                counts.missing += 1;
+                return db.set_document_integrity_issue(document.id, "missing", None, None).await;
+            }
+        };
+
+        match db.fill_document_file_hash(document.id, &file_hash).await {
+            Ok(filled) => {
+                if filled {
+                    counts.hashes_filled += 1;
+                }
+                // Any issue recorded while the document had no hash to compare with is stale now
+                db.clear_document_integrity_issue(document.id).await
+            }
+            Err(e) => match e.downcast_ref::<sqlx::Error>().and_then(|e| e.as_database_error()) {
+                // Another of the owner's documents already has this content
+                Some(db_error) if db_error.is_unique_violation() => {
+                    warn!("File of document {} duplicates another document of its owner, leaving it without a hash", document.id);
+                    counts.hash_conflicts += 1;
+                    Ok(())
+                }
+                _ => Err(e),
+            },
+        }
+    }
+}
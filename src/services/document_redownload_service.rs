@@ -0,0 +1,143 @@
+/*!
+ * Document Re-download
+ *
+ * Repairs the stored file of a document that was synced from a source by fetching it
+ * again from the source path it was synced from. The download must hash to the
+ * document's stored hash before it replaces anything, so a failed or changed download
+ * never overwrites the local file.
+ */
+
+use anyhow::anyhow;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use thiserror::Error;
+use tracing::{info, warn};
+
+use crate::{
+    models::{Document, LocalFolderSourceConfig, S3SourceConfig, SmbSourceConfig, Source, SourceType, WebDAVSourceConfig},
+    services::{
+        file_service::FileService,
+        local_folder_service::LocalFolderService,
+        s3_service::S3Service,
+        smb_service::SmbService,
+        webdav::WebDAVService,
+    },
+    AppState,
+};
+
+/// Same priority as uploads: a repaired document is one the user is waiting for
+const REDOWNLOAD_OCR_PRIORITY: i32 = 5;
+
+/// Why a document could not be re-downloaded
+#[derive(Error, Debug)]
+pub enum RedownloadError {
+    #[error("Document was not synced from a source")]
+    NoSource,
+
+    #[error("The source the document was synced from no longer exists")]
+    SourceNotFound,
+
+    #[error("Files of {0} sources cannot be downloaded again individually")]
+    UnsupportedSource(SourceType),
+
+    #[error("Document has no stored hash to verify a download against")]
+    NoStoredHash,
+
+    #[error("Download from the source failed: {0}")]
+    DownloadFailed(anyhow::Error),
+
+    /// The source file changed since it was synced; a sync picks it up as a new version
+    #[error("The file on the source no longer matches the document (expected hash {expected}, got {actual})")]
+    HashMismatch { expected: String, actual: String },
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// What a successful re-download did
+#[derive(Debug, Clone, Copy)]
+pub struct RedownloadOutcome {
+    pub file_size: i64,
+    pub ocr_enqueued: bool,
+}
+
+#[derive(Clone)]
+pub struct DocumentRedownloadService {
+    state: Arc<AppState>,
+}
+
+impl DocumentRedownloadService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Fetches the document's file from its source, checks it against the stored hash and
+    /// replaces the local file with it. With `reenqueue_ocr`, the document is queued for OCR
+    /// again afterwards.
+    pub async fn redownload(&self, document: &Document, reenqueue_ocr: bool) -> Result<RedownloadOutcome, RedownloadError> {
+        let source_path = document.source_path.as_deref().ok_or(RedownloadError::NoSource)?;
+        let expected_hash = document.file_hash.as_deref().ok_or(RedownloadError::NoStoredHash)?;
+        // Deleting a source detaches its documents but keeps their source paths
+        let source = match document.source_id {
+            Some(source_id) => self.state.db.get_source_by_id(source_id).await?,
+            None => None,
+        };
+        let mut source = source.ok_or(RedownloadError::SourceNotFound)?;
+        source.config = self.state.source_secrets.decrypt_config(&source.config)?;
+
+        let download_permit = self.state.download_limiter.acquire().await;
+        let data = download_from_source(&source, source_path).await?;
+        drop(download_permit);
+        let actual_hash = format!("{:x}", Sha256::digest(&data));
+        if actual_hash != expected_hash {
+            warn!("Re-download of document {} from {} does not match its stored hash", document.id, source_path);
+            return Err(RedownloadError::HashMismatch { expected: expected_hash.to_string(), actual: actual_hash });
+        }
+
+        let file_service = FileService::new(self.state.config.upload_path.clone());
+        file_service.replace_file(&document.file_path, &data).await?;
+        self.state.db.clear_document_integrity_issue(document.id).await?;
+
+        let file_size = data.len() as i64;
+        let mut ocr_enqueued = false;
+        if reenqueue_ocr {
+            self.state.db.replace_document_file_hash(document.id, &actual_hash, file_size).await?;
+            self.state.queue_service.enqueue_document(document.id, REDOWNLOAD_OCR_PRIORITY, file_size).await?;
+            ocr_enqueued = true;
+        }
+
+        info!("Re-downloaded document {} from source {} ({} bytes)", document.id, source.id, file_size);
+        Ok(RedownloadOutcome { file_size, ocr_enqueued })
+    }
+}
+
+/// Downloads the file at `source_path` the way a sync of `source` would
+async fn download_from_source(source: &Source, source_path: &str) -> Result<Vec<u8>, RedownloadError> {
+    let data = match source.source_type {
+        SourceType::WebDAV => async {
+            let config: WebDAVSourceConfig = serde_json::from_value(source.config.clone())
+                .map_err(|e| anyhow!("Invalid WebDAV config: {}", e))?;
+            let service = WebDAVService::new(config.into())?;
+            service.download_file(source_path).await
+        }.await,
+        SourceType::LocalFolder => async {
+            let config: LocalFolderSourceConfig = serde_json::from_value(source.config.clone())
+                .map_err(|e| anyhow!("Invalid LocalFolder config: {}", e))?;
+            LocalFolderService::new(config)?.read_file(source_path).await
+        }.await,
+        SourceType::S3 => async {
+            let config: S3SourceConfig = serde_json::from_value(source.config.clone())
+                .map_err(|e| anyhow!("Invalid S3 config: {}", e))?;
+            S3Service::new(config).await?.download_file(source_path).await
+        }.await,
+        SourceType::Smb => async {
+            let config: SmbSourceConfig = serde_json::from_value(source.config.clone())
+                .map_err(|e| anyhow!("Invalid SMB config: {}", e))?;
+            SmbService::new(config)?.download_file(source_path).await
+        }.await,
+        // Attachments are taken apart from messages; there is no file path to fetch again
+        SourceType::Imap => return Err(RedownloadError::UnsupportedSource(source.source_type)),
+    };
+
+    data.map_err(RedownloadError::DownloadFailed)
+}
@@ -0,0 +1,105 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::{
+    db::documents::DocumentFileRecord,
+    models::{DocumentRun, ReingestDetails},
+    services::{document_runs::DocumentRunTracker, file_service::FileService},
+    AppState,
+};
+
+/// Documents loaded and checked per batch; progress is saved after each batch
+const REINGEST_BATCH_SIZE: i64 = 200;
+
+/// Below the priority of uploads so a reingest never holds them up
+const REINGEST_OCR_PRIORITY: i32 = 1;
+
+/// Re-hashes every document file on disk and compares it with the database
+#[derive(Clone)]
+pub struct DocumentReingestService {
+    state: Arc<AppState>,
+}
+
+impl DocumentReingestService {
+    pub fn new(state: Arc<AppState>) -> Self {
+        Self { state }
+    }
+
+    /// Checks every live document of a run that was already created, then closes the run.
+    /// Progress is registered with the sync progress tracker under the run id.
+    pub async fn run(&self, run: DocumentRun<ReingestDetails>) {
+        let mut tracker = DocumentRunTracker::start(self.state.clone(), &run);
+        info!("Starting document reingest run {} (re-enqueue OCR: {})", run.id, run.details.reenqueue_ocr);
+
+        let result = self.check_all_documents(&mut tracker).await;
+        let details = tracker.finish(&result).await;
+
+        info!(
+            "Document reingest run {} finished: {} checked, {} mismatched, {} missing, {} re-enqueued",
+            run.id, details.checked, details.mismatched, details.missing, details.reenqueued
+        );
+    }
+
+    async fn check_all_documents(&self, tracker: &mut DocumentRunTracker<ReingestDetails>) -> Result<()> {
+        let file_service = FileService::new(self.state.config.upload_path.clone());
+        let mut after_id = None;
+
+        loop {
+            let batch = self.state.db.get_document_file_records(after_id, REINGEST_BATCH_SIZE).await?;
+            let Some(last) = batch.last() else { break };
+            after_id = Some(last.id);
+            tracker.progress.add_files_found(batch.len());
+
+            for document in &batch {
+                tracker.progress.set_current_file(Some(&document.file_path));
+                self.check_document(&file_service, document, &mut tracker.details).await?;
+                tracker.progress.add_files_processed(1, document.file_size.max(0) as u64);
+            }
+
+            tracker.save_progress().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn check_document(
+        &self,
+        file_service: &FileService,
+        document: &DocumentFileRecord,
+        counts: &mut ReingestDetails,
+    ) -> Result<()> {
+        counts.checked += 1;
+        let db = &self.state.db;
+
+        let (actual_hash, actual_size) = match file_service.hash_file(&document.file_path).await {
+            Ok(hashed) => hashed,
+            Err(e) => {
+                warn!("File of document {} is missing or unreadable: {}", document.id, e);
+                counts.missing += 1;
+                return db.set_document_integrity_issue(document.id, "missing", document.file_hash.as_deref(), None).await;
+            }
+        };
+
+        if document.file_hash.as_deref() == Some(actual_hash.as_str()) {
+            return db.clear_document_integrity_issue(document.id).await;
+        }
+
+        counts.mismatched += 1;
+        db.set_document_integrity_issue(document.id, "hash_mismatch", document.file_hash.as_deref(), Some(&actual_hash)).await?;
+
+        if counts.reenqueue_ocr {
+            // The stored hash may collide with another of the user's documents; the issue stays flagged then
+            if let Err(e) = db.replace_document_file_hash(document.id, &actual_hash, actual_size).await {
+                warn!("Could not adopt the changed file of document {}: {}", document.id, e);
+                return Ok(());
+            }
+            match self.state.queue_service.enqueue_document(document.id, REINGEST_OCR_PRIORITY, actual_size).await {
+                Ok(_) => counts.reenqueued += 1,
+                Err(e) => warn!("Failed to re-enqueue OCR for document {}: {}", document.id, e),
+            }
+        }
+
+        Ok(())
+    }
+}
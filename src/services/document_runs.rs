@@ -0,0 +1,111 @@
+use anyhow::Result;
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    models::{DocumentRun, DocumentRunDetails, DocumentRunOutcome},
+    services::webdav::{SyncPhase, SyncProgress},
+    AppState,
+};
+
+/// Why a document run could not be started
+#[derive(Debug)]
+pub enum StartRunError {
+    /// A run of the same kind is still working
+    AlreadyRunning,
+    Failed(anyhow::Error),
+}
+
+impl std::fmt::Display for StartRunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartRunError::AlreadyRunning => write!(f, "a run of this kind is already running"),
+            StartRunError::Failed(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+/// Creates a run of the kind of `D`. A run of that kind still marked as running is closed
+/// as failed first if nothing is working on it any more, e.g. the server restarted mid-run.
+pub async fn start_document_run<D: DocumentRunDetails>(
+    state: &AppState,
+    started_by: Option<Uuid>,
+    details: D,
+) -> Result<DocumentRun<D>, StartRunError> {
+    let running = state.db.get_running_document_run::<D>().await.map_err(StartRunError::Failed)?;
+    if let Some(run) = running {
+        if state.sync_progress_tracker.is_syncing(run.id) {
+            return Err(StartRunError::AlreadyRunning);
+        }
+        warn!("Closing {} run {} that was interrupted", D::KIND, run.id);
+        state
+            .db
+            .fail_unfinished_document_runs::<D>("Run interrupted")
+            .await
+            .map_err(StartRunError::Failed)?;
+    }
+
+    state.db.create_document_run(started_by, &details).await.map_err(|e| {
+        match e.downcast_ref::<sqlx::Error>().and_then(|e| e.as_database_error()) {
+            // The single-running index rejects a run started concurrently
+            Some(db_error) if db_error.is_unique_violation() => StartRunError::AlreadyRunning,
+            _ => StartRunError::Failed(e),
+        }
+    })
+}
+
+/// Tracks a document run while it works: progress is registered with the sync progress
+/// tracker under the run id, and the details are saved to the run as they change.
+pub struct DocumentRunTracker<D: DocumentRunDetails> {
+    state: Arc<AppState>,
+    run_id: Uuid,
+    pub details: D,
+    pub progress: Arc<SyncProgress>,
+}
+
+impl<D: DocumentRunDetails> DocumentRunTracker<D> {
+    pub fn start(state: Arc<AppState>, run: &DocumentRun<D>) -> Self {
+        let progress = Arc::new(SyncProgress::new());
+        progress.set_phase(SyncPhase::ProcessingFiles);
+        state.sync_progress_tracker.register_sync(run.id, progress.clone());
+
+        Self { state, run_id: run.id, details: run.details.clone(), progress }
+    }
+
+    pub fn run_id(&self) -> Uuid {
+        self.run_id
+    }
+
+    /// Stores the current details of the run
+    pub async fn save_progress(&self) -> Result<()> {
+        self.state.db.update_document_run(self.run_id, &self.details).await
+    }
+
+    /// Closes the run with the result of its work and returns its final details
+    pub async fn finish(self, result: &Result<()>) -> D {
+        let (outcome, error_message) = match result {
+            Ok(()) => {
+                self.progress.set_phase(SyncPhase::Completed);
+                (DocumentRunOutcome::Success, None)
+            }
+            Err(e) => {
+                error!("{} run {} failed: {}", D::KIND, self.run_id, e);
+                self.progress.set_phase(SyncPhase::Failed(e.to_string()));
+                (DocumentRunOutcome::Failed, Some(e.to_string()))
+            }
+        };
+
+        if let Err(e) = self
+            .state
+            .db
+            .finish_document_run(self.run_id, &self.details, outcome, error_message.as_deref())
+            .await
+        {
+            error!("Failed to record the end of {} run {}: {}", D::KIND, self.run_id, e);
+        }
+        self.state.sync_progress_tracker.unregister_sync(self.run_id);
+
+        self.details
+    }
+}
@@ -0,0 +1,43 @@
+use rand::RngCore;
+
+/// How long a share link stays valid when no expiry is requested
+pub const DEFAULT_SHARE_EXPIRY_HOURS: i64 = 7 * 24;
+
+/// Longest expiry a share link may be given
+pub const MAX_SHARE_EXPIRY_HOURS: i64 = 365 * 24;
+
+/// Generates a random URL-safe share token (256 bits, hex-encoded)
+pub fn generate_share_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Builds the link handed to the reviewer. Falls back to a relative link when no public URL is configured.
+pub fn build_share_link(public_base_url: Option<&str>, token: &str) -> String {
+    format!("{}/api/shared/{}", public_base_url.unwrap_or(""), token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_tokens_are_random() {
+        let a = generate_share_token();
+        let b = generate_share_token();
+
+        assert_eq!(a.len(), 64);
+        assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_build_share_link() {
+        assert_eq!(
+            build_share_link(Some("https://docs.example.com"), "abc"),
+            "https://docs.example.com/api/shared/abc"
+        );
+        assert_eq!(build_share_link(None, "abc"), "/api/shared/abc");
+    }
+}
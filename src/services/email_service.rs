@@ -0,0 +1,79 @@
+use anyhow::{anyhow, Result};
+use lettre::{
+    message::header::ContentType,
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use tracing::{info, warn};
+
+use crate::config::Config;
+
+/// Sends transactional email over SMTP. When `SMTP_HOST` is not configured the
+/// service is disabled and messages are logged instead of sent.
+#[derive(Clone)]
+pub struct EmailService {
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+    from: String,
+}
+
+impl EmailService {
+    pub fn new(config: &Config) -> Result<Self> {
+        let transport = match &config.smtp_host {
+            Some(host) => {
+                let mut builder = if config.smtp_starttls {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?
+                } else {
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+                };
+                builder = builder.port(config.smtp_port);
+
+                if let (Some(username), Some(password)) = (&config.smtp_username, &config.smtp_password) {
+                    builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+                }
+
+                Some(builder.build())
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            transport,
+            from: config.smtp_from.clone(),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.transport.is_some()
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, body: &str) -> Result<()> {
+        let Some(transport) = &self.transport else {
+            warn!("Email is not configured (SMTP_HOST unset); dropping message '{}' to {}", subject, to);
+            return Ok(());
+        };
+
+        let message = Message::builder()
+            .from(self.from.parse().map_err(|e| anyhow!("Invalid SMTP_FROM address: {}", e))?)
+            .to(to.parse().map_err(|e| anyhow!("Invalid recipient address '{}': {}", to, e))?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())?;
+
+        transport.send(message).await?;
+        info!("Sent email '{}' to {}", subject, to);
+        Ok(())
+    }
+
+    pub async fn send_password_reset(&self, to: &str, username: &str, reset_link: &str, expires_minutes: i64) -> Result<()> {
+        let body = format!(
+            "Hello {},\n\n\
+             A password reset was requested for your Readur account.\n\n\
+             Use the link below to choose a new password. It expires in {} minutes and can only be used once.\n\n\
+             {}\n\n\
+             If you didn't request this, you can ignore this email.\n",
+            username, expires_minutes, reset_link
+        );
+
+        self.send(to, "Reset your Readur password", &body).await
+    }
+}
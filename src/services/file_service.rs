@@ -1,23 +1,219 @@
 use anyhow::Result;
 use chrono::Utc;
+use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufWriter};
 use uuid::Uuid;
 use tracing::{info, warn, error};
 
+use crate::db::{documents::StoredFileTable, Database};
 use crate::models::Document;
+use crate::services::derived_asset_cache::{DerivedAssetCache, DerivedAssetKind};
 
 #[cfg(feature = "ocr")]
 use image::{DynamicImage, ImageFormat, imageops::FilterType};
 
+/// Longest edge, in pixels, of rendered PDF page thumbnails
+const PDF_THUMBNAIL_MAX_EDGE: u32 = 400;
+
+/// How long `pdftoppm` may spend rendering a thumbnail before it is killed
+const PDF_THUMBNAIL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Served when a PDF's first page cannot be rendered
+const PDF_PLACEHOLDER_SVG: &str = r##"<svg xmlns="http://www.w3.org/2000/svg" width="300" height="400" viewBox="0 0 300 400"><rect width="300" height="400" fill="#f5f5f5"/><path d="M70 40h120l60 60v260H70z" fill="#fff" stroke="#c62828" stroke-width="8"/><path d="M190 40v60h60" fill="none" stroke="#c62828" stroke-width="8"/><rect x="90" y="230" width="140" height="60" rx="8" fill="#c62828"/><text x="160" y="273" font-family="sans-serif" font-size="36" font-weight="bold" fill="#fff" text-anchor="middle">PDF</text></svg>"##;
+
+/// Most directory levels a sharded layout may nest
+pub const MAX_SHARD_LEVELS: u8 = 4;
+
+/// Levels of a sharded layout configured without a count: 65,536 directories
+const DEFAULT_SHARD_LEVELS: u8 = 2;
+
+/// Rows loaded per batch while migrating between layouts
+const STORAGE_MIGRATION_BATCH_SIZE: i64 = 500;
+
+/// How document files are arranged in the documents directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StorageLayout {
+    /// Every file directly in `documents/`
+    #[default]
+    Flat,
+    /// Files in nested directories named after the leading bytes of their SHA-256 hash,
+    /// one byte (two hex characters, 256 directories) per level, e.g.
+    /// `documents/3f/a2/<file>` with two levels
+    Sharded { levels: u8 },
+}
+
+impl StorageLayout {
+    /// The directory within `documents_dir` for a file. Files without a usable hash, such
+    /// as those of documents ingested before hashes were recorded, are sharded by the hash
+    /// of their stored file name instead.
+    pub fn directory_for(&self, documents_dir: &Path, file_hash: Option<&str>, file_name: &str) -> PathBuf {
+        let StorageLayout::Sharded { levels } = *self else {
+            return documents_dir.to_path_buf();
+        };
+
+        let levels = levels as usize;
+        let key = match file_hash {
+            Some(hash) if hash.len() >= levels * 2 && hash.bytes().all(|b| b.is_ascii_hexdigit()) => hash.to_ascii_lowercase(),
+            _ => format!("{:x}", Sha256::digest(file_name.as_bytes())),
+        };
+
+        let mut dir = documents_dir.to_path_buf();
+        for level in 0..levels {
+            dir.push(&key[level * 2..level * 2 + 2]);
+        }
+        dir
+    }
+}
+
+impl std::fmt::Display for StorageLayout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StorageLayout::Flat => write!(f, "flat"),
+            StorageLayout::Sharded { levels } => write!(f, "sharded:{}", levels),
+        }
+    }
+}
+
+impl std::str::FromStr for StorageLayout {
+    type Err = String;
+
+    /// Parses `flat`, `sharded` or `sharded:<levels>`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_lowercase();
+        match value.split_once(':') {
+            None if value == "flat" => Ok(StorageLayout::Flat),
+            None if value == "sharded" => Ok(StorageLayout::Sharded { levels: DEFAULT_SHARD_LEVELS }),
+            Some(("sharded", levels)) => match levels.parse::<u8>() {
+                Ok(levels) if (1..=MAX_SHARD_LEVELS).contains(&levels) => Ok(StorageLayout::Sharded { levels }),
+                _ => Err(format!("Shard levels must be between 1 and {}, got '{}'", MAX_SHARD_LEVELS, levels)),
+            },
+            _ => Err(format!("Unknown storage layout '{}', expected 'flat', 'sharded' or 'sharded:<levels>'", value)),
+        }
+    }
+}
+
+/// Outcome of moving stored files to where the configured layout puts them. In a dry run
+/// `moved` counts the files that would be moved and nothing is touched.
+#[derive(Debug, Clone, Default)]
+pub struct StorageMigrationReport {
+    pub dry_run: bool,
+    /// Files in the root of the upload directory, left there by very old versions
+    pub legacy_files_moved: u64,
+    pub checked: u64,
+    pub moved: u64,
+    pub already_in_place: u64,
+    /// Files outside the documents directory, e.g. of documents created by tests or tools;
+    /// they are left alone
+    pub outside_documents_dir: u64,
+    pub missing: u64,
+    pub failed: u64,
+}
+
+/// Thumbnail bytes along with the image format they are encoded in
+#[derive(Debug, Clone)]
+pub struct Thumbnail {
+    pub data: Vec<u8>,
+    pub content_type: &'static str,
+}
+
 #[derive(Clone)]
 pub struct FileService {
     upload_path: String,
+    asset_cache: Option<Arc<DerivedAssetCache>>,
+    layout: StorageLayout,
+}
+
+/// A file that was streamed to storage, with its hash computed as the bytes were written
+#[derive(Debug, Clone)]
+pub struct StoredFile {
+    pub file_path: String,
+    pub file_hash: String,
+    pub file_size: i64,
+}
+
+/// Writes a file to storage chunk by chunk so large uploads never sit fully in memory.
+/// Bytes go to a temp file first and are moved into the documents directory on `finish`.
+pub struct StreamingFileWriter {
+    writer: BufWriter<fs::File>,
+    temp_path: PathBuf,
+    documents_dir: PathBuf,
+    saved_filename: String,
+    layout: StorageLayout,
+    hasher: Sha256,
+    bytes_written: u64,
+}
+
+impl StreamingFileWriter {
+    pub async fn write_chunk(&mut self, chunk: &[u8]) -> Result<()> {
+        self.writer.write_all(chunk).await?;
+        self.hasher.update(chunk);
+        self.bytes_written += chunk.len() as u64;
+        Ok(())
+    }
+
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Flushes the temp file and moves it to its final location, which the layout may
+    /// derive from the file's hash
+    pub async fn finish(mut self) -> Result<StoredFile> {
+        if let Err(e) = self.writer.flush().await {
+            let _ = fs::remove_file(&self.temp_path).await;
+            return Err(e.into());
+        }
+        drop(self.writer);
+
+        let file_hash = format!("{:x}", self.hasher.finalize());
+        let final_dir = self.layout.directory_for(&self.documents_dir, Some(&file_hash), &self.saved_filename);
+        let final_path = final_dir.join(&self.saved_filename);
+
+        let moved = async {
+            fs::create_dir_all(&final_dir).await?;
+            fs::rename(&self.temp_path, &final_path).await
+        }.await;
+        if let Err(e) = moved {
+            let _ = fs::remove_file(&self.temp_path).await;
+            return Err(anyhow::anyhow!("Failed to move uploaded file into place: {}", e));
+        }
+
+        Ok(StoredFile {
+            file_path: final_path.to_string_lossy().to_string(),
+            file_hash,
+            file_size: self.bytes_written as i64,
+        })
+    }
+
+    /// Discards the partially written file
+    pub async fn abort(self) {
+        drop(self.writer);
+        if let Err(e) = fs::remove_file(&self.temp_path).await {
+            warn!("Failed to remove partial upload {:?}: {}", self.temp_path, e);
+        }
+    }
 }
 
 impl FileService {
     pub fn new(upload_path: String) -> Self {
-        Self { upload_path }
+        Self { upload_path, asset_cache: None, layout: StorageLayout::default() }
+    }
+
+    /// Stores new and restored document files the way `layout` arranges them. Files that
+    /// are already stored stay where they are until `migrate_existing_files` moves them.
+    pub fn with_storage_layout(mut self, layout: StorageLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Reports cache hits and newly generated thumbnails and searchable PDFs to `cache`,
+    /// which keeps them under its size cap
+    pub fn with_asset_cache(mut self, cache: Arc<DerivedAssetCache>) -> Self {
+        self.asset_cache = Some(cache);
+        self
     }
 
     /// Initialize the upload directory structure
@@ -31,6 +227,8 @@ impl FileService {
             "processed_images", // OCR processed images for review
             "temp",            // Temporary files during processing
             "backups",         // Document backups
+            "trash",           // Soft-deleted documents awaiting purge
+            "searchable_pdfs", // Generated PDFs with an OCR text layer
         ];
         
         for dir in directories.iter() {
@@ -70,56 +268,278 @@ impl FileService {
         self.get_subdirectory_path("temp")
     }
 
-    /// Migrate existing files from the root upload directory to the structured format
-    pub async fn migrate_existing_files(&self) -> Result<()> {
+    /// Get the trash directory path
+    pub fn get_trash_path(&self) -> PathBuf {
+        self.get_subdirectory_path("trash")
+    }
+
+    /// Get the directory where generated searchable PDFs are cached
+    pub fn get_searchable_pdfs_path(&self) -> PathBuf {
+        self.get_subdirectory_path("searchable_pdfs")
+    }
+
+    /// The directory the configured layout puts a document file in
+    fn document_directory(&self, file_hash: Option<&str>, file_name: &str) -> PathBuf {
+        self.layout.directory_for(&self.get_documents_path(), file_hash, file_name)
+    }
+
+    /// Moves stored files to where the configured layout puts them, updating each row's
+    /// `file_path` in the same transaction as its move. Files left in the root of the
+    /// upload directory by very old versions are moved into the documents directory first.
+    ///
+    /// Files already in place are skipped, so the migration can be repeated, and one that
+    /// was interrupted picks up where it stopped: a file that was moved without its row
+    /// being updated is recognised at its new location. With `dry_run` the planned moves
+    /// are logged and counted, and neither disk nor database is touched.
+    pub async fn migrate_existing_files(&self, db: &Database, dry_run: bool) -> Result<StorageMigrationReport> {
+        let mut report = StorageMigrationReport { dry_run, ..Default::default() };
+        info!(
+            "Starting {}migration of stored files to the {} layout...",
+            if dry_run { "dry run of the " } else { "" },
+            self.layout
+        );
+
+        self.migrate_legacy_root_files(dry_run, &mut report).await?;
+        for table in [StoredFileTable::Documents, StoredFileTable::DocumentVersions] {
+            self.migrate_table_files(db, table, dry_run, &mut report).await?;
+        }
+
+        info!(
+            "Storage migration {}: {} checked, {} {}, {} already in place, {} outside the documents directory, {} missing, {} failed, {} legacy files",
+            if dry_run { "dry run completed" } else { "completed" },
+            report.checked,
+            report.moved,
+            if dry_run { "to move" } else { "moved" },
+            report.already_in_place,
+            report.outside_documents_dir,
+            report.missing,
+            report.failed,
+            report.legacy_files_moved
+        );
+        Ok(report)
+    }
+
+    /// Moves files from the root upload directory into the documents and thumbnails directories
+    async fn migrate_legacy_root_files(&self, dry_run: bool, report: &mut StorageMigrationReport) -> Result<()> {
         let base_path = Path::new(&self.upload_path);
         let documents_dir = self.get_documents_path();
         let thumbnails_dir = self.get_thumbnails_path();
-        
-        info!("Starting migration of existing files to structured directories...");
-        let mut migrated_count = 0;
-        let mut thumbnail_count = 0;
-        
-        // Read all files in the base upload directory
+
         let mut entries = fs::read_dir(base_path).await?;
-        
         while let Some(entry) = entries.next_entry().await? {
             let file_path = entry.path();
-            
+
             // Skip directories and already structured subdirectories
             if file_path.is_dir() {
                 continue;
             }
-            
-            if let Some(filename) = file_path.file_name().and_then(|n| n.to_str()) {
-                // Handle thumbnail files
-                if filename.ends_with("_thumb.jpg") {
-                    let new_path = thumbnails_dir.join(filename);
-                    if let Err(e) = fs::rename(&file_path, &new_path).await {
-                        warn!("Failed to migrate thumbnail {}: {}", filename, e);
-                    } else {
-                        thumbnail_count += 1;
-                        info!("Migrated thumbnail: {} -> {:?}", filename, new_path);
+
+            let Some(filename) = file_path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let new_path = if filename.ends_with("_thumb.jpg") {
+                thumbnails_dir.join(filename)
+            } else {
+                documents_dir.join(filename)
+            };
+
+            if dry_run {
+                info!("Would move legacy file: {:?} -> {:?}", file_path, new_path);
+                report.legacy_files_moved += 1;
+            } else if let Err(e) = fs::rename(&file_path, &new_path).await {
+                warn!("Failed to migrate legacy file {}: {}", filename, e);
+            } else {
+                report.legacy_files_moved += 1;
+                info!("Migrated legacy file: {} -> {:?}", filename, new_path);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn migrate_table_files(
+        &self,
+        db: &Database,
+        table: StoredFileTable,
+        dry_run: bool,
+        report: &mut StorageMigrationReport,
+    ) -> Result<()> {
+        let documents_dir = self.get_documents_path();
+        let mut after_id = None;
+
+        loop {
+            let batch = db.get_stored_file_records(table, after_id, STORAGE_MIGRATION_BATCH_SIZE).await?;
+            let Some(last) = batch.last() else { break };
+            after_id = Some(last.id);
+
+            for record in &batch {
+                report.checked += 1;
+                let source = Path::new(&record.file_path);
+                let Some(file_name) = source.file_name().and_then(|n| n.to_str()) else {
+                    report.failed += 1;
+                    continue;
+                };
+                if !source.starts_with(&documents_dir) {
+                    report.outside_documents_dir += 1;
+                    continue;
+                }
+
+                let target_dir = self.document_directory(record.file_hash.as_deref(), file_name);
+                let target = target_dir.join(file_name);
+                if source == target {
+                    report.already_in_place += 1;
+                    continue;
+                }
+
+                let source_exists = fs::try_exists(source).await.unwrap_or(false);
+                let target_exists = fs::try_exists(&target).await.unwrap_or(false);
+                match (source_exists, target_exists) {
+                    (false, false) => {
+                        warn!("File of {} row {} is missing at {}", table, record.id, record.file_path);
+                        report.missing += 1;
+                        continue;
+                    }
+                    (true, true) => {
+                        warn!("Not moving {} over the existing {}", record.file_path, target.display());
+                        report.failed += 1;
+                        continue;
                     }
+                    _ => {}
                 }
-                // Handle regular document files
-                else {
-                    let new_path = documents_dir.join(filename);
-                    if let Err(e) = fs::rename(&file_path, &new_path).await {
-                        warn!("Failed to migrate document {}: {}", filename, e);
-                    } else {
-                        migrated_count += 1;
-                        info!("Migrated document: {} -> {:?}", filename, new_path);
+
+                if dry_run {
+                    info!("Would move {} row {}: {} -> {}", table, record.id, record.file_path, target.display());
+                    report.moved += 1;
+                    continue;
+                }
+
+                match self.relocate_file(db, table, record.id, source, &target, source_exists).await {
+                    Ok(true) => report.moved += 1,
+                    // The row changed while the migration ran; a later run will see it
+                    Ok(false) => {}
+                    Err(e) => {
+                        warn!("Failed to move file of {} row {}: {}", table, record.id, e);
+                        report.failed += 1;
                     }
                 }
             }
         }
-        
-        info!("Migration completed: {} documents, {} thumbnails moved to structured directories", 
-              migrated_count, thumbnail_count);
+
         Ok(())
     }
 
+    /// Moves one file and repoints its row at it, committing the row only once the file is
+    /// in place. Without `source_exists` the file was already moved by an interrupted run
+    /// and only the row is updated. Returns false if the row no longer pointed at `source`.
+    async fn relocate_file(
+        &self,
+        db: &Database,
+        table: StoredFileTable,
+        id: Uuid,
+        source: &Path,
+        target: &Path,
+        source_exists: bool,
+    ) -> Result<bool> {
+        let source_path = source.to_string_lossy();
+        let target_path = target.to_string_lossy();
+        let Some(tx) = db.begin_stored_file_relocation(table, id, &source_path, &target_path).await? else {
+            return Ok(false);
+        };
+
+        if source_exists {
+            let moved = async {
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent).await?;
+                }
+                fs::rename(source, target).await
+            }.await;
+            if let Err(e) = moved {
+                tx.rollback().await?;
+                return Err(anyhow::anyhow!("Failed to move {} to {}: {}", source_path, target.display(), e));
+            }
+        }
+
+        if let Err(e) = tx.commit().await {
+            if source_exists {
+                if let Err(undo) = fs::rename(target, source).await {
+                    error!("Failed to move {} back to {} after a failed commit: {}", target.display(), source_path, undo);
+                }
+            }
+            return Err(e.into());
+        }
+
+        // Drop the shard directory the file left if it is now empty; removing a directory
+        // that still has files fails and is ignored
+        if let Some(parent) = source.parent() {
+            if parent != self.get_documents_path() {
+                let _ = fs::remove_dir(parent).await;
+            }
+        }
+
+        info!("Moved file of {} row {}: {} -> {}", table, id, source_path, target_path);
+        Ok(true)
+    }
+
+    /// Starts a streamed save. The stored filename is derived the same way as `save_file`.
+    pub async fn begin_streaming_save(&self, filename: &str) -> Result<StreamingFileWriter> {
+        let file_id = Uuid::new_v4();
+        let extension = Path::new(filename)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("");
+
+        let saved_filename = if extension.is_empty() {
+            file_id.to_string()
+        } else {
+            format!("{}.{}", file_id, extension)
+        };
+
+        let documents_dir = self.get_documents_path();
+        let temp_dir = self.get_temp_path();
+        for dir in [&documents_dir, &temp_dir] {
+            if let Err(e) = fs::create_dir_all(dir).await {
+                error!("Failed to create directory {:?}: {}", dir, e);
+                return Err(anyhow::anyhow!("Failed to create directory {:?}: {}", dir, e));
+            }
+        }
+
+        let temp_path = temp_dir.join(format!("{}.upload", file_id));
+        let file = fs::File::create(&temp_path).await?;
+
+        Ok(StreamingFileWriter {
+            writer: BufWriter::new(file),
+            temp_path,
+            documents_dir,
+            saved_filename,
+            layout: self.layout,
+            hasher: Sha256::new(),
+            bytes_written: 0,
+        })
+    }
+
+    /// Streams a file that is already on disk, such as one extracted from an archive, into
+    /// storage under a name derived from `filename`
+    pub async fn store_file_from_path(&self, filename: &str, source_path: &Path) -> Result<StoredFile> {
+        let mut source = fs::File::open(source_path).await?;
+        let mut writer = self.begin_streaming_save(filename).await?;
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = match source.read(&mut buffer).await {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) => {
+                    writer.abort().await;
+                    return Err(e.into());
+                }
+            };
+            if let Err(e) = writer.write_chunk(&buffer[..read]).await {
+                writer.abort().await;
+                return Err(e);
+            }
+        }
+        writer.finish().await
+    }
+
     pub async fn save_file(&self, filename: &str, data: &[u8]) -> Result<String> {
         let file_id = Uuid::new_v4();
         let extension = Path::new(filename)
@@ -133,8 +553,12 @@ impl FileService {
             format!("{}.{}", file_id, extension)
         };
         
-        // Save to documents subdirectory
-        let documents_dir = self.get_documents_path();
+        // Save to documents subdirectory, in the directory the layout picks for its content
+        let file_hash = match self.layout {
+            StorageLayout::Flat => None,
+            StorageLayout::Sharded { .. } => Some(format!("{:x}", Sha256::digest(data))),
+        };
+        let documents_dir = self.document_directory(file_hash.as_deref(), &saved_filename);
         let file_path = documents_dir.join(&saved_filename);
         
         // Ensure the documents directory exists
@@ -148,6 +572,36 @@ impl FileService {
         Ok(file_path.to_string_lossy().to_string())
     }
 
+    /// Replaces the stored file at `file_path` with `data`. The bytes are written to a temp
+    /// file beside it and moved into place, so a failed write leaves the old file untouched.
+    pub async fn replace_file(&self, file_path: &str, data: &[u8]) -> Result<()> {
+        let target = match self.resolve_file_path(file_path).await {
+            Ok(resolved) => PathBuf::from(resolved),
+            // The file is gone entirely; restore it where the database expects it
+            Err(_) => PathBuf::from(file_path),
+        };
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+
+        let mut temp_name = target.file_name().map(|name| name.to_os_string()).unwrap_or_default();
+        temp_name.push(format!(".{}.tmp", Uuid::new_v4()));
+        let temp_path = target.with_file_name(temp_name);
+
+        let written = async {
+            let mut file = fs::File::create(&temp_path).await?;
+            file.write_all(data).await?;
+            file.sync_all().await?;
+            fs::rename(&temp_path, &target).await
+        }.await;
+
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(anyhow::anyhow!("Failed to replace {}: {}", target.display(), e));
+        }
+        Ok(())
+    }
+
     pub fn create_document(
         &self,
         filename: &str,
@@ -242,14 +696,57 @@ impl FileService {
         Err(anyhow::anyhow!("File not found: {} (checked original path and structured directory)", file_path))
     }
 
+    /// Streams a stored file through SHA-256 and returns its hash and size
+    pub async fn hash_file(&self, file_path: &str) -> Result<(String, i64)> {
+        let resolved_path = self.resolve_file_path(file_path).await?;
+        let mut file = fs::File::open(&resolved_path).await?;
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut file_size = 0i64;
+
+        loop {
+            let read = file.read(&mut buffer).await?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
+            file_size += read as i64;
+        }
+
+        Ok((format!("{:x}", hasher.finalize()), file_size))
+    }
+
     pub async fn read_file(&self, file_path: &str) -> Result<Vec<u8>> {
         let resolved_path = self.resolve_file_path(file_path).await?;
         let data = fs::read(&resolved_path).await?;
         Ok(data)
     }
 
+    /// Size in bytes of a stored file
+    pub async fn file_size(&self, file_path: &str) -> Result<u64> {
+        let resolved_path = self.resolve_file_path(file_path).await?;
+        Ok(fs::metadata(&resolved_path).await?.len())
+    }
+
+    /// Reads `length` bytes of a stored file starting at byte `start`, without loading the rest
+    pub async fn read_file_range(&self, file_path: &str, start: u64, length: u64) -> Result<Vec<u8>> {
+        let resolved_path = self.resolve_file_path(file_path).await?;
+        let mut file = fs::File::open(&resolved_path).await?;
+        file.seek(std::io::SeekFrom::Start(start)).await?;
+
+        let mut data = Vec::with_capacity(length as usize);
+        file.take(length).read_to_end(&mut data).await?;
+        if (data.len() as u64) < length {
+            return Err(anyhow::anyhow!("File {} ended before byte {}", file_path, start + length));
+        }
+        Ok(data)
+    }
+
+    /// Get the thumbnail of a document, generating it if needed. Images are turned clockwise
+    /// by `rotation_degrees` so they show upright.
     #[cfg(feature = "ocr")]
-    pub async fn get_or_generate_thumbnail(&self, file_path: &str, filename: &str) -> Result<Vec<u8>> {
+    pub async fn get_or_generate_thumbnail(&self, document: &Document, rotation_degrees: u32) -> Result<Vec<u8>> {
+        let file_path = document.file_path.as_str();
         // Use the structured thumbnails directory
         let thumbnails_dir = self.get_thumbnails_path();
         if !thumbnails_dir.exists() {
@@ -259,30 +756,212 @@ impl FileService {
             }
         }
 
-        // Generate thumbnail filename based on original file path
-        let file_stem = Path::new(file_path)
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown");
-        let thumbnail_path = thumbnails_dir.join(format!("{}_thumb.jpg", file_stem));
+        let thumbnail_path = self.thumbnail_path(file_path);
 
         // Check if thumbnail already exists
         if thumbnail_path.exists() {
-            return self.read_file(&thumbnail_path.to_string_lossy()).await;
+            let data = self.read_file(&thumbnail_path.to_string_lossy()).await?;
+            self.record_cache_hit(DerivedAssetKind::Thumbnail, document.id, &thumbnail_path, data.len()).await;
+            return Ok(data);
         }
 
         // Resolve file path and generate thumbnail
         let resolved_path = self.resolve_file_path(file_path).await?;
-        let thumbnail_data = self.generate_thumbnail(&resolved_path, filename).await?;
+        let thumbnail_data = self.generate_thumbnail(&resolved_path, &document.original_filename, rotation_degrees).await?;
         
         // Save thumbnail to cache
         fs::write(&thumbnail_path, &thumbnail_data).await?;
+        self.record_cache_miss(DerivedAssetKind::Thumbnail, document.id, &thumbnail_path, thumbnail_data.len()).await;
         
         Ok(thumbnail_data)
     }
 
+    /// Cached thumbnail of a document, named after its stored file
+    fn thumbnail_path(&self, file_path: &str) -> PathBuf {
+        let file_stem = Path::new(file_path)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown");
+        self.get_thumbnails_path().join(format!("{}_thumb.jpg", file_stem))
+    }
+
+    /// Drops the cached thumbnail of a document, e.g. after it was rotated, so the next
+    /// request generates it afresh
+    pub async fn remove_thumbnail(&self, file_path: &str) {
+        let thumbnail_path = self.thumbnail_path(file_path);
+        if let Err(e) = fs::remove_file(&thumbnail_path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Failed to remove cached thumbnail {}: {}", thumbnail_path.display(), e);
+            }
+        }
+    }
+
+    /// Get the first-page thumbnail of a PDF document, rendering it with `pdftoppm` if needed.
+    ///
+    /// Rendered PNGs are cached in the thumbnails directory under a name that includes the
+    /// document's file hash, so replacing the file produces a fresh thumbnail. Falls back to a
+    /// generic PDF icon when rendering fails or `pdftoppm` is not installed.
+    pub async fn get_or_generate_pdf_thumbnail(&self, document: &Document) -> Result<Thumbnail> {
+        let thumbnails_dir = self.get_thumbnails_path();
+        fs::create_dir_all(&thumbnails_dir).await?;
+
+        let cache_path = thumbnails_dir.join(Self::pdf_thumbnail_filename(document));
+        if let Ok(data) = fs::read(&cache_path).await {
+            self.record_cache_hit(DerivedAssetKind::PdfThumbnail, document.id, &cache_path, data.len()).await;
+            return Ok(Thumbnail { data, content_type: "image/png" });
+        }
+
+        let resolved_path = self.resolve_file_path(&document.file_path).await?;
+        match self.render_pdf_first_page(&resolved_path).await {
+            Ok(data) => {
+                self.remove_pdf_thumbnails(document.id).await;
+                match fs::write(&cache_path, &data).await {
+                    Ok(()) => self.record_cache_miss(DerivedAssetKind::PdfThumbnail, document.id, &cache_path, data.len()).await,
+                    Err(e) => warn!("Failed to cache PDF thumbnail for document {}: {}", document.id, e),
+                }
+                Ok(Thumbnail { data, content_type: "image/png" })
+            }
+            Err(e) => {
+                warn!("Failed to render PDF thumbnail for document {}: {}", document.id, e);
+                Ok(Thumbnail {
+                    data: PDF_PLACEHOLDER_SVG.as_bytes().to_vec(),
+                    content_type: "image/svg+xml",
+                })
+            }
+        }
+    }
+
+    fn pdf_thumbnail_filename(document: &Document) -> String {
+        let hash = document.file_hash.as_deref().unwrap_or("unhashed");
+        format!("{}_{}_thumb.png", document.id, &hash[..hash.len().min(16)])
+    }
+
+    /// Renders the first page of a PDF to PNG, bounded by `PDF_THUMBNAIL_MAX_EDGE` and `PDF_THUMBNAIL_TIMEOUT`
+    async fn render_pdf_first_page(&self, pdf_path: &str) -> Result<Vec<u8>> {
+        let temp_dir = self.get_temp_path();
+        fs::create_dir_all(&temp_dir).await?;
+        let output_prefix = temp_dir.join(format!("pdf_thumb_{}", Uuid::new_v4()));
+        let output_png = output_prefix.with_extension("png");
+
+        let child = tokio::process::Command::new("pdftoppm")
+            .arg("-f").arg("1")
+            .arg("-l").arg("1")
+            .arg("-singlefile")
+            .arg("-scale-to").arg(PDF_THUMBNAIL_MAX_EDGE.to_string())
+            .arg("-png")
+            .arg(pdf_path)
+            .arg(&output_prefix)
+            .kill_on_drop(true)
+            .output();
+
+        let result = match tokio::time::timeout(PDF_THUMBNAIL_TIMEOUT, child).await {
+            Ok(Ok(output)) if output.status.success() => fs::read(&output_png).await.map_err(anyhow::Error::from),
+            Ok(Ok(output)) => Err(anyhow::anyhow!(
+                "pdftoppm exited with {}: {}",
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )),
+            Ok(Err(e)) => Err(anyhow::anyhow!("Failed to run pdftoppm: {}", e)),
+            Err(_) => Err(anyhow::anyhow!("pdftoppm timed out after {}s", PDF_THUMBNAIL_TIMEOUT.as_secs())),
+        };
+
+        let _ = fs::remove_file(&output_png).await;
+        result
+    }
+
+    /// Removes every cached PDF thumbnail for a document, whatever file hash it was rendered from
+    async fn remove_pdf_thumbnails(&self, document_id: Uuid) {
+        let prefix = format!("{}_", document_id);
+        let Ok(mut entries) = fs::read_dir(self.get_thumbnails_path()).await else {
+            return;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(&prefix) && name.ends_with("_thumb.png") {
+                if let Err(e) = fs::remove_file(entry.path()).await {
+                    warn!("Failed to remove cached thumbnail {}: {}", entry.path().display(), e);
+                }
+            }
+        }
+    }
+
+    /// Get a searchable version of a PDF or image document, generating it with `ocrmypdf`
+    /// in `language` if needed.
+    ///
+    /// Generated PDFs are cached in the searchable PDFs directory under a name that includes
+    /// the document's file hash, so replacing the file generates a fresh one.
+    pub async fn get_or_generate_searchable_pdf(&self, document: &Document, language: &str) -> Result<Vec<u8>> {
+        let cache_dir = self.get_searchable_pdfs_path();
+        fs::create_dir_all(&cache_dir).await?;
+
+        let resolved_path = self.resolve_file_path(&document.file_path).await?;
+        let file_hash = match &document.file_hash {
+            Some(hash) => hash.clone(),
+            None => self.hash_file(&resolved_path).await?.0,
+        };
+        let cache_path = cache_dir.join(format!("{}_{}.pdf", document.id, &file_hash[..file_hash.len().min(16)]));
+        if let Ok(data) = fs::read(&cache_path).await {
+            self.record_cache_hit(DerivedAssetKind::SearchablePdf, document.id, &cache_path, data.len()).await;
+            return Ok(data);
+        }
+
+        let pdf_service = crate::services::pdf_service::PdfService::new(self.get_temp_path());
+        let is_image = document.mime_type.starts_with("image/");
+        let data = pdf_service.make_searchable(Path::new(&resolved_path), language, is_image).await?;
+
+        self.remove_searchable_pdfs(document.id).await;
+        // Written under a temporary name first so a concurrent request never reads half a file
+        let partial_path = cache_path.with_extension(format!("{}.partial", Uuid::new_v4()));
+        let cached = async {
+            fs::write(&partial_path, &data).await?;
+            fs::rename(&partial_path, &cache_path).await
+        }
+        .await;
+        match cached {
+            Ok(()) => self.record_cache_miss(DerivedAssetKind::SearchablePdf, document.id, &cache_path, data.len()).await,
+            Err(e) => {
+                warn!("Failed to cache searchable PDF for document {}: {}", document.id, e);
+                let _ = fs::remove_file(&partial_path).await;
+            }
+        }
+
+        Ok(data)
+    }
+
+    async fn record_cache_hit(&self, kind: DerivedAssetKind, document_id: Uuid, path: &Path, size: usize) {
+        if let Some(cache) = &self.asset_cache {
+            cache.record_hit(kind, document_id, &path.to_string_lossy(), size as u64).await;
+        }
+    }
+
+    async fn record_cache_miss(&self, kind: DerivedAssetKind, document_id: Uuid, path: &Path, size: usize) {
+        if let Some(cache) = &self.asset_cache {
+            cache.record_miss(kind, document_id, &path.to_string_lossy(), size as u64).await;
+        }
+    }
+
+    /// Removes every cached searchable PDF for a document, whatever file hash it was generated from
+    async fn remove_searchable_pdfs(&self, document_id: Uuid) {
+        let prefix = format!("{}_", document_id);
+        let Ok(mut entries) = fs::read_dir(self.get_searchable_pdfs_path()).await else {
+            return;
+        };
+
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with(&prefix) && name.ends_with(".pdf") {
+                if let Err(e) = fs::remove_file(entry.path()).await {
+                    warn!("Failed to remove cached searchable PDF {}: {}", entry.path().display(), e);
+                }
+            }
+        }
+    }
+
     #[cfg(feature = "ocr")]
-    async fn generate_thumbnail(&self, file_path: &str, filename: &str) -> Result<Vec<u8>> {
+    async fn generate_thumbnail(&self, file_path: &str, filename: &str, rotation_degrees: u32) -> Result<Vec<u8>> {
         let file_data = self.read_file(file_path).await?;
         
         // Determine file type from extension
@@ -294,10 +973,11 @@ impl FileService {
 
         match extension.as_str() {
             "jpg" | "jpeg" | "png" | "bmp" | "tiff" | "gif" => {
-                self.generate_image_thumbnail(&file_data).await
+                self.generate_image_thumbnail(&file_data, rotation_degrees).await
             }
             "pdf" => {
-                self.generate_pdf_thumbnail(&file_data).await
+                // Real PDF previews are rendered by get_or_generate_pdf_thumbnail
+                self.generate_placeholder_thumbnail("PDF").await
             }
             "txt" => {
                 self.generate_text_thumbnail(&file_data).await
@@ -313,9 +993,9 @@ impl FileService {
     }
 
     #[cfg(feature = "ocr")]
-    async fn generate_image_thumbnail(&self, file_data: &[u8]) -> Result<Vec<u8>> {
+    async fn generate_image_thumbnail(&self, file_data: &[u8], rotation_degrees: u32) -> Result<Vec<u8>> {
         let img = image::load_from_memory(file_data)?;
-        let thumbnail = img.resize(200, 200, FilterType::Lanczos3);
+        let thumbnail = crate::ocr::orientation::rotate_image(img.resize(200, 200, FilterType::Lanczos3), rotation_degrees);
         
         // Convert to RGB if the image has an alpha channel (RGBA)
         // JPEG doesn't support transparency, so we need to remove the alpha channel
@@ -337,80 +1017,6 @@ impl FileService {
         Ok(buffer)
     }
 
-    #[cfg(feature = "ocr")]
-    async fn generate_pdf_thumbnail(&self, file_data: &[u8]) -> Result<Vec<u8>> {
-        use std::process::Command;
-        use tokio::fs;
-        use uuid::Uuid;
-        
-        // Create a temporary file for the PDF
-        let temp_id = Uuid::new_v4();
-        let temp_pdf_path = format!("/tmp/pdf_thumb_{}.pdf", temp_id);
-        let temp_png_path = format!("/tmp/pdf_thumb_{}.png", temp_id);
-        
-        // Write PDF data to temporary file
-        if let Err(e) = fs::write(&temp_pdf_path, file_data).await {
-            error!("Failed to write temporary PDF file: {}", e);
-            return self.generate_placeholder_thumbnail("PDF").await;
-        }
-        
-        // Use pdftoppm to convert first page to PNG
-        let output = Command::new("pdftoppm")
-            .arg("-f").arg("1")          // First page only
-            .arg("-l").arg("1")          // Last page (same as first)
-            .arg("-scale-to").arg("200") // Scale to 200px width
-            .arg("-png")                 // Output as PNG
-            .arg(&temp_pdf_path)
-            .arg(&format!("/tmp/pdf_thumb_{}", temp_id)) // Output prefix
-            .output();
-        
-        // Clean up temporary PDF file
-        let _ = fs::remove_file(&temp_pdf_path).await;
-        
-        match output {
-            Ok(result) if result.status.success() => {
-                // pdftoppm adds "-1" to the filename for the first page
-                let actual_png_path = format!("/tmp/pdf_thumb_{}-1.png", temp_id);
-                
-                // Read the generated PNG file
-                match fs::read(&actual_png_path).await {
-                    Ok(png_data) => {
-                        // Clean up temporary PNG file
-                        let _ = fs::remove_file(&actual_png_path).await;
-                        
-                        // Convert PNG to JPEG thumbnail
-                        match image::load_from_memory(&png_data) {
-                            Ok(img) => {
-                                // Resize to 200x200 maintaining aspect ratio
-                                let thumbnail = img.resize(200, 200, image::imageops::FilterType::Lanczos3);
-                                
-                                // Convert to JPEG
-                                let mut buffer = Vec::new();
-                                let mut cursor = std::io::Cursor::new(&mut buffer);
-                                if thumbnail.write_to(&mut cursor, ImageFormat::Jpeg).is_ok() {
-                                    Ok(buffer)
-                                } else {
-                                    self.generate_placeholder_thumbnail("PDF").await
-                                }
-                            }
-                            Err(_) => self.generate_placeholder_thumbnail("PDF").await,
-                        }
-                    }
-                    Err(_) => {
-                        let _ = fs::remove_file(&actual_png_path).await;
-                        self.generate_placeholder_thumbnail("PDF").await
-                    }
-                }
-            }
-            _ => {
-                // Clean up any potential PNG files
-                let _ = fs::remove_file(&temp_png_path).await;
-                let _ = fs::remove_file(&format!("/tmp/pdf_thumb_{}-1.png", temp_id)).await;
-                self.generate_placeholder_thumbnail("PDF").await
-            }
-        }
-    }
-
     #[cfg(feature = "ocr")]
     async fn generate_text_thumbnail(&self, file_data: &[u8]) -> Result<Vec<u8>> {
         use image::Rgb;
@@ -503,10 +1109,51 @@ impl FileService {
     }
 
     #[cfg(not(feature = "ocr"))]
-    pub async fn get_or_generate_thumbnail(&self, _file_path: &str, _filename: &str) -> Result<Vec<u8>> {
+    pub async fn get_or_generate_thumbnail(&self, _document: &Document, _rotation_degrees: u32) -> Result<Vec<u8>> {
         anyhow::bail!("Thumbnail generation requires OCR feature")
     }
 
+    /// Move a soft-deleted document's file into the trash directory, returning its new path
+    pub async fn move_to_trash(&self, document: &Document) -> Result<String> {
+        self.move_document_file(document, &self.get_trash_path()).await
+    }
+
+    /// Move a restored document's file from the trash back into the documents directory
+    pub async fn restore_from_trash(&self, document: &Document) -> Result<String> {
+        let file_name = Path::new(&document.file_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or_default();
+        let target_dir = self.document_directory(document.file_hash.as_deref(), file_name);
+        self.move_document_file(document, &target_dir).await
+    }
+
+    async fn move_document_file(&self, document: &Document, target_dir: &Path) -> Result<String> {
+        let source = Path::new(&document.file_path);
+        let file_name = source
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Document {} has no file name in path {}", document.id, document.file_path))?;
+        let target = target_dir.join(file_name);
+
+        if source == target {
+            return Ok(document.file_path.clone());
+        }
+
+        fs::create_dir_all(target_dir).await?;
+        match fs::rename(source, &target).await {
+            Ok(_) => {
+                info!("Moved file for document {}: {} -> {}", document.id, source.display(), target.display());
+                Ok(target.to_string_lossy().to_string())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                // Nothing to move (already gone); keep the recorded path so purge/restore stay consistent
+                warn!("File for document {} not found at {}, leaving path unchanged", document.id, source.display());
+                Ok(document.file_path.clone())
+            }
+            Err(e) => Err(anyhow::anyhow!("Failed to move {} to {}: {}", source.display(), target.display(), e)),
+        }
+    }
+
     pub async fn delete_document_files(&self, document: &Document) -> Result<()> {
         let mut deleted_files = Vec::new();
         let mut serious_errors = Vec::new();
@@ -549,6 +1196,12 @@ impl FileService {
             deleted_files.push(deleted_path);
         }
 
+        // Delete rendered PDF thumbnails
+        self.remove_pdf_thumbnails(document.id).await;
+
+        // Delete generated searchable PDFs
+        self.remove_searchable_pdfs(document.id).await;
+
         // Delete processed image if it exists
         let processed_image_filename = format!("{}_processed.png", document.id);
         let processed_image_path = self.get_processed_images_path().join(&processed_image_filename);
@@ -0,0 +1,19 @@
+//! Include/exclude glob filters shared by sources that list remote paths (SMB, S3)
+
+use anyhow::{anyhow, Result};
+
+pub fn compile_patterns(patterns: &[String]) -> Result<Vec<glob::Pattern>> {
+    patterns
+        .iter()
+        .map(|p| glob::Pattern::new(p).map_err(|e| anyhow!("Invalid glob pattern '{}': {}", p, e)))
+        .collect()
+}
+
+/// Patterns containing a `/` match the path relative to the source root; others match just the name
+pub fn pattern_matches(pattern: &glob::Pattern, relative_path: &str, name: &str) -> bool {
+    if pattern.as_str().contains('/') {
+        pattern.matches(relative_path)
+    } else {
+        pattern.matches(name)
+    }
+}
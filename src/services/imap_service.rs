@@ -0,0 +1,478 @@
+use anyhow::{anyhow, Result};
+use tracing::{debug, info, warn};
+
+#[cfg(feature = "imap")]
+use std::sync::Arc;
+#[cfg(feature = "imap")]
+use futures::TryStreamExt;
+#[cfg(feature = "imap")]
+use mail_parser::{Message, MessageParser, MimeHeaders};
+#[cfg(feature = "imap")]
+use tokio::io::{AsyncRead, AsyncWrite};
+#[cfg(feature = "imap")]
+use tokio::net::TcpStream;
+#[cfg(feature = "imap")]
+use tokio_rustls::{rustls, TlsConnector};
+
+use crate::models::{FileIngestionInfo, ImapSourceConfig, ImapTlsMode};
+#[cfg(feature = "imap")]
+use crate::services::source_connection_test::ConnectionTestError;
+
+/// Upper bound on messages downloaded per sync; the rest are picked up by the next poll
+const MAX_MESSAGES_PER_SYNC: usize = 50;
+
+/// An unseen message and the attachments in it that passed the source's extension filter
+#[derive(Debug)]
+pub struct ImapMessage {
+    pub uid: u32,
+    pub attachments: Vec<ImapAttachment>,
+}
+
+#[derive(Debug)]
+pub struct ImapAttachment {
+    pub file_info: FileIngestionInfo,
+    pub data: Vec<u8>,
+}
+
+#[cfg(feature = "imap")]
+trait ImapStream: AsyncRead + AsyncWrite + Unpin + Send + std::fmt::Debug {}
+
+#[cfg(feature = "imap")]
+impl<T: AsyncRead + AsyncWrite + Unpin + Send + std::fmt::Debug> ImapStream for T {}
+
+#[cfg(feature = "imap")]
+type ImapSession = async_imap::Session<Box<dyn ImapStream>>;
+
+#[derive(Debug, Clone)]
+pub struct ImapService {
+    config: ImapSourceConfig,
+}
+
+impl ImapService {
+    pub fn new(config: ImapSourceConfig) -> Result<Self> {
+        #[cfg(not(feature = "imap"))]
+        {
+            let _ = config;
+            return Err(anyhow!("IMAP support not compiled in"));
+        }
+
+        #[cfg(feature = "imap")]
+        {
+            if config.host.trim().is_empty() {
+                return Err(anyhow!("IMAP host is required"));
+            }
+            if config.username.trim().is_empty() {
+                return Err(anyhow!("IMAP username is required"));
+            }
+            if config.tls_mode == ImapTlsMode::None {
+                warn!("IMAP source for {} is configured without TLS; credentials are sent in plain text", config.host);
+            }
+
+            Ok(Self { config })
+        }
+    }
+
+    /// Fetch unseen messages from the configured folder and extract their attachments.
+    ///
+    /// Messages are fetched with `BODY.PEEK[]` so they stay unseen until [`mark_seen`](Self::mark_seen)
+    /// is called, which lets a failed ingestion be retried on the next poll.
+    pub async fn fetch_unseen_messages(&self) -> Result<Vec<ImapMessage>> {
+        #[cfg(not(feature = "imap"))]
+        {
+            return Err(anyhow!("IMAP support not compiled in"));
+        }
+
+        #[cfg(feature = "imap")]
+        {
+        let mut session = self.connect().await?;
+        let mailbox = session
+            .select(&self.config.folder)
+            .await
+            .map_err(|e| anyhow!("Failed to open IMAP folder '{}': {}", self.config.folder, e))?;
+        let uid_validity = mailbox.uid_validity.unwrap_or(0);
+
+        let mut uids: Vec<u32> = session
+            .uid_search("UNSEEN")
+            .await
+            .map_err(|e| anyhow!("Failed to search IMAP folder '{}': {}", self.config.folder, e))?
+            .into_iter()
+            .collect();
+        uids.sort_unstable();
+
+        if uids.len() > MAX_MESSAGES_PER_SYNC {
+            info!(
+                "{} unseen messages in {}, fetching the oldest {} this sync",
+                uids.len(), self.config.folder, MAX_MESSAGES_PER_SYNC
+            );
+            uids.truncate(MAX_MESSAGES_PER_SYNC);
+        }
+
+        let mut messages = Vec::with_capacity(uids.len());
+        if !uids.is_empty() {
+            let fetches: Vec<_> = session
+                .uid_fetch(uid_set(&uids), "(UID BODY.PEEK[])")
+                .await
+                .map_err(|e| anyhow!("Failed to fetch IMAP messages: {}", e))?
+                .try_collect()
+                .await
+                .map_err(|e| anyhow!("Failed to fetch IMAP messages: {}", e))?;
+
+            for fetch in &fetches {
+                let (Some(uid), Some(body)) = (fetch.uid, fetch.body()) else {
+                    continue;
+                };
+
+                match MessageParser::default().parse(body) {
+                    Some(message) => messages.push(ImapMessage {
+                        uid,
+                        attachments: self.extract_attachments(&message, uid_validity, uid),
+                    }),
+                    None => warn!("Failed to parse IMAP message {} in {}", uid, self.config.folder),
+                }
+            }
+        }
+
+        if let Err(e) = session.logout().await {
+            debug!("IMAP logout failed: {}", e);
+        }
+
+        info!(
+            "Fetched {} unseen messages with {} attachments from {} on {}",
+            messages.len(),
+            messages.iter().map(|m| m.attachments.len()).sum::<usize>(),
+            self.config.folder,
+            self.config.host
+        );
+        Ok(messages)
+        }
+    }
+
+    /// Flag messages as `\Seen` so later polls skip them
+    pub async fn mark_seen(&self, uids: &[u32]) -> Result<()> {
+        #[cfg(not(feature = "imap"))]
+        {
+            let _ = uids;
+            return Err(anyhow!("IMAP support not compiled in"));
+        }
+
+        #[cfg(feature = "imap")]
+        {
+        if uids.is_empty() {
+            return Ok(());
+        }
+
+        let mut session = self.connect().await?;
+        session
+            .select(&self.config.folder)
+            .await
+            .map_err(|e| anyhow!("Failed to open IMAP folder '{}': {}", self.config.folder, e))?;
+
+        let _: Vec<_> = session
+            .uid_store(uid_set(uids), "+FLAGS.SILENT (\\Seen)")
+            .await
+            .map_err(|e| anyhow!("Failed to mark IMAP messages as seen: {}", e))?
+            .try_collect()
+            .await
+            .map_err(|e| anyhow!("Failed to mark IMAP messages as seen: {}", e))?;
+
+        if let Err(e) = session.logout().await {
+            debug!("IMAP logout failed: {}", e);
+        }
+
+        debug!("Marked {} IMAP messages as seen in {}", uids.len(), self.config.folder);
+        Ok(())
+        }
+    }
+
+    /// Log in and open the folder without changing any message flags
+    pub async fn test_connection(&self) -> Result<String> {
+        #[cfg(not(feature = "imap"))]
+        {
+            return Err(anyhow!("IMAP support not compiled in"));
+        }
+
+        #[cfg(feature = "imap")]
+        {
+        let mut session = self.connect().await?;
+        let mailbox = session
+            .examine(&self.config.folder)
+            .await
+            .map_err(|e| ConnectionTestError::ResourceUnavailable(format!(
+                "IMAP folder '{}' could not be opened: {}",
+                self.config.folder, e
+            )))?;
+        let unseen = session
+            .uid_search("UNSEEN")
+            .await
+            .map(|uids| uids.len())
+            .unwrap_or(0);
+
+        if let Err(e) = session.logout().await {
+            debug!("IMAP logout failed: {}", e);
+        }
+
+        Ok(format!(
+            "Successfully connected to {} as {}; folder '{}' has {} messages ({} unseen)",
+            self.config.host, self.config.username, self.config.folder, mailbox.exists, unseen
+        ))
+        }
+    }
+
+    pub fn get_config(&self) -> &ImapSourceConfig {
+        &self.config
+    }
+
+    #[cfg(feature = "imap")]
+    async fn connect(&self) -> Result<ImapSession> {
+        let host = self.config.host.trim();
+        let port = self.config.effective_port();
+
+        let tcp = TcpStream::connect((host, port))
+            .await
+            .map_err(|e| ConnectionTestError::Unreachable(format!(
+                "Could not reach IMAP server {}:{}: {}",
+                host, port, e
+            )))?;
+
+        let stream: Box<dyn ImapStream> = match self.config.tls_mode {
+            ImapTlsMode::Tls => Box::new(tls_connect(host, tcp).await?),
+            ImapTlsMode::StartTls => {
+                let mut client = async_imap::Client::new(tcp);
+                client
+                    .run_command_and_check_ok("STARTTLS", None)
+                    .await
+                    .map_err(|e| ConnectionTestError::Unreachable(format!(
+                        "IMAP server {} rejected STARTTLS: {}",
+                        host, e
+                    )))?;
+                Box::new(tls_connect(host, client.into_inner()).await?)
+            }
+            ImapTlsMode::None => Box::new(tcp),
+        };
+
+        async_imap::Client::new(stream)
+            .login(&self.config.username, &self.config.password)
+            .await
+            .map_err(|(e, _)| ConnectionTestError::AuthenticationFailed(format!(
+                "IMAP login failed for user '{}' on {}: {}",
+                self.config.username, host, e
+            )).into())
+    }
+
+    #[cfg(feature = "imap")]
+    fn extract_attachments(&self, message: &Message<'_>, uid_validity: u32, uid: u32) -> Vec<ImapAttachment> {
+        let sender = message
+            .from()
+            .and_then(|from| from.first())
+            .and_then(|addr| addr.address())
+            .unwrap_or("")
+            .to_string();
+        let subject = message.subject().unwrap_or("").to_string();
+        let received_at = message
+            .date()
+            .and_then(|date| chrono::DateTime::from_timestamp(date.to_timestamp(), 0));
+
+        let mut parts = Vec::new();
+        collect_attachment_parts(message, &mut parts);
+
+        let mut attachments = Vec::new();
+        for (index, part) in parts.into_iter().enumerate() {
+            let content_type = part
+                .content_type()
+                .map(|ct| format!("{}/{}", ct.ctype(), ct.subtype().unwrap_or("octet-stream")).to_lowercase())
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            let filename = part.attachment_name();
+
+            if !is_real_attachment(
+                part.content_disposition().map(|cd| cd.ctype()),
+                part.content_id(),
+                &content_type,
+                filename,
+            ) {
+                continue;
+            }
+
+            let Some(name) = filename.map(sanitize_attachment_name) else {
+                continue;
+            };
+            if !self.matches_extension(&name) {
+                debug!("Skipping attachment {} in message {}: extension not in source filter", name, uid);
+                continue;
+            }
+
+            let data = part.contents().to_vec();
+            let mut metadata = serde_json::Map::new();
+            metadata.insert("sender".to_string(), serde_json::Value::String(sender.clone()));
+            metadata.insert("subject".to_string(), serde_json::Value::String(subject.clone()));
+            if let Some(message_id) = message.message_id() {
+                metadata.insert("message_id".to_string(), serde_json::Value::String(message_id.to_string()));
+            }
+            metadata.insert("imap_host".to_string(), serde_json::Value::String(self.config.host.clone()));
+            metadata.insert("imap_folder".to_string(), serde_json::Value::String(self.config.folder.clone()));
+            metadata.insert("imap_uid".to_string(), serde_json::Value::Number(uid.into()));
+
+            let relative_path = attachment_source_path(&self.config.folder, uid_validity, uid, index, &name);
+            let mime_type = if content_type == "application/octet-stream" {
+                mime_guess::from_path(&name).first_or_octet_stream().to_string()
+            } else {
+                content_type
+            };
+
+            attachments.push(ImapAttachment {
+                file_info: FileIngestionInfo {
+                    full_path: format!("imap://{}/{}", self.config.host, relative_path),
+                    #[allow(deprecated)]
+                    path: relative_path.clone(),
+                    relative_path,
+                    name,
+                    size: data.len() as i64,
+                    mime_type,
+                    last_modified: received_at,
+                    etag: format!("{}-{}-{}", uid_validity, uid, index),
+                    is_directory: false,
+                    created_at: received_at,
+                    permissions: None,
+                    owner: None,
+                    group: None,
+                    metadata: Some(serde_json::Value::Object(metadata)),
+                },
+                data,
+            });
+        }
+
+        attachments
+    }
+
+    fn matches_extension(&self, name: &str) -> bool {
+        let extension = std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        self.config.file_extensions.iter().any(|ext| ext.trim_start_matches('.').eq_ignore_ascii_case(&extension))
+    }
+}
+
+/// Walk the MIME tree, descending into forwarded (`message/rfc822`) messages
+#[cfg(feature = "imap")]
+fn collect_attachment_parts<'a>(message: &'a Message<'a>, parts: &mut Vec<&'a mail_parser::MessagePart<'a>>) {
+    for part in message.attachments() {
+        match part.message() {
+            Some(nested) => collect_attachment_parts(nested, parts),
+            None => parts.push(part),
+        }
+    }
+}
+
+#[cfg(feature = "imap")]
+async fn tls_connect<S>(host: &str, stream: S) -> Result<tokio_rustls::client::TlsStream<S>>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let roots = rustls::RootCertStore {
+        roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+    };
+    let config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    let server_name = rustls::pki_types::ServerName::try_from(host.to_string())
+        .map_err(|e| anyhow!("Invalid IMAP host name '{}': {}", host, e))?;
+
+    TlsConnector::from(Arc::new(config))
+        .connect(server_name, stream)
+        .await
+        .map_err(|e| ConnectionTestError::Unreachable(format!("TLS handshake with {} failed: {}", host, e)).into())
+}
+
+#[cfg(feature = "imap")]
+fn uid_set(uids: &[u32]) -> String {
+    uids.iter().map(|uid| uid.to_string()).collect::<Vec<_>>().join(",")
+}
+
+/// Inline parts referenced by a Content-ID (signature logos, embedded screenshots) are
+/// part of the message body, not documents. Parts without a filename are body text.
+fn is_real_attachment(disposition: Option<&str>, content_id: Option<&str>, content_type: &str, filename: Option<&str>) -> bool {
+    if filename.map_or(true, |name| name.trim().is_empty()) {
+        return false;
+    }
+
+    match disposition.map(|d| d.to_ascii_lowercase()) {
+        Some(d) if d == "attachment" => true,
+        // Some clients mark real attachments inline; only skip those embedded in the body
+        Some(d) if d == "inline" => content_id.is_none(),
+        _ => !(content_id.is_some() && content_type.starts_with("image/")),
+    }
+}
+
+fn sanitize_attachment_name(name: &str) -> String {
+    name.trim()
+        .chars()
+        .map(|c| if matches!(c, '/' | '\\') || c.is_control() { '_' } else { c })
+        .collect()
+}
+
+/// Unique per attachment, so two messages both carrying `scan.pdf` aren't treated as versions of one file
+fn attachment_source_path(folder: &str, uid_validity: u32, uid: u32, index: usize, name: &str) -> String {
+    format!("{}/{}/{}/{}/{}", folder.trim_matches('/'), uid_validity, uid, index, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_real_attachment() {
+        assert!(is_real_attachment(Some("attachment"), None, "application/pdf", Some("invoice.pdf")));
+        assert!(is_real_attachment(Some("ATTACHMENT"), Some("<img1>"), "image/png", Some("scan.png")));
+        assert!(is_real_attachment(Some("inline"), None, "application/pdf", Some("invoice.pdf")));
+        assert!(is_real_attachment(None, None, "image/jpeg", Some("photo.jpg")));
+
+        // Embedded images referenced from the HTML body
+        assert!(!is_real_attachment(Some("inline"), Some("<logo@example.com>"), "image/png", Some("logo.png")));
+        assert!(!is_real_attachment(None, Some("<logo@example.com>"), "image/png", Some("logo.png")));
+        // Body parts without a filename
+        assert!(!is_real_attachment(None, None, "text/plain", None));
+        assert!(!is_real_attachment(Some("attachment"), None, "application/pdf", Some("  ")));
+    }
+
+    #[test]
+    fn test_sanitize_attachment_name() {
+        assert_eq!(sanitize_attachment_name(" ../etc/passwd "), ".._etc_passwd");
+        assert_eq!(sanitize_attachment_name("a\\b\tc.pdf"), "a_b_c.pdf");
+        assert_eq!(sanitize_attachment_name("invoice.pdf"), "invoice.pdf");
+    }
+
+    #[test]
+    fn test_attachment_source_path_is_unique_per_part() {
+        let first = attachment_source_path("INBOX", 7, 42, 0, "scan.pdf");
+        let second = attachment_source_path("INBOX", 7, 42, 1, "scan.pdf");
+        assert_eq!(first, "INBOX/7/42/0/scan.pdf");
+        assert_ne!(first, second);
+        assert_ne!(first, attachment_source_path("INBOX", 7, 43, 0, "scan.pdf"));
+    }
+
+    #[test]
+    fn test_matches_extension() {
+        let service = ImapService {
+            config: ImapSourceConfig {
+                host: "imap.example.com".to_string(),
+                port: None,
+                username: "scanner".to_string(),
+                password: "secret".to_string(),
+                folder: "INBOX".to_string(),
+                tls_mode: ImapTlsMode::Tls,
+                file_extensions: vec!["pdf".to_string(), ".PNG".to_string()],
+                auto_sync: true,
+                sync_interval_minutes: 15,
+            },
+        };
+
+        assert!(service.matches_extension("Invoice.PDF"));
+        assert!(service.matches_extension("scan.png"));
+        assert!(!service.matches_extension("notes.docx"));
+        assert!(!service.matches_extension("README"));
+        assert_eq!(service.get_config().effective_port(), 993);
+    }
+}
@@ -8,6 +8,7 @@ use sha2::{Sha256, Digest};
 use serde_json;
 
 use crate::models::{FileIngestionInfo, LocalFolderSourceConfig};
+use crate::services::source_connection_test::ConnectionTestError;
 
 #[derive(Debug, Clone)]
 pub struct LocalFolderService {
@@ -20,10 +21,10 @@ impl LocalFolderService {
         for folder in &config.watch_folders {
             let path = Path::new(folder);
             if !path.exists() {
-                return Err(anyhow!("Watch folder does not exist: {}", folder));
+                return Err(ConnectionTestError::Unreachable(format!("Watch folder does not exist: {}", folder)).into());
             }
             if !path.is_dir() {
-                return Err(anyhow!("Watch folder is not a directory: {}", folder));
+                return Err(ConnectionTestError::ResourceUnavailable(format!("Watch folder is not a directory: {}", folder)).into());
             }
         }
 
@@ -192,6 +193,10 @@ impl LocalFolderService {
         let mut total_files = 0;
 
         for folder in &self.config.watch_folders {
+            // Walking the folder skips what it can't read, so check the folder itself first
+            if let Err(e) = fs::read_dir(folder) {
+                return Err(ConnectionTestError::ResourceUnavailable(format!("Cannot read folder {}: {}", folder, e)).into());
+            }
             match self.discover_files_in_folder(folder).await {
                 Ok(files) => {
                     accessible_folders += 1;
@@ -0,0 +1,484 @@
+/*!
+ * Document Metadata Import
+ *
+ * Applies tags, labels and original creation dates from a CSV or JSON export of another
+ * system to documents that are already in Readur. Each row is matched to the importing
+ * user's own documents by `original_filename` and/or `file_hash`. Rows are validated and
+ * applied one by one, so a bad row is reported without holding back the rest.
+ */
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use thiserror::Error;
+use tracing::error;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::db::Database;
+
+/// Most rows accepted in one import
+pub const MAX_IMPORT_ROWS: usize = 10_000;
+
+/// Separates several tags or labels within one CSV field
+pub const CSV_LIST_SEPARATOR: char = ';';
+
+const CSV_COLUMNS: [&str; 5] = ["original_filename", "file_hash", "tags", "labels", "original_created_at"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetadataImportFormat {
+    Csv,
+    Json,
+}
+
+/// Problems with the import as a whole; problems with single rows are reported per row
+#[derive(Error, Debug)]
+pub enum MetadataImportError {
+    #[error("Invalid CSV: {0}")]
+    InvalidCsv(String),
+
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("The import contains no rows")]
+    Empty,
+
+    #[error("The import has {0} rows; at most {MAX_IMPORT_ROWS} are allowed")]
+    TooManyRows(usize),
+
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+/// A row as it appears in the upload, before validation
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawRow {
+    original_filename: Option<String>,
+    file_hash: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    original_created_at: Option<String>,
+}
+
+/// A validated row, ready to be matched and applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetadataImportRow {
+    pub original_filename: Option<String>,
+    /// Lowercase hex SHA-256
+    pub file_hash: Option<String>,
+    pub tags: Vec<String>,
+    pub labels: Vec<String>,
+    pub original_created_at: Option<DateTime<Utc>>,
+}
+
+/// One row of the upload with the keys it was given, for reporting
+#[derive(Debug)]
+pub struct ParsedRow {
+    /// 1-based position among the data rows (a CSV header is not counted)
+    pub row: usize,
+    pub original_filename: Option<String>,
+    pub file_hash: Option<String>,
+    pub parsed: Result<MetadataImportRow, Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataImportRowStatus {
+    /// The metadata was applied to the matched documents
+    Applied,
+    /// No document of the user matches the row
+    Unmatched,
+    /// The row failed validation or matched more than one document; nothing was changed
+    Invalid,
+    /// The row was valid but updating a matched document failed
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MetadataImportRowResult {
+    /// 1-based position among the data rows (a CSV header is not counted)
+    pub row: usize,
+    pub status: MetadataImportRowStatus,
+    pub original_filename: Option<String>,
+    pub file_hash: Option<String>,
+    /// Documents the row was applied to
+    pub document_ids: Vec<Uuid>,
+    pub errors: Vec<String>,
+}
+
+/// Splits an upload into rows and validates each one
+pub fn parse_rows(format: MetadataImportFormat, body: &str) -> Result<Vec<ParsedRow>, MetadataImportError> {
+    let body = body.strip_prefix('\u{feff}').unwrap_or(body);
+    let rows = match format {
+        MetadataImportFormat::Csv => parse_csv_rows(body)?,
+        MetadataImportFormat::Json => parse_json_rows(body)?,
+    };
+
+    if rows.is_empty() {
+        return Err(MetadataImportError::Empty);
+    }
+    if rows.len() > MAX_IMPORT_ROWS {
+        return Err(MetadataImportError::TooManyRows(rows.len()));
+    }
+    Ok(rows)
+}
+
+fn parse_json_rows(body: &str) -> Result<Vec<ParsedRow>, MetadataImportError> {
+    let values: Vec<serde_json::Value> = serde_json::from_str(body)
+        .map_err(|e| MetadataImportError::InvalidJson(format!("expected an array of row objects: {}", e)))?;
+
+    Ok(values
+        .into_iter()
+        .enumerate()
+        .map(|(i, value)| {
+            let key = |name: &str| value.get(name).and_then(|v| v.as_str()).map(str::to_string);
+            let (original_filename, file_hash) = (key("original_filename"), key("file_hash"));
+            let parsed = serde_json::from_value::<RawRow>(value)
+                .map_err(|e| vec![e.to_string()])
+                .and_then(validate_row);
+            ParsedRow { row: i + 1, original_filename, file_hash, parsed }
+        })
+        .collect())
+}
+
+fn parse_csv_rows(body: &str) -> Result<Vec<ParsedRow>, MetadataImportError> {
+    let mut records = parse_csv_records(body)?.into_iter();
+    let header: Vec<String> = records
+        .next()
+        .ok_or(MetadataImportError::Empty)?
+        .into_iter()
+        .map(|name| name.trim().to_lowercase())
+        .collect();
+
+    for name in &header {
+        if !CSV_COLUMNS.contains(&name.as_str()) {
+            return Err(MetadataImportError::InvalidCsv(format!(
+                "unknown column '{}'; expected {}",
+                name,
+                CSV_COLUMNS.join(", ")
+            )));
+        }
+    }
+    if !header.iter().any(|name| name == "original_filename" || name == "file_hash") {
+        return Err(MetadataImportError::InvalidCsv(
+            "the header needs an original_filename or file_hash column".to_string(),
+        ));
+    }
+
+    Ok(records
+        .filter(|fields| !(fields.len() == 1 && fields[0].trim().is_empty()))
+        .enumerate()
+        .map(|(i, fields)| {
+            let field = |name: &str| {
+                header
+                    .iter()
+                    .position(|column| column == name)
+                    .and_then(|index| fields.get(index))
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+            let list = |name: &str| {
+                field(name)
+                    .map(|value| value.split(CSV_LIST_SEPARATOR).map(str::to_string).collect())
+                    .unwrap_or_default()
+            };
+
+            let raw = RawRow {
+                original_filename: field("original_filename"),
+                file_hash: field("file_hash"),
+                tags: list("tags"),
+                labels: list("labels"),
+                original_created_at: field("original_created_at"),
+            };
+            let (original_filename, file_hash) = (raw.original_filename.clone(), raw.file_hash.clone());
+            let parsed = if fields.len() != header.len() {
+                Err(vec![format!("expected {} fields, found {}", header.len(), fields.len())])
+            } else {
+                validate_row(raw)
+            };
+            ParsedRow { row: i + 1, original_filename, file_hash, parsed }
+        })
+        .collect())
+}
+
+/// Splits CSV text into records. Quoted fields may contain commas, doubled quotes and line breaks.
+fn parse_csv_records(text: &str) -> Result<Vec<Vec<String>>, MetadataImportError> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+
+        match c {
+            '"' if field.is_empty() => in_quotes = true,
+            ',' => record.push(std::mem::take(&mut field)),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                record.push(std::mem::take(&mut field));
+                records.push(std::mem::take(&mut record));
+            }
+            _ => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err(MetadataImportError::InvalidCsv(format!(
+            "unterminated quoted field in record {}",
+            records.len() + 1
+        )));
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// Checks a row and normalizes its values, collecting every problem rather than the first
+fn validate_row(raw: RawRow) -> Result<MetadataImportRow, Vec<String>> {
+    let mut errors = Vec::new();
+
+    let original_filename = raw.original_filename.map(|name| name.trim().to_string()).filter(|name| !name.is_empty());
+    let file_hash = raw.file_hash.map(|hash| hash.trim().to_lowercase()).filter(|hash| !hash.is_empty());
+    if original_filename.is_none() && file_hash.is_none() {
+        errors.push("row needs an original_filename or file_hash to match a document".to_string());
+    }
+    if let Some(hash) = &file_hash {
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            errors.push(format!("file_hash '{}' is not a hex SHA-256 digest", hash));
+        }
+    }
+
+    let original_created_at = match raw.original_created_at.as_deref().map(str::trim).filter(|value| !value.is_empty()) {
+        Some(value) => match parse_date(value) {
+            Some(date) => Some(date),
+            None => {
+                errors.push(format!(
+                    "original_created_at '{}' is not an RFC 3339 timestamp or a YYYY-MM-DD date",
+                    value
+                ));
+                None
+            }
+        },
+        None => None,
+    };
+
+    let tags = normalize_list(raw.tags);
+    let labels = normalize_list(raw.labels);
+    if tags.is_empty() && labels.is_empty() && original_created_at.is_none() && errors.is_empty() {
+        errors.push("row has no tags, labels or original_created_at to apply".to_string());
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(MetadataImportRow { original_filename, file_hash, tags, labels, original_created_at })
+}
+
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|midnight| midnight.and_utc())
+}
+
+/// Trims entries and drops empty ones and repeats, keeping the first spelling of each
+fn normalize_list(values: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if !value.is_empty() && !normalized.iter().any(|existing| existing == value) {
+            normalized.push(value.to_string());
+        }
+    }
+    normalized
+}
+
+/// Matches each row to `user_id`'s documents and applies it. Only the user's own documents
+/// are ever matched, whatever their role.
+pub async fn import_document_metadata(
+    db: &Database,
+    user_id: Uuid,
+    rows: Vec<ParsedRow>,
+) -> Result<Vec<MetadataImportRowResult>, MetadataImportError> {
+    // Label names are matched case-insensitively; the user's own labels win over system labels
+    let mut labels_by_name: HashMap<String, Uuid> = HashMap::new();
+    for (id, name) in db.get_assignable_labels(user_id).await? {
+        labels_by_name.entry(name.to_lowercase()).or_insert(id);
+    }
+
+    let mut results = Vec::with_capacity(rows.len());
+    for parsed_row in rows {
+        let mut result = MetadataImportRowResult {
+            row: parsed_row.row,
+            status: MetadataImportRowStatus::Invalid,
+            original_filename: parsed_row.original_filename,
+            file_hash: parsed_row.file_hash,
+            document_ids: Vec::new(),
+            errors: Vec::new(),
+        };
+
+        let row = match parsed_row.parsed {
+            Ok(row) => row,
+            Err(errors) => {
+                result.errors = errors;
+                results.push(result);
+                continue;
+            }
+        };
+
+        let mut label_ids = Vec::with_capacity(row.labels.len());
+        for label in &row.labels {
+            match labels_by_name.get(&label.to_lowercase()) {
+                Some(id) => label_ids.push(*id),
+                None => result.errors.push(format!("unknown label '{}'", label)),
+            }
+        }
+        if !result.errors.is_empty() {
+            results.push(result);
+            continue;
+        }
+
+        let documents = db
+            .find_documents_for_metadata_import(user_id, row.original_filename.as_deref(), row.file_hash.as_deref())
+            .await?;
+        match documents.len() {
+            0 => {
+                result.status = MetadataImportRowStatus::Unmatched;
+                results.push(result);
+                continue;
+            }
+            1 => {}
+            count => {
+                result.errors.push(format!(
+                    "matches {} documents; add a file_hash to pick one",
+                    if count >= 100 { "100 or more".to_string() } else { count.to_string() }
+                ));
+                results.push(result);
+                continue;
+            }
+        }
+
+        let (document_id, existing_tags) = documents.into_iter().next().expect("one matched document");
+        let tags = (!row.tags.is_empty()).then(|| {
+            let mut merged = existing_tags;
+            for tag in &row.tags {
+                if !merged.contains(tag) {
+                    merged.push(tag.clone());
+                }
+            }
+            merged
+        });
+
+        match db
+            .apply_imported_document_metadata(document_id, user_id, tags.as_deref(), row.original_created_at, &label_ids)
+            .await
+        {
+            Ok(()) => {
+                result.status = MetadataImportRowStatus::Applied;
+                result.document_ids.push(document_id);
+            }
+            Err(e) => {
+                error!("Failed to apply imported metadata to document {}: {}", document_id, e);
+                result.status = MetadataImportRowStatus::Failed;
+                result.errors.push("failed to update the matched document".to_string());
+            }
+        }
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_quoted_fields_and_lists() {
+        let body = "\u{feff}original_filename,tags,labels,original_created_at\r\n\
+                    \"Scan, 2019.pdf\",tax; receipts ;tax,Finance,2019-04-01\r\n\
+                    \"He said \"\"hi\"\".pdf\",,,2020-01-02T03:04:05+02:00\r\n";
+        let rows = parse_rows(MetadataImportFormat::Csv, body).unwrap();
+        assert_eq!(rows.len(), 2);
+
+        let first = rows[0].parsed.as_ref().unwrap();
+        assert_eq!(first.original_filename.as_deref(), Some("Scan, 2019.pdf"));
+        assert_eq!(first.tags, ["tax", "receipts"]);
+        assert_eq!(first.labels, ["Finance"]);
+        assert_eq!(first.original_created_at.unwrap().to_rfc3339(), "2019-04-01T00:00:00+00:00");
+
+        let second = rows[1].parsed.as_ref().unwrap();
+        assert_eq!(second.original_filename.as_deref(), Some("He said \"hi\".pdf"));
+        assert_eq!(second.original_created_at.unwrap().to_rfc3339(), "2020-01-02T01:04:05+00:00");
+    }
+
+    #[test]
+    fn test_invalid_rows_report_every_problem() {
+        let body = "file_hash,original_created_at\nnot-a-hash,yesterday\n,\n";
+        let rows = parse_rows(MetadataImportFormat::Csv, body).unwrap();
+
+        let errors = rows[0].parsed.as_ref().unwrap_err();
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors[0].contains("not a hex SHA-256 digest"));
+        assert!(errors[1].contains("original_created_at 'yesterday'"));
+
+        let errors = rows[1].parsed.as_ref().unwrap_err();
+        assert_eq!(errors, &["row needs an original_filename or file_hash to match a document"]);
+    }
+
+    #[test]
+    fn test_csv_schema_errors_reject_the_import() {
+        assert!(matches!(
+            parse_rows(MetadataImportFormat::Csv, "filename,tags\na.pdf,x\n"),
+            Err(MetadataImportError::InvalidCsv(_))
+        ));
+        assert!(matches!(
+            parse_rows(MetadataImportFormat::Csv, "tags\nx\n"),
+            Err(MetadataImportError::InvalidCsv(_))
+        ));
+        assert!(matches!(
+            parse_rows(MetadataImportFormat::Csv, "original_filename\n\"a.pdf\n"),
+            Err(MetadataImportError::InvalidCsv(_))
+        ));
+        assert!(matches!(parse_rows(MetadataImportFormat::Csv, "original_filename\n"), Err(MetadataImportError::Empty)));
+    }
+
+    #[test]
+    fn test_json_rows_are_validated_one_by_one() {
+        let body = r#"[
+            {"original_filename": "a.pdf", "tags": ["x"]},
+            {"original_filename": "b.pdf", "tags": "x"},
+            {"original_filename": "c.pdf", "colour": "red"}
+        ]"#;
+        let rows = parse_rows(MetadataImportFormat::Json, body).unwrap();
+        assert!(rows[0].parsed.is_ok());
+        assert_eq!(rows[1].original_filename.as_deref(), Some("b.pdf"));
+        assert!(rows[1].parsed.is_err());
+        assert!(rows[2].parsed.as_ref().unwrap_err()[0].contains("colour"));
+
+        assert!(matches!(
+            parse_rows(MetadataImportFormat::Json, r#"{"rows": []}"#),
+            Err(MetadataImportError::InvalidJson(_))
+        ));
+    }
+}
@@ -1,8 +1,32 @@
+pub mod byte_range;
+pub mod client_certificate;
+pub mod database_maintenance_service;
+pub mod derived_asset_cache;
+pub mod document_backfill_service;
+pub mod document_redownload_service;
+pub mod document_reingest_service;
+pub mod document_runs;
+pub mod document_share_service;
+pub mod email_service;
 pub mod file_service;
+pub mod glob_filters;
+pub mod imap_service;
 pub mod local_folder_service;
+pub mod metadata_import_service;
+pub mod ocr_progress_tracker;
 pub mod ocr_retry_service;
+pub mod password_reset_service;
+pub mod pdf_service;
+pub mod perceptual_hash;
+pub mod processing_time_model;
 pub mod s3_service;
 pub mod s3_service_stub;
+pub mod smb_service;
+pub mod source_file_deletion_service;
+pub mod source_secrets;
+pub mod source_connection_test;
+pub mod source_upload_service;
 pub mod sync_progress_tracker;
+pub mod two_factor_service;
 pub mod user_watch_service;
 pub mod webdav;
\ No newline at end of file
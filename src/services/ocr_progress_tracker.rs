@@ -0,0 +1,85 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+/// Events buffered per subscriber; a subscriber that falls further behind skips the oldest
+const EVENT_BUFFER: usize = 256;
+
+/// What happened to an OCR job
+#[derive(Debug, Clone, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum OcrEventKind {
+    Enqueued { priority: i32 },
+    Started { filename: String },
+    /// The job moved to another step, e.g. `recognizing_text` or `saving_results`
+    Progress { stage: String },
+    Completed { confidence: f32, word_count: i32, processing_time_ms: i32 },
+    /// `will_retry` is set when the job goes back to the queue for another attempt
+    Failed { error: String, will_retry: bool },
+}
+
+impl OcrEventKind {
+    /// The message type used on the OCR progress websocket
+    pub fn name(&self) -> &'static str {
+        match self {
+            OcrEventKind::Enqueued { .. } => "enqueued",
+            OcrEventKind::Started { .. } => "started",
+            OcrEventKind::Progress { .. } => "progress",
+            OcrEventKind::Completed { .. } => "completed",
+            OcrEventKind::Failed { .. } => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct OcrProgressEvent {
+    /// Owner of the document; events are only delivered to this user
+    #[serde(skip)]
+    pub user_id: Uuid,
+    pub document_id: Uuid,
+    pub job_id: Uuid,
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub kind: OcrEventKind,
+}
+
+/// Fans OCR queue events out to live subscribers, such as the OCR progress websocket.
+/// Events published while nobody is subscribed are dropped; a subscription ends when its
+/// receiver is dropped.
+#[derive(Debug, Clone)]
+pub struct OcrProgressTracker {
+    sender: broadcast::Sender<OcrProgressEvent>,
+}
+
+impl OcrProgressTracker {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER);
+        Self { sender }
+    }
+
+    pub fn publish(&self, user_id: Uuid, document_id: Uuid, job_id: Uuid, kind: OcrEventKind) {
+        // Only fails when there are no subscribers
+        let _ = self.sender.send(OcrProgressEvent {
+            user_id,
+            document_id,
+            job_id,
+            timestamp: chrono::Utc::now().timestamp(),
+            kind,
+        });
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<OcrProgressEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Number of live subscriptions
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for OcrProgressTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,52 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+/// How long a password reset link stays valid
+pub const RESET_TOKEN_EXPIRY_MINUTES: i64 = 30;
+
+/// Minimum accepted length for a new password
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+
+/// Generates a random URL-safe reset token (256 bits, hex-encoded)
+pub fn generate_reset_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hashes a reset token for storage and lookup
+pub fn hash_reset_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Builds the link sent to the user. Falls back to a relative link when no public URL is configured.
+pub fn build_reset_link(public_base_url: Option<&str>, token: &str) -> String {
+    format!("{}/reset-password?token={}", public_base_url.unwrap_or(""), token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_tokens_are_random_and_hash_consistently() {
+        let a = generate_reset_token();
+        let b = generate_reset_token();
+
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+        assert_eq!(hash_reset_token(&a), hash_reset_token(&format!(" {} ", a)));
+        assert_ne!(hash_reset_token(&a), hash_reset_token(&b));
+    }
+
+    #[test]
+    fn test_build_reset_link() {
+        assert_eq!(
+            build_reset_link(Some("https://docs.example.com"), "abc"),
+            "https://docs.example.com/reset-password?token=abc"
+        );
+        assert_eq!(build_reset_link(None, "abc"), "/reset-password?token=abc");
+    }
+}
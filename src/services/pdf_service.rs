@@ -0,0 +1,314 @@
+/*!
+ * PDF Page Operations
+ *
+ * Page counting, page extraction, rendering, rotation and merging for PDFs. These shell out
+ * to the poppler-utils tools (`pdfinfo`, `pdfseparate`, `pdftoppm`, `pdfunite`), to `qpdf`
+ * and to `ocrmypdf`, which the OCR pipeline already depends on, each bounded by a timeout.
+ */
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::time::Duration;
+use tokio::fs;
+use tokio::process::Command;
+use tracing::warn;
+use uuid::Uuid;
+
+/// How long a single poppler tool invocation may run before it is killed
+const PDF_TOOL_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How long `ocrmypdf` may spend adding a text layer to a whole document
+const SEARCHABLE_PDF_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Resolution assumed for images that do not record one, so ocrmypdf can size the page
+const SEARCHABLE_PDF_IMAGE_DPI: u32 = 300;
+
+#[derive(Clone)]
+pub struct PdfService {
+    temp_path: PathBuf,
+}
+
+impl PdfService {
+    /// Scratch files are written under `temp_path` and removed when each operation finishes
+    pub fn new(temp_path: PathBuf) -> Self {
+        Self { temp_path }
+    }
+
+    /// Returns the number of pages in a PDF
+    pub async fn page_count(&self, pdf_path: &Path) -> Result<u32> {
+        let output = run_tool(Command::new("pdfinfo").arg(pdf_path)).await?;
+        let info = String::from_utf8_lossy(&output.stdout);
+        parse_page_count(&info)
+            .ok_or_else(|| anyhow::anyhow!("pdfinfo did not report a page count for {}", pdf_path.display()))
+    }
+
+    /// Extracts pages `first..=last` (1-based) of a PDF into a new PDF
+    pub async fn extract_pages(&self, pdf_path: &Path, first: u32, last: u32) -> Result<Vec<u8>> {
+        let scratch_dir = self.create_scratch_dir().await?;
+        let result = async {
+            let page_pattern = scratch_dir.join("page-%d.pdf");
+            run_tool(
+                Command::new("pdfseparate")
+                    .arg("-f").arg(first.to_string())
+                    .arg("-l").arg(last.to_string())
+                    .arg(pdf_path)
+                    .arg(&page_pattern),
+            )
+            .await?;
+
+            let pages: Vec<PathBuf> = (first..=last)
+                .map(|page| scratch_dir.join(format!("page-{}.pdf", page)))
+                .collect();
+            self.unite(&pages, &scratch_dir).await
+        }
+        .await;
+
+        remove_scratch_dir(&scratch_dir).await;
+        result
+    }
+
+    /// Splits every page of a PDF into its own single-page PDF inside `output_dir`.
+    /// Returns the page files in page order; the caller owns `output_dir` and its cleanup.
+    pub async fn split_pages(&self, pdf_path: &Path, output_dir: &Path) -> Result<Vec<PathBuf>> {
+        let page_count = self.page_count(pdf_path).await?;
+        run_tool(
+            Command::new("pdfseparate")
+                .arg(pdf_path)
+                .arg(output_dir.join("page-%d.pdf")),
+        )
+        .await?;
+
+        Ok((1..=page_count)
+            .map(|page| output_dir.join(format!("page-{}.pdf", page)))
+            .collect())
+    }
+
+    /// Renders every page of a PDF to a PNG inside `output_dir` at `dpi`.
+    /// Returns the images in page order; the caller owns `output_dir` and its cleanup.
+    pub async fn render_pages(&self, pdf_path: &Path, output_dir: &Path, dpi: u32) -> Result<Vec<PathBuf>> {
+        run_tool(
+            Command::new("pdftoppm")
+                .arg("-r").arg(dpi.to_string())
+                .arg("-png")
+                .arg(pdf_path)
+                .arg(output_dir.join("page")),
+        )
+        .await?;
+
+        // pdftoppm zero-pads page numbers to the same width, so name order is page order
+        let mut pages = Vec::new();
+        let mut entries = fs::read_dir(output_dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "png") {
+                pages.push(path);
+            }
+        }
+        pages.sort();
+        Ok(pages)
+    }
+
+    /// Renders only the first page of a PDF to `output_dir/first-page.png` at `dpi`.
+    /// The caller owns `output_dir` and its cleanup.
+    pub async fn render_first_page(&self, pdf_path: &Path, output_dir: &Path, dpi: u32) -> Result<PathBuf> {
+        run_tool(
+            Command::new("pdftoppm")
+                .arg("-r").arg(dpi.to_string())
+                .arg("-f").arg("1")
+                .arg("-l").arg("1")
+                .arg("-singlefile")
+                .arg("-png")
+                .arg(pdf_path)
+                .arg(output_dir.join("first-page")),
+        )
+        .await?;
+
+        Ok(output_dir.join("first-page.png"))
+    }
+
+    /// Turns pages of a PDF clockwise by `degrees`, a multiple of 90, on top of any rotation
+    /// they already have. `pages` are 1-based; `None` rotates every page.
+    pub async fn rotate_pages(&self, pdf_path: &Path, degrees: u32, pages: Option<&[u32]>) -> Result<Vec<u8>> {
+        let scratch_dir = self.create_scratch_dir().await?;
+        let result = async {
+            let output_path = scratch_dir.join("rotated.pdf");
+            let mut rotation = format!("--rotate=+{}", degrees);
+            if let Some(pages) = pages {
+                let pages: Vec<String> = pages.iter().map(u32::to_string).collect();
+                rotation.push(':');
+                rotation.push_str(&pages.join(","));
+            }
+
+            run_tool(Command::new("qpdf").arg(pdf_path).arg(&output_path).arg(rotation)).await?;
+            Ok(fs::read(&output_path).await?)
+        }
+        .await;
+
+        remove_scratch_dir(&scratch_dir).await;
+        result
+    }
+
+    /// Concatenates whole PDFs, in order, into a single PDF
+    pub async fn merge(&self, pdf_paths: &[PathBuf]) -> Result<Vec<u8>> {
+        let scratch_dir = self.create_scratch_dir().await?;
+        let result = self.unite(pdf_paths, &scratch_dir).await;
+        remove_scratch_dir(&scratch_dir).await;
+        result
+    }
+
+    /// Produces a searchable PDF from a PDF or image by running `ocrmypdf` over it with
+    /// `language`, e.g. `eng+deu`. Pages that already carry text are kept as they are.
+    pub async fn make_searchable(&self, input_path: &Path, language: &str, is_image: bool) -> Result<Vec<u8>> {
+        let scratch_dir = self.create_scratch_dir().await?;
+        let result = async {
+            let output_path = scratch_dir.join("searchable.pdf");
+            let mut command = Command::new("ocrmypdf");
+            command
+                .arg("-l").arg(language)
+                .arg("--skip-text")
+                .arg("--output-type").arg("pdf");
+            if is_image {
+                command.arg("--image-dpi").arg(SEARCHABLE_PDF_IMAGE_DPI.to_string());
+            }
+            command.arg(input_path).arg(&output_path);
+
+            run_tool_with_timeout(&mut command, SEARCHABLE_PDF_TIMEOUT).await?;
+            Ok(fs::read(&output_path).await?)
+        }
+        .await;
+
+        remove_scratch_dir(&scratch_dir).await;
+        result
+    }
+
+    async fn unite(&self, pdf_paths: &[PathBuf], scratch_dir: &Path) -> Result<Vec<u8>> {
+        match pdf_paths {
+            [] => Err(anyhow::anyhow!("No PDFs to combine")),
+            [single] => Ok(fs::read(single).await?),
+            _ => {
+                let output_path = scratch_dir.join("combined.pdf");
+                run_tool(Command::new("pdfunite").args(pdf_paths).arg(&output_path)).await?;
+                Ok(fs::read(&output_path).await?)
+            }
+        }
+    }
+
+    async fn create_scratch_dir(&self) -> Result<PathBuf> {
+        let dir = self.temp_path.join(format!("pdf_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).await?;
+        Ok(dir)
+    }
+}
+
+async fn remove_scratch_dir(dir: &Path) {
+    if let Err(e) = fs::remove_dir_all(dir).await {
+        warn!("Failed to remove PDF scratch directory {}: {}", dir.display(), e);
+    }
+}
+
+async fn run_tool(command: &mut Command) -> Result<Output> {
+    run_tool_with_timeout(command, PDF_TOOL_TIMEOUT).await
+}
+
+async fn run_tool_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output> {
+    let program = command.as_std().get_program().to_string_lossy().to_string();
+    let output = tokio::time::timeout(timeout, command.kill_on_drop(true).output())
+        .await
+        .map_err(|_| anyhow::anyhow!("{} timed out after {}s", program, timeout.as_secs()))?
+        .map_err(|e| anyhow::anyhow!("Failed to run {}: {}", program, e))?;
+
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "{} exited with {}: {}",
+            program,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    Ok(output)
+}
+
+fn parse_page_count(pdfinfo_output: &str) -> Option<u32> {
+    pdfinfo_output
+        .lines()
+        .find_map(|line| line.strip_prefix("Pages:"))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Checks that every `[first, last]` range is 1-based, ordered and within `page_count`.
+/// Ranges may overlap; each one produces its own document.
+pub fn validate_page_ranges(ranges: &[[u32; 2]], page_count: u32) -> Result<(), String> {
+    if ranges.is_empty() {
+        return Err("At least one page range is required".to_string());
+    }
+
+    for &[first, last] in ranges {
+        if first == 0 {
+            return Err(format!("Page ranges are 1-based, got [{}, {}]", first, last));
+        }
+        if first > last {
+            return Err(format!("Range [{}, {}] starts after it ends", first, last));
+        }
+        if last > page_count {
+            return Err(format!(
+                "Range [{}, {}] is outside the document, which has {} pages",
+                first, last, page_count
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that every page number is 1-based and within `page_count`
+pub fn validate_pages(pages: &[u32], page_count: u32) -> Result<(), String> {
+    if pages.is_empty() {
+        return Err("At least one page is required".to_string());
+    }
+
+    for &page in pages {
+        if page == 0 || page > page_count {
+            return Err(format!(
+                "Page {} is outside the document, which has {} pages",
+                page, page_count
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page_count() {
+        let output = "Title:          Scan\nProducer:       scanner\nPages:          12\nEncrypted:      no\n";
+        assert_eq!(parse_page_count(output), Some(12));
+        assert_eq!(parse_page_count("Title: nothing here"), None);
+    }
+
+    #[test]
+    fn test_validate_page_ranges() {
+        assert!(validate_page_ranges(&[[1, 3], [4, 4], [5, 10]], 10).is_ok());
+        // Overlapping ranges are allowed
+        assert!(validate_page_ranges(&[[1, 5], [3, 7]], 10).is_ok());
+
+        assert!(validate_page_ranges(&[], 10).is_err());
+        assert!(validate_page_ranges(&[[0, 2]], 10).is_err());
+        assert!(validate_page_ranges(&[[4, 2]], 10).is_err());
+        assert!(validate_page_ranges(&[[9, 11]], 10).is_err());
+    }
+
+    #[test]
+    fn test_validate_pages() {
+        assert!(validate_pages(&[1, 3, 10], 10).is_ok());
+
+        assert!(validate_pages(&[], 10).is_err());
+        assert!(validate_pages(&[0], 10).is_err());
+        assert!(validate_pages(&[2, 11], 10).is_err());
+    }
+}
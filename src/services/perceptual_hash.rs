@@ -0,0 +1,123 @@
+/*!
+ * Perceptual Image Hashing
+ *
+ * Computes a 64-bit difference hash (dHash) for image documents so that
+ * near-identical scans can be grouped even when their bytes differ. Two
+ * images are considered similar when the Hamming distance between their
+ * hashes is at or below a threshold.
+ */
+
+use anyhow::Result;
+use std::path::Path;
+
+/// Default Hamming distance under which two images count as duplicates
+pub const DEFAULT_PERCEPTUAL_THRESHOLD: u32 = 10;
+
+/// Largest meaningful threshold for a 64-bit hash
+pub const MAX_PERCEPTUAL_THRESHOLD: u32 = 64;
+
+/// Whether documents of this MIME type get a perceptual hash
+pub fn supports_perceptual_hash(mime_type: &str) -> bool {
+    mime_type.starts_with("image/")
+}
+
+/// Computes the dHash of an image file: the image is reduced to 9x8 grayscale
+/// and each bit records whether a pixel is brighter than its right neighbour.
+#[cfg(feature = "ocr")]
+pub fn compute_perceptual_hash(image_path: &Path) -> Result<i64> {
+    use image::imageops::FilterType;
+
+    let img = image::open(image_path)?;
+    let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+
+    let mut hash: u64 = 0;
+    for y in 0..8 {
+        for x in 0..8 {
+            let left = small.get_pixel(x, y)[0];
+            let right = small.get_pixel(x + 1, y)[0];
+            hash = (hash << 1) | u64::from(left > right);
+        }
+    }
+
+    // Stored in a BIGINT column; the bit pattern is what matters
+    Ok(hash as i64)
+}
+
+#[cfg(not(feature = "ocr"))]
+pub fn compute_perceptual_hash(_image_path: &Path) -> Result<i64> {
+    Err(anyhow::anyhow!("Perceptual hashing requires the ocr feature"))
+}
+
+/// Number of differing bits between two hashes
+pub fn hamming_distance(a: i64, b: i64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// Groups hashes whose Hamming distance is at most `threshold`, transitively.
+/// Returns groups of indices into `hashes`; singletons are omitted.
+pub fn group_similar_hashes(hashes: &[i64], threshold: u32) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..hashes.len()).collect();
+
+    fn find(parent: &mut [usize], mut i: usize) -> usize {
+        while parent[i] != i {
+            parent[i] = parent[parent[i]];
+            i = parent[i];
+        }
+        i
+    }
+
+    for i in 0..hashes.len() {
+        for j in (i + 1)..hashes.len() {
+            if hamming_distance(hashes[i], hashes[j]) <= threshold {
+                let (root_i, root_j) = (find(&mut parent, i), find(&mut parent, j));
+                if root_i != root_j {
+                    parent[root_j] = root_i;
+                }
+            }
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut group_of_root = std::collections::HashMap::new();
+    for i in 0..hashes.len() {
+        let root = find(&mut parent, i);
+        let group = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[group].push(i);
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hamming_distance() {
+        assert_eq!(hamming_distance(0, 0), 0);
+        assert_eq!(hamming_distance(0b1011, 0b0001), 2);
+        assert_eq!(hamming_distance(0, -1), 64);
+    }
+
+    #[test]
+    fn test_group_similar_hashes() {
+        let hashes = [0b0000, 0b0001, 0b0011, -1, 0x00F0_0000];
+        let groups = group_similar_hashes(&hashes, 1);
+        // 0 ~ 1 ~ 3 chain together; the others stand alone
+        assert_eq!(groups, vec![vec![0, 1, 2]]);
+
+        assert!(group_similar_hashes(&hashes, 0).is_empty());
+        assert_eq!(group_similar_hashes(&hashes, 64).len(), 1);
+    }
+
+    #[test]
+    fn test_supports_perceptual_hash() {
+        assert!(supports_perceptual_hash("image/png"));
+        assert!(supports_perceptual_hash("image/jpeg"));
+        assert!(!supports_perceptual_hash("application/pdf"));
+    }
+}
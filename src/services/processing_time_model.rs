@@ -0,0 +1,183 @@
+/*!
+ * OCR Processing Time Model
+ *
+ * Projects how long OCR of a set of files will take from how long this instance took for
+ * documents of the same kind and size. Completed documents are grouped by file kind and
+ * size bucket; a file is estimated at the average of its bucket, at the average of all
+ * history when its bucket is empty, and at a flat two seconds when there is no history.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::db::Database;
+
+/// Estimate per file when this instance has not processed any documents yet
+pub const DEFAULT_PROCESSING_TIME_MS: f64 = 2000.0;
+
+/// Only documents processed this recently count, so the model follows hardware and settings changes
+pub const HISTORY_DAYS: i32 = 90;
+
+/// Upper bounds of the size buckets; the last bucket has no upper bound
+const SIZE_BOUNDARIES: [i64; 3] = [100 * 1024, 1024 * 1024, 10 * 1024 * 1024];
+const SIZE_BUCKET_LABELS: [&str; 4] = ["under_100kb", "100kb_to_1mb", "1mb_to_10mb", "over_10mb"];
+
+/// Files whose processing times are comparable
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    Pdf,
+    Image,
+    Text,
+    Other,
+}
+
+impl FileKind {
+    pub fn from_mime_type(mime_type: &str) -> Self {
+        let mime_type = mime_type.to_ascii_lowercase();
+        if mime_type == "application/pdf" {
+            FileKind::Pdf
+        } else if mime_type.starts_with("image/") {
+            FileKind::Image
+        } else if mime_type.starts_with("text/") {
+            FileKind::Text
+        } else {
+            FileKind::Other
+        }
+    }
+}
+
+/// Observed processing time of one kind of file within one size range
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProcessingTimeBucket {
+    pub kind: FileKind,
+    pub size_bucket: String,
+    pub min_bytes: i64,
+    /// None for the largest bucket
+    pub max_bytes: Option<i64>,
+    /// Documents the average is based on
+    pub samples: i64,
+    pub average_ms: f64,
+}
+
+/// How the processing time of a file is projected
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProcessingTimeModel {
+    /// Whether any processing history was found; otherwise every file is estimated at `fallback_ms`
+    pub based_on_history: bool,
+    /// Estimate for files whose bucket has no history: the average of all history, or the
+    /// default when there is none
+    pub fallback_ms: f64,
+    pub buckets: Vec<ProcessingTimeBucket>,
+}
+
+impl Default for ProcessingTimeModel {
+    fn default() -> Self {
+        Self {
+            based_on_history: false,
+            fallback_ms: DEFAULT_PROCESSING_TIME_MS,
+            buckets: Vec::new(),
+        }
+    }
+}
+
+impl ProcessingTimeModel {
+    /// Builds the model from the documents this instance processed within `HISTORY_DAYS`
+    pub async fn load(db: &Database) -> anyhow::Result<Self> {
+        let stats = db.get_ocr_processing_time_stats(&SIZE_BOUNDARIES, HISTORY_DAYS).await?;
+        Ok(Self::from_stats(
+            stats.into_iter().map(|s| (FileKind::from_mime_type(&s.mime_type), s.size_bucket as usize, s.samples, s.average_ms)),
+        ))
+    }
+
+    /// Like `load`, but falls back to the default model when the history can't be read
+    pub async fn load_or_default(db: &Database) -> Self {
+        Self::load(db).await.unwrap_or_else(|e| {
+            warn!("Failed to load OCR processing history, estimating with defaults: {}", e);
+            Self::default()
+        })
+    }
+
+    /// Builds the model from (kind, size bucket, samples, average ms) groups. Groups of the
+    /// same kind and bucket are merged weighted by their samples.
+    fn from_stats(stats: impl IntoIterator<Item = (FileKind, usize, i64, f64)>) -> Self {
+        let mut totals: HashMap<(FileKind, usize), (i64, f64)> = HashMap::new();
+        for (kind, bucket, samples, average_ms) in stats {
+            if samples <= 0 || bucket >= SIZE_BUCKET_LABELS.len() {
+                continue;
+            }
+            let total = totals.entry((kind, bucket)).or_default();
+            total.0 += samples;
+            total.1 += average_ms * samples as f64;
+        }
+
+        let all_samples: i64 = totals.values().map(|(samples, _)| samples).sum();
+        if all_samples == 0 {
+            return Self::default();
+        }
+        let all_ms: f64 = totals.values().map(|(_, ms)| ms).sum();
+
+        let mut buckets: Vec<ProcessingTimeBucket> = totals
+            .into_iter()
+            .map(|((kind, bucket), (samples, total_ms))| ProcessingTimeBucket {
+                kind,
+                size_bucket: SIZE_BUCKET_LABELS[bucket].to_string(),
+                min_bytes: if bucket == 0 { 0 } else { SIZE_BOUNDARIES[bucket - 1] },
+                max_bytes: SIZE_BOUNDARIES.get(bucket).copied(),
+                samples,
+                average_ms: total_ms / samples as f64,
+            })
+            .collect();
+        buckets.sort_by_key(|b| (b.kind as u8, b.min_bytes));
+
+        Self {
+            based_on_history: true,
+            fallback_ms: all_ms / all_samples as f64,
+            buckets,
+        }
+    }
+
+    /// Projected processing time of a file of `mime_type` and `size` bytes
+    pub fn estimate_ms(&self, mime_type: &str, size: i64) -> f64 {
+        let kind = FileKind::from_mime_type(mime_type);
+        let size_bucket = SIZE_BUCKET_LABELS[SIZE_BOUNDARIES.iter().filter(|&&boundary| size >= boundary).count()];
+        self.buckets
+            .iter()
+            .find(|b| b.kind == kind && b.size_bucket == size_bucket)
+            .map(|b| b.average_ms)
+            .unwrap_or(self.fallback_ms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_without_history_every_file_takes_the_default() {
+        let model = ProcessingTimeModel::from_stats(vec![]);
+        assert!(!model.based_on_history);
+        assert_eq!(model.estimate_ms("application/pdf", 50 * 1024 * 1024), DEFAULT_PROCESSING_TIME_MS);
+        assert_eq!(model.estimate_ms("text/plain", 10), DEFAULT_PROCESSING_TIME_MS);
+    }
+
+    #[test]
+    fn test_files_are_estimated_by_kind_and_size() {
+        let model = ProcessingTimeModel::from_stats(vec![
+            (FileKind::Pdf, 2, 2, 30_000.0),
+            (FileKind::Pdf, 2, 2, 10_000.0),
+            (FileKind::Text, 0, 4, 50.0),
+        ]);
+        assert!(model.based_on_history);
+        assert_eq!(model.buckets.len(), 2);
+        assert_eq!(model.buckets[0].samples, 4);
+        assert_eq!(model.buckets[0].max_bytes, Some(10 * 1024 * 1024));
+
+        assert_eq!(model.estimate_ms("application/pdf", 5 * 1024 * 1024), 20_000.0);
+        assert_eq!(model.estimate_ms("text/plain", 2_000), 50.0);
+        // A bucket without history falls back to the average of all history
+        assert_eq!(model.estimate_ms("image/png", 2_000), (4.0 * 20_000.0 + 4.0 * 50.0) / 8.0);
+    }
+}
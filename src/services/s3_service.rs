@@ -9,14 +9,40 @@ use aws_sdk_s3::Client;
 use aws_credential_types::Credentials;
 #[cfg(feature = "s3")]
 use aws_types::region::Region as AwsRegion;
+#[cfg(feature = "s3")]
+use aws_smithy_http_client::tls::{self, rustls_provider::CryptoMode, TlsContext, TrustStore};
+#[cfg(feature = "s3")]
+use aws_sdk_s3::{
+    config::{
+        http::{HttpRequest, HttpResponse},
+        IntoShared,
+    },
+    error::{ConnectorError, DisplayErrorContext, ProvideErrorMetadata, SdkError},
+    primitives::SdkBody,
+};
+#[cfg(feature = "s3")]
+use aws_smithy_runtime_api::client::http::{http_client_fn, HttpConnectorFuture};
+#[cfg(feature = "s3")]
+use hyper_util::{client::legacy::connect::HttpConnector, rt::TokioExecutor};
+#[cfg(feature = "s3")]
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+#[cfg(feature = "s3")]
+use std::sync::Arc;
 
 use crate::models::{FileIngestionInfo, S3SourceConfig};
+use crate::services::glob_filters::{compile_patterns, pattern_matches};
+#[cfg(feature = "s3")]
+use crate::services::client_certificate::{load_client_identity, ClientIdentity};
+#[cfg(feature = "s3")]
+use crate::services::source_connection_test::{is_client_certificate_alert, ConnectionTestError};
 
 #[derive(Debug, Clone)]
 pub struct S3Service {
     #[cfg(feature = "s3")]
     client: Client,
     config: S3SourceConfig,
+    include_patterns: Vec<glob::Pattern>,
+    exclude_patterns: Vec<glob::Pattern>,
 }
 
 impl S3Service {
@@ -38,6 +64,14 @@ impl S3Service {
         if config.secret_access_key.is_empty() {
             return Err(anyhow!("Secret access key is required"));
         }
+        if let Some(pem) = &config.ca_certificate {
+            if !pem.trim().is_empty() && !pem.contains("-----BEGIN CERTIFICATE-----") {
+                return Err(anyhow!("CA certificate must be PEM encoded"));
+            }
+        }
+
+        let include_patterns = compile_patterns(&config.include_patterns)?;
+        let exclude_patterns = compile_patterns(&config.exclude_patterns)?;
 
         // Create S3 client with custom configuration
         let credentials = Credentials::new(
@@ -57,6 +91,7 @@ impl S3Service {
         let mut s3_config_builder = aws_sdk_s3::config::Builder::new()
             .region(AwsRegion::new(region))
             .credentials_provider(credentials)
+            .force_path_style(config.force_path_style)
             .behavior_version_latest();
 
         // Set custom endpoint if provided (for S3-compatible services)
@@ -67,17 +102,74 @@ impl S3Service {
             }
         }
 
+        let ca_certificate = config.ca_certificate.as_deref().filter(|pem| !pem.trim().is_empty());
+        if let Some(identity) = load_client_identity(&config.client_certificate)? {
+            s3_config_builder = s3_config_builder.http_client(mutual_tls_http_client(ca_certificate, &identity)?);
+            info!("Using a client certificate for the S3 endpoint");
+        } else if let Some(pem) = ca_certificate {
+            // Trust a custom CA on top of the system roots, for self-signed endpoints
+            let tls_context = TlsContext::builder()
+                .with_trust_store(TrustStore::default().with_pem_certificate(pem.as_bytes()))
+                .build()
+                .map_err(|e| anyhow!("Invalid CA certificate: {}", e))?;
+            let http_client = aws_smithy_http_client::Builder::new()
+                .tls_provider(tls::Provider::Rustls(CryptoMode::AwsLc))
+                .tls_context(tls_context)
+                .build_https();
+            s3_config_builder = s3_config_builder.http_client(http_client);
+            info!("Using custom CA certificate for S3 endpoint");
+        }
+
         let s3_config = s3_config_builder.build();
         let client = Client::from_conf(s3_config);
 
         Ok(Self { 
             #[cfg(feature = "s3")]
             client, 
-            config 
+            config,
+            include_patterns,
+            exclude_patterns,
         })
         }
     }
 
+    /// Full key prefix for a watch folder: the configured prefix and the folder joined as
+    /// directories. Empty when both are empty, which lists the whole bucket.
+    pub fn object_prefix(&self, folder_path: &str) -> String {
+        let parts: Vec<&str> = [self.config.prefix.as_deref().unwrap_or(""), folder_path]
+            .iter()
+            .map(|part| part.trim_matches('/'))
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        if parts.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", parts.join("/"))
+        }
+    }
+
+    /// Applies the extension and glob filters to an object key. Globs match the key
+    /// relative to the configured prefix.
+    fn should_include(&self, key: &str) -> bool {
+        let name = key.rsplit('/').next().unwrap_or(key);
+        let extension = std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        if !self.config.file_extensions.contains(&extension) {
+            return false;
+        }
+
+        let relative_path = key.strip_prefix(&self.object_prefix("")).unwrap_or(key);
+        if self.exclude_patterns.iter().any(|pattern| pattern_matches(pattern, relative_path, name)) {
+            return false;
+        }
+        self.include_patterns.is_empty()
+            || self.include_patterns.iter().any(|pattern| pattern_matches(pattern, relative_path, name))
+    }
+
     /// Discover files in a specific S3 prefix (folder)
     pub async fn discover_files_in_folder(&self, folder_path: &str) -> Result<Vec<FileIngestionInfo>> {
         #[cfg(not(feature = "s3"))]
@@ -87,7 +179,8 @@ impl S3Service {
         
         #[cfg(feature = "s3")]
         {
-        info!("Scanning S3 bucket: {} prefix: {}", self.config.bucket_name, folder_path);
+        let prefix = self.object_prefix(folder_path);
+        info!("Scanning S3 bucket: {} prefix: {}", self.config.bucket_name, prefix);
 
         let mut files = Vec::new();
         let mut continuation_token: Option<String> = None;
@@ -96,7 +189,7 @@ impl S3Service {
             let mut list_request = self.client
                 .list_objects_v2()
                 .bucket(&self.config.bucket_name)
-                .prefix(folder_path);
+                .prefix(&prefix);
 
             if let Some(token) = &continuation_token {
                 list_request = list_request.continuation_token(token);
@@ -112,18 +205,17 @@ impl S3Service {
                                     continue;
                                 }
 
-                                // Check file extension
+                                if !self.should_include(&key) {
+                                    debug!("Skipping S3 object excluded by extension or glob filters: {}", key);
+                                    continue;
+                                }
+
                                 let extension = std::path::Path::new(&key)
                                     .extension()
                                     .and_then(|ext| ext.to_str())
                                     .unwrap_or("")
                                     .to_lowercase();
 
-                                if !self.config.file_extensions.contains(&extension) {
-                                    debug!("Skipping S3 object with unsupported extension: {}", key);
-                                    continue;
-                                }
-
                                 let file_name = std::path::Path::new(&key)
                                     .file_name()
                                     .and_then(|name| name.to_str())
@@ -210,7 +302,7 @@ impl S3Service {
             }
         }
 
-        info!("Found {} files in S3 bucket {} prefix {}", files.len(), self.config.bucket_name, folder_path);
+        info!("Found {} files in S3 bucket {} prefix {}", files.len(), self.config.bucket_name, prefix);
         Ok(files)
         }
     }
@@ -244,6 +336,29 @@ impl S3Service {
         }
     }
 
+    /// Delete an object from S3. Deleting an object that does not exist succeeds.
+    pub async fn delete_file(&self, object_key: &str) -> Result<()> {
+        #[cfg(not(feature = "s3"))]
+        {
+            return Err(anyhow!("S3 support not compiled in"));
+        }
+        
+        #[cfg(feature = "s3")]
+        {
+        info!("Deleting S3 object: {}/{}", self.config.bucket_name, object_key);
+
+        self.client
+            .delete_object()
+            .bucket(&self.config.bucket_name)
+            .key(object_key)
+            .send()
+            .await
+            .map_err(|e| anyhow!("Failed to delete S3 object {}: {}", object_key, e))?;
+
+        Ok(())
+        }
+    }
+
     /// Test S3 connection and access to bucket
     pub async fn test_connection(&self) -> Result<String> {
         #[cfg(not(feature = "s3"))]
@@ -255,22 +370,29 @@ impl S3Service {
         {
             info!("Testing S3 connection to bucket: {}", self.config.bucket_name);
 
-            // Test bucket access by listing objects with a limit
-            let response = self.client
-                .list_objects_v2()
+            // HeadBucket checks the endpoint, credentials and bucket without listing anything.
+            // Its error responses have no body, so a 403 is left to the listing below, whose
+            // error code tells bad credentials from a missing permission.
+            if let Err(e) = self.client
+                .head_bucket()
                 .bucket(&self.config.bucket_name)
-                .max_keys(1)
                 .send()
                 .await
-                .map_err(|e| anyhow!("Failed to access S3 bucket {}: {}", self.config.bucket_name, e))?;
+            {
+                if e.raw_response().map(|response| response.status().as_u16()) != Some(403) {
+                    return Err(self.connection_error(&e));
+                }
+            }
 
-            // Test if we can get bucket region (additional validation)
-            let _head_bucket_response = self.client
-                .head_bucket()
+            // Listing needs its own permission, so check it under the configured prefix too
+            let response = self.client
+                .list_objects_v2()
                 .bucket(&self.config.bucket_name)
+                .prefix(self.object_prefix(""))
+                .max_keys(1)
                 .send()
                 .await
-                .map_err(|e| anyhow!("Cannot access bucket {}: {}", self.config.bucket_name, e))?;
+                .map_err(|e| self.connection_error(&e))?;
 
             let object_count = response.key_count.unwrap_or(0);
             
@@ -281,6 +403,44 @@ impl S3Service {
         }
     }
 
+    /// Turn a failed request into a `ConnectionTestError` saying what to fix
+    #[cfg(feature = "s3")]
+    fn connection_error<E>(&self, error: &SdkError<E, HttpResponse>) -> anyhow::Error
+    where
+        E: ProvideErrorMetadata + std::error::Error + 'static,
+    {
+        let status = error.raw_response().map(|response| response.status().as_u16());
+        let bucket = &self.config.bucket_name;
+
+        match classify_s3_error(status, error.code()) {
+            S3Failure::Unreachable if is_client_certificate_alert(error) => ConnectionTestError::ClientCertificateRejected(format!(
+                "{} requires a client certificate and did not accept the configured one: {}",
+                self.config.endpoint_url.as_deref().filter(|url| !url.is_empty()).unwrap_or("AWS S3"),
+                DisplayErrorContext(error)
+            )).into(),
+            S3Failure::Unreachable => ConnectionTestError::Unreachable(format!(
+                "Could not reach {}: {}",
+                self.config.endpoint_url.as_deref().filter(|url| !url.is_empty()).unwrap_or("AWS S3"),
+                DisplayErrorContext(error)
+            )).into(),
+            S3Failure::InvalidCredentials => ConnectionTestError::AuthenticationFailed(format!(
+                "S3 rejected access key '{}': check the access key ID and secret access key",
+                self.config.access_key_id
+            )).into(),
+            S3Failure::BucketNotFound => ConnectionTestError::ResourceUnavailable(format!(
+                "Bucket '{}' does not exist", bucket
+            )).into(),
+            S3Failure::WrongRegion => ConnectionTestError::ResourceUnavailable(format!(
+                "Bucket '{}' is not in region '{}': check the region setting", bucket, self.config.region
+            )).into(),
+            S3Failure::AccessDenied => ConnectionTestError::ResourceUnavailable(format!(
+                "Bucket '{}' exists but access was denied: the key needs s3:ListBucket and s3:GetObject on it",
+                bucket
+            )).into(),
+            S3Failure::Other => anyhow!("Cannot access bucket {}: {}", bucket, DisplayErrorContext(error)),
+        }
+    }
+
     /// Get estimated file count and size for all watch folders
     pub async fn estimate_sync(&self) -> Result<(usize, i64)> {
         let mut total_files = 0;
@@ -327,6 +487,92 @@ impl S3Service {
     }
 }
 
+/// HTTP client that presents a client certificate, for endpoints that require mutual TLS.
+/// The SDK's default client has no setting for client certificates, so requests go through a
+/// connector of our own on the same hyper 1 and rustls 0.23 stack.
+#[cfg(feature = "s3")]
+fn mutual_tls_http_client(
+    ca_certificate: Option<&str>,
+    identity: &ClientIdentity,
+) -> Result<aws_sdk_s3::config::SharedHttpClient> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    if let Some(pem) = ca_certificate {
+        for certificate in CertificateDer::pem_slice_iter(pem.as_bytes()) {
+            let certificate = certificate.map_err(|e| anyhow!("Invalid CA certificate: {}", e))?;
+            roots.add(certificate).map_err(|e| anyhow!("Invalid CA certificate: {}", e))?;
+        }
+    }
+
+    let tls_config = rustls::ClientConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots)
+        .with_client_auth_cert(identity.certificate_chain.clone(), identity.private_key.clone_key())
+        .map_err(|e| anyhow!("Invalid client certificate: {}", e))?;
+
+    let https = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config(tls_config)
+        .https_or_http()
+        .enable_http1()
+        .build();
+    let connector = MutualTlsConnector {
+        client: hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build(https),
+    }
+    .into_shared();
+    Ok(http_client_fn(move |_, _| connector.clone()))
+}
+
+#[cfg(feature = "s3")]
+#[derive(Debug)]
+struct MutualTlsConnector {
+    client: hyper_util::client::legacy::Client<hyper_rustls::HttpsConnector<HttpConnector>, SdkBody>,
+}
+
+#[cfg(feature = "s3")]
+impl aws_smithy_runtime_api::client::http::HttpConnector for MutualTlsConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let client = self.client.clone();
+        HttpConnectorFuture::new(async move {
+            let request = request.try_into_http1x().map_err(|e| ConnectorError::user(e.into()))?;
+            let response = client.request(request).await.map_err(|e| ConnectorError::io(e.into()))?;
+            HttpResponse::try_from(response.map(SdkBody::from_body_1_x))
+                .map_err(|e| ConnectorError::other(e.into(), None))
+        })
+    }
+}
+
+/// What a failed S3 request says is wrong with the source settings
+#[cfg(feature = "s3")]
+#[derive(Debug, PartialEq)]
+enum S3Failure {
+    Unreachable,
+    InvalidCredentials,
+    BucketNotFound,
+    WrongRegion,
+    AccessDenied,
+    Other,
+}
+
+/// Classifies a failed request by its HTTP status and S3 error code. HEAD responses
+/// carry no error code, so the status alone has to do for them.
+#[cfg(feature = "s3")]
+fn classify_s3_error(status: Option<u16>, code: Option<&str>) -> S3Failure {
+    let Some(status) = status else {
+        return S3Failure::Unreachable;
+    };
+
+    match (status, code) {
+        (_, Some("InvalidAccessKeyId" | "SignatureDoesNotMatch" | "InvalidToken" | "ExpiredToken")) | (401, _) => {
+            S3Failure::InvalidCredentials
+        }
+        (_, Some("NoSuchBucket")) | (404, None) => S3Failure::BucketNotFound,
+        (_, Some("PermanentRedirect" | "AuthorizationHeaderMalformed" | "IllegalLocationConstraintException"))
+        | (301, _) => S3Failure::WrongRegion,
+        (_, Some("AccessDenied" | "AllAccessDisabled")) | (403, None) => S3Failure::AccessDenied,
+        _ => S3Failure::Other,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -342,8 +588,13 @@ mod tests {
             prefix: None,
             watch_folders: vec!["documents/".to_string()],
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
+            include_patterns: vec![],
+            exclude_patterns: vec![],
+            force_path_style: false,
+            ca_certificate: None,
             auto_sync: true,
             sync_interval_minutes: 60,
+            client_certificate: Default::default(),
         };
 
         // This will create the client but won't test actual S3 access
@@ -354,6 +605,77 @@ mod tests {
         assert!(service.is_err());
     }
 
+    fn test_service(config: S3SourceConfig) -> S3Service {
+        S3Service {
+            #[cfg(feature = "s3")]
+            client: Client::from_conf(aws_sdk_s3::config::Builder::new().behavior_version_latest().build()),
+            include_patterns: compile_patterns(&config.include_patterns).unwrap(),
+            exclude_patterns: compile_patterns(&config.exclude_patterns).unwrap(),
+            config,
+        }
+    }
+
+    fn minio_config() -> S3SourceConfig {
+        S3SourceConfig {
+            bucket_name: "scans".to_string(),
+            region: "us-east-1".to_string(),
+            access_key_id: "minioadmin".to_string(),
+            secret_access_key: "minioadmin".to_string(),
+            endpoint_url: Some("https://minio.local:9000".to_string()),
+            prefix: Some("/archive/".to_string()),
+            watch_folders: vec!["inbox".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            include_patterns: vec!["invoice_*".to_string(), "2024/**/*.pdf".to_string()],
+            exclude_patterns: vec!["*draft*".to_string()],
+            force_path_style: true,
+            ca_certificate: None,
+            auto_sync: true,
+            sync_interval_minutes: 60,
+            client_certificate: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_object_prefix() {
+        let service = test_service(minio_config());
+        assert_eq!(service.object_prefix("inbox/"), "archive/inbox/");
+        assert_eq!(service.object_prefix(""), "archive/");
+
+        let service = test_service(S3SourceConfig { prefix: None, ..minio_config() });
+        assert_eq!(service.object_prefix("/inbox"), "inbox/");
+        assert_eq!(service.object_prefix(""), "");
+    }
+
+    #[test]
+    fn test_extension_and_glob_filters() {
+        let service = test_service(minio_config());
+        assert!(service.should_include("archive/inbox/invoice_01.pdf"));
+        assert!(service.should_include("archive/2024/03/report.pdf"));
+        assert!(!service.should_include("archive/inbox/report.pdf"));
+        assert!(!service.should_include("archive/inbox/invoice_draft.pdf"));
+        assert!(!service.should_include("archive/inbox/invoice_01.docx"));
+    }
+
+    #[test]
+    fn test_config_accepts_short_field_names() {
+        let config: S3SourceConfig = serde_json::from_value(serde_json::json!({
+            "bucket": "scans",
+            "region": "us-west-000",
+            "access_key": "key",
+            "secret_key": "secret",
+            "endpoint_url": "https://s3.us-west-000.backblazeb2.com",
+            "prefix": null,
+            "watch_folders": [""],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60
+        })).unwrap();
+        assert_eq!(config.bucket_name, "scans");
+        assert_eq!(config.access_key_id, "key");
+        assert!(!config.force_path_style);
+        assert!(config.include_patterns.is_empty());
+    }
+
     #[test]
     fn test_mime_type_detection() {
         assert_eq!(S3Service::get_mime_type("pdf"), "application/pdf");
@@ -361,4 +683,19 @@ mod tests {
         assert_eq!(S3Service::get_mime_type("txt"), "text/plain");
         assert_eq!(S3Service::get_mime_type("unknown"), "application/octet-stream");
     }
-}
\ No newline at end of file
+
+    #[cfg(feature = "s3")]
+    #[test]
+    fn test_classify_s3_errors() {
+        assert_eq!(classify_s3_error(None, None), S3Failure::Unreachable);
+        assert_eq!(classify_s3_error(Some(403), Some("InvalidAccessKeyId")), S3Failure::InvalidCredentials);
+        assert_eq!(classify_s3_error(Some(403), Some("SignatureDoesNotMatch")), S3Failure::InvalidCredentials);
+        assert_eq!(classify_s3_error(Some(404), Some("NoSuchBucket")), S3Failure::BucketNotFound);
+        assert_eq!(classify_s3_error(Some(404), None), S3Failure::BucketNotFound);
+        assert_eq!(classify_s3_error(Some(301), None), S3Failure::WrongRegion);
+        assert_eq!(classify_s3_error(Some(400), Some("AuthorizationHeaderMalformed")), S3Failure::WrongRegion);
+        assert_eq!(classify_s3_error(Some(403), Some("AccessDenied")), S3Failure::AccessDenied);
+        assert_eq!(classify_s3_error(Some(403), None), S3Failure::AccessDenied);
+        assert_eq!(classify_s3_error(Some(500), Some("InternalError")), S3Failure::Other);
+    }
+}
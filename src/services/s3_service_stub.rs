@@ -23,6 +23,10 @@ impl S3Service {
         Err(anyhow!("S3 support not compiled in"))
     }
 
+    pub async fn delete_file(&self, _object_key: &str) -> Result<()> {
+        Err(anyhow!("S3 support not compiled in"))
+    }
+
     pub async fn test_connection(&self) -> Result<String> {
         Err(anyhow!("S3 support not compiled in"))
     }
@@ -0,0 +1,413 @@
+use anyhow::{anyhow, Result};
+use std::io::ErrorKind;
+use tracing::{debug, info, warn};
+
+#[cfg(feature = "smb")]
+use pavao::{SmbClient, SmbCredentials, SmbDirentType, SmbError, SmbOpenOptions, SmbOptions};
+
+use crate::models::{FileIngestionInfo, SmbSourceConfig};
+use crate::services::glob_filters::{compile_patterns, pattern_matches};
+#[cfg(feature = "smb")]
+use crate::services::source_connection_test::ConnectionTestError;
+
+#[derive(Debug, Clone)]
+pub struct SmbService {
+    config: SmbSourceConfig,
+    include_patterns: Vec<glob::Pattern>,
+    exclude_patterns: Vec<glob::Pattern>,
+}
+
+/// Why an SMB operation failed, so users can tell a wrong share from wrong credentials
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SmbFailure {
+    AuthenticationFailed,
+    ShareNotFound,
+    ServerUnreachable,
+    Other,
+}
+
+impl SmbService {
+    pub fn new(config: SmbSourceConfig) -> Result<Self> {
+        #[cfg(not(feature = "smb"))]
+        {
+            let _ = config;
+            return Err(anyhow!("SMB support not compiled in. Enable the 'smb' feature to use SMB sources."));
+        }
+
+        #[cfg(feature = "smb")]
+        {
+        if config.host.trim().is_empty() {
+            return Err(anyhow!("SMB host is required"));
+        }
+        if config.share.trim().trim_matches('/').is_empty() {
+            return Err(anyhow!("SMB share name is required"));
+        }
+
+        let include_patterns = compile_patterns(&config.include_patterns)?;
+        let exclude_patterns = compile_patterns(&config.exclude_patterns)?;
+
+        Ok(Self { config, include_patterns, exclude_patterns })
+        }
+    }
+
+    /// Recursively discover files under a watch folder (relative to the configured subpath)
+    pub async fn discover_files_in_folder(&self, folder_path: &str) -> Result<Vec<FileIngestionInfo>> {
+        #[cfg(not(feature = "smb"))]
+        {
+            let _ = folder_path;
+            return Err(anyhow!("SMB support not compiled in"));
+        }
+
+        #[cfg(feature = "smb")]
+        {
+        let service = self.clone();
+        let root = self.share_path(folder_path);
+        info!("Scanning SMB share //{}/{} folder: {}", self.config.host, self.share_name(), root);
+
+        let files = tokio::task::spawn_blocking(move || -> Result<Vec<FileIngestionInfo>> {
+            let client = service.connect()?;
+            let mut files = Vec::new();
+            let mut pending_dirs = vec![root.clone()];
+
+            while let Some(dir) = pending_dirs.pop() {
+                let entries = client.list_dir(&dir).map_err(|e| service.describe_error(&e, &dir))?;
+
+                for entry in entries {
+                    let name = entry.name().to_string();
+                    if name == "." || name == ".." {
+                        continue;
+                    }
+                    let path = join_share_path(&dir, &name);
+                    let relative = path.strip_prefix(&service.root_path()).unwrap_or(&path).trim_start_matches('/').to_string();
+
+                    match entry.get_type() {
+                        SmbDirentType::Dir => {
+                            if service.is_excluded(&relative, &name) {
+                                debug!("Skipping excluded SMB directory: {}", path);
+                            } else {
+                                pending_dirs.push(path);
+                            }
+                        }
+                        SmbDirentType::File => {
+                            if !service.should_include(&relative, &name) {
+                                debug!("Skipping filtered SMB file: {}", path);
+                                continue;
+                            }
+
+                            match client.stat(&path) {
+                                Ok(stat) => files.push(service.file_info(&path, &name, stat.size as i64, stat.modified)),
+                                Err(e) => warn!("Failed to stat SMB file {}: {}", path, e),
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            Ok(files)
+        })
+        .await??;
+
+        info!("Found {} files in SMB share //{}/{} folder {}", files.len(), self.config.host, self.share_name(), folder_path);
+        Ok(files)
+        }
+    }
+
+    /// Download a file by its path within the share (the `relative_path` of its FileIngestionInfo)
+    pub async fn download_file(&self, file_path: &str) -> Result<Vec<u8>> {
+        #[cfg(not(feature = "smb"))]
+        {
+            let _ = file_path;
+            return Err(anyhow!("SMB support not compiled in"));
+        }
+
+        #[cfg(feature = "smb")]
+        {
+        let service = self.clone();
+        let file_path = file_path.to_string();
+
+        tokio::task::spawn_blocking(move || -> Result<Vec<u8>> {
+            use std::io::Read;
+
+            let client = service.connect()?;
+            let mut file = client
+                .open_with(&file_path, SmbOpenOptions::default().read(true))
+                .map_err(|e| service.describe_error(&e, &file_path))?;
+
+            let mut content = Vec::new();
+            file.read_to_end(&mut content)
+                .map_err(|e| anyhow!("Failed to read SMB file {}: {}", file_path, e))?;
+            Ok(content)
+        })
+        .await?
+        }
+    }
+
+    /// Connect to the share and check that the subpath and watch folders exist
+    pub async fn test_connection(&self) -> Result<String> {
+        #[cfg(not(feature = "smb"))]
+        {
+            return Err(anyhow!("SMB support not compiled in"));
+        }
+
+        #[cfg(feature = "smb")]
+        {
+        let service = self.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<String> {
+            let client = service.connect()?;
+
+            // Listing the share root distinguishes a bad share from a bad subpath
+            client.list_dir("/").map_err(|e| service.describe_error(&e, "/"))?;
+
+            let root = service.root_path();
+            client.list_dir(&root).map_err(|e| match classify_error_kind(smb_error_kind(&e)) {
+                SmbFailure::ShareNotFound => ConnectionTestError::ResourceUnavailable(format!(
+                    "Subpath '{}' does not exist in share '{}'",
+                    root, service.share_name()
+                )).into(),
+                _ => service.describe_error(&e, &root),
+            })?;
+
+            for folder in &service.config.watch_folders {
+                let path = service.share_path(folder);
+                client.list_dir(&path).map_err(|e| match classify_error_kind(smb_error_kind(&e)) {
+                    SmbFailure::ShareNotFound => ConnectionTestError::ResourceUnavailable(format!(
+                        "Watch folder '{}' does not exist in share '{}'",
+                        path, service.share_name()
+                    )).into(),
+                    _ => service.describe_error(&e, &path),
+                })?;
+            }
+
+            Ok(format!(
+                "Successfully connected to //{}/{} as {}",
+                service.config.host,
+                service.share_name(),
+                service.config.username
+            ))
+        })
+        .await?
+        }
+    }
+
+    pub fn get_config(&self) -> &SmbSourceConfig {
+        &self.config
+    }
+
+    #[cfg(feature = "smb")]
+    fn connect(&self) -> Result<SmbClient> {
+        let mut credentials = SmbCredentials::default()
+            .server(format!("smb://{}", self.config.host.trim()))
+            .share(format!("/{}", self.share_name()))
+            .username(&self.config.username)
+            .password(&self.config.password);
+        if let Some(domain) = self.config.domain.as_deref().filter(|d| !d.trim().is_empty()) {
+            credentials = credentials.workgroup(domain);
+        }
+
+        SmbClient::new(credentials, SmbOptions::default().one_share_per_server(true))
+            .map_err(|e| self.describe_error(&e, "/"))
+    }
+
+    #[cfg(feature = "smb")]
+    fn describe_error(&self, error: &SmbError, path: &str) -> anyhow::Error {
+        match classify_error_kind(smb_error_kind(error)) {
+            SmbFailure::AuthenticationFailed => ConnectionTestError::AuthenticationFailed(format!(
+                "Authentication failed for user '{}' on {}: check the username, password and domain",
+                self.config.username, self.config.host
+            )).into(),
+            SmbFailure::ShareNotFound if path == "/" => ConnectionTestError::ResourceUnavailable(format!(
+                "Share '{}' was not found on {}",
+                self.share_name(), self.config.host
+            )).into(),
+            SmbFailure::ShareNotFound => ConnectionTestError::ResourceUnavailable(format!(
+                "Path '{}' was not found in share '{}'",
+                path, self.share_name()
+            )).into(),
+            SmbFailure::ServerUnreachable => ConnectionTestError::Unreachable(format!(
+                "Could not reach SMB server {}: {}",
+                self.config.host, error
+            )).into(),
+            SmbFailure::Other => anyhow!("SMB error on {}: {}", path, error),
+        }
+    }
+
+    #[cfg(feature = "smb")]
+    fn file_info(&self, path: &str, name: &str, size: i64, modified: std::time::SystemTime) -> FileIngestionInfo {
+        let last_modified: Option<chrono::DateTime<chrono::Utc>> = Some(modified.into());
+        let extension = std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let mut metadata_map = serde_json::Map::new();
+        metadata_map.insert("smb_host".to_string(), serde_json::Value::String(self.config.host.clone()));
+        metadata_map.insert("smb_share".to_string(), serde_json::Value::String(self.share_name().to_string()));
+
+        FileIngestionInfo {
+            relative_path: path.to_string(),
+            full_path: format!("smb://{}/{}{}", self.config.host, self.share_name(), path),
+            #[allow(deprecated)]
+            path: path.to_string(),
+            name: name.to_string(),
+            size,
+            mime_type: mime_guess::from_ext(&extension).first_or_octet_stream().to_string(),
+            last_modified,
+            // SMB has no ETag; size and modification time change whenever the content does
+            etag: format!("{}-{}", size, last_modified.map_or(0, |t| t.timestamp())),
+            is_directory: false,
+            created_at: None,
+            permissions: None,
+            owner: None,
+            group: None,
+            metadata: Some(serde_json::Value::Object(metadata_map)),
+        }
+    }
+
+    fn share_name(&self) -> &str {
+        self.config.share.trim().trim_matches('/')
+    }
+
+    /// The configured subpath as an absolute path within the share
+    fn root_path(&self) -> String {
+        let subpath = self.config.subpath.as_deref().unwrap_or("").trim().trim_matches('/');
+        if subpath.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/{}", subpath)
+        }
+    }
+
+    /// Absolute path within the share of a folder relative to the subpath
+    fn share_path(&self, folder: &str) -> String {
+        join_share_path(&self.root_path(), folder.trim().trim_matches('/'))
+    }
+
+    fn should_include(&self, relative_path: &str, name: &str) -> bool {
+        let extension = std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        if !self.config.file_extensions.contains(&extension) {
+            return false;
+        }
+        if self.is_excluded(relative_path, name) {
+            return false;
+        }
+
+        self.include_patterns.is_empty()
+            || self.include_patterns.iter().any(|pattern| pattern_matches(pattern, relative_path, name))
+    }
+
+    fn is_excluded(&self, relative_path: &str, name: &str) -> bool {
+        self.exclude_patterns.iter().any(|pattern| pattern_matches(pattern, relative_path, name))
+    }
+}
+
+fn join_share_path(dir: &str, name: &str) -> String {
+    match (dir.trim_end_matches('/'), name) {
+        (dir, "") if dir.is_empty() => "/".to_string(),
+        (dir, "") => dir.to_string(),
+        (dir, name) => format!("{}/{}", dir, name),
+    }
+}
+
+#[cfg(feature = "smb")]
+fn smb_error_kind(error: &SmbError) -> Option<(ErrorKind, Option<i32>)> {
+    match error {
+        SmbError::Io(e) => Some((e.kind(), e.raw_os_error())),
+        _ => None,
+    }
+}
+
+/// libsmbclient reports failures as errno values
+fn classify_error_kind(kind: Option<(ErrorKind, Option<i32>)>) -> SmbFailure {
+    const ENXIO: i32 = 6;
+    const ENODEV: i32 = 19;
+
+    match kind {
+        Some((ErrorKind::PermissionDenied, _)) => SmbFailure::AuthenticationFailed,
+        Some((ErrorKind::NotFound, _)) | Some((_, Some(ENODEV))) | Some((_, Some(ENXIO))) => SmbFailure::ShareNotFound,
+        Some((ErrorKind::ConnectionRefused, _))
+        | Some((ErrorKind::TimedOut, _))
+        | Some((ErrorKind::HostUnreachable, _))
+        | Some((ErrorKind::NetworkUnreachable, _)) => SmbFailure::ServerUnreachable,
+        _ => SmbFailure::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config() -> SmbSourceConfig {
+        SmbSourceConfig {
+            host: "nas.local".to_string(),
+            share: "/documents/".to_string(),
+            username: "reader".to_string(),
+            password: "secret".to_string(),
+            domain: None,
+            subpath: Some("scans/".to_string()),
+            watch_folders: vec!["inbox".to_string()],
+            file_extensions: vec!["pdf".to_string(), "png".to_string()],
+            include_patterns: vec![],
+            exclude_patterns: vec![],
+            auto_sync: true,
+            sync_interval_minutes: 60,
+        }
+    }
+
+    fn test_service(config: SmbSourceConfig) -> SmbService {
+        SmbService {
+            include_patterns: compile_patterns(&config.include_patterns).unwrap(),
+            exclude_patterns: compile_patterns(&config.exclude_patterns).unwrap(),
+            config,
+        }
+    }
+
+    #[test]
+    fn test_paths() {
+        let service = test_service(test_config());
+        assert_eq!(service.share_name(), "documents");
+        assert_eq!(service.root_path(), "/scans");
+        assert_eq!(service.share_path("/inbox/"), "/scans/inbox");
+        assert_eq!(service.share_path(""), "/scans");
+
+        let service = test_service(SmbSourceConfig { subpath: None, ..test_config() });
+        assert_eq!(service.root_path(), "/");
+        assert_eq!(service.share_path("inbox"), "/inbox");
+    }
+
+    #[test]
+    fn test_extension_and_glob_filters() {
+        let service = test_service(SmbSourceConfig {
+            include_patterns: vec!["invoice_*".to_string(), "archive/**/*.pdf".to_string()],
+            exclude_patterns: vec!["*draft*".to_string()],
+            ..test_config()
+        });
+
+        assert!(service.should_include("inbox/invoice_01.pdf", "invoice_01.pdf"));
+        assert!(service.should_include("archive/2024/report.pdf", "report.pdf"));
+        assert!(!service.should_include("inbox/report.pdf", "report.pdf"));
+        assert!(!service.should_include("inbox/invoice_draft.pdf", "invoice_draft.pdf"));
+        assert!(!service.should_include("inbox/invoice_01.docx", "invoice_01.docx"));
+        assert!(service.is_excluded("drafts_old", "drafts_old"));
+    }
+
+    #[test]
+    fn test_invalid_glob_rejected() {
+        assert!(compile_patterns(&["[unclosed".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_classify_errors() {
+        assert_eq!(classify_error_kind(Some((ErrorKind::PermissionDenied, Some(13)))), SmbFailure::AuthenticationFailed);
+        assert_eq!(classify_error_kind(Some((ErrorKind::NotFound, Some(2)))), SmbFailure::ShareNotFound);
+        assert_eq!(classify_error_kind(Some((ErrorKind::Other, Some(19)))), SmbFailure::ShareNotFound);
+        assert_eq!(classify_error_kind(Some((ErrorKind::ConnectionRefused, Some(111)))), SmbFailure::ServerUnreachable);
+        assert_eq!(classify_error_kind(None), SmbFailure::Other);
+    }
+}
@@ -0,0 +1,267 @@
+/*!
+ * Source Connection Tests
+ *
+ * Tests the settings of any source type, saved or not, and reports the outcome the
+ * same way for all of them: whether the server (or for local folders, the folder)
+ * could be reached, whether the credentials were accepted, and what went wrong.
+ *
+ * Services tell these stages apart by failing their `test_connection` with a
+ * `ConnectionTestError`; any other error counts as a failure before the server was
+ * reached, such as a configuration error.
+ */
+
+use serde_json::json;
+use thiserror::Error;
+
+use crate::models::{
+    ImapSourceConfig, LocalFolderSourceConfig, S3SourceConfig, SmbSourceConfig, SourceConnectionTestResult,
+    SourceType, WebDAVSourceConfig, WebDAVTestConnection,
+};
+use crate::services::{
+    imap_service::ImapService, local_folder_service::LocalFolderService, s3_service::S3Service,
+    smb_service::SmbService, webdav::test_webdav_connection,
+};
+
+/// How far a connection test got before it failed
+#[derive(Error, Debug)]
+pub enum ConnectionTestError {
+    /// The server, or for local folders the folder, could not be reached
+    #[error("{0}")]
+    Unreachable(String),
+
+    /// The server's TLS certificate is not trusted, e.g. because it is self-signed
+    #[error("{0}")]
+    CertificateRejected(String),
+
+    /// The server requires a client certificate and rejected the configured one, or
+    /// none was configured
+    #[error("{0}")]
+    ClientCertificateRejected(String),
+
+    /// The server answered but rejected the credentials
+    #[error("{0}")]
+    AuthenticationFailed(String),
+
+    /// Logged in, but the bucket, share, folder or mailbox is missing or may not be read
+    #[error("{0}")]
+    ResourceUnavailable(String),
+}
+
+/// Whether a failed test reached the server and whether it authenticated, in that order
+pub fn connection_stage(error: &anyhow::Error) -> (bool, bool) {
+    match error.downcast_ref::<ConnectionTestError>() {
+        Some(ConnectionTestError::ResourceUnavailable(_)) => (true, true),
+        Some(
+            ConnectionTestError::AuthenticationFailed(_)
+            | ConnectionTestError::CertificateRejected(_)
+            | ConnectionTestError::ClientCertificateRejected(_),
+        ) => (true, false),
+        Some(ConnectionTestError::Unreachable(_)) | None => (false, false),
+    }
+}
+
+/// Whether a failed test was stopped by an untrusted TLS certificate
+pub fn is_certificate_rejected(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ConnectionTestError>(), Some(ConnectionTestError::CertificateRejected(_)))
+}
+
+/// Whether a failed test was stopped by the server refusing the client certificate
+pub fn is_client_certificate_rejected(error: &anyhow::Error) -> bool {
+    matches!(error.downcast_ref::<ConnectionTestError>(), Some(ConnectionTestError::ClientCertificateRejected(_)))
+}
+
+/// Whether an error, or any error that caused it, is a TLS alert sent by a server that
+/// wanted a client certificate and didn't get one it accepts. OpenSSL words these as e.g.
+/// "tlsv13 alert certificate required", rustls as "received fatal alert: CertificateRequired".
+pub fn is_client_certificate_alert(error: &(dyn std::error::Error + 'static)) -> bool {
+    const ALERTS: [&str; 6] = [
+        "badcertificate",
+        "unknownca",
+        "certificateunknown",
+        "certificateexpired",
+        "certificaterevoked",
+        "unsupportedcertificate",
+    ];
+
+    let mut source = Some(error);
+    while let Some(error) = source {
+        let message: String = error
+            .to_string()
+            .to_lowercase()
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '_')
+            .collect();
+        if message.contains("certificaterequired")
+            || (message.contains("alert") && ALERTS.iter().any(|alert| message.contains(alert)))
+        {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// Tests `config` as settings of a `source_type` source. Fails only if `config` doesn't
+/// fit the source type; connection problems are reported in the result.
+pub async fn test_source_connection(
+    source_type: SourceType,
+    config: serde_json::Value,
+) -> Result<SourceConnectionTestResult, serde_json::Error> {
+    let result = match source_type {
+        SourceType::WebDAV => {
+            let config: WebDAVSourceConfig = serde_json::from_value(config)?;
+            let test_config = WebDAVTestConnection {
+                server_url: config.server_url,
+                username: config.username,
+                password: config.password,
+                server_type: config.server_type,
+                tls: config.tls,
+            };
+
+            match test_webdav_connection(&test_config).await {
+                Ok(result) => SourceConnectionTestResult {
+                    success: result.success,
+                    reachable: result.reachable,
+                    authenticated: result.authenticated,
+                    certificate_error: result.certificate_error,
+                    client_certificate_error: result.client_certificate_error,
+                    message: result.message,
+                    server_info: Some(json!({
+                        "server_version": result.server_version,
+                        "server_type": result.server_type,
+                        "detected_server_type": result.detected_server_type,
+                    })),
+                },
+                Err(e) => failed("WebDAV connection failed", &e),
+            }
+        }
+        SourceType::LocalFolder => {
+            let config: LocalFolderSourceConfig = serde_json::from_value(config)?;
+            match LocalFolderService::new(config) {
+                Ok(service) => outcome("Local folder test failed", service.test_connection().await),
+                Err(e) => failed("Local folder configuration error", &e),
+            }
+        }
+        SourceType::S3 => {
+            let config: S3SourceConfig = serde_json::from_value(config)?;
+            let endpoint = config.endpoint_url.clone().filter(|url| !url.is_empty());
+            let region = config.region.clone();
+            match S3Service::new(config).await {
+                Ok(service) => {
+                    let mut result = outcome("S3 test failed", service.test_connection().await);
+                    result.server_info = Some(json!({ "endpoint_url": endpoint, "region": region }));
+                    result
+                }
+                Err(e) => failed("S3 configuration error", &e),
+            }
+        }
+        SourceType::Smb => {
+            let config: SmbSourceConfig = serde_json::from_value(config)?;
+            match SmbService::new(config) {
+                Ok(service) => outcome("SMB test failed", service.test_connection().await),
+                Err(e) => failed("SMB configuration error", &e),
+            }
+        }
+        SourceType::Imap => {
+            let config: ImapSourceConfig = serde_json::from_value(config)?;
+            match ImapService::new(config) {
+                Ok(service) => outcome("IMAP test failed", service.test_connection().await),
+                Err(e) => failed("IMAP configuration error", &e),
+            }
+        }
+    };
+
+    Ok(result)
+}
+
+fn outcome(failure: &str, result: anyhow::Result<String>) -> SourceConnectionTestResult {
+    match result {
+        Ok(message) => SourceConnectionTestResult {
+            success: true,
+            reachable: true,
+            authenticated: true,
+            certificate_error: false,
+            client_certificate_error: false,
+            message,
+            server_info: None,
+        },
+        Err(e) => failed(failure, &e),
+    }
+}
+
+fn failed(failure: &str, error: &anyhow::Error) -> SourceConnectionTestResult {
+    let (reachable, authenticated) = connection_stage(error);
+    SourceConnectionTestResult {
+        success: false,
+        reachable,
+        authenticated,
+        certificate_error: is_certificate_rejected(error),
+        client_certificate_error: is_client_certificate_rejected(error),
+        message: format!("{}: {}", failure, error),
+        server_info: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connection_stage_of_errors() {
+        let stage = |error: anyhow::Error| connection_stage(&error);
+
+        assert_eq!(stage(ConnectionTestError::Unreachable("refused".into()).into()), (false, false));
+        assert_eq!(stage(ConnectionTestError::AuthenticationFailed("bad password".into()).into()), (true, false));
+        assert_eq!(stage(ConnectionTestError::ResourceUnavailable("no such bucket".into()).into()), (true, true));
+        assert_eq!(stage(ConnectionTestError::ClientCertificateRejected("certificate required".into()).into()), (true, false));
+        assert_eq!(stage(anyhow::anyhow!("Bucket name is required")), (false, false));
+
+        // Context added on the way up doesn't hide the stage
+        let error = anyhow::Error::from(ConnectionTestError::AuthenticationFailed("bad password".into()))
+            .context("IMAP test failed");
+        assert_eq!(connection_stage(&error), (true, false));
+    }
+
+    #[test]
+    fn test_client_certificate_alerts() {
+        let alert = |message: &str| is_client_certificate_alert(&std::io::Error::other(message.to_string()));
+
+        assert!(alert("error:0A00045C:SSL routines::tlsv13 alert certificate required"));
+        assert!(alert("error:0A000412:SSL routines:ssl3_read_bytes:sslv3 alert bad certificate"));
+        assert!(alert("received fatal alert: CertificateRequired"));
+        assert!(alert("received fatal alert: UnknownCA"));
+        // The server's own certificate failing verification is a different problem
+        assert!(!alert("error:0A000086:SSL routines:tls_post_process_server_certificate:certificate verify failed"));
+        assert!(!alert("invalid peer certificate: UnknownIssuer"));
+        assert!(!alert("Connection refused (os error 111)"));
+    }
+
+    #[tokio::test]
+    async fn test_local_folder_results() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = |folder: &std::path::Path| json!({
+            "watch_folders": [folder.to_string_lossy()],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "recursive": true,
+            "follow_symlinks": false
+        });
+
+        let result = test_source_connection(SourceType::LocalFolder, config(dir.path())).await.unwrap();
+        assert!(result.success && result.reachable && result.authenticated, "{:?}", result);
+
+        let missing = dir.path().join("missing");
+        let result = test_source_connection(SourceType::LocalFolder, config(&missing)).await.unwrap();
+        assert!(!result.success && !result.reachable && !result.authenticated, "{:?}", result);
+        assert!(result.message.contains("does not exist"), "{}", result.message);
+
+        let file = dir.path().join("scan.pdf");
+        std::fs::write(&file, b"%PDF-1.4").unwrap();
+        let result = test_source_connection(SourceType::LocalFolder, config(&file)).await.unwrap();
+        assert!(!result.success && result.reachable, "{:?}", result);
+        assert!(result.message.contains("not a directory"), "{}", result.message);
+
+        assert!(test_source_connection(SourceType::LocalFolder, json!({ "watch_folders": 3 })).await.is_err());
+    }
+}
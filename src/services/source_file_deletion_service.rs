@@ -0,0 +1,183 @@
+/*!
+ * Source File Deletion
+ *
+ * Deleting a document removes Readur's copy only. The file it was synced from is deleted
+ * as well only when the request asks for it or the source's `deletion_behavior` is
+ * `delete_source`, and only once the deletion is confirmed. A failed deletion on the
+ * source is reported but never undoes the local deletion.
+ */
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    db::Database,
+    models::{Document, S3SourceConfig, Source, SourceType, WebDAVSourceConfig},
+    services::{
+        s3_service::S3Service,
+        source_secrets::SourceSecrets,
+        webdav::WebDAVService,
+    },
+};
+
+/// What deleting a document does to the file on the source it was synced from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceDeletionBehavior {
+    /// Only the document in Readur is deleted
+    #[default]
+    KeepSource,
+    /// The file is deleted on the source too
+    DeleteSource,
+}
+
+impl SourceDeletionBehavior {
+    /// Reads the `deletion_behavior` of a source config. Absent or null keeps source files.
+    pub fn from_source_config(config: &serde_json::Value) -> Result<Self, String> {
+        match config.get("deletion_behavior") {
+            None | Some(serde_json::Value::Null) => Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|_| "deletion_behavior must be \"keep_source\" or \"delete_source\"".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SourceFileDeletionStatus {
+    /// The file is gone from the source, including when it already was
+    Deleted,
+    Failed,
+    /// Files of this source type cannot be deleted individually
+    Unsupported,
+}
+
+/// Outcome of deleting a document's file on its source
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SourceFileDeletion {
+    pub document_id: Uuid,
+    pub source_id: Uuid,
+    pub source_path: String,
+    pub status: SourceFileDeletionStatus,
+    pub error: Option<String>,
+}
+
+/// A source file to delete once its document is deleted
+#[derive(Debug, Clone)]
+pub struct PendingSourceDeletion {
+    pub document_id: Uuid,
+    pub source: Source,
+    pub source_path: String,
+}
+
+/// The source files deleting `documents` is to remove: those of sources whose
+/// `deletion_behavior` is `delete_source`, or with `behavior`, those of every synced
+/// document the way it says. Documents of deleted sources keep nothing to delete.
+pub async fn plan_source_deletions(
+    db: &Database,
+    documents: &[Document],
+    behavior: Option<SourceDeletionBehavior>,
+) -> anyhow::Result<Vec<PendingSourceDeletion>> {
+    let mut sources: HashMap<Uuid, Option<Source>> = HashMap::new();
+    let mut pending = Vec::new();
+
+    for document in documents {
+        let (Some(source_id), Some(source_path)) = (document.source_id, document.source_path.as_ref()) else {
+            continue;
+        };
+        if !sources.contains_key(&source_id) {
+            sources.insert(source_id, db.get_source_by_id(source_id).await?);
+        }
+        let Some(source) = sources.get(&source_id).and_then(Option::as_ref) else {
+            continue;
+        };
+
+        let behavior = behavior.unwrap_or_else(|| {
+            SourceDeletionBehavior::from_source_config(&source.config).unwrap_or_else(|e| {
+                warn!("Keeping files of source {} on delete: {}", source.id, e);
+                SourceDeletionBehavior::KeepSource
+            })
+        });
+        if behavior == SourceDeletionBehavior::DeleteSource {
+            pending.push(PendingSourceDeletion {
+                document_id: document.id,
+                source: source.clone(),
+                source_path: source_path.clone(),
+            });
+        }
+    }
+
+    Ok(pending)
+}
+
+/// Deletes the files on their sources. Every file is tried; failures are reported in the
+/// results rather than returned.
+pub async fn delete_source_files(pending: Vec<PendingSourceDeletion>, secrets: &SourceSecrets) -> Vec<SourceFileDeletion> {
+    let mut results = Vec::with_capacity(pending.len());
+
+    for deletion in pending {
+        let (status, error) = match delete_from_source(&deletion.source, &deletion.source_path, secrets).await {
+            Ok(true) => {
+                info!("Deleted {} of document {} on source {}", deletion.source_path, deletion.document_id, deletion.source.id);
+                (SourceFileDeletionStatus::Deleted, None)
+            }
+            Ok(false) => (
+                SourceFileDeletionStatus::Unsupported,
+                Some(format!("Files of {} sources cannot be deleted", deletion.source.source_type)),
+            ),
+            Err(e) => {
+                warn!("Failed to delete {} of document {} on source {}: {}", deletion.source_path, deletion.document_id, deletion.source.id, e);
+                (SourceFileDeletionStatus::Failed, Some(e.to_string()))
+            }
+        };
+        results.push(SourceFileDeletion {
+            document_id: deletion.document_id,
+            source_id: deletion.source.id,
+            source_path: deletion.source_path,
+            status,
+            error,
+        });
+    }
+
+    results
+}
+
+/// Deletes the file at `source_path` on `source`; `false` when the source type has no way
+/// to delete a single file
+async fn delete_from_source(source: &Source, source_path: &str, secrets: &SourceSecrets) -> anyhow::Result<bool> {
+    match source.source_type {
+        SourceType::WebDAV => {
+            let config: WebDAVSourceConfig = serde_json::from_value(secrets.decrypt_config(&source.config)?)
+                .map_err(|e| anyhow!("Invalid WebDAV config: {}", e))?;
+            let service = WebDAVService::new(config.into())?;
+            service.delete_file(source_path).await?;
+        }
+        SourceType::S3 => {
+            let config: S3SourceConfig = serde_json::from_value(secrets.decrypt_config(&source.config)?)
+                .map_err(|e| anyhow!("Invalid S3 config: {}", e))?;
+            S3Service::new(config).await?.delete_file(source_path).await?;
+        }
+        SourceType::LocalFolder | SourceType::Smb | SourceType::Imap => return Ok(false),
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deletion_behavior_defaults_to_keeping_source_files() {
+        assert_eq!(SourceDeletionBehavior::from_source_config(&serde_json::json!({})), Ok(SourceDeletionBehavior::KeepSource));
+        assert_eq!(
+            SourceDeletionBehavior::from_source_config(&serde_json::json!({ "deletion_behavior": "delete_source" })),
+            Ok(SourceDeletionBehavior::DeleteSource)
+        );
+        assert!(SourceDeletionBehavior::from_source_config(&serde_json::json!({ "deletion_behavior": "delete" })).is_err());
+        assert!(SourceDeletionBehavior::from_source_config(&serde_json::json!({ "deletion_behavior": true })).is_err());
+    }
+}
@@ -0,0 +1,149 @@
+/*!
+ * Default Upload Source
+ *
+ * A user can pick one of their sources as the default for uploads. Documents uploaded
+ * through the API are then filed under that source like the files synced from it. With
+ * write-back enabled, an upload is also written to the first watch folder of the source,
+ * and the document gets the path a later sync finds it under, so the sync recognises it as
+ * the same document. Write-back never replaces a file on the source: an upload whose name
+ * is taken there is written under a numbered name instead.
+ */
+
+use anyhow::anyhow;
+use std::path::Path;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+    db::Database,
+    models::{Settings, Source, SourceType, WebDAVSourceConfig},
+    services::{
+        source_secrets::SourceSecrets,
+        webdav::{encode_path, WebDAVService},
+    },
+};
+
+/// Most numbered names tried for an upload whose name is taken on the source
+const MAX_NAME_ATTEMPTS: u32 = 100;
+
+/// Where an upload is filed
+#[derive(Debug, Clone)]
+pub struct UploadTarget {
+    pub source: Source,
+    /// Whether the upload is also written to the source
+    pub write_back: bool,
+}
+
+impl UploadTarget {
+    /// The user's default source, while it still exists and belongs to them
+    pub async fn for_user(db: &Database, user_id: Uuid, settings: Option<&Settings>) -> Option<Self> {
+        let settings = settings?;
+        let source_id = settings.default_source_id?;
+        match db.get_source(user_id, source_id).await {
+            Ok(Some(source)) => Some(Self {
+                source,
+                write_back: settings.default_source_write_back,
+            }),
+            Ok(None) => {
+                warn!("Default source {} of user {} no longer exists, not filing upload under it", source_id, user_id);
+                None
+            }
+            Err(e) => {
+                warn!("Failed to load default source {} of user {}: {}", source_id, user_id, e);
+                None
+            }
+        }
+    }
+
+    /// The WebDAV config uploads are written back with, or `None` when they are not
+    fn write_back_config(&self, secrets: &SourceSecrets) -> Option<WebDAVSourceConfig> {
+        if !self.write_back {
+            return None;
+        }
+        if self.source.source_type != SourceType::WebDAV {
+            warn!("Not writing uploads back to {} source {}", self.source.source_type, self.source.id);
+            return None;
+        }
+        let config = secrets
+            .decrypt_config(&self.source.config)
+            .and_then(|config| serde_json::from_value(config).map_err(Into::into));
+        match config {
+            Ok(config) => Some(config),
+            Err(e) => {
+                warn!("Not writing uploads back to source {} with invalid WebDAV config: {}", self.source.id, e);
+                None
+            }
+        }
+    }
+
+    /// Writes the stored upload at `file_path`, uploaded as `filename`, to the first watch
+    /// folder of the source, in chunks of `chunk_size` bytes where the server supports it.
+    /// Returns the path the sync of the source lists the copy under, or `None` when uploads
+    /// aren't written back.
+    pub async fn write_back(
+        &self,
+        secrets: &SourceSecrets,
+        filename: &str,
+        file_path: &str,
+        chunk_size: u64,
+    ) -> anyhow::Result<Option<String>> {
+        let Some(config) = self.write_back_config(secrets) else {
+            return Ok(None);
+        };
+        let name = upload_name(filename).ok_or_else(|| anyhow!("'{}' is not a usable file name", filename))?;
+        let folder = config.watch_folders.first().map(|folder| folder.trim_end_matches('/').to_string()).unwrap_or_default();
+
+        let service = WebDAVService::new(config.into())?
+        .with_upload_chunk_size(chunk_size);
+
+        for attempt in 0..MAX_NAME_ATTEMPTS {
+            let path = format!("{}/{}", folder, numbered_name(name, attempt));
+            if service.upload_file(&path, Path::new(file_path)).await? {
+                return Ok(Some(encode_path(&path)));
+            }
+            info!("{} already exists on source {}, trying another name", path, self.source.id);
+        }
+        Err(anyhow!("'{}' and {} numbered variants of it already exist in {}", name, MAX_NAME_ATTEMPTS - 1, folder))
+    }
+}
+
+/// The last component of an uploaded file name, as clients may send a whole path. Names that
+/// would climb out of the folder they are written to are refused.
+fn upload_name(filename: &str) -> Option<&str> {
+    let name = filename.rsplit(['/', '\\']).next()?.trim();
+    (!name.is_empty() && name != "." && name != "..").then_some(name)
+}
+
+/// `name` for the first attempt, then `name (1)`, `name (2)`, ... before the extension
+fn numbered_name(name: &str, attempt: u32) -> String {
+    if attempt == 0 {
+        return name.to_string();
+    }
+    match name.rfind('.').filter(|&dot| dot > 0) {
+        Some(dot) => format!("{} ({}){}", &name[..dot], attempt, &name[dot..]),
+        None => format!("{} ({})", name, attempt),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upload_name_keeps_only_the_last_component() {
+        assert_eq!(upload_name("receipt.pdf"), Some("receipt.pdf"));
+        assert_eq!(upload_name("../../etc/receipt.pdf"), Some("receipt.pdf"));
+        assert_eq!(upload_name("C:\\Users\\me\\receipt.pdf"), Some("receipt.pdf"));
+        assert_eq!(upload_name("scans/.."), None);
+        assert_eq!(upload_name("scans/"), None);
+    }
+
+    #[test]
+    fn test_numbered_names_keep_the_extension() {
+        assert_eq!(numbered_name("receipt.pdf", 0), "receipt.pdf");
+        assert_eq!(numbered_name("receipt.pdf", 2), "receipt (2).pdf");
+        assert_eq!(numbered_name("archive.tar.gz", 1), "archive.tar (1).gz");
+        assert_eq!(numbered_name("README", 1), "README (1)");
+        assert_eq!(numbered_name(".hidden", 1), ".hidden (1)");
+    }
+}
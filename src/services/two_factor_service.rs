@@ -0,0 +1,220 @@
+use anyhow::{anyhow, Result};
+use chrono::{Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+const TOTP_ISSUER: &str = "Readur";
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECONDS: u64 = 30;
+/// Accept codes from one step before and after the current one to tolerate clock drift
+const TOTP_SKEW: u8 = 1;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+
+const CHALLENGE_TOKEN_MINUTES: i64 = 5;
+const CHALLENGE_PURPOSE: &str = "2fa_challenge";
+
+/// Claims for the short-lived token issued between the password and TOTP steps of login
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TwoFactorChallengeClaims {
+    pub sub: Uuid,
+    pub purpose: String,
+    pub exp: usize,
+}
+
+/// Generates a new random base32-encoded TOTP secret
+pub fn generate_secret() -> String {
+    match Secret::generate_secret().to_encoded() {
+        Secret::Encoded(encoded) => encoded,
+        Secret::Raw(_) => unreachable!("to_encoded always returns an encoded secret"),
+    }
+}
+
+fn build_totp(secret: &str, account_name: &str) -> Result<TOTP> {
+    let secret_bytes = Secret::Encoded(secret.to_string())
+        .to_bytes()
+        .map_err(|e| anyhow!("Invalid TOTP secret: {:?}", e))?;
+
+    // otpauth labels use ':' as the issuer separator, so it can't appear in the account name
+    let account_name = account_name.replace(':', "_");
+
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        TOTP_SKEW,
+        TOTP_STEP_SECONDS,
+        secret_bytes,
+        Some(TOTP_ISSUER.to_string()),
+        account_name,
+    )
+    .map_err(|e| anyhow!("Failed to build TOTP: {}", e))
+}
+
+/// Builds the otpauth:// URI for authenticator apps
+pub fn otpauth_url(secret: &str, account_name: &str) -> Result<String> {
+    Ok(build_totp(secret, account_name)?.get_url())
+}
+
+/// Checks a 6-digit code against the secret using the current time
+pub fn verify_code(secret: &str, account_name: &str, code: &str) -> Result<bool> {
+    Ok(matching_step(secret, account_name, code)?.is_some())
+}
+
+/// The time step a 6-digit code is valid for, if it is valid now. Callers that sign users in
+/// record it, so the same code can't be used twice within its window.
+pub fn matching_step(secret: &str, account_name: &str, code: &str) -> Result<Option<u64>> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_err(|e| anyhow!("System clock is before the epoch: {}", e))?
+        .as_secs();
+    matching_step_at(secret, account_name, code, now)
+}
+
+fn matching_step_at(secret: &str, account_name: &str, code: &str, time: u64) -> Result<Option<u64>> {
+    let code = code.trim();
+    if code.len() != TOTP_DIGITS || !code.chars().all(|c| c.is_ascii_digit()) {
+        return Ok(None);
+    }
+
+    let totp = build_totp(secret, account_name)?;
+    let current = time / TOTP_STEP_SECONDS;
+    let skew = u64::from(TOTP_SKEW);
+    Ok((current.saturating_sub(skew)..=current + skew).find(|step| {
+        let expected = totp.generate(step * TOTP_STEP_SECONDS);
+        // Compare without an early exit, so timing doesn't tell how many digits were right
+        expected.len() == code.len()
+            && expected.bytes().zip(code.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+    }))
+}
+
+/// Generates a fresh set of human-friendly recovery codes (e.g. `ABCD-EFGH`)
+pub fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| {
+            let chars: String = (0..8)
+                .map(|_| RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+                .collect();
+            format!("{}-{}", &chars[..4], &chars[4..])
+        })
+        .collect()
+}
+
+/// Hashes a recovery code for storage. Input is normalized so that case and dashes don't matter.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(normalized.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Challenge tokens are signed with a key derived from the JWT secret so they can never be
+/// accepted as a regular session token
+fn challenge_key(jwt_secret: &str) -> String {
+    format!("{}:{}", jwt_secret, CHALLENGE_PURPOSE)
+}
+
+pub fn create_challenge_token(user_id: Uuid, jwt_secret: &str) -> Result<String> {
+    let expiration = Utc::now()
+        .checked_add_signed(Duration::minutes(CHALLENGE_TOKEN_MINUTES))
+        .expect("valid timestamp")
+        .timestamp();
+
+    let claims = TwoFactorChallengeClaims {
+        sub: user_id,
+        purpose: CHALLENGE_PURPOSE.to_string(),
+        exp: expiration as usize,
+    };
+
+    let token = encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(challenge_key(jwt_secret).as_bytes()),
+    )?;
+
+    Ok(token)
+}
+
+pub fn verify_challenge_token(token: &str, jwt_secret: &str) -> Result<TwoFactorChallengeClaims> {
+    let token_data = decode::<TwoFactorChallengeClaims>(
+        token,
+        &DecodingKey::from_secret(challenge_key(jwt_secret).as_bytes()),
+        &Validation::default(),
+    )?;
+
+    if token_data.claims.purpose != CHALLENGE_PURPOSE {
+        return Err(anyhow!("Token is not a 2FA challenge token"));
+    }
+
+    Ok(token_data.claims)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_code_verifies() {
+        let secret = generate_secret();
+        let totp = build_totp(&secret, "alice").unwrap();
+        let code = totp.generate_current().unwrap();
+
+        assert!(verify_code(&secret, "alice", &code).unwrap());
+        assert!(!verify_code(&secret, "alice", "abcdef").unwrap());
+        assert!(!verify_code(&secret, "alice", "12345").unwrap());
+    }
+
+    #[test]
+    fn test_matching_step_tolerates_one_step_of_drift() {
+        let secret = generate_secret();
+        let totp = build_totp(&secret, "alice").unwrap();
+        let now = 1_700_000_000;
+        let step = now / TOTP_STEP_SECONDS;
+
+        let code = totp.generate(now);
+        assert_eq!(matching_step_at(&secret, "alice", &code, now).unwrap(), Some(step));
+        assert_eq!(matching_step_at(&secret, "alice", &code, now + TOTP_STEP_SECONDS).unwrap(), Some(step));
+        assert_eq!(matching_step_at(&secret, "alice", &code, now + 3 * TOTP_STEP_SECONDS).unwrap(), None);
+    }
+
+    #[test]
+    fn test_otpauth_url_contains_issuer() {
+        let secret = generate_secret();
+        let url = otpauth_url(&secret, "user:with:colons").unwrap();
+
+        assert!(url.starts_with("otpauth://totp/"));
+        assert!(url.contains("issuer=Readur"));
+    }
+
+    #[test]
+    fn test_recovery_codes_are_unique_and_hash_normalized() {
+        let codes = generate_recovery_codes();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+
+        let unique: std::collections::HashSet<_> = codes.iter().collect();
+        assert_eq!(unique.len(), codes.len());
+
+        let code = &codes[0];
+        assert_eq!(hash_recovery_code(code), hash_recovery_code(&code.to_lowercase().replace('-', "")));
+    }
+
+    #[test]
+    fn test_challenge_token_is_not_a_session_token() {
+        let user_id = Uuid::new_v4();
+        let token = create_challenge_token(user_id, "secret").unwrap();
+
+        assert_eq!(verify_challenge_token(&token, "secret").unwrap().sub, user_id);
+        assert!(crate::auth::verify_jwt(&token, "secret").is_err());
+        assert!(verify_challenge_token(&token, "other-secret").is_err());
+    }
+}
@@ -0,0 +1,137 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Breakers shared by every WebDAVService talking to the same server
+static BREAKERS: OnceLock<Mutex<HashMap<String, Arc<CircuitBreaker>>>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// Requests go through normally
+    Closed,
+    /// Too many consecutive failures; requests fail fast until the cool-down ends
+    Open,
+    /// The cool-down ended and a single probe request is testing whether the server recovered
+    HalfOpen,
+}
+
+/// Snapshot of a server's breaker for health and validation reporting
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CircuitBreakerStatus {
+    pub state: CircuitState,
+    pub consecutive_failures: u32,
+    /// Seconds until a probe request is allowed again, while the circuit is open
+    pub retry_after_seconds: Option<u64>,
+}
+
+impl CircuitBreakerStatus {
+    /// Whether requests to the server currently fail fast
+    pub fn is_unavailable(&self) -> bool {
+        self.state != CircuitState::Closed
+    }
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    consecutive_failures: u32,
+    opened_until: Option<Instant>,
+    /// Set while the half-open probe is in flight
+    probe_started: Option<Instant>,
+}
+
+/// Per-server circuit breaker. After `failure_threshold` consecutive failures the circuit
+/// opens and requests fail fast for the cool-down, then a single probe is let through:
+/// success closes the circuit, failure opens it for another cool-down.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<BreakerState>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(BreakerState { consecutive_failures: 0, opened_until: None, probe_started: None }),
+        }
+    }
+
+    /// The breaker shared by all services for `server_url`. The thresholds of the first caller win.
+    pub fn for_server(server_url: &str, failure_threshold: u32, cooldown: Duration) -> Arc<Self> {
+        let key = server_url.trim().trim_end_matches('/').to_lowercase();
+        let mut breakers = BREAKERS
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        breakers
+            .entry(key)
+            .or_insert_with(|| Arc::new(Self::new(failure_threshold, cooldown)))
+            .clone()
+    }
+
+    /// Checks whether a request may be sent. Returns the time left in the cool-down if not.
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut state = self.lock();
+
+        let Some(opened_until) = state.opened_until else {
+            return Ok(());
+        };
+        if now < opened_until {
+            return Err(opened_until - now);
+        }
+        // A probe that never reported back (e.g. a dropped future) must not block the server forever
+        if let Some(probe_started) = state.probe_started {
+            if now.duration_since(probe_started) < self.cooldown {
+                return Err(self.cooldown - now.duration_since(probe_started));
+            }
+        }
+        state.probe_started = Some(now);
+        Ok(())
+    }
+
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+        state.opened_until = None;
+        state.probe_started = None;
+    }
+
+    /// Counts a failure; returns true if this failure opened the circuit
+    pub fn record_failure(&self) -> bool {
+        let now = Instant::now();
+        let mut state = self.lock();
+        state.consecutive_failures = state.consecutive_failures.saturating_add(1);
+
+        let probe_failed = state.probe_started.take().is_some();
+        if probe_failed || (state.opened_until.is_none() && state.consecutive_failures >= self.failure_threshold) {
+            state.opened_until = Some(now + self.cooldown);
+            return true;
+        }
+        false
+    }
+
+    pub fn status(&self) -> CircuitBreakerStatus {
+        let now = Instant::now();
+        let state = self.lock();
+        let (circuit_state, retry_after) = match state.opened_until {
+            None => (CircuitState::Closed, None),
+            Some(until) if now < until => (CircuitState::Open, Some(until - now)),
+            Some(_) => (CircuitState::HalfOpen, None),
+        };
+        CircuitBreakerStatus {
+            state: circuit_state,
+            consecutive_failures: state.consecutive_failures,
+            // Round up so an open circuit never reports 0 seconds
+            retry_after_seconds: retry_after.map(|left| left.as_millis().div_ceil(1000) as u64),
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,4 +1,6 @@
 
+use crate::models::{WebDAVSourceConfig, WebDAVTlsConfig};
+
 /// WebDAV server configuration
 #[derive(Debug, Clone)]
 pub struct WebDAVConfig {
@@ -8,7 +10,8 @@ pub struct WebDAVConfig {
     pub watch_folders: Vec<String>,
     pub file_extensions: Vec<String>,
     pub timeout_seconds: u64,
-    pub server_type: Option<String>, // "nextcloud", "owncloud", "generic"
+    pub server_type: Option<String>, // "nextcloud", "owncloud", "seafile", "apache", "generic"
+    pub tls: WebDAVTlsConfig,
 }
 
 /// Retry configuration for WebDAV operations
@@ -20,6 +23,10 @@ pub struct RetryConfig {
     pub backoff_multiplier: f64,
     pub timeout_seconds: u64,
     pub rate_limit_backoff_ms: u64, // Additional backoff for 429 responses
+    /// Consecutive failed requests after which the server's circuit breaker opens
+    pub circuit_failure_threshold: u32,
+    /// How long an open circuit fails fast before a probe request is let through
+    pub circuit_cooldown_ms: u64,
 }
 
 /// Concurrency configuration for WebDAV operations
@@ -56,6 +63,8 @@ impl Default for RetryConfig {
             backoff_multiplier: 2.0,
             timeout_seconds: 30,
             rate_limit_backoff_ms: 5000, // 5 seconds
+            circuit_failure_threshold: 5,
+            circuit_cooldown_ms: 60000, // 1 minute
         }
     }
 }
@@ -83,6 +92,27 @@ impl Default for DepthInfinityConfig {
     }
 }
 
+/// Request timeout of the services built for a source. Listing a large Nextcloud folder
+/// can take more than 2 minutes.
+pub const SOURCE_TIMEOUT_SECONDS: u64 = 180;
+
+/// The configuration a source's sync uses; everything else that talks to the source's
+/// server builds its service from this too, so it gets the same timeout and TLS settings
+impl From<WebDAVSourceConfig> for WebDAVConfig {
+    fn from(config: WebDAVSourceConfig) -> Self {
+        Self {
+            server_url: config.server_url,
+            username: config.username,
+            password: config.password,
+            watch_folders: config.watch_folders,
+            file_extensions: config.file_extensions,
+            timeout_seconds: SOURCE_TIMEOUT_SECONDS,
+            server_type: config.server_type,
+            tls: config.tls,
+        }
+    }
+}
+
 impl WebDAVConfig {
     /// Creates a new WebDAV configuration
     pub fn new(
@@ -100,6 +130,7 @@ impl WebDAVConfig {
             file_extensions,
             timeout_seconds: 30,
             server_type: None,
+            tls: WebDAVTlsConfig::default(),
         }
     }
 
@@ -173,6 +204,25 @@ impl WebDAVConfig {
         Ok(())
     }
 
+    /// The CA certificates to trust in addition to the system's roots, read from the PEM in
+    /// `tls.ca_certificate`. File paths aren't read: they are user input, and reading them
+    /// would let any user probe or exhaust the server's filesystem.
+    pub fn ca_certificates(&self) -> anyhow::Result<Vec<reqwest::Certificate>> {
+        let Some(ca_certificate) = self.tls.ca_certificate.as_deref().map(str::trim).filter(|ca| !ca.is_empty()) else {
+            return Ok(Vec::new());
+        };
+
+        if !ca_certificate.contains("-----BEGIN") {
+            return Err(anyhow::anyhow!("ca_certificate must be the PEM text of the CA certificate, not a file path"));
+        }
+        let certificates = reqwest::Certificate::from_pem_bundle(ca_certificate.as_bytes())
+            .map_err(|e| anyhow::anyhow!("Invalid CA certificate: {}", e))?;
+        if certificates.is_empty() {
+            return Err(anyhow::anyhow!("The CA certificate contains no PEM certificates"));
+        }
+        Ok(certificates)
+    }
+
     /// Returns the base URL for WebDAV operations
     pub fn webdav_url(&self) -> String {
         // Normalize the server URL by adding protocol if missing and removing trailing slashes
@@ -194,13 +244,32 @@ impl WebDAVConfig {
                     normalized_url
                 }
             }
+            Some("seafile") => {
+                // SeafDAV lists libraries at its root: /seafdav/<library>/<path>
+                if !normalized_url.contains("/seafdav") {
+                    format!("{}/seafdav", normalized_url)
+                } else {
+                    normalized_url
+                }
+            }
             _ => {
-                // Generic WebDAV - use the normalized URL as provided
+                // Generic WebDAV and Apache mod_dav - the URL is the full WebDAV root as provided
                 normalized_url
             }
         }
     }
 
+    /// Path part of the WebDAV base URL, e.g. `/seafdav` or `/dav/files`. Hrefs in PROPFIND
+    /// responses start with it; empty if the WebDAV root is the server root.
+    pub fn webdav_root_path(&self) -> String {
+        let webdav_url = self.webdav_url();
+        let without_scheme = webdav_url.split_once("://").map(|(_, rest)| rest).unwrap_or(&webdav_url);
+        match without_scheme.find('/') {
+            Some(index) => without_scheme[index..].trim_end_matches('/').to_string(),
+            None => String::new(),
+        }
+    }
+
     /// Returns alternative WebDAV URLs to try if the primary one fails
     /// This is used for fallback mechanisms when encountering 405 errors
     pub fn webdav_fallback_urls(&self) -> Vec<String> {
@@ -222,6 +291,9 @@ impl WebDAVConfig {
                 fallback_urls.push(format!("{}/remote.php/dav/files/{}", normalized_url, self.username));
                 fallback_urls.push(format!("{}/webdav", normalized_url));
             }
+            Some("seafile") | Some("apache") => {
+                // The WebDAV root is known exactly; guessing other endpoints would only hit the wrong service
+            }
             _ => {
                 // Generic WebDAV - try common patterns
                 // Fallback 1: /remote.php/webdav (ownCloud/Nextcloud)
@@ -1,16 +1,19 @@
 // Simplified WebDAV service modules - consolidated architecture
 
 pub mod config;
+pub mod circuit_breaker;
 pub mod service; 
 pub mod smart_sync;
 pub mod progress_shim; // Backward compatibility shim for simplified progress tracking
 
 // Re-export main types for convenience
 pub use config::{WebDAVConfig, RetryConfig, ConcurrencyConfig};
+pub use circuit_breaker::{CircuitBreaker, CircuitBreakerStatus, CircuitState};
 pub use service::{
     WebDAVService, WebDAVDiscoveryResult, ServerCapabilities, HealthStatus, test_webdav_connection,
     ValidationReport, ValidationIssue, ValidationIssueType, ValidationSeverity, 
-    ValidationRecommendation, ValidationAction, ValidationSummary
+    ValidationRecommendation, ValidationAction, ValidationSummary, DEEP_SCAN_HEALTH_THRESHOLD,
+    DEFAULT_UPLOAD_CHUNK_SIZE_MB, encode_path
 };
 pub use smart_sync::{SmartSyncService, SmartSyncDecision, SmartSyncStrategy, SmartSyncResult};
 
@@ -12,6 +12,7 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         }
     }
 
@@ -25,6 +26,7 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         }
     }
 
@@ -38,6 +40,7 @@ mod tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         }
     }
 
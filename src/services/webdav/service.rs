@@ -10,19 +10,32 @@ use tracing::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
 
 use crate::models::{
-    FileIngestionInfo, WebDAVConnectionResult, WebDAVCrawlEstimate, WebDAVTestConnection,
-    WebDAVFolderInfo,
+    FileIngestionInfo, OrphanedDirectoryCleanup, SourceCleanupQuery, WebDAVConnectionResult,
+    WebDAVCrawlEstimate, WebDAVTestConnection, WebDAVFolderInfo,
 };
+use crate::AppState;
 use crate::webdav_xml_parser::{parse_propfind_response, parse_propfind_response_with_directories};
 use crate::mime_detection::{detect_mime_from_content, update_mime_type_with_content, MimeDetectionResult};
+use crate::services::client_certificate::load_client_identity;
+use crate::services::processing_time_model::ProcessingTimeModel;
+use crate::services::source_connection_test::{
+    connection_stage, is_certificate_rejected, is_client_certificate_alert, is_client_certificate_rejected,
+    ConnectionTestError,
+};
 
-use super::{config::{WebDAVConfig, RetryConfig, ConcurrencyConfig}, SyncProgress};
+use super::{
+    circuit_breaker::{CircuitBreaker, CircuitBreakerStatus, CircuitState},
+    config::{WebDAVConfig, RetryConfig, ConcurrencyConfig},
+    SyncProgress,
+};
 
 /// Results from WebDAV discovery including both files and directories
 #[derive(Debug, Clone)]
 pub struct WebDAVDiscoveryResult {
     pub files: Vec<FileIngestionInfo>,
     pub directories: Vec<FileIngestionInfo>,
+    /// Directories that were listed successfully; their files and subdirectories are complete
+    pub scanned_directories: Vec<String>,
 }
 
 /// Result of downloading a file with MIME type detection
@@ -68,6 +81,38 @@ pub struct ValidationReport {
     pub summary: ValidationSummary,
 }
 
+/// Health score below which a scheduled validation queues a deep scan
+pub const DEEP_SCAN_HEALTH_THRESHOLD: i32 = 80;
+
+/// Size of the chunks large uploads to Nextcloud and ownCloud are split into, unless set
+/// with `WEBDAV_UPLOAD_CHUNK_SIZE_MB`
+pub const DEFAULT_UPLOAD_CHUNK_SIZE_MB: u64 = 10;
+
+impl ValidationReport {
+    /// Whether directory tracking looks unreliable enough that the next sync should be a full
+    /// deep scan instead of an ETag-driven incremental one. A deep scan can't help while the
+    /// server itself is unreachable, so critical connectivity issues never trigger one.
+    pub fn should_trigger_deep_scan(&self) -> bool {
+        if self.issues.iter().any(|i| matches!(i.severity, ValidationSeverity::Critical)) {
+            return false;
+        }
+
+        let tracking_drift = self.issues.iter().any(|i| matches!(
+            i.issue_type,
+            ValidationIssueType::Untracked
+                | ValidationIssueType::Missing
+                | ValidationIssueType::ETagMismatch
+                | ValidationIssueType::ETagUnreliable
+        ));
+        let rescan_recommended = self.recommendations.iter().any(|r| matches!(
+            r.action,
+            ValidationAction::DeepScanRequired | ValidationAction::RebuildTracking
+        ));
+
+        tracking_drift || rescan_recommended || self.overall_health_score < DEEP_SCAN_HEALTH_THRESHOLD
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationIssue {
     pub issue_type: ValidationIssueType,
@@ -147,6 +192,12 @@ pub struct WebDAVService {
     download_semaphore: Arc<Semaphore>,
     /// Stores the working protocol (updated after successful protocol detection)
     working_protocol: Arc<std::sync::RwLock<Option<String>>>,
+    /// Shared with every other service for the same server
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Server type found by `detect_server_type`, used when the config leaves it unset
+    detected_server_type: Arc<std::sync::RwLock<Option<String>>>,
+    /// Uploads larger than this go to Nextcloud and ownCloud in chunks of this size
+    upload_chunk_size: u64,
 }
 
 impl WebDAVService {
@@ -169,15 +220,18 @@ impl WebDAVService {
         // Validate configuration
         config.validate()?;
 
-        // Create HTTP client with timeout
-        let client = Client::builder()
-            .timeout(config.timeout())
-            .build()?;
+        let client = build_http_client(&config)?;
 
         // Create semaphores for concurrency control
         let scan_semaphore = Arc::new(Semaphore::new(concurrency_config.max_concurrent_scans));
         let download_semaphore = Arc::new(Semaphore::new(concurrency_config.max_concurrent_downloads));
 
+        let circuit_breaker = CircuitBreaker::for_server(
+            &config.server_url,
+            retry_config.circuit_failure_threshold,
+            Duration::from_millis(retry_config.circuit_cooldown_ms),
+        );
+
         Ok(Self {
             client,
             config,
@@ -186,9 +240,18 @@ impl WebDAVService {
             scan_semaphore,
             download_semaphore,
             working_protocol: Arc::new(std::sync::RwLock::new(None)),
+            circuit_breaker,
+            detected_server_type: Arc::new(std::sync::RwLock::new(None)),
+            upload_chunk_size: DEFAULT_UPLOAD_CHUNK_SIZE_MB * 1024 * 1024,
         })
     }
 
+    /// Splits uploads to Nextcloud and ownCloud into chunks of `bytes` instead of the default
+    pub fn with_upload_chunk_size(mut self, bytes: u64) -> Self {
+        self.upload_chunk_size = bytes.max(1);
+        self
+    }
+
     // ============================================================================
     // Protocol Detection Methods
     // ============================================================================
@@ -220,6 +283,12 @@ impl WebDAVService {
             }
             Err(https_error) => {
                 warn!("❌ HTTPS connection failed: {}", https_error);
+
+                // A rejected certificate is a configuration problem; falling back to HTTP
+                // would hide it and send the credentials unencrypted
+                if is_certificate_rejected(&https_error) || is_client_certificate_rejected(&https_error) {
+                    return Err(https_error);
+                }
                 
                 // Check if this is a connection-related error (not auth error)
                 if self.is_connection_error(&https_error) {
@@ -242,22 +311,26 @@ impl WebDAVService {
                             error!("❌ Both HTTPS and HTTP failed");
                             error!("   HTTPS error: {}", https_error);
                             error!("   HTTP error: {}", http_error);
-                            return Err(anyhow!(
+                            return Err(ConnectionTestError::Unreachable(format!(
                                 "Protocol detection failed. Both HTTPS and HTTP connections failed. \
                                 HTTPS error: {}. HTTP error: {}. \
                                 Please verify the server URL and ensure WebDAV is properly configured.",
                                 https_error, http_error
-                            ));
+                            )).into());
                         }
                     }
                 } else {
                     // Auth or other non-connection error with HTTPS - don't try HTTP
                     error!("❌ HTTPS failed with non-connection error (likely auth or server config): {}", https_error);
-                    return Err(anyhow!(
+                    let message = format!(
                         "HTTPS connection failed with authentication or server configuration error: {}. \
                         Please check your credentials and server settings.", 
                         https_error
-                    ));
+                    );
+                    return Err(match https_error.downcast_ref::<ConnectionTestError>() {
+                        Some(ConnectionTestError::AuthenticationFailed(_)) => ConnectionTestError::AuthenticationFailed(message),
+                        _ => ConnectionTestError::ResourceUnavailable(message),
+                    }.into());
                 }
             }
         }
@@ -275,7 +348,8 @@ impl WebDAVService {
             watch_folders: self.config.watch_folders.clone(),
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
-            server_type: self.config.server_type.clone(),
+            server_type: self.get_server_type(),
+            tls: self.config.tls.clone(),
         };
 
         // Test basic OPTIONS request
@@ -287,14 +361,10 @@ impl WebDAVService {
             .basic_auth(&self.config.username, Some(&self.config.password))
             .send()
             .await
-            .map_err(|e| anyhow!("Connection failed: {}", e))?;
+            .map_err(|e| request_error(&webdav_url, e))?;
 
         if !response.status().is_success() {
-            return Err(anyhow!(
-                "Protocol test failed with status: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            ));
+            return Err(options_status_error("Protocol test", response).await);
         }
 
         debug!("✅ Protocol connection test successful");
@@ -350,6 +420,11 @@ impl WebDAVService {
                 message: format!("Configuration error: {}", e),
                 server_version: None,
                 server_type: None,
+                detected_server_type: None,
+                reachable: false,
+                authenticated: false,
+                certificate_error: false,
+                client_certificate_error: false,
             });
         }
 
@@ -361,34 +436,60 @@ impl WebDAVService {
             }
             Err(e) => {
                 error!("❌ Protocol detection failed: {}", e);
+                let (reachable, authenticated) = connection_stage(&e);
                 return Ok(WebDAVConnectionResult {
                     success: false,
                     message: format!("Protocol detection failed: {}", e),
                     server_version: None,
                     server_type: None,
+                    detected_server_type: None,
+                    reachable,
+                    authenticated,
+                    certificate_error: is_certificate_rejected(&e),
+                    client_certificate_error: is_client_certificate_rejected(&e),
                 });
             }
         };
 
+        // Detect the server type even if one is configured, so a wrong choice can be pointed out
+        let detected_server_type = match self.detect_server_type().await {
+            Ok(server_type) => Some(server_type),
+            Err(e) => {
+                warn!("Server type detection failed: {}", e);
+                None
+            }
+        };
+
         // Test basic connectivity with OPTIONS request using detected protocol
         match self.test_options_request().await {
-            Ok((server_version, server_type)) => {
+            Ok(server_version) => {
                 let effective_url = self.get_effective_server_url();
                 info!("✅ WebDAV connection successful using {} ({})", working_protocol.to_uppercase(), effective_url);
                 Ok(WebDAVConnectionResult {
                     success: true,
                     message: format!("Connection successful using {}", working_protocol.to_uppercase()),
                     server_version,
-                    server_type,
+                    server_type: self.get_server_type(),
+                    detected_server_type,
+                    reachable: true,
+                    authenticated: true,
+                    certificate_error: false,
+                    client_certificate_error: false,
                 })
             }
             Err(e) => {
                 error!("❌ WebDAV connection failed: {}", e);
+                let (reachable, authenticated) = connection_stage(&e);
                 Ok(WebDAVConnectionResult {
                     success: false,
                     message: format!("Connection failed: {}", e),
                     server_version: None,
                     server_type: None,
+                    detected_server_type,
+                    reachable,
+                    authenticated,
+                    certificate_error: is_certificate_rejected(&e),
+                    client_certificate_error: is_client_certificate_rejected(&e),
                 })
             }
         }
@@ -404,14 +505,15 @@ impl WebDAVService {
             file_extensions: vec![],
             timeout_seconds: 30,
             server_type: test_config.server_type.clone(),
+            tls: test_config.tls.clone(),
         };
 
         let service = Self::new(config)?;
         service.test_connection().await
     }
 
-    /// Performs OPTIONS request to test basic connectivity
-    async fn test_options_request(&self) -> Result<(Option<String>, Option<String>)> {
+    /// Performs OPTIONS request to test basic connectivity; returns the `Server` header
+    async fn test_options_request(&self) -> Result<Option<String>> {
         // Create a temporary config with the effective server URL for WebDAV operations
         let effective_server_url = self.get_effective_server_url();
         let temp_config = WebDAVConfig {
@@ -421,7 +523,8 @@ impl WebDAVService {
             watch_folders: self.config.watch_folders.clone(),
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
-            server_type: self.config.server_type.clone(),
+            server_type: self.get_server_type(),
+            tls: self.config.tls.clone(),
         };
         let webdav_url = temp_config.webdav_url();
         
@@ -429,14 +532,11 @@ impl WebDAVService {
             .request(Method::OPTIONS, &webdav_url)
             .basic_auth(&self.config.username, Some(&self.config.password))
             .send()
-            .await?;
+            .await
+            .map_err(|e| request_error(&webdav_url, e))?;
 
         if !response.status().is_success() {
-            return Err(anyhow!(
-                "OPTIONS request failed with status: {} - {}",
-                response.status(),
-                response.text().await.unwrap_or_default()
-            ));
+            return Err(options_status_error("OPTIONS request", response).await);
         }
 
         // Extract server information from headers
@@ -446,74 +546,96 @@ impl WebDAVService {
             .and_then(|v| v.to_str().ok())
             .map(|s| s.to_string());
 
-        let server_type = self.detect_server_type(&response, &server_version).await;
-
-        Ok((server_version, server_type))
+        Ok(server_version)
     }
 
-    /// Detects the WebDAV server type based on response headers and capabilities
-    async fn detect_server_type(
-        &self,
-        response: &reqwest::Response,
-        server_version: &Option<String>,
-    ) -> Option<String> {
-        // Check server header first
-        if let Some(ref server) = server_version {
-            let server_lower = server.to_lowercase();
-            if server_lower.contains("nextcloud") {
-                return Some("nextcloud".to_string());
-            }
-            if server_lower.contains("owncloud") {
-                return Some("owncloud".to_string());
-            }
-            if server_lower.contains("apache") || server_lower.contains("nginx") {
-                // Could be generic WebDAV
+    /// Guesses the server type ("nextcloud", "owncloud", "seafile" or "generic") by probing the
+    /// server: Nextcloud/ownCloud answer `status.php`, Seafile answers `api2/server-info/`, and
+    /// otherwise the `Server` and `X-...` headers of an OPTIONS request are checked. The result is
+    /// cached and used for URL construction when the config leaves `server_type` unset.
+    pub async fn detect_server_type(&self) -> Result<String> {
+        let root_url = server_root_url(&self.get_effective_server_url());
+        info!("🔍 Detecting WebDAV server type at: {}", root_url);
+
+        let mut reached_server = false;
+        let mut last_error = None;
+        let mut detected = None;
+
+        for probe_url in [format!("{}/status.php", root_url), format!("{}/api2/server-info/", root_url)] {
+            match self.client.get(&probe_url).basic_auth(&self.config.username, Some(&self.config.password)).send().await {
+                Ok(response) => {
+                    reached_server = true;
+                    if let Some(server_type) = server_type_from_headers(response.headers()) {
+                        detected = Some(server_type);
+                        break;
+                    }
+                    if !response.status().is_success() {
+                        continue;
+                    }
+                    if let Ok(body) = response.json::<serde_json::Value>().await {
+                        if let Some(server_type) = server_type_from_status(&body).or_else(|| server_type_from_server_info(&body)) {
+                            detected = Some(server_type);
+                            break;
+                        }
+                    }
+                }
+                Err(e) => last_error = Some(e),
             }
         }
 
-        // Check DAV capabilities
-        if let Some(dav_header) = response.headers().get("dav") {
-            if let Ok(dav_str) = dav_header.to_str() {
-                debug!("DAV capabilities: {}", dav_str);
-                // Different servers expose different DAV levels
-                if dav_str.contains("3") {
-                    return Some("webdav_level_3".to_string());
+        if detected.is_none() {
+            match self.client
+                .request(Method::OPTIONS, &root_url)
+                .basic_auth(&self.config.username, Some(&self.config.password))
+                .send()
+                .await
+            {
+                Ok(response) => {
+                    reached_server = true;
+                    detected = server_type_from_headers(response.headers());
                 }
+                Err(e) => last_error = Some(e),
             }
         }
 
-        // Test for Nextcloud/ownCloud specific endpoints
-        if self.test_nextcloud_capabilities().await.is_ok() {
-            return Some("nextcloud".to_string());
+        if !reached_server {
+            return Err(anyhow!("Server type detection could not reach {}: {}",
+                root_url, last_error.map(|e| e.to_string()).unwrap_or_default()));
         }
 
-        Some("generic".to_string())
+        let server_type = detected.unwrap_or("generic").to_string();
+        info!("✅ Detected WebDAV server type: {}", server_type);
+        if let Ok(mut cached) = self.detected_server_type.write() {
+            *cached = Some(server_type.clone());
+        }
+        Ok(server_type)
     }
 
-    /// Tests for Nextcloud-specific capabilities
-    async fn test_nextcloud_capabilities(&self) -> Result<()> {
-        let effective_server_url = self.get_effective_server_url();
-        let capabilities_url = format!("{}/ocs/v1.php/cloud/capabilities", 
-            effective_server_url.trim_end_matches('/'));
-
-        let response = self.client
-            .get(&capabilities_url)
-            .basic_auth(&self.config.username, Some(&self.config.password))
-            .header("OCS-APIRequest", "true")
-            .send()
-            .await?;
-
-        if response.status().is_success() {
-            debug!("Nextcloud capabilities endpoint accessible");
-            Ok(())
-        } else {
-            Err(anyhow!("Nextcloud capabilities not accessible"))
+    /// Runs server type detection once if the config leaves the type unset; detection
+    /// failures leave URLs in the generic form
+    pub async fn ensure_server_type(&self) -> Option<String> {
+        if let Some(server_type) = self.get_server_type() {
+            return Some(server_type);
+        }
+        match self.detect_server_type().await {
+            Ok(server_type) => Some(server_type),
+            Err(e) => {
+                warn!("Could not detect WebDAV server type, using generic URLs: {}", e);
+                None
+            }
         }
     }
 
+    /// The configured server type, or the detected one if none is configured
+    pub fn get_server_type(&self) -> Option<String> {
+        self.config.server_type.clone().or_else(|| {
+            self.detected_server_type.read().ok().and_then(|detected| detected.clone())
+        })
+    }
+
     /// Tests PROPFIND request on root directory
     pub async fn test_propfind(&self, path: &str) -> Result<()> {
-        let url = self.get_url_for_path(path);
+        let url = self.get_directory_url(path);
         
         debug!("🧪 Testing PROPFIND for path '{}' at URL '{}'", path, url);
         
@@ -541,15 +663,7 @@ impl WebDAVService {
                 </D:prop>
             </D:propfind>"#;
 
-        let response = self.authenticated_request(
-            Method::from_bytes(b"PROPFIND")?,
-            &url,
-            Some(propfind_body.to_string()),
-            Some(vec![
-                ("Depth", "1"),
-                ("Content-Type", "application/xml"),
-            ]),
-        ).await?;
+        let response = self.propfind_directory(&url, propfind_body, "1").await?;
 
         if response.status().as_u16() == 207 {
             debug!("✅ PROPFIND successful for path: {}", path);
@@ -628,7 +742,11 @@ impl WebDAVService {
     // HTTP Request Methods with Simple Retry Logic
     // ============================================================================
 
-    /// Performs authenticated request with simple retry logic (simplified from complex error recovery)
+    /// Performs authenticated request with simple retry logic (simplified from complex error recovery).
+    ///
+    /// Retries wait a random delay below the exponential backoff ("full jitter"). Failures that
+    /// would be retried count toward the server's circuit breaker; while it is open, requests
+    /// fail immediately instead of reaching the server.
     pub async fn authenticated_request(
         &self,
         method: Method,
@@ -658,6 +776,13 @@ impl WebDAVService {
         }
 
         loop {
+            if let Err(remaining) = self.circuit_breaker.try_acquire() {
+                return Err(anyhow!(
+                    "WebDAV server {} is temporarily unavailable after repeated failures, retrying in {}s",
+                    self.config.server_url, remaining.as_secs().max(1)
+                ));
+            }
+
             let mut request = self.client
                 .request(method.clone(), url)
                 .basic_auth(&self.config.username, Some(&self.config.password));
@@ -677,6 +802,13 @@ impl WebDAVService {
                 Ok(response) => {
                     let status = response.status();
                     debug!("📥 HTTP Response: {} {}", status.as_u16(), status.canonical_reason().unwrap_or(""));
+
+                    if is_retryable_status(status) {
+                        self.record_circuit_failure();
+                    } else {
+                        // Anything else, client errors included, shows the server is answering
+                        self.circuit_breaker.record_success();
+                    }
                     
                     // Log response headers for debugging
                     for (key, value) in response.headers() {
@@ -693,7 +825,7 @@ impl WebDAVService {
                     }
 
                     // Handle rate limiting
-                    if status.as_u16() == 429 {
+                    if is_rate_limit_status(status) {
                         warn!("Rate limited, backing off for {}ms", self.retry_config.rate_limit_backoff_ms);
                         sleep(Duration::from_millis(self.retry_config.rate_limit_backoff_ms)).await;
                         continue;
@@ -739,10 +871,11 @@ impl WebDAVService {
 
                     // Handle server errors (retry)
                     if status.is_server_error() && attempt < self.retry_config.max_retries {
+                        let wait = full_jitter_delay(delay);
                         warn!("Server error {}, retrying in {}ms (attempt {}/{})", 
-                            status, delay, attempt + 1, self.retry_config.max_retries);
+                            status, wait, attempt + 1, self.retry_config.max_retries);
                         
-                        sleep(Duration::from_millis(delay)).await;
+                        sleep(Duration::from_millis(wait)).await;
                         delay = std::cmp::min(
                             (delay as f64 * self.retry_config.backoff_multiplier) as u64,
                             self.retry_config.max_delay_ms
@@ -755,11 +888,14 @@ impl WebDAVService {
                         response.text().await.unwrap_or_default()));
                 }
                 Err(e) => {
+                    self.record_circuit_failure();
+
                     if attempt < self.retry_config.max_retries {
+                        let wait = full_jitter_delay(delay);
                         warn!("Request error: {}, retrying in {}ms (attempt {}/{})", 
-                            e, delay, attempt + 1, self.retry_config.max_retries);
+                            e, wait, attempt + 1, self.retry_config.max_retries);
                         
-                        sleep(Duration::from_millis(delay)).await;
+                        sleep(Duration::from_millis(wait)).await;
                         delay = std::cmp::min(
                             (delay as f64 * self.retry_config.backoff_multiplier) as u64,
                             self.retry_config.max_delay_ms
@@ -775,11 +911,41 @@ impl WebDAVService {
         }
     }
 
+    /// Sends a PROPFIND to a directory URL. Some servers only answer collection URLs with a
+    /// trailing slash and others only without one, so a 404 or 405 is retried once with the
+    /// opposite convention before giving up.
+    async fn propfind_directory(&self, url: &str, propfind_body: &str, depth: &str) -> Result<reqwest::Response> {
+        let headers = vec![("Depth", depth), ("Content-Type", "application/xml")];
+        match self.authenticated_request(Method::from_bytes(b"PROPFIND")?, url, Some(propfind_body.to_string()), Some(headers.clone())).await {
+            Err(e) if is_wrong_collection_url_error(&e) => {
+                let alternate_url = toggle_trailing_slash(url);
+                debug!("🔁 PROPFIND for {} failed ({}), retrying as {}", url, e, alternate_url);
+                self.authenticated_request(Method::from_bytes(b"PROPFIND")?, &alternate_url, Some(propfind_body.to_string()), Some(headers)).await
+            }
+            result => result,
+        }
+    }
+
+    fn record_circuit_failure(&self) {
+        if self.circuit_breaker.record_failure() {
+            warn!("Circuit breaker opened for {} after {} consecutive failures, failing fast for {}ms",
+                self.config.server_url,
+                self.circuit_breaker.status().consecutive_failures,
+                self.retry_config.circuit_cooldown_ms);
+        }
+    }
+
+    /// State of the server's circuit breaker, for health and validation reporting
+    pub fn circuit_breaker_status(&self) -> CircuitBreakerStatus {
+        self.circuit_breaker.status()
+    }
+
     // ============================================================================
     // URL Management Helper Methods (Previously separate module)
     // ============================================================================
 
-    /// Gets the WebDAV URL for a specific path
+    /// Gets the WebDAV URL for a file path. The URL never ends in a slash, whichever way the
+    /// path was written; directories should use [`Self::get_directory_url`].
     pub fn get_url_for_path(&self, path: &str) -> String {
         // Create a temporary config with the effective server URL
         let effective_server_url = self.get_effective_server_url();
@@ -790,10 +956,11 @@ impl WebDAVService {
             watch_folders: self.config.watch_folders.clone(),
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
-            server_type: self.config.server_type.clone(),
+            server_type: self.get_server_type(),
+            tls: self.config.tls.clone(),
         };
         let base_url = temp_config.webdav_url();
-        let clean_path = path.trim_start_matches('/');
+        let clean_path = path.trim_matches('/');
         
         let final_url = if clean_path.is_empty() {
             base_url.clone()
@@ -808,18 +975,24 @@ impl WebDAVService {
         debug!("   Clean path: '{}'", clean_path);
         debug!("   Base WebDAV URL: '{}'", base_url);
         debug!("   Final URL: '{}'", final_url);
-        debug!("   Server type: {:?}", self.config.server_type);
+        debug!("   Server type: {:?}", self.get_server_type());
         debug!("   Server base URL: '{}'", self.config.server_url);
         
         final_url
     }
 
+    /// Gets the WebDAV URL for a directory PROPFIND, which always ends in exactly one slash.
+    /// Servers that only answer the slash-less form are handled by [`Self::propfind_directory`].
+    pub fn get_directory_url(&self, path: &str) -> String {
+        format!("{}/", self.get_url_for_path(path).trim_end_matches('/'))
+    }
+
     /// Convert full WebDAV href (from XML response) to relative path
     /// 
     /// Input:  "/remote.php/dav/files/username/Photos/image.jpg"
     /// Output: "/Photos/image.jpg"
     pub fn href_to_relative_path(&self, href: &str) -> String {
-        match self.config.server_type.as_deref() {
+        match self.get_server_type().as_deref() {
             Some("nextcloud") => {
                 let prefix = format!("/remote.php/dav/files/{}", self.config.username);
                 if href.starts_with(&prefix) {
@@ -837,8 +1010,13 @@ impl WebDAVService {
                     href.to_string()
                 }
             }
+            Some("seafile") | Some("apache") => {
+                self.strip_webdav_root_path(href).unwrap_or_else(|| href.to_string())
+            }
             Some("generic") => {
-                if href.starts_with("/webdav") {
+                if let Some(relative) = self.strip_webdav_root_path(href) {
+                    relative
+                } else if href.starts_with("/webdav") {
                     let relative = &href[7..]; // Remove "/webdav"
                     if relative.is_empty() { "/" } else { relative }.to_string()
                 } else {
@@ -849,6 +1027,27 @@ impl WebDAVService {
         }
     }
 
+    /// Removes the path of the configured WebDAV root from an href, if the href lies below it
+    fn strip_webdav_root_path(&self, href: &str) -> Option<String> {
+        let temp_config = WebDAVConfig {
+            server_url: self.get_effective_server_url(),
+            server_type: self.get_server_type(),
+            ..self.config.clone()
+        };
+        let root_path = temp_config.webdav_root_path();
+        if root_path.is_empty() {
+            return None;
+        }
+        let relative = href.strip_prefix(root_path.as_str())?;
+        if relative.is_empty() {
+            Some("/".to_string())
+        } else if relative.starts_with('/') {
+            Some(relative.to_string())
+        } else {
+            None
+        }
+    }
+
     /// Convert file paths to the proper URL format for the server
     pub fn path_to_url(&self, relative_path: &str) -> String {
         let clean_path = relative_path.trim_start_matches('/');
@@ -861,7 +1060,8 @@ impl WebDAVService {
             watch_folders: self.config.watch_folders.clone(),
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
-            server_type: self.config.server_type.clone(),
+            server_type: self.get_server_type(),
+            tls: self.config.tls.clone(),
         };
         let base_url = temp_config.webdav_url();
         
@@ -875,7 +1075,7 @@ impl WebDAVService {
     /// Converts a full WebDAV path to a relative path by removing server-specific prefixes
     pub fn convert_to_relative_path(&self, full_webdav_path: &str) -> String {
         // For Nextcloud/ownCloud, remove the server-specific prefixes
-        if let Some(server_type) = &self.config.server_type {
+        if let Some(server_type) = &self.get_server_type() {
             if server_type == "nextcloud" {
                 let username = &self.config.username;
                 let prefix = format!("/remote.php/dav/files/{}", username);
@@ -890,8 +1090,15 @@ impl WebDAVService {
                     let relative = &full_webdav_path[18..]; // Remove "/remote.php/webdav"
                     return if relative.is_empty() { "/" } else { relative }.to_string();
                 }
+            } else if server_type == "seafile" || server_type == "apache" {
+                if let Some(relative) = self.strip_webdav_root_path(full_webdav_path) {
+                    return relative;
+                }
             } else if server_type == "generic" {
-                // For generic servers, remove the /webdav prefix if present
+                // For generic servers, remove the root path of the server URL, or else the /webdav prefix
+                if let Some(relative) = self.strip_webdav_root_path(full_webdav_path) {
+                    return relative;
+                }
                 if full_webdav_path.starts_with("/webdav") {
                     let relative = &full_webdav_path[7..]; // Remove "/webdav"
                     return if relative.is_empty() { "/" } else { relative }.to_string();
@@ -947,7 +1154,7 @@ impl WebDAVService {
 
     /// Discovers files in a single directory (non-recursive)
     async fn discover_files_single_directory(&self, directory_path: &str) -> Result<Vec<FileIngestionInfo>> {
-        let url = self.get_url_for_path(directory_path);
+        let url = self.get_directory_url(directory_path);
         
         let propfind_body = r#"<?xml version="1.0" encoding="utf-8"?>
             <D:propfind xmlns:D="DAV:">
@@ -961,15 +1168,7 @@ impl WebDAVService {
                 </D:prop>
             </D:propfind>"#;
 
-        let response = self.authenticated_request(
-            Method::from_bytes(b"PROPFIND")?,
-            &url,
-            Some(propfind_body.to_string()),
-            Some(vec![
-                ("Depth", "1"),
-                ("Content-Type", "application/xml"),
-            ]),
-        ).await?;
+        let response = self.propfind_directory(&url, propfind_body, "1").await?;
 
         let body = response.text().await?;
         let files = parse_propfind_response(&body)?;
@@ -1055,7 +1254,7 @@ impl WebDAVService {
     /// Discovers both files and directories in a single directory
     async fn discover_files_and_directories_single(&self, directory_path: &str) -> Result<WebDAVDiscoveryResult> {
         // Try the primary URL first, then fallback URLs if we get a 405 error
-        match self.discover_files_and_directories_single_with_url(directory_path, &self.get_url_for_path(directory_path)).await {
+        match self.discover_files_and_directories_single_with_url(directory_path, &self.get_directory_url(directory_path)).await {
             Ok(result) => Ok(result),
             Err(e) => {
                 // Check if this is a 405 Method Not Allowed error
@@ -1124,15 +1323,7 @@ impl WebDAVService {
         debug!("📤 Sending PROPFIND request to URL: {}", url);
         debug!("📋 PROPFIND body length: {} bytes", propfind_body.len());
 
-        let response = self.authenticated_request(
-            Method::from_bytes(b"PROPFIND")?,
-            url,
-            Some(propfind_body.to_string()),
-            Some(vec![
-                ("Depth", "1"),
-                ("Content-Type", "application/xml"),
-            ]),
-        ).await.map_err(|e| {
+        let response = self.propfind_directory(url, propfind_body, "1").await.map_err(|e| {
             error!("❌ PROPFIND request failed for directory '{}' at URL '{}': {}", 
                    directory_path, url, e);
             e
@@ -1149,9 +1340,9 @@ impl WebDAVService {
         let mut directories = Vec::new();
         
         for item in processed_items {
-            // Skip the directory itself (handle both with and without trailing slash)
-            let normalized_item_path = item.relative_path.trim_end_matches('/');
-            let normalized_directory_path = directory_path.trim_end_matches('/');
+            // Skip the directory itself (handle both with and without leading and trailing slashes)
+            let normalized_item_path = item.relative_path.trim_matches('/');
+            let normalized_directory_path = directory_path.trim_matches('/');
             
             if normalized_item_path == normalized_directory_path {
                 continue; // Skip the directory itself
@@ -1165,13 +1356,14 @@ impl WebDAVService {
         }
 
         debug!("Found {} files and {} directories in: {}", files.len(), directories.len(), directory_path);
-        Ok(WebDAVDiscoveryResult { files, directories })
+        Ok(WebDAVDiscoveryResult { files, directories, scanned_directories: vec![directory_path.to_string()] })
     }
 
     /// Discovers files and directories recursively
     async fn discover_files_and_directories_recursive(&self, directory_path: &str) -> Result<WebDAVDiscoveryResult> {
         let mut all_files = Vec::new();
         let mut all_directories = Vec::new();
+        let mut listed_directories = Vec::new();
         let mut directories_to_scan = vec![directory_path.to_string()];
         let mut scanned_directories = std::collections::HashSet::new();
         let semaphore = Arc::new(Semaphore::new(self.concurrency_config.max_concurrent_scans));
@@ -1214,6 +1406,7 @@ impl WebDAVService {
                                scanned_dir, discovery_result.files.len(), discovery_result.directories.len());
                         
                         all_files.extend(discovery_result.files);
+                        listed_directories.extend(discovery_result.scanned_directories);
                         
                         // Add directories to our results and to the scan queue
                         for dir in discovery_result.directories {
@@ -1241,29 +1434,24 @@ impl WebDAVService {
         info!("Recursive scan completed. Found {} files and {} directories", all_files.len(), all_directories.len());
         Ok(WebDAVDiscoveryResult { 
             files: all_files, 
-            directories: all_directories 
+            directories: all_directories,
+            scanned_directories: listed_directories,
         })
     }
 
-    /// Estimates crawl time and resource requirements
-    pub async fn estimate_crawl(&self) -> Result<WebDAVCrawlEstimate> {
+    /// Estimates crawl time and resource requirements. The time of a folder is its scan
+    /// time plus the OCR time `model` projects for each supported file in it.
+    pub async fn estimate_crawl(&self, model: &ProcessingTimeModel) -> Result<WebDAVCrawlEstimate> {
         info!("📊 Estimating WebDAV crawl requirements");
         
-        let start_time = Instant::now();
-        let mut total_directories = 0;
-        let mut total_files = 0;
-        let mut sample_scan_time = Duration::from_millis(0);
+        let mut folders = Vec::new();
         
         // Sample the first few watch folders to estimate
-        for (index, watch_folder) in self.config.watch_folders.iter().enumerate() {
-            if index >= 3 { break; } // Only sample first 3 folders
-            
+        for watch_folder in self.config.watch_folders.iter().take(3) {
             let scan_start = Instant::now();
             match self.discover_files_and_directories(watch_folder, false).await {
                 Ok(result) => {
-                    total_directories += result.directories.len();
-                    total_files += result.files.len();
-                    sample_scan_time += scan_start.elapsed();
+                    folders.push(self.estimate_folder(watch_folder, &result.files, scan_start.elapsed(), model));
                 }
                 Err(e) => {
                     warn!("Failed to scan folder '{}' for estimation: {}", watch_folder, e);
@@ -1271,26 +1459,48 @@ impl WebDAVService {
             }
         }
         
-        // Simple estimation based on sample
-        let avg_scan_time_per_folder = if total_directories > 0 {
-            sample_scan_time.as_millis() as f64 / total_directories as f64
+        // Folders beyond the sample are assumed to look like the average sampled folder
+        let scale = if folders.is_empty() {
+            0.0
         } else {
-            100.0 // Default 100ms per folder
+            self.config.watch_folders.len() as f64 / folders.len() as f64
         };
-        
-        let estimated_total_scan_time = Duration::from_millis(
-            (avg_scan_time_per_folder * total_directories as f64 * self.config.watch_folders.len() as f64) as u64
-        );
+        let total_files: i64 = folders.iter().map(|f| f.total_files).sum();
+        let total_supported_files: i64 = folders.iter().map(|f| f.supported_files).sum();
+        let total_hours: f64 = folders.iter().map(|f| f.estimated_time_hours as f64).sum();
+        let total_size_mb: f64 = folders.iter().map(|f| f.total_size_mb).sum();
         
         Ok(WebDAVCrawlEstimate {
-            folders: vec![], // Simplified: not building detailed folder info for basic estimation
-            total_files: (total_files * self.config.watch_folders.len()) as i64,
-            total_supported_files: (total_files * self.config.watch_folders.len()) as i64, // Assume all files are supported
-            total_estimated_time_hours: estimated_total_scan_time.as_secs_f32() / 3600.0,
-            total_size_mb: (total_files * 2) as f64, // Rough estimate in MB
+            total_files: (total_files as f64 * scale).round() as i64,
+            total_supported_files: (total_supported_files as f64 * scale).round() as i64,
+            total_estimated_time_hours: (total_hours * scale) as f32,
+            total_size_mb: total_size_mb * scale,
+            folders,
+            processing_model: model.clone(),
         })
     }
 
+    /// Estimate of one scanned folder, weighting each supported file by its type and size
+    fn estimate_folder(&self, path: &str, files: &[FileIngestionInfo], scan_time: Duration, model: &ProcessingTimeModel) -> WebDAVFolderInfo {
+        let supported: Vec<&FileIngestionInfo> = files
+            .iter()
+            .filter(|file| self.config.is_supported_extension(&file.name))
+            .collect();
+        let processing_ms: f64 = supported
+            .iter()
+            .map(|file| model.estimate_ms(&file.mime_type, file.size))
+            .sum();
+        let total_bytes: i64 = files.iter().map(|file| file.size.max(0)).sum();
+        
+        WebDAVFolderInfo {
+            path: path.to_string(),
+            total_files: files.len() as i64,
+            supported_files: supported.len() as i64,
+            estimated_time_hours: ((scan_time.as_millis() as f64 + processing_ms) / 3_600_000.0) as f32,
+            total_size_mb: total_bytes as f64 / (1024.0 * 1024.0),
+        }
+    }
+
     /// Deduplicates files across multiple folders
     pub fn deduplicate_files(&self, files: Vec<FileIngestionInfo>) -> Vec<FileIngestionInfo> {
         let mut seen = HashSet::new();
@@ -1364,25 +1574,10 @@ impl WebDAVService {
         let relative_path = self.convert_to_relative_path(file_path);
         let url = self.get_url_for_path(&relative_path);
         
-        let response = self.authenticated_request(
-            reqwest::Method::GET,
-            &url,
-            None,
-            None,
-        ).await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Failed to download file '{}': HTTP {}",
-                file_path,
-                response.status()
-            ));
-        }
-
-        let content = response.bytes().await?;
+        let (content, _) = self.download_with_resume(&url, file_path).await?;
         debug!("✅ Downloaded {} bytes for file: {}", content.len(), file_path);
         
-        Ok(content.to_vec())
+        Ok(content)
     }
 
     /// Downloads a file from WebDAV server using FileIngestionInfo
@@ -1395,25 +1590,121 @@ impl WebDAVService {
         let relative_path = &file_info.relative_path;
         let url = self.get_url_for_path(&relative_path);
         
-        let response = self.authenticated_request(
-            reqwest::Method::GET,
-            &url,
-            None,
-            None,
-        ).await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Failed to download file '{}': HTTP {}",
-                file_info.relative_path,
-                response.status()
-            ));
-        }
-
-        let content = response.bytes().await?;
+        let (content, _) = self.download_with_resume(&url, &file_info.relative_path).await?;
         debug!("✅ Downloaded {} bytes for file: {}", content.len(), file_info.relative_path);
         
-        Ok(content.to_vec())
+        Ok(content)
+    }
+
+    /// Fetches the body at `url`, resuming with HTTP Range requests if the transfer drops mid-stream.
+    ///
+    /// On a retryable body error the GET is re-issued with `Range: bytes=<received>-` and the
+    /// new bytes are appended. If the server ignores the Range header (200 instead of 206), or the
+    /// ETag changed between attempts, the buffer is discarded and the file is downloaded from scratch.
+    ///
+    /// Returns the content and the server-provided content type (without parameters such as charset).
+    async fn download_with_resume(&self, url: &str, label: &str) -> Result<(Vec<u8>, Option<String>)> {
+        let mut buffer: Vec<u8> = Vec::new();
+        let mut etag: Option<String> = None;
+        let mut content_type: Option<String> = None;
+        let mut attempt = 0;
+        let mut delay = self.retry_config.initial_delay_ms;
+
+        loop {
+            let resume_from = buffer.len();
+            let range_header = format!("bytes={}-", resume_from);
+            let mut headers = Vec::new();
+            if resume_from > 0 {
+                headers.push(("Range", range_header.as_str()));
+                // If-Range only accepts strong validators; with it the server sends the full body if the file changed
+                if let Some(tag) = etag.as_deref().filter(|tag| !tag.starts_with("W/")) {
+                    headers.push(("If-Range", tag));
+                }
+            }
+
+            let mut response = self.authenticated_request(
+                reqwest::Method::GET,
+                url,
+                None,
+                if headers.is_empty() { None } else { Some(headers) },
+            ).await?;
+
+            let status = response.status();
+            if !status.is_success() {
+                return Err(anyhow!("Failed to download file '{}': HTTP {}", label, status));
+            }
+
+            let response_etag = response
+                .headers()
+                .get("etag")
+                .and_then(|header| header.to_str().ok())
+                .map(|s| s.to_string());
+
+            if resume_from > 0 {
+                let content_range_start = response
+                    .headers()
+                    .get("content-range")
+                    .and_then(|header| header.to_str().ok())
+                    .and_then(parse_content_range_start);
+                let etag_changed = matches!((&etag, &response_etag), (Some(old), Some(new)) if old != new);
+
+                if status.as_u16() != 206 {
+                    warn!("Server ignored Range request for '{}' (HTTP {}), restarting download", label, status);
+                    buffer.clear();
+                } else if etag_changed {
+                    // The partial body belongs to a different version of the file; start over
+                    warn!("ETag changed while resuming '{}', restarting download", label);
+                    buffer.clear();
+                    etag = None;
+                    continue;
+                } else if content_range_start != Some(resume_from as u64) {
+                    warn!("Unexpected Content-Range for '{}' (expected start {}), restarting download", label, resume_from);
+                    buffer.clear();
+                    continue;
+                } else {
+                    debug!("Resuming download of '{}' from byte {}", label, resume_from);
+                }
+            }
+
+            if buffer.is_empty() {
+                etag = response_etag;
+                content_type = response
+                    .headers()
+                    .get("content-type")
+                    .and_then(|header| header.to_str().ok())
+                    .map(|s| s.split(';').next().unwrap_or(s).trim().to_string()); // Remove charset info
+            }
+
+            let stream_error = loop {
+                match response.chunk().await {
+                    Ok(Some(chunk)) => buffer.extend_from_slice(&chunk),
+                    Ok(None) => break None,
+                    Err(e) => break Some(e),
+                }
+            };
+
+            let Some(e) = stream_error else {
+                return Ok((buffer, content_type));
+            };
+
+            if attempt >= self.retry_config.max_retries {
+                return Err(anyhow!(
+                    "Download of '{}' failed after {} attempts ({} bytes received): {}",
+                    label, attempt + 1, buffer.len(), e
+                ));
+            }
+
+            let wait = full_jitter_delay(delay);
+            warn!("Download of '{}' interrupted after {} bytes: {}, resuming in {}ms (attempt {}/{})",
+                label, buffer.len(), e, wait, attempt + 1, self.retry_config.max_retries);
+
+            sleep(Duration::from_millis(wait)).await;
+            delay = std::cmp::min(
+                (delay as f64 * self.retry_config.backoff_multiplier) as u64,
+                self.retry_config.max_delay_ms
+            );
+            attempt += 1;
+        }
     }
 
     /// Downloads multiple files concurrently
@@ -1460,29 +1751,7 @@ impl WebDAVService {
         let relative_path = &file_info.relative_path;
         let url = self.get_url_for_path(&relative_path);
         
-        let response = self.authenticated_request(
-            reqwest::Method::GET,
-            &url,
-            None,
-            None,
-        ).await?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!(
-                "Failed to download file '{}': HTTP {}",
-                file_info.relative_path,
-                response.status()
-            ));
-        }
-
-        // Get server-provided content type from response headers
-        let server_content_type = response
-            .headers()
-            .get("content-type")
-            .and_then(|header| header.to_str().ok())
-            .map(|s| s.split(';').next().unwrap_or(s).trim().to_string()); // Remove charset info and convert to owned
-
-        let content = response.bytes().await?;
+        let (content, server_content_type) = self.download_with_resume(&url, &file_info.relative_path).await?;
         debug!("✅ Downloaded {} bytes for file: {}", content.len(), file_info.relative_path);
         
         // Perform content-based MIME type detection
@@ -1520,7 +1789,7 @@ impl WebDAVService {
         };
 
         Ok(WebDAVDownloadResult {
-            content: content.to_vec(),
+            content,
             file_info: updated_file_info,
             mime_detection: mime_detection_result,
             mime_type_updated,
@@ -1611,6 +1880,242 @@ impl WebDAVService {
         }
     }
 
+    /// Deletes a file on the server. A file that is already gone counts as deleted.
+    pub async fn delete_file(&self, file_path: &str) -> Result<()> {
+        let relative_path = self.convert_to_relative_path(file_path);
+        let url = self.get_url_for_path(&relative_path);
+
+        let response = self.client
+            .delete(&url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .send()
+            .await?;
+
+        let status = response.status();
+        match status.as_u16() {
+            404 | 410 => Ok(()),
+            _ if status.is_success() => Ok(()),
+            _ => Err(anyhow!("Could not delete '{}': HTTP {}", file_path, status)),
+        }
+    }
+
+    /// Writes the local file at `local_path` to `file_path`, unless a file is already there:
+    /// then nothing is written and `false` is returned. `file_path` is a plain path such as
+    /// `/Inbox/Tax return #2.pdf`; it is percent-encoded for the request. The folder it goes
+    /// into must exist. Files larger than the upload chunk size go to Nextcloud and ownCloud
+    /// in chunks, so they don't hit the request size limits of the server or a proxy before it.
+    pub async fn upload_file(&self, file_path: &str, local_path: &std::path::Path) -> Result<bool> {
+        let relative_path = encode_path(&self.convert_to_relative_path(file_path));
+        let size = tokio::fs::metadata(local_path).await?.len();
+
+        let chunked = matches!(self.get_server_type().as_deref(), Some("nextcloud") | Some("owncloud"));
+        if chunked && size > self.upload_chunk_size {
+            return self.upload_file_in_chunks(file_path, &relative_path, local_path, size).await;
+        }
+
+        let url = self.get_url_for_path(&relative_path);
+        let file = tokio::fs::File::open(local_path).await?;
+        let response = self.client
+            .put(&url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("If-None-Match", "*")
+            .header(reqwest::header::CONTENT_LENGTH, size)
+            .body(reqwest::Body::wrap_stream(tokio_util::io::ReaderStream::new(file)))
+            .send()
+            .await?;
+
+        let status = response.status();
+        match status.as_u16() {
+            412 => Ok(false),
+            _ if status.is_success() => Ok(true),
+            _ => Err(anyhow!("Could not upload '{}': HTTP {}", file_path, status)),
+        }
+    }
+
+    /// Uploads through the chunking API of Nextcloud and ownCloud: creates an upload session,
+    /// PUTs the chunks into it and MOVEs its `.file` to the destination, which makes the
+    /// server assemble them. A session that fails part way, or whose destination turns out to
+    /// be taken, is deleted with its chunks. `relative_path` is already percent-encoded.
+    async fn upload_file_in_chunks(&self, file_path: &str, relative_path: &str, local_path: &std::path::Path, size: u64) -> Result<bool> {
+        let dav_root = format!("{}/remote.php/dav", server_root_url(&self.get_effective_server_url()));
+        let username = encode_path(&self.config.username);
+        let session_url = format!("{}/uploads/{}/readur-{}", dav_root, username, uuid::Uuid::new_v4().simple());
+        let destination = format!("{}/files/{}/{}", dav_root, username, relative_path.trim_matches('/'));
+        info!("Uploading '{}' to {} in {} chunks", file_path, destination, size.div_ceil(self.upload_chunk_size));
+
+        let result = self.send_chunks(&session_url, &destination, local_path, size).await;
+        if !matches!(result, Ok(true)) {
+            match &result {
+                Err(e) => warn!("Chunked upload of '{}' failed, removing upload session: {}", file_path, e),
+                _ => info!("'{}' already exists, removing upload session", file_path),
+            }
+            match self.client
+                .delete(&session_url)
+                .basic_auth(&self.config.username, Some(&self.config.password))
+                .send()
+                .await
+            {
+                Ok(response) if !response.status().is_success() && response.status().as_u16() != 404 => {
+                    warn!("Could not remove upload session {}: HTTP {}", session_url, response.status());
+                }
+                Err(cleanup_error) => warn!("Could not remove upload session {}: {}", session_url, cleanup_error),
+                Ok(_) => {}
+            }
+        }
+        result.map_err(|e| anyhow!("Could not upload '{}': {}", file_path, e))
+    }
+
+    /// Returns `false` if the chunks couldn't be assembled because a file is at the destination
+    async fn send_chunks(&self, session_url: &str, destination: &str, local_path: &std::path::Path, size: u64) -> Result<bool> {
+        use tokio::io::AsyncReadExt;
+
+        let response = self.client
+            .request(Method::from_bytes(b"MKCOL")?, session_url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("Destination", destination)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("creating the upload session returned HTTP {}", response.status()));
+        }
+
+        // Only one chunk is held in memory at a time, however large the file
+        let mut file = tokio::fs::File::open(local_path).await?;
+        let owncloud = self.get_server_type().as_deref() == Some("owncloud");
+        let mut sent = 0;
+        let mut index = 0;
+        while sent < size {
+            let mut chunk = Vec::with_capacity(self.upload_chunk_size.min(size - sent) as usize);
+            (&mut file).take(self.upload_chunk_size).read_to_end(&mut chunk).await?;
+            if chunk.is_empty() {
+                return Err(anyhow!("file ended after {} of {} bytes", sent, size));
+            }
+            sent += chunk.len() as u64;
+
+            // Nextcloud wants the chunks numbered from 1; ownCloud assembles them in name order
+            let name = if owncloud { format!("{:05}", index) } else { (index + 1).to_string() };
+            let response = self.client
+                .put(format!("{}/{}", session_url, name))
+                .basic_auth(&self.config.username, Some(&self.config.password))
+                .header("Destination", destination)
+                .header("OC-Total-Length", size)
+                .body(chunk)
+                .send()
+                .await?;
+            if !response.status().is_success() {
+                return Err(anyhow!("chunk {} returned HTTP {}", index + 1, response.status()));
+            }
+            index += 1;
+        }
+
+        let response = self.client
+            .request(Method::from_bytes(b"MOVE")?, format!("{}/.file", session_url))
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("Destination", destination)
+            .header("Overwrite", "F")
+            .header("OC-Total-Length", size)
+            .send()
+            .await?;
+        match response.status().as_u16() {
+            412 => Ok(false),
+            _ if response.status().is_success() => Ok(true),
+            _ => Err(anyhow!("assembling the chunks returned HTTP {}", response.status())),
+        }
+    }
+
+    /// Whether a directory still exists on the server. Only 404 and 410 count as gone; any
+    /// other failure is an error, so an outage is never mistaken for a deletion.
+    pub async fn directory_exists(&self, directory_path: &str) -> Result<bool> {
+        let url = self.get_directory_url(directory_path);
+        let mut status = self.directory_status(&url).await?;
+        if matches!(status.as_u16(), 404 | 405) {
+            // The server may only know the directory under the other trailing-slash convention
+            status = self.directory_status(&toggle_trailing_slash(&url)).await?;
+        }
+
+        match status.as_u16() {
+            404 | 410 => Ok(false),
+            _ if status.is_success() => Ok(true),
+            _ => Err(anyhow!("Could not check directory '{}': HTTP {}", directory_path, status)),
+        }
+    }
+
+    async fn directory_status(&self, url: &str) -> Result<reqwest::StatusCode> {
+        let response = self.client
+            .request(Method::from_bytes(b"PROPFIND")?, url)
+            .basic_auth(&self.config.username, Some(&self.config.password))
+            .header("Depth", "0")
+            .send()
+            .await?;
+        Ok(response.status())
+    }
+
+    // ============================================================================
+    // Directory Tracking Maintenance
+    // ============================================================================
+
+    /// Finds the user's tracked directories below this service's watch folders that no longer
+    /// exist on the server. With `apply` they are removed from `webdav_directories`, and with
+    /// `mark_documents` the documents `source_id` synced from them are flagged `source_missing`;
+    /// otherwise nothing is changed.
+    pub async fn cleanup_orphaned_directories(
+        &self,
+        user_id: uuid::Uuid,
+        source_id: uuid::Uuid,
+        state: &AppState,
+        options: &SourceCleanupQuery,
+    ) -> Result<OrphanedDirectoryCleanup> {
+        let watch_folders: Vec<String> = self.config.watch_folders
+            .iter()
+            .map(|folder| format!("/{}", folder.trim_matches('/')))
+            .collect();
+        let is_below = |path: &str, parent: &str| {
+            parent == "/" || path == parent || path.starts_with(&format!("{}/", parent))
+        };
+
+        // Listed in path order, so a directory's parent is always checked before it
+        let tracked: Vec<String> = state.db.list_webdav_directories(user_id).await?
+            .into_iter()
+            .map(|directory| directory.directory_path)
+            .filter(|path| watch_folders.iter().any(|folder| is_below(path, folder)))
+            .collect();
+
+        let mut orphaned_paths: Vec<String> = Vec::new();
+        let mut orphaned_roots: Vec<String> = Vec::new();
+        for path in &tracked {
+            if orphaned_roots.iter().any(|root| is_below(path, root)) {
+                orphaned_paths.push(path.clone());
+                continue;
+            }
+            if !self.directory_exists(path).await? {
+                debug!("Tracked directory no longer exists on the server: {}", path);
+                orphaned_roots.push(path.clone());
+                orphaned_paths.push(path.clone());
+            }
+        }
+
+        let mut documents_marked = 0;
+        if options.apply {
+            for path in &orphaned_paths {
+                state.db.delete_webdav_directory(user_id, path).await?;
+            }
+            if options.mark_documents {
+                for root in &orphaned_roots {
+                    documents_marked += state.db.mark_source_directory_documents_missing(source_id, root).await?;
+                }
+            }
+            info!("Removed {} orphaned WebDAV directories for source {} ({} documents marked missing)",
+                orphaned_paths.len(), source_id, documents_marked);
+        }
+
+        Ok(OrphanedDirectoryCleanup {
+            applied: options.apply,
+            directories_checked: tracked.len(),
+            orphaned_paths,
+            documents_marked,
+        })
+    }
+
     // ============================================================================
     // Server Capabilities and Health Checks
     // ============================================================================
@@ -1628,7 +2133,8 @@ impl WebDAVService {
             watch_folders: self.config.watch_folders.clone(),
             file_extensions: self.config.file_extensions.clone(),
             timeout_seconds: self.config.timeout_seconds,
-            server_type: self.config.server_type.clone(),
+            server_type: self.get_server_type(),
+            tls: self.config.tls.clone(),
         };
         
         let options_response = self.authenticated_request(
@@ -1675,6 +2181,19 @@ impl WebDAVService {
         info!("🏥 Performing WebDAV service health check");
         
         let start_time = std::time::Instant::now();
+
+        let circuit = self.circuit_breaker_status();
+        if circuit.state == CircuitState::Open {
+            return Ok(HealthStatus {
+                healthy: false,
+                message: format!(
+                    "Server temporarily unavailable after {} consecutive failures, retrying in {}s",
+                    circuit.consecutive_failures, circuit.retry_after_seconds.unwrap_or_default()
+                ),
+                response_time_ms: 0,
+                details: Some(serde_json::json!({ "circuit_breaker": circuit })),
+            });
+        }
         
         // Test basic connectivity
         let connection_result = self.test_connection().await?;
@@ -1711,7 +2230,8 @@ impl WebDAVService {
             details: Some(serde_json::json!({
                 "tested_folders": self.config.watch_folders,
                 "server_type": connection_result.server_type,
-                "server_version": connection_result.server_version
+                "server_version": connection_result.server_version,
+                "circuit_breaker": self.circuit_breaker_status()
             })),
         })
     }
@@ -1730,6 +2250,19 @@ impl WebDAVService {
         let mut directories_checked = 0;
         let mut healthy_directories = 0;
 
+        // Watch folder checks below fail fast while the circuit is open
+        let circuit = self.circuit_breaker_status();
+        if circuit.is_unavailable() {
+            issues.push(ValidationIssue {
+                issue_type: ValidationIssueType::Inaccessible,
+                severity: ValidationSeverity::Critical,
+                directory_path: "/".to_string(),
+                description: "WebDAV server temporarily unavailable after repeated failures".to_string(),
+                details: Some(serde_json::json!({ "circuit_breaker": circuit })),
+                detected_at: chrono::Utc::now(),
+            });
+        }
+
         // Test basic connectivity first
         match self.test_connection().await {
             Ok(result) if !result.success => {
@@ -1853,6 +2386,17 @@ impl WebDAVService {
     // Utility Methods
     // ============================================================================
 
+    /// Whether a directory's ETag changes when anything below it changes, if the server type
+    /// is known to behave one way or the other. Smart sync relies on this to skip unchanged subtrees.
+    pub fn propagates_recursive_etags(&self) -> Option<bool> {
+        match self.get_server_type().as_deref() {
+            Some("nextcloud") | Some("owncloud") => Some(true),
+            // SeafDAV directory ETags only change with the directory's own entries
+            Some("seafile") => Some(false),
+            _ => None,
+        }
+    }
+
     /// Tests if the server supports recursive ETag scanning
     pub async fn test_recursive_etag_support(&self) -> Result<bool> {
         debug!("🔍 Testing recursive ETag support");
@@ -1866,6 +2410,11 @@ impl WebDAVService {
             return Ok(false);
         }
 
+        if let Some(recursive) = self.propagates_recursive_etags() {
+            debug!("Recursive ETag support known from server type {:?}: {}", self.get_server_type(), recursive);
+            return Ok(recursive);
+        }
+
         // Check server type for known recursive ETag support
         if let Some(server_software) = &capabilities.server_software {
             let server_lower = server_software.to_lowercase();
@@ -1967,11 +2516,202 @@ impl Clone for WebDAVService {
             scan_semaphore: Arc::clone(&self.scan_semaphore),
             download_semaphore: Arc::clone(&self.download_semaphore),
             working_protocol: Arc::clone(&self.working_protocol),
+            circuit_breaker: Arc::clone(&self.circuit_breaker),
+            detected_server_type: Arc::clone(&self.detected_server_type),
         }
     }
 }
 
+/// Responses worth retrying: server errors and rate limiting. These also count toward the circuit breaker.
+pub(crate) fn is_retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error() || is_rate_limit_status(status)
+}
+
+pub(crate) fn is_rate_limit_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Whether an `authenticated_request` error is a 404 or 405, the answers servers give when a
+/// collection URL uses the trailing-slash convention they don't accept
+fn is_wrong_collection_url_error(error: &anyhow::Error) -> bool {
+    let message = error.to_string();
+    message.starts_with("Client error: 404") || message.contains("not allowed (405)")
+}
+
+/// Percent-encodes each segment of a plain path the way Nextcloud, ownCloud and most other
+/// WebDAV servers write the hrefs they list, so `/Inbox/Tax return #2.pdf` becomes
+/// `/Inbox/Tax%20return%20%232.pdf`
+pub fn encode_path(path: &str) -> String {
+    const KEPT: &[u8] = b"-._~!$&'()*+,;=:@/";
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || KEPT.contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// Adds a trailing slash to a URL that has none, or removes the one it has
+pub(crate) fn toggle_trailing_slash(url: &str) -> String {
+    match url.strip_suffix('/') {
+        Some(without_slash) => without_slash.trim_end_matches('/').to_string(),
+        None => format!("{}/", url),
+    }
+}
+
+/// Picks a random delay between 0 and the current backoff so that clients failing together
+/// don't retry in lockstep
+pub(crate) fn full_jitter_delay(backoff_ms: u64) -> u64 {
+    rand::Rng::gen_range(&mut rand::thread_rng(), 0..=backoff_ms)
+}
+
+/// Strips a WebDAV endpoint such as `/remote.php/dav/files/user` or `/seafdav` from a server URL,
+/// leaving the root the server's status endpoints live under
+pub(crate) fn server_root_url(url: &str) -> String {
+    let url = url.trim().trim_end_matches('/');
+    let root = ["/remote.php", "/seafdav"]
+        .iter()
+        .filter_map(|marker| url.find(marker).map(|index| &url[..index]))
+        .min_by_key(|root| root.len())
+        .unwrap_or(url);
+    root.to_string()
+}
+
+/// Server type from response headers: the `Server` header or vendor `X-` headers
+pub(crate) fn server_type_from_headers(headers: &reqwest::header::HeaderMap) -> Option<&'static str> {
+    let server = headers
+        .get("server")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_lowercase())
+        .unwrap_or_default();
+
+    let has_header = |prefix: &str| headers.keys().any(|name| name.as_str().starts_with(prefix));
+    if server.contains("nextcloud") || has_header("x-nextcloud") {
+        Some("nextcloud")
+    } else if server.contains("owncloud") || has_header("x-owncloud") {
+        Some("owncloud")
+    } else if server.contains("seafile") || server.contains("seafdav") || has_header("x-seafile") {
+        Some("seafile")
+    } else {
+        None
+    }
+}
+
+/// Server type from a Nextcloud/ownCloud `status.php` response
+pub(crate) fn server_type_from_status(status: &serde_json::Value) -> Option<&'static str> {
+    status.get("installed")?;
+    let product = status.get("productname").and_then(|name| name.as_str()).unwrap_or_default().to_lowercase();
+    if product.contains("nextcloud") {
+        Some("nextcloud")
+    } else {
+        // Nextcloud always names itself; ownCloud installs may leave the product name empty
+        Some("owncloud")
+    }
+}
+
+/// Recognizes Seafile's `api2/server-info/` response by its `seafile-*` feature flags
+pub(crate) fn server_type_from_server_info(info: &serde_json::Value) -> Option<&'static str> {
+    let features = info.get("features")?.as_array()?;
+    features
+        .iter()
+        .any(|feature| feature.as_str().is_some_and(|feature| feature.starts_with("seafile")))
+        .then_some("seafile")
+}
+
+/// Parses the first byte position from a `Content-Range` header such as `bytes 100-199/200`
+pub(crate) fn parse_content_range_start(value: &str) -> Option<u64> {
+    let range = value.trim().strip_prefix("bytes")?.trim_start();
+    let (start, _) = range.split_once('-')?;
+    start.trim().parse().ok()
+}
+
 /// Tests WebDAV connection with provided configuration (standalone function for backward compatibility)
 pub async fn test_webdav_connection(test_config: &WebDAVTestConnection) -> Result<WebDAVConnectionResult> {
     WebDAVService::test_connection_with_config(test_config).await
-}
\ No newline at end of file
+}
+
+/// Builds the HTTP client for a server: with the request timeout, trusting the configured CA
+/// certificates, presenting the client certificate if one is configured and, only if
+/// explicitly asked to, skipping certificate verification
+fn build_http_client(config: &WebDAVConfig) -> Result<Client> {
+    let mut builder = Client::builder().timeout(config.timeout());
+    for certificate in config.ca_certificates()? {
+        builder = builder.add_root_certificate(certificate);
+    }
+    if let Some(identity) = load_client_identity(&config.tls.client_certificate)? {
+        builder = builder.identity(reqwest::Identity::from_pem(&identity.to_pem())?);
+    }
+    if config.tls.accept_invalid_certs {
+        warn!(
+            "⚠️ TLS certificate verification is DISABLED for WebDAV server {}: connections to it \
+            can be intercepted. Trust the server's CA with ca_certificate instead if possible.",
+            config.server_url
+        );
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    Ok(builder.build()?)
+}
+
+/// Classifies a failed request: a rejected TLS certificate, the server's or ours, is reported
+/// as such, so it isn't mistaken for an unreachable server
+fn request_error(url: &str, error: reqwest::Error) -> ConnectionTestError {
+    if is_client_certificate_alert(&error) {
+        ConnectionTestError::ClientCertificateRejected(format!(
+            "{} requires a client certificate and did not accept the configured one: {}. Check \
+            that client_certificate is signed by a CA the server trusts and has not expired.",
+            url, error_chain(&error)
+        ))
+    } else if is_certificate_error(&error) {
+        ConnectionTestError::CertificateRejected(format!(
+            "The TLS certificate of {} is not trusted: {}. If the server uses a self-signed \
+            certificate or a private CA, set ca_certificate to its CA certificate.",
+            url, error_chain(&error)
+        ))
+    } else {
+        ConnectionTestError::Unreachable(format!("Could not reach {}: {}", url, error))
+    }
+}
+
+/// Whether an error, or any error that caused it, is a TLS certificate verification failure
+pub(crate) fn is_certificate_error(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if error.to_string().to_lowercase().contains("certificate") {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// An error with its causes, since reqwest's own message leaves out why a connection failed
+fn error_chain(error: &(dyn std::error::Error + 'static)) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Error for an OPTIONS probe the server answered with a failure status. 401 means the
+/// credentials were rejected; any other status comes from a server that accepted them or
+/// never asked, e.g. for a wrong WebDAV path.
+async fn options_status_error(request: &str, response: Response) -> anyhow::Error {
+    let status = response.status();
+    let message = format!("{} failed with status: {} - {}", request, status, response.text().await.unwrap_or_default());
+    // nginx completes the handshake and answers 400 "No required SSL certificate was sent"
+    // or "The SSL certificate error" when verifying client certificates is optional
+    if status == reqwest::StatusCode::BAD_REQUEST && message.contains("SSL certificate") {
+        ConnectionTestError::ClientCertificateRejected(message).into()
+    } else if status == reqwest::StatusCode::UNAUTHORIZED {
+        ConnectionTestError::AuthenticationFailed(message).into()
+    } else {
+        ConnectionTestError::ResourceUnavailable(message).into()
+    }
+}
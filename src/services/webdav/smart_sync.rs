@@ -41,6 +41,8 @@ pub struct SmartSyncResult {
     pub strategy_used: SmartSyncStrategy,
     pub directories_scanned: usize,
     pub directories_skipped: usize,
+    /// Directories that were listed successfully, see `WebDAVDiscoveryResult::scanned_directories`
+    pub scanned_directories: Vec<String>,
 }
 
 impl SmartSyncService {
@@ -62,6 +64,12 @@ impl SmartSyncService {
         _progress: Option<&SyncProgress>, // Simplified: no complex progress tracking
     ) -> Result<SmartSyncDecision> {
         info!("🧠 Evaluating smart sync for folder: {}", folder_path);
+
+        // Comparing immediate subdirectories only catches deeper changes if ETags propagate upwards
+        if webdav_service.propagates_recursive_etags() == Some(false) {
+            info!("Server does not propagate recursive ETags, requires full deep scan of {}", folder_path);
+            return Ok(SmartSyncDecision::RequiresSync(SmartSyncStrategy::FullDeepScan));
+        }
         
         // Get all known directory ETags from database in bulk
         let known_directories = self.state.db.list_webdav_directories(user_id).await
@@ -201,6 +209,86 @@ impl SmartSyncService {
         }
     }
 
+    /// Cheapest way to pick up newly added folders: lists `folder_path` one level deep and
+    /// scans only the subdirectories that are not tracked yet. Known directories are not
+    /// requested at all and no ETags are compared, so changes inside them, deletions and
+    /// new folders nested below known ones are missed. A folder with no tracked directories
+    /// at all is new in its entirety and gets a full deep scan.
+    pub async fn sync_new_directories_only(
+        &self,
+        user_id: Uuid,
+        webdav_service: &WebDAVService,
+        folder_path: &str,
+        _progress: Option<&SyncProgress>,
+    ) -> Result<Option<SmartSyncResult>> {
+        let known_directories = self.state.db.list_webdav_directories(user_id).await
+            .map_err(|e| anyhow::anyhow!("Failed to fetch known directories: {}", e))?;
+        let known_paths: std::collections::HashSet<String> = known_directories
+            .into_iter()
+            .filter(|dir| dir.directory_path.starts_with(folder_path))
+            .map(|dir| dir.directory_path.trim_end_matches('/').to_string())
+            .collect();
+
+        if known_paths.is_empty() {
+            info!("No known directories for {}, new-only sync scans it in full", folder_path);
+            return self.perform_full_deep_scan(user_id, webdav_service, folder_path, _progress).await.map(Some);
+        }
+
+        let root_discovery = webdav_service.discover_files_and_directories(folder_path, false).await?;
+        let (new_directories, known_count) = root_discovery.directories
+            .iter()
+            .filter(|directory| directory.relative_path.trim_end_matches('/') != folder_path.trim_end_matches('/'))
+            .fold((Vec::new(), 0), |(mut new_directories, known_count), directory| {
+                if known_paths.contains(directory.relative_path.trim_end_matches('/')) {
+                    (new_directories, known_count + 1)
+                } else {
+                    info!("New directory discovered: {}", directory.relative_path);
+                    new_directories.push(directory);
+                    (new_directories, known_count)
+                }
+            });
+
+        if new_directories.is_empty() {
+            info!("✅ New-only sync: no new directories under {}, {} known directories skipped", folder_path, known_count);
+            return Ok(None);
+        }
+
+        info!("🆕 New-only sync: scanning {} new directories under {}, skipping {} known", new_directories.len(), folder_path, known_count);
+        let mut result = SmartSyncResult {
+            files: Vec::new(),
+            directories: Vec::new(),
+            strategy_used: SmartSyncStrategy::TargetedScan(new_directories.iter().map(|d| d.relative_path.clone()).collect()),
+            directories_scanned: 0,
+            directories_skipped: known_count,
+            scanned_directories: Vec::new(),
+        };
+        for directory in new_directories {
+            let scan = self.perform_targeted_scan(user_id, webdav_service, vec![directory.relative_path.clone()], _progress).await?;
+            if scan.directories_scanned == 0 {
+                // Left untracked so the next new-only sync tries it again
+                continue;
+            }
+
+            // The scan only records the subdirectories, so track the new directory itself too
+            let tracked = CreateWebDAVDirectory {
+                user_id,
+                directory_path: directory.relative_path.clone(),
+                directory_etag: directory.etag.clone(),
+                file_count: 0,
+                total_size_bytes: 0,
+            };
+            if let Err(e) = self.state.db.create_or_update_webdav_directory(&tracked).await {
+                warn!("Failed to save directory ETag for {}: {}", directory.relative_path, e);
+            }
+
+            result.files.extend(scan.files);
+            result.directories.extend(scan.directories);
+            result.directories_scanned += scan.directories_scanned;
+            result.scanned_directories.extend(scan.scanned_directories);
+        }
+        Ok(Some(result))
+    }
+
     /// Performs a full deep scan and saves all directory ETags
     async fn perform_full_deep_scan(
         &self,
@@ -265,6 +353,7 @@ impl SmartSyncService {
             strategy_used: SmartSyncStrategy::FullDeepScan,
             directories_scanned: discovery_result.directories.len(),
             directories_skipped: 0,
+            scanned_directories: discovery_result.scanned_directories,
         })
     }
 
@@ -278,6 +367,7 @@ impl SmartSyncService {
     ) -> Result<SmartSyncResult> {
         let mut all_files = Vec::new();
         let mut all_directories = Vec::new();
+        let mut scanned_directories = Vec::new();
         let mut directories_scanned = 0;
 
         // Scan each target directory recursively
@@ -328,6 +418,7 @@ impl SmartSyncService {
                     }
                     
                     all_directories.extend(discovery_result.directories);
+                    scanned_directories.extend(discovery_result.scanned_directories);
                     directories_scanned += 1;
                 }
                 Err(e) => {
@@ -348,6 +439,7 @@ impl SmartSyncService {
             strategy_used: SmartSyncStrategy::TargetedScan(target_directories),
             directories_scanned,
             directories_skipped: 0, // TODO: Could track this if needed
+            scanned_directories,
         })
     }
 }
\ No newline at end of file
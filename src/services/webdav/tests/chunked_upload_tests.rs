@@ -0,0 +1,222 @@
+#[cfg(test)]
+mod tests {
+    use crate::services::webdav::{WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{header, method, path, path_regex},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const DESTINATION: &str = "/remote.php/dav/files/testuser/Documents/report.pdf";
+
+    fn create_test_service(mock_server_url: &str, server_type: &str) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some(server_type.to_string()),
+            tls: Default::default(),
+        };
+        WebDAVService::new(config)
+            .expect("Failed to create test service")
+            .with_upload_chunk_size(4)
+    }
+
+    fn local_file(dir: &tempfile::TempDir, content: &[u8]) -> std::path::PathBuf {
+        let path = dir.path().join("upload");
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_large_upload_is_sent_in_chunks_and_assembled() {
+        let mock_server = MockServer::start().await;
+        let destination = format!("{}{}", mock_server.uri(), DESTINATION);
+
+        Mock::given(method("MKCOL"))
+            .and(path_regex(r"^/remote\.php/dav/uploads/testuser/readur-[0-9a-f]+$"))
+            .and(header("Destination", destination.as_str()))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path_regex(r"^/remote\.php/dav/uploads/testuser/readur-[0-9a-f]+/[0-9]+$"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("MOVE"))
+            .and(path_regex(r"^/remote\.php/dav/uploads/testuser/readur-[0-9a-f]+/\.file$"))
+            .and(header("Destination", destination.as_str()))
+            .and(header("OC-Total-Length", "10"))
+            .and(header("Overwrite", "F"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = create_test_service(&mock_server.uri(), "nextcloud");
+        assert!(service.upload_file("/Documents/report.pdf", &local_file(&dir, b"0123456789")).await.unwrap());
+
+        let received = mock_server.received_requests().await.unwrap();
+        let chunks: Vec<(String, Vec<u8>)> = received
+            .iter()
+            .filter(|r| r.method.as_str() == "PUT")
+            .map(|r| (r.url.path().rsplit('/').next().unwrap().to_string(), r.body.clone()))
+            .collect();
+        assert_eq!(chunks, vec![
+            ("1".to_string(), b"0123".to_vec()),
+            ("2".to_string(), b"4567".to_vec()),
+            ("3".to_string(), b"89".to_vec()),
+        ]);
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_small_files_and_generic_servers_get_a_plain_put() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PUT"))
+            .and(path(DESTINATION))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/Documents/report.pdf"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("MKCOL"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let nextcloud = create_test_service(&mock_server.uri(), "nextcloud");
+        assert!(nextcloud.upload_file("/Documents/report.pdf", &local_file(&dir, b"0123")).await.unwrap());
+        let generic = create_test_service(&mock_server.uri(), "generic");
+        assert!(generic.upload_file("/Documents/report.pdf", &local_file(&dir, b"0123456789")).await.unwrap());
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_failed_chunked_upload_removes_the_upload_session() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("MKCOL"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path_regex(r"/00000$"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path_regex(r"/00001$"))
+            .respond_with(ResponseTemplate::new(507))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("MOVE"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(0)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"^/remote\.php/dav/uploads/testuser/readur-[0-9a-f]+$"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = create_test_service(&format!("{}/remote.php/webdav", mock_server.uri()), "owncloud");
+        let error = service.upload_file("/Documents/report.pdf", &local_file(&dir, b"0123456789")).await.unwrap_err();
+        assert!(error.to_string().contains("507"), "{}", error);
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_names_with_spaces_and_hashes_are_encoded() {
+        let mock_server = MockServer::start().await;
+        let encoded = "/remote.php/dav/files/testuser/Documents/Tax%20return%20%232.pdf";
+        let destination = format!("{}{}", mock_server.uri(), encoded);
+
+        Mock::given(method("MKCOL"))
+            .and(header("Destination", destination.as_str()))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path_regex(r"^/remote\.php/dav/uploads/testuser/readur-[0-9a-f]+/[0-9]+$"))
+            .and(header("Destination", destination.as_str()))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("MOVE"))
+            .and(header("Destination", destination.as_str()))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        // Small files are PUT straight to the encoded path
+        Mock::given(method("PUT"))
+            .and(path(encoded))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = create_test_service(&mock_server.uri(), "nextcloud");
+        assert!(service.upload_file("/Documents/Tax return #2.pdf", &local_file(&dir, b"0123456789")).await.unwrap());
+        assert!(service.upload_file("/Documents/Tax return #2.pdf", &local_file(&dir, b"0123")).await.unwrap());
+
+        mock_server.verify().await;
+    }
+
+    #[tokio::test]
+    async fn test_taken_destination_is_left_alone() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("MKCOL"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PUT"))
+            .respond_with(ResponseTemplate::new(201))
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("MOVE"))
+            .respond_with(ResponseTemplate::new(412))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path_regex(r"^/remote\.php/dav/uploads/testuser/readur-[0-9a-f]+$"))
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let service = create_test_service(&mock_server.uri(), "nextcloud");
+        assert!(!service.upload_file("/Documents/report.pdf", &local_file(&dir, b"0123456789")).await.unwrap());
+
+        mock_server.verify().await;
+    }
+}
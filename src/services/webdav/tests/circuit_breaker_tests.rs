@@ -0,0 +1,160 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use crate::services::webdav::service::{full_jitter_delay, is_rate_limit_status, is_retryable_status};
+    use crate::services::webdav::{CircuitBreaker, CircuitState, RetryConfig, WebDAVConfig, WebDAVService};
+    use reqwest::{Method, StatusCode};
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn create_test_service(mock_server_url: &str, failure_threshold: u32) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
+        };
+        let retry_config = RetryConfig {
+            max_retries: 0,
+            initial_delay_ms: 10,
+            circuit_failure_threshold: failure_threshold,
+            circuit_cooldown_ms: 60000,
+            ..RetryConfig::default()
+        };
+        WebDAVService::new_with_retry(config, retry_config).expect("Failed to create test service")
+    }
+
+    #[test]
+    fn test_opens_after_threshold_consecutive_failures() {
+        let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
+
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        breaker.record_success();
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.try_acquire().is_ok());
+
+        assert!(breaker.record_failure());
+        assert!(breaker.try_acquire().is_err());
+
+        let status = breaker.status();
+        assert_eq!(status.state, CircuitState::Open);
+        assert_eq!(status.consecutive_failures, 3);
+        assert_eq!(status.retry_after_seconds, Some(60));
+        assert!(status.is_unavailable());
+    }
+
+    #[test]
+    fn test_half_open_allows_a_single_probe() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        assert!(breaker.try_acquire().is_err());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+        assert!(breaker.try_acquire().is_ok());
+        assert!(breaker.try_acquire().is_err(), "only one probe while half-open");
+
+        breaker.record_success();
+        assert_eq!(breaker.status().state, CircuitState::Closed);
+        assert_eq!(breaker.status().consecutive_failures, 0);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_failed_probe_reopens_the_circuit() {
+        let breaker = CircuitBreaker::new(2, Duration::from_millis(20));
+        breaker.record_failure();
+        breaker.record_failure();
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(breaker.try_acquire().is_ok());
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.status().state, CircuitState::Open);
+        assert!(breaker.try_acquire().is_err());
+    }
+
+    #[test]
+    fn test_breakers_are_shared_per_server() {
+        let a = CircuitBreaker::for_server("https://breaker-test.example.com/", 5, Duration::from_secs(60));
+        let b = CircuitBreaker::for_server("HTTPS://breaker-test.example.com", 5, Duration::from_secs(60));
+        let other = CircuitBreaker::for_server("https://other-breaker-test.example.com", 5, Duration::from_secs(60));
+
+        assert!(Arc::ptr_eq(&a, &b));
+        assert!(!Arc::ptr_eq(&a, &other));
+    }
+
+    #[test]
+    fn test_full_jitter_stays_within_backoff() {
+        assert_eq!(full_jitter_delay(0), 0);
+        for _ in 0..100 {
+            assert!(full_jitter_delay(1000) <= 1000);
+        }
+    }
+
+    #[test]
+    fn test_retryable_status_classification() {
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_rate_limit_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_fails_fast_without_reaching_server() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PROPFIND"))
+            .and(path("/remote.php/dav/files/testuser/Documents"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), 2);
+        let url = format!("{}/remote.php/dav/files/testuser/Documents", mock_server.uri());
+
+        for _ in 0..2 {
+            assert!(service.authenticated_request(Method::from_bytes(b"PROPFIND").unwrap(), &url, None, None).await.is_err());
+        }
+        assert_eq!(service.circuit_breaker_status().state, CircuitState::Open);
+
+        let error = service
+            .authenticated_request(Method::from_bytes(b"PROPFIND").unwrap(), &url, None, None)
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("temporarily unavailable"));
+
+        let health = service.health_check().await.unwrap();
+        assert!(!health.healthy);
+        assert_eq!(health.details.unwrap()["circuit_breaker"]["state"], "open");
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_do_not_open_the_circuit() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), 1);
+        let url = format!("{}/remote.php/dav/files/testuser/missing.pdf", mock_server.uri());
+
+        for _ in 0..3 {
+            assert!(service.authenticated_request(Method::GET, &url, None, None).await.is_err());
+        }
+        assert_eq!(service.circuit_breaker_status().state, CircuitState::Closed);
+    }
+}
@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use crate::services::webdav::{
+        ValidationAction, ValidationIssue, ValidationIssueType, ValidationRecommendation, ValidationReport,
+        ValidationSeverity, ValidationSummary, DEEP_SCAN_HEALTH_THRESHOLD,
+    };
+
+    fn report(health_score: i32, issues: Vec<ValidationIssue>, actions: Vec<ValidationAction>) -> ValidationReport {
+        ValidationReport {
+            overall_health_score: health_score,
+            issues,
+            recommendations: actions
+                .into_iter()
+                .map(|action| ValidationRecommendation {
+                    action,
+                    reason: "test".to_string(),
+                    affected_directories: vec![],
+                    priority: ValidationSeverity::Info,
+                })
+                .collect(),
+            summary: ValidationSummary {
+                total_directories_checked: 1,
+                healthy_directories: 1,
+                directories_with_issues: 0,
+                critical_issues: 0,
+                warning_issues: 0,
+                info_issues: 0,
+                validation_duration_ms: 5,
+            },
+        }
+    }
+
+    fn issue(issue_type: ValidationIssueType, severity: ValidationSeverity) -> ValidationIssue {
+        ValidationIssue {
+            issue_type,
+            severity,
+            directory_path: "/Documents".to_string(),
+            description: "test".to_string(),
+            details: None,
+            detected_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_healthy_report_does_not_trigger() {
+        let healthy = report(100, vec![], vec![ValidationAction::NoActionRequired]);
+        assert!(!healthy.should_trigger_deep_scan());
+    }
+
+    #[test]
+    fn test_low_health_score_triggers() {
+        assert!(report(DEEP_SCAN_HEALTH_THRESHOLD - 1, vec![], vec![]).should_trigger_deep_scan());
+        assert!(!report(DEEP_SCAN_HEALTH_THRESHOLD, vec![], vec![]).should_trigger_deep_scan());
+    }
+
+    #[test]
+    fn test_tracking_drift_triggers() {
+        for issue_type in [
+            ValidationIssueType::Untracked,
+            ValidationIssueType::Missing,
+            ValidationIssueType::ETagMismatch,
+            ValidationIssueType::ETagUnreliable,
+        ] {
+            let drifted = report(100, vec![issue(issue_type.clone(), ValidationSeverity::Warning)], vec![]);
+            assert!(drifted.should_trigger_deep_scan(), "{:?} should trigger a deep scan", issue_type);
+        }
+
+        let stale = report(100, vec![issue(ValidationIssueType::Stale, ValidationSeverity::Info)], vec![]);
+        assert!(!stale.should_trigger_deep_scan());
+    }
+
+    #[test]
+    fn test_rescan_recommendation_triggers() {
+        assert!(report(100, vec![], vec![ValidationAction::DeepScanRequired]).should_trigger_deep_scan());
+        assert!(report(100, vec![], vec![ValidationAction::RebuildTracking]).should_trigger_deep_scan());
+    }
+
+    #[test]
+    fn test_unreachable_server_never_triggers() {
+        let unreachable = report(
+            0,
+            vec![issue(ValidationIssueType::Inaccessible, ValidationSeverity::Critical)],
+            vec![ValidationAction::CheckServerConfiguration, ValidationAction::DeepScanRequired],
+        );
+        assert!(!unreachable.should_trigger_deep_scan());
+    }
+}
@@ -1,3 +1,9 @@
 pub mod etag_comparison_tests;
 pub mod deletion_detection_tests;
-pub mod path_processing_tests;
\ No newline at end of file
+pub mod path_processing_tests;
+pub mod range_download_tests;
+pub mod circuit_breaker_tests;
+pub mod server_detection_tests;
+pub mod deep_scan_policy_tests;
+pub mod tls_tests;
+pub mod chunked_upload_tests;
@@ -18,6 +18,7 @@ mod path_processing_tests {
             file_extensions: vec!["pdf".to_string(), "txt".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         };
         WebDAVService::new(config).expect("Failed to create test service")
     }
@@ -284,6 +285,66 @@ mod path_processing_tests {
         assert_ne!(result.files[0].relative_path, "TEMP", "File path should not be TEMP");
     }
 
+    #[tokio::test]
+    async fn test_directory_propfind_uses_trailing_slash() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PROPFIND"))
+            .and(path("/remote.php/dav/files/testuser/TestDocuments/"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(mock_propfind_response()))
+            .expect(3)
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri());
+        for folder in ["TestDocuments", "/TestDocuments/"] {
+            let result = service.discover_files_and_directories(folder, false).await
+                .expect("Discovery should succeed");
+            assert_eq!(result.directories.len(), 2, "directories for {:?}", folder);
+        }
+        assert!(service.directory_exists("/TestDocuments").await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_directory_propfind_retries_without_trailing_slash() {
+        let mock_server = MockServer::start().await;
+
+        // This server only answers collection URLs without a trailing slash
+        Mock::given(method("PROPFIND"))
+            .and(path("/remote.php/dav/files/testuser/TestDocuments/"))
+            .respond_with(ResponseTemplate::new(405))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+        Mock::given(method("PROPFIND"))
+            .and(path("/remote.php/dav/files/testuser/TestDocuments"))
+            .respond_with(ResponseTemplate::new(207).set_body_string(mock_propfind_response()))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri());
+        let result = service.discover_files_and_directories("/TestDocuments/", false).await
+            .expect("Discovery should succeed after retrying without the slash");
+        assert_eq!(result.files.len(), 1);
+        assert_eq!(result.directories.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_missing_directory_fails_after_one_retry() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("PROPFIND"))
+            .respond_with(ResponseTemplate::new(404))
+            .expect(4)
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri());
+        assert!(service.discover_files_and_directories("/Missing", false).await.is_err());
+        assert!(!service.directory_exists("/Missing").await.unwrap());
+    }
+
     #[tokio::test]
     async fn test_recursive_directory_discovery_integration() {
         let mock_server = MockServer::start().await;
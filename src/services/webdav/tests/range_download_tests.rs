@@ -0,0 +1,182 @@
+#[cfg(test)]
+mod tests {
+    use crate::services::webdav::service::parse_content_range_start;
+    use crate::services::webdav::{RetryConfig, WebDAVConfig, WebDAVService};
+    use wiremock::{
+        matchers::{header, method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    const FILE_PATH: &str = "/remote.php/dav/files/testuser/Documents/report.pdf";
+    const CONTENT: &[u8] = b"%PDF-1.4 first half|second half";
+    /// Bytes the interrupted response sends before the connection drops
+    const RECEIVED: usize = 19;
+
+    fn create_test_service(mock_server_url: &str) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: mock_server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
+        };
+        WebDAVService::new(config).expect("Failed to create test service")
+    }
+
+    /// A service that waits only a few milliseconds before resuming
+    fn create_resuming_service(mock_server_url: &str) -> WebDAVService {
+        let retry_config = RetryConfig { initial_delay_ms: 10, max_delay_ms: 10, ..Default::default() };
+        WebDAVService::new_with_retry(create_test_service(mock_server_url).get_config().clone(), retry_config)
+            .expect("Failed to create test service")
+    }
+
+    /// Serves the first `RECEIVED` bytes of `CONTENT` once, announcing the full length so the
+    /// transfer ends mid-body. Requests without a Range header after that get `fallback`.
+    async fn mount_interrupted_download(mock_server: &MockServer, fallback: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path(FILE_PATH))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(CONTENT[..RECEIVED].to_vec())
+                    .insert_header("content-length", CONTENT.len().to_string().as_str())
+                    .insert_header("etag", "\"v1\"")
+            )
+            .up_to_n_times(1)
+            .with_priority(2)
+            .named("interrupted download")
+            .mount(mock_server)
+            .await;
+
+        Mock::given(method("GET"))
+            .and(path(FILE_PATH))
+            .respond_with(fallback)
+            .with_priority(3)
+            .named("download from scratch")
+            .mount(mock_server)
+            .await;
+    }
+
+    /// Answers the request that resumes from `RECEIVED` with `response`
+    async fn mount_resume(mock_server: &MockServer, response: ResponseTemplate) {
+        Mock::given(method("GET"))
+            .and(path(FILE_PATH))
+            .and(header("range", format!("bytes={}-", RECEIVED).as_str()))
+            .and(header("if-range", "\"v1\""))
+            .respond_with(response)
+            .with_priority(1)
+            .expect(1)
+            .named("resumed download")
+            .mount(mock_server)
+            .await;
+    }
+
+    fn partial_content(start: usize, etag: &str) -> ResponseTemplate {
+        ResponseTemplate::new(206)
+            .set_body_bytes(CONTENT[start..].to_vec())
+            .insert_header("content-range", format!("bytes {}-{}/{}", start, CONTENT.len() - 1, CONTENT.len()).as_str())
+            .insert_header("etag", etag)
+    }
+
+    fn full_content(content: &[u8], etag: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .set_body_bytes(content.to_vec())
+            .insert_header("etag", etag)
+    }
+
+    #[test]
+    fn test_parse_content_range_start() {
+        assert_eq!(parse_content_range_start("bytes 100-199/200"), Some(100));
+        assert_eq!(parse_content_range_start("bytes 0-0/*"), Some(0));
+        assert_eq!(parse_content_range_start("  bytes  42-99/100 "), Some(42));
+    }
+
+    #[test]
+    fn test_parse_content_range_start_rejects_invalid_values() {
+        assert_eq!(parse_content_range_start("bytes */200"), None);
+        assert_eq!(parse_content_range_start("items 1-2/3"), None);
+        assert_eq!(parse_content_range_start(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_download_without_interruption_returns_full_body() {
+        let mock_server = MockServer::start().await;
+
+        Mock::given(method("GET"))
+            .and(path("/remote.php/dav/files/testuser/Documents/report.pdf"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_bytes(b"%PDF-1.4 test content".to_vec())
+                    .insert_header("content-type", "application/pdf; charset=binary")
+                    .insert_header("etag", "\"abc123\"")
+            )
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri());
+        let content = service.download_file("/Documents/report.pdf").await.unwrap();
+
+        assert_eq!(content, b"%PDF-1.4 test content");
+    }
+
+    #[tokio::test]
+    async fn test_interrupted_download_resumes_with_range_request() {
+        let mock_server = MockServer::start().await;
+        mount_interrupted_download(&mock_server, full_content(CONTENT, "\"v1\"")).await;
+        mount_resume(&mock_server, partial_content(RECEIVED, "\"v1\"")).await;
+
+        let service = create_resuming_service(&mock_server.uri());
+        let content = service.download_file("/Documents/report.pdf").await.unwrap();
+
+        assert_eq!(content, CONTENT);
+        // Only the missing bytes were requested again
+        let requests = mock_server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_full_response_to_range_request_restarts_download() {
+        let mock_server = MockServer::start().await;
+        mount_interrupted_download(&mock_server, full_content(CONTENT, "\"v1\"")).await;
+        // A server without range support sends the whole file again
+        mount_resume(&mock_server, full_content(CONTENT, "\"v1\"")).await;
+
+        let service = create_resuming_service(&mock_server.uri());
+        let content = service.download_file("/Documents/report.pdf").await.unwrap();
+
+        // The received bytes were dropped instead of being doubled up
+        assert_eq!(content, CONTENT);
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_changed_etag_restarts_download() {
+        let mock_server = MockServer::start().await;
+        let changed: &[u8] = b"%PDF-1.4 the file was replaced meanwhile";
+        mount_interrupted_download(&mock_server, full_content(changed, "\"v2\"")).await;
+        mount_resume(&mock_server, partial_content(RECEIVED, "\"v2\"")).await;
+
+        let service = create_resuming_service(&mock_server.uri());
+        let content = service.download_file("/Documents/report.pdf").await.unwrap();
+
+        // Nothing of the old version is mixed into the new one
+        assert_eq!(content, changed);
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_mismatched_content_range_restarts_download() {
+        let mock_server = MockServer::start().await;
+        mount_interrupted_download(&mock_server, full_content(CONTENT, "\"v1\"")).await;
+        // The range starts before the bytes that are missing
+        mount_resume(&mock_server, partial_content(RECEIVED - 4, "\"v1\"")).await;
+
+        let service = create_resuming_service(&mock_server.uri());
+        let content = service.download_file("/Documents/report.pdf").await.unwrap();
+
+        assert_eq!(content, CONTENT);
+        assert_eq!(mock_server.received_requests().await.unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use crate::services::webdav::service::{
+        server_root_url, server_type_from_headers, server_type_from_server_info, server_type_from_status,
+    };
+    use crate::services::webdav::{WebDAVConfig, WebDAVService};
+    use reqwest::header::{HeaderMap, HeaderValue};
+    use serde_json::json;
+    use wiremock::{
+        matchers::{method, path},
+        Mock, MockServer, ResponseTemplate,
+    };
+
+    fn create_test_service(server_url: &str, server_type: Option<&str>) -> WebDAVService {
+        let config = WebDAVConfig {
+            server_url: server_url.to_string(),
+            username: "testuser".to_string(),
+            password: "testpass".to_string(),
+            watch_folders: vec!["/Documents".to_string()],
+            file_extensions: vec!["pdf".to_string()],
+            timeout_seconds: 30,
+            server_type: server_type.map(str::to_string),
+            tls: Default::default(),
+        };
+        WebDAVService::new(config).expect("Failed to create test service")
+    }
+
+    #[test]
+    fn test_server_root_url_strips_webdav_endpoints() {
+        assert_eq!(server_root_url("https://cloud.example.com/"), "https://cloud.example.com");
+        assert_eq!(server_root_url("https://cloud.example.com/nc/remote.php/dav/files/alice"), "https://cloud.example.com/nc");
+        assert_eq!(server_root_url("https://files.example.com/seafdav"), "https://files.example.com");
+    }
+
+    #[test]
+    fn test_server_type_from_status() {
+        assert_eq!(server_type_from_status(&json!({"installed": true, "productname": "Nextcloud"})), Some("nextcloud"));
+        assert_eq!(server_type_from_status(&json!({"installed": true, "productname": "ownCloud"})), Some("owncloud"));
+        assert_eq!(server_type_from_status(&json!({"installed": true, "productname": ""})), Some("owncloud"));
+        assert_eq!(server_type_from_status(&json!({"status": "ok"})), None);
+    }
+
+    #[test]
+    fn test_server_type_from_server_info() {
+        assert_eq!(server_type_from_server_info(&json!({"version": "11.0.3", "features": ["seafile-basic", "seafile-pro"]})), Some("seafile"));
+        assert_eq!(server_type_from_server_info(&json!({"version": "1.0"})), None);
+    }
+
+    #[test]
+    fn test_server_type_from_headers() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(server_type_from_headers(&headers), None);
+
+        headers.insert("server", HeaderValue::from_static("Apache/2.4.57"));
+        assert_eq!(server_type_from_headers(&headers), None);
+
+        headers.insert("x-nextcloud-well-known", HeaderValue::from_static("1"));
+        assert_eq!(server_type_from_headers(&headers), Some("nextcloud"));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("server", HeaderValue::from_static("SeafDAV/1.0"));
+        assert_eq!(server_type_from_headers(&headers), Some("seafile"));
+    }
+
+    #[tokio::test]
+    async fn test_detects_nextcloud_from_status_endpoint() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/status.php"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "installed": true,
+                "version": "28.0.1.1",
+                "productname": "Nextcloud"
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), None);
+        assert_eq!(service.get_server_type(), None);
+
+        assert_eq!(service.ensure_server_type().await.as_deref(), Some("nextcloud"));
+        assert_eq!(service.get_server_type().as_deref(), Some("nextcloud"));
+        assert_eq!(
+            service.get_url_for_path("/Documents"),
+            format!("{}/remote.php/dav/files/testuser/Documents", mock_server.uri())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_detects_seafile_from_server_info() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api2/server-info/"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "version": "11.0.3",
+                "features": ["seafile-basic"]
+            })))
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), None);
+        assert_eq!(service.detect_server_type().await.unwrap(), "seafile");
+    }
+
+    #[tokio::test]
+    async fn test_falls_back_to_generic() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("OPTIONS"))
+            .respond_with(ResponseTemplate::new(200).insert_header("dav", "1, 2"))
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), None);
+        assert_eq!(service.detect_server_type().await.unwrap(), "generic");
+    }
+
+    #[tokio::test]
+    async fn test_configured_type_wins_over_detection() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/status.php"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"installed": true, "productname": "Nextcloud"})))
+            .mount(&mock_server)
+            .await;
+
+        let service = create_test_service(&mock_server.uri(), Some("owncloud"));
+        assert_eq!(service.detect_server_type().await.unwrap(), "nextcloud");
+        assert_eq!(service.get_server_type().as_deref(), Some("owncloud"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_server_is_an_error() {
+        let service = create_test_service("http://127.0.0.1:1", None);
+        assert!(service.detect_server_type().await.is_err());
+        assert_eq!(service.ensure_server_type().await, None);
+    }
+}
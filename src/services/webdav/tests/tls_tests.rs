@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod tests {
+    use crate::models::{ClientCertificateConfig, WebDAVSourceConfig, WebDAVTlsConfig};
+    use crate::services::webdav::service::is_certificate_error;
+    use crate::services::webdav::config::SOURCE_TIMEOUT_SECONDS;
+    use crate::services::webdav::{WebDAVConfig, WebDAVService};
+    use std::fmt;
+
+    const TEST_CA: &str = "-----BEGIN CERTIFICATE-----
+MIIBiDCCAS+gAwIBAgIUHeXleUW513Ke6vQhU6AQej1UAhkwCgYIKoZIzj0EAwIw
+GTEXMBUGA1UEAwwOUmVhZHVyIFRlc3QgQ0EwIBcNMjYxMDE2MDQwMjM3WhgPMjEy
+NjA5MjIwNDAyMzdaMBkxFzAVBgNVBAMMDlJlYWR1ciBUZXN0IENBMFkwEwYHKoZI
+zj0CAQYIKoZIzj0DAQcDQgAEQMzKxaUgziLhjPjkm4zOn/MiA9zaLu3j9JgtWa/U
+6x1c7A8IlGvs2Q6mcTabGzMQE/oXJzYdD990v6V6yok6iaNTMFEwHQYDVR0OBBYE
+FPwP/vHRPE1yCAmsXFj2tbn5HCmdMB8GA1UdIwQYMBaAFPwP/vHRPE1yCAmsXFj2
+tbn5HCmdMA8GA1UdEwEB/wQFMAMBAf8wCgYIKoZIzj0EAwIDRwAwRAIgbKpsEwmd
+wUWIO6EQQV3Qrd1lVRKzHVay1o/3ccOqhrcCIDzMcKlK9JyyxS8fVmxB6D7fvqAW
+TSzHjq7Fzc7DCPlY
+-----END CERTIFICATE-----
+";
+
+    fn config_with_tls(tls: WebDAVTlsConfig) -> WebDAVConfig {
+        let mut config = WebDAVConfig::new(
+            "https://nextcloud.internal".to_string(),
+            "testuser".to_string(),
+            "testpass".to_string(),
+            vec!["/Documents".to_string()],
+            vec!["pdf".to_string()],
+        );
+        config.tls = tls;
+        config
+    }
+
+    #[test]
+    fn test_tls_options_are_off_unless_configured() {
+        let config: WebDAVSourceConfig = serde_json::from_value(serde_json::json!({
+            "server_url": "https://nextcloud.internal",
+            "username": "testuser",
+            "password": "testpass",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "server_type": "nextcloud"
+        })).unwrap();
+        assert_eq!(config.tls, WebDAVTlsConfig::default());
+        assert!(!config.tls.accept_invalid_certs);
+
+        let config: WebDAVSourceConfig = serde_json::from_value(serde_json::json!({
+            "server_url": "https://nextcloud.internal",
+            "username": "testuser",
+            "password": "testpass",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "server_type": "nextcloud",
+            "accept_invalid_certs": true,
+            "ca_certificate": TEST_CA
+        })).unwrap();
+        assert!(config.tls.accept_invalid_certs);
+        assert_eq!(config.tls.ca_certificate.as_deref(), Some(TEST_CA));
+        // The options stay top-level fields of the source config
+        let value = serde_json::to_value(&config).unwrap();
+        assert_eq!(value["accept_invalid_certs"], true);
+
+        // Every service built for the source gets its TLS settings and the sync's timeout
+        let service_config = WebDAVConfig::from(config.clone());
+        assert_eq!(service_config.tls, config.tls);
+        assert_eq!(service_config.timeout_seconds, SOURCE_TIMEOUT_SECONDS);
+        assert_eq!(service_config.server_type.as_deref(), Some("nextcloud"));
+    }
+
+    #[test]
+    fn test_ca_certificate_from_pem() {
+        assert!(config_with_tls(WebDAVTlsConfig::default()).ca_certificates().unwrap().is_empty());
+
+        let inline = config_with_tls(WebDAVTlsConfig { ca_certificate: Some(TEST_CA.to_string()), ..Default::default() });
+        assert_eq!(inline.ca_certificates().unwrap().len(), 1);
+        assert!(WebDAVService::new(inline).is_ok());
+
+        let bundle = config_with_tls(WebDAVTlsConfig { ca_certificate: Some(TEST_CA.repeat(2)), ..Default::default() });
+        assert_eq!(bundle.ca_certificates().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_unusable_ca_certificate_fails_service_creation() {
+        // Paths are never read, whether or not the file exists
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ca.pem");
+        std::fs::write(&path, TEST_CA).unwrap();
+        for ca_certificate in [path.to_string_lossy().to_string(), "/dev/zero".to_string()] {
+            let config = config_with_tls(WebDAVTlsConfig { ca_certificate: Some(ca_certificate), ..Default::default() });
+            let error = WebDAVService::new(config).err().expect("a file path should be an error");
+            assert!(error.to_string().contains("not a file path"), "{}", error);
+        }
+
+        let garbage = config_with_tls(WebDAVTlsConfig {
+            ca_certificate: Some("-----BEGIN CERTIFICATE-----\nnot a certificate\n-----END CERTIFICATE-----\n".to_string()),
+            ..Default::default()
+        });
+        assert!(WebDAVService::new(garbage).is_err());
+    }
+
+    #[test]
+    fn test_unusable_client_certificate_fails_service_creation() {
+        let config = config_with_tls(WebDAVTlsConfig {
+            client_certificate: ClientCertificateConfig {
+                client_certificate: Some(TEST_CA.to_string()),
+                client_key: None,
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let error = WebDAVService::new(config).err().expect("a certificate without a key should be an error");
+        assert!(error.to_string().contains("client_key"), "{}", error);
+    }
+
+    #[test]
+    fn test_accept_invalid_certs_builds_a_client() {
+        let config = config_with_tls(WebDAVTlsConfig { accept_invalid_certs: true, ..Default::default() });
+        assert!(WebDAVService::new(config).is_ok());
+    }
+
+    #[derive(Debug)]
+    struct ChainedError(&'static str, Option<Box<ChainedError>>);
+
+    impl fmt::Display for ChainedError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str(self.0)
+        }
+    }
+
+    impl std::error::Error for ChainedError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            self.1.as_deref().map(|e| e as _)
+        }
+    }
+
+    #[test]
+    fn test_certificate_errors_are_found_in_the_cause_chain() {
+        let tls_failure = ChainedError(
+            "error sending request",
+            Some(Box::new(ChainedError(
+                "client error (Connect)",
+                Some(Box::new(ChainedError("error:0A000086:SSL routines:tls_post_process_server_certificate:certificate verify failed", None))),
+            ))),
+        );
+        assert!(is_certificate_error(&tls_failure));
+
+        let refused = ChainedError("error sending request", Some(Box::new(ChainedError("Connection refused (os error 111)", None))));
+        assert!(!is_certificate_error(&refused));
+    }
+}
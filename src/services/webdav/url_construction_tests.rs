@@ -12,6 +12,7 @@ fn create_nextcloud_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     WebDAVService::new(config).unwrap()
@@ -27,6 +28,7 @@ fn create_generic_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     WebDAVService::new(config).unwrap()
@@ -200,6 +202,7 @@ async fn test_different_usernames() {
             file_extensions: vec!["pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         };
         
         let service = WebDAVService::new(config).unwrap();
@@ -224,6 +227,7 @@ async fn test_fix_prevents_original_bug() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     let service = WebDAVService::new(config).unwrap();
     
@@ -266,6 +270,7 @@ async fn test_nextcloud_url_normalization_with_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     // This should not panic and should normalize the URL properly
@@ -286,6 +291,7 @@ async fn test_nextcloud_url_normalization_without_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let webdav_url = config.webdav_url();
@@ -305,6 +311,7 @@ async fn test_owncloud_url_normalization_with_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("owncloud".to_string()),
+        tls: Default::default(),
     };
     
     let webdav_url = config.webdav_url();
@@ -324,6 +331,7 @@ async fn test_owncloud_url_normalization_without_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("owncloud".to_string()),
+        tls: Default::default(),
     };
     
     let webdav_url = config.webdav_url();
@@ -343,6 +351,7 @@ async fn test_generic_webdav_url_normalization_with_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     let webdav_url = config.webdav_url();
@@ -361,6 +370,7 @@ async fn test_generic_webdav_url_normalization_without_trailing_slash() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     let webdav_url = config.webdav_url();
@@ -379,6 +389,7 @@ async fn test_connection_get_url_for_path_normalization() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -421,6 +432,7 @@ async fn test_url_normalization_edge_cases() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let webdav_url = config.webdav_url();
@@ -456,6 +468,7 @@ async fn test_all_server_types_url_consistency() {
                 file_extensions: vec!["pdf".to_string()],
                 timeout_seconds: 30,
                 server_type: server_type.clone(),
+                tls: Default::default(),
             };
             
             let webdav_url = config.webdav_url();
@@ -484,6 +497,7 @@ async fn test_service_download_file_url_construction() {
         file_extensions: vec!["png".to_string(), "pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -548,6 +562,7 @@ async fn test_file_fetch_url_construction_with_convert_to_relative_path() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -601,6 +616,7 @@ async fn test_file_fetch_real_world_error_scenario() {
         file_extensions: vec!["png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -676,6 +692,7 @@ async fn test_file_fetch_different_server_types() {
             file_extensions: vec!["pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some(server_type.to_string()),
+            tls: Default::default(),
         };
         
         let service = WebDAVService::new(config).unwrap();
@@ -721,6 +738,7 @@ async fn test_webdav_service_methods_use_correct_url_construction() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).unwrap();
@@ -751,4 +769,99 @@ async fn test_webdav_service_methods_use_correct_url_construction() {
     }
 }
 
+
+fn create_service_for(server_url: &str, server_type: &str) -> WebDAVService {
+    let config = WebDAVConfig {
+        server_url: server_url.to_string(),
+        username: "testuser".to_string(),
+        password: "testpass".to_string(),
+        watch_folders: vec!["/My Library/Scans".to_string()],
+        file_extensions: vec!["pdf".to_string()],
+        timeout_seconds: 30,
+        server_type: Some(server_type.to_string()),
+        tls: Default::default(),
+    };
+
+    WebDAVService::new(config).unwrap()
+}
+
+#[tokio::test]
+async fn test_seafile_url_construction() {
+    let service = create_service_for("https://seafile.example.com/", "seafile");
+
+    assert_eq!(service.get_config().webdav_url(), "https://seafile.example.com/seafdav");
+    assert_eq!(
+        service.get_url_for_path("/My Library/Scans"),
+        "https://seafile.example.com/seafdav/My Library/Scans"
+    );
+    assert_eq!(service.href_to_relative_path("/seafdav/My Library/Scans/a.pdf"), "/My Library/Scans/a.pdf");
+    assert_eq!(service.convert_to_relative_path("/seafdav"), "/");
+    assert!(service.get_config().webdav_fallback_urls().is_empty());
+
+    // An explicit /seafdav in the server URL is not doubled
+    let service = create_service_for("https://seafile.example.com/seafdav", "seafile");
+    assert_eq!(service.get_config().webdav_url(), "https://seafile.example.com/seafdav");
+}
+
+#[tokio::test]
+async fn test_apache_uses_configured_root_path() {
+    let service = create_service_for("https://files.example.com/dav/archive/", "apache");
+
+    assert_eq!(service.get_config().webdav_url(), "https://files.example.com/dav/archive");
+    assert_eq!(service.get_config().webdav_root_path(), "/dav/archive");
+    assert_eq!(service.get_url_for_path("/Scans"), "https://files.example.com/dav/archive/Scans");
+    assert_eq!(service.href_to_relative_path("/dav/archive/Scans/a.pdf"), "/Scans/a.pdf");
+    assert_eq!(service.convert_to_relative_path("/dav/archive/Scans"), "/Scans");
+    // Only whole path segments match the root
+    assert_eq!(service.href_to_relative_path("/dav/archived/a.pdf"), "/dav/archived/a.pdf");
+}
+
+#[tokio::test]
+async fn test_generic_strips_root_path_from_server_url() {
+    let service = create_service_for("https://files.example.com/shares/dav", "generic");
+    assert_eq!(service.href_to_relative_path("/shares/dav/Scans/a.pdf"), "/Scans/a.pdf");
+
+    // Without a path in the server URL the legacy /webdav prefix is still removed
+    let service = create_service_for("https://files.example.com", "generic");
+    assert_eq!(service.href_to_relative_path("/webdav/Scans/a.pdf"), "/Scans/a.pdf");
+}
+
+#[tokio::test]
+async fn test_recursive_etag_propagation_by_server_type() {
+    assert_eq!(create_service_for("https://cloud.example.com", "nextcloud").propagates_recursive_etags(), Some(true));
+    assert_eq!(create_service_for("https://cloud.example.com", "owncloud").propagates_recursive_etags(), Some(true));
+    assert_eq!(create_service_for("https://seafile.example.com", "seafile").propagates_recursive_etags(), Some(false));
+    assert_eq!(create_service_for("https://files.example.com/dav", "apache").propagates_recursive_etags(), None);
+}
+
+#[tokio::test]
+async fn test_directory_and_file_urls_normalize_slashes() {
+    let service = create_nextcloud_webdav_service();
+    let base = "https://nas.example.com/remote.php/dav/files/testuser";
+
+    for path in ["Documents/Scans", "/Documents/Scans", "Documents/Scans/", "/Documents/Scans/", "//Documents/Scans//"] {
+        assert_eq!(service.get_url_for_path(path), format!("{}/Documents/Scans", base), "file URL for {:?}", path);
+        assert_eq!(service.get_directory_url(path), format!("{}/Documents/Scans/", base), "directory URL for {:?}", path);
+    }
+    for path in ["", "/", "//"] {
+        assert_eq!(service.get_url_for_path(path), base, "file URL for {:?}", path);
+        assert_eq!(service.get_directory_url(path), format!("{}/", base), "directory URL for {:?}", path);
+    }
+
+    let service = create_service_for("https://files.example.com/dav/archive/", "apache");
+    assert_eq!(service.get_directory_url("Scans"), "https://files.example.com/dav/archive/Scans/");
+    assert_eq!(service.get_directory_url("/"), "https://files.example.com/dav/archive/");
+    assert_eq!(service.get_url_for_path("/Scans/a.pdf/"), "https://files.example.com/dav/archive/Scans/a.pdf");
+}
+
+#[test]
+fn test_toggle_trailing_slash() {
+    use super::super::service::toggle_trailing_slash;
+
+    assert_eq!(toggle_trailing_slash("https://nas.example.com/dav/Scans/"), "https://nas.example.com/dav/Scans");
+    assert_eq!(toggle_trailing_slash("https://nas.example.com/dav/Scans"), "https://nas.example.com/dav/Scans/");
+    assert_eq!(toggle_trailing_slash("https://nas.example.com/dav/Scans//"), "https://nas.example.com/dav/Scans");
+}
+
+
 }
\ No newline at end of file
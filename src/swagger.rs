@@ -6,26 +6,30 @@ use std::sync::Arc;
 
 use crate::{
     models::{
-        CreateUser, LoginRequest, LoginResponse, UserResponse, UpdateUser,
+        CreateUser, LoginRequest, LoginResponse, ImpersonationResponse, UserResponse, UpdateUser,
+        TwoFactorChallengeResponse, TwoFactorSetupResponse, TwoFactorCodeRequest,
+        TwoFactorVerifyResponse, TwoFactorLoginRequest, ForgotPasswordRequest, ResetPasswordRequest,
         DocumentResponse, SearchRequest, SearchResponse, EnhancedDocumentResponse,
-        SettingsResponse, UpdateSettings, SearchMode, SearchSnippet, HighlightRange,
+        SettingsResponse, UpdateSettings, OcrOverrides, SearchMode, SearchSort, FilterMatch, SearchSnippet, HighlightRange,
         FacetItem, SearchFacetsResponse, Notification, NotificationSummary, CreateNotification,
         Source, SourceResponse, CreateSource, UpdateSource, SourceWithStats,
-        WebDAVSourceConfig, LocalFolderSourceConfig, S3SourceConfig,
-        WebDAVCrawlEstimate, WebDAVTestConnection, WebDAVConnectionResult, WebDAVSyncStatus,
+        WebDAVSourceConfig, LocalFolderSourceConfig, S3SourceConfig, SmbSourceConfig, ImapSourceConfig, ImapTlsMode, WebDAVTlsConfig, ClientCertificateConfig, SourceSyncRun, SyncRunOutcome, OrphanedDirectoryCleanup, MissingSourceDocument, SourceDocumentStats, MissingDocumentAction, ResolveMissingDocumentsRequest, ResolveMissingDocumentsResponse, SourceCleanupQuery, SourceSyncAbortQuery, SourceSyncAbortResult, SourceSyncMode, SourceSyncQuery, SourceSyncStarted, SourceValidationReport,
+        WebDAVCrawlEstimate, WebDAVTestConnection, WebDAVConnectionResult, SourceConnectionTestResult, WebDAVSyncStatus,
         ProcessedImage, CreateProcessedImage, IgnoredFileResponse, IgnoredFilesQuery,
         DocumentListResponse, DocumentOcrResponse, DocumentOperationResponse,
         BulkDeleteResponse, PaginationInfo, DocumentDuplicatesResponse
     },
     routes::{
         metrics::{
-            SystemMetrics, DatabaseMetrics, OcrMetrics, DocumentMetrics, UserMetrics, GeneralSystemMetrics
+            SystemMetrics, DatabaseMetrics, OcrMetrics, DocumentMetrics, UserMetrics, GeneralSystemMetrics, DerivedAssetCacheMetrics
         },
         labels::{
-            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, BulkUpdateRequest as LabelBulkUpdateRequest
+            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, BulkUpdateRequest as LabelBulkUpdateRequest,
+            ApplyLabelToSearchRequest, ApplyLabelToSearchResponse
         },
         documents::BulkDeleteRequest
     },
+    ocr::preprocessing::{PreprocessingConfig, PreprocessingPreset, PreprocessingStep},
     AppState,
 };
 
@@ -36,8 +40,15 @@ use crate::{
         crate::routes::auth::register,
         crate::routes::auth::login,
         crate::routes::auth::me,
+        crate::routes::auth::stop_impersonation,
         crate::routes::auth::oidc_login,
         crate::routes::auth::oidc_callback,
+        crate::routes::auth::setup_two_factor,
+        crate::routes::auth::verify_two_factor,
+        crate::routes::auth::disable_two_factor,
+        crate::routes::auth::two_factor_login,
+        crate::routes::auth::forgot_password,
+        crate::routes::auth::reset_password,
         // Document endpoints
         crate::routes::documents::crud::upload_document,
         crate::routes::documents::crud::list_documents,
@@ -46,16 +57,54 @@ use crate::{
         crate::routes::documents::bulk::bulk_delete_documents,
         crate::routes::documents::crud::download_document,
         crate::routes::documents::crud::view_document,
+        crate::routes::documents::crud::export_document_content,
         crate::routes::documents::debug::get_document_thumbnail,
+        crate::routes::documents::pdf::rotate_document,
         crate::routes::documents::ocr::get_document_ocr,
+        crate::routes::documents::ocr::correct_document_ocr,
+        crate::routes::documents::ocr::get_document_ocr_boxes,
         crate::routes::documents::debug::get_processed_image,
+        crate::routes::documents::debug::get_ocr_debug_images,
+        crate::routes::documents::debug::get_ocr_debug_processed_image,
         crate::routes::documents::ocr::retry_ocr,
+        crate::routes::documents::ocr::retry_failed_ocr,
+        crate::routes::documents::ocr::reprocess_ocr,
         crate::routes::documents::debug::get_document_debug_info,
+        crate::routes::documents::debug::get_document_history,
         crate::routes::documents::failed::get_failed_ocr_documents,
         crate::routes::documents::failed::view_failed_document,
         crate::routes::documents::bulk::delete_low_confidence_documents,
         crate::routes::documents::bulk::delete_failed_ocr_documents,
+        crate::routes::documents::bulk::bulk_assign_source,
+        crate::routes::documents::bulk::import_document_metadata,
+        crate::routes::documents::bulk::apply_retention_cleanup,
         crate::routes::documents::crud::get_user_duplicates,
+        crate::routes::documents::crud::get_related_documents,
+        crate::routes::documents::trash::list_trash,
+        crate::routes::documents::trash::restore_document,
+        crate::routes::documents::trash::purge_trash,
+        crate::routes::documents::pdf::split_document,
+        crate::routes::documents::pdf::merge_documents,
+        crate::routes::documents::pdf::download_searchable_pdf,
+        crate::routes::documents::versions::list_document_versions,
+        crate::routes::documents::versions::get_document_version,
+        crate::routes::documents::versions::download_document_version,
+        crate::routes::documents::shares::create_document_share,
+        crate::routes::documents::shares::list_document_shares,
+        crate::routes::documents::shares::delete_document_share,
+        // Shared document endpoints
+        crate::routes::shared::get_shared_document,
+        crate::routes::shared::download_shared_document,
+        crate::routes::documents::maintenance::start_reingest,
+        crate::routes::documents::maintenance::get_reingest_run,
+        crate::routes::documents::maintenance::start_backfill,
+        crate::routes::documents::maintenance::get_backfill_run,
+        // Admin maintenance endpoints
+        crate::routes::admin::start_reindex,
+        crate::routes::admin::start_vacuum,
+        crate::routes::admin::get_maintenance_run,
+        crate::routes::documents::maintenance::redownload_document,
+        crate::routes::documents::maintenance::bulk_redownload_documents,
         // Labels endpoints
         crate::routes::labels::get_labels,
         crate::routes::labels::create_label,
@@ -67,6 +116,7 @@ use crate::{
         crate::routes::labels::add_document_label,
         crate::routes::labels::remove_document_label,
         crate::routes::labels::bulk_update_document_labels,
+        crate::routes::labels::apply_label_to_search,
         // Search endpoints
         crate::routes::search::search_documents,
         crate::routes::search::enhanced_search_documents,
@@ -74,12 +124,14 @@ use crate::{
         // Settings endpoints
         crate::routes::settings::get_settings,
         crate::routes::settings::update_settings,
+        crate::routes::settings::get_ocr_languages,
         // User endpoints
         crate::routes::users::list_users,
         crate::routes::users::create_user,
         crate::routes::users::get_user,
         crate::routes::users::update_user,
         crate::routes::users::delete_user,
+        crate::routes::users::impersonate_user,
         // Queue endpoints
         crate::routes::queue::get_queue_stats,
         crate::routes::queue::requeue_failed,
@@ -87,6 +139,15 @@ use crate::{
         crate::routes::queue::get_ocr_status,
         crate::routes::queue::pause_ocr_processing,
         crate::routes::queue::resume_ocr_processing,
+        crate::routes::queue::ocr_progress_websocket,
+        crate::routes::queue::list_admin_queue,
+        crate::routes::queue::set_queue_item_priority,
+        crate::routes::queue::cancel_queue_item,
+        crate::routes::queue::requeue_stale_items,
+        crate::routes::queue::list_dead_letter_queue,
+        crate::routes::queue::requeue_dead_letter_item,
+        // Batch job endpoints
+        crate::routes::jobs::get_batch_job,
         // Metrics endpoints
         crate::routes::metrics::get_system_metrics,
         crate::routes::prometheus_metrics::get_prometheus_metrics,
@@ -104,11 +165,19 @@ use crate::{
         crate::routes::sources::crud::delete_source,
         crate::routes::sources::sync::trigger_sync,
         crate::routes::sources::sync::stop_sync,
+        crate::routes::sources::sync::abort_sync,
         crate::routes::sources::sync::trigger_deep_scan,
+        crate::routes::sources::sync::cleanup_source_directories,
         crate::routes::sources::sync::sync_progress_websocket,
         crate::routes::sources::sync::get_sync_status,
+        crate::routes::sources::sync::get_sync_history,
+        crate::routes::sources::missing::list_missing_documents,
+        crate::routes::sources::documents::list_source_documents,
+        crate::routes::sources::documents::get_source_document_stats,
+        crate::routes::sources::missing::resolve_missing_documents,
         crate::routes::sources::validation::test_connection,
         crate::routes::sources::validation::validate_source,
+        crate::routes::sources::validation::get_source_validation_report,
         crate::routes::sources::estimation::estimate_crawl,
         crate::routes::sources::estimation::estimate_crawl_with_config,
         crate::routes::sources::validation::test_connection_with_config,
@@ -120,6 +189,9 @@ use crate::{
         crate::routes::webdav::estimate_webdav_crawl,
         // OCR endpoints
         crate::routes::ocr::get_available_languages,
+        crate::routes::ocr::preview_ocr,
+        crate::routes::ocr::run_ocr_benchmark,
+        crate::routes::ocr::get_ocr_diagnostics,
         crate::ocr::api::health_check,
         crate::ocr::api::perform_ocr,
         // Ignored files endpoints
@@ -130,29 +202,73 @@ use crate::{
         crate::routes::ignored_files::get_ignored_files_stats,
         // Health check
         crate::health_check,
+        crate::routes::health::readiness_check,
     ),
     components(
         schemas(
-            CreateUser, LoginRequest, LoginResponse, UserResponse, UpdateUser,
+            CreateUser, LoginRequest, LoginResponse, ImpersonationResponse, UserResponse, UpdateUser,
+            TwoFactorChallengeResponse, TwoFactorSetupResponse, TwoFactorCodeRequest,
+            TwoFactorVerifyResponse, TwoFactorLoginRequest, ForgotPasswordRequest, ResetPasswordRequest,
             DocumentResponse, SearchRequest, SearchResponse, EnhancedDocumentResponse,
-            SettingsResponse, UpdateSettings, SearchMode, SearchSnippet, HighlightRange,
+            SettingsResponse, UpdateSettings, OcrOverrides, PreprocessingConfig, PreprocessingPreset, PreprocessingStep, SearchMode, SearchSort, FilterMatch, SearchSnippet, HighlightRange,
             FacetItem, SearchFacetsResponse, Notification, NotificationSummary, CreateNotification,
             Source, SourceResponse, CreateSource, UpdateSource, SourceWithStats,
-            WebDAVSourceConfig, LocalFolderSourceConfig, S3SourceConfig,
-            WebDAVCrawlEstimate, WebDAVTestConnection, WebDAVConnectionResult, WebDAVSyncStatus,
+            WebDAVSourceConfig, LocalFolderSourceConfig, S3SourceConfig, SmbSourceConfig, ImapSourceConfig, ImapTlsMode, WebDAVTlsConfig, ClientCertificateConfig, SourceSyncRun, SyncRunOutcome, OrphanedDirectoryCleanup, MissingSourceDocument, SourceDocumentStats, MissingDocumentAction, ResolveMissingDocumentsRequest, ResolveMissingDocumentsResponse, SourceCleanupQuery, SourceSyncAbortQuery, SourceSyncAbortResult, SourceSyncMode, SourceSyncQuery, SourceSyncStarted, SourceValidationReport,
+            WebDAVCrawlEstimate, WebDAVTestConnection, WebDAVConnectionResult, SourceConnectionTestResult, WebDAVSyncStatus,
+            crate::services::processing_time_model::ProcessingTimeModel,
+            crate::services::processing_time_model::ProcessingTimeBucket,
+            crate::services::processing_time_model::FileKind,
             ProcessedImage, CreateProcessedImage, IgnoredFileResponse, IgnoredFilesQuery,
             crate::routes::ignored_files::BulkDeleteIgnoredFilesRequest,
             crate::routes::ignored_files::IgnoredFilesStats,
             crate::routes::ignored_files::SourceTypeCount,
-            SystemMetrics, DatabaseMetrics, OcrMetrics, DocumentMetrics, UserMetrics, GeneralSystemMetrics,
+            SystemMetrics, DatabaseMetrics, OcrMetrics, DocumentMetrics, UserMetrics, GeneralSystemMetrics, DerivedAssetCacheMetrics,
             // Labels schemas
-            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, LabelBulkUpdateRequest,
+            Label, CreateLabel, UpdateLabel, LabelAssignment, LabelQuery, LabelBulkUpdateRequest, ApplyLabelToSearchRequest, ApplyLabelToSearchResponse,
             // Document schemas
             BulkDeleteRequest, DocumentListResponse, DocumentOcrResponse, DocumentOperationResponse,
-            BulkDeleteResponse, PaginationInfo, DocumentDuplicatesResponse, crate::routes::documents::RetryOcrRequest,
+            BulkDeleteResponse, PaginationInfo, DocumentDuplicatesResponse, crate::routes::documents::RetryOcrRequest, crate::routes::documents::CorrectOcrTextRequest,
+            crate::routes::documents::ArchiveUploadResponse, crate::ingestion::archive_extraction::SkippedArchiveEntry,
+            crate::routes::documents::RetryFailedOcrRequest, crate::routes::documents::RetryFailedOcrResponse,
+            crate::routes::documents::ReprocessOcrRequest, crate::routes::documents::ReprocessOcrResponse,
+            crate::routes::documents::ReingestRequest, crate::models::DocumentRun<crate::models::ReingestDetails>, crate::models::DocumentRun<crate::models::BackfillDetails>, crate::models::DocumentRunOutcome, crate::models::ReingestDetails, crate::models::BackfillDetails,
+            crate::models::DocumentRun<crate::models::MaintenanceDetails>, crate::models::MaintenanceDetails, crate::models::MaintenanceStep, crate::models::MaintenanceOperation,
+            crate::models::BatchJob, crate::models::DocumentEvent, crate::models::DocumentEventType,
+            crate::routes::documents::BulkAssignSourceRequest, crate::routes::documents::BulkAssignSourceResponse,
+            crate::routes::documents::MetadataImportResponse,
+            crate::services::metadata_import_service::MetadataImportRowResult,
+            crate::services::metadata_import_service::MetadataImportRowStatus,
+            crate::routes::documents::RedownloadRequest, crate::routes::documents::RedownloadResult,
+            crate::routes::documents::BulkRedownloadRequest, crate::routes::documents::BulkRedownloadResponse,
+            crate::routes::documents::ContentFormat, crate::routes::documents::DocumentContentResponse,
+            crate::routes::documents::DocumentContentOcr,
+            crate::routes::documents::DeleteDocumentResponse,
+            crate::services::source_file_deletion_service::SourceDeletionBehavior,
+            crate::services::source_file_deletion_service::SourceFileDeletion,
+            crate::services::source_file_deletion_service::SourceFileDeletionStatus,
+            crate::routes::documents::RelatedDocumentResponse, crate::routes::documents::RelatedDocumentsResponse,
+            crate::routes::documents::RotateDocumentRequest, crate::routes::documents::RotateDocumentResponse, crate::models::DocumentOrientation,
+            crate::routes::documents::RetentionCleanupRequest, crate::routes::documents::RetentionCleanupResponse,
+            crate::models::RetentionCandidate,
+            crate::routes::documents::OcrBoxesResponse, crate::ocr::word_boxes::OcrPageWords, crate::ocr::word_boxes::OcrWord,
+            crate::routes::documents::OcrDebugImages,
+            crate::routes::documents::TrashedDocumentResponse, crate::routes::documents::PaginatedTrashResponse,
+            crate::routes::documents::PurgeTrashResponse,
+            crate::routes::documents::SplitDocumentRequest, crate::routes::documents::SplitDocumentResponse,
+            crate::routes::documents::MergeDocumentsRequest, crate::routes::documents::DuplicateMode,
+            crate::routes::documents::DocumentVersionInfo, crate::routes::documents::DocumentVersionsResponse,
+            crate::routes::documents::DocumentVersionDetailResponse,
+            crate::routes::documents::CreateDocumentShareRequest, crate::routes::documents::DocumentShareResponse,
+            crate::models::DocumentShare, crate::routes::shared::SharedDocumentResponse,
             // OCR schemas
-            crate::routes::ocr::AvailableLanguagesResponse, crate::routes::ocr::LanguageInfo,
+            crate::routes::ocr::AvailableLanguagesResponse, crate::routes::ocr::LanguageInfo, crate::routes::settings::OcrLanguagesResponse, crate::routes::ocr::OcrPreviewResponse,
+            crate::ocr::benchmark::OcrBenchmarkReport, crate::ocr::benchmark::OcrBenchmarkSampleResult,
+            crate::routes::ocr::OcrDiagnosticsResponse, crate::routes::ocr::OcrToolStatus, crate::routes::ocr::OcrLanguageStatus,
             crate::ocr::api::OcrHealthResponse, crate::ocr::api::OcrErrorResponse, crate::ocr::api::OcrRequest,
+            // Queue schemas
+            crate::routes::queue::AdminQueueResponse, crate::routes::queue::QueuePriorityRequest, crate::ocr::queue::AdminQueueItem,
+            // Health schemas
+            crate::routes::health::ReadinessResponse, crate::routes::health::DependencyCheck,
             // Sync progress schemas
             crate::services::sync_progress_tracker::SyncProgressInfo
         )
@@ -160,11 +276,14 @@ use crate::{
     tags(
         (name = "auth", description = "Authentication endpoints"),
         (name = "documents", description = "Document management endpoints"),
+        (name = "shared", description = "Document share links, usable without an account"),
         (name = "labels", description = "Document labeling and categorization endpoints"),
         (name = "search", description = "Document search endpoints"),
         (name = "settings", description = "User settings endpoints"),
         (name = "users", description = "User management endpoints"),
         (name = "queue", description = "OCR queue management endpoints"),
+        (name = "jobs", description = "Batch job progress endpoints"),
+        (name = "admin", description = "Database maintenance endpoints"),
         (name = "metrics", description = "System metrics and monitoring endpoints"),
         (name = "notifications", description = "User notification endpoints"),
         (name = "sources", description = "Document source management endpoints"),
@@ -280,6 +280,11 @@ impl TestContext {
             None
         };
         
+        let source_secrets = Arc::new(crate::services::source_secrets::SourceSecrets::new(&config.jwt_secret));
+        let login_rate_limiter = Arc::new(crate::monitoring::login_rate_limiter::LoginRateLimiter::from_config(&config));
+        let derived_asset_cache = Arc::new(crate::services::derived_asset_cache::DerivedAssetCache::from_config(db.clone(), &config));
+        let download_limiter = Arc::new(crate::scheduling::download_limiter::DownloadLimiter::new(config.source_download_max_concurrency));
+        
         let state = Arc::new(AppState { 
             db, 
             config,
@@ -289,17 +294,28 @@ impl TestContext {
             oidc_client: None,
             sync_progress_tracker: Arc::new(crate::services::sync_progress_tracker::SyncProgressTracker::new()),
             user_watch_service,
+            login_rate_limiter,
+            derived_asset_cache,
+            download_limiter,
+            source_secrets,
         });
         
         let app = Router::new()
+            .route("/api/health/ready", axum::routing::get(crate::routes::health::readiness_check))
+            .nest("/api/admin", crate::routes::admin::router())
             .nest("/api/auth", crate::routes::auth::router())
             .nest("/api/documents", crate::routes::documents::router())
             .nest("/api/search", crate::routes::search::router())
             .nest("/api/settings", crate::routes::settings::router())
+            .nest("/api/shared", crate::routes::shared::router())
+            .nest("/api/sources", crate::routes::sources::router())
             .nest("/api/users", crate::routes::users::router())
             .nest("/api/ignored-files", crate::routes::ignored_files::ignored_files_routes())
+            .nest("/api/jobs", crate::routes::jobs::router())
+            .nest("/api/ocr", crate::routes::ocr::router())
             .nest("/api/metrics", crate::routes::metrics::router())
             .nest("/metrics", crate::routes::prometheus_metrics::router())
+            .layer(axum::middleware::from_fn(crate::monitoring::request_id::request_id_middleware))
             .with_state(state.clone());
         
         Self { 
@@ -739,6 +755,7 @@ impl TestContext {
 #[cfg(any(test, feature = "test-utils"))]
 pub struct TestConfigBuilder {
     upload_path: String,
+    storage_layout: crate::services::file_service::StorageLayout,
     watch_folder: String,
     jwt_secret: String,
     concurrent_ocr_jobs: usize,
@@ -753,6 +770,7 @@ impl Default for TestConfigBuilder {
     fn default() -> Self {
         Self {
             upload_path: "./test-uploads".to_string(),
+            storage_layout: Default::default(),
             watch_folder: "./test-watch".to_string(),
             jwt_secret: "test-secret".to_string(),
             concurrent_ocr_jobs: 2,
@@ -771,6 +789,11 @@ impl TestConfigBuilder {
         self
     }
     
+    pub fn with_storage_layout(mut self, layout: crate::services::file_service::StorageLayout) -> Self {
+        self.storage_layout = layout;
+        self
+    }
+    
     pub fn with_watch_folder(mut self, folder: &str) -> Self {
         self.watch_folder = folder.to_string();
         self
@@ -781,6 +804,11 @@ impl TestConfigBuilder {
         self
     }
     
+    pub fn with_max_file_size_mb(mut self, max_file_size_mb: u64) -> Self {
+        self.max_file_size_mb = max_file_size_mb;
+        self
+    }
+
     pub fn with_oidc_enabled(mut self, enabled: bool) -> Self {
         self.oidc_enabled = enabled;
         self
@@ -792,6 +820,7 @@ impl TestConfigBuilder {
             server_address: "127.0.0.1:0".to_string(),
             jwt_secret: self.jwt_secret,
             upload_path: self.upload_path,
+            storage_layout: self.storage_layout,
             watch_folder: self.watch_folder,
             user_watch_base_dir: "./test-user-watch".to_string(),
             enable_per_user_watch: false,
@@ -803,6 +832,8 @@ impl TestConfigBuilder {
             // OCR Configuration
             ocr_language: "eng".to_string(),
             concurrent_ocr_jobs: self.concurrent_ocr_jobs,
+            ocr_db_pool_fraction: 0.5,
+            ocr_claim_batch_size: 1,
             ocr_timeout_seconds: self.ocr_timeout_seconds,
             max_file_size_mb: self.max_file_size_mb,
             
@@ -816,6 +847,37 @@ impl TestConfigBuilder {
             oidc_client_secret: None,
             oidc_issuer_url: None,
             oidc_redirect_uri: None,
+            
+            // Email Configuration
+            smtp_host: None,
+            smtp_port: 587,
+            smtp_username: None,
+            smtp_password: None,
+            smtp_from: "Readur <noreply@readur.local>".to_string(),
+            smtp_starttls: true,
+            public_base_url: None,
+            
+            // Login Rate Limiting
+            login_max_failed_attempts: 5,
+            login_attempt_window_seconds: 900,
+            login_lockout_seconds: 900,
+            trusted_proxies: crate::auth::TrustedProxy::parse_list(crate::auth::DEFAULT_TRUSTED_PROXIES).unwrap(),
+            
+            // Trash
+            trash_retention_days: 30,
+            document_version_retention: 10,
+            webdav_validation_interval_hours: 24,
+            strip_gps_metadata: false,
+            impersonation_read_only: true,
+            backfill_on_startup: false,
+            document_event_retention_days: 90,
+            source_sync_max_concurrency: 16,
+            source_download_max_concurrency: 8,
+            webdav_upload_chunk_size_mb: 10,
+            ocr_worker_threads: 2,
+            background_worker_threads: 2,
+            db_worker_threads: 2,
+            derived_asset_cache_max_mb: 2048,
         }
     }
 }
@@ -824,14 +886,17 @@ impl TestConfigBuilder {
 #[cfg(any(test, feature = "test-utils"))]
 pub fn create_test_app(state: Arc<AppState>) -> Router {
     Router::new()
+        .nest("/api/admin", crate::routes::admin::router())
         .nest("/api/auth", crate::routes::auth::router())
         .nest("/api/documents", crate::routes::documents::router())
         .nest("/api/search", crate::routes::search::router())
         .nest("/api/settings", crate::routes::settings::router())
         .nest("/api/users", crate::routes::users::router())
         .nest("/api/ignored-files", crate::routes::ignored_files::ignored_files_routes())
+        .nest("/api/jobs", crate::routes::jobs::router())
         .nest("/api/ocr", crate::routes::ocr::router())
         .nest("/api/queue", crate::routes::queue::router())
+        .layer(axum::middleware::from_fn(crate::monitoring::request_id::request_id_middleware))
         .with_state(state)
 }
 
@@ -1087,6 +1152,40 @@ impl TestAuthHelper {
     pub async fn make_authenticated_request(&self, method: &str, uri: &str, body: Option<serde_json::Value>, token: &str) -> Vec<u8> {
         self.make_request(method, uri, body, Some(token)).await
     }
+
+    /// Make an authenticated HTTP request, returning the status and the JSON response body
+    /// (`Null` when the body is empty or not JSON)
+    pub async fn send_authenticated_request(&self, method: &str, uri: &str, body: Option<serde_json::Value>, token: &str) -> (axum::http::StatusCode, serde_json::Value) {
+        let builder = axum::http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Authorization", format!("Bearer {}", token));
+        let request = match body {
+            Some(body) => builder
+                .header("Content-Type", "application/json")
+                .body(axum::body::Body::from(serde_json::to_vec(&body).unwrap())),
+            None => builder.body(axum::body::Body::empty()),
+        };
+
+        let response = self.app.clone().oneshot(request.unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+    }
+
+    /// Polls the document run at `uri`, e.g. a backfill or maintenance run, until it is no
+    /// longer running and returns it
+    pub async fn wait_for_document_run(&self, uri: &str, token: &str) -> serde_json::Value {
+        for _ in 0..300 {
+            let (status, run) = self.send_authenticated_request("GET", uri, None, token).await;
+            assert_eq!(status, axum::http::StatusCode::OK, "{}", run);
+            if run["outcome"] != "running" {
+                return run;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        panic!("run {} did not finish", uri)
+    }
     
     /// Make an HTTP request (internal helper)
     async fn make_request(&self, method: &str, uri: &str, body: Option<serde_json::Value>, token: Option<&str>) -> Vec<u8> {
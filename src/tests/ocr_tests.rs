@@ -61,6 +61,34 @@ mod tests {
         assert!(!ocr_service.is_image_file("noextension"));
     }
 
+    #[test]
+    fn test_is_image_file_sniffs_content() {
+        let ocr_service = OcrService::new();
+
+        let misnamed_jpeg = NamedTempFile::with_suffix(".pdf").unwrap();
+        fs::write(misnamed_jpeg.path(), [0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F']).unwrap();
+        assert!(ocr_service.is_image_file(misnamed_jpeg.path().to_str().unwrap()));
+
+        let text_named_png = NamedTempFile::with_suffix(".png").unwrap();
+        fs::write(text_named_png.path(), "not an image").unwrap();
+        assert!(!ocr_service.is_image_file(text_named_png.path().to_str().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_extract_text_routes_on_sniffed_type() {
+        let ocr_service = OcrService::new();
+
+        // Declared and named as a PDF, but the content is plain text
+        let temp_file = NamedTempFile::with_suffix(".pdf").unwrap();
+        fs::write(temp_file.path(), "Minutes of the annual meeting").unwrap();
+
+        let text = ocr_service
+            .extract_text(temp_file.path().to_str().unwrap(), "application/pdf")
+            .await
+            .unwrap();
+        assert_eq!(text, "Minutes of the annual meeting");
+    }
+
     #[tokio::test]
     async fn test_extract_text_from_plain_text() {
         let ocr_service = OcrService::new();
@@ -86,6 +86,7 @@ mod tests {
                 oidc_email: None,
                 auth_provider: AuthProvider::Local,
             },
+            impersonated_by: None,
         };
         
         let regular_user = crate::auth::AuthUser {
@@ -102,6 +103,7 @@ mod tests {
                 oidc_email: None,
                 auth_provider: AuthProvider::Local,
             },
+            impersonated_by: None,
         };
         
         // Test admin access
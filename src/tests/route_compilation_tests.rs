@@ -27,6 +27,7 @@ mod tests {
                 oidc_email: None,
                 auth_provider: AuthProvider::Local,
             },
+            impersonated_by: None,
         };
         
         // This function call would fail if there were compilation issues
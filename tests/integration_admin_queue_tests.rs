@@ -0,0 +1,269 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext};
+
+async fn call(ctx: &TestContext, token: &str, method: &str, uri: &str, body: Option<serde_json::Value>) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(body.map(|b| Body::from(b.to_string())).unwrap_or_else(Body::empty))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+/// Enqueues a new document of `user_id` and returns the queue entry's id
+async fn enqueue(ctx: &TestContext, user_id: Uuid, name: &str) -> Result<Uuid> {
+    let document = ctx.state.db.create_document(create_test_document_with_hash(user_id, name, format!("hash-{}", name))).await?;
+    Ok(ctx.state.queue_service.enqueue_document(document.id, 5, 1024).await?)
+}
+
+async fn queue_row(ctx: &TestContext, item_id: Uuid) -> Result<(String, i32)> {
+    Ok(sqlx::query_as("SELECT status, priority FROM ocr_queue WHERE id = $1")
+        .bind(item_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_admin_queue_actions_require_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let item_id = enqueue(&ctx, user.user_response.id, "mine.pdf").await?;
+
+        for (method, uri, body) in [
+            ("GET", "/api/queue/admin".to_string(), None),
+            ("POST", format!("/api/queue/admin/{}/priority", item_id), Some(serde_json::json!({ "priority": 50 }))),
+            ("POST", format!("/api/queue/admin/{}/cancel", item_id), None),
+            ("POST", "/api/queue/admin/requeue-stale".to_string(), None),
+            ("GET", "/api/queue/dead-letter".to_string(), None),
+            ("POST", format!("/api/queue/dead-letter/{}/requeue", item_id), None),
+        ] {
+            let (status, _) = call(&ctx, &token, method, &uri, body).await?;
+            assert_eq!(status, StatusCode::FORBIDDEN, "{} {}", method, uri);
+        }
+        assert_eq!(queue_row(&ctx, item_id).await?, ("pending".to_string(), 5));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_admin_lists_whole_queue_with_filters() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let alice = auth_helper.create_test_user().await;
+        let bob = auth_helper.create_test_user().await;
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        enqueue(&ctx, alice.user_response.id, "alice.pdf").await?;
+        let running = enqueue(&ctx, bob.user_response.id, "bob.pdf").await?;
+        sqlx::query("UPDATE ocr_queue SET status = 'processing', started_at = NOW(), worker_id = 'worker-7' WHERE id = $1")
+            .bind(running)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, all) = call(&ctx, &token, "GET", "/api/queue/admin", None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(all["total"], 2);
+
+        let (_, bobs) = call(&ctx, &token, "GET", &format!("/api/queue/admin?user_id={}", bob.user_response.id), None).await?;
+        assert_eq!(bobs["total"], 1);
+        let item = &bobs["items"][0];
+        assert_eq!(item["id"], running.to_string());
+        assert_eq!(item["username"], bob.username);
+        assert_eq!(item["filename"], "bob.pdf");
+        assert_eq!(item["status"], "processing");
+        assert_eq!(item["worker_id"], "worker-7");
+        assert!(item["age_seconds"].as_i64().unwrap() >= 0);
+
+        let (_, pending) = call(&ctx, &token, "GET", "/api/queue/admin?status=pending", None).await?;
+        assert_eq!(pending["total"], 1);
+        assert_eq!(pending["items"][0]["username"], alice.username);
+
+        let (status, _) = call(&ctx, &token, "GET", "/api/queue/admin?status=stuck", None).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_admin_bumps_priority_and_cancels_pending_jobs_only() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let waiting = enqueue(&ctx, user.user_response.id, "waiting.pdf").await?;
+        let running = enqueue(&ctx, user.user_response.id, "running.pdf").await?;
+        sqlx::query("UPDATE ocr_queue SET status = 'processing', started_at = NOW() WHERE id = $1")
+            .bind(running)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let priority = |id: Uuid| format!("/api/queue/admin/{}/priority", id);
+        let (status, _) = call(&ctx, &token, "POST", &priority(waiting), Some(serde_json::json!({ "priority": 50 }))).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(queue_row(&ctx, waiting).await?, ("pending".to_string(), 50));
+        assert_eq!(ctx.state.queue_service.dequeue().await?.map(|item| item.id), Some(waiting), "the bumped job runs next");
+        sqlx::query("UPDATE ocr_queue SET status = 'pending', started_at = NULL, worker_id = NULL WHERE id = $1")
+            .bind(waiting)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, _) = call(&ctx, &token, "POST", &priority(running), Some(serde_json::json!({ "priority": 50 }))).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call(&ctx, &token, "POST", &priority(waiting), Some(serde_json::json!({ "priority": 1001 }))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = call(&ctx, &token, "POST", &priority(Uuid::new_v4()), Some(serde_json::json!({ "priority": 5 }))).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let cancel = |id: Uuid| format!("/api/queue/admin/{}/cancel", id);
+        let (status, response) = call(&ctx, &token, "POST", &cancel(waiting), None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["status"], "cancelled");
+        assert_eq!(queue_row(&ctx, waiting).await?.0, "cancelled");
+
+        let (status, _) = call(&ctx, &token, "POST", &cancel(waiting), None).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call(&ctx, &token, "POST", &cancel(running), None).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(queue_row(&ctx, running).await?.0, "processing");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_admin_requeues_stale_jobs_on_demand() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let stale = enqueue(&ctx, user.user_response.id, "stale.pdf").await?;
+        let fresh = enqueue(&ctx, user.user_response.id, "fresh.pdf").await?;
+        for (id, started_minutes_ago) in [(stale, 30), (fresh, 1)] {
+            sqlx::query(
+                "UPDATE ocr_queue SET status = 'processing', worker_id = 'gone', started_at = NOW() - INTERVAL '1 minute' * $2 WHERE id = $1"
+            )
+            .bind(id)
+            .bind(started_minutes_ago)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        }
+
+        let (status, response) = call(&ctx, &token, "POST", "/api/queue/admin/requeue-stale", None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["requeued_count"], 1);
+        assert_eq!(queue_row(&ctx, stale).await?.0, "pending");
+        assert_eq!(queue_row(&ctx, fresh).await?.0, "processing");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_admin_requeues_dead_letter_jobs() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let exhausted = enqueue(&ctx, user.user_response.id, "exhausted.pdf").await?;
+        let waiting = enqueue(&ctx, user.user_response.id, "waiting.pdf").await?;
+        sqlx::query(
+            "UPDATE ocr_queue SET status = 'dead_letter', attempts = max_attempts, error_message = 'OCR timed out', dead_lettered_at = NOW() WHERE id = $1"
+        )
+            .bind(exhausted)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, dead) = call(&ctx, &token, "GET", "/api/queue/dead-letter", None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(dead["total"], 1);
+        assert_eq!(dead["items"][0]["id"], exhausted.to_string());
+        assert_eq!(dead["items"][0]["error_message"], "OCR timed out");
+        assert!(dead["items"][0]["dead_lettered_at"].is_string());
+
+        let (_, stats) = call(&ctx, &token, "GET", "/api/queue/stats", None).await?;
+        assert_eq!(stats["dead_letter"], 1);
+
+        // Dead-letter jobs are not picked up by workers
+        assert_eq!(ctx.state.queue_service.dequeue().await?.map(|item| item.id), Some(waiting));
+        assert_eq!(ctx.state.queue_service.dequeue().await?.map(|item| item.id), None);
+
+        let requeue = |id: Uuid| format!("/api/queue/dead-letter/{}/requeue", id);
+        let (status, body) = call(&ctx, &token, "POST", &requeue(exhausted), None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "pending");
+        let (attempts, error_message): (i32, Option<String>) = sqlx::query_as("SELECT attempts, error_message FROM ocr_queue WHERE id = $1")
+            .bind(exhausted)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!((attempts, error_message), (0, None));
+        assert_eq!(ctx.state.queue_service.dequeue().await?.map(|item| item.id), Some(exhausted));
+
+        let (status, _) = call(&ctx, &token, "POST", &requeue(exhausted), None).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let (status, _) = call(&ctx, &token, "POST", &requeue(Uuid::new_v4()), None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (_, dead) = call(&ctx, &token, "GET", "/api/queue/dead-letter", None).await?;
+        assert_eq!(dead["total"], 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,175 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use std::io::Write;
+use tower::ServiceExt;
+use zip::write::SimpleFileOptions;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+const BOUNDARY: &str = "archive-upload-test-boundary";
+
+const PDF: &[u8] = b"%PDF-1.4\n1 0 obj << /Type /Catalog >> endobj\ntrailer << /Root 1 0 R >>\n%%EOF\n";
+const EXE: &[u8] = b"MZ\x90\x00\x03\x00\x00\x00\x04\x00\x00\x00\xff\xff\x00\x00\xb8\x00\x00\x00";
+
+fn zip_archive(entries: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in entries {
+        if name.ends_with('/') {
+            writer.add_directory(*name, SimpleFileOptions::default()).unwrap();
+        } else {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content).unwrap();
+        }
+    }
+    writer.finish().unwrap().into_inner()
+}
+
+async fn upload(ctx: &TestContext, token: &str, filename: &str, data: &[u8], keep_archive: bool) -> Result<(StatusCode, serde_json::Value)> {
+    let mut body = Vec::new();
+    if keep_archive {
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"keep_archive\"\r\n\r\ntrue\r\n",
+            BOUNDARY
+        ).as_bytes());
+    }
+    body.extend_from_slice(format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/zip\r\n\r\n",
+        BOUNDARY, filename
+    ).as_bytes());
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn enable_archive_extraction(ctx: &TestContext, token: &str) -> Result<()> {
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/api/settings")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::json!({ "extract_archives": true }).to_string()))?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    Ok(())
+}
+
+async fn documents_of(ctx: &TestContext, user_id: uuid::Uuid) -> Result<Vec<(String, String, Vec<String>)>> {
+    Ok(sqlx::query_as("SELECT filename, mime_type, tags FROM documents WHERE user_id = $1 ORDER BY filename")
+        .bind(user_id)
+        .fetch_all(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_archive_is_extracted_into_tagged_documents() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        enable_archive_extraction(&ctx, &token).await?;
+
+        let archive = zip_archive(&[
+            ("invoice.pdf", PDF),
+            ("2024/", b""),
+            ("2024/march/notes.txt", b"Call the landlord on Monday\n"),
+            ("2024/setup.exe", EXE),
+            ("__MACOSX/2024/._notes.txt", b"fork"),
+        ]);
+        let (status, response) = upload(&ctx, &token, "scans.zip", &archive, false).await?;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", response);
+        assert_eq!(response["status"], "extracted");
+        assert_eq!(response["document_ids"].as_array().unwrap().len(), 2);
+        assert!(response["archive_document_id"].is_null());
+        let skipped = response["skipped"].as_array().unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0]["path"], "2024/setup.exe");
+
+        let documents = documents_of(&ctx, user.user_response.id).await?;
+        let names: Vec<(&str, &str)> = documents.iter().map(|(filename, mime_type, _)| (filename.as_str(), mime_type.as_str())).collect();
+        assert_eq!(names, vec![("invoice.pdf", "application/pdf"), ("notes.txt", "text/plain")]);
+        assert!(documents.iter().all(|(_, _, tags)| *tags == ["scans.zip"]));
+
+        // Uploading the same archive again only finds duplicates; keeping it stores the archive too
+        let (status, response) = upload(&ctx, &token, "scans.zip", &archive, true).await?;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", response);
+        assert!(response["document_ids"].as_array().unwrap().is_empty());
+        assert_eq!(response["duplicate_document_ids"].as_array().unwrap().len(), 2);
+        assert!(response["archive_document_id"].is_string());
+
+        let documents = documents_of(&ctx, user.user_response.id).await?;
+        assert_eq!(documents.len(), 3);
+        assert!(documents.iter().any(|(filename, mime_type, _)| filename == "scans.zip" && mime_type == "application/zip"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_archive_with_path_traversal_is_rejected() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        enable_archive_extraction(&ctx, &token).await?;
+
+        let archive = zip_archive(&[("invoice.pdf", PDF), ("../../etc/cron.d/evil.txt", b"* * * * * root true\n")]);
+        let (status, response) = upload(&ctx, &token, "evil.zip", &archive, false).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(response["error"].as_str().unwrap().contains("../../etc/cron.d/evil.txt"));
+        assert!(documents_of(&ctx, user.user_response.id).await?.is_empty());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_archive_is_not_extracted_unless_enabled() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        // Without the setting a ZIP is an ordinary upload, and zip is not an allowed type
+        let archive = zip_archive(&[("invoice.pdf", PDF)]);
+        let (status, _) = upload(&ctx, &token, "scans.zip", &archive, false).await?;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(documents_of(&ctx, user.user_response.id).await?.is_empty());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
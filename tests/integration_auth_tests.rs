@@ -1,5 +1,14 @@
 mod tests {
-    use readur::auth::{create_jwt, verify_jwt};
+    use axum::{
+        body::Body,
+        extract::ConnectInfo,
+        http::{HeaderMap, Request, StatusCode},
+    };
+    use readur::auth::{create_jwt, resolve_client_ip, verify_jwt, TrustedProxy};
+    use readur::services::password_reset_service::{generate_reset_token, hash_reset_token};
+    use readur::test_utils::{TestAuthHelper, TestContext};
+    use std::net::{IpAddr, SocketAddr};
+    use tower::ServiceExt;
     use readur::models::User;
     use chrono::Utc;
     use uuid::Uuid;
@@ -76,4 +85,132 @@ mod tests {
         let result = verify_jwt(empty_token, secret);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    fn headers(forwarded_for: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", forwarded_for.parse().unwrap());
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_trusted_proxy_parsing() {
+        let proxies = TrustedProxy::parse_list(" 10.0.0.0/8, 192.168.1.5 ,fd00::/8").unwrap();
+        assert_eq!(proxies.iter().map(ToString::to_string).collect::<Vec<_>>(), ["10.0.0.0/8", "192.168.1.5/32", "fd00::/8"]);
+        assert!(proxies[0].contains(ip("10.20.30.40")));
+        assert!(!proxies[0].contains(ip("11.0.0.1")));
+        assert!(proxies[1].contains(ip("::ffff:192.168.1.5")));
+        assert!(proxies[2].contains(ip("fd12::1")));
+
+        assert!(TrustedProxy::parse_list("").unwrap().is_empty());
+        assert!(TrustedProxy::parse_list("10.0.0.0/33").is_err());
+        assert!(TrustedProxy::parse_list("proxy.local").is_err());
+    }
+
+    #[test]
+    fn test_forwarded_for_is_read_from_the_right() {
+        let proxies = TrustedProxy::parse_list("127.0.0.1/32,10.0.0.0/8").unwrap();
+
+        // The client wrote the first entry; the proxy appended the address it saw
+        let client = resolve_client_ip(ip("127.0.0.1"), &headers("1.1.1.1, 203.0.113.7"), &proxies);
+        assert_eq!(client, ip("203.0.113.7"));
+
+        // Proxies of a chain are skipped
+        let client = resolve_client_ip(ip("127.0.0.1"), &headers("1.1.1.1, 203.0.113.7, 10.1.2.3"), &proxies);
+        assert_eq!(client, ip("203.0.113.7"));
+
+        // Untrusted peers can't forward anything
+        let client = resolve_client_ip(ip("192.168.1.10"), &headers("203.0.113.7"), &proxies);
+        assert_eq!(client, ip("192.168.1.10"));
+
+        let mut real_ip = HeaderMap::new();
+        real_ip.insert("x-real-ip", "203.0.113.9".parse().unwrap());
+        assert_eq!(resolve_client_ip(ip("10.0.0.2"), &real_ip, &proxies), ip("203.0.113.9"));
+    }
+
+    #[tokio::test]
+    async fn test_spoofed_forwarded_for_does_not_escape_lockout() {
+        let ctx = TestContext::new().await;
+
+        let result: anyhow::Result<()> = async {
+            let mut statuses = Vec::new();
+            // The test config allows 5 failed attempts; each guess claims another address
+            for attempt in 0..6 {
+                let body = serde_json::json!({ "username": format!("nobody{}", attempt), "password": "wrong" });
+                let mut request = Request::builder()
+                    .method("POST")
+                    .uri("/api/auth/login")
+                    .header("Content-Type", "application/json")
+                    .header("X-Forwarded-For", format!("198.51.100.{}, 203.0.113.7", attempt))
+                    .body(Body::from(body.to_string()))?;
+                request.extensions_mut().insert(ConnectInfo(SocketAddr::from(([127, 0, 0, 1], 40000))));
+                statuses.push(ctx.app.clone().oneshot(request).await?.status());
+            }
+
+            assert_eq!(statuses[..4], [StatusCode::UNAUTHORIZED; 4]);
+            assert_eq!(statuses[4..], [StatusCode::TOO_MANY_REQUESTS; 2]);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+        result.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_password_reset_uses_up_every_outstanding_token() {
+        let ctx = TestContext::new().await;
+
+        let result: anyhow::Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+
+            // Two links are outstanding, e.g. one leaked from an older email
+            let tokens = [generate_reset_token(), generate_reset_token()];
+            for token in &tokens {
+                sqlx::query("INSERT INTO password_reset_tokens (user_id, token_hash, expires_at) VALUES ($1, $2, NOW() + INTERVAL '30 minutes')")
+                    .bind(user.user_response.id)
+                    .bind(hash_reset_token(token))
+                    .execute(ctx.state.db.get_pool())
+                    .await?;
+            }
+
+            let post = |uri: &str, body: serde_json::Value| {
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap()
+            };
+            let reset = |token: &str, password: &str| {
+                post("/api/auth/reset-password", serde_json::json!({ "token": token, "new_password": password }))
+            };
+
+            let response = ctx.app.clone().oneshot(reset(&tokens[0], "first-new-password")).await?;
+            assert_eq!(response.status(), StatusCode::OK);
+            let response = ctx.app.clone().oneshot(reset(&tokens[1], "second-new-password")).await?;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let response = ctx.app.clone().oneshot(reset(&tokens[0], "second-new-password")).await?;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let login = |password: &str| {
+                post("/api/auth/login", serde_json::json!({ "username": user.username, "password": password }))
+            };
+            assert_eq!(ctx.app.clone().oneshot(login("first-new-password")).await?.status(), StatusCode::OK);
+            assert_eq!(ctx.app.clone().oneshot(login("second-new-password")).await?.status(), StatusCode::UNAUTHORIZED);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+        result.unwrap();
+    }
+}
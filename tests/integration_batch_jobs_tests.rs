@@ -0,0 +1,139 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use readur::{
+    ocr::{enhanced::EnhancedOcrService, queue::{OcrQueueService, BATCH_JOB_WINDOW}},
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+/// Creates a plain-text document with completed OCR whose file exists in `dir`
+async fn create_text_document(ctx: &TestContext, user_id: Uuid, dir: &tempfile::TempDir, name: &str) -> Result<Uuid> {
+    let path = dir.path().join(name);
+    std::fs::write(&path, format!("Contents of {}", name))?;
+
+    let mut document = create_test_document_with_hash(user_id, name, format!("hash-{}", name));
+    document.file_path = path.to_string_lossy().to_string();
+    document.mime_type = "text/plain".to_string();
+    document.ocr_text = Some("old text".to_string());
+    document.ocr_status = Some("completed".to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn get_job(auth_helper: &TestAuthHelper, token: &str, job_id: &str) -> Result<serde_json::Value> {
+    let (status, job) = auth_helper.send_authenticated_request("GET", &format!("/api/jobs/{}", job_id), None, token).await;
+    assert_eq!(status, StatusCode::OK);
+    Ok(job)
+}
+
+#[tokio::test]
+async fn test_reprocess_batch_job_feeds_queue_in_windows_and_tracks_results() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+        let dir = tempfile::tempdir()?;
+
+        let total = BATCH_JOB_WINDOW + 2;
+        for i in 0..total {
+            create_text_document(&ctx, user_id, &dir, &format!("doc-{:03}.txt", i)).await?;
+        }
+
+        let (status, response) = auth_helper.send_authenticated_request("POST", "/api/documents/ocr/reprocess", Some(serde_json::json!({})), &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["queued"], total);
+        let job_id = response["job_id"].as_str().unwrap().to_string();
+
+        let job = get_job(&auth_helper, &token, &job_id).await?;
+        assert_eq!(job["kind"], "reocr");
+        assert_eq!(job["status"], "running");
+        assert_eq!(job["total"], total);
+        assert_eq!(job["enqueued"], BATCH_JOB_WINDOW, "only one window is queued at a time");
+
+        // A restarted server picks the job up, but does not go past the window
+        let restarted = OcrQueueService::new(ctx.state.db.clone(), ctx.state.db.get_pool().clone(), 2);
+        assert_eq!(restarted.feed_running_batch_jobs().await?, 0);
+
+        // A result recorded before the restart frees a slot that the resumed job fills
+        let (queue_id, document_id): (Uuid, Uuid) = sqlx::query_as(
+            "SELECT id, document_id FROM ocr_queue WHERE batch_job_id = $1 ORDER BY document_id LIMIT 1"
+        )
+        .bind(Uuid::parse_str(&job_id)?)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        sqlx::query("UPDATE ocr_queue SET status = 'completed', completed_at = NOW() WHERE id = $1")
+            .bind(queue_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        ctx.state.db.record_batch_job_result(Uuid::parse_str(&job_id)?, document_id, true).await?;
+        assert_eq!(restarted.feed_running_batch_jobs().await?, 1);
+
+        // The queue reports a finished job back and refills the window itself
+        let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+        assert_eq!(item.batch_job_id, Some(Uuid::parse_str(&job_id)?));
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        ctx.state.queue_service.process_item(item, &ocr_service).await?;
+
+        let job = get_job(&auth_helper, &token, &job_id).await?;
+        assert_eq!(job["enqueued"], total);
+        assert_eq!(job["done"], 2);
+        assert_eq!(job["failed"], 0);
+
+        // Cancelled entries count as failed, and the job completes with the last of them
+        let pending: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM ocr_queue WHERE batch_job_id = $1 AND status = 'pending'")
+            .bind(Uuid::parse_str(&job_id)?)
+            .fetch_all(ctx.state.db.get_pool())
+            .await?;
+        for id in &pending {
+            ctx.state.queue_service.cancel_item(*id, "test").await?;
+        }
+
+        let job = get_job(&auth_helper, &token, &job_id).await?;
+        assert_eq!(job["status"], "completed");
+        assert_eq!(job["done"], 2);
+        assert_eq!(job["failed"], pending.len());
+        assert_eq!(job["done"].as_i64().unwrap() + job["failed"].as_i64().unwrap(), total);
+        assert!(!job["finished_at"].is_null());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_batch_job_is_only_visible_to_its_owner() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let owner_token = auth_helper.login_user(&owner.username, &owner.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let dir = tempfile::tempdir()?;
+        create_text_document(&ctx, owner.user_response.id, &dir, "only.txt").await?;
+
+        let (status, response) = auth_helper.send_authenticated_request("POST", "/api/documents/ocr/reprocess", Some(serde_json::json!({})), &owner_token).await;
+        assert_eq!(status, StatusCode::OK);
+        let job_id = response["job_id"].as_str().unwrap().to_string();
+
+        let (status, _) = auth_helper.send_authenticated_request("GET", &format!("/api/jobs/{}", job_id), None, &other_token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = auth_helper.send_authenticated_request("GET", &format!("/api/jobs/{}", Uuid::new_v4()), None, &owner_token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,137 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn assign_source(ctx: &TestContext, token: &str, document_ids: &[Uuid], source_id: Option<Uuid>) -> Result<(StatusCode, serde_json::Value)> {
+    let body = serde_json::json!({ "document_ids": document_ids, "source_id": source_id });
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents/bulk/assign-source")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body)?))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_local_source(ctx: &TestContext, user_id: Uuid, name: &str) -> Result<Uuid> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: name.to_string(),
+        source_type: SourceType::LocalFolder,
+        enabled: Some(false),
+        config: serde_json::json!({
+            "watch_folders": ["/tmp/readur-assign-source"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "recursive": true,
+            "follow_symlinks": false
+        }),
+    }).await?;
+    Ok(source.id)
+}
+
+async fn create_document(ctx: &TestContext, user_id: Uuid, name: &str) -> Result<Uuid> {
+    let document = create_test_document_with_hash(user_id, name, format!("hash-{}", name));
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn document_source(ctx: &TestContext, document_id: Uuid) -> Result<(Option<Uuid>, Option<String>)> {
+    Ok(sqlx::query_as("SELECT source_id, source_type FROM documents WHERE id = $1")
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_assign_and_detach_source() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let source_id = create_local_source(&ctx, user.user_response.id, "Scanner folder").await?;
+        let other_source_id = create_local_source(&ctx, other.user_response.id, "Other folder").await?;
+        let own = create_document(&ctx, user.user_response.id, "own.pdf").await?;
+        let foreign = create_document(&ctx, other.user_response.id, "foreign.pdf").await?;
+
+        let (status, response) = assign_source(&ctx, &token, &[own, foreign], Some(source_id)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["updated_documents"], serde_json::json!([own]));
+        assert_eq!(response["failed_documents"], serde_json::json!([foreign]));
+        assert_eq!(document_source(&ctx, own).await?, (Some(source_id), Some("local_folder".to_string())));
+        assert_eq!(document_source(&ctx, foreign).await?, (None, None));
+
+        // Another user's source is not visible
+        let (status, _) = assign_source(&ctx, &token, &[own], Some(other_source_id)).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, response) = assign_source(&ctx, &token, &[own], None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["updated_count"], 1);
+        assert_eq!(document_source(&ctx, own).await?, (None, None));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_admin_assigns_documents_only_to_a_source_of_their_owner() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let owner_source = create_local_source(&ctx, owner.user_response.id, "Owner folder").await?;
+        let other_source = create_local_source(&ctx, other.user_response.id, "Other folder").await?;
+        let admin_source = create_local_source(&ctx, admin.user_response.id, "Admin folder").await?;
+        let document = create_document(&ctx, owner.user_response.id, "owned.pdf").await?;
+
+        let (status, response) = assign_source(&ctx, &token, &[document], Some(other_source)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["failed_documents"], serde_json::json!([document]));
+
+        let (status, response) = assign_source(&ctx, &token, &[document], Some(admin_source)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["failed_documents"], serde_json::json!([document]));
+        assert_eq!(document_source(&ctx, document).await?, (None, None));
+
+        let (status, response) = assign_source(&ctx, &token, &[document], Some(owner_source)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["updated_documents"], serde_json::json!([document]));
+        assert_eq!(document_source(&ctx, document).await?.0, Some(owner_source));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,162 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use tempfile::TempDir;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ingestion::document_ingestion::{DeduplicationPolicy, DocumentIngestionService, IngestionResult},
+    metadata_extraction::{enrich_file_info, enrich_file_info_from_path, MetadataExtractionOptions},
+    models::{FileIngestionInfo, SearchRequest},
+    services::file_service::FileService,
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+fn pdf_created_at(creation_date: &str, title: &str) -> Vec<u8> {
+    format!(
+        "%PDF-1.4\n\
+1 0 obj << /Type /Catalog /Pages 2 0 R >> endobj\n\
+2 0 obj << /Type /Pages /Kids [3 0 R] /Count 1 >> endobj\n\
+3 0 obj << /Type /Page /Parent 2 0 R >> endobj\n\
+4 0 obj << /Title ({}) /Author (Records Office) /CreationDate ({}) >> endobj\n\
+trailer << /Root 1 0 R /Info 4 0 R >>\n%%EOF",
+        title, creation_date
+    )
+    .into_bytes()
+}
+
+fn file_info(name: &str, size: usize) -> FileIngestionInfo {
+    FileIngestionInfo {
+        relative_path: format!("/archive/{}", name),
+        full_path: format!("/archive/{}", name),
+        #[allow(deprecated)]
+        path: format!("/archive/{}", name),
+        name: name.to_string(),
+        size: size as i64,
+        mime_type: "application/pdf".to_string(),
+        last_modified: Some(Utc::now()),
+        etag: format!("etag-{}", name),
+        is_directory: false,
+        created_at: Some(Utc::now()),
+        permissions: None,
+        owner: Some("archivist".to_string()),
+        group: None,
+        metadata: None,
+    }
+}
+
+/// Runs a file through metadata extraction and ingestion the way a watch folder does
+async fn ingest(ingestion: &DocumentIngestionService, user_id: Uuid, name: &str, content: Vec<u8>) -> Result<Uuid> {
+    let mut info = file_info(name, content.len());
+    enrich_file_info(&mut info, &content, MetadataExtractionOptions::default()).await;
+    match ingestion.ingest_from_file_info(&info, content, user_id, DeduplicationPolicy::Skip, "watch_folder", None).await.unwrap() {
+        IngestionResult::Created(doc) => Ok(doc.id),
+        other => panic!("expected a new document, got {:?}", other),
+    }
+}
+
+fn search(captured_after: Option<DateTime<Utc>>, captured_before: Option<DateTime<Utc>>) -> SearchRequest {
+    SearchRequest {
+        query: String::new(),
+        tags: None,
+        labels: None,
+        filter_match: None,
+        mime_types: None,
+        limit: Some(10),
+        offset: Some(0),
+        include_snippets: Some(false),
+        snippet_length: None,
+        clean_snippets: None,
+        search_mode: None,
+        sort: None,
+        captured_after,
+        captured_before,
+    }
+}
+
+#[tokio::test]
+async fn test_metadata_from_stored_file_matches_metadata_from_content() -> Result<()> {
+    let dir = TempDir::new()?;
+    let content = pdf_created_at("D:20230101120000Z", "Lease");
+    let path = dir.path().join("lease.pdf");
+    std::fs::write(&path, &content)?;
+
+    let mut from_content = file_info("lease.pdf", content.len());
+    enrich_file_info(&mut from_content, &content, MetadataExtractionOptions::default()).await;
+    let mut from_path = file_info("lease.pdf", content.len());
+    enrich_file_info_from_path(&mut from_path, &path, MetadataExtractionOptions::default()).await?;
+    assert_eq!(from_path.metadata, from_content.metadata);
+    assert_eq!(from_path.metadata.as_ref().unwrap()["pdf_title"], "Lease");
+
+    // Empty files get what the extractors make of no content
+    let empty = dir.path().join("empty.pdf");
+    std::fs::write(&empty, b"")?;
+    let mut info = file_info("empty.pdf", 0);
+    enrich_file_info_from_path(&mut info, &empty, MetadataExtractionOptions::default()).await?;
+
+    assert!(enrich_file_info_from_path(&mut info, &dir.path().join("missing.pdf"), MetadataExtractionOptions::default()).await.is_err());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pdf_properties_populate_document_and_capture_date_filter() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let upload_dir = TempDir::new()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let ingestion = DocumentIngestionService::new(
+            ctx.state.db.clone(),
+            FileService::new(upload_dir.path().to_string_lossy().to_string()),
+        );
+        let old_id = ingest(&ingestion, user_id, "deed.pdf", pdf_created_at("D:19990412093000Z", "Property Deed")).await?;
+        let new_id = ingest(&ingestion, user_id, "lease.pdf", pdf_created_at("D:20230101120000Z", "Lease")).await?;
+        // No embedded date: falls back to the file's own timestamp
+        let undated_id = ingest(&ingestion, user_id, "notes.pdf", b"%PDF-1.4\n%%EOF".to_vec()).await?;
+
+        let request = Request::builder()
+            .method("GET")
+            .uri(format!("/api/documents/{}", old_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())?;
+        let response = ctx.app.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let document: serde_json::Value = serde_json::from_slice(&bytes)?;
+        assert_eq!(document["source_metadata"]["pdf_title"], "Property Deed");
+        assert_eq!(document["source_metadata"]["pdf_author"], "Records Office");
+        assert_eq!(document["source_metadata"]["page_count"], 1);
+        assert_eq!(document["source_metadata"]["owner"], "archivist");
+        let original_created_at: DateTime<Utc> = serde_json::from_value(document["original_created_at"].clone())?;
+        assert_eq!(original_created_at, "1999-04-12T09:30:00Z".parse::<DateTime<Utc>>()?);
+
+        let cutoff = "2000-01-01T00:00:00Z".parse::<DateTime<Utc>>()?;
+        let before: Vec<Uuid> = ctx.state.db.enhanced_search_documents(user_id, &search(None, Some(cutoff))).await?
+            .iter().map(|doc| doc.id).collect();
+        assert_eq!(before, vec![old_id]);
+
+        let after: Vec<Uuid> = ctx.state.db.enhanced_search_documents(user_id, &search(Some(cutoff), Some(Utc::now() - Duration::days(1)))).await?
+            .iter().map(|doc| doc.id).collect();
+        assert_eq!(after, vec![new_id]);
+
+        let recent: Vec<Uuid> = ctx.state.db.enhanced_search_documents(user_id, &search(Some(Utc::now() - Duration::days(1)), None)).await?
+            .iter().map(|doc| doc.id).collect();
+        assert_eq!(recent, vec![undated_id]);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
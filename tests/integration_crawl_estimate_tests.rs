@@ -0,0 +1,119 @@
+use anyhow::Result;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{method, path_regex},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use readur::{
+    services::{
+        processing_time_model::ProcessingTimeModel,
+        webdav::{WebDAVConfig, WebDAVService},
+    },
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+const DAV_ROOT: &str = "/remote.php/dav/files/testuser";
+const MB: i64 = 1024 * 1024;
+
+/// Mock server listing /Documents with a 5 MB scan and a 2 KB text file
+async fn start_webdav_server() -> MockServer {
+    let files = [("scan.pdf", 5 * MB, "application/pdf"), ("notes.txt", 2048, "text/plain")];
+    let responses: String = files.iter().map(|(name, size, mime_type)| format!(
+        "<d:response><d:href>{}/Documents/{}</d:href><d:propstat><d:prop>\
+         <d:displayname>{}</d:displayname>\
+         <d:getcontentlength>{}</d:getcontentlength>\
+         <d:getcontenttype>{}</d:getcontenttype>\
+         <d:getlastmodified>Sat, 26 Jul 2025 22:19:19 GMT</d:getlastmodified>\
+         <d:getetag>&quot;{}&quot;</d:getetag>\
+         <d:resourcetype></d:resourcetype>\
+         </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+        DAV_ROOT, name, name, size, mime_type, name
+    )).collect();
+    let body = format!("<?xml version=\"1.0\"?><d:multistatus xmlns:d=\"DAV:\">{}</d:multistatus>", responses);
+
+    let server = MockServer::start().await;
+    Mock::given(method("PROPFIND"))
+        .and(path_regex(format!("^{}/Documents/?$", DAV_ROOT)))
+        .respond_with(ResponseTemplate::new(207).set_body_string(body))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn webdav_service(server: &MockServer) -> WebDAVService {
+    WebDAVService::new(WebDAVConfig {
+        server_url: server.uri(),
+        username: "testuser".to_string(),
+        password: "secret".to_string(),
+        watch_folders: vec!["/Documents".to_string()],
+        file_extensions: vec!["pdf".to_string(), "txt".to_string()],
+        timeout_seconds: 30,
+        server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
+    }).expect("Failed to create WebDAV service")
+}
+
+async fn seed_processed_document(ctx: &TestContext, user_id: Uuid, mime_type: &str, file_size: i64, processing_time_ms: i32) -> Result<()> {
+    let mut document = create_test_document(user_id);
+    document.mime_type = mime_type.to_string();
+    document.file_size = file_size;
+    document.ocr_processing_time_ms = Some(processing_time_ms);
+    ctx.state.db.create_document(document).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_crawl_estimate_follows_processing_history() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user_id = auth_helper.create_test_user().await.user_response.id;
+        let server = start_webdav_server().await;
+        let service = webdav_service(&server);
+
+        // Without history every file is estimated at the default two seconds
+        let model = ProcessingTimeModel::load(&ctx.state.db).await?;
+        assert!(!model.based_on_history);
+        let estimate = service.estimate_crawl(&model).await?;
+        assert_eq!(estimate.total_files, 2);
+        assert_eq!(estimate.total_supported_files, 2);
+        assert!((estimate.total_size_mb - 5.0).abs() < 0.01, "{}", estimate.total_size_mb);
+        let default_ms = estimate.total_estimated_time_hours as f64 * 3_600_000.0;
+        assert!((4_000.0..9_000.0).contains(&default_ms), "{}", default_ms);
+
+        // Large scans take a minute on this instance, small text files a tenth of a second
+        seed_processed_document(&ctx, user_id, "application/pdf", 3 * MB, 50_000).await?;
+        seed_processed_document(&ctx, user_id, "application/pdf", 8 * MB, 70_000).await?;
+        seed_processed_document(&ctx, user_id, "text/plain", 4096, 100).await?;
+
+        let model = ProcessingTimeModel::load(&ctx.state.db).await?;
+        assert!(model.based_on_history);
+        assert_eq!(model.buckets.len(), 2);
+        assert_eq!(model.estimate_ms("application/pdf", 5 * MB), 60_000.0);
+        assert_eq!(model.estimate_ms("text/plain", 2048), 100.0);
+
+        let estimate = service.estimate_crawl(&model).await?;
+        let history_ms = estimate.total_estimated_time_hours as f64 * 3_600_000.0;
+        assert!((60_100.0..65_100.0).contains(&history_ms), "{}", history_ms);
+        assert_eq!(estimate.folders.len(), 1);
+        assert_eq!(estimate.folders[0].path, "/Documents");
+        assert!(estimate.processing_model.based_on_history);
+
+        let json = serde_json::to_value(&estimate)?;
+        let pdf_bucket = json["processing_model"]["buckets"].as_array().unwrap().iter()
+            .find(|b| b["kind"] == "pdf")
+            .cloned()
+            .unwrap();
+        assert_eq!(pdf_bucket["size_bucket"], "1mb_to_10mb");
+        assert_eq!(pdf_bucket["samples"], 2);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,110 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+async fn run_maintenance(auth_helper: &TestAuthHelper, token: &str, operation: &str) -> serde_json::Value {
+    let (status, run) = auth_helper.send_authenticated_request("POST", &format!("/api/admin/maintenance/{}", operation), None, token).await;
+    assert_eq!(status, StatusCode::ACCEPTED, "{}", run);
+    assert_eq!(run["operation"], operation);
+    let uri = format!("/api/admin/maintenance/{}", run["id"].as_str().unwrap());
+    auth_helper.wait_for_document_run(&uri, token).await
+}
+
+fn statements(run: &serde_json::Value) -> Vec<String> {
+    run["steps"].as_array().unwrap().iter()
+        .map(|step| step["statement"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_reindex_rebuilds_search_indexes_concurrently_and_reports_timing() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+        for _ in 0..5 {
+            ctx.state.db.create_document(create_test_document(admin.user_response.id)).await?;
+        }
+
+        let run = run_maintenance(&auth_helper, &token, "reindex").await;
+        assert_eq!(run["outcome"], "success", "{}", run);
+        assert!(run["finished_at"].is_string());
+
+        let statements = statements(&run);
+        assert!(statements.contains(&"REINDEX INDEX CONCURRENTLY idx_documents_content_search".to_string()), "{:?}", statements);
+        assert!(statements.contains(&"REINDEX INDEX CONCURRENTLY idx_documents_content_trgm".to_string()), "{:?}", statements);
+        assert_eq!(statements.last().map(String::as_str), Some("ANALYZE documents"));
+        assert_eq!(run["completed_steps"], statements.len());
+        assert!(run["steps"].as_array().unwrap().iter().all(|step| step["duration_ms"].is_i64()));
+
+        // The rebuilt indexes are valid and searches keep working
+        let invalid: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM pg_index i JOIN pg_class c ON c.oid = i.indrelid WHERE c.relname = 'documents' AND NOT i.indisvalid"
+        )
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(invalid, 0);
+        assert_eq!(auth_helper.send_authenticated_request("GET", "/api/search?query=test", None, &token).await.0, StatusCode::OK);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_vacuum_covers_documents_and_ocr_queue() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let run = run_maintenance(&auth_helper, &token, "vacuum").await;
+        assert_eq!(run["outcome"], "success", "{}", run);
+        assert_eq!(statements(&run), vec!["VACUUM (ANALYZE) documents", "VACUUM (ANALYZE) ocr_queue"]);
+        assert_eq!(run["completed_steps"], 2);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_maintenance_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        assert_eq!(auth_helper.send_authenticated_request("POST", "/api/admin/maintenance/reindex", None, &token).await.0, StatusCode::FORBIDDEN);
+        assert_eq!(auth_helper.send_authenticated_request("POST", "/api/admin/maintenance/vacuum", None, &token).await.0, StatusCode::FORBIDDEN);
+        let unknown = format!("/api/admin/maintenance/{}", uuid::Uuid::new_v4());
+        assert_eq!(auth_helper.send_authenticated_request("GET", &unknown, None, &token).await.0, StatusCode::FORBIDDEN);
+
+        let runs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM document_runs WHERE kind = 'maintenance'")
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(runs, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -225,12 +225,18 @@ mod tests {
             let search_request = SearchRequest {
                 query: "searchable".to_string(),
                 tags: None,
+                labels: None,
+                filter_match: None,
                 mime_types: None,
                 limit: Some(10),
                 offset: Some(0),
                 include_snippets: Some(true),
                 snippet_length: Some(200),
+                clean_snippets: None,
                 search_mode: None,
+                sort: None,
+                captured_after: None,
+                captured_before: None,
             };
 
             let result = db.search_documents(user.id, &search_request).await;
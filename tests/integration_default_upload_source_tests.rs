@@ -0,0 +1,283 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+const BOUNDARY: &str = "default-source-test-boundary";
+
+async fn save_settings(ctx: &TestContext, token: &str, settings: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/api/settings")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(settings.to_string()))?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn upload(ctx: &TestContext, token: &str, filename: &str, data: &[u8]) -> Result<(StatusCode, serde_json::Value)> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: text/plain\r\n\r\n",
+        BOUNDARY, filename
+    ).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_source(ctx: &TestContext, user_id: Uuid, source_type: SourceType, server_url: &str) -> Result<Uuid> {
+    let config = match source_type {
+        SourceType::WebDAV => serde_json::json!({
+            "server_url": server_url,
+            "username": "archive",
+            "password": "secret",
+            "watch_folders": ["/Inbox/"],
+            "file_extensions": ["txt"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "server_type": "generic"
+        }),
+        _ => serde_json::json!({
+            "watch_folders": ["/tmp/readur-inbox"],
+            "file_extensions": ["txt"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60
+        }),
+    };
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: format!("Inbox {}", Uuid::new_v4().simple()),
+        source_type,
+        enabled: Some(false),
+        config,
+    }).await?;
+    Ok(source.id)
+}
+
+async fn source_of(ctx: &TestContext, document_id: &str) -> Result<(Option<Uuid>, Option<String>)> {
+    Ok(sqlx::query_as("SELECT source_id, source_path FROM documents WHERE id = $1")
+        .bind(Uuid::parse_str(document_id)?)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_uploads_are_filed_under_the_default_source() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let server = MockServer::start().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let source_id = create_source(&ctx, user.user_response.id, SourceType::WebDAV, &server.uri()).await?;
+
+        // Without write-back nothing is sent to the source
+        Mock::given(method("PUT")).respond_with(ResponseTemplate::new(201)).expect(0).mount(&server).await;
+
+        let (status, body) = save_settings(&ctx, &token, serde_json::json!({ "default_source_id": source_id })).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["default_source_id"], source_id.to_string());
+        assert_eq!(body["default_source_write_back"], false);
+
+        let (status, body) = upload(&ctx, &token, "filed.txt", b"Filed under the inbox").await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["source_id"], source_id.to_string());
+        // It has no copy on the source, so it gets no path there that a deletion could reach
+        assert_eq!(source_of(&ctx, body["id"].as_str().unwrap()).await?, (Some(source_id), None));
+
+        // Clearing the default source files uploads under no source again
+        let (status, body) = save_settings(&ctx, &token, serde_json::json!({ "default_source_id": null })).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body["default_source_id"].is_null());
+
+        let (status, body) = upload(&ctx, &token, "unfiled.txt", b"Not filed anywhere").await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body["source_id"].is_null());
+        assert_eq!(source_of(&ctx, body["id"].as_str().unwrap()).await?.0, None);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    server.verify().await;
+    result
+}
+
+#[tokio::test]
+async fn test_uploads_are_written_back_to_the_default_source() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let server = MockServer::start().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let source_id = create_source(&ctx, user.user_response.id, SourceType::WebDAV, &server.uri()).await?;
+
+        Mock::given(method("PUT"))
+            .and(path("/Inbox/receipt.txt"))
+            .and(header("If-None-Match", "*"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/Inbox/locked.txt"))
+            .respond_with(ResponseTemplate::new(403))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let settings = serde_json::json!({ "default_source_id": source_id, "default_source_write_back": true });
+        let (status, body) = save_settings(&ctx, &token, settings).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+
+        let (status, body) = upload(&ctx, &token, "receipt.txt", b"Receipt for the new desk").await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body.get("write_back_error").is_none(), "{}", body);
+        // The document has the path a sync of the source finds the copy under
+        let (document_source, source_path) = source_of(&ctx, body["id"].as_str().unwrap()).await?;
+        assert_eq!(document_source, Some(source_id));
+        assert_eq!(source_path.as_deref(), Some("/Inbox/receipt.txt"));
+
+        // A failed write-back is reported but keeps the upload
+        let (status, body) = upload(&ctx, &token, "locked.txt", b"The source refuses this one").await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["status"], "success");
+        assert!(body["write_back_error"].as_str().unwrap().contains("403"), "{}", body);
+
+        let received = server.received_requests().await.unwrap();
+        let receipt = received.iter().find(|r| r.url.path() == "/Inbox/receipt.txt").unwrap();
+        assert_eq!(receipt.body, b"Receipt for the new desk");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    server.verify().await;
+    result
+}
+
+#[tokio::test]
+async fn test_write_back_never_replaces_or_escapes() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let server = MockServer::start().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let source_id = create_source(&ctx, user.user_response.id, SourceType::WebDAV, &server.uri()).await?;
+
+        // The source already has a file of this name and refuses to replace it
+        Mock::given(method("PUT"))
+            .and(path("/Inbox/report.txt"))
+            .and(header("If-None-Match", "*"))
+            .respond_with(ResponseTemplate::new(412))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/Inbox/report%20(1).txt"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("PUT"))
+            .and(path("/Inbox/escape.txt"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let settings = serde_json::json!({ "default_source_id": source_id, "default_source_write_back": true });
+        let (status, body) = save_settings(&ctx, &token, settings).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+
+        let (status, body) = upload(&ctx, &token, "report.txt", b"A second report").await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body.get("write_back_error").is_none(), "{}", body);
+        let (_, source_path) = source_of(&ctx, body["id"].as_str().unwrap()).await?;
+        assert_eq!(source_path.as_deref(), Some("/Inbox/report%20(1).txt"));
+
+        // Only the last component of the name is used
+        let (status, body) = upload(&ctx, &token, "../../escape.txt", b"Trying to leave the inbox").await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body.get("write_back_error").is_none(), "{}", body);
+        let (_, source_path) = source_of(&ctx, body["id"].as_str().unwrap()).await?;
+        assert_eq!(source_path.as_deref(), Some("/Inbox/escape.txt"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    server.verify().await;
+    result
+}
+
+#[tokio::test]
+async fn test_default_source_must_belong_to_the_user() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&other.username, &other.password).await;
+        let foreign_source = create_source(&ctx, owner.user_response.id, SourceType::WebDAV, "http://localhost:1").await?;
+        let local_source = create_source(&ctx, other.user_response.id, SourceType::LocalFolder, "").await?;
+
+        let (status, body) = save_settings(&ctx, &token, serde_json::json!({ "default_source_id": foreign_source })).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        let (status, _) = save_settings(&ctx, &token, serde_json::json!({ "default_source_id": Uuid::new_v4() })).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Local folders can be the default source, but uploads can't be written back to them
+        let (status, body) = save_settings(&ctx, &token, serde_json::json!({ "default_source_id": local_source })).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let (status, body) = save_settings(&ctx, &token, serde_json::json!({ "default_source_write_back": true })).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+
+        let settings = ctx.state.db.get_user_settings(other.user_response.id).await?.unwrap();
+        assert_eq!(settings.default_source_id, Some(local_source));
+        assert!(!settings.default_source_write_back);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
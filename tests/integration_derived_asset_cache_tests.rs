@@ -0,0 +1,58 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use readur::{
+    services::derived_asset_cache::{DerivedAssetCache, DerivedAssetKind},
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+#[tokio::test]
+async fn test_least_recently_used_files_are_evicted_over_the_cap() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+        let dir = tempfile::tempdir()?;
+        let cache = DerivedAssetCache::new(ctx.state.db.clone(), 2500);
+
+        // Three 1000-byte thumbnails, each used more recently than the last
+        let mut paths = Vec::new();
+        for name in ["old.pdf", "middle.pdf", "recent.pdf"] {
+            let document = ctx.state.db
+                .create_document(create_test_document_with_hash(user_id, name, Uuid::new_v4().to_string()))
+                .await?;
+            let path = dir.path().join(format!("{}_thumb.png", document.id));
+            tokio::fs::write(&path, vec![0u8; 1000]).await?;
+            cache.record_miss(DerivedAssetKind::PdfThumbnail, document.id, &path.to_string_lossy(), 1000).await;
+            paths.push((document.id, path));
+        }
+        // Serving the oldest one makes it the most recently used
+        let (first_id, first_path) = &paths[0];
+        cache.record_hit(DerivedAssetKind::PdfThumbnail, *first_id, &first_path.to_string_lossy(), 1000).await;
+
+        let summary = cache.evict_to_limit().await?;
+        assert_eq!(summary.evicted, 1);
+        assert_eq!(summary.freed_bytes, 1000);
+        assert!(paths[0].1.exists(), "recently served file should be kept");
+        assert!(!paths[1].1.exists(), "least recently used file should be evicted");
+        assert!(paths[2].1.exists());
+
+        let stats = cache.stats().await?;
+        assert_eq!(stats.entries, 2);
+        assert_eq!(stats.size_bytes, 2000);
+        assert_eq!((stats.hits, stats.misses), (1, 3));
+        assert_eq!(stats.hit_rate(), Some(0.25));
+
+        // Under the cap nothing more is evicted
+        assert_eq!(cache.evict_to_limit().await?.evicted, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
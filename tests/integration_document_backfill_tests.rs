@@ -0,0 +1,128 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+/// Creates a document the way an older version stored it: no file hash and no word count
+async fn create_legacy_document(
+    ctx: &TestContext,
+    user_id: Uuid,
+    dir: &tempfile::TempDir,
+    name: &str,
+    disk_content: Option<&str>,
+    ocr_text: Option<&str>,
+) -> Result<Uuid> {
+    let path = dir.path().join(name);
+    if let Some(content) = disk_content {
+        std::fs::write(&path, content)?;
+    }
+
+    let mut document = create_test_document(user_id);
+    document.file_path = path.to_string_lossy().to_string();
+    document.file_hash = None;
+    document.ocr_text = ocr_text.map(str::to_string);
+    document.ocr_word_count = None;
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn run_backfill(auth_helper: &TestAuthHelper, token: &str) -> serde_json::Value {
+    let (status, run) = auth_helper.send_authenticated_request("POST", "/api/documents/maintenance/backfill", None, token).await;
+    assert_eq!(status, StatusCode::ACCEPTED);
+    let uri = format!("/api/documents/maintenance/backfill/{}", run["id"].as_str().unwrap());
+    auth_helper.wait_for_document_run(&uri, token).await
+}
+
+async fn derived_fields(ctx: &TestContext, document_id: Uuid) -> Result<(Option<String>, Option<i32>)> {
+    Ok(sqlx::query_as("SELECT file_hash, ocr_word_count FROM documents WHERE id = $1")
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_backfill_fills_missing_hashes_and_word_counts() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+        let user_id = admin.user_response.id;
+        let dir = tempfile::tempdir()?;
+
+        let legacy = create_legacy_document(&ctx, user_id, &dir, "legacy.txt", Some("legacy content"), Some("four words of text")).await?;
+        let missing = create_legacy_document(&ctx, user_id, &dir, "missing.txt", None, Some("still counted")).await?;
+
+        // Same content as a document that already has its hash
+        let mut original = create_test_document(user_id);
+        original.file_hash = Some(format!("{:x}", Sha256::digest(b"shared content")));
+        ctx.state.db.create_document(original).await?;
+        let copy = create_legacy_document(&ctx, user_id, &dir, "copy.txt", Some("shared content"), None).await?;
+
+        let run = run_backfill(&auth_helper, &token).await;
+        assert_eq!(run["outcome"], "success");
+        assert_eq!(run["total_documents"], 3);
+        assert_eq!(run["checked"], 3);
+        assert_eq!(run["hashes_filled"], 1);
+        assert_eq!(run["word_counts_filled"], 2);
+        assert_eq!(run["missing"], 1);
+        assert_eq!(run["hash_conflicts"], 1);
+
+        assert_eq!(derived_fields(&ctx, legacy).await?, (Some(format!("{:x}", Sha256::digest(b"legacy content"))), Some(4)));
+        assert_eq!(derived_fields(&ctx, missing).await?, (None, Some(2)));
+        assert_eq!(derived_fields(&ctx, copy).await?, (None, None));
+
+        let issue: Option<String> = sqlx::query_scalar("SELECT issue FROM document_integrity_issues WHERE document_id = $1")
+            .bind(missing)
+            .fetch_optional(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(issue.as_deref(), Some("missing"));
+
+        // A second run only revisits what could not be filled and changes nothing
+        let rerun = run_backfill(&auth_helper, &token).await;
+        assert_eq!(rerun["outcome"], "success");
+        assert_eq!(rerun["total_documents"], 2);
+        assert_eq!(rerun["hashes_filled"], 0);
+        assert_eq!(rerun["word_counts_filled"], 0);
+        assert_eq!(derived_fields(&ctx, legacy).await?.1, Some(4));
+
+        // Backfilling never queues OCR
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue WHERE document_id = ANY($1)")
+            .bind(vec![legacy, missing, copy])
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(queued, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_backfill_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", "/api/documents/maintenance/backfill", None, &token).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,108 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+async fn get_content(ctx: &TestContext, token: &str, document_id: Uuid, format: &str) -> Result<(StatusCode, Option<String>, Vec<u8>)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/documents/{}/content?format={}", document_id, format))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let content_type = response
+        .headers()
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, content_type, bytes.to_vec()))
+}
+
+#[tokio::test]
+async fn test_export_document_content_as_text_and_json() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let mut document = create_test_document(user_id);
+        document.original_filename = "invoice.pdf".to_string();
+        document.content = Some("Embedded text layer".to_string());
+        document.ocr_text = Some("Scanned page text".to_string());
+        document.tags = vec!["finance".to_string()];
+        let document = ctx.state.db.create_document(document).await?;
+
+        let label_id: Uuid = sqlx::query_scalar("INSERT INTO labels (user_id, name) VALUES ($1, 'Tax') RETURNING id")
+            .bind(user_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        sqlx::query("INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2)")
+            .bind(document.id)
+            .bind(label_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, content_type, body) = get_content(&ctx, &token, document.id, "txt").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.unwrap().starts_with("text/plain"));
+        assert_eq!(String::from_utf8(body)?, "Embedded text layer\n\nScanned page text");
+
+        let (status, _, body) = get_content(&ctx, &token, document.id, "json").await?;
+        assert_eq!(status, StatusCode::OK);
+        let export: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(export["filename"], "invoice.pdf");
+        assert_eq!(export["mime_type"], "application/pdf");
+        assert_eq!(export["tags"], serde_json::json!(["finance"]));
+        assert_eq!(export["labels"], serde_json::json!(["Tax"]));
+        assert_eq!(export["ocr"]["word_count"], 150);
+        assert_eq!(export["ocr"]["status"], "completed");
+        assert_eq!(export["text"], "Embedded text layer\n\nScanned page text");
+
+        let (status, _, _) = get_content(&ctx, &token, document.id, "docx").await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_export_document_content_respects_ownership() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&other.username, &other.password).await;
+
+        let document = ctx.state.db.create_document(create_test_document(owner.user_response.id)).await?;
+
+        let (status, _, _) = get_content(&ctx, &token, document.id, "txt").await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,134 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
+    models::DocumentEventType,
+    ocr::enhanced::EnhancedOcrService,
+    services::file_service::FileService,
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+async fn get_json(ctx: &TestContext, token: &str, uri: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn upload(ctx: &TestContext, dir: &tempfile::TempDir, user_id: Uuid, name: &str, content: &[u8]) -> Result<Uuid> {
+    let ingestion = DocumentIngestionService::new(
+        ctx.state.db.clone(),
+        FileService::new(dir.path().to_string_lossy().to_string()),
+    );
+    match ingestion.ingest_upload(name, content.to_vec(), "text/plain", user_id).await.unwrap() {
+        IngestionResult::Created(document) => Ok(document.id),
+        other => panic!("expected a new document, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_history_records_upload_queue_and_ocr_transitions() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let dir = tempfile::tempdir()?;
+
+        let document_id = upload(&ctx, &dir, user.user_response.id, "notes.txt", b"Invoice 4711 total 12.50").await?;
+        ctx.state.queue_service.enqueue_document(document_id, 5, 24).await?;
+        let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+        let worker_id = item.worker_id.clone();
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        ctx.state.queue_service.process_item(item, &ocr_service).await?;
+
+        let (status, history) = get_json(&ctx, &token, &format!("/api/documents/{}/history", document_id)).await?;
+        assert_eq!(status, StatusCode::OK);
+        let events = history.as_array().unwrap();
+        let types: Vec<&str> = events.iter().map(|event| event["event_type"].as_str().unwrap()).collect();
+        assert_eq!(types, vec!["uploaded", "queued", "ocr_started", "ocr_completed"]);
+        assert_eq!(events[0]["detail"], "direct_upload");
+        assert_eq!(events[1]["detail"], "priority 5");
+        assert!(events[1]["worker_id"].is_null(), "enqueueing is not done by a worker");
+        assert_eq!(events[2]["worker_id"].as_str(), worker_id.as_deref());
+        assert_eq!(events[3]["worker_id"].as_str(), worker_id.as_deref());
+
+        // The debug view carries the same timeline
+        let (status, debug) = get_json(&ctx, &token, &format!("/api/documents/{}/debug", document_id)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(debug["history"].as_array().unwrap().len(), 4);
+
+        let (status, _) = get_json(&ctx, &other_token, &format!("/api/documents/{}/history", document_id)).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_history_records_failed_attempts_and_is_pruned() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let dir = tempfile::tempdir()?;
+
+        let document_id = upload(&ctx, &dir, user.user_response.id, "gone.txt", b"soon deleted").await?;
+        let file_path: String = sqlx::query_scalar("SELECT file_path FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        std::fs::remove_file(file_path)?;
+
+        ctx.state.queue_service.enqueue_document(document_id, 5, 12).await?;
+        let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        ctx.state.queue_service.process_item(item, &ocr_service).await?;
+
+        let events = ctx.state.db.get_document_events(document_id).await?;
+        let last = events.last().expect("history events");
+        assert_eq!(last.event_type, DocumentEventType::OcrRetried, "the first failed attempt is retried");
+        assert!(last.detail.as_deref().unwrap_or_default().starts_with("OCR extraction failed"));
+
+        // Only events past the horizon are pruned
+        sqlx::query("UPDATE document_events SET created_at = $2 WHERE document_id = $1 AND event_type = 'uploaded'")
+            .bind(document_id)
+            .bind(Utc::now() - Duration::days(100))
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        assert!(ctx.state.db.prune_document_events(90).await? >= 1);
+
+        let remaining = ctx.state.db.get_document_events(document_id).await?;
+        assert_eq!(remaining.len(), events.len() - 1);
+        assert!(remaining.iter().all(|event| event.event_type != DocumentEventType::Uploaded));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{DateTime, Duration, Utc};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext};
+
+async fn list(ctx: &TestContext, token: &str, query: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/documents?{}", query))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_document(ctx: &TestContext, user_id: Uuid, name: &str, created_at: DateTime<Utc>) -> Result<Uuid> {
+    let document = ctx.state.db.create_document(create_test_document_with_hash(user_id, name, format!("hash-{}", name))).await?;
+    sqlx::query("UPDATE documents SET created_at = $2 WHERE id = $1")
+        .bind(document.id)
+        .bind(created_at)
+        .execute(ctx.state.db.get_pool())
+        .await?;
+    Ok(document.id)
+}
+
+fn filenames(response: &serde_json::Value) -> Vec<String> {
+    response["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|document| document["filename"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_cursor_pages_are_stable_while_documents_are_added() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let base = Utc::now() - Duration::hours(1);
+        for (index, name) in ["e.pdf", "d.pdf", "c.pdf", "b.pdf", "a.pdf"].iter().enumerate() {
+            create_document(&ctx, user_id, name, base - Duration::minutes(index as i64)).await?;
+        }
+
+        let (status, first) = list(&ctx, &token, "limit=2").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(filenames(&first), vec!["e.pdf", "d.pdf"]);
+        assert_eq!(first["pagination"]["has_more"], true);
+        let cursor = first["pagination"]["next_cursor"].as_str().unwrap().to_string();
+
+        // A new upload lands between pages; offset paging would now repeat d.pdf
+        create_document(&ctx, user_id, "new.pdf", Utc::now()).await?;
+
+        let (_, second) = list(&ctx, &token, &format!("limit=2&cursor={}", cursor)).await?;
+        assert_eq!(filenames(&second), vec!["c.pdf", "b.pdf"]);
+        let cursor = second["pagination"]["next_cursor"].as_str().unwrap().to_string();
+
+        let (_, last) = list(&ctx, &token, &format!("limit=2&cursor={}", cursor)).await?;
+        assert_eq!(filenames(&last), vec!["a.pdf"]);
+        assert_eq!(last["pagination"]["has_more"], false);
+        assert!(last["pagination"].get("next_cursor").is_none());
+
+        let (_, drifted) = list(&ctx, &token, "limit=2&offset=2").await?;
+        assert_eq!(filenames(&drifted), vec!["d.pdf", "c.pdf"]);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_cursor_breaks_created_at_ties_by_id_and_supports_oldest_first() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        // Bulk imports commonly share a timestamp
+        let same_time = Utc::now() - Duration::hours(1);
+        for name in ["one.pdf", "two.pdf", "three.pdf", "four.pdf"] {
+            create_document(&ctx, user_id, name, same_time).await?;
+        }
+
+        for sort in ["newest", "oldest"] {
+            let mut seen = Vec::new();
+            let mut query = format!("limit=1&sort={}", sort);
+            loop {
+                let (status, page) = list(&ctx, &token, &query).await?;
+                assert_eq!(status, StatusCode::OK);
+                seen.extend(filenames(&page));
+                match page["pagination"]["next_cursor"].as_str() {
+                    Some(cursor) => query = format!("limit=1&sort={}&cursor={}", sort, cursor),
+                    None => break,
+                }
+            }
+            let mut unique = seen.clone();
+            unique.sort();
+            unique.dedup();
+            assert_eq!(seen.len(), 4, "{} paging returned {:?}", sort, seen);
+            assert_eq!(unique.len(), 4, "{} paging returned {:?}", sort, seen);
+        }
+
+        let (_, newest) = list(&ctx, &token, "limit=4&sort=newest").await?;
+        let (_, oldest) = list(&ctx, &token, "limit=4&sort=oldest").await?;
+        let mut reversed = filenames(&oldest);
+        reversed.reverse();
+        assert_eq!(filenames(&newest), reversed);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_fields_projection_and_invalid_cursor() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let document_id = create_document(&ctx, user.user_response.id, "grid.pdf", Utc::now()).await?;
+        sqlx::query("UPDATE documents SET ocr_text = 'Quarterly totals' WHERE id = $1")
+            .bind(document_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, response) = list(&ctx, &token, "fields=id,filename,thumbnail").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            response["documents"][0],
+            serde_json::json!({
+                "id": document_id,
+                "filename": "grid.pdf",
+                "thumbnail_url": format!("/api/documents/{}/thumbnail", document_id),
+            })
+        );
+
+        let (_, full) = list(&ctx, &token, "").await?;
+        assert_eq!(full["documents"][0]["has_ocr_text"], true, "OCR text presence is reported without loading it");
+        assert!(full["documents"][0].get("mime_type").is_some());
+
+        let (status, _) = list(&ctx, &token, "cursor=not-a-cursor").await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
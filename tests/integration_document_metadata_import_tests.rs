@@ -0,0 +1,132 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::UserRole,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn post(ctx: &TestContext, token: &str, uri: &str, content_type: &str, body: String) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", content_type)
+        .body(Body::from(body))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+fn hash(seed: &str) -> String {
+    use sha2::{Digest, Sha256};
+    format!("{:x}", Sha256::digest(seed.as_bytes()))
+}
+
+#[tokio::test]
+async fn test_csv_import_applies_matched_rows_and_reports_the_rest() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+        let other = auth_helper.create_test_user().await;
+
+        let (status, _) = post(&ctx, &token, "/api/labels", "application/json", serde_json::json!({ "name": "Finance" }).to_string()).await?;
+        assert_eq!(status, StatusCode::OK);
+
+        let mut invoice = create_test_document_with_hash(user_id, "invoice.pdf", hash("invoice"));
+        invoice.tags = vec!["inbox".to_string()];
+        let invoice = ctx.state.db.create_document(invoice).await?;
+        let receipt = ctx.state.db.create_document(create_test_document_with_hash(user_id, "receipt.pdf", hash("receipt"))).await?;
+        // Someone else's document with a matching name is never touched
+        let foreign = ctx.state.db.create_document(create_test_document_with_hash(other.user_response.id, "letter.pdf", hash("letter"))).await?;
+
+        let csv = format!(
+            "original_filename,file_hash,tags,labels,original_created_at\n\
+             invoice.pdf,,tax;2019,finance,2019-04-01\n\
+             ,{},,,2018-12-24T10:00:00Z\n\
+             letter.pdf,,old,,\n\
+             receipt.pdf,,,Unknown,\n\
+             receipt.pdf,,x,,not-a-date\n",
+            hash("receipt")
+        );
+        let (status, body) = post(&ctx, &token, "/api/documents/import-metadata", "text/csv; charset=utf-8", csv).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["total_rows"], 5);
+        assert_eq!(body["applied_rows"], 2);
+        assert_eq!(body["unmatched_rows"], 1);
+        assert_eq!(body["invalid_rows"], 2);
+
+        let rows = body["rows"].as_array().unwrap();
+        let statuses: Vec<&str> = rows.iter().map(|row| row["status"].as_str().unwrap()).collect();
+        assert_eq!(statuses, ["applied", "applied", "unmatched", "invalid", "invalid"]);
+        assert_eq!(rows[3]["errors"][0], "unknown label 'Unknown'");
+        assert!(rows[4]["errors"][0].as_str().unwrap().contains("not-a-date"));
+
+        let invoice = ctx.state.db.get_document_by_id(invoice.id, user_id, UserRole::User).await?.unwrap();
+        assert_eq!(invoice.tags, ["inbox", "tax", "2019"]);
+        assert_eq!(invoice.original_created_at.unwrap().to_rfc3339(), "2019-04-01T00:00:00+00:00");
+        let labels = ctx.state.db.get_document_labels(invoice.id).await?;
+        assert_eq!(labels.iter().map(|label| label.name.as_str()).collect::<Vec<_>>(), ["Finance"]);
+
+        let receipt = ctx.state.db.get_document_by_id(receipt.id, user_id, UserRole::User).await?.unwrap();
+        assert_eq!(receipt.original_created_at.unwrap().to_rfc3339(), "2018-12-24T10:00:00+00:00");
+        assert!(receipt.tags.is_empty());
+
+        let foreign = ctx.state.db.get_document_by_id(foreign.id, other.user_response.id, UserRole::User).await?.unwrap();
+        assert!(foreign.tags.is_empty());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_import_rejects_malformed_uploads() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+        let document = ctx.state.db.create_document(create_test_document_with_hash(user_id, "scan.pdf", Uuid::new_v4().to_string())).await?;
+
+        let uri = "/api/documents/import-metadata";
+        assert_eq!(post(&ctx, &token, uri, "text/plain", "original_filename\na.pdf\n".to_string()).await?.0, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(post(&ctx, &token, uri, "text/csv", "filename,tags\na.pdf,x\n".to_string()).await?.0, StatusCode::BAD_REQUEST);
+        assert_eq!(post(&ctx, &token, uri, "application/json", "{\"rows\": []}".to_string()).await?.0, StatusCode::BAD_REQUEST);
+
+        // JSON rows are validated one by one
+        let json = serde_json::json!([
+            { "original_filename": "scan.pdf", "tags": ["archive"] },
+            { "original_filename": "scan.pdf", "tags": "archive" }
+        ]);
+        let (status, body) = post(&ctx, &token, uri, "application/json", json.to_string()).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["applied_rows"], 1);
+        assert_eq!(body["invalid_rows"], 1);
+        assert_eq!(body["rows"][0]["document_ids"][0], document.id.to_string());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
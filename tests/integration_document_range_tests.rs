@@ -0,0 +1,98 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+const BOUNDARY: &str = "document-range-test-boundary";
+const CONTENT: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+async fn upload(ctx: &TestContext, token: &str) -> Result<String> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"alphabet.txt\"\r\nContent-Type: text/plain\r\n\r\n",
+        BOUNDARY
+    ).into_bytes();
+    body.extend_from_slice(CONTENT);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let document: serde_json::Value = serde_json::from_slice(&bytes)?;
+    Ok(document["id"].as_str().unwrap().to_string())
+}
+
+/// Status, `Content-Range` header and body of a GET with an optional `Range` header
+async fn get_range(ctx: &TestContext, token: &str, uri: &str, range: Option<&str>) -> Result<(StatusCode, Option<String>, Vec<u8>)> {
+    let mut request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token));
+    if let Some(range) = range {
+        request = request.header("Range", range);
+    }
+
+    let response = ctx.app.clone().oneshot(request.body(Body::empty())?).await?;
+    let status = response.status();
+    assert_eq!(response.headers()["accept-ranges"], "bytes");
+    let content_range = response.headers().get("content-range").map(|value| value.to_str().unwrap().to_string());
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, content_range, bytes.to_vec()))
+}
+
+#[tokio::test]
+async fn test_download_and_view_serve_byte_ranges() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let document_id = upload(&ctx, &token).await?;
+
+        for endpoint in ["download", "view"] {
+            let uri = format!("/api/documents/{}/{}", document_id, endpoint);
+
+            let (status, content_range, body) = get_range(&ctx, &token, &uri, None).await?;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(content_range, None);
+            assert_eq!(body, CONTENT);
+
+            let (status, content_range, body) = get_range(&ctx, &token, &uri, Some("bytes=10-15")).await?;
+            assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+            assert_eq!(content_range.as_deref(), Some("bytes 10-15/36"));
+            assert_eq!(body, b"abcdef");
+
+            let (status, content_range, body) = get_range(&ctx, &token, &uri, Some("bytes=-3")).await?;
+            assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+            assert_eq!(content_range.as_deref(), Some("bytes 33-35/36"));
+            assert_eq!(body, b"xyz");
+
+            let (status, content_range, body) = get_range(&ctx, &token, &uri, Some("bytes=36-")).await?;
+            assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+            assert_eq!(content_range.as_deref(), Some("bytes */36"));
+            assert!(body.is_empty());
+
+            // Several ranges at once are answered with the whole file
+            let (status, _, body) = get_range(&ctx, &token, &uri, Some("bytes=0-1,5-6")).await?;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(body, CONTENT);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
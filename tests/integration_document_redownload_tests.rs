@@ -0,0 +1,152 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+/// A local folder source holding `scan.pdf`, and a document synced from it whose stored
+/// copy is truncated. Returns the document id and the paths of the source and stored files.
+async fn create_corrupted_document(
+    ctx: &TestContext,
+    user_id: Uuid,
+    source_dir: &std::path::Path,
+    storage_dir: &std::path::Path,
+    original: &[u8],
+) -> Result<(Uuid, std::path::PathBuf, std::path::PathBuf)> {
+    let source_file = source_dir.join("scan.pdf");
+    std::fs::write(&source_file, original)?;
+    let stored_file = storage_dir.join(format!("{}.pdf", Uuid::new_v4()));
+    std::fs::write(&stored_file, &original[..original.len() / 2])?;
+
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: format!("Scans {}", Uuid::new_v4().simple()),
+        source_type: SourceType::LocalFolder,
+        enabled: Some(true),
+        config: serde_json::json!({
+            "watch_folders": [source_dir.to_string_lossy()],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "recursive": true,
+            "follow_symlinks": false
+        }),
+    }).await?;
+
+    let mut document = create_test_document(user_id);
+    document.file_path = stored_file.to_string_lossy().to_string();
+    document.file_size = original.len() as i64;
+    document.file_hash = Some(format!("{:x}", Sha256::digest(original)));
+    document.source_id = Some(source.id);
+    document.source_type = Some("local_folder".to_string());
+    document.source_path = Some(source_file.to_string_lossy().to_string());
+    let document = ctx.state.db.create_document(document).await?;
+
+    Ok((document.id, source_file, stored_file))
+}
+
+#[tokio::test]
+async fn test_redownload_restores_corrupted_file() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let source_dir = tempfile::tempdir()?;
+    let storage_dir = tempfile::tempdir()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+
+        let original = b"%PDF-1.4 original scan contents".repeat(20);
+        let (document_id, source_file, stored_file) =
+            create_corrupted_document(&ctx, user.user_response.id, source_dir.path(), storage_dir.path(), &original).await?;
+        let uri = format!("/api/documents/{}/redownload", document_id);
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, None, &other_token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({ "reenqueue_ocr": true })), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["file_size"], original.len());
+        assert_eq!(body["ocr_enqueued"], true);
+        assert_eq!(std::fs::read(&stored_file)?, original);
+
+        // A source file that changed since the sync is not a repair: the stored file stays
+        std::fs::write(&source_file, b"edited on the source")?;
+        std::fs::write(&stored_file, b"truncated")?;
+        let (status, body) = auth_helper.send_authenticated_request("POST", &uri, None, &token).await;
+        assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+        assert!(body["error"].as_str().unwrap().contains("no longer matches"));
+        assert_eq!(std::fs::read(&stored_file)?, b"truncated");
+
+        // Nor is a failed download
+        std::fs::remove_file(&source_file)?;
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, None, &token).await;
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(std::fs::read(&stored_file)?, b"truncated");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_redownload_requires_an_existing_source() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let source_dir = tempfile::tempdir()?;
+    let storage_dir = tempfile::tempdir()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let uploaded = ctx.state.db.create_document(create_test_document(user.user_response.id)).await?;
+        let (status, body) = auth_helper.send_authenticated_request("POST", &format!("/api/documents/{}/redownload", uploaded.id), None, &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "Document was not synced from a source");
+
+        let original = b"%PDF-1.4 bulk scan".repeat(10);
+        let (synced, _, stored_file) =
+            create_corrupted_document(&ctx, user.user_response.id, source_dir.path(), storage_dir.path(), &original).await?;
+
+        let (status, body) = auth_helper.send_authenticated_request("POST", "/api/documents/bulk/redownload", Some(serde_json::json!({
+            "document_ids": [synced, uploaded.id, Uuid::new_v4()]
+        })), &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["redownloaded_count"], 1);
+        assert_eq!(body["failed_count"], 2);
+        assert_eq!(body["results"][0]["success"], true);
+        assert_eq!(body["results"][0]["ocr_enqueued"], false);
+        assert_eq!(body["results"][2]["error"], "Document not found");
+        assert_eq!(std::fs::read(&stored_file)?, original);
+
+        // Once the source is gone there is nothing to download from
+        let document = ctx.state.db.get_document_by_id(synced, user.user_response.id, readur::models::UserRole::User).await?.unwrap();
+        sqlx::query("DELETE FROM sources WHERE id = $1")
+            .bind(document.source_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        let (status, body) = auth_helper.send_authenticated_request("POST", &format!("/api/documents/{}/redownload", synced), None, &token).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "The source the document was synced from no longer exists");
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", "/api/documents/bulk/redownload", Some(serde_json::json!({ "document_ids": [] })), &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
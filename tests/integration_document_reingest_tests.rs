@@ -0,0 +1,114 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+/// Creates a document whose stored hash is that of `stored_content`, with `disk_content` on disk
+async fn create_document_with_file(
+    ctx: &TestContext,
+    user_id: Uuid,
+    dir: &tempfile::TempDir,
+    name: &str,
+    stored_content: &str,
+    disk_content: Option<&str>,
+) -> Result<Uuid> {
+    let path = dir.path().join(name);
+    if let Some(content) = disk_content {
+        std::fs::write(&path, content)?;
+    }
+
+    let mut document = create_test_document(user_id);
+    document.file_path = path.to_string_lossy().to_string();
+    document.file_size = stored_content.len() as i64;
+    document.file_hash = Some(format!("{:x}", Sha256::digest(stored_content.as_bytes())));
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn integrity_issue(ctx: &TestContext, document_id: Uuid) -> Result<Option<String>> {
+    Ok(sqlx::query_scalar("SELECT issue FROM document_integrity_issues WHERE document_id = $1")
+        .bind(document_id)
+        .fetch_optional(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_reingest_reports_mismatched_and_missing_files() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+        let user_id = admin.user_response.id;
+        let dir = tempfile::tempdir()?;
+
+        let intact = create_document_with_file(&ctx, user_id, &dir, "intact.txt", "original", Some("original")).await?;
+        let changed = create_document_with_file(&ctx, user_id, &dir, "changed.txt", "before", Some("after the fix")).await?;
+        let missing = create_document_with_file(&ctx, user_id, &dir, "missing.txt", "gone", None).await?;
+
+        let (status, run) = auth_helper.send_authenticated_request(
+            "POST", "/api/documents/maintenance/reingest",
+            Some(serde_json::json!({ "reenqueue_ocr": true })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        let run = auth_helper.wait_for_document_run(&format!("/api/documents/maintenance/reingest/{}", run["id"].as_str().unwrap()), &token).await;
+        assert_eq!(run["outcome"], "success");
+        assert_eq!(run["total_documents"], 3);
+        assert_eq!(run["checked"], 3);
+        assert_eq!(run["mismatched"], 1);
+        assert_eq!(run["missing"], 1);
+        assert_eq!(run["reenqueued"], 1);
+
+        assert_eq!(integrity_issue(&ctx, intact).await?, None);
+        assert_eq!(integrity_issue(&ctx, changed).await?.as_deref(), Some("hash_mismatch"));
+        assert_eq!(integrity_issue(&ctx, missing).await?.as_deref(), Some("missing"));
+
+        // The changed file was adopted and queued for OCR
+        let (file_hash, ocr_status): (Option<String>, Option<String>) = sqlx::query_as(
+            "SELECT file_hash, ocr_status FROM documents WHERE id = $1"
+        )
+        .bind(changed)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(file_hash, Some(format!("{:x}", Sha256::digest(b"after the fix"))));
+        assert_eq!(ocr_status.as_deref(), Some("pending"));
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue WHERE document_id = $1")
+            .bind(changed)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(queued, 1);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_reingest_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", "/api/documents/maintenance/reingest", Some(serde_json::json!({})), &token).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,182 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+/// Creates a document for a copy of `fixture` in `dir`, with OCR already completed
+async fn create_document(ctx: &TestContext, user_id: Uuid, dir: &tempfile::TempDir, fixture: &str, mime_type: &str) -> Result<Uuid> {
+    let filename = std::path::Path::new(fixture).file_name().unwrap().to_string_lossy().to_string();
+    let path = dir.path().join(&filename);
+    std::fs::copy(fixture, &path)?;
+
+    let mut document = create_test_document(user_id);
+    document.filename = filename.clone();
+    document.original_filename = filename;
+    document.file_path = path.to_string_lossy().to_string();
+    document.file_size = std::fs::metadata(&path)?.len() as i64;
+    document.mime_type = mime_type.to_string();
+    document.ocr_status = Some("completed".to_string());
+    document.ocr_text = Some("Quarterly report".to_string());
+    document.file_hash = Some(Uuid::new_v4().simple().to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+#[tokio::test]
+async fn test_rotate_rejects_invalid_requests() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let dir = tempfile::tempdir()?;
+        let user_id = user.user_response.id;
+
+        let pdf_id = create_document(&ctx, user_id, &dir, "test_files/multipage_test.pdf", "application/pdf").await?;
+        let text_id = create_document(&ctx, user_id, &dir, "test_files/sample.txt", "text/plain").await?;
+        let uri = format!("/api/documents/{}/rotate", pdf_id);
+
+        for degrees in [45, 0, 360] {
+            let (status, _) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": degrees})), &token).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{} degrees", degrees);
+        }
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 90, "pages": [0]})), &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 90, "pages": [999]})), &token).await;
+        assert!(matches!(status, StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY), "{}", status);
+
+        let text_uri = format!("/api/documents/{}/rotate", text_id);
+        let (status, _) = auth_helper.send_authenticated_request("POST", &text_uri, Some(serde_json::json!({"degrees": 90})), &token).await;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 90})), &other_token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        sqlx::query("UPDATE documents SET ocr_status = 'processing' WHERE id = $1")
+            .bind(pdf_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 90})), &token).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[cfg(feature = "ocr")]
+#[tokio::test]
+async fn test_rotate_image_stores_new_version() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let dir = tempfile::tempdir()?;
+        let user_id = user.user_response.id;
+
+        let scan_id = create_document(&ctx, user_id, &dir, "test_files/landscape_300x200.png", "image/png").await?;
+        let original = ctx.state.db.get_document_by_id(scan_id, user_id, readur::models::UserRole::User).await?.unwrap();
+        // Orientation detection was unsure, so the scan is flagged for review
+        ctx.state.db.set_document_detected_orientation(scan_id, 0, 90, 2.5, true).await?;
+
+        let uri = format!("/api/documents/{}/rotate", scan_id);
+        let (status, body) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 90, "reprocess_ocr": false})), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["version_number"], 2);
+        assert_eq!(body["ocr_queued"], false);
+
+        let rotated = ctx.state.db.get_document_by_id(scan_id, user_id, readur::models::UserRole::User).await?.unwrap();
+        assert_ne!(rotated.file_path, original.file_path);
+        assert_ne!(rotated.file_hash, original.file_hash);
+        let image = image::open(&rotated.file_path)?;
+        assert_eq!((image.width(), image.height()), (200, 300));
+        // The text was kept as asked
+        assert_eq!(rotated.ocr_text.as_deref(), Some("Quarterly report"));
+        assert_eq!(rotated.ocr_status.as_deref(), Some("completed"));
+
+        let orientation = ctx.state.db.get_document_orientation(scan_id).await?.unwrap();
+        assert_eq!(orientation.rotation_degrees, 0);
+        assert!(!orientation.needs_review);
+
+        // The original file is kept as the previous version
+        let versions = ctx.state.db.get_document_versions(scan_id).await?;
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].file_path, original.file_path);
+
+        // By default OCR runs again on the rotated file
+        let (status, body) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": -90})), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["version_number"], 3);
+        assert_eq!(body["ocr_queued"], true);
+        let rotated = ctx.state.db.get_document_by_id(scan_id, user_id, readur::models::UserRole::User).await?.unwrap();
+        assert_eq!(rotated.ocr_status.as_deref(), Some("pending"));
+        let image = image::open(&rotated.file_path)?;
+        assert_eq!((image.width(), image.height()), (300, 200));
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 90, "pages": [1]})), &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_rotate_selected_pdf_pages() -> Result<()> {
+    if std::process::Command::new("qpdf").arg("--version").output().is_err() {
+        eprintln!("Skipping PDF rotation test: qpdf is not installed");
+        return Ok(());
+    }
+
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let dir = tempfile::tempdir()?;
+        let user_id = user.user_response.id;
+
+        let pdf_id = create_document(&ctx, user_id, &dir, "test_files/multipage_test.pdf", "application/pdf").await?;
+        let uri = format!("/api/documents/{}/rotate", pdf_id);
+        let (status, body) = auth_helper.send_authenticated_request("POST", &uri, Some(serde_json::json!({"degrees": 180, "pages": [2]})), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["pages"], serde_json::json!([2]));
+        assert_eq!(body["ocr_queued"], true);
+
+        let rotated = ctx.state.db.get_document_by_id(pdf_id, user_id, readur::models::UserRole::User).await?.unwrap();
+        let info = std::process::Command::new("pdfinfo").args(["-f", "1", "-l", "2"]).arg(&rotated.file_path).output()?;
+        let info = String::from_utf8_lossy(&info.stdout);
+        let rotation_of = |page: u32| {
+            info.lines()
+                .find(|line| line.starts_with("Page") && line.contains(" rot:") && line.split_whitespace().nth(1) == Some(&page.to_string()))
+                .and_then(|line| line.split_whitespace().last())
+                .map(str::to_string)
+        };
+        assert_eq!(rotation_of(1).as_deref(), Some("0"));
+        assert_eq!(rotation_of(2).as_deref(), Some("180"));
+
+        let history = ctx.state.db.get_document_events(pdf_id).await?;
+        assert!(history.iter().any(|event| event.detail.as_deref() == Some("rotated page 2 by 180°")));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -78,6 +78,8 @@ mod document_routes_deletion_tests {
     fn test_bulk_delete_request_serialization() {
         let request = BulkDeleteRequest {
             document_ids: vec![Uuid::new_v4(), Uuid::new_v4()],
+            source_files: None,
+            confirm: false,
         };
 
         // Test serialization
@@ -94,6 +96,8 @@ mod document_routes_deletion_tests {
     fn test_bulk_delete_request_empty_list() {
         let request = BulkDeleteRequest {
             document_ids: vec![],
+            source_files: None,
+            confirm: false,
         };
 
         let json = serde_json::to_string(&request).unwrap();
@@ -343,18 +347,24 @@ mod document_routes_deletion_tests {
         // Test reasonable request size
         let reasonable_request = BulkDeleteRequest {
             document_ids: (0..10).map(|_| Uuid::new_v4()).collect(),
+            source_files: None,
+            confirm: false,
         };
         assert_eq!(reasonable_request.document_ids.len(), 10);
 
         // Test large request size (should still be valid but might be rate-limited in real app)
         let large_request = BulkDeleteRequest {
             document_ids: (0..100).map(|_| Uuid::new_v4()).collect(),
+            source_files: None,
+            confirm: false,
         };
         assert_eq!(large_request.document_ids.len(), 100);
 
         // Test very large request size (might need limits in production)
         let very_large_request = BulkDeleteRequest {
             document_ids: (0..1000).map(|_| Uuid::new_v4()).collect(),
+            source_files: None,
+            confirm: false,
         };
         assert_eq!(very_large_request.document_ids.len(), 1000);
     }
@@ -0,0 +1,164 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+const BOUNDARY: &str = "document-share-test-boundary";
+const CONTENT: &[u8] = b"Quarterly figures for review\n";
+
+async fn send(ctx: &TestContext, method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> Result<(StatusCode, Vec<u8>)> {
+    let mut request = Request::builder().method(method).uri(uri);
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    let request = match body {
+        Some(body) => request.header("Content-Type", "application/json").body(Body::from(body.to_string()))?,
+        None => request.body(Body::empty())?,
+    };
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, bytes.to_vec()))
+}
+
+async fn send_json(ctx: &TestContext, method: &str, uri: &str, token: Option<&str>, body: Option<serde_json::Value>) -> Result<(StatusCode, serde_json::Value)> {
+    let (status, bytes) = send(ctx, method, uri, token, body).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn upload(ctx: &TestContext, token: &str) -> Result<String> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"figures.txt\"\r\nContent-Type: text/plain\r\n\r\n",
+        BOUNDARY
+    ).into_bytes();
+    body.extend_from_slice(CONTENT);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let document: serde_json::Value = serde_json::from_slice(&bytes)?;
+    Ok(document["id"].as_str().unwrap().to_string())
+}
+
+#[tokio::test]
+async fn test_share_link_serves_document_until_revoked() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let document_id = upload(&ctx, &token).await?;
+
+        let (status, share) = send_json(&ctx, "POST", &format!("/api/documents/{}/share", document_id), Some(&token),
+            Some(serde_json::json!({ "expires_in_hours": 24, "allow_download": true }))).await?;
+        assert_eq!(status, StatusCode::CREATED, "unexpected response: {}", share);
+        let share_token = share["token"].as_str().unwrap().to_string();
+        assert_eq!(share_token.len(), 64);
+        assert_eq!(share["url"], format!("/api/shared/{}", share_token));
+
+        // The share link works without logging in
+        let (status, shared) = send_json(&ctx, "GET", &format!("/api/shared/{}", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(shared["filename"], "figures.txt");
+        assert_eq!(shared["allow_download"], true);
+        assert!(shared.get("document_id").is_none() && shared.get("user_id").is_none());
+
+        let (status, file) = send(&ctx, "GET", &format!("/api/shared/{}/download", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(file, CONTENT);
+
+        let (status, shares) = send_json(&ctx, "GET", &format!("/api/documents/{}/shares", document_id), Some(&token), None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(shares.as_array().unwrap().len(), 1);
+        assert_eq!(shares[0]["access_count"], 2);
+        assert!(shares[0]["last_accessed_at"].is_string());
+
+        let (status, _) = send(&ctx, "DELETE", &format!("/api/documents/{}/shares/{}", document_id, share_token), Some(&token), None).await?;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+        let (status, _) = send(&ctx, "GET", &format!("/api/shared/{}", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&ctx, "GET", &format!("/api/shared/{}/download", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_share_links_are_scoped_and_expire() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let owner_token = auth_helper.login_user(&owner.username, &owner.password).await;
+        let other = auth_helper.create_test_user().await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let document_id = upload(&ctx, &owner_token).await?;
+
+        let (status, share) = send_json(&ctx, "POST", &format!("/api/documents/{}/share", document_id), Some(&owner_token),
+            Some(serde_json::json!({}))).await?;
+        assert_eq!(status, StatusCode::CREATED);
+        let share_token = share["token"].as_str().unwrap().to_string();
+        assert_eq!(share["allow_download"], false);
+
+        // Metadata only, unless downloads were allowed
+        let (status, shared) = send_json(&ctx, "GET", &format!("/api/shared/{}", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert!(shared["download_url"].is_null());
+        let (status, _) = send(&ctx, "GET", &format!("/api/shared/{}/download", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Other users can neither share the document nor see or revoke its shares
+        let (status, _) = send(&ctx, "POST", &format!("/api/documents/{}/share", document_id), Some(&other_token), Some(serde_json::json!({}))).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&ctx, "GET", &format!("/api/documents/{}/shares", document_id), Some(&other_token), None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&ctx, "DELETE", &format!("/api/documents/{}/shares/{}", document_id, share_token), Some(&other_token), None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // A token only revokes shares of its own document
+        let other_document_id = upload(&ctx, &other_token).await?;
+        let (status, _) = send(&ctx, "DELETE", &format!("/api/documents/{}/shares/{}", other_document_id, share_token), Some(&other_token), None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = send(&ctx, "GET", &format!("/api/shared/{}", "0".repeat(64)), None, None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = send(&ctx, "POST", &format!("/api/documents/{}/share", document_id), Some(&owner_token),
+            Some(serde_json::json!({ "expires_in_hours": 0 }))).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        sqlx::query("UPDATE document_shares SET expires_at = NOW() - INTERVAL '1 minute' WHERE token = $1")
+            .bind(&share_token)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+        let (status, _) = send(&ctx, "GET", &format!("/api/shared/{}", share_token), None, None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,187 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+async fn create_webdav_source(ctx: &TestContext, user_id: Uuid, server_url: &str, deletion_behavior: Option<&str>) -> Result<Uuid> {
+    let mut config = serde_json::json!({
+        "server_url": server_url,
+        "username": "archive",
+        "password": "secret",
+        "watch_folders": ["/Documents"],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "server_type": "generic"
+    });
+    if let Some(behavior) = deletion_behavior {
+        config["deletion_behavior"] = serde_json::json!(behavior);
+    }
+
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: format!("Archive {}", Uuid::new_v4().simple()),
+        source_type: SourceType::WebDAV,
+        enabled: Some(false),
+        config,
+    }).await?;
+    Ok(source.id)
+}
+
+async fn create_synced_document(ctx: &TestContext, user_id: Uuid, source_id: Uuid, filename: &str) -> Result<Uuid> {
+    let storage_dir = std::env::temp_dir();
+    let stored_file = storage_dir.join(format!("{}.pdf", Uuid::new_v4()));
+    std::fs::write(&stored_file, b"%PDF-1.4 synced")?;
+
+    let mut document = create_test_document(user_id);
+    document.filename = filename.to_string();
+    document.file_path = stored_file.to_string_lossy().to_string();
+    document.source_id = Some(source_id);
+    document.source_type = Some("webdav".to_string());
+    document.source_path = Some(format!("/Documents/{}", filename));
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+/// Mock server answering DELETE of `/Documents/<filename>` with `status`, expected `times` times
+async fn expect_delete(server: &MockServer, filename: &str, status: u16, times: u64) {
+    Mock::given(method("DELETE"))
+        .and(path(format!("/Documents/{}", filename)))
+        .respond_with(ResponseTemplate::new(status))
+        .expect(times)
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn test_deleting_a_document_keeps_the_source_file_by_default() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let server = MockServer::start().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        expect_delete(&server, "kept.pdf", 204, 0).await;
+        expect_delete(&server, "overridden.pdf", 204, 0).await;
+
+        let keeping = create_webdav_source(&ctx, user_id, &server.uri(), None).await?;
+        let kept = create_synced_document(&ctx, user_id, keeping, "kept.pdf").await?;
+        let (status, _) = auth_helper.send_authenticated_request("DELETE", &format!("/api/documents/{}", kept), None, &token).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        // A request can keep the file even when the source deletes by default
+        let deleting = create_webdav_source(&ctx, user_id, &server.uri(), Some("delete_source")).await?;
+        let overridden = create_synced_document(&ctx, user_id, deleting, "overridden.pdf").await?;
+        let uri = format!("/api/documents/{}?source_files=keep_source", overridden);
+        let (status, _) = auth_helper.send_authenticated_request("DELETE", &uri, None, &token).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    server.verify().await;
+    result
+}
+
+#[tokio::test]
+async fn test_deleting_the_source_file_requires_confirmation() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let server = MockServer::start().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        expect_delete(&server, "invoice.pdf", 204, 1).await;
+
+        let source_id = create_webdav_source(&ctx, user_id, &server.uri(), Some("delete_source")).await?;
+        let document_id = create_synced_document(&ctx, user_id, source_id, "invoice.pdf").await?;
+        let uri = format!("/api/documents/{}", document_id);
+
+        // Nothing is deleted until the deletion on the source is confirmed
+        let (status, body) = auth_helper.send_authenticated_request("DELETE", &uri, None, &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("confirm=true"), "{}", body);
+        assert_eq!(auth_helper.send_authenticated_request("GET", &uri, None, &token).await.0, StatusCode::OK);
+
+        let (status, body) = auth_helper.send_authenticated_request("DELETE", &format!("{}?confirm=true", uri), None, &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["document_id"], document_id.to_string());
+        assert_eq!(body["source_file"]["status"], "deleted");
+        assert_eq!(body["source_file"]["source_path"], "/Documents/invoice.pdf");
+        assert_eq!(auth_helper.send_authenticated_request("GET", &uri, None, &token).await.0, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    server.verify().await;
+    result
+}
+
+#[tokio::test]
+async fn test_failed_source_deletion_is_reported_without_undoing_the_local_deletion() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let server = MockServer::start().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        expect_delete(&server, "locked.pdf", 403, 1).await;
+        expect_delete(&server, "removable.pdf", 204, 1).await;
+
+        // The source keeps files; the request asks for them to be deleted
+        let source_id = create_webdav_source(&ctx, user_id, &server.uri(), None).await?;
+        let locked = create_synced_document(&ctx, user_id, source_id, "locked.pdf").await?;
+        let removable = create_synced_document(&ctx, user_id, source_id, "removable.pdf").await?;
+        let uploaded = ctx.state.db.create_document(create_test_document(user_id)).await?.id;
+
+        let request = serde_json::json!({ "document_ids": [locked, removable, uploaded], "source_files": "delete_source" });
+        let (status, _) = auth_helper.send_authenticated_request("DELETE", "/api/documents", Some(request.clone()), &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let mut confirmed = request;
+        confirmed["confirm"] = serde_json::json!(true);
+        let (status, body) = auth_helper.send_authenticated_request("DELETE", "/api/documents", Some(confirmed), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["deleted_count"], 3);
+
+        let source_files = body["source_files"].as_array().unwrap();
+        assert_eq!(source_files.len(), 2, "the upload has no source file");
+        let outcome = |id: Uuid| source_files.iter().find(|f| f["document_id"] == id.to_string()).unwrap().clone();
+        assert_eq!(outcome(removable)["status"], "deleted");
+        assert_eq!(outcome(locked)["status"], "failed");
+        assert!(outcome(locked)["error"].as_str().unwrap().contains("403"));
+
+        for id in [locked, removable, uploaded] {
+            assert_eq!(auth_helper.send_authenticated_request("GET", &format!("/api/documents/{}", id), None, &token).await.0, StatusCode::NOT_FOUND);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    server.verify().await;
+    result
+}
@@ -0,0 +1,160 @@
+use anyhow::Result;
+use chrono::Utc;
+use uuid::Uuid;
+
+use readur::{
+    db::Database,
+    models::{CreateUser, Document, User, UserRole},
+    test_utils::TestContext,
+};
+
+async fn create_test_user(db: &Database) -> Result<User> {
+    let unique_suffix = Uuid::new_v4().simple();
+    Ok(db.create_user(CreateUser {
+        username: format!("trash_{}", unique_suffix),
+        email: format!("trash_{}@example.com", unique_suffix),
+        password: "password123".to_string(),
+        role: Some(UserRole::User),
+    }).await?)
+}
+
+fn create_test_document(user_id: Uuid, file_hash: &str) -> Document {
+    Document {
+        id: Uuid::new_v4(),
+        filename: "trash_test.pdf".to_string(),
+        original_filename: "trash_test.pdf".to_string(),
+        file_path: "/uploads/documents/trash_test.pdf".to_string(),
+        file_size: 1024,
+        mime_type: "application/pdf".to_string(),
+        content: Some("Quarterly invoice".to_string()),
+        ocr_text: None,
+        ocr_confidence: None,
+        ocr_word_count: None,
+        ocr_processing_time_ms: None,
+        ocr_status: Some("completed".to_string()),
+        ocr_error: None,
+        ocr_completed_at: None,
+        ocr_retry_count: None,
+        ocr_failure_reason: None,
+        tags: vec![],
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        user_id,
+        file_hash: Some(file_hash.to_string()),
+        original_created_at: None,
+        original_modified_at: None,
+        source_path: None,
+        source_type: None,
+        source_id: None,
+        file_permissions: None,
+        file_owner: None,
+        file_group: None,
+        source_metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn test_soft_delete_and_restore() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let user = create_test_user(db).await?;
+        let hash = format!("{:x}", Uuid::new_v4().as_u128());
+        let document = db.create_document(create_test_document(user.id, &hash)).await?;
+
+        assert!(db.delete_document(document.id, user.id, user.role).await?);
+        // Deleting again is a no-op
+        assert!(!db.delete_document(document.id, user.id, user.role).await?);
+
+        // Trashed documents are hidden from normal lookups and listings
+        assert!(db.get_document_by_id(document.id, user.id, user.role).await?.is_none());
+        assert!(db.get_documents_by_user_with_role(user.id, user.role, 50, 0).await?.is_empty());
+        assert_eq!(db.count_documents_by_user_with_role(user.id, user.role).await?, 0);
+
+        let trash = db.get_deleted_documents(user.id, user.role, 50, 0).await?;
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].0.id, document.id);
+        assert_eq!(db.count_deleted_documents(user.id, user.role).await?, 1);
+
+        // Another user cannot restore it
+        let other = create_test_user(db).await?;
+        assert!(!db.restore_document(document.id, &document.file_path, other.id, other.role).await?);
+
+        assert!(db.restore_document(document.id, &document.file_path, user.id, user.role).await?);
+        assert!(db.get_document_by_id(document.id, user.id, user.role).await?.is_some());
+        assert_eq!(db.count_deleted_documents(user.id, user.role).await?, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_trashed_document_does_not_block_reupload() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let user = create_test_user(db).await?;
+        let hash = format!("{:x}", Uuid::new_v4().as_u128());
+        let original = db.create_document(create_test_document(user.id, &hash)).await?;
+        db.delete_document(original.id, user.id, user.role).await?;
+
+        assert!(db.get_document_by_user_and_hash(user.id, &hash).await?.is_none());
+        let reuploaded = db.create_document(create_test_document(user.id, &hash)).await?;
+
+        // Restoring would now duplicate the live document's content
+        assert!(db.restore_document(original.id, &original.file_path, user.id, user.role).await.is_err());
+        assert!(db.get_document_by_id(reuploaded.id, user.id, user.role).await?.is_some());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_purge_only_removes_expired_trash() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let user = create_test_user(db).await?;
+
+        let expired = db.create_document(create_test_document(user.id, &format!("{:x}", Uuid::new_v4().as_u128()))).await?;
+        let recent = db.create_document(create_test_document(user.id, &format!("{:x}", Uuid::new_v4().as_u128()))).await?;
+        let live = db.create_document(create_test_document(user.id, &format!("{:x}", Uuid::new_v4().as_u128()))).await?;
+
+        db.bulk_delete_documents(&[expired.id, recent.id], user.id, user.role).await?;
+        sqlx::query("UPDATE documents SET deleted_at = NOW() - INTERVAL '40 days' WHERE id = $1")
+            .bind(expired.id)
+            .execute(&db.pool)
+            .await?;
+
+        let purged = db.purge_deleted_documents(Utc::now() - chrono::Duration::days(30)).await?;
+        let purged_ids: Vec<Uuid> = purged.iter().map(|d| d.id).collect();
+        assert_eq!(purged_ids, vec![expired.id]);
+
+        assert!(db.get_deleted_document_by_id(expired.id, user.id, user.role).await?.is_none());
+        assert!(db.get_deleted_document_by_id(recent.id, user.id, user.role).await?.is_some());
+        assert!(db.get_document_by_id(live.id, user.id, user.role).await?.is_some());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
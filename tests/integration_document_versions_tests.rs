@@ -0,0 +1,116 @@
+use anyhow::Result;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+use readur::{
+    db::Database,
+    ingestion::document_ingestion::{
+        DeduplicationPolicy, DocumentIngestionRequest, DocumentIngestionService, IngestionResult,
+    },
+    models::{CreateSource, CreateUser, Source, SourceType, User, UserRole},
+    services::file_service::FileService,
+    test_utils::TestContext,
+};
+
+async fn create_test_user(db: &Database) -> Result<User> {
+    let unique_suffix = Uuid::new_v4().simple();
+    Ok(db.create_user(CreateUser {
+        username: format!("versions_{}", unique_suffix),
+        email: format!("versions_{}@example.com", unique_suffix),
+        password: "password123".to_string(),
+        role: Some(UserRole::User),
+    }).await?)
+}
+
+async fn create_test_source(db: &Database, user_id: Uuid) -> Result<Source> {
+    Ok(db.create_source(user_id, &CreateSource {
+        name: "versioned_source".to_string(),
+        source_type: SourceType::WebDAV,
+        enabled: Some(true),
+        config: serde_json::json!({}),
+    }).await?)
+}
+
+fn synced_file(user_id: Uuid, source_id: Uuid, data: &[u8]) -> DocumentIngestionRequest {
+    DocumentIngestionRequest {
+        filename: "report.txt".to_string(),
+        original_filename: "report.txt".to_string(),
+        file_data: data.to_vec(),
+        mime_type: "text/plain".to_string(),
+        user_id,
+        deduplication_policy: DeduplicationPolicy::Skip,
+        source_type: Some("source_sync".to_string()),
+        source_id: Some(source_id),
+        original_created_at: None,
+        original_modified_at: None,
+        source_path: Some("/Reports/report.txt".to_string()),
+        file_permissions: None,
+        file_owner: None,
+        file_group: None,
+        source_metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn test_changed_source_file_creates_version() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let upload_dir = TempDir::new()?;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let user = create_test_user(db).await?;
+        let source = create_test_source(db, user.id).await?;
+        let ingestion = DocumentIngestionService::new(
+            db.clone(),
+            FileService::new(upload_dir.path().to_string_lossy().to_string()),
+        )
+        .with_version_retention(1);
+
+        let original = match ingestion.ingest_document(synced_file(user.id, source.id, b"first draft")).await.unwrap() {
+            IngestionResult::Created(doc) => doc,
+            other => panic!("expected a new document, got {:?}", other),
+        };
+        db.update_document_ocr(original.id, Some("first draft".to_string()), Some(95.0), Some(2), Some(10), Some("completed".to_string())).await?;
+
+        // Unchanged content is deduplicated as before
+        assert!(matches!(
+            ingestion.ingest_document(synced_file(user.id, source.id, b"first draft")).await.unwrap(),
+            IngestionResult::Skipped { .. }
+        ));
+
+        let updated = match ingestion.ingest_document(synced_file(user.id, source.id, b"second draft")).await.unwrap() {
+            IngestionResult::NewVersion(doc) => doc,
+            other => panic!("expected a new version, got {:?}", other),
+        };
+        assert_eq!(updated.id, original.id);
+        assert_ne!(updated.file_path, original.file_path);
+        assert_eq!(updated.ocr_status.as_deref(), Some("pending"));
+        assert!(updated.ocr_text.is_none());
+        assert_eq!(db.get_document_version_number(original.id).await?, 2);
+
+        // The prior content and its OCR text are archived, file included
+        let versions = db.get_document_versions(original.id).await?;
+        assert_eq!(versions.len(), 1);
+        assert_eq!(versions[0].version_number, 1);
+        assert_eq!(versions[0].file_path, original.file_path);
+        assert_eq!(versions[0].ocr_text.as_deref(), Some("first draft"));
+        assert!(std::path::Path::new(&original.file_path).exists());
+
+        // Only the current version appears in listings
+        assert_eq!(db.get_documents_by_user_with_role(user.id, user.role, 50, 0).await?.len(), 1);
+
+        // Retention of one archived version prunes the oldest and its file
+        ingestion.ingest_document(synced_file(user.id, source.id, b"third draft")).await.unwrap();
+        let versions = db.get_document_versions(original.id).await?;
+        assert_eq!(versions.iter().map(|v| v.version_number).collect::<Vec<_>>(), vec![2]);
+        assert!(!std::path::Path::new(&original.file_path).exists());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
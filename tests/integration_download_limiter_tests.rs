@@ -0,0 +1,120 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tower::ServiceExt;
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+async fn get_metrics(ctx: &TestContext) -> Result<String> {
+    let request = Request::builder().method("GET").uri("/metrics").body(Body::empty())?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok(String::from_utf8(bytes.to_vec())?)
+}
+
+#[tokio::test]
+async fn test_downloads_in_use_are_exported() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let limiter = &ctx.state.download_limiter;
+        let metrics = get_metrics(&ctx).await?;
+        assert!(metrics.contains("readur_source_downloads_active 0 "), "{}", metrics);
+        assert!(metrics.contains(&format!("readur_source_downloads_max {} ", limiter.capacity())));
+
+        let _first = limiter.acquire().await;
+        let _second = limiter.acquire().await;
+        let metrics = get_metrics(&ctx).await?;
+        assert!(metrics.contains("readur_source_downloads_active 2 "), "{}", metrics);
+        assert!(metrics.contains("# TYPE readur_source_downloads_active gauge"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_source_downloads_wait_for_the_instance_limit() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let source_dir = tempfile::tempdir()?;
+    let storage_dir = tempfile::tempdir()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        // A document synced from a local folder whose stored copy is truncated
+        let original = b"%PDF-1.4 original scan contents".repeat(20);
+        let source_file = source_dir.path().join("scan.pdf");
+        std::fs::write(&source_file, &original)?;
+        let stored_file = storage_dir.path().join("scan.pdf");
+        std::fs::write(&stored_file, &original[..10])?;
+
+        let source = ctx.state.db.create_source(user_id, &CreateSource {
+            name: "Scans".to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(true),
+            config: serde_json::json!({
+                "watch_folders": [source_dir.path().to_string_lossy()],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false
+            }),
+        }).await?;
+        let mut document = create_test_document(user_id);
+        document.file_path = stored_file.to_string_lossy().to_string();
+        document.file_size = original.len() as i64;
+        document.file_hash = Some(format!("{:x}", Sha256::digest(&original)));
+        document.source_id = Some(source.id);
+        document.source_type = Some("local_folder".to_string());
+        document.source_path = Some(source_file.to_string_lossy().to_string());
+        let document = ctx.state.db.create_document(document).await?;
+
+        // Other syncs hold every download slot
+        let limiter = ctx.state.download_limiter.clone();
+        let mut held = Vec::new();
+        for _ in 0..limiter.capacity() {
+            held.push(limiter.acquire().await);
+        }
+
+        let request = Request::builder()
+            .method("POST")
+            .uri(format!("/api/documents/{}/redownload", document.id))
+            .header("Authorization", format!("Bearer {}", token))
+            .header("Content-Type", "application/json")
+            .body(Body::from("{}"))?;
+        let redownload = tokio::spawn(ctx.app.clone().oneshot(request));
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(!redownload.is_finished(), "the download must wait for a free slot");
+        assert_eq!(std::fs::read(&stored_file)?, &original[..10]);
+
+        held.clear();
+        let response = tokio::time::timeout(Duration::from_secs(10), redownload).await???;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(std::fs::read(&stored_file)?, original);
+        assert_eq!(limiter.in_use(), 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
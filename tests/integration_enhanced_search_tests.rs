@@ -208,12 +208,18 @@ mod tests {
         let request = SearchRequest {
             query: "test".to_string(),
             tags: None,
+            labels: None,
+            filter_match: None,
             mime_types: None,
             limit: None,
             offset: None,
             include_snippets: None,
             snippet_length: None,
+            clean_snippets: None,
             search_mode: None,
+            sort: None,
+            captured_after: None,
+            captured_before: None,
         };
         
         // Test that default values work correctly
@@ -227,12 +233,18 @@ mod tests {
         let request = SearchRequest {
             query: "test query".to_string(),
             tags: Some(vec!["tag1".to_string(), "tag2".to_string()]),
+            labels: None,
+            filter_match: None,
             mime_types: Some(vec!["application/pdf".to_string()]),
             limit: Some(10),
             offset: Some(0),
             include_snippets: Some(true),
             snippet_length: Some(300),
+            clean_snippets: None,
             search_mode: Some(SearchMode::Phrase),
+            sort: None,
+            captured_after: None,
+            captured_before: None,
         };
         
         assert_eq!(request.query, "test query");
@@ -594,12 +606,18 @@ mod tests {
         let empty_request = SearchRequest {
             query: "".to_string(),
             tags: None,
+            labels: None,
+            filter_match: None,
             mime_types: None,
             limit: None,
             offset: None,
             include_snippets: None,
             snippet_length: None,
+            clean_snippets: None,
             search_mode: None,
+            sort: None,
+            captured_after: None,
+            captured_before: None,
         };
         
         // Should handle empty query gracefully
@@ -609,12 +627,18 @@ mod tests {
         let extreme_request = SearchRequest {
             query: "a".repeat(10000), // Very long query
             tags: Some(vec!["tag".to_string(); 1000]), // Many tags
+            labels: None,
+            filter_match: None,
             mime_types: Some(vec!["type".to_string(); 100]), // Many mime types
             limit: Some(i64::MAX),
             offset: Some(i64::MAX),
             include_snippets: Some(true),
             snippet_length: Some(i32::MAX),
+            clean_snippets: None,
             search_mode: Some(SearchMode::Boolean),
+            sort: None,
+            captured_after: None,
+            captured_before: None,
         };
         
         // Should handle extreme values without panicking
@@ -947,12 +971,18 @@ mod tests {
         let search_request = SearchRequest {
             query: "test".to_string(),
             tags: None,
+            labels: None,
+            filter_match: None,
             mime_types: None,
             limit: Some(10),
             offset: Some(0),
             include_snippets: Some(true),
             snippet_length: Some(100),
+            clean_snippets: None,
             search_mode: Some(SearchMode::Simple),
+            sort: None,
+            captured_after: None,
+            captured_before: None,
         };
         
         let result = ctx.state.db.enhanced_search_documents(user.user_response.id, &search_request).await;
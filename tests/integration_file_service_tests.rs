@@ -69,6 +69,94 @@ mod tests {
         assert!(!filename_part.contains('.'));
     }
 
+    #[tokio::test]
+    async fn test_streaming_save_hashes_incrementally() {
+        let (service, _temp_dir) = create_test_file_service();
+
+        let mut writer = service.begin_streaming_save("streamed.pdf").await.unwrap();
+        writer.write_chunk(b"Hello, ").await.unwrap();
+        writer.write_chunk(b"World!").await.unwrap();
+        assert_eq!(writer.bytes_written(), 13);
+
+        let stored = writer.finish().await.unwrap();
+        assert!(stored.file_path.ends_with(".pdf"));
+        assert_eq!(stored.file_size, 13);
+        assert_eq!(fs::read(&stored.file_path).unwrap(), b"Hello, World!");
+
+        // SHA-256 of "Hello, World!"
+        assert_eq!(
+            stored.file_hash,
+            "dffd6021bb2bd5b0af676290809ec3a53191dd81c7f70a4b28688a362182986f"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_streaming_save_of_uneven_chunks_matches_whole_file() {
+        use sha2::{Digest, Sha256};
+
+        let (service, _temp_dir) = create_test_file_service();
+        // Larger than the writer's buffer, in chunks that don't line up with it
+        let data: Vec<u8> = (0..3 * 1024 * 1024 + 17).map(|i| (i % 251) as u8).collect();
+
+        let mut writer = service.begin_streaming_save("large.bin").await.unwrap();
+        for chunk in data.chunks(65_521) {
+            writer.write_chunk(chunk).await.unwrap();
+        }
+        assert_eq!(writer.bytes_written(), data.len() as u64);
+
+        let stored = writer.finish().await.unwrap();
+        assert_eq!(stored.file_size, data.len() as i64);
+        assert_eq!(stored.file_hash, format!("{:x}", Sha256::digest(&data)));
+        assert_eq!(fs::read(&stored.file_path).unwrap(), data);
+    }
+
+    #[test]
+    fn test_storage_layout_parsing() {
+        use readur::services::file_service::StorageLayout;
+
+        assert_eq!("flat".parse::<StorageLayout>(), Ok(StorageLayout::Flat));
+        assert_eq!("sharded".parse::<StorageLayout>(), Ok(StorageLayout::Sharded { levels: 2 }));
+        assert_eq!(" Sharded:3 ".parse::<StorageLayout>(), Ok(StorageLayout::Sharded { levels: 3 }));
+        assert!("sharded:0".parse::<StorageLayout>().is_err());
+        assert!("sharded:5".parse::<StorageLayout>().is_err());
+        assert!("nested".parse::<StorageLayout>().is_err());
+        assert_eq!(StorageLayout::Sharded { levels: 3 }.to_string(), "sharded:3");
+    }
+
+    #[tokio::test]
+    async fn test_sharded_layout_nests_new_files_by_hash() {
+        use readur::services::file_service::StorageLayout;
+
+        let (service, _temp_dir) = create_test_file_service();
+        let service = service.with_storage_layout(StorageLayout::Sharded { levels: 2 });
+        // SHA-256 of "Hello, World!" starts with dffd
+        let shard_dir = service.get_documents_path().join("df").join("fd");
+
+        let saved = service.save_file("saved.txt", b"Hello, World!").await.unwrap();
+        assert_eq!(std::path::Path::new(&saved).parent(), Some(shard_dir.as_path()));
+        assert_eq!(fs::read(&saved).unwrap(), b"Hello, World!");
+
+        let mut writer = service.begin_streaming_save("streamed.pdf").await.unwrap();
+        writer.write_chunk(b"Hello, World!").await.unwrap();
+        let stored = writer.finish().await.unwrap();
+        assert_eq!(std::path::Path::new(&stored.file_path).parent(), Some(shard_dir.as_path()));
+        assert_eq!(fs::read(&stored.file_path).unwrap(), b"Hello, World!");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_save_abort_removes_partial_file() {
+        let (service, _temp_dir) = create_test_file_service();
+
+        let mut writer = service.begin_streaming_save("partial.pdf").await.unwrap();
+        writer.write_chunk(b"partial data").await.unwrap();
+        writer.abort().await;
+
+        let temp_entries = fs::read_dir(service.get_temp_path()).unwrap().count();
+        let document_entries = fs::read_dir(service.get_documents_path()).unwrap().count();
+        assert_eq!(temp_entries, 0);
+        assert_eq!(document_entries, 0);
+    }
+
     #[test]
     fn test_create_document() {
         let (service, _temp_dir) = create_test_file_service();
@@ -592,4 +680,65 @@ mod file_deletion_tests {
         assert!(!Path::new(&thumb_path).exists());
         assert!(!Path::new(&processed_path).exists());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_move_to_trash_and_restore() {
+        let (service, temp_dir) = create_test_file_service();
+        let user_id = uuid::Uuid::new_v4();
+
+        let (mut document, main_path, thumb_path, _processed_path) =
+            create_test_document_with_files(&service, &temp_dir, user_id);
+
+        let trash_path = service.move_to_trash(&document).await.unwrap();
+        assert_eq!(Path::new(&trash_path), service.get_trash_path().join("test_document.pdf"));
+        assert!(Path::new(&trash_path).exists());
+        assert!(!Path::new(&main_path).exists());
+        // Thumbnails stay in place until the document is purged
+        assert!(Path::new(&thumb_path).exists());
+
+        document.file_path = trash_path;
+        let restored_path = service.restore_from_trash(&document).await.unwrap();
+        assert_eq!(restored_path, main_path);
+        assert!(Path::new(&main_path).exists());
+    }
+
+    #[tokio::test]
+    async fn test_move_to_trash_missing_file_keeps_path() {
+        let (service, temp_dir) = create_test_file_service();
+        let user_id = uuid::Uuid::new_v4();
+
+        let (document, main_path, _thumb_path, _processed_path) =
+            create_test_document_with_files(&service, &temp_dir, user_id);
+        fs::remove_file(&main_path).unwrap();
+
+        let trash_path = service.move_to_trash(&document).await.unwrap();
+        assert_eq!(trash_path, document.file_path);
+    }
+
+    #[tokio::test]
+    async fn test_pdf_thumbnail_served_from_hash_keyed_cache() {
+        let (service, temp_dir) = create_test_file_service();
+        let user_id = uuid::Uuid::new_v4();
+
+        let (mut document, _main_path, _thumb_path, _processed_path) =
+            create_test_document_with_files(&service, &temp_dir, user_id);
+        document.file_hash = Some("0123456789abcdef0123456789abcdef".to_string());
+
+        let cached = service.get_thumbnails_path().join(format!("{}_0123456789abcdef_thumb.png", document.id));
+        fs::write(&cached, b"cached png").unwrap();
+
+        let thumbnail = service.get_or_generate_pdf_thumbnail(&document).await.unwrap();
+        assert_eq!(thumbnail.content_type, "image/png");
+        assert_eq!(thumbnail.data, b"cached png");
+
+        // A different hash misses the cache; the fake PDF can't be rendered so we get the placeholder
+        document.file_hash = Some("fedcba9876543210fedcba9876543210".to_string());
+        let thumbnail = service.get_or_generate_pdf_thumbnail(&document).await.unwrap();
+        assert_eq!(thumbnail.content_type, "image/svg+xml");
+
+        // Deleting the document removes rendered thumbnails too
+        service.delete_document_files(&document).await.unwrap();
+        assert!(!cached.exists());
+    }
+}
+
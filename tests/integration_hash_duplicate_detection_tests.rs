@@ -367,4 +367,39 @@ async fn test_file_service_create_document_without_hash() {
     assert_eq!(document.original_filename, "original.pdf");
     assert_eq!(document.file_hash, None);
     assert_eq!(document.user_id, user_id);
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn test_bulk_create_documents_skips_hash_conflicts() -> Result<()> {
+    let ctx = TestContext::new().await;
+    
+    let result = async {
+        let user_id = create_test_user(&ctx.state.db, "bulkuser").await?;
+        let existing_hash = calculate_file_hash(b"already uploaded");
+        
+        let existing = create_test_document(user_id, "existing.pdf", Some(existing_hash.clone()));
+        ctx.state.db.create_document(existing).await?;
+        
+        let conflicting = create_test_document(user_id, "conflict.pdf", Some(existing_hash));
+        let new_a = create_test_document(user_id, "new_a.pdf", Some(calculate_file_hash(b"new a")));
+        let new_b = create_test_document(user_id, "new_b.pdf", Some(calculate_file_hash(b"new b")));
+        // Same content twice within one batch: only the first row is inserted
+        let in_batch_duplicate = create_test_document(user_id, "new_b_copy.pdf", new_b.file_hash.clone());
+        
+        let documents = vec![conflicting.clone(), new_a.clone(), new_b.clone(), in_batch_duplicate.clone()];
+        let inserted = ctx.state.db.bulk_create_documents(&documents).await?;
+        
+        assert_eq!(inserted.len(), 2);
+        assert!(inserted.contains(&new_a.id));
+        assert!(inserted.contains(&new_b.id));
+        assert!(!inserted.contains(&conflicting.id));
+        assert!(!inserted.contains(&in_batch_duplicate.id));
+        
+        Ok(())
+    }.await;
+    
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    
+    result
+}
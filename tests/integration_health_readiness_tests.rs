@@ -0,0 +1,74 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+
+use readur::test_utils::TestContext;
+
+async fn get_readiness(ctx: &TestContext) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("GET")
+        .uri("/api/health/ready")
+        .body(Body::empty())
+        .unwrap();
+
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn test_readiness_reports_each_dependency() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let (status, body) = get_readiness(&ctx).await;
+
+        // The test database is migrated, so only OCR tooling can make this environment not ready
+        assert_eq!(body["checks"]["database"]["status"], "ok", "{}", body);
+        assert_eq!(body["checks"]["migrations"]["status"], "ok", "{}", body);
+        assert!(body["checks"]["ocr"]["status"].is_string());
+
+        let ocr_ok = body["checks"]["ocr"]["status"] == "ok";
+        assert_eq!(status, if ocr_ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE });
+        assert_eq!(body["status"], if ocr_ok { "ready" } else { "not_ready" });
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_readiness_fails_when_migrations_are_pending() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let latest: i64 = sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations")
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = $1")
+            .bind(latest)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, body) = get_readiness(&ctx).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "not_ready");
+        assert_eq!(body["checks"]["migrations"]["status"], "error");
+        assert!(body["checks"]["migrations"]["error"].as_str().unwrap().contains(&latest.to_string()));
+        assert_eq!(body["checks"]["database"]["status"], "ok");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,103 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+async fn send(ctx: &TestContext, method: &str, uri: &str, token: &str, body: Option<serde_json::Value>) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token));
+    let request = match body {
+        Some(body) => request.header("Content-Type", "application/json").body(Body::from(body.to_string()))?,
+        None => request.body(Body::empty())?,
+    };
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+#[tokio::test]
+async fn test_admin_sees_user_view_until_impersonation_stops() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let admin_token = auth_helper.login_user(&admin.username, &admin.password).await;
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let (status, impersonation) = send(&ctx, "POST", &format!("/api/users/{}/impersonate", user_id), &admin_token, None).await?;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", impersonation);
+        assert_eq!(impersonation["user"]["id"], user_id.to_string());
+        assert_eq!(impersonation["impersonated_by"], admin.user_response.id.to_string());
+        assert_eq!(impersonation["read_only"], true);
+        let token = impersonation["token"].as_str().unwrap().to_string();
+
+        let (status, me) = send(&ctx, "GET", "/api/auth/me", &token, None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(me["id"], user_id.to_string());
+        let (status, _) = send(&ctx, "GET", "/api/documents", &token, None).await?;
+        assert_eq!(status, StatusCode::OK);
+
+        // Changes are rejected while impersonating
+        let (status, _) = send(&ctx, "PUT", "/api/settings", &token, Some(serde_json::json!({ "search_results_per_page": 10 }))).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, session) = send(&ctx, "POST", "/api/auth/impersonate/stop", &token, None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(session["user"]["id"], admin.user_response.id.to_string());
+        let (status, me) = send(&ctx, "GET", "/api/auth/me", session["token"].as_str().unwrap(), None).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(me["role"], "admin");
+
+        // A regular token is not an impersonation to stop
+        let (status, _) = send(&ctx, "POST", "/api/auth/impersonate/stop", &admin_token, None).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_impersonation_is_admin_only() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let admin_token = auth_helper.login_user(&admin.username, &admin.password).await;
+        let other_admin = auth_helper.create_admin_user().await;
+        let user = auth_helper.create_test_user().await;
+        let user_token = auth_helper.login_user(&user.username, &user.password).await;
+        let victim = auth_helper.create_test_user().await;
+
+        let (status, _) = send(&ctx, "POST", &format!("/api/users/{}/impersonate", victim.user_response.id), &user_token, None).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&ctx, "POST", &format!("/api/users/{}/impersonate", other_admin.user_response.id), &admin_token, None).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&ctx, "POST", &format!("/api/users/{}/impersonate", admin.user_response.id), &admin_token, None).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        let (status, _) = send(&ctx, "POST", &format!("/api/users/{}/impersonate", uuid::Uuid::new_v4()), &admin_token, None).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,126 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+async fn apply_to_search(ctx: &TestContext, token: &str, label_id: Uuid, body: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/labels/{}/apply-to-search", label_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_document(ctx: &TestContext, user_id: Uuid, content: &str) -> Result<Uuid> {
+    let mut document = create_test_document(user_id);
+    document.content = Some(content.to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn create_label(ctx: &TestContext, user_id: Uuid, name: &str) -> Result<Uuid> {
+    Ok(sqlx::query_scalar("INSERT INTO labels (user_id, name) VALUES ($1, $2) RETURNING id")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+async fn labeled_documents(ctx: &TestContext, label_id: Uuid) -> Result<Vec<Uuid>> {
+    Ok(sqlx::query_scalar("SELECT document_id FROM document_labels WHERE label_id = $1 ORDER BY document_id")
+        .bind(label_id)
+        .fetch_all(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_label_is_applied_to_every_search_result() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let mut invoices = Vec::new();
+        for i in 0..3 {
+            invoices.push(create_document(&ctx, user_id, &format!("Quarterly invoice number {}", i)).await?);
+        }
+        create_document(&ctx, user_id, "Grandmother's apple pie recipe").await?;
+        let foreign = create_document(&ctx, other.user_response.id, "Someone else's invoice").await?;
+        invoices.sort();
+        let label_id = create_label(&ctx, user_id, "Invoices").await?;
+
+        // A dry run only counts
+        let (status, body) = apply_to_search(&ctx, &token, label_id, serde_json::json!({ "query": "invoice", "limit": 1, "dry_run": true })).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["matched"], 3);
+        assert_eq!(body["labeled"], 0);
+        assert!(labeled_documents(&ctx, label_id).await?.is_empty());
+
+        // Every match is labeled, not just the first page
+        let (status, body) = apply_to_search(&ctx, &token, label_id, serde_json::json!({ "query": "invoice", "limit": 1 })).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["matched"], 3);
+        assert_eq!(body["labeled"], 3);
+        let labeled = labeled_documents(&ctx, label_id).await?;
+        assert_eq!(labeled, invoices);
+        assert!(!labeled.contains(&foreign));
+
+        // Documents that already have the label are not counted again
+        let (status, body) = apply_to_search(&ctx, &token, label_id, serde_json::json!({ "query": "invoice" })).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["matched"], 3);
+        assert_eq!(body["labeled"], 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_apply_to_search_respects_label_ownership_and_search_limits() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        create_document(&ctx, user.user_response.id, "Quarterly invoice").await?;
+
+        let foreign_label = create_label(&ctx, other.user_response.id, "Theirs").await?;
+        let (status, _) = apply_to_search(&ctx, &token, foreign_label, serde_json::json!({ "query": "invoice" })).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(labeled_documents(&ctx, foreign_label).await?.is_empty());
+
+        let label_id = create_label(&ctx, user.user_response.id, "Mine").await?;
+        let (status, body) = apply_to_search(&ctx, &token, label_id, serde_json::json!({ "query": "i" })).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        let (status, _) = apply_to_search(&ctx, &token, label_id, serde_json::json!({ "dry_run": true })).await?;
+        assert!(status.is_client_error(), "a search needs a query");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
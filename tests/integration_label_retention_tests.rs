@@ -0,0 +1,148 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::UpdateSettings,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn post_json(ctx: &TestContext, token: &str, uri: &str, body: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body)?))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_label(ctx: &TestContext, token: &str, body: serde_json::Value) -> Result<Uuid> {
+    let (status, label) = post_json(ctx, token, "/api/labels", body).await?;
+    assert_eq!(status, StatusCode::OK);
+    Ok(label["id"].as_str().unwrap().parse()?)
+}
+
+/// Creates a document that is `age_days` old and carries the given labels
+async fn create_aged_document(ctx: &TestContext, user_id: Uuid, name: &str, age_days: i32, labels: &[Uuid]) -> Result<Uuid> {
+    let document = create_test_document_with_hash(user_id, name, format!("hash-{}", name));
+    let document = ctx.state.db.create_document(document).await?;
+
+    sqlx::query("UPDATE documents SET created_at = NOW() - make_interval(days => $2) WHERE id = $1")
+        .bind(document.id)
+        .bind(age_days)
+        .execute(ctx.state.db.get_pool())
+        .await?;
+    for label_id in labels {
+        sqlx::query("INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2)")
+            .bind(document.id)
+            .bind(label_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+    }
+
+    Ok(document.id)
+}
+
+fn document_ids(response: &serde_json::Value) -> Vec<Uuid> {
+    let mut ids: Vec<Uuid> = response["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|document| document["id"].as_str().unwrap().parse().unwrap())
+        .collect();
+    ids.sort();
+    ids
+}
+
+#[tokio::test]
+async fn test_retention_cleanup_uses_longest_label_retention() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let mut settings = UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string());
+        settings.retention_days = Some(Some(30));
+        ctx.state.db.create_or_update_settings(user_id, &settings).await?;
+
+        let tax = create_label(&ctx, &token, serde_json::json!({ "name": "Tax", "retention_days": 2555 })).await?;
+        let scratch = create_label(&ctx, &token, serde_json::json!({ "name": "Scratch", "retention_days": 7 })).await?;
+        let keep = create_label(&ctx, &token, serde_json::json!({ "name": "Keep", "retain_forever": true })).await?;
+
+        let old_unlabeled = create_aged_document(&ctx, user_id, "old.pdf", 60, &[]).await?;
+        create_aged_document(&ctx, user_id, "recent.pdf", 10, &[]).await?;
+        create_aged_document(&ctx, user_id, "tax_return.pdf", 60, &[tax]).await?;
+        let old_scratch = create_aged_document(&ctx, user_id, "scan.pdf", 10, &[scratch]).await?;
+        create_aged_document(&ctx, user_id, "deed.pdf", 400, &[scratch, keep]).await?;
+        create_aged_document(&ctx, user_id, "receipt.pdf", 60, &[scratch, tax]).await?;
+
+        let mut expected = vec![old_unlabeled, old_scratch];
+        expected.sort();
+
+        let (status, preview) = post_json(&ctx, &token, "/api/documents/cleanup/retention", serde_json::json!({ "dry_run": true })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(preview["dry_run"], true);
+        assert_eq!(document_ids(&preview), expected);
+
+        let trashed: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE deleted_at IS NOT NULL")
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(trashed, 0, "a dry run must not remove anything");
+
+        let (status, applied) = post_json(&ctx, &token, "/api/documents/cleanup/retention", serde_json::json!({})).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(applied["count"], 2);
+        assert_eq!(document_ids(&applied), expected);
+
+        let mut trashed: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM documents WHERE deleted_at IS NOT NULL")
+            .fetch_all(ctx.state.db.get_pool())
+            .await?;
+        trashed.sort();
+        assert_eq!(trashed, expected);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_retention_cleanup_for_all_users_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, _) = post_json(
+            &ctx, &token, "/api/documents/cleanup/retention",
+            serde_json::json!({ "dry_run": true, "all_users": true }),
+        ).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
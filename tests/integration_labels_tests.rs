@@ -28,6 +28,8 @@ mod tests {
                 color: "#ff0000".to_string(),
                 background_color: None,
                 icon: Some("star".to_string()),
+                retention_days: None,
+                retain_forever: false,
             };
 
             let result = sqlx::query_scalar::<_, uuid::Uuid>(
@@ -156,6 +158,8 @@ mod tests {
                 color: Some("#00ff00".to_string()),
                 background_color: None,
                 icon: Some("edit".to_string()),
+                retention_days: None,
+                retain_forever: None,
             };
 
             let result = sqlx::query_as::<_, Label>(
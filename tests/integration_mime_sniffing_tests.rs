@@ -0,0 +1,58 @@
+use anyhow::Result;
+use tempfile::TempDir;
+use uuid::Uuid;
+
+use readur::{
+    ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
+    models::Document,
+    services::file_service::FileService,
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0x00, 0x01];
+
+async fn upload(ingestion: &DocumentIngestionService, user_id: Uuid, filename: &str, content: &[u8], mime_type: &str) -> Document {
+    match ingestion.ingest_upload(filename, content.to_vec(), mime_type, user_id).await.unwrap() {
+        IngestionResult::Created(doc) => doc,
+        other => panic!("expected a new document, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_ingestion_stores_the_sniffed_mime_type() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let upload_dir = TempDir::new()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let ingestion = DocumentIngestionService::new(
+            ctx.state.db.clone(),
+            FileService::new(upload_dir.path().to_string_lossy().to_string()),
+        );
+
+        let misnamed = upload(&ingestion, user_id, "scan.pdf", JPEG, "application/pdf").await;
+        assert_eq!(misnamed.mime_type, "image/jpeg");
+
+        let extensionless = upload(&ingestion, user_id, "README", b"Plain notes without an extension", "application/octet-stream").await;
+        assert_eq!(extensionless.mime_type, "text/plain");
+
+        let unknown = upload(&ingestion, user_id, "blob", &[0x00, 0x9C, 0x42, 0x00, 0x07], "application/octet-stream").await;
+        assert_eq!(unknown.mime_type, "application/octet-stream");
+
+        let csv = upload(&ingestion, user_id, "ledger.csv", b"name,amount\nrent,900\n", "text/csv").await;
+        assert_eq!(csv.mime_type, "text/csv", "text formats keep their declared type");
+
+        let stored = ctx.state.db.get_document_by_id(misnamed.id, user_id, readur::models::UserRole::User).await?.unwrap();
+        assert_eq!(stored.mime_type, "image/jpeg");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,125 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+const BOUNDARY: &str = "ocr-benchmark-test-boundary";
+
+fn multipart_body(files: &[(&str, &str, &[u8])], fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            BOUNDARY, name, value
+        ).as_bytes());
+    }
+    for (filename, content_type, data) in files {
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            BOUNDARY, filename, content_type
+        ).as_bytes());
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+async fn post_benchmark(ctx: &TestContext, token: &str, body: Vec<u8>) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/ocr/benchmark")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+}
+
+#[tokio::test]
+async fn test_benchmark_reports_throughput_of_uploaded_samples_without_touching_the_queue() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let body = multipart_body(
+            &[
+                ("invoice.txt", "text/plain", b"Invoice 4711 total 12.50"),
+                ("letter.txt", "text/plain", b"Dear customer, thank you for your order."),
+            ],
+            &[("iterations", "3"), ("concurrency", "1")],
+        );
+        let (status, report) = post_benchmark(&ctx, &token, body).await;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", report);
+        assert_eq!(report["bundled_samples"], false);
+        assert_eq!(report["iterations"], 3);
+        assert_eq!(report["concurrency"], 1);
+        assert_eq!(report["runs"], 6);
+        assert_eq!(report["failed_runs"], 0);
+        assert_eq!(report["pages"], 6);
+        assert!(report["pages_per_second"].as_f64().unwrap() > 0.0);
+        assert!(report["files_per_hour"].as_f64().unwrap() > 0.0);
+        assert!(report["p95_latency_ms"].is_u64());
+
+        let samples = report["samples"].as_array().unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[0]["filename"], "invoice.txt");
+        assert_eq!(samples[0]["runs"], 3);
+        assert!(samples[0]["average_latency_ms"].is_f64());
+
+        // Nothing is persisted
+        let documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents")
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(documents, 0);
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue")
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(queued, 0);
+
+        // Parameters are bounded
+        for fields in [[("iterations", "0")], [("iterations", "11")], [("concurrency", "50")]] {
+            let body = multipart_body(&[("a.txt", "text/plain", b"text")], &fields);
+            let (status, _) = post_benchmark(&ctx, &token, body).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{:?}", fields);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_benchmark_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, _) = post_benchmark(&ctx, &token, multipart_body(&[], &[])).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
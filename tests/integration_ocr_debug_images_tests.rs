@@ -0,0 +1,129 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tempfile::TempDir;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::CreateProcessedImage,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+const PNG: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+async fn get(ctx: &TestContext, token: &str, uri: String) -> Result<(StatusCode, Option<String>, Vec<u8>)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let content_type = response.headers().get("Content-Type").and_then(|v| v.to_str().ok()).map(str::to_string);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, content_type, bytes.to_vec()))
+}
+
+async fn create_image_document(ctx: &TestContext, user_id: Uuid, name: &str) -> Result<Uuid> {
+    let mut document = create_test_document_with_hash(user_id, name, format!("hash-{}", name));
+    document.mime_type = "image/jpeg".to_string();
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+#[tokio::test]
+async fn test_debug_images_return_parameters_to_owner_and_admin_only() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let processed_dir = TempDir::new()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let admin = auth_helper.create_admin_user().await;
+        let owner_token = auth_helper.login_user(&owner.username, &owner.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let admin_token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let document_id = create_image_document(&ctx, owner.user_response.id, "receipt.jpg").await?;
+        let processed_path = processed_dir.path().join(format!("{}_processed.png", document_id));
+        std::fs::write(&processed_path, PNG)?;
+        ctx.state.db.create_processed_image(&CreateProcessedImage {
+            document_id,
+            user_id: owner.user_response.id,
+            original_image_path: "/tmp/receipt.jpg".to_string(),
+            processed_image_path: processed_path.to_string_lossy().to_string(),
+            processing_parameters: serde_json::json!({
+                "steps": ["Contrast enhancement"],
+                "original_path": "/tmp/receipt.jpg",
+                "preprocessing": {
+                    "rotation_degrees": 90,
+                    "contrast_multiplier": 2.5,
+                    "binarization_window": 31,
+                },
+            }),
+            processing_steps: vec!["Contrast enhancement".to_string()],
+            image_width: 600,
+            image_height: 800,
+            file_size: PNG.len() as i64,
+        }).await?;
+
+        let uri = format!("/api/documents/{}/ocr/debug-images", document_id);
+        let (status, _, body) = get(&ctx, &owner_token, uri.clone()).await?;
+        assert_eq!(status, StatusCode::OK);
+        let debug: serde_json::Value = serde_json::from_slice(&body)?;
+        assert_eq!(debug["original_url"], format!("/api/documents/{}/view", document_id));
+        assert_eq!(debug["original_mime_type"], "image/jpeg");
+        assert_eq!(debug["processing_steps"], serde_json::json!(["Contrast enhancement"]));
+        assert_eq!(debug["parameters"]["rotation_degrees"], 90);
+        assert_eq!(debug["parameters"]["binarization_window"], 31);
+        assert!(debug["parameters"].get("original_path").is_none(), "server paths are not exposed");
+
+        let (status, _, _) = get(&ctx, &admin_token, uri.clone()).await?;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _, _) = get(&ctx, &other_token, uri).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let processed_uri = debug["processed_url"].as_str().unwrap().to_string();
+        let (status, content_type, image) = get(&ctx, &owner_token, processed_uri.clone()).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type.as_deref(), Some("image/png"));
+        assert_eq!(image, PNG);
+
+        let (status, _, _) = get(&ctx, &other_token, processed_uri).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_debug_images_not_found_without_saved_processed_image() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let document_id = create_image_document(&ctx, user.user_response.id, "scan.jpg").await?;
+        let (status, _, _) = get(&ctx, &token, format!("/api/documents/{}/ocr/debug-images", document_id)).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
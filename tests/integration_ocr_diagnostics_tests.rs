@@ -0,0 +1,75 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+async fn diagnostics(ctx: &TestContext, token: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri("/api/ocr/diagnostics")
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+#[tokio::test]
+async fn test_ocr_diagnostics_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, _) = diagnostics(&ctx, &token).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_ocr_diagnostics_reports_tools_and_languages() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let (status, response) = diagnostics(&ctx, &token).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["ocr_enabled"], cfg!(feature = "ocr"));
+
+        let tesseract = &response["tesseract"];
+        assert_eq!(tesseract["installed"], true);
+        assert!(tesseract["version"].as_str().unwrap().to_lowercase().contains("tesseract"));
+        assert_eq!(tesseract["usable"], cfg!(feature = "ocr"));
+        assert!(response["ocrmypdf"]["installed"].is_boolean());
+
+        let languages = response["languages"].as_array().unwrap();
+        let eng = languages.iter().find(|language| language["code"] == "eng").expect("eng is installed");
+        assert_eq!(eng["name"], "English");
+        assert_eq!(eng["usable"], cfg!(feature = "ocr"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
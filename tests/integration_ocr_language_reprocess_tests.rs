@@ -0,0 +1,148 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::OcrOverrides,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn reprocess(ctx: &TestContext, token: &str, body: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents/ocr/reprocess")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+/// Creates a document whose OCR already completed in the wrong language
+async fn create_document(ctx: &TestContext, user_id: Uuid, name: &str, mime_type: &str) -> Result<Uuid> {
+    let mut document = create_test_document_with_hash(user_id, name, format!("hash-{}", name));
+    document.mime_type = mime_type.to_string();
+    document.ocr_text = Some("Rechnunq fur Beratunq".to_string());
+    document.ocr_confidence = Some(41.0);
+    document.ocr_status = Some("completed".to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn queued_overrides(ctx: &TestContext, document_id: Uuid) -> Result<Option<(i32, OcrOverrides)>> {
+    let row: Option<(i32, Option<serde_json::Value>)> = sqlx::query_as(
+        "SELECT priority, ocr_overrides FROM ocr_queue WHERE document_id = $1 AND status = 'pending'"
+    )
+    .bind(document_id)
+    .fetch_optional(ctx.state.db.get_pool())
+    .await?;
+    Ok(match row {
+        Some((priority, overrides)) => Some((priority, serde_json::from_value(overrides.expect("overrides stored with the job"))?)),
+        None => None,
+    })
+}
+
+#[tokio::test]
+async fn test_reprocess_clears_ocr_and_queues_with_language_override() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let scan = create_document(&ctx, user_id, "scan.pdf", "application/pdf").await?;
+        let photo = create_document(&ctx, user_id, "photo.png", "image/png").await?;
+        let busy = create_document(&ctx, user_id, "busy.pdf", "application/pdf").await?;
+        let foreign = create_document(&ctx, other.user_response.id, "foreign.pdf", "application/pdf").await?;
+        ctx.state.queue_service.enqueue_document(busy, 5, 1024).await?;
+
+        let (status, response) = reprocess(&ctx, &token, serde_json::json!({
+            "language": "spa",
+            "mime_types": ["application/pdf"],
+        })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["queued"], 1);
+        assert_eq!(response["skipped"], 1, "documents already queued are left alone");
+
+        let (priority, overrides) = queued_overrides(&ctx, scan).await?.expect("scan.pdf is queued");
+        assert!(priority < 5, "reprocessing runs below normal ingestion");
+        assert_eq!(overrides, OcrOverrides { language: Some("spa".to_string()), ..OcrOverrides::default() });
+        assert_eq!(overrides.apply_to(&readur::models::Settings::default()).primary_language, "spa");
+
+        let document = ctx.state.db.get_document_by_id(scan, user_id, user.user_response.role).await?.unwrap();
+        assert_eq!(document.ocr_text, None);
+        assert_eq!(document.ocr_confidence, None);
+        assert_eq!(document.ocr_status.as_deref(), Some("pending"));
+
+        assert!(queued_overrides(&ctx, photo).await?.is_none(), "other MIME types are not matched");
+        assert!(queued_overrides(&ctx, foreign).await?.is_none(), "other users' documents are not matched");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_reprocess_filters_by_ids_and_dates_and_rejects_bad_input() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let picked = create_document(&ctx, user_id, "picked.pdf", "application/pdf").await?;
+        let old = create_document(&ctx, user_id, "old.pdf", "application/pdf").await?;
+        sqlx::query("UPDATE documents SET created_at = $2 WHERE id = $1")
+            .bind(old)
+            .bind(Utc::now() - Duration::days(30))
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let (status, response) = reprocess(&ctx, &token, serde_json::json!({
+            "language": "eng+spa",
+            "document_ids": [picked, old],
+            "created_after": Utc::now() - Duration::days(1),
+        })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["queued"], 1);
+        assert!(queued_overrides(&ctx, picked).await?.is_some());
+        assert!(queued_overrides(&ctx, old).await?.is_none());
+
+        for body in [
+            serde_json::json!({ "language": "" }),
+            serde_json::json!({ "language": "eng; rm" }),
+            serde_json::json!({ "language": "notalanguage" }),
+            serde_json::json!({
+                "language": "eng",
+                "created_after": Utc::now(),
+                "created_before": Utc::now() - Duration::days(1),
+            }),
+        ] {
+            let (status, _) = reprocess(&ctx, &token, body.clone()).await?;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,143 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, Settings, SourceType},
+    ocr::{error::OcrError, language_routing::OcrLanguageRoutes, queue::OcrQueueService},
+    routes::sources::check_language_routes_installed,
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+fn local_folder_config(routes: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "watch_folders": ["/Docs"],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "recursive": true,
+        "follow_symlinks": false,
+        "ocr_language_routes": routes
+    })
+}
+
+/// Creates a document synced from `source_id` at `source_path` with `labels`, queues it and
+/// returns the OCR language its job resolves to
+async fn resolved_language(ctx: &TestContext, user_id: Uuid, source_id: Uuid, source_path: &str, labels: &[Uuid]) -> Result<String> {
+    let mut document = create_test_document(user_id);
+    document.source_id = Some(source_id);
+    document.source_type = Some("local_folder".to_string());
+    document.source_path = Some(source_path.to_string());
+    let document = ctx.state.db.create_document(document).await?;
+    for label_id in labels {
+        sqlx::query("INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2)")
+            .bind(document.id)
+            .bind(label_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+    }
+    ctx.state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+
+    let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+    assert_eq!(item.document_id, document.id);
+    let (settings, _) = OcrQueueService::resolve_job_settings(&item, &Settings::default());
+    Ok(settings.ocr_language)
+}
+
+#[tokio::test]
+async fn test_documents_are_read_in_the_language_of_their_folder_or_label() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user_id = auth_helper.create_test_user().await.user_response.id;
+
+        let french: Uuid = sqlx::query_scalar("INSERT INTO labels (user_id, name) VALUES ($1, 'Français') RETURNING id")
+            .bind(user_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        let source = ctx.state.db.create_source(user_id, &CreateSource {
+            name: "Library".to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(false),
+            config: local_folder_config(serde_json::json!([
+                { "path_prefix": "/Docs/Deutsch", "language": "deu" },
+                { "path_prefix": "/Docs/English", "language": "eng" },
+                { "label": "Français", "language": "fra" }
+            ])),
+        }).await?;
+
+        assert_eq!(resolved_language(&ctx, user_id, source.id, "/Docs/Deutsch/2024/brief.pdf", &[]).await?, "deu");
+        assert_eq!(resolved_language(&ctx, user_id, source.id, "/Docs/English/letter.pdf", &[french]).await?, "eng");
+        assert_eq!(resolved_language(&ctx, user_id, source.id, "/Docs/Misc/lettre.pdf", &[french]).await?, "fra");
+        // Unmatched documents keep the user's language
+        let default_language = Settings::default().ocr_language;
+        assert_eq!(resolved_language(&ctx, user_id, source.id, "/Docs/Deutschland.pdf", &[]).await?, default_language);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_invalid_language_routes_are_rejected_on_save() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        // Routes can't be checked, and so aren't saved, without Tesseract listing its languages
+        let (_, languages) = auth_helper.send_authenticated_request("GET", "/api/settings/ocr/languages", None, &token).await;
+        let installed_route_status = if languages["detected"] == true { StatusCode::OK } else { StatusCode::BAD_REQUEST };
+
+        let cases = [
+            (serde_json::json!([{ "language": "deu" }]), StatusCode::BAD_REQUEST),
+            (serde_json::json!([{ "path_prefix": "/Docs/Deutsch", "label": "German", "language": "deu" }]), StatusCode::BAD_REQUEST),
+            (serde_json::json!([{ "path_prefix": "/Docs/Deutsch", "language": "../deu" }]), StatusCode::BAD_REQUEST),
+            (serde_json::json!([{ "path_prefix": "/Docs/English", "language": "eng" }]), installed_route_status),
+        ];
+        for (routes, expected) in cases {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/sources")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_vec(&serde_json::json!({
+                    "name": format!("Library {}", Uuid::new_v4().simple()),
+                    "source_type": "local_folder",
+                    "enabled": false,
+                    "config": local_folder_config(routes.clone())
+                }))?))?;
+
+            let response = ctx.app.clone().oneshot(request).await?;
+            assert_eq!(response.status(), expected, "routes {}", routes);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[test]
+fn test_language_routes_are_rejected_when_tesseract_is_unavailable() {
+    let config = local_folder_config(serde_json::json!([{ "path_prefix": "/Docs/English", "language": "eng" }]));
+    let routes = OcrLanguageRoutes::from_source_config(&config).unwrap().unwrap();
+
+    assert!(check_language_routes_installed(&routes, Ok(vec!["eng".to_string()])).is_ok());
+    assert!(check_language_routes_installed(&routes, Ok(vec!["deu".to_string()])).is_err());
+    let error = check_language_routes_installed(&routes, Err(OcrError::TesseractNotInstalled)).unwrap_err();
+    assert!(error.contains("can't be checked"), "{}", error);
+}
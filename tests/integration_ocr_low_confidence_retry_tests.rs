@@ -0,0 +1,126 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use readur::{
+    models::{OcrOverrides, UpdateSettings},
+    ocr::enhanced::EnhancedOcrService,
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+/// Creates a plain-text document for a user whose confidence threshold no result can meet,
+/// so every OCR run (100% for plain text) counts as low confidence.
+async fn setup_low_confidence_document(ctx: &TestContext, dir: &tempfile::TempDir, retry_enabled: bool) -> Result<Uuid> {
+    let auth_helper = TestAuthHelper::new(ctx.app.clone());
+    let user = auth_helper.create_test_user().await;
+    let user_id = user.user_response.id;
+
+    let mut update = UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string());
+    update.ocr_min_confidence = Some(101.0);
+    update.ocr_retry_low_confidence = Some(retry_enabled);
+    ctx.state.db.create_or_update_settings(user_id, &update).await?;
+
+    let path = dir.path().join("faded_scan.txt");
+    std::fs::write(&path, "Invoice 4711 total 12.50")?;
+
+    let mut document = create_test_document(user_id);
+    document.file_path = path.to_string_lossy().to_string();
+    document.mime_type = "text/plain".to_string();
+    document.ocr_status = Some("pending".to_string());
+    let document = ctx.state.db.create_document(document).await?;
+
+    ctx.state.queue_service.enqueue_document(document.id, 5, 24).await?;
+    Ok(document.id)
+}
+
+/// OCR status, error, failure reason and retry count of a document
+async fn ocr_state(ctx: &TestContext, document_id: Uuid) -> Result<(Option<String>, Option<String>, Option<String>, Option<i32>)> {
+    Ok(sqlx::query_as(
+        "SELECT ocr_status, ocr_error, ocr_failure_reason, ocr_retry_count FROM documents WHERE id = $1"
+    )
+    .bind(document_id)
+    .fetch_one(ctx.state.db.get_pool())
+    .await?)
+}
+
+async fn process_next_job(ctx: &TestContext, ocr_service: &EnhancedOcrService) -> Result<()> {
+    let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+    ctx.state.queue_service.process_item(item, ocr_service).await
+}
+
+#[tokio::test]
+async fn test_low_confidence_result_is_retried_once_with_escalated_settings() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let dir = tempfile::tempdir()?;
+        let document_id = setup_low_confidence_document(&ctx, &dir, true).await?;
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+
+        process_next_job(&ctx, &ocr_service).await?;
+
+        let (status, overrides): (String, Option<serde_json::Value>) = sqlx::query_as(
+            "SELECT status, ocr_overrides FROM ocr_queue WHERE document_id = $1"
+        )
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(status, "pending");
+        let overrides: OcrOverrides = serde_json::from_value(overrides.expect("escalation stored with the job"))?;
+        assert_eq!(overrides.escalate, Some(true));
+
+        let (status, _, _, retry_count) = ocr_state(&ctx, document_id).await?;
+        assert_eq!(status.as_deref(), Some("pending"));
+        assert_eq!(retry_count, Some(1));
+
+        // The escalated run is still below the threshold, so the document fails for good
+        process_next_job(&ctx, &ocr_service).await?;
+
+        let (status, error, failure_reason, retry_count) = ocr_state(&ctx, document_id).await?;
+        assert_eq!(status.as_deref(), Some("failed"));
+        assert_eq!(failure_reason.as_deref(), Some("low_ocr_confidence"));
+        assert!(error.unwrap().starts_with("low confidence after retry"));
+        assert_eq!(retry_count, Some(1));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_low_confidence_retry_can_be_disabled() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let dir = tempfile::tempdir()?;
+        let document_id = setup_low_confidence_document(&ctx, &dir, false).await?;
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+
+        process_next_job(&ctx, &ocr_service).await?;
+
+        let (status, error, _, retry_count) = ocr_state(&ctx, document_id).await?;
+        assert_eq!(status.as_deref(), Some("failed"));
+        assert!(error.unwrap().starts_with("OCR quality below threshold"));
+        assert_eq!(retry_count.unwrap_or(0), 0);
+
+        let overrides: Option<serde_json::Value> = sqlx::query_scalar(
+            "SELECT ocr_overrides FROM ocr_queue WHERE document_id = $1"
+        )
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(overrides, None);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
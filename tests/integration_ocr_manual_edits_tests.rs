@@ -0,0 +1,147 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use readur::{
+    ocr::enhanced::EnhancedOcrService,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+const CORRECTED_TEXT: &str = "Invoice 4711 total 12.50 EUR";
+
+/// Creates a plain-text document with completed OCR and corrects its text by hand
+async fn create_corrected_document(ctx: &TestContext, auth_helper: &TestAuthHelper, token: &str, user_id: Uuid, dir: &tempfile::TempDir) -> Result<Uuid> {
+    let path = dir.path().join("invoice.txt");
+    std::fs::write(&path, "Invoice 4711 total 12.5O EUR")?;
+
+    let mut document = create_test_document_with_hash(user_id, "invoice.txt", "hash-invoice".to_string());
+    document.file_path = path.to_string_lossy().to_string();
+    document.mime_type = "text/plain".to_string();
+    document.ocr_text = Some("Invoice 4711 total 12.5O EUR".to_string());
+    document.ocr_status = Some("completed".to_string());
+    let document_id = ctx.state.db.create_document(document).await?.id;
+
+    let (status, _) = auth_helper.send_authenticated_request(
+        "PUT", &format!("/api/documents/{}/ocr", document_id),
+        Some(serde_json::json!({ "ocr_text": CORRECTED_TEXT })), token,
+    ).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    Ok(document_id)
+}
+
+async fn get_ocr(auth_helper: &TestAuthHelper, token: &str, document_id: Uuid) -> Result<serde_json::Value> {
+    let (status, ocr) = auth_helper.send_authenticated_request("GET", &format!("/api/documents/{}/ocr", document_id), None, token).await;
+    assert_eq!(status, StatusCode::OK);
+    Ok(ocr)
+}
+
+async fn process_next_job(ctx: &TestContext, dir: &tempfile::TempDir) -> Result<()> {
+    let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+    let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+    ctx.state.queue_service.process_item(item, &ocr_service).await
+}
+
+#[tokio::test]
+async fn test_reruns_keep_manually_corrected_text() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let dir = tempfile::tempdir()?;
+        let document_id = create_corrected_document(&ctx, &auth_helper, &token, user.user_response.id, &dir).await?;
+
+        let ocr = get_ocr(&auth_helper, &token, document_id).await?;
+        assert_eq!(ocr["ocr_text"], CORRECTED_TEXT);
+        assert_eq!(ocr["manually_edited"], true);
+
+        // Bulk reprocessing leaves the document out
+        let (status, response) = auth_helper.send_authenticated_request(
+            "POST", "/api/documents/ocr/reprocess",
+            Some(serde_json::json!({ "document_ids": [document_id] })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["queued"], 0);
+        assert_eq!(response["skipped"], 1);
+        assert!(ctx.state.queue_service.dequeue().await?.is_none(), "nothing was queued");
+
+        // A single retry has to be confirmed
+        let (status, _) = auth_helper.send_authenticated_request(
+            "POST", &format!("/api/documents/{}/ocr/retry", document_id),
+            Some(serde_json::json!({})), &token,
+        ).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // A job queued some other way completes without reading the file again
+        ctx.state.queue_service.enqueue_document(document_id, 5, 28).await?;
+        process_next_job(&ctx, &dir).await?;
+
+        let ocr = get_ocr(&auth_helper, &token, document_id).await?;
+        assert_eq!(ocr["ocr_text"], CORRECTED_TEXT);
+        assert_eq!(ocr["ocr_status"], "completed");
+        assert_eq!(ocr["manually_edited"], true);
+
+        let events = ctx.state.db.get_document_events(document_id).await?;
+        assert_eq!(events.last().unwrap().detail.as_deref(), Some("kept manually corrected text"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_forced_reruns_replace_manually_corrected_text() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let dir = tempfile::tempdir()?;
+        let document_id = create_corrected_document(&ctx, &auth_helper, &token, user.user_response.id, &dir).await?;
+
+        let (status, response) = auth_helper.send_authenticated_request(
+            "POST", "/api/documents/ocr/reprocess",
+            Some(serde_json::json!({ "document_ids": [document_id], "force": true })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["queued"], 1);
+        assert_eq!(response["skipped"], 0);
+        process_next_job(&ctx, &dir).await?;
+
+        let ocr = get_ocr(&auth_helper, &token, document_id).await?;
+        assert_eq!(ocr["ocr_text"], "Invoice 4711 total 12.5O EUR");
+        assert_eq!(ocr["manually_edited"], false);
+
+        // A confirmed retry replaces a new correction as well
+        let (status, _) = auth_helper.send_authenticated_request(
+            "PUT", &format!("/api/documents/{}/ocr", document_id),
+            Some(serde_json::json!({ "ocr_text": CORRECTED_TEXT })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::NO_CONTENT);
+
+        let (status, _) = auth_helper.send_authenticated_request(
+            "POST", &format!("/api/documents/{}/ocr/retry", document_id),
+            Some(serde_json::json!({ "overwrite_manual_edits": true })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        process_next_job(&ctx, &dir).await?;
+
+        let ocr = get_ocr(&auth_helper, &token, document_id).await?;
+        assert_eq!(ocr["ocr_text"], "Invoice 4711 total 12.5O EUR");
+        assert_eq!(ocr["ocr_status"], "completed");
+        assert_eq!(ocr["manually_edited"], false);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
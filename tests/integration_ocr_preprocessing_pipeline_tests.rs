@@ -0,0 +1,102 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+
+use readur::{
+    ocr::preprocessing::{PreprocessingConfig, PreprocessingPreset, PreprocessingStep},
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+fn webdav_config(preprocessing: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "server_url": "https://cloud.example.com",
+        "username": "user",
+        "password": "secret",
+        "watch_folders": ["/Documents"],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "ocr_overrides": { "preprocessing": preprocessing }
+    })
+}
+
+#[tokio::test]
+async fn test_user_pipeline_is_saved_and_validated() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let pipeline = serde_json::json!([
+            { "step": "deskew", "max_angle": 4 },
+            { "step": "threshold", "threshold": 140 }
+        ]);
+        let (status, _) = auth_helper.send_authenticated_request("PUT", "/api/settings", Some(serde_json::json!({ "ocr_preprocessing": pipeline })), &token).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let settings = ctx.state.db.get_user_settings(user.user_response.id).await?.expect("settings saved");
+        assert_eq!(settings.ocr_preprocessing, Some(PreprocessingConfig::Steps(vec![
+            PreprocessingStep::Deskew { max_angle: 4.0 },
+            PreprocessingStep::Threshold { threshold: 140 },
+        ])));
+
+        // Unknown steps and out-of-range parameters leave the saved pipeline alone
+        for invalid in [
+            serde_json::json!([{ "step": "blur" }]),
+            serde_json::json!([{ "step": "binarize", "window": 4 }]),
+            serde_json::json!("grainy_photo"),
+        ] {
+            let (status, _) = auth_helper.send_authenticated_request("PUT", "/api/settings", Some(serde_json::json!({ "ocr_preprocessing": invalid })), &token).await;
+            assert!(status.is_client_error(), "accepted {}: {}", invalid, status);
+        }
+
+        let (status, _) = auth_helper.send_authenticated_request("PUT", "/api/settings", Some(serde_json::json!({ "ocr_preprocessing": "photo" })), &token).await;
+        assert_eq!(status, StatusCode::OK);
+        let (_, settings) = auth_helper.send_authenticated_request("GET", "/api/settings", None, &token).await;
+        assert_eq!(settings["ocr_preprocessing"], "photo");
+        assert_eq!(
+            ctx.state.db.get_user_settings(user.user_response.id).await?.unwrap().ocr_preprocessing,
+            Some(PreprocessingConfig::Preset(PreprocessingPreset::Photo))
+        );
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_source_pipeline_is_validated() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, _) = auth_helper.send_authenticated_request("POST", "/api/sources", Some(serde_json::json!({
+            "name": "Scanner",
+            "source_type": "webdav",
+            "config": webdav_config(serde_json::json!([{ "step": "denoise", "level": 5 }]))
+        })), &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, source) = auth_helper.send_authenticated_request("POST", "/api/sources", Some(serde_json::json!({
+            "name": "Scanner",
+            "source_type": "webdav",
+            "config": webdav_config(serde_json::json!("clean_scan"))
+        })), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", source);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,115 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+const BOUNDARY: &str = "ocr-preview-test-boundary";
+
+fn multipart_body(file: Option<(&str, &str, &[u8])>, fields: &[(&str, &str)]) -> Vec<u8> {
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+            BOUNDARY, name, value
+        ).as_bytes());
+    }
+    if let Some((filename, content_type, data)) = file {
+        body.extend_from_slice(format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+            BOUNDARY, filename, content_type
+        ).as_bytes());
+        body.extend_from_slice(data);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+    body
+}
+
+async fn post_preview(ctx: &TestContext, token: &str, body: Vec<u8>) -> (StatusCode, serde_json::Value) {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/ocr/preview")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))
+        .unwrap();
+
+    let response = ctx.app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null))
+}
+
+#[tokio::test]
+async fn test_preview_returns_text_without_creating_documents() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let body = multipart_body(
+            Some(("sample.txt", "text/plain", b"Invoice 4711 total 12.50")),
+            &[("psm", "6"), ("oem", "1")],
+        );
+        let (status, response) = post_preview(&ctx, &token, body).await;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", response);
+        assert!(response["text"].as_str().unwrap().contains("Invoice 4711"));
+        assert_eq!(response["psm"], 6);
+        assert_eq!(response["oem"], 1);
+        assert!(response["processing_time_ms"].is_u64());
+
+        // Nothing is persisted
+        let documents: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE user_id = $1")
+            .bind(user.user_response.id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(documents, 0);
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM ocr_queue")
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(queued, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_preview_rejects_invalid_input() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let sample = Some(("sample.txt", "text/plain", &b"hello"[..]));
+
+        // Same type restrictions as uploads
+        let body = multipart_body(Some(("tool.exe", "application/octet-stream", b"MZ")), &[]);
+        assert_eq!(post_preview(&ctx, &token, body).await.0, StatusCode::BAD_REQUEST);
+
+        for fields in [[("psm", "14")], [("oem", "9")], [("psm", "auto")], [("remove_noise", "maybe")]] {
+            let body = multipart_body(sample, &fields);
+            assert_eq!(post_preview(&ctx, &token, body).await.0, StatusCode::BAD_REQUEST, "{:?}", fields);
+        }
+
+        let body = multipart_body(None, &[("psm", "6")]);
+        assert_eq!(post_preview(&ctx, &token, body).await.0, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,163 @@
+use anyhow::Result;
+use axum::{body::Body, http::Request};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ocr::enhanced::EnhancedOcrService,
+    services::ocr_progress_tracker::{OcrEventKind, OcrProgressEvent},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+/// Creates a pending plain-text document whose file lives at `dir/name`, written only when `content` is set
+async fn create_text_document(ctx: &TestContext, user_id: Uuid, dir: &tempfile::TempDir, name: &str, content: Option<&str>) -> Result<Uuid> {
+    let path = dir.path().join(name);
+    if let Some(content) = content {
+        std::fs::write(&path, content)?;
+    }
+
+    let mut document = create_test_document(user_id);
+    document.file_path = path.to_string_lossy().to_string();
+    document.filename = name.to_string();
+    document.mime_type = "text/plain".to_string();
+    document.file_hash = Some(format!("hash-{}", name));
+    document.ocr_status = Some("pending".to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+fn drain(receiver: &mut tokio::sync::broadcast::Receiver<OcrProgressEvent>) -> Vec<OcrProgressEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = receiver.try_recv() {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+async fn test_queue_publishes_job_lifecycle_events() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+        let dir = tempfile::tempdir()?;
+        let document_id = create_text_document(&ctx, user_id, &dir, "notes.txt", Some("Invoice 4711 total 12.50")).await?;
+
+        let tracker = ctx.state.queue_service.progress_tracker();
+        let mut receiver = tracker.subscribe();
+        assert_eq!(tracker.subscriber_count(), 1);
+
+        let job_id = ctx.state.queue_service.enqueue_document(document_id, 5, 24).await?;
+
+        let depth = ctx.state.queue_service.get_queue_depth(user_id).await?;
+        assert_eq!((depth.queue_depth, depth.user_pending, depth.user_processing), (1, 1, 0));
+
+        let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        ctx.state.queue_service.process_item(item, &ocr_service).await?;
+
+        let events = drain(&mut receiver);
+        assert!(events.iter().all(|event| event.user_id == user_id && event.document_id == document_id && event.job_id == job_id));
+        let kinds: Vec<&str> = events.iter().map(|event| event.kind.name()).collect();
+        assert_eq!(kinds, vec!["enqueued", "started", "progress", "progress", "completed"]);
+        assert_eq!(events[0].kind, OcrEventKind::Enqueued { priority: 5 });
+        assert_eq!(events[2].kind, OcrEventKind::Progress { stage: "recognizing_text".to_string() });
+        assert!(matches!(events[4].kind, OcrEventKind::Completed { word_count: 4, .. }));
+
+        // The websocket sends the event as the message data, with the event type flattened in
+        let data = serde_json::to_value(&events[4])?;
+        assert_eq!(data["event"], "completed");
+        assert_eq!(data["document_id"], document_id.to_string());
+        assert!(data.get("user_id").is_none());
+
+        drop(receiver);
+        assert_eq!(tracker.subscriber_count(), 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_failed_job_event_reports_retry() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let dir = tempfile::tempdir()?;
+        let document_id = create_text_document(&ctx, user.user_response.id, &dir, "missing.txt", None).await?;
+
+        let mut receiver = ctx.state.queue_service.progress_tracker().subscribe();
+        ctx.state.queue_service.enqueue_document(document_id, 5, 24).await?;
+
+        let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        ctx.state.queue_service.process_item(item, &ocr_service).await?;
+
+        let last = drain(&mut receiver).pop().expect("OCR events");
+        match last.kind {
+            OcrEventKind::Failed { error, will_retry } => {
+                assert!(error.starts_with("OCR extraction failed"));
+                assert!(will_retry, "the first attempt of a job is retried");
+            }
+            other => panic!("expected a failed event, got {:?}", other),
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_completed_job_stays_in_processing_time_histogram() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let dir = tempfile::tempdir()?;
+        let document_id = create_text_document(&ctx, user.user_response.id, &dir, "receipt.txt", Some("Receipt 0815 total 3.20")).await?;
+
+        ctx.state.queue_service.enqueue_document(document_id, 5, 23).await?;
+        let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        ctx.state.queue_service.process_item(item, &ocr_service).await?;
+
+        // Completed rows are removed by the queue's cleanup; the job must stay counted anyway
+        ctx.state.queue_service.cleanup_completed(0).await?;
+        sqlx::query("DELETE FROM ocr_queue WHERE document_id = $1")
+            .bind(document_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+
+        let request = Request::builder().method("GET").uri("/metrics").body(Body::empty())?;
+        let response = ctx.app.clone().oneshot(request).await?;
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let metrics = String::from_utf8(bytes.to_vec())?;
+
+        // A text file is read in well under five minutes, so it lands in the largest bucket
+        assert!(metrics.contains("readur_ocr_processing_time_ms_bucket{le=\"300000\"} 1 "), "{}", metrics);
+        assert!(metrics.contains("readur_ocr_processing_time_ms_bucket{le=\"+Inf\"} 1 "), "{}", metrics);
+        assert!(metrics.contains("readur_ocr_processing_time_ms_count 1 "), "{}", metrics);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
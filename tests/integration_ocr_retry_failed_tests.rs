@@ -0,0 +1,135 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+async fn post_retry_failed(ctx: &TestContext, token: &str, body: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents/ocr/retry-failed")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body)?))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    Ok((status, body))
+}
+
+async fn create_failed_document(ctx: &TestContext, user_id: Uuid, failure_reason: &str) -> Result<Uuid> {
+    let mut document = create_test_document(user_id);
+    document.ocr_status = Some("failed".to_string());
+    document.ocr_failure_reason = Some(failure_reason.to_string());
+    document.ocr_error = Some("OCR failed".to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn ocr_status(ctx: &TestContext, document_id: Uuid) -> Result<(Option<String>, Option<String>)> {
+    Ok(sqlx::query_as("SELECT ocr_status, ocr_error FROM documents WHERE id = $1")
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_retry_failed_filters_by_reason_and_queues_at_low_priority() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let low_confidence = create_failed_document(&ctx, user_id, "low_ocr_confidence").await?;
+        let timed_out = create_failed_document(&ctx, user_id, "ocr_timeout").await?;
+
+        let (status, body) = post_retry_failed(&ctx, &token, serde_json::json!({
+            "failure_reason": "low_ocr_confidence"
+        })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["enqueued"], 1);
+        assert_eq!(body["remaining"], 0);
+
+        assert_eq!(ocr_status(&ctx, low_confidence).await?, (Some("pending".to_string()), None));
+        assert_eq!(ocr_status(&ctx, timed_out).await?.0.as_deref(), Some("failed"));
+
+        let priority: i32 = sqlx::query_scalar("SELECT priority FROM ocr_queue WHERE document_id = $1")
+            .bind(low_confidence)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert!(priority < 5, "reprocessing must not outrank uploads");
+
+        let history: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM ocr_retry_history WHERE document_id = $1 AND retry_reason = 'bulk_retry_failed'"
+        )
+        .bind(low_confidence)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(history, 1);
+
+        // A date range that excludes the documents selects nothing
+        let (status, body) = post_retry_failed(&ctx, &token, serde_json::json!({
+            "before": "2000-01-01T00:00:00Z"
+        })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["enqueued"], 0);
+
+        let (status, _) = post_retry_failed(&ctx, &token, serde_json::json!({
+            "after": "2030-01-01T00:00:00Z",
+            "before": "2020-01-01T00:00:00Z"
+        })).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_retry_failed_for_all_users_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other_user = auth_helper.create_test_user().await;
+        let admin = auth_helper.create_admin_user().await;
+        let user_token = auth_helper.login_user(&user.username, &user.password).await;
+        let admin_token = auth_helper.login_user(&admin.username, &admin.password).await;
+
+        let own = create_failed_document(&ctx, user.user_response.id, "ocr_timeout").await?;
+        let other = create_failed_document(&ctx, other_user.user_response.id, "ocr_timeout").await?;
+
+        let (status, _) = post_retry_failed(&ctx, &user_token, serde_json::json!({ "all_users": true })).await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // Without the flag only the caller's own documents are reprocessed
+        let (status, body) = post_retry_failed(&ctx, &user_token, serde_json::json!({})).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["enqueued"], 1);
+        assert_eq!(ocr_status(&ctx, own).await?.0.as_deref(), Some("pending"));
+        assert_eq!(ocr_status(&ctx, other).await?.0.as_deref(), Some("failed"));
+
+        let (status, _) = post_retry_failed(&ctx, &admin_token, serde_json::json!({ "all_users": true })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(ocr_status(&ctx, other).await?.0.as_deref(), Some("pending"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,122 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{OcrOverrides, Settings},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+async fn post_retry(ctx: &TestContext, token: &str, document_id: Uuid, body: serde_json::Value) -> StatusCode {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/documents/{}/ocr/retry", document_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body).unwrap()))
+        .unwrap();
+
+    ctx.app.clone().oneshot(request).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn test_retry_with_overrides_queues_them_without_saving() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let mut document = create_test_document(user.user_response.id);
+        document.ocr_status = Some("failed".to_string());
+        let document = ctx.state.db.create_document(document).await?;
+
+        // Out-of-range values are rejected before anything is queued
+        for body in [
+            serde_json::json!({ "psm": 14 }),
+            serde_json::json!({ "psm": -1 }),
+            serde_json::json!({ "oem": 4 }),
+            serde_json::json!({ "whitelist_chars": "AB\u{0007}" }),
+        ] {
+            assert_eq!(post_retry(&ctx, &token, document.id, body).await, StatusCode::BAD_REQUEST);
+        }
+
+        let status = post_retry(&ctx, &token, document.id, serde_json::json!({
+            "psm": 7,
+            "oem": 1,
+            "whitelist_chars": "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789"
+        })).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let queued: Vec<Option<serde_json::Value>> = sqlx::query_scalar(
+            "SELECT ocr_overrides FROM ocr_queue WHERE document_id = $1"
+        )
+        .bind(document.id)
+        .fetch_all(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(queued.len(), 1);
+        let overrides: OcrOverrides = serde_json::from_value(queued[0].clone().expect("overrides stored with the job"))?;
+        assert_eq!(overrides, OcrOverrides {
+            whitelist_chars: Some("ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789".to_string()),
+            blacklist_chars: None,
+            psm: Some(7),
+            oem: Some(1),
+            escalate: None,
+            ..OcrOverrides::default()
+        });
+
+        // The user's saved settings are untouched
+        let defaults = Settings::default();
+        if let Some(settings) = ctx.state.db.get_user_settings(user.user_response.id).await? {
+            assert_eq!(settings.ocr_page_segmentation_mode, defaults.ocr_page_segmentation_mode);
+            assert_eq!(settings.ocr_engine_mode, defaults.ocr_engine_mode);
+            assert_eq!(settings.ocr_whitelist_chars, defaults.ocr_whitelist_chars);
+        }
+
+        // The job runs with the overrides layered on the saved settings
+        let effective = overrides.apply_to(&defaults);
+        assert_eq!(effective.ocr_page_segmentation_mode, 7);
+        assert_eq!(effective.ocr_engine_mode, 1);
+        assert_eq!(effective.ocr_blacklist_chars, defaults.ocr_blacklist_chars);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_retry_without_overrides_queues_plain_job() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let document = ctx.state.db.create_document(create_test_document(user.user_response.id)).await?;
+
+        assert_eq!(post_retry(&ctx, &token, document.id, serde_json::json!({})).await, StatusCode::OK);
+
+        let queued: Vec<Option<serde_json::Value>> = sqlx::query_scalar(
+            "SELECT ocr_overrides FROM ocr_queue WHERE document_id = $1"
+        )
+        .bind(document.id)
+        .fetch_all(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!(queued, vec![None]);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
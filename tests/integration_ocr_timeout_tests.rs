@@ -0,0 +1,124 @@
+#![cfg(all(unix, feature = "ocr"))]
+
+use anyhow::Result;
+use std::os::unix::fs::PermissionsExt;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use readur::{
+    models::UpdateSettings,
+    ocr::enhanced::EnhancedOcrService,
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+/// Puts an `ocrmypdf` on PATH that answers `--version` but hangs on any real work, the way
+/// it can on a pathological PDF. Its hung subprocess writes its pid to `sleep.pid`.
+fn install_hanging_ocrmypdf(dir: &tempfile::TempDir) -> Result<()> {
+    let bin_dir = dir.path().join("bin");
+    std::fs::create_dir_all(&bin_dir)?;
+    let script = bin_dir.join("ocrmypdf");
+    std::fs::write(
+        &script,
+        "#!/bin/sh\n\
+         if [ \"$1\" = \"--version\" ]; then echo 16.0.0; exit 0; fi\n\
+         sleep 120 &\n\
+         echo $! > \"$(dirname \"$0\")/sleep.pid\"\n\
+         wait\n",
+    )?;
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755))?;
+
+    let path = std::env::var("PATH").unwrap_or_default();
+    std::env::set_var("PATH", format!("{}:{}", bin_dir.display(), path));
+    Ok(())
+}
+
+async fn create_pending_document(ctx: &TestContext, user_id: Uuid, path: &std::path::Path, mime_type: &str, priority: i32) -> Result<Uuid> {
+    let mut document = create_test_document(user_id);
+    document.file_path = path.to_string_lossy().to_string();
+    document.filename = path.file_name().unwrap().to_string_lossy().to_string();
+    document.mime_type = mime_type.to_string();
+    document.ocr_status = Some("pending".to_string());
+    let document = ctx.state.db.create_document(document).await?;
+    ctx.state.queue_service.enqueue_document(document.id, priority, 64).await?;
+    Ok(document.id)
+}
+
+#[tokio::test]
+async fn test_hung_ocrmypdf_is_killed_and_the_worker_moves_on() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let dir = tempfile::tempdir()?;
+        install_hanging_ocrmypdf(&dir)?;
+
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let mut update = UpdateSettings::language_update(vec!["eng".to_string()], "eng".to_string(), "eng".to_string());
+        update.ocr_timeout_seconds = Some(2);
+        update.ocr_text_layer_min_chars = Some(0);
+        update.ocr_parallel_pages = Some(false);
+        ctx.state.db.create_or_update_settings(user_id, &update).await?;
+
+        // A scan without a text layer, so it goes straight to ocrmypdf
+        let scan_path = dir.path().join("scan.pdf");
+        std::fs::write(&scan_path, b"%PDF-1.4\n1 0 obj << /Type /Catalog >> endobj\ntrailer << /Root 1 0 R >>\n%%EOF\n")?;
+        let scan_id = create_pending_document(&ctx, user_id, &scan_path, "application/pdf", 8).await?;
+        let note_path = dir.path().join("note.txt");
+        std::fs::write(&note_path, "Meeting notes for Tuesday")?;
+        let note_id = create_pending_document(&ctx, user_id, &note_path, "text/plain", 5).await?;
+
+        let queue = &ctx.state.queue_service;
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+
+        let started = Instant::now();
+        let item = queue.dequeue().await?.expect("the scan's OCR job");
+        assert_eq!(item.document_id, scan_id);
+        queue.process_item(item, &ocr_service).await?;
+        assert!(started.elapsed() < Duration::from_secs(15), "worker hung for {:?}", started.elapsed());
+
+        let (ocr_status, failure_reason): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT ocr_status, ocr_failure_reason FROM documents WHERE id = $1")
+                .bind(scan_id)
+                .fetch_one(ctx.state.db.get_pool())
+                .await?;
+        assert_eq!(ocr_status.as_deref(), Some("failed"));
+        assert_eq!(failure_reason.as_deref(), Some("ocr_timeout"));
+
+        // The job is no longer held by the worker
+        let job_status: String = sqlx::query_scalar("SELECT status FROM ocr_queue WHERE document_id = $1")
+            .bind(scan_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_ne!(job_status, "processing");
+
+        // The hung subprocess was killed along with ocrmypdf
+        let hung_pid = std::fs::read_to_string(dir.path().join("bin/sleep.pid"))?.trim().to_string();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        let state = std::fs::read_to_string(format!("/proc/{}/stat", hung_pid)).ok()
+            .and_then(|stat| stat.rsplit(") ").next().and_then(|rest| rest.chars().next()));
+        assert!(matches!(state, None | Some('Z')), "ocrmypdf's subprocess outlived the timeout");
+
+        // The next job is processed normally
+        let item = loop {
+            let item = queue.dequeue().await?.expect("the note's OCR job");
+            if item.document_id == note_id {
+                break item;
+            }
+        };
+        queue.process_item(item, &ocr_service).await?;
+        let ocr_status: Option<String> = sqlx::query_scalar("SELECT ocr_status FROM documents WHERE id = $1")
+            .bind(note_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(ocr_status.as_deref(), Some("completed"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ocr::word_boxes::{OcrPageWords, OcrWord},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+fn page_words(page: i32, words: &[&str]) -> OcrPageWords {
+    OcrPageWords {
+        page,
+        width: 2480,
+        height: 3508,
+        words: words
+            .iter()
+            .enumerate()
+            .map(|(index, text)| OcrWord {
+                text: text.to_string(),
+                confidence: 90.0,
+                bbox: [100 + index as i32 * 200, 150, 180, 50],
+                page,
+            })
+            .collect(),
+    }
+}
+
+async fn get_boxes(ctx: &TestContext, token: &str, document_id: Uuid, query: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/documents/{}/ocr/boxes{}", document_id, query))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    let body = serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null);
+    Ok((status, body))
+}
+
+#[tokio::test]
+async fn test_word_boxes_are_paginated_by_page() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let mut document = create_test_document(user.user_response.id);
+        document.mime_type = "application/pdf".to_string();
+        let document = ctx.state.db.create_document(document).await?;
+
+        ctx.state.db.replace_document_ocr_words(document.id, &[
+            page_words(1, &["Invoice", "4711"]),
+            page_words(2, &["Total"]),
+            page_words(3, &["Thank", "you"]),
+        ]).await?;
+
+        let (status, body) = get_boxes(&ctx, &token, document.id, "?page=2&limit=1").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_pages"], 3);
+        let pages: Vec<OcrPageWords> = serde_json::from_value(body["pages"].clone())?;
+        assert_eq!(pages, vec![page_words(2, &["Total"])]);
+
+        let (_, body) = get_boxes(&ctx, &token, document.id, "").await?;
+        let pages: Vec<OcrPageWords> = serde_json::from_value(body["pages"].clone())?;
+        assert_eq!(pages.iter().map(|page| page.page).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(pages[0].words[1].bbox, [300, 150, 180, 50]);
+
+        // Reprocessing replaces the stored boxes
+        ctx.state.db.replace_document_ocr_words(document.id, &[page_words(1, &["Rescanned"])]).await?;
+        let (_, body) = get_boxes(&ctx, &token, document.id, "").await?;
+        assert_eq!(body["total_pages"], 1);
+        assert_eq!(body["pages"][0]["words"][0]["text"], "Rescanned");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_word_boxes_of_other_users_documents_are_hidden() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+
+        let document = ctx.state.db.create_document(create_test_document(owner.user_response.id)).await?;
+        ctx.state.db.replace_document_ocr_words(document.id, &[page_words(1, &["Private"])]).await?;
+
+        let (status, _) = get_boxes(&ctx, &other_token, document.id, "").await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,83 @@
+#[cfg(all(test, feature = "ocr"))]
+mod ocr_work_dir_tests {
+    use readur::models::Settings;
+    use readur::ocr::enhanced::EnhancedOcrService;
+    use readur::ocr::work_dir::OcrWorkDir;
+    use std::path::Path;
+    use tempfile::TempDir;
+
+    /// Writes a one-page PDF whose text layer is `text`, with a correct xref table so
+    /// pdftotext reads it without repairs
+    fn write_text_pdf(path: &Path, text: &str) {
+        let stream = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R /Resources << /Font << /F1 5 0 R >> >> >>".to_string(),
+            format!("<< /Length {} >>\nstream\n{}\nendstream", stream.len(), stream),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+        ];
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{}\nendobj\n", index + 1, object));
+        }
+        let xref_offset = pdf.len();
+        pdf.push_str(&format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1));
+        for offset in offsets {
+            pdf.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref_offset
+        ));
+
+        std::fs::write(path, pdf).expect("Failed to write test PDF");
+    }
+
+    async fn ocr_in_own_work_dir(service: &EnhancedOcrService, pdf_path: &Path, settings: &Settings) -> String {
+        let work_dir = OcrWorkDir::create(&service.temp_dir).expect("Failed to create work directory");
+        service
+            .in_work_dir(&work_dir)
+            .extract_text_from_pdf(pdf_path.to_str().unwrap(), settings)
+            .await
+            .expect("PDF text extraction failed")
+            .text
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_pdf_jobs_do_not_share_intermediate_files() {
+        if std::process::Command::new("pdftotext").arg("-v").output().is_err() {
+            println!("pdftotext is not installed, skipping");
+            return;
+        }
+
+        let documents = TempDir::new().unwrap();
+        let scratch = TempDir::new().unwrap();
+        let first_pdf = documents.path().join("first.pdf");
+        let second_pdf = documents.path().join("second.pdf");
+        write_text_pdf(&first_pdf, "Alpha invoice from the first supplier covering October");
+        write_text_pdf(&second_pdf, "Bravo receipt from the second vendor covering November");
+
+        let service = EnhancedOcrService::new(scratch.path().to_string_lossy().to_string());
+        let settings = Settings::default();
+
+        for _ in 0..5 {
+            let (first_text, second_text) = tokio::join!(
+                ocr_in_own_work_dir(&service, &first_pdf, &settings),
+                ocr_in_own_work_dir(&service, &second_pdf, &settings),
+            );
+
+            assert!(first_text.contains("Alpha"), "first PDF lost its text: {:?}", first_text);
+            assert!(!first_text.contains("Bravo"), "first PDF got the second PDF's text: {:?}", first_text);
+            assert!(second_text.contains("Bravo"), "second PDF lost its text: {:?}", second_text);
+            assert!(!second_text.contains("Alpha"), "second PDF got the first PDF's text: {:?}", second_text);
+        }
+
+        let leftovers: Vec<_> = std::fs::read_dir(scratch.path()).unwrap().collect();
+        assert!(leftovers.is_empty(), "OCR left files behind: {:?}", leftovers);
+    }
+}
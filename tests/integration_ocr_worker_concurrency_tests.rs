@@ -0,0 +1,184 @@
+use anyhow::Result;
+use axum::{body::Body, http::Request};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ocr::{enhanced::EnhancedOcrService, queue::OcrQueueService},
+    test_utils::{document_helpers::{create_test_document, create_test_document_with_hash}, TestAuthHelper, TestContext},
+};
+
+async fn enqueue(ctx: &TestContext, user_id: Uuid, name: &str, priority: i32, file_size: i64) -> Result<Uuid> {
+    let document = ctx.state.db.create_document(create_test_document_with_hash(user_id, name, format!("hash-{}", name))).await?;
+    ctx.state.queue_service.enqueue_document(document.id, priority, file_size).await?;
+    Ok(document.id)
+}
+
+#[tokio::test]
+async fn test_dequeue_prefers_priority_then_smaller_files() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let large = enqueue(&ctx, user_id, "large.pdf", 5, 40 * 1024 * 1024).await?;
+        let small = enqueue(&ctx, user_id, "small.pdf", 5, 20 * 1024).await?;
+        let urgent = enqueue(&ctx, user_id, "urgent.pdf", 8, 80 * 1024 * 1024).await?;
+
+        let mut order = Vec::new();
+        while let Some(item) = ctx.state.queue_service.dequeue().await? {
+            order.push(item.document_id);
+        }
+        assert_eq!(order, vec![urgent, small, large]);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_worker_gauges_are_exported() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let request = Request::builder().method("GET").uri("/metrics").body(Body::empty())?;
+        let response = ctx.app.clone().oneshot(request).await?;
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+        let metrics = String::from_utf8(bytes.to_vec())?;
+
+        let limit = ctx.state.queue_service.worker_limit();
+        assert!(limit >= 1 && limit <= ctx.state.queue_service.max_concurrent_jobs().max(1));
+        assert!(metrics.contains("readur_ocr_workers_active 0 "), "no jobs run in tests:\n{}", metrics);
+        assert!(metrics.contains(&format!("readur_ocr_workers_max {} ", limit)));
+        assert!(metrics.contains("# TYPE readur_ocr_workers_configured_max gauge"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_document_enqueued_twice_is_processed_once() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("statement.txt");
+        std::fs::write(&path, "Statement of account 2024")?;
+
+        let mut document = create_test_document(user.user_response.id);
+        document.file_path = path.to_string_lossy().to_string();
+        document.filename = "statement.txt".to_string();
+        document.mime_type = "text/plain".to_string();
+        document.ocr_status = Some("pending".to_string());
+        let document_id = ctx.state.db.create_document(document).await?.id;
+
+        let queue = &ctx.state.queue_service;
+        let mut events = queue.progress_tracker().subscribe();
+
+        // A second enqueue, e.g. from a sync re-run, joins the waiting job
+        let first = queue.enqueue_document(document_id, 5, 25).await?;
+        let second = queue.enqueue_document(document_id, 8, 25).await?;
+        assert_eq!(first, second);
+
+        let item = queue.dequeue().await?.expect("a queued OCR job");
+        assert_eq!(item.priority, 8);
+        assert!(queue.dequeue().await?.is_none());
+
+        // Two tasks handed the same job only run OCR once
+        let ocr_service = EnhancedOcrService::new(dir.path().to_string_lossy().to_string());
+        let (a, b) = tokio::join!(queue.process_item(item.clone(), &ocr_service), queue.process_item(item, &ocr_service));
+        a?;
+        b?;
+
+        let mut started = 0;
+        while let Ok(event) = events.try_recv() {
+            if event.kind.name() == "started" {
+                started += 1;
+            }
+        }
+        assert_eq!(started, 1);
+
+        let jobs: Vec<(String, i32)> = sqlx::query_as("SELECT status, attempts FROM ocr_queue WHERE document_id = $1")
+            .bind(document_id)
+            .fetch_all(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(jobs, vec![("completed".to_string(), 1)]);
+        let (ocr_status, ocr_text): (Option<String>, Option<String>) = sqlx::query_as("SELECT ocr_status, ocr_text FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(ocr_status.as_deref(), Some("completed"));
+        assert!(ocr_text.is_some_and(|text| text.contains("Statement of account")));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_two_workers_claiming_batches_never_share_a_job() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let mut queued = Vec::new();
+        for i in 0..12 {
+            queued.push(enqueue(&ctx, user_id, &format!("batch-{}.pdf", i), 5, 1024).await?);
+        }
+
+        let pool = ctx.state.db.get_pool().clone();
+        let first = OcrQueueService::new(ctx.state.db.clone(), pool.clone(), 4).with_claim_batch_size(4);
+        let second = OcrQueueService::new(ctx.state.db.clone(), pool, 4).with_claim_batch_size(4);
+
+        let mut claimed = Vec::new();
+        loop {
+            let (a, b) = tokio::join!(first.dequeue_batch(4), second.dequeue_batch(4));
+            let (a, b) = (a?, b?);
+            if a.is_empty() && b.is_empty() {
+                break;
+            }
+            assert!(a.len() <= 4 && b.len() <= 4);
+            assert!(a.iter().chain(&b).all(|item| item.status == "processing" && item.attempts == 1));
+            claimed.extend(a.into_iter().chain(b).map(|item| item.document_id));
+        }
+
+        let unique: std::collections::HashSet<Uuid> = claimed.iter().copied().collect();
+        assert_eq!(unique.len(), claimed.len(), "a job was claimed by both workers");
+        claimed.sort();
+        queued.sort();
+        assert_eq!(claimed, queued);
+
+        let workers: Vec<(Option<String>,)> = sqlx::query_as("SELECT DISTINCT worker_id FROM ocr_queue WHERE document_id = ANY($1)")
+            .bind(&queued)
+            .fetch_all(ctx.state.db.get_pool())
+            .await?;
+        assert!(!workers.is_empty() && workers.len() <= 2);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
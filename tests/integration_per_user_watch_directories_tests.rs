@@ -31,6 +31,7 @@ async fn create_test_config() -> Result<(Config, TempDir, TempDir)> {
         server_address: "127.0.0.1:0".to_string(),
         jwt_secret: "test_secret".to_string(),
         upload_path: temp_upload_dir.path().to_string_lossy().to_string(),
+        storage_layout: Default::default(),
         watch_folder: temp_watch_dir.path().to_string_lossy().to_string(),
         user_watch_base_dir: temp_user_watch_dir.path().to_string_lossy().to_string(),
         enable_per_user_watch: true,
@@ -40,6 +41,8 @@ async fn create_test_config() -> Result<(Config, TempDir, TempDir)> {
         max_file_age_hours: None,
         ocr_language: "eng".to_string(),
         concurrent_ocr_jobs: 1,
+        ocr_db_pool_fraction: 0.5,
+        ocr_claim_batch_size: 1,
         ocr_timeout_seconds: 30,
         max_file_size_mb: 10,
         memory_limit_mb: 512,
@@ -49,6 +52,31 @@ async fn create_test_config() -> Result<(Config, TempDir, TempDir)> {
         oidc_client_secret: None,
         oidc_issuer_url: None,
         oidc_redirect_uri: None,
+        smtp_host: None,
+        smtp_port: 587,
+        smtp_username: None,
+        smtp_password: None,
+        smtp_from: "Readur <noreply@readur.local>".to_string(),
+        smtp_starttls: true,
+        public_base_url: None,
+        login_max_failed_attempts: 5,
+        login_attempt_window_seconds: 900,
+        login_lockout_seconds: 900,
+        trusted_proxies: readur::auth::TrustedProxy::parse_list(readur::auth::DEFAULT_TRUSTED_PROXIES).unwrap(),
+        trash_retention_days: 30,
+        document_version_retention: 10,
+        webdav_validation_interval_hours: 24,
+        strip_gps_metadata: false,
+        impersonation_read_only: true,
+        backfill_on_startup: false,
+        document_event_retention_days: 90,
+        source_sync_max_concurrency: 16,
+        source_download_max_concurrency: 8,
+        webdav_upload_chunk_size_mb: 10,
+        ocr_worker_threads: 2,
+        background_worker_threads: 2,
+        db_worker_threads: 2,
+        derived_asset_cache_max_mb: 2048,
     };
     
     Ok((config, temp_upload_dir, temp_user_watch_dir))
@@ -69,6 +97,11 @@ async fn create_test_app_state(config: Config) -> Result<Arc<AppState>> {
         None
     };
 
+    let login_rate_limiter = Arc::new(readur::monitoring::login_rate_limiter::LoginRateLimiter::from_config(&config));
+    let derived_asset_cache = Arc::new(readur::services::derived_asset_cache::DerivedAssetCache::from_config(db.clone(), &config));
+    let download_limiter = Arc::new(readur::scheduling::download_limiter::DownloadLimiter::new(config.source_download_max_concurrency));
+    let source_secrets = Arc::new(readur::services::source_secrets::SourceSecrets::new(&config.jwt_secret));
+
     Ok(Arc::new(AppState {
         db,
         config,
@@ -78,6 +111,10 @@ async fn create_test_app_state(config: Config) -> Result<Arc<AppState>> {
         oidc_client: None,
         sync_progress_tracker: Arc::new(readur::services::sync_progress_tracker::SyncProgressTracker::new()),
         user_watch_service,
+        login_rate_limiter,
+        derived_asset_cache,
+        download_limiter,
+        source_secrets,
     }))
 }
 
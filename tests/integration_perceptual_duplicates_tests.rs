@@ -0,0 +1,100 @@
+use anyhow::Result;
+use chrono::Utc;
+use uuid::Uuid;
+
+use readur::{
+    db::Database,
+    models::{CreateUser, Document, User, UserRole},
+    test_utils::TestContext,
+};
+
+async fn create_test_user(db: &Database) -> Result<User> {
+    let unique_suffix = Uuid::new_v4().simple();
+    Ok(db.create_user(CreateUser {
+        username: format!("phash_{}", unique_suffix),
+        email: format!("phash_{}@example.com", unique_suffix),
+        password: "password123".to_string(),
+        role: Some(UserRole::User),
+    }).await?)
+}
+
+fn create_test_image(user_id: Uuid, filename: &str) -> Document {
+    Document {
+        id: Uuid::new_v4(),
+        filename: filename.to_string(),
+        original_filename: filename.to_string(),
+        file_path: format!("/uploads/documents/{}", filename),
+        file_size: 2048,
+        mime_type: "image/png".to_string(),
+        content: None,
+        ocr_text: None,
+        ocr_confidence: None,
+        ocr_word_count: None,
+        ocr_processing_time_ms: None,
+        ocr_status: Some("pending".to_string()),
+        ocr_error: None,
+        ocr_completed_at: None,
+        ocr_retry_count: None,
+        ocr_failure_reason: None,
+        tags: vec![],
+        created_at: Utc::now(),
+        updated_at: Utc::now(),
+        user_id,
+        file_hash: Some(format!("{:x}", Uuid::new_v4().as_u128())),
+        original_created_at: None,
+        original_modified_at: None,
+        source_path: None,
+        source_type: None,
+        source_id: None,
+        file_permissions: None,
+        file_owner: None,
+        file_group: None,
+        source_metadata: None,
+    }
+}
+
+#[tokio::test]
+async fn test_perceptual_duplicates_grouped_by_hamming_distance() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let user = create_test_user(db).await?;
+
+        let scan_a = db.create_document(create_test_image(user.id, "scan_a.png")).await?;
+        let scan_b = db.create_document(create_test_image(user.id, "scan_b.png")).await?;
+        let other = db.create_document(create_test_image(user.id, "other.png")).await?;
+        // Images without a hash yet are ignored
+        db.create_document(create_test_image(user.id, "unhashed.png")).await?;
+
+        db.update_document_perceptual_hash(scan_a.id, 0x0F0F_0F0F_0F0F_0F0F).await?;
+        db.update_document_perceptual_hash(scan_b.id, 0x0F0F_0F0F_0F0F_0F0E).await?;
+        db.update_document_perceptual_hash(other.id, 0x7070_7070_7070_7070).await?;
+
+        let groups = db.get_user_perceptual_duplicates(user.id, user.role, 4, 25, 0).await?;
+        assert_eq!(groups.len(), 1);
+        let mut ids: Vec<Uuid> = groups[0].iter().map(|d| d.id).collect();
+        ids.sort();
+        let mut expected = vec![scan_a.id, scan_b.id];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        // Exact-hash mode does not see them as duplicates
+        assert!(db.get_user_duplicates(user.id, user.role, 25, 0).await?.is_empty());
+
+        // A zero threshold only matches identical hashes
+        assert!(db.get_user_perceptual_duplicates(user.id, user.role, 0, 25, 0).await?.is_empty());
+
+        // Trashed documents drop out of the groups
+        db.delete_document(scan_b.id, user.id, user.role).await?;
+        assert!(db.get_user_perceptual_duplicates(user.id, user.role, 4, 25, 0).await?.is_empty());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
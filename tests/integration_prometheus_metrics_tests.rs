@@ -150,6 +150,8 @@ async fn test_all_expected_metrics_are_present() {
         "readur_ocr_completed_today",
         "readur_ocr_stuck_jobs",
         "readur_ocr_queue_depth",
+        "readur_ocr_processing_time_ms_bucket",
+        "readur_ocr_processing_time_ms_count",
         
         // User metrics
         "readur_users_total",
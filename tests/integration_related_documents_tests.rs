@@ -0,0 +1,97 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext};
+
+async fn get(ctx: &TestContext, token: &str, uri: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_document(ctx: &TestContext, user_id: Uuid, filename: &str, text: &str) -> Result<Uuid> {
+    let mut document = create_test_document(user_id);
+    document.filename = filename.to_string();
+    document.original_filename = filename.to_string();
+    document.content = Some(text.to_string());
+    document.ocr_text = None;
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+const INVOICE: &str = "Invoice 2024-117 from Harbor Plumbing Services for the replacement of the kitchen water heater. \
+    Payment of the invoice is due within thirty days. Harbor Plumbing Services thanks you for your business.";
+
+#[tokio::test]
+async fn test_related_documents_rank_near_duplicates_first() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let user_id = user.user_response.id;
+
+        let origin = create_document(&ctx, user_id, "invoice.pdf", INVOICE).await?;
+        let near_duplicate = create_document(&ctx, user_id, "invoice-copy.pdf",
+            &INVOICE.replace("2024-117", "2024-118")).await?;
+        let same_sender = create_document(&ctx, user_id, "reminder.pdf",
+            "Reminder from Harbor Plumbing Services: the invoice for the water heater is overdue. Please arrange payment.").await?;
+        let unrelated = create_document(&ctx, user_id, "recipe.pdf",
+            "Preheat the oven, whisk the eggs with sugar and fold in the flour before baking the cake.").await?;
+        // Another user's copy of the same text is never offered
+        let foreign = create_document(&ctx, other.user_response.id, "invoice.pdf", INVOICE).await?;
+
+        let uri = format!("/api/documents/{}/related?min_similarity=0.05", origin);
+        let (status, body) = get(&ctx, &token, &uri).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["document_id"], origin.to_string());
+
+        let related = body["related"].as_array().unwrap();
+        let ids: Vec<&str> = related.iter().map(|d| d["id"].as_str().unwrap()).collect();
+        assert_eq!(ids.first().copied(), Some(near_duplicate.to_string().as_str()), "{}", body);
+        assert!(ids.contains(&same_sender.to_string().as_str()), "{}", body);
+        assert!(!ids.contains(&origin.to_string().as_str()));
+        assert!(!ids.contains(&unrelated.to_string().as_str()));
+        assert!(!ids.contains(&foreign.to_string().as_str()));
+
+        let scores: Vec<f64> = related.iter().map(|d| d["similarity"].as_f64().unwrap()).collect();
+        assert!(scores[0] > 0.8, "near-duplicate scored {}", scores[0]);
+        assert!(scores.windows(2).all(|w| w[0] >= w[1]));
+
+        // The limit and minimum similarity narrow the result
+        let (_, body) = get(&ctx, &token, &format!("/api/documents/{}/related?limit=1&min_similarity=0.05", origin)).await?;
+        assert_eq!(body["related"].as_array().unwrap().len(), 1);
+        assert_eq!(body["related"][0]["id"], near_duplicate.to_string());
+
+        let (_, body) = get(&ctx, &token, &format!("/api/documents/{}/related?min_similarity=0.8", origin)).await?;
+        let ids: Vec<&str> = body["related"].as_array().unwrap().iter().map(|d| d["id"].as_str().unwrap()).collect();
+        assert_eq!(ids, vec![near_duplicate.to_string().as_str()]);
+
+        assert_eq!(get(&ctx, &token, &format!("/api/documents/{}/related?limit=0", origin)).await?.0, StatusCode::BAD_REQUEST);
+        assert_eq!(get(&ctx, &token, &format!("/api/documents/{}/related?min_similarity=1.5", origin)).await?.0, StatusCode::BAD_REQUEST);
+
+        // Another user can't look up related documents of a document they don't own
+        assert_eq!(get(&ctx, &other_token, &format!("/api/documents/{}/related", origin)).await?.0, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
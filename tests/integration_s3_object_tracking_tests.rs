@@ -0,0 +1,126 @@
+use anyhow::Result;
+use chrono::Utc;
+use uuid::Uuid;
+
+use readur::{
+    db::Database,
+    models::{CreateS3Object, CreateSource, CreateUser, SourceType, UserRole},
+    test_utils::{document_helpers::create_test_document, TestContext},
+};
+
+async fn create_s3_source(db: &Database) -> Result<(Uuid, Uuid)> {
+    let unique_suffix = Uuid::new_v4().simple();
+    let user = db.create_user(CreateUser {
+        username: format!("s3_objects_{}", unique_suffix),
+        email: format!("s3_objects_{}@example.com", unique_suffix),
+        password: "password123".to_string(),
+        role: Some(UserRole::User),
+    }).await?;
+
+    let source = db.create_source(user.id, &CreateSource {
+        name: "S3 tracking source".to_string(),
+        source_type: SourceType::S3,
+        enabled: Some(true),
+        config: serde_json::json!({
+            "bucket_name": "documents",
+            "region": "us-east-1",
+            "access_key_id": "access",
+            "secret_access_key": "secret",
+            "watch_folders": [""],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60
+        }),
+    }).await?;
+
+    Ok((user.id, source.id))
+}
+
+fn s3_object(source_id: Uuid, key: &str, etag: &str) -> CreateS3Object {
+    CreateS3Object {
+        source_id,
+        object_key: key.to_string(),
+        etag: etag.to_string(),
+        last_modified: Some(Utc::now()),
+        size: 1024,
+    }
+}
+
+#[tokio::test]
+async fn test_s3_object_upsert_and_delete() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let (_, source_id) = create_s3_source(db).await?;
+
+        db.upsert_s3_object(&s3_object(source_id, "invoices/a.pdf", "etag-1")).await?;
+        db.upsert_s3_object(&s3_object(source_id, "invoices/b.pdf", "etag-2")).await?;
+        // A changed object replaces the tracked ETag instead of adding a row
+        db.upsert_s3_object(&s3_object(source_id, "invoices/a.pdf", "etag-3")).await?;
+
+        let mut objects = db.list_s3_objects(source_id).await?;
+        objects.sort_by(|a, b| a.object_key.cmp(&b.object_key));
+        assert_eq!(objects.len(), 2);
+        assert_eq!(objects[0].etag, "etag-3");
+        assert_eq!(objects[1].etag, "etag-2");
+
+        let removed = db.delete_s3_objects(source_id, &["invoices/b.pdf".to_string()]).await?;
+        assert_eq!(removed, 1);
+        let objects = db.list_s3_objects(source_id).await?;
+        assert_eq!(objects.len(), 1);
+        assert_eq!(objects[0].object_key, "invoices/a.pdf");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_removed_objects_flag_their_documents() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let (user_id, source_id) = create_s3_source(db).await?;
+
+        let mut document = create_test_document(user_id);
+        document.source_id = Some(source_id);
+        document.source_path = Some("invoices/a.pdf".to_string());
+        let document = db.create_document(document).await?;
+
+        let removed_keys = vec!["invoices/a.pdf".to_string()];
+        assert_eq!(db.set_source_documents_missing(source_id, &removed_keys, true).await?, 1);
+        // Flagging again leaves the original timestamp alone
+        assert_eq!(db.set_source_documents_missing(source_id, &removed_keys, true).await?, 0);
+
+        let missing = db.get_missing_source_documents(source_id).await?;
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].id, document.id);
+        assert!(missing[0].missing_since.is_some());
+
+        // The object came back, so the flag is cleared
+        assert_eq!(db.set_source_documents_missing(source_id, &removed_keys, false).await?, 1);
+        let source_status: Option<String> = sqlx::query_scalar(
+            "SELECT source_status FROM documents WHERE id = $1"
+        )
+        .bind(document.id)
+        .fetch_one(db.get_pool())
+        .await?;
+        assert_eq!(source_status, None);
+        assert!(db.get_missing_source_documents(source_id).await?.is_empty());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
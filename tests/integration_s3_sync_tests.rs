@@ -33,6 +33,11 @@ fn create_test_aws_s3_config() -> S3SourceConfig {
         auto_sync: true,
         sync_interval_minutes: 120,
         file_extensions: vec![".pdf".to_string(), ".txt".to_string(), ".docx".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
+        client_certificate: Default::default(),
     }
 }
 
@@ -49,6 +54,11 @@ fn create_test_minio_config() -> S3SourceConfig {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string(), ".jpg".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
+        client_certificate: Default::default(),
     }
 }
 
@@ -368,6 +378,11 @@ fn test_s3_error_handling_scenarios() {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
+        client_certificate: Default::default(),
     };
     
     assert!(invalid_bucket_config.bucket_name.contains('_'));
@@ -385,6 +400,11 @@ fn test_s3_error_handling_scenarios() {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
+        client_certificate: Default::default(),
     };
     
     assert!(empty_creds_config.access_key_id.is_empty());
@@ -402,6 +422,11 @@ fn test_s3_error_handling_scenarios() {
         auto_sync: true,
         sync_interval_minutes: 60,
         file_extensions: vec![".pdf".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
+        client_certificate: Default::default(),
     };
     
     assert!(!is_valid_aws_region(&invalid_region_config.region));
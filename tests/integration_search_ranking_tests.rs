@@ -0,0 +1,125 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use chrono::{Duration, Utc};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{SearchRequest, SearchSort},
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+fn search_request(query: &str, sort: Option<SearchSort>) -> SearchRequest {
+    SearchRequest {
+        query: query.to_string(),
+        tags: None,
+        labels: None,
+        filter_match: None,
+        mime_types: None,
+        limit: Some(10),
+        offset: Some(0),
+        include_snippets: Some(false),
+        snippet_length: None,
+        clean_snippets: None,
+        search_mode: None,
+        sort,
+        captured_after: None,
+        captured_before: None,
+    }
+}
+
+#[tokio::test]
+async fn test_filename_and_text_match_ranks_above_single_occurrence() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        // The stronger match is the older document, so a tie-break on date alone would put it last
+        let mut strong = create_test_document_with_hash(user_id, "budget report.pdf", "hash-strong".to_string());
+        strong.content = None;
+        strong.ocr_text = Some("The budget was approved by the board".to_string());
+        strong.created_at = Utc::now() - Duration::hours(1);
+        let strong = ctx.state.db.create_document(strong).await?;
+
+        let mut weak = create_test_document_with_hash(user_id, "minutes.pdf", "hash-weak".to_string());
+        weak.content = None;
+        weak.ocr_text = Some("The budget was approved by the board".to_string());
+        let weak = ctx.state.db.create_document(weak).await?;
+
+        let results = ctx.state.db.enhanced_search_documents(user_id, &search_request("budget", None)).await?;
+        let ids: Vec<Uuid> = results.iter().map(|doc| doc.id).collect();
+        assert_eq!(ids, vec![strong.id, weak.id]);
+        let strong_rank = results[0].search_rank.unwrap();
+        let weak_rank = results[1].search_rank.unwrap();
+        assert!(strong_rank > weak_rank, "{} should outrank {}", strong_rank, weak_rank);
+        assert!(weak_rank > 0.0 && strong_rank <= 1.0);
+
+        // The basic search endpoint ranks the same way and exposes the score
+        let request = Request::builder()
+            .method("GET")
+            .uri("/api/search?query=budget")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap();
+        let response = ctx.app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&bytes)?;
+        let documents = body["documents"].as_array().unwrap();
+        assert_eq!(documents[0]["id"], strong.id.to_string());
+        assert!(documents[0]["search_rank"].as_f64().unwrap() > documents[1]["search_rank"].as_f64().unwrap());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_search_sort_by_date_and_name() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+
+        let mut ids = Vec::new();
+        for (index, filename) in ["charlie.pdf", "Alpha.pdf", "bravo.pdf"].iter().enumerate() {
+            let mut document = create_test_document_with_hash(user_id, filename, format!("hash-{}", index));
+            document.ocr_text = Some("quarterly invoice".to_string());
+            document.created_at = Utc::now() - Duration::minutes(10 - index as i64);
+            ids.push(ctx.state.db.create_document(document).await?.id);
+        }
+
+        let by_date = ctx.state.db.enhanced_search_documents(user_id, &search_request("invoice", Some(SearchSort::Date))).await?;
+        let date_order: Vec<Uuid> = by_date.iter().map(|doc| doc.id).collect();
+        assert_eq!(date_order, vec![ids[2], ids[1], ids[0]]);
+
+        let by_name = ctx.state.db.enhanced_search_documents(user_id, &search_request("invoice", Some(SearchSort::Name))).await?;
+        let names: Vec<&str> = by_name.iter().map(|doc| doc.original_filename.as_str()).collect();
+        assert_eq!(names, vec!["Alpha.pdf", "bravo.pdf", "charlie.pdf"]);
+
+        let (basic, _) = ctx.state.db.search_documents(user_id, &search_request("invoice", Some(SearchSort::Name))).await?
+            .into_iter()
+            .next()
+            .unwrap();
+        assert_eq!(basic.original_filename, "Alpha.pdf");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,171 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use chrono::{Duration, Utc};
+use tower::ServiceExt;
+
+use readur::test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext};
+
+async fn get_json(ctx: &TestContext, token: &str, uri: &str) -> Result<serde_json::Value> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK, "GET {}", uri);
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok(serde_json::from_slice(&bytes)?)
+}
+
+async fn save_settings(ctx: &TestContext, token: &str, settings: serde_json::Value) -> Result<()> {
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/api/settings")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(settings.to_string()))?;
+    let response = ctx.app.clone().oneshot(request).await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    Ok(())
+}
+
+fn filenames(documents: &serde_json::Value) -> Vec<String> {
+    documents.as_array().unwrap().iter()
+        .map(|document| document["original_filename"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_search_and_list_defaults_come_from_user_settings() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let filler = "The quarterly figures were reviewed in detail by the finance team. ".repeat(10);
+        for (index, filename) in ["charlie.pdf", "Alpha.pdf", "delta.pdf", "bravo.pdf"].iter().enumerate() {
+            let mut document = create_test_document_with_hash(user_id, filename, format!("hash-{}", index));
+            document.content = None;
+            document.ocr_text = Some(format!("{}invoice {}", filler, filler));
+            document.created_at = Utc::now() - Duration::minutes(10 - index as i64);
+            ctx.state.db.create_document(document).await?;
+        }
+
+        save_settings(&ctx, &token, serde_json::json!({
+            "search_results_per_page": 2,
+            "search_snippet_length": 40,
+            "search_sort": "name",
+        })).await?;
+        let settings = get_json(&ctx, &token, "/api/settings").await?;
+        assert_eq!(settings["search_sort"], "name");
+
+        let search = get_json(&ctx, &token, "/api/search?query=invoice").await?;
+        assert_eq!(filenames(&search["documents"]), vec!["Alpha.pdf", "bravo.pdf"]);
+        assert_eq!(search["total"], 4);
+
+        let enhanced = get_json(&ctx, &token, "/api/search/enhanced?query=invoice").await?;
+        assert_eq!(filenames(&enhanced["documents"]), vec!["Alpha.pdf", "bravo.pdf"]);
+        let snippet = enhanced["documents"][0]["snippets"][0]["text"].as_str().unwrap();
+        assert!(snippet.contains("invoice"));
+        assert!(snippet.len() < 80, "snippet should follow search_snippet_length: {:?}", snippet);
+
+        // Request parameters still win over the saved defaults
+        let search = get_json(&ctx, &token, "/api/search?query=invoice&limit=3&sort=date").await?;
+        assert_eq!(filenames(&search["documents"]), vec!["bravo.pdf", "delta.pdf", "Alpha.pdf"]);
+        let enhanced = get_json(&ctx, &token, "/api/search/enhanced?query=invoice&snippet_length=200").await?;
+        assert!(enhanced["documents"][0]["snippets"][0]["text"].as_str().unwrap().len() > 150);
+
+        let list = get_json(&ctx, &token, "/api/documents").await?;
+        assert_eq!(list["documents"].as_array().unwrap().len(), 2);
+        assert_eq!(list["pagination"]["limit"], 2);
+        let list = get_json(&ctx, &token, "/api/documents?limit=3").await?;
+        assert_eq!(list["documents"].as_array().unwrap().len(), 3);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_defaults_without_saved_settings() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+        assert!(ctx.state.db.get_user_settings(user_id).await?.is_none());
+
+        for (index, filename) in ["weak.pdf", "invoice.pdf"].iter().enumerate() {
+            let mut document = create_test_document_with_hash(user_id, filename, format!("hash-{}", index));
+            document.content = None;
+            document.ocr_text = Some("Unpaid invoice".to_string());
+            document.created_at = Utc::now() - Duration::minutes(index as i64);
+            ctx.state.db.create_document(document).await?;
+        }
+
+        // Relevance order: the filename match ranks first although it is older
+        let search = get_json(&ctx, &token, "/api/search?query=invoice").await?;
+        assert_eq!(filenames(&search["documents"]), vec!["invoice.pdf", "weak.pdf"]);
+
+        let list = get_json(&ctx, &token, "/api/documents").await?;
+        assert_eq!(list["pagination"]["limit"], 25);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_ocr_snippets_are_cleaned_unless_disabled() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let ocr_text = "Payment  | ~ terms for the\n\n  invoice ;  i l | r n  are 30 days . net";
+        let mut document = create_test_document_with_hash(user.user_response.id, "scan.pdf", "hash-scan".to_string());
+        document.content = None;
+        document.ocr_text = Some(ocr_text.to_string());
+        let document = ctx.state.db.create_document(document).await?;
+
+        let enhanced = get_json(&ctx, &token, "/api/search/enhanced?query=invoice").await?;
+        let snippet = &enhanced["documents"][0]["snippets"][0];
+        let text = snippet["text"].as_str().unwrap();
+        assert_eq!(text, "Payment terms for the invoice are 30 days net");
+        let (start, end) = (snippet["highlight_ranges"][0]["start"].as_u64().unwrap() as usize, snippet["highlight_ranges"][0]["end"].as_u64().unwrap() as usize);
+        assert_eq!(&text[start..end], "invoice");
+
+        // The stored OCR text is left alone
+        let stored = ctx.state.db.get_document_by_id(document.id, user.user_response.id, readur::models::UserRole::User).await?.unwrap();
+        assert_eq!(stored.ocr_text.as_deref(), Some(ocr_text));
+
+        save_settings(&ctx, &token, serde_json::json!({ "search_clean_snippets": false })).await?;
+        let enhanced = get_json(&ctx, &token, "/api/search/enhanced?query=invoice").await?;
+        assert_eq!(enhanced["documents"][0]["snippets"][0]["text"], ocr_text);
+
+        let enhanced = get_json(&ctx, &token, "/api/search/enhanced?query=invoice&clean_snippets=true").await?;
+        assert_eq!(enhanced["documents"][0]["snippets"][0]["text"], "Payment terms for the invoice are 30 days net");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext};
+
+async fn search(ctx: &TestContext, token: &str, query: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/search?{}", query))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+fn filenames(response: &serde_json::Value) -> Vec<String> {
+    let mut names: Vec<String> = response["documents"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|document| document["original_filename"].as_str().unwrap().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+async fn create_document(ctx: &TestContext, user_id: Uuid, name: &str, tags: &[&str]) -> Result<Uuid> {
+    let mut document = create_test_document_with_hash(user_id, name, format!("hash-{}", name));
+    document.ocr_text = Some("Quarterly invoice for consulting".to_string());
+    document.tags = tags.iter().map(|tag| tag.to_string()).collect();
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+async fn create_label(ctx: &TestContext, user_id: Uuid, name: &str, document_ids: &[Uuid]) -> Result<Uuid> {
+    let label_id: Uuid = sqlx::query_scalar("INSERT INTO labels (user_id, name) VALUES ($1, $2) RETURNING id")
+        .bind(user_id)
+        .bind(name)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+    for document_id in document_ids {
+        sqlx::query("INSERT INTO document_labels (document_id, label_id) VALUES ($1, $2)")
+            .bind(document_id)
+            .bind(label_id)
+            .execute(ctx.state.db.get_pool())
+            .await?;
+    }
+    Ok(label_id)
+}
+
+#[tokio::test]
+async fn test_all_mode_excludes_documents_missing_a_required_tag() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        create_document(&ctx, user_id, "both.pdf", &["finance", "2024"]).await?;
+        create_document(&ctx, user_id, "finance-only.pdf", &["finance"]).await?;
+        create_document(&ctx, user_id, "untagged.pdf", &[]).await?;
+
+        let (status, all) = search(&ctx, &token, "query=invoice&tags=finance,2024&filter_match=all").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(filenames(&all), vec!["both.pdf"]);
+        assert_eq!(all["total"], 1);
+
+        let (_, any) = search(&ctx, &token, "query=invoice&tags=finance,2024").await?;
+        assert_eq!(filenames(&any), vec!["both.pdf", "finance-only.pdf"]);
+        assert_eq!(any["total"], 2);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_label_filter_and_total_across_pages() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let a = create_document(&ctx, user_id, "a.pdf", &[]).await?;
+        let b = create_document(&ctx, user_id, "b.pdf", &[]).await?;
+        let c = create_document(&ctx, user_id, "c.pdf", &[]).await?;
+        create_document(&ctx, user_id, "unlabelled.pdf", &[]).await?;
+        let clients = create_label(&ctx, user_id, "Clients", &[a, b, c]).await?;
+        let urgent = create_label(&ctx, user_id, "Urgent", &[b]).await?;
+
+        let (status, page) = search(&ctx, &token, &format!("query=invoice&labels={}&limit=2", clients)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["documents"].as_array().unwrap().len(), 2);
+        assert_eq!(page["total"], 3, "total counts every match, not just the page");
+
+        let (_, all) = search(&ctx, &token, &format!("query=invoice&labels={},{}&filter_match=all", clients, urgent)).await?;
+        assert_eq!(filenames(&all), vec!["b.pdf"]);
+        assert_eq!(all["total"], 1);
+
+        let (_, any) = search(&ctx, &token, &format!("query=invoice&labels={},{}&filter_match=any", clients, urgent)).await?;
+        assert_eq!(filenames(&any), vec!["a.pdf", "b.pdf", "c.pdf"]);
+
+        let (status, _) = search(&ctx, &token, "query=invoice&labels=not-a-uuid").await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
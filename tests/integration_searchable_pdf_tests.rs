@@ -0,0 +1,112 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    services::file_service::FileService,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+const FILE_HASH: &str = "0f343b0931126a20f133d67c2b018a3b5b6e1a2c9d8e7f6a5b4c3d2e1f0a9b8c";
+
+async fn get(ctx: &TestContext, token: &str, uri: &str) -> Result<(StatusCode, Vec<u8>)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, bytes.to_vec()))
+}
+
+async fn create_document(
+    ctx: &TestContext,
+    user_id: Uuid,
+    dir: &tempfile::TempDir,
+    name: &str,
+    mime_type: &str,
+    ocr_status: &str,
+) -> Result<Uuid> {
+    let path = dir.path().join(name);
+    std::fs::write(&path, b"%PDF-1.4 scanned page")?;
+
+    let mut document = create_test_document_with_hash(user_id, name, FILE_HASH.to_string());
+    document.file_path = path.to_string_lossy().to_string();
+    document.mime_type = mime_type.to_string();
+    document.ocr_status = Some(ocr_status.to_string());
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+#[tokio::test]
+async fn test_searchable_pdf_requires_completed_ocr_and_a_pdf_or_image() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let dir = tempfile::tempdir()?;
+        let user_id = user.user_response.id;
+
+        let pending = create_document(&ctx, user_id, &dir, "pending.pdf", "application/pdf", "pending").await?;
+        let (status, body) = get(&ctx, &token, &format!("/api/documents/{}/searchable-pdf", pending)).await?;
+        assert_eq!(status, StatusCode::CONFLICT);
+        let error: serde_json::Value = serde_json::from_slice(&body)?;
+        assert!(error["error"].as_str().unwrap().contains("run OCR first"));
+
+        let text = create_document(&ctx, user_id, &dir, "notes.txt", "text/plain", "completed").await?;
+        let (status, _) = get(&ctx, &token, &format!("/api/documents/{}/searchable-pdf", text)).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = get(&ctx, &other_token, &format!("/api/documents/{}/searchable-pdf", pending)).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_searchable_pdf_is_served_from_cache_for_the_current_file() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let dir = tempfile::tempdir()?;
+
+        let document_id = create_document(&ctx, user.user_response.id, &dir, "scan.pdf", "application/pdf", "completed").await?;
+
+        // A PDF generated earlier for the same file hash is served without running ocrmypdf
+        let cache_dir = FileService::new(ctx.state.config.upload_path.clone()).get_searchable_pdfs_path();
+        std::fs::create_dir_all(&cache_dir)?;
+        let cache_path = cache_dir.join(format!("{}_{}.pdf", document_id, &FILE_HASH[..16]));
+        std::fs::write(&cache_path, b"%PDF-1.4 with text layer")?;
+
+        let (status, body) = get(&ctx, &token, &format!("/api/documents/{}/searchable-pdf", document_id)).await?;
+        std::fs::remove_file(&cache_path)?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, b"%PDF-1.4 with text layer");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
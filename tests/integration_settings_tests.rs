@@ -107,6 +107,17 @@ mod tests {
                 ocr_quality_threshold_noise: None,
                 ocr_quality_threshold_sharpness: None,
                 ocr_skip_enhancement: None,
+                ocr_parallel_pages: None,
+                ocr_retry_low_confidence: None,
+                extract_archives: None,
+                search_sort: None,
+                ocr_detect_script: None,
+                ocr_preprocessing: None,
+                search_clean_snippets: None,
+                ocr_text_layer_min_chars: None,
+                sync_notification_mode: None,
+                default_source_id: None,
+                default_source_write_back: None,
                 webdav_enabled: None,
                 webdav_server_url: None,
                 webdav_username: None,
@@ -230,6 +241,17 @@ mod tests {
                 ocr_quality_threshold_noise: None,
                 ocr_quality_threshold_sharpness: None,
                 ocr_skip_enhancement: None,
+                ocr_parallel_pages: None,
+                ocr_retry_low_confidence: None,
+                extract_archives: None,
+                search_sort: None,
+                ocr_detect_script: None,
+                ocr_preprocessing: None,
+                search_clean_snippets: None,
+                ocr_text_layer_min_chars: None,
+                sync_notification_mode: None,
+                default_source_id: None,
+                default_source_write_back: None,
                 webdav_enabled: None,
                 webdav_server_url: None,
                 webdav_username: None,
@@ -380,6 +402,17 @@ mod tests {
                 ocr_quality_threshold_noise: None,
                 ocr_quality_threshold_sharpness: None,
                 ocr_skip_enhancement: None,
+                ocr_parallel_pages: None,
+                ocr_retry_low_confidence: None,
+                extract_archives: None,
+                search_sort: None,
+                ocr_detect_script: None,
+                ocr_preprocessing: None,
+                search_clean_snippets: None,
+                ocr_text_layer_min_chars: None,
+                sync_notification_mode: None,
+                default_source_id: None,
+                default_source_write_back: None,
                 webdav_enabled: None,
                 webdav_server_url: None,
                 webdav_username: None,
@@ -507,6 +540,17 @@ mod tests {
                 ocr_quality_threshold_noise: None,
                 ocr_quality_threshold_sharpness: None,
                 ocr_skip_enhancement: None,
+                ocr_parallel_pages: None,
+                ocr_retry_low_confidence: None,
+                extract_archives: None,
+                search_sort: None,
+                ocr_detect_script: None,
+                ocr_preprocessing: None,
+                search_clean_snippets: None,
+                ocr_text_layer_min_chars: None,
+                sync_notification_mode: None,
+                default_source_id: None,
+                default_source_write_back: None,
                 webdav_enabled: None,
                 webdav_server_url: None,
                 webdav_username: None,
@@ -544,4 +588,71 @@ mod tests {
         
         result.unwrap();
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_ocr_languages_listed_and_unknown_language_rejected() {
+        let ctx = TestContext::new().await;
+
+        let result: Result<()> = async {
+            let auth_helper = TestAuthHelper::new(ctx.app.clone());
+            let user = auth_helper.create_test_user().await;
+            let token = auth_helper.login_user(&user.username, "password123").await;
+
+            let response = ctx.app.clone()
+                .oneshot(
+                    axum::http::Request::builder()
+                        .method("GET")
+                        .uri("/api/settings/ocr/languages")
+                        .header("Authorization", format!("Bearer {}", token))
+                        .body(axum::body::Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let listing: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let languages = listing["languages"].as_array().unwrap();
+
+            // Without Tesseract nothing can be checked, and languages are saved unchecked
+            if listing["detected"] != true {
+                assert!(languages.is_empty());
+                return Ok(());
+            }
+            if let Some(english) = languages.iter().find(|language| language["code"] == "eng") {
+                assert_eq!(english["name"], "English");
+            }
+
+            let update = |language: &str| {
+                axum::http::Request::builder()
+                    .method("PUT")
+                    .uri("/api/settings")
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("Content-Type", "application/json")
+                    .body(axum::body::Body::from(serde_json::json!({ "ocr_language": language }).to_string()))
+                    .unwrap()
+            };
+
+            let response = ctx.app.clone().oneshot(update("xyz")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert!(error["error"].as_str().unwrap().contains("'xyz' is not installed"), "{}", error);
+
+            let installed = languages[0]["code"].as_str().unwrap();
+            let response = ctx.app.clone().oneshot(update(&format!("{}+xyz", installed))).await.unwrap();
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+            let response = ctx.app.clone().oneshot(update(installed)).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            Ok(())
+        }.await;
+
+        if let Err(e) = ctx.cleanup_and_close().await {
+            eprintln!("Warning: Test cleanup failed: {}", e);
+        }
+
+        result.unwrap();
+    }
+}
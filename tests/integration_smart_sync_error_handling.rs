@@ -50,6 +50,7 @@ fn create_test_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     WebDAVService::new(config).expect("Failed to create WebDAV service")
@@ -96,6 +97,7 @@ async fn test_webdav_error_fallback() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 1, // Very short timeout to fail quickly
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     let failing_webdav_service = WebDAVService::new(invalid_config)
@@ -0,0 +1,179 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{method, path_regex},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use readur::{
+    models::{CreateSource, CreateWebDAVDirectory, SourceType},
+    services::webdav::{SmartSyncService, SmartSyncStrategy, WebDAVConfig, WebDAVService},
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+const DAV_ROOT: &str = "/remote.php/dav/files/testuser";
+
+/// PROPFIND response listing `entries` as (path, etag, is_directory)
+fn multistatus(entries: &[(&str, &str, bool)]) -> String {
+    let responses: String = entries.iter().map(|(path, etag, is_directory)| {
+        let (href, resource_type, length) = if *is_directory {
+            (format!("{}{}/", DAV_ROOT, path), "<d:collection/>", "")
+        } else {
+            (format!("{}{}", DAV_ROOT, path), "", "<d:getcontentlength>1024</d:getcontentlength>")
+        };
+        format!(
+            "<d:response><d:href>{}</d:href><d:propstat><d:prop>\
+             <d:displayname>{}</d:displayname>{}\
+             <d:getlastmodified>Sat, 26 Jul 2025 22:19:19 GMT</d:getlastmodified>\
+             <d:getetag>&quot;{}&quot;</d:getetag>\
+             <d:resourcetype>{}</d:resourcetype>\
+             </d:prop><d:status>HTTP/1.1 200 OK</d:status></d:propstat></d:response>",
+            href, path.rsplit('/').next().unwrap_or_default(), length, etag, resource_type
+        )
+    }).collect();
+    format!("<?xml version=\"1.0\"?><d:multistatus xmlns:d=\"DAV:\">{}</d:multistatus>", responses)
+}
+
+async fn mock_propfind(server: &MockServer, directory: &str, entries: &[(&str, &str, bool)], expected_calls: u64) {
+    Mock::given(method("PROPFIND"))
+        .and(path_regex(format!("^{}{}/?$", DAV_ROOT, directory)))
+        .respond_with(ResponseTemplate::new(207).set_body_string(multistatus(entries)))
+        .expect(expected_calls)
+        .mount(server)
+        .await;
+}
+
+fn webdav_service(server: &MockServer) -> WebDAVService {
+    WebDAVService::new(WebDAVConfig {
+        server_url: server.uri(),
+        username: "testuser".to_string(),
+        password: "secret".to_string(),
+        watch_folders: vec!["/Documents".to_string()],
+        file_extensions: vec!["pdf".to_string()],
+        timeout_seconds: 30,
+        server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
+    }).expect("Failed to create WebDAV service")
+}
+
+async fn track_directories(ctx: &TestContext, user_id: Uuid, paths: &[&str]) -> Result<()> {
+    for path in paths {
+        ctx.state.db.create_or_update_webdav_directory(&CreateWebDAVDirectory {
+            user_id,
+            directory_path: path.to_string(),
+            directory_etag: format!("etag-{}", path),
+            file_count: 1,
+            total_size_bytes: 100,
+        }).await?;
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_new_only_sync_scans_only_untracked_directories() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user_id = auth_helper.create_test_user().await.user_response.id;
+        track_directories(&ctx, user_id, &["/Documents", "/Documents/Known"]).await?;
+
+        let server = MockServer::start().await;
+        mock_propfind(&server, "/Documents", &[
+            ("/Documents", "root-changed", true),
+            ("/Documents/Known", "known-changed", true),
+            ("/Documents/New", "new-etag", true),
+            ("/Documents/loose.pdf", "loose-etag", false),
+        ], 2).await;
+        mock_propfind(&server, "/Documents/New", &[
+            ("/Documents/New", "new-etag", true),
+            ("/Documents/New/report.pdf", "report-etag", false),
+            ("/Documents/New/Sub", "sub-etag", true),
+        ], 1).await;
+        mock_propfind(&server, "/Documents/New/Sub", &[
+            ("/Documents/New/Sub", "sub-etag", true),
+            ("/Documents/New/Sub/scan.pdf", "scan-etag", false),
+        ], 1).await;
+        mock_propfind(&server, "/Documents/Known", &[], 0).await;
+
+        let smart_sync = SmartSyncService::new(ctx.state.clone());
+        let sync = smart_sync
+            .sync_new_directories_only(user_id, &webdav_service(&server), "/Documents", None)
+            .await?
+            .expect("the new directory is scanned");
+
+        let mut files: Vec<String> = sync.files.iter().map(|file| file.name.clone()).collect();
+        files.sort();
+        assert_eq!(files, vec!["report.pdf".to_string(), "scan.pdf".to_string()]);
+        assert_eq!(sync.directories_scanned, 1);
+        assert_eq!(sync.directories_skipped, 1);
+        assert!(matches!(sync.strategy_used, SmartSyncStrategy::TargetedScan(ref dirs) if dirs.len() == 1));
+
+        let tracked = ctx.state.db.list_webdav_directories(user_id).await?;
+        let etag_of = |path: &str| tracked.iter()
+            .find(|directory| directory.directory_path.trim_end_matches('/') == path)
+            .map(|directory| directory.directory_etag.clone());
+        assert_eq!(etag_of("/Documents/New").as_deref(), Some("new-etag"));
+        assert_eq!(etag_of("/Documents/New/Sub").as_deref(), Some("sub-etag"));
+        assert_eq!(etag_of("/Documents").as_deref(), Some("etag-/Documents"), "known ETags are not compared or updated");
+        assert_eq!(etag_of("/Documents/Known").as_deref(), Some("etag-/Documents/Known"));
+
+        // Everything is tracked now, so a second run finds nothing to do
+        let second = smart_sync
+            .sync_new_directories_only(user_id, &webdav_service(&server), "/Documents", None)
+            .await?;
+        assert!(second.is_none());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_new_only_sync_is_rejected_for_non_webdav_sources() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let source = ctx.state.db.create_source(user.user_response.id, &CreateSource {
+            name: "Scanner inbox".to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(false),
+            config: serde_json::json!({
+                "watch_folders": ["/tmp/scanner-inbox"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "recursive": true,
+                "follow_symlinks": false
+            }),
+        }).await?;
+
+        let request = Request::builder()
+            .method("POST")
+            .uri(format!("/api/sources/{}/sync?mode=new-only", source.id))
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())?;
+        let response = ctx.app.clone().oneshot(request).await?;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,93 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+
+use readur::services::source_secrets;
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+/// A self-signed client certificate and its key, PEM-encoded
+fn client_certificate() -> (String, String) {
+    let certified = rcgen::generate_simple_self_signed(vec!["readur-client".to_string()]).unwrap();
+    (certified.cert.pem(), certified.key_pair.serialize_pem())
+}
+
+fn webdav_config(extra: serde_json::Value) -> serde_json::Value {
+    let mut config = serde_json::json!({
+        "server_url": "https://dav.internal",
+        "username": "scanner",
+        "password": "app-password",
+        "watch_folders": ["/Documents"],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "server_type": "generic"
+    });
+    config.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    config
+}
+
+#[tokio::test]
+async fn test_client_certificate_is_stored_encrypted_and_never_returned() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let (certificate, key) = client_certificate();
+
+        let (status, body) = auth_helper.send_authenticated_request("POST", "/api/sources", Some(serde_json::json!({
+            "name": "Secure DAV",
+            "source_type": "webdav",
+            "enabled": false,
+            "config": webdav_config(serde_json::json!({ "client_certificate": certificate, "client_key": key })),
+        })), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let source_id = body["id"].as_str().unwrap().to_string();
+        let returned = body["config"].clone();
+        assert!(returned.get("client_key").is_none() && returned.get("client_certificate").is_none(), "{}", returned);
+        assert_eq!(returned["client_certificate_configured"], true);
+
+        let stored = ctx.state.db.get_source(user.user_response.id, source_id.parse()?).await?.unwrap().config;
+        let stored_key = stored["client_key"].as_str().unwrap();
+        assert!(source_secrets::is_encrypted(stored_key));
+        assert!(!stored.to_string().contains("PRIVATE KEY"));
+        assert_eq!(ctx.state.source_secrets.decrypt(stored_key)?, key);
+
+        for uri in ["/api/sources".to_string(), format!("/api/sources/{}", source_id)] {
+            let (status, body) = auth_helper.send_authenticated_request("GET", &uri, None, &token).await;
+            assert_eq!(status, StatusCode::OK);
+            assert!(!body.to_string().contains("client_key"), "{} returned the key", uri);
+        }
+
+        // Saving the config as it was returned keeps the certificate
+        let mut edited = returned.clone();
+        edited["sync_interval_minutes"] = serde_json::json!(30);
+        let (status, body) = auth_helper.send_authenticated_request("PUT", &format!("/api/sources/{}", source_id), Some(serde_json::json!({ "config": edited })), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["config"]["client_certificate_configured"], true);
+        let stored = ctx.state.db.get_source(user.user_response.id, source_id.parse()?).await?.unwrap().config;
+        assert_eq!(ctx.state.source_secrets.decrypt(stored["client_key"].as_str().unwrap())?, key);
+        assert!(stored.get("client_certificate_configured").is_none());
+
+        // A key that doesn't belong to the certificate is rejected
+        let (_, other_key) = client_certificate();
+        let mismatched = webdav_config(serde_json::json!({ "client_certificate": certificate, "client_key": other_key }));
+        let (status, _) = auth_helper.send_authenticated_request("PUT", &format!("/api/sources/{}", source_id), Some(serde_json::json!({ "config": mismatched })), &token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        // Empty values remove it
+        let cleared = webdav_config(serde_json::json!({ "client_certificate": "", "client_key": "" }));
+        let (status, body) = auth_helper.send_authenticated_request("PUT", &format!("/api/sources/{}", source_id), Some(serde_json::json!({ "config": cleared })), &token).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert!(body["config"].get("client_certificate_configured").is_none());
+        let stored = ctx.state.db.get_source(user.user_response.id, source_id.parse()?).await?.unwrap().config;
+        assert!(stored.get("client_key").is_none());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
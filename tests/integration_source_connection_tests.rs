@@ -0,0 +1,129 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use wiremock::{matchers::any, Mock, MockServer, ResponseTemplate};
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+async fn post_json(ctx: &TestContext, token: &str, uri: &str, body: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+fn local_folder_config(folder: &str) -> serde_json::Value {
+    serde_json::json!({
+        "watch_folders": [folder],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "recursive": true,
+        "follow_symlinks": false
+    })
+}
+
+#[tokio::test]
+async fn test_local_folder_settings_are_tested_before_saving() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let dir = tempfile::tempdir()?;
+        let folder = dir.path().to_string_lossy().to_string();
+
+        let (status, response) = post_json(&ctx, &token, "/api/sources/test-connection", serde_json::json!({
+            "source_type": "local_folder",
+            "config": local_folder_config(&folder),
+        })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["success"], true, "unexpected response: {}", response);
+        assert_eq!(response["reachable"], true);
+        assert_eq!(response["authenticated"], true);
+
+        // `/test` is the same endpoint
+        let missing = format!("{}/missing", folder);
+        let (status, response) = post_json(&ctx, &token, "/api/sources/test", serde_json::json!({
+            "source_type": "local_folder",
+            "config": local_folder_config(&missing),
+        })).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["success"], false);
+        assert_eq!(response["reachable"], false);
+        assert!(response["message"].as_str().unwrap().contains(&missing));
+
+        let (status, _) = post_json(&ctx, &token, "/api/sources/test-connection", serde_json::json!({
+            "source_type": "s3",
+            "config": local_folder_config(&folder),
+        })).await?;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_saved_webdav_source_reports_rejected_credentials() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let server = MockServer::start().await;
+        Mock::given(any()).respond_with(ResponseTemplate::new(401)).mount(&server).await;
+
+        let source = ctx.state.db.create_source(user.user_response.id, &CreateSource {
+            name: "Office Nextcloud".to_string(),
+            source_type: SourceType::WebDAV,
+            enabled: Some(false),
+            config: serde_json::json!({
+                "server_url": server.uri(),
+                "username": "scanner",
+                "password": "wrong",
+                "watch_folders": ["/Documents"],
+                "file_extensions": ["pdf"],
+                "auto_sync": false,
+                "sync_interval_minutes": 60,
+                "server_type": "generic"
+            }),
+        }).await?;
+
+        let (status, response) = post_json(&ctx, &token, &format!("/api/sources/{}/test", source.id), serde_json::json!({})).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["success"], false, "unexpected response: {}", response);
+        assert_eq!(response["reachable"], true);
+        assert_eq!(response["authenticated"], false);
+
+        let (status, _) = post_json(&ctx, &token, &format!("/api/sources/{}/test", uuid::Uuid::new_v4()), serde_json::json!({})).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -0,0 +1,180 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+use wiremock::{
+    matchers::{method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use readur::{
+    models::{CreateSource, CreateWebDAVDirectory, SourceType},
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn cleanup(ctx: &TestContext, token: &str, source_id: Uuid, query: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/sources/{}/cleanup{}", source_id, query))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_webdav_source(ctx: &TestContext, user_id: Uuid, server_url: &str) -> Result<Uuid> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: "Archive server".to_string(),
+        source_type: SourceType::WebDAV,
+        enabled: Some(false),
+        config: serde_json::json!({
+            "server_url": server_url,
+            "username": "archive",
+            "password": "secret",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "server_type": "generic"
+        }),
+    }).await?;
+    Ok(source.id)
+}
+
+async fn track_directories(ctx: &TestContext, user_id: Uuid, paths: &[&str]) -> Result<()> {
+    for path in paths {
+        ctx.state.db.create_or_update_webdav_directory(&CreateWebDAVDirectory {
+            user_id,
+            directory_path: path.to_string(),
+            directory_etag: format!("etag-{}", path),
+            file_count: 1,
+            total_size_bytes: 100,
+        }).await?;
+    }
+    Ok(())
+}
+
+async fn tracked_paths(ctx: &TestContext, user_id: Uuid) -> Result<Vec<String>> {
+    Ok(ctx.state.db.list_webdav_directories(user_id).await?
+        .into_iter()
+        .map(|directory| directory.directory_path)
+        .collect())
+}
+
+/// Mock server on which only /Documents and /Documents/Kept still exist
+async fn start_webdav_server() -> MockServer {
+    let server = MockServer::start().await;
+    for existing in ["/Documents", "/Documents/Kept"] {
+        Mock::given(method("PROPFIND"))
+            .and(path(existing))
+            .respond_with(ResponseTemplate::new(207).set_body_string("<d:multistatus xmlns:d=\"DAV:\"/>"))
+            .mount(&server)
+            .await;
+    }
+    server
+}
+
+#[tokio::test]
+async fn test_cleanup_is_a_dry_run_by_default() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+        let user_id = admin.user_response.id;
+
+        let server = start_webdav_server().await;
+        let source_id = create_webdav_source(&ctx, user_id, &server.uri()).await?;
+        track_directories(&ctx, user_id, &["/Documents", "/Documents/Gone", "/Documents/Gone/Sub", "/Documents/Kept", "/Elsewhere"]).await?;
+
+        let (status, response) = cleanup(&ctx, &token, source_id, "").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["applied"], false);
+        assert_eq!(response["directories_checked"], 4, "directories outside the watch folders are ignored");
+        assert_eq!(response["orphaned_paths"], serde_json::json!(["/Documents/Gone", "/Documents/Gone/Sub"]));
+        assert_eq!(tracked_paths(&ctx, user_id).await?.len(), 5);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_cleanup_applies_and_marks_documents() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let admin = auth_helper.create_admin_user().await;
+        let token = auth_helper.login_user(&admin.username, &admin.password).await;
+        let user_id = admin.user_response.id;
+
+        let server = start_webdav_server().await;
+        let source_id = create_webdav_source(&ctx, user_id, &server.uri()).await?;
+        track_directories(&ctx, user_id, &["/Documents", "/Documents/Gone", "/Documents/Gone/Sub", "/Documents/Kept"]).await?;
+
+        let mut documents = Vec::new();
+        for (name, source_path) in [("gone.pdf", "/Documents/Gone/Sub/gone.pdf"), ("kept.pdf", "/Documents/Kept/kept.pdf")] {
+            let document = ctx.state.db.create_document(create_test_document_with_hash(user_id, name, format!("hash-{}", name))).await?;
+            sqlx::query("UPDATE documents SET source_id = $2, source_path = $3 WHERE id = $1")
+                .bind(document.id)
+                .bind(source_id)
+                .bind(source_path)
+                .execute(ctx.state.db.get_pool())
+                .await?;
+            documents.push(document.id);
+        }
+
+        let (status, response) = cleanup(&ctx, &token, source_id, "?apply=true&mark_documents=true").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["applied"], true);
+        assert_eq!(response["documents_marked"], 1);
+        assert_eq!(tracked_paths(&ctx, user_id).await?, vec!["/Documents".to_string(), "/Documents/Kept".to_string()]);
+
+        let issues: Vec<(Uuid, String)> = sqlx::query_as("SELECT document_id, issue FROM document_integrity_issues WHERE document_id = ANY($1)")
+            .bind(&documents)
+            .fetch_all(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(issues, vec![(documents[0], "source_missing".to_string())]);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_cleanup_requires_admin() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let source_id = create_webdav_source(&ctx, user.user_response.id, "https://webdav.example.com").await?;
+        let (status, _) = cleanup(&ctx, &token, source_id, "?apply=true").await?;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
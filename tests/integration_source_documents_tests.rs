@@ -0,0 +1,119 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+async fn get(ctx: &TestContext, token: &str, uri: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_source(ctx: &TestContext, user_id: Uuid) -> Result<Uuid> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: format!("Scans {}", Uuid::new_v4().simple()),
+        source_type: SourceType::WebDAV,
+        enabled: Some(true),
+        config: serde_json::json!({
+            "server_url": "https://cloud.example.com",
+            "username": "user",
+            "password": "secret",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60
+        }),
+    }).await?;
+    Ok(source.id)
+}
+
+async fn create_document(ctx: &TestContext, user_id: Uuid, source_id: Option<Uuid>, filename: &str, file_size: i64, ocr_status: &str) -> Result<Uuid> {
+    let mut document = create_test_document(user_id);
+    document.filename = filename.to_string();
+    document.file_size = file_size;
+    document.ocr_status = Some(ocr_status.to_string());
+    if let Some(source_id) = source_id {
+        document.source_id = Some(source_id);
+        document.source_type = Some("webdav".to_string());
+        document.source_path = Some(format!("/Documents/{}", filename));
+    }
+    Ok(ctx.state.db.create_document(document).await?.id)
+}
+
+#[tokio::test]
+async fn test_source_documents_exclude_uploads_and_other_sources() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+        let user_id = user.user_response.id;
+
+        let source_id = create_source(&ctx, user_id).await?;
+        let second_source_id = create_source(&ctx, user_id).await?;
+        let a = create_document(&ctx, user_id, Some(source_id), "a.pdf", 1000, "completed").await?;
+        let b = create_document(&ctx, user_id, Some(source_id), "b.pdf", 2500, "failed").await?;
+        let c = create_document(&ctx, user_id, Some(source_id), "c.pdf", 500, "pending").await?;
+        create_document(&ctx, user_id, Some(second_source_id), "elsewhere.pdf", 9000, "completed").await?;
+        create_document(&ctx, user_id, None, "uploaded.pdf", 7000, "completed").await?;
+
+        let uri = format!("/api/sources/{}/documents", source_id);
+        let (status, body) = get(&ctx, &token, &uri).await?;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        let mut listed: Vec<&str> = body["documents"].as_array().unwrap().iter().map(|d| d["id"].as_str().unwrap()).collect();
+        listed.sort();
+        let mut expected = vec![a.to_string(), b.to_string(), c.to_string()];
+        expected.sort();
+        assert_eq!(listed, expected);
+        assert_eq!(body["pagination"]["total"], 3);
+
+        // Filters and paging work as on the document list
+        let (_, body) = get(&ctx, &token, &format!("{}?ocr_status=failed", uri)).await?;
+        assert_eq!(body["pagination"]["total"], 1);
+        assert_eq!(body["documents"][0]["id"], b.to_string());
+
+        let (_, body) = get(&ctx, &token, &format!("{}?limit=2&sort=oldest&fields=id,filename", uri)).await?;
+        assert_eq!(body["documents"].as_array().unwrap().len(), 2);
+        assert_eq!(body["pagination"]["has_more"], true);
+        assert_eq!(body["documents"][0]["filename"], "a.pdf");
+        assert!(body["documents"][0].get("file_size").is_none());
+
+        let (status, stats) = get(&ctx, &token, &format!("{}/stats", uri)).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(stats["total_documents"], 3);
+        assert_eq!(stats["total_size_bytes"], 4000);
+        assert_eq!(stats["ocr_completed"], 1);
+        assert_eq!(stats["ocr_failed"], 1);
+        assert_eq!(stats["ocr_pending"], 1);
+        assert_eq!(stats["ocr_processing"], 0);
+
+        // Another user can't see the source at all
+        assert_eq!(get(&ctx, &other_token, &uri).await?.0, StatusCode::NOT_FOUND);
+        assert_eq!(get(&ctx, &other_token, &format!("{}/stats", uri)).await?.0, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
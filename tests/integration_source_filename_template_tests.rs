@@ -0,0 +1,140 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{TimeZone, Utc};
+use tempfile::TempDir;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ingestion::document_ingestion::{DeduplicationPolicy, DocumentIngestionService, IngestionResult},
+    models::{CreateSource, Document, FileIngestionInfo, SourceType},
+    services::file_service::FileService,
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+fn local_folder_config(filename_template: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "watch_folders": ["/phone"],
+        "file_extensions": ["pdf"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "recursive": true,
+        "follow_symlinks": false,
+        "filename_template": filename_template
+    })
+}
+
+fn file_info(path: &str, size: usize) -> FileIngestionInfo {
+    let name = path.rsplit('/').next().unwrap().to_string();
+    let taken_at = Utc.with_ymd_and_hms(2024, 3, 9, 14, 30, 0).unwrap();
+    FileIngestionInfo {
+        relative_path: path.to_string(),
+        full_path: path.to_string(),
+        #[allow(deprecated)]
+        path: path.to_string(),
+        name,
+        size: size as i64,
+        mime_type: "application/pdf".to_string(),
+        last_modified: Some(taken_at),
+        etag: format!("etag-{}", path),
+        is_directory: false,
+        created_at: Some(taken_at),
+        permissions: None,
+        owner: None,
+        group: None,
+        metadata: None,
+    }
+}
+
+async fn ingest(ingestion: &DocumentIngestionService, user_id: Uuid, source_id: Uuid, path: &str, content: &[u8]) -> Result<Document> {
+    let info = file_info(path, content.len());
+    match ingestion.ingest_from_file_info(&info, content.to_vec(), user_id, DeduplicationPolicy::Skip, "source_sync", Some(source_id)).await.unwrap() {
+        IngestionResult::Created(doc) | IngestionResult::NewVersion(doc) => Ok(doc),
+        other => panic!("expected a stored document, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_source_filename_template_names_documents() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let upload_dir = TempDir::new()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user_id = auth_helper.create_test_user().await.user_response.id;
+        let ingestion = DocumentIngestionService::new(
+            ctx.state.db.clone(),
+            FileService::new(upload_dir.path().to_string_lossy().to_string()),
+        );
+        let source = ctx.state.db.create_source(user_id, &CreateSource {
+            name: "Phone".to_string(),
+            source_type: SourceType::LocalFolder,
+            enabled: Some(false),
+            config: local_folder_config(serde_json::json!("{date}_{source}_{original}")),
+        }).await?;
+
+        let first = ingest(&ingestion, user_id, source.id, "/phone/IMG_0423.pdf", b"first photo").await?;
+        assert_eq!(first.filename, "2024-03-09_Phone_IMG_0423.pdf");
+        assert_eq!(first.original_filename, "IMG_0423.pdf");
+
+        // A different file with the same remote name gets the next free name
+        let second = ingest(&ingestion, user_id, source.id, "/phone/backup/IMG_0423.pdf", b"second photo").await?;
+        assert_eq!(second.filename, "2024-03-09_Phone_IMG_0423_2.pdf");
+        assert_eq!(second.original_filename, "IMG_0423.pdf");
+
+        // Changed content becomes a version and keeps the name the document was given
+        let version = ingest(&ingestion, user_id, source.id, "/phone/IMG_0423.pdf", b"first photo, edited").await?;
+        assert_eq!(version.id, first.id);
+        assert_eq!(version.filename, "2024-03-09_Phone_IMG_0423.pdf");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_invalid_filename_templates_are_rejected_on_save() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        for (template, expected) in [
+            ("{date}_{author}", StatusCode::BAD_REQUEST),
+            ("{date}/{original}", StatusCode::BAD_REQUEST),
+            ("{date_{original}", StatusCode::BAD_REQUEST),
+            ("Scan {counter}", StatusCode::OK),
+        ] {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/sources")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_vec(&serde_json::json!({
+                    "name": format!("Template {}", template),
+                    "source_type": "local_folder",
+                    "enabled": false,
+                    "config": local_folder_config(serde_json::json!(template))
+                }))?))?;
+
+            let response = ctx.app.clone().oneshot(request).await?;
+            assert_eq!(response.status(), expected, "template {}", template);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
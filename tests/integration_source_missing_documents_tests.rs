@@ -0,0 +1,131 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+/// Creates a WebDAV source with one synced document per path
+async fn create_source_with_documents(ctx: &TestContext, user_id: Uuid, paths: &[&str]) -> Result<(Uuid, Vec<Uuid>)> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: format!("Missing documents {}", Uuid::new_v4().simple()),
+        source_type: SourceType::WebDAV,
+        enabled: Some(true),
+        config: serde_json::json!({
+            "server_url": "https://cloud.example.com",
+            "username": "user",
+            "password": "secret",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60
+        }),
+    }).await?;
+
+    let mut document_ids = Vec::new();
+    for path in paths {
+        let mut document = create_test_document(user_id);
+        document.source_id = Some(source.id);
+        document.source_type = Some("webdav".to_string());
+        document.source_path = Some(path.to_string());
+        document_ids.push(ctx.state.db.create_document(document).await?.id);
+    }
+    Ok((source.id, document_ids))
+}
+
+#[tokio::test]
+async fn test_missing_documents_are_listed_and_detached() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let other_token = auth_helper.login_user(&other.username, &other.password).await;
+
+        let paths = ["/Documents/a.pdf", "/Documents/b.pdf", "/Documents/c.pdf"];
+        let (source_id, documents) = create_source_with_documents(&ctx, user.user_response.id, &paths).await?;
+        let missing_paths = vec![paths[0].to_string(), paths[1].to_string()];
+        ctx.state.db.set_source_documents_missing(source_id, &missing_paths, true).await?;
+
+        let (status, missing) = auth_helper.send_authenticated_request("GET", &format!("/api/sources/{}/missing", source_id), None, &token).await;
+        assert_eq!(status, StatusCode::OK);
+        let mut listed: Vec<&str> = missing.as_array().unwrap().iter().map(|d| d["source_path"].as_str().unwrap()).collect();
+        listed.sort();
+        assert_eq!(listed, vec![paths[0], paths[1]]);
+
+        let (status, _) = auth_helper.send_authenticated_request("GET", &format!("/api/sources/{}/missing", source_id), None, &other_token).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // Only documents flagged as missing on this source are detached
+        let (status, response) = auth_helper.send_authenticated_request(
+            "POST", &format!("/api/sources/{}/missing/resolve", source_id),
+            Some(serde_json::json!({ "action": "detach", "document_ids": [documents[0], documents[2]] })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["resolved_count"], 1);
+        assert_eq!(response["resolved_documents"], serde_json::json!([documents[0]]));
+        assert_eq!(response["failed_documents"], serde_json::json!([documents[2]]));
+
+        let (source, source_status): (Option<Uuid>, Option<String>) = sqlx::query_as(
+            "SELECT source_id, source_status FROM documents WHERE id = $1"
+        )
+        .bind(documents[0])
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+        assert_eq!((source, source_status), (None, None));
+
+        let (_, missing) = auth_helper.send_authenticated_request("GET", &format!("/api/sources/{}/missing", source_id), None, &token).await;
+        assert_eq!(missing.as_array().unwrap().len(), 1);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_all_missing_documents_are_deleted_when_no_ids_are_given() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let paths = ["/Documents/a.pdf", "/Documents/b.pdf", "/Documents/c.pdf"];
+        let (source_id, documents) = create_source_with_documents(&ctx, user.user_response.id, &paths).await?;
+        let missing_paths = vec![paths[0].to_string(), paths[1].to_string()];
+        ctx.state.db.set_source_documents_missing(source_id, &missing_paths, true).await?;
+
+        let (status, response) = auth_helper.send_authenticated_request(
+            "POST", &format!("/api/sources/{}/missing/resolve", source_id),
+            Some(serde_json::json!({ "action": "delete" })), &token,
+        ).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["resolved_count"], 2);
+
+        let live: Vec<Uuid> = sqlx::query_scalar("SELECT id FROM documents WHERE id = ANY($1) AND deleted_at IS NULL")
+            .bind(&documents)
+            .fetch_all(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(live, vec![documents[2]]);
+
+        let (status, missing) = auth_helper.send_authenticated_request("GET", &format!("/api/sources/{}/missing", source_id), None, &token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(missing.as_array().unwrap().is_empty());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
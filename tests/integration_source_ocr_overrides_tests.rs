@@ -0,0 +1,141 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tempfile::TempDir;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    ingestion::document_ingestion::{DocumentIngestionService, IngestionResult},
+    models::{CreateSource, Settings, SourceType},
+    ocr::queue::OcrQueueService,
+    services::file_service::FileService,
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+fn local_folder_config(ocr_overrides: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "watch_folders": ["/scans"],
+        "file_extensions": ["png"],
+        "auto_sync": false,
+        "sync_interval_minutes": 60,
+        "recursive": true,
+        "follow_symlinks": false,
+        "ocr_overrides": ocr_overrides
+    })
+}
+
+async fn create_source(ctx: &TestContext, user_id: Uuid, name: &str, ocr_overrides: serde_json::Value) -> Result<Uuid> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: name.to_string(),
+        source_type: SourceType::LocalFolder,
+        enabled: Some(false),
+        config: local_folder_config(ocr_overrides),
+    }).await?;
+    Ok(source.id)
+}
+
+/// Ingests a file from a source, queues it like a sync would and returns the settings its OCR job resolves to
+async fn ingest_and_resolve(
+    ctx: &TestContext,
+    ingestion: &DocumentIngestionService,
+    user_id: Uuid,
+    source_id: Option<Uuid>,
+    content: &[u8],
+) -> Result<Settings> {
+    let result = match source_id {
+        Some(source_id) => ingestion.ingest_from_source("scan.png", content.to_vec(), "image/png", user_id, source_id, "local_folder").await,
+        None => ingestion.ingest_upload("scan.png", content.to_vec(), "image/png", user_id).await,
+    };
+    let document = match result.unwrap() {
+        IngestionResult::Created(doc) => doc,
+        other => panic!("expected a new document, got {:?}", other),
+    };
+    ctx.state.queue_service.enqueue_document(document.id, 5, document.file_size).await?;
+
+    let item = ctx.state.queue_service.dequeue().await?.expect("a queued OCR job");
+    assert_eq!(item.document_id, document.id);
+    let (settings, _) = OcrQueueService::resolve_job_settings(&item, &Settings::default());
+    Ok(settings)
+}
+
+#[tokio::test]
+async fn test_sources_apply_their_own_ocr_settings() -> Result<()> {
+    let ctx = TestContext::new().await;
+    let upload_dir = TempDir::new()?;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user_id = auth_helper.create_test_user().await.user_response.id;
+        let ingestion = DocumentIngestionService::new(
+            ctx.state.db.clone(),
+            FileService::new(upload_dir.path().to_string_lossy().to_string()),
+        );
+
+        let phone = create_source(&ctx, user_id, "Phone photos", serde_json::json!({
+            "dpi": 600, "enhance_contrast": true, "remove_noise": true, "detect_orientation": true, "auto_rotate": true
+        })).await?;
+        let scanner = create_source(&ctx, user_id, "Scanner", serde_json::json!({
+            "dpi": 200, "enhance_contrast": false, "remove_noise": false, "detect_orientation": false, "auto_rotate": false
+        })).await?;
+
+        let phone_settings = ingest_and_resolve(&ctx, &ingestion, user_id, Some(phone), b"phone photo").await?;
+        let scanner_settings = ingest_and_resolve(&ctx, &ingestion, user_id, Some(scanner), b"clean scan").await?;
+        let upload_settings = ingest_and_resolve(&ctx, &ingestion, user_id, None, b"manual upload").await?;
+
+        assert_eq!(phone_settings.ocr_dpi, 600);
+        assert_eq!(scanner_settings.ocr_dpi, 200);
+        assert!(phone_settings.ocr_enhance_contrast && phone_settings.ocr_remove_noise && phone_settings.auto_rotate_images);
+        assert!(!scanner_settings.ocr_enhance_contrast && !scanner_settings.ocr_remove_noise);
+        assert!(!scanner_settings.ocr_detect_orientation && !scanner_settings.auto_rotate_images);
+
+        // Documents without a source keep the user's settings
+        let defaults = Settings::default();
+        assert_eq!(upload_settings.ocr_dpi, defaults.ocr_dpi);
+        assert_eq!(upload_settings.ocr_enhance_contrast, defaults.ocr_enhance_contrast);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_out_of_range_ocr_overrides_are_rejected_on_save() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        for (dpi, expected) in [(5000, StatusCode::BAD_REQUEST), (450, StatusCode::OK)] {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/sources")
+                .header("Authorization", format!("Bearer {}", token))
+                .header("Content-Type", "application/json")
+                .body(Body::from(serde_json::to_vec(&serde_json::json!({
+                    "name": format!("Scans at {} dpi", dpi),
+                    "source_type": "local_folder",
+                    "enabled": false,
+                    "config": local_folder_config(serde_json::json!({ "dpi": dpi }))
+                }))?))?;
+
+            let response = ctx.app.clone().oneshot(request).await?;
+            assert_eq!(response.status(), expected, "dpi {}", dpi);
+        }
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -238,6 +238,7 @@ fn test_auto_sync_configuration() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     assert!(webdav_config.auto_sync);
@@ -253,6 +254,7 @@ fn test_auto_sync_configuration() {
         auto_sync: false,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     assert!(!webdav_disabled.auto_sync);
@@ -314,6 +316,7 @@ fn test_source_configuration_sizes() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let serialized = serde_json::to_string(&webdav_config).unwrap();
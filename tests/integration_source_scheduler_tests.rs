@@ -634,6 +634,7 @@ async fn test_config_validation() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     assert!(!webdav_config.server_url.is_empty());
@@ -667,6 +668,11 @@ async fn test_config_validation() {
         auto_sync: true,
         sync_interval_minutes: 120,
         file_extensions: vec![".pdf".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
+        client_certificate: Default::default(),
     };
     
     assert!(!s3_config.bucket_name.is_empty());
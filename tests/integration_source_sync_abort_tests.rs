@@ -0,0 +1,163 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, SourceStatus, SourceType},
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn abort(ctx: &TestContext, token: &str, source_id: Uuid, query: &str) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("POST")
+        .uri(format!("/api/sources/{}/sync/abort{}", source_id, query))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_source(ctx: &TestContext, user_id: Uuid, name: &str) -> Result<Uuid> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: name.to_string(),
+        source_type: SourceType::WebDAV,
+        enabled: Some(false),
+        config: serde_json::json!({
+            "server_url": "https://webdav.example.com",
+            "username": "archive",
+            "password": "secret",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "server_type": "generic"
+        }),
+    }).await?;
+    Ok(source.id)
+}
+
+/// Creates a document of `source_id` with a pending OCR job
+async fn synced_document(ctx: &TestContext, user_id: Uuid, source_id: Uuid, name: &str, age: Duration) -> Result<Uuid> {
+    let document = ctx.state.db.create_document(create_test_document_with_hash(user_id, name, format!("hash-{}", name))).await?;
+    sqlx::query("UPDATE documents SET source_id = $2, created_at = $3 WHERE id = $1")
+        .bind(document.id)
+        .bind(source_id)
+        .bind(Utc::now() - age)
+        .execute(ctx.state.db.get_pool())
+        .await?;
+    ctx.state.queue_service.enqueue_document(document.id, 5, 1024).await?;
+    Ok(document.id)
+}
+
+async fn queue_status(ctx: &TestContext, document_id: Uuid) -> Result<String> {
+    Ok(sqlx::query_scalar("SELECT status FROM ocr_queue WHERE document_id = $1")
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_abort_cancels_pending_ocr_and_keeps_documents() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let source_id = create_source(&ctx, user_id, "Wrong folder").await?;
+        let other_source_id = create_source(&ctx, user_id, "Archive").await?;
+        ctx.state.db.update_source_status(source_id, SourceStatus::Syncing, None).await?;
+        let run_id = ctx.state.db.create_source_sync_run(source_id).await?;
+
+        let synced = synced_document(&ctx, user_id, source_id, "synced.pdf", Duration::zero()).await?;
+        let other = synced_document(&ctx, user_id, other_source_id, "other.pdf", Duration::zero()).await?;
+
+        let (status, response) = abort(&ctx, &token, source_id, "").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["sync_stopped"], true);
+        assert_eq!(response["sync_run_id"], run_id.to_string());
+        assert_eq!(response["ocr_jobs_cancelled"], 1);
+        assert_eq!(response["documents_deleted"], 0);
+
+        assert_eq!(queue_status(&ctx, synced).await?, "cancelled");
+        assert_eq!(queue_status(&ctx, other).await?, "pending", "other sources are untouched");
+        assert!(ctx.state.db.get_document_by_id(synced, user_id, user.user_response.role).await?.is_some());
+
+        let source = ctx.state.db.get_source(user_id, source_id).await?.unwrap();
+        assert!(matches!(source.status, SourceStatus::Idle));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_abort_deletes_documents_of_the_current_run_when_asked() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+
+        let source_id = create_source(&ctx, user_id, "Wrong folder").await?;
+        let earlier = synced_document(&ctx, user_id, source_id, "earlier.pdf", Duration::days(1)).await?;
+        ctx.state.db.create_source_sync_run(source_id).await?;
+        let current = synced_document(&ctx, user_id, source_id, "current.pdf", Duration::zero()).await?;
+
+        let (status, response) = abort(&ctx, &token, source_id, "?delete_documents=true").await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response["ocr_jobs_cancelled"], 2);
+        assert_eq!(response["documents_deleted"], 1);
+
+        let role = user.user_response.role;
+        assert!(ctx.state.db.get_document_by_id(current, user_id, role).await?.is_none());
+        assert!(ctx.state.db.get_deleted_document_by_id(current, user_id, role).await?.is_some(), "deleted documents go to the trash");
+        assert!(ctx.state.db.get_document_by_id(earlier, user_id, role).await?.is_some(), "documents from earlier runs are kept");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_abort_of_another_users_source_is_not_found() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&other.username, &other.password).await;
+
+        let source_id = create_source(&ctx, owner.user_response.id, "Private").await?;
+        let (status, _) = abort(&ctx, &token, source_id, "?delete_documents=true").await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -218,6 +218,7 @@ fn create_auth_header(user: &User, jwt_secret: &str) -> String {
         sub: user.id,
         username: user.username.clone(),
         exp: (chrono::Utc::now() + chrono::Duration::hours(24)).timestamp() as usize,
+        impersonated_by: None,
     };
     
     let token = jsonwebtoken::encode(
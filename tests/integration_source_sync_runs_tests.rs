@@ -0,0 +1,112 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use readur::{
+    db::{Database, source_sync_runs::SyncRunCounts},
+    models::{CreateSource, CreateUser, SourceType, SyncRunOutcome, UserRole},
+    test_utils::TestContext,
+};
+
+async fn create_test_source(db: &Database) -> Result<Uuid> {
+    let unique_suffix = Uuid::new_v4().simple();
+    let user = db.create_user(CreateUser {
+        username: format!("sync_runs_{}", unique_suffix),
+        email: format!("sync_runs_{}@example.com", unique_suffix),
+        password: "password123".to_string(),
+        role: Some(UserRole::User),
+    }).await?;
+
+    let source = db.create_source(user.id, &CreateSource {
+        name: "Sync runs source".to_string(),
+        source_type: SourceType::LocalFolder,
+        enabled: Some(true),
+        config: serde_json::json!({
+            "watch_folders": ["/tmp"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "recursive": true,
+            "follow_symlinks": false
+        }),
+    }).await?;
+
+    Ok(source.id)
+}
+
+#[tokio::test]
+async fn test_sync_run_lifecycle_and_history() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let source_id = create_test_source(db).await?;
+
+        let first_run = db.create_source_sync_run(source_id).await?;
+        let counts = SyncRunCounts {
+            files_discovered: 5,
+            files_downloaded: 4,
+            files_failed: 1,
+            bytes_transferred: 4096,
+        };
+        db.finish_source_sync_run(first_run, counts, SyncRunOutcome::Success, None).await?;
+
+        let second_run = db.create_source_sync_run(source_id).await?;
+        db.finish_source_sync_run(second_run, SyncRunCounts::default(), SyncRunOutcome::Cancelled, None).await?;
+
+        // A finished run is not overwritten by a late update
+        db.finish_source_sync_run(second_run, counts, SyncRunOutcome::Failed, Some("late")).await?;
+
+        let runs = db.get_source_sync_runs(source_id, 10).await?;
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].id, second_run);
+        assert_eq!(runs[0].outcome, SyncRunOutcome::Cancelled);
+        assert_eq!(runs[0].files_downloaded, 0);
+        assert_eq!(runs[1].id, first_run);
+        assert_eq!(runs[1].outcome, SyncRunOutcome::Success);
+        assert_eq!(runs[1].bytes_transferred, 4096);
+        assert!(runs[1].finished_at.is_some());
+
+        assert_eq!(db.get_source_sync_runs(source_id, 1).await?.len(), 1);
+
+        let totals = db.get_source_sync_totals().await?;
+        let source_totals = totals.iter().find(|t| t.source_id == source_id).expect("source totals");
+        assert_eq!(source_totals.files_synced_total, 4);
+        assert_eq!(source_totals.bytes_transferred_total, 4096);
+        assert!(source_totals.last_run_duration_seconds.is_some());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
+
+#[tokio::test]
+async fn test_unfinished_sync_runs_marked_failed() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let db = &ctx.state.db;
+        let source_id = create_test_source(db).await?;
+
+        let run_id = db.create_source_sync_run(source_id).await?;
+        let closed = db.fail_unfinished_source_sync_runs(Some(source_id), "Sync operation timed out").await?;
+        assert_eq!(closed, 1);
+
+        let runs = db.get_source_sync_runs(source_id, 10).await?;
+        assert_eq!(runs[0].id, run_id);
+        assert_eq!(runs[0].outcome, SyncRunOutcome::Failed);
+        assert_eq!(runs[0].error_message.as_deref(), Some("Sync operation timed out"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+
+    result
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    models::{CreateSource, SourceType},
+    test_utils::{TestAuthHelper, TestContext},
+};
+
+async fn get_report(ctx: &TestContext, token: &str, source_id: Uuid) -> Result<(StatusCode, serde_json::Value)> {
+    let request = Request::builder()
+        .method("GET")
+        .uri(format!("/api/sources/{}/validate", source_id))
+        .header("Authorization", format!("Bearer {}", token))
+        .body(Body::empty())?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn create_webdav_source(ctx: &TestContext, user_id: Uuid) -> Result<Uuid> {
+    let source = ctx.state.db.create_source(user_id, &CreateSource {
+        name: "Nextcloud".to_string(),
+        source_type: SourceType::WebDAV,
+        enabled: Some(false),
+        config: serde_json::json!({
+            "server_url": "https://cloud.example.com",
+            "username": "alice",
+            "password": "secret",
+            "watch_folders": ["/Documents"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "server_type": "nextcloud"
+        }),
+    }).await?;
+    Ok(source.id)
+}
+
+#[tokio::test]
+async fn test_latest_validation_report_is_returned_without_rerunning() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let source_id = create_webdav_source(&ctx, user.user_response.id).await?;
+
+        let (status, _) = get_report(&ctx, &token, source_id).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND, "no report before the first validation");
+
+        let report = serde_json::json!({ "overall_health_score": 50, "issues": [], "recommendations": [] });
+        ctx.state.db.save_source_validation_report(source_id, 50, &report, true).await?;
+
+        let (status, body) = get_report(&ctx, &token, source_id).await?;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["health_score"], 50);
+        assert_eq!(body["deep_scan_pending"], true);
+        assert_eq!(body["report"], report);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_queued_deep_scan_survives_until_cleared() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let source_id = create_webdav_source(&ctx, user.user_response.id).await?;
+        let report = serde_json::json!({ "overall_health_score": 100 });
+
+        ctx.state.db.save_source_validation_report(source_id, 40, &report, true).await?;
+        let saved = ctx.state.db.save_source_validation_report(source_id, 100, &report, false).await?;
+        assert_eq!(saved.health_score, 100);
+        assert!(saved.deep_scan_pending, "a healthy validation must not cancel a queued deep scan");
+
+        ctx.state.db.clear_source_deep_scan_pending(source_id).await?;
+        let cleared = ctx.state.db.get_source_validation_report(source_id).await?.unwrap();
+        assert!(!cleared.deep_scan_pending);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_validation_report_is_scoped_to_the_owner() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let owner = auth_helper.create_test_user().await;
+        let other = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&other.username, &other.password).await;
+
+        let source_id = create_webdav_source(&ctx, owner.user_response.id).await?;
+        ctx.state.db.save_source_validation_report(source_id, 90, &serde_json::json!({}), false).await?;
+
+        let (status, _) = get_report(&ctx, &token, source_id).await?;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
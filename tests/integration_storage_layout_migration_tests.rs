@@ -0,0 +1,128 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use readur::{
+    services::file_service::{FileService, StorageLayout},
+    test_utils::{document_helpers::create_test_document, TestAuthHelper, TestContext},
+};
+
+const SHARDED: StorageLayout = StorageLayout::Sharded { levels: 2 };
+
+/// Stores `content` the way the flat layout does and creates its document
+async fn create_stored_document(ctx: &TestContext, service: &FileService, user_id: Uuid, content: &str) -> Result<(Uuid, String)> {
+    let documents_dir = service.get_documents_path();
+    std::fs::create_dir_all(&documents_dir)?;
+    let file_path = documents_dir.join(format!("{}.pdf", Uuid::new_v4()));
+    std::fs::write(&file_path, content)?;
+
+    let mut document = create_test_document(user_id);
+    document.file_path = file_path.to_string_lossy().to_string();
+    let file_hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+    document.file_hash = Some(file_hash.clone());
+    Ok((ctx.state.db.create_document(document).await?.id, file_hash))
+}
+
+async fn stored_path(ctx: &TestContext, document_id: Uuid) -> Result<PathBuf> {
+    let path: String = sqlx::query_scalar("SELECT file_path FROM documents WHERE id = $1")
+        .bind(document_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?;
+    Ok(PathBuf::from(path))
+}
+
+fn shard_dir(service: &FileService, file_hash: &str) -> PathBuf {
+    service.get_documents_path().join(&file_hash[0..2]).join(&file_hash[2..4])
+}
+
+#[tokio::test]
+async fn test_migration_moves_files_between_layouts_and_updates_paths() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+        let upload_dir = tempfile::tempdir()?;
+        let flat = FileService::new(upload_dir.path().to_string_lossy().to_string());
+        let sharded = flat.clone().with_storage_layout(SHARDED);
+
+        let (invoice, invoice_hash) = create_stored_document(&ctx, &flat, user_id, "invoice contents").await?;
+        let (receipt, receipt_hash) = create_stored_document(&ctx, &flat, user_id, "receipt contents").await?;
+        let flat_invoice = stored_path(&ctx, invoice).await?;
+
+        // A dry run reports the moves and touches nothing
+        let report = sharded.migrate_existing_files(&ctx.state.db, true).await?;
+        assert_eq!(report.moved, 2);
+        assert_eq!(stored_path(&ctx, invoice).await?, flat_invoice);
+        assert!(flat_invoice.exists());
+
+        let report = sharded.migrate_existing_files(&ctx.state.db, false).await?;
+        assert_eq!((report.moved, report.failed, report.missing), (2, 0, 0));
+        for (id, hash, content) in [(invoice, &invoice_hash, "invoice contents"), (receipt, &receipt_hash, "receipt contents")] {
+            let path = stored_path(&ctx, id).await?;
+            assert_eq!(path.parent(), Some(shard_dir(&sharded, hash).as_path()));
+            assert_eq!(std::fs::read_to_string(&path)?, content);
+        }
+        assert!(!flat_invoice.exists());
+
+        // Running it again finds everything in place
+        let report = sharded.migrate_existing_files(&ctx.state.db, false).await?;
+        assert_eq!((report.moved, report.already_in_place), (0, 2));
+
+        // And back to flat, dropping the emptied shard directories
+        let report = flat.migrate_existing_files(&ctx.state.db, false).await?;
+        assert_eq!(report.moved, 2);
+        assert_eq!(stored_path(&ctx, invoice).await?, flat_invoice);
+        assert!(flat_invoice.exists());
+        assert!(!shard_dir(&sharded, &invoice_hash).exists());
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_migration_resumes_after_an_interrupted_move() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+        let upload_dir = tempfile::tempdir()?;
+        let flat = FileService::new(upload_dir.path().to_string_lossy().to_string());
+        let sharded = flat.clone().with_storage_layout(SHARDED);
+
+        let (document_id, file_hash) = create_stored_document(&ctx, &flat, user_id, "moved before the crash").await?;
+        let old_path = stored_path(&ctx, document_id).await?;
+
+        // The file was moved but the run stopped before its row was updated
+        let target_dir = shard_dir(&sharded, &file_hash);
+        std::fs::create_dir_all(&target_dir)?;
+        let target = target_dir.join(old_path.file_name().unwrap());
+        std::fs::rename(&old_path, &target)?;
+
+        // A document stored outside the documents directory is left alone
+        let mut external = create_test_document(user_id);
+        external.file_path = "/srv/elsewhere/external.pdf".to_string();
+        let external_id = ctx.state.db.create_document(external).await?.id;
+
+        let report = sharded.migrate_existing_files(&ctx.state.db, false).await?;
+        assert_eq!((report.moved, report.outside_documents_dir, report.failed), (1, 1, 0));
+        assert_eq!(stored_path(&ctx, document_id).await?, target);
+        assert_eq!(stored_path(&ctx, external_id).await?, Path::new("/srv/elsewhere/external.pdf"));
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
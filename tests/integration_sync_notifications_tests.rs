@@ -0,0 +1,124 @@
+use anyhow::Result;
+use axum::{body::Body, http::{Request, StatusCode}};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use readur::{
+    db::source_sync_runs::SyncRunCounts,
+    models::{CreateSource, Source, SourceType, SyncRunOutcome},
+    scheduling::sync_notifications::notify_sync_finished,
+    test_utils::{document_helpers::create_test_document_with_hash, TestAuthHelper, TestContext},
+};
+
+async fn save_settings(ctx: &TestContext, token: &str, body: serde_json::Value) -> Result<StatusCode> {
+    let request = Request::builder()
+        .method("PUT")
+        .uri("/api/settings")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))?;
+
+    Ok(ctx.app.clone().oneshot(request).await?.status())
+}
+
+async fn create_source(ctx: &TestContext, user_id: Uuid) -> Result<Source> {
+    ctx.state.db.create_source(user_id, &CreateSource {
+        name: "Scans".to_string(),
+        source_type: SourceType::LocalFolder,
+        enabled: Some(true),
+        config: serde_json::json!({
+            "watch_folders": ["/tmp/scans"],
+            "file_extensions": ["pdf"],
+            "auto_sync": false,
+            "sync_interval_minutes": 60,
+            "recursive": true,
+            "follow_symlinks": false
+        }),
+    }).await
+}
+
+/// Records a finished sync run of `source` that added `filenames` as documents
+async fn record_run(ctx: &TestContext, source: &Source, filenames: &[&str], counts: SyncRunCounts) -> Result<()> {
+    let run_id = ctx.state.db.create_source_sync_run(source.id).await?;
+    for filename in filenames {
+        let mut document = create_test_document_with_hash(source.user_id, filename, Uuid::new_v4().to_string());
+        document.original_filename = filename.to_string();
+        document.source_id = Some(source.id);
+        ctx.state.db.create_document(document).await?;
+    }
+    ctx.state.db.finish_source_sync_run(run_id, counts, SyncRunOutcome::Success, None).await?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_sync_run_is_summarized_in_one_notification() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let user_id = user.user_response.id;
+        let source = create_source(&ctx, user_id).await?;
+
+        record_run(&ctx, &source, &["a.pdf", "b.pdf"], SyncRunCounts {
+            files_discovered: 1245,
+            files_downloaded: 1240,
+            files_failed: 3,
+            bytes_transferred: 2_254_857_830,
+        }).await?;
+        notify_sync_finished(&ctx.state, &source, Some(88)).await;
+
+        let notifications = ctx.state.db.get_user_notifications(user_id, 10, 0).await?;
+        assert_eq!(notifications.len(), 1, "{:?}", notifications);
+        assert_eq!(notifications[0].notification_type, "warning");
+        assert_eq!(notifications[0].message, "Scans: 1,240 new files, 3 failed, 2.1 GB, health score 88/100");
+
+        // Runs that found nothing are not worth a notification
+        record_run(&ctx, &source, &[], SyncRunCounts::default()).await?;
+        notify_sync_finished(&ctx.state, &source, Some(100)).await;
+        assert_eq!(ctx.state.db.get_user_notifications(user_id, 10, 0).await?.len(), 1);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_per_file_mode_notifies_each_new_file() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let user_id = user.user_response.id;
+        let source = create_source(&ctx, user_id).await?;
+
+        assert_eq!(save_settings(&ctx, &token, serde_json::json!({ "sync_notification_mode": "hourly" })).await?, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(save_settings(&ctx, &token, serde_json::json!({ "sync_notification_mode": "per_file" })).await?, StatusCode::OK);
+
+        record_run(&ctx, &source, &["invoice.pdf", "receipt.pdf"], SyncRunCounts {
+            files_discovered: 2,
+            files_downloaded: 2,
+            files_failed: 0,
+            bytes_transferred: 4096,
+        }).await?;
+        notify_sync_finished(&ctx.state, &source, None).await;
+
+        let notifications = ctx.state.db.get_user_notifications(user_id, 10, 0).await?;
+        let mut messages: Vec<&str> = notifications.iter().map(|n| n.message.as_str()).collect();
+        messages.sort();
+        assert_eq!(messages, ["invoice.pdf was synced from Scans", "receipt.pdf was synced from Scans"]);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
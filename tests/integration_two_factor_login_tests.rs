@@ -0,0 +1,128 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use totp_rs::{Algorithm, Secret, TOTP};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestContext};
+
+async fn post(ctx: &TestContext, uri: &str, token: Option<&str>, body: serde_json::Value) -> Result<(StatusCode, serde_json::Value)> {
+    let mut request = Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("Content-Type", "application/json");
+    if let Some(token) = token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    let response = ctx.app.clone().oneshot(request.body(Body::from(body.to_string()))?).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+/// Seconds since the epoch, a few seconds clear of the end of a TOTP step so the codes of
+/// one test all stay valid
+async fn now_within_step() -> u64 {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    if now % 30 >= 25 {
+        tokio::time::sleep(Duration::from_secs(30 - now % 30)).await;
+        return SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    }
+    now
+}
+
+/// Turns on 2FA for the user and returns their authenticator. The setup code is the one of
+/// the step before `now`.
+async fn enable_two_factor(ctx: &TestContext, token: &str, username: &str, now: u64) -> Result<TOTP> {
+    let (status, body) = post(ctx, "/api/auth/2fa/setup", Some(token), serde_json::json!({})).await?;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let secret = Secret::Encoded(body["secret"].as_str().unwrap().to_string()).to_bytes().unwrap();
+    let totp = TOTP::new(Algorithm::SHA1, 6, 1, 30, secret, Some("Readur".to_string()), username.to_string())?;
+
+    let (status, body) = post(ctx, "/api/auth/2fa/verify", Some(token), serde_json::json!({ "code": totp.generate(now - 30) })).await?;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    Ok(totp)
+}
+
+async fn challenge(ctx: &TestContext, username: &str, password: &str) -> Result<String> {
+    let (status, body) = post(ctx, "/api/auth/login", None, serde_json::json!({ "username": username, "password": password })).await?;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["requires_2fa"], true);
+    Ok(body["challenge_token"].as_str().unwrap().to_string())
+}
+
+async fn second_factor(ctx: &TestContext, challenge_token: &str, code: &str) -> Result<StatusCode> {
+    let body = serde_json::json!({ "challenge_token": challenge_token, "code": code });
+    Ok(post(ctx, "/api/auth/2fa/login", None, body).await?.0)
+}
+
+#[tokio::test]
+async fn test_totp_codes_can_only_be_used_once() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let now = now_within_step().await;
+        let totp = enable_two_factor(&ctx, &token, &user.username, now).await?;
+
+        let code = totp.generate(now);
+        let challenge_token = challenge(&ctx, &user.username, &user.password).await?;
+        assert_eq!(second_factor(&ctx, &challenge_token, &code).await?, StatusCode::OK);
+
+        // The same code is refused, even with a fresh challenge
+        let challenge_token = challenge(&ctx, &user.username, &user.password).await?;
+        assert_eq!(second_factor(&ctx, &challenge_token, &code).await?, StatusCode::UNAUTHORIZED);
+        // So is the earlier code of the setup, although it is still within the allowed drift
+        assert_eq!(second_factor(&ctx, &challenge_token, &totp.generate(now - 30)).await?, StatusCode::UNAUTHORIZED);
+        // A code of a later step is still accepted
+        assert_eq!(second_factor(&ctx, &challenge_token, &totp.generate(now + 30)).await?, StatusCode::OK);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_repeated_wrong_codes_lock_the_account_out() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        let now = now_within_step().await;
+        let totp = enable_two_factor(&ctx, &token, &user.username, now).await?;
+        let correct = totp.generate(now);
+        let wrong = if correct == "000000" { "111111" } else { "000000" };
+
+        // The test config allows 5 failed attempts
+        let mut statuses = Vec::new();
+        for _ in 0..5 {
+            // A new challenge per guess doesn't reset the count
+            let challenge_token = challenge(&ctx, &user.username, &user.password).await?;
+            statuses.push(second_factor(&ctx, &challenge_token, wrong).await?);
+        }
+        assert_eq!(statuses[..4], [StatusCode::UNAUTHORIZED; 4]);
+        assert_eq!(statuses[4], StatusCode::TOO_MANY_REQUESTS);
+
+        // The lockout covers the password step as well
+        let (status, _) = post(&ctx, "/api/auth/login", None, serde_json::json!({ "username": user.username, "password": user.password })).await?;
+        assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
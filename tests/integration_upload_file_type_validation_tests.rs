@@ -0,0 +1,167 @@
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
+
+use readur::test_utils::{TestAuthHelper, TestConfigBuilder, TestContext};
+
+const BOUNDARY: &str = "upload-file-type-test-boundary";
+
+const PDF: &[u8] = b"%PDF-1.4\n1 0 obj << /Type /Catalog >> endobj\ntrailer << /Root 1 0 R >>\n%%EOF\n";
+const EXE: &[u8] = b"MZ\x90\x00\x03\x00\x00\x00\x04\x00\x00\x00\xff\xff\x00\x00\xb8\x00\x00\x00";
+const JPEG: &[u8] = &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0x00, 0x01];
+
+async fn upload(ctx: &TestContext, token: &str, filename: &str, content_type: &str, data: &[u8]) -> Result<(StatusCode, serde_json::Value)> {
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+        BOUNDARY, filename, content_type
+    ).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+
+    let request = Request::builder()
+        .method("POST")
+        .uri("/api/documents")
+        .header("Authorization", format!("Bearer {}", token))
+        .header("Content-Type", format!("multipart/form-data; boundary={}", BOUNDARY))
+        .body(Body::from(body))?;
+
+    let response = ctx.app.clone().oneshot(request).await?;
+    let status = response.status();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await?;
+    Ok((status, serde_json::from_slice(&bytes).unwrap_or(serde_json::Value::Null)))
+}
+
+async fn document_count(ctx: &TestContext, user_id: uuid::Uuid) -> Result<i64> {
+    Ok(sqlx::query_scalar("SELECT COUNT(*) FROM documents WHERE user_id = $1")
+        .bind(user_id)
+        .fetch_one(ctx.state.db.get_pool())
+        .await?)
+}
+
+#[tokio::test]
+async fn test_correctly_typed_pdf_is_stored_with_sniffed_type() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        // Browsers often send a generic type; the content decides
+        let (status, response) = upload(&ctx, &token, "invoice.pdf", "application/octet-stream", PDF).await?;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", response);
+        assert_eq!(response["mime_type"], "application/pdf");
+
+        let document_id: uuid::Uuid = response["id"].as_str().unwrap().parse()?;
+        let mime_type: String = sqlx::query_scalar("SELECT mime_type FROM documents WHERE id = $1")
+            .bind(document_id)
+            .fetch_one(ctx.state.db.get_pool())
+            .await?;
+        assert_eq!(mime_type, "application/pdf");
+
+        // Plain text passes under an allowed extension
+        let (status, response) = upload(&ctx, &token, "notes.txt", "text/plain", b"Call the landlord on Monday\n").await?;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", response);
+        assert_eq!(response["mime_type"], "text/plain");
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_spoofed_extension_is_rejected_with_detected_type() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let (status, response) = upload(&ctx, &token, "invoice.pdf", "application/pdf", EXE).await?;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert_eq!(response["error_code"], "UPLOAD_UNSUPPORTED_TYPE");
+        let message = response["error"].as_str().unwrap();
+        assert!(message.contains("application/pdf"), "declared type missing: {}", message);
+        assert!(message.contains("application/vnd.microsoft.portable-executable"), "detected type missing: {}", message);
+
+        assert_eq!(document_count(&ctx, user.user_response.id).await?, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_disallowed_type_is_rejected() -> Result<()> {
+    let ctx = TestContext::new().await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+        assert!(!ctx.state.config.allowed_file_types.contains(&"jpg".to_string()));
+
+        // Not allowed by extension
+        let (status, response) = upload(&ctx, &token, "photo.jpg", "image/jpeg", JPEG).await?;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(response["error"].as_str().unwrap().contains("photo.jpg"));
+
+        // Nor by content when renamed to an allowed extension
+        let (status, response) = upload(&ctx, &token, "photo.png", "image/png", JPEG).await?;
+        assert_eq!(status, StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        assert!(response["error"].as_str().unwrap().contains("image/jpeg"));
+
+        assert_eq!(document_count(&ctx, user.user_response.id).await?, 0);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
+
+#[tokio::test]
+async fn test_upload_over_size_limit_is_rejected_while_streaming() -> Result<()> {
+    let ctx = TestContext::with_config(TestConfigBuilder::default().with_max_file_size_mb(1)).await;
+
+    let result = async {
+        let auth_helper = TestAuthHelper::new(ctx.app.clone());
+        let user = auth_helper.create_test_user().await;
+        let token = auth_helper.login_user(&user.username, &user.password).await;
+
+        let mut oversized = PDF.to_vec();
+        oversized.resize(1024 * 1024 + 1, b' ');
+        let (status, response) = upload(&ctx, &token, "scan.pdf", "application/pdf", &oversized).await?;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(response["error_code"], "UPLOAD_TOO_LARGE");
+        assert!(response["error"].as_str().unwrap().contains("scan.pdf"));
+        assert_eq!(document_count(&ctx, user.user_response.id).await?, 0);
+
+        // Exactly at the limit is still accepted
+        oversized.truncate(1024 * 1024);
+        let (status, response) = upload(&ctx, &token, "scan.pdf", "application/pdf", &oversized).await?;
+        assert_eq!(status, StatusCode::OK, "unexpected response: {}", response);
+        assert_eq!(response["file_size"], 1024 * 1024);
+
+        Ok(())
+    }.await;
+
+    if let Err(e) = ctx.cleanup_and_close().await {
+        eprintln!("Warning: Test cleanup failed: {}", e);
+    }
+    result
+}
@@ -33,6 +33,7 @@ async fn test_webdav_service_with_custom_retry() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let retry_config = RetryConfig {
@@ -42,6 +43,8 @@ async fn test_webdav_service_with_custom_retry() {
         backoff_multiplier: 1.5,
         timeout_seconds: 60,
         rate_limit_backoff_ms: 5000,
+        circuit_failure_threshold: 5,
+        circuit_cooldown_ms: 60000,
     };
 
     let result = WebDAVService::new_with_retry(config, retry_config);
@@ -58,6 +61,7 @@ fn test_webdav_config_builder() {
         file_extensions: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string()],
         timeout_seconds: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     // Test Nextcloud URL construction
@@ -251,6 +255,7 @@ fn test_webdav_url_construction() {
             file_extensions: vec!["pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some(server_type.to_string()),
+            tls: Default::default(),
         };
 
         let service = WebDAVService::new(config);
@@ -291,6 +296,7 @@ fn test_settings_webdav_integration() {
             file_extensions: settings.webdav_file_extensions.clone(),
             timeout_seconds: 30,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         };
 
         assert_eq!(webdav_config.server_url, "https://nextcloud.example.com");
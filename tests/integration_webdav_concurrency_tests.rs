@@ -39,6 +39,7 @@ async fn create_test_webdav_source(
         auto_sync,
         sync_interval_minutes: 1, // Fast interval for testing
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let create_source = CreateSource {
@@ -131,6 +132,7 @@ impl MockWebDAVService {
         Ok(readur::services::webdav::WebDAVDiscoveryResult {
             files: mock_files,
             directories: mock_directories,
+            scanned_directories: vec![directory_path.to_string()],
         })
     }
 }
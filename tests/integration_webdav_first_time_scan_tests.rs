@@ -16,6 +16,7 @@ fn create_test_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "docx".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     WebDAVService::new(config).unwrap()
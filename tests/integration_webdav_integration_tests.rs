@@ -64,6 +64,17 @@ fn create_empty_update_settings() -> UpdateSettings {
         ocr_quality_threshold_noise: None,
         ocr_quality_threshold_sharpness: None,
         ocr_skip_enhancement: None,
+        ocr_parallel_pages: None,
+        ocr_retry_low_confidence: None,
+        extract_archives: None,
+        search_sort: None,
+        ocr_detect_script: None,
+        ocr_preprocessing: None,
+        search_clean_snippets: None,
+        ocr_text_layer_min_chars: None,
+        sync_notification_mode: None,
+        default_source_id: None,
+        default_source_write_back: None,
         webdav_enabled: None,
         webdav_server_url: None,
         webdav_username: None,
@@ -201,6 +212,17 @@ async fn setup_webdav_settings(state: &AppState, user_id: Uuid) {
         ocr_quality_threshold_noise: None,
         ocr_quality_threshold_sharpness: None,
         ocr_skip_enhancement: None,
+        ocr_parallel_pages: None,
+        ocr_retry_low_confidence: None,
+        extract_archives: None,
+        search_sort: None,
+        ocr_detect_script: None,
+        ocr_preprocessing: None,
+        search_clean_snippets: None,
+        ocr_text_layer_min_chars: None,
+        sync_notification_mode: None,
+        default_source_id: None,
+        default_source_write_back: None,
     };
 
     state.db.create_or_update_settings(user_id, &update_settings).await
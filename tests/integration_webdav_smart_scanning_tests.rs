@@ -11,6 +11,7 @@ fn create_test_config() -> WebDAVConfig {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     }
 }
 
@@ -38,6 +39,7 @@ async fn test_recursive_etag_support_detection() {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).expect("Failed to create WebDAV service");
@@ -99,6 +101,7 @@ async fn test_etag_support_detection_capabilities() {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     let service = WebDAVService::new(config).expect("Failed to create WebDAV service");
@@ -30,6 +30,7 @@ fn create_test_webdav_config() -> WebDAVConfig {
         file_extensions: vec![".pdf".to_string(), ".txt".to_string(), ".jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     }
 }
 
@@ -44,6 +45,7 @@ fn create_test_source_config() -> WebDAVSourceConfig {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     }
 }
 
@@ -285,6 +287,7 @@ fn test_crawl_estimate_structure() {
         total_supported_files: 98,
         total_estimated_time_hours: 2.5,
         total_size_mb: 550.0,
+        processing_model: Default::default(),
     };
     
     assert_eq!(estimate.folders.len(), 2);
@@ -345,6 +348,7 @@ fn test_server_type_detection() {
             file_extensions: vec![".pdf".to_string()],
             timeout_seconds: 30,
             server_type: Some(server_type.to_string()),
+            tls: Default::default(),
         };
         
         if is_supported {
@@ -368,6 +372,7 @@ fn test_error_handling_scenarios() {
         file_extensions: vec![".pdf".to_string()],
         timeout_seconds: 1, // Very short timeout
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     assert_eq!(timeout_config.timeout_seconds, 1);
@@ -381,6 +386,7 @@ fn test_error_handling_scenarios() {
         file_extensions: vec![".pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     assert_eq!(auth_config.username, "invalid_user");
@@ -395,6 +401,7 @@ fn test_error_handling_scenarios() {
         file_extensions: vec![".pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     assert_eq!(invalid_path_config.watch_folders[0], "/nonexistent_folder");
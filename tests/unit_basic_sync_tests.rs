@@ -35,6 +35,7 @@ fn test_webdav_config_serialization() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let json_value = serde_json::to_value(&config).unwrap();
@@ -77,8 +78,13 @@ fn test_s3_config_serialization() {
         prefix: Some("documents/".to_string()),
         watch_folders: vec!["documents/".to_string()],
         file_extensions: vec![".pdf".to_string(), ".docx".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
         auto_sync: true,
         sync_interval_minutes: 120,
+        client_certificate: Default::default(),
     };
     
     let json_value = serde_json::to_value(&config).unwrap();
@@ -106,6 +112,7 @@ fn test_auto_sync_validation() {
             auto_sync: true,
             sync_interval_minutes: interval,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         };
         
         assert!(webdav_config.auto_sync);
@@ -134,6 +141,7 @@ fn test_file_extension_validation() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     for ext in &config.file_extensions {
@@ -183,6 +191,7 @@ fn test_server_type_validation() {
             auto_sync: true,
             sync_interval_minutes: 60,
             server_type: server_type.clone(),
+            tls: Default::default(),
         };
         
         assert_eq!(config.server_type, server_type);
@@ -215,8 +224,13 @@ fn test_s3_bucket_name_validation() {
             prefix: None,
             watch_folders: vec!["".to_string()],
             file_extensions: vec![".pdf".to_string()],
+            include_patterns: vec![],
+            exclude_patterns: vec![],
+            force_path_style: false,
+            ca_certificate: None,
             auto_sync: true,
             sync_interval_minutes: 120,
+            client_certificate: Default::default(),
         };
         
         assert_eq!(config.bucket_name, bucket_name);
@@ -238,8 +252,13 @@ fn test_endpoint_url_handling() {
         prefix: None,
         watch_folders: vec!["".to_string()],
         file_extensions: vec![".pdf".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
         auto_sync: true,
         sync_interval_minutes: 120,
+        client_certificate: Default::default(),
     };
     
     assert!(aws_config.endpoint_url.is_none());
@@ -254,8 +273,13 @@ fn test_endpoint_url_handling() {
         prefix: None,
         watch_folders: vec!["".to_string()],
         file_extensions: vec![".pdf".to_string()],
+        include_patterns: vec![],
+        exclude_patterns: vec![],
+        force_path_style: false,
+        ca_certificate: None,
         auto_sync: true,
         sync_interval_minutes: 120,
+        client_certificate: Default::default(),
     };
     
     assert!(minio_config.endpoint_url.is_some());
@@ -287,6 +311,7 @@ fn test_sync_interval_ranges() {
             auto_sync: true,
             sync_interval_minutes: interval,
             server_type: Some("nextcloud".to_string()),
+            tls: Default::default(),
         };
         
         assert_eq!(config.sync_interval_minutes, interval);
@@ -316,6 +341,7 @@ fn test_configuration_size_limits() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
     
     let serialized = serde_json::to_string(&large_webdav_config).unwrap();
@@ -342,6 +368,7 @@ fn test_concurrent_configuration_access() {
         auto_sync: true,
         sync_interval_minutes: 60,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     });
     
     let mut handles = vec![];
@@ -320,6 +320,7 @@ fn create_real_webdav_service() -> WebDAVService {
         file_extensions: vec!["pdf".to_string(), "txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
     
     WebDAVService::new(config).expect("Failed to create WebDAV service")
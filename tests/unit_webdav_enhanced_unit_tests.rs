@@ -113,6 +113,7 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     assert!(WebDAVService::new(valid_config).is_ok());
@@ -126,6 +127,7 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     // Should fail early with enhanced validation
@@ -140,6 +142,7 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     assert!(WebDAVService::new(invalid_scheme_config).is_err());
@@ -153,6 +156,7 @@ fn test_webdav_config_validation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     assert!(WebDAVService::new(relative_url_config).is_err());
@@ -169,6 +173,7 @@ fn test_webdav_url_construction_comprehensive() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let service = WebDAVService::new(nextcloud_config).unwrap();
@@ -183,6 +188,7 @@ fn test_webdav_url_construction_comprehensive() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 60,
         server_type: Some("owncloud".to_string()),
+        tls: Default::default(),
     };
 
     assert!(WebDAVService::new(owncloud_config).is_ok());
@@ -196,6 +202,7 @@ fn test_webdav_url_construction_comprehensive() {
         file_extensions: vec!["txt".to_string()],
         timeout_seconds: 45,
         server_type: None, // No server type = generic
+        tls: Default::default(),
     };
 
     assert!(WebDAVService::new(generic_config).is_ok());
@@ -211,6 +218,7 @@ fn test_webdav_response_parsing_comprehensive() {
         file_extensions: vec!["pdf".to_string(), "png".to_string(), "jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let service = WebDAVService::new(config.clone()).unwrap();
@@ -266,6 +274,7 @@ fn test_empty_folder_parsing() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
 
     let service = WebDAVService::new(config).unwrap();
@@ -288,6 +297,7 @@ fn test_malformed_xml_handling() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let service = WebDAVService::new(config).unwrap();
@@ -308,6 +318,8 @@ fn test_retry_config_custom_values() {
         backoff_multiplier: 1.5,
         timeout_seconds: 90,
         rate_limit_backoff_ms: 10000,
+        circuit_failure_threshold: 5,
+        circuit_cooldown_ms: 60000,
     };
 
     assert_eq!(custom_retry.max_retries, 5);
@@ -325,6 +337,7 @@ fn test_retry_config_custom_values() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     assert!(WebDAVService::new_with_retry(config, custom_retry).is_ok());
@@ -533,6 +546,7 @@ fn test_crawl_estimate_calculation() {
         total_supported_files: 230,
         total_estimated_time_hours: 0.127, // ~7.6 minutes
         total_size_mb: 650.0,
+        processing_model: Default::default(),
     };
 
     assert_eq!(estimate.folders.len(), 2);
@@ -550,6 +564,11 @@ fn test_connection_result_variants() {
         message: "Connected successfully to Nextcloud 28.0.1".to_string(),
         server_version: Some("28.0.1".to_string()),
         server_type: Some("nextcloud".to_string()),
+        detected_server_type: Some("nextcloud".to_string()),
+        reachable: true,
+        authenticated: true,
+        certificate_error: false,
+        client_certificate_error: false,
     };
 
     assert!(success_result.success);
@@ -562,6 +581,11 @@ fn test_connection_result_variants() {
         message: "Authentication failed: 401 Unauthorized".to_string(),
         server_version: None,
         server_type: None,
+        detected_server_type: None,
+        reachable: true,
+        authenticated: false,
+        certificate_error: false,
+        client_certificate_error: false,
     };
 
     assert!(!failure_result.success);
@@ -61,6 +61,7 @@ fn test_webdav_config_creation() {
         file_extensions: vec!["pdf".to_string(), "png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     assert_eq!(config.server_url, "https://cloud.example.com");
@@ -82,6 +83,7 @@ fn test_webdav_service_creation() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let result = WebDAVService::new(config);
@@ -98,6 +100,7 @@ fn test_webdav_response_parsing() {
         file_extensions: vec!["pdf".to_string(), "png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let service = WebDAVService::new(config).unwrap();
@@ -148,6 +151,7 @@ fn test_webdav_models() {
         total_supported_files: 75,
         total_estimated_time_hours: 2.5,
         total_size_mb: 250.0,
+        processing_model: Default::default(),
     };
 
     assert_eq!(estimate.folders.len(), 1);
@@ -160,6 +164,7 @@ fn test_webdav_models() {
         username: "testuser".to_string(),
         password: "testpass".to_string(),
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     assert_eq!(test_config.server_url, "https://cloud.example.com");
@@ -173,6 +178,11 @@ fn test_webdav_models() {
         message: "Connection successful".to_string(),
         server_version: Some("28.0.1".to_string()),
         server_type: Some("nextcloud".to_string()),
+        detected_server_type: Some("nextcloud".to_string()),
+        reachable: true,
+        authenticated: true,
+        certificate_error: false,
+        client_certificate_error: false,
     };
 
     assert!(result.success);
@@ -11,6 +11,7 @@ fn test_nextcloud_directory_path_handling() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -55,6 +56,7 @@ fn test_nextcloud_file_path_handling() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -99,6 +101,7 @@ fn test_webdav_root_path_handling() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -139,6 +142,7 @@ fn test_url_construction_from_relative_path() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -164,6 +168,7 @@ fn test_owncloud_path_handling() {
         file_extensions: vec!["pdf".to_string()],
         timeout_seconds: 30,
         server_type: Some("owncloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -202,6 +207,7 @@ fn test_generic_webdav_path_handling() {
         file_extensions: vec!["txt".to_string()],
         timeout_seconds: 30,
         server_type: Some("generic".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -241,6 +247,7 @@ fn test_download_path_resolution() {
         file_extensions: vec!["jpg".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -290,6 +297,7 @@ fn test_with_nextcloud_fixture_data() {
         file_extensions: vec!["jpg".to_string(), "jpeg".to_string(), "png".to_string()],
         timeout_seconds: 30,
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let manager = WebDAVService::new(config).unwrap();
@@ -47,6 +47,7 @@ async fn create_production_webdav_source(
         auto_sync,
         sync_interval_minutes: 5, // Realistic interval
         server_type: Some("nextcloud".to_string()),
+        tls: Default::default(),
     };
 
     let create_source = CreateSource {
@@ -196,12 +197,14 @@ impl ProductionMockWebDAVService {
             Ok(WebDAVDiscoveryResult {
                 files: files.clone(),
                 directories: vec![directory_info],
+                scanned_directories: vec![directory_path.to_string()],
             })
         } else {
             // Unknown directory
             Ok(WebDAVDiscoveryResult {
                 files: vec![],
                 directories: vec![],
+                scanned_directories: vec![directory_path.to_string()],
             })
         }
     }
@@ -114,6 +114,7 @@ impl MockWebDAVServiceForSmartSync {
                         metadata: None,
                     }
                 ],
+                scanned_directories: vec![directory_path.to_string()],
             })
         }
     }
@@ -210,6 +211,7 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    scanned_directories: vec!["/test".to_string()],
                 });
             }
             "changed" => {
@@ -234,6 +236,7 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    scanned_directories: vec!["/test".to_string()],
                 });
             }
             "new_dirs" => {
@@ -258,6 +261,7 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    scanned_directories: vec!["/test".to_string()],
                 });
             }
             "mixed" => {
@@ -298,6 +302,7 @@ async fn test_concurrent_smart_sync_etag_evaluation() {
                             metadata: None,
                         },
                     ],
+                    scanned_directories: vec!["/test".to_string()],
                 });
             }
             _ => {} // Failed case doesn't need setup